@@ -99,6 +99,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("UpdateCollection.hnsw_config", ""),
             ("UpdateCollection.vectors_config", ""),
             ("UpdateCollection.quantization_config", ""),
+            ("UpdateCollection.add_vectors", ""),
             ("DeleteCollection.collection_name", "length(min = 1, max = 255)"),
             ("DeleteCollection.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("CollectionConfig.params", ""),
@@ -110,8 +111,13 @@ fn configure_validation(builder: Builder) -> Builder {
             ("ListCollectionAliasesRequest.collection_name", "length(min = 1, max = 255)"),
             ("HnswConfigDiff.ef_construct", "custom = \"crate::grpc::validate::validate_u64_range_min_4\""),
             ("WalConfigDiff.wal_capacity_mb", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("WalConfigDiff.wal_fsync_interval_ms", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("OptimizersConfigDiff.deleted_threshold", "custom = \"crate::grpc::validate::validate_f64_range_1\""),
             ("OptimizersConfigDiff.vacuum_min_vector_number", "custom = \"crate::grpc::validate::validate_u64_range_min_100\""),
+            ("OptimizersConfigDiff.merge_policy", ""),
+            ("TieredMergePolicy.floor_segment_size_kb", "range(min = 1)"),
+            ("TieredMergePolicy.max_segments_per_tier", "range(min = 3)"),
+            ("TieredMergePolicy.merge_factor", "range(min = 3)"),
             ("VectorsConfig.config", ""),
             ("VectorsConfigDiff.config", ""),
             ("VectorParams.size", "range(min = 1, max = 65536)"),
@@ -140,6 +146,7 @@ fn configure_validation(builder: Builder) -> Builder {
         .validates(&[
             ("GetCollectionInfoRequestInternal.get_collection_info_request", ""),
             ("InitiateShardTransferRequest.collection_name", "length(min = 1, max = 255)"),
+            ("CreateShardSnapshotRequestInternal.collection_name", "length(min = 1, max = 255)"),
         ], &[])
         // Service: points.proto
         .validates(&[
@@ -150,6 +157,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("DeletePointVectors.collection_name", "length(min = 1, max = 255)"),
             ("DeletePointVectors.vector_names", "length(min = 1, message = \"must specify vector names to delete\")"),
             ("GetPoints.collection_name", "length(min = 1, max = 255)"),
+            ("GetPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("SetPayloadPoints.collection_name", "length(min = 1, max = 255)"),
             ("DeletePayloadPoints.collection_name", "length(min = 1, max = 255)"),
             ("ClearPayloadPoints.collection_name", "length(min = 1, max = 255)"),
@@ -164,8 +172,12 @@ fn configure_validation(builder: Builder) -> Builder {
             ("SearchPoints.limit", "range(min = 1)"),
             ("SearchPoints.params", ""),
             ("SearchPoints.vector_name", "custom = \"common::validation::validate_not_empty\""),
+            ("SearchPoints.text", ""),
+            ("SearchPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("TextQuery.key", "length(min = 1)"),
             ("SearchBatchPoints.collection_name", "length(min = 1, max = 255)"),
             ("SearchBatchPoints.search_points", ""),
+            ("SearchBatchPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("SearchPointGroups.collection_name", "length(min = 1, max = 255)"),
             ("SearchPointGroups.group_by", "length(min = 1)"),
             ("SearchPointGroups.filter", ""),
@@ -178,24 +190,43 @@ fn configure_validation(builder: Builder) -> Builder {
             ("ScrollPoints.collection_name", "length(min = 1, max = 255)"),
             ("ScrollPoints.filter", ""),
             ("ScrollPoints.limit", "custom = \"crate::grpc::validate::validate_u32_range_min_1\""),
+            ("ScrollPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("RecommendPoints.collection_name", "length(min = 1, max = 255)"),
             ("RecommendPoints.filter", ""),
             ("RecommendPoints.params", ""),
+            ("RecommendPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("RecommendBatchPoints.collection_name", "length(min = 1, max = 255)"),
             ("RecommendBatchPoints.recommend_points", ""),
+            ("RecommendBatchPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("RecommendPointGroups.collection_name", "length(min = 1, max = 255)"),
             ("RecommendPointGroups.filter", ""),
             ("RecommendPointGroups.group_by", "length(min = 1)"),
             ("RecommendPointGroups.group_size", "range(min = 1)"),
             ("RecommendPointGroups.limit", "range(min = 1)"),
             ("RecommendPointGroups.params", ""),
+            ("QueryPoints.collection_name", "length(min = 1, max = 255)"),
+            ("QueryPoints.prefetch", ""),
+            ("QueryPoints.filter", ""),
+            ("QueryPoints.params", ""),
+            ("QueryPoints.limit", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("QueryPoints.diversity", ""),
+            ("QueryPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("PrefetchQuery.prefetch", ""),
+            ("PrefetchQuery.filter", ""),
+            ("PrefetchQuery.params", ""),
+            ("PrefetchQuery.limit", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+            ("Diversity.lambda", "range(min = 0.0, max = 1.0)"),
+            ("Diversity.candidates_limit", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("CountPoints.collection_name", "length(min = 1, max = 255)"),
             ("CountPoints.filter", ""),
+            ("CountPoints.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
             ("GeoPolygon.exterior", "custom = \"crate::grpc::validate::validate_geo_polygon_exterior\""),
             ("GeoPolygon.interiors", "custom = \"crate::grpc::validate::validate_geo_polygon_interiors\""),
             ("Filter.should", ""),
             ("Filter.must", ""),
             ("Filter.must_not", ""),
+            ("Filter.min_should", ""),
+            ("MinShould.conditions", ""),
             ("NestedCondition.filter", ""),
             ("Condition.condition_one_of", "")
         ], &[])
@@ -209,6 +240,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("SetPayloadPointsInternal.set_payload_points", ""),
             ("DeletePayloadPointsInternal.delete_payload_points", ""),
             ("ClearPayloadPointsInternal.clear_payload_points", ""),
+            ("UpdateBatchInternal.update_batch_points", ""),
             ("CreateFieldIndexCollectionInternal.create_field_index_collection", ""),
             ("DeleteFieldIndexCollectionInternal.delete_field_index_collection", ""),
             ("SearchPointsInternal.search_points", ""),
@@ -244,7 +276,16 @@ fn configure_validation(builder: Builder) -> Builder {
             "CreateFullSnapshotRequest",
             "ListFullSnapshotsRequest",
         ])
+        // Service: cluster_service.proto
+        .validates(&[
+            ("UpdatePeerAddressRequest.uri", "length(min = 1)"),
+            ("UpdatePeerAddressRequest.timeout", "custom = \"crate::grpc::validate::validate_u64_range_min_1\""),
+        ], &[])
         .field_attribute("SnapshotDescription.creation_time", "#[serde(skip)]")
+        .field_attribute("DatetimeRange.lt", "#[serde(skip)]")
+        .field_attribute("DatetimeRange.gt", "#[serde(skip)]")
+        .field_attribute("DatetimeRange.gte", "#[serde(skip)]")
+        .field_attribute("DatetimeRange.lte", "#[serde(skip)]")
 }
 
 fn append_to_file(path: &str, line: &str) {
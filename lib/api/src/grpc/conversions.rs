@@ -1,32 +1,40 @@
 use std::collections::{HashMap, HashSet};
 use std::time::Instant;
 
-use chrono::{NaiveDateTime, Timelike};
+use chrono::{DateTime, NaiveDateTime, Timelike, Utc};
 use segment::data_types::text_index::TextIndexType;
-use segment::data_types::vectors::VectorElementType;
 use segment::types::default_quantization_ignore_value;
+use segment::vector_storage::query::context_query::ContextPair;
+use sparse::common::sparse_vector::SparseVector;
 use tonic::Status;
 use uuid::Uuid;
 
-use super::qdrant::{BinaryQuantization, CompressionRatio, GeoLineString, GroupId};
+use super::qdrant::{
+    BinaryQuantization, CardinalityEstimate, CompressionRatio, ExplainMode, GeoLineString, GroupId,
+    HardwareUsage, PrefetchScore, ScoreExplanation, SearchCandidates, SearchStrategy,
+    SegmentSearchExplain, ShardSearchExplain, VectorSearchExplain,
+};
 use crate::grpc::models::{CollectionsResponse, VersionInfo};
 use crate::grpc::qdrant::condition::ConditionOneOf;
 use crate::grpc::qdrant::payload_index_params::IndexParams;
 use crate::grpc::qdrant::point_id::PointIdOptions;
 use crate::grpc::qdrant::r#match::MatchValue;
+use crate::grpc::qdrant::start_from;
 use crate::grpc::qdrant::value::Kind;
 use crate::grpc::qdrant::vectors::VectorsOptions;
 use crate::grpc::qdrant::with_payload_selector::SelectorOptions;
 use crate::grpc::qdrant::{
-    with_vectors_selector, CollectionDescription, CollectionOperationResponse, Condition, Distance,
-    FieldCondition, Filter, GeoBoundingBox, GeoPoint, GeoPolygon, GeoRadius, HasIdCondition,
-    HealthCheckReply, HnswConfigDiff, IsEmptyCondition, IsNullCondition, ListCollectionsResponse,
-    ListValue, Match, NamedVectors, NestedCondition, PayloadExcludeSelector,
-    PayloadIncludeSelector, PayloadIndexParams, PayloadSchemaInfo, PayloadSchemaType, PointId,
-    ProductQuantization, QuantizationConfig, QuantizationSearchParams, QuantizationType, Range,
-    RepeatedIntegers, RepeatedStrings, ScalarQuantization, ScoredPoint, SearchParams, Struct,
-    TextIndexParams, TokenizerType, Value, ValuesCount, Vector, Vectors, VectorsSelector,
-    WithPayloadSelector, WithVectorsSelector,
+    with_vectors_selector, CollectionDescription, CollectionOperationResponse,
+    CompositeIndexParams, Condition, Datatype, DatetimeRange, Direction, Distance, FieldCondition,
+    Filter, FloatIndexParams, GeoBoundingBox, GeoIndexParams, GeoPoint, GeoPolygon, GeoRadius,
+    HasIdCondition, HasVectorCondition, HealthCheckReply, HnswConfigDiff, IntegerIndexParams,
+    IsEmptyCondition, IsNullCondition, KeywordIndexParams, ListCollectionsResponse, ListValue,
+    Match, MinShould, MultiVectorComparator, MultiVectorConfig, NamedVectors, NestedCondition,
+    OrderBy, PayloadExcludeSelector, PayloadIncludeSelector, PayloadIndexParams, PayloadSchemaInfo,
+    PayloadSchemaType, PointId, ProductQuantization, QuantizationConfig, QuantizationSearchParams,
+    QuantizationType, Range, RepeatedIntegers, RepeatedStrings, ScalarQuantization, ScoredPoint,
+    SearchParams, SparseIndices, StartFrom, StopwordsSet, Struct, TextIndexParams, TokenizerType,
+    Value, ValuesCount, Vector, Vectors, VectorsSelector, WithPayloadSelector, WithVectorsSelector,
 };
 
 pub fn payload_to_proto(payload: segment::types::Payload) -> HashMap<String, Value> {
@@ -36,7 +44,7 @@ pub fn payload_to_proto(payload: segment::types::Payload) -> HashMap<String, Val
         .collect()
 }
 
-fn json_to_proto(json_value: serde_json::Value) -> Value {
+pub fn json_to_proto(json_value: serde_json::Value) -> Value {
     match json_value {
         serde_json::Value::Null => Value {
             kind: Some(Kind::NullValue(0)),
@@ -77,7 +85,7 @@ pub fn proto_to_payloads(proto: HashMap<String, Value>) -> Result<segment::types
     Ok(map.into())
 }
 
-fn proto_to_json(proto: Value) -> Result<serde_json::Value, Status> {
+pub fn proto_to_json(proto: Value) -> Result<serde_json::Value, Status> {
     match proto.kind {
         None => Ok(serde_json::Value::default()),
         Some(kind) => match kind {
@@ -156,6 +164,70 @@ impl From<segment::data_types::text_index::TextIndexParams> for PayloadIndexPara
                 lowercase: params.lowercase,
                 min_token_len: params.min_token_len.map(|x| x as u64),
                 max_token_len: params.max_token_len.map(|x| x as u64),
+                stemmer: params
+                    .stemmer
+                    .map(|language| Language::from(language) as i32),
+                stopwords: params.stopwords.map(|stopwords| StopwordsSet {
+                    languages: stopwords
+                        .languages
+                        .into_iter()
+                        .map(|language| Language::from(language) as i32)
+                        .collect(),
+                    custom: stopwords.custom,
+                }),
+                phrase_matching: params.phrase_matching,
+                on_disk: params.on_disk,
+            })),
+        }
+    }
+}
+
+impl From<segment::data_types::keyword_index::KeywordIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::keyword_index::KeywordIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::KeywordIndexParams(KeywordIndexParams {
+                is_tenant: params.is_tenant,
+                on_disk: params.on_disk,
+            })),
+        }
+    }
+}
+
+impl From<segment::data_types::integer_index::IntegerIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::integer_index::IntegerIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::IntegerIndexParams(IntegerIndexParams {
+                on_disk: params.on_disk,
+            })),
+        }
+    }
+}
+
+impl From<segment::data_types::float_index::FloatIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::float_index::FloatIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::FloatIndexParams(FloatIndexParams {
+                on_disk: params.on_disk,
+            })),
+        }
+    }
+}
+
+impl From<segment::data_types::geo_index::GeoIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::geo_index::GeoIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::GeoIndexParams(GeoIndexParams {
+                on_disk: params.on_disk,
+            })),
+        }
+    }
+}
+
+impl From<segment::data_types::composite_index::CompositeIndexParams> for PayloadIndexParams {
+    fn from(params: segment::data_types::composite_index::CompositeIndexParams) -> Self {
+        PayloadIndexParams {
+            index_params: Some(IndexParams::CompositeIndexParams(CompositeIndexParams {
+                fields: params.fields,
             })),
         }
     }
@@ -171,12 +243,30 @@ impl From<segment::types::PayloadIndexInfo> for PayloadSchemaInfo {
                 segment::types::PayloadSchemaType::Geo => PayloadSchemaType::Geo,
                 segment::types::PayloadSchemaType::Text => PayloadSchemaType::Text,
                 segment::types::PayloadSchemaType::Bool => PayloadSchemaType::Bool,
+                segment::types::PayloadSchemaType::Datetime => PayloadSchemaType::Datetime,
+                segment::types::PayloadSchemaType::Uuid => PayloadSchemaType::Uuid,
+                segment::types::PayloadSchemaType::Composite => PayloadSchemaType::Composite,
             }
             .into(),
             params: schema.params.map(|params| match params {
+                segment::types::PayloadSchemaParams::Keyword(keyword_index_params) => {
+                    keyword_index_params.into()
+                }
+                segment::types::PayloadSchemaParams::Integer(integer_index_params) => {
+                    integer_index_params.into()
+                }
+                segment::types::PayloadSchemaParams::Float(float_index_params) => {
+                    float_index_params.into()
+                }
+                segment::types::PayloadSchemaParams::Geo(geo_index_params) => {
+                    geo_index_params.into()
+                }
                 segment::types::PayloadSchemaParams::Text(text_index_params) => {
                     text_index_params.into()
                 }
+                segment::types::PayloadSchemaParams::Composite(composite_index_params) => {
+                    composite_index_params.into()
+                }
             }),
             points: Some(schema.points as u64),
         }
@@ -200,6 +290,62 @@ impl TryFrom<TokenizerType> for segment::data_types::text_index::TokenizerType {
     }
 }
 
+impl From<segment::data_types::text_index::Language> for Language {
+    fn from(language: segment::data_types::text_index::Language) -> Self {
+        match language {
+            segment::data_types::text_index::Language::Arabic => Language::Arabic,
+            segment::data_types::text_index::Language::Danish => Language::Danish,
+            segment::data_types::text_index::Language::Dutch => Language::Dutch,
+            segment::data_types::text_index::Language::English => Language::English,
+            segment::data_types::text_index::Language::Finnish => Language::Finnish,
+            segment::data_types::text_index::Language::French => Language::French,
+            segment::data_types::text_index::Language::German => Language::German,
+            segment::data_types::text_index::Language::Greek => Language::Greek,
+            segment::data_types::text_index::Language::Hungarian => Language::Hungarian,
+            segment::data_types::text_index::Language::Italian => Language::Italian,
+            segment::data_types::text_index::Language::Norwegian => Language::Norwegian,
+            segment::data_types::text_index::Language::Portuguese => Language::Portuguese,
+            segment::data_types::text_index::Language::Romanian => Language::Romanian,
+            segment::data_types::text_index::Language::Russian => Language::Russian,
+            segment::data_types::text_index::Language::Spanish => Language::Spanish,
+            segment::data_types::text_index::Language::Swedish => Language::Swedish,
+            segment::data_types::text_index::Language::Turkish => Language::Turkish,
+        }
+    }
+}
+
+impl TryFrom<Language> for segment::data_types::text_index::Language {
+    type Error = Status;
+    fn try_from(language: Language) -> Result<Self, Self::Error> {
+        match language {
+            Language::UnknownLanguage => Err(Status::invalid_argument("unknown language")),
+            Language::Arabic => Ok(segment::data_types::text_index::Language::Arabic),
+            Language::Danish => Ok(segment::data_types::text_index::Language::Danish),
+            Language::Dutch => Ok(segment::data_types::text_index::Language::Dutch),
+            Language::English => Ok(segment::data_types::text_index::Language::English),
+            Language::Finnish => Ok(segment::data_types::text_index::Language::Finnish),
+            Language::French => Ok(segment::data_types::text_index::Language::French),
+            Language::German => Ok(segment::data_types::text_index::Language::German),
+            Language::Greek => Ok(segment::data_types::text_index::Language::Greek),
+            Language::Hungarian => Ok(segment::data_types::text_index::Language::Hungarian),
+            Language::Italian => Ok(segment::data_types::text_index::Language::Italian),
+            Language::Norwegian => Ok(segment::data_types::text_index::Language::Norwegian),
+            Language::Portuguese => Ok(segment::data_types::text_index::Language::Portuguese),
+            Language::Romanian => Ok(segment::data_types::text_index::Language::Romanian),
+            Language::Russian => Ok(segment::data_types::text_index::Language::Russian),
+            Language::Spanish => Ok(segment::data_types::text_index::Language::Spanish),
+            Language::Swedish => Ok(segment::data_types::text_index::Language::Swedish),
+            Language::Turkish => Ok(segment::data_types::text_index::Language::Turkish),
+        }
+    }
+}
+
+fn language_from_i32(language: i32) -> Result<segment::data_types::text_index::Language, Status> {
+    Language::from_i32(language)
+        .map(TryInto::try_into)
+        .unwrap_or_else(|| Err(Status::invalid_argument("unknown language")))
+}
+
 impl TryFrom<TextIndexParams> for segment::data_types::text_index::TextIndexParams {
     type Error = Status;
     fn try_from(params: TextIndexParams) -> Result<Self, Self::Error> {
@@ -211,6 +357,22 @@ impl TryFrom<TextIndexParams> for segment::data_types::text_index::TextIndexPara
             lowercase: params.lowercase,
             min_token_len: params.min_token_len.map(|x| x as usize),
             max_token_len: params.max_token_len.map(|x| x as usize),
+            stemmer: params.stemmer.map(language_from_i32).transpose()?,
+            stopwords: params
+                .stopwords
+                .map(|stopwords| {
+                    Ok::<_, Status>(segment::data_types::text_index::StopwordsSet {
+                        languages: stopwords
+                            .languages
+                            .into_iter()
+                            .map(language_from_i32)
+                            .collect::<Result<_, _>>()?,
+                        custom: stopwords.custom,
+                    })
+                })
+                .transpose()?,
+            phrase_matching: params.phrase_matching,
+            on_disk: params.on_disk,
         })
     }
 }
@@ -223,10 +385,77 @@ impl TryFrom<PayloadIndexParams> for segment::data_types::text_index::TextIndexP
             Some(IndexParams::TextIndexParams(text_index_params)) => {
                 Ok(text_index_params.try_into()?)
             }
+            Some(IndexParams::CompositeIndexParams(_)) => Err(Status::invalid_argument(
+                "expected text index params, got composite index params",
+            )),
+            Some(IndexParams::KeywordIndexParams(_)) => Err(Status::invalid_argument(
+                "expected text index params, got keyword index params",
+            )),
+            Some(IndexParams::IntegerIndexParams(_)) => Err(Status::invalid_argument(
+                "expected text index params, got integer index params",
+            )),
+            Some(IndexParams::FloatIndexParams(_)) => Err(Status::invalid_argument(
+                "expected text index params, got float index params",
+            )),
+            Some(IndexParams::GeoIndexParams(_)) => Err(Status::invalid_argument(
+                "expected text index params, got geo index params",
+            )),
+        }
+    }
+}
+
+impl From<KeywordIndexParams> for segment::data_types::keyword_index::KeywordIndexParams {
+    fn from(params: KeywordIndexParams) -> Self {
+        segment::data_types::keyword_index::KeywordIndexParams {
+            r#type: segment::data_types::keyword_index::KeywordIndexType::Keyword,
+            is_tenant: params.is_tenant,
+            on_disk: params.on_disk,
         }
     }
 }
 
+impl From<IntegerIndexParams> for segment::data_types::integer_index::IntegerIndexParams {
+    fn from(params: IntegerIndexParams) -> Self {
+        segment::data_types::integer_index::IntegerIndexParams {
+            r#type: segment::data_types::integer_index::IntegerIndexType::Integer,
+            on_disk: params.on_disk,
+        }
+    }
+}
+
+impl From<FloatIndexParams> for segment::data_types::float_index::FloatIndexParams {
+    fn from(params: FloatIndexParams) -> Self {
+        segment::data_types::float_index::FloatIndexParams {
+            r#type: segment::data_types::float_index::FloatIndexType::Float,
+            on_disk: params.on_disk,
+        }
+    }
+}
+
+impl From<GeoIndexParams> for segment::data_types::geo_index::GeoIndexParams {
+    fn from(params: GeoIndexParams) -> Self {
+        segment::data_types::geo_index::GeoIndexParams {
+            r#type: segment::data_types::geo_index::GeoIndexType::Geo,
+            on_disk: params.on_disk,
+        }
+    }
+}
+
+impl TryFrom<CompositeIndexParams> for segment::data_types::composite_index::CompositeIndexParams {
+    type Error = Status;
+    fn try_from(params: CompositeIndexParams) -> Result<Self, Self::Error> {
+        if params.fields.len() < 2 {
+            return Err(Status::invalid_argument(
+                "composite index requires at least 2 fields",
+            ));
+        }
+        Ok(segment::data_types::composite_index::CompositeIndexParams {
+            r#type: segment::data_types::composite_index::CompositeIndexType::Composite,
+            fields: params.fields,
+        })
+    }
+}
+
 impl TryFrom<IndexParams> for segment::types::PayloadSchemaParams {
     type Error = Status;
 
@@ -235,6 +464,21 @@ impl TryFrom<IndexParams> for segment::types::PayloadSchemaParams {
             IndexParams::TextIndexParams(text_index_params) => Ok(
                 segment::types::PayloadSchemaParams::Text(text_index_params.try_into()?),
             ),
+            IndexParams::CompositeIndexParams(composite_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Composite(composite_index_params.try_into()?),
+            ),
+            IndexParams::KeywordIndexParams(keyword_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Keyword(keyword_index_params.into()),
+            ),
+            IndexParams::IntegerIndexParams(integer_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Integer(integer_index_params.into()),
+            ),
+            IndexParams::FloatIndexParams(float_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Float(float_index_params.into()),
+            ),
+            IndexParams::GeoIndexParams(geo_index_params) => Ok(
+                segment::types::PayloadSchemaParams::Geo(geo_index_params.into()),
+            ),
         }
     }
 }
@@ -256,6 +500,9 @@ impl TryFrom<PayloadSchemaInfo> for segment::types::PayloadIndexInfo {
                 PayloadSchemaType::Geo => segment::types::PayloadSchemaType::Geo,
                 PayloadSchemaType::Text => segment::types::PayloadSchemaType::Text,
                 PayloadSchemaType::Bool => segment::types::PayloadSchemaType::Bool,
+                PayloadSchemaType::Datetime => segment::types::PayloadSchemaType::Datetime,
+                PayloadSchemaType::Uuid => segment::types::PayloadSchemaType::Uuid,
+                PayloadSchemaType::Composite => segment::types::PayloadSchemaType::Composite,
                 PayloadSchemaType::UnknownType => {
                     return Err(Status::invalid_argument(
                         "Malformed payload schema".to_string(),
@@ -394,12 +641,99 @@ impl From<segment::types::PointIdType> for PointId {
 
 impl From<segment::data_types::vectors::VectorType> for Vector {
     fn from(vector: segment::data_types::vectors::VectorType) -> Self {
-        Self { data: vector }
+        Self {
+            data: vector,
+            indices: None,
+            vectors_count: None,
+        }
+    }
+}
+
+impl From<Vec<segment::data_types::vectors::VectorType>> for Vector {
+    fn from(vectors: Vec<segment::data_types::vectors::VectorType>) -> Self {
+        let vectors_count = vectors.len() as u32;
+        Self {
+            data: vectors.into_iter().flatten().collect(),
+            indices: None,
+            vectors_count: Some(vectors_count),
+        }
+    }
+}
+
+impl From<ContextPair<segment::data_types::vectors::VectorType>>
+    for crate::grpc::qdrant::ContextPair
+{
+    fn from(pair: ContextPair<segment::data_types::vectors::VectorType>) -> Self {
+        let ContextPair { positive, negative } = pair;
+        Self {
+            positive: Some(positive.into()),
+            negative: Some(negative.into()),
+        }
+    }
+}
+
+impl TryFrom<crate::grpc::qdrant::ContextPair>
+    for ContextPair<segment::data_types::vectors::VectorType>
+{
+    type Error = Status;
+
+    fn try_from(pair: crate::grpc::qdrant::ContextPair) -> Result<Self, Self::Error> {
+        let crate::grpc::qdrant::ContextPair { positive, negative } = pair;
+        Ok(Self {
+            positive: positive
+                .ok_or_else(|| Status::invalid_argument("Positive example is not specified"))?
+                .data,
+            negative: negative
+                .ok_or_else(|| Status::invalid_argument("Negative example is not specified"))?
+                .data,
+        })
+    }
+}
+
+impl From<SparseVector> for Vector {
+    fn from(vector: SparseVector) -> Self {
+        Self {
+            data: vector.weights,
+            indices: Some(SparseIndices {
+                data: vector.indices,
+            }),
+            vectors_count: None,
+        }
+    }
+}
+
+impl From<segment::data_types::vectors::Vector> for Vector {
+    fn from(vector: segment::data_types::vectors::Vector) -> Self {
+        match vector {
+            segment::data_types::vectors::Vector::Dense(vector) => vector.into(),
+            segment::data_types::vectors::Vector::MultiDense(vectors) => vectors.into(),
+            segment::data_types::vectors::Vector::Sparse(vector) => vector.into(),
+        }
+    }
+}
+
+impl From<Vector> for segment::data_types::vectors::Vector {
+    fn from(vector: Vector) -> Self {
+        match (vector.indices, vector.vectors_count) {
+            (Some(indices), _) => SparseVector::new(indices.data, vector.data).into(),
+            (None, Some(0)) => Self::MultiDense(Vec::new()),
+            (None, Some(vectors_count)) => {
+                let dim = vector.data.len() / vectors_count as usize;
+                Self::MultiDense(
+                    vector
+                        .data
+                        .chunks(dim.max(1))
+                        .map(|vector| vector.to_vec())
+                        .collect(),
+                )
+            }
+            (None, None) => vector.data.into(),
+        }
     }
 }
 
-impl From<HashMap<String, Vec<VectorElementType>>> for NamedVectors {
-    fn from(vectors: HashMap<String, Vec<VectorElementType>>) -> Self {
+impl From<HashMap<String, segment::data_types::vectors::Vector>> for NamedVectors {
+    fn from(vectors: HashMap<String, segment::data_types::vectors::Vector>) -> Self {
         Self {
             vectors: vectors
                 .into_iter()
@@ -430,10 +764,290 @@ impl From<segment::types::ScoredPoint> for ScoredPoint {
             score: point.score,
             version: point.version,
             vectors: point.vector.map(|v| v.into()),
+            explanation: point.explanation.map(|explanation| (*explanation).into()),
+        }
+    }
+}
+
+impl From<segment::types::ScoreExplanation> for ScoreExplanation {
+    fn from(explanation: segment::types::ScoreExplanation) -> Self {
+        let segment::types::ScoreExplanation {
+            similarity,
+            quantized_similarity,
+            prefetches,
+            formula_terms,
+        } = explanation;
+        Self {
+            similarity,
+            quantized_similarity,
+            // Prefetches, which did not return the point, are skipped
+            prefetches: prefetches
+                .into_iter()
+                .enumerate()
+                .filter_map(|(prefetch, score)| {
+                    let segment::types::PrefetchScore {
+                        rank,
+                        score,
+                        contribution,
+                        explanation,
+                    } = score?;
+                    Some(PrefetchScore {
+                        prefetch: prefetch as u32,
+                        rank: rank as u64,
+                        score,
+                        contribution,
+                        explanation: explanation.map(|explanation| (*explanation).into()),
+                    })
+                })
+                .collect(),
+            formula_terms,
+        }
+    }
+}
+
+impl From<ScoreExplanation> for segment::types::ScoreExplanation {
+    fn from(explanation: ScoreExplanation) -> Self {
+        let ScoreExplanation {
+            similarity,
+            quantized_similarity,
+            prefetches: scores,
+            formula_terms,
+        } = explanation;
+
+        let prefetches_count = scores
+            .iter()
+            .map(|score| score.prefetch as usize + 1)
+            .max()
+            .unwrap_or_default();
+        let mut prefetches = vec![None; prefetches_count];
+        for score in scores {
+            let PrefetchScore {
+                prefetch,
+                rank,
+                score,
+                contribution,
+                explanation,
+            } = score;
+            prefetches[prefetch as usize] = Some(segment::types::PrefetchScore {
+                rank: rank as usize,
+                score,
+                contribution,
+                explanation: explanation.map(|explanation| Box::new(explanation.into())),
+            });
+        }
+
+        Self {
+            similarity,
+            quantized_similarity,
+            prefetches,
+            formula_terms,
+        }
+    }
+}
+
+impl From<segment::common::hardware_counter::HardwareUsage> for HardwareUsage {
+    fn from(usage: segment::common::hardware_counter::HardwareUsage) -> Self {
+        let segment::common::hardware_counter::HardwareUsage {
+            cpu_time_micros,
+            vector_bytes_read,
+            payload_bytes_read,
+            segments_searched,
+        } = usage;
+        Self {
+            cpu_time_micros,
+            vector_bytes_read,
+            payload_bytes_read,
+            segments_searched,
+        }
+    }
+}
+
+impl From<HardwareUsage> for segment::common::hardware_counter::HardwareUsage {
+    fn from(usage: HardwareUsage) -> Self {
+        let HardwareUsage {
+            cpu_time_micros,
+            vector_bytes_read,
+            payload_bytes_read,
+            segments_searched,
+        } = usage;
+        Self {
+            cpu_time_micros,
+            vector_bytes_read,
+            payload_bytes_read,
+            segments_searched,
+        }
+    }
+}
+
+impl From<segment::common::search_explain::ExplainMode> for ExplainMode {
+    fn from(mode: segment::common::search_explain::ExplainMode) -> Self {
+        match mode {
+            segment::common::search_explain::ExplainMode::Plan => ExplainMode::Plan,
+            segment::common::search_explain::ExplainMode::Analyze => ExplainMode::Analyze,
+        }
+    }
+}
+
+impl From<ExplainMode> for segment::common::search_explain::ExplainMode {
+    fn from(mode: ExplainMode) -> Self {
+        match mode {
+            ExplainMode::Plan => segment::common::search_explain::ExplainMode::Plan,
+            ExplainMode::Analyze => segment::common::search_explain::ExplainMode::Analyze,
+        }
+    }
+}
+
+pub fn explain_mode_from_i32(
+    mode: i32,
+) -> Result<segment::common::search_explain::ExplainMode, Status> {
+    ExplainMode::from_i32(mode)
+        .map(Into::into)
+        .ok_or_else(|| Status::invalid_argument(format!("Unknown explain mode: {mode}")))
+}
+
+impl From<segment::common::search_explain::SearchStrategy> for SearchStrategy {
+    fn from(strategy: segment::common::search_explain::SearchStrategy) -> Self {
+        match strategy {
+            segment::common::search_explain::SearchStrategy::Hnsw => SearchStrategy::Hnsw,
+            segment::common::search_explain::SearchStrategy::FullScan => SearchStrategy::FullScan,
+            segment::common::search_explain::SearchStrategy::FilteredScan => {
+                SearchStrategy::FilteredScan
+            }
+            segment::common::search_explain::SearchStrategy::SparseIndex => {
+                SearchStrategy::SparseIndex
+            }
+        }
+    }
+}
+
+impl From<SearchStrategy> for segment::common::search_explain::SearchStrategy {
+    fn from(strategy: SearchStrategy) -> Self {
+        match strategy {
+            SearchStrategy::Hnsw => segment::common::search_explain::SearchStrategy::Hnsw,
+            SearchStrategy::FullScan => segment::common::search_explain::SearchStrategy::FullScan,
+            SearchStrategy::FilteredScan => {
+                segment::common::search_explain::SearchStrategy::FilteredScan
+            }
+            SearchStrategy::SparseIndex => {
+                segment::common::search_explain::SearchStrategy::SparseIndex
+            }
+        }
+    }
+}
+
+impl From<segment::common::search_explain::VectorSearchExplain> for VectorSearchExplain {
+    fn from(explain: segment::common::search_explain::VectorSearchExplain) -> Self {
+        let segment::common::search_explain::VectorSearchExplain {
+            strategy,
+            queries,
+            quantized,
+            estimated_cardinality,
+            actual_cardinality,
+            payload_indexes,
+            candidates,
+        } = explain;
+        Self {
+            strategy: SearchStrategy::from(strategy) as i32,
+            queries: queries as u64,
+            quantized,
+            estimated_cardinality: estimated_cardinality.map(|cardinality| CardinalityEstimate {
+                min: cardinality.min as u64,
+                exp: cardinality.exp as u64,
+                max: cardinality.max as u64,
+            }),
+            actual_cardinality: actual_cardinality.map(|cardinality| cardinality as u64),
+            payload_indexes,
+            candidates: candidates.map(|candidates| SearchCandidates {
+                scored: candidates.scored as u64,
+                rescored: candidates.rescored as u64,
+                returned: candidates.returned as u64,
+            }),
+        }
+    }
+}
+
+impl TryFrom<VectorSearchExplain> for segment::common::search_explain::VectorSearchExplain {
+    type Error = Status;
+
+    fn try_from(explain: VectorSearchExplain) -> Result<Self, Self::Error> {
+        let VectorSearchExplain {
+            strategy,
+            queries,
+            quantized,
+            estimated_cardinality,
+            actual_cardinality,
+            payload_indexes,
+            candidates,
+        } = explain;
+        let strategy = SearchStrategy::from_i32(strategy).ok_or_else(|| {
+            Status::invalid_argument(format!("Unknown search strategy: {strategy}"))
+        })?;
+        Ok(Self {
+            strategy: strategy.into(),
+            queries: queries as usize,
+            quantized,
+            estimated_cardinality: estimated_cardinality.map(|cardinality| {
+                segment::common::search_explain::CardinalityEstimate {
+                    min: cardinality.min as usize,
+                    exp: cardinality.exp as usize,
+                    max: cardinality.max as usize,
+                }
+            }),
+            actual_cardinality: actual_cardinality.map(|cardinality| cardinality as usize),
+            payload_indexes,
+            candidates: candidates.map(|candidates| {
+                segment::common::search_explain::SearchCandidates {
+                    scored: candidates.scored as usize,
+                    rescored: candidates.rescored as usize,
+                    returned: candidates.returned as usize,
+                }
+            }),
+        })
+    }
+}
+
+impl From<segment::common::search_explain::ShardSearchExplain> for ShardSearchExplain {
+    fn from(explain: segment::common::search_explain::ShardSearchExplain) -> Self {
+        Self {
+            shard_id: explain.shard_id,
+            peer_id: explain.peer_id,
+            segments: explain
+                .segments
+                .into_iter()
+                .map(|segment| SegmentSearchExplain {
+                    segment_id: segment.segment_id as u64,
+                    searches: segment.searches.into_iter().map(Into::into).collect(),
+                })
+                .collect(),
         }
     }
 }
 
+impl TryFrom<ShardSearchExplain> for segment::common::search_explain::ShardSearchExplain {
+    type Error = Status;
+
+    fn try_from(explain: ShardSearchExplain) -> Result<Self, Self::Error> {
+        Ok(Self {
+            shard_id: explain.shard_id,
+            peer_id: explain.peer_id,
+            segments: explain
+                .segments
+                .into_iter()
+                .map(|segment| {
+                    Ok(segment::common::search_explain::SegmentSearchExplain {
+                        segment_id: segment.segment_id as usize,
+                        searches: segment
+                            .searches
+                            .into_iter()
+                            .map(TryInto::try_into)
+                            .collect::<Result<_, Status>>()?,
+                    })
+                })
+                .collect::<Result<_, Status>>()?,
+        })
+    }
+}
+
 impl From<segment::data_types::groups::GroupId> for GroupId {
     fn from(key: segment::data_types::groups::GroupId) -> Self {
         match key {
@@ -450,12 +1064,12 @@ impl From<segment::data_types::groups::GroupId> for GroupId {
     }
 }
 
-impl From<NamedVectors> for HashMap<String, Vec<VectorElementType>> {
+impl From<NamedVectors> for HashMap<String, segment::data_types::vectors::Vector> {
     fn from(vectors: NamedVectors) -> Self {
         vectors
             .vectors
             .into_iter()
-            .map(|(name, vector)| (name, vector.data))
+            .map(|(name, vector)| (name, vector.into()))
             .collect()
     }
 }
@@ -467,6 +1081,9 @@ impl TryFrom<Vectors> for segment::data_types::vectors::VectorStruct {
         match vectors.vectors_options {
             Some(vectors_options) => Ok(match vectors_options {
                 VectorsOptions::Vector(vector) => {
+                    if vector.indices.is_some() {
+                        return Err(Status::invalid_argument("Sparse vector must be named"));
+                    }
                     segment::data_types::vectors::VectorStruct::Single(vector.data)
                 }
                 VectorsOptions::Vectors(vectors) => {
@@ -695,6 +1312,7 @@ impl TryFrom<Filter> for segment::types::Filter {
     fn try_from(value: Filter) -> Result<Self, Self::Error> {
         Ok(Self {
             should: conditions_helper_from_grpc(value.should)?,
+            min_should: value.min_should.map(|v| v.try_into()).transpose()?,
             must: conditions_helper_from_grpc(value.must)?,
             must_not: conditions_helper_from_grpc(value.must_not)?,
         })
@@ -707,6 +1325,31 @@ impl From<segment::types::Filter> for Filter {
             should: conditions_helper_to_grpc(value.should),
             must: conditions_helper_to_grpc(value.must),
             must_not: conditions_helper_to_grpc(value.must_not),
+            min_should: value.min_should.map(|v| v.into()),
+        }
+    }
+}
+
+impl TryFrom<MinShould> for segment::types::MinShould {
+    type Error = Status;
+
+    fn try_from(value: MinShould) -> Result<Self, Self::Error> {
+        Ok(Self {
+            conditions: value
+                .conditions
+                .into_iter()
+                .map(|c| c.try_into())
+                .collect::<Result<_, _>>()?,
+            min_count: value.min_count as usize,
+        })
+    }
+}
+
+impl From<segment::types::MinShould> for MinShould {
+    fn from(value: segment::types::MinShould) -> Self {
+        Self {
+            conditions: value.conditions.into_iter().map(|c| c.into()).collect(),
+            min_count: value.min_count as u64,
         }
     }
 }
@@ -723,6 +1366,9 @@ impl TryFrom<Condition> for segment::types::Condition {
                 ConditionOneOf::HasId(has_id) => {
                     Ok(segment::types::Condition::HasId(has_id.try_into()?))
                 }
+                ConditionOneOf::HasVector(has_vector) => {
+                    Ok(segment::types::Condition::HasVector(has_vector.into()))
+                }
                 ConditionOneOf::Filter(filter) => {
                     Ok(segment::types::Condition::Filter(filter.try_into()?))
                 }
@@ -750,6 +1396,9 @@ impl From<segment::types::Condition> for Condition {
             }
             segment::types::Condition::IsNull(is_null) => ConditionOneOf::IsNull(is_null.into()),
             segment::types::Condition::HasId(has_id) => ConditionOneOf::HasId(has_id.into()),
+            segment::types::Condition::HasVector(has_vector) => {
+                ConditionOneOf::HasVector(has_vector.into())
+            }
             segment::types::Condition::Filter(filter) => ConditionOneOf::Filter(filter.into()),
             segment::types::Condition::Nested(nested) => {
                 ConditionOneOf::Nested(nested.nested.into())
@@ -839,6 +1488,22 @@ impl From<segment::types::HasIdCondition> for HasIdCondition {
     }
 }
 
+impl From<HasVectorCondition> for segment::types::HasVectorCondition {
+    fn from(value: HasVectorCondition) -> Self {
+        Self {
+            has_vector: value.has_vector,
+        }
+    }
+}
+
+impl From<segment::types::HasVectorCondition> for HasVectorCondition {
+    fn from(value: segment::types::HasVectorCondition) -> Self {
+        Self {
+            has_vector: value.has_vector,
+        }
+    }
+}
+
 impl TryFrom<FieldCondition> for segment::types::FieldCondition {
     type Error = Status;
 
@@ -851,6 +1516,7 @@ impl TryFrom<FieldCondition> for segment::types::FieldCondition {
             geo_radius,
             values_count,
             geo_polygon,
+            datetime_range,
         } = value;
 
         let geo_bounding_box =
@@ -861,6 +1527,7 @@ impl TryFrom<FieldCondition> for segment::types::FieldCondition {
             key,
             r#match: r#match.map_or_else(|| Ok(None), |m| m.try_into().map(Some))?,
             range: range.map(Into::into),
+            datetime_range: datetime_range.map_or_else(|| Ok(None), |r| r.try_into().map(Some))?,
             geo_bounding_box,
             geo_radius,
             geo_polygon,
@@ -875,6 +1542,7 @@ impl From<segment::types::FieldCondition> for FieldCondition {
             key,
             r#match,
             range,
+            datetime_range,
             geo_bounding_box,
             geo_radius,
             geo_polygon,
@@ -892,6 +1560,7 @@ impl From<segment::types::FieldCondition> for FieldCondition {
             geo_radius,
             geo_polygon,
             values_count: values_count.map(Into::into),
+            datetime_range: datetime_range.map(Into::into),
         }
     }
 }
@@ -1026,6 +1695,124 @@ impl From<segment::types::Range> for Range {
     }
 }
 
+impl TryFrom<DatetimeRange> for segment::types::DatetimeRange {
+    type Error = Status;
+
+    fn try_from(value: DatetimeRange) -> Result<Self, Self::Error> {
+        let convert = |timestamp: Option<prost_types::Timestamp>| {
+            timestamp.map(proto_to_date_time).transpose()
+        };
+        Ok(Self {
+            lt: convert(value.lt)?,
+            gt: convert(value.gt)?,
+            gte: convert(value.gte)?,
+            lte: convert(value.lte)?,
+        })
+    }
+}
+
+impl From<segment::types::DatetimeRange> for DatetimeRange {
+    fn from(value: segment::types::DatetimeRange) -> Self {
+        let convert = |datetime: segment::types::DateTimePayloadType| {
+            date_time_to_proto(datetime.naive_utc())
+        };
+        Self {
+            lt: value.lt.map(convert),
+            gt: value.gt.map(convert),
+            gte: value.gte.map(convert),
+            lte: value.lte.map(convert),
+        }
+    }
+}
+
+impl From<Direction> for segment::data_types::order_by::Direction {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Asc => segment::data_types::order_by::Direction::Asc,
+            Direction::Desc => segment::data_types::order_by::Direction::Desc,
+        }
+    }
+}
+
+impl From<segment::data_types::order_by::Direction> for Direction {
+    fn from(value: segment::data_types::order_by::Direction) -> Self {
+        match value {
+            segment::data_types::order_by::Direction::Asc => Direction::Asc,
+            segment::data_types::order_by::Direction::Desc => Direction::Desc,
+        }
+    }
+}
+
+impl TryFrom<StartFrom> for segment::data_types::order_by::StartFrom {
+    type Error = Status;
+
+    fn try_from(value: StartFrom) -> Result<Self, Self::Error> {
+        match value.value {
+            Some(start_from::Value::Float(float)) => Ok(Self::Float(float)),
+            Some(start_from::Value::Integer(integer)) => Ok(Self::Integer(integer)),
+            Some(start_from::Value::Datetime(datetime)) => {
+                segment::types::parse_datetime(&datetime)
+                    .map(Self::Datetime)
+                    .ok_or_else(|| {
+                        Status::invalid_argument(format!("Malformed RFC 3339 datetime: {datetime}"))
+                    })
+            }
+            None => Err(Status::invalid_argument("Malformed StartFrom type")),
+        }
+    }
+}
+
+impl From<segment::data_types::order_by::StartFrom> for StartFrom {
+    fn from(value: segment::data_types::order_by::StartFrom) -> Self {
+        let value = match value {
+            segment::data_types::order_by::StartFrom::Integer(integer) => {
+                start_from::Value::Integer(integer)
+            }
+            segment::data_types::order_by::StartFrom::Float(float) => {
+                start_from::Value::Float(float)
+            }
+            segment::data_types::order_by::StartFrom::Datetime(datetime) => {
+                start_from::Value::Datetime(datetime.to_rfc3339())
+            }
+        };
+        Self { value: Some(value) }
+    }
+}
+
+impl TryFrom<OrderBy> for segment::data_types::order_by::OrderBy {
+    type Error = Status;
+
+    fn try_from(value: OrderBy) -> Result<Self, Self::Error> {
+        let direction = value
+            .direction
+            .map(|direction| {
+                Direction::from_i32(direction)
+                    .map(Into::into)
+                    .ok_or_else(|| {
+                        Status::invalid_argument(format!("Unknown direction: {direction}"))
+                    })
+            })
+            .transpose()?;
+        Ok(Self {
+            key: value.key,
+            direction,
+            start_from: value.start_from.map(TryInto::try_into).transpose()?,
+        })
+    }
+}
+
+impl From<segment::data_types::order_by::OrderBy> for OrderBy {
+    fn from(value: segment::data_types::order_by::OrderBy) -> Self {
+        Self {
+            key: value.key,
+            direction: value
+                .direction
+                .map(|direction| Direction::from(direction) as i32),
+            start_from: value.start_from.map(Into::into),
+        }
+    }
+}
+
 impl From<ValuesCount> for segment::types::ValuesCount {
     fn from(value: ValuesCount) -> Self {
         Self {
@@ -1058,6 +1845,7 @@ impl TryFrom<Match> for segment::types::Match {
                 MatchValue::Integer(int) => int.into(),
                 MatchValue::Boolean(flag) => flag.into(),
                 MatchValue::Text(text) => segment::types::Match::Text(text.into()),
+                MatchValue::Phrase(phrase) => segment::types::Match::Phrase(phrase.into()),
                 MatchValue::Keywords(kwds) => kwds.strings.into(),
                 MatchValue::Integers(ints) => ints.integers.into(),
                 MatchValue::ExceptIntegers(kwds) => {
@@ -1066,6 +1854,16 @@ impl TryFrom<Match> for segment::types::Match {
                 MatchValue::ExceptKeywords(ints) => {
                     segment::types::Match::Except(ints.strings.into())
                 }
+                MatchValue::Wildcard(wildcard) => segment::types::Match::Wildcard(
+                    segment::types::MatchWildcard::new(wildcard).map_err(|err| {
+                        Status::invalid_argument(format!("Invalid wildcard pattern: {err}"))
+                    })?,
+                ),
+                MatchValue::Regex(regex) => segment::types::Match::Regex(
+                    segment::types::MatchRegex::new(regex).map_err(|err| {
+                        Status::invalid_argument(format!("Invalid regular expression: {err}"))
+                    })?,
+                ),
             }),
             _ => Err(Status::invalid_argument("Malformed Match condition")),
         }
@@ -1083,6 +1881,9 @@ impl From<segment::types::Match> for Match {
             segment::types::Match::Text(segment::types::MatchText { text }) => {
                 MatchValue::Text(text)
             }
+            segment::types::Match::Phrase(segment::types::MatchPhrase { phrase }) => {
+                MatchValue::Phrase(phrase)
+            }
             segment::types::Match::Any(any) => match any.any {
                 segment::types::AnyVariants::Keywords(strings) => {
                     MatchValue::Keywords(RepeatedStrings { strings })
@@ -1099,6 +1900,8 @@ impl From<segment::types::Match> for Match {
                     MatchValue::ExceptIntegers(RepeatedIntegers { integers })
                 }
             },
+            segment::types::Match::Wildcard(wildcard) => MatchValue::Wildcard(wildcard.wildcard),
+            segment::types::Match::Regex(regex) => MatchValue::Regex(regex.regex),
         };
         Self {
             match_value: Some(match_value),
@@ -1126,6 +1929,12 @@ pub fn date_time_to_proto(date_time: NaiveDateTime) -> prost_types::Timestamp {
     }
 }
 
+pub fn proto_to_date_time(timestamp: prost_types::Timestamp) -> Result<DateTime<Utc>, Status> {
+    NaiveDateTime::from_timestamp_opt(timestamp.seconds, timestamp.nanos as u32)
+        .map(|date_time| DateTime::from_naive_utc_and_offset(date_time, Utc))
+        .ok_or_else(|| Status::invalid_argument("Malformed timestamp"))
+}
+
 impl TryFrom<Distance> for segment::types::Distance {
     type Error = Status;
 
@@ -1139,6 +1948,9 @@ impl TryFrom<Distance> for segment::types::Distance {
             Distance::Cosine => segment::types::Distance::Cosine,
             Distance::Euclid => segment::types::Distance::Euclid,
             Distance::Dot => segment::types::Distance::Dot,
+            Distance::Manhattan => segment::types::Distance::Manhattan,
+            Distance::Hamming => segment::types::Distance::Hamming,
+            Distance::Jaccard => segment::types::Distance::Jaccard,
         })
     }
 }
@@ -1151,3 +1963,56 @@ pub fn from_grpc_dist(dist: i32) -> Result<segment::types::Distance, Status> {
         Some(grpc_distance) => Ok(grpc_distance.try_into()?),
     }
 }
+
+impl From<segment::types::VectorStorageDatatype> for Datatype {
+    fn from(value: segment::types::VectorStorageDatatype) -> Self {
+        match value {
+            segment::types::VectorStorageDatatype::Float32 => Datatype::Float32,
+            segment::types::VectorStorageDatatype::Float16 => Datatype::Float16,
+            segment::types::VectorStorageDatatype::Uint8 => Datatype::Uint8,
+        }
+    }
+}
+
+/// Convert gRPC datatype, `Default` means no datatype is specified
+pub fn from_grpc_datatype(
+    datatype: i32,
+) -> Result<Option<segment::types::VectorStorageDatatype>, Status> {
+    match Datatype::from_i32(datatype) {
+        None => Err(Status::invalid_argument(format!(
+            "Malformed datatype parameter, unexpected value: {datatype}"
+        ))),
+        Some(Datatype::Default) => Ok(None),
+        Some(Datatype::Float32) => Ok(Some(segment::types::VectorStorageDatatype::Float32)),
+        Some(Datatype::Float16) => Ok(Some(segment::types::VectorStorageDatatype::Float16)),
+        Some(Datatype::Uint8) => Ok(Some(segment::types::VectorStorageDatatype::Uint8)),
+    }
+}
+
+impl From<segment::types::MultiVectorConfig> for MultiVectorConfig {
+    fn from(value: segment::types::MultiVectorConfig) -> Self {
+        let comparator = match value.comparator {
+            segment::types::MultiVectorComparator::MaxSim => MultiVectorComparator::MaxSim,
+        };
+        Self {
+            comparator: comparator.into(),
+        }
+    }
+}
+
+impl TryFrom<MultiVectorConfig> for segment::types::MultiVectorConfig {
+    type Error = Status;
+
+    fn try_from(value: MultiVectorConfig) -> Result<Self, Self::Error> {
+        let comparator = match MultiVectorComparator::from_i32(value.comparator) {
+            Some(MultiVectorComparator::MaxSim) => segment::types::MultiVectorComparator::MaxSim,
+            None => {
+                return Err(Status::invalid_argument(format!(
+                    "Malformed multivector comparator, unexpected value: {}",
+                    value.comparator
+                )))
+            }
+        };
+        Ok(Self { comparator })
+    }
+}
@@ -1,6 +1,8 @@
 use std::fmt::Debug;
 
 use schemars::JsonSchema;
+use segment::common::hardware_counter::HardwareUsage;
+use segment::common::search_explain::ShardSearchExplain;
 use serde;
 use serde::{Deserialize, Serialize};
 
@@ -46,6 +48,16 @@ pub struct ApiResponse<D> {
     pub result: Option<D>,
     pub status: ApiStatus,
     pub time: f64,
+    /// Set for requests with a timeout: whether some of the results are missing,
+    /// because they were not gathered in time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partial: Option<bool>,
+    /// Set for search requests with `with_usage`: resources used to process the request
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<HardwareUsage>,
+    /// Set for search requests with `explain`: strategies of the searches in the segments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explain: Option<Vec<ShardSearchExplain>>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
@@ -1,3 +1,335 @@
+#[derive(serde::Serialize)]
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdatePeerAddressRequest {
+    /// Id of the peer
+    #[prost(uint64, tag = "1")]
+    pub peer_id: u64,
+    /// New URI of the peer for the internal communication
+    #[prost(string, tag = "2")]
+    #[validate(length(min = 1))]
+    pub uri: ::prost::alloc::string::String,
+    /// Wait timeout for operation commit in seconds, if not specified - default value will be supplied
+    #[prost(uint64, optional, tag = "3")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClusterOperationResponse {
+    /// Whether the operation was applied
+    #[prost(bool, tag = "1")]
+    pub result: bool,
+    /// Time spent to process
+    #[prost(double, tag = "2")]
+    pub time: f64,
+}
+/// Generated client implementations.
+pub mod cluster_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct ClusterClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl ClusterClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> ClusterClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> ClusterClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            ClusterClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_decoding_message_size(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.inner = self.inner.max_encoding_message_size(limit);
+            self
+        }
+        ///
+        /// Update the address of a peer in the cluster
+        pub async fn update_peer_address(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdatePeerAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ClusterOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Cluster/UpdatePeerAddress",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("qdrant.Cluster", "UpdatePeerAddress"));
+            self.inner.unary(req, path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod cluster_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with ClusterServer.
+    #[async_trait]
+    pub trait Cluster: Send + Sync + 'static {
+        ///
+        /// Update the address of a peer in the cluster
+        async fn update_peer_address(
+            &self,
+            request: tonic::Request<super::UpdatePeerAddressRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ClusterOperationResponse>,
+            tonic::Status,
+        >;
+    }
+    #[derive(Debug)]
+    pub struct ClusterServer<T: Cluster> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+        max_decoding_message_size: Option<usize>,
+        max_encoding_message_size: Option<usize>,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: Cluster> ClusterServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+                max_decoding_message_size: None,
+                max_encoding_message_size: None,
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.accept_compression_encodings.enable(encoding);
+            self
+        }
+        /// Compress responses with the given encoding, if the client supports it.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.send_compression_encodings.enable(encoding);
+            self
+        }
+        /// Limits the maximum size of a decoded message.
+        ///
+        /// Default: `4MB`
+        #[must_use]
+        pub fn max_decoding_message_size(mut self, limit: usize) -> Self {
+            self.max_decoding_message_size = Some(limit);
+            self
+        }
+        /// Limits the maximum size of an encoded message.
+        ///
+        /// Default: `usize::MAX`
+        #[must_use]
+        pub fn max_encoding_message_size(mut self, limit: usize) -> Self {
+            self.max_encoding_message_size = Some(limit);
+            self
+        }
+    }
+    impl<T, B> tonic::codegen::Service<http::Request<B>> for ClusterServer<T>
+    where
+        T: Cluster,
+        B: Body + Send + 'static,
+        B::Error: Into<StdError> + Send + 'static,
+    {
+        type Response = http::Response<tonic::body::BoxBody>;
+        type Error = std::convert::Infallible;
+        type Future = BoxFuture<Self::Response, Self::Error>;
+        fn poll_ready(
+            &mut self,
+            _cx: &mut Context<'_>,
+        ) -> Poll<std::result::Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+        fn call(&mut self, req: http::Request<B>) -> Self::Future {
+            let inner = self.inner.clone();
+            match req.uri().path() {
+                "/qdrant.Cluster/UpdatePeerAddress" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdatePeerAddressSvc<T: Cluster>(pub Arc<T>);
+                    impl<
+                        T: Cluster,
+                    > tonic::server::UnaryService<super::UpdatePeerAddressRequest>
+                    for UpdatePeerAddressSvc<T> {
+                        type Response = super::ClusterOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdatePeerAddressRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Cluster>::update_peer_address(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdatePeerAddressSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                _ => {
+                    Box::pin(async move {
+                        Ok(
+                            http::Response::builder()
+                                .status(200)
+                                .header("grpc-status", "12")
+                                .header("content-type", "application/grpc")
+                                .body(empty_body())
+                                .unwrap(),
+                        )
+                    })
+                }
+            }
+        }
+    }
+    impl<T: Cluster> Clone for ClusterServer<T> {
+        fn clone(&self) -> Self {
+            let inner = self.inner.clone();
+            Self {
+                inner,
+                accept_compression_encodings: self.accept_compression_encodings,
+                send_compression_encodings: self.send_compression_encodings,
+                max_decoding_message_size: self.max_decoding_message_size,
+                max_encoding_message_size: self.max_encoding_message_size,
+            }
+        }
+    }
+    impl<T: Cluster> Clone for _Inner<T> {
+        fn clone(&self) -> Self {
+            Self(Arc::clone(&self.0))
+        }
+    }
+    impl<T: std::fmt::Debug> std::fmt::Debug for _Inner<T> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self.0)
+        }
+    }
+    impl<T: Cluster> tonic::server::NamedService for ClusterServer<T> {
+        const NAME: &'static str = "qdrant.Cluster";
+    }
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MultiVectorConfig {
+    /// Comparator for multivector
+    #[prost(enumeration = "MultiVectorComparator", tag = "1")]
+    pub comparator: i32,
+}
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -21,6 +353,12 @@ pub struct VectorParams {
     /// If true - serve vectors from disk. If set to false, the vectors will be loaded in RAM.
     #[prost(bool, optional, tag = "5")]
     pub on_disk: ::core::option::Option<bool>,
+    /// If set, each point holds a list of vectors of the given size
+    #[prost(message, optional, tag = "6")]
+    pub multivector_config: ::core::option::Option<MultiVectorConfig>,
+    /// Data type used to store vector elements
+    #[prost(enumeration = "Datatype", optional, tag = "7")]
+    pub datatype: ::core::option::Option<i32>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -60,6 +398,34 @@ pub struct VectorParamsDiffMap {
         VectorParamsDiff,
     >,
 }
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseIndexConfig {
+    ///
+    /// Prefer a full scan search upto (excluding) this number of vectors.
+    /// Note: this is number of vectors, not KiloBytes.
+    #[prost(uint64, optional, tag = "1")]
+    pub full_scan_threshold: ::core::option::Option<u64>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseVectorParams {
+    /// Configuration of sparse index
+    #[prost(message, optional, tag = "1")]
+    pub index: ::core::option::Option<SparseIndexConfig>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseVectorConfig {
+    #[prost(map = "string, message", tag = "1")]
+    pub map: ::std::collections::HashMap<
+        ::prost::alloc::string::String,
+        SparseVectorParams,
+    >,
+}
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -200,6 +566,25 @@ pub struct WalConfigDiff {
     /// Number of segments to create in advance
     #[prost(uint64, optional, tag = "2")]
     pub wal_segments_ahead: ::core::option::Option<u64>,
+    /// Compression of the WAL records
+    #[prost(enumeration = "WalCompression", optional, tag = "3")]
+    pub wal_compression: ::core::option::Option<i32>,
+    /// When the WAL is synced to disk
+    #[prost(enumeration = "WalDurability", optional, tag = "4")]
+    pub wal_durability: ::core::option::Option<i32>,
+    /// Interval between WAL syncs in milliseconds, used with `Interval` durability
+    #[prost(uint64, optional, tag = "5")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub wal_fsync_interval_ms: ::core::option::Option<u64>,
+    /// Keep at most this number of already applied operations in the WAL
+    #[prost(uint64, optional, tag = "6")]
+    pub wal_retention_ops: ::core::option::Option<u64>,
+    /// Keep already applied operations in the WAL for at most this number of seconds
+    #[prost(uint64, optional, tag = "7")]
+    pub wal_retention_sec: ::core::option::Option<u64>,
+    /// Keep at most this size of already applied operations in the WAL, in MB
+    #[prost(uint64, optional, tag = "8")]
+    pub wal_retention_mb: ::core::option::Option<u64>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -267,6 +652,43 @@ pub struct OptimizersConfigDiff {
     /// Max number of threads, which can be used for optimization. If 0 - `NUM_CPU - 1` will be used
     #[prost(uint64, optional, tag = "8")]
     pub max_optimization_threads: ::core::option::Option<u64>,
+    ///
+    /// Payload key with the expiration time of points, as an RFC 3339 datetime.
+    /// Expired points are deleted every minute and replicated like regular updates.
+    /// A datetime index is created on the key, if missing. No expiration if not set.
+    #[prost(string, optional, tag = "9")]
+    pub expiration_key: ::core::option::Option<::prost::alloc::string::String>,
+    ///
+    /// Policy of merging small segments by size tiers.
+    /// If set, replaces merging of the smallest segments down to `default_segment_number`.
+    #[prost(message, optional, tag = "10")]
+    #[validate]
+    pub merge_policy: ::core::option::Option<TieredMergePolicy>,
+    ///
+    /// Payload key, which groups the points in optimized segments, e.g. a tenant id.
+    /// Points with the same value are stored next to each other, which speeds up search filtered by this key.
+    #[prost(string, optional, tag = "11")]
+    pub defragmentation_key: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TieredMergePolicy {
+    ///
+    /// Upper size bound of the smallest tier (in kilobytes).
+    /// All segments below this size are merged as if they were of this size.
+    #[prost(uint64, tag = "1")]
+    #[validate(range(min = 1))]
+    pub floor_segment_size_kb: u64,
+    /// Number of segments in a tier, which triggers merging of the tier
+    #[prost(uint64, tag = "2")]
+    #[validate(range(min = 3))]
+    pub max_segments_per_tier: u64,
+    /// Maximal number of segments merged at once, and size ratio of the adjacent tiers
+    #[prost(uint64, tag = "3")]
+    #[validate(range(min = 3))]
+    pub merge_factor: u64,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -408,6 +830,9 @@ pub struct CreateCollection {
     #[prost(message, optional, tag = "14")]
     #[validate]
     pub quantization_config: ::core::option::Option<QuantizationConfig>,
+    /// Sparse vector data config
+    #[prost(message, optional, tag = "15")]
+    pub sparse_vectors_config: ::core::option::Option<SparseVectorConfig>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -442,6 +867,13 @@ pub struct UpdateCollection {
     #[prost(message, optional, tag = "7")]
     #[validate]
     pub quantization_config: ::core::option::Option<QuantizationConfigDiff>,
+    /// New named vectors to add to the collection, existing points don't have them until set
+    #[prost(message, optional, tag = "8")]
+    #[validate]
+    pub add_vectors: ::core::option::Option<VectorsConfig>,
+    /// Names of the vectors to remove from the collection together with their data
+    #[prost(string, repeated, tag = "9")]
+    pub drop_vectors: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -468,6 +900,42 @@ pub struct CollectionOperationResponse {
     #[prost(double, tag = "2")]
     pub time: f64,
 }
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScoringPlugin {
+    /// Name of the plugin, used by `plugin` queries
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Version of the plugin, must increase with every change of the plugin
+    #[prost(uint64, tag = "2")]
+    pub version: u64,
+    /// WASM module, which exports function `score`
+    #[prost(bytes = "vec", tag = "3")]
+    pub module: ::prost::alloc::vec::Vec<u8>,
+    /// Payload fields, passed to the plugin after the scores of the prefetches
+    #[prost(string, repeated, tag = "4")]
+    pub features: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScoringPluginDiff {
+    #[prost(oneof = "scoring_plugin_diff::Plugin", tags = "1, 2")]
+    pub plugin: ::core::option::Option<scoring_plugin_diff::Plugin>,
+}
+/// Nested message and enum types in `ScoringPluginDiff`.
+pub mod scoring_plugin_diff {
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Plugin {
+        #[prost(message, tag = "1")]
+        Set(super::ScoringPlugin),
+        #[prost(message, tag = "2")]
+        Disabled(super::Disabled),
+    }
+}
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -492,6 +960,15 @@ pub struct CollectionParams {
     /// Fan-out every read request to these many additional remote nodes (and return first available response)
     #[prost(uint32, optional, tag = "8")]
     pub read_fan_out_factor: ::core::option::Option<u32>,
+    /// Configuration for sparse vectors
+    #[prost(message, optional, tag = "9")]
+    pub sparse_vectors_config: ::core::option::Option<SparseVectorConfig>,
+    /// Number of search results to keep in the cache of the collection
+    #[prost(uint64, optional, tag = "10")]
+    pub search_cache_size: ::core::option::Option<u64>,
+    /// WASM module, which scores the candidates of `plugin` queries
+    #[prost(message, optional, tag = "11")]
+    pub scoring_plugin: ::core::option::Option<ScoringPlugin>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -510,6 +987,12 @@ pub struct CollectionParamsDiff {
     /// Fan-out every read request to these many additional remote nodes (and return first available response)
     #[prost(uint32, optional, tag = "4")]
     pub read_fan_out_factor: ::core::option::Option<u32>,
+    /// Number of search results to keep in the cache of the collection, 0 to disable the cache
+    #[prost(uint64, optional, tag = "5")]
+    pub search_cache_size: ::core::option::Option<u64>,
+    /// Set or disable the scoring plugin of the collection
+    #[prost(message, optional, tag = "6")]
+    pub scoring_plugin: ::core::option::Option<ScoringPluginDiff>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -538,6 +1021,17 @@ pub struct CollectionConfig {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StopwordsSet {
+    /// Use built-in stopword lists of these languages
+    #[prost(enumeration = "Language", repeated, tag = "1")]
+    pub languages: ::prost::alloc::vec::Vec<i32>,
+    /// Additional words to ignore
+    #[prost(string, repeated, tag = "2")]
+    pub custom: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct TextIndexParams {
     /// Tokenizer type
     #[prost(enumeration = "TokenizerType", tag = "1")]
@@ -551,12 +1045,67 @@ pub struct TextIndexParams {
     /// Maximal token length
     #[prost(uint64, optional, tag = "4")]
     pub max_token_len: ::core::option::Option<u64>,
+    /// If set, reduce tokens to their stem with the Snowball stemmer of this language
+    #[prost(enumeration = "Language", optional, tag = "5")]
+    pub stemmer: ::core::option::Option<i32>,
+    /// Tokens to ignore in documents and queries
+    #[prost(message, optional, tag = "6")]
+    pub stopwords: ::core::option::Option<StopwordsSet>,
+    /// If true, keep the order of tokens to serve phrase conditions with the index
+    #[prost(bool, optional, tag = "7")]
+    pub phrase_matching: ::core::option::Option<bool>,
+    /// If true - store index on disk
+    #[prost(bool, optional, tag = "8")]
+    pub on_disk: ::core::option::Option<bool>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct KeywordIndexParams {
+    /// If true - points of the same tenant are stored together in optimized segments
+    #[prost(bool, optional, tag = "1")]
+    pub is_tenant: ::core::option::Option<bool>,
+    /// If true - store index on disk
+    #[prost(bool, optional, tag = "2")]
+    pub on_disk: ::core::option::Option<bool>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IntegerIndexParams {
+    /// If true - store index on disk
+    #[prost(bool, optional, tag = "1")]
+    pub on_disk: ::core::option::Option<bool>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FloatIndexParams {
+    /// If true - store index on disk
+    #[prost(bool, optional, tag = "1")]
+    pub on_disk: ::core::option::Option<bool>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GeoIndexParams {
+    /// If true - store index on disk
+    #[prost(bool, optional, tag = "1")]
+    pub on_disk: ::core::option::Option<bool>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CompositeIndexParams {
+    /// Ordered list of payload fields, which values are indexed together
+    #[prost(string, repeated, tag = "1")]
+    pub fields: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PayloadIndexParams {
-    #[prost(oneof = "payload_index_params::IndexParams", tags = "1")]
+    #[prost(oneof = "payload_index_params::IndexParams", tags = "1, 2, 3, 4, 5, 6")]
     pub index_params: ::core::option::Option<payload_index_params::IndexParams>,
 }
 /// Nested message and enum types in `PayloadIndexParams`.
@@ -568,6 +1117,21 @@ pub mod payload_index_params {
         /// Parameters for text index
         #[prost(message, tag = "1")]
         TextIndexParams(super::TextIndexParams),
+        /// Parameters for composite index
+        #[prost(message, tag = "2")]
+        CompositeIndexParams(super::CompositeIndexParams),
+        /// Parameters for keyword index
+        #[prost(message, tag = "3")]
+        KeywordIndexParams(super::KeywordIndexParams),
+        /// Parameters for integer index
+        #[prost(message, tag = "4")]
+        IntegerIndexParams(super::IntegerIndexParams),
+        /// Parameters for float index
+        #[prost(message, tag = "5")]
+        FloatIndexParams(super::FloatIndexParams),
+        /// Parameters for geo index
+        #[prost(message, tag = "6")]
+        GeoIndexParams(super::GeoIndexParams),
     }
 }
 #[derive(serde::Serialize)]
@@ -859,6 +1423,11 @@ pub enum Distance {
     Cosine = 1,
     Euclid = 2,
     Dot = 3,
+    Manhattan = 4,
+    /// Only for uint8 vectors, compares bits of the elements
+    Hamming = 5,
+    /// Only for uint8 vectors, compares bits of the elements
+    Jaccard = 6,
 }
 impl Distance {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -871,6 +1440,9 @@ impl Distance {
             Distance::Cosine => "Cosine",
             Distance::Euclid => "Euclid",
             Distance::Dot => "Dot",
+            Distance::Manhattan => "Manhattan",
+            Distance::Hamming => "Hamming",
+            Distance::Jaccard => "Jaccard",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -880,6 +1452,66 @@ impl Distance {
             "Cosine" => Some(Self::Cosine),
             "Euclid" => Some(Self::Euclid),
             "Dot" => Some(Self::Dot),
+            "Manhattan" => Some(Self::Manhattan),
+            "Hamming" => Some(Self::Hamming),
+            "Jaccard" => Some(Self::Jaccard),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum MultiVectorComparator {
+    MaxSim = 0,
+}
+impl MultiVectorComparator {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            MultiVectorComparator::MaxSim => "MaxSim",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "MaxSim" => Some(Self::MaxSim),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Datatype {
+    Default = 0,
+    Float32 = 1,
+    Float16 = 2,
+    Uint8 = 3,
+}
+impl Datatype {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Datatype::Default => "Default",
+            Datatype::Float32 => "Float32",
+            Datatype::Float16 => "Float16",
+            Datatype::Uint8 => "Uint8",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Default" => Some(Self::Default),
+            "Float32" => Some(Self::Float32),
+            "Float16" => Some(Self::Float16),
+            "Uint8" => Some(Self::Uint8),
             _ => None,
         }
     }
@@ -931,6 +1563,9 @@ pub enum PayloadSchemaType {
     Geo = 4,
     Text = 5,
     Bool = 6,
+    Datetime = 7,
+    Uuid = 8,
+    Composite = 9,
 }
 impl PayloadSchemaType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -946,6 +1581,9 @@ impl PayloadSchemaType {
             PayloadSchemaType::Geo => "Geo",
             PayloadSchemaType::Text => "Text",
             PayloadSchemaType::Bool => "Bool",
+            PayloadSchemaType::Datetime => "Datetime",
+            PayloadSchemaType::Uuid => "Uuid",
+            PayloadSchemaType::Composite => "Composite",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -958,6 +1596,9 @@ impl PayloadSchemaType {
             "Geo" => Some(Self::Geo),
             "Text" => Some(Self::Text),
             "Bool" => Some(Self::Bool),
+            "Datetime" => Some(Self::Datetime),
+            "Uuid" => Some(Self::Uuid),
+            "Composite" => Some(Self::Composite),
             _ => None,
         }
     }
@@ -992,6 +1633,73 @@ impl QuantizationType {
 #[derive(serde::Serialize)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum WalCompression {
+    None = 0,
+    Lz4 = 1,
+    Zstd = 2,
+}
+impl WalCompression {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            WalCompression::None => "WalCompressionNone",
+            WalCompression::Lz4 => "WalCompressionLz4",
+            WalCompression::Zstd => "WalCompressionZstd",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "WalCompressionNone" => Some(Self::None),
+            "WalCompressionLz4" => Some(Self::Lz4),
+            "WalCompressionZstd" => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum WalDurability {
+    /// Sync only before applying operations, which wait for the result
+    WaitedOperations = 0,
+    /// Sync after writing every operation
+    EveryOperation = 1,
+    /// Sync every `wal_fsync_interval_ms` milliseconds
+    Interval = 2,
+    /// Sync once records of a whole WAL segment are written since the last sync
+    SegmentRotation = 3,
+}
+impl WalDurability {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            WalDurability::WaitedOperations => "WalDurabilityWaitedOperations",
+            WalDurability::EveryOperation => "WalDurabilityEveryOperation",
+            WalDurability::Interval => "WalDurabilityInterval",
+            WalDurability::SegmentRotation => "WalDurabilitySegmentRotation",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "WalDurabilityWaitedOperations" => Some(Self::WaitedOperations),
+            "WalDurabilityEveryOperation" => Some(Self::EveryOperation),
+            "WalDurabilityInterval" => Some(Self::Interval),
+            "WalDurabilitySegmentRotation" => Some(Self::SegmentRotation),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum CompressionRatio {
     X4 = 0,
     X8 = 1,
@@ -1064,6 +1772,81 @@ impl TokenizerType {
 #[derive(serde::Serialize)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum Language {
+    UnknownLanguage = 0,
+    Arabic = 1,
+    Danish = 2,
+    Dutch = 3,
+    English = 4,
+    Finnish = 5,
+    French = 6,
+    German = 7,
+    Greek = 8,
+    Hungarian = 9,
+    Italian = 10,
+    Norwegian = 11,
+    Portuguese = 12,
+    Romanian = 13,
+    Russian = 14,
+    Spanish = 15,
+    Swedish = 16,
+    Turkish = 17,
+}
+impl Language {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Language::UnknownLanguage => "UnknownLanguage",
+            Language::Arabic => "Arabic",
+            Language::Danish => "Danish",
+            Language::Dutch => "Dutch",
+            Language::English => "English",
+            Language::Finnish => "Finnish",
+            Language::French => "French",
+            Language::German => "German",
+            Language::Greek => "Greek",
+            Language::Hungarian => "Hungarian",
+            Language::Italian => "Italian",
+            Language::Norwegian => "Norwegian",
+            Language::Portuguese => "Portuguese",
+            Language::Romanian => "Romanian",
+            Language::Russian => "Russian",
+            Language::Spanish => "Spanish",
+            Language::Swedish => "Swedish",
+            Language::Turkish => "Turkish",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UnknownLanguage" => Some(Self::UnknownLanguage),
+            "Arabic" => Some(Self::Arabic),
+            "Danish" => Some(Self::Danish),
+            "Dutch" => Some(Self::Dutch),
+            "English" => Some(Self::English),
+            "Finnish" => Some(Self::Finnish),
+            "French" => Some(Self::French),
+            "German" => Some(Self::German),
+            "Greek" => Some(Self::Greek),
+            "Hungarian" => Some(Self::Hungarian),
+            "Italian" => Some(Self::Italian),
+            "Norwegian" => Some(Self::Norwegian),
+            "Portuguese" => Some(Self::Portuguese),
+            "Romanian" => Some(Self::Romanian),
+            "Russian" => Some(Self::Russian),
+            "Spanish" => Some(Self::Spanish),
+            "Swedish" => Some(Self::Swedish),
+            "Turkish" => Some(Self::Turkish),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum ReplicaState {
     /// Active and sound
     Active = 0,
@@ -2162,6 +2945,19 @@ pub struct InitiateShardTransferRequest {
     #[prost(uint32, tag = "2")]
     pub shard_id: u32,
 }
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateShardSnapshotRequestInternal {
+    /// Name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Id of the shard
+    #[prost(uint32, tag = "2")]
+    pub shard_id: u32,
+}
 /// Generated client implementations.
 pub mod collections_internal_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -2251,9 +3047,36 @@ pub mod collections_internal_client {
         /// Get collection info
         pub async fn get(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetCollectionInfoRequestInternal>,
+            request: impl tonic::IntoRequest<super::GetCollectionInfoRequestInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::GetCollectionInfoResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.CollectionsInternal/Get",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.CollectionsInternal", "Get"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
+        /// Initiate shard transfer
+        pub async fn initiate(
+            &mut self,
+            request: impl tonic::IntoRequest<super::InitiateShardTransferRequest>,
         ) -> std::result::Result<
-            tonic::Response<super::GetCollectionInfoResponse>,
+            tonic::Response<super::CollectionOperationResponse>,
             tonic::Status,
         > {
             self.inner
@@ -2267,20 +3090,20 @@ pub mod collections_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.CollectionsInternal/Get",
+                "/qdrant.CollectionsInternal/Initiate",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.CollectionsInternal", "Get"));
+                .insert(GrpcMethod::new("qdrant.CollectionsInternal", "Initiate"));
             self.inner.unary(req, path, codec).await
         }
         ///
-        /// Initiate shard transfer
-        pub async fn initiate(
+        /// Create snapshot of the local shard
+        pub async fn create_shard_snapshot(
             &mut self,
-            request: impl tonic::IntoRequest<super::InitiateShardTransferRequest>,
+            request: impl tonic::IntoRequest<super::CreateShardSnapshotRequestInternal>,
         ) -> std::result::Result<
-            tonic::Response<super::CollectionOperationResponse>,
+            tonic::Response<super::CreateSnapshotResponse>,
             tonic::Status,
         > {
             self.inner
@@ -2294,11 +3117,13 @@ pub mod collections_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.CollectionsInternal/Initiate",
+                "/qdrant.CollectionsInternal/CreateShardSnapshot",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.CollectionsInternal", "Initiate"));
+                .insert(
+                    GrpcMethod::new("qdrant.CollectionsInternal", "CreateShardSnapshot"),
+                );
             self.inner.unary(req, path, codec).await
         }
     }
@@ -2328,6 +3153,15 @@ pub mod collections_internal_server {
             tonic::Response<super::CollectionOperationResponse>,
             tonic::Status,
         >;
+        ///
+        /// Create snapshot of the local shard
+        async fn create_shard_snapshot(
+            &self,
+            request: tonic::Request<super::CreateShardSnapshotRequestInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::CreateSnapshotResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct CollectionsInternalServer<T: CollectionsInternal> {
@@ -2503,6 +3337,53 @@ pub mod collections_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.CollectionsInternal/CreateShardSnapshot" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateShardSnapshotSvc<T: CollectionsInternal>(pub Arc<T>);
+                    impl<
+                        T: CollectionsInternal,
+                    > tonic::server::UnaryService<super::CreateShardSnapshotRequestInternal>
+                    for CreateShardSnapshotSvc<T> {
+                        type Response = super::CreateSnapshotResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateShardSnapshotRequestInternal>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as CollectionsInternal>::create_shard_snapshot(&inner, request)
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateShardSnapshotSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -2698,9 +3579,22 @@ pub mod point_id {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SparseIndices {
+    #[prost(uint32, repeated, tag = "1")]
+    pub data: ::prost::alloc::vec::Vec<u32>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Vector {
     #[prost(float, repeated, tag = "1")]
     pub data: ::prost::alloc::vec::Vec<f32>,
+    /// Indices of non-zero elements, if the vector is sparse
+    #[prost(message, optional, tag = "2")]
+    pub indices: ::core::option::Option<SparseIndices>,
+    /// Number of vectors flattened in data, if the vector is a multivector
+    #[prost(uint32, optional, tag = "3")]
+    pub vectors_count: ::core::option::Option<u32>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -2719,6 +3613,15 @@ pub struct UpsertPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Upsert only if all points are stored with this version. Points must belong to the same shard, requires `wait`
+    #[prost(uint64, optional, tag = "5")]
+    pub if_version: ::core::option::Option<u64>,
+    /// Upsert only if none of the points are stored. Points must belong to the same shard, requires `wait`
+    #[prost(bool, optional, tag = "6")]
+    pub if_absent: ::core::option::Option<bool>,
+    /// Retries with the same token are applied only once, and are assigned the same IDs for points without ID
+    #[prost(string, optional, tag = "7")]
+    pub idempotency_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -2738,6 +3641,9 @@ pub struct DeletePoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Retries with the same token are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub idempotency_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -2760,6 +3666,10 @@ pub struct GetPoints {
     /// Options for specifying read consistency guarantees
     #[prost(message, optional, tag = "6")]
     pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// If set, the request fails with a timeout error once this number of seconds passes
+    #[prost(uint64, optional, tag = "7")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -2779,6 +3689,9 @@ pub struct UpdatePointVectors {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Retries with the same token are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub idempotency_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2812,6 +3725,9 @@ pub struct DeletePointVectors {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "5")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Retries with the same token are applied only once
+    #[prost(string, optional, tag = "6")]
+    pub idempotency_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -2834,6 +3750,12 @@ pub struct SetPayloadPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "6")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Merge values into nested objects recursively, as JSON merge patch
+    #[prost(bool, optional, tag = "7")]
+    pub deep_merge: ::core::option::Option<bool>,
+    /// Retries with the same token are applied only once
+    #[prost(string, optional, tag = "8")]
+    pub idempotency_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -2847,7 +3769,7 @@ pub struct DeletePayloadPoints {
     /// Wait until the changes have been applied?
     #[prost(bool, optional, tag = "2")]
     pub wait: ::core::option::Option<bool>,
-    /// List of keys to delete
+    /// List of keys to delete, `*` in a key matches all keys of an object
     #[prost(string, repeated, tag = "3")]
     pub keys: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     /// Affected points
@@ -2856,6 +3778,9 @@ pub struct DeletePayloadPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "6")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Retries with the same token are applied only once
+    #[prost(string, optional, tag = "7")]
+    pub idempotency_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -2875,6 +3800,9 @@ pub struct ClearPayloadPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Retries with the same token are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub idempotency_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -3052,6 +3980,8 @@ pub struct SearchParams {
     pub hnsw_ef: ::core::option::Option<u64>,
     ///
     /// Search without approximation. If set to true, search may run long but with exact results.
+    /// All points of all segments are scored with full precision vectors then,
+    /// quantization and `indexed_only` are ignored.
     #[prost(bool, optional, tag = "2")]
     pub exact: ::core::option::Option<bool>,
     ///
@@ -3070,305 +4000,758 @@ pub struct SearchParams {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SearchPoints {
+pub struct SearchPoints {
+    /// name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// vector
+    #[prost(float, repeated, tag = "2")]
+    pub vector: ::prost::alloc::vec::Vec<f32>,
+    /// Filter conditions - return only those points that satisfy the specified conditions
+    #[prost(message, optional, tag = "3")]
+    #[validate]
+    pub filter: ::core::option::Option<Filter>,
+    /// Max number of result
+    #[prost(uint64, tag = "4")]
+    #[validate(range(min = 1))]
+    pub limit: u64,
+    /// Options for specifying which payload to include or not
+    #[prost(message, optional, tag = "6")]
+    pub with_payload: ::core::option::Option<WithPayloadSelector>,
+    /// Search config
+    #[prost(message, optional, tag = "7")]
+    #[validate]
+    pub params: ::core::option::Option<SearchParams>,
+    /// If provided - cut off results with worse scores
+    #[prost(float, optional, tag = "8")]
+    pub score_threshold: ::core::option::Option<f32>,
+    /// Offset of the result
+    #[prost(uint64, optional, tag = "9")]
+    pub offset: ::core::option::Option<u64>,
+    /// Which vector to use for search, if not specified - use default vector
+    #[prost(string, optional, tag = "10")]
+    #[validate(custom = "common::validation::validate_not_empty")]
+    pub vector_name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Options for specifying which vectors to include into response
+    #[prost(message, optional, tag = "11")]
+    pub with_vectors: ::core::option::Option<WithVectorsSelector>,
+    /// Options for specifying read consistency guarantees
+    #[prost(message, optional, tag = "12")]
+    pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// Indices of non-zero elements, if the search vector is sparse
+    #[prost(message, optional, tag = "13")]
+    pub sparse_indices: ::core::option::Option<SparseIndices>,
+    /// Full-text query, results are fused with the vector search results
+    #[prost(message, optional, tag = "14")]
+    #[validate]
+    pub text: ::core::option::Option<TextQuery>,
+    /// If set, the request fails with a timeout error once this number of seconds passes
+    #[prost(uint64, optional, tag = "15")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
+    /// If true, return the resources used to process the request
+    #[prost(bool, optional, tag = "16")]
+    pub with_usage: ::core::option::Option<bool>,
+    /// If set, return the strategies of the search in each segment
+    #[prost(enumeration = "ExplainMode", optional, tag = "17")]
+    pub explain: ::core::option::Option<i32>,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TextQuery {
+    /// Payload field with a full-text index
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1))]
+    pub key: ::prost::alloc::string::String,
+    /// Text to score the documents against
+    #[prost(string, tag = "2")]
+    pub text: ::prost::alloc::string::String,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchBatchPoints {
+    /// Name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "2")]
+    #[validate]
+    pub search_points: ::prost::alloc::vec::Vec<SearchPoints>,
+    /// Options for specifying read consistency guarantees
+    #[prost(message, optional, tag = "3")]
+    pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// If set, the request fails with a timeout error once this number of seconds passes
+    #[prost(uint64, optional, tag = "4")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
+    /// If true, return the resources used to process the request
+    #[prost(bool, optional, tag = "5")]
+    pub with_usage: ::core::option::Option<bool>,
+    /// If set, return the strategies of the search in each segment
+    #[prost(enumeration = "ExplainMode", optional, tag = "6")]
+    pub explain: ::core::option::Option<i32>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct WithLookup {
+    /// Name of the collection to use for points lookup
+    #[prost(string, tag = "1")]
+    pub collection: ::prost::alloc::string::String,
+    /// Options for specifying which payload to include (or not)
+    #[prost(message, optional, tag = "2")]
+    pub with_payload: ::core::option::Option<WithPayloadSelector>,
+    /// Options for specifying which vectors to include (or not)
+    #[prost(message, optional, tag = "3")]
+    pub with_vectors: ::core::option::Option<WithVectorsSelector>,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchPointGroups {
+    /// Name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Vector to compare against
+    #[prost(float, repeated, tag = "2")]
+    pub vector: ::prost::alloc::vec::Vec<f32>,
+    /// Filter conditions - return only those points that satisfy the specified conditions
+    #[prost(message, optional, tag = "3")]
+    #[validate]
+    pub filter: ::core::option::Option<Filter>,
+    /// Max number of result
+    #[prost(uint32, tag = "4")]
+    #[validate(range(min = 1))]
+    pub limit: u32,
+    /// Options for specifying which payload to include or not
+    #[prost(message, optional, tag = "5")]
+    pub with_payload: ::core::option::Option<WithPayloadSelector>,
+    /// Search config
+    #[prost(message, optional, tag = "6")]
+    #[validate]
+    pub params: ::core::option::Option<SearchParams>,
+    /// If provided - cut off results with worse scores
+    #[prost(float, optional, tag = "7")]
+    pub score_threshold: ::core::option::Option<f32>,
+    /// Which vector to use for search, if not specified - use default vector
+    #[prost(string, optional, tag = "8")]
+    #[validate(custom = "common::validation::validate_not_empty")]
+    pub vector_name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Options for specifying which vectors to include into response
+    #[prost(message, optional, tag = "9")]
+    pub with_vectors: ::core::option::Option<WithVectorsSelector>,
+    /// Payload field to group by, must be a string or number field. If there are multiple values for the field, all of them will be used. One point can be in multiple groups.
+    #[prost(string, tag = "10")]
+    #[validate(length(min = 1))]
+    pub group_by: ::prost::alloc::string::String,
+    /// Maximum amount of points to return per group
+    #[prost(uint32, tag = "11")]
+    #[validate(range(min = 1))]
+    pub group_size: u32,
+    /// Options for specifying read consistency guarantees
+    #[prost(message, optional, tag = "12")]
+    pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// Options for specifying how to use the group id to lookup points in another collection
+    #[prost(message, optional, tag = "13")]
+    pub with_lookup: ::core::option::Option<WithLookup>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct StartFrom {
+    #[prost(oneof = "start_from::Value", tags = "1, 2, 3")]
+    pub value: ::core::option::Option<start_from::Value>,
+}
+/// Nested message and enum types in `StartFrom`.
+pub mod start_from {
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Value {
+        #[prost(double, tag = "1")]
+        Float(f64),
+        #[prost(int64, tag = "2")]
+        Integer(i64),
+        /// RFC 3339 datetime
+        #[prost(string, tag = "3")]
+        Datetime(::prost::alloc::string::String),
+    }
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct OrderBy {
+    /// Payload key to order by
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+    /// Ascending or descending order
+    #[prost(enumeration = "Direction", optional, tag = "2")]
+    pub direction: ::core::option::Option<i32>,
+    /// Start from this value, including it
+    #[prost(message, optional, tag = "3")]
+    pub start_from: ::core::option::Option<StartFrom>,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScrollPoints {
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Filter conditions - return only those points that satisfy the specified conditions
+    #[prost(message, optional, tag = "2")]
+    #[validate]
+    pub filter: ::core::option::Option<Filter>,
+    /// Start with this ID
+    #[prost(message, optional, tag = "3")]
+    pub offset: ::core::option::Option<PointId>,
+    /// Max number of result
+    #[prost(uint32, optional, tag = "4")]
+    #[validate(custom = "crate::grpc::validate::validate_u32_range_min_1")]
+    pub limit: ::core::option::Option<u32>,
+    /// Options for specifying which payload to include or not
+    #[prost(message, optional, tag = "6")]
+    pub with_payload: ::core::option::Option<WithPayloadSelector>,
+    /// Options for specifying which vectors to include into response
+    #[prost(message, optional, tag = "7")]
+    pub with_vectors: ::core::option::Option<WithVectorsSelector>,
+    /// Options for specifying read consistency guarantees
+    #[prost(message, optional, tag = "8")]
+    pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// Order the records by a payload field, `offset` can't be used along with it
+    #[prost(message, optional, tag = "9")]
+    pub order_by: ::core::option::Option<OrderBy>,
+    /// Scroll only the points, which exist at the first page, in their latest state. Points inserted or deleted after it are skipped
+    #[prost(bool, optional, tag = "10")]
+    pub stable: ::core::option::Option<bool>,
+    /// Cursor of the next page of a stable scroll
+    #[prost(string, optional, tag = "11")]
+    pub cursor: ::core::option::Option<::prost::alloc::string::String>,
+    /// If set, the request fails with a timeout error once this number of seconds passes
+    #[prost(uint64, optional, tag = "12")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LookupLocation {
+    #[prost(string, tag = "1")]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Which vector to use for search, if not specified - use default vector
+    #[prost(string, optional, tag = "2")]
+    pub vector_name: ::core::option::Option<::prost::alloc::string::String>,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecommendPoints {
     /// name of the collection
     #[prost(string, tag = "1")]
     #[validate(length(min = 1, max = 255))]
     pub collection_name: ::prost::alloc::string::String,
-    /// vector
-    #[prost(float, repeated, tag = "2")]
-    pub vector: ::prost::alloc::vec::Vec<f32>,
+    /// Look for vectors closest to the vectors from these points
+    #[prost(message, repeated, tag = "2")]
+    pub positive: ::prost::alloc::vec::Vec<PointId>,
+    /// Try to avoid vectors like the vector from these points
+    #[prost(message, repeated, tag = "3")]
+    pub negative: ::prost::alloc::vec::Vec<PointId>,
     /// Filter conditions - return only those points that satisfy the specified conditions
-    #[prost(message, optional, tag = "3")]
+    #[prost(message, optional, tag = "4")]
     #[validate]
     pub filter: ::core::option::Option<Filter>,
     /// Max number of result
-    #[prost(uint64, tag = "4")]
-    #[validate(range(min = 1))]
+    #[prost(uint64, tag = "5")]
     pub limit: u64,
     /// Options for specifying which payload to include or not
-    #[prost(message, optional, tag = "6")]
+    #[prost(message, optional, tag = "7")]
     pub with_payload: ::core::option::Option<WithPayloadSelector>,
     /// Search config
-    #[prost(message, optional, tag = "7")]
+    #[prost(message, optional, tag = "8")]
     #[validate]
     pub params: ::core::option::Option<SearchParams>,
     /// If provided - cut off results with worse scores
-    #[prost(float, optional, tag = "8")]
+    #[prost(float, optional, tag = "9")]
     pub score_threshold: ::core::option::Option<f32>,
     /// Offset of the result
-    #[prost(uint64, optional, tag = "9")]
+    #[prost(uint64, optional, tag = "10")]
     pub offset: ::core::option::Option<u64>,
-    /// Which vector to use for search, if not specified - use default vector
-    #[prost(string, optional, tag = "10")]
-    #[validate(custom = "common::validation::validate_not_empty")]
-    pub vector_name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Define which vector to use for recommendation, if not specified - default vector
+    #[prost(string, optional, tag = "11")]
+    pub using: ::core::option::Option<::prost::alloc::string::String>,
     /// Options for specifying which vectors to include into response
-    #[prost(message, optional, tag = "11")]
+    #[prost(message, optional, tag = "12")]
     pub with_vectors: ::core::option::Option<WithVectorsSelector>,
+    /// Name of the collection to use for points lookup, if not specified - use current collection
+    #[prost(message, optional, tag = "13")]
+    pub lookup_from: ::core::option::Option<LookupLocation>,
     /// Options for specifying read consistency guarantees
-    #[prost(message, optional, tag = "12")]
+    #[prost(message, optional, tag = "14")]
     pub read_consistency: ::core::option::Option<ReadConsistency>,
+    /// How to use the example vectors to find the results
+    #[prost(enumeration = "RecommendStrategy", optional, tag = "16")]
+    pub strategy: ::core::option::Option<i32>,
+    /// Look for vectors closest to those
+    #[prost(message, repeated, tag = "17")]
+    pub positive_vectors: ::prost::alloc::vec::Vec<Vector>,
+    /// Try to avoid vectors like this
+    #[prost(message, repeated, tag = "18")]
+    pub negative_vectors: ::prost::alloc::vec::Vec<Vector>,
+    /// If set, the request fails with a timeout error once this number of seconds passes
+    #[prost(uint64, optional, tag = "19")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SearchBatchPoints {
+pub struct RecommendBatchPoints {
     /// Name of the collection
     #[prost(string, tag = "1")]
     #[validate(length(min = 1, max = 255))]
     pub collection_name: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "2")]
     #[validate]
-    pub search_points: ::prost::alloc::vec::Vec<SearchPoints>,
+    pub recommend_points: ::prost::alloc::vec::Vec<RecommendPoints>,
     /// Options for specifying read consistency guarantees
     #[prost(message, optional, tag = "3")]
     pub read_consistency: ::core::option::Option<ReadConsistency>,
-}
-#[derive(serde::Serialize)]
-#[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct WithLookup {
-    /// Name of the collection to use for points lookup
-    #[prost(string, tag = "1")]
-    pub collection: ::prost::alloc::string::String,
-    /// Options for specifying which payload to include (or not)
-    #[prost(message, optional, tag = "2")]
-    pub with_payload: ::core::option::Option<WithPayloadSelector>,
-    /// Options for specifying which vectors to include (or not)
-    #[prost(message, optional, tag = "3")]
-    pub with_vectors: ::core::option::Option<WithVectorsSelector>,
+    /// If set, the request fails with a timeout error once this number of seconds passes
+    #[prost(uint64, optional, tag = "4")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SearchPointGroups {
+pub struct RecommendPointGroups {
     /// Name of the collection
     #[prost(string, tag = "1")]
     #[validate(length(min = 1, max = 255))]
     pub collection_name: ::prost::alloc::string::String,
-    /// Vector to compare against
-    #[prost(float, repeated, tag = "2")]
-    pub vector: ::prost::alloc::vec::Vec<f32>,
+    /// Look for vectors closest to the vectors from these points
+    #[prost(message, repeated, tag = "2")]
+    pub positive: ::prost::alloc::vec::Vec<PointId>,
+    /// Try to avoid vectors like the vector from these points
+    #[prost(message, repeated, tag = "3")]
+    pub negative: ::prost::alloc::vec::Vec<PointId>,
     /// Filter conditions - return only those points that satisfy the specified conditions
-    #[prost(message, optional, tag = "3")]
+    #[prost(message, optional, tag = "4")]
     #[validate]
     pub filter: ::core::option::Option<Filter>,
-    /// Max number of result
-    #[prost(uint32, tag = "4")]
+    /// Max number of groups in result
+    #[prost(uint32, tag = "5")]
     #[validate(range(min = 1))]
     pub limit: u32,
     /// Options for specifying which payload to include or not
-    #[prost(message, optional, tag = "5")]
+    #[prost(message, optional, tag = "6")]
     pub with_payload: ::core::option::Option<WithPayloadSelector>,
     /// Search config
-    #[prost(message, optional, tag = "6")]
+    #[prost(message, optional, tag = "7")]
     #[validate]
     pub params: ::core::option::Option<SearchParams>,
     /// If provided - cut off results with worse scores
-    #[prost(float, optional, tag = "7")]
+    #[prost(float, optional, tag = "8")]
     pub score_threshold: ::core::option::Option<f32>,
-    /// Which vector to use for search, if not specified - use default vector
-    #[prost(string, optional, tag = "8")]
-    #[validate(custom = "common::validation::validate_not_empty")]
-    pub vector_name: ::core::option::Option<::prost::alloc::string::String>,
+    /// Define which vector to use for recommendation, if not specified - default vector
+    #[prost(string, optional, tag = "9")]
+    pub using: ::core::option::Option<::prost::alloc::string::String>,
     /// Options for specifying which vectors to include into response
-    #[prost(message, optional, tag = "9")]
+    #[prost(message, optional, tag = "10")]
     pub with_vectors: ::core::option::Option<WithVectorsSelector>,
+    /// Name of the collection to use for points lookup, if not specified - use current collection
+    #[prost(message, optional, tag = "11")]
+    pub lookup_from: ::core::option::Option<LookupLocation>,
     /// Payload field to group by, must be a string or number field. If there are multiple values for the field, all of them will be used. One point can be in multiple groups.
-    #[prost(string, tag = "10")]
+    #[prost(string, tag = "12")]
     #[validate(length(min = 1))]
     pub group_by: ::prost::alloc::string::String,
     /// Maximum amount of points to return per group
-    #[prost(uint32, tag = "11")]
+    #[prost(uint32, tag = "13")]
     #[validate(range(min = 1))]
     pub group_size: u32,
     /// Options for specifying read consistency guarantees
-    #[prost(message, optional, tag = "12")]
+    #[prost(message, optional, tag = "14")]
     pub read_consistency: ::core::option::Option<ReadConsistency>,
     /// Options for specifying how to use the group id to lookup points in another collection
-    #[prost(message, optional, tag = "13")]
+    #[prost(message, optional, tag = "15")]
     pub with_lookup: ::core::option::Option<WithLookup>,
+    /// How to use the example vectors to find the results
+    #[prost(enumeration = "RecommendStrategy", optional, tag = "17")]
+    pub strategy: ::core::option::Option<i32>,
+    /// Look for vectors closest to those
+    #[prost(message, repeated, tag = "18")]
+    pub positive_vectors: ::prost::alloc::vec::Vec<Vector>,
+    /// Try to avoid vectors like this
+    #[prost(message, repeated, tag = "19")]
+    pub negative_vectors: ::prost::alloc::vec::Vec<Vector>,
 }
-#[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct ScrollPoints {
-    #[prost(string, tag = "1")]
-    #[validate(length(min = 1, max = 255))]
-    pub collection_name: ::prost::alloc::string::String,
-    /// Filter conditions - return only those points that satisfy the specified conditions
+pub struct VectorInput {
+    #[prost(oneof = "vector_input::Variant", tags = "1, 2")]
+    pub variant: ::core::option::Option<vector_input::Variant>,
+}
+/// Nested message and enum types in `VectorInput`.
+pub mod vector_input {
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Variant {
+        /// Id of the point, which vector is used
+        #[prost(message, tag = "1")]
+        Id(super::PointId),
+        /// Dense vector given as is
+        #[prost(message, tag = "2")]
+        Vector(super::Vector),
+    }
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RecommendInput {
+    /// Look for vectors closest to those
+    #[prost(message, repeated, tag = "1")]
+    pub positive: ::prost::alloc::vec::Vec<VectorInput>,
+    /// Try to avoid vectors like this
+    #[prost(message, repeated, tag = "2")]
+    pub negative: ::prost::alloc::vec::Vec<VectorInput>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContextInputPair {
+    /// Points closer to this example are in the positive zone
+    #[prost(message, optional, tag = "1")]
+    pub positive: ::core::option::Option<VectorInput>,
+    /// Points closer to this example are in the negative zone
     #[prost(message, optional, tag = "2")]
-    #[validate]
-    pub filter: ::core::option::Option<Filter>,
-    /// Start with this ID
-    #[prost(message, optional, tag = "3")]
-    pub offset: ::core::option::Option<PointId>,
-    /// Max number of result
-    #[prost(uint32, optional, tag = "4")]
-    #[validate(custom = "crate::grpc::validate::validate_u32_range_min_1")]
-    pub limit: ::core::option::Option<u32>,
-    /// Options for specifying which payload to include or not
-    #[prost(message, optional, tag = "6")]
-    pub with_payload: ::core::option::Option<WithPayloadSelector>,
-    /// Options for specifying which vectors to include into response
-    #[prost(message, optional, tag = "7")]
-    pub with_vectors: ::core::option::Option<WithVectorsSelector>,
-    /// Options for specifying read consistency guarantees
-    #[prost(message, optional, tag = "8")]
-    pub read_consistency: ::core::option::Option<ReadConsistency>,
+    pub negative: ::core::option::Option<VectorInput>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DiscoverInput {
+    /// Look for vectors closest to this
+    #[prost(message, optional, tag = "1")]
+    pub target: ::core::option::Option<VectorInput>,
+    /// Pairs of examples to constrain the search
+    #[prost(message, repeated, tag = "2")]
+    pub context: ::prost::alloc::vec::Vec<ContextInputPair>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContextInput {
+    /// Pairs of examples to find the points in the positive zones of
+    #[prost(message, repeated, tag = "1")]
+    pub pairs: ::prost::alloc::vec::Vec<ContextInputPair>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MultExpression {
+    #[prost(message, repeated, tag = "1")]
+    pub mult: ::prost::alloc::vec::Vec<Expression>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SumExpression {
+    #[prost(message, repeated, tag = "1")]
+    pub sum: ::prost::alloc::vec::Vec<Expression>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DivExpression {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub left: ::core::option::Option<::prost::alloc::boxed::Box<Expression>>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub right: ::core::option::Option<::prost::alloc::boxed::Box<Expression>>,
+    /// Result of division by zero, fails the query if not specified
+    #[prost(double, optional, tag = "3")]
+    pub by_zero_default: ::core::option::Option<f64>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PowExpression {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub base: ::core::option::Option<::prost::alloc::boxed::Box<Expression>>,
+    #[prost(message, optional, boxed, tag = "2")]
+    pub exponent: ::core::option::Option<::prost::alloc::boxed::Box<Expression>>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GeoDistance {
+    #[prost(message, optional, tag = "1")]
+    pub origin: ::core::option::Option<GeoPoint>,
+    /// Payload key with the geo point
+    #[prost(string, tag = "2")]
+    pub to: ::prost::alloc::string::String,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DecayParamsExpression {
+    #[prost(message, optional, boxed, tag = "1")]
+    pub x: ::core::option::Option<::prost::alloc::boxed::Box<Expression>>,
+    /// Default is 0
+    #[prost(message, optional, boxed, tag = "2")]
+    pub target: ::core::option::Option<::prost::alloc::boxed::Box<Expression>>,
+    /// Must be positive. Default is 1
+    #[prost(double, optional, tag = "3")]
+    pub scale: ::core::option::Option<f64>,
+    /// Must be within (0, 1). Default is 0.5
+    #[prost(double, optional, tag = "4")]
+    pub midpoint: ::core::option::Option<f64>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Expression {
+    #[prost(
+        oneof = "expression::Variant",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19"
+    )]
+    pub variant: ::core::option::Option<expression::Variant>,
+}
+/// Nested message and enum types in `Expression`.
+pub mod expression {
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Variant {
+        #[prost(double, tag = "1")]
+        Constant(f64),
+        /// `$score`, `$score\[i\]` or a payload key with a numeric value
+        #[prost(string, tag = "2")]
+        Variable(::prost::alloc::string::String),
+        #[prost(message, tag = "3")]
+        Mult(super::MultExpression),
+        #[prost(message, tag = "4")]
+        Sum(super::SumExpression),
+        #[prost(message, tag = "5")]
+        Neg(::prost::alloc::boxed::Box<super::Expression>),
+        #[prost(message, tag = "6")]
+        Abs(::prost::alloc::boxed::Box<super::Expression>),
+        #[prost(message, tag = "7")]
+        Div(::prost::alloc::boxed::Box<super::DivExpression>),
+        #[prost(message, tag = "8")]
+        Sqrt(::prost::alloc::boxed::Box<super::Expression>),
+        #[prost(message, tag = "9")]
+        Pow(::prost::alloc::boxed::Box<super::PowExpression>),
+        #[prost(message, tag = "10")]
+        Exp(::prost::alloc::boxed::Box<super::Expression>),
+        #[prost(message, tag = "11")]
+        Log10(::prost::alloc::boxed::Box<super::Expression>),
+        #[prost(message, tag = "12")]
+        Ln(::prost::alloc::boxed::Box<super::Expression>),
+        /// Distance in meters between the origin and the geo point in the payload
+        #[prost(message, tag = "13")]
+        GeoDistance(super::GeoDistance),
+        /// RFC 3339 datetime, evaluated to a timestamp in seconds
+        #[prost(string, tag = "14")]
+        Datetime(::prost::alloc::string::String),
+        /// Payload key with a datetime, evaluated to a timestamp in seconds
+        #[prost(string, tag = "15")]
+        DatetimeKey(::prost::alloc::string::String),
+        #[prost(message, tag = "16")]
+        ExpDecay(::prost::alloc::boxed::Box<super::DecayParamsExpression>),
+        #[prost(message, tag = "17")]
+        GaussDecay(::prost::alloc::boxed::Box<super::DecayParamsExpression>),
+        #[prost(message, tag = "18")]
+        LinDecay(::prost::alloc::boxed::Box<super::DecayParamsExpression>),
+        /// 1 if the point matches the condition, 0 otherwise
+        #[prost(message, tag = "19")]
+        Condition(super::Condition),
+    }
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Formula {
+    #[prost(message, optional, tag = "1")]
+    pub expression: ::core::option::Option<Expression>,
+    /// Values of the variables, which are not found in the payload or in the prefetches
+    #[prost(map = "string, message", tag = "2")]
+    pub defaults: ::std::collections::HashMap<::prost::alloc::string::String, Value>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PluginQuery {
+    /// Name of the scoring plugin of the collection
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Fail, if the plugin of the collection has another version
+    #[prost(uint64, optional, tag = "2")]
+    pub version: ::core::option::Option<u64>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct LookupLocation {
-    #[prost(string, tag = "1")]
-    pub collection_name: ::prost::alloc::string::String,
-    /// Which vector to use for search, if not specified - use default vector
-    #[prost(string, optional, tag = "2")]
-    pub vector_name: ::core::option::Option<::prost::alloc::string::String>,
+pub struct Query {
+    #[prost(oneof = "query::Variant", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9")]
+    pub variant: ::core::option::Option<query::Variant>,
+}
+/// Nested message and enum types in `Query`.
+pub mod query {
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Variant {
+        /// Find the nearest neighbors of this vector
+        #[prost(message, tag = "1")]
+        Nearest(super::Vector),
+        /// Find points closer to positive and further from negative examples
+        #[prost(message, tag = "2")]
+        Recommend(super::RecommendInput),
+        /// Find points in the positive zones of the context pairs, which are closest to the target
+        #[prost(message, tag = "3")]
+        Discover(super::DiscoverInput),
+        /// Find points in the positive zones of as many context pairs as possible
+        #[prost(message, tag = "4")]
+        Context(super::ContextInput),
+        /// Rank points by BM25 relevance of the text
+        #[prost(message, tag = "5")]
+        Text(super::TextQuery),
+        /// Fuse the results of the prefetches
+        #[prost(enumeration = "super::Fusion", tag = "6")]
+        Fusion(i32),
+        /// Sample points matching the filter
+        #[prost(enumeration = "super::Sample", tag = "7")]
+        Sample(i32),
+        /// Score the candidates of the prefetches by a formula
+        #[prost(message, tag = "8")]
+        Formula(super::Formula),
+        /// Score the candidates of the prefetches by the scoring plugin of the collection
+        #[prost(message, tag = "9")]
+        Plugin(super::PluginQuery),
+    }
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RecommendPoints {
-    /// name of the collection
-    #[prost(string, tag = "1")]
-    #[validate(length(min = 1, max = 255))]
-    pub collection_name: ::prost::alloc::string::String,
-    /// Look for vectors closest to the vectors from these points
-    #[prost(message, repeated, tag = "2")]
-    pub positive: ::prost::alloc::vec::Vec<PointId>,
-    /// Try to avoid vectors like the vector from these points
-    #[prost(message, repeated, tag = "3")]
-    pub negative: ::prost::alloc::vec::Vec<PointId>,
+pub struct Diversity {
+    /// Trade-off between relevance and diversity: 1 selects by relevance only, 0 selects by diversity only
+    #[prost(float, tag = "1")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub lambda: f32,
+    /// Number of the best candidates to select the results from
+    #[prost(uint64, optional, tag = "2")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub candidates_limit: ::core::option::Option<u64>,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PrefetchQuery {
+    /// Sub-requests to perform first. If present, the query will be performed on the results of the prefetches
+    #[prost(message, repeated, tag = "1")]
+    #[validate]
+    pub prefetch: ::prost::alloc::vec::Vec<PrefetchQuery>,
+    /// Query to perform. If missing, the result of the single prefetch is returned as is
+    #[prost(message, optional, tag = "2")]
+    pub query: ::core::option::Option<Query>,
+    /// Define which vector to use for querying. If missing, the default vector is used
+    #[prost(string, optional, tag = "3")]
+    pub using: ::core::option::Option<::prost::alloc::string::String>,
     /// Filter conditions - return only those points that satisfy the specified conditions
     #[prost(message, optional, tag = "4")]
     #[validate]
     pub filter: ::core::option::Option<Filter>,
-    /// Max number of result
-    #[prost(uint64, tag = "5")]
-    pub limit: u64,
-    /// Options for specifying which payload to include or not
-    #[prost(message, optional, tag = "7")]
-    pub with_payload: ::core::option::Option<WithPayloadSelector>,
-    /// Search config
-    #[prost(message, optional, tag = "8")]
+    /// Search params for when there is no prefetch
+    #[prost(message, optional, tag = "5")]
     #[validate]
     pub params: ::core::option::Option<SearchParams>,
-    /// If provided - cut off results with worse scores
-    #[prost(float, optional, tag = "9")]
+    /// Return points with scores better than this threshold
+    #[prost(float, optional, tag = "6")]
     pub score_threshold: ::core::option::Option<f32>,
-    /// Offset of the result
-    #[prost(uint64, optional, tag = "10")]
-    pub offset: ::core::option::Option<u64>,
-    /// Define which vector to use for recommendation, if not specified - default vector
-    #[prost(string, optional, tag = "11")]
-    pub using: ::core::option::Option<::prost::alloc::string::String>,
-    /// Options for specifying which vectors to include into response
-    #[prost(message, optional, tag = "12")]
-    pub with_vectors: ::core::option::Option<WithVectorsSelector>,
-    /// Name of the collection to use for points lookup, if not specified - use current collection
-    #[prost(message, optional, tag = "13")]
+    /// Max number of points to return from this stage. Default is 10
+    #[prost(uint64, optional, tag = "7")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub limit: ::core::option::Option<u64>,
+    /// The location to use for IDs lookup, if not specified - use the current collection
+    #[prost(message, optional, tag = "8")]
     pub lookup_from: ::core::option::Option<LookupLocation>,
-    /// Options for specifying read consistency guarantees
-    #[prost(message, optional, tag = "14")]
-    pub read_consistency: ::core::option::Option<ReadConsistency>,
-    /// How to use the example vectors to find the results
-    #[prost(enumeration = "RecommendStrategy", optional, tag = "16")]
-    pub strategy: ::core::option::Option<i32>,
-    /// Look for vectors closest to those
-    #[prost(message, repeated, tag = "17")]
-    pub positive_vectors: ::prost::alloc::vec::Vec<Vector>,
-    /// Try to avoid vectors like this
-    #[prost(message, repeated, tag = "18")]
-    pub negative_vectors: ::prost::alloc::vec::Vec<Vector>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RecommendBatchPoints {
+pub struct QueryPoints {
     /// Name of the collection
     #[prost(string, tag = "1")]
     #[validate(length(min = 1, max = 255))]
     pub collection_name: ::prost::alloc::string::String,
+    /// Sub-requests to perform first. If present, the query will be performed on the results of the prefetches
     #[prost(message, repeated, tag = "2")]
     #[validate]
-    pub recommend_points: ::prost::alloc::vec::Vec<RecommendPoints>,
-    /// Options for specifying read consistency guarantees
+    pub prefetch: ::prost::alloc::vec::Vec<PrefetchQuery>,
+    /// Query to perform. If missing, the result of the single prefetch is returned as is
     #[prost(message, optional, tag = "3")]
-    pub read_consistency: ::core::option::Option<ReadConsistency>,
-}
-#[derive(validator::Validate)]
-#[derive(serde::Serialize)]
-#[allow(clippy::derive_partial_eq_without_eq)]
-#[derive(Clone, PartialEq, ::prost::Message)]
-pub struct RecommendPointGroups {
-    /// Name of the collection
-    #[prost(string, tag = "1")]
-    #[validate(length(min = 1, max = 255))]
-    pub collection_name: ::prost::alloc::string::String,
-    /// Look for vectors closest to the vectors from these points
-    #[prost(message, repeated, tag = "2")]
-    pub positive: ::prost::alloc::vec::Vec<PointId>,
-    /// Try to avoid vectors like the vector from these points
-    #[prost(message, repeated, tag = "3")]
-    pub negative: ::prost::alloc::vec::Vec<PointId>,
+    pub query: ::core::option::Option<Query>,
+    /// Define which vector to use for querying. If missing, the default vector is used
+    #[prost(string, optional, tag = "4")]
+    pub using: ::core::option::Option<::prost::alloc::string::String>,
     /// Filter conditions - return only those points that satisfy the specified conditions
-    #[prost(message, optional, tag = "4")]
+    #[prost(message, optional, tag = "5")]
     #[validate]
     pub filter: ::core::option::Option<Filter>,
-    /// Max number of groups in result
-    #[prost(uint32, tag = "5")]
-    #[validate(range(min = 1))]
-    pub limit: u32,
-    /// Options for specifying which payload to include or not
+    /// Search params for when there is no prefetch
     #[prost(message, optional, tag = "6")]
-    pub with_payload: ::core::option::Option<WithPayloadSelector>,
-    /// Search config
-    #[prost(message, optional, tag = "7")]
     #[validate]
     pub params: ::core::option::Option<SearchParams>,
-    /// If provided - cut off results with worse scores
-    #[prost(float, optional, tag = "8")]
+    /// Return points with scores better than this threshold
+    #[prost(float, optional, tag = "7")]
     pub score_threshold: ::core::option::Option<f32>,
-    /// Define which vector to use for recommendation, if not specified - default vector
-    #[prost(string, optional, tag = "9")]
-    pub using: ::core::option::Option<::prost::alloc::string::String>,
-    /// Options for specifying which vectors to include into response
+    /// Max number of points to return. Default is 10
+    #[prost(uint64, optional, tag = "8")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub limit: ::core::option::Option<u64>,
+    /// Offset of the result. Skip this many points. Default is 0
+    #[prost(uint64, optional, tag = "9")]
+    pub offset: ::core::option::Option<u64>,
+    /// Options for specifying which vectors to include into the response
     #[prost(message, optional, tag = "10")]
     pub with_vectors: ::core::option::Option<WithVectorsSelector>,
-    /// Name of the collection to use for points lookup, if not specified - use current collection
+    /// Options for specifying which payload to include or not
     #[prost(message, optional, tag = "11")]
-    pub lookup_from: ::core::option::Option<LookupLocation>,
-    /// Payload field to group by, must be a string or number field. If there are multiple values for the field, all of them will be used. One point can be in multiple groups.
-    #[prost(string, tag = "12")]
-    #[validate(length(min = 1))]
-    pub group_by: ::prost::alloc::string::String,
-    /// Maximum amount of points to return per group
-    #[prost(uint32, tag = "13")]
-    #[validate(range(min = 1))]
-    pub group_size: u32,
+    pub with_payload: ::core::option::Option<WithPayloadSelector>,
     /// Options for specifying read consistency guarantees
-    #[prost(message, optional, tag = "14")]
+    #[prost(message, optional, tag = "12")]
     pub read_consistency: ::core::option::Option<ReadConsistency>,
-    /// Options for specifying how to use the group id to lookup points in another collection
-    #[prost(message, optional, tag = "15")]
-    pub with_lookup: ::core::option::Option<WithLookup>,
-    /// How to use the example vectors to find the results
-    #[prost(enumeration = "RecommendStrategy", optional, tag = "17")]
-    pub strategy: ::core::option::Option<i32>,
-    /// Look for vectors closest to those
-    #[prost(message, repeated, tag = "18")]
-    pub positive_vectors: ::prost::alloc::vec::Vec<Vector>,
-    /// Try to avoid vectors like this
-    #[prost(message, repeated, tag = "19")]
-    pub negative_vectors: ::prost::alloc::vec::Vec<Vector>,
+    /// The location to use for IDs lookup, if not specified - use the current collection
+    #[prost(message, optional, tag = "13")]
+    pub lookup_from: ::core::option::Option<LookupLocation>,
+    /// Diversify the results, so near-duplicates of the selected points are selected later
+    #[prost(message, optional, tag = "14")]
+    #[validate]
+    pub diversity: ::core::option::Option<Diversity>,
+    /// Return the breakdown of the score of every point. Default is false
+    #[prost(bool, optional, tag = "15")]
+    pub with_explanation: ::core::option::Option<bool>,
+    /// If set, the request fails with a timeout error once this number of seconds passes
+    #[prost(uint64, optional, tag = "16")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -3386,6 +4769,71 @@ pub struct CountPoints {
     /// If `true` - return exact count, if `false` - return approximate count
     #[prost(bool, optional, tag = "3")]
     pub exact: ::core::option::Option<bool>,
+    /// If set, the request fails with a timeout error once this number of seconds passes
+    #[prost(uint64, optional, tag = "4")]
+    #[validate(custom = "crate::grpc::validate::validate_u64_range_min_1")]
+    pub timeout: ::core::option::Option<u64>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PayloadMutation {
+    #[prost(oneof = "payload_mutation::Mutation", tags = "1, 2, 3, 4")]
+    pub mutation: ::core::option::Option<payload_mutation::Mutation>,
+}
+/// Nested message and enum types in `PayloadMutation`.
+pub mod payload_mutation {
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Set {
+        #[prost(string, tag = "1")]
+        pub key: ::prost::alloc::string::String,
+        #[prost(message, optional, tag = "2")]
+        pub value: ::core::option::Option<super::Value>,
+    }
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Increment {
+        #[prost(string, tag = "1")]
+        pub key: ::prost::alloc::string::String,
+        #[prost(double, tag = "2")]
+        pub by: f64,
+    }
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct CopyValue {
+        #[prost(string, tag = "1")]
+        pub from: ::prost::alloc::string::String,
+        #[prost(string, tag = "2")]
+        pub to: ::prost::alloc::string::String,
+    }
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct Remove {
+        #[prost(string, tag = "1")]
+        pub key: ::prost::alloc::string::String,
+    }
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Mutation {
+        /// Set the key to a constant value
+        #[prost(message, tag = "1")]
+        Set(Set),
+        /// Add the number to the numeric value of the key, a missing key counts as zero
+        #[prost(message, tag = "2")]
+        Increment(Increment),
+        /// Copy the value of one key into another
+        #[prost(message, tag = "3")]
+        Copy(CopyValue),
+        /// Remove the key
+        #[prost(message, tag = "4")]
+        Remove(Remove),
+    }
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3393,7 +4841,7 @@ pub struct CountPoints {
 pub struct PointsUpdateOperation {
     #[prost(
         oneof = "points_update_operation::Operation",
-        tags = "1, 2, 3, 4, 5, 6, 7, 8"
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9"
     )]
     pub operation: ::core::option::Option<points_update_operation::Operation>,
 }
@@ -3418,6 +4866,9 @@ pub mod points_update_operation {
         /// Affected points
         #[prost(message, optional, tag = "2")]
         pub points_selector: ::core::option::Option<super::PointsSelector>,
+        /// Merge values into nested objects recursively, as JSON merge patch
+        #[prost(bool, optional, tag = "3")]
+        pub deep_merge: ::core::option::Option<bool>,
     }
     #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3450,6 +4901,17 @@ pub mod points_update_operation {
     }
     #[derive(serde::Serialize)]
     #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Message)]
+    pub struct MutatePayload {
+        /// Mutations, applied in order to payload of each point
+        #[prost(message, repeated, tag = "1")]
+        pub mutations: ::prost::alloc::vec::Vec<super::PayloadMutation>,
+        /// Mutate payload of points, which satisfy this filter condition
+        #[prost(message, optional, tag = "2")]
+        pub filter: ::core::option::Option<super::Filter>,
+    }
+    #[derive(serde::Serialize)]
+    #[allow(clippy::derive_partial_eq_without_eq)]
     #[derive(Clone, PartialEq, ::prost::Oneof)]
     pub enum Operation {
         #[prost(message, tag = "1")]
@@ -3468,6 +4930,8 @@ pub mod points_update_operation {
         UpdateVectors(UpdateVectors),
         #[prost(message, tag = "8")]
         DeleteVectors(DeleteVectors),
+        #[prost(message, tag = "9")]
+        MutatePayload(MutatePayload),
     }
 }
 #[derive(validator::Validate)]
@@ -3488,6 +4952,9 @@ pub struct UpdateBatchPoints {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Retries with the same token are applied only once
+    #[prost(string, optional, tag = "5")]
+    pub idempotency_token: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3509,6 +4976,12 @@ pub struct UpdateResult {
     /// Operation status
     #[prost(enumeration = "UpdateStatus", tag = "2")]
     pub status: i32,
+    /// Number of points affected by the operation, if waited for it to be applied
+    #[prost(uint64, optional, tag = "3")]
+    pub affected_points: ::core::option::Option<u64>,
+    /// IDs assigned by the server to the inserted points
+    #[prost(message, repeated, tag = "4")]
+    pub ids: ::prost::alloc::vec::Vec<PointId>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3529,6 +5002,45 @@ pub struct ScoredPoint {
     /// Vectors to search
     #[prost(message, optional, tag = "6")]
     pub vectors: ::core::option::Option<Vectors>,
+    /// Breakdown of the score, if requested
+    #[prost(message, optional, tag = "7")]
+    pub explanation: ::core::option::Option<ScoreExplanation>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ScoreExplanation {
+    /// Score by the vector query, computed with the original vectors
+    #[prost(float, optional, tag = "1")]
+    pub similarity: ::core::option::Option<f32>,
+    /// Score by the vector query, computed with the quantized vectors only, before rescoring
+    #[prost(float, optional, tag = "2")]
+    pub quantized_similarity: ::core::option::Option<f32>,
+    /// Scores of the point in the prefetches, which were fused or combined by a formula
+    #[prost(message, repeated, tag = "3")]
+    pub prefetches: ::prost::alloc::vec::Vec<PrefetchScore>,
+    /// Values of the top-level terms of the formula
+    #[prost(double, repeated, tag = "4")]
+    pub formula_terms: ::prost::alloc::vec::Vec<f64>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PrefetchScore {
+    /// Index of the prefetch, which returned the point
+    #[prost(uint32, tag = "1")]
+    pub prefetch: u32,
+    /// Position of the point in the results of the prefetch, starting from 0
+    #[prost(uint64, tag = "2")]
+    pub rank: u64,
+    /// Score of the point in the prefetch
+    #[prost(float, tag = "3")]
+    pub score: f32,
+    /// Part of the fused score, which comes from this prefetch
+    #[prost(float, optional, tag = "4")]
+    pub contribution: ::core::option::Option<f32>,
+    #[prost(message, optional, tag = "5")]
+    pub explanation: ::core::option::Option<ScoreExplanation>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3557,24 +5069,114 @@ pub mod group_id {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct PointGroup {
-    /// Group id
-    #[prost(message, optional, tag = "1")]
-    pub id: ::core::option::Option<GroupId>,
-    /// Points in the group
+pub struct PointGroup {
+    /// Group id
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<GroupId>,
+    /// Points in the group
+    #[prost(message, repeated, tag = "2")]
+    pub hits: ::prost::alloc::vec::Vec<ScoredPoint>,
+    /// Point(s) from the lookup collection that matches the group id
+    #[prost(message, optional, tag = "3")]
+    pub lookup: ::core::option::Option<RetrievedPoint>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GroupsResult {
+    /// Groups
+    #[prost(message, repeated, tag = "1")]
+    pub groups: ::prost::alloc::vec::Vec<PointGroup>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HardwareUsage {
+    /// CPU time, spent on the search in the segments, in microseconds
+    #[prost(uint64, tag = "1")]
+    pub cpu_time_micros: u64,
+    /// Bytes of vectors, read from the on-disk vector storages
+    #[prost(uint64, tag = "2")]
+    pub vector_bytes_read: u64,
+    /// Bytes of payloads, read from the on-disk payload storages
+    #[prost(uint64, tag = "3")]
+    pub payload_bytes_read: u64,
+    /// Number of segments, the search was executed on
+    #[prost(uint64, tag = "4")]
+    pub segments_searched: u64,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CardinalityEstimate {
+    #[prost(uint64, tag = "1")]
+    pub min: u64,
+    #[prost(uint64, tag = "2")]
+    pub exp: u64,
+    #[prost(uint64, tag = "3")]
+    pub max: u64,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchCandidates {
+    /// Points, scored to find the nearest ones. Not counted for the inverted index of sparse vectors
+    #[prost(uint64, tag = "1")]
+    pub scored: u64,
+    /// Best of the points, scored with quantized vectors, and rescored with the original ones
+    #[prost(uint64, tag = "2")]
+    pub rescored: u64,
+    /// Points, returned by the vector index
+    #[prost(uint64, tag = "3")]
+    pub returned: u64,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VectorSearchExplain {
+    #[prost(enumeration = "SearchStrategy", tag = "1")]
+    pub strategy: i32,
+    /// Number of query vectors, searched at once
+    #[prost(uint64, tag = "2")]
+    pub queries: u64,
+    /// Vectors are scored with their quantized versions
+    #[prost(bool, tag = "3")]
+    pub quantized: bool,
+    /// Cardinality of the filter, estimated to choose the strategy
+    #[prost(message, optional, tag = "4")]
+    pub estimated_cardinality: ::core::option::Option<CardinalityEstimate>,
+    /// Number of points, matching the filter. Only counted in the analyze mode
+    #[prost(uint64, optional, tag = "5")]
+    pub actual_cardinality: ::core::option::Option<u64>,
+    /// Payload indexes, used to select the points matching the filter
+    #[prost(string, repeated, tag = "6")]
+    pub payload_indexes: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Only counted in the analyze mode
+    #[prost(message, optional, tag = "7")]
+    pub candidates: ::core::option::Option<SearchCandidates>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SegmentSearchExplain {
+    /// Id of the segment within the shard, assigned anew on restart
+    #[prost(uint64, tag = "1")]
+    pub segment_id: u64,
+    /// Searches in the vector indexes of the segment
     #[prost(message, repeated, tag = "2")]
-    pub hits: ::prost::alloc::vec::Vec<ScoredPoint>,
-    /// Point(s) from the lookup collection that matches the group id
-    #[prost(message, optional, tag = "3")]
-    pub lookup: ::core::option::Option<RetrievedPoint>,
+    pub searches: ::prost::alloc::vec::Vec<VectorSearchExplain>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GroupsResult {
-    /// Groups
-    #[prost(message, repeated, tag = "1")]
-    pub groups: ::prost::alloc::vec::Vec<PointGroup>,
+pub struct ShardSearchExplain {
+    #[prost(uint32, tag = "1")]
+    pub shard_id: u32,
+    /// Peer, which searched the shard
+    #[prost(uint64, tag = "2")]
+    pub peer_id: u64,
+    #[prost(message, repeated, tag = "3")]
+    pub segments: ::prost::alloc::vec::Vec<SegmentSearchExplain>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3585,6 +5187,12 @@ pub struct SearchResponse {
     /// Time spent to process
     #[prost(double, tag = "2")]
     pub time: f64,
+    /// Resources used to process the request, if requested
+    #[prost(message, optional, tag = "3")]
+    pub usage: ::core::option::Option<HardwareUsage>,
+    /// Strategies of the search in each segment, if requested
+    #[prost(message, repeated, tag = "4")]
+    pub explain: ::prost::alloc::vec::Vec<ShardSearchExplain>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3602,6 +5210,12 @@ pub struct SearchBatchResponse {
     /// Time spent to process
     #[prost(double, tag = "2")]
     pub time: f64,
+    /// Resources used to process the request, if requested
+    #[prost(message, optional, tag = "3")]
+    pub usage: ::core::option::Option<HardwareUsage>,
+    /// Strategies of the search in each segment, if requested
+    #[prost(message, repeated, tag = "4")]
+    pub explain: ::prost::alloc::vec::Vec<ShardSearchExplain>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3635,6 +5249,9 @@ pub struct ScrollResponse {
     /// Time spent to process
     #[prost(double, tag = "3")]
     pub time: f64,
+    /// Use this cursor for the next page of a stable scroll
+    #[prost(string, optional, tag = "4")]
+    pub next_page_cursor: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3653,6 +5270,9 @@ pub struct RetrievedPoint {
     pub payload: ::std::collections::HashMap<::prost::alloc::string::String, Value>,
     #[prost(message, optional, tag = "4")]
     pub vectors: ::core::option::Option<Vectors>,
+    /// Value of the `order_by` key, if the points are ordered by a payload field
+    #[prost(double, optional, tag = "5")]
+    pub order_value: ::core::option::Option<f64>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3697,6 +5317,16 @@ pub struct RecommendGroupsResponse {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct QueryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub result: ::prost::alloc::vec::Vec<ScoredPoint>,
+    /// Time spent to process
+    #[prost(double, tag = "2")]
+    pub time: f64,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct UpdateBatchResponse {
     #[prost(message, repeated, tag = "1")]
     pub result: ::prost::alloc::vec::Vec<UpdateResult>,
@@ -3721,6 +5351,21 @@ pub struct Filter {
     #[prost(message, repeated, tag = "3")]
     #[validate]
     pub must_not: ::prost::alloc::vec::Vec<Condition>,
+    /// At least minimum amount of given conditions should match
+    #[prost(message, optional, tag = "4")]
+    #[validate]
+    pub min_should: ::core::option::Option<MinShould>,
+}
+#[derive(validator::Validate)]
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MinShould {
+    #[prost(message, repeated, tag = "1")]
+    #[validate]
+    pub conditions: ::prost::alloc::vec::Vec<Condition>,
+    #[prost(uint64, tag = "2")]
+    pub min_count: u64,
 }
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
@@ -3749,6 +5394,8 @@ pub mod condition {
         IsNull(super::IsNullCondition),
         #[prost(message, tag = "6")]
         Nested(super::NestedCondition),
+        #[prost(message, tag = "7")]
+        HasVector(super::HasVectorCondition),
     }
 }
 #[derive(serde::Serialize)]
@@ -3772,6 +5419,13 @@ pub struct HasIdCondition {
     #[prost(message, repeated, tag = "1")]
     pub has_id: ::prost::alloc::vec::Vec<PointId>,
 }
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HasVectorCondition {
+    #[prost(string, tag = "1")]
+    pub has_vector: ::prost::alloc::string::String,
+}
 #[derive(validator::Validate)]
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3809,12 +5463,15 @@ pub struct FieldCondition {
     /// Check if geo point is within a given polygon
     #[prost(message, optional, tag = "7")]
     pub geo_polygon: ::core::option::Option<GeoPolygon>,
+    /// Check if points datetime value lies in a given range
+    #[prost(message, optional, tag = "8")]
+    pub datetime_range: ::core::option::Option<DatetimeRange>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Match {
-    #[prost(oneof = "r#match::MatchValue", tags = "1, 2, 3, 4, 5, 6, 7, 8")]
+    #[prost(oneof = "r#match::MatchValue", tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11")]
     pub match_value: ::core::option::Option<r#match::MatchValue>,
 }
 /// Nested message and enum types in `Match`.
@@ -3847,6 +5504,15 @@ pub mod r#match {
         /// Match any other value except those keywords
         #[prost(message, tag = "8")]
         ExceptKeywords(super::RepeatedStrings),
+        /// Match phrase text
+        #[prost(string, tag = "9")]
+        Phrase(::prost::alloc::string::String),
+        /// Match keyword against a wildcard pattern, `*` - any sequence of characters, `?` - any single character
+        #[prost(string, tag = "10")]
+        Wildcard(::prost::alloc::string::String),
+        /// Match keyword against a regular expression
+        #[prost(string, tag = "11")]
+        Regex(::prost::alloc::string::String),
     }
 }
 #[derive(serde::Serialize)]
@@ -3879,6 +5545,23 @@ pub struct Range {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DatetimeRange {
+    #[prost(message, optional, tag = "1")]
+    #[serde(skip)]
+    pub lt: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "2")]
+    #[serde(skip)]
+    pub gt: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "3")]
+    #[serde(skip)]
+    pub gte: ::core::option::Option<::prost_types::Timestamp>,
+    #[prost(message, optional, tag = "4")]
+    #[serde(skip)]
+    pub lte: ::core::option::Option<::prost_types::Timestamp>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GeoBoundingBox {
     /// north-west corner
     #[prost(message, optional, tag = "1")]
@@ -4059,6 +5742,9 @@ pub enum FieldType {
     Geo = 3,
     Text = 4,
     Bool = 5,
+    Datetime = 6,
+    Uuid = 7,
+    Composite = 8,
 }
 impl FieldType {
     /// String value of the enum field names used in the ProtoBuf definition.
@@ -4073,6 +5759,9 @@ impl FieldType {
             FieldType::Geo => "FieldTypeGeo",
             FieldType::Text => "FieldTypeText",
             FieldType::Bool => "FieldTypeBool",
+            FieldType::Datetime => "FieldTypeDatetime",
+            FieldType::Uuid => "FieldTypeUuid",
+            FieldType::Composite => "FieldTypeComposite",
         }
     }
     /// Creates an enum from field names used in the ProtoBuf definition.
@@ -4084,6 +5773,36 @@ impl FieldType {
             "FieldTypeGeo" => Some(Self::Geo),
             "FieldTypeText" => Some(Self::Text),
             "FieldTypeBool" => Some(Self::Bool),
+            "FieldTypeDatetime" => Some(Self::Datetime),
+            "FieldTypeUuid" => Some(Self::Uuid),
+            "FieldTypeComposite" => Some(Self::Composite),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Direction {
+    Asc = 0,
+    Desc = 1,
+}
+impl Direction {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Direction::Asc => "Asc",
+            Direction::Desc => "Desc",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Asc" => Some(Self::Asc),
+            "Desc" => Some(Self::Desc),
             _ => None,
         }
     }
@@ -4124,6 +5843,60 @@ impl RecommendStrategy {
 #[derive(serde::Serialize)]
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum Fusion {
+    /// Reciprocal Rank Fusion, a point scores `1 / (60 + rank)` in each ranking it appears in
+    Rrf = 0,
+    /// Scores of each ranking are normalized by its best and worst scores, then summed up per point
+    RelativeScore = 1,
+}
+impl Fusion {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Fusion::Rrf => "RRF",
+            Fusion::RelativeScore => "RelativeScore",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RRF" => Some(Self::Rrf),
+            "RelativeScore" => Some(Self::RelativeScore),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum Sample {
+    /// Uniform random sample of points
+    Random = 0,
+}
+impl Sample {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            Sample::Random => "Random",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Random" => Some(Self::Random),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum UpdateStatus {
     UnknownUpdateStatus = 0,
     /// Update is received, but not processed yet
@@ -4153,6 +5926,72 @@ impl UpdateStatus {
         }
     }
 }
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ExplainMode {
+    /// Only choose the strategies of the search, without executing it. No points are returned
+    Plan = 0,
+    /// Execute the search and also report the actual cardinality and the number of candidates
+    Analyze = 1,
+}
+impl ExplainMode {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ExplainMode::Plan => "Plan",
+            ExplainMode::Analyze => "Analyze",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Plan" => Some(Self::Plan),
+            "Analyze" => Some(Self::Analyze),
+            _ => None,
+        }
+    }
+}
+#[derive(serde::Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum SearchStrategy {
+    /// Search in the HNSW graph, the filter is checked on the visited points
+    Hnsw = 0,
+    /// Score all vectors of the segment
+    FullScan = 1,
+    /// Select the points, matching the filter, and score each of them
+    FilteredScan = 2,
+    /// Search in the inverted index of sparse vectors
+    SparseIndex = 3,
+}
+impl SearchStrategy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            SearchStrategy::Hnsw => "Hnsw",
+            SearchStrategy::FullScan => "FullScan",
+            SearchStrategy::FilteredScan => "FilteredScan",
+            SearchStrategy::SparseIndex => "SparseIndex",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "Hnsw" => Some(Self::Hnsw),
+            "FullScan" => Some(Self::FullScan),
+            "FilteredScan" => Some(Self::FilteredScan),
+            "SparseIndex" => Some(Self::SparseIndex),
+            _ => None,
+        }
+    }
+}
 /// Generated client implementations.
 pub mod points_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -4616,6 +6455,33 @@ pub mod points_client {
             self.inner.unary(req, path, codec).await
         }
         ///
+        /// Iterate over all or filtered points, streamed in chunks of `limit` points until all of them are sent
+        pub async fn scroll_stream(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ScrollPoints>,
+        ) -> std::result::Result<
+            tonic::Response<tonic::codec::Streaming<super::ScrollResponse>>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/ScrollStream",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "ScrollStream"));
+            self.inner.server_streaming(req, path, codec).await
+        }
+        ///
         /// Look for the points which are closer to stored positive examples and at the same time further to negative examples.
         pub async fn recommend(
             &mut self,
@@ -4715,7 +6581,28 @@ pub mod points_client {
             self.inner.unary(req, path, codec).await
         }
         ///
-        /// Perform multiple update operations in one request
+        /// Universal query: prefetch candidates in stages, then fuse or rescore them by the query
+        pub async fn query(
+            &mut self,
+            request: impl tonic::IntoRequest<super::QueryPoints>,
+        ) -> std::result::Result<tonic::Response<super::QueryResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static("/qdrant.Points/Query");
+            let mut req = request.into_request();
+            req.extensions_mut().insert(GrpcMethod::new("qdrant.Points", "Query"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
+        /// Perform multiple update operations in one request, applied in order as a single update of each shard
         pub async fn update_batch(
             &mut self,
             request: impl tonic::IntoRequest<super::UpdateBatchPoints>,
@@ -4875,6 +6762,21 @@ pub mod points_server {
             &self,
             request: tonic::Request<super::ScrollPoints>,
         ) -> std::result::Result<tonic::Response<super::ScrollResponse>, tonic::Status>;
+        /// Server streaming response type for the ScrollStream method.
+        type ScrollStreamStream: futures_core::Stream<
+                Item = std::result::Result<super::ScrollResponse, tonic::Status>,
+            >
+            + Send
+            + 'static;
+        ///
+        /// Iterate over all or filtered points, streamed in chunks of `limit` points until all of them are sent
+        async fn scroll_stream(
+            &self,
+            request: tonic::Request<super::ScrollPoints>,
+        ) -> std::result::Result<
+            tonic::Response<Self::ScrollStreamStream>,
+            tonic::Status,
+        >;
         ///
         /// Look for the points which are closer to stored positive examples and at the same time further to negative examples.
         async fn recommend(
@@ -4909,7 +6811,13 @@ pub mod points_server {
             request: tonic::Request<super::CountPoints>,
         ) -> std::result::Result<tonic::Response<super::CountResponse>, tonic::Status>;
         ///
-        /// Perform multiple update operations in one request
+        /// Universal query: prefetch candidates in stages, then fuse or rescore them by the query
+        async fn query(
+            &self,
+            request: tonic::Request<super::QueryPoints>,
+        ) -> std::result::Result<tonic::Response<super::QueryResponse>, tonic::Status>;
+        ///
+        /// Perform multiple update operations in one request, applied in order as a single update of each shard
         async fn update_batch(
             &self,
             request: tonic::Request<super::UpdateBatchPoints>,
@@ -5583,21 +7491,65 @@ pub mod points_server {
                 }
                 "/qdrant.Points/SearchGroups" => {
                     #[allow(non_camel_case_types)]
-                    struct SearchGroupsSvc<T: Points>(pub Arc<T>);
-                    impl<T: Points> tonic::server::UnaryService<super::SearchPointGroups>
-                    for SearchGroupsSvc<T> {
-                        type Response = super::SearchGroupsResponse;
+                    struct SearchGroupsSvc<T: Points>(pub Arc<T>);
+                    impl<T: Points> tonic::server::UnaryService<super::SearchPointGroups>
+                    for SearchGroupsSvc<T> {
+                        type Response = super::SearchGroupsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SearchPointGroups>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Points>::search_groups(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SearchGroupsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/Scroll" => {
+                    #[allow(non_camel_case_types)]
+                    struct ScrollSvc<T: Points>(pub Arc<T>);
+                    impl<T: Points> tonic::server::UnaryService<super::ScrollPoints>
+                    for ScrollSvc<T> {
+                        type Response = super::ScrollResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SearchPointGroups>,
+                            request: tonic::Request<super::ScrollPoints>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Points>::search_groups(&inner, request).await
+                                <T as Points>::scroll(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -5609,7 +7561,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = SearchGroupsSvc(inner);
+                        let method = ScrollSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -5625,14 +7577,17 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
-                "/qdrant.Points/Scroll" => {
+                "/qdrant.Points/ScrollStream" => {
                     #[allow(non_camel_case_types)]
-                    struct ScrollSvc<T: Points>(pub Arc<T>);
-                    impl<T: Points> tonic::server::UnaryService<super::ScrollPoints>
-                    for ScrollSvc<T> {
+                    struct ScrollStreamSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::ServerStreamingService<super::ScrollPoints>
+                    for ScrollStreamSvc<T> {
                         type Response = super::ScrollResponse;
+                        type ResponseStream = T::ScrollStreamStream;
                         type Future = BoxFuture<
-                            tonic::Response<Self::Response>,
+                            tonic::Response<Self::ResponseStream>,
                             tonic::Status,
                         >;
                         fn call(
@@ -5641,7 +7596,7 @@ pub mod points_server {
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                <T as Points>::scroll(&inner, request).await
+                                <T as Points>::scroll_stream(&inner, request).await
                             };
                             Box::pin(fut)
                         }
@@ -5653,7 +7608,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ScrollSvc(inner);
+                        let method = ScrollStreamSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -5664,7 +7619,7 @@ pub mod points_server {
                                 max_decoding_message_size,
                                 max_encoding_message_size,
                             );
-                        let res = grpc.unary(method, req).await;
+                        let res = grpc.server_streaming(method, req).await;
                         Ok(res)
                     };
                     Box::pin(fut)
@@ -5849,6 +7804,50 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.Points/Query" => {
+                    #[allow(non_camel_case_types)]
+                    struct QuerySvc<T: Points>(pub Arc<T>);
+                    impl<T: Points> tonic::server::UnaryService<super::QueryPoints>
+                    for QuerySvc<T> {
+                        type Response = super::QueryResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::QueryPoints>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as Points>::query(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = QuerySvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/qdrant.Points/UpdateBatch" => {
                     #[allow(non_camel_case_types)]
                     struct UpdateBatchSvc<T: Points>(pub Arc<T>);
@@ -5961,12 +7960,36 @@ pub struct SyncPoints {
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ClockTag {
+    /// Peer, which assigned the clock
+    #[prost(uint64, tag = "1")]
+    pub peer_id: u64,
+    #[prost(uint64, tag = "2")]
+    pub clock_tick: u64,
+}
+#[derive(serde::Serialize)]
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PointClockTag {
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<PointId>,
+    #[prost(message, optional, tag = "2")]
+    pub clock_tag: ::core::option::Option<ClockTag>,
+}
+#[derive(serde::Serialize)]
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SyncPointsInternal {
     #[prost(message, optional, tag = "1")]
     #[validate]
     pub sync_points: ::core::option::Option<SyncPoints>,
     #[prost(uint32, optional, tag = "2")]
     pub shard_id: ::core::option::Option<u32>,
+    /// Clocks of the last changes of the synced points
+    #[prost(message, repeated, tag = "3")]
+    pub point_clock_tags: ::prost::alloc::vec::Vec<PointClockTag>,
 }
 #[derive(serde::Serialize)]
 #[derive(validator::Validate)]
@@ -6049,6 +8072,20 @@ pub struct ClearPayloadPointsInternal {
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateBatchInternal {
+    #[prost(message, optional, tag = "1")]
+    #[validate]
+    pub update_batch_points: ::core::option::Option<UpdateBatchPoints>,
+    #[prost(uint32, optional, tag = "2")]
+    pub shard_id: ::core::option::Option<u32>,
+    /// Clock, assigned to the update by the replica set
+    #[prost(message, optional, tag = "3")]
+    pub clock_tag: ::core::option::Option<ClockTag>,
+}
+#[derive(serde::Serialize)]
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct CreateFieldIndexCollectionInternal {
     #[prost(message, optional, tag = "1")]
     #[validate]
@@ -6095,6 +8132,10 @@ pub struct SearchBatchPointsInternal {
     pub search_points: ::prost::alloc::vec::Vec<SearchPoints>,
     #[prost(uint32, optional, tag = "3")]
     pub shard_id: ::core::option::Option<u32>,
+    #[prost(bool, optional, tag = "4")]
+    pub with_usage: ::core::option::Option<bool>,
+    #[prost(enumeration = "ExplainMode", optional, tag = "5")]
+    pub explain: ::core::option::Option<i32>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -6108,8 +8149,33 @@ pub struct RecoQuery {
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContextPair {
+    #[prost(message, optional, tag = "1")]
+    pub positive: ::core::option::Option<Vector>,
+    #[prost(message, optional, tag = "2")]
+    pub negative: ::core::option::Option<Vector>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct DiscoveryQuery {
+    #[prost(message, optional, tag = "1")]
+    pub target: ::core::option::Option<Vector>,
+    #[prost(message, repeated, tag = "2")]
+    pub context: ::prost::alloc::vec::Vec<ContextPair>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ContextQuery {
+    #[prost(message, repeated, tag = "1")]
+    pub context: ::prost::alloc::vec::Vec<ContextPair>,
+}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct QueryEnum {
-    #[prost(oneof = "query_enum::Query", tags = "1, 2")]
+    #[prost(oneof = "query_enum::Query", tags = "1, 2, 3, 4, 5, 6")]
     pub query: ::core::option::Option<query_enum::Query>,
 }
 /// Nested message and enum types in `QueryEnum`.
@@ -6124,6 +8190,18 @@ pub mod query_enum {
         /// Recommend points with higher similarity to positive examples
         #[prost(message, tag = "2")]
         RecommendBestScore(super::RecoQuery),
+        /// BM25 score of a full-text indexed payload field
+        #[prost(message, tag = "3")]
+        Text(super::TextQuery),
+        /// Search for points in the positive zones of the context, closer to the target
+        #[prost(message, tag = "4")]
+        Discover(super::DiscoveryQuery),
+        /// Search for points in the positive zones of the context
+        #[prost(message, tag = "5")]
+        Context(super::ContextQuery),
+        /// Sample points matching the filter
+        #[prost(enumeration = "super::Sample", tag = "6")]
+        Sample(i32),
     }
 }
 /// This is only used internally, so it makes more sense to add it here rather than in points.proto
@@ -6173,6 +8251,10 @@ pub struct CoreSearchBatchPointsInternal {
     pub search_points: ::prost::alloc::vec::Vec<CoreSearchPoints>,
     #[prost(uint32, optional, tag = "3")]
     pub shard_id: ::core::option::Option<u32>,
+    #[prost(bool, optional, tag = "4")]
+    pub with_usage: ::core::option::Option<bool>,
+    #[prost(enumeration = "ExplainMode", optional, tag = "5")]
+    pub explain: ::core::option::Option<i32>,
 }
 #[derive(serde::Serialize)]
 #[derive(validator::Validate)]
@@ -6528,6 +8610,31 @@ pub mod points_internal_client {
                 .insert(GrpcMethod::new("qdrant.PointsInternal", "ClearPayload"));
             self.inner.unary(req, path, codec).await
         }
+        pub async fn update_batch(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateBatchInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/UpdateBatch",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "UpdateBatch"));
+            self.inner.unary(req, path, codec).await
+        }
         pub async fn create_field_index(
             &mut self,
             request: impl tonic::IntoRequest<super::CreateFieldIndexCollectionInternal>,
@@ -6812,6 +8919,13 @@ pub mod points_internal_server {
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
         >;
+        async fn update_batch(
+            &self,
+            request: tonic::Request<super::UpdateBatchInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
         async fn create_field_index(
             &self,
             request: tonic::Request<super::CreateFieldIndexCollectionInternal>,
@@ -7358,6 +9472,52 @@ pub mod points_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.PointsInternal/UpdateBatch" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateBatchSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::UpdateBatchInternal>
+                    for UpdateBatchSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateBatchInternal>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as PointsInternal>::update_batch(&inner, request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateBatchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/qdrant.PointsInternal/CreateFieldIndex" => {
                     #[allow(non_camel_case_types)]
                     struct CreateFieldIndexSvc<T: PointsInternal>(pub Arc<T>);
@@ -7855,6 +10015,18 @@ pub struct WaitOnConsensusCommitResponse {
     #[prost(bool, tag = "1")]
     pub ok: bool,
 }
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsensusFeaturesRequest {}
+#[derive(serde::Serialize)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConsensusFeaturesResponse {
+    /// Batches of collection meta operations can be applied
+    #[prost(bool, tag = "1")]
+    pub collection_meta_batch: bool,
+}
 /// Generated client implementations.
 pub mod qdrant_internal_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -7996,6 +10168,33 @@ pub mod qdrant_internal_client {
                 );
             self.inner.unary(req, path, codec).await
         }
+        ///
+        /// Get consensus entry formats, which the node can apply.
+        pub async fn get_consensus_features(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConsensusFeaturesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConsensusFeaturesResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.QdrantInternal/GetConsensusFeatures",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.QdrantInternal", "GetConsensusFeatures"));
+            self.inner.unary(req, path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -8023,6 +10222,15 @@ pub mod qdrant_internal_server {
             tonic::Response<super::WaitOnConsensusCommitResponse>,
             tonic::Status,
         >;
+        ///
+        /// Get consensus entry formats, which the node can apply.
+        async fn get_consensus_features(
+            &self,
+            request: tonic::Request<super::ConsensusFeaturesRequest>,
+        ) -> std::result::Result<
+            tonic::Response<super::ConsensusFeaturesResponse>,
+            tonic::Status,
+        >;
     }
     #[derive(Debug)]
     pub struct QdrantInternalServer<T: QdrantInternal> {
@@ -8199,6 +10407,56 @@ pub mod qdrant_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.QdrantInternal/GetConsensusFeatures" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetConsensusFeaturesSvc<T: QdrantInternal>(pub Arc<T>);
+                    impl<
+                        T: QdrantInternal,
+                    > tonic::server::UnaryService<super::ConsensusFeaturesRequest>
+                    for GetConsensusFeaturesSvc<T> {
+                        type Response = super::ConsensusFeaturesResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConsensusFeaturesRequest>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                <T as QdrantInternal>::get_consensus_features(
+                                        &inner,
+                                        request,
+                                    )
+                                    .await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetConsensusFeaturesSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -8835,6 +11093,9 @@ pub struct CreateSnapshotRequest {
     #[prost(string, tag = "1")]
     #[validate(length(min = 1, max = 255))]
     pub collection_name: ::prost::alloc::string::String,
+    /// Create an incremental snapshot on top of this collection snapshot
+    #[prost(string, optional, tag = "2")]
+    pub base: ::core::option::Option<::prost::alloc::string::String>,
 }
 #[derive(serde::Serialize)]
 #[derive(validator::Validate)]
@@ -8874,6 +11135,18 @@ pub struct SnapshotDescription {
     /// Size of the snapshot in bytes
     #[prost(int64, tag = "3")]
     pub size: i64,
+    /// SHA256 checksum of the snapshot file
+    #[prost(string, optional, tag = "4")]
+    pub checksum: ::core::option::Option<::prost::alloc::string::String>,
+    /// Version of Qdrant, which created the snapshot
+    #[prost(string, optional, tag = "5")]
+    pub version: ::core::option::Option<::prost::alloc::string::String>,
+    /// Time it took to create the snapshot, in seconds
+    #[prost(double, optional, tag = "6")]
+    pub creation_duration_sec: ::core::option::Option<f64>,
+    /// Whether the snapshot can be recovered by the running version of Qdrant
+    #[prost(bool, optional, tag = "7")]
+    pub compatible: ::core::option::Option<bool>,
 }
 #[derive(serde::Serialize)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -0,0 +1,68 @@
+//! Propagation of the OpenTelemetry trace context through the internal gRPC requests,
+//! so the spans of a request on the other peers are attached to the same trace.
+//!
+//! Without the `opentelemetry` feature the requests are sent as is.
+
+use tonic::metadata::MetadataMap;
+use tonic::service::Interceptor;
+use tonic::{Request, Status};
+
+/// Client interceptor, which adds the context of the current span to the request metadata
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TraceContextInterceptor;
+
+impl Interceptor for TraceContextInterceptor {
+    fn call(&mut self, mut request: Request<()>) -> Result<Request<()>, Status> {
+        inject_trace_context(request.metadata_mut());
+        Ok(request)
+    }
+}
+
+#[cfg(feature = "opentelemetry")]
+pub fn inject_trace_context(metadata: &mut MetadataMap) {
+    use opentelemetry::propagation::Injector;
+    use tonic::metadata::{MetadataKey, MetadataValue};
+    use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+    struct MetadataInjector<'a>(&'a mut MetadataMap);
+
+    impl Injector for MetadataInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            // Keys and values of the trace context propagator are always valid ASCII
+            if let (Ok(key), Ok(value)) = (
+                MetadataKey::from_bytes(key.as_bytes()),
+                MetadataValue::try_from(value),
+            ) {
+                self.0.insert(key, value);
+            }
+        }
+    }
+
+    let context = tracing::Span::current().context();
+    opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.inject_context(&context, &mut MetadataInjector(metadata))
+    });
+}
+
+#[cfg(not(feature = "opentelemetry"))]
+pub fn inject_trace_context(_metadata: &mut MetadataMap) {}
+
+/// Attach the span to the trace context, received in the headers of a request
+#[cfg(feature = "opentelemetry")]
+pub fn set_parent_from_headers<'a>(
+    span: &tracing::Span,
+    headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+) {
+    use std::collections::HashMap;
+
+    use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+
+    // `HashMap` implements the `Extractor` of the propagators
+    let headers: HashMap<String, String> = headers
+        .into_iter()
+        .map(|(key, value)| (key.to_ascii_lowercase(), value.to_string()))
+        .collect();
+    let parent =
+        opentelemetry::global::get_text_map_propagator(|propagator| propagator.extract(&headers));
+    span.set_parent(parent);
+}
@@ -125,6 +125,7 @@ impl Validate for crate::grpc::qdrant::condition::ConditionOneOf {
             ConditionOneOf::Filter(filter) => filter.validate(),
             ConditionOneOf::IsEmpty(_) => Ok(()),
             ConditionOneOf::HasId(_) => Ok(()),
+            ConditionOneOf::HasVector(_) => Ok(()),
             ConditionOneOf::IsNull(_) => Ok(()),
         }
     }
@@ -134,6 +135,7 @@ impl Validate for crate::grpc::qdrant::FieldCondition {
     fn validate(&self) -> Result<(), ValidationErrors> {
         let all_fields_none = self.r#match.is_none()
             && self.range.is_none()
+            && self.datetime_range.is_none()
             && self.geo_bounding_box.is_none()
             && self.geo_radius.is_none()
             && self.geo_polygon.is_none()
@@ -54,6 +54,7 @@ fn batch_search_bench(c: &mut Criterion) {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -63,6 +64,8 @@ fn batch_search_bench(c: &mut Criterion) {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            multivector_config: None,
+            datatype: None,
         }
         .into(),
         ..CollectionParams::empty()
@@ -79,6 +82,9 @@ fn batch_search_bench(c: &mut Criterion) {
             indexing_threshold: Some(50_000),
             flush_interval_sec: 30,
             max_optimization_threads: 2,
+            expiration_key: None,
+            merge_policy: None,
+            defragmentation_key: None,
         },
         wal_config,
         hnsw_config: Default::default(),
@@ -140,6 +146,7 @@ fn batch_search_bench(c: &mut Criterion) {
                             with_payload: None,
                             with_vector: None,
                             score_threshold: None,
+                            text: None,
                         };
                         let result = shard
                             .search(
@@ -172,6 +179,7 @@ fn batch_search_bench(c: &mut Criterion) {
                             with_payload: None,
                             with_vector: None,
                             score_threshold: None,
+                            text: None,
                         };
                         searches.push(search_query);
                     }
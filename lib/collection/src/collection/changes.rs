@@ -0,0 +1,72 @@
+use futures::future::{self, BoxFuture};
+use futures::FutureExt as _;
+use itertools::Itertools as _;
+
+use super::Collection;
+use crate::operations::change_ops::{ChangesOffset, ChangesResult, CollectionChange, PointsChange};
+use crate::operations::types::CollectionResult;
+
+impl Collection {
+    /// Changes of the points after the `offset`, read from WAL of the local shards.
+    ///
+    /// Reads at most `limit` operations, shards without a local replica are skipped.
+    pub async fn changes(
+        &self,
+        offset: &ChangesOffset,
+        limit: usize,
+    ) -> CollectionResult<ChangesResult> {
+        let shards_holder = self.shards_holder.read().await;
+
+        let mut changes = Vec::new();
+        let mut next_offset = offset.clone();
+        let mut operations_read = 0;
+        let shards = shards_holder
+            .get_shards()
+            .sorted_by_key(|(shard_id, _)| **shard_id);
+        for (&shard_id, replica_set) in shards {
+            let from = offset.0.get(&shard_id).copied();
+            let Some((operations, next)) = replica_set
+                .read_local_wal_operations(from, limit - operations_read)
+                .await?
+            else {
+                continue;
+            };
+            operations_read += operations.len();
+            next_offset.0.insert(shard_id, next);
+
+            for (operation_id, operation) in operations {
+                changes.extend(
+                    PointsChange::from_operation(operation)
+                        .into_iter()
+                        .map(|change| CollectionChange {
+                            shard_id,
+                            operation_id,
+                            change,
+                        }),
+                );
+            }
+        }
+
+        Ok(ChangesResult {
+            changes,
+            next_offset,
+        })
+    }
+
+    /// Resolves on the next update of any local shard.
+    ///
+    /// Doesn't borrow the collection, so it can be awaited without holding the collection lock.
+    pub async fn watch_local_updates(&self) -> BoxFuture<'static, ()> {
+        let shards_holder = self.shards_holder.read().await;
+
+        let mut update_watchers = Vec::new();
+        for replica_set in shards_holder.all_shards() {
+            update_watchers.push(replica_set.watch_local_update().await);
+        }
+
+        if update_watchers.is_empty() {
+            return future::pending().boxed();
+        }
+        future::select_all(update_watchers).map(|_| ()).boxed()
+    }
+}
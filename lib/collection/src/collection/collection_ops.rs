@@ -1,15 +1,18 @@
 use std::cmp;
 use std::sync::Arc;
 
+use futures::future::BoxFuture;
 use futures::{future, TryStreamExt as _};
-use segment::types::QuantizationConfig;
+use segment::types::{PayloadKeyType, QuantizationConfig};
 
 use super::Collection;
+use crate::common::optimize_tasks::OptimizeTasks;
 use crate::operations::config_diff::*;
 use crate::operations::types::*;
-use crate::optimizers_builder::OptimizersConfig;
+use crate::optimizers_builder::{OptimizeTarget, OptimizersConfig, RebuildIndexTarget};
 use crate::shards::replica_set::{Change, ReplicaState};
 use crate::shards::shard::{PeerId, ShardId};
+use crate::shards::shard_holder::shard_not_found_error;
 
 impl Collection {
     /// Updates collection params:
@@ -19,11 +22,19 @@ impl Collection {
     /// the updated configuration.
     pub async fn update_params_from_diff(
         &self,
-        params_diff: CollectionParamsDiff,
+        mut params_diff: CollectionParamsDiff,
     ) -> CollectionResult<()> {
+        // Scoring plugin is replaced or removed as a whole, instead of merging its fields
+        let scoring_plugin_diff = params_diff.scoring_plugin.take();
         {
             let mut config = self.collection_config.write().await;
-            config.params = params_diff.update(&config.params)?;
+            let mut params = params_diff.update(&config.params)?;
+            if let Some(diff) = scoring_plugin_diff {
+                params.scoring_plugin = diff.update(config.params.scoring_plugin.as_ref())?;
+            }
+            config.params = params;
+            self.search_cache
+                .resize(config.params.search_cache_size.unwrap_or_default());
         }
         self.collection_config.read().await.save(&self.path)?;
         Ok(())
@@ -64,6 +75,30 @@ impl Collection {
         Ok(())
     }
 
+    /// Adds new vectors to the collection:
+    /// Saves new params on disk
+    ///
+    /// After this, `recreate_optimizers_blocking` must be called to create storage for the new
+    /// vectors in existing segments.
+    pub async fn add_vectors(&self, new_vectors: &VectorsConfig) -> CollectionResult<()> {
+        let mut config = self.collection_config.write().await;
+        config.params.add_vectors(new_vectors)?;
+        config.save(&self.path)?;
+        Ok(())
+    }
+
+    /// Removes vectors from the collection:
+    /// Saves new params on disk
+    ///
+    /// After this, `recreate_optimizers_blocking` must be called to remove the vectors data from
+    /// existing segments.
+    pub async fn drop_vectors(&self, vector_names: &[String]) -> CollectionResult<()> {
+        let mut config = self.collection_config.write().await;
+        config.params.drop_vectors(vector_names)?;
+        config.save(&self.path)?;
+        Ok(())
+    }
+
     /// Updates shard optimization params:
     /// Saves new params on disk
     ///
@@ -224,6 +259,8 @@ impl Collection {
                     .and_modify(|info_schema| info_schema.points += response_schema.points)
                     .or_insert(response_schema);
             }
+
+            info.optimizations.merge(response.optimizations);
         }
 
         Ok(info)
@@ -285,4 +322,100 @@ impl Collection {
         };
         Ok(info)
     }
+
+    /// Usage of WAL of the local shards
+    pub async fn wal_info(&self) -> CollectionResult<Vec<ShardWalInfo>> {
+        let shards_holder = self.shards_holder.read().await;
+        let mut wal_info = Vec::new();
+        for (_shard_id, replica_set) in shards_holder.get_shards() {
+            if let Some(shard_wal_info) = replica_set.local_wal_info().await? {
+                wal_info.push(shard_wal_info);
+            }
+        }
+        wal_info.sort_by_key(|k| k.shard_id);
+        Ok(wal_info)
+    }
+
+    /// Segments of the local shards
+    pub async fn segments_info(&self) -> Vec<ShardSegmentsInfo> {
+        let shards_holder = self.shards_holder.read().await;
+        let mut segments_info = Vec::new();
+        for (_shard_id, replica_set) in shards_holder.get_shards() {
+            if let Some(shard_segments_info) = replica_set.local_segments_info().await {
+                segments_info.push(shard_segments_info);
+            }
+        }
+        segments_info.sort_by_key(|k| k.shard_id);
+        segments_info
+    }
+
+    pub fn optimize_tasks(&self) -> &OptimizeTasks {
+        &self.optimize_tasks
+    }
+
+    /// Launch optimizations of the local shards right away, instead of waiting for the optimizer
+    /// thresholds. Returns futures, which resolve once the optimizations of a shard are finished.
+    pub async fn launch_forced_optimization(
+        &self,
+        target: Option<OptimizeTarget>,
+    ) -> Vec<BoxFuture<'static, CollectionResult<()>>> {
+        let shards_holder = self.shards_holder.read().await;
+        let mut optimizations = Vec::new();
+        for (_shard_id, replica_set) in shards_holder.get_shards() {
+            if let Some(optimization) = replica_set.launch_local_forced_optimization(target).await {
+                optimizations.push(optimization);
+            }
+        }
+        optimizations
+    }
+
+    /// Rebuild indexes of the local shards right away, segment by segment.
+    /// Returns futures, which resolve once the indexes of a shard are rebuilt.
+    pub async fn launch_index_rebuild(
+        &self,
+        target: RebuildIndexTarget,
+        field_name: Option<PayloadKeyType>,
+    ) -> CollectionResult<Vec<BoxFuture<'static, CollectionResult<()>>>> {
+        if field_name.is_some() && target == RebuildIndexTarget::Vectors {
+            return Err(CollectionError::bad_request(
+                "Field name can only be given to rebuild payload indexes".to_string(),
+            ));
+        }
+        let shards_holder = self.shards_holder.read().await;
+        let mut rebuilds = Vec::new();
+        for (_shard_id, replica_set) in shards_holder.get_shards() {
+            if let Some(rebuild) = replica_set
+                .launch_local_index_rebuild(target, field_name.clone())
+                .await
+            {
+                rebuilds.push(rebuild);
+            }
+        }
+        Ok(rebuilds)
+    }
+
+    /// Pause or resume background optimizations of the local shards.
+    ///
+    /// Pause is not persisted, optimizations are resumed once the collection is reloaded.
+    pub async fn set_optimizers_paused(&self, paused: bool) -> CollectionResult<()> {
+        let shards_holder = self.shards_holder.read().await;
+        for (_shard_id, replica_set) in shards_holder.get_shards() {
+            replica_set.set_local_optimizers_paused(paused).await?;
+        }
+        Ok(())
+    }
+
+    /// Remove applied operations, kept in WAL of the local shard within the retention limits
+    pub async fn truncate_retained_wal(&self, shard_id: ShardId) -> CollectionResult<ShardWalInfo> {
+        let shards_holder = self.shards_holder.read().await;
+        let replica_set = shards_holder
+            .get_shard(&shard_id)
+            .ok_or_else(|| shard_not_found_error(shard_id))?;
+        replica_set
+            .truncate_local_retained_wal()
+            .await?
+            .ok_or_else(|| {
+                CollectionError::bad_input(format!("Shard {shard_id} is not a local shard"))
+            })
+    }
 }
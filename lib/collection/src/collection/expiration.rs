@@ -0,0 +1,131 @@
+use chrono::{DateTime, Utc};
+use segment::types::{
+    Condition, DatetimeRange, FieldCondition, Filter, PayloadFieldSchema, PayloadKeyType,
+    PayloadSchemaType,
+};
+
+use super::Collection;
+use crate::operations::point_ops::WriteOrdering;
+use crate::operations::types::CollectionResult;
+use crate::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
+use crate::shards::shard::ShardId;
+
+/// Number of expired points deleted at once
+const EXPIRATION_BATCH_SIZE: usize = 10_000;
+
+/// Expired points are deleted by the highest alive replica of each shard,
+/// and forwarded to the other replicas as a regular update
+const EXPIRATION_ORDERING: WriteOrdering = WriteOrdering::Medium;
+
+/// Points, which expiration time is before `now`
+fn expired_points_filter(expiration_key: PayloadKeyType, now: DateTime<Utc>) -> Filter {
+    Filter::new_must(Condition::Field(FieldCondition::new_datetime_range(
+        expiration_key,
+        DatetimeRange {
+            lt: Some(now),
+            ..Default::default()
+        },
+    )))
+}
+
+impl Collection {
+    /// Delete points, which expiration time has passed, from the shards led by this peer.
+    ///
+    /// Deletions go through the replica set, like updates of the clients, so every replica
+    /// receives them along with a clock tag. Expired points are looked up in the datetime index
+    /// on the expiration key, which is created if missing.
+    ///
+    /// Returns the number of deleted points.
+    pub async fn expire_points(&self) -> CollectionResult<usize> {
+        let expiration_key = self
+            .collection_config
+            .read()
+            .await
+            .optimizer_config
+            .expiration_key
+            .clone();
+        let Some(expiration_key) = expiration_key else {
+            return Ok(0);
+        };
+
+        let shard_ids = self.led_shard_ids(EXPIRATION_ORDERING).await;
+        if shard_ids.is_empty() {
+            return Ok(0);
+        }
+
+        if !self.ensure_expiration_index(&expiration_key).await? {
+            return Ok(0);
+        }
+
+        let filter = expired_points_filter(expiration_key, Utc::now());
+        let mut deleted = 0;
+        for shard_id in shard_ids {
+            loop {
+                let batch = self
+                    .delete_points_by_filter_batch(
+                        shard_id,
+                        &filter,
+                        EXPIRATION_BATCH_SIZE,
+                        EXPIRATION_ORDERING,
+                    )
+                    .await?;
+                if batch == 0 {
+                    break;
+                }
+                deleted += batch;
+            }
+        }
+        Ok(deleted)
+    }
+
+    /// Shards, which updates with the given ordering are led by this peer
+    async fn led_shard_ids(&self, ordering: WriteOrdering) -> Vec<ShardId> {
+        let shards_holder = self.shards_holder.read().await;
+        shards_holder
+            .get_shards()
+            .filter(|(_, replica_set)| {
+                replica_set.leader_peer_for_update(ordering) == Some(replica_set.this_peer_id())
+            })
+            .map(|(shard_id, _)| *shard_id)
+            .collect()
+    }
+
+    /// Create the datetime index on the expiration key, if it doesn't exist yet.
+    ///
+    /// Returns false, if the key is indexed with another type. Expired points can't be found
+    /// without a full scan of the payload then, so they are not deleted.
+    async fn ensure_expiration_index(
+        &self,
+        expiration_key: &PayloadKeyType,
+    ) -> CollectionResult<bool> {
+        let payload_schema = self.info(None).await?.payload_schema;
+        match payload_schema.get(expiration_key) {
+            Some(index) if index.data_type == PayloadSchemaType::Datetime => Ok(true),
+            Some(index) => {
+                log::warn!(
+                    "Expired points of collection {} are not deleted, expiration key {expiration_key} is indexed as {:?} instead of datetime",
+                    self.id,
+                    index.data_type,
+                );
+                Ok(false)
+            }
+            None => {
+                log::info!(
+                    "Creating datetime index on expiration key {expiration_key} of collection {}",
+                    self.id,
+                );
+                let operation = CollectionUpdateOperations::FieldIndexOperation(
+                    FieldIndexOperations::CreateIndex(CreateIndex {
+                        field_name: expiration_key.clone(),
+                        field_schema: Some(PayloadFieldSchema::FieldType(
+                            PayloadSchemaType::Datetime,
+                        )),
+                    }),
+                );
+                self.update_from_client(operation, true, EXPIRATION_ORDERING)
+                    .await?;
+                Ok(true)
+            }
+        }
+    }
+}
@@ -1,22 +1,41 @@
+mod changes;
 mod collection_ops;
+mod expiration;
+mod point_in_time;
 mod point_ops;
+mod query;
 mod search;
 mod shard_transfer;
+mod snapshot_manifest;
 mod snapshots;
 
+pub use point_in_time::PointInTimeOperations;
+pub use query::resolve_query_lookups;
+
 use std::collections::HashSet;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
+use common::types::TelemetryDetail;
 use segment::common::version::StorageVersion;
+use segment::index::field_index::stats::{FieldIndexStats, PayloadIndexStatistics};
+use segment::types::PayloadKeyTypeRef;
 use semver::Version;
 use tokio::runtime::Handle;
 use tokio::sync::{Mutex, RwLock, RwLockWriteGuard};
 
 use crate::collection_state::{ShardInfo, State};
+use crate::common::delete_tasks::DeleteTasks;
+use crate::common::import_tasks::ImportTasks;
 use crate::common::is_ready::IsReady;
+use crate::common::optimize_tasks::OptimizeTasks;
+use crate::common::scoring_plugin::ScoringPluginCache;
+use crate::common::scroll_cursors::ScrollCursors;
+use crate::common::search_cache::SearchCache;
+use crate::common::snapshot_retention::SnapshotRetentionPolicy;
+use crate::common::snapshot_schedule::ScheduledSnapshots;
 use crate::config::CollectionConfig;
 use crate::hash_ring::HashRing;
 use crate::operations::shared_storage_config::SharedStorageConfig;
@@ -56,6 +75,22 @@ pub struct Collection {
     updates_lock: RwLock<()>,
     // Update runtime handle.
     update_runtime: Handle,
+    // Cursors of stable scrolls, which pin the points of the shards to read.
+    scroll_cursors: ScrollCursors,
+    // Results of repeated searches, enabled by `search_cache_size` of the collection params.
+    search_cache: SearchCache,
+    // Compiled module of the scoring plugin of the collection, used by `plugin` queries.
+    scoring_plugin: ScoringPluginCache,
+    // Background deletions of points by filter, started on this peer.
+    delete_tasks: DeleteTasks,
+    // Imports of points, streamed to this peer.
+    import_tasks: ImportTasks,
+    // Optimizations of the local shards, requested to run right away on this peer.
+    optimize_tasks: OptimizeTasks,
+    // Automatic snapshots, created by this peer.
+    scheduled_snapshots: ScheduledSnapshots,
+    // Limits on the snapshots, stored on this peer.
+    snapshot_retention: SnapshotRetentionPolicy,
 }
 
 pub type RequestShardTransfer = Arc<dyn Fn(ShardTransfer) + Send + Sync>;
@@ -128,6 +163,14 @@ impl Collection {
             is_initialized: Arc::new(Default::default()),
             updates_lock: RwLock::new(()),
             update_runtime: update_runtime.unwrap_or_else(Handle::current),
+            scroll_cursors: ScrollCursors::default(),
+            search_cache: SearchCache::default(),
+            scoring_plugin: ScoringPluginCache::default(),
+            delete_tasks: DeleteTasks::default(),
+            import_tasks: ImportTasks::default(),
+            optimize_tasks: OptimizeTasks::default(),
+            scheduled_snapshots: ScheduledSnapshots::load(path),
+            snapshot_retention: SnapshotRetentionPolicy::load(path),
         })
     }
 
@@ -215,6 +258,14 @@ impl Collection {
             is_initialized: Arc::new(Default::default()),
             updates_lock: RwLock::new(()),
             update_runtime: update_runtime.unwrap_or_else(Handle::current),
+            scroll_cursors: ScrollCursors::default(),
+            search_cache: SearchCache::default(),
+            scoring_plugin: ScoringPluginCache::default(),
+            delete_tasks: DeleteTasks::default(),
+            import_tasks: ImportTasks::default(),
+            optimize_tasks: OptimizeTasks::default(),
+            scheduled_snapshots: ScheduledSnapshots::load(path),
+            snapshot_retention: SnapshotRetentionPolicy::load(path),
         }
     }
 
@@ -241,6 +292,20 @@ impl Collection {
         true
     }
 
+    /// Version of the collection storage, written by this version of Qdrant
+    pub fn storage_version() -> String {
+        CollectionVersion::current()
+    }
+
+    /// Whether collection storage of the `stored` version can be loaded by this version of Qdrant
+    pub fn is_storage_compatible(stored: &Version) -> bool {
+        let app_version: Version = CollectionVersion::current()
+            .parse()
+            .expect("Failed to parse current collection version as semver");
+        *stored == app_version
+            || (*stored < app_version && Self::can_upgrade_storage(stored, &app_version))
+    }
+
     pub fn name(&self) -> String {
         self.id.clone()
     }
@@ -502,12 +567,12 @@ impl Collection {
         Ok(())
     }
 
-    pub async fn get_telemetry_data(&self) -> CollectionTelemetry {
+    pub async fn get_telemetry_data(&self, detail: TelemetryDetail) -> CollectionTelemetry {
         let (shards_telemetry, transfers) = {
             let mut shards_telemetry = Vec::new();
             let shards_holder = self.shards_holder.read().await;
             for shard in shards_holder.all_shards() {
-                shards_telemetry.push(shard.get_telemetry_data().await)
+                shards_telemetry.push(shard.get_telemetry_data(detail).await)
             }
             (shards_telemetry, shards_holder.get_shard_transfer_info())
         };
@@ -518,7 +583,31 @@ impl Collection {
             config: self.collection_config.read().await.clone(),
             shards: shards_telemetry,
             transfers,
+            search_cache: (self.search_cache_size().await > 0)
+                .then(|| self.search_cache.get_telemetry_data()),
+        }
+    }
+
+    /// Statistics of the payload index of `field`, collected over the shards of this peer
+    pub async fn payload_index_stats(
+        &self,
+        field: PayloadKeyTypeRef,
+    ) -> CollectionResult<PayloadIndexStatistics> {
+        let mut stats: Option<FieldIndexStats> = None;
+        let shards_holder = self.shards_holder.read().await;
+        for shard in shards_holder.all_shards() {
+            if let Some(shard_stats) = shard.get_field_index_stats(field).await {
+                match &mut stats {
+                    Some(stats) => stats.merge(shard_stats),
+                    None => stats = Some(shard_stats),
+                }
+            }
         }
+        stats
+            .map(FieldIndexStats::summarize)
+            .ok_or_else(|| CollectionError::NotFound {
+                what: format!("Payload index for field {field}"),
+            })
     }
 
     pub async fn lock_updates(&self) -> RwLockWriteGuard<()> {
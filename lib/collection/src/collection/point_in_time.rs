@@ -0,0 +1,163 @@
+use std::collections::{BTreeMap, HashMap};
+
+use segment::types::SeqNumberType;
+
+use super::snapshot_manifest::SnapshotManifest;
+use super::Collection;
+use crate::operations::change_ops::ChangesOffset;
+use crate::operations::snapshot_ops::{PointInTimeRecover, RecoveryWindow};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::shard::ShardId;
+use crate::shards::shard_holder::shard_not_found_error;
+
+/// Operations to replay on top of a snapshot, to recover the local shards to a point in time
+pub type PointInTimeOperations = HashMap<ShardId, Vec<CollectionUpdateOperations>>;
+
+impl Collection {
+    /// Snapshots of this peer, which can be recovered to a later point in time
+    /// with the operations, retained in WAL of the local shards.
+    pub async fn recovery_windows(&self) -> CollectionResult<Vec<RecoveryWindow>> {
+        let mut wal_ranges = BTreeMap::new();
+        {
+            let shards_holder = self.shards_holder.read().await;
+            for (&shard_id, replica_set) in shards_holder.get_shards() {
+                if let Some(wal_range) = replica_set.local_stored_wal_range().await {
+                    wal_ranges.insert(shard_id, wal_range);
+                }
+            }
+        }
+
+        let mut windows = Vec::new();
+        for snapshot in self.list_snapshots().await? {
+            let Some(wal_positions) = self.snapshot_wal_positions(&snapshot.name).await? else {
+                continue;
+            };
+            let mut from_offset = ChangesOffset::default();
+            let mut to_offset = ChangesOffset::default();
+            for (shard_id, position) in wal_positions {
+                let Some(&(first_stored, next)) = wal_ranges.get(&shard_id) else {
+                    continue;
+                };
+                if first_stored <= position && position <= next {
+                    from_offset.0.insert(shard_id, position);
+                    to_offset.0.insert(shard_id, next);
+                }
+            }
+            // Operations after the snapshot must be retained for all local shards
+            if from_offset.0.is_empty() || from_offset.0.len() != wal_ranges.len() {
+                continue;
+            }
+            windows.push(RecoveryWindow {
+                snapshot: snapshot.name,
+                creation_time: snapshot.creation_time,
+                from_offset,
+                to_offset,
+            });
+        }
+        Ok(windows)
+    }
+
+    /// Numbers of the next operation after the snapshot in each shard, `None` if the snapshot
+    /// was created without them, e.g. by an older version or without WAL.
+    async fn snapshot_wal_positions(
+        &self,
+        snapshot_name: &str,
+    ) -> CollectionResult<Option<HashMap<ShardId, SeqNumberType>>> {
+        let snapshot_path = self.get_snapshot_path(snapshot_name).await?;
+        let manifest = tokio::task::spawn_blocking(move || {
+            SnapshotManifest::read_from_archive(&snapshot_path)
+        })
+        .await??;
+        Ok(manifest
+            .map(|manifest| manifest.wal_positions)
+            .filter(|wal_positions| !wal_positions.is_empty()))
+    }
+
+    /// Read operations from WAL of the local shards, which followed the snapshot up to the point in time.
+    ///
+    /// Must be called before the snapshot is recovered, as recovery replaces WAL of the shards.
+    pub async fn point_in_time_operations(
+        &self,
+        request: &PointInTimeRecover,
+    ) -> CollectionResult<PointInTimeOperations> {
+        let until_timestamp = match (&request.until_offset, request.until_time) {
+            (Some(_), None) => None,
+            (None, Some(until_time)) => Some(until_time.timestamp_millis()),
+            _ => {
+                return Err(CollectionError::bad_input(
+                    "Exactly one of `until_offset` and `until_time` must be specified".to_string(),
+                ))
+            }
+        };
+        let wal_positions = self
+            .snapshot_wal_positions(&request.snapshot)
+            .await?
+            .ok_or_else(|| {
+                CollectionError::bad_input(format!(
+                    "Snapshot {} has no WAL positions, it can only be recovered as is",
+                    request.snapshot
+                ))
+            })?;
+
+        let shards_holder = self.shards_holder.read().await;
+        let mut operations = PointInTimeOperations::new();
+        for (shard_id, position) in wal_positions {
+            let Some(replica_set) = shards_holder.get_shard(&shard_id) else {
+                continue;
+            };
+            let Some((_, next)) = replica_set.local_stored_wal_range().await else {
+                continue;
+            };
+            let until = match until_timestamp {
+                Some(timestamp) => replica_set
+                    .local_wal_index_after(timestamp)
+                    .await
+                    .unwrap_or(next),
+                None => request
+                    .until_offset
+                    .as_ref()
+                    .and_then(|until_offset| until_offset.0.get(&shard_id).copied())
+                    .unwrap_or(next)
+                    .min(next),
+            };
+            if until < position {
+                return Err(CollectionError::bad_input(format!(
+                    "Shard {shard_id} can't be recovered to operation {until}, snapshot {} already contains operations up to {position}",
+                    request.snapshot
+                )));
+            }
+
+            let Some((shard_operations, _)) = replica_set
+                .read_local_wal_operations(Some(position), (until - position) as usize)
+                .await?
+            else {
+                continue;
+            };
+            operations.insert(
+                shard_id,
+                shard_operations
+                    .into_iter()
+                    .map(|(_, operation)| operation)
+                    .collect(),
+            );
+        }
+        Ok(operations)
+    }
+
+    /// Apply operations to the local replica of the shard, e.g. replay them after snapshot recovery
+    pub async fn replay_local_operations(
+        &self,
+        shard_id: ShardId,
+        operations: Vec<CollectionUpdateOperations>,
+    ) -> CollectionResult<()> {
+        let shards_holder = self.shards_holder.read().await;
+        let replica_set = shards_holder
+            .get_shard(&shard_id)
+            .ok_or_else(|| shard_not_found_error(shard_id))?;
+        for operation in operations {
+            replica_set.update_local(operation, false).await?;
+        }
+        Ok(())
+    }
+}
@@ -1,13 +1,19 @@
+use std::ops::Bound;
 use std::sync::Arc;
 
 use futures::{future, TryStreamExt as _};
 use itertools::Itertools as _;
-use segment::types::{WithPayload, WithPayloadInterface};
+use segment::types::{Filter, PointIdType, WithPayload, WithPayloadInterface, WithVector};
+use uuid::Uuid;
 use validator::Validate as _;
 
 use super::Collection;
+use crate::common::delete_tasks::DeleteTasks;
+use crate::common::import_tasks::ImportTasks;
+use crate::common::query_trace::trace_shard;
+use crate::common::scroll_cursors::{ScrollCursors, ScrollSnapshot};
 use crate::operations::consistency_params::ReadConsistency;
-use crate::operations::point_ops::WriteOrdering;
+use crate::operations::point_ops::{PointOperations, WriteOrdering};
 use crate::operations::types::*;
 use crate::operations::CollectionUpdateOperations;
 use crate::shards::shard::ShardId;
@@ -54,11 +60,33 @@ impl Collection {
         ordering: WriteOrdering,
     ) -> CollectionResult<UpdateResult> {
         operation.validate()?;
+
+        // Preconditions are checked against the applied points of the update leader replica.
+        // Conditional upserts are waited for, so the next one sees the result of the previous one.
+        let is_conditional = operation.is_conditional();
+        let ordering = if is_conditional {
+            if !wait {
+                return Err(CollectionError::bad_request(
+                    "Conditional upsert requires `wait=true`".to_string(),
+                ));
+            }
+            WriteOrdering::Strong
+        } else {
+            ordering
+        };
+
         let _update_lock = self.updates_lock.read().await;
 
         let mut results = {
             let shards_holder = self.shards_holder.read().await;
-            let shard_to_op = shards_holder.split_by_shard(operation);
+            // Part of the operation in each shard is applied once for the token
+            let (idempotency_token, operation) = operation.into_idempotency_token();
+            let shard_to_op = match operation {
+                CollectionUpdateOperations::BatchOperation(operations) => {
+                    shards_holder.split_batch_by_shard(operations)
+                }
+                operation => shards_holder.split_by_shard(operation),
+            };
 
             if shard_to_op.is_empty() {
                 return Err(CollectionError::bad_request(
@@ -66,9 +94,18 @@ impl Collection {
                 ));
             }
 
+            // Preconditions are checked by each shard on its own. If the update spans several
+            // shards, only some of them could fail the check, leaving the update half-applied.
+            if is_conditional && shard_to_op.len() > 1 {
+                return Err(CollectionError::bad_request(
+                    "Conditional upsert can only update points of a single shard".to_string(),
+                ));
+            }
+
             let shard_requests = shard_to_op
                 .into_iter()
                 .map(move |(replica_set, operation)| {
+                    let operation = operation.with_idempotency_token(idempotency_token.clone());
                     replica_set.update_with_consistency(operation, wait, ordering)
                 });
             future::join_all(shard_requests).await
@@ -97,8 +134,20 @@ impl Collection {
                 first_err
             }
         } else {
+            // Points of different shards are distinct, so the affected ones are summed up
+            let affected_points = results
+                .iter()
+                .map(|result| {
+                    result
+                        .as_ref()
+                        .ok()
+                        .and_then(|result| result.affected_points)
+                })
+                .sum::<Option<usize>>();
             // At least one result is always present.
-            results.pop().unwrap()
+            let mut result = results.pop().unwrap()?;
+            result.affected_points = affected_points;
+            Ok(result)
         }
     }
 
@@ -107,6 +156,121 @@ impl Collection {
         request: ScrollRequest,
         read_consistency: Option<ReadConsistency>,
         shard_selection: Option<ShardId>,
+    ) -> CollectionResult<ScrollResult> {
+        if let Some(cursor) = &request.cursor {
+            let (id, snapshot, last_point_id) = self.scroll_cursors.get(cursor)?;
+            return self
+                .scroll_snapshot(id, &snapshot, Bound::Excluded(last_point_id), &request)
+                .await;
+        }
+        if !request.stable.unwrap_or(false) {
+            return self
+                .scroll_by_offset(request, read_consistency, shard_selection)
+                .await;
+        }
+        if request.order_by.is_some() {
+            return Err(CollectionError::bad_request(
+                "Stable scroll can't be used with order_by".to_string(),
+            ));
+        }
+
+        // Pin IDs of the points in the local replicas, points inserted later are not scrolled
+        let shard_points = {
+            let shards_holder = self.shards_holder.read().await;
+            let target_shards = shards_holder.target_shard(shard_selection)?;
+            let filter = request.filter.as_ref();
+            let point_ids_futures = target_shards.into_iter().map(|shard| async move {
+                let point_ids = shard.local_point_ids(filter).await?;
+                CollectionResult::Ok((shard.shard_id, point_ids))
+            });
+            future::try_join_all(point_ids_futures).await?
+        };
+        let (id, snapshot) = self.scroll_cursors.pin(ScrollSnapshot { shard_points })?;
+        let from = request.offset.map_or(Bound::Unbounded, Bound::Included);
+        self.scroll_snapshot(id, &snapshot, from, &request).await
+    }
+
+    /// Read a page of a stable scroll from the local replicas.
+    ///
+    /// Only the points, which existed at the first page of the scroll, are read. Deleted points
+    /// are skipped, changed ones are read in their latest state.
+    async fn scroll_snapshot(
+        &self,
+        id: Uuid,
+        snapshot: &ScrollSnapshot,
+        from: Bound<PointIdType>,
+        request: &ScrollRequest,
+    ) -> CollectionResult<ScrollResult> {
+        let default_request = ScrollRequest::default();
+        let limit = request
+            .limit
+            .unwrap_or_else(|| default_request.limit.unwrap());
+        let with_payload_interface = request
+            .with_payload
+            .clone()
+            .unwrap_or_else(|| default_request.with_payload.clone().unwrap());
+
+        if limit == 0 {
+            return Err(CollectionError::BadRequest {
+                description: "Limit cannot be 0".to_string(),
+            });
+        }
+
+        let retrieved_points: Vec<_> = {
+            let shards_holder = self.shards_holder.read().await;
+            let scroll_futures = snapshot
+                .shard_points
+                .iter()
+                .map(|(shard_id, point_ids)| {
+                    let shard = shards_holder.get_shard(shard_id).ok_or_else(|| {
+                        CollectionError::bad_request(format!(
+                            "Shard {shard_id} of the stable scroll is not found"
+                        ))
+                    })?;
+                    // Needed to return next page cursor.
+                    let scroll = shard.scroll_local_pinned(
+                        point_ids,
+                        from,
+                        limit + 1,
+                        &with_payload_interface,
+                        &request.with_vector,
+                    );
+                    Ok(trace_shard(shard.shard_id, scroll))
+                })
+                .collect::<CollectionResult<Vec<_>>>()?;
+
+            future::try_join_all(scroll_futures).await?
+        };
+
+        let mut points: Vec<_> = retrieved_points
+            .into_iter()
+            .flatten()
+            .sorted_by_key(|point| point.id)
+            .take(limit + 1)
+            .collect();
+
+        let next_page_cursor = if points.len() <= limit {
+            // This was the last page
+            None
+        } else {
+            // remove extra point, it would be a first point of the next page
+            points.pop();
+            points
+                .last()
+                .map(|point| ScrollCursors::cursor(id, point.id))
+        };
+        Ok(ScrollResult {
+            points,
+            next_page_offset: None,
+            next_page_cursor,
+        })
+    }
+
+    async fn scroll_by_offset(
+        &self,
+        request: ScrollRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
     ) -> CollectionResult<ScrollResult> {
         let default_request = ScrollRequest::default();
 
@@ -126,13 +290,21 @@ impl Collection {
             });
         }
 
+        let order_by = request.order_by.as_ref();
+        if order_by.is_some() && offset.is_some() {
+            return Err(CollectionError::BadRequest {
+                description: "Offset can't be used with order_by, use start_from instead"
+                    .to_string(),
+            });
+        }
+
         // Needed to return next page offset.
-        let limit = limit + 1;
+        let limit = if order_by.is_some() { limit } else { limit + 1 };
         let retrieved_points: Vec<_> = {
             let shards_holder = self.shards_holder.read().await;
             let target_shards = shards_holder.target_shard(shard_selection)?;
             let scroll_futures = target_shards.into_iter().map(|shard| {
-                shard.scroll_by(
+                let scroll = shard.scroll_by(
                     offset,
                     limit,
                     &with_payload_interface,
@@ -140,11 +312,34 @@ impl Collection {
                     request.filter.as_ref(),
                     read_consistency,
                     shard_selection.is_some(),
-                )
+                    order_by,
+                );
+                trace_shard(shard.shard_id, scroll)
             });
 
             future::try_join_all(scroll_futures).await?
         };
+
+        if let Some(order_by) = order_by {
+            // Next page is requested with `start_from`, so there is no next page offset
+            let direction = order_by.direction();
+            let points = retrieved_points
+                .into_iter()
+                .flatten()
+                .sorted_by(|a, b| {
+                    let value_a = a.order_value.unwrap_or_default();
+                    let value_b = b.order_value.unwrap_or_default();
+                    direction.compare(value_a, value_b).then(a.id.cmp(&b.id))
+                })
+                .take(limit)
+                .collect();
+            return Ok(ScrollResult {
+                points,
+                next_page_offset: None,
+                next_page_cursor: None,
+            });
+        }
+
         let mut points: Vec<_> = retrieved_points
             .into_iter()
             .flatten()
@@ -162,6 +357,7 @@ impl Collection {
         Ok(ScrollResult {
             points,
             next_page_offset,
+            next_page_cursor: None,
         })
     }
 
@@ -177,7 +373,12 @@ impl Collection {
         let mut requests: futures::stream::FuturesUnordered<_> = shards
             .into_iter()
             // `count` requests received through internal gRPC *always* have `shard_selection`
-            .map(|shard| shard.count(request.clone(), shard_selection.is_some()))
+            .map(|shard| {
+                trace_shard(
+                    shard.shard_id,
+                    shard.count(request.clone(), shard_selection.is_some()),
+                )
+            })
             .collect();
 
         let mut count = 0;
@@ -189,6 +390,21 @@ impl Collection {
         Ok(CountResult { count })
     }
 
+    /// Count points for each request of the batch, requests are performed concurrently
+    pub async fn count_batch(
+        &self,
+        request: CountRequestBatch,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<CountResult>> {
+        future::try_join_all(
+            request
+                .counts
+                .into_iter()
+                .map(|request| self.count(request, shard_selection)),
+        )
+        .await
+    }
+
     pub async fn retrieve(
         &self,
         request: PointRequest,
@@ -205,17 +421,77 @@ impl Collection {
             let shard_holder = self.shards_holder.read().await;
             let target_shards = shard_holder.target_shard(shard_selection)?;
             let retrieve_futures = target_shards.into_iter().map(|shard| {
-                shard.retrieve(
+                let retrieve = shard.retrieve(
                     request.clone(),
                     &with_payload,
                     &request.with_vector,
                     read_consistency,
                     shard_selection.is_some(),
-                )
+                );
+                trace_shard(shard.shard_id, retrieve)
             });
             future::try_join_all(retrieve_futures).await?
         };
         let points = all_shard_collection_results.into_iter().flatten().collect();
         Ok(points)
     }
+
+    pub fn delete_tasks(&self) -> &DeleteTasks {
+        &self.delete_tasks
+    }
+
+    pub fn import_tasks(&self) -> &ImportTasks {
+        &self.import_tasks
+    }
+
+    pub async fn shard_ids(&self) -> Vec<ShardId> {
+        let shards_holder = self.shards_holder.read().await;
+        shards_holder
+            .get_shards()
+            .map(|(shard_id, _)| *shard_id)
+            .collect()
+    }
+
+    /// Delete up to `limit` points of the shard, which match the filter.
+    ///
+    /// Returns the number of deleted points, zero means there are no matching points left.
+    pub async fn delete_points_by_filter_batch(
+        &self,
+        shard_id: ShardId,
+        filter: &Filter,
+        limit: usize,
+        ordering: WriteOrdering,
+    ) -> CollectionResult<usize> {
+        let _update_lock = self.updates_lock.read().await;
+        let shards_holder = self.shards_holder.read().await;
+        let replica_set = shards_holder
+            .get_shard(&shard_id)
+            .ok_or_else(|| CollectionError::service_error(format!("Shard {shard_id} not found")))?;
+
+        let ids: Vec<_> = replica_set
+            .scroll_by(
+                None,
+                limit,
+                &WithPayloadInterface::Bool(false),
+                &WithVector::Bool(false),
+                Some(filter),
+                None,
+                false,
+                None,
+            )
+            .await?
+            .into_iter()
+            .map(|point| point.id)
+            .collect();
+
+        let deleted = ids.len();
+        if deleted > 0 {
+            let operation =
+                CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints { ids });
+            replica_set
+                .update_with_consistency(operation, true, ordering)
+                .await?;
+        }
+        Ok(deleted)
+    }
 }
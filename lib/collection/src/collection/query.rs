@@ -0,0 +1,971 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+
+use common::types::ScoreType;
+use futures::future::{self, BoxFuture};
+use futures::FutureExt;
+use segment::data_types::vectors::{
+    NamedContextQuery, NamedDiscoveryQuery, NamedRecoQuery, NamedVectorStruct, VectorElementType,
+    VectorType, DEFAULT_VECTOR_NAME,
+};
+use segment::spaces::tools;
+use segment::types::{
+    Condition, Filter, Payload, PayloadContainer, PointIdType, PrefetchScore,
+    QuantizationSearchParams, ScoreExplanation, ScoredPoint, SearchParams, WithPayloadInterface,
+    WithVector,
+};
+use segment::vector_storage::query::context_query::{ContextPair, ContextQuery};
+use segment::vector_storage::query::discovery_query::DiscoveryQuery;
+use segment::vector_storage::query::reco_query::RecoQuery;
+use serde_json::Value;
+use tokio::sync::RwLockReadGuard;
+
+use super::search::{relative_score_fusion, relative_scores, rrf_fusion, rrf_score};
+use super::Collection;
+use crate::operations::consistency_params::ReadConsistency;
+use crate::operations::formula::{FormulaPoint, FormulaQuery};
+use crate::operations::types::{
+    CollectionError, CollectionResult, ContextExamplePair, CoreSearchRequest,
+    CoreSearchRequestBatch, Diversity, Fusion, PluginQuery, PointRequest, Prefetch, Query,
+    QueryEnum, QueryRequest, RecommendExample,
+};
+use crate::shards::shard::ShardId;
+
+impl Collection {
+    /// Resolve prefetches of the request recursively, then fuse or rescore their results
+    pub async fn query(
+        &self,
+        request: QueryRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        if request.limit == 0 {
+            return Ok(vec![]);
+        }
+        let QueryRequest {
+            prefetch,
+            query,
+            using,
+            lookup_from,
+            filter,
+            params,
+            score_threshold,
+            limit,
+            offset,
+            diversity,
+            with_payload,
+            with_vector,
+            with_explanation,
+        } = request;
+
+        // Top level is resolved as any other stage, including the points skipped by `offset`
+        let stage = Prefetch {
+            prefetch,
+            query,
+            using: using.clone(),
+            lookup_from,
+            filter,
+            params,
+            score_threshold,
+            limit: diversity.map_or(limit + offset, |diversity| {
+                diversity.candidates_limit(limit + offset)
+            }),
+        };
+        let mut result = self
+            .query_stage(
+                stage,
+                None,
+                with_explanation,
+                read_consistency,
+                shard_selection,
+            )
+            .await?;
+        if let Some(diversity) = diversity {
+            result = self
+                .diversify(
+                    result,
+                    diversity,
+                    using,
+                    limit + offset,
+                    read_consistency,
+                    shard_selection,
+                )
+                .await?;
+        }
+        result.drain(..offset.min(result.len()));
+
+        self.fill_search_result_with_payload(
+            result,
+            Some(with_payload.unwrap_or(WithPayloadInterface::Bool(false))),
+            with_vector.unwrap_or_default(),
+            read_consistency,
+            shard_selection,
+        )
+        .await
+    }
+
+    /// Stages are nested, so the future of a stage has to be boxed
+    fn query_stage(
+        &self,
+        stage: Prefetch,
+        parent_filter: Option<Filter>,
+        explain: bool,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> BoxFuture<'_, CollectionResult<Vec<ScoredPoint>>> {
+        async move {
+            let Prefetch {
+                prefetch,
+                query,
+                using,
+                lookup_from,
+                filter,
+                params,
+                score_threshold,
+                limit,
+            } = stage;
+
+            if lookup_from.is_some() {
+                return Err(CollectionError::service_error(
+                    "Examples from other collections must be resolved before the query".to_string(),
+                ));
+            }
+
+            let filter = match (parent_filter, filter) {
+                (Some(parent_filter), Some(filter)) => Some(parent_filter.merge(&filter)),
+                (parent_filter, filter) => filter.or(parent_filter),
+            };
+
+            if prefetch.is_empty() {
+                let Some(query) = query else {
+                    return Err(CollectionError::bad_request(
+                        "Either query or prefetch must be specified".to_string(),
+                    ));
+                };
+                return self
+                    .query_search(
+                        query,
+                        using,
+                        filter,
+                        params,
+                        score_threshold,
+                        limit,
+                        explain,
+                        read_consistency,
+                        shard_selection,
+                    )
+                    .await;
+            }
+
+            let mut prefetch_results = future::try_join_all(prefetch.into_iter().map(|prefetch| {
+                self.query_stage(
+                    prefetch,
+                    filter.clone(),
+                    explain,
+                    read_consistency,
+                    shard_selection,
+                )
+            }))
+            .await?;
+
+            let mut result = match query {
+                None if prefetch_results.len() == 1 => {
+                    let mut result = prefetch_results.pop().unwrap_or_default();
+                    result.truncate(limit);
+                    return Ok(result);
+                }
+                None => {
+                    return Err(CollectionError::bad_request(
+                        "Query is required to merge the results of multiple prefetches".to_string(),
+                    ))
+                }
+                Some(Query::Fusion(fusion)) => {
+                    let explained_prefetches = explain.then(|| prefetch_results.clone());
+                    let mut result = match fusion {
+                        Fusion::Rrf => rrf_fusion(prefetch_results, limit),
+                        Fusion::RelativeScore => relative_score_fusion(prefetch_results, limit),
+                    };
+                    if let Some(prefetch_results) = explained_prefetches {
+                        explain_fusion(&mut result, &prefetch_results, fusion);
+                    }
+                    result
+                }
+                Some(Query::Formula(formula)) => {
+                    self.formula_rescore(
+                        formula,
+                        prefetch_results,
+                        limit,
+                        explain,
+                        read_consistency,
+                        shard_selection,
+                    )
+                    .await?
+                }
+                Some(Query::Plugin(plugin)) => {
+                    self.plugin_rescore(
+                        plugin,
+                        prefetch_results,
+                        limit,
+                        explain,
+                        read_consistency,
+                        shard_selection,
+                    )
+                    .await?
+                }
+                Some(query) => {
+                    // Rescore the candidates of all prefetches with the query of this stage
+                    let candidates: HashSet<PointIdType> = prefetch_results
+                        .into_iter()
+                        .flatten()
+                        .map(|point| point.id)
+                        .collect();
+                    if candidates.is_empty() {
+                        return Ok(vec![]);
+                    }
+                    let candidates_filter = Filter::new_must(Condition::HasId(candidates.into()));
+                    let filter = match filter {
+                        Some(filter) => candidates_filter.merge(&filter),
+                        None => candidates_filter,
+                    };
+                    // All candidates are scored with full precision vectors, so a cheap prefetch,
+                    // e.g. by a quantized or a smaller vector, is refined by the query
+                    let params = SearchParams {
+                        exact: true,
+                        ..params.unwrap_or_default()
+                    };
+                    return self
+                        .query_search(
+                            query,
+                            using,
+                            Some(filter),
+                            Some(params),
+                            score_threshold,
+                            limit,
+                            explain,
+                            read_consistency,
+                            shard_selection,
+                        )
+                        .await;
+                }
+            };
+
+            // Fused, formula and plugin scores are always larger for better points
+            if let Some(score_threshold) = score_threshold {
+                result.retain(|point| point.score >= score_threshold);
+            }
+            Ok(result)
+        }
+        .boxed()
+    }
+
+    /// Select `limit` points out of the candidates by Maximal Marginal Relevance
+    async fn diversify(
+        &self,
+        candidates: Vec<ScoredPoint>,
+        diversity: Diversity,
+        using: Option<String>,
+        limit: usize,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        let vector_name = using.unwrap_or_else(|| DEFAULT_VECTOR_NAME.to_string());
+        let vector_params = self
+            .collection_config
+            .read()
+            .await
+            .params
+            .get_vector_params(&vector_name)?;
+        if vector_params.multivector_config.is_some() {
+            return Err(CollectionError::bad_request(format!(
+                "Diversity is not supported for multivector {vector_name}"
+            )));
+        }
+        if candidates.len() <= 1 {
+            return Ok(candidates);
+        }
+
+        let mut vectors: HashMap<_, _> = self
+            .retrieve(
+                PointRequest {
+                    ids: candidates.iter().map(|point| point.id).collect(),
+                    with_payload: Some(WithPayloadInterface::Bool(false)),
+                    with_vector: WithVector::Selector(vec![vector_name.clone()]),
+                },
+                read_consistency,
+                shard_selection,
+            )
+            .await?
+            .into_iter()
+            .filter_map(|record| {
+                let vector = record.get_vector_by_name(&vector_name)?;
+                Some((record.id, normalized(vector)))
+            })
+            .collect();
+        let vectors = candidates
+            .iter()
+            .map(|point| vectors.remove(&point.id).flatten())
+            .collect();
+
+        Ok(mmr_select(candidates, vectors, diversity.lambda, limit))
+    }
+
+    /// Score the candidates of all prefetches by the formula.
+    /// `$score[i]` variables of the formula are the scores of a candidate in the i-th prefetch.
+    async fn formula_rescore(
+        &self,
+        formula: FormulaQuery,
+        prefetch_results: Vec<Vec<ScoredPoint>>,
+        limit: usize,
+        explain: bool,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        let explained_prefetches = explain.then(|| prefetch_results.clone());
+        let prefetches_count = prefetch_results.len();
+        let mut candidates: HashMap<PointIdType, (ScoredPoint, Vec<Option<ScoreType>>)> =
+            HashMap::new();
+        for (prefetch_index, points) in prefetch_results.into_iter().enumerate() {
+            for point in points {
+                let score = point.score;
+                let (_, scores) = candidates
+                    .entry(point.id)
+                    .or_insert_with(|| (point, vec![None; prefetches_count]));
+                scores[prefetch_index] = Some(score);
+            }
+        }
+        if candidates.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let vector_names = formula.vector_names();
+        let with_vector = if vector_names.is_empty() {
+            WithVector::Bool(false)
+        } else {
+            WithVector::Selector(vector_names)
+        };
+        // Candidates, which are deleted after the prefetch, are not returned
+        let records = self
+            .retrieve(
+                PointRequest {
+                    ids: candidates.keys().copied().collect(),
+                    with_payload: Some(WithPayloadInterface::Bool(true)),
+                    with_vector,
+                },
+                read_consistency,
+                shard_selection,
+            )
+            .await?;
+
+        let mut scored = Vec::with_capacity(records.len());
+        for record in records {
+            let Some((point, scores)) = candidates.remove(&record.id) else {
+                continue;
+            };
+            let formula_point = FormulaPoint {
+                scores: &scores,
+                record: &record,
+            };
+            let score = formula.evaluate(&formula_point)?;
+            let explanation = match &explained_prefetches {
+                Some(prefetch_results) => Some(Box::new(ScoreExplanation {
+                    prefetches: prefetch_scores(prefetch_results, point.id, None),
+                    formula_terms: formula.terms(&formula_point)?,
+                    ..Default::default()
+                })),
+                None => point.explanation,
+            };
+            scored.push(ScoredPoint {
+                score,
+                explanation,
+                ..point
+            });
+        }
+        Ok(tools::peek_top_largest_iterable(scored, limit))
+    }
+
+    /// Score the candidates of all prefetches by the scoring plugin of the collection.
+    /// The plugin gets the scores of a candidate in each prefetch, followed by its payload features.
+    async fn plugin_rescore(
+        &self,
+        query: PluginQuery,
+        prefetch_results: Vec<Vec<ScoredPoint>>,
+        limit: usize,
+        explain: bool,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        let config = self
+            .collection_config
+            .read()
+            .await
+            .params
+            .scoring_plugin
+            .clone()
+            .filter(|config| config.name == query.name)
+            .ok_or_else(|| {
+                CollectionError::bad_request(format!(
+                    "Scoring plugin {} is not configured for the collection",
+                    query.name,
+                ))
+            })?;
+        if let Some(version) = query.version.filter(|version| *version != config.version) {
+            return Err(CollectionError::bad_request(format!(
+                "Scoring plugin {} has version {}, but version {version} is requested",
+                config.name, config.version,
+            )));
+        }
+        let plugin = self.scoring_plugin.get(&config)?;
+
+        let explained_prefetches = explain.then(|| prefetch_results.clone());
+        let prefetches_count = prefetch_results.len();
+        let mut candidates: HashMap<PointIdType, (ScoredPoint, Vec<Option<ScoreType>>)> =
+            HashMap::new();
+        for (prefetch_index, points) in prefetch_results.into_iter().enumerate() {
+            for point in points {
+                let score = point.score;
+                let (_, scores) = candidates
+                    .entry(point.id)
+                    .or_insert_with(|| (point, vec![None; prefetches_count]));
+                scores[prefetch_index] = Some(score);
+            }
+        }
+        if candidates.is_empty() {
+            return Ok(vec![]);
+        }
+
+        // Candidates, which are deleted after the prefetch, are not returned
+        let records = self
+            .retrieve(
+                PointRequest {
+                    ids: candidates.keys().copied().collect(),
+                    with_payload: Some(WithPayloadInterface::Fields(config.features.clone())),
+                    with_vector: WithVector::Bool(false),
+                },
+                read_consistency,
+                shard_selection,
+            )
+            .await?;
+
+        let mut instance = plugin.instantiate(records.len())?;
+        let mut scored = Vec::with_capacity(records.len());
+        for record in records {
+            let Some((point, scores)) = candidates.remove(&record.id) else {
+                continue;
+            };
+            let features: Vec<_> = config
+                .features
+                .iter()
+                .map(|key| payload_feature(record.payload.as_ref(), key))
+                .collect();
+            let score = instance.score(&scores, &features)?;
+            let explanation = match &explained_prefetches {
+                Some(prefetch_results) => Some(Box::new(ScoreExplanation {
+                    prefetches: prefetch_scores(prefetch_results, point.id, None),
+                    ..Default::default()
+                })),
+                None => point.explanation,
+            };
+            scored.push(ScoredPoint {
+                score,
+                explanation,
+                ..point
+            });
+        }
+        Ok(tools::peek_top_largest_iterable(scored, limit))
+    }
+
+    /// Search by the query of a single stage, candidates of the stage are selected by the filter
+    #[allow(clippy::too_many_arguments)]
+    async fn query_search(
+        &self,
+        query: Query,
+        using: Option<String>,
+        filter: Option<Filter>,
+        params: Option<SearchParams>,
+        score_threshold: Option<ScoreType>,
+        limit: usize,
+        explain: bool,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<ScoredPoint>> {
+        let example_ids = query.example_point_ids();
+        let query = match query {
+            Query::Nearest(vector) => {
+                QueryEnum::Nearest(NamedVectorStruct::new_from_vector(vector, using))
+            }
+            Query::Text(text) => QueryEnum::Text(text),
+            Query::Sample(sample) => QueryEnum::Sample(sample),
+            Query::Fusion(_) => {
+                return Err(CollectionError::bad_request(
+                    "Fusion requires prefetches to fuse".to_string(),
+                ))
+            }
+            Query::Formula(_) => {
+                return Err(CollectionError::bad_request(
+                    "Formula requires prefetches to rescore".to_string(),
+                ))
+            }
+            Query::Plugin(_) => {
+                return Err(CollectionError::bad_request(
+                    "Scoring plugin requires prefetches to rescore".to_string(),
+                ))
+            }
+            query => {
+                self.query_by_examples(query, &example_ids, using, read_consistency)
+                    .await?
+            }
+        };
+
+        // Examples are not returned as results
+        let filter = if example_ids.is_empty() {
+            filter
+        } else {
+            let examples_filter = Filter::new_must_not(Condition::HasId(example_ids.into()));
+            Some(match filter {
+                Some(filter) => examples_filter.merge(&filter),
+                None => examples_filter,
+            })
+        };
+
+        let request = CoreSearchRequest {
+            query,
+            filter,
+            params,
+            limit,
+            offset: 0,
+            with_payload: Some(WithPayloadInterface::Bool(false)),
+            with_vector: None,
+            score_threshold,
+        };
+        let explained_request = explain.then(|| request.clone());
+        let results = self
+            .core_search_batch(
+                CoreSearchRequestBatch {
+                    searches: vec![request],
+                },
+                read_consistency,
+                shard_selection,
+            )
+            .await?;
+        let mut result = results.into_iter().next().unwrap_or_default();
+        if let Some(request) = explained_request {
+            self.explain_similarity(&mut result, request, read_consistency, shard_selection)
+                .await?;
+        }
+        Ok(result)
+    }
+
+    /// Explain the scores of the points found by a vector query:
+    /// score them with the original vectors and, if the vectors are quantized, with the quantized ones
+    async fn explain_similarity(
+        &self,
+        points: &mut [ScoredPoint],
+        request: CoreSearchRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<()> {
+        let Some(vector_name) = request.query.get_vector_name() else {
+            return Ok(());
+        };
+        if points.is_empty() {
+            return Ok(());
+        }
+        let is_quantized = {
+            let collection_config = self.collection_config.read().await;
+            collection_config.quantization_config.is_some()
+                || collection_config
+                    .params
+                    .get_vector_params(vector_name)?
+                    .quantization_config
+                    .is_some()
+        };
+
+        let ids: HashSet<PointIdType> = points.iter().map(|point| point.id).collect();
+        let points_filter = Filter::new_must(Condition::HasId(ids.into()));
+        let limit = points.len();
+        let rescore_request = |params: SearchParams| CoreSearchRequest {
+            filter: Some(points_filter.clone()),
+            params: Some(params),
+            limit,
+            score_threshold: None,
+            ..request.clone()
+        };
+        let mut searches = vec![rescore_request(SearchParams {
+            exact: true,
+            ..Default::default()
+        })];
+        if is_quantized {
+            // Without rescoring, the scores of the quantized vectors are returned as is
+            searches.push(rescore_request(SearchParams {
+                quantization: Some(QuantizationSearchParams {
+                    ignore: false,
+                    rescore: Some(false),
+                    oversampling: None,
+                }),
+                ..Default::default()
+            }));
+        }
+
+        let mut results = self
+            .core_search_batch(
+                CoreSearchRequestBatch { searches },
+                read_consistency,
+                shard_selection,
+            )
+            .await?
+            .into_iter()
+            .map(|result| -> HashMap<_, _> {
+                result
+                    .into_iter()
+                    .map(|point| (point.id, point.score))
+                    .collect()
+            });
+        let similarities = results.next().unwrap_or_default();
+        let quantized_similarities = results.next().unwrap_or_default();
+
+        for point in points {
+            point.explanation = Some(Box::new(ScoreExplanation {
+                similarity: similarities.get(&point.id).copied(),
+                quantized_similarity: quantized_similarities.get(&point.id).copied(),
+                ..Default::default()
+            }));
+        }
+        Ok(())
+    }
+
+    /// Build the query defined by examples, the vectors of example points are retrieved first
+    async fn query_by_examples(
+        &self,
+        query: Query,
+        example_ids: &HashSet<PointIdType>,
+        using: Option<String>,
+        read_consistency: Option<ReadConsistency>,
+    ) -> CollectionResult<QueryEnum> {
+        let vector_name = using.as_deref().unwrap_or(DEFAULT_VECTOR_NAME);
+
+        let records: HashMap<_, _> = if example_ids.is_empty() {
+            HashMap::new()
+        } else {
+            self.retrieve(
+                PointRequest {
+                    ids: example_ids.iter().copied().collect(),
+                    with_payload: Some(WithPayloadInterface::Bool(false)),
+                    with_vector: WithVector::Selector(vec![vector_name.to_string()]),
+                },
+                read_consistency,
+                None,
+            )
+            .await?
+            .into_iter()
+            .map(|record| (record.id, record))
+            .collect()
+        };
+
+        let to_vector = |example: RecommendExample| match example {
+            RecommendExample::Vector(vector) => Ok(vector),
+            RecommendExample::PointId(point_id) => records
+                .get(&point_id)
+                .and_then(|record| record.get_vector_by_name(vector_name))
+                .cloned()
+                .ok_or(CollectionError::PointNotFound {
+                    missed_point_id: point_id,
+                }),
+        };
+        let to_pairs = |pairs: Vec<ContextExamplePair>| {
+            pairs
+                .into_iter()
+                .map(|pair| {
+                    Ok(ContextPair {
+                        positive: to_vector(pair.positive)?,
+                        negative: to_vector(pair.negative)?,
+                    })
+                })
+                .collect::<CollectionResult<Vec<_>>>()
+        };
+
+        let query = match query {
+            Query::Recommend(input) => {
+                if input.positive.is_empty() && input.negative.is_empty() {
+                    return Err(CollectionError::bad_request(
+                        "At least one positive or negative example required".to_string(),
+                    ));
+                }
+                let positives = input.positive.into_iter().map(to_vector);
+                let negatives = input.negative.into_iter().map(to_vector);
+                QueryEnum::RecommendBestScore(NamedRecoQuery {
+                    query: RecoQuery::new(
+                        positives.collect::<CollectionResult<_>>()?,
+                        negatives.collect::<CollectionResult<_>>()?,
+                    ),
+                    using,
+                })
+            }
+            Query::Discover(input) => QueryEnum::Discover(NamedDiscoveryQuery {
+                query: DiscoveryQuery::new(to_vector(input.target)?, to_pairs(input.context)?),
+                using,
+            }),
+            Query::Context(pairs) => {
+                if pairs.is_empty() {
+                    return Err(CollectionError::bad_request(
+                        "At least one context pair required".to_string(),
+                    ));
+                }
+                QueryEnum::Context(NamedContextQuery {
+                    query: ContextQuery::new(to_pairs(pairs)?),
+                    using,
+                })
+            }
+            Query::Nearest(_)
+            | Query::Text(_)
+            | Query::Fusion(_)
+            | Query::Sample(_)
+            | Query::Formula(_)
+            | Query::Plugin(_) => {
+                return Err(CollectionError::service_error(
+                    "Query is not defined by examples".to_string(),
+                ))
+            }
+        };
+        Ok(query)
+    }
+}
+
+/// Numeric value of the payload key, `NaN` if the value is missing or not a number
+fn payload_feature(payload: Option<&Payload>, key: &str) -> f32 {
+    payload
+        .and_then(|payload| {
+            payload
+                .get_value(key)
+                .values()
+                .into_iter()
+                .find_map(Value::as_f64)
+        })
+        .map_or(f32::NAN, |value| value as f32)
+}
+
+/// Scores of the point in each of the prefetches, with the contributions to the fused score
+fn prefetch_scores(
+    prefetch_results: &[Vec<ScoredPoint>],
+    id: PointIdType,
+    contributions: Option<&[Vec<ScoreType>]>,
+) -> Vec<Option<PrefetchScore>> {
+    prefetch_results
+        .iter()
+        .enumerate()
+        .map(|(prefetch_index, ranking)| {
+            let rank = ranking.iter().position(|point| point.id == id)?;
+            let point = &ranking[rank];
+            Some(PrefetchScore {
+                rank,
+                score: point.score,
+                contribution: contributions
+                    .and_then(|contributions| contributions[prefetch_index].get(rank).copied()),
+                explanation: point.explanation.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Explain the fused scores by the ranks and the scores of the points in the prefetches
+fn explain_fusion(
+    result: &mut [ScoredPoint],
+    prefetch_results: &[Vec<ScoredPoint>],
+    fusion: Fusion,
+) {
+    let contributions: Vec<Vec<ScoreType>> = prefetch_results
+        .iter()
+        .map(|ranking| match fusion {
+            Fusion::Rrf => (0..ranking.len()).map(rrf_score).collect(),
+            Fusion::RelativeScore => relative_scores(ranking),
+        })
+        .collect();
+    for point in result {
+        point.explanation = Some(Box::new(ScoreExplanation {
+            prefetches: prefetch_scores(prefetch_results, point.id, Some(&contributions)),
+            ..Default::default()
+        }));
+    }
+}
+
+/// Vector of unit length, so the dot product of such vectors is their cosine similarity
+fn normalized(vector: &[VectorElementType]) -> Option<VectorType> {
+    let norm = vector
+        .iter()
+        .map(|x| x * x)
+        .sum::<VectorElementType>()
+        .sqrt();
+    (norm > 0.0).then(|| vector.iter().map(|x| x / norm).collect())
+}
+
+/// Select points one by one by Maximal Marginal Relevance.
+///
+/// Candidates must be ordered from the best to the worst, their relevance is the normalized score.
+/// Candidates without a vector are never penalized by similarity.
+fn mmr_select(
+    candidates: Vec<ScoredPoint>,
+    vectors: Vec<Option<VectorType>>,
+    lambda: ScoreType,
+    limit: usize,
+) -> Vec<ScoredPoint> {
+    let (Some(best), Some(worst)) = (candidates.first(), candidates.last()) else {
+        return candidates;
+    };
+    let (best, range) = (best.score, best.score - worst.score);
+    let relevance: Vec<ScoreType> = candidates
+        .iter()
+        .map(|point| {
+            if range == 0.0 {
+                1.0
+            } else {
+                1.0 - (best - point.score) / range
+            }
+        })
+        .collect();
+
+    // Highest similarity of each candidate to the selected points
+    let mut similarity: Vec<Option<ScoreType>> = vec![None; candidates.len()];
+    let mut remaining: Vec<usize> = (0..candidates.len()).collect();
+    let mut selected: Vec<usize> = Vec::with_capacity(limit.min(candidates.len()));
+
+    while selected.len() < limit && !remaining.is_empty() {
+        let mmr =
+            |idx: usize| lambda * relevance[idx] - (1.0 - lambda) * similarity[idx].unwrap_or(0.0);
+        let (position, _) = remaining
+            .iter()
+            .enumerate()
+            .max_by(|(_, &a), (_, &b)| mmr(a).total_cmp(&mmr(b)).then(b.cmp(&a)))
+            .unwrap();
+        let chosen = remaining.swap_remove(position);
+        selected.push(chosen);
+
+        let Some(chosen_vector) = &vectors[chosen] else {
+            continue;
+        };
+        for &idx in &remaining {
+            if let Some(vector) = &vectors[idx] {
+                let dot: ScoreType = vector.iter().zip(chosen_vector).map(|(a, b)| a * b).sum();
+                similarity[idx] = Some(similarity[idx].map_or(dot, |max| max.max(dot)));
+            }
+        }
+    }
+
+    let mut candidates: Vec<Option<ScoredPoint>> = candidates.into_iter().map(Some).collect();
+    selected
+        .into_iter()
+        .filter_map(|idx| candidates[idx].take())
+        .collect()
+}
+
+/// Replace point ids of the examples by their vectors in all stages with `lookup_from`,
+/// so the examples of these stages are not looked up in the queried collection.
+pub async fn resolve_query_lookups<'a, F, Fut>(
+    mut request: QueryRequest,
+    collection_by_name: F,
+    read_consistency: Option<ReadConsistency>,
+) -> CollectionResult<QueryRequest>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
+{
+    let mut stages = vec![(request.lookup_from.take(), request.query.as_mut())];
+    let mut prefetches: Vec<&mut Prefetch> = request.prefetch.iter_mut().collect();
+    while let Some(Prefetch {
+        prefetch,
+        query,
+        lookup_from,
+        ..
+    }) = prefetches.pop()
+    {
+        stages.push((lookup_from.take(), query.as_mut()));
+        prefetches.extend(prefetch.iter_mut());
+    }
+
+    for (lookup_from, query) in stages {
+        let (Some(lookup_from), Some(query)) = (lookup_from, query) else {
+            continue;
+        };
+        let example_ids = query.example_point_ids();
+        if example_ids.is_empty() {
+            continue;
+        }
+
+        let collection = collection_by_name(lookup_from.collection.clone())
+            .await
+            .ok_or_else(|| CollectionError::NotFound {
+                what: format!("Collection {}", lookup_from.collection),
+            })?;
+        let vector_name = lookup_from.vector.as_deref().unwrap_or(DEFAULT_VECTOR_NAME);
+        let records = collection
+            .retrieve(
+                PointRequest {
+                    ids: example_ids.into_iter().collect(),
+                    with_payload: Some(WithPayloadInterface::Bool(false)),
+                    with_vector: WithVector::Selector(vec![vector_name.to_string()]),
+                },
+                read_consistency,
+                None,
+            )
+            .await?;
+        let vectors: HashMap<_, _> = records
+            .iter()
+            .filter_map(|record| Some((record.id, record.get_vector_by_name(vector_name)?)))
+            .collect();
+
+        for example in query.examples_mut() {
+            if let RecommendExample::PointId(point_id) = *example {
+                let vector = vectors
+                    .get(&point_id)
+                    .ok_or(CollectionError::PointNotFound {
+                        missed_point_id: point_id,
+                    })?;
+                *example = RecommendExample::Vector((*vector).clone());
+            }
+        }
+    }
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored(id: u64, score: ScoreType) -> ScoredPoint {
+        ScoredPoint {
+            id: id.into(),
+            version: 0,
+            score,
+            payload: None,
+            vector: None,
+            explanation: None,
+        }
+    }
+
+    #[test]
+    fn test_mmr_select() {
+        // Points 0 and 1 are near-duplicates, point 2 is less relevant, but different
+        let candidates = vec![
+            scored(0, 0.9),
+            scored(1, 0.89),
+            scored(2, 0.7),
+            scored(3, 0.5),
+        ];
+        let vectors = vec![
+            normalized(&[1.0, 0.0]),
+            normalized(&[1.0, 0.01]),
+            normalized(&[0.0, 1.0]),
+            None,
+        ];
+
+        let ids = |points: Vec<ScoredPoint>| points.into_iter().map(|p| p.id).collect::<Vec<_>>();
+
+        let relevant = mmr_select(candidates.clone(), vectors.clone(), 1.0, 3);
+        assert_eq!(ids(relevant), vec![0.into(), 1.into(), 2.into()]);
+
+        let diverse = mmr_select(candidates.clone(), vectors.clone(), 0.5, 3);
+        assert_eq!(ids(diverse), vec![0.into(), 2.into(), 3.into()]);
+
+        // Scores are not changed
+        let diverse = mmr_select(candidates, vectors, 0.5, 2);
+        assert_eq!(diverse[1].score, 0.7);
+    }
+}
@@ -1,15 +1,28 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
+use common::types::ScoreType;
 use futures::future;
+use segment::data_types::vectors::{
+    Named, NamedVectorStruct, Vector, VectorStruct, DEFAULT_VECTOR_NAME,
+};
 use segment::spaces::tools;
 use segment::types::{ExtendedPointId, Order, ScoredPoint, WithPayloadInterface, WithVector};
+use tokio::time::Instant;
 
 use super::Collection;
+use crate::common::query_explain::{explain_mode, explain_shard};
+use crate::common::query_trace::trace_shard;
+use crate::common::search_cache::{DataVersions, SearchCache};
+use crate::common::stopping_guard::StoppingGuard;
 use crate::operations::consistency_params::ReadConsistency;
 use crate::operations::types::*;
 use crate::shards::shard::ShardId;
 
+/// Constant of Reciprocal Rank Fusion, dampens the advantage of the top ranks
+const RRF_K: ScoreType = 60.0;
+
 impl Collection {
     pub async fn search(
         &self,
@@ -24,8 +37,11 @@ impl Collection {
         let request_batch = SearchRequestBatch {
             searches: vec![request],
         };
+        let request_batch = self
+            .resolve_point_vectors(request_batch, read_consistency, shard_selection)
+            .await?;
         let results = self
-            .do_search_batch(request_batch, read_consistency, shard_selection)
+            .do_cached_search_batch(request_batch, read_consistency, shard_selection)
             .await?;
         Ok(results.into_iter().next().unwrap())
     }
@@ -42,6 +58,9 @@ impl Collection {
         if request.searches.iter().all(|s| s.limit == 0) {
             return Ok(vec![]);
         }
+        let request = self
+            .resolve_point_vectors(request, read_consistency, shard_selection)
+            .await?;
         // A factor which determines if we need to use the 2-step search or not
         // Should be adjusted based on usage statistics.
         const PAYLOAD_TRANSFERS_FACTOR_THRESHOLD: usize = 10;
@@ -88,7 +107,7 @@ impl Collection {
                 searches: without_payload_requests,
             };
             let without_payload_results = self
-                .do_search_batch(without_payload_batch, read_consistency, shard_selection)
+                .do_cached_search_batch(without_payload_batch, read_consistency, shard_selection)
                 .await?;
             let filled_results = without_payload_results
                 .into_iter()
@@ -105,7 +124,7 @@ impl Collection {
             future::try_join_all(filled_results).await
         } else {
             let result = self
-                .do_search_batch(request, read_consistency, shard_selection)
+                .do_cached_search_batch(request, read_consistency, shard_selection)
                 .await?;
             Ok(result)
         }
@@ -192,6 +211,254 @@ impl Collection {
         }
     }
 
+    /// Search with a timeout for the responses of the shards.
+    ///
+    /// If `allow_partial` is set, shards which did not respond in time are left out
+    /// and the results are flagged as partial, instead of failing the whole request.
+    /// Local replicas are searched segment by segment, so only the segments which are not
+    /// searched in time are left out of them.
+    pub async fn search_batch_with_timeout(
+        &self,
+        request: SearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+        timeout: Duration,
+        allow_partial: bool,
+    ) -> CollectionResult<PartialSearchBatchResult> {
+        if request.searches.iter().all(|s| s.limit == 0) {
+            return Ok(PartialSearchBatchResult::default());
+        }
+        if request.searches.iter().any(|search| search.text.is_some()) {
+            return Err(CollectionError::bad_request(
+                "Timeout is not supported for searches with a text query".to_string(),
+            ));
+        }
+
+        let request = self
+            .resolve_point_vectors(request, read_consistency, shard_selection)
+            .await?;
+        let request: Arc<CoreSearchRequestBatch> = Arc::new(request.try_into()?);
+
+        // Stops the searches in the local segments, which are still running after the timeout
+        let is_stopped = StoppingGuard::new();
+        let deadline = Instant::now() + timeout;
+
+        let mut partial = false;
+        let all_searches_res = {
+            let shard_holder = self.shards_holder.read().await;
+            let target_shards = shard_holder.target_shard(shard_selection)?;
+            let all_searches = target_shards.iter().map(|shard| {
+                let request = request.clone();
+                let is_stopped = is_stopped.get_is_stopped();
+                let search = async move {
+                    if read_consistency.is_none() {
+                        if let Some(res) = shard
+                            .search_local_until(request.clone(), deadline, is_stopped)
+                            .await?
+                        {
+                            return Ok(res);
+                        }
+                    }
+                    let search =
+                        shard.core_search(request, read_consistency, shard_selection.is_some());
+                    match tokio::time::timeout_at(deadline, search).await {
+                        Ok(res) => res.map(|res| (res, false)),
+                        Err(_elapsed) => Err(CollectionError::timeout(timeout, "Search")),
+                    }
+                };
+                let search = explain_shard(shard.shard_id, self.this_peer_id, search);
+                trace_shard(shard.shard_id, search)
+            });
+
+            let mut all_searches_res = Vec::with_capacity(target_shards.len());
+            for shard_res in future::join_all(all_searches).await {
+                match shard_res {
+                    Ok((res, shard_partial)) => {
+                        partial |= shard_partial;
+                        all_searches_res.push(res);
+                    }
+                    // Shard hit its own search timeout, which is the same as not responding
+                    Err(CollectionError::Timeout { .. }) if allow_partial => {
+                        partial = true;
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+            all_searches_res
+        };
+
+        if partial {
+            is_stopped.stop();
+            if !allow_partial {
+                return Err(CollectionError::timeout(timeout, "Search"));
+            }
+            log::debug!("Search timeout reached: {timeout:?}, returning partial results");
+        }
+
+        // Segment searches, which are still running after the timeout, may hold clones of the request
+        let request = Arc::unwrap_or_clone(request);
+
+        let results = self
+            .merge_from_shards(all_searches_res, request, shard_selection)
+            .await?;
+        Ok(PartialSearchBatchResult { results, partial })
+    }
+
+    /// Replace the vectors given by point ids with the stored vectors of these points
+    pub(crate) async fn resolve_point_vectors(
+        &self,
+        mut request: SearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<SearchRequestBatch> {
+        let points: Vec<_> = request
+            .searches
+            .iter()
+            .filter_map(|search| match &search.vector {
+                SearchVector::Point(point) => Some(point),
+                SearchVector::Vector(_) => None,
+            })
+            .collect();
+        if points.is_empty() {
+            return Ok(request);
+        }
+
+        let ids: HashSet<_> = points.iter().map(|point| point.id).collect();
+        let vector_names: HashSet<_> = points
+            .iter()
+            .map(|point| {
+                point
+                    .vector_name
+                    .clone()
+                    .unwrap_or_else(|| DEFAULT_VECTOR_NAME.to_string())
+            })
+            .collect();
+        let records: HashMap<_, _> = self
+            .retrieve(
+                PointRequest {
+                    ids: ids.into_iter().collect(),
+                    with_payload: Some(WithPayloadInterface::Bool(false)),
+                    with_vector: WithVector::Selector(vector_names.into_iter().collect()),
+                },
+                read_consistency,
+                shard_selection,
+            )
+            .await?
+            .into_iter()
+            .map(|record| (record.id, record))
+            .collect();
+
+        for search in &mut request.searches {
+            let SearchVector::Point(point) = &search.vector else {
+                continue;
+            };
+            let record = records
+                .get(&point.id)
+                .ok_or(CollectionError::PointNotFound {
+                    missed_point_id: point.id,
+                })?;
+            let vector_name = search.vector.get_name();
+            let vector = match &record.vector {
+                Some(VectorStruct::Single(vector)) if vector_name == DEFAULT_VECTOR_NAME => {
+                    Some(Vector::from(vector.clone()))
+                }
+                Some(VectorStruct::Multi(vectors)) => vectors.get(vector_name).cloned(),
+                _ => None,
+            }
+            .ok_or_else(|| {
+                CollectionError::bad_request(format!(
+                    "Point {} has no vector {vector_name}",
+                    point.id
+                ))
+            })?;
+            search.vector =
+                NamedVectorStruct::new_from_vector(vector, point.vector_name.clone()).into();
+        }
+        Ok(request)
+    }
+
+    /// Capacity of the search cache, 0 if the cache is disabled
+    pub(crate) async fn search_cache_size(&self) -> usize {
+        self.collection_config
+            .read()
+            .await
+            .params
+            .search_cache_size
+            .unwrap_or_default()
+    }
+
+    /// Versions of the data of all shards, if all of them are served by active local replicas
+    async fn data_versions(&self) -> Option<DataVersions> {
+        let shard_holder = self.shards_holder.read().await;
+        let mut versions = Vec::new();
+        for (shard_id, replica_set) in shard_holder.get_shards() {
+            versions.push((*shard_id, replica_set.local_data_version().await?));
+        }
+        versions.sort_unstable();
+        Some(versions)
+    }
+
+    /// Serve searches from the search cache, if it is enabled, and search the missed ones
+    async fn do_cached_search_batch(
+        &self,
+        request: SearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let cache_size = self.search_cache_size().await;
+        // Versions of the local replicas don't tell about the data of the remote ones,
+        // so only client requests with the default read consistency are cached.
+        // Explained requests have to reach the segments, and planned ones return no points
+        let versions = if cache_size > 0
+            && read_consistency.is_none()
+            && shard_selection.is_none()
+            && explain_mode().is_none()
+        {
+            self.data_versions().await
+        } else {
+            None
+        };
+        let Some(versions) = versions else {
+            return self
+                .do_search_batch(request, read_consistency, shard_selection)
+                .await;
+        };
+
+        let keys: Vec<_> = request
+            .searches
+            .iter()
+            .map(|search| SearchCache::key(search, &versions))
+            .collect();
+        let mut results: Vec<_> = keys.iter().map(|key| self.search_cache.get(*key)).collect();
+
+        let missed_searches: Vec<_> = request
+            .searches
+            .into_iter()
+            .zip(&results)
+            .filter(|(_, result)| result.is_none())
+            .map(|(search, _)| search)
+            .collect();
+        if !missed_searches.is_empty() {
+            let missed_batch = SearchRequestBatch {
+                searches: missed_searches,
+            };
+            let mut missed_results = self
+                .do_search_batch(missed_batch, read_consistency, shard_selection)
+                .await?
+                .into_iter();
+            for (key, result) in keys.iter().zip(&mut results) {
+                if result.is_none() {
+                    let missed_result = missed_results.next().unwrap_or_default();
+                    self.search_cache
+                        .insert(*key, missed_result.clone(), cache_size);
+                    *result = Some(missed_result);
+                }
+            }
+        }
+
+        Ok(results.into_iter().map(Option::unwrap_or_default).collect())
+    }
+
     // ! COPY-PASTE: `do_core_search_batch` is a copy-paste of `do_search_batch` with different request type
     // ! please replicate any changes to both methods
     async fn do_search_batch(
@@ -200,6 +467,12 @@ impl Collection {
         read_consistency: Option<ReadConsistency>,
         shard_selection: Option<ShardId>,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        if request.searches.iter().any(|search| search.text.is_some()) {
+            return self
+                .do_hybrid_search_batch(request, read_consistency, shard_selection)
+                .await;
+        }
+
         let request = Arc::new(request);
 
         // query all shards concurrently
@@ -207,14 +480,17 @@ impl Collection {
             let shard_holder = self.shards_holder.read().await;
             let target_shards = shard_holder.target_shard(shard_selection)?;
             let all_searches = target_shards.iter().map(|shard| {
-                shard.search(request.clone(), read_consistency, shard_selection.is_some())
+                let search =
+                    shard.search(request.clone(), read_consistency, shard_selection.is_some());
+                let search = explain_shard(shard.shard_id, self.this_peer_id, search);
+                trace_shard(shard.shard_id, search)
             });
             future::try_join_all(all_searches).await?
         };
 
-        let request = Arc::into_inner(request)
+        let request: CoreSearchRequestBatch = Arc::into_inner(request)
             .expect("We have already dropped all of the Arc clones at this point")
-            .into();
+            .try_into()?;
 
         self.merge_from_shards(all_searches_res, request, shard_selection)
             .await
@@ -235,7 +511,10 @@ impl Collection {
             let shard_holder = self.shards_holder.read().await;
             let target_shards = shard_holder.target_shard(shard_selection)?;
             let all_searches = target_shards.iter().map(|shard| {
-                shard.core_search(request.clone(), read_consistency, shard_selection.is_some())
+                let search =
+                    shard.core_search(request.clone(), read_consistency, shard_selection.is_some());
+                let search = explain_shard(shard.shard_id, self.this_peer_id, search);
+                trace_shard(shard.shard_id, search)
             });
             future::try_join_all(all_searches).await?
         };
@@ -247,6 +526,63 @@ impl Collection {
             .await
     }
 
+    /// Search requests with a text query by vector and by text separately,
+    /// then fuse both rankings with Reciprocal Rank Fusion
+    async fn do_hybrid_search_batch(
+        &self,
+        request: SearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let mut core_searches = Vec::with_capacity(request.searches.len() * 2);
+        for search in &request.searches {
+            let mut vector_search = CoreSearchRequest::try_from(search.clone())?;
+            if let Some(text) = &search.text {
+                // Both rankings must be complete up to `offset` before fusion
+                vector_search.limit += vector_search.offset;
+                vector_search.offset = 0;
+                let text_search = CoreSearchRequest {
+                    query: QueryEnum::Text(text.clone()),
+                    score_threshold: None,
+                    ..vector_search.clone()
+                };
+                core_searches.push(vector_search);
+                core_searches.push(text_search);
+            } else {
+                core_searches.push(vector_search);
+            }
+        }
+
+        let mut results = self
+            .do_core_search_batch(
+                CoreSearchRequestBatch {
+                    searches: core_searches,
+                },
+                read_consistency,
+                shard_selection,
+            )
+            .await?
+            .into_iter();
+
+        let mut fused_results = Vec::with_capacity(request.searches.len());
+        for search in &request.searches {
+            let vector_result = results.next().unwrap_or_default();
+            if search.text.is_none() {
+                fused_results.push(vector_result);
+                continue;
+            }
+            let text_result = results.next().unwrap_or_default();
+            let mut fused = rrf_fusion([vector_result, text_result], search.limit + search.offset);
+            // Remove `offset` only for client requests, same as in `merge_from_shards`
+            if shard_selection.is_none() {
+                fused.drain(..search.offset.min(fused.len()));
+            }
+            fused_results.push(fused);
+        }
+
+        Ok(fused_results)
+    }
+
     pub(crate) async fn fill_search_result_with_payload(
         &self,
         search_result: Vec<ScoredPoint>,
@@ -317,10 +653,15 @@ impl Collection {
             .into_iter()
             .zip(request.searches.iter())
             .map(|(res, request)| {
-                let distance = collection_params
-                    .get_vector_params(request.query.get_vector_name())?
-                    .distance;
-                let mut top_res = match distance.distance_order() {
+                let order = match request.query.get_vector_name() {
+                    Some(vector_name) if request.query.is_distance_scored() => collection_params
+                        .get_distance(vector_name)?
+                        .distance_order(),
+                    // Text relevance, discovery, context and sample scores
+                    // are larger for better matches
+                    _ => Order::LargeBetter,
+                };
+                let mut top_res = match order {
                     Order::LargeBetter => {
                         tools::peek_top_largest_iterable(res, request.limit + request.offset)
                     }
@@ -345,3 +686,112 @@ impl Collection {
         Ok(top_results)
     }
 }
+
+/// Merge rankings by Reciprocal Rank Fusion: each point scores `1 / (k + rank)` in every ranking
+/// it appears in, ranks are 1-based.
+pub(super) fn rrf_fusion(
+    rankings: impl IntoIterator<Item = Vec<ScoredPoint>>,
+    limit: usize,
+) -> Vec<ScoredPoint> {
+    let mut fused: HashMap<ExtendedPointId, ScoredPoint> = HashMap::new();
+    for ranking in rankings {
+        for (rank, point) in ranking.into_iter().enumerate() {
+            let score = rrf_score(rank);
+            fused
+                .entry(point.id)
+                .and_modify(|fused_point| fused_point.score += score)
+                .or_insert(ScoredPoint { score, ..point });
+        }
+    }
+    tools::peek_top_largest_iterable(fused.into_values(), limit)
+}
+
+/// Part of the RRF score of a point, which comes from its `rank` in a ranking, starting from 0
+pub(super) fn rrf_score(rank: usize) -> ScoreType {
+    1.0 / (RRF_K + (rank + 1) as ScoreType)
+}
+
+/// Scores of a ranking, normalized so the best one is 1 and the worst one is 0
+pub(super) fn relative_scores(ranking: &[ScoredPoint]) -> Vec<ScoreType> {
+    let (Some(best), Some(worst)) = (ranking.first(), ranking.last()) else {
+        return vec![];
+    };
+    let (best, range) = (best.score, best.score - worst.score);
+    ranking
+        .iter()
+        .map(|point| {
+            if range == 0.0 {
+                1.0
+            } else {
+                1.0 - (best - point.score) / range
+            }
+        })
+        .collect()
+}
+
+/// Merge rankings by their normalized scores: the best score of a ranking becomes 1 and the worst
+/// becomes 0, normalized scores of a point are summed up over the rankings.
+///
+/// Rankings must be ordered from the best to the worst, so the order of the raw scores doesn't matter.
+pub(super) fn relative_score_fusion(
+    rankings: impl IntoIterator<Item = Vec<ScoredPoint>>,
+    limit: usize,
+) -> Vec<ScoredPoint> {
+    let mut fused: HashMap<ExtendedPointId, ScoredPoint> = HashMap::new();
+    for ranking in rankings {
+        let scores = relative_scores(&ranking);
+        for (point, score) in ranking.into_iter().zip(scores) {
+            fused
+                .entry(point.id)
+                .and_modify(|fused_point| fused_point.score += score)
+                .or_insert(ScoredPoint { score, ..point });
+        }
+    }
+    tools::peek_top_largest_iterable(fused.into_values(), limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scored(id: u64, score: ScoreType) -> ScoredPoint {
+        ScoredPoint {
+            id: id.into(),
+            version: 0,
+            score,
+            payload: None,
+            vector: None,
+            explanation: None,
+        }
+    }
+
+    #[test]
+    fn test_rrf_fusion() {
+        let vector_ranking = vec![scored(1, 0.9), scored(2, 0.8), scored(3, 0.7)];
+        let text_ranking = vec![scored(3, 12.0), scored(1, 5.0), scored(4, 1.0)];
+
+        let fused = rrf_fusion([vector_ranking, text_ranking], 3);
+        let ids: Vec<_> = fused.iter().map(|point| point.id).collect();
+
+        // Raw scores are ignored, only ranks matter
+        assert_eq!(ids, vec![1.into(), 3.into(), 2.into()]);
+        assert!((fused[0].score - (1.0 / 61.0 + 1.0 / 62.0)).abs() < 1e-6);
+        assert!((fused[2].score - 1.0 / 62.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_relative_score_fusion() {
+        let vector_ranking = vec![scored(1, 0.9), scored(2, 0.7), scored(3, 0.5)];
+        // Euclidean distances, smaller is better
+        let distance_ranking = vec![scored(3, 1.0), scored(1, 2.0), scored(4, 5.0)];
+
+        let fused = relative_score_fusion([vector_ranking, distance_ranking, vec![]], 4);
+        let ids: Vec<_> = fused.iter().map(|point| point.id).collect();
+
+        assert_eq!(ids, vec![1.into(), 3.into(), 2.into(), 4.into()]);
+        assert!((fused[0].score - 1.75).abs() < 1e-6);
+        assert!((fused[1].score - 1.0).abs() < 1e-6);
+        assert!((fused[2].score - 0.5).abs() < 1e-6);
+        assert!(fused[3].score.abs() < 1e-6);
+    }
+}
@@ -0,0 +1,158 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+
+use segment::types::SeqNumberType;
+use serde::{Deserialize, Serialize};
+
+use crate::collection_manager::holders::segment_holder::SegmentVersions;
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::shards::local_shard::LocalShard;
+use crate::shards::shard::ShardId;
+use crate::shards::shard_versioning;
+
+pub const SNAPSHOT_MANIFEST_FILE: &str = "snapshot_manifest.json";
+
+/// Segments of a collection snapshot.
+///
+/// Stored as the first entry of the archive, so it can be read without unpacking the snapshot.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct SnapshotManifest {
+    /// Snapshot, which contains segments not archived into this one
+    #[serde(default)]
+    pub base: Option<String>,
+    /// Versions of all segments of the local shards, including ones stored in the base snapshots
+    #[serde(default)]
+    pub shards: HashMap<ShardId, SegmentVersions>,
+    /// Number of the next operation after the WAL of the local shards,
+    /// the snapshot contains all operations before it
+    #[serde(default)]
+    pub wal_positions: HashMap<ShardId, SeqNumberType>,
+}
+
+/// Path of the archive entry without the leading `./`
+fn entry_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| *component != Component::CurDir)
+        .collect()
+}
+
+fn parse_manifest(
+    reader: impl std::io::Read,
+    snapshot_path: &Path,
+) -> CollectionResult<SnapshotManifest> {
+    serde_json::from_reader(reader).map_err(|err| {
+        CollectionError::service_error(format!(
+            "Can't read manifest of snapshot {}: {err}",
+            snapshot_path.display()
+        ))
+    })
+}
+
+impl SnapshotManifest {
+    /// Read manifest of the snapshot archive, `None` if the snapshot was created without one
+    ///
+    /// This method performs blocking IO.
+    pub fn read_from_archive(snapshot_path: &Path) -> CollectionResult<Option<Self>> {
+        let mut archive = tar::Archive::new(File::open(snapshot_path)?);
+        let Some(entry) = archive.entries()?.next() else {
+            return Ok(None);
+        };
+        let entry = entry?;
+        if entry_path(&entry.path()?) != Path::new(SNAPSHOT_MANIFEST_FILE) {
+            return Ok(None);
+        }
+        parse_manifest(entry, snapshot_path).map(Some)
+    }
+
+    /// Check, that the base snapshot is referenced by a file name, not by a path
+    pub fn check_base_name(base_name: &str) -> CollectionResult<()> {
+        if Path::new(base_name).file_name() != Some(base_name.as_ref()) {
+            return Err(CollectionError::bad_input(format!(
+                "Invalid base snapshot name {base_name}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Path of the base snapshot in the first of `base_dirs`, which contains it
+    pub fn find_base(base_name: &str, base_dirs: &[PathBuf]) -> Option<PathBuf> {
+        base_dirs
+            .iter()
+            .map(|dir| dir.join(base_name))
+            .find(|path| path.is_file())
+    }
+
+    /// Unpack segments, which are not archived into the snapshot, from its chain of base snapshots.
+    ///
+    /// Base snapshots are looked up in the directory of the snapshot, then in `base_dirs`.
+    /// Newer snapshots of the chain are checked first, so the latest archive of a segment is used.
+    ///
+    /// This method performs blocking IO.
+    pub fn restore_base_segments(
+        &self,
+        snapshot_path: &Path,
+        base_dirs: &[PathBuf],
+        target_dir: &Path,
+    ) -> CollectionResult<()> {
+        let mut missing: HashSet<PathBuf> = self
+            .shards
+            .iter()
+            .flat_map(|(shard_id, versions)| {
+                let segments_path = LocalShard::segments_path(
+                    &shard_versioning::versioned_shard_path(Path::new(""), *shard_id, 0),
+                );
+                versions
+                    .keys()
+                    .map(move |segment_name| segments_path.join(format!("{segment_name}.tar")))
+            })
+            .filter(|segment_archive| !target_dir.join(segment_archive).exists())
+            .collect();
+
+        let base_dirs: Vec<_> = snapshot_path
+            .parent()
+            .map(Path::to_path_buf)
+            .into_iter()
+            .chain(base_dirs.iter().cloned())
+            .collect();
+        let mut base = self.base.clone();
+
+        while !missing.is_empty() {
+            let Some(base_name) = base.take() else {
+                return Err(CollectionError::service_error(format!(
+                    "Segments {:?} of snapshot {} are not found in its base snapshots",
+                    missing,
+                    snapshot_path.display()
+                )));
+            };
+
+            Self::check_base_name(&base_name)?;
+            let Some(base_path) = Self::find_base(&base_name, &base_dirs) else {
+                return Err(CollectionError::bad_input(format!(
+                    "Base snapshot {base_name} is not found in {base_dirs:?}"
+                )));
+            };
+
+            log::debug!(
+                "Restoring segments from base snapshot {}",
+                base_path.display()
+            );
+            let mut archive = tar::Archive::new(File::open(&base_path)?);
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let path = entry_path(&entry.path()?);
+                if path == Path::new(SNAPSHOT_MANIFEST_FILE) {
+                    base = parse_manifest(&mut entry, &base_path)?.base;
+                } else if missing.remove(&path) {
+                    let target_path = target_dir.join(&path);
+                    if let Some(parent) = target_path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                    }
+                    entry.unpack(&target_path)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
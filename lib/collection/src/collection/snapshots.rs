@@ -1,13 +1,18 @@
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 
+use io::file_operations::read_json;
 use segment::common::version::StorageVersion as _;
 use tokio::fs;
 
+use super::snapshot_manifest::{SnapshotManifest, SNAPSHOT_MANIFEST_FILE};
 use super::Collection;
 use crate::collection::CollectionVersion;
 use crate::common::file_utils::FileCleaner;
+use crate::common::snapshot_retention::SnapshotRetentionPolicy;
+use crate::common::snapshot_schedule::ScheduledSnapshots;
 use crate::config::CollectionConfig;
-use crate::operations::snapshot_ops::{self, SnapshotDescription};
+use crate::operations::snapshot_ops::{self, SnapshotDescription, SnapshotMetadata};
 use crate::operations::types::{CollectionError, CollectionResult, NodeType};
 use crate::shards::local_shard::LocalShard;
 use crate::shards::remote_shard::RemoteShard;
@@ -21,6 +26,62 @@ impl Collection {
         snapshot_ops::list_snapshots_in_directory(&self.snapshots_path).await
     }
 
+    pub fn scheduled_snapshots(&self) -> &ScheduledSnapshots {
+        &self.scheduled_snapshots
+    }
+
+    pub fn snapshot_retention(&self) -> &SnapshotRetentionPolicy {
+        &self.snapshot_retention
+    }
+
+    /// Delete collection snapshots of this peer beyond the retention policy.
+    ///
+    /// Snapshots, which are bases of kept incremental snapshots, are not deleted.
+    ///
+    /// returns: names of the deleted snapshots
+    pub async fn prune_snapshots(&self) -> CollectionResult<Vec<String>> {
+        let Some(retention) = self.snapshot_retention.get() else {
+            return Ok(vec![]);
+        };
+        if !self.snapshots_path.exists() {
+            return Ok(vec![]);
+        }
+
+        let snapshots = self.list_snapshots().await?;
+        let mut expired: HashSet<_> = retention
+            .expired(&snapshots, chrono::Utc::now().naive_utc())
+            .into_iter()
+            .collect();
+        if expired.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut kept: Vec<_> = snapshots
+            .into_iter()
+            .map(|snapshot| snapshot.name)
+            .filter(|name| !expired.contains(name))
+            .collect();
+        while let Some(name) = kept.pop() {
+            let snapshot_path = self.snapshots_path.join(&name);
+            let manifest = tokio::task::spawn_blocking(move || {
+                SnapshotManifest::read_from_archive(&snapshot_path)
+            })
+            .await??;
+            if let Some(base) = manifest.and_then(|manifest| manifest.base) {
+                if expired.remove(&base) {
+                    kept.push(base);
+                }
+            }
+        }
+
+        let mut deleted = Vec::with_capacity(expired.len());
+        for name in expired {
+            snapshot_ops::remove_snapshot(&self.snapshots_path.join(&name)).await?;
+            deleted.push(name);
+        }
+        Ok(deleted)
+    }
+
     /// Creates a snapshot of the collection.
     ///
     /// The snapshot is created in three steps:
@@ -28,16 +89,22 @@ impl Collection {
     /// 2. Archive the temporary directory into a single file.
     /// 3. Move the archive to the final location.
     ///
+    /// Incremental snapshot only includes segments, which changed since the `base_snapshot`.
+    /// It can only be restored, if the chain of its base snapshots is available,
+    /// see [`Collection::restore_snapshot_with_bases`].
+    ///
     /// # Arguments
     ///
     /// * `global_temp_dir`: directory used to host snapshots while they are being created
     /// * `this_peer_id`: current peer id
+    /// * `base_snapshot`: name of the collection snapshot, to create an incremental snapshot on top of
     ///
     /// returns: Result<SnapshotDescription, CollectionError>
     pub async fn create_snapshot(
         &self,
         global_temp_dir: &Path,
         this_peer_id: PeerId,
+        base_snapshot: Option<&str>,
     ) -> CollectionResult<SnapshotDescription> {
         let snapshot_name = format!(
             "{}-{}-{}.snapshot",
@@ -45,6 +112,44 @@ impl Collection {
             this_peer_id,
             chrono::Utc::now().format("%Y-%m-%d-%H-%M-%S")
         );
+        self.create_named_snapshot(global_temp_dir, this_peer_id, base_snapshot, snapshot_name)
+            .await
+    }
+
+    pub(crate) async fn create_named_snapshot(
+        &self,
+        global_temp_dir: &Path,
+        this_peer_id: PeerId,
+        base_snapshot: Option<&str>,
+        snapshot_name: String,
+    ) -> CollectionResult<SnapshotDescription> {
+        let started = std::time::Instant::now();
+        let base_manifest = match base_snapshot {
+            Some(base_snapshot) => {
+                let base_path = self.get_snapshot_path(base_snapshot).await?;
+                let manifest = tokio::task::spawn_blocking(move || {
+                    SnapshotManifest::read_from_archive(&base_path)
+                })
+                .await??;
+                Some(manifest.ok_or_else(|| {
+                    CollectionError::bad_input(format!(
+                        "Snapshot {base_snapshot} was created without segment versions and can't be a base of incremental snapshot"
+                    ))
+                })?)
+            }
+            None => None,
+        };
+        let mut manifest = SnapshotManifest {
+            base: base_snapshot.map(str::to_string),
+            shards: Default::default(),
+            wal_positions: Default::default(),
+        };
+
+        if base_snapshot == Some(snapshot_name.as_str()) {
+            return Err(CollectionError::bad_input(format!(
+                "Snapshot {snapshot_name} can't be replaced by an incremental snapshot on top of it"
+            )));
+        }
 
         // Final location of snapshot
         let snapshot_path = self.snapshots_path.join(&snapshot_name);
@@ -76,22 +181,38 @@ impl Collection {
                 fs::create_dir_all(&shard_snapshot_path).await?;
                 // If node is listener, we can save whatever currently is in the storage
                 let save_wal = self.shared_storage_config.node_type != NodeType::Listener;
+                let base_segments = base_manifest
+                    .as_ref()
+                    .and_then(|base_manifest| base_manifest.shards.get(shard_id));
                 replica_set
                     .create_snapshot(
                         snapshot_temp_temp_dir.path(),
                         &shard_snapshot_path,
                         save_wal,
+                        base_segments,
                     )
                     .await?;
+                if let Some(segment_versions) =
+                    LocalShard::load_snapshot_segment_versions(&shard_snapshot_path)?
+                {
+                    manifest.shards.insert(*shard_id, segment_versions);
+                }
+                if let Some(wal_position) =
+                    LocalShard::load_snapshot_wal_position(&shard_snapshot_path)?
+                {
+                    manifest.wal_positions.insert(*shard_id, wal_position);
+                }
             }
         }
+        let manifest = serde_json::to_vec(&manifest)?;
 
         // Save collection config and version
         CollectionVersion::save(&snapshot_temp_target_dir_path)?;
-        self.collection_config
-            .read()
-            .await
-            .save(&snapshot_temp_target_dir_path)?;
+        let collection_params = {
+            let collection_config = self.collection_config.read().await;
+            collection_config.save(&snapshot_temp_target_dir_path)?;
+            collection_config.params.clone()
+        };
 
         // Dedicated temporary file for archiving this snapshot (deleted on drop)
         let mut snapshot_temp_arc_file = tempfile::Builder::new()
@@ -102,6 +223,12 @@ impl Collection {
         log::debug!("Archiving snapshot {:?}", &snapshot_temp_target_dir_path);
         let archiving = tokio::task::spawn_blocking(move || -> CollectionResult<_> {
             let mut builder = tar::Builder::new(snapshot_temp_arc_file.as_file_mut());
+            // manifest goes first, so it can be read without unpacking the archive
+            let mut header = tar::Header::new_gnu();
+            header.set_size(manifest.len() as u64);
+            header.set_mode(0o644);
+            header.set_mtime(chrono::Utc::now().timestamp() as u64);
+            builder.append_data(&mut header, SNAPSHOT_MANIFEST_FILE, manifest.as_slice())?;
             // archive recursively collection directory `snapshot_path_with_arc_extension` into `snapshot_path`
             builder.append_dir_all(".", &snapshot_temp_target_dir_path)?;
             builder.finish()?;
@@ -122,6 +249,13 @@ impl Collection {
         fs::copy(&snapshot_temp_arc_file.path(), &snapshot_path_tmp_move).await?;
         fs::rename(&snapshot_path_tmp_move, &snapshot_path).await?;
 
+        snapshot_ops::create_snapshot_checksum(&snapshot_path).await?;
+        snapshot_ops::save_snapshot_metadata(
+            &snapshot_path,
+            &SnapshotMetadata::new(started, Some(collection_params)),
+        )
+        .await?;
+
         log::info!(
             "Collection snapshot {} completed into {:?}",
             snapshot_name,
@@ -130,6 +264,18 @@ impl Collection {
         snapshot_ops::get_snapshot_description(&snapshot_path).await
     }
 
+    /// Name of the base snapshot of an incremental snapshot, `None` for a full snapshot
+    ///
+    /// This method performs blocking IO.
+    pub fn snapshot_base(snapshot_path: &Path) -> CollectionResult<Option<String>> {
+        let base =
+            SnapshotManifest::read_from_archive(snapshot_path)?.and_then(|manifest| manifest.base);
+        if let Some(base) = &base {
+            SnapshotManifest::check_base_name(base)?;
+        }
+        Ok(base)
+    }
+
     /// Restore collection from snapshot
     ///
     /// This method performs blocking IO.
@@ -138,12 +284,40 @@ impl Collection {
         target_dir: &Path,
         this_peer_id: PeerId,
         is_distributed: bool,
+    ) -> CollectionResult<()> {
+        Self::restore_snapshot_with_bases(
+            snapshot_path,
+            &[],
+            target_dir,
+            this_peer_id,
+            is_distributed,
+        )
+    }
+
+    /// Restore collection from snapshot.
+    ///
+    /// Base snapshots of an incremental snapshot are looked up next to it, then in `base_dirs`.
+    ///
+    /// This method performs blocking IO.
+    pub fn restore_snapshot_with_bases(
+        snapshot_path: &Path,
+        base_dirs: &[PathBuf],
+        target_dir: &Path,
+        this_peer_id: PeerId,
+        is_distributed: bool,
     ) -> CollectionResult<()> {
         // decompress archive
         let archive_file = std::fs::File::open(snapshot_path)?;
         let mut ar = tar::Archive::new(archive_file);
         ar.unpack(target_dir)?;
 
+        let manifest_path = target_dir.join(SNAPSHOT_MANIFEST_FILE);
+        if manifest_path.exists() {
+            let manifest: SnapshotManifest = read_json(&manifest_path)?;
+            manifest.restore_base_segments(snapshot_path, base_dirs, target_dir)?;
+            std::fs::remove_file(manifest_path)?;
+        }
+
         let config = CollectionConfig::load(target_dir)?;
         config.validate_and_warn();
         let configured_shards = config.params.shard_number.get();
@@ -240,10 +414,17 @@ impl Collection {
         shard_id: ShardId,
         temp_dir: &Path,
     ) -> CollectionResult<SnapshotDescription> {
+        let collection_params = self.collection_config.read().await.params.clone();
         self.shards_holder
             .read()
             .await
-            .create_shard_snapshot(&self.snapshots_path, &self.name(), shard_id, temp_dir)
+            .create_shard_snapshot(
+                &self.snapshots_path,
+                &self.name(),
+                collection_params,
+                shard_id,
+                temp_dir,
+            )
             .await
     }
 
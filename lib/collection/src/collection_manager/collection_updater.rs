@@ -1,10 +1,18 @@
+use std::cmp::max;
+use std::collections::{HashMap, HashSet};
+
 use parking_lot::RwLock;
-use segment::types::SeqNumberType;
+use segment::types::{PointIdType, SeqNumberType};
 
 use crate::collection_manager::holders::segment_holder::SegmentHolder;
 use crate::collection_manager::segments_updater::*;
+use crate::operations::clock_ops::{ClockTag, ClockedChange, ClockedOperation};
+use crate::operations::idempotency_ops::IdempotentOperation;
+use crate::operations::operation_effect::{EstimateOperationEffectArea, OperationEffectArea};
+use crate::operations::point_ops::PointOperations;
 use crate::operations::types::CollectionResult;
 use crate::operations::CollectionUpdateOperations;
+use crate::shards::clocks::PointClocks;
 
 /// Implementation of the update operation
 #[derive(Default)]
@@ -49,7 +57,19 @@ impl CollectionUpdater {
     ) -> CollectionResult<usize> {
         // Allow only one update at a time, ensure no data races between segments.
         // let _lock = self.update_lock.lock().unwrap();
-        let operation_result = match operation {
+        let operation_result = Self::process_operation(segments, op_num, operation);
+
+        CollectionUpdater::handle_update_result(segments, op_num, &operation_result);
+
+        operation_result
+    }
+
+    fn process_operation(
+        segments: &RwLock<SegmentHolder>,
+        op_num: SeqNumberType,
+        operation: CollectionUpdateOperations,
+    ) -> CollectionResult<usize> {
+        match operation {
             CollectionUpdateOperations::PointOperation(point_operation) => {
                 process_point_operation(segments, op_num, point_operation)
             }
@@ -62,16 +82,185 @@ impl CollectionUpdater {
             CollectionUpdateOperations::FieldIndexOperation(index_operation) => {
                 process_field_index_operation(segments, op_num, &index_operation)
             }
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                // None of the operations is applied, if any of them would fail
+                check_batch_operations(&segments.read(), op_num, &operations)?;
+                // All operations of the batch share the same sequence number,
+                // so each of them is applied on top of the previous ones
+                let mut updated = 0;
+                for operation in operations {
+                    updated += Self::process_operation(segments, op_num, operation)?;
+                }
+                Ok(updated)
+            }
+            CollectionUpdateOperations::ClockedOperation(clocked) => {
+                Self::process_clocked_operation(segments, op_num, clocked)
+            }
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => {
+                Self::process_idempotent_operation(segments, op_num, idempotent)
+            }
+        }
+    }
+
+    /// Apply the operation, unless another operation with the same token was applied already.
+    /// The operation itself is applied again, e.g. when the WAL is replayed on recovery.
+    fn process_idempotent_operation(
+        segments: &RwLock<SegmentHolder>,
+        op_num: SeqNumberType,
+        idempotent: IdempotentOperation,
+    ) -> CollectionResult<usize> {
+        let IdempotentOperation {
+            idempotency_token,
+            operation,
+        } = idempotent;
+
+        let Some(idempotency_tokens) = segments.read().idempotency_tokens.clone() else {
+            return Self::process_operation(segments, op_num, *operation);
+        };
+
+        if let Some(applied_op_num) = idempotency_tokens.get(&idempotency_token)? {
+            if applied_op_num != op_num {
+                log::debug!(
+                    "Skipping operation {op_num}, its token was applied by operation {applied_op_num}"
+                );
+                return Ok(0);
+            }
+        }
+
+        let result = Self::process_operation(segments, op_num, *operation)?;
+        idempotency_tokens.record(&idempotency_token, op_num)?;
+        Ok(result)
+    }
+
+    /// Apply the operation to the points, whose changed part was not replaced
+    /// by an operation with a higher clock yet, and remember the clock of replaced parts.
+    /// So replicas end up with the same points, whatever order they receive concurrent updates in.
+    fn process_clocked_operation(
+        segments: &RwLock<SegmentHolder>,
+        op_num: SeqNumberType,
+        clocked: ClockedOperation,
+    ) -> CollectionResult<usize> {
+        let ClockedOperation {
+            clock_tag,
+            point_clock_tags,
+            operation,
+        } = clocked;
+
+        let Some(point_clocks) = segments.read().point_clocks.clone() else {
+            return Self::process_operation(segments, op_num, *operation);
         };
 
-        CollectionUpdater::handle_update_result(segments, op_num, &operation_result);
+        let point_clock_tags: HashMap<_, _> = point_clock_tags.into_iter().collect();
+        let clock_of = |point_id: PointIdType| {
+            point_clock_tags
+                .get(&point_id)
+                .copied()
+                .unwrap_or(clock_tag)
+        };
 
-        operation_result
+        let result =
+            Self::process_clocked_change(segments, op_num, &point_clocks, &clock_of, *operation)?;
+
+        // Deleted points are not updated anymore, once the retention window has passed
+        point_clocks.purge_tombstones(|point_id| {
+            segments
+                .read()
+                .iter()
+                .any(|(_, segment)| segment.get().read().has_point(point_id))
+        })?;
+        Ok(result)
+    }
+
+    fn process_clocked_change(
+        segments: &RwLock<SegmentHolder>,
+        op_num: SeqNumberType,
+        point_clocks: &PointClocks,
+        clock_of: &dyn Fn(PointIdType) -> ClockTag,
+        operation: CollectionUpdateOperations,
+    ) -> CollectionResult<usize> {
+        if let CollectionUpdateOperations::BatchOperation(operations) = operation {
+            check_batch_operations(&segments.read(), op_num, &operations)?;
+            // Operations of the batch may change different parts of the points
+            let mut updated = 0;
+            for operation in operations {
+                updated += Self::process_clocked_change(
+                    segments,
+                    op_num,
+                    point_clocks,
+                    clock_of,
+                    operation,
+                )?;
+            }
+            return Ok(updated);
+        }
+
+        if let CollectionUpdateOperations::PointOperation(PointOperations::SyncPoints(sync)) =
+            &operation
+        {
+            // Sync copies points from another replica as is, together with their clocks
+            let synced_points: Vec<_> = sync.points.iter().map(|point| point.id).collect();
+            let result = Self::process_operation(segments, op_num, operation)?;
+            for point_id in synced_points {
+                point_clocks.set(point_id, clock_of(point_id))?;
+            }
+            return Ok(result);
+        }
+
+        let change = operation.clocked_change();
+        if change == ClockedChange::None {
+            return Self::process_operation(segments, op_num, operation);
+        }
+
+        let affected_points = match operation.estimate_effect_area() {
+            OperationEffectArea::Empty => Vec::new(),
+            OperationEffectArea::Points(point_ids) => point_ids,
+            OperationEffectArea::Filter(filter) => points_by_filter(&segments.read(), &filter)?,
+        };
+
+        let mut outdated_points = HashSet::new();
+        let mut changed_points = Vec::with_capacity(affected_points.len());
+        for point_id in affected_points {
+            let point_clock_tag = clock_of(point_id);
+            let mut current = point_clocks.get(point_id)?;
+            if matches!(change, ClockedChange::Payload | ClockedChange::PayloadKeys) {
+                current = max(current, point_clocks.get_payload(point_id)?);
+            }
+            match current {
+                Some(current) if current > point_clock_tag => {
+                    outdated_points.insert(point_id);
+                }
+                _ => changed_points.push((point_id, point_clock_tag)),
+            }
+        }
+
+        if !outdated_points.is_empty() {
+            log::debug!(
+                "Skipping update of {} points, replaced by operations with higher clock",
+                outdated_points.len()
+            );
+        }
+
+        let result =
+            Self::process_operation(segments, op_num, operation.without_points(&outdated_points))?;
+        for (point_id, point_clock_tag) in changed_points {
+            match change {
+                ClockedChange::Points => point_clocks.advance(point_id, point_clock_tag)?,
+                ClockedChange::Payload => {
+                    point_clocks.advance_payload(point_id, point_clock_tag)?
+                }
+                // Partial updates commute with each other, so their clocks are not kept
+                ClockedChange::PayloadKeys | ClockedChange::Vectors | ClockedChange::None => {}
+            }
+        }
+        Ok(result)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
     use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
     use segment::types::{Payload, WithPayload};
     use serde_json::json;
@@ -82,7 +271,13 @@ mod tests {
     use crate::collection_manager::segments_searcher::SegmentsSearcher;
     use crate::collection_manager::segments_updater::upsert_points;
     use crate::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
-    use crate::operations::point_ops::{PointOperations, PointStruct};
+    use crate::operations::point_ops::{
+        ConditionalInsertOperation, PointInsertOperations, PointOperations, PointStruct,
+        UpsertCondition,
+    };
+    use crate::operations::types::{CollectionError, Record};
+    use crate::operations::vector_ops::VectorOperations;
+    use crate::shards::idempotency_tokens::IdempotencyTokens;
 
     #[test]
     fn test_sync_ops() {
@@ -284,4 +479,282 @@ mod tests {
         assert_eq!(res.len(), 1);
         assert!(!res[0].payload.as_ref().unwrap().contains_key("color"));
     }
+
+    #[test]
+    fn test_batch_ops() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segments = build_test_holder(dir.path());
+
+        let payload: Payload = serde_json::from_str(r#"{"color":"blue"}"#).unwrap();
+
+        let batch = CollectionUpdateOperations::BatchOperation(vec![
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(vec![PointStruct {
+                    id: 600.into(),
+                    vector: vec![1., 0., 1., 0.].into(),
+                    payload: None,
+                }]),
+            )),
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+                payload,
+                points: Some(vec![600.into()]),
+                filter: None,
+            })),
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+                ids: vec![1.into()],
+            }),
+        ]);
+
+        CollectionUpdater::update(&segments, 100, batch).unwrap();
+
+        // Operations are applied in order, so the payload is set on the upserted point
+        let res = SegmentsSearcher::retrieve(
+            &segments,
+            &[1.into(), 600.into()],
+            &WithPayload::from(true),
+            &false.into(),
+        )
+        .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id, 600.into());
+        assert!(res[0].payload.as_ref().unwrap().contains_key("color"));
+    }
+
+    #[test]
+    fn test_failed_batch_ops() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segments = build_test_holder(dir.path());
+
+        let batch = |last_operation| {
+            CollectionUpdateOperations::BatchOperation(vec![
+                CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                    PointInsertOperations::PointsList(vec![PointStruct {
+                        id: 600.into(),
+                        vector: vec![1., 0., 1., 0.].into(),
+                        payload: None,
+                    }]),
+                )),
+                CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+                    payload: json!({"size": "big"}).into(),
+                    points: Some(vec![600.into(), 1.into()]),
+                    filter: None,
+                })),
+                CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+                    ids: vec![1.into()],
+                }),
+                last_operation,
+            ])
+        };
+
+        // Point 1 is deleted by the batch itself
+        let missing_point =
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayload {
+                points: vec![600.into(), 1.into()],
+            });
+        let wrong_dimension = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(PointInsertOperations::PointsList(vec![PointStruct {
+                id: 601.into(),
+                vector: vec![1., 0.].into(),
+                payload: None,
+            }])),
+        );
+        let wrong_vector_name = CollectionUpdateOperations::VectorOperation(
+            VectorOperations::DeleteVectors(vec![2.into()].into(), vec!["missing".to_string()]),
+        );
+
+        let result = CollectionUpdater::update(&segments, 100, batch(missing_point));
+        assert!(
+            matches!(
+                result,
+                Err(CollectionError::PointNotFound { missed_point_id }) if missed_point_id == 1.into()
+            ),
+            "{result:?}",
+        );
+        for last_operation in [wrong_dimension, wrong_vector_name] {
+            let result = CollectionUpdater::update(&segments, 101, batch(last_operation));
+            assert!(result.is_err());
+        }
+
+        // Nothing is applied, if the last operation of the batch fails
+        let res = SegmentsSearcher::retrieve(
+            &segments,
+            &[1.into(), 600.into(), 601.into()],
+            &WithPayload::from(true),
+            &false.into(),
+        )
+        .unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].id, 1.into());
+        assert!(!res[0].payload.as_ref().unwrap().contains_key("size"));
+    }
+
+    #[test]
+    fn test_conditional_upsert() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segments = build_test_holder(dir.path());
+
+        let upsert = |condition| {
+            PointOperations::UpsertPointsConditional(ConditionalInsertOperation {
+                points: PointInsertOperations::PointsList(vec![PointStruct {
+                    id: 600.into(),
+                    vector: vec![1., 0., 1., 0.].into(),
+                    payload: None,
+                }]),
+                condition,
+            })
+        };
+
+        process_point_operation(&segments, 100, upsert(UpsertCondition::IfAbsent)).unwrap();
+
+        let res = process_point_operation(&segments, 101, upsert(UpsertCondition::IfAbsent));
+        assert!(matches!(
+            res,
+            Err(CollectionError::PreconditionFailed { description })
+                if description == "Point 600 already exists with version 100"
+        ));
+
+        process_point_operation(&segments, 102, upsert(UpsertCondition::IfVersion(100))).unwrap();
+
+        // Version of the point is updated by the previous upsert
+        let res = process_point_operation(&segments, 103, upsert(UpsertCondition::IfVersion(100)));
+        assert!(matches!(
+            res,
+            Err(CollectionError::PreconditionFailed { description })
+                if description == "Point 600 has version 102, expected 100"
+        ));
+    }
+
+    #[test]
+    fn test_idempotent_ops() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segments = build_test_holder(dir.path());
+        segments.write().idempotency_tokens = Some(Arc::new(
+            IdempotencyTokens::open(dir.path(), Duration::from_secs(60)).unwrap(),
+        ));
+
+        let delete = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+            ids: vec![1.into()],
+        })
+        .with_idempotency_token(Some("retry".to_string()));
+        let upsert = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperations::PointsList(vec![PointStruct {
+                id: 1.into(),
+                vector: vec![1., 0., 1., 0.].into(),
+                payload: None,
+            }]),
+        ));
+
+        assert_eq!(
+            CollectionUpdater::update(&segments, 100, delete.clone()).unwrap(),
+            1
+        );
+        CollectionUpdater::update(&segments, 101, upsert).unwrap();
+
+        // Retry of the delete is skipped, so the point upserted after it remains
+        assert_eq!(
+            CollectionUpdater::update(&segments, 102, delete).unwrap(),
+            0
+        );
+        let res = SegmentsSearcher::retrieve(
+            &segments,
+            &[1.into()],
+            &WithPayload::from(false),
+            &false.into(),
+        )
+        .unwrap();
+        assert_eq!(res.len(), 1);
+
+        // Token keeps the number of the operation, which was actually applied
+        let idempotency_tokens = segments.read().idempotency_tokens.clone().unwrap();
+        assert_eq!(idempotency_tokens.get("retry").unwrap(), Some(100));
+    }
+
+    /// Apply clocked operations to a new replica in the given order and read point 1 back
+    fn apply_clocked(operations: &[(u64, CollectionUpdateOperations)]) -> Record {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segments = build_test_holder(dir.path());
+        // Ticks of the test operations are long in the past, so clocks of deleted points are kept
+        segments.write().point_clocks = Some(Arc::new(
+            PointClocks::open(dir.path(), Duration::MAX).unwrap(),
+        ));
+
+        for (op_num, (clock_tick, operation)) in operations.iter().enumerate() {
+            let clocked = CollectionUpdateOperations::ClockedOperation(ClockedOperation::new(
+                ClockTag::new(*clock_tick, 1),
+                operation.clone(),
+            ));
+            CollectionUpdater::update(&segments, 100 + op_num as SeqNumberType, clocked).unwrap();
+        }
+
+        let mut res = SegmentsSearcher::retrieve(
+            &segments,
+            &[1.into()],
+            &WithPayload::from(true),
+            &true.into(),
+        )
+        .unwrap();
+        assert_eq!(res.len(), 1);
+        res.pop().unwrap()
+    }
+
+    fn set_payload(payload: serde_json::Value) -> CollectionUpdateOperations {
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+            payload: payload.into(),
+            points: Some(vec![1.into()]),
+            filter: None,
+        }))
+    }
+
+    #[test]
+    fn test_clocked_partial_updates() {
+        let set_a = (10, set_payload(json!({ "a": 1 })));
+        let set_b = (11, set_payload(json!({ "b": 2 })));
+
+        // Partial updates don't drop each other, whatever order they are received in
+        let in_order = apply_clocked(&[set_a.clone(), set_b.clone()]);
+        let reordered = apply_clocked(&[set_b, set_a]);
+        let payload = in_order.payload.clone().unwrap();
+        assert_eq!(payload.0.get("a"), Some(&json!(1)));
+        assert_eq!(payload.0.get("b"), Some(&json!(2)));
+        assert_eq!(in_order.payload, reordered.payload);
+        assert_eq!(in_order.vector, reordered.vector);
+    }
+
+    #[test]
+    fn test_clocked_overwrites() {
+        let set_a = (10, set_payload(json!({ "a": 1 })));
+        let overwrite = (
+            11,
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::OverwritePayload(
+                SetPayload {
+                    payload: json!({ "b": 2 }).into(),
+                    points: Some(vec![1.into()]),
+                    filter: None,
+                },
+            )),
+        );
+        let upsert = (
+            12,
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(vec![PointStruct {
+                    id: 1.into(),
+                    vector: vec![0., 1., 0., 1.].into(),
+                    payload: Some(json!({ "c": 3 }).into()),
+                }]),
+            )),
+        );
+        let set_d = (13, set_payload(json!({ "d": 4 })));
+
+        // Updates, received after a later replacement of the point or its payload, are skipped
+        let in_order = apply_clocked(&[
+            set_a.clone(),
+            overwrite.clone(),
+            upsert.clone(),
+            set_d.clone(),
+        ]);
+        let reordered = apply_clocked(&[upsert, overwrite, set_a, set_d]);
+        assert_eq!(in_order.payload, Some(json!({ "c": 3, "d": 4 }).into()));
+        assert_eq!(in_order.payload, reordered.payload);
+        assert_eq!(in_order.vector, reordered.vector);
+    }
 }
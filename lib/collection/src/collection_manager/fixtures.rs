@@ -204,10 +204,12 @@ pub(crate) fn get_merge_optimizer(
 ) -> MergeOptimizer {
     MergeOptimizer::new(
         5,
+        None,
         OptimizerThresholds {
             max_segment_size: 100_000,
             memmap_threshold: 1000000,
             indexing_threshold: 1000000,
+            defragmentation_key: None,
         },
         segment_path.to_owned(),
         collection_temp_dir.to_owned(),
@@ -218,6 +220,8 @@ pub(crate) fn get_merge_optimizer(
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                multivector_config: None,
+                datatype: None,
             }),
             ..CollectionParams::empty()
         },
@@ -236,6 +240,7 @@ pub(crate) fn get_indexing_optimizer(
             max_segment_size: 100_000,
             memmap_threshold: 100,
             indexing_threshold: 100,
+            defragmentation_key: None,
         },
         segment_path.to_owned(),
         collection_temp_dir.to_owned(),
@@ -246,6 +251,8 @@ pub(crate) fn get_indexing_optimizer(
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                multivector_config: None,
+                datatype: None,
             }),
             ..CollectionParams::empty()
         },
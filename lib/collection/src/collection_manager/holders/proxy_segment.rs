@@ -7,8 +7,10 @@ use std::sync::Arc;
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 use segment::common::operation_error::{OperationResult, SegmentFailedState};
 use segment::data_types::named_vectors::NamedVectors;
-use segment::data_types::vectors::{QueryVector, VectorElementType};
+use segment::data_types::order_by::{OrderBy, OrderValue};
+use segment::data_types::vectors::{QueryVector, Vector};
 use segment::entry::entry_point::SegmentEntry;
+use segment::index::field_index::stats::FieldIndexStats;
 use segment::index::field_index::CardinalityEstimation;
 use segment::telemetry::SegmentTelemetry;
 use segment::types::{
@@ -296,6 +298,99 @@ impl SegmentEntry for ProxySegment {
         Ok(wrapped_results)
     }
 
+    fn search_text(
+        &self,
+        key: PayloadKeyTypeRef,
+        text: &str,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPoint>> {
+        let deleted_points = self.deleted_points.read();
+
+        // Some point might be deleted after temporary segment creation
+        // We need to prevent them from being found by search request
+        let mut wrapped_result = if deleted_points.is_empty() {
+            self.wrapped_segment.get().read().search_text(
+                key,
+                text,
+                with_payload,
+                with_vector,
+                filter,
+                top,
+                is_stopped,
+            )?
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+
+            self.wrapped_segment.get().read().search_text(
+                key,
+                text,
+                with_payload,
+                with_vector,
+                Some(&wrapped_filter),
+                top,
+                is_stopped,
+            )?
+        };
+        let mut write_result = self.write_segment.get().read().search_text(
+            key,
+            text,
+            with_payload,
+            with_vector,
+            filter,
+            top,
+            is_stopped,
+        )?;
+        wrapped_result.append(&mut write_result);
+        Ok(wrapped_result)
+    }
+
+    fn sample_points(
+        &self,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPoint>> {
+        let deleted_points = self.deleted_points.read();
+
+        // Points deleted after temporary segment creation must not be sampled
+        let mut wrapped_result = if deleted_points.is_empty() {
+            self.wrapped_segment.get().read().sample_points(
+                with_payload,
+                with_vector,
+                filter,
+                top,
+                is_stopped,
+            )?
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+
+            self.wrapped_segment.get().read().sample_points(
+                with_payload,
+                with_vector,
+                Some(&wrapped_filter),
+                top,
+                is_stopped,
+            )?
+        };
+        let mut write_result = self.write_segment.get().read().sample_points(
+            with_payload,
+            with_vector,
+            filter,
+            top,
+            is_stopped,
+        )?;
+        wrapped_result.append(&mut write_result);
+        Ok(wrapped_result)
+    }
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -404,11 +499,7 @@ impl SegmentEntry for ProxySegment {
             .clear_payload(op_num, point_id)
     }
 
-    fn vector(
-        &self,
-        vector_name: &str,
-        point_id: PointIdType,
-    ) -> OperationResult<Option<Vec<VectorElementType>>> {
+    fn vector(&self, vector_name: &str, point_id: PointIdType) -> OperationResult<Option<Vector>> {
         return if self.deleted_points.read().contains(&point_id) {
             self.write_segment
                 .get()
@@ -431,16 +522,16 @@ impl SegmentEntry for ProxySegment {
 
     fn all_vectors(&self, point_id: PointIdType) -> OperationResult<NamedVectors> {
         let mut result = NamedVectors::default();
-        for vector_name in self
-            .wrapped_segment
-            .get()
-            .read()
-            .config()
+        let wrapped_segment = self.wrapped_segment.get();
+        let wrapped_segment_guard = wrapped_segment.read();
+        let config = wrapped_segment_guard.config();
+        for vector_name in config
             .vector_data
             .keys()
+            .chain(config.sparse_vector_data.keys())
         {
             if let Some(vector) = self.vector(vector_name, point_id)? {
-                result.insert(vector_name.clone(), vector);
+                result.insert_vector(vector_name.clone(), vector);
             }
         }
         Ok(result)
@@ -498,6 +589,40 @@ impl SegmentEntry for ProxySegment {
         read_points
     }
 
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>> {
+        let deleted_points = self.deleted_points.read();
+        let mut read_points = if deleted_points.is_empty() {
+            self.wrapped_segment
+                .get()
+                .read()
+                .read_ordered_filtered(limit, filter, order_by)?
+        } else {
+            let wrapped_filter =
+                self.add_deleted_points_condition_to_filter(filter, &deleted_points);
+            self.wrapped_segment.get().read().read_ordered_filtered(
+                limit,
+                Some(&wrapped_filter),
+                order_by,
+            )?
+        };
+        let mut write_segment_points = self
+            .write_segment
+            .get()
+            .read()
+            .read_ordered_filtered(limit, filter, order_by)?;
+        read_points.append(&mut write_segment_points);
+        let direction = order_by.direction();
+        read_points.sort_unstable_by(|(value_a, id_a), (value_b, id_b)| {
+            direction.compare(*value_a, *value_b).then(id_a.cmp(id_b))
+        });
+        Ok(read_points)
+    }
+
     /// Read points in [from; to) range
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType> {
         let deleted_points = self.deleted_points.read();
@@ -715,6 +840,14 @@ impl SegmentEntry for ProxySegment {
             .collect()
     }
 
+    fn get_field_index_stats(&self, key: PayloadKeyTypeRef) -> Option<FieldIndexStats> {
+        // Write segment is also present in the segment holder, so only the wrapped one is counted
+        if self.deleted_indexes.read().contains(key) {
+            return None;
+        }
+        self.wrapped_segment.get().read().get_field_index_stats(key)
+    }
+
     fn check_error(&self) -> Option<SegmentFailedState> {
         self.write_segment.get().read().check_error()
     }
@@ -1322,6 +1455,8 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
                 (
@@ -1332,9 +1467,12 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
             ]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
         let mut original_segment = build_segment(dir.path(), &config, true).unwrap();
@@ -17,10 +17,15 @@ use segment::types::{PointIdType, SeqNumberType};
 
 use crate::collection_manager::holders::proxy_segment::ProxySegment;
 use crate::operations::types::CollectionError;
+use crate::shards::clocks::PointClocks;
+use crate::shards::idempotency_tokens::IdempotencyTokens;
 use crate::shards::update_tracker::UpdateTracker;
 
 pub type SegmentId = usize;
 
+/// Versions of the segments, by the name of the segment directory
+pub type SegmentVersions = HashMap<String, SeqNumberType>;
+
 const DROP_SPIN_TIMEOUT: Duration = Duration::from_millis(10);
 const DROP_DATA_TIMEOUT: Duration = Duration::from_secs(60 * 60);
 
@@ -148,6 +153,14 @@ pub struct SegmentHolder {
 
     /// Holds the first uncorrected error happened with optimizer
     pub optimizer_errors: Option<CollectionError>,
+
+    /// Clocks of the last changes of the points, used to resolve conflicting updates of replicas.
+    /// If None - clocks of the operations are ignored.
+    pub point_clocks: Option<Arc<PointClocks>>,
+
+    /// Idempotency tokens of the applied operations, so their retries are not applied again.
+    /// If None - tokens of the operations are ignored.
+    pub idempotency_tokens: Option<Arc<IdempotencyTokens>>,
 }
 
 pub type LockedSegmentHolder = Arc<RwLock<SegmentHolder>>;
@@ -499,6 +512,16 @@ impl<'s> SegmentHolder {
             .collect();
         segment_reads.reverse();
 
+        // Clocks may be ahead of the segments, but never behind them,
+        // otherwise an outdated update could override the persisted points after recovery
+        if let Some(point_clocks) = &self.point_clocks {
+            point_clocks.flusher()()?;
+        }
+        // Same for tokens: an operation, replayed on recovery, is applied despite its own token
+        if let Some(idempotency_tokens) = &self.idempotency_tokens {
+            idempotency_tokens.flusher()()?;
+        }
+
         // Assert we flush appendable segments first
         debug_assert!(
             segment_reads
@@ -555,17 +578,37 @@ impl<'s> SegmentHolder {
     /// Take a snapshot of all segments into `snapshot_dir_path`
     ///
     /// Shortcuts at the first failing segment snapshot
+    /// Archive all segments into `snapshot_dir_path`.
+    ///
+    /// Segments, which have the same version as in `base`, are not archived again.
+    /// Returns versions of all segments, including the skipped ones.
     pub fn snapshot_all_segments(
         &self,
         temp_dir: &Path,
         snapshot_dir_path: &Path,
-    ) -> OperationResult<()> {
+        base: Option<&SegmentVersions>,
+    ) -> OperationResult<SegmentVersions> {
+        let mut versions = SegmentVersions::new();
         for segment in self.segments.values() {
             let segment_lock = segment.get();
             let read_segment = segment_lock.read();
-            read_segment.take_snapshot(temp_dir, snapshot_dir_path)?;
+            let data_path = read_segment.data_path();
+            let Some(segment_name) = data_path.file_stem().and_then(|name| name.to_str()) else {
+                return Err(OperationError::service_error(format!(
+                    "Invalid segment path {}",
+                    data_path.display()
+                )));
+            };
+            let version = read_segment.version();
+            let unchanged = base
+                .and_then(|base| base.get(segment_name))
+                .map_or(false, |base_version| *base_version == version);
+            if !unchanged {
+                read_segment.take_snapshot(temp_dir, snapshot_dir_path)?;
+            }
+            versions.insert(segment_name.to_string(), version);
         }
-        Ok(())
+        Ok(versions)
     }
 
     pub fn report_optimizer_error<E: Into<CollectionError>>(&mut self, error: E) {
@@ -813,12 +856,30 @@ mod tests {
 
         let temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
         let snapshot_dir = Builder::new().prefix("snapshot_dir").tempdir().unwrap();
-        holder
-            .snapshot_all_segments(temp_dir.path(), snapshot_dir.path())
+        let versions = holder
+            .snapshot_all_segments(temp_dir.path(), snapshot_dir.path(), None)
             .unwrap();
 
         let archive_count = read_dir(&snapshot_dir).unwrap().count();
         // one archive produced per concrete segment in the SegmentHolder
         assert_eq!(archive_count, 2);
+        assert_eq!(versions.len(), 2);
+
+        // only the changed segment is archived on top of the previous snapshot
+        holder
+            .get(sid1)
+            .unwrap()
+            .get()
+            .write()
+            .delete_point(100, 1.into())
+            .unwrap();
+        let incremental_dir = Builder::new().prefix("snapshot_dir").tempdir().unwrap();
+        let incremental_versions = holder
+            .snapshot_all_segments(temp_dir.path(), incremental_dir.path(), Some(&versions))
+            .unwrap();
+
+        assert_eq!(read_dir(&incremental_dir).unwrap().count(), 1);
+        assert_eq!(incremental_versions.len(), 2);
+        assert_ne!(incremental_versions, versions);
     }
 }
@@ -7,6 +7,7 @@ use parking_lot::Mutex;
 use segment::common::operation_time_statistics::{
     OperationDurationStatistics, OperationDurationsAggregator,
 };
+use segment::segment::Segment;
 use segment::types::{HnswConfig, Indexes, QuantizationConfig, SegmentType, VECTOR_ELEMENT_SIZE};
 
 use crate::collection_manager::holders::segment_holder::{LockedSegmentHolder, SegmentId};
@@ -122,6 +123,11 @@ impl ConfigMismatchOptimizer {
                     return Some((*idx, vector_size)); // Skip segments with payload mismatch
                 }
 
+                // Data of removed vectors is only freed by rebuilding the segment
+                if Segment::has_dropped_vector_data(&read_segment.data_path()) {
+                    return Some((*idx, vector_size));
+                }
+
                 // Determine whether segment has mismatch
                 let has_mismatch =
                     segment_config
@@ -159,7 +165,8 @@ impl ConfigMismatchOptimizer {
                                 });
                             let target_quantization = target_quantization_vector
                                 .as_ref()
-                                .or(target_quantization_collection);
+                                .or(target_quantization_collection)
+                                .filter(|_| vector_data.supports_quantization());
                             let quantization_mismatch = vector_data
                                 .quantization_config
                                 .as_ref()
@@ -278,6 +285,7 @@ mod tests {
             max_segment_size: std::usize::MAX,
             memmap_threshold: std::usize::MAX,
             indexing_threshold: 10,
+            defragmentation_key: None,
         };
         let collection_params = CollectionParams {
             vectors: VectorsConfig::Single(VectorParams {
@@ -286,6 +294,8 @@ mod tests {
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                multivector_config: None,
+                datatype: None,
             }),
             ..CollectionParams::empty()
         };
@@ -401,6 +411,7 @@ mod tests {
             max_segment_size: std::usize::MAX,
             memmap_threshold: std::usize::MAX,
             indexing_threshold: 10,
+            defragmentation_key: None,
         };
         let hnsw_config_vector1 = HnswConfigDiff {
             m: Some(10),
@@ -418,6 +429,8 @@ mod tests {
                         hnsw_config: Some(hnsw_config_vector1),
                         quantization_config: None,
                         on_disk: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
                 (
@@ -428,6 +441,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
             ])),
@@ -539,6 +554,129 @@ mod tests {
             });
     }
 
+    /// This test the config mismatch optimizer for a vector removed from the collection
+    ///
+    /// In short, this is what happens in this test:
+    /// - create randomized multi segment as base
+    /// - use indexing optimizer to build index for our segment
+    /// - remove vector2 from the collection config and the segments
+    /// - test config mismatch condition: should trigger due to leftover vector data
+    /// - optimize segment with config mismatch optimizer
+    /// - assert leftover vector data is gone
+    #[test]
+    fn test_dropped_vector_data() {
+        // Collection configuration
+        let (point_count, vector1_dim, vector2_dim) = (1000, 10, 20);
+        let thresholds_config = OptimizerThresholds {
+            max_segment_size: std::usize::MAX,
+            memmap_threshold: std::usize::MAX,
+            indexing_threshold: 10,
+            defragmentation_key: None,
+        };
+        let vector_params = |size: u64| VectorParams {
+            size: size.try_into().unwrap(),
+            distance: Distance::Dot,
+            hnsw_config: None,
+            quantization_config: None,
+            on_disk: None,
+            multivector_config: None,
+            datatype: None,
+        };
+        let collection_params = CollectionParams {
+            vectors: VectorsConfig::Multi(BTreeMap::from([
+                ("vector1".into(), vector_params(vector1_dim)),
+                ("vector2".into(), vector_params(vector2_dim)),
+            ])),
+            ..CollectionParams::empty()
+        };
+
+        // Base segment
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let mut holder = SegmentHolder::default();
+
+        let segment = random_multi_vec_segment(
+            dir.path(),
+            100,
+            point_count,
+            vector1_dim as usize,
+            vector2_dim as usize,
+        );
+
+        let segment_id = holder.add(segment);
+        let locked_holder: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
+
+        let hnsw_config = HnswConfig {
+            m: 16,
+            ef_construct: 100,
+            full_scan_threshold: 10,
+            max_indexing_threads: 0,
+            on_disk: None,
+            payload_m: None,
+        };
+
+        // Optimizers used in test
+        let index_optimizer = IndexingOptimizer::new(
+            thresholds_config.clone(),
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            collection_params.clone(),
+            hnsw_config.clone(),
+            Default::default(),
+        );
+        let mut config_mismatch_optimizer = ConfigMismatchOptimizer::new(
+            thresholds_config,
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            collection_params,
+            hnsw_config,
+            Default::default(),
+        );
+
+        let changed = index_optimizer
+            .optimize(locked_holder.clone(), vec![segment_id], &false.into())
+            .unwrap();
+        assert!(changed, "optimizer should have rebuilt this segment");
+
+        let suggested_to_optimize =
+            config_mismatch_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested_to_optimize.len(), 0);
+
+        // Remove vector2, like the shard does when the collection config changes
+        config_mismatch_optimizer.collection_params.vectors = VectorsConfig::Multi(BTreeMap::from(
+            [("vector1".into(), vector_params(vector1_dim))],
+        ));
+        for (_, segment) in locked_holder.read().iter() {
+            match segment {
+                LockedSegment::Original(s) => s.write().drop_vector_data("vector2").unwrap(),
+                LockedSegment::Proxy(_) => unreachable!(),
+            }
+        }
+
+        // Indexed segment still holds the data of vector2 on disk, it must be rebuilt
+        let suggested_to_optimize =
+            config_mismatch_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested_to_optimize.len(), 1);
+        let changed = config_mismatch_optimizer
+            .optimize(locked_holder.clone(), suggested_to_optimize, &false.into())
+            .unwrap();
+        assert!(changed, "optimizer should have rebuilt this segment");
+
+        locked_holder
+            .read()
+            .iter()
+            .map(|(_, segment)| match segment {
+                LockedSegment::Original(s) => s.read(),
+                LockedSegment::Proxy(_) => unreachable!(),
+            })
+            .filter(|segment| segment.total_point_count() > 0)
+            .for_each(|segment| {
+                assert!(!Segment::has_dropped_vector_data(&segment.data_path()));
+                assert!(!segment.config().vector_data.contains_key("vector2"));
+                assert_eq!(segment.total_point_count(), point_count as usize);
+            });
+    }
+
     /// This test the config mismatch optimizer for a changed vector specific HNSW config
     ///
     /// Similar to `test_hnsw_config_mismatch` but for multi vector segment with a vector specific
@@ -564,6 +702,7 @@ mod tests {
             max_segment_size: std::usize::MAX,
             memmap_threshold: std::usize::MAX,
             indexing_threshold: 10,
+            defragmentation_key: None,
         };
         let quantization_config_vector1 =
             QuantizationConfig::Scalar(segment::types::ScalarQuantization {
@@ -583,6 +722,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: Some(quantization_config_vector1.clone()),
                         on_disk: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
                 (
@@ -593,6 +734,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
             ])),
@@ -302,6 +302,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 )
             })
@@ -312,6 +314,7 @@ mod tests {
                 max_segment_size: 300,
                 memmap_threshold: 1000,
                 indexing_threshold: 1000,
+                defragmentation_key: None,
             },
             segments_dir.path().to_owned(),
             segments_temp_dir.path().to_owned(),
@@ -404,6 +407,7 @@ mod tests {
                 max_segment_size: 300,
                 memmap_threshold: 1000,
                 indexing_threshold: 1000,
+                defragmentation_key: None,
             },
             segments_dir.path().to_owned(),
             segments_temp_dir.path().to_owned(),
@@ -417,6 +421,8 @@ mod tests {
                     hnsw_config: None,
                     quantization_config: None,
                     on_disk: None,
+                    multivector_config: None,
+                    datatype: None,
                 }),
                 ..CollectionParams::empty()
             },
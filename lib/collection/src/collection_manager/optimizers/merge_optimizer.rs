@@ -16,6 +16,7 @@ use crate::collection_manager::optimizers::segment_optimizer::{
     OptimizerThresholds, SegmentOptimizer,
 };
 use crate::config::CollectionParams;
+use crate::optimizers_builder::TieredMergePolicy;
 
 const BYTES_IN_KB: usize = 1024;
 
@@ -23,8 +24,11 @@ const BYTES_IN_KB: usize = 1024;
 /// It merges 3 smallest segments into a single large segment.
 /// Merging 3 segments instead of 2 guarantees that after the optimization the number of segments
 /// will be less than before.
+///
+/// If a merge policy is configured, segments are merged by size tiers instead.
 pub struct MergeOptimizer {
     max_segments: usize,
+    merge_policy: Option<TieredMergePolicy>,
     thresholds_config: OptimizerThresholds,
     segments_path: PathBuf,
     collection_temp_dir: PathBuf,
@@ -38,6 +42,7 @@ impl MergeOptimizer {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         max_segments: usize,
+        merge_policy: Option<TieredMergePolicy>,
         thresholds_config: OptimizerThresholds,
         segments_path: PathBuf,
         collection_temp_dir: PathBuf,
@@ -47,6 +52,7 @@ impl MergeOptimizer {
     ) -> Self {
         MergeOptimizer {
             max_segments,
+            merge_policy,
             thresholds_config,
             segments_path,
             collection_temp_dir,
@@ -56,6 +62,43 @@ impl MergeOptimizer {
             telemetry_durations_aggregator: OperationDurationsAggregator::new(),
         }
     }
+
+    /// Smallest segments of the lowest tier, which has too many segments
+    fn tiered_candidates(
+        &self,
+        merge_policy: &TieredMergePolicy,
+        sorted_segment_sizes: &[(SegmentId, usize)],
+    ) -> Vec<SegmentId> {
+        let max_segment_size = self
+            .thresholds_config
+            .max_segment_size
+            .saturating_mul(BYTES_IN_KB);
+
+        let tiers = sorted_segment_sizes
+            .iter()
+            .group_by(|(_, size)| merge_policy.tier(size / BYTES_IN_KB));
+        for (_tier, segments) in &tiers {
+            let segments = segments.collect_vec();
+            if segments.len() < merge_policy.max_segments_per_tier {
+                continue;
+            }
+            let candidates: Vec<_> = segments
+                .into_iter()
+                .scan(0, |size_sum, (sid, size)| {
+                    *size_sum += size;
+                    Some((*sid, *size_sum))
+                })
+                .take_while(|(_, size_sum)| *size_sum < max_segment_size)
+                .take(merge_policy.merge_factor)
+                .map(|(sid, _)| sid)
+                .collect();
+            // Same as for the default merging, 3 segments guarantee less segments after merge
+            if candidates.len() >= 3 {
+                return candidates;
+            }
+        }
+        vec![]
+    }
 }
 
 impl SegmentOptimizer for MergeOptimizer {
@@ -101,15 +144,11 @@ impl SegmentOptimizer for MergeOptimizer {
             })
             .collect_vec();
 
-        if raw_segments.len() <= self.max_segments {
+        if self.merge_policy.is_none() && raw_segments.len() <= self.max_segments {
             return vec![];
         }
-        let max_candidates = raw_segments.len() - self.max_segments + 2;
 
-        // Find at least top-3 smallest segments to join.
-        // We need 3 segments because in this case we can guarantee that total segments number will be less
-
-        let candidates: Vec<_> = raw_segments
+        let sorted_segment_sizes: Vec<_> = raw_segments
             .iter()
             .cloned()
             .filter_map(|(idx, segment)| {
@@ -128,6 +167,23 @@ impl SegmentOptimizer for MergeOptimizer {
                 ))
             })
             .sorted_by_key(|(_, size)| *size)
+            .collect();
+
+        if let Some(merge_policy) = &self.merge_policy {
+            let candidates = self.tiered_candidates(merge_policy, &sorted_segment_sizes);
+            if !candidates.is_empty() {
+                log::debug!("Merge candidates: {:?}", candidates);
+            }
+            return candidates;
+        }
+
+        let max_candidates = raw_segments.len() - self.max_segments + 2;
+
+        // Find at least top-3 smallest segments to join.
+        // We need 3 segments because in this case we can guarantee that total segments number will be less
+
+        let candidates: Vec<_> = sorted_segment_sizes
+            .into_iter()
             .scan(0, |size_sum, (sid, size)| {
                 *size_sum += size; // produce a cumulative sum of segment sizes starting from smallest
                 Some((sid, *size_sum))
@@ -170,6 +226,8 @@ mod tests {
     use super::*;
     use crate::collection_manager::fixtures::{get_merge_optimizer, random_segment};
     use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+    use crate::collection_manager::optimizers::{TrackerHandle, TrackerState};
+    use crate::update_handler::{Optimizer, UpdateHandler};
 
     #[test]
     fn test_max_merge_size() {
@@ -205,6 +263,106 @@ mod tests {
         assert_eq!(check_result.len(), 3);
     }
 
+    #[test]
+    fn test_pending_optimizations() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+        let mut holder = SegmentHolder::default();
+        let dim = 256;
+
+        let mut segments = [
+            holder.add(random_segment(dir.path(), 100, 40, dim)),
+            holder.add(random_segment(dir.path(), 100, 50, dim)),
+            holder.add(random_segment(dir.path(), 100, 60, dim)),
+        ];
+
+        let mut merge_optimizer = get_merge_optimizer(dir.path(), temp_dir.path(), dim);
+        merge_optimizer.max_segments = 1;
+        merge_optimizer.thresholds_config.max_segment_size = 100;
+
+        let locked_holder = Arc::new(RwLock::new(holder));
+        let optimizers: Vec<Arc<Optimizer>> = vec![Arc::new(merge_optimizer)];
+
+        // Merged segment would be too large, nothing to do
+        assert!(
+            UpdateHandler::pending_optimizations(&optimizers, locked_holder.clone()).is_empty()
+        );
+
+        let mut merge_optimizer = get_merge_optimizer(dir.path(), temp_dir.path(), dim);
+        merge_optimizer.max_segments = 1;
+        merge_optimizer.thresholds_config.max_segment_size = 200;
+        let optimizers: Vec<Arc<Optimizer>> = vec![Arc::new(merge_optimizer)];
+
+        // Every segment is listed in a single pending optimization only
+        let pending = UpdateHandler::pending_optimizations(&optimizers, locked_holder.clone());
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].name, "merge");
+        let mut pending_ids = pending[0].segment_ids.clone();
+        pending_ids.sort_unstable();
+        segments.sort_unstable();
+        assert_eq!(pending_ids, segments.to_vec());
+
+        // Pending optimizations are not launched
+        assert_eq!(locked_holder.read().len(), 3);
+    }
+
+    #[test]
+    fn test_tiered_merge_policy() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+        let mut holder = SegmentHolder::default();
+        // 1 point takes 1 Kb
+        let dim = 256;
+
+        let _small_segments = [
+            holder.add(random_segment(dir.path(), 100, 3, dim)),
+            holder.add(random_segment(dir.path(), 100, 5, dim)),
+        ];
+        let medium_segments = [
+            holder.add(random_segment(dir.path(), 100, 20, dim)),
+            holder.add(random_segment(dir.path(), 100, 25, dim)),
+            holder.add(random_segment(dir.path(), 100, 30, dim)),
+            holder.add(random_segment(dir.path(), 100, 35, dim)),
+        ];
+
+        let mut merge_optimizer = get_merge_optimizer(dir.path(), temp_dir.path(), dim);
+        merge_optimizer.max_segments = 100;
+        let locked_holder: Arc<RwLock<_>> = Arc::new(RwLock::new(holder));
+
+        // Number of segments is below the limit, default merging does nothing
+        assert!(merge_optimizer
+            .check_condition(locked_holder.clone(), &Default::default())
+            .is_empty());
+
+        let merge_policy = TieredMergePolicy {
+            floor_segment_size_kb: 10,
+            max_segments_per_tier: 3,
+            merge_factor: 4,
+        };
+        assert_eq!(merge_policy.tier(5), 0);
+        assert_eq!(merge_policy.tier(35), 1);
+        assert_eq!(merge_policy.tier(41), 2);
+        merge_optimizer.merge_policy = Some(merge_policy);
+
+        // Smallest tier has not enough segments, the next one is merged
+        let candidates =
+            merge_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(candidates, medium_segments.to_vec());
+
+        // Merged segment doesn't exceed the max segment size
+        merge_optimizer.thresholds_config.max_segment_size = 80;
+        let candidates =
+            merge_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(candidates, medium_segments[..3].to_vec());
+
+        merge_optimizer.thresholds_config.max_segment_size = 60;
+        assert!(merge_optimizer
+            .check_condition(locked_holder, &Default::default())
+            .is_empty());
+    }
+
     #[test]
     fn test_merge_optimizer() {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -247,14 +405,22 @@ mod tests {
             })
             .collect_vec();
 
+        let tracker_state = Arc::new(Mutex::new(TrackerState::default()));
         merge_optimizer
-            .optimize(
+            .optimize_with_progress(
                 locked_holder.clone(),
                 suggested_for_merge,
+                &TrackerHandle::from(tracker_state.clone()),
                 &AtomicBool::new(false),
             )
             .unwrap();
 
+        let progress = tracker_state.lock().progress.clone();
+        assert_eq!(progress.segments_done, 4);
+        assert_eq!(progress.points_done, 3 * 3 + 10);
+        assert_eq!(progress.points_total, 3 * 3 + 10);
+        assert!(progress.indexing);
+
         let after_optimization_segments =
             locked_holder.read().iter().map(|(x, _)| *x).collect_vec();
 
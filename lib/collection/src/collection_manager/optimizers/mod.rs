@@ -11,6 +11,7 @@ use super::holders::segment_holder::SegmentId;
 pub mod config_mismatch_optimizer;
 pub mod indexing_optimizer;
 pub mod merge_optimizer;
+pub mod rebuild_index_optimizer;
 pub mod segment_optimizer;
 pub mod vacuum_optimizer;
 
@@ -57,6 +58,24 @@ impl TrackerLog {
         });
     }
 
+    /// Optimizations, which are still running
+    pub fn running(&self) -> Vec<TrackerTelemetry> {
+        self.descriptions
+            .iter()
+            .filter(|tracker| tracker.state.lock().status == TrackerStatus::Optimizing)
+            .map(Tracker::to_telemetry)
+            .collect()
+    }
+
+    /// Latest optimization, which failed
+    pub fn last_error(&self) -> Option<TrackerTelemetry> {
+        self.descriptions
+            .iter()
+            .rev()
+            .find(|tracker| matches!(tracker.state.lock().status, TrackerStatus::Error(_)))
+            .map(Tracker::to_telemetry)
+    }
+
     /// Convert log into list of objects usable in telemetry
     pub fn to_telemetry(&self) -> Vec<TrackerTelemetry> {
         self.descriptions
@@ -104,6 +123,7 @@ impl Tracker {
             name: self.name.clone(),
             segment_ids: self.segment_ids.clone(),
             status: state.status.clone(),
+            progress: state.progress.clone(),
             start_at: self.start_at,
             end_at: state.end_at,
         }
@@ -119,14 +139,26 @@ pub struct TrackerTelemetry {
     pub segment_ids: Vec<SegmentId>,
     /// Latest status of the optimizer
     pub status: TrackerStatus,
+    /// Progress of the optimization
+    #[serde(default)]
+    pub progress: TrackerProgress,
     /// Start time of the optimizer
     pub start_at: DateTime<Utc>,
     /// End time of the optimizer
     pub end_at: Option<DateTime<Utc>>,
 }
 
+/// Optimization, which is required by the optimizer conditions, but not launched yet
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, PartialEq, Eq)]
+pub struct PendingOptimization {
+    /// Name of the optimizer
+    pub name: String,
+    /// Segment IDs to optimize
+    pub segment_ids: Vec<SegmentId>,
+}
+
 /// Handle to an optimizer tracker, allows updating its state
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct TrackerHandle {
     handle: Arc<Mutex<TrackerState>>,
 }
@@ -135,6 +167,10 @@ impl TrackerHandle {
     pub fn update(&self, status: TrackerStatus) {
         self.handle.lock().update(status);
     }
+
+    pub fn update_progress(&self, update: impl FnOnce(&mut TrackerProgress)) {
+        update(&mut self.handle.lock().progress);
+    }
 }
 
 impl From<Arc<Mutex<TrackerState>>> for TrackerHandle {
@@ -147,6 +183,7 @@ impl From<Arc<Mutex<TrackerState>>> for TrackerHandle {
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 pub struct TrackerState {
     pub status: TrackerStatus,
+    pub progress: TrackerProgress,
     pub end_at: Option<DateTime<Utc>>,
 }
 
@@ -165,6 +202,22 @@ impl TrackerState {
     }
 }
 
+/// Progress of an optimization.
+///
+/// Points of the optimized segments are copied into a new segment first,
+/// indexes of the new segment are built after that.
+#[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, Eq, PartialEq)]
+pub struct TrackerProgress {
+    /// Number of optimized segments, which points are copied into the new segment
+    pub segments_done: usize,
+    /// Number of points, copied into the new segment
+    pub points_done: usize,
+    /// Number of points in the optimized segments
+    pub points_total: usize,
+    /// All points are copied and indexes of the new segment are being built
+    pub indexing: bool,
+}
+
 /// Represents the current state of the optimizer being tracked
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default, Eq, PartialEq, Hash)]
 #[serde(rename_all = "lowercase")]
@@ -175,3 +228,99 @@ pub enum TrackerStatus {
     Cancelled(String),
     Error(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+
+    use super::*;
+    use crate::operations::types::OptimizationsInfo;
+
+    #[test]
+    fn test_tracker_log_running_and_last_error() {
+        let mut log = TrackerLog::default();
+        assert!(log.running().is_empty());
+        assert!(log.last_error().is_none());
+
+        let failed = Tracker::start("merge", vec![1, 2]);
+        failed
+            .handle()
+            .update(TrackerStatus::Error("first".to_string()));
+        log.register(failed);
+
+        let done = Tracker::start("vacuum", vec![3]);
+        done.handle().update(TrackerStatus::Done);
+        log.register(done);
+
+        let failed_again = Tracker::start("indexing", vec![4]);
+        failed_again
+            .handle()
+            .update(TrackerStatus::Error("second".to_string()));
+        log.register(failed_again);
+
+        let running = Tracker::start("merge", vec![5, 6]);
+        running.handle().update_progress(|progress| {
+            progress.segments_done = 1;
+            progress.points_done = 10;
+            progress.points_total = 20;
+        });
+        log.register(running);
+
+        let running = log.running();
+        assert_eq!(running.len(), 1);
+        assert_eq!(running[0].segment_ids, vec![5, 6]);
+        assert_eq!(running[0].progress.points_done, 10);
+        assert_eq!(running[0].progress.points_total, 20);
+        assert!(!running[0].progress.indexing);
+
+        let last_error = log.last_error().unwrap();
+        assert_eq!(last_error.name, "indexing");
+        assert_eq!(
+            last_error.status,
+            TrackerStatus::Error("second".to_string())
+        );
+        assert!(last_error.end_at.is_some());
+    }
+
+    #[test]
+    fn test_optimizations_info_merge() {
+        let telemetry = |name: &str, start_at: DateTime<Utc>| TrackerTelemetry {
+            name: name.to_string(),
+            segment_ids: vec![],
+            status: TrackerStatus::Error(name.to_string()),
+            progress: TrackerProgress::default(),
+            start_at,
+            end_at: None,
+        };
+        let pending = |name: &str| PendingOptimization {
+            name: name.to_string(),
+            segment_ids: vec![1],
+        };
+        let now = Utc::now();
+
+        let mut info = OptimizationsInfo {
+            running: vec![telemetry("merge", now)],
+            pending: vec![pending("vacuum")],
+            last_error: Some(telemetry("older", now - Duration::seconds(10))),
+        };
+        info.merge(OptimizationsInfo {
+            running: vec![telemetry("indexing", now)],
+            pending: vec![pending("merge")],
+            last_error: Some(telemetry("newer", now)),
+        });
+        assert_eq!(info.running.len(), 2);
+        assert_eq!(info.pending, vec![pending("vacuum"), pending("merge")]);
+        assert_eq!(info.last_error.as_ref().unwrap().name, "newer");
+
+        // Shards without errors keep the latest known one
+        info.merge(OptimizationsInfo::default());
+        assert_eq!(info.last_error.as_ref().unwrap().name, "newer");
+
+        let mut info = OptimizationsInfo::default();
+        info.merge(OptimizationsInfo {
+            last_error: Some(telemetry("only", now)),
+            ..Default::default()
+        });
+        assert_eq!(info.last_error.unwrap().name, "only");
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use segment::common::operation_time_statistics::{
+    OperationDurationStatistics, OperationDurationsAggregator,
+};
+use segment::entry::entry_point::SegmentEntry;
+use segment::types::{HnswConfig, Indexes, PayloadKeyType, QuantizationConfig, SegmentType};
+
+use crate::collection_manager::holders::segment_holder::{
+    LockedSegment, LockedSegmentHolder, SegmentId,
+};
+use crate::collection_manager::optimizers::segment_optimizer::{
+    OptimizerThresholds, SegmentOptimizer,
+};
+use crate::config::CollectionParams;
+use crate::optimizers_builder::RebuildIndexTarget;
+
+/// Rebuilds segments from scratch, so their vector and payload indexes are built anew.
+///
+/// Unlike other optimizers, it doesn't look at the segment state and is only launched on request,
+/// e.g. after the index parameters were changed or an index is suspected to be corrupted.
+/// Segments are rebuilt one at a time, see [`RebuildIndexOptimizer::for_segment`].
+#[derive(Clone)]
+pub struct RebuildIndexOptimizer {
+    target: RebuildIndexTarget,
+    /// Rebuild only the segments, which have an index on this payload field
+    field_name: Option<PayloadKeyType>,
+    /// Rebuild only this segment, if set
+    segment_id: Option<SegmentId>,
+    thresholds_config: OptimizerThresholds,
+    segments_path: PathBuf,
+    collection_temp_dir: PathBuf,
+    collection_params: CollectionParams,
+    hnsw_config: HnswConfig,
+    quantization_config: Option<QuantizationConfig>,
+    telemetry_durations_aggregator: Arc<Mutex<OperationDurationsAggregator>>,
+}
+
+impl RebuildIndexOptimizer {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        target: RebuildIndexTarget,
+        field_name: Option<PayloadKeyType>,
+        thresholds_config: OptimizerThresholds,
+        segments_path: PathBuf,
+        collection_temp_dir: PathBuf,
+        collection_params: CollectionParams,
+        hnsw_config: HnswConfig,
+        quantization_config: Option<QuantizationConfig>,
+    ) -> Self {
+        RebuildIndexOptimizer {
+            target,
+            field_name,
+            segment_id: None,
+            thresholds_config,
+            segments_path,
+            collection_temp_dir,
+            collection_params,
+            hnsw_config,
+            quantization_config,
+            telemetry_durations_aggregator: OperationDurationsAggregator::new(),
+        }
+    }
+
+    /// Same optimizer, which rebuilds only the given segment
+    pub fn for_segment(&self, segment_id: SegmentId) -> Self {
+        RebuildIndexOptimizer {
+            segment_id: Some(segment_id),
+            ..self.clone()
+        }
+    }
+
+    /// All segments, which have indexes of the target to rebuild
+    pub fn candidates(
+        &self,
+        segments: LockedSegmentHolder,
+        excluded_ids: &HashSet<SegmentId>,
+    ) -> Vec<SegmentId> {
+        let segments_read_guard = segments.read();
+        segments_read_guard
+            .iter()
+            .filter(|(idx, _)| {
+                self.segment_id
+                    .map_or(true, |segment_id| segment_id == **idx)
+            })
+            // Excluded externally, might already be scheduled for optimization
+            .filter(|(idx, _)| !excluded_ids.contains(idx))
+            .filter_map(|(idx, segment)| {
+                // Segments under optimization are rebuilt by the running optimization anyway
+                let LockedSegment::Original(segment) = segment else {
+                    return None;
+                };
+                let read_segment = segment.read();
+                if read_segment.segment_type() == SegmentType::Special
+                    || read_segment.available_point_count() == 0
+                {
+                    return None;
+                }
+
+                let indexed_fields = read_segment.get_indexed_fields();
+                let has_vector_index = read_segment
+                    .config()
+                    .vector_data
+                    .values()
+                    .any(|vector_data| matches!(vector_data.index, Indexes::Hnsw(_)));
+
+                let is_candidate = match (self.target, &self.field_name) {
+                    (_, Some(field_name)) => indexed_fields.contains_key(field_name),
+                    (RebuildIndexTarget::All, None) => true,
+                    (RebuildIndexTarget::Vectors, None) => has_vector_index,
+                    (RebuildIndexTarget::Payload, None) => !indexed_fields.is_empty(),
+                };
+                is_candidate.then_some(*idx)
+            })
+            .collect()
+    }
+}
+
+impl SegmentOptimizer for RebuildIndexOptimizer {
+    fn name(&self) -> &str {
+        "rebuild index"
+    }
+
+    fn collection_path(&self) -> &Path {
+        self.segments_path.as_path()
+    }
+
+    fn temp_path(&self) -> &Path {
+        self.collection_temp_dir.as_path()
+    }
+
+    fn collection_params(&self) -> CollectionParams {
+        self.collection_params.clone()
+    }
+
+    fn hnsw_config(&self) -> &HnswConfig {
+        &self.hnsw_config
+    }
+
+    fn quantization_config(&self) -> Option<QuantizationConfig> {
+        self.quantization_config.clone()
+    }
+
+    fn threshold_config(&self) -> &OptimizerThresholds {
+        &self.thresholds_config
+    }
+
+    fn check_condition(
+        &self,
+        segments: LockedSegmentHolder,
+        excluded_ids: &HashSet<SegmentId>,
+    ) -> Vec<SegmentId> {
+        // Rebuild a single segment per optimization
+        self.candidates(segments, excluded_ids)
+            .into_iter()
+            .take(1)
+            .collect()
+    }
+
+    fn get_telemetry_data(&self) -> OperationDurationStatistics {
+        self.get_telemetry_counter().lock().get_statistics()
+    }
+
+    fn get_telemetry_counter(&self) -> Arc<Mutex<OperationDurationsAggregator>> {
+        self.telemetry_durations_aggregator.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use parking_lot::RwLock;
+    use segment::types::{Distance, PayloadSchemaType};
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::collection_manager::fixtures::{empty_segment, random_segment};
+    use crate::collection_manager::holders::segment_holder::SegmentHolder;
+    use crate::collection_manager::optimizers::indexing_optimizer::IndexingOptimizer;
+    use crate::operations::types::{VectorParams, VectorsConfig};
+
+    #[test]
+    fn test_rebuild_index_candidates() {
+        let dim = 10;
+        let thresholds_config = OptimizerThresholds {
+            max_segment_size: std::usize::MAX,
+            memmap_threshold: std::usize::MAX,
+            indexing_threshold: 10,
+            defragmentation_key: None,
+        };
+        let collection_params = CollectionParams {
+            vectors: VectorsConfig::Single(VectorParams {
+                size: dim.try_into().unwrap(),
+                distance: Distance::Dot,
+                hnsw_config: None,
+                quantization_config: None,
+                on_disk: None,
+                multivector_config: None,
+                datatype: None,
+            }),
+            ..CollectionParams::empty()
+        };
+
+        let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let mut holder = SegmentHolder::default();
+        let large_segment_id = holder.add(random_segment(dir.path(), 100, 1000, dim as usize));
+        let mut payload_segment = random_segment(dir.path(), 100, 100, dim as usize);
+        payload_segment
+            .create_field_index(101, "color", Some(&PayloadSchemaType::Keyword.into()))
+            .unwrap();
+        let payload_segment_id = holder.add(payload_segment);
+        holder.add(empty_segment(dir.path()));
+        let locked_holder: LockedSegmentHolder = Arc::new(RwLock::new(holder));
+
+        // Build HNSW index for the large segment
+        let index_optimizer = IndexingOptimizer::new(
+            thresholds_config.clone(),
+            dir.path().to_owned(),
+            temp_dir.path().to_owned(),
+            collection_params.clone(),
+            Default::default(),
+            Default::default(),
+        );
+        index_optimizer
+            .optimize(locked_holder.clone(), vec![large_segment_id], &false.into())
+            .unwrap();
+        let indexed_segment_id = locked_holder
+            .read()
+            .iter()
+            .find(|(_, segment)| segment.get().read().available_point_count() == 1000)
+            .map(|(idx, _)| *idx)
+            .unwrap();
+
+        let optimizer = |target, field_name: Option<&str>| {
+            RebuildIndexOptimizer::new(
+                target,
+                field_name.map(ToString::to_string),
+                thresholds_config.clone(),
+                dir.path().to_owned(),
+                temp_dir.path().to_owned(),
+                collection_params.clone(),
+                Default::default(),
+                Default::default(),
+            )
+        };
+        let candidates = |optimizer: &RebuildIndexOptimizer| {
+            let mut candidates = optimizer.candidates(locked_holder.clone(), &HashSet::new());
+            candidates.sort();
+            candidates
+        };
+
+        // Empty segments are never rebuilt
+        assert_eq!(
+            candidates(&optimizer(RebuildIndexTarget::All, None)),
+            vec![payload_segment_id, indexed_segment_id],
+        );
+        assert_eq!(
+            candidates(&optimizer(RebuildIndexTarget::Vectors, None)),
+            vec![indexed_segment_id],
+        );
+        assert_eq!(
+            candidates(&optimizer(RebuildIndexTarget::Payload, None)),
+            vec![payload_segment_id],
+        );
+        assert_eq!(
+            candidates(&optimizer(RebuildIndexTarget::Payload, Some("color"))),
+            vec![payload_segment_id],
+        );
+        assert!(candidates(&optimizer(RebuildIndexTarget::Payload, Some("size"))).is_empty());
+
+        // A single segment is rebuilt per optimization
+        let all_optimizer = optimizer(RebuildIndexTarget::All, None);
+        assert_eq!(
+            all_optimizer
+                .check_condition(locked_holder.clone(), &Default::default())
+                .len(),
+            1,
+        );
+        let segment_optimizer = all_optimizer.for_segment(payload_segment_id);
+        let suggested =
+            segment_optimizer.check_condition(locked_holder.clone(), &Default::default());
+        assert_eq!(suggested, vec![payload_segment_id]);
+
+        let changed = segment_optimizer
+            .optimize(locked_holder.clone(), suggested, &false.into())
+            .unwrap();
+        assert!(changed, "optimizer should have rebuilt this segment");
+        assert!(locked_holder.read().get(payload_segment_id).is_none());
+
+        // Rebuilt segment keeps its points and payload index
+        let rebuilt = candidates(&optimizer(RebuildIndexTarget::Payload, Some("color")));
+        assert_eq!(rebuilt.len(), 1);
+        let rebuilt_segment = locked_holder.read().get(rebuilt[0]).unwrap().get();
+        assert_eq!(rebuilt_segment.read().available_point_count(), 100);
+    }
+}
@@ -1,4 +1,5 @@
 use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
 use std::path::Path;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -23,6 +24,7 @@ use crate::collection_manager::holders::proxy_segment::ProxySegment;
 use crate::collection_manager::holders::segment_holder::{
     LockedSegment, LockedSegmentHolder, SegmentId,
 };
+use crate::collection_manager::optimizers::TrackerHandle;
 use crate::config::CollectionParams;
 use crate::operations::config_diff::DiffConfig;
 use crate::operations::types::{CollectionError, CollectionResult};
@@ -34,6 +36,8 @@ pub struct OptimizerThresholds {
     pub max_segment_size: usize,
     pub memmap_threshold: usize,
     pub indexing_threshold: usize,
+    /// Points of optimized segments are grouped by the value of this payload key
+    pub defragmentation_key: Option<PayloadKeyType>,
 }
 
 /// SegmentOptimizer - trait implementing common functionality of the optimizers
@@ -82,6 +86,7 @@ pub trait SegmentOptimizer {
         let collection_params = self.collection_params();
         let config = SegmentConfig {
             vector_data: collection_params.into_base_vector_data()?,
+            sparse_vector_data: collection_params.into_sparse_vector_data()?,
             payload_storage_type: if collection_params.on_disk_payload {
                 PayloadStorageType::OnDisk
             } else {
@@ -171,6 +176,9 @@ pub trait SegmentOptimizer {
                 config.index = Indexes::Hnsw(vector_hnsw);
 
                 // Assign quantization config
+                if !config.supports_quantization() {
+                    return;
+                }
                 let param_quantization = collection_params
                     .vectors
                     .get_params(vector_name)
@@ -184,13 +192,17 @@ pub trait SegmentOptimizer {
 
         // If storing on disk, set storage type
         if is_on_disk {
-            vector_data.values_mut().for_each(|config| {
-                config.storage_type = VectorStorageType::Mmap;
-            });
+            vector_data
+                .values_mut()
+                .filter(|config| config.supports_on_disk())
+                .for_each(|config| {
+                    config.storage_type = VectorStorageType::Mmap;
+                });
         }
 
         let optimized_config = SegmentConfig {
             vector_data,
+            sparse_vector_data: collection_params.into_sparse_vector_data()?,
             payload_storage_type: if collection_params.on_disk_payload {
                 PayloadStorageType::OnDisk
             } else {
@@ -198,11 +210,10 @@ pub trait SegmentOptimizer {
             },
         };
 
-        Ok(SegmentBuilder::new(
-            self.collection_path(),
-            self.temp_path(),
-            &optimized_config,
-        )?)
+        let mut segment_builder =
+            SegmentBuilder::new(self.collection_path(), self.temp_path(), &optimized_config)?;
+        segment_builder.defragmentation_key = self.threshold_config().defragmentation_key.clone();
+        Ok(segment_builder)
     }
 
     /// Restores original segments from proxies
@@ -290,6 +301,7 @@ pub trait SegmentOptimizer {
     /// * `proxy_deleted_points` - Holds a set of points, deleted while optimization was running
     /// * `proxy_deleted_indexes` - Holds a set of Indexes, deleted while optimization was running
     /// * `proxy_created_indexes` - Holds a set of Indexes, created while optimization was running
+    /// * `progress` - handle to report progress of the optimization
     /// * `stopped` - flag to check if optimization was cancelled by external thread
     ///
     /// # Result
@@ -301,21 +313,34 @@ pub trait SegmentOptimizer {
         proxy_deleted_points: Arc<RwLock<HashSet<PointIdType>>>,
         proxy_deleted_indexes: Arc<RwLock<HashSet<PayloadKeyType>>>,
         proxy_created_indexes: Arc<RwLock<HashMap<PayloadKeyType, PayloadFieldSchema>>>,
+        progress: &TrackerHandle,
         stopped: &AtomicBool,
     ) -> CollectionResult<Segment> {
         let mut segment_builder = self.optimized_segment_builder(optimizing_segments)?;
 
         self.check_cancellation(stopped)?;
 
-        for segment in optimizing_segments {
-            match segment {
-                LockedSegment::Original(segment_arc) => {
-                    let segment_guard = segment_arc.read();
-                    segment_builder.update_from(&segment_guard, stopped)?;
-                }
+        let points_total = optimizing_segments
+            .iter()
+            .map(|segment| segment.get().read().available_point_count())
+            .sum();
+        progress.update_progress(|progress| progress.points_total = points_total);
+
+        // Points of all segments are copied at once, so they can be grouped by the defragmentation key
+        let segment_guards = optimizing_segments
+            .iter()
+            .map(|segment| match segment {
+                LockedSegment::Original(segment_arc) => segment_arc.read(),
                 LockedSegment::Proxy(_) => panic!("Attempt to optimize segment which is already currently under optimization. Should never happen"),
-            }
-        }
+            })
+            .collect_vec();
+        let segment_refs = segment_guards.iter().map(Deref::deref).collect_vec();
+        segment_builder.update(&segment_refs, stopped)?;
+        drop(segment_guards);
+        progress.update_progress(|progress| {
+            progress.segments_done = optimizing_segments.len();
+            progress.points_done = points_total;
+        });
 
         for field in proxy_deleted_indexes.read().iter() {
             segment_builder.indexed_fields.remove(field);
@@ -326,7 +351,9 @@ pub trait SegmentOptimizer {
                 .insert(field.to_owned(), schema_type.to_owned());
         }
 
+        progress.update_progress(|progress| progress.indexing = true);
         let mut optimized_segment: Segment = segment_builder.build(stopped)?;
+        optimized_segment.set_optimized_by(self.name())?;
 
         // Delete points in 2 steps
         // First step - delete all points with read lock
@@ -383,6 +410,17 @@ pub trait SegmentOptimizer {
         segments: LockedSegmentHolder,
         ids: Vec<SegmentId>,
         stopped: &AtomicBool,
+    ) -> CollectionResult<bool> {
+        self.optimize_with_progress(segments, ids, &TrackerHandle::default(), stopped)
+    }
+
+    /// Same as [`SegmentOptimizer::optimize`], reports progress of the optimization to `progress`
+    fn optimize_with_progress(
+        &self,
+        segments: LockedSegmentHolder,
+        ids: Vec<SegmentId>,
+        progress: &TrackerHandle,
+        stopped: &AtomicBool,
     ) -> CollectionResult<bool> {
         check_process_stopped(stopped)?;
 
@@ -479,6 +517,7 @@ pub trait SegmentOptimizer {
             proxy_deleted_points.clone(),
             proxy_deleted_indexes.clone(),
             proxy_created_indexes.clone(),
+            progress,
             stopped,
         ) {
             Ok(segment) => segment,
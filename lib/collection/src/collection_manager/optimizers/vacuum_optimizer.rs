@@ -300,6 +300,7 @@ mod tests {
                 max_segment_size: 1000000,
                 memmap_threshold: 1000000,
                 indexing_threshold: 1000000,
+                defragmentation_key: None,
             },
             dir.path().to_owned(),
             temp_dir.path().to_owned(),
@@ -310,6 +311,8 @@ mod tests {
                     hnsw_config: None,
                     quantization_config: None,
                     on_disk: None,
+                    multivector_config: None,
+                    datatype: None,
                 }),
                 ..CollectionParams::empty()
             },
@@ -389,6 +392,7 @@ mod tests {
             max_segment_size: std::usize::MAX,
             memmap_threshold: std::usize::MAX,
             indexing_threshold: 10,
+            defragmentation_key: None,
         };
         let collection_params = CollectionParams {
             vectors: VectorsConfig::Multi(BTreeMap::from([
@@ -400,6 +404,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
                 (
@@ -410,6 +416,8 @@ mod tests {
                         hnsw_config: None,
                         quantization_config: None,
                         on_disk: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
             ])),
@@ -1,14 +1,17 @@
 use std::collections::HashMap;
 use std::ops::Deref;
-use std::sync::atomic::AtomicBool;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use common::types::ScoreType;
 use futures::future::try_join_all;
 use itertools::Itertools;
 use ordered_float::Float;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use segment::common::full_scan_tracker::track_full_scan;
+use segment::common::hardware_counter::{measure_hardware_usage, HardwareUsage};
 use segment::common::operation_error::OperationError;
+use segment::common::search_explain::{explain_search, ExplainMode, SegmentSearchExplain};
 use segment::common::BYTES_IN_KB;
 use segment::data_types::named_vectors::NamedVectors;
 use segment::data_types::vectors::QueryVector;
@@ -19,14 +22,67 @@ use segment::types::{
 };
 use tokio::runtime::Handle;
 use tokio::task::JoinHandle;
+use tokio::time::Instant;
 
-use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder, SegmentId};
 use crate::collection_manager::probabilistic_segment_search_sampling::find_search_sampling_over_point_distribution;
 use crate::collection_manager::search_result_aggregator::BatchResultAggregator;
+use crate::common::hardware_usage::{is_hardware_usage_collected, record_hardware_usage};
+use crate::common::query_explain::{explain_mode, record_segment_explain};
+use crate::common::query_trace::record_full_scan;
 use crate::operations::types::{
     CollectionError, CollectionResult, CoreSearchRequestBatch, QueryEnum, Record,
 };
 
+/// Shared state of the searches in the segments of a single request
+struct SearchTracker {
+    /// Set if a search in any segment falls back to a full scan
+    full_scan: AtomicBool,
+    /// Resources used by the searches, only measured if requested
+    usage: Option<Mutex<HardwareUsage>>,
+    /// Explanations of the searches, only made if requested
+    explain: Option<(ExplainMode, Mutex<Vec<SegmentSearchExplain>>)>,
+}
+
+impl SearchTracker {
+    fn new(measure_usage: bool, explain: Option<ExplainMode>) -> Self {
+        Self {
+            full_scan: AtomicBool::new(false),
+            usage: measure_usage.then(Mutex::default),
+            explain: explain.map(|mode| (mode, Mutex::default())),
+        }
+    }
+
+    /// Run the search in a segment, keeping track of its full scan and resource usage,
+    /// and explaining it if requested
+    fn track<T>(&self, segment_id: SegmentId, search: impl FnOnce() -> T) -> T {
+        let Some((mode, explains)) = &self.explain else {
+            return self.track_usage(search);
+        };
+        let (result, searches) = explain_search(*mode, || self.track_usage(search));
+        explains.lock().push(SegmentSearchExplain {
+            segment_id,
+            searches,
+        });
+        result
+    }
+
+    fn track_usage<T>(&self, search: impl FnOnce() -> T) -> T {
+        let (result, full_scan) = match &self.usage {
+            Some(usage) => {
+                let (result, segment_usage) = measure_hardware_usage(|| track_full_scan(search));
+                *usage.lock() += segment_usage;
+                result
+            }
+            None => track_full_scan(search),
+        };
+        if full_scan {
+            self.full_scan.store(true, Ordering::Relaxed);
+        }
+        result
+    }
+}
+
 type BatchOffset = usize;
 type SegmentOffset = usize;
 
@@ -44,21 +100,50 @@ type SegmentSearchExecutedResult = CollectionResult<(SegmentBatchSearchResult, V
 pub struct SegmentsSearcher {}
 
 impl SegmentsSearcher {
+    /// Wait for the searches in the segments.
+    ///
+    /// Segments, which are not searched before the deadline, get empty results of `batch_size`
+    /// and the searches in them are stopped. Returns whether any segment is left out this way.
     async fn execute_searches(
         searches: Vec<JoinHandle<SegmentSearchExecutedResult>>,
-    ) -> CollectionResult<(BatchSearchResult, Vec<Vec<bool>>)> {
-        let searches = try_join_all(searches);
+        batch_size: usize,
+        deadline: Option<Instant>,
+        is_stopped: &AtomicBool,
+    ) -> CollectionResult<(BatchSearchResult, Vec<Vec<bool>>, bool)> {
+        let searches = try_join_all(searches.into_iter().map(|search| async move {
+            match deadline {
+                Some(deadline) => match tokio::time::timeout_at(deadline, search).await {
+                    Ok(result) => result.map(Some),
+                    Err(_elapsed) => Ok(None),
+                },
+                None => search.await.map(Some),
+            }
+        }));
         let search_results_per_segment_res = searches.await?;
 
         let mut search_results_per_segment = vec![];
         let mut further_searches_per_segment = vec![];
+        let mut partial = false;
         for search_result in search_results_per_segment_res {
+            let Some(search_result) = search_result else {
+                partial = true;
+                search_results_per_segment.push(vec![vec![]; batch_size]);
+                further_searches_per_segment.push(vec![false; batch_size]);
+                continue;
+            };
             let (search_results, further_searches) = search_result?;
             debug_assert!(search_results.len() == further_searches.len());
             search_results_per_segment.push(search_results);
             further_searches_per_segment.push(further_searches);
         }
-        Ok((search_results_per_segment, further_searches_per_segment))
+        if partial {
+            is_stopped.store(true, Ordering::Relaxed);
+        }
+        Ok((
+            search_results_per_segment,
+            further_searches_per_segment,
+            partial,
+        ))
     }
 
     /// Processes search result of [segment_size x batch_size]
@@ -159,6 +244,31 @@ impl SegmentsSearcher {
         is_stopped: Arc<AtomicBool>,
         indexing_threshold_kb: usize,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let (results, _partial) = Self::search_until(
+            segments,
+            batch_request,
+            runtime_handle,
+            sampling_enabled,
+            is_stopped,
+            indexing_threshold_kb,
+            None,
+        )
+        .await?;
+        Ok(results)
+    }
+
+    /// Search in the segments, leaving out the segments which are not searched before the deadline.
+    ///
+    /// Returns the results of the searched segments, along with the flag whether any segment is left out.
+    pub async fn search_until(
+        segments: Arc<RwLock<SegmentHolder>>,
+        batch_request: Arc<CoreSearchRequestBatch>,
+        runtime_handle: &Handle,
+        sampling_enabled: bool,
+        is_stopped: Arc<AtomicBool>,
+        indexing_threshold_kb: usize,
+        deadline: Option<Instant>,
+    ) -> CollectionResult<(Vec<Vec<ScoredPoint>>, bool)> {
         // Do blocking calls in a blocking task: `segment.get().read()` calls might block async runtime
         let task = {
             let segments = segments.clone();
@@ -180,9 +290,14 @@ impl SegmentsSearcher {
         };
 
         let Some(available_point_count) = task.await? else {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), false));
         };
 
+        let tracker = Arc::new(SearchTracker::new(
+            is_hardware_usage_collected(),
+            explain_mode(),
+        ));
+
         // Using block to ensure `segments` variable is dropped in the end of it
         let (locked_segments, searches): (Vec<_>, Vec<_>) = {
             // Unfortunately, we have to do `segments.read()` twice, once in blocking task
@@ -200,30 +315,40 @@ impl SegmentsSearcher {
 
             segments
                 .iter()
-                .map(|(_id, segment)| {
+                .map(|(id, segment)| {
                     let search = runtime_handle.spawn_blocking({
-                        let (segment, batch_request) = (segment.clone(), batch_request.clone());
+                        let (segment_id, segment) = (*id, segment.clone());
+                        let batch_request = batch_request.clone();
                         let is_stopped_clone = is_stopped.clone();
+                        let tracker = tracker.clone();
+                        // Blocking tasks don't inherit the span of the request
+                        #[cfg(feature = "tracing")]
+                        let span = tracing::Span::current();
                         move || {
-                            search_in_segment(
-                                segment,
-                                batch_request,
-                                available_point_count,
-                                use_sampling,
-                                &is_stopped_clone,
-                                indexing_threshold_kb,
-                            )
+                            #[cfg(feature = "tracing")]
+                            let _span = span.enter();
+                            tracker.track(segment_id, || {
+                                search_in_segment(
+                                    segment,
+                                    batch_request,
+                                    available_point_count,
+                                    use_sampling,
+                                    &is_stopped_clone,
+                                    indexing_threshold_kb,
+                                )
+                            })
                         }
                     });
-                    (segment.clone(), search)
+                    ((*id, segment.clone()), search)
                 })
                 .unzip()
         };
 
         // perform search on all segments concurrently
         // the resulting Vec is in the same order as the segment searches were provided.
-        let (all_search_results_per_segment, further_results) =
-            Self::execute_searches(searches).await?;
+        let batch_size = batch_request.searches.len();
+        let (all_search_results_per_segment, further_results, mut partial) =
+            Self::execute_searches(searches, batch_size, deadline, &is_stopped).await?;
         debug_assert!(all_search_results_per_segment.len() == locked_segments.len());
 
         let (mut result_aggregator, searches_to_rerun) = Self::process_search_result_step1(
@@ -237,7 +362,8 @@ impl SegmentsSearcher {
         );
         // The second step of the search is to re-run the search without sampling on some segments
         // Expected that this stage will be executed rarely
-        if !searches_to_rerun.is_empty() {
+        // It is skipped, if the deadline is already reached and the searches are stopped
+        if !partial && !searches_to_rerun.is_empty() {
             // TODO notify telemetry of failing sampling
             // Ensure consistent order of segment ids
             let searches_to_rerun: Vec<(SegmentOffset, Vec<BatchOffset>)> =
@@ -245,8 +371,8 @@ impl SegmentsSearcher {
 
             let secondary_searches: Vec<_> = {
                 let mut res = vec![];
-                for (segment_id, batch_ids) in searches_to_rerun.iter() {
-                    let segment = locked_segments[*segment_id].clone();
+                for (segment_offset, batch_ids) in searches_to_rerun.iter() {
+                    let (segment_id, segment) = locked_segments[*segment_offset].clone();
                     let partial_batch_request = Arc::new(CoreSearchRequestBatch {
                         searches: batch_ids
                             .iter()
@@ -254,22 +380,32 @@ impl SegmentsSearcher {
                             .collect(),
                     });
                     let is_stopped_clone = is_stopped.clone();
+                    let tracker = tracker.clone();
+                    #[cfg(feature = "tracing")]
+                    let span = tracing::Span::current();
                     res.push(runtime_handle.spawn_blocking(move || {
-                        search_in_segment(
-                            segment,
-                            partial_batch_request,
-                            0,
-                            false,
-                            &is_stopped_clone,
-                            indexing_threshold_kb,
-                        )
+                        #[cfg(feature = "tracing")]
+                        let _span = span.enter();
+                        tracker.track(segment_id, || {
+                            search_in_segment(
+                                segment,
+                                partial_batch_request,
+                                0,
+                                false,
+                                &is_stopped_clone,
+                                indexing_threshold_kb,
+                            )
+                        })
                     }))
                 }
                 res
             };
 
-            let (secondary_search_results_per_segment, _) =
-                Self::execute_searches(secondary_searches).await?;
+            // Segments left out here keep their results of the first step
+            let (secondary_search_results_per_segment, _, secondary_partial) =
+                Self::execute_searches(secondary_searches, batch_size, deadline, &is_stopped)
+                    .await?;
+            partial |= secondary_partial;
 
             result_aggregator.update_point_versions(&secondary_search_results_per_segment);
 
@@ -286,8 +422,25 @@ impl SegmentsSearcher {
             }
         }
 
+        if tracker.full_scan.load(Ordering::Relaxed) {
+            record_full_scan();
+        }
+        if let Some(usage) = &tracker.usage {
+            // Segments of the second step were already searched in the first one
+            let usage = HardwareUsage {
+                segments_searched: locked_segments.len() as u64,
+                ..*usage.lock()
+            };
+            record_hardware_usage(usage);
+        }
+        if let Some((_, explains)) = &tracker.explain {
+            for explain in explains.lock().drain(..) {
+                record_segment_explain(explain);
+            }
+        }
+
         let top_scores: Vec<_> = result_aggregator.into_topk();
-        Ok(top_scores)
+        Ok((top_scores, partial))
     }
 
     pub fn retrieve(
@@ -325,12 +478,13 @@ impl SegmentsSearcher {
                                 let mut selected_vectors = NamedVectors::default();
                                 for vector_name in vector_names {
                                     if let Some(vector) = segment.vector(vector_name, id)? {
-                                        selected_vectors.insert(vector_name.into(), vector);
+                                        selected_vectors.insert_vector(vector_name.into(), vector);
                                     }
                                 }
                                 Some(selected_vectors.into())
                             }
                         },
+                        order_value: None,
                     },
                 );
                 point_version.insert(id, version);
@@ -346,6 +500,10 @@ pub enum SearchType {
     #[default]
     Nearest,
     RecommendBestScore,
+    Discover,
+    Context,
+    Text,
+    Sample,
 }
 
 impl From<&QueryEnum> for SearchType {
@@ -353,6 +511,10 @@ impl From<&QueryEnum> for SearchType {
         match query {
             QueryEnum::Nearest(_) => Self::Nearest,
             QueryEnum::RecommendBestScore(_) => Self::RecommendBestScore,
+            QueryEnum::Discover(_) => Self::Discover,
+            QueryEnum::Context(_) => Self::Context,
+            QueryEnum::Text(_) => Self::Text,
+            QueryEnum::Sample(_) => Self::Sample,
         }
     }
 }
@@ -411,6 +573,7 @@ fn effective_limit(limit: usize, ef_limit: usize, poisson_sampling: usize) -> us
 /// Collection Result of:
 /// * Vector of ScoredPoints for each request in the batch
 /// * Vector of boolean indicating if the segment have further points to search
+#[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
 fn search_in_segment(
     segment: LockedSegment,
     request: Arc<CoreSearchRequestBatch>,
@@ -423,7 +586,7 @@ fn search_in_segment(
 
     let mut result: Vec<Vec<ScoredPoint>> = Vec::with_capacity(batch_size);
     let mut further_results: Vec<bool> = Vec::with_capacity(batch_size); // if segment have more points to return
-    let mut vectors_batch: Vec<QueryVector> = vec![];
+    let mut vectors_batch: Vec<QueryEnum> = vec![];
     let mut prev_params = BatchSearchParams::default();
 
     for search_query in &request.searches {
@@ -434,7 +597,8 @@ fn search_in_segment(
 
         let params = BatchSearchParams {
             search_type: search_query.query.as_ref().into(),
-            vector_name: search_query.query.get_vector_name(),
+            // Text and sample queries are not searched by vector
+            vector_name: search_query.query.get_vector_name().unwrap_or_default(),
             filter: search_query.filter.as_ref(),
             with_payload: WithPayload::from(with_payload_interface),
             with_vector: search_query.with_vector.clone().unwrap_or_default(),
@@ -442,7 +606,7 @@ fn search_in_segment(
             params: search_query.params.as_ref(),
         };
 
-        let query = search_query.query.clone().into();
+        let query = search_query.query.clone();
 
         // same params enables batching
         if params == prev_params {
@@ -490,20 +654,35 @@ fn search_in_segment(
 
 fn execute_batch_search(
     segment: &LockedSegment,
-    vectors_batch: &Vec<QueryVector>,
+    queries_batch: &[QueryEnum],
     search_params: &BatchSearchParams,
     use_sampling: bool,
     total_points: usize,
     is_stopped: &AtomicBool,
     indexing_threshold_kb: usize,
 ) -> CollectionResult<(Vec<Vec<ScoredPoint>>, Vec<bool>)> {
+    if search_params.search_type == SearchType::Text {
+        return execute_text_search(segment, queries_batch, search_params, is_stopped);
+    }
+    if search_params.search_type == SearchType::Sample {
+        return execute_sample_search(segment, queries_batch, search_params, is_stopped);
+    }
+    let vectors_batch: Vec<QueryVector> = queries_batch
+        .iter()
+        .cloned()
+        .map(QueryVector::try_from)
+        .collect::<CollectionResult<_>>()?;
+
     let locked_segment = segment.get();
     let read_segment = locked_segment.read();
 
     let segment_points = read_segment.available_point_count();
     let segment_config = read_segment.config();
 
-    let top = if use_sampling {
+    // Exact search must score all points of all segments, so it doesn't sample
+    let exact = search_params.params.map(|p| p.exact).unwrap_or(false);
+
+    let top = if use_sampling && !exact {
         let ef_limit = search_params
             .params
             .and_then(|p| p.hnsw_ef)
@@ -518,6 +697,7 @@ fn execute_batch_search(
         .map(|p| p.indexed_only)
         .unwrap_or(false);
     if ignore_plain_index
+        && !exact
         && !is_search_optimized(
             read_segment.deref(),
             indexing_threshold_kb,
@@ -547,12 +727,82 @@ fn execute_batch_search(
     Ok((res, further_results))
 }
 
+/// Text queries are scored by the payload index of the segment one by one
+fn execute_text_search(
+    segment: &LockedSegment,
+    queries_batch: &[QueryEnum],
+    search_params: &BatchSearchParams,
+    is_stopped: &AtomicBool,
+) -> CollectionResult<(Vec<Vec<ScoredPoint>>, Vec<bool>)> {
+    let locked_segment = segment.get();
+    let read_segment = locked_segment.read();
+
+    let mut res = Vec::with_capacity(queries_batch.len());
+    for query in queries_batch {
+        let QueryEnum::Text(text_query) = query else {
+            return Err(CollectionError::service_error(
+                "Vector query can't be searched by text",
+            ));
+        };
+        res.push(read_segment.search_text(
+            &text_query.key,
+            &text_query.text,
+            &search_params.with_payload,
+            &search_params.with_vector,
+            search_params.filter,
+            search_params.top,
+            is_stopped,
+        )?);
+    }
+
+    // Text search does not use sampling, so there is nothing to search further
+    let further_results = vec![false; res.len()];
+    Ok((res, further_results))
+}
+
+/// Points are sampled by the segment independently for each query of the batch
+fn execute_sample_search(
+    segment: &LockedSegment,
+    queries_batch: &[QueryEnum],
+    search_params: &BatchSearchParams,
+    is_stopped: &AtomicBool,
+) -> CollectionResult<(Vec<Vec<ScoredPoint>>, Vec<bool>)> {
+    let locked_segment = segment.get();
+    let read_segment = locked_segment.read();
+
+    let res = queries_batch
+        .iter()
+        .map(|_| {
+            read_segment.sample_points(
+                &search_params.with_payload,
+                &search_params.with_vector,
+                search_params.filter,
+                search_params.top,
+                is_stopped,
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // Full sample is taken from each segment, so there is nothing to search further
+    let further_results = vec![false; res.len()];
+    Ok((res, further_results))
+}
+
 /// Check if the segment is indexed enough to be searched with `indexed_only` parameter
 fn is_search_optimized(
     segment: &dyn SegmentEntry,
     indexing_threshold_kb: usize,
     vector_name: &str,
 ) -> CollectionResult<bool> {
+    // Sparse vectors are indexed on insertion
+    if segment
+        .config()
+        .sparse_vector_data
+        .contains_key(vector_name)
+    {
+        return Ok(true);
+    }
+
     let segment_info = segment.info();
     let vector_name_error =
         || CollectionError::bad_request(format!("Vector {} doesn't exist", vector_name));
@@ -610,6 +860,8 @@ fn get_hnsw_ef_construct(config: &SegmentConfig, vector_name: &str) -> Option<us
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use segment::fixtures::index_fixtures::random_vector;
     use segment::types::SegmentType;
     use tempfile::Builder;
@@ -695,6 +947,60 @@ mod tests {
         assert!(result[1].id == 3.into() || result[1].id == 11.into());
     }
 
+    #[tokio::test]
+    async fn test_segments_search_exact() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let segment_holder = Arc::new(build_test_holder(dir.path()));
+
+        let search = |params: Option<SearchParams>| {
+            let batch_request = CoreSearchRequestBatch {
+                searches: vec![CoreSearchRequest {
+                    query: vec![1.0, 1.0, 1.0, 1.0].into(),
+                    with_payload: None,
+                    with_vector: None,
+                    filter: None,
+                    params,
+                    limit: 5,
+                    score_threshold: None,
+                    offset: 0,
+                }],
+            };
+            SegmentsSearcher::search(
+                segment_holder.clone(),
+                Arc::new(batch_request),
+                &Handle::current(),
+                true,
+                Arc::new(AtomicBool::new(false)),
+                // None of the segments is indexed enough
+                0,
+            )
+        };
+
+        let plain = search(None).await.unwrap();
+        assert_eq!(plain[0].len(), 5);
+
+        let indexed_only = search(Some(SearchParams {
+            indexed_only: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+        assert!(indexed_only[0].is_empty());
+
+        // Exact search scores all segments anyway
+        let exact = search(Some(SearchParams {
+            exact: true,
+            indexed_only: true,
+            ..Default::default()
+        }))
+        .await
+        .unwrap();
+        // Different IDs may have same scores
+        let scores = |result: &[ScoredPoint]| result.iter().map(|point| point.score).collect_vec();
+        assert_eq!(scores(&exact[0]), scores(&plain[0]));
+    }
+
     #[tokio::test]
     async fn test_segments_search_sampling() {
         let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
@@ -721,6 +1027,7 @@ mod tests {
                 filter: None,
                 params: None,
                 score_threshold: None,
+                text: None,
             };
             let req2 = SearchRequest {
                 vector: random_vector(&mut rnd, 4).into(),
@@ -731,6 +1038,7 @@ mod tests {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                text: None,
             };
 
             let batch_request = CoreSearchRequestBatch {
@@ -791,6 +1099,44 @@ mod tests {
         assert_eq!(records.len(), 3);
     }
 
+    #[tokio::test]
+    async fn test_execute_searches_until_deadline() {
+        let point = ScoredPoint {
+            id: 1.into(),
+            version: 0,
+            score: 1.0,
+            payload: None,
+            vector: None,
+            explanation: None,
+        };
+        let (release, blocked) = std::sync::mpsc::channel::<()>();
+        let searches = vec![
+            tokio::task::spawn_blocking({
+                let point = point.clone();
+                move || Ok((vec![vec![point]], vec![true]))
+            }),
+            // Search in this segment is not finished before the deadline
+            tokio::task::spawn_blocking(move || {
+                blocked.recv().ok();
+                Ok((vec![vec![]], vec![false]))
+            }),
+        ];
+
+        let is_stopped = AtomicBool::new(false);
+        let deadline = Instant::now() + Duration::from_millis(200);
+        let (results, further_results, partial) =
+            SegmentsSearcher::execute_searches(searches, 1, Some(deadline), &is_stopped)
+                .await
+                .unwrap();
+        release.send(()).unwrap();
+
+        // Results of the searched segment are kept, the other one is left out and stopped
+        assert!(partial);
+        assert!(is_stopped.load(Ordering::Relaxed));
+        assert_eq!(results, vec![vec![vec![point]], vec![vec![]]]);
+        assert_eq!(further_results, vec![vec![true], vec![false]]);
+    }
+
     #[test]
     fn test_sampling_limit() {
         assert_eq!(sampling_limit(1000, None, 464530, 35103551), 30);
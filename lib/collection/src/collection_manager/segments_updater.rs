@@ -4,19 +4,20 @@ use std::collections::{HashMap, HashSet};
 
 use parking_lot::{RwLock, RwLockWriteGuard};
 use segment::common::operation_error::{OperationError, OperationResult};
+use segment::common::{check_named_vectors, check_vector_name};
 use segment::data_types::named_vectors::NamedVectors;
 use segment::entry::entry_point::SegmentEntry;
 use segment::types::{
     Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
-    SeqNumberType,
+    SegmentConfig, SeqNumberType,
 };
 
 use crate::collection_manager::holders::segment_holder::SegmentHolder;
-use crate::operations::payload_ops::PayloadOps;
-use crate::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
+use crate::operations::payload_ops::{DeletePayload, PayloadMutation, PayloadOps, SetPayload};
+use crate::operations::point_ops::{PointOperations, PointStruct, UpsertCondition};
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::operations::vector_ops::{PointVectors, VectorOperations};
-use crate::operations::FieldIndexOperations;
+use crate::operations::{CollectionUpdateOperations, FieldIndexOperations};
 
 pub(crate) fn check_unprocessed_points(
     points: &[PointIdType],
@@ -131,7 +132,55 @@ pub(crate) fn set_payload(
     Ok(updated_points.len())
 }
 
-fn points_by_filter(
+/// Merge `payload` into nested objects of the stored payload of the points
+pub(crate) fn merge_payload(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    payload: &Payload,
+    points: &[PointIdType],
+) -> CollectionResult<usize> {
+    let updated_points =
+        segments.apply_points_to_appendable(op_num, points, |id, write_segment| {
+            let mut full_payload = write_segment.payload(id)?;
+            full_payload.merge_deep(payload);
+            write_segment.set_full_payload(op_num, id, &full_payload)
+        })?;
+
+    check_unprocessed_points(points, &updated_points)?;
+    Ok(updated_points.len())
+}
+
+pub(crate) fn merge_payload_by_filter(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    payload: &Payload,
+    filter: &Filter,
+) -> CollectionResult<usize> {
+    let affected_points = points_by_filter(segments, filter)?;
+    merge_payload(segments, op_num, payload, &affected_points)
+}
+
+pub(crate) fn mutate_payload_by_filter(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    mutations: &[PayloadMutation],
+    filter: &Filter,
+) -> CollectionResult<usize> {
+    let affected_points = points_by_filter(segments, filter)?;
+    let updated_points =
+        segments.apply_points_to_appendable(op_num, &affected_points, |id, write_segment| {
+            let mut full_payload = write_segment.payload(id)?;
+            for mutation in mutations {
+                mutation.apply(&mut full_payload);
+            }
+            write_segment.set_full_payload(op_num, id, &full_payload)
+        })?;
+
+    check_unprocessed_points(&affected_points, &updated_points)?;
+    Ok(updated_points.len())
+}
+
+pub(crate) fn points_by_filter(
     segments: &SegmentHolder,
     filter: &Filter,
 ) -> CollectionResult<Vec<PointIdType>> {
@@ -394,6 +443,249 @@ where
     Ok(res)
 }
 
+/// Latest versions of the stored points, missing points are omitted
+fn stored_point_versions(
+    segments: &SegmentHolder,
+    ids: &[PointIdType],
+) -> CollectionResult<HashMap<PointIdType, SeqNumberType>> {
+    // Point might be present in several segments during optimization, the latest version wins
+    let mut versions: HashMap<PointIdType, SeqNumberType> = HashMap::new();
+    segments.read_points(ids, |id, segment| {
+        if let Some(version) = segment.point_version(id) {
+            let latest = versions.entry(id).or_insert(version);
+            *latest = (*latest).max(version);
+        }
+        Ok(true)
+    })?;
+    Ok(versions)
+}
+
+/// Checks the condition of a conditional upsert against the stored versions of the points.
+/// Returns an error with the conflicting version of the first point, which doesn't match.
+pub(crate) fn check_upsert_condition(
+    segments: &SegmentHolder,
+    ids: &[PointIdType],
+    condition: UpsertCondition,
+) -> CollectionResult<()> {
+    let versions = stored_point_versions(segments, ids)?;
+    check_point_versions(ids, condition, |id| versions.get(&id).copied())
+}
+
+fn check_point_versions(
+    ids: &[PointIdType],
+    condition: UpsertCondition,
+    version_of: impl Fn(PointIdType) -> Option<SeqNumberType>,
+) -> CollectionResult<()> {
+    for id in ids {
+        let version = version_of(*id);
+        let conflict = match (condition, version) {
+            (UpsertCondition::IfAbsent, Some(version)) => {
+                format!("Point {id} already exists with version {version}")
+            }
+            (UpsertCondition::IfAbsent, None) => continue,
+            (UpsertCondition::IfVersion(expected), Some(version)) if version == expected => {
+                continue
+            }
+            (UpsertCondition::IfVersion(expected), Some(version)) => {
+                format!("Point {id} has version {version}, expected {expected}")
+            }
+            (UpsertCondition::IfVersion(expected), None) => {
+                format!("Point {id} does not exist, expected version {expected}")
+            }
+        };
+        return Err(CollectionError::precondition_failed(conflict));
+    }
+    Ok(())
+}
+
+/// Points of a batch, as they are after the already checked operations of the batch
+struct BatchPoints<'a> {
+    segments: &'a SegmentHolder,
+    op_num: SeqNumberType,
+    /// Versions of the points, changed by the checked operations, `None` if a point is deleted
+    changed: HashMap<PointIdType, Option<SeqNumberType>>,
+}
+
+impl<'a> BatchPoints<'a> {
+    fn versions(
+        &self,
+        ids: &[PointIdType],
+    ) -> CollectionResult<HashMap<PointIdType, SeqNumberType>> {
+        let stored_ids: Vec<_> = ids
+            .iter()
+            .copied()
+            .filter(|id| !self.changed.contains_key(id))
+            .collect();
+        let mut versions = stored_point_versions(self.segments, &stored_ids)?;
+        versions.extend(
+            ids.iter()
+                .filter_map(|id| Some((*id, (*self.changed.get(id)?)?))),
+        );
+        Ok(versions)
+    }
+
+    fn check_exist(&self, ids: &[PointIdType]) -> CollectionResult<()> {
+        let versions = self.versions(ids)?;
+        match ids.iter().find(|id| !versions.contains_key(id)) {
+            None => Ok(()),
+            Some(missed_point_id) => Err(CollectionError::PointNotFound {
+                missed_point_id: *missed_point_id,
+            }),
+        }
+    }
+
+    fn update(&mut self, ids: impl IntoIterator<Item = PointIdType>) {
+        let op_num = self.op_num;
+        self.changed
+            .extend(ids.into_iter().map(|id| (id, Some(op_num))));
+    }
+
+    fn delete(&mut self, ids: impl IntoIterator<Item = PointIdType>) {
+        self.changed.extend(ids.into_iter().map(|id| (id, None)));
+    }
+}
+
+/// Check that the operations of a batch can be applied, before any of them is applied.
+///
+/// Operations are checked one after another, each on top of the points changed by the previous
+/// ones: existence of the updated points, names and dimensions of the vectors and conditions of
+/// the upserts. Points deleted by a filter are those matching it before the batch.
+/// Failures of the storage itself are not predicted, such a batch is still applied partially.
+pub(crate) fn check_batch_operations(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    operations: &[CollectionUpdateOperations],
+) -> CollectionResult<()> {
+    let Some(appendable_segment) = segments.random_appendable_segment() else {
+        return Err(CollectionError::service_error(
+            "No segments exists, expected at least one".to_string(),
+        ));
+    };
+    let segment_config = appendable_segment.get().read().config().clone();
+
+    let mut points = BatchPoints {
+        segments,
+        op_num,
+        changed: HashMap::new(),
+    };
+    let mut pending: Vec<&CollectionUpdateOperations> = operations.iter().rev().collect();
+    while let Some(operation) = pending.pop() {
+        match operation {
+            CollectionUpdateOperations::PointOperation(point_operation) => {
+                check_point_operation(&mut points, &segment_config, point_operation)?
+            }
+            CollectionUpdateOperations::VectorOperation(vector_operation) => match vector_operation
+            {
+                VectorOperations::UpdateVectors(operation) => {
+                    for point in &operation.points {
+                        check_named_vectors(
+                            &point.vector.clone().into_all_vectors(),
+                            &segment_config,
+                        )?;
+                    }
+                    let ids: Vec<_> = operation.points.iter().map(|point| point.id).collect();
+                    points.check_exist(&ids)?;
+                    points.update(ids);
+                }
+                VectorOperations::DeleteVectors(_, vector_names)
+                | VectorOperations::DeleteVectorsByFilter(_, vector_names) => {
+                    for vector_name in vector_names {
+                        check_vector_name(vector_name, &segment_config)?;
+                    }
+                }
+            },
+            CollectionUpdateOperations::PayloadOperation(payload_operation) => {
+                let ids = match payload_operation {
+                    PayloadOps::SetPayload(SetPayload { points, filter, .. })
+                    | PayloadOps::OverwritePayload(SetPayload { points, filter, .. })
+                    | PayloadOps::MergePayload(SetPayload { points, filter, .. })
+                    | PayloadOps::DeletePayload(DeletePayload { points, filter, .. }) => {
+                        if points.is_none() && filter.is_none() {
+                            return Err(CollectionError::bad_request(
+                                "No points or filter specified".to_string(),
+                            ));
+                        }
+                        points.as_deref()
+                    }
+                    PayloadOps::ClearPayload { points } => Some(points.as_slice()),
+                    PayloadOps::ClearPayloadByFilter(_) | PayloadOps::MutatePayload(_) => None,
+                };
+                if let Some(ids) = ids {
+                    points.check_exist(ids)?;
+                    points.update(ids.iter().copied());
+                }
+            }
+            CollectionUpdateOperations::FieldIndexOperation(_) => {}
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                pending.extend(operations.iter().rev());
+            }
+            CollectionUpdateOperations::ClockedOperation(clocked) => {
+                pending.push(clocked.operation.as_ref());
+            }
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => {
+                pending.push(idempotent.operation.as_ref());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_point_operation(
+    points: &mut BatchPoints,
+    segment_config: &SegmentConfig,
+    operation: &PointOperations,
+) -> CollectionResult<()> {
+    match operation {
+        PointOperations::UpsertPoints(operation) => {
+            let inserted = operation.clone().into_point_vec();
+            for point in &inserted {
+                check_named_vectors(&point.get_vectors(), segment_config)?;
+            }
+            points.update(inserted.iter().map(|point| point.id));
+        }
+        PointOperations::UpsertPointsConditional(operation) => {
+            let inserted = operation.points.clone().into_point_vec();
+            for point in &inserted {
+                check_named_vectors(&point.get_vectors(), segment_config)?;
+            }
+            let ids: Vec<_> = inserted.iter().map(|point| point.id).collect();
+            let versions = points.versions(&ids)?;
+            check_point_versions(&ids, operation.condition, |id| versions.get(&id).copied())?;
+            points.update(ids);
+        }
+        PointOperations::DeletePoints { ids } => points.delete(ids.iter().copied()),
+        PointOperations::DeletePointsByFilter(filter) => {
+            let deleted = points_by_filter(points.segments, filter)?;
+            points.delete(deleted);
+        }
+        PointOperations::SyncPoints(operation) => {
+            for point in &operation.points {
+                check_named_vectors(&point.get_vectors(), segment_config)?;
+            }
+            let in_range = |id: &PointIdType| {
+                operation.from_id.map_or(true, |from_id| *id >= from_id)
+                    && operation.to_id.map_or(true, |to_id| *id < to_id)
+            };
+            let synced: HashSet<_> = operation.points.iter().map(|point| point.id).collect();
+            let deleted: Vec<_> = points
+                .segments
+                .iter()
+                .flat_map(|(_, segment)| {
+                    segment
+                        .get()
+                        .read()
+                        .read_range(operation.from_id, operation.to_id)
+                })
+                .chain(points.changed.keys().copied().filter(in_range))
+                .filter(|id| !synced.contains(id))
+                .collect();
+            points.delete(deleted);
+            points.update(synced);
+        }
+    }
+    Ok(())
+}
+
 pub(crate) fn process_point_operation(
     segments: &RwLock<SegmentHolder>,
     op_num: SeqNumberType,
@@ -402,33 +694,18 @@ pub(crate) fn process_point_operation(
     match point_operation {
         PointOperations::DeletePoints { ids, .. } => delete_points(&segments.read(), op_num, &ids),
         PointOperations::UpsertPoints(operation) => {
-            let points: Vec<_> = match operation {
-                PointInsertOperations::PointsBatch(batch) => {
-                    let all_vectors = batch.vectors.into_all_vectors(batch.ids.len());
-                    let vectors_iter = batch.ids.into_iter().zip(all_vectors);
-                    match batch.payloads {
-                        None => vectors_iter
-                            .map(|(id, vectors)| PointStruct {
-                                id,
-                                vector: vectors.into(),
-                                payload: None,
-                            })
-                            .collect(),
-                        Some(payloads) => vectors_iter
-                            .zip(payloads)
-                            .map(|((id, vectors), payload)| PointStruct {
-                                id,
-                                vector: vectors.into(),
-                                payload,
-                            })
-                            .collect(),
-                    }
-                }
-                PointInsertOperations::PointsList(points) => points,
-            };
+            let points = operation.into_point_vec();
             let res = upsert_points(&segments.read(), op_num, points.iter())?;
             Ok(res)
         }
+        PointOperations::UpsertPointsConditional(operation) => {
+            let points = operation.points.into_point_vec();
+            let segments = segments.read();
+            let ids: Vec<_> = points.iter().map(|point| point.id).collect();
+            check_upsert_condition(&segments, &ids, operation.condition)?;
+            let res = upsert_points(&segments, op_num, points.iter())?;
+            Ok(res)
+        }
         PointOperations::DeletePointsByFilter(filter) => {
             delete_points_by_filter(&segments.read(), op_num, &filter)
         }
@@ -510,6 +787,21 @@ pub(crate) fn process_payload_operation(
                 })
             }
         }
+        PayloadOps::MergePayload(sp) => {
+            let payload: Payload = sp.payload;
+            if let Some(points) = sp.points {
+                merge_payload(&segments.read(), op_num, &payload, &points)
+            } else if let Some(filter) = sp.filter {
+                merge_payload_by_filter(&segments.read(), op_num, &payload, &filter)
+            } else {
+                Err(CollectionError::BadRequest {
+                    description: "No points or filter specified".to_string(),
+                })
+            }
+        }
+        PayloadOps::MutatePayload(mp) => {
+            mutate_payload_by_filter(&segments.read(), op_num, &mp.mutations, &mp.filter)
+        }
     }
 }
 
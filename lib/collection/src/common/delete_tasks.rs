@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// Finished tasks are kept for this long, so their result can be read
+pub const FINISHED_DELETE_TASK_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeleteTaskStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Progress of a background deletion of points by filter
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct DeleteTaskInfo {
+    pub id: String,
+    pub status: DeleteTaskStatus,
+    /// Number of points deleted so far
+    pub points_deleted: usize,
+    /// Number of shards, from which all matching points are deleted
+    pub shards_done: usize,
+    pub shards_total: usize,
+    /// Error, which stopped the task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Shared state of a running deletion, updated by the task and read by the API
+pub struct DeleteTask {
+    id: Uuid,
+    points_deleted: AtomicUsize,
+    shards_done: AtomicUsize,
+    shards_total: usize,
+    cancelled: AtomicBool,
+    /// Result of the task and the time it finished at, if finished
+    finished: Mutex<Option<(CollectionResult<()>, Instant)>>,
+}
+
+impl DeleteTask {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn add_deleted(&self, count: usize) {
+        self.points_deleted.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn shard_done(&self) {
+        self.shards_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self, result: CollectionResult<()>) {
+        *self.finished.lock() = Some((result, Instant::now()));
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.finished
+            .lock()
+            .as_ref()
+            .map_or(false, |(_, finished_at)| {
+                now.duration_since(*finished_at) >= FINISHED_DELETE_TASK_TTL
+            })
+    }
+
+    pub fn info(&self) -> DeleteTaskInfo {
+        let (status, error) = match &*self.finished.lock() {
+            None => (DeleteTaskStatus::Running, None),
+            Some((Err(err), _)) => (DeleteTaskStatus::Failed, Some(err.to_string())),
+            Some((Ok(()), _)) if self.is_cancelled() => (DeleteTaskStatus::Cancelled, None),
+            Some((Ok(()), _)) => (DeleteTaskStatus::Completed, None),
+        };
+        DeleteTaskInfo {
+            id: self.id.to_string(),
+            status,
+            points_deleted: self.points_deleted.load(Ordering::Relaxed),
+            shards_done: self.shards_done.load(Ordering::Relaxed),
+            shards_total: self.shards_total,
+            error,
+        }
+    }
+}
+
+/// Background deletions of points by filter of a collection.
+///
+/// Tasks are kept in memory of the peer, which started them.
+#[derive(Default)]
+pub struct DeleteTasks {
+    tasks: Mutex<HashMap<Uuid, Arc<DeleteTask>>>,
+}
+
+impl DeleteTasks {
+    /// Register a new task, which deletes points from `shards_total` shards
+    pub fn start(&self, shards_total: usize) -> Arc<DeleteTask> {
+        let now = Instant::now();
+        let mut tasks = self.tasks.lock();
+        tasks.retain(|_, task| !task.is_expired(now));
+
+        let task = Arc::new(DeleteTask {
+            id: Uuid::new_v4(),
+            points_deleted: AtomicUsize::new(0),
+            shards_done: AtomicUsize::new(0),
+            shards_total,
+            cancelled: AtomicBool::new(false),
+            finished: Mutex::new(None),
+        });
+        tasks.insert(task.id, task.clone());
+        task
+    }
+
+    fn get(&self, task_id: &str) -> CollectionResult<Arc<DeleteTask>> {
+        let not_found = || CollectionError::NotFound {
+            what: format!("Delete task {task_id}"),
+        };
+        let task_id = Uuid::parse_str(task_id).map_err(|_| not_found())?;
+        self.tasks
+            .lock()
+            .get(&task_id)
+            .cloned()
+            .ok_or_else(not_found)
+    }
+
+    pub fn info(&self, task_id: &str) -> CollectionResult<DeleteTaskInfo> {
+        Ok(self.get(task_id)?.info())
+    }
+
+    pub fn list(&self) -> Vec<DeleteTaskInfo> {
+        self.tasks.lock().values().map(|task| task.info()).collect()
+    }
+
+    /// Request the task to stop, points deleted so far stay deleted
+    pub fn cancel(&self, task_id: &str) -> CollectionResult<DeleteTaskInfo> {
+        let task = self.get(task_id)?;
+        task.cancelled.store(true, Ordering::Relaxed);
+        Ok(task.info())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delete_task_status() {
+        let tasks = DeleteTasks::default();
+        let task = tasks.start(2);
+        let id = task.info().id;
+
+        task.add_deleted(10);
+        task.shard_done();
+        let info = tasks.info(&id).unwrap();
+        assert_eq!(info.status, DeleteTaskStatus::Running);
+        assert_eq!(info.points_deleted, 10);
+        assert_eq!(info.shards_done, 1);
+
+        tasks.cancel(&id).unwrap();
+        assert!(task.is_cancelled());
+        task.finish(Ok(()));
+        assert_eq!(tasks.info(&id).unwrap().status, DeleteTaskStatus::Cancelled);
+
+        assert!(tasks.info("not a task").is_err());
+        assert_eq!(tasks.list().len(), 1);
+    }
+}
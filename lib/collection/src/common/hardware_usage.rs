@@ -0,0 +1,79 @@
+//! Resources, used by the local shards to process a search request.
+//!
+//! Same as the shard traces, the usage is collected in a task local of the request.
+//! Remote shards report their usage in the responses, which is added to the same counter.
+
+use std::cell::Cell;
+use std::future::Future;
+
+use segment::common::hardware_counter::HardwareUsage;
+
+tokio::task_local! {
+    static HARDWARE_USAGE: Cell<HardwareUsage>;
+}
+
+/// Run the request and, if enabled, collect the resources used by all shards it searches
+pub async fn collect_hardware_usage<F: Future>(
+    enabled: bool,
+    request: F,
+) -> (F::Output, Option<HardwareUsage>) {
+    if !enabled {
+        return (request.await, None);
+    }
+
+    HARDWARE_USAGE
+        .scope(Cell::new(HardwareUsage::default()), async {
+            let output = request.await;
+            let usage = HARDWARE_USAGE.with(Cell::get);
+            (output, Some(usage))
+        })
+        .await
+}
+
+/// If the resource usage of the current request is collected
+pub fn is_hardware_usage_collected() -> bool {
+    HARDWARE_USAGE.try_with(|_| ()).is_ok()
+}
+
+/// Add the usage of a shard to the usage of the current request
+pub fn record_hardware_usage(usage: HardwareUsage) {
+    // Requests without usage collection are ignored
+    let _ = HARDWARE_USAGE.try_with(|total| {
+        let mut sum = total.get();
+        sum += usage;
+        total.set(sum);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_collect_hardware_usage() {
+        let usage = HardwareUsage {
+            segments_searched: 2,
+            vector_bytes_read: 64,
+            ..Default::default()
+        };
+
+        let (collected, total) = collect_hardware_usage(true, async {
+            record_hardware_usage(usage);
+            record_hardware_usage(usage);
+            is_hardware_usage_collected()
+        })
+        .await;
+        assert!(collected);
+        let total = total.unwrap();
+        assert_eq!(total.segments_searched, 4);
+        assert_eq!(total.vector_bytes_read, 128);
+
+        let (collected, total) = collect_hardware_usage(false, async {
+            record_hardware_usage(usage);
+            is_hardware_usage_collected()
+        })
+        .await;
+        assert!(!collected);
+        assert_eq!(total, None);
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// Finished imports are kept for this long, so their result can be read
+pub const FINISHED_IMPORT_TASK_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportTaskStatus {
+    Running,
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Progress of an import of points
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct ImportTaskInfo {
+    pub id: String,
+    pub status: ImportTaskStatus,
+    /// Number of points read from the request so far
+    pub points_read: usize,
+    /// Number of points upserted so far
+    pub points_imported: usize,
+    /// Error, which stopped the import
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Shared state of a running import, updated by the request and read by the API
+pub struct ImportTask {
+    id: Uuid,
+    points_read: AtomicUsize,
+    points_imported: AtomicUsize,
+    cancelled: AtomicBool,
+    /// Result of the import and the time it finished at, if finished
+    finished: Mutex<Option<(CollectionResult<()>, Instant)>>,
+}
+
+impl ImportTask {
+    pub fn id(&self) -> String {
+        self.id.to_string()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn add_read(&self, count: usize) {
+        self.points_read.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn add_imported(&self, count: usize) {
+        self.points_imported.fetch_add(count, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self, result: CollectionResult<()>) {
+        *self.finished.lock() = Some((result, Instant::now()));
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.finished
+            .lock()
+            .as_ref()
+            .map_or(false, |(_, finished_at)| {
+                now.duration_since(*finished_at) >= FINISHED_IMPORT_TASK_TTL
+            })
+    }
+
+    pub fn info(&self) -> ImportTaskInfo {
+        let (status, error) = match &*self.finished.lock() {
+            None => (ImportTaskStatus::Running, None),
+            Some((Err(err), _)) => (ImportTaskStatus::Failed, Some(err.to_string())),
+            Some((Ok(()), _)) if self.is_cancelled() => (ImportTaskStatus::Cancelled, None),
+            Some((Ok(()), _)) => (ImportTaskStatus::Completed, None),
+        };
+        ImportTaskInfo {
+            id: self.id(),
+            status,
+            points_read: self.points_read.load(Ordering::Relaxed),
+            points_imported: self.points_imported.load(Ordering::Relaxed),
+            error,
+        }
+    }
+}
+
+/// Imports of points into a collection.
+///
+/// An import runs while its request is read, the tasks allow to follow its progress and cancel it
+/// from another request. Tasks are kept in memory of the peer, which received the import.
+#[derive(Default)]
+pub struct ImportTasks {
+    tasks: Mutex<HashMap<Uuid, Arc<ImportTask>>>,
+}
+
+impl ImportTasks {
+    /// Register a new import
+    pub fn start(&self) -> Arc<ImportTask> {
+        let now = Instant::now();
+        let mut tasks = self.tasks.lock();
+        tasks.retain(|_, task| !task.is_expired(now));
+
+        let task = Arc::new(ImportTask {
+            id: Uuid::new_v4(),
+            points_read: AtomicUsize::new(0),
+            points_imported: AtomicUsize::new(0),
+            cancelled: AtomicBool::new(false),
+            finished: Mutex::new(None),
+        });
+        tasks.insert(task.id, task.clone());
+        task
+    }
+
+    fn get(&self, task_id: &str) -> CollectionResult<Arc<ImportTask>> {
+        let not_found = || CollectionError::NotFound {
+            what: format!("Import task {task_id}"),
+        };
+        let task_id = Uuid::parse_str(task_id).map_err(|_| not_found())?;
+        self.tasks
+            .lock()
+            .get(&task_id)
+            .cloned()
+            .ok_or_else(not_found)
+    }
+
+    pub fn info(&self, task_id: &str) -> CollectionResult<ImportTaskInfo> {
+        Ok(self.get(task_id)?.info())
+    }
+
+    pub fn list(&self) -> Vec<ImportTaskInfo> {
+        self.tasks.lock().values().map(|task| task.info()).collect()
+    }
+
+    /// Request the import to stop after the batches in progress, imported points are kept
+    pub fn cancel(&self, task_id: &str) -> CollectionResult<ImportTaskInfo> {
+        let task = self.get(task_id)?;
+        task.cancelled.store(true, Ordering::Relaxed);
+        Ok(task.info())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_task_status() {
+        let tasks = ImportTasks::default();
+        let task = tasks.start();
+        let id = task.id();
+
+        task.add_read(20);
+        task.add_imported(10);
+        let info = tasks.info(&id).unwrap();
+        assert_eq!(info.status, ImportTaskStatus::Running);
+        assert_eq!(info.points_read, 20);
+        assert_eq!(info.points_imported, 10);
+
+        task.finish(Err(CollectionError::bad_input(
+            "Invalid point at line 21".to_string(),
+        )));
+        let info = tasks.info(&id).unwrap();
+        assert_eq!(info.status, ImportTaskStatus::Failed);
+        assert!(info.error.unwrap().contains("line 21"));
+
+        let task = tasks.start();
+        tasks.cancel(&task.id()).unwrap();
+        assert!(task.is_cancelled());
+        task.finish(Ok(()));
+        assert_eq!(
+            tasks.info(&task.id()).unwrap().status,
+            ImportTaskStatus::Cancelled
+        );
+
+        assert!(tasks.info("not a task").is_err());
+        assert_eq!(tasks.list().len(), 2);
+    }
+}
@@ -1,5 +1,16 @@
+pub mod delete_tasks;
 pub mod file_utils;
+pub mod hardware_usage;
+pub mod import_tasks;
 pub mod is_ready;
+pub mod optimize_tasks;
+pub mod query_explain;
+pub mod query_trace;
+pub mod scroll_cursors;
+pub mod scoring_plugin;
+pub mod search_cache;
+pub mod snapshot_retention;
+pub mod snapshot_schedule;
 pub mod stoppable_task;
 pub mod stoppable_task_async;
 pub mod stopping_guard;
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::optimizers_builder::OptimizeTarget;
+
+/// Finished tasks are kept for this long, so their result can be read
+pub const FINISHED_OPTIMIZE_TASK_TTL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizeTaskStatus {
+    Running,
+    Completed,
+    Failed,
+}
+
+/// Progress of optimizations, requested to run right away
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct OptimizeTaskInfo {
+    pub id: String,
+    pub status: OptimizeTaskStatus,
+    /// Requested optimizations, all of them if not set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<OptimizeTarget>,
+    /// Number of local shards, which finished the optimizations
+    pub shards_done: usize,
+    pub shards_total: usize,
+    /// Error, which stopped the optimizations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Shared state of running optimizations, updated by the task and read by the API
+pub struct OptimizeTask {
+    id: Uuid,
+    target: Option<OptimizeTarget>,
+    shards_done: AtomicUsize,
+    shards_total: usize,
+    /// Result of the task and the time it finished at, if finished
+    finished: Mutex<Option<(CollectionResult<()>, Instant)>>,
+}
+
+impl OptimizeTask {
+    pub fn shard_done(&self) {
+        self.shards_done.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn finish(&self, result: CollectionResult<()>) {
+        *self.finished.lock() = Some((result, Instant::now()));
+    }
+
+    fn is_expired(&self, now: Instant) -> bool {
+        self.finished
+            .lock()
+            .as_ref()
+            .map_or(false, |(_, finished_at)| {
+                now.duration_since(*finished_at) >= FINISHED_OPTIMIZE_TASK_TTL
+            })
+    }
+
+    pub fn info(&self) -> OptimizeTaskInfo {
+        let (status, error) = match &*self.finished.lock() {
+            None => (OptimizeTaskStatus::Running, None),
+            Some((Err(err), _)) => (OptimizeTaskStatus::Failed, Some(err.to_string())),
+            Some((Ok(()), _)) => (OptimizeTaskStatus::Completed, None),
+        };
+        OptimizeTaskInfo {
+            id: self.id.to_string(),
+            status,
+            target: self.target,
+            shards_done: self.shards_done.load(Ordering::Relaxed),
+            shards_total: self.shards_total,
+            error,
+        }
+    }
+}
+
+/// Optimizations of the local shards of a collection, requested to run right away.
+///
+/// Tasks are kept in memory of the peer, which started them.
+#[derive(Default)]
+pub struct OptimizeTasks {
+    tasks: Mutex<HashMap<Uuid, Arc<OptimizeTask>>>,
+}
+
+impl OptimizeTasks {
+    /// Register a new task, which optimizes `shards_total` local shards
+    pub fn start(&self, target: Option<OptimizeTarget>, shards_total: usize) -> Arc<OptimizeTask> {
+        let now = Instant::now();
+        let mut tasks = self.tasks.lock();
+        tasks.retain(|_, task| !task.is_expired(now));
+
+        let task = Arc::new(OptimizeTask {
+            id: Uuid::new_v4(),
+            target,
+            shards_done: AtomicUsize::new(0),
+            shards_total,
+            finished: Mutex::new(None),
+        });
+        tasks.insert(task.id, task.clone());
+        task
+    }
+
+    pub fn info(&self, task_id: &str) -> CollectionResult<OptimizeTaskInfo> {
+        let not_found = || CollectionError::NotFound {
+            what: format!("Optimize task {task_id}"),
+        };
+        let task_id = Uuid::parse_str(task_id).map_err(|_| not_found())?;
+        self.tasks
+            .lock()
+            .get(&task_id)
+            .map(|task| task.info())
+            .ok_or_else(not_found)
+    }
+
+    pub fn list(&self) -> Vec<OptimizeTaskInfo> {
+        self.tasks.lock().values().map(|task| task.info()).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_optimize_task_status() {
+        let tasks = OptimizeTasks::default();
+        let task = tasks.start(Some(OptimizeTarget::Indexing), 2);
+        let id = task.info().id;
+
+        task.shard_done();
+        let info = tasks.info(&id).unwrap();
+        assert_eq!(info.status, OptimizeTaskStatus::Running);
+        assert_eq!(info.target, Some(OptimizeTarget::Indexing));
+        assert_eq!(info.shards_done, 1);
+
+        task.finish(Err(CollectionError::service_error("failed")));
+        let info = tasks.info(&id).unwrap();
+        assert_eq!(info.status, OptimizeTaskStatus::Failed);
+        assert!(info.error.is_some());
+
+        assert!(tasks.info("not a task").is_err());
+        assert_eq!(tasks.list().len(), 1);
+    }
+}
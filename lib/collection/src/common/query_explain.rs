@@ -0,0 +1,138 @@
+//! Explanation of the searches in the segments of the shards, which took part in a request.
+//!
+//! Same as the shard traces, the explanations are kept in task locals of the request.
+//! Searches of the local shards are explained segment by segment, while remote shards
+//! return their explanations in the responses.
+
+use std::cell::RefCell;
+use std::future::Future;
+
+use segment::common::search_explain::{ExplainMode, SegmentSearchExplain, ShardSearchExplain};
+
+use crate::shards::shard::{PeerId, ShardId};
+
+tokio::task_local! {
+    static EXPLAIN_MODE: ExplainMode;
+    static SHARD_EXPLAINS: RefCell<Vec<ShardSearchExplain>>;
+    static SEGMENT_EXPLAINS: RefCell<Vec<SegmentSearchExplain>>;
+}
+
+/// Run the request and, if requested, explain the searches of all shards it makes
+pub async fn collect_search_explain<F: Future>(
+    mode: Option<ExplainMode>,
+    request: F,
+) -> (F::Output, Option<Vec<ShardSearchExplain>>) {
+    let Some(mode) = mode else {
+        return (request.await, None);
+    };
+
+    let scoped = SHARD_EXPLAINS.scope(RefCell::new(Vec::new()), async {
+        let output = request.await;
+        let explains = SHARD_EXPLAINS.with(|explains| explains.take());
+        (output, Some(explains))
+    });
+    EXPLAIN_MODE.scope(mode, scoped).await
+}
+
+/// Mode of the explanation of the current request, if it is explained
+pub fn explain_mode() -> Option<ExplainMode> {
+    EXPLAIN_MODE.try_with(|mode| *mode).ok()
+}
+
+/// Run the request to a shard, explaining its local searches if the whole request is explained
+pub async fn explain_shard<F: Future>(shard_id: ShardId, peer_id: PeerId, request: F) -> F::Output {
+    if SHARD_EXPLAINS.try_with(|_| ()).is_err() {
+        return request.await;
+    }
+
+    let (output, segments) = SEGMENT_EXPLAINS
+        .scope(RefCell::new(Vec::new()), async {
+            let output = request.await;
+            (output, SEGMENT_EXPLAINS.with(|segments| segments.take()))
+        })
+        .await;
+    // Shards, searched only remotely, are explained by the remote peers
+    if !segments.is_empty() {
+        let explain = ShardSearchExplain {
+            shard_id,
+            peer_id,
+            segments,
+        };
+        SHARD_EXPLAINS.with(|explains| explains.borrow_mut().push(explain));
+    }
+    output
+}
+
+/// Add the explanation of a segment to the explained shard request.
+/// Searches in the same segment, made by the same shard request, are merged.
+pub fn record_segment_explain(explain: SegmentSearchExplain) {
+    // Not explained requests are ignored
+    let _ = SEGMENT_EXPLAINS.try_with(|segments| {
+        let mut segments = segments.borrow_mut();
+        match segments
+            .iter_mut()
+            .find(|segment| segment.segment_id == explain.segment_id)
+        {
+            Some(segment) => segment.searches.extend(explain.searches),
+            None => segments.push(explain),
+        }
+    });
+}
+
+/// Add the explanations, returned by a remote shard, to the explained request
+pub fn record_shard_explain(explains: Vec<ShardSearchExplain>) {
+    // Not explained requests are ignored
+    let _ = SHARD_EXPLAINS.try_with(|total| total.borrow_mut().extend(explains));
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::common::search_explain::{SearchStrategy, VectorSearchExplain};
+
+    use super::*;
+
+    fn segment_explain(segment_id: usize) -> SegmentSearchExplain {
+        SegmentSearchExplain {
+            segment_id,
+            searches: vec![VectorSearchExplain::new(SearchStrategy::Hnsw, 1, None)],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_collect_search_explain() {
+        let (mode, explains) = collect_search_explain(Some(ExplainMode::Plan), async {
+            let local = explain_shard(1, 10, async {
+                record_segment_explain(segment_explain(0));
+                record_segment_explain(segment_explain(0));
+                record_segment_explain(segment_explain(1));
+            });
+            let remote = explain_shard(2, 10, async {
+                record_shard_explain(vec![ShardSearchExplain {
+                    shard_id: 2,
+                    peer_id: 20,
+                    segments: vec![segment_explain(0)],
+                }]);
+            });
+            futures::join!(local, remote);
+            explain_mode()
+        })
+        .await;
+        assert_eq!(mode, Some(ExplainMode::Plan));
+
+        let mut explains = explains.unwrap();
+        explains.sort_by_key(|explain| explain.shard_id);
+        assert_eq!(explains.len(), 2);
+        assert_eq!((explains[0].shard_id, explains[0].peer_id), (1, 10));
+        assert_eq!(explains[0].segments.len(), 2);
+        assert_eq!(explains[0].segments[0].searches.len(), 2);
+        assert_eq!((explains[1].shard_id, explains[1].peer_id), (2, 20));
+
+        let (mode, explains) = collect_search_explain(None, async {
+            explain_shard(1, 10, async { record_segment_explain(segment_explain(0)) }).await;
+            explain_mode()
+        })
+        .await;
+        assert_eq!(mode, None);
+        assert_eq!(explains, None);
+    }
+}
@@ -0,0 +1,96 @@
+//! Timings of the shards, which took part in a read request.
+//!
+//! Tracing is enabled for a request by running it within [`collect_shard_traces`].
+//! Shard requests are awaited in the task of the request, so the traces are kept in
+//! task locals and don't have to be passed through the shard interfaces.
+
+use std::cell::{Cell, RefCell};
+use std::future::Future;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::shards::shard::ShardId;
+
+tokio::task_local! {
+    static SHARD_TRACES: RefCell<Vec<ShardTrace>>;
+    static SHARD_FULL_SCAN: Cell<bool>;
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct ShardTrace {
+    pub shard_id: ShardId,
+    /// Time of the shard response, in seconds
+    pub duration_secs: f64,
+    /// If a search in a local replica of the shard fell back to a full scan of a segment
+    pub full_scan: bool,
+}
+
+/// Run the request and collect the traces of all shard requests it makes
+pub async fn collect_shard_traces<F: Future>(request: F) -> (F::Output, Vec<ShardTrace>) {
+    SHARD_TRACES
+        .scope(RefCell::new(Vec::new()), async {
+            let output = request.await;
+            let traces = SHARD_TRACES.with(|traces| traces.take());
+            (output, traces)
+        })
+        .await
+}
+
+/// Run the request to a shard, tracing it if the whole request is traced
+pub async fn trace_shard<F: Future>(shard_id: ShardId, request: F) -> F::Output {
+    #[cfg(feature = "tracing")]
+    let request = tracing::Instrument::instrument(request, tracing::debug_span!("shard", shard_id));
+
+    if SHARD_TRACES.try_with(|_| ()).is_err() {
+        return request.await;
+    }
+
+    let start = Instant::now();
+    let (output, full_scan) = SHARD_FULL_SCAN
+        .scope(Cell::new(false), async {
+            let output = request.await;
+            (output, SHARD_FULL_SCAN.with(Cell::get))
+        })
+        .await;
+    let trace = ShardTrace {
+        shard_id,
+        duration_secs: start.elapsed().as_secs_f64(),
+        full_scan,
+    };
+    SHARD_TRACES.with(|traces| traces.borrow_mut().push(trace));
+    output
+}
+
+/// Mark the traced shard request as fallen back to a full scan
+pub fn record_full_scan() {
+    // Not traced requests are ignored
+    let _ = SHARD_FULL_SCAN.try_with(|full_scan| full_scan.set(true));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_collect_shard_traces() {
+        let ((), traces) = collect_shard_traces(async {
+            let first = trace_shard(1, async {});
+            let second = trace_shard(2, async { record_full_scan() });
+            futures::join!(first, second);
+        })
+        .await;
+
+        assert_eq!(traces.len(), 2);
+        let full_scans: Vec<_> = traces
+            .iter()
+            .map(|trace| (trace.shard_id, trace.full_scan))
+            .collect();
+        assert!(full_scans.contains(&(1, false)));
+        assert!(full_scans.contains(&(2, true)));
+
+        // Requests without tracing are executed as usual
+        assert_eq!(trace_shard(3, async { 42 }).await, 42);
+    }
+}
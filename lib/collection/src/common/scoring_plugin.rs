@@ -0,0 +1,287 @@
+use std::sync::Arc;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use common::types::ScoreType;
+use parking_lot::Mutex;
+use wasmi::core::{ValueType, F32};
+use wasmi::{
+    Config, Engine, Func, Linker, Module, ResourceLimiter, Store, StoreLimits, StoreLimitsBuilder,
+    Value,
+};
+
+use crate::config::ScoringPluginConfig;
+use crate::operations::types::{CollectionError, CollectionResult};
+
+/// Function of the module, which scores a candidate
+pub const SCORE_FUNCTION: &str = "score";
+
+/// Max size of a WASM module of a scoring plugin, in bytes
+pub const MAX_SCORING_PLUGIN_SIZE: usize = 1024 * 1024;
+
+/// Max size of the memory of a scoring plugin instance, in bytes
+const MAX_SCORING_PLUGIN_MEMORY: usize = 16 * 1024 * 1024;
+
+/// Fuel to instantiate a scoring plugin, roughly the number of executed instructions
+const INSTANTIATE_FUEL: u64 = 1_000_000;
+
+/// Fuel to score a single candidate
+const SCORE_FUEL: u64 = 100_000;
+
+/// Compiled WASM module of a scoring plugin.
+///
+/// Modules are sandboxed: they can't import functions or memory from the host, execution is
+/// limited by fuel and memory by the limits of the store.
+pub struct ScoringPlugin {
+    name: String,
+    version: u64,
+    engine: Engine,
+    module: Module,
+}
+
+impl ScoringPlugin {
+    /// Compile the module of the plugin and check its `score` function
+    pub fn compile(config: &ScoringPluginConfig) -> CollectionResult<Self> {
+        let invalid = |message: String| {
+            CollectionError::bad_request(format!("Scoring plugin {}: {message}", config.name))
+        };
+
+        let wasm = BASE64
+            .decode(&config.module)
+            .map_err(|err| invalid(format!("module is not valid base64: {err}")))?;
+        if wasm.len() > MAX_SCORING_PLUGIN_SIZE {
+            return Err(invalid(format!(
+                "module is too large: {} bytes, max {MAX_SCORING_PLUGIN_SIZE}",
+                wasm.len(),
+            )));
+        }
+
+        let mut engine_config = Config::default();
+        engine_config.consume_fuel(true);
+        let engine = Engine::new(&engine_config);
+        let module = Module::new(&engine, &wasm[..])
+            .map_err(|err| invalid(format!("module is not valid WASM: {err}")))?;
+        if module.imports().next().is_some() {
+            return Err(invalid("module can't import anything".to_string()));
+        }
+
+        let plugin = Self {
+            name: config.name.clone(),
+            version: config.version,
+            engine,
+            module,
+        };
+        plugin.instantiate(0)?;
+        Ok(plugin)
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Instance of the plugin with the fuel to score `candidates` candidates
+    pub fn instantiate(&self, candidates: usize) -> CollectionResult<ScoringPluginInstance> {
+        let failed = |err: &dyn std::fmt::Display| {
+            CollectionError::bad_request(format!("Scoring plugin {} failed: {err}", self.name))
+        };
+
+        let limits = StoreLimitsBuilder::new()
+            .memory_size(MAX_SCORING_PLUGIN_MEMORY)
+            .build();
+        let mut store = Store::new(&self.engine, limits);
+        store.limiter(|limits| limits as &mut dyn ResourceLimiter);
+        store
+            .add_fuel(INSTANTIATE_FUEL + SCORE_FUEL.saturating_mul(candidates as u64))
+            .map_err(|err| failed(&err))?;
+
+        let instance = Linker::<StoreLimits>::new(&self.engine)
+            .instantiate(&mut store, &self.module)
+            .and_then(|instance| instance.start(&mut store))
+            .map_err(|err| failed(&err))?;
+
+        let score = instance.get_func(&store, SCORE_FUNCTION).ok_or_else(|| {
+            CollectionError::bad_request(format!(
+                "Scoring plugin {} doesn't export function `{SCORE_FUNCTION}`",
+                self.name,
+            ))
+        })?;
+        let score_type = score.ty(&store);
+        let is_f32 = |value_type: &ValueType| *value_type == ValueType::F32;
+        if !score_type.params().iter().all(is_f32) || score_type.results() != [ValueType::F32] {
+            return Err(CollectionError::bad_request(format!(
+                "Function `{SCORE_FUNCTION}` of scoring plugin {} must take `f32` arguments and return `f32`",
+                self.name,
+            )));
+        }
+
+        Ok(ScoringPluginInstance {
+            name: self.name.clone(),
+            arguments: score_type.params().len(),
+            store,
+            score,
+        })
+    }
+}
+
+/// Instance of a scoring plugin, which scores the candidates of a single query
+pub struct ScoringPluginInstance {
+    name: String,
+    arguments: usize,
+    store: Store<StoreLimits>,
+    score: Func,
+}
+
+impl ScoringPluginInstance {
+    /// Score a candidate by its scores in the prefetches and its payload features.
+    ///
+    /// Missing scores and features are passed as `NaN`.
+    pub fn score(
+        &mut self,
+        prefetch_scores: &[Option<ScoreType>],
+        features: &[f32],
+    ) -> CollectionResult<ScoreType> {
+        if prefetch_scores.len() + features.len() != self.arguments {
+            return Err(CollectionError::bad_request(format!(
+                "Scoring plugin {} takes {} arguments, but {} prefetch scores and {} payload features are given",
+                self.name,
+                self.arguments,
+                prefetch_scores.len(),
+                features.len(),
+            )));
+        }
+
+        let arguments: Vec<_> = prefetch_scores
+            .iter()
+            .map(|score| score.unwrap_or(ScoreType::NAN))
+            .chain(features.iter().copied())
+            .map(|argument| Value::F32(F32::from(argument)))
+            .collect();
+        let mut result = [Value::F32(F32::from(0.0))];
+        self.score
+            .call(&mut self.store, &arguments, &mut result)
+            .map_err(|err| {
+                CollectionError::bad_request(format!("Scoring plugin {} failed: {err}", self.name))
+            })?;
+
+        match result {
+            [Value::F32(score)] => Ok(f32::from(score)),
+            _ => Err(CollectionError::service_error(format!(
+                "Scoring plugin {} returned a non `f32` score",
+                self.name,
+            ))),
+        }
+    }
+}
+
+/// Compiled scoring plugin of a collection, compiled again once the plugin is changed
+#[derive(Default)]
+pub struct ScoringPluginCache {
+    compiled: Mutex<Option<Arc<ScoringPlugin>>>,
+}
+
+impl ScoringPluginCache {
+    pub fn get(&self, config: &ScoringPluginConfig) -> CollectionResult<Arc<ScoringPlugin>> {
+        let mut compiled = self.compiled.lock();
+        if let Some(plugin) = compiled.as_ref() {
+            // Every change of the plugin increases its version
+            if plugin.name == config.name && plugin.version == config.version {
+                return Ok(plugin.clone());
+            }
+        }
+        let plugin = Arc::new(ScoringPlugin::compile(config)?);
+        *compiled = Some(plugin.clone());
+        Ok(plugin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WASM_HEADER: [u8; 8] = [0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00];
+
+    /// `score(a: f32, b: f32) -> f32`, which returns `a + b`
+    const ADD_SECTIONS: [u8; 35] = [
+        0x01, 0x07, 0x01, 0x60, 0x02, 0x7d, 0x7d, 0x01, 0x7d, // type (f32, f32) -> f32
+        0x03, 0x02, 0x01, 0x00, // function of the type
+        0x07, 0x09, 0x01, 0x05, b's', b'c', b'o', b'r', b'e', 0x00, 0x00, // export `score`
+        0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x92, 0x0b, // a + b
+    ];
+
+    /// `score(a: f32) -> f32`, which never returns
+    const LOOP_SECTIONS: [u8; 39] = [
+        0x01, 0x06, 0x01, 0x60, 0x01, 0x7d, 0x01, 0x7d, // type (f32) -> f32
+        0x03, 0x02, 0x01, 0x00, // function of the type
+        0x07, 0x09, 0x01, 0x05, b's', b'c', b'o', b'r', b'e', 0x00, 0x00, // export `score`
+        0x0a, 0x0e, 0x01, 0x0c, 0x00, 0x03, 0x40, 0x0c, 0x00, 0x0b, // loop { br 0 }
+        0x43, 0x00, 0x00, 0x00, 0x00, 0x0b, // 0.0
+    ];
+
+    /// Imports function `env.log`
+    const IMPORT_SECTIONS: [u8; 19] = [
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // type () -> ()
+        // import of the function of the type
+        0x02, 0x0b, 0x01, 0x03, b'e', b'n', b'v', 0x03, b'l', b'o', b'g', 0x00, 0x00,
+    ];
+
+    fn plugin_config(sections: &[u8]) -> ScoringPluginConfig {
+        let wasm: Vec<u8> = WASM_HEADER.iter().chain(sections).copied().collect();
+        ScoringPluginConfig {
+            name: "test".to_string(),
+            version: 1,
+            module: BASE64.encode(wasm),
+            features: vec!["boost".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_scoring_plugin_score() {
+        let plugin = ScoringPlugin::compile(&plugin_config(&ADD_SECTIONS)).unwrap();
+        let mut instance = plugin.instantiate(3).unwrap();
+
+        assert_eq!(instance.score(&[Some(0.5)], &[2.0]).unwrap(), 2.5);
+        assert!(instance.score(&[None], &[2.0]).unwrap().is_nan());
+
+        // Number of the arguments must match the function
+        assert!(instance.score(&[Some(0.5), Some(0.5)], &[2.0]).is_err());
+    }
+
+    #[test]
+    fn test_scoring_plugin_out_of_fuel() {
+        let plugin = ScoringPlugin::compile(&plugin_config(&LOOP_SECTIONS)).unwrap();
+        let mut instance = plugin.instantiate(1).unwrap();
+        assert!(instance.score(&[Some(0.5)], &[]).is_err());
+    }
+
+    #[test]
+    fn test_scoring_plugin_invalid_module() {
+        assert!(ScoringPlugin::compile(&plugin_config(&IMPORT_SECTIONS)).is_err());
+        assert!(ScoringPlugin::compile(&plugin_config(&ADD_SECTIONS[..9])).is_err());
+
+        let not_base64 = ScoringPluginConfig {
+            module: "not base64!".to_string(),
+            ..plugin_config(&ADD_SECTIONS)
+        };
+        assert!(ScoringPlugin::compile(&not_base64).is_err());
+    }
+
+    #[test]
+    fn test_scoring_plugin_cache() {
+        let cache = ScoringPluginCache::default();
+        let config = plugin_config(&ADD_SECTIONS);
+        let plugin = cache.get(&config).unwrap();
+        assert!(Arc::ptr_eq(&plugin, &cache.get(&config).unwrap()));
+
+        let next_version = ScoringPluginConfig {
+            version: 2,
+            ..config
+        };
+        let recompiled = cache.get(&next_version).unwrap();
+        assert_eq!(recompiled.version(), 2);
+        assert!(!Arc::ptr_eq(&plugin, &recompiled));
+    }
+}
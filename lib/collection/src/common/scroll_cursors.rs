@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::mem::size_of;
+use std::str::FromStr as _;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+use segment::types::PointIdType;
+use uuid::Uuid;
+
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::shards::shard::ShardId;
+
+/// Cursors, which are not read for this long, are dropped
+pub const SCROLL_CURSOR_TTL: Duration = Duration::from_secs(10 * 60);
+
+/// Max number of stable scrolls of a collection, the least recently read ones are dropped
+pub const MAX_SCROLL_CURSORS: usize = 1024;
+
+/// Max total size of the snapshots of stable scrolls of a collection, in bytes
+pub const MAX_SCROLL_CURSORS_SIZE: usize = 64 * 1024 * 1024;
+
+/// Points of the collection at the first page of a stable scroll
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrollSnapshot {
+    /// Sorted IDs of the points of the scrolled shards, which matched the filter at the first page.
+    /// Points inserted by later operations are not read by the following pages.
+    pub shard_points: Vec<(ShardId, Vec<PointIdType>)>,
+}
+
+impl ScrollSnapshot {
+    /// Approximate size of the snapshot in memory
+    fn size(&self) -> usize {
+        size_of::<Self>()
+            + self
+                .shard_points
+                .iter()
+                .map(|(_, point_ids)| {
+                    size_of::<(ShardId, Vec<PointIdType>)>()
+                        + point_ids.len() * size_of::<PointIdType>()
+                })
+                .sum::<usize>()
+    }
+}
+
+struct PinnedSnapshot {
+    snapshot: Arc<ScrollSnapshot>,
+    size: usize,
+    last_access: Instant,
+}
+
+/// Cursors of stable scrolls of a collection.
+///
+/// A scroll pins a snapshot of the collection at its first page, the cursor of each following page
+/// is the id of the snapshot along with the last point ID of the previous page. Reading a page
+/// doesn't change the snapshot, so a page can be read again by the same cursor.
+/// Snapshots are kept in memory of the peer, which served the first page.
+#[derive(Default)]
+pub struct ScrollCursors {
+    snapshots: Mutex<HashMap<Uuid, PinnedSnapshot>>,
+}
+
+impl ScrollCursors {
+    /// Pin the snapshot of a new scroll, returns the id of the snapshot
+    pub fn pin(&self, snapshot: ScrollSnapshot) -> CollectionResult<(Uuid, Arc<ScrollSnapshot>)> {
+        let size = snapshot.size();
+        if size > MAX_SCROLL_CURSORS_SIZE {
+            return Err(CollectionError::bad_request(format!(
+                "Stable scroll pins too many points: {size} bytes, max {MAX_SCROLL_CURSORS_SIZE}"
+            )));
+        }
+
+        let now = Instant::now();
+        let mut snapshots = self.snapshots.lock();
+        snapshots.retain(|_, pinned| now.duration_since(pinned.last_access) < SCROLL_CURSOR_TTL);
+
+        let mut total_size: usize = snapshots.values().map(|pinned| pinned.size).sum();
+        while snapshots.len() >= MAX_SCROLL_CURSORS || total_size + size > MAX_SCROLL_CURSORS_SIZE {
+            let Some((&oldest, _)) = snapshots
+                .iter()
+                .min_by_key(|(_, pinned)| pinned.last_access)
+            else {
+                break;
+            };
+            total_size -= snapshots.remove(&oldest).map_or(0, |pinned| pinned.size);
+        }
+
+        let id = Uuid::new_v4();
+        let snapshot = Arc::new(snapshot);
+        snapshots.insert(
+            id,
+            PinnedSnapshot {
+                snapshot: snapshot.clone(),
+                size,
+                last_access: now,
+            },
+        );
+        Ok((id, snapshot))
+    }
+
+    /// Cursor of the page, which follows the given last point of the previous page
+    pub fn cursor(id: Uuid, last_point_id: PointIdType) -> String {
+        format!("{id}/{last_point_id}")
+    }
+
+    /// Find the snapshot of the cursor, also returns the last point ID of the previous page
+    pub fn get(&self, cursor: &str) -> CollectionResult<(Uuid, Arc<ScrollSnapshot>, PointIdType)> {
+        let not_found = || {
+            CollectionError::bad_request(format!("Scroll cursor {cursor} is not found or expired"))
+        };
+        let (id, last_point_id) = cursor.split_once('/').ok_or_else(not_found)?;
+        let id = Uuid::parse_str(id).map_err(|_| not_found())?;
+        let last_point_id = PointIdType::from_str(last_point_id).map_err(|_| not_found())?;
+
+        let now = Instant::now();
+        let mut snapshots = self.snapshots.lock();
+        let pinned = snapshots
+            .get_mut(&id)
+            .filter(|pinned| now.duration_since(pinned.last_access) < SCROLL_CURSOR_TTL)
+            .ok_or_else(not_found)?;
+        pinned.last_access = now;
+        Ok((id, pinned.snapshot.clone(), last_point_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(point_id: u64) -> ScrollSnapshot {
+        ScrollSnapshot {
+            shard_points: vec![(0, vec![PointIdType::from(point_id)])],
+        }
+    }
+
+    #[test]
+    fn test_scroll_cursor_pages() {
+        let cursors = ScrollCursors::default();
+        let (id, pinned) = cursors.pin(snapshot(10)).unwrap();
+        assert_eq!(*pinned, snapshot(10));
+
+        // Reading a cursor doesn't advance it, so the same page can be read again
+        let cursor = ScrollCursors::cursor(id, PointIdType::from(2));
+        for _ in 0..2 {
+            let (read_id, read_snapshot, last_point_id) = cursors.get(&cursor).unwrap();
+            assert_eq!(read_id, id);
+            assert_eq!(*read_snapshot, snapshot(10));
+            assert_eq!(last_point_id, PointIdType::from(2));
+        }
+
+        let uuid_point = PointIdType::Uuid(Uuid::new_v4());
+        let (_, _, last_point_id) = cursors.get(&ScrollCursors::cursor(id, uuid_point)).unwrap();
+        assert_eq!(last_point_id, uuid_point);
+
+        assert!(cursors.get(&id.to_string()).is_err());
+        assert!(cursors.get("not a cursor").is_err());
+        assert!(cursors
+            .get(&ScrollCursors::cursor(Uuid::new_v4(), PointIdType::from(2)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_scroll_cursors_limit() {
+        let cursors = ScrollCursors::default();
+        let (first, _) = cursors.pin(snapshot(0)).unwrap();
+        let (second, _) = cursors.pin(snapshot(1)).unwrap();
+        for point_id in 2..MAX_SCROLL_CURSORS as u64 {
+            cursors.pin(snapshot(point_id)).unwrap();
+        }
+
+        // Reading the first cursor makes the second one the least recently read
+        cursors
+            .get(&ScrollCursors::cursor(first, PointIdType::from(0)))
+            .unwrap();
+        cursors.pin(snapshot(0)).unwrap();
+
+        assert_eq!(cursors.snapshots.lock().len(), MAX_SCROLL_CURSORS);
+        assert!(cursors
+            .get(&ScrollCursors::cursor(first, PointIdType::from(0)))
+            .is_ok());
+        assert!(cursors
+            .get(&ScrollCursors::cursor(second, PointIdType::from(0)))
+            .is_err());
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use segment::types::{ScoredPoint, SeqNumberType};
+use serde::{Deserialize, Serialize};
+
+use crate::operations::types::SearchRequest;
+use crate::shards::shard::ShardId;
+
+/// Versions of the data of all shards of a collection
+pub type DataVersions = Vec<(ShardId, SeqNumberType)>;
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+pub struct SearchCacheTelemetry {
+    /// Number of cached search results
+    pub size: usize,
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Least recently used entries, evicted first when the capacity is exceeded
+#[derive(Default)]
+struct Lru {
+    /// Cached results and the tick of their last use
+    entries: HashMap<u64, (u64, Vec<ScoredPoint>)>,
+    /// Keys of the entries by the tick of their last use
+    recency: BTreeMap<u64, u64>,
+    tick: u64,
+}
+
+impl Lru {
+    fn touch(&mut self, key: u64) -> Option<&Vec<ScoredPoint>> {
+        let (last_used, result) = self.entries.get_mut(&key)?;
+        self.recency.remove(last_used);
+        self.tick += 1;
+        *last_used = self.tick;
+        self.recency.insert(self.tick, key);
+        Some(result)
+    }
+
+    fn insert(&mut self, key: u64, result: Vec<ScoredPoint>) {
+        self.tick += 1;
+        if let Some((last_used, _)) = self.entries.insert(key, (self.tick, result)) {
+            self.recency.remove(&last_used);
+        }
+        self.recency.insert(self.tick, key);
+    }
+
+    fn evict_to(&mut self, capacity: usize) {
+        while self.entries.len() > capacity {
+            let Some((_, key)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&key);
+        }
+    }
+}
+
+/// LRU cache of search results of a collection.
+///
+/// Results are keyed by the hash of the request together with the versions of the shards' data,
+/// so entries cached before any update of the collection are never hit again and age out.
+#[derive(Default)]
+pub struct SearchCache {
+    lru: Mutex<Lru>,
+    hits: AtomicUsize,
+    misses: AtomicUsize,
+}
+
+impl SearchCache {
+    pub fn key(request: &SearchRequest, versions: &DataVersions) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        // Serialized form covers vectors and scores, which are not `Hash`
+        serde_json::to_vec(request)
+            .expect("Search request is always serializable")
+            .hash(&mut hasher);
+        versions.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    pub fn get(&self, key: u64) -> Option<Vec<ScoredPoint>> {
+        let result = self.lru.lock().touch(key).cloned();
+        let counter = if result.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+        result
+    }
+
+    pub fn insert(&self, key: u64, result: Vec<ScoredPoint>, capacity: usize) {
+        let mut lru = self.lru.lock();
+        lru.insert(key, result);
+        lru.evict_to(capacity);
+    }
+
+    /// Drop the least recently used entries above the new `capacity`
+    pub fn resize(&self, capacity: usize) {
+        self.lru.lock().evict_to(capacity);
+    }
+
+    pub fn get_telemetry_data(&self) -> SearchCacheTelemetry {
+        SearchCacheTelemetry {
+            size: self.lru.lock().entries.len(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::PointIdType;
+
+    use super::*;
+
+    fn result(id: u64) -> Vec<ScoredPoint> {
+        vec![ScoredPoint {
+            id: PointIdType::from(id),
+            version: 0,
+            score: 1.0,
+            payload: None,
+            vector: None,
+            explanation: None,
+        }]
+    }
+
+    #[test]
+    fn test_search_cache_lru() {
+        let cache = SearchCache::default();
+        cache.insert(1, result(1), 2);
+        cache.insert(2, result(2), 2);
+
+        // Key 1 becomes the most recently used, so key 2 is evicted by the next insert
+        assert_eq!(cache.get(1), Some(result(1)));
+        cache.insert(3, result(3), 2);
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(3), Some(result(3)));
+
+        cache.resize(1);
+        assert_eq!(cache.get(1), None);
+
+        let telemetry = cache.get_telemetry_data();
+        assert_eq!(telemetry.size, 1);
+        assert_eq!(telemetry.hits, 2);
+        assert_eq!(telemetry.misses, 2);
+    }
+}
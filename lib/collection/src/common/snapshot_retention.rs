@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{Duration, NaiveDateTime};
+use io::file_operations::{atomic_save_json, read_json};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::operations::snapshot_ops::SnapshotDescription;
+use crate::operations::types::CollectionResult;
+
+pub const SNAPSHOT_RETENTION_FILE: &str = "snapshot_retention.json";
+
+/// Limits on the snapshots of the collection, stored on this peer.
+///
+/// Snapshots beyond any of the limits are deleted, oldest first.
+/// The latest snapshot is always kept.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SnapshotRetention {
+    /// Keep at most this number of the latest snapshots
+    #[validate(range(min = 1))]
+    pub keep_last: Option<usize>,
+    /// Delete snapshots older than this number of seconds
+    #[validate(range(min = 1))]
+    pub max_age_sec: Option<u64>,
+    /// Delete the oldest snapshots, while the total size of snapshots exceeds this number of bytes
+    pub max_total_size: Option<u64>,
+}
+
+impl SnapshotRetention {
+    /// Names of the snapshots, which are beyond the limits at `now`
+    pub fn expired(&self, snapshots: &[SnapshotDescription], now: NaiveDateTime) -> Vec<String> {
+        let mut snapshots: Vec<_> = snapshots.iter().collect();
+        // Latest first, snapshots of unknown age are considered the oldest
+        snapshots.sort_by(|a, b| {
+            b.creation_time
+                .cmp(&a.creation_time)
+                .then_with(|| b.name.cmp(&a.name))
+        });
+
+        let max_age = self
+            .max_age_sec
+            .map(|max_age_sec| Duration::seconds(max_age_sec as i64));
+
+        let mut total_size = 0;
+        let mut expired = Vec::new();
+        for (index, snapshot) in snapshots.into_iter().enumerate() {
+            total_size += snapshot.size;
+            if index == 0 {
+                continue;
+            }
+            let too_many = self.keep_last.map_or(false, |keep_last| index >= keep_last);
+            let too_old = max_age.map_or(false, |max_age| {
+                snapshot
+                    .creation_time
+                    .map_or(true, |creation_time| now - creation_time > max_age)
+            });
+            let too_large = self
+                .max_total_size
+                .map_or(false, |max_total_size| total_size > max_total_size);
+            if too_many || too_old || too_large {
+                expired.push(snapshot.name.clone());
+                total_size -= snapshot.size;
+            }
+        }
+        expired
+    }
+}
+
+/// Snapshot retention of the collection, persisted in the collection directory
+pub struct SnapshotRetentionPolicy {
+    path: PathBuf,
+    retention: Mutex<Option<SnapshotRetention>>,
+}
+
+impl SnapshotRetentionPolicy {
+    pub fn load(collection_path: &Path) -> Self {
+        let path = collection_path.join(SNAPSHOT_RETENTION_FILE);
+        let retention = if path.exists() {
+            read_json(&path)
+                .map_err(|err| {
+                    log::warn!(
+                        "Failed to load snapshot retention from {}: {err}",
+                        path.display()
+                    )
+                })
+                .ok()
+        } else {
+            None
+        };
+        Self {
+            path,
+            retention: Mutex::new(retention),
+        }
+    }
+
+    pub fn get(&self) -> Option<SnapshotRetention> {
+        self.retention.lock().clone()
+    }
+
+    pub fn set(&self, retention: Option<SnapshotRetention>) -> CollectionResult<()> {
+        let mut current = self.retention.lock();
+        match &retention {
+            Some(retention) => atomic_save_json(&self.path, retention)?,
+            None => {
+                if self.path.exists() {
+                    std::fs::remove_file(&self.path)?;
+                }
+            }
+        }
+        *current = retention;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    fn snapshot(name: &str, age_sec: i64, size: u64, now: NaiveDateTime) -> SnapshotDescription {
+        SnapshotDescription {
+            name: name.to_string(),
+            creation_time: Some(now - Duration::seconds(age_sec)),
+            size,
+            checksum: None,
+            version: None,
+            creation_duration_sec: None,
+            collection_params: None,
+            compatible: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_retention() {
+        let now = chrono::Utc::now().naive_utc();
+        let snapshots = vec![
+            snapshot("c", 10, 100, now),
+            snapshot("a", 1000, 100, now),
+            snapshot("b", 100, 100, now),
+        ];
+
+        let keep_last = SnapshotRetention {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(keep_last.expired(&snapshots, now), vec!["a"]);
+
+        let max_age = SnapshotRetention {
+            max_age_sec: Some(50),
+            ..Default::default()
+        };
+        assert_eq!(max_age.expired(&snapshots, now), vec!["b", "a"]);
+
+        let max_total_size = SnapshotRetention {
+            max_total_size: Some(250),
+            ..Default::default()
+        };
+        assert_eq!(max_total_size.expired(&snapshots, now), vec!["a"]);
+
+        // The latest snapshot is kept, even if it exceeds the limits
+        let strict = SnapshotRetention {
+            keep_last: Some(1),
+            max_age_sec: Some(1),
+            max_total_size: Some(1),
+        };
+        assert_eq!(strict.expired(&snapshots, now), vec!["b", "a"]);
+
+        assert!(SnapshotRetention::default()
+            .expired(&snapshots, now)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_retention_policy() {
+        let dir = Builder::new().prefix("collection").tempdir().unwrap();
+        let policy = SnapshotRetentionPolicy::load(dir.path());
+        assert!(policy.get().is_none());
+
+        let retention = SnapshotRetention {
+            keep_last: Some(3),
+            ..Default::default()
+        };
+        policy.set(Some(retention.clone())).unwrap();
+        assert_eq!(
+            SnapshotRetentionPolicy::load(dir.path()).get(),
+            Some(retention)
+        );
+
+        policy.set(None).unwrap();
+        assert!(SnapshotRetentionPolicy::load(dir.path()).get().is_none());
+    }
+}
@@ -0,0 +1,220 @@
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use io::file_operations::{atomic_save_json, read_json};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::operations::types::CollectionResult;
+
+pub const SNAPSHOT_SCHEDULE_FILE: &str = "snapshot_schedule.json";
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotTarget {
+    /// Keep snapshots in the snapshots directory of the peer
+    #[default]
+    Local,
+    /// Upload snapshots to the S3 bucket, configured in `storage.snapshots_s3`, and remove
+    /// the local copy
+    S3,
+}
+
+/// Automatic snapshots of the collection, created by this peer
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct SnapshotSchedule {
+    /// Cron expression in UTC, with seconds: `sec min hour day_of_month month day_of_week [year]`.
+    /// For example, `0 30 2 * * *` creates a snapshot every day at 02:30.
+    #[validate(custom = "validate_cron")]
+    pub cron: String,
+    /// Number of the latest scheduled snapshots to keep, older ones are deleted.
+    /// All are kept, if not set
+    #[validate(range(min = 1))]
+    pub retention: Option<usize>,
+    /// Where to store the snapshots. Default: local
+    #[serde(default)]
+    pub target: SnapshotTarget,
+}
+
+fn validate_cron(cron: &str) -> Result<(), ValidationError> {
+    cron::Schedule::from_str(cron)
+        .map(|_| ())
+        .map_err(|_| ValidationError::new("invalid cron expression"))
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct SnapshotScheduleInfo {
+    pub schedule: SnapshotSchedule,
+    /// Time of the last scheduled snapshot
+    pub last_run: Option<DateTime<Utc>>,
+    /// Time of the next scheduled snapshot
+    pub next_run: Option<DateTime<Utc>>,
+    /// Snapshots created by the schedule and not deleted by retention yet, oldest first
+    pub snapshots: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct ScheduleState {
+    schedule: SnapshotSchedule,
+    created_at: DateTime<Utc>,
+    last_run: Option<DateTime<Utc>>,
+    snapshots: Vec<String>,
+}
+
+impl ScheduleState {
+    fn next_run(&self) -> Option<DateTime<Utc>> {
+        let schedule = cron::Schedule::from_str(&self.schedule.cron).ok()?;
+        let after = self.last_run.unwrap_or(self.created_at);
+        schedule.after(&after).next()
+    }
+}
+
+/// Snapshot schedule of the collection, persisted in the collection directory
+pub struct ScheduledSnapshots {
+    path: PathBuf,
+    state: Mutex<Option<ScheduleState>>,
+}
+
+impl ScheduledSnapshots {
+    pub fn load(collection_path: &Path) -> Self {
+        let path = collection_path.join(SNAPSHOT_SCHEDULE_FILE);
+        let state = if path.exists() {
+            read_json(&path)
+                .map_err(|err| {
+                    log::warn!(
+                        "Failed to load snapshot schedule from {}: {err}",
+                        path.display()
+                    )
+                })
+                .ok()
+        } else {
+            None
+        };
+        Self {
+            path,
+            state: Mutex::new(state),
+        }
+    }
+
+    pub fn info(&self) -> Option<SnapshotScheduleInfo> {
+        self.state
+            .lock()
+            .as_ref()
+            .map(|state| SnapshotScheduleInfo {
+                schedule: state.schedule.clone(),
+                last_run: state.last_run,
+                next_run: state.next_run(),
+                snapshots: state.snapshots.clone(),
+            })
+    }
+
+    /// Replace the schedule, snapshots created by the previous one are still subject to retention
+    pub fn set(&self, schedule: Option<SnapshotSchedule>) -> CollectionResult<()> {
+        let mut state = self.state.lock();
+        match schedule {
+            Some(schedule) => {
+                let new_state = ScheduleState {
+                    schedule,
+                    created_at: Utc::now(),
+                    last_run: None,
+                    snapshots: state
+                        .take()
+                        .map(|state| state.snapshots)
+                        .unwrap_or_default(),
+                };
+                atomic_save_json(&self.path, &new_state)?;
+                *state = Some(new_state);
+            }
+            None => {
+                if self.path.exists() {
+                    std::fs::remove_file(&self.path)?;
+                }
+                *state = None;
+            }
+        }
+        Ok(())
+    }
+
+    /// Schedule, if the snapshot is due at `now`
+    pub fn due(&self, now: DateTime<Utc>) -> Option<SnapshotSchedule> {
+        let state = self.state.lock();
+        let state = state.as_ref()?;
+        (state.next_run()? <= now).then(|| state.schedule.clone())
+    }
+
+    /// Record the created snapshot, returns snapshots to delete by the retention
+    pub fn record(
+        &self,
+        snapshot_name: String,
+        created_at: DateTime<Utc>,
+    ) -> CollectionResult<Vec<String>> {
+        let mut state = self.state.lock();
+        let Some(state) = state.as_mut() else {
+            // Schedule is removed while the snapshot was created
+            return Ok(vec![]);
+        };
+        state.last_run = Some(created_at);
+        state.snapshots.push(snapshot_name);
+        let expired = match state.schedule.retention {
+            Some(retention) if state.snapshots.len() > retention => {
+                let expired_count = state.snapshots.len() - retention;
+                state.snapshots.drain(..expired_count).collect()
+            }
+            _ => vec![],
+        };
+        atomic_save_json(&self.path, &*state)?;
+        Ok(expired)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Duration;
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn test_scheduled_snapshots() {
+        let dir = Builder::new().prefix("collection").tempdir().unwrap();
+        let scheduled = ScheduledSnapshots::load(dir.path());
+        assert!(scheduled.info().is_none());
+        assert!(scheduled.due(Utc::now()).is_none());
+
+        let schedule = SnapshotSchedule {
+            cron: "0 0 * * * *".to_string(),
+            retention: Some(2),
+            target: SnapshotTarget::Local,
+        };
+        assert!(schedule.validate().is_ok());
+        scheduled.set(Some(schedule.clone())).unwrap();
+
+        let now = Utc::now();
+        assert!(scheduled.due(now).is_none());
+        assert_eq!(scheduled.due(now + Duration::hours(1)), Some(schedule));
+
+        for name in ["a", "b"] {
+            assert!(scheduled.record(name.to_string(), now).unwrap().is_empty());
+        }
+        assert_eq!(scheduled.record("c".to_string(), now).unwrap(), vec!["a"]);
+
+        // The schedule survives restart
+        let loaded = ScheduledSnapshots::load(dir.path()).info().unwrap();
+        assert_eq!(loaded.snapshots, vec!["b", "c"]);
+        assert_eq!(loaded.last_run, Some(now));
+
+        scheduled.set(None).unwrap();
+        assert!(ScheduledSnapshots::load(dir.path()).info().is_none());
+
+        let invalid = SnapshotSchedule {
+            cron: "every hour".to_string(),
+            retention: None,
+            target: SnapshotTarget::S3,
+        };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -1,8 +1,9 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::num::NonZeroU32;
 use std::path::Path;
+use std::time::Duration;
 
 use atomicwrites::AtomicFile;
 use atomicwrites::OverwriteBehavior::AllowOverwrite;
@@ -10,7 +11,8 @@ use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
 use segment::types::{
-    HnswConfig, Indexes, QuantizationConfig, VectorDataConfig, VectorStorageType,
+    Distance, HnswConfig, Indexes, PayloadKeyType, QuantizationConfig, SparseIndexConfig,
+    SparseVectorDataConfig, VectorDataConfig, VectorStorageType,
 };
 use serde::{Deserialize, Serialize};
 use validator::Validate;
@@ -18,14 +20,55 @@ use wal::WalOptions;
 
 use crate::operations::config_diff::{DiffConfig, QuantizationConfigDiff};
 use crate::operations::types::{
-    CollectionError, CollectionResult, VectorParams, VectorParamsDiff, VectorsConfig,
-    VectorsConfigDiff,
+    CollectionError, CollectionResult, SparseVectorParams, VectorParams, VectorParamsDiff,
+    VectorsConfig, VectorsConfigDiff,
 };
 use crate::operations::validation;
 use crate::optimizers_builder::OptimizersConfig;
+use crate::wal::{WalCompression, WalRetention};
 
 pub const COLLECTION_CONFIG_FILE: &str = "config.json";
 
+pub const DEFAULT_WAL_FSYNC_INTERVAL_MS: u64 = 100;
+
+/// When the WAL is synced to disk.
+///
+/// In every mode, operations, which wait for the result, are synced before they are applied.
+/// The WAL is also synced on the periodic flush of the collection, which is required before
+/// the WAL can be truncated. Other operations, written after the last sync, may be lost on crash.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WalDurability {
+    /// Sync only before applying operations, which wait for the result
+    #[default]
+    WaitedOperations,
+    /// Sync after writing every operation
+    EveryOperation,
+    /// Sync every `wal_fsync_interval_ms` milliseconds
+    Interval,
+    /// Sync once records of a whole WAL segment are written since the last sync
+    SegmentRotation,
+}
+
+impl WalDurability {
+    /// Whether the WAL is synced right after an operation is written.
+    ///
+    /// `segment_filled` tells, if records of a whole WAL segment were written since the last sync.
+    pub fn sync_after_write(self, segment_filled: bool) -> bool {
+        match self {
+            WalDurability::EveryOperation => true,
+            WalDurability::SegmentRotation => segment_filled,
+            WalDurability::WaitedOperations | WalDurability::Interval => false,
+        }
+    }
+
+    /// Whether the WAL is synced before a written operation is applied
+    pub fn sync_before_apply(self, wait: bool) -> bool {
+        // Operations are already synced on write
+        wait && self != WalDurability::EveryOperation
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq)]
 pub struct WalConfig {
     /// Size of a single WAL segment in MB
@@ -33,6 +76,54 @@ pub struct WalConfig {
     pub wal_capacity_mb: usize,
     /// Number of WAL segments to create ahead of actually used ones
     pub wal_segments_ahead: usize,
+    /// Compression of the WAL records, applies to records written from now on
+    #[serde(default)]
+    pub wal_compression: WalCompression,
+    /// When the WAL is synced to disk
+    #[serde(default)]
+    pub wal_durability: WalDurability,
+    /// Interval between WAL syncs in milliseconds, used with `interval` durability.
+    /// Default: 100
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub wal_fsync_interval_ms: Option<u64>,
+    /// Keep at most this number of already applied operations in the WAL.
+    /// Applied operations allow to read changes and recover the collection to a point in time
+    /// after a snapshot. They are kept while within all of the set retention limits, none by default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_retention_ops: Option<u64>,
+    /// Keep already applied operations in the WAL for at most this number of seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_retention_sec: Option<u64>,
+    /// Keep at most this size of already applied operations in the WAL, in MB
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_retention_mb: Option<usize>,
+}
+
+impl WalConfig {
+    /// Interval of the background WAL sync, if required by the durability mode
+    pub fn fsync_interval(&self) -> Option<Duration> {
+        match self.wal_durability {
+            WalDurability::Interval => Some(Duration::from_millis(
+                self.wal_fsync_interval_ms
+                    .unwrap_or(DEFAULT_WAL_FSYNC_INTERVAL_MS),
+            )),
+            WalDurability::WaitedOperations
+            | WalDurability::EveryOperation
+            | WalDurability::SegmentRotation => None,
+        }
+    }
+
+    /// Limits of the applied operations, kept in the WAL
+    pub fn retention(&self) -> WalRetention {
+        WalRetention {
+            records: self.wal_retention_ops,
+            duration: self.wal_retention_sec.map(Duration::from_secs),
+            size_bytes: self
+                .wal_retention_mb
+                .map(|retention_mb| retention_mb as u64 * 1024 * 1024),
+        }
+    }
 }
 
 impl From<&WalConfig> for WalOptions {
@@ -49,6 +140,12 @@ impl Default for WalConfig {
         WalConfig {
             wal_capacity_mb: 32,
             wal_segments_ahead: 0,
+            wal_compression: WalCompression::default(),
+            wal_durability: WalDurability::default(),
+            wal_fsync_interval_ms: None,
+            wal_retention_ops: None,
+            wal_retention_sec: None,
+            wal_retention_mb: None,
         }
     }
 }
@@ -59,6 +156,9 @@ pub struct CollectionParams {
     /// Configuration of the vector storage
     #[validate]
     pub vectors: VectorsConfig,
+    /// Configuration of the sparse vector storage
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
     /// Number of shards the collection has
     #[serde(default = "default_shard_number")]
     pub shard_number: NonZeroU32,
@@ -83,17 +183,59 @@ pub struct CollectionParams {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default = "default_on_disk_payload")]
     pub on_disk_payload: bool,
+    /// Number of search results to keep in the cache of the collection, disabled if not set or 0.
+    /// Identical searches are served from the cache until the collection is updated.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_cache_size: Option<usize>,
+    /// WASM module, which scores the candidates of `plugin` queries
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring_plugin: Option<ScoringPluginConfig>,
+}
+
+/// Custom scoring of the query candidates by a WASM module.
+///
+/// The module must export function `score`, which takes `f32` arguments and returns `f32` score,
+/// larger is better. Arguments are the scores of a candidate in each prefetch of the query, `NaN`
+/// if the candidate is not found by the prefetch, followed by the numeric payload `features` of the
+/// candidate, `NaN` if the value is missing. The module can't import anything, its execution and
+/// memory are limited.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct ScoringPluginConfig {
+    /// Name of the plugin, queries select the plugin by it
+    pub name: String,
+    /// Version of the plugin, must be increased with every change of the plugin
+    pub version: u64,
+    /// Base64 encoded WASM module
+    pub module: String,
+    /// Payload keys, whose values are passed to the `score` function after the prefetch scores
+    #[serde(default)]
+    pub features: Vec<PayloadKeyType>,
+}
+
+impl Anonymize for ScoringPluginConfig {
+    fn anonymize(&self) -> Self {
+        ScoringPluginConfig {
+            name: self.name.anonymize(),
+            version: self.version,
+            module: String::new(),
+            features: self.features.anonymize(),
+        }
+    }
 }
 
 impl Anonymize for CollectionParams {
     fn anonymize(&self) -> Self {
         CollectionParams {
             vectors: self.vectors.anonymize(),
+            sparse_vectors: None,
             shard_number: self.shard_number,
             replication_factor: self.replication_factor,
             write_consistency_factor: self.write_consistency_factor,
             read_fan_out_factor: self.read_fan_out_factor,
             on_disk_payload: self.on_disk_payload,
+            search_cache_size: self.search_cache_size,
+            scoring_plugin: self.scoring_plugin.anonymize(),
         }
     }
 }
@@ -164,11 +306,14 @@ impl CollectionParams {
     pub fn empty() -> Self {
         CollectionParams {
             vectors: VectorsConfig::empty(),
+            sparse_vectors: None,
             shard_number: default_shard_number(),
             replication_factor: default_replication_factor(),
             write_consistency_factor: default_write_consistency_factor(),
             read_fan_out_factor: None,
             on_disk_payload: default_on_disk_payload(),
+            search_cache_size: None,
+            scoring_plugin: None,
         }
     }
 
@@ -185,6 +330,22 @@ impl CollectionParams {
             })
     }
 
+    /// Get distance function used for scoring the given vector
+    ///
+    /// Sparse vectors are always scored with dot product.
+    pub fn get_distance(&self, vector_name: &str) -> CollectionResult<Distance> {
+        if self
+            .sparse_vectors
+            .as_ref()
+            .map_or(false, |sparse_vectors| {
+                sparse_vectors.contains_key(vector_name)
+            })
+        {
+            return Ok(Distance::Dot);
+        }
+        Ok(self.get_vector_params(vector_name)?.distance)
+    }
+
     fn get_vector_params_mut(&mut self, vector_name: &str) -> CollectionResult<&mut VectorParams> {
         self.vectors
             .get_params_mut(vector_name)
@@ -202,8 +363,10 @@ impl CollectionParams {
         &mut self,
         update_vectors_diff: &VectorsConfigDiff,
     ) -> CollectionResult<()> {
+        // Validate all updated params before changing any of them
+        let mut updated_params = Vec::with_capacity(update_vectors_diff.0.len());
         for (vector_name, update_params) in update_vectors_diff.0.iter() {
-            let vector_params = self.get_vector_params_mut(vector_name)?;
+            let mut vector_params = self.get_vector_params(vector_name)?;
 
             let VectorParamsDiff {
                 hnsw_config,
@@ -237,6 +400,100 @@ impl CollectionParams {
             if let Some(on_disk) = on_disk {
                 vector_params.on_disk = Some(on_disk);
             }
+
+            vector_params.validate()?;
+            updated_params.push((vector_name, vector_params));
+        }
+
+        for (vector_name, vector_params) in updated_params {
+            *self.get_vector_params_mut(vector_name)? = vector_params;
+        }
+        Ok(())
+    }
+
+    /// Add new vectors to the collection
+    ///
+    /// Names of the new vectors must not be used by any existing dense or sparse vector.
+    pub fn add_vectors(&mut self, new_vectors: &VectorsConfig) -> CollectionResult<()> {
+        for (vector_name, _) in new_vectors.params_iter() {
+            let is_sparse = self
+                .sparse_vectors
+                .as_ref()
+                .map_or(false, |sparse_vectors| {
+                    sparse_vectors.contains_key(vector_name)
+                });
+            if is_sparse || self.vectors.get_params(vector_name).is_some() {
+                return Err(CollectionError::BadInput {
+                    description: format!("Vector {vector_name} already exists in collection"),
+                });
+            }
+        }
+
+        if self.vectors.vectors_num() == 0 {
+            self.vectors = new_vectors.clone();
+            return Ok(());
+        }
+
+        let vectors = self
+            .vectors
+            .params_iter()
+            .chain(new_vectors.params_iter())
+            .map(|(vector_name, params)| (vector_name.to_owned(), params.clone()))
+            .collect();
+        self.vectors = VectorsConfig::Multi(vectors);
+        Ok(())
+    }
+
+    /// Remove dense or sparse vectors from the collection
+    ///
+    /// All given vectors must exist and at least one vector must remain, otherwise nothing is
+    /// removed.
+    pub fn drop_vectors(&mut self, vector_names: &[String]) -> CollectionResult<()> {
+        for vector_name in vector_names {
+            let is_sparse = self
+                .sparse_vectors
+                .as_ref()
+                .map_or(false, |sparse_vectors| {
+                    sparse_vectors.contains_key(vector_name)
+                });
+            if !is_sparse && self.vectors.get_params(vector_name).is_none() {
+                return Err(CollectionError::BadInput {
+                    description: format!("Vector {vector_name} does not exist in collection"),
+                });
+            }
+        }
+
+        let remaining_vectors = self
+            .vectors
+            .params_iter()
+            .map(|(vector_name, _)| vector_name)
+            .chain(
+                self.sparse_vectors
+                    .iter()
+                    .flat_map(|sparse| sparse.keys().map(String::as_str)),
+            )
+            .filter(|vector_name| !vector_names.iter().any(|name| name == *vector_name))
+            .count();
+        if remaining_vectors == 0 {
+            return Err(CollectionError::BadInput {
+                description: "Can't remove the last vector of the collection".to_string(),
+            });
+        }
+
+        for vector_name in vector_names {
+            if let Some(sparse_vectors) = &mut self.sparse_vectors {
+                sparse_vectors.remove(vector_name);
+            }
+            match &mut self.vectors {
+                VectorsConfig::Single(_) => {
+                    if vector_name == DEFAULT_VECTOR_NAME {
+                        self.vectors = VectorsConfig::empty();
+                    }
+                }
+                VectorsConfig::Multi(vectors) => {
+                    vectors.remove(vector_name);
+                }
+            }
         }
         Ok(())
     }
@@ -265,6 +522,31 @@ impl CollectionParams {
                         } else {
                             VectorStorageType::Memory
                         },
+                        multivector_config: params.multivector_config,
+                        datatype: params.datatype,
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Convert into named sparse vector data configs
+    pub fn into_sparse_vector_data(
+        &self,
+    ) -> CollectionResult<HashMap<String, SparseVectorDataConfig>> {
+        Ok(self
+            .sparse_vectors
+            .iter()
+            .flatten()
+            .map(|(name, params)| {
+                (
+                    name.into(),
+                    SparseVectorDataConfig {
+                        index: SparseIndexConfig {
+                            full_scan_threshold: params
+                                .index
+                                .and_then(|index| index.full_scan_threshold),
+                        },
                     },
                 )
             })
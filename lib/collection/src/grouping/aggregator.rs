@@ -196,6 +196,7 @@ mod unit_tests {
             score,
             payload: Some(Payload::from(serde_json::json!({ "docId": payloads }))),
             vector: None,
+            explanation: None,
         }
     }
 
@@ -206,6 +207,7 @@ mod unit_tests {
             score,
             payload: None,
             vector: None,
+            explanation: None,
         }
     }
 
@@ -7,6 +7,7 @@ use segment::types::{
     AnyVariants, Condition, FieldCondition, Filter, Match, ScoredPoint, WithPayloadInterface,
     WithVector,
 };
+use serde::Serialize;
 use serde_json::Value;
 use tokio::sync::RwLockReadGuard;
 
@@ -16,7 +17,8 @@ use crate::lookup::WithLookup;
 use crate::operations::consistency_params::ReadConsistency;
 use crate::operations::types::{
     BaseGroupRequest, CollectionError, CollectionResult, PointGroup, RecommendGroupsRequest,
-    RecommendRequest, SearchGroupsRequest, SearchRequest, UsingVector,
+    RecommendRequest, SearchGroupsRequest, SearchRequest, SearchRequestBatch, SearchVector,
+    UsingVector,
 };
 use crate::recommendations::recommend_by;
 use crate::shards::shard::ShardId;
@@ -24,7 +26,8 @@ use crate::shards::shard::ShardId;
 const MAX_GET_GROUPS_REQUESTS: usize = 5;
 const MAX_GROUP_FILLING_REQUESTS: usize = 5;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SourceRequest {
     Search(SearchRequest),
     Recommend(RecommendRequest),
@@ -70,7 +73,7 @@ impl SourceRequest {
     }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Serialize)]
 pub struct GroupRequest {
     /// Request to use (search or recommend)
     pub source: SourceRequest,
@@ -199,6 +202,7 @@ impl From<SearchGroupsRequest> for GroupRequest {
             with_payload,
             with_vector,
             score_threshold,
+            text: None,
         };
 
         GroupRequest {
@@ -260,7 +264,7 @@ impl From<RecommendGroupsRequest> for GroupRequest {
 
 /// Uses the request to fill up groups of points.
 pub async fn group_by<'a, F, Fut>(
-    request: GroupRequest,
+    mut request: GroupRequest,
     collection: &Collection,
     // Obligatory for recommend
     collection_by_name: F,
@@ -271,11 +275,31 @@ where
     F: Fn(String) -> Fut + Clone,
     Fut: Future<Output = Option<RwLockReadGuard<'a, Collection>>>,
 {
+    // Vector of the point is retrieved once, instead of by each of the searches below
+    if let SourceRequest::Search(
+        search @ SearchRequest {
+            vector: SearchVector::Point(_),
+            ..
+        },
+    ) = &request.source
+    {
+        let resolved = collection
+            .resolve_point_vectors(
+                SearchRequestBatch {
+                    searches: vec![search.clone()],
+                },
+                read_consistency,
+                shard_selection,
+            )
+            .await?;
+        request.source = SourceRequest::Search(resolved.searches.into_iter().next().unwrap());
+    }
+
     let score_ordering = {
         let vector_name = request.source.vector_field_name();
         let collection_params = collection.collection_config.read().await;
-        let vector_params = collection_params.params.get_vector_params(vector_name)?;
-        vector_params.distance.distance_order()
+        let distance = collection_params.params.get_distance(vector_name)?;
+        distance.distance_order()
     };
 
     let mut aggregator = GroupsAggregator::new(
@@ -482,6 +506,7 @@ mod tests {
                         score: 1.0,
                         payload: None,
                         vector: None,
+                        explanation: None,
                     },
                     ScoredPoint {
                         id: 2.into(),
@@ -489,6 +514,7 @@ mod tests {
                         score: 1.0,
                         payload: None,
                         vector: None,
+                        explanation: None,
                     },
                 ],
             ),
@@ -501,6 +527,7 @@ mod tests {
                         score: 1.0,
                         payload: None,
                         vector: None,
+                        explanation: None,
                     },
                     ScoredPoint {
                         id: 4.into(),
@@ -508,6 +535,7 @@ mod tests {
                         score: 1.0,
                         payload: None,
                         vector: None,
+                        explanation: None,
                     },
                 ],
             ),
@@ -531,6 +559,7 @@ mod tests {
                 score: 1.0,
                 payload: Some(payload_a.clone()),
                 vector: None,
+                explanation: None,
             },
             ScoredPoint {
                 id: 2.into(),
@@ -538,6 +567,7 @@ mod tests {
                 score: 1.0,
                 payload: Some(payload_a.clone()),
                 vector: None,
+                explanation: None,
             },
             ScoredPoint {
                 id: 3.into(),
@@ -545,6 +575,7 @@ mod tests {
                 score: 1.0,
                 payload: Some(payload_b.clone()),
                 vector: None,
+                explanation: None,
             },
             ScoredPoint {
                 id: 4.into(),
@@ -552,6 +583,7 @@ mod tests {
                 score: 1.0,
                 payload: Some(payload_b.clone()),
                 vector: None,
+                explanation: None,
             },
         ];
 
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+
+use schemars::JsonSchema;
+use segment::types::{PointIdType, SeqNumberType};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use super::payload_ops::{DeletePayload, MutatePayload, PayloadOps, SetPayload};
+use super::point_ops::{
+    FilterSelector, PointIdsList, PointInsertOperations, PointOperations, PointStruct,
+    PointsSelector, UpsertCondition,
+};
+use super::vector_ops::{DeleteVectors, UpdateVectors, VectorOperations};
+use super::CollectionUpdateOperations;
+use crate::shards::shard::ShardId;
+
+pub const DEFAULT_CHANGES_LIMIT: usize = 100;
+
+/// Position in the changes of a collection: number of the next operation to read from each shard.
+///
+/// Operation numbers are assigned by the WAL of each replica,
+/// so the offset is only valid on the peer, which returned it.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct ChangesOffset(pub BTreeMap<ShardId, SeqNumberType>);
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct ChangesRequest {
+    /// Continue after the changes, returned by the previous request.
+    /// If not specified, or if it has no entry for a shard - start from the earliest change, which is still stored.
+    pub offset: Option<ChangesOffset>,
+    /// Maximal number of operations to read, a batch operation may contain several changes.
+    /// Default: 100
+    #[validate(range(min = 1))]
+    pub limit: Option<usize>,
+    /// Wait up to this number of seconds for new changes, if there are none yet. Default: 0
+    pub timeout: Option<u64>,
+}
+
+/// Change of the points, in the same form as in the update API
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PointsChange {
+    Upsert(PointInsertOperations),
+    /// Upsert, which is applied only if the condition held at the time of the update
+    UpsertConditional {
+        points: PointInsertOperations,
+        condition: UpsertCondition,
+    },
+    Delete(PointsSelector),
+    /// Points in the ID range are replaced with the given points, other points in the range are deleted
+    Sync {
+        from_id: Option<PointIdType>,
+        to_id: Option<PointIdType>,
+        points: Vec<PointStruct>,
+    },
+    UpdateVectors(UpdateVectors),
+    DeleteVectors(DeleteVectors),
+    SetPayload(SetPayload),
+    OverwritePayload(SetPayload),
+    MergePayload(SetPayload),
+    DeletePayload(DeletePayload),
+    ClearPayload(PointsSelector),
+    MutatePayload(MutatePayload),
+}
+
+impl PointsChange {
+    /// Changes of the points, made by the operation.
+    /// Operations, which don't change points, like creation of payload index, have none.
+    pub fn from_operation(operation: CollectionUpdateOperations) -> Vec<PointsChange> {
+        let change = match operation {
+            CollectionUpdateOperations::PointOperation(operation) => match operation {
+                PointOperations::UpsertPoints(points) => PointsChange::Upsert(points),
+                PointOperations::UpsertPointsConditional(operation) => {
+                    PointsChange::UpsertConditional {
+                        points: operation.points,
+                        condition: operation.condition,
+                    }
+                }
+                PointOperations::DeletePoints { ids } => PointsChange::Delete(points_selector(ids)),
+                PointOperations::DeletePointsByFilter(filter) => {
+                    PointsChange::Delete(PointsSelector::FilterSelector(FilterSelector { filter }))
+                }
+                PointOperations::SyncPoints(operation) => PointsChange::Sync {
+                    from_id: operation.from_id,
+                    to_id: operation.to_id,
+                    points: operation.points,
+                },
+            },
+            CollectionUpdateOperations::VectorOperation(operation) => match operation {
+                VectorOperations::UpdateVectors(update) => PointsChange::UpdateVectors(update),
+                VectorOperations::DeleteVectors(points, vector) => {
+                    PointsChange::DeleteVectors(DeleteVectors {
+                        points: Some(points.points),
+                        filter: None,
+                        vector: vector.into_iter().collect(),
+                    })
+                }
+                VectorOperations::DeleteVectorsByFilter(filter, vector) => {
+                    PointsChange::DeleteVectors(DeleteVectors {
+                        points: None,
+                        filter: Some(filter),
+                        vector: vector.into_iter().collect(),
+                    })
+                }
+            },
+            CollectionUpdateOperations::PayloadOperation(operation) => match operation {
+                PayloadOps::SetPayload(payload) => PointsChange::SetPayload(payload),
+                PayloadOps::DeletePayload(payload) => PointsChange::DeletePayload(payload),
+                PayloadOps::ClearPayload { points } => {
+                    PointsChange::ClearPayload(points_selector(points))
+                }
+                PayloadOps::ClearPayloadByFilter(filter) => {
+                    PointsChange::ClearPayload(PointsSelector::FilterSelector(FilterSelector {
+                        filter,
+                    }))
+                }
+                PayloadOps::OverwritePayload(payload) => PointsChange::OverwritePayload(payload),
+                PayloadOps::MergePayload(payload) => PointsChange::MergePayload(payload),
+                PayloadOps::MutatePayload(mutation) => PointsChange::MutatePayload(mutation),
+            },
+            CollectionUpdateOperations::FieldIndexOperation(_) => return Vec::new(),
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                return operations
+                    .into_iter()
+                    .flat_map(PointsChange::from_operation)
+                    .collect()
+            }
+            CollectionUpdateOperations::ClockedOperation(clocked) => {
+                return PointsChange::from_operation(*clocked.operation)
+            }
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => {
+                return PointsChange::from_operation(*idempotent.operation)
+            }
+        };
+        vec![change]
+    }
+}
+
+fn points_selector(points: Vec<PointIdType>) -> PointsSelector {
+    PointsSelector::PointIdsSelector(PointIdsList { points })
+}
+
+/// Change of the points, written to the WAL of a shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct CollectionChange {
+    pub shard_id: ShardId,
+    /// Number of the operation in the WAL of the shard.
+    /// Changes, made by a single batch operation, have the same number.
+    pub operation_id: SeqNumberType,
+    pub change: PointsChange,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ChangesResult {
+    /// Changes in the order they were applied to each shard
+    pub changes: Vec<CollectionChange>,
+    /// Offset to continue after these changes
+    pub next_offset: ChangesOffset,
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::types::Filter;
+
+    use super::*;
+    use crate::operations::FieldIndexOperations;
+
+    #[test]
+    fn test_points_change_from_operation() {
+        let operation = CollectionUpdateOperations::BatchOperation(vec![
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+                ids: vec![1.into(), 2.into()],
+            }),
+            CollectionUpdateOperations::FieldIndexOperation(FieldIndexOperations::DeleteIndex(
+                "field".to_string(),
+            )),
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayloadByFilter(
+                Filter::default(),
+            )),
+        ]);
+
+        let changes = PointsChange::from_operation(operation);
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(
+            &changes[0],
+            PointsChange::Delete(PointsSelector::PointIdsSelector(PointIdsList { points })) if points.len() == 2
+        ));
+        assert!(matches!(
+            &changes[1],
+            PointsChange::ClearPayload(PointsSelector::FilterSelector(_))
+        ));
+
+        let json = serde_json::to_value(&changes[0]).unwrap();
+        assert_eq!(json, serde_json::json!({"delete": {"points": [1, 2]}}));
+    }
+}
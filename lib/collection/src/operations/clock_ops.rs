@@ -0,0 +1,320 @@
+use std::collections::HashSet;
+
+use segment::types::{Condition, Filter, HasIdCondition, PointIdType};
+use serde::{Deserialize, Serialize};
+
+use super::payload_ops::{DeletePayload, PayloadOps, SetPayload};
+use super::point_ops::{PointIdsList, PointInsertOperations, PointOperations};
+use super::vector_ops::{UpdateVectors, VectorOperations};
+use super::CollectionUpdateOperations;
+use crate::shards::shard::PeerId;
+
+/// Logical clock of an update operation, assigned by the replica set, which received the update.
+///
+/// Clocks are ordered by tick first and by peer then,
+/// so concurrent operations are ordered the same way on all replicas.
+#[derive(
+    Debug, Deserialize, Serialize, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct ClockTag {
+    pub clock_tick: u64,
+    pub peer_id: PeerId,
+}
+
+impl ClockTag {
+    pub fn new(clock_tick: u64, peer_id: PeerId) -> Self {
+        Self {
+            clock_tick,
+            peer_id,
+        }
+    }
+}
+
+/// Update operation with the clock, assigned by the replica set.
+///
+/// A replica applies the operation only to the points, whose changed part
+/// was not replaced by an operation with a higher clock yet, see [`ClockedChange`].
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ClockedOperation {
+    pub clock_tag: ClockTag,
+    /// Clocks of specific points, which override the clock of the operation.
+    /// E.g. clocks of the points, copied from another replica by a shard transfer.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub point_clock_tags: Vec<(PointIdType, ClockTag)>,
+    pub operation: Box<CollectionUpdateOperations>,
+}
+
+impl ClockedOperation {
+    pub fn new(clock_tag: ClockTag, operation: CollectionUpdateOperations) -> Self {
+        Self {
+            clock_tag,
+            point_clock_tags: Vec::new(),
+            operation: Box::new(operation),
+        }
+    }
+}
+
+/// Part of the points, which an operation changes.
+///
+/// Only operations, which replace the whole point or the whole payload, are ordered by clocks.
+/// Partial updates are applied in any order, unless the part they change
+/// was replaced by an operation with a higher clock.
+/// Partial updates of the same key or vector still end up in the order they are received in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClockedChange {
+    /// Replaces or deletes whole points
+    Points,
+    /// Replaces the whole payload of the points
+    Payload,
+    /// Changes some payload keys of the points
+    PayloadKeys,
+    /// Changes some vectors of the points
+    Vectors,
+    /// Doesn't change the points
+    None,
+}
+
+impl CollectionUpdateOperations {
+    /// Part of the points, which the operation changes.
+    /// Wrapped and batched operations are expected to be unwrapped first.
+    pub fn clocked_change(&self) -> ClockedChange {
+        match self {
+            CollectionUpdateOperations::PointOperation(_) => ClockedChange::Points,
+            CollectionUpdateOperations::VectorOperation(_) => ClockedChange::Vectors,
+            CollectionUpdateOperations::PayloadOperation(operation) => match operation {
+                PayloadOps::OverwritePayload(_)
+                | PayloadOps::ClearPayload { .. }
+                | PayloadOps::ClearPayloadByFilter(_) => ClockedChange::Payload,
+                PayloadOps::SetPayload(_)
+                | PayloadOps::DeletePayload(_)
+                | PayloadOps::MergePayload(_)
+                | PayloadOps::MutatePayload(_) => ClockedChange::PayloadKeys,
+            },
+            CollectionUpdateOperations::FieldIndexOperation(_)
+            | CollectionUpdateOperations::BatchOperation(_)
+            | CollectionUpdateOperations::ClockedOperation(_)
+            | CollectionUpdateOperations::IdempotentOperation(_) => ClockedChange::None,
+        }
+    }
+}
+
+fn retain_ids(ids: &mut Vec<PointIdType>, excluded: &HashSet<PointIdType>) {
+    ids.retain(|id| !excluded.contains(id));
+}
+
+fn filter_without_points(mut filter: Filter, excluded: &HashSet<PointIdType>) -> Filter {
+    filter
+        .must_not
+        .get_or_insert_with(Vec::new)
+        .push(Condition::HasId(HasIdCondition::from(excluded.clone())));
+    filter
+}
+
+fn set_payload_without_points(
+    mut set_payload: SetPayload,
+    excluded: &HashSet<PointIdType>,
+) -> SetPayload {
+    if let Some(points) = &mut set_payload.points {
+        retain_ids(points, excluded);
+    }
+    set_payload.filter = set_payload
+        .filter
+        .map(|filter| filter_without_points(filter, excluded));
+    set_payload
+}
+
+fn delete_payload_without_points(
+    mut delete_payload: DeletePayload,
+    excluded: &HashSet<PointIdType>,
+) -> DeletePayload {
+    if let Some(points) = &mut delete_payload.points {
+        retain_ids(points, excluded);
+    }
+    delete_payload.filter = delete_payload
+        .filter
+        .map(|filter| filter_without_points(filter, excluded));
+    delete_payload
+}
+
+fn insert_without_points(
+    operation: PointInsertOperations,
+    excluded: &HashSet<PointIdType>,
+) -> PointInsertOperations {
+    let mut points = operation.into_point_vec();
+    points.retain(|point| !excluded.contains(&point.id));
+    PointInsertOperations::PointsList(points)
+}
+
+impl CollectionUpdateOperations {
+    /// Same operation, which leaves the excluded points unchanged
+    pub fn without_points(self, excluded: &HashSet<PointIdType>) -> Self {
+        if excluded.is_empty() {
+            return self;
+        }
+        match self {
+            CollectionUpdateOperations::PointOperation(operation) => {
+                CollectionUpdateOperations::PointOperation(operation.without_points(excluded))
+            }
+            CollectionUpdateOperations::VectorOperation(operation) => {
+                CollectionUpdateOperations::VectorOperation(operation.without_points(excluded))
+            }
+            CollectionUpdateOperations::PayloadOperation(operation) => {
+                CollectionUpdateOperations::PayloadOperation(operation.without_points(excluded))
+            }
+            operation @ CollectionUpdateOperations::FieldIndexOperation(_) => operation,
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                CollectionUpdateOperations::BatchOperation(
+                    operations
+                        .into_iter()
+                        .map(|operation| operation.without_points(excluded))
+                        .collect(),
+                )
+            }
+            CollectionUpdateOperations::ClockedOperation(mut clocked) => {
+                clocked.operation = Box::new(clocked.operation.without_points(excluded));
+                CollectionUpdateOperations::ClockedOperation(clocked)
+            }
+            CollectionUpdateOperations::IdempotentOperation(mut idempotent) => {
+                idempotent.operation = Box::new(idempotent.operation.without_points(excluded));
+                CollectionUpdateOperations::IdempotentOperation(idempotent)
+            }
+        }
+    }
+}
+
+impl PointOperations {
+    fn without_points(self, excluded: &HashSet<PointIdType>) -> Self {
+        match self {
+            PointOperations::UpsertPoints(operation) => {
+                PointOperations::UpsertPoints(insert_without_points(operation, excluded))
+            }
+            PointOperations::UpsertPointsConditional(mut operation) => {
+                operation.points = insert_without_points(operation.points, excluded);
+                PointOperations::UpsertPointsConditional(operation)
+            }
+            PointOperations::DeletePoints { mut ids } => {
+                retain_ids(&mut ids, excluded);
+                PointOperations::DeletePoints { ids }
+            }
+            PointOperations::DeletePointsByFilter(filter) => {
+                PointOperations::DeletePointsByFilter(filter_without_points(filter, excluded))
+            }
+            // Sync replaces the range with the state of another replica, so it is applied as is
+            operation @ PointOperations::SyncPoints(_) => operation,
+        }
+    }
+}
+
+impl VectorOperations {
+    fn without_points(self, excluded: &HashSet<PointIdType>) -> Self {
+        match self {
+            VectorOperations::UpdateVectors(UpdateVectors { mut points }) => {
+                points.retain(|point| !excluded.contains(&point.id));
+                VectorOperations::UpdateVectors(UpdateVectors { points })
+            }
+            VectorOperations::DeleteVectors(PointIdsList { mut points }, vector_names) => {
+                retain_ids(&mut points, excluded);
+                VectorOperations::DeleteVectors(PointIdsList { points }, vector_names)
+            }
+            VectorOperations::DeleteVectorsByFilter(filter, vector_names) => {
+                VectorOperations::DeleteVectorsByFilter(
+                    filter_without_points(filter, excluded),
+                    vector_names,
+                )
+            }
+        }
+    }
+}
+
+impl PayloadOps {
+    fn without_points(self, excluded: &HashSet<PointIdType>) -> Self {
+        match self {
+            PayloadOps::SetPayload(set_payload) => {
+                PayloadOps::SetPayload(set_payload_without_points(set_payload, excluded))
+            }
+            PayloadOps::OverwritePayload(set_payload) => {
+                PayloadOps::OverwritePayload(set_payload_without_points(set_payload, excluded))
+            }
+            PayloadOps::MergePayload(set_payload) => {
+                PayloadOps::MergePayload(set_payload_without_points(set_payload, excluded))
+            }
+            PayloadOps::DeletePayload(delete_payload) => {
+                PayloadOps::DeletePayload(delete_payload_without_points(delete_payload, excluded))
+            }
+            PayloadOps::ClearPayload { mut points } => {
+                retain_ids(&mut points, excluded);
+                PayloadOps::ClearPayload { points }
+            }
+            PayloadOps::ClearPayloadByFilter(filter) => {
+                PayloadOps::ClearPayloadByFilter(filter_without_points(filter, excluded))
+            }
+            PayloadOps::MutatePayload(mut mutate_payload) => {
+                mutate_payload.filter = filter_without_points(mutate_payload.filter, excluded);
+                PayloadOps::MutatePayload(mutate_payload)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use segment::data_types::vectors::only_default_vector;
+
+    use super::*;
+    use crate::operations::point_ops::PointStruct;
+
+    #[test]
+    fn test_clock_tag_order() {
+        let earlier = ClockTag::new(10, 2);
+        let concurrent = ClockTag::new(11, 1);
+        let later = ClockTag::new(11, 2);
+        assert!(earlier < concurrent);
+        assert!(concurrent < later);
+        assert!(ClockTag::default() < earlier);
+    }
+
+    #[test]
+    fn test_operation_without_points() {
+        let points = (1_u64..=3)
+            .map(|id| PointStruct {
+                id: id.into(),
+                vector: only_default_vector(&[1.0, 0.0]).into(),
+                payload: None,
+            })
+            .collect::<Vec<_>>();
+        let operation = CollectionUpdateOperations::BatchOperation(vec![
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(points),
+            )),
+            CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayloadByFilter(
+                Filter::default(),
+            )),
+        ]);
+
+        let excluded = HashSet::from([2.into()]);
+        let CollectionUpdateOperations::BatchOperation(operations) =
+            operation.without_points(&excluded)
+        else {
+            panic!("batch operation expected");
+        };
+
+        let CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperations::PointsList(points),
+        )) = &operations[0]
+        else {
+            panic!("upsert operation expected");
+        };
+        let ids: Vec<_> = points.iter().map(|point| point.id).collect();
+        assert_eq!(ids, vec![1.into(), 3.into()]);
+
+        let CollectionUpdateOperations::PayloadOperation(PayloadOps::ClearPayloadByFilter(filter)) =
+            &operations[1]
+        else {
+            panic!("clear payload operation expected");
+        };
+        assert!(matches!(
+            filter.must_not.as_deref(),
+            Some([Condition::HasId(condition)]) if condition.has_id == excluded
+        ));
+    }
+}
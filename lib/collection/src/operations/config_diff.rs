@@ -2,15 +2,19 @@ use std::num::NonZeroU32;
 
 use merge::Merge;
 use schemars::JsonSchema;
-use segment::types::{BinaryQuantization, HnswConfig, ProductQuantization, ScalarQuantization};
+use segment::types::{
+    BinaryQuantization, HnswConfig, PayloadKeyType, ProductQuantization, ScalarQuantization,
+};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use validator::{Validate, ValidationErrors};
 
-use crate::config::{CollectionParams, WalConfig};
-use crate::operations::types::CollectionResult;
-use crate::optimizers_builder::OptimizersConfig;
+use crate::common::scoring_plugin::ScoringPlugin;
+use crate::config::{CollectionParams, ScoringPluginConfig, WalConfig, WalDurability};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::optimizers_builder::{OptimizersConfig, TieredMergePolicy};
+use crate::wal::WalCompression;
 
 // Structures for partial update of collection params
 // TODO: make auto-generated somehow...
@@ -91,6 +95,19 @@ pub struct WalConfigDiff {
     pub wal_capacity_mb: Option<usize>,
     /// Number of WAL segments to create ahead of actually used ones
     pub wal_segments_ahead: Option<usize>,
+    /// Compression of the WAL records
+    pub wal_compression: Option<WalCompression>,
+    /// When the WAL is synced to disk
+    pub wal_durability: Option<WalDurability>,
+    /// Interval between WAL syncs in milliseconds, used with `interval` durability
+    #[validate(range(min = 1))]
+    pub wal_fsync_interval_ms: Option<u64>,
+    /// Keep at most this number of already applied operations in the WAL
+    pub wal_retention_ops: Option<u64>,
+    /// Keep already applied operations in the WAL for at most this number of seconds
+    pub wal_retention_sec: Option<u64>,
+    /// Keep at most this size of already applied operations in the WAL, in MB
+    pub wal_retention_mb: Option<usize>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Merge, PartialEq, Eq, Hash)]
@@ -107,6 +124,11 @@ pub struct CollectionParamsDiff {
     /// Note: those payload values that are involved in filtering and are indexed - remain in RAM.
     #[serde(default)]
     pub on_disk_payload: Option<bool>,
+    /// Number of search results to keep in the cache of the collection, 0 to disable the cache
+    pub search_cache_size: Option<usize>,
+    /// WASM module, which scores the candidates of `plugin` queries, `Disabled` to remove it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scoring_plugin: Option<ScoringPluginDiff>,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Merge)]
@@ -156,6 +178,17 @@ pub struct OptimizersConfigDiff {
     pub flush_interval_sec: Option<u64>,
     /// Maximum available threads for optimization workers
     pub max_optimization_threads: Option<usize>,
+    /// Payload key with the expiration time of points, as an RFC 3339 datetime.
+    /// Expired points are deleted every minute and replicated like regular updates.
+    /// A datetime index is created on the key, if missing. No expiration if not set.
+    pub expiration_key: Option<PayloadKeyType>,
+    /// Policy of merging small segments by size tiers.
+    /// If set, replaces merging of the smallest segments down to `default_segment_number`.
+    #[validate]
+    pub merge_policy: Option<TieredMergePolicy>,
+    /// Payload key, which groups the points in optimized segments, e.g. a tenant id.
+    /// Points with the same value are stored next to each other, which speeds up search filtered by this key.
+    pub defragmentation_key: Option<PayloadKeyType>,
 }
 
 impl std::hash::Hash for OptimizersConfigDiff {
@@ -168,6 +201,9 @@ impl std::hash::Hash for OptimizersConfigDiff {
         self.indexing_threshold.hash(state);
         self.flush_interval_sec.hash(state);
         self.max_optimization_threads.hash(state);
+        self.expiration_key.hash(state);
+        self.merge_policy.hash(state);
+        self.defragmentation_key.hash(state);
     }
 }
 
@@ -182,6 +218,9 @@ impl PartialEq for OptimizersConfigDiff {
             && self.indexing_threshold == other.indexing_threshold
             && self.flush_interval_sec == other.flush_interval_sec
             && self.max_optimization_threads == other.max_optimization_threads
+            && self.expiration_key == other.expiration_key
+            && self.merge_policy == other.merge_policy
+            && self.defragmentation_key == other.defragmentation_key
     }
 }
 
@@ -309,6 +348,39 @@ impl QuantizationConfigDiff {
     }
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+#[serde(untagged)]
+pub enum ScoringPluginDiff {
+    Plugin(ScoringPluginConfig),
+    Disabled(Disabled),
+}
+
+impl ScoringPluginDiff {
+    /// Scoring plugin, which replaces the `current` one.
+    ///
+    /// The module of a new plugin is checked, a changed plugin must have a higher version.
+    pub fn update(
+        self,
+        current: Option<&ScoringPluginConfig>,
+    ) -> CollectionResult<Option<ScoringPluginConfig>> {
+        let plugin = match self {
+            ScoringPluginDiff::Plugin(plugin) => plugin,
+            ScoringPluginDiff::Disabled(_) => return Ok(None),
+        };
+        if let Some(current) = current.filter(|current| **current != plugin) {
+            if plugin.version <= current.version {
+                return Err(CollectionError::bad_request(format!(
+                    "Version {} of scoring plugin {} must be higher than the current version {}",
+                    plugin.version, plugin.name, current.version,
+                )));
+            }
+        }
+        ScoringPlugin::compile(&plugin)?;
+        Ok(Some(plugin))
+    }
+}
+
 impl Validate for QuantizationConfigDiff {
     fn validate(&self) -> Result<(), ValidationErrors> {
         match self {
@@ -322,13 +394,16 @@ impl Validate for QuantizationConfigDiff {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::num::NonZeroU64;
+    use std::time::Duration;
 
     use segment::types::{Distance, HnswConfig};
 
     use super::*;
-    use crate::operations::types::VectorParams;
+    use crate::operations::types::{VectorParams, VectorsConfig, VectorsConfigDiff};
     use crate::optimizers_builder::OptimizersConfig;
+    use crate::wal::WalRetention;
 
     #[test]
     fn test_update_collection_params() {
@@ -339,6 +414,8 @@ mod tests {
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                multivector_config: None,
+                datatype: None,
             }
             .into(),
             ..CollectionParams::empty()
@@ -349,6 +426,8 @@ mod tests {
             write_consistency_factor: Some(NonZeroU32::new(2).unwrap()),
             read_fan_out_factor: None,
             on_disk_payload: None,
+            search_cache_size: None,
+            scoring_plugin: None,
         };
 
         let new_params = diff.update(&params).unwrap();
@@ -358,6 +437,94 @@ mod tests {
         assert!(!new_params.on_disk_payload);
     }
 
+    #[test]
+    fn test_multivector_params_validation() {
+        let multi_params: VectorParams = serde_json::from_str(
+            r#"{ "size": 4, "distance": "Dot", "multivector_config": { "comparator": "max_sim" } }"#,
+        )
+        .unwrap();
+        assert!(multi_params.validate().is_ok());
+
+        let on_disk = VectorParams {
+            on_disk: Some(true),
+            ..multi_params.clone()
+        };
+        assert!(on_disk.validate().is_err());
+
+        let quantized: VectorParams = serde_json::from_str(
+            r#"{ "size": 4, "distance": "Dot", "multivector_config": { "comparator": "max_sim" }, "quantization_config": { "scalar": { "type": "int8" } } }"#,
+        )
+        .unwrap();
+        assert!(quantized.validate().is_err());
+
+        let dense_params: VectorParams =
+            serde_json::from_str(r#"{ "size": 4, "distance": "Dot" }"#).unwrap();
+        let mut params = CollectionParams {
+            vectors: VectorsConfig::Multi(BTreeMap::from([
+                ("dense".to_string(), dense_params),
+                ("multi".to_string(), multi_params),
+            ])),
+            ..CollectionParams::empty()
+        };
+
+        // Update, which makes any of the vectors invalid, is rejected as a whole
+        let diff: VectorsConfigDiff = serde_json::from_str(
+            r#"{ "dense": { "on_disk": true }, "multi": { "on_disk": true } }"#,
+        )
+        .unwrap();
+        assert!(params.update_vectors_from_diff(&diff).is_err());
+        assert_eq!(params.vectors.get_params("dense").unwrap().on_disk, None);
+        assert_eq!(params.vectors.get_params("multi").unwrap().on_disk, None);
+
+        let diff: VectorsConfigDiff =
+            serde_json::from_str(r#"{ "dense": { "on_disk": true } }"#).unwrap();
+        params.update_vectors_from_diff(&diff).unwrap();
+        assert_eq!(
+            params.vectors.get_params("dense").unwrap().on_disk,
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_float16_params_validation() {
+        let half_params: VectorParams =
+            serde_json::from_str(r#"{ "size": 4, "distance": "Cosine", "datatype": "float16" }"#)
+                .unwrap();
+        assert!(half_params.validate().is_ok());
+
+        let on_disk = VectorParams {
+            on_disk: Some(true),
+            ..half_params.clone()
+        };
+        assert!(on_disk.validate().is_err());
+
+        let multi_half: VectorParams = serde_json::from_str(
+            r#"{ "size": 4, "distance": "Dot", "datatype": "float16", "multivector_config": { "comparator": "max_sim" } }"#,
+        )
+        .unwrap();
+        assert!(multi_half.validate().is_err());
+    }
+
+    #[test]
+    fn test_uint8_params_validation() {
+        let byte_params: VectorParams =
+            serde_json::from_str(r#"{ "size": 4, "distance": "Hamming", "datatype": "uint8" }"#)
+                .unwrap();
+        assert!(byte_params.validate().is_ok());
+
+        let on_disk = VectorParams {
+            on_disk: Some(true),
+            ..byte_params.clone()
+        };
+        assert!(on_disk.validate().is_err());
+
+        let quantized: VectorParams = serde_json::from_str(
+            r#"{ "size": 4, "distance": "Dot", "datatype": "uint8", "quantization_config": { "scalar": { "type": "int8" } } }"#,
+        )
+        .unwrap();
+        assert!(quantized.validate().is_err());
+    }
+
     #[test]
     fn test_hnsw_update() {
         let base_config = HnswConfig::default();
@@ -377,11 +544,29 @@ mod tests {
             indexing_threshold: Some(50_000),
             flush_interval_sec: 30,
             max_optimization_threads: 1,
+            expiration_key: None,
+            merge_policy: None,
+            defragmentation_key: None,
         };
         let update: OptimizersConfigDiff =
             serde_json::from_str(r#"{ "indexing_threshold": 10000 }"#).unwrap();
         let new_config = update.update(&base_config).unwrap();
-        assert_eq!(new_config.indexing_threshold, Some(10000))
+        assert_eq!(new_config.indexing_threshold, Some(10000));
+
+        let update: OptimizersConfigDiff = serde_json::from_str(
+            r#"{ "merge_policy": { "floor_segment_size_kb": 1000, "max_segments_per_tier": 5, "merge_factor": 4 } }"#,
+        )
+        .unwrap();
+        let new_config = update.update(&new_config).unwrap();
+        assert_eq!(new_config.indexing_threshold, Some(10000));
+        assert_eq!(
+            new_config.merge_policy,
+            Some(TieredMergePolicy {
+                floor_segment_size_kb: 1000,
+                max_segments_per_tier: 5,
+                merge_factor: 4,
+            })
+        );
     }
 
     #[test]
@@ -389,6 +574,36 @@ mod tests {
         let base_config = WalConfig::default();
         let update: WalConfigDiff = serde_json::from_str(r#"{ "wal_segments_ahead": 2 }"#).unwrap();
         let new_config = update.update(&base_config).unwrap();
-        assert_eq!(new_config.wal_segments_ahead, 2)
+        assert_eq!(new_config.wal_segments_ahead, 2);
+        assert_eq!(new_config.wal_compression, WalCompression::None);
+
+        let update: WalConfigDiff =
+            serde_json::from_str(r#"{ "wal_compression": "zstd" }"#).unwrap();
+        let new_config = update.update(&new_config).unwrap();
+        assert_eq!(new_config.wal_segments_ahead, 2);
+        assert_eq!(new_config.wal_compression, WalCompression::Zstd);
+        assert_eq!(new_config.wal_durability, WalDurability::WaitedOperations);
+        assert_eq!(new_config.fsync_interval(), None);
+
+        let update: WalConfigDiff = serde_json::from_str(
+            r#"{ "wal_durability": "interval", "wal_fsync_interval_ms": 500 }"#,
+        )
+        .unwrap();
+        let new_config = update.update(&new_config).unwrap();
+        assert_eq!(new_config.wal_durability, WalDurability::Interval);
+        assert_eq!(
+            new_config.fsync_interval(),
+            Some(Duration::from_millis(500))
+        );
+        assert_eq!(new_config.retention(), WalRetention::default());
+
+        let update: WalConfigDiff =
+            serde_json::from_str(r#"{ "wal_retention_sec": 3600, "wal_retention_mb": 64 }"#)
+                .unwrap();
+        let new_config = update.update(&new_config).unwrap();
+        let retention = new_config.retention();
+        assert_eq!(retention.records, None);
+        assert_eq!(retention.duration, Some(Duration::from_secs(3600)));
+        assert_eq!(retention.size_bytes, Some(64 * 1024 * 1024));
     }
 }
@@ -1,47 +1,69 @@
 use std::collections::{BTreeMap, HashMap};
 use std::num::{NonZeroU32, NonZeroU64};
 
-use api::grpc::conversions::{from_grpc_dist, payload_to_proto, proto_to_payloads};
+use api::grpc::conversions::{
+    from_grpc_datatype, from_grpc_dist, json_to_proto, payload_to_proto, proto_to_json,
+    proto_to_payloads,
+};
+use api::grpc::qdrant::payload_mutation::{self, Mutation};
 use api::grpc::qdrant::quantization_config_diff::Quantization;
+use api::grpc::qdrant::scoring_plugin_diff::Plugin;
 use api::grpc::qdrant::update_collection_cluster_setup_request::Operation as ClusterOperationsPb;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
 use itertools::Itertools;
 use segment::data_types::vectors::{
-    Named, NamedRecoQuery, NamedVector, VectorStruct, DEFAULT_VECTOR_NAME,
+    flatten_multi_dense, Named, NamedContextQuery, NamedDiscoveryQuery, NamedRecoQuery,
+    NamedVectorStruct, VectorRef, VectorStruct, DEFAULT_VECTOR_NAME,
 };
-use segment::types::{Distance, QuantizationConfig};
+use segment::types::{Distance, PointIdType, QuantizationConfig};
+use segment::vector_storage::query::context_query::{ContextPair, ContextQuery};
+use segment::vector_storage::query::discovery_query::DiscoveryQuery;
 use segment::vector_storage::query::reco_query::RecoQuery;
+use sparse::common::sparse_vector::SparseVector;
 use tonic::Status;
 
+use super::formula::{
+    AbsExpression, DatetimeExpression, DatetimeKeyExpression, DecayParams, DivExpression,
+    DivParams, ExpDecayExpression, ExpExpression, Expression, FormulaQuery, GaussDecayExpression,
+    GeoDistanceExpression, GeoDistanceParams, LinDecayExpression, LnExpression, Log10Expression,
+    MultExpression, NegExpression, PowExpression, PowParams, SqrtExpression, SumExpression,
+};
 use super::types::{
-    BaseGroupRequest, CoreSearchRequest, GroupsResult, PointGroup, QueryEnum, RecommendExample,
-    RecommendGroupsRequest, RecommendStrategy, SearchGroupsRequest, VectorParamsDiff,
-    VectorsConfigDiff,
+    default_query_limit, BaseGroupRequest, ContextExamplePair, CoreSearchRequest, DiscoverInput,
+    Diversity, Fusion, GroupsResult, PluginQuery, PointGroup, Prefetch, Query, QueryEnum,
+    QueryRequest, RecommendExample, RecommendGroupsRequest, RecommendInput, RecommendStrategy,
+    Sample, SearchGroupsRequest, TextQuery, VectorParamsDiff, VectorsConfigDiff,
 };
 use crate::config::{
     default_replication_factor, default_write_consistency_factor, CollectionConfig,
-    CollectionParams, WalConfig,
+    CollectionParams, ScoringPluginConfig, WalConfig, WalDurability,
 };
 use crate::lookup::types::WithLookupInterface;
 use crate::lookup::WithLookup;
+use crate::operations::clock_ops::ClockTag;
 use crate::operations::cluster_ops::{
     AbortTransferOperation, ClusterOperations, DropReplicaOperation, MoveShard, MoveShardOperation,
     Replica, ReplicateShardOperation,
 };
 use crate::operations::config_diff::{
-    CollectionParamsDiff, HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff,
-    WalConfigDiff,
+    CollectionParamsDiff, Disabled, HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff,
+    ScoringPluginDiff, WalConfigDiff,
 };
+use crate::operations::payload_ops::PayloadMutation;
 use crate::operations::point_ops::PointsSelector::PointIdsSelector;
 use crate::operations::point_ops::{
     Batch, FilterSelector, PointIdsList, PointStruct, PointsSelector, WriteOrdering,
 };
 use crate::operations::types::{
-    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionStatus, CountResult,
-    LocalShardInfo, LookupLocation, OptimizersStatus, RecommendRequest, Record, RemoteShardInfo,
-    SearchRequest, ShardTransferInfo, UpdateResult, UpdateStatus, VectorParams, VectorsConfig,
+    validate_vector_params, AliasDescription, CollectionClusterInfo, CollectionError,
+    CollectionInfo, CollectionStatus, CountResult, LocalShardInfo, LookupLocation,
+    OptimizersStatus, RecommendRequest, Record, RemoteShardInfo, SearchRequest, ShardTransferInfo,
+    SparseIndexParams, SparseVectorParams, UpdateResult, UpdateStatus, VectorParams, VectorsConfig,
 };
-use crate::optimizers_builder::OptimizersConfig;
+use crate::optimizers_builder::{OptimizersConfig, TieredMergePolicy};
 use crate::shards::remote_shard::{CollectionCoreSearchRequest, CollectionSearchRequest};
+use crate::wal::WalCompression;
 
 pub fn write_ordering_to_proto(ordering: WriteOrdering) -> api::grpc::qdrant::WriteOrdering {
     api::grpc::qdrant::WriteOrdering {
@@ -53,6 +75,43 @@ pub fn write_ordering_to_proto(ordering: WriteOrdering) -> api::grpc::qdrant::Wr
     }
 }
 
+impl From<ClockTag> for api::grpc::qdrant::ClockTag {
+    fn from(clock_tag: ClockTag) -> Self {
+        Self {
+            peer_id: clock_tag.peer_id,
+            clock_tick: clock_tag.clock_tick,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::ClockTag> for ClockTag {
+    fn from(clock_tag: api::grpc::qdrant::ClockTag) -> Self {
+        Self::new(clock_tag.clock_tick, clock_tag.peer_id)
+    }
+}
+
+pub fn point_clock_tag_to_proto(
+    (point_id, clock_tag): (PointIdType, ClockTag),
+) -> api::grpc::qdrant::PointClockTag {
+    api::grpc::qdrant::PointClockTag {
+        id: Some(point_id.into()),
+        clock_tag: Some(clock_tag.into()),
+    }
+}
+
+pub fn point_clock_tag_from_proto(
+    point_clock_tag: api::grpc::qdrant::PointClockTag,
+) -> Result<(PointIdType, ClockTag), Status> {
+    let api::grpc::qdrant::PointClockTag { id, clock_tag } = point_clock_tag;
+    let point_id = id
+        .ok_or_else(|| Status::invalid_argument("Point clock id is missing"))?
+        .try_into()?;
+    let clock_tag = clock_tag
+        .ok_or_else(|| Status::invalid_argument("Point clock tag is missing"))?
+        .into();
+    Ok((point_id, clock_tag))
+}
+
 pub fn write_ordering_from_proto(
     ordering: Option<api::grpc::qdrant::WriteOrdering>,
 ) -> Result<WriteOrdering, Status> {
@@ -103,6 +162,7 @@ pub fn try_record_from_grpc(
         id,
         payload,
         vector,
+        order_value: point.order_value,
     })
 }
 
@@ -137,6 +197,18 @@ impl From<api::grpc::qdrant::WalConfigDiff> for WalConfigDiff {
         Self {
             wal_capacity_mb: value.wal_capacity_mb.map(|v| v as usize),
             wal_segments_ahead: value.wal_segments_ahead.map(|v| v as usize),
+            wal_compression: value
+                .wal_compression
+                .and_then(api::grpc::qdrant::WalCompression::from_i32)
+                .map(WalCompression::from),
+            wal_durability: value
+                .wal_durability
+                .and_then(api::grpc::qdrant::WalDurability::from_i32)
+                .map(WalDurability::from),
+            wal_fsync_interval_ms: value.wal_fsync_interval_ms,
+            wal_retention_ops: value.wal_retention_ops,
+            wal_retention_sec: value.wal_retention_sec,
+            wal_retention_mb: value.wal_retention_mb.map(|v| v as usize),
         }
     }
 }
@@ -163,10 +235,61 @@ impl TryFrom<api::grpc::qdrant::CollectionParamsDiff> for CollectionParamsDiff {
                 .transpose()?,
             read_fan_out_factor: value.read_fan_out_factor,
             on_disk_payload: value.on_disk_payload,
+            search_cache_size: value.search_cache_size.map(|size| size as usize),
+            scoring_plugin: value.scoring_plugin.map(TryInto::try_into).transpose()?,
         })
     }
 }
 
+impl From<api::grpc::qdrant::ScoringPlugin> for ScoringPluginConfig {
+    fn from(value: api::grpc::qdrant::ScoringPlugin) -> Self {
+        let api::grpc::qdrant::ScoringPlugin {
+            name,
+            version,
+            module,
+            features,
+        } = value;
+        Self {
+            name,
+            version,
+            module: BASE64.encode(module),
+            features,
+        }
+    }
+}
+
+impl From<ScoringPluginConfig> for api::grpc::qdrant::ScoringPlugin {
+    fn from(value: ScoringPluginConfig) -> Self {
+        let ScoringPluginConfig {
+            name,
+            version,
+            module,
+            features,
+        } = value;
+        Self {
+            name,
+            version,
+            // Modules are validated on update, so they are always valid base64
+            module: BASE64.decode(module).unwrap_or_default(),
+            features,
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::ScoringPluginDiff> for ScoringPluginDiff {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::ScoringPluginDiff) -> Result<Self, Self::Error> {
+        match value.plugin {
+            None => Err(Status::invalid_argument(
+                "Scoring plugin diff is not specified",
+            )),
+            Some(Plugin::Set(plugin)) => Ok(Self::Plugin(plugin.into())),
+            Some(Plugin::Disabled(_)) => Ok(Self::Disabled(Disabled::Disabled)),
+        }
+    }
+}
+
 impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfigDiff {
     fn from(value: api::grpc::qdrant::OptimizersConfigDiff) -> Self {
         Self {
@@ -178,6 +301,39 @@ impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfigDiff {
             indexing_threshold: value.indexing_threshold.map(|v| v as usize),
             flush_interval_sec: value.flush_interval_sec,
             max_optimization_threads: value.max_optimization_threads.map(|v| v as usize),
+            expiration_key: value.expiration_key,
+            merge_policy: value.merge_policy.map(TieredMergePolicy::from),
+            defragmentation_key: value.defragmentation_key,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::TieredMergePolicy> for TieredMergePolicy {
+    fn from(value: api::grpc::qdrant::TieredMergePolicy) -> Self {
+        let api::grpc::qdrant::TieredMergePolicy {
+            floor_segment_size_kb,
+            max_segments_per_tier,
+            merge_factor,
+        } = value;
+        Self {
+            floor_segment_size_kb: floor_segment_size_kb as usize,
+            max_segments_per_tier: max_segments_per_tier as usize,
+            merge_factor: merge_factor as usize,
+        }
+    }
+}
+
+impl From<TieredMergePolicy> for api::grpc::qdrant::TieredMergePolicy {
+    fn from(value: TieredMergePolicy) -> Self {
+        let TieredMergePolicy {
+            floor_segment_size_kb,
+            max_segments_per_tier,
+            merge_factor,
+        } = value;
+        Self {
+            floor_segment_size_kb: floor_segment_size_kb as u64,
+            max_segments_per_tier: max_segments_per_tier as u64,
+            merge_factor: merge_factor as u64,
         }
     }
 }
@@ -211,6 +367,8 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
             segments_count,
             config,
             payload_schema,
+            // Optimizations are reported only by the peer, which answers the request
+            optimizations: _,
         } = value;
 
         api::grpc::qdrant::CollectionInfo {
@@ -262,6 +420,9 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                     on_disk_payload: config.params.on_disk_payload,
                     write_consistency_factor: Some(config.params.write_consistency_factor.get()),
                     read_fan_out_factor: config.params.read_fan_out_factor,
+                    sparse_vectors_config: config.params.sparse_vectors.map(Into::into),
+                    search_cache_size: config.params.search_cache_size.map(|size| size as u64),
+                    scoring_plugin: config.params.scoring_plugin.map(Into::into),
                 }),
                 hnsw_config: Some(api::grpc::qdrant::HnswConfigDiff {
                     m: Some(config.hnsw_config.m as u64),
@@ -289,10 +450,25 @@ impl From<CollectionInfo> for api::grpc::qdrant::CollectionInfo {
                     max_optimization_threads: Some(
                         config.optimizer_config.max_optimization_threads as u64,
                     ),
+                    expiration_key: config.optimizer_config.expiration_key,
+                    merge_policy: config.optimizer_config.merge_policy.map(Into::into),
+                    defragmentation_key: config.optimizer_config.defragmentation_key,
                 }),
                 wal_config: Some(api::grpc::qdrant::WalConfigDiff {
                     wal_capacity_mb: Some(config.wal_config.wal_capacity_mb as u64),
                     wal_segments_ahead: Some(config.wal_config.wal_segments_ahead as u64),
+                    wal_compression: Some(
+                        api::grpc::qdrant::WalCompression::from(config.wal_config.wal_compression)
+                            .into(),
+                    ),
+                    wal_durability: Some(
+                        api::grpc::qdrant::WalDurability::from(config.wal_config.wal_durability)
+                            .into(),
+                    ),
+                    wal_fsync_interval_ms: config.wal_config.wal_fsync_interval_ms,
+                    wal_retention_ops: config.wal_config.wal_retention_ops,
+                    wal_retention_sec: config.wal_config.wal_retention_sec,
+                    wal_retention_mb: config.wal_config.wal_retention_mb.map(|v| v as u64),
                 }),
                 quantization_config: config.quantization_config.map(|x| x.into()),
             }),
@@ -312,6 +488,7 @@ impl From<Record> for api::grpc::qdrant::RetrievedPoint {
             id: Some(record.id.into()),
             payload: record.payload.map(payload_to_proto).unwrap_or_default(),
             vectors,
+            order_value: record.order_value,
         }
     }
 }
@@ -345,6 +522,9 @@ impl From<api::grpc::qdrant::OptimizersConfigDiff> for OptimizersConfig {
             max_optimization_threads: optimizer_config
                 .max_optimization_threads
                 .unwrap_or_default() as usize,
+            expiration_key: optimizer_config.expiration_key,
+            merge_policy: optimizer_config.merge_policy.map(TieredMergePolicy::from),
+            defragmentation_key: optimizer_config.defragmentation_key,
         }
     }
 }
@@ -354,6 +534,62 @@ impl From<api::grpc::qdrant::WalConfigDiff> for WalConfig {
         Self {
             wal_capacity_mb: wal_config.wal_capacity_mb.unwrap_or_default() as usize,
             wal_segments_ahead: wal_config.wal_segments_ahead.unwrap_or_default() as usize,
+            wal_compression: wal_config
+                .wal_compression
+                .and_then(api::grpc::qdrant::WalCompression::from_i32)
+                .map(WalCompression::from)
+                .unwrap_or_default(),
+            wal_durability: wal_config
+                .wal_durability
+                .and_then(api::grpc::qdrant::WalDurability::from_i32)
+                .map(WalDurability::from)
+                .unwrap_or_default(),
+            wal_fsync_interval_ms: wal_config.wal_fsync_interval_ms,
+            wal_retention_ops: wal_config.wal_retention_ops,
+            wal_retention_sec: wal_config.wal_retention_sec,
+            wal_retention_mb: wal_config.wal_retention_mb.map(|v| v as usize),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::WalCompression> for WalCompression {
+    fn from(value: api::grpc::qdrant::WalCompression) -> Self {
+        match value {
+            api::grpc::qdrant::WalCompression::None => WalCompression::None,
+            api::grpc::qdrant::WalCompression::Lz4 => WalCompression::Lz4,
+            api::grpc::qdrant::WalCompression::Zstd => WalCompression::Zstd,
+        }
+    }
+}
+
+impl From<WalCompression> for api::grpc::qdrant::WalCompression {
+    fn from(value: WalCompression) -> Self {
+        match value {
+            WalCompression::None => api::grpc::qdrant::WalCompression::None,
+            WalCompression::Lz4 => api::grpc::qdrant::WalCompression::Lz4,
+            WalCompression::Zstd => api::grpc::qdrant::WalCompression::Zstd,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::WalDurability> for WalDurability {
+    fn from(value: api::grpc::qdrant::WalDurability) -> Self {
+        match value {
+            api::grpc::qdrant::WalDurability::WaitedOperations => WalDurability::WaitedOperations,
+            api::grpc::qdrant::WalDurability::EveryOperation => WalDurability::EveryOperation,
+            api::grpc::qdrant::WalDurability::Interval => WalDurability::Interval,
+            api::grpc::qdrant::WalDurability::SegmentRotation => WalDurability::SegmentRotation,
+        }
+    }
+}
+
+impl From<WalDurability> for api::grpc::qdrant::WalDurability {
+    fn from(value: WalDurability) -> Self {
+        match value {
+            WalDurability::WaitedOperations => api::grpc::qdrant::WalDurability::WaitedOperations,
+            WalDurability::EveryOperation => api::grpc::qdrant::WalDurability::EveryOperation,
+            WalDurability::Interval => api::grpc::qdrant::WalDurability::Interval,
+            WalDurability::SegmentRotation => api::grpc::qdrant::WalDurability::SegmentRotation,
         }
     }
 }
@@ -403,7 +639,7 @@ impl TryFrom<api::grpc::qdrant::VectorParams> for VectorParams {
     type Error = Status;
 
     fn try_from(vector_params: api::grpc::qdrant::VectorParams) -> Result<Self, Self::Error> {
-        Ok(Self {
+        let vector_params = Self {
             size: NonZeroU64::new(vector_params.size).ok_or_else(|| {
                 Status::invalid_argument("VectorParams size must be greater than zero")
             })?,
@@ -414,7 +650,38 @@ impl TryFrom<api::grpc::qdrant::VectorParams> for VectorParams {
                 .map(grpc_to_segment_quantization_config)
                 .transpose()?,
             on_disk: vector_params.on_disk,
-        })
+            multivector_config: vector_params
+                .multivector_config
+                .map(TryInto::try_into)
+                .transpose()?,
+            datatype: vector_params
+                .datatype
+                .map(from_grpc_datatype)
+                .transpose()?
+                .flatten(),
+        };
+        validate_vector_params(&vector_params).map_err(|err| Status::invalid_argument(err.code))?;
+        Ok(vector_params)
+    }
+}
+
+impl From<api::grpc::qdrant::SparseVectorParams> for SparseVectorParams {
+    fn from(sparse_vector_params: api::grpc::qdrant::SparseVectorParams) -> Self {
+        Self {
+            index: sparse_vector_params.index.map(|index| SparseIndexParams {
+                full_scan_threshold: index.full_scan_threshold.map(|v| v as usize),
+            }),
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::SparseVectorConfig> for BTreeMap<String, SparseVectorParams> {
+    fn from(sparse_vector_config: api::grpc::qdrant::SparseVectorConfig) -> Self {
+        sparse_vector_config
+            .map
+            .into_iter()
+            .map(|(name, params)| (name, params.into()))
+            .collect()
     }
 }
 
@@ -486,6 +753,7 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                             ),
                         },
                     },
+                    sparse_vectors: params.sparse_vectors_config.map(Into::into),
                     shard_number: NonZeroU32::new(params.shard_number)
                         .ok_or_else(|| Status::invalid_argument("`shard_number` cannot be zero"))?,
                     on_disk_payload: params.on_disk_payload,
@@ -507,6 +775,8 @@ impl TryFrom<api::grpc::qdrant::CollectionConfig> for CollectionConfig {
                     })?,
 
                     read_fan_out_factor: params.read_fan_out_factor,
+                    search_cache_size: params.search_cache_size.map(|size| size as usize),
+                    scoring_plugin: params.scoring_plugin.map(Into::into),
                 },
             },
             hnsw_config: match config.hnsw_config {
@@ -569,6 +839,7 @@ impl TryFrom<api::grpc::qdrant::GetCollectionInfoResponse> for CollectionInfo {
                     .into_iter()
                     .map(|(k, v)| v.try_into().map(|v| (k, v)))
                     .try_collect()?,
+                optimizations: Default::default(),
             }),
         }
     }
@@ -684,6 +955,13 @@ impl From<UpdateResult> for api::grpc::qdrant::UpdateResult {
                 UpdateStatus::Acknowledged => api::grpc::qdrant::UpdateStatus::Acknowledged as i32,
                 UpdateStatus::Completed => api::grpc::qdrant::UpdateStatus::Completed as i32,
             },
+            affected_points: value.affected_points.map(|count| count as u64),
+            ids: value
+                .ids
+                .into_iter()
+                .flatten()
+                .map(|id| id.into())
+                .collect(),
         }
     }
 }
@@ -703,6 +981,18 @@ impl TryFrom<api::grpc::qdrant::UpdateResult> for UpdateResult {
                 }
                 _ => return Err(Status::invalid_argument("Malformed UpdateStatus type")),
             },
+            affected_points: value.affected_points.map(|count| count as usize),
+            ids: if value.ids.is_empty() {
+                None
+            } else {
+                Some(
+                    value
+                        .ids
+                        .into_iter()
+                        .map(|id| id.try_into())
+                        .collect::<Result<_, _>>()?,
+                )
+            },
         })
     }
 }
@@ -724,13 +1014,28 @@ impl From<CountResult> for api::grpc::qdrant::CountResult {
 }
 
 // Use wrapper type to bundle CollectionId & SearchRequest
-impl<'a> From<CollectionSearchRequest<'a>> for api::grpc::qdrant::SearchPoints {
-    fn from(value: CollectionSearchRequest<'a>) -> Self {
+impl<'a> TryFrom<CollectionSearchRequest<'a>> for api::grpc::qdrant::SearchPoints {
+    type Error = CollectionError;
+
+    fn try_from(value: CollectionSearchRequest<'a>) -> Result<Self, Self::Error> {
         let (collection_id, request) = value.0;
+        let search_vector = request.vector.as_vector()?;
+
+        let (vector, sparse_indices) = match search_vector.get_vector() {
+            VectorRef::Dense(vector) => (vector.to_vec(), None),
+            // Multivector queries are scored flattened anyway
+            VectorRef::MultiDense(vectors) => (flatten_multi_dense(vectors), None),
+            VectorRef::Sparse(vector) => (
+                vector.weights.clone(),
+                Some(api::grpc::qdrant::SparseIndices {
+                    data: vector.indices.clone(),
+                }),
+            ),
+        };
 
-        Self {
+        Ok(Self {
             collection_name: collection_id,
-            vector: request.vector.get_vector().to_vec(),
+            vector,
             filter: request.filter.clone().map(|f| f.into()),
             limit: request.limit as u64,
             with_vectors: request.with_vector.clone().map(|wv| wv.into()),
@@ -738,14 +1043,50 @@ impl<'a> From<CollectionSearchRequest<'a>> for api::grpc::qdrant::SearchPoints {
             params: request.params.map(|sp| sp.into()),
             score_threshold: request.score_threshold,
             offset: Some(request.offset as u64),
-            vector_name: match request.vector.get_name() {
+            vector_name: match search_vector.get_name() {
                 DEFAULT_VECTOR_NAME => None,
                 vector_name => Some(vector_name.to_string()),
             },
             read_consistency: None,
+            sparse_indices,
+            text: request.text.clone().map(|text| text.into()),
+            timeout: None,
+            with_usage: None,
+            explain: None,
+        })
+    }
+}
+
+impl From<TextQuery> for api::grpc::qdrant::TextQuery {
+    fn from(value: TextQuery) -> Self {
+        let TextQuery { key, text } = value;
+        Self { key, text }
+    }
+}
+
+impl From<api::grpc::qdrant::TextQuery> for TextQuery {
+    fn from(value: api::grpc::qdrant::TextQuery) -> Self {
+        let api::grpc::qdrant::TextQuery { key, text } = value;
+        Self { key, text }
+    }
+}
+
+impl From<Sample> for api::grpc::qdrant::Sample {
+    fn from(value: Sample) -> Self {
+        match value {
+            Sample::Random => api::grpc::qdrant::Sample::Random,
+        }
+    }
+}
+
+impl From<api::grpc::qdrant::Sample> for Sample {
+    fn from(value: api::grpc::qdrant::Sample) -> Self {
+        match value {
+            api::grpc::qdrant::Sample::Random => Sample::Random,
         }
     }
 }
+
 impl From<QueryEnum> for api::grpc::qdrant::QueryEnum {
     fn from(value: QueryEnum) -> Self {
         match value {
@@ -761,17 +1102,50 @@ impl From<QueryEnum> for api::grpc::qdrant::QueryEnum {
                             .query
                             .positives
                             .into_iter()
-                            .map(|v| api::grpc::qdrant::Vector { data: v })
+                            .map(|v| v.into())
                             .collect(),
                         negatives: named
                             .query
                             .negatives
                             .into_iter()
-                            .map(|v| api::grpc::qdrant::Vector { data: v })
+                            .map(|v| v.into())
+                            .collect(),
+                    },
+                )),
+            },
+            QueryEnum::Discover(named) => api::grpc::qdrant::QueryEnum {
+                query: Some(api::grpc::qdrant::query_enum::Query::Discover(
+                    api::grpc::qdrant::DiscoveryQuery {
+                        target: Some(named.query.target.into()),
+                        context: named
+                            .query
+                            .pairs
+                            .into_iter()
+                            .map(|pair| pair.into())
+                            .collect(),
+                    },
+                )),
+            },
+            QueryEnum::Context(named) => api::grpc::qdrant::QueryEnum {
+                query: Some(api::grpc::qdrant::query_enum::Query::Context(
+                    api::grpc::qdrant::ContextQuery {
+                        context: named
+                            .query
+                            .pairs
+                            .into_iter()
+                            .map(|pair| pair.into())
                             .collect(),
                     },
                 )),
             },
+            QueryEnum::Text(text) => api::grpc::qdrant::QueryEnum {
+                query: Some(api::grpc::qdrant::query_enum::Query::Text(text.into())),
+            },
+            QueryEnum::Sample(sample) => api::grpc::qdrant::QueryEnum {
+                query: Some(api::grpc::qdrant::query_enum::Query::Sample(
+                    api::grpc::qdrant::Sample::from(sample) as i32,
+                )),
+            },
         }
     }
 }
@@ -790,7 +1164,7 @@ impl<'a> From<CollectionCoreSearchRequest<'a>> for api::grpc::qdrant::CoreSearch
             params: request.params.map(|sp| sp.into()),
             score_threshold: request.score_threshold,
             offset: Some(request.offset as u64),
-            vector_name: Some(request.query.get_vector_name().to_owned()),
+            vector_name: request.query.get_vector_name().map(ToOwned::to_owned),
             read_consistency: None,
         }
     }
@@ -826,31 +1200,55 @@ impl TryFrom<api::grpc::qdrant::CoreSearchPoints> for CoreSearchRequest {
     type Error = Status;
 
     fn try_from(value: api::grpc::qdrant::CoreSearchPoints) -> Result<Self, Self::Error> {
-        let query = value
+        let query = match value
             .query
             .and_then(|query| query.query)
-            .map(|query| match query {
-                api::grpc::qdrant::query_enum::Query::NearestNeighbors(vector) => {
-                    QueryEnum::Nearest(match value.vector_name {
-                        Some(name) => NamedVector {
-                            name,
-                            vector: vector.data,
-                        }
-                        .into(),
-                        None => vector.data.into(),
-                    })
-                }
-                api::grpc::qdrant::query_enum::Query::RecommendBestScore(query) => {
-                    QueryEnum::RecommendBestScore(NamedRecoQuery {
-                        query: RecoQuery::new(
-                            query.positives.into_iter().map(|v| v.data).collect(),
-                            query.negatives.into_iter().map(|v| v.data).collect(),
-                        ),
-                        using: value.vector_name,
-                    })
-                }
-            })
-            .ok_or(Status::invalid_argument("Query is not specified"))?;
+            .ok_or(Status::invalid_argument("Query is not specified"))?
+        {
+            api::grpc::qdrant::query_enum::Query::NearestNeighbors(vector) => QueryEnum::Nearest(
+                NamedVectorStruct::new_from_vector(vector.into(), value.vector_name),
+            ),
+            api::grpc::qdrant::query_enum::Query::RecommendBestScore(query) => {
+                QueryEnum::RecommendBestScore(NamedRecoQuery {
+                    query: RecoQuery::new(
+                        query.positives.into_iter().map(|v| v.data).collect(),
+                        query.negatives.into_iter().map(|v| v.data).collect(),
+                    ),
+                    using: value.vector_name,
+                })
+            }
+            api::grpc::qdrant::query_enum::Query::Discover(query) => {
+                let target = query
+                    .target
+                    .ok_or_else(|| Status::invalid_argument("Target is not specified"))?;
+                let pairs = query
+                    .context
+                    .into_iter()
+                    .map(ContextPair::try_from)
+                    .collect::<Result<_, _>>()?;
+                QueryEnum::Discover(NamedDiscoveryQuery {
+                    query: DiscoveryQuery::new(target.data, pairs),
+                    using: value.vector_name,
+                })
+            }
+            api::grpc::qdrant::query_enum::Query::Context(query) => {
+                let pairs = query
+                    .context
+                    .into_iter()
+                    .map(ContextPair::try_from)
+                    .collect::<Result<_, _>>()?;
+                QueryEnum::Context(NamedContextQuery {
+                    query: ContextQuery::new(pairs),
+                    using: value.vector_name,
+                })
+            }
+            api::grpc::qdrant::query_enum::Query::Text(text) => QueryEnum::Text(text.into()),
+            api::grpc::qdrant::query_enum::Query::Sample(sample) => {
+                let sample = api::grpc::qdrant::Sample::from_i32(sample)
+                    .ok_or_else(|| Status::invalid_argument("Unknown sample method"))?;
+                QueryEnum::Sample(sample.into())
+            }
+        };
 
         Ok(Self {
             query,
@@ -874,15 +1272,13 @@ impl TryFrom<api::grpc::qdrant::SearchPoints> for SearchRequest {
     type Error = Status;
 
     fn try_from(value: api::grpc::qdrant::SearchPoints) -> Result<Self, Self::Error> {
+        let vector = match value.sparse_indices {
+            Some(indices) => SparseVector::new(indices.data, value.vector).into(),
+            None => value.vector.into(),
+        };
+
         Ok(SearchRequest {
-            vector: match value.vector_name {
-                Some(vector_name) => NamedVector {
-                    name: vector_name,
-                    vector: value.vector,
-                }
-                .into(),
-                None => value.vector.into(),
-            },
+            vector: NamedVectorStruct::new_from_vector(vector, value.vector_name).into(),
             filter: value.filter.map(|f| f.try_into()).transpose()?,
             params: value.params.map(|p| p.into()),
             limit: value.limit as usize,
@@ -895,6 +1291,7 @@ impl TryFrom<api::grpc::qdrant::SearchPoints> for SearchRequest {
                     .unwrap_or_default(),
             ),
             score_threshold: value.score_threshold,
+            text: value.text.map(|text| text.into()),
         })
     }
 }
@@ -915,6 +1312,11 @@ impl TryFrom<api::grpc::qdrant::SearchPointGroups> for SearchGroupsRequest {
             offset: None,
             collection_name: String::new(),
             read_consistency: None,
+            sparse_indices: None,
+            text: None,
+            timeout: None,
+            with_usage: None,
+            explain: None,
         };
 
         let SearchRequest {
@@ -926,6 +1328,7 @@ impl TryFrom<api::grpc::qdrant::SearchPointGroups> for SearchGroupsRequest {
             with_payload,
             with_vector,
             score_threshold,
+            text: _,
         } = search_points.try_into()?;
 
         Ok(SearchGroupsRequest {
@@ -1063,6 +1466,7 @@ impl TryFrom<api::grpc::qdrant::RecommendPointGroups> for RecommendGroupsRequest
             collection_name: String::new(),
             positive_vectors: value.positive_vectors,
             negative_vectors: value.negative_vectors,
+            timeout: None,
         };
 
         let RecommendRequest {
@@ -1101,6 +1505,351 @@ impl TryFrom<api::grpc::qdrant::RecommendPointGroups> for RecommendGroupsRequest
     }
 }
 
+impl TryFrom<api::grpc::qdrant::VectorInput> for RecommendExample {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::VectorInput) -> Result<Self, Self::Error> {
+        use api::grpc::qdrant::vector_input::Variant;
+
+        match value.variant {
+            Some(Variant::Id(id)) => Ok(Self::PointId(id.try_into()?)),
+            Some(Variant::Vector(vector)) => {
+                match segment::data_types::vectors::Vector::from(vector) {
+                    segment::data_types::vectors::Vector::Dense(vector) => Ok(Self::Vector(vector)),
+                    segment::data_types::vectors::Vector::MultiDense(_)
+                    | segment::data_types::vectors::Vector::Sparse(_) => {
+                        Err(Status::invalid_argument(
+                            "Only dense vectors can be used as query examples",
+                        ))
+                    }
+                }
+            }
+            None => Err(Status::invalid_argument("Vector input is missing")),
+        }
+    }
+}
+
+fn try_example_from_grpc(
+    value: Option<api::grpc::qdrant::VectorInput>,
+    name: &str,
+) -> Result<RecommendExample, Status> {
+    value
+        .ok_or_else(|| Status::invalid_argument(format!("`{name}` example is missing")))?
+        .try_into()
+}
+
+impl TryFrom<api::grpc::qdrant::ContextInputPair> for ContextExamplePair {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::ContextInputPair) -> Result<Self, Self::Error> {
+        Ok(Self {
+            positive: try_example_from_grpc(value.positive, "positive")?,
+            negative: try_example_from_grpc(value.negative, "negative")?,
+        })
+    }
+}
+
+impl From<api::grpc::qdrant::Fusion> for Fusion {
+    fn from(value: api::grpc::qdrant::Fusion) -> Self {
+        match value {
+            api::grpc::qdrant::Fusion::Rrf => Fusion::Rrf,
+            api::grpc::qdrant::Fusion::RelativeScore => Fusion::RelativeScore,
+        }
+    }
+}
+
+fn try_expression_from_grpc(
+    value: Option<impl Into<Box<api::grpc::qdrant::Expression>>>,
+) -> Result<Box<Expression>, Status> {
+    let expression = value
+        .ok_or_else(|| Status::invalid_argument("Formula expression is missing"))?
+        .into();
+    Ok(Box::new((*expression).try_into()?))
+}
+
+impl TryFrom<api::grpc::qdrant::DecayParamsExpression> for DecayParams {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::DecayParamsExpression) -> Result<Self, Self::Error> {
+        let api::grpc::qdrant::DecayParamsExpression {
+            x,
+            target,
+            scale,
+            midpoint,
+        } = value;
+        Ok(Self {
+            x: try_expression_from_grpc(x)?,
+            target: target
+                .map(|target| try_expression_from_grpc(Some(target)))
+                .transpose()?,
+            scale,
+            midpoint,
+        })
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::Expression> for Expression {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::Expression) -> Result<Self, Self::Error> {
+        use api::grpc::qdrant::expression::Variant;
+
+        let try_expressions = |expressions: Vec<api::grpc::qdrant::Expression>| {
+            expressions
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<Expression>, Status>>()
+        };
+
+        let variant = value
+            .variant
+            .ok_or_else(|| Status::invalid_argument("Formula expression is missing"))?;
+
+        let expression = match variant {
+            Variant::Constant(constant) => Expression::Constant(constant),
+            Variant::Variable(variable) => Expression::Variable(variable),
+            Variant::Mult(mult) => Expression::Mult(MultExpression {
+                mult: try_expressions(mult.mult)?,
+            }),
+            Variant::Sum(sum) => Expression::Sum(SumExpression {
+                sum: try_expressions(sum.sum)?,
+            }),
+            Variant::Neg(neg) => Expression::Neg(NegExpression {
+                neg: try_expression_from_grpc(Some(neg))?,
+            }),
+            Variant::Abs(abs) => Expression::Abs(AbsExpression {
+                abs: try_expression_from_grpc(Some(abs))?,
+            }),
+            Variant::Div(div) => {
+                let api::grpc::qdrant::DivExpression {
+                    left,
+                    right,
+                    by_zero_default,
+                } = *div;
+                Expression::Div(DivExpression {
+                    div: DivParams {
+                        left: try_expression_from_grpc(left)?,
+                        right: try_expression_from_grpc(right)?,
+                        by_zero_default,
+                    },
+                })
+            }
+            Variant::Sqrt(sqrt) => Expression::Sqrt(SqrtExpression {
+                sqrt: try_expression_from_grpc(Some(sqrt))?,
+            }),
+            Variant::Pow(pow) => {
+                let api::grpc::qdrant::PowExpression { base, exponent } = *pow;
+                Expression::Pow(PowExpression {
+                    pow: PowParams {
+                        base: try_expression_from_grpc(base)?,
+                        exponent: try_expression_from_grpc(exponent)?,
+                    },
+                })
+            }
+            Variant::Exp(exp) => Expression::Exp(ExpExpression {
+                exp: try_expression_from_grpc(Some(exp))?,
+            }),
+            Variant::Log10(log10) => Expression::Log10(Log10Expression {
+                log10: try_expression_from_grpc(Some(log10))?,
+            }),
+            Variant::Ln(ln) => Expression::Ln(LnExpression {
+                ln: try_expression_from_grpc(Some(ln))?,
+            }),
+            Variant::GeoDistance(geo_distance) => {
+                let api::grpc::qdrant::GeoDistance { origin, to } = geo_distance;
+                let origin = origin
+                    .ok_or_else(|| Status::invalid_argument("Geo distance origin is missing"))?;
+                Expression::GeoDistance(GeoDistanceExpression {
+                    geo_distance: GeoDistanceParams {
+                        origin: origin.into(),
+                        to,
+                    },
+                })
+            }
+            Variant::Datetime(datetime) => Expression::Datetime(DatetimeExpression { datetime }),
+            Variant::DatetimeKey(datetime_key) => {
+                Expression::DatetimeKey(DatetimeKeyExpression { datetime_key })
+            }
+            Variant::ExpDecay(params) => Expression::ExpDecay(ExpDecayExpression {
+                exp_decay: (*params).try_into()?,
+            }),
+            Variant::GaussDecay(params) => Expression::GaussDecay(GaussDecayExpression {
+                gauss_decay: (*params).try_into()?,
+            }),
+            Variant::LinDecay(params) => Expression::LinDecay(LinDecayExpression {
+                lin_decay: (*params).try_into()?,
+            }),
+            Variant::Condition(condition) => Expression::Condition(Box::new(condition.try_into()?)),
+        };
+
+        Ok(expression)
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::Formula> for FormulaQuery {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::Formula) -> Result<Self, Self::Error> {
+        let api::grpc::qdrant::Formula {
+            expression,
+            defaults,
+        } = value;
+        Ok(Self {
+            expression: *try_expression_from_grpc(expression)?,
+            defaults: defaults
+                .into_iter()
+                .map(|(key, value)| Ok((key, proto_to_json(value)?)))
+                .collect::<Result<_, Status>>()?,
+        })
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::Query> for Query {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::Query) -> Result<Self, Self::Error> {
+        use api::grpc::qdrant::query::Variant;
+
+        let try_examples = |examples: Vec<api::grpc::qdrant::VectorInput>| {
+            examples
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<RecommendExample>, Status>>()
+        };
+        let try_pairs = |pairs: Vec<api::grpc::qdrant::ContextInputPair>| {
+            pairs
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<Vec<ContextExamplePair>, Status>>()
+        };
+
+        let variant = value
+            .variant
+            .ok_or_else(|| Status::invalid_argument("Query is missing"))?;
+
+        let query = match variant {
+            Variant::Nearest(vector) => Query::Nearest(vector.into()),
+            Variant::Recommend(input) => Query::Recommend(RecommendInput {
+                positive: try_examples(input.positive)?,
+                negative: try_examples(input.negative)?,
+            }),
+            Variant::Discover(input) => Query::Discover(DiscoverInput {
+                target: try_example_from_grpc(input.target, "target")?,
+                context: try_pairs(input.context)?,
+            }),
+            Variant::Context(input) => Query::Context(try_pairs(input.pairs)?),
+            Variant::Text(text) => Query::Text(text.into()),
+            Variant::Fusion(fusion) => {
+                let fusion = api::grpc::qdrant::Fusion::from_i32(fusion)
+                    .ok_or_else(|| Status::invalid_argument("Unknown fusion method"))?;
+                Query::Fusion(fusion.into())
+            }
+            Variant::Sample(sample) => {
+                let sample = api::grpc::qdrant::Sample::from_i32(sample)
+                    .ok_or_else(|| Status::invalid_argument("Unknown sample method"))?;
+                Query::Sample(sample.into())
+            }
+            Variant::Formula(formula) => Query::Formula(formula.try_into()?),
+            Variant::Plugin(plugin) => Query::Plugin(plugin.into()),
+        };
+
+        Ok(query)
+    }
+}
+
+impl From<api::grpc::qdrant::PluginQuery> for PluginQuery {
+    fn from(value: api::grpc::qdrant::PluginQuery) -> Self {
+        let api::grpc::qdrant::PluginQuery { name, version } = value;
+        Self { name, version }
+    }
+}
+
+impl From<api::grpc::qdrant::Diversity> for Diversity {
+    fn from(value: api::grpc::qdrant::Diversity) -> Self {
+        let api::grpc::qdrant::Diversity {
+            lambda,
+            candidates_limit,
+        } = value;
+        Self {
+            lambda,
+            candidates_limit: candidates_limit.map(|limit| limit as usize),
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::PrefetchQuery> for Prefetch {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::PrefetchQuery) -> Result<Self, Self::Error> {
+        let api::grpc::qdrant::PrefetchQuery {
+            prefetch,
+            query,
+            using,
+            filter,
+            params,
+            score_threshold,
+            limit,
+            lookup_from,
+        } = value;
+        Ok(Self {
+            prefetch: prefetch
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, Status>>()?,
+            query: query.map(TryInto::try_into).transpose()?,
+            using,
+            lookup_from: lookup_from.map(Into::into),
+            filter: filter.map(TryInto::try_into).transpose()?,
+            params: params.map(Into::into),
+            score_threshold,
+            limit: limit.map_or(default_query_limit(), |limit| limit as usize),
+        })
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::QueryPoints> for QueryRequest {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::QueryPoints) -> Result<Self, Self::Error> {
+        let api::grpc::qdrant::QueryPoints {
+            collection_name: _,
+            prefetch,
+            query,
+            using,
+            filter,
+            params,
+            score_threshold,
+            limit,
+            offset,
+            with_vectors,
+            with_payload,
+            read_consistency: _,
+            lookup_from,
+            diversity,
+            with_explanation,
+            timeout: _,
+        } = value;
+        Ok(Self {
+            prefetch: prefetch
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, Status>>()?,
+            query: query.map(TryInto::try_into).transpose()?,
+            using,
+            lookup_from: lookup_from.map(Into::into),
+            filter: filter.map(TryInto::try_into).transpose()?,
+            params: params.map(Into::into),
+            score_threshold,
+            limit: limit.map_or(default_query_limit(), |limit| limit as usize),
+            offset: offset.unwrap_or_default() as usize,
+            diversity: diversity.map(Into::into),
+            with_payload: with_payload.map(TryInto::try_into).transpose()?,
+            with_vector: with_vectors.map(Into::into),
+            with_explanation: with_explanation.unwrap_or_default(),
+        })
+    }
+}
+
 impl From<GroupsResult> for api::grpc::qdrant::GroupsResult {
     fn from(value: GroupsResult) -> Self {
         Self {
@@ -1117,11 +1866,41 @@ impl From<VectorParams> for api::grpc::qdrant::VectorParams {
                 Distance::Cosine => api::grpc::qdrant::Distance::Cosine,
                 Distance::Euclid => api::grpc::qdrant::Distance::Euclid,
                 Distance::Dot => api::grpc::qdrant::Distance::Dot,
+                Distance::Manhattan => api::grpc::qdrant::Distance::Manhattan,
+                Distance::Hamming => api::grpc::qdrant::Distance::Hamming,
+                Distance::Jaccard => api::grpc::qdrant::Distance::Jaccard,
             }
             .into(),
             hnsw_config: value.hnsw_config.map(Into::into),
             quantization_config: value.quantization_config.map(Into::into),
             on_disk: value.on_disk,
+            multivector_config: value.multivector_config.map(Into::into),
+            datatype: value
+                .datatype
+                .map(|datatype| api::grpc::qdrant::Datatype::from(datatype).into()),
+        }
+    }
+}
+
+impl From<SparseVectorParams> for api::grpc::qdrant::SparseVectorParams {
+    fn from(value: SparseVectorParams) -> Self {
+        api::grpc::qdrant::SparseVectorParams {
+            index: value
+                .index
+                .map(|index| api::grpc::qdrant::SparseIndexConfig {
+                    full_scan_threshold: index.full_scan_threshold.map(|v| v as u64),
+                }),
+        }
+    }
+}
+
+impl From<BTreeMap<String, SparseVectorParams>> for api::grpc::qdrant::SparseVectorConfig {
+    fn from(value: BTreeMap<String, SparseVectorParams>) -> Self {
+        api::grpc::qdrant::SparseVectorConfig {
+            map: value
+                .into_iter()
+                .map(|(name, params)| (name, params.into()))
+                .collect(),
         }
     }
 }
@@ -1229,3 +2008,199 @@ impl From<ClusterOperationsPb> for ClusterOperations {
         }
     }
 }
+
+impl TryFrom<api::grpc::qdrant::PayloadMutation> for PayloadMutation {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::PayloadMutation) -> Result<Self, Self::Error> {
+        let mutation = value
+            .mutation
+            .ok_or_else(|| Status::invalid_argument("Payload mutation is missing"))?;
+        Ok(match mutation {
+            Mutation::Set(payload_mutation::Set { key, value }) => PayloadMutation::Set {
+                key,
+                value: value.map(proto_to_json).transpose()?.unwrap_or_default(),
+            },
+            Mutation::Increment(payload_mutation::Increment { key, by }) => {
+                PayloadMutation::Increment { key, by }
+            }
+            Mutation::Copy(payload_mutation::CopyValue { from, to }) => {
+                PayloadMutation::Copy { from, to }
+            }
+            Mutation::Remove(payload_mutation::Remove { key }) => PayloadMutation::Remove { key },
+        })
+    }
+}
+
+impl From<PayloadMutation> for api::grpc::qdrant::PayloadMutation {
+    fn from(value: PayloadMutation) -> Self {
+        let mutation = match value {
+            PayloadMutation::Set { key, value } => Mutation::Set(payload_mutation::Set {
+                key,
+                value: Some(json_to_proto(value)),
+            }),
+            PayloadMutation::Increment { key, by } => {
+                Mutation::Increment(payload_mutation::Increment { key, by })
+            }
+            PayloadMutation::Copy { from, to } => {
+                Mutation::Copy(payload_mutation::CopyValue { from, to })
+            }
+            PayloadMutation::Remove { key } => Mutation::Remove(payload_mutation::Remove { key }),
+        };
+        Self {
+            mutation: Some(mutation),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::grpc::qdrant::{expression, query, vector_input};
+    use serde_json::json;
+
+    use super::*;
+
+    fn grpc_expression(variant: expression::Variant) -> api::grpc::qdrant::Expression {
+        api::grpc::qdrant::Expression {
+            variant: Some(variant),
+        }
+    }
+
+    #[test]
+    fn test_query_points_from_grpc() {
+        let nearest = api::grpc::qdrant::Query {
+            variant: Some(query::Variant::Nearest(vec![0.1, 0.2].into())),
+        };
+        let recommend = api::grpc::qdrant::Query {
+            variant: Some(query::Variant::Recommend(
+                api::grpc::qdrant::RecommendInput {
+                    positive: vec![api::grpc::qdrant::VectorInput {
+                        variant: Some(vector_input::Variant::Id(PointIdType::NumId(1).into())),
+                    }],
+                    negative: vec![],
+                },
+            )),
+        };
+        let formula = api::grpc::qdrant::Query {
+            variant: Some(query::Variant::Formula(api::grpc::qdrant::Formula {
+                expression: Some(grpc_expression(expression::Variant::Sum(
+                    api::grpc::qdrant::SumExpression {
+                        sum: vec![
+                            grpc_expression(expression::Variant::Variable("$score".to_string())),
+                            grpc_expression(expression::Variant::ExpDecay(Box::new(
+                                api::grpc::qdrant::DecayParamsExpression {
+                                    x: Some(Box::new(grpc_expression(
+                                        expression::Variant::DatetimeKey("updated".to_string()),
+                                    ))),
+                                    target: None,
+                                    scale: Some(86400.0),
+                                    midpoint: None,
+                                },
+                            ))),
+                        ],
+                    },
+                ))),
+                defaults: HashMap::from([("updated".to_string(), json_to_proto(json!(0)))]),
+            })),
+        };
+
+        let request = QueryRequest::try_from(api::grpc::qdrant::QueryPoints {
+            collection_name: "test".to_string(),
+            prefetch: vec![api::grpc::qdrant::PrefetchQuery {
+                prefetch: vec![api::grpc::qdrant::PrefetchQuery {
+                    query: Some(recommend),
+                    lookup_from: Some(api::grpc::qdrant::LookupLocation {
+                        collection_name: "other".to_string(),
+                        vector_name: None,
+                    }),
+                    limit: Some(100),
+                    ..Default::default()
+                }],
+                query: Some(nearest),
+                ..Default::default()
+            }],
+            query: Some(formula),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let expected: FormulaQuery = serde_json::from_value(json!({
+            "expression": {"sum": [
+                "$score",
+                {"exp_decay": {"x": {"datetime_key": "updated"}, "scale": 86400.0}},
+            ]},
+            "defaults": {"updated": 0},
+        }))
+        .unwrap();
+        assert!(matches!(&request.query, Some(Query::Formula(formula)) if *formula == expected));
+        assert_eq!(request.limit, default_query_limit());
+        assert!(!request.with_explanation);
+
+        let prefetch = &request.prefetch[0];
+        assert!(matches!(prefetch.query, Some(Query::Nearest(_))));
+        assert_eq!(prefetch.limit, default_query_limit());
+        assert!(prefetch.lookup_from.is_none());
+
+        let nested = &prefetch.prefetch[0];
+        assert_eq!(nested.limit, 100);
+        assert_eq!(nested.lookup_from.as_ref().unwrap().collection, "other");
+        let Some(Query::Recommend(input)) = &nested.query else {
+            panic!("recommend query expected");
+        };
+        assert_eq!(input.positive, vec![RecommendExample::PointId(1u64.into())]);
+    }
+
+    #[test]
+    fn test_query_from_grpc_rejects_invalid_input() {
+        let sparse = api::grpc::qdrant::VectorInput {
+            variant: Some(vector_input::Variant::Vector(api::grpc::qdrant::Vector {
+                data: vec![0.5],
+                indices: Some(api::grpc::qdrant::SparseIndices { data: vec![3] }),
+                vectors_count: None,
+            })),
+        };
+        assert!(RecommendExample::try_from(sparse).is_err());
+
+        let missing_target = api::grpc::qdrant::Query {
+            variant: Some(query::Variant::Discover(api::grpc::qdrant::DiscoverInput {
+                target: None,
+                context: vec![],
+            })),
+        };
+        assert!(Query::try_from(missing_target).is_err());
+
+        let unknown_fusion = api::grpc::qdrant::Query {
+            variant: Some(query::Variant::Fusion(42)),
+        };
+        assert!(Query::try_from(unknown_fusion).is_err());
+    }
+
+    #[test]
+    fn test_score_explanation_grpc_round_trip() {
+        let explanation = segment::types::ScoreExplanation {
+            similarity: None,
+            quantized_similarity: None,
+            prefetches: vec![
+                None,
+                Some(segment::types::PrefetchScore {
+                    rank: 2,
+                    score: 0.5,
+                    contribution: Some(1.0 / 63.0),
+                    explanation: Some(Box::new(segment::types::ScoreExplanation {
+                        similarity: Some(0.5),
+                        quantized_similarity: Some(0.49),
+                        ..Default::default()
+                    })),
+                }),
+            ],
+            formula_terms: vec![],
+        };
+
+        let grpc = api::grpc::qdrant::ScoreExplanation::from(explanation.clone());
+        // Prefetches, which did not return the point, are not sent
+        assert_eq!(grpc.prefetches.len(), 1);
+        assert_eq!(grpc.prefetches[0].prefetch, 1);
+
+        assert_eq!(segment::types::ScoreExplanation::from(grpc), explanation);
+    }
+}
@@ -0,0 +1,673 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use common::types::ScoreType;
+use schemars::JsonSchema;
+use segment::index::field_index::FieldIndex;
+use segment::payload_storage::query_checker::{
+    check_field_condition, check_filter, check_is_empty_condition, check_is_null_condition,
+    check_payload,
+};
+use segment::types::{
+    parse_datetime, Condition, GeoPoint, OwnedPayloadRef, Payload, PayloadContainer, PayloadKeyType,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+use crate::operations::types::{CollectionError, CollectionResult, Record};
+
+/// Variable with the score of a point in the first prefetch,
+/// `$score[i]` is the score in the i-th prefetch
+const SCORE_VARIABLE: &str = "$score";
+
+/// Rescore the candidates of the prefetches by a formula
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct FormulaQuery {
+    pub expression: Expression,
+    /// Values of the variables, which are not found in the payload of a point
+    /// or in the results of a prefetch
+    #[serde(default)]
+    pub defaults: HashMap<String, Value>,
+}
+
+impl Validate for FormulaQuery {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        if let Err(error) = self.expression.check() {
+            errors.add("expression", error);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Arithmetic expression over the score and the payload of a point
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum Expression {
+    Constant(f64),
+    /// `$score`, `$score[i]` or a payload key with a numeric value
+    Variable(String),
+    Mult(MultExpression),
+    Sum(SumExpression),
+    Neg(NegExpression),
+    Abs(AbsExpression),
+    Div(DivExpression),
+    Sqrt(SqrtExpression),
+    Pow(PowExpression),
+    Exp(ExpExpression),
+    Log10(Log10Expression),
+    Ln(LnExpression),
+    GeoDistance(GeoDistanceExpression),
+    Datetime(DatetimeExpression),
+    DatetimeKey(DatetimeKeyExpression),
+    ExpDecay(ExpDecayExpression),
+    GaussDecay(GaussDecayExpression),
+    LinDecay(LinDecayExpression),
+    /// 1 if the point matches the condition, 0 otherwise
+    Condition(Box<Condition>),
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct MultExpression {
+    pub mult: Vec<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct SumExpression {
+    pub sum: Vec<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct NegExpression {
+    pub neg: Box<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct AbsExpression {
+    pub abs: Box<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct DivExpression {
+    pub div: DivParams,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct DivParams {
+    pub left: Box<Expression>,
+    pub right: Box<Expression>,
+    /// Result of division by zero, fails the query if not specified
+    pub by_zero_default: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct SqrtExpression {
+    pub sqrt: Box<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct PowExpression {
+    pub pow: PowParams,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct PowParams {
+    pub base: Box<Expression>,
+    pub exponent: Box<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct ExpExpression {
+    pub exp: Box<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct Log10Expression {
+    pub log10: Box<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct LnExpression {
+    pub ln: Box<Expression>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct GeoDistanceExpression {
+    pub geo_distance: GeoDistanceParams,
+}
+
+/// Distance in meters between the origin and the geo point in the payload
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct GeoDistanceParams {
+    pub origin: GeoPoint,
+    /// Payload key with the geo point
+    pub to: PayloadKeyType,
+}
+
+/// RFC 3339 datetime, evaluated to a timestamp in seconds
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct DatetimeExpression {
+    pub datetime: String,
+}
+
+/// Payload key with a datetime, evaluated to a timestamp in seconds
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct DatetimeKeyExpression {
+    pub datetime_key: PayloadKeyType,
+}
+
+/// `exp(ln(midpoint) * |x - target| / scale)`
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct ExpDecayExpression {
+    pub exp_decay: DecayParams,
+}
+
+/// `exp(ln(midpoint) * ((x - target) / scale)^2)`
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct GaussDecayExpression {
+    pub gauss_decay: DecayParams,
+}
+
+/// `max(0, 1 - (1 - midpoint) * |x - target| / scale)`
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct LinDecayExpression {
+    pub lin_decay: DecayParams,
+}
+
+/// Decay from 1 at the target down to `midpoint` at `scale` distance from it
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct DecayParams {
+    pub x: Box<Expression>,
+    /// Default is 0
+    pub target: Option<Box<Expression>>,
+    /// Must be positive. Default is 1
+    pub scale: Option<f64>,
+    /// Must be within (0, 1). Default is 0.5
+    pub midpoint: Option<f64>,
+}
+
+impl DecayParams {
+    fn scale(&self) -> f64 {
+        self.scale.unwrap_or(1.0)
+    }
+
+    fn midpoint(&self) -> f64 {
+        self.midpoint.unwrap_or(0.5)
+    }
+}
+
+/// Candidate point to score by a formula
+pub struct FormulaPoint<'a> {
+    /// Scores of the point in each prefetch, if the prefetch has found it
+    pub scores: &'a [Option<ScoreType>],
+    pub record: &'a Record,
+}
+
+impl FormulaQuery {
+    pub fn evaluate(&self, point: &FormulaPoint) -> CollectionResult<ScoreType> {
+        self.expression
+            .evaluate(point, &self.defaults)
+            .map(|score| score as ScoreType)
+    }
+
+    /// Values of the top-level terms of the formula: operands of a sum or a product,
+    /// or the whole expression otherwise
+    pub fn terms(&self, point: &FormulaPoint) -> CollectionResult<Vec<f64>> {
+        match &self.expression {
+            Expression::Sum(SumExpression { sum: terms })
+            | Expression::Mult(MultExpression { mult: terms }) => terms
+                .iter()
+                .map(|term| term.evaluate(point, &self.defaults))
+                .collect(),
+            expression => Ok(vec![expression.evaluate(point, &self.defaults)?]),
+        }
+    }
+
+    /// Names of the vectors used by `has_vector` conditions of the formula
+    pub fn vector_names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        self.expression.visit(&mut |expression| {
+            if let Expression::Condition(condition) = expression {
+                collect_vector_names(condition, &mut names);
+            }
+        });
+        names.sort_unstable();
+        names.dedup();
+        names
+    }
+}
+
+impl Expression {
+    fn children(&self) -> Vec<&Expression> {
+        match self {
+            Expression::Constant(_)
+            | Expression::Variable(_)
+            | Expression::GeoDistance(_)
+            | Expression::Datetime(_)
+            | Expression::DatetimeKey(_)
+            | Expression::Condition(_) => vec![],
+            Expression::Mult(MultExpression { mult: expressions })
+            | Expression::Sum(SumExpression { sum: expressions }) => expressions.iter().collect(),
+            Expression::Neg(NegExpression { neg: expression })
+            | Expression::Abs(AbsExpression { abs: expression })
+            | Expression::Sqrt(SqrtExpression { sqrt: expression })
+            | Expression::Exp(ExpExpression { exp: expression })
+            | Expression::Log10(Log10Expression { log10: expression })
+            | Expression::Ln(LnExpression { ln: expression }) => vec![expression.as_ref()],
+            Expression::Div(DivExpression { div }) => vec![div.left.as_ref(), div.right.as_ref()],
+            Expression::Pow(PowExpression { pow }) => {
+                vec![pow.base.as_ref(), pow.exponent.as_ref()]
+            }
+            Expression::ExpDecay(ExpDecayExpression { exp_decay: decay })
+            | Expression::GaussDecay(GaussDecayExpression { gauss_decay: decay })
+            | Expression::LinDecay(LinDecayExpression { lin_decay: decay }) => {
+                let mut children = vec![decay.x.as_ref()];
+                children.extend(decay.target.as_deref());
+                children
+            }
+        }
+    }
+
+    fn visit(&self, visitor: &mut impl FnMut(&Expression)) {
+        visitor(self);
+        for child in self.children() {
+            child.visit(visitor);
+        }
+    }
+
+    /// Check the parameters, which don't depend on the evaluated point
+    fn check(&self) -> Result<(), ValidationError> {
+        let mut result = Ok(());
+        self.visit(&mut |expression| {
+            if result.is_err() {
+                return;
+            }
+            result = match expression {
+                Expression::Variable(name) if name.starts_with('$') => score_index(name)
+                    .map(|_| ())
+                    .ok_or_else(|| invalid(format!("unknown variable {name}"))),
+                Expression::Datetime(DatetimeExpression { datetime }) => parse_datetime(datetime)
+                    .map(|_| ())
+                    .ok_or_else(|| invalid(format!("{datetime} is not a valid datetime"))),
+                Expression::ExpDecay(ExpDecayExpression { exp_decay: decay })
+                | Expression::GaussDecay(GaussDecayExpression { gauss_decay: decay })
+                | Expression::LinDecay(LinDecayExpression { lin_decay: decay }) => {
+                    if decay.scale() <= 0.0 {
+                        Err(invalid("decay scale must be positive".to_string()))
+                    } else if decay.midpoint() <= 0.0 || decay.midpoint() >= 1.0 {
+                        Err(invalid("decay midpoint must be within (0, 1)".to_string()))
+                    } else {
+                        Ok(())
+                    }
+                }
+                _ => Ok(()),
+            };
+        });
+        result
+    }
+
+    fn evaluate(
+        &self,
+        point: &FormulaPoint,
+        defaults: &HashMap<String, Value>,
+    ) -> CollectionResult<f64> {
+        let eval = |expression: &Expression| expression.evaluate(point, defaults);
+        let id = point.record.id;
+
+        let value = match self {
+            Expression::Constant(value) => *value,
+            Expression::Variable(name) => {
+                let score = score_index(name)
+                    .and_then(|index| point.scores.get(index).copied().flatten())
+                    .map(f64::from);
+                score
+                    .or_else(|| lookup(name, point.record, defaults, Value::as_f64))
+                    .ok_or_else(|| missing_variable(name, point))?
+            }
+            Expression::Condition(condition) => {
+                if check_condition(condition, point.record) {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            Expression::GeoDistance(GeoDistanceExpression { geo_distance }) => {
+                let to = lookup(&geo_distance.to, point.record, defaults, |value| {
+                    GeoPoint::deserialize(value).ok()
+                })
+                .ok_or_else(|| missing_variable(&geo_distance.to, point))?;
+                geo_distance.origin.haversine_distance(&to)
+            }
+            Expression::Datetime(DatetimeExpression { datetime }) => parse_datetime(datetime)
+                .map(timestamp_seconds)
+                .ok_or_else(|| {
+                    CollectionError::bad_request(format!("{datetime} is not a valid datetime"))
+                })?,
+            Expression::DatetimeKey(DatetimeKeyExpression { datetime_key }) => {
+                lookup(datetime_key, point.record, defaults, |value| {
+                    value.as_str().and_then(parse_datetime)
+                })
+                .map(timestamp_seconds)
+                .ok_or_else(|| missing_variable(datetime_key, point))?
+            }
+            Expression::Mult(MultExpression { mult }) => {
+                mult.iter().map(eval).product::<CollectionResult<f64>>()?
+            }
+            Expression::Sum(SumExpression { sum }) => {
+                sum.iter().map(eval).sum::<CollectionResult<f64>>()?
+            }
+            Expression::Neg(NegExpression { neg }) => -eval(neg)?,
+            Expression::Abs(AbsExpression { abs }) => eval(abs)?.abs(),
+            Expression::Div(DivExpression { div }) => {
+                let (left, right) = (eval(&div.left)?, eval(&div.right)?);
+                if right == 0.0 {
+                    div.by_zero_default.ok_or_else(|| {
+                        CollectionError::bad_request(format!(
+                            "Formula divides by zero for point {id}, `by_zero_default` can be specified"
+                        ))
+                    })?
+                } else {
+                    left / right
+                }
+            }
+            Expression::Sqrt(SqrtExpression { sqrt }) => eval(sqrt)?.sqrt(),
+            Expression::Pow(PowExpression { pow }) => eval(&pow.base)?.powf(eval(&pow.exponent)?),
+            Expression::Exp(ExpExpression { exp }) => eval(exp)?.exp(),
+            Expression::Log10(Log10Expression { log10 }) => eval(log10)?.log10(),
+            Expression::Ln(LnExpression { ln }) => eval(ln)?.ln(),
+            Expression::ExpDecay(ExpDecayExpression { exp_decay: decay }) => {
+                let distance = decay_distance(decay, eval)?;
+                (decay.midpoint().ln() * distance).exp()
+            }
+            Expression::GaussDecay(GaussDecayExpression { gauss_decay: decay }) => {
+                let distance = decay_distance(decay, eval)?;
+                (decay.midpoint().ln() * distance * distance).exp()
+            }
+            Expression::LinDecay(LinDecayExpression { lin_decay: decay }) => {
+                let distance = decay_distance(decay, eval)?;
+                (1.0 - (1.0 - decay.midpoint()) * distance).max(0.0)
+            }
+        };
+
+        if value.is_finite() {
+            Ok(value)
+        } else {
+            Err(CollectionError::bad_request(format!(
+                "Formula evaluates to {value} for point {id}"
+            )))
+        }
+    }
+}
+
+/// Distance between `x` and the target in units of the scale
+fn decay_distance(
+    decay: &DecayParams,
+    eval: impl Fn(&Expression) -> CollectionResult<f64>,
+) -> CollectionResult<f64> {
+    let x = eval(&decay.x)?;
+    let target = match &decay.target {
+        Some(target) => eval(target)?,
+        None => 0.0,
+    };
+    Ok((x - target).abs() / decay.scale())
+}
+
+/// Index of the prefetch for `$score` and `$score[i]` variables
+fn score_index(name: &str) -> Option<usize> {
+    match name.strip_prefix(SCORE_VARIABLE)? {
+        "" => Some(0),
+        index => index.strip_prefix('[')?.strip_suffix(']')?.parse().ok(),
+    }
+}
+
+/// First suitable value of the payload key, or its default
+fn lookup<T>(
+    key: &str,
+    record: &Record,
+    defaults: &HashMap<String, Value>,
+    parse: impl Fn(&Value) -> Option<T>,
+) -> Option<T> {
+    record
+        .payload
+        .as_ref()
+        .and_then(|payload| payload.get_value(key).values().into_iter().find_map(&parse))
+        .or_else(|| defaults.get(key).and_then(&parse))
+}
+
+fn timestamp_seconds(datetime: segment::types::DateTimePayloadType) -> f64 {
+    datetime.timestamp_micros() as f64 / 1_000_000.0
+}
+
+fn missing_variable(name: &str, point: &FormulaPoint) -> CollectionError {
+    CollectionError::bad_request(format!(
+        "No value of {name} for point {}, a default value can be specified in `defaults`",
+        point.record.id,
+    ))
+}
+
+fn invalid(message: String) -> ValidationError {
+    let mut error = ValidationError::new("formula");
+    error.message.replace(Cow::from(message));
+    error
+}
+
+fn collect_vector_names(condition: &Condition, names: &mut Vec<String>) {
+    match condition {
+        Condition::HasVector(has_vector) => names.push(has_vector.has_vector.clone()),
+        Condition::Filter(filter) => {
+            let conditions = [&filter.should, &filter.must, &filter.must_not]
+                .into_iter()
+                .flatten()
+                .flatten()
+                .chain(
+                    filter
+                        .min_should
+                        .iter()
+                        .flat_map(|min_should| &min_should.conditions),
+                );
+            for condition in conditions {
+                collect_vector_names(condition, names);
+            }
+        }
+        Condition::Field(_)
+        | Condition::IsEmpty(_)
+        | Condition::IsNull(_)
+        | Condition::HasId(_)
+        | Condition::Nested(_) => {}
+    }
+}
+
+/// Check the condition against the retrieved point, payload indexes are not used
+fn check_condition(condition: &Condition, record: &Record) -> bool {
+    let empty_payload = Payload::default();
+    let payload = record.payload.as_ref().unwrap_or(&empty_payload);
+    let no_indexes: HashMap<PayloadKeyType, Vec<FieldIndex>> = HashMap::new();
+
+    let checker = |condition: &Condition| match condition {
+        Condition::Field(field_condition) => {
+            check_field_condition(field_condition, payload, &no_indexes)
+        }
+        Condition::IsEmpty(is_empty) => check_is_empty_condition(is_empty, payload),
+        Condition::IsNull(is_null) => check_is_null_condition(is_null, payload),
+        Condition::HasId(has_id) => has_id.has_id.contains(&record.id),
+        Condition::HasVector(has_vector) => record
+            .vector_names()
+            .contains(&has_vector.has_vector.as_str()),
+        Condition::Nested(nested) => payload
+            .get_value(&nested.array_key())
+            .values()
+            .iter()
+            .filter_map(|value| value.as_object())
+            .any(|object| {
+                check_payload(
+                    Box::new(|| OwnedPayloadRef::from(object)),
+                    None,
+                    None,
+                    &nested.nested.filter,
+                    0,
+                    &no_indexes,
+                )
+            }),
+        Condition::Filter(_) => unreachable!(),
+    };
+
+    match condition {
+        Condition::Filter(filter) => check_filter(&checker, filter),
+        condition => checker(condition),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn record(payload: Value) -> Record {
+        Record {
+            id: 1u64.into(),
+            payload: Some(serde_json::from_value(payload).unwrap()),
+            vector: None,
+            order_value: None,
+        }
+    }
+
+    fn evaluate(
+        formula: Value,
+        scores: &[Option<ScoreType>],
+        payload: Value,
+    ) -> CollectionResult<ScoreType> {
+        let query: FormulaQuery = serde_json::from_value(formula).unwrap();
+        query.validate().unwrap();
+        query.evaluate(&FormulaPoint {
+            scores,
+            record: &record(payload),
+        })
+    }
+
+    #[test]
+    fn test_parse_expression() {
+        let query: FormulaQuery = serde_json::from_value(json!({
+            "expression": {"sum": [
+                "$score",
+                {"mult": [0.5, {"key": "tag", "match": {"value": "sale"}}]},
+                {"exp_decay": {"x": {"datetime_key": "updated"}, "target": {"datetime": "2024-01-01T00:00:00Z"}}},
+            ]},
+            "defaults": {"price": 1.0},
+        }))
+        .unwrap();
+
+        let Expression::Sum(SumExpression { sum }) = &query.expression else {
+            panic!("sum expression expected, got {:?}", query.expression);
+        };
+        assert_eq!(sum[0], Expression::Variable("$score".to_string()));
+        let Expression::Mult(MultExpression { mult }) = &sum[1] else {
+            panic!("mult expression expected, got {:?}", sum[1]);
+        };
+        assert!(matches!(mult[1], Expression::Condition(_)));
+        assert!(matches!(sum[2], Expression::ExpDecay(_)));
+    }
+
+    #[test]
+    fn test_evaluate_arithmetic() {
+        let payload = json!({"price": 20, "rating": [4.5, 3.0], "tag": "sale"});
+
+        let formula = json!({"expression": {"sum": [
+            {"mult": ["$score", 2.0]},
+            {"div": {"left": "rating", "right": 10}},
+            {"mult": [0.25, {"key": "tag", "match": {"value": "sale"}}]},
+        ]}});
+        let score = evaluate(formula, &[Some(0.5)], payload.clone()).unwrap();
+        assert!((score - 1.7).abs() < 1e-6);
+
+        // Scores of the other prefetches and defaults of missing values
+        let formula = json!({
+            "expression": {"sum": ["$score[1]", "discount"]},
+            "defaults": {"discount": 0.1, "$score[1]": 0.0},
+        });
+        let score = evaluate(formula.clone(), &[Some(0.5), Some(0.3)], payload.clone()).unwrap();
+        assert!((score - 0.4).abs() < 1e-6);
+        let score = evaluate(formula, &[Some(0.5), None], payload.clone()).unwrap();
+        assert!((score - 0.1).abs() < 1e-6);
+
+        // Missing values without defaults and division by zero fail the query
+        let formula = json!({"expression": {"mult": ["$score", "discount"]}});
+        assert!(evaluate(formula, &[Some(0.5)], payload.clone()).is_err());
+        let formula = json!({"expression": {"div": {"left": 1, "right": {"neg": 0}}}});
+        assert!(evaluate(formula, &[], payload.clone()).is_err());
+        let formula = json!({"expression": {"div": {"left": 1, "right": 0, "by_zero_default": 5}}});
+        assert_eq!(evaluate(formula, &[], payload.clone()).unwrap(), 5.0);
+        let formula = json!({"expression": {"ln": {"neg": "price"}}});
+        assert!(evaluate(formula, &[], payload).is_err());
+    }
+
+    #[test]
+    fn test_formula_terms() {
+        let record = record(json!({"price": 20}));
+        let point = FormulaPoint {
+            scores: &[Some(0.5)],
+            record: &record,
+        };
+
+        let query: FormulaQuery = serde_json::from_value(
+            json!({"expression": {"sum": ["$score", {"mult": [0.5, "price"]}]}}),
+        )
+        .unwrap();
+        assert_eq!(query.terms(&point).unwrap(), [0.5, 10.0]);
+
+        let query: FormulaQuery =
+            serde_json::from_value(json!({"expression": {"neg": "price"}})).unwrap();
+        assert_eq!(query.terms(&point).unwrap(), [-20.0]);
+    }
+
+    #[test]
+    fn test_evaluate_decay() {
+        let payload = json!({
+            "updated": "2024-01-02T00:00:00Z",
+            "location": {"lon": 13.4050, "lat": 52.5200},
+        });
+
+        // One day from the target is at the midpoint of the decay with one day scale
+        for decay in ["exp_decay", "gauss_decay", "lin_decay"] {
+            let formula = json!({"expression": {decay: {
+                "x": {"datetime_key": "updated"},
+                "target": {"datetime": "2024-01-01T00:00:00Z"},
+                "scale": 86400,
+                "midpoint": 0.2,
+            }}});
+            let score = evaluate(formula, &[], payload.clone()).unwrap();
+            assert!((score - 0.2).abs() < 1e-6, "{decay}: {score}");
+        }
+
+        let formula = json!({"expression": {"lin_decay": {"x": 10, "scale": 2}}});
+        assert_eq!(evaluate(formula, &[], payload.clone()).unwrap(), 0.0);
+
+        let formula = json!({"expression": {"geo_distance": {
+            "origin": {"lon": 13.3777, "lat": 52.5163},
+            "to": "location",
+        }}});
+        let distance = evaluate(formula, &[], payload).unwrap();
+        assert!((1800.0..2000.0).contains(&distance), "{distance}");
+    }
+
+    #[test]
+    fn test_validate_formula() {
+        let invalid = [
+            json!({"expression": {"sum": ["$scores"]}}),
+            json!({"expression": {"sum": ["$score[x]"]}}),
+            json!({"expression": {"datetime": "yesterday"}}),
+            json!({"expression": {"exp_decay": {"x": "$score", "scale": 0}}}),
+            json!({"expression": {"gauss_decay": {"x": "$score", "midpoint": 1}}}),
+        ];
+        for formula in invalid {
+            let query: FormulaQuery = serde_json::from_value(formula.clone()).unwrap();
+            assert!(query.validate().is_err(), "{formula}");
+        }
+    }
+}
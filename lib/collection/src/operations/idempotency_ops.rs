@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use super::CollectionUpdateOperations;
+
+/// Update operation with the idempotency token of the client request.
+///
+/// A shard applies operations with the same token only once within the retention window,
+/// so a retry of a request, which might have been applied already, doesn't apply it twice.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct IdempotentOperation {
+    pub idempotency_token: String,
+    pub operation: Box<CollectionUpdateOperations>,
+}
+
+impl IdempotentOperation {
+    pub fn new(idempotency_token: String, operation: CollectionUpdateOperations) -> Self {
+        Self {
+            idempotency_token,
+            operation: Box::new(operation),
+        }
+    }
+}
+
+impl CollectionUpdateOperations {
+    /// Wrap the operation with the idempotency token of the request, if there is one
+    pub fn with_idempotency_token(self, idempotency_token: Option<String>) -> Self {
+        match idempotency_token {
+            None => self,
+            Some(idempotency_token) => CollectionUpdateOperations::IdempotentOperation(
+                IdempotentOperation::new(idempotency_token, self),
+            ),
+        }
+    }
+
+    /// Take the idempotency token off the operation, if it has one
+    pub fn into_idempotency_token(self) -> (Option<String>, Self) {
+        match self {
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => {
+                (Some(idempotent.idempotency_token), *idempotent.operation)
+            }
+            operation => (None, operation),
+        }
+    }
+
+    /// Operation without the idempotency token
+    pub fn without_idempotency_token(&self) -> &Self {
+        match self {
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => &idempotent.operation,
+            operation => operation,
+        }
+    }
+}
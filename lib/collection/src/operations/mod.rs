@@ -1,7 +1,11 @@
+pub mod change_ops;
+pub mod clock_ops;
 pub mod cluster_ops;
 pub mod config_diff;
 pub mod consistency_params;
 pub mod conversions;
+pub mod formula;
+pub mod idempotency_ops;
 pub mod operation_effect;
 pub mod payload_ops;
 pub mod point_ops;
@@ -44,6 +48,12 @@ pub enum CollectionUpdateOperations {
     VectorOperation(vector_ops::VectorOperations),
     PayloadOperation(payload_ops::PayloadOps),
     FieldIndexOperation(FieldIndexOperations),
+    /// Operations applied in order, as a single update of the shard
+    BatchOperation(Vec<CollectionUpdateOperations>),
+    /// Operation with the logical clock, assigned by the replica set
+    ClockedOperation(clock_ops::ClockedOperation),
+    /// Operation, which is applied only once for the idempotency token of the request
+    IdempotentOperation(idempotency_ops::IdempotentOperation),
 }
 
 /// A mapping of operation to shard.
@@ -104,6 +114,13 @@ impl Validate for CollectionUpdateOperations {
             CollectionUpdateOperations::VectorOperation(operation) => operation.validate(),
             CollectionUpdateOperations::PayloadOperation(operation) => operation.validate(),
             CollectionUpdateOperations::FieldIndexOperation(operation) => operation.validate(),
+            CollectionUpdateOperations::BatchOperation(operations) => operations
+                .iter()
+                .try_for_each(|operation| operation.validate()),
+            CollectionUpdateOperations::ClockedOperation(clocked) => clocked.operation.validate(),
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => {
+                idempotent.operation.validate()
+            }
         }
     }
 }
@@ -154,6 +171,27 @@ impl SplitByShard for CollectionUpdateOperations {
             operation @ CollectionUpdateOperations::FieldIndexOperation(_) => {
                 OperationToShard::to_all(operation)
             }
+            operation @ CollectionUpdateOperations::BatchOperation(_) => {
+                // Operations of the batch might be sent to all shards, which are unknown here
+                debug_assert!(
+                    false,
+                    "Batch operation should be split by `ShardHolder::split_batch_by_shard`"
+                );
+                OperationToShard::to_all(operation)
+            }
+            operation @ CollectionUpdateOperations::ClockedOperation(_) => {
+                // Clocks are assigned by the replica set of a shard, after the operation is split
+                debug_assert!(false, "Clocked operation can't be split by shard");
+                OperationToShard::to_all(operation)
+            }
+            operation @ CollectionUpdateOperations::IdempotentOperation(_) => {
+                // Each part of the operation keeps the token, see `Collection::update_from_client`
+                debug_assert!(
+                    false,
+                    "Idempotent operation should be split without the token"
+                );
+                OperationToShard::to_all(operation)
+            }
         }
     }
 }
@@ -171,6 +209,95 @@ impl CollectionUpdateOperations {
             CollectionUpdateOperations::FieldIndexOperation(operation) => {
                 operation.is_write_operation()
             }
+            CollectionUpdateOperations::BatchOperation(operations) => operations
+                .iter()
+                .any(CollectionUpdateOperations::is_write_operation),
+            CollectionUpdateOperations::ClockedOperation(clocked) => {
+                clocked.operation.is_write_operation()
+            }
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => {
+                idempotent.operation.is_write_operation()
+            }
+        }
+    }
+
+    /// Whether the operation contains an upsert with a precondition on the stored point versions
+    pub fn is_conditional(&self) -> bool {
+        match self {
+            CollectionUpdateOperations::PointOperation(operation) => matches!(
+                operation,
+                point_ops::PointOperations::UpsertPointsConditional(_)
+            ),
+            CollectionUpdateOperations::VectorOperation(_)
+            | CollectionUpdateOperations::PayloadOperation(_)
+            | CollectionUpdateOperations::FieldIndexOperation(_) => false,
+            CollectionUpdateOperations::BatchOperation(operations) => operations
+                .iter()
+                .any(CollectionUpdateOperations::is_conditional),
+            CollectionUpdateOperations::ClockedOperation(clocked) => {
+                clocked.operation.is_conditional()
+            }
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => {
+                idempotent.operation.is_conditional()
+            }
+        }
+    }
+
+    /// Take preconditions out of the conditional upserts of the operation.
+    /// Returns IDs of the upserted points together with the conditions,
+    /// and the same operation with plain upserts.
+    pub fn take_upsert_conditions(
+        self,
+    ) -> (
+        Vec<(Vec<ExtendedPointId>, point_ops::UpsertCondition)>,
+        Self,
+    ) {
+        let mut conditions = Vec::new();
+        let operation = self.take_upsert_conditions_into(&mut conditions);
+        (conditions, operation)
+    }
+
+    fn take_upsert_conditions_into(
+        self,
+        conditions: &mut Vec<(Vec<ExtendedPointId>, point_ops::UpsertCondition)>,
+    ) -> Self {
+        match self {
+            CollectionUpdateOperations::PointOperation(
+                point_ops::PointOperations::UpsertPointsConditional(operation),
+            ) => {
+                let points = operation.points.into_point_vec();
+                conditions.push((
+                    points.iter().map(|point| point.id).collect(),
+                    operation.condition,
+                ));
+                CollectionUpdateOperations::PointOperation(
+                    point_ops::PointOperations::UpsertPoints(
+                        point_ops::PointInsertOperations::PointsList(points),
+                    ),
+                )
+            }
+            operation @ (CollectionUpdateOperations::PointOperation(_)
+            | CollectionUpdateOperations::VectorOperation(_)
+            | CollectionUpdateOperations::PayloadOperation(_)
+            | CollectionUpdateOperations::FieldIndexOperation(_)) => operation,
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                CollectionUpdateOperations::BatchOperation(
+                    operations
+                        .into_iter()
+                        .map(|operation| operation.take_upsert_conditions_into(conditions))
+                        .collect(),
+                )
+            }
+            CollectionUpdateOperations::ClockedOperation(mut clocked) => {
+                clocked.operation =
+                    Box::new(clocked.operation.take_upsert_conditions_into(conditions));
+                CollectionUpdateOperations::ClockedOperation(clocked)
+            }
+            CollectionUpdateOperations::IdempotentOperation(mut idempotent) => {
+                idempotent.operation =
+                    Box::new(idempotent.operation.take_upsert_conditions_into(conditions));
+                CollectionUpdateOperations::IdempotentOperation(idempotent)
+            }
         }
     }
 }
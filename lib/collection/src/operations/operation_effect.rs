@@ -1,4 +1,6 @@
-use segment::types::{Filter, PointIdType};
+use std::collections::HashSet;
+
+use segment::types::{Condition, Filter, HasIdCondition, PointIdType};
 
 use super::vector_ops;
 use crate::operations::payload_ops::PayloadOps;
@@ -38,6 +40,41 @@ impl EstimateOperationEffectArea for CollectionUpdateOperations {
                 payload_operation.estimate_effect_area()
             }
             CollectionUpdateOperations::FieldIndexOperation(_) => OperationEffectArea::Empty,
+            CollectionUpdateOperations::ClockedOperation(clocked) => {
+                clocked.operation.estimate_effect_area()
+            }
+            CollectionUpdateOperations::IdempotentOperation(idempotent) => {
+                idempotent.operation.estimate_effect_area()
+            }
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                let mut points = Vec::new();
+                let mut filters = Vec::new();
+                for operation in operations {
+                    match operation.estimate_effect_area() {
+                        OperationEffectArea::Empty => {}
+                        OperationEffectArea::Points(ids) => points.extend(ids),
+                        OperationEffectArea::Filter(filter) => filters.push(filter),
+                    }
+                }
+                if filters.is_empty() {
+                    return if points.is_empty() {
+                        OperationEffectArea::Empty
+                    } else {
+                        OperationEffectArea::Points(points)
+                    };
+                }
+                // Union of the areas of all operations
+                let mut should: Vec<_> = filters.into_iter().map(Condition::Filter).collect();
+                if !points.is_empty() {
+                    should.push(Condition::HasId(HasIdCondition::from(
+                        points.into_iter().collect::<HashSet<_>>(),
+                    )));
+                }
+                OperationEffectArea::Filter(Filter {
+                    should: Some(should),
+                    ..Default::default()
+                })
+            }
         }
     }
 }
@@ -48,6 +85,9 @@ impl EstimateOperationEffectArea for point_ops::PointOperations {
             point_ops::PointOperations::UpsertPoints(insert_operations) => {
                 insert_operations.estimate_effect_area()
             }
+            point_ops::PointOperations::UpsertPointsConditional(operation) => {
+                operation.points.estimate_effect_area()
+            }
             point_ops::PointOperations::DeletePoints { ids } => {
                 OperationEffectArea::Points(ids.clone())
             }
@@ -118,7 +158,10 @@ impl EstimateOperationEffectArea for PayloadOps {
             }
             PayloadOps::ClearPayload { points } => OperationEffectArea::Points(points.clone()),
             PayloadOps::ClearPayloadByFilter(filter) => OperationEffectArea::Filter(filter.clone()),
-            PayloadOps::OverwritePayload(set_payload) => {
+            PayloadOps::MutatePayload(mutate_payload) => {
+                OperationEffectArea::Filter(mutate_payload.filter.clone())
+            }
+            PayloadOps::OverwritePayload(set_payload) | PayloadOps::MergePayload(set_payload) => {
                 if let Some(points) = &set_payload.points {
                     OperationEffectArea::Points(points.clone())
                 } else if let Some(filter) = &set_payload.filter {
@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use segment::types::{Filter, Payload, PayloadKeyType, PointIdType};
 use serde;
 use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
 use validator::Validate;
 
 use super::{split_iter_by_shard, OperationToShard, SplitByShard};
@@ -55,7 +56,8 @@ impl TryFrom<SetPayloadShadow> for SetPayload {
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(try_from = "DeletePayloadShadow")]
 pub struct DeletePayload {
-    /// List of payload keys to remove from payload
+    /// List of payload keys to remove from payload.
+    /// `*` in a key matches all keys of an object, e.g. `metadata.tmp.*`
     pub keys: Vec<PayloadKeyType>,
     /// Deletes values from each point in this list
     pub points: Option<Vec<PointIdType>>,
@@ -100,6 +102,79 @@ pub enum PayloadOps {
     ClearPayloadByFilter(Filter),
     /// Overwrite full payload with given keys
     OverwritePayload(SetPayload),
+    /// Merge payload values into nested objects of the existing payload, as JSON merge patch
+    MergePayload(SetPayload),
+    /// Apply mutations to payload of each point, which matches the filter
+    MutatePayload(MutatePayload),
+}
+
+/// Change of a top-level payload value, computed from the current payload of the point
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PayloadMutation {
+    /// Set the key to a constant value
+    Set { key: PayloadKeyType, value: Value },
+    /// Add the number to the numeric value of the key, a missing key counts as zero.
+    /// Non-numeric values are left as they are.
+    Increment { key: PayloadKeyType, by: f64 },
+    /// Copy the value of one key into another, nothing is copied if the source key is missing
+    Copy {
+        from: PayloadKeyType,
+        to: PayloadKeyType,
+    },
+    /// Remove the key
+    Remove { key: PayloadKeyType },
+}
+
+impl PayloadMutation {
+    pub fn apply(&self, payload: &mut Payload) {
+        let values = &mut payload.0;
+        match self {
+            PayloadMutation::Set { key, value } => {
+                values.insert(key.clone(), value.clone());
+            }
+            PayloadMutation::Increment { key, by } => {
+                let incremented = match values.get(key) {
+                    None => increment_number(&Number::from(0), *by),
+                    Some(Value::Number(number)) => increment_number(number, *by),
+                    Some(_) => None,
+                };
+                if let Some(number) = incremented {
+                    values.insert(key.clone(), Value::Number(number));
+                }
+            }
+            PayloadMutation::Copy { from, to } => {
+                if let Some(value) = values.get(from).cloned() {
+                    values.insert(to.clone(), value);
+                }
+            }
+            PayloadMutation::Remove { key } => {
+                values.remove(key);
+            }
+        }
+    }
+}
+
+/// Integers stay integers, if incremented by a whole number without overflow
+fn increment_number(number: &Number, by: f64) -> Option<Number> {
+    if let Some(integer) = number.as_i64() {
+        if by.fract() == 0.0 && by.abs() < i64::MAX as f64 {
+            if let Some(sum) = integer.checked_add(by as i64) {
+                return Some(Number::from(sum));
+            }
+        }
+    }
+    Number::from_f64(number.as_f64()? + by)
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct MutatePayload {
+    /// Mutations, applied in order to payload of each point
+    #[validate(length(min = 1))]
+    pub mutations: Vec<PayloadMutation>,
+    /// Mutate payload of points, which satisfy this filter condition
+    pub filter: Filter,
 }
 
 impl PayloadOps {
@@ -110,6 +185,8 @@ impl PayloadOps {
             PayloadOps::ClearPayload { .. } => false,
             PayloadOps::ClearPayloadByFilter(_) => false,
             PayloadOps::OverwritePayload(_) => true,
+            PayloadOps::MergePayload(_) => true,
+            PayloadOps::MutatePayload(_) => true,
         }
     }
 }
@@ -122,6 +199,8 @@ impl Validate for PayloadOps {
             PayloadOps::ClearPayload { .. } => Ok(()),
             PayloadOps::ClearPayloadByFilter(_) => Ok(()),
             PayloadOps::OverwritePayload(operation) => operation.validate(),
+            PayloadOps::MergePayload(operation) => operation.validate(),
+            PayloadOps::MutatePayload(operation) => operation.validate(),
         }
     }
 }
@@ -141,6 +220,10 @@ impl SplitByShard for PayloadOps {
             PayloadOps::OverwritePayload(operation) => operation
                 .split_by_shard(ring)
                 .map(PayloadOps::OverwritePayload),
+            PayloadOps::MergePayload(operation) => {
+                operation.split_by_shard(ring).map(PayloadOps::MergePayload)
+            }
+            operation @ PayloadOps::MutatePayload(_) => OperationToShard::to_all(operation),
         }
     }
 }
@@ -251,4 +334,33 @@ mod tests {
             _ => panic!("Wrong operation"),
         }
     }
+
+    #[test]
+    fn test_payload_mutations() {
+        let mut payload: Payload =
+            serde_json::from_str(r#"{"views": 10, "score": 1.5, "name": "a", "tag": "old"}"#)
+                .unwrap();
+
+        let mutations: Vec<PayloadMutation> = serde_json::from_str(
+            r#"[
+                {"increment": {"key": "views", "by": 1}},
+                {"increment": {"key": "score", "by": 0.5}},
+                {"increment": {"key": "name", "by": 1}},
+                {"increment": {"key": "missing", "by": 2}},
+                {"copy": {"from": "name", "to": "title"}},
+                {"set": {"key": "tag", "value": ["new"]}},
+                {"remove": {"key": "name"}}
+            ]"#,
+        )
+        .unwrap();
+        for mutation in &mutations {
+            mutation.apply(&mut payload);
+        }
+
+        let expected: Payload = serde_json::from_str(
+            r#"{"views": 11, "score": 2.0, "missing": 2, "title": "a", "tag": ["new"]}"#,
+        )
+        .unwrap();
+        assert_eq!(payload, expected);
+    }
 }
@@ -8,13 +8,14 @@ use schemars::JsonSchema;
 use segment::common::utils::transpose_map_into_named_vector;
 use segment::data_types::named_vectors::NamedVectors;
 use segment::data_types::vectors::{only_default_vector, BatchVectorStruct, VectorStruct};
-use segment::types::{Filter, Payload, PointIdType};
+use segment::types::{Filter, Payload, PointIdType, SeqNumberType};
 use serde::{Deserialize, Serialize};
+use uuid::{Builder, Uuid};
 use validator::Validate;
 
 use super::{point_to_shard, split_iter_by_shard, OperationToShard, SplitByShard};
 use crate::hash_ring::HashRing;
-use crate::operations::types::Record;
+use crate::operations::types::{CollectionError, CollectionResult, Record};
 use crate::shards::shard::ShardId;
 
 /// Defines write ordering guarantees for collection operations
@@ -55,6 +56,7 @@ impl TryFrom<Record> for PointStruct {
             id,
             payload,
             vector,
+            order_value: _,
         } = record;
 
         if vector.is_none() {
@@ -267,6 +269,36 @@ impl Validate for PointInsertOperations {
     }
 }
 
+impl PointInsertOperations {
+    /// Points of the operation, in the same order
+    pub fn into_point_vec(self) -> Vec<PointStruct> {
+        match self {
+            PointInsertOperations::PointsBatch(batch) => {
+                let all_vectors = batch.vectors.into_all_vectors(batch.ids.len());
+                let vectors_iter = batch.ids.into_iter().zip(all_vectors);
+                match batch.payloads {
+                    None => vectors_iter
+                        .map(|(id, vectors)| PointStruct {
+                            id,
+                            vector: vectors.into(),
+                            payload: None,
+                        })
+                        .collect(),
+                    Some(payloads) => vectors_iter
+                        .zip(payloads)
+                        .map(|((id, vectors), payload)| PointStruct {
+                            id,
+                            vector: vectors.into(),
+                            payload,
+                        })
+                        .collect(),
+                }
+            }
+            PointInsertOperations::PointsList(points) => points,
+        }
+    }
+}
+
 impl SplitByShard for PointInsertOperations {
     fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
         match self {
@@ -292,11 +324,113 @@ impl From<Vec<PointStruct>> for PointInsertOperations {
     }
 }
 
+/// Point to insert, which ID is assigned by the server
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct PointWithoutId {
+    /// Vectors
+    #[serde(alias = "vectors")]
+    pub vector: VectorStruct,
+    /// Payload values (optional)
+    pub payload: Option<Payload>,
+}
+
+/// Points to insert, which IDs are assigned by the server
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct PointsAutoId {
+    pub points: Vec<PointWithoutId>,
+    /// Retries of the request with the same token are assigned the same IDs,
+    /// so the points are overwritten instead of being duplicated.
+    /// Random IDs are assigned if not specified.
+    pub idempotency_token: Option<String>,
+}
+
+impl PointsAutoId {
+    /// Assign IDs to the points, also returns the assigned IDs in the order of the points
+    pub fn into_points(self) -> (Vec<PointIdType>, Vec<PointStruct>) {
+        let ids = generate_point_ids(self.points.len(), self.idempotency_token.as_deref());
+        let points = ids
+            .iter()
+            .zip(self.points)
+            .map(|(id, point)| PointStruct {
+                id: *id,
+                vector: point.vector,
+                payload: point.payload,
+            })
+            .collect();
+        (ids, points)
+    }
+}
+
+/// Generate UUIDs for `count` points inserted without IDs.
+///
+/// With an idempotency token, IDs are derived from the token and the position of the point.
+pub fn generate_point_ids(count: usize, idempotency_token: Option<&str>) -> Vec<PointIdType> {
+    (0..count)
+        .map(|position| {
+            let uuid = match idempotency_token {
+                None => Uuid::new_v4(),
+                Some(token) => {
+                    let key = format!("{token}/{position}");
+                    let high = seahash::hash_seeded(key.as_bytes(), 1, 2, 3, 4);
+                    let low = seahash::hash_seeded(key.as_bytes(), 5, 6, 7, 8);
+                    let mut bytes = [0; 16];
+                    bytes[..8].copy_from_slice(&high.to_be_bytes());
+                    bytes[8..].copy_from_slice(&low.to_be_bytes());
+                    Builder::from_random_bytes(bytes).into_uuid()
+                }
+            };
+            PointIdType::Uuid(uuid)
+        })
+        .collect()
+}
+
+/// Precondition on the stored versions of the upserted points.
+///
+/// The condition is checked for all points before any of them is written. As each shard checks
+/// the condition on its own, all points of a conditional upsert must belong to the same shard.
+/// The shard checks the condition once, on the update leader of `strong` write ordering,
+/// against the versions of its replica. Other replicas apply the upsert unconditionally.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum UpsertCondition {
+    /// All points must be stored with exactly this version
+    IfVersion(SeqNumberType),
+    /// None of the points must be stored
+    IfAbsent,
+}
+
+impl UpsertCondition {
+    /// Condition from the `if_version` and `if_absent` parameters of the request
+    pub fn from_params(
+        if_version: Option<SeqNumberType>,
+        if_absent: Option<bool>,
+    ) -> CollectionResult<Option<Self>> {
+        match (if_version, if_absent.unwrap_or(false)) {
+            (Some(_), true) => Err(CollectionError::bad_request(
+                "Only one of `if_version` and `if_absent` can be specified".to_string(),
+            )),
+            (Some(version), false) => Ok(Some(UpsertCondition::IfVersion(version))),
+            (None, true) => Ok(Some(UpsertCondition::IfAbsent)),
+            (None, false) => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ConditionalInsertOperation {
+    pub points: PointInsertOperations,
+    pub condition: UpsertCondition,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum PointOperations {
     /// Insert or update points
     UpsertPoints(PointInsertOperations),
+    /// Insert or update points, only if all of them match the condition
+    UpsertPointsConditional(ConditionalInsertOperation),
     /// Delete point if exists
     DeletePoints { ids: Vec<PointIdType> },
     /// Delete points by given filter criteria
@@ -309,6 +443,7 @@ impl PointOperations {
     pub fn is_write_operation(&self) -> bool {
         match self {
             PointOperations::UpsertPoints(_) => true,
+            PointOperations::UpsertPointsConditional(_) => true,
             PointOperations::DeletePoints { .. } => false,
             PointOperations::DeletePointsByFilter(_) => false,
             PointOperations::SyncPoints(_) => true,
@@ -320,6 +455,7 @@ impl Validate for PointOperations {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
             PointOperations::UpsertPoints(upsert_points) => upsert_points.validate(),
+            PointOperations::UpsertPointsConditional(operation) => operation.points.validate(),
             PointOperations::DeletePoints { ids: _ } => Ok(()),
             PointOperations::DeletePointsByFilter(_) => Ok(()),
             PointOperations::SyncPoints(_) => Ok(()),
@@ -431,6 +567,15 @@ impl SplitByShard for PointOperations {
             PointOperations::UpsertPoints(upsert_points) => upsert_points
                 .split_by_shard(ring)
                 .map(PointOperations::UpsertPoints),
+            PointOperations::UpsertPointsConditional(operation) => {
+                let condition = operation.condition;
+                operation.points.split_by_shard(ring).map(|points| {
+                    PointOperations::UpsertPointsConditional(ConditionalInsertOperation {
+                        points,
+                        condition,
+                    })
+                })
+            }
             PointOperations::DeletePoints { ids } => split_iter_by_shard(ids, |id| *id, ring)
                 .map(|ids| PointOperations::DeletePoints { ids }),
             by_filter @ PointOperations::DeletePointsByFilter(_) => {
@@ -469,6 +614,8 @@ impl PointStruct {
 
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
+
     use super::*;
 
     #[test]
@@ -494,4 +641,14 @@ mod tests {
         });
         assert!(batch.validate().is_err());
     }
+
+    #[test]
+    fn test_generate_point_ids() {
+        let ids = generate_point_ids(3, Some("token"));
+        assert_eq!(ids, generate_point_ids(3, Some("token")));
+        assert_eq!(ids.iter().unique().count(), 3);
+        assert_ne!(ids, generate_point_ids(3, Some("other token")));
+
+        assert_ne!(generate_point_ids(3, None), generate_point_ids(3, None));
+    }
 }
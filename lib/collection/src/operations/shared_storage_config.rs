@@ -8,6 +8,8 @@ use crate::operations::types::NodeType;
 const DEFAULT_SEARCH_TIMEOUT: Duration = Duration::from_secs(60);
 const DEFAULT_UPDATE_QUEUE_SIZE: usize = 100;
 const DEFAULT_UPDATE_QUEUE_SIZE_LISTENER: usize = 10_000;
+const DEFAULT_IDEMPOTENCY_TOKEN_RETENTION: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_DELETED_POINT_CLOCKS_RETENTION: Duration = Duration::from_secs(24 * 60 * 60);
 
 /// Storage configuration shared between all collections.
 /// Represents a per-node configuration, which might be changes with restart.
@@ -21,6 +23,10 @@ pub struct SharedStorageConfig {
     pub search_timeout: Duration,
     pub update_concurrency: Option<NonZeroUsize>,
     pub is_distributed: bool,
+    /// How long shards remember idempotency tokens of the applied operations
+    pub idempotency_token_retention: Duration,
+    /// How long shards remember the clocks of deleted points, longer than any replica may lag
+    pub deleted_point_clocks_retention: Duration,
 }
 
 impl Default for SharedStorageConfig {
@@ -33,6 +39,8 @@ impl Default for SharedStorageConfig {
             search_timeout: DEFAULT_SEARCH_TIMEOUT,
             update_concurrency: None,
             is_distributed: false,
+            idempotency_token_retention: DEFAULT_IDEMPOTENCY_TOKEN_RETENTION,
+            deleted_point_clocks_retention: DEFAULT_DELETED_POINT_CLOCKS_RETENTION,
         }
     }
 }
@@ -46,6 +54,8 @@ impl SharedStorageConfig {
         search_timeout: Option<Duration>,
         update_concurrency: Option<NonZeroUsize>,
         is_distributed: bool,
+        idempotency_token_retention: Option<Duration>,
+        deleted_point_clocks_retention: Option<Duration>,
     ) -> Self {
         let update_queue_size = update_queue_size.unwrap_or(match node_type {
             NodeType::Normal => DEFAULT_UPDATE_QUEUE_SIZE,
@@ -59,6 +69,10 @@ impl SharedStorageConfig {
             search_timeout: search_timeout.unwrap_or(DEFAULT_SEARCH_TIMEOUT),
             update_concurrency,
             is_distributed,
+            idempotency_token_retention: idempotency_token_retention
+                .unwrap_or(DEFAULT_IDEMPOTENCY_TOKEN_RETENTION),
+            deleted_point_clocks_retention: deleted_point_clocks_retention
+                .unwrap_or(DEFAULT_DELETED_POINT_CLOCKS_RETENTION),
         }
     }
 }
@@ -1,14 +1,20 @@
+use std::fs::File;
+use std::io::BufReader;
 use std::path::{Path, PathBuf};
-use std::time::SystemTime;
+use std::time::{Instant, SystemTime};
 
 use api::grpc::conversions::date_time_to_proto;
 use chrono::NaiveDateTime;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use url::Url;
 use validator::Validate;
 
-use crate::operations::types::CollectionResult;
+use crate::collection::Collection;
+use crate::config::CollectionParams;
+use crate::operations::change_ops::ChangesOffset;
+use crate::operations::types::{CollectionError, CollectionResult};
 
 /// Defines source of truth for snapshot recovery:
 /// `NoSync` means - restore snapshot without *any* additional synchronization.
@@ -23,6 +29,10 @@ pub enum SnapshotPriority {
     Replica,
 }
 
+/// Snapshot to recover a collection from.
+///
+/// Collection snapshots don't store the name of the collection, so a snapshot can be recovered
+/// into a collection with any name.
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 pub struct SnapshotRecover {
     /// Examples:
@@ -35,6 +45,41 @@ pub struct SnapshotRecover {
     /// If set to `Replica`, the current state will be used as a source of truth, and after recovery if will be synchronized with the snapshot.
     #[serde(default)]
     pub priority: Option<SnapshotPriority>,
+
+    /// SHA256 checksum of the snapshot file. If set, the snapshot is only recovered if it matches.
+    #[serde(default)]
+    pub checksum: Option<String>,
+}
+
+/// Point in time to recover a collection to.
+///
+/// The collection is recovered from its snapshot on this peer, and the operations, which followed
+/// the snapshot, are replayed from the WAL, retained on this peer.
+/// Recovered shards become the source of truth for other replicas.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct PointInTimeRecover {
+    /// Name of the collection snapshot on this peer, see the recovery windows of the collection
+    pub snapshot: String,
+    /// Replay operations of each shard up to, but not including these operation numbers.
+    /// Shards, which are not listed, are recovered up to the latest operation.
+    #[serde(default)]
+    pub until_offset: Option<ChangesOffset>,
+    /// Replay operations, made up to and including this time (UTC), with millisecond precision
+    #[serde(default)]
+    pub until_time: Option<NaiveDateTime>,
+}
+
+/// States, a collection can be recovered to from a snapshot and the WAL retained after it
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct RecoveryWindow {
+    /// Name of the collection snapshot
+    pub snapshot: String,
+    /// The collection can be recovered to any time after the snapshot was created
+    pub creation_time: Option<NaiveDateTime>,
+    /// Number of the first operation after the snapshot in each shard
+    pub from_offset: ChangesOffset,
+    /// Number of the next operation in each shard, the shard can be recovered up to any operation before it
+    pub to_offset: ChangesOffset,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
@@ -42,6 +87,58 @@ pub struct SnapshotDescription {
     pub name: String,
     pub creation_time: Option<NaiveDateTime>,
     pub size: u64,
+    /// SHA256 checksum of the snapshot file, computed when the snapshot was created
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+    /// Version of Qdrant, which created the snapshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Time it took to create the snapshot, in seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creation_duration_sec: Option<f64>,
+    /// Parameters of the collection at the time of the snapshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collection_params: Option<CollectionParams>,
+    /// Whether the snapshot can be recovered by the running version of Qdrant.
+    /// Unknown for snapshots, which were not created on this peer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compatible: Option<bool>,
+}
+
+/// Details of the snapshot creation, stored next to the snapshot
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SnapshotMetadata {
+    version: String,
+    storage_version: String,
+    creation_duration_sec: f64,
+    collection_params: Option<CollectionParams>,
+}
+
+impl SnapshotMetadata {
+    pub fn new(started: Instant, collection_params: Option<CollectionParams>) -> Self {
+        Self {
+            version: api::grpc::api_crate_version().to_string(),
+            storage_version: Collection::storage_version(),
+            creation_duration_sec: started.elapsed().as_secs_f64(),
+            collection_params,
+        }
+    }
+
+    fn is_compatible(&self) -> bool {
+        self.storage_version
+            .parse()
+            .map_or(false, |version| Collection::is_storage_compatible(&version))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct SnapshotVerification {
+    /// SHA256 checksum of the snapshot file as it is now
+    pub checksum: String,
+    /// Checksum computed when the snapshot was created
+    pub expected_checksum: Option<String>,
+    /// Whether the snapshot file matches the checksum computed on its creation
+    pub valid: bool,
 }
 
 impl From<SnapshotDescription> for api::grpc::qdrant::SnapshotDescription {
@@ -50,10 +147,124 @@ impl From<SnapshotDescription> for api::grpc::qdrant::SnapshotDescription {
             name: value.name,
             creation_time: value.creation_time.map(date_time_to_proto),
             size: value.size as i64,
+            checksum: value.checksum,
+            version: value.version,
+            creation_duration_sec: value.creation_duration_sec,
+            compatible: value.compatible,
         }
     }
 }
 
+/// Path of the file with the checksum of the snapshot, stored next to it
+pub fn get_checksum_path(snapshot_path: &Path) -> PathBuf {
+    let mut checksum_path = snapshot_path.as_os_str().to_owned();
+    checksum_path.push(".checksum");
+    PathBuf::from(checksum_path)
+}
+
+/// SHA256 checksum of the file, as a hex string
+pub async fn hash_file(path: &Path) -> CollectionResult<String> {
+    let path = path.to_owned();
+    tokio::task::spawn_blocking(move || -> CollectionResult<String> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut reader, &mut hasher)?;
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await?
+}
+
+/// Compute the checksum of the created snapshot and store it next to the snapshot
+pub async fn create_snapshot_checksum(snapshot_path: &Path) -> CollectionResult<String> {
+    let checksum = hash_file(snapshot_path).await?;
+    tokio::fs::write(get_checksum_path(snapshot_path), &checksum).await?;
+    Ok(checksum)
+}
+
+/// Check that the snapshot file matches the expected checksum
+pub async fn verify_snapshot_checksum(
+    snapshot_path: &Path,
+    expected_checksum: &str,
+) -> CollectionResult<()> {
+    let checksum = hash_file(snapshot_path).await?;
+    if !checksum.eq_ignore_ascii_case(expected_checksum.trim()) {
+        return Err(CollectionError::bad_input(format!(
+            "Snapshot checksum mismatch: expected {expected_checksum}, got {checksum}"
+        )));
+    }
+    Ok(())
+}
+
+/// Compare the snapshot file with the checksum stored on its creation
+pub async fn verify_snapshot(snapshot_path: &Path) -> CollectionResult<SnapshotVerification> {
+    let checksum = hash_file(snapshot_path).await?;
+    let expected_checksum = read_snapshot_checksum(snapshot_path).await?;
+    let valid = expected_checksum.as_deref() == Some(checksum.as_str());
+    Ok(SnapshotVerification {
+        checksum,
+        expected_checksum,
+        valid,
+    })
+}
+
+/// Path of the file with the metadata of the snapshot, stored next to it
+pub fn get_metadata_path(snapshot_path: &Path) -> PathBuf {
+    let mut metadata_path = snapshot_path.as_os_str().to_owned();
+    metadata_path.push(".metadata.json");
+    PathBuf::from(metadata_path)
+}
+
+pub async fn save_snapshot_metadata(
+    snapshot_path: &Path,
+    metadata: &SnapshotMetadata,
+) -> CollectionResult<()> {
+    let metadata = serde_json::to_vec(metadata)?;
+    tokio::fs::write(get_metadata_path(snapshot_path), metadata).await?;
+    Ok(())
+}
+
+async fn read_snapshot_metadata(snapshot_path: &Path) -> Option<SnapshotMetadata> {
+    let metadata_path = get_metadata_path(snapshot_path);
+    if !metadata_path.exists() {
+        return None;
+    }
+    let metadata = tokio::fs::read(&metadata_path)
+        .await
+        .map_err(CollectionError::from)
+        .and_then(|metadata| Ok(serde_json::from_slice(&metadata)?));
+    metadata
+        .map_err(|err| {
+            log::warn!(
+                "Failed to read snapshot metadata from {}: {err}",
+                metadata_path.display()
+            )
+        })
+        .ok()
+}
+
+async fn read_snapshot_checksum(snapshot_path: &Path) -> CollectionResult<Option<String>> {
+    let checksum_path = get_checksum_path(snapshot_path);
+    if !checksum_path.exists() {
+        return Ok(None);
+    }
+    let checksum = tokio::fs::read_to_string(checksum_path).await?;
+    Ok(Some(checksum.trim().to_string()))
+}
+
+/// Remove the snapshot file along with its checksum and metadata
+pub async fn remove_snapshot(snapshot_path: &Path) -> CollectionResult<()> {
+    tokio::fs::remove_file(snapshot_path).await?;
+    for sidecar_path in [
+        get_checksum_path(snapshot_path),
+        get_metadata_path(snapshot_path),
+    ] {
+        if sidecar_path.exists() {
+            tokio::fs::remove_file(sidecar_path).await?;
+        }
+    }
+    Ok(())
+}
+
 pub async fn get_snapshot_description(path: &Path) -> CollectionResult<SnapshotDescription> {
     let name = path.file_name().unwrap().to_str().unwrap();
     let file_meta = tokio::fs::metadata(&path).await?;
@@ -66,10 +277,19 @@ pub async fn get_snapshot_description(path: &Path) -> CollectionResult<SnapshotD
             })
     });
     let size = file_meta.len();
+    let checksum = read_snapshot_checksum(path).await?;
+    let metadata = read_snapshot_metadata(path).await;
     Ok(SnapshotDescription {
         name: name.to_string(),
         creation_time,
         size,
+        checksum,
+        compatible: metadata.as_ref().map(SnapshotMetadata::is_compatible),
+        version: metadata.as_ref().map(|metadata| metadata.version.clone()),
+        creation_duration_sec: metadata
+            .as_ref()
+            .map(|metadata| metadata.creation_duration_sec),
+        collection_params: metadata.and_then(|metadata| metadata.collection_params),
     })
 }
 
@@ -96,6 +316,10 @@ pub struct ShardSnapshotRecover {
 
     #[serde(default)]
     pub priority: Option<SnapshotPriority>,
+
+    /// SHA256 checksum of the snapshot file. If set, the snapshot is only recovered if it matches.
+    #[serde(default)]
+    pub checksum: Option<String>,
 }
 
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize, schemars::JsonSchema)]
@@ -104,3 +328,94 @@ pub enum ShardSnapshotLocation {
     Url(Url),
     Path(PathBuf),
 }
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_snapshot_checksum() {
+        let dir = Builder::new().prefix("snapshots").tempdir().unwrap();
+        let snapshot_path = dir.path().join("test.snapshot");
+        std::fs::write(&snapshot_path, b"snapshot data").unwrap();
+
+        let checksum = create_snapshot_checksum(&snapshot_path).await.unwrap();
+        assert_eq!(checksum.len(), 64);
+        assert_eq!(
+            get_snapshot_description(&snapshot_path)
+                .await
+                .unwrap()
+                .checksum,
+            Some(checksum.clone())
+        );
+        assert!(verify_snapshot(&snapshot_path).await.unwrap().valid);
+        assert!(verify_snapshot_checksum(&snapshot_path, &checksum)
+            .await
+            .is_ok());
+
+        // Truncated snapshot
+        std::fs::write(&snapshot_path, b"snapshot").unwrap();
+        assert!(!verify_snapshot(&snapshot_path).await.unwrap().valid);
+        assert!(verify_snapshot_checksum(&snapshot_path, &checksum)
+            .await
+            .is_err());
+
+        let metadata = SnapshotMetadata::new(Instant::now(), None);
+        save_snapshot_metadata(&snapshot_path, &metadata)
+            .await
+            .unwrap();
+        let description = get_snapshot_description(&snapshot_path).await.unwrap();
+        assert_eq!(description.version, Some(metadata.version));
+        assert_eq!(description.compatible, Some(true));
+
+        // Checksum and metadata are not listed as snapshots and are removed along with the snapshot
+        assert_eq!(
+            list_snapshots_in_directory(dir.path()).await.unwrap().len(),
+            1
+        );
+        remove_snapshot(&snapshot_path).await.unwrap();
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_metadata() {
+        let dir = Builder::new().prefix("snapshots").tempdir().unwrap();
+        let snapshot_path = dir.path().join("test.snapshot");
+        std::fs::write(&snapshot_path, b"snapshot data").unwrap();
+
+        // Uploaded snapshots have no metadata
+        let description = get_snapshot_description(&snapshot_path).await.unwrap();
+        assert_eq!(description.version, None);
+        assert_eq!(description.creation_duration_sec, None);
+        assert_eq!(description.compatible, None);
+        assert!(description.collection_params.is_none());
+
+        let mut metadata = SnapshotMetadata::new(Instant::now(), None);
+        assert!(metadata.creation_duration_sec >= 0.0);
+        save_snapshot_metadata(&snapshot_path, &metadata)
+            .await
+            .unwrap();
+        let description = get_snapshot_description(&snapshot_path).await.unwrap();
+        assert_eq!(
+            description.creation_duration_sec,
+            Some(metadata.creation_duration_sec)
+        );
+        assert!(description.collection_params.is_none());
+
+        // Storage of a newer version can't be loaded
+        metadata.storage_version = "999.0.0".to_string();
+        save_snapshot_metadata(&snapshot_path, &metadata)
+            .await
+            .unwrap();
+        let description = get_snapshot_description(&snapshot_path).await.unwrap();
+        assert_eq!(description.compatible, Some(false));
+
+        // Unparsable metadata is ignored instead of failing the listing
+        std::fs::write(get_metadata_path(&snapshot_path), b"{ invalid").unwrap();
+        let description = get_snapshot_description(&snapshot_path).await.unwrap();
+        assert_eq!(description.version, None);
+        assert_eq!(description.compatible, None);
+    }
+}
@@ -1,28 +1,30 @@
 use std::backtrace::Backtrace;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::error::Error as _;
 use std::fmt::Write as _;
 use std::iter;
 use std::num::NonZeroU64;
-use std::time::SystemTimeError;
+use std::time::{Duration, SystemTimeError};
 
 use api::grpc::transport_channel_pool::RequestError;
 use common::types::ScoreType;
 use common::validation::validate_range_generic;
 use io::file_operations::FileStorageError;
-use itertools::Itertools;
 use merge::Merge;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use segment::common::operation_error::OperationError;
 use segment::data_types::groups::GroupId;
+use segment::data_types::order_by::{OrderBy, OrderValue};
 use segment::data_types::vectors::{
-    Named, NamedRecoQuery, NamedVectorStruct, QueryVector, VectorElementType, VectorStruct,
-    VectorType, DEFAULT_VECTOR_NAME,
+    Named, NamedContextQuery, NamedDiscoveryQuery, NamedRecoQuery, NamedSparseVector, NamedVector,
+    NamedVectorStruct, QueryVector, Vector, VectorElementType, VectorStruct, VectorType,
+    DEFAULT_VECTOR_NAME,
 };
 use segment::types::{
-    Distance, Filter, Payload, PayloadIndexInfo, PayloadKeyType, PointIdType, QuantizationConfig,
-    ScoredPoint, SearchParams, SeqNumberType, WithPayloadInterface, WithVector,
+    Distance, Filter, MultiVectorConfig, Payload, PayloadIndexInfo, PayloadKeyType, PointIdType,
+    QuantizationConfig, ScoredPoint, SearchParams, SegmentDetails, SeqNumberType,
+    VectorStorageDatatype, WithPayloadInterface, WithVector,
 };
 use serde;
 use serde::{Deserialize, Serialize};
@@ -35,9 +37,12 @@ use tonic::codegen::http::uri::InvalidUri;
 use validator::{Validate, ValidationError, ValidationErrors};
 
 use super::config_diff;
+use crate::collection_manager::holders::segment_holder::SegmentId;
+use crate::collection_manager::optimizers::{PendingOptimization, TrackerTelemetry};
 use crate::config::{CollectionConfig, CollectionParams};
 use crate::lookup::types::WithLookupInterface;
 use crate::operations::config_diff::{HnswConfigDiff, QuantizationConfigDiff};
+use crate::operations::formula::FormulaQuery;
 use crate::save_on_disk;
 use crate::shards::replica_set::ReplicaState;
 use crate::shards::shard::{PeerId, ShardId};
@@ -82,6 +87,9 @@ pub struct Record {
     pub payload: Option<Payload>,
     /// Vector of the point
     pub vector: Option<VectorStruct>,
+    /// Value of the `order_by` key of the point, if the points are ordered by a payload field
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order_value: Option<OrderValue>,
 }
 
 /// Current statistics and configuration of the collection
@@ -111,6 +119,41 @@ pub struct CollectionInfo {
     pub config: CollectionConfig,
     /// Types of stored payload
     pub payload_schema: HashMap<PayloadKeyType, PayloadIndexInfo>,
+    /// Optimizations of the shards, which are local to the peer, that answered the request
+    #[serde(default)]
+    pub optimizations: OptimizationsInfo,
+}
+
+/// Running, pending and failed optimizations of the collection shards
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct OptimizationsInfo {
+    /// Background optimizations are paused on any of the shards
+    #[serde(default)]
+    pub paused: bool,
+    /// Optimizations, which are running right now, with their progress
+    pub running: Vec<TrackerTelemetry>,
+    /// Optimizations, which are required by the optimizer conditions, but not launched yet
+    pub pending: Vec<PendingOptimization>,
+    /// Latest optimization, which failed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<TrackerTelemetry>,
+}
+
+impl OptimizationsInfo {
+    /// Combine optimizations of another shard into these ones
+    pub fn merge(&mut self, other: OptimizationsInfo) {
+        self.paused |= other.paused;
+        self.running.extend(other.running);
+        self.pending.extend(other.pending);
+        self.last_error = match (self.last_error.take(), other.last_error) {
+            (Some(this), Some(other)) => Some(if other.start_at > this.start_at {
+                other
+            } else {
+                this
+            }),
+            (this, other) => this.or(other),
+        };
+    }
 }
 
 /// Current clustering distribution for the collection
@@ -149,6 +192,46 @@ pub struct LocalShardInfo {
     pub state: ReplicaState,
 }
 
+/// Usage of the WAL of a local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardWalInfo {
+    /// Local shard id
+    pub shard_id: ShardId,
+    /// Number of the first operation, stored in WAL
+    pub first_stored_operation: SeqNumberType,
+    /// Number of the first operation, replayed from WAL on restart.
+    /// Operations before it are applied and flushed, they are kept within the retention limits
+    pub first_replayed_operation: SeqNumberType,
+    /// Number of the next operation
+    pub next_operation: SeqNumberType,
+    /// Number of applied operations, kept in WAL
+    pub retained_operations: u64,
+    /// Size of the WAL files, including segments created ahead
+    pub disk_size_bytes: u64,
+}
+
+/// Segments of a local shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardSegmentsInfo {
+    /// Local shard id
+    pub shard_id: ShardId,
+    pub segments: Vec<ShardSegmentInfo>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub struct ShardSegmentInfo {
+    /// Id of the segment within the shard, assigned anew on restart
+    pub id: SegmentId,
+    /// Name of the segment directory
+    pub name: String,
+    /// Segment is being optimized right now
+    pub under_optimization: bool,
+    pub details: SegmentDetails,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct RemoteShardInfo {
@@ -162,20 +245,26 @@ pub struct RemoteShardInfo {
 
 /// `Acknowledged` - Request is saved to WAL and will be process in a queue.
 /// `Completed` - Request is completed, changes are actual.
-#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, PartialEq, Eq, Clone, Copy)]
 #[serde(rename_all = "snake_case")]
 pub enum UpdateStatus {
     Acknowledged,
     Completed,
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct UpdateResult {
     /// Sequential number of the operation
     pub operation_id: SeqNumberType,
     /// Update status
     pub status: UpdateStatus,
+    /// Number of points affected by the operation, known only if waited for it to be applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub affected_points: Option<usize>,
+    /// IDs assigned by the server to the inserted points
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ids: Option<Vec<PointIdType>>,
 }
 
 /// Scroll request - paginate over all points which matches given condition
@@ -195,6 +284,20 @@ pub struct ScrollRequest {
     /// Whether to return the point vector with the result?
     #[serde(default, alias = "with_vectors")]
     pub with_vector: WithVector,
+    /// Order the points by values of a payload field instead of by ID.
+    /// `offset` can't be used along with it, use `start_from` of the ordering to read the next page.
+    #[validate]
+    pub order_by: Option<OrderBy>,
+    /// Scroll only the points, which exist at the first page, in their latest state.
+    /// Points inserted or deleted after it are skipped. Following pages are read with
+    /// `next_page_cursor` of the result from the same peer, it must have active replicas
+    /// of the scrolled shards.
+    /// Can't be used along with `order_by`.
+    pub stable: Option<bool>,
+    /// Cursor of the next page of a stable scroll.
+    /// Only `limit`, `with_payload` and `with_vector` are used along with it,
+    /// the same page is returned if the cursor is read again.
+    pub cursor: Option<String>,
 }
 
 impl Default for ScrollRequest {
@@ -205,6 +308,9 @@ impl Default for ScrollRequest {
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: WithVector::Bool(false),
+            order_by: None,
+            stable: None,
+            cursor: None,
         }
     }
 }
@@ -217,6 +323,9 @@ pub struct ScrollResult {
     pub points: Vec<Record>,
     /// Offset which should be used to retrieve a next page result
     pub next_page_offset: Option<PointIdType>,
+    /// Cursor of the next page of a stable scroll, if there are more points
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_page_cursor: Option<String>,
 }
 
 /// Search request.
@@ -225,8 +334,9 @@ pub struct ScrollResult {
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct SearchRequest {
-    /// Look for vectors closest to this
-    pub vector: NamedVectorStruct,
+    /// Look for vectors closest to this.
+    /// Might be the id of an existing point, then its stored vector is used.
+    pub vector: SearchVector,
     /// Look only for points which satisfies this conditions
     #[validate]
     pub filter: Option<Filter>,
@@ -252,6 +362,105 @@ pub struct SearchRequest {
     /// Score of the returned result might be higher or smaller than the threshold depending on the
     /// Distance function used. E.g. for cosine similarity only higher scores will be returned.
     pub score_threshold: Option<ScoreType>,
+    /// Also rank points by BM25 relevance to this text and fuse both rankings with
+    /// Reciprocal Rank Fusion. Scores of the result are fused scores then.
+    /// `score_threshold` is applied to vector similarity only.
+    #[validate]
+    pub text: Option<TextQuery>,
+}
+
+/// Stored vector of an existing point
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct PointVectorInput {
+    /// Id of the point to take the vector from
+    pub id: PointIdType,
+    /// Name of the vector to take and to search by. If missing, the default vector is used.
+    pub vector_name: Option<String>,
+}
+
+/// Vector to search with, given as is or by the id of the point which stores it
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(untagged)]
+pub enum SearchVector {
+    Vector(NamedVectorStruct),
+    Point(PointVectorInput),
+}
+
+impl SearchVector {
+    /// Vector given as is, vectors of points have to be resolved before the search
+    pub fn as_vector(&self) -> CollectionResult<&NamedVectorStruct> {
+        match self {
+            SearchVector::Vector(vector) => Ok(vector),
+            SearchVector::Point(point) => Err(Self::unresolved_error(point)),
+        }
+    }
+
+    pub fn into_vector(self) -> CollectionResult<NamedVectorStruct> {
+        match self {
+            SearchVector::Vector(vector) => Ok(vector),
+            SearchVector::Point(point) => Err(Self::unresolved_error(&point)),
+        }
+    }
+
+    fn unresolved_error(point: &PointVectorInput) -> CollectionError {
+        CollectionError::service_error(format!(
+            "Vector of point {} is not resolved before the search",
+            point.id
+        ))
+    }
+}
+
+impl Named for SearchVector {
+    fn get_name(&self) -> &str {
+        match self {
+            SearchVector::Vector(vector) => vector.get_name(),
+            SearchVector::Point(point) => {
+                point.vector_name.as_deref().unwrap_or(DEFAULT_VECTOR_NAME)
+            }
+        }
+    }
+}
+
+impl From<NamedVectorStruct> for SearchVector {
+    fn from(vector: NamedVectorStruct) -> Self {
+        SearchVector::Vector(vector)
+    }
+}
+
+impl From<VectorType> for SearchVector {
+    fn from(vector: VectorType) -> Self {
+        SearchVector::Vector(vector.into())
+    }
+}
+
+impl From<NamedVector> for SearchVector {
+    fn from(vector: NamedVector) -> Self {
+        SearchVector::Vector(vector.into())
+    }
+}
+
+impl From<NamedSparseVector> for SearchVector {
+    fn from(vector: NamedSparseVector) -> Self {
+        SearchVector::Vector(vector.into())
+    }
+}
+
+impl From<PointVectorInput> for SearchVector {
+    fn from(point: PointVectorInput) -> Self {
+        SearchVector::Point(point)
+    }
+}
+
+/// Query scored by BM25 relevance of the text to a payload field with a full-text index
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct TextQuery {
+    /// Payload field to score, must have a full-text index
+    #[validate(length(min = 1))]
+    pub key: PayloadKeyType,
+    /// Text to score points by
+    pub text: String,
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
@@ -261,17 +470,353 @@ pub struct SearchRequestBatch {
     pub searches: Vec<SearchRequest>,
 }
 
+/// Results of a search batch with a timeout
+#[derive(Debug, Clone, Default)]
+pub struct PartialSearchBatchResult {
+    pub results: Vec<Vec<ScoredPoint>>,
+    /// Some shards or segments were not searched in time, so their points are missing from the results
+    pub partial: bool,
+}
+
+/// Search request in the specified collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionSearchRequest {
+    /// Name of the collection to search in
+    #[validate(length(min = 1))]
+    pub collection_name: String,
+    #[serde(flatten)]
+    #[validate]
+    pub search: SearchRequest,
+}
+
+/// Batch of searches in multiple collections.
+/// Searches in each collection are batched together, collections are searched concurrently.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CollectionsSearchRequestBatch {
+    #[validate]
+    pub searches: Vec<CollectionSearchRequest>,
+}
+
+/// Universal query request.
+/// Results of the prefetches are either fused, or rescored by the query.
+/// Rescoring is exact, the candidates are scored with full precision vectors.
+/// Prefetches may have own prefetches, so multi-stage pipelines could be built.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct QueryRequest {
+    /// Sub-requests to perform first. If present, the query will be performed on the results of the prefetches.
+    #[serde(default)]
+    #[validate]
+    pub prefetch: Vec<Prefetch>,
+    /// Query to perform. If missing, the result of the single prefetch is returned as is.
+    #[validate]
+    pub query: Option<Query>,
+    /// Define which vector to use for querying. If missing, the default vector is used.
+    pub using: Option<String>,
+    /// The location used to lookup vectors of the examples given by point ids.
+    /// If not specified, the examples are looked up in the queried collection.
+    #[serde(default)]
+    pub lookup_from: Option<LookupLocation>,
+    /// Filter conditions - return only those points that satisfy the specified conditions.
+    /// Also applied to all of the prefetches.
+    #[validate]
+    pub filter: Option<Filter>,
+    /// Additional search params
+    #[validate]
+    pub params: Option<SearchParams>,
+    /// Return points with scores better than this threshold.
+    pub score_threshold: Option<ScoreType>,
+    /// Max number of points to return. Default is 10.
+    #[serde(default = "default_query_limit")]
+    #[validate(range(min = 1))]
+    pub limit: usize,
+    /// Offset of the result. Skip this many points. Default is 0
+    #[serde(default)]
+    pub offset: usize,
+    /// Diversify the results, so near-duplicates of the selected points are selected later
+    #[validate]
+    pub diversity: Option<Diversity>,
+    /// Select which payload to return with the response. Default: None
+    pub with_payload: Option<WithPayloadInterface>,
+    /// Whether to return the point vector with the result?
+    #[serde(default, alias = "with_vectors")]
+    pub with_vector: Option<WithVector>,
+    /// Return the breakdown of the score of every point: similarities with the original and
+    /// the quantized vectors, contributions of the fused prefetches and terms of the formula.
+    /// Default is false.
+    #[serde(default)]
+    pub with_explanation: bool,
+}
+
+/// Stage of the query, which selects candidates for the next stage
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct Prefetch {
+    /// Sub-requests to perform first. If present, the query will be performed on the results of the prefetches.
+    #[serde(default)]
+    #[validate]
+    pub prefetch: Vec<Prefetch>,
+    /// Query to perform. If missing, the result of the single prefetch is returned as is.
+    #[validate]
+    pub query: Option<Query>,
+    /// Define which vector to use for querying. If missing, the default vector is used.
+    pub using: Option<String>,
+    /// The location used to lookup vectors of the examples given by point ids.
+    /// If not specified, the examples are looked up in the queried collection.
+    #[serde(default)]
+    pub lookup_from: Option<LookupLocation>,
+    /// Filter conditions - return only those points that satisfy the specified conditions.
+    /// Also applied to the nested prefetches.
+    #[validate]
+    pub filter: Option<Filter>,
+    /// Additional search params
+    #[validate]
+    pub params: Option<SearchParams>,
+    /// Return points with scores better than this threshold.
+    pub score_threshold: Option<ScoreType>,
+    /// Max number of points to return from this stage. Default is 10.
+    #[serde(default = "default_query_limit")]
+    #[validate(range(min = 1))]
+    pub limit: usize,
+}
+
+pub const fn default_query_limit() -> usize {
+    10
+}
+
+/// Maximal Marginal Relevance diversification of the query results.
+///
+/// Points are selected one by one from the best candidates of the query.
+/// Each time, the selected point has the best `lambda * relevance - (1 - lambda) * similarity`,
+/// where relevance is the score normalized to `[0, 1]` over the candidates,
+/// and similarity is the highest cosine similarity of the `using` vectors to the points
+/// selected before.
+/// Scores of the results are not changed, they are returned in the order of selection.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct Diversity {
+    /// Trade-off between relevance and diversity:
+    /// 1 selects by relevance only, 0 selects by diversity only
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub lambda: f32,
+    /// Number of the best candidates to select the results from.
+    /// Default is 10 times the number of the requested points.
+    #[validate(range(min = 1))]
+    pub candidates_limit: Option<usize>,
+}
+
+impl Diversity {
+    const DEFAULT_CANDIDATES_FACTOR: usize = 10;
+
+    /// Number of candidates to select `limit` points from
+    pub fn candidates_limit(&self, limit: usize) -> usize {
+        self.candidates_limit
+            .unwrap_or(limit * Self::DEFAULT_CANDIDATES_FACTOR)
+            .max(limit)
+    }
+}
+
+/// Query of a single stage of the query request
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum Query {
+    /// Find the nearest neighbors of this vector
+    Nearest(Vector),
+    /// Find points closer to positive and further from negative examples,
+    /// same as `best_score` recommend strategy
+    Recommend(RecommendInput),
+    /// Find points in the positive zones of the context pairs, which are closest to the target
+    Discover(DiscoverInput),
+    /// Find points in the positive zones of as many context pairs as possible
+    Context(Vec<ContextExamplePair>),
+    /// Rank points by BM25 relevance of the text
+    Text(TextQuery),
+    /// Fuse the results of the prefetches
+    Fusion(Fusion),
+    /// Sample points matching the filter, scores of the sample are random
+    Sample(Sample),
+    /// Score the candidates of the prefetches by a formula over their scores and payload
+    Formula(FormulaQuery),
+    /// Score the candidates of the prefetches by the scoring plugin of the collection
+    Plugin(PluginQuery),
+}
+
+impl Validate for Query {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Query::Text(text) => text.validate(),
+            Query::Formula(formula) => formula.validate(),
+            Query::Plugin(plugin) => plugin.validate(),
+            Query::Nearest(_)
+            | Query::Recommend(_)
+            | Query::Discover(_)
+            | Query::Context(_)
+            | Query::Fusion(_)
+            | Query::Sample(_) => Ok(()),
+        }
+    }
+}
+
+impl Query {
+    /// Ids of the points, which are used as examples by the query
+    pub fn example_point_ids(&self) -> HashSet<PointIdType> {
+        let examples: Vec<&RecommendExample> = match self {
+            Query::Recommend(input) => input.positive.iter().chain(&input.negative).collect(),
+            Query::Discover(input) => iter::once(&input.target)
+                .chain(input.context.iter().flat_map(ContextExamplePair::iter))
+                .collect(),
+            Query::Context(pairs) => pairs.iter().flat_map(ContextExamplePair::iter).collect(),
+            Query::Nearest(_)
+            | Query::Text(_)
+            | Query::Fusion(_)
+            | Query::Sample(_)
+            | Query::Formula(_)
+            | Query::Plugin(_) => Vec::new(),
+        };
+        examples
+            .into_iter()
+            .filter_map(RecommendExample::as_point_id)
+            .collect()
+    }
+
+    /// Examples of the query, so the point ids could be replaced by vectors
+    pub fn examples_mut(&mut self) -> Vec<&mut RecommendExample> {
+        match self {
+            Query::Recommend(input) => input
+                .positive
+                .iter_mut()
+                .chain(&mut input.negative)
+                .collect(),
+            Query::Discover(input) => iter::once(&mut input.target)
+                .chain(
+                    input
+                        .context
+                        .iter_mut()
+                        .flat_map(ContextExamplePair::iter_mut),
+                )
+                .collect(),
+            Query::Context(pairs) => pairs
+                .iter_mut()
+                .flat_map(ContextExamplePair::iter_mut)
+                .collect(),
+            Query::Nearest(_)
+            | Query::Text(_)
+            | Query::Fusion(_)
+            | Query::Sample(_)
+            | Query::Formula(_)
+            | Query::Plugin(_) => Vec::new(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct RecommendInput {
+    /// Look for vectors closest to those
+    #[serde(default)]
+    pub positive: Vec<RecommendExample>,
+    /// Try to avoid vectors like this
+    #[serde(default)]
+    pub negative: Vec<RecommendExample>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct DiscoverInput {
+    /// Look for vectors closest to this
+    pub target: RecommendExample,
+    /// Pairs of {positive, negative} examples to constrain the search
+    #[serde(default)]
+    pub context: Vec<ContextExamplePair>,
+}
+
+/// Pair of examples, which splits the space into the positive and the negative zone:
+/// points closer to the positive example are in the positive zone
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct ContextExamplePair {
+    pub positive: RecommendExample,
+    pub negative: RecommendExample,
+}
+
+impl ContextExamplePair {
+    pub fn iter(&self) -> impl Iterator<Item = &RecommendExample> {
+        [&self.positive, &self.negative].into_iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut RecommendExample> {
+        [&mut self.positive, &mut self.negative].into_iter()
+    }
+}
+
+/// Fusion algorithm, which merges the rankings of the prefetches:
+///
+/// * `rrf` - Reciprocal Rank Fusion, a point scores `1 / (60 + rank)` in each ranking it appears in
+///
+/// * `relative_score` - scores of each ranking are normalized to `[0, 1]` by its best and worst
+///   scores, then summed up per point
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Fusion {
+    Rrf,
+    RelativeScore,
+}
+
+/// Sampling method:
+///
+/// * `random` - uniform random sample of the points. With a filter, the sample is uniform
+///   among the matching points of each segment, and approximately uniform among all of them.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Sample {
+    Random,
+}
+
+/// Query scored by the scoring plugin, configured in the params of the collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct PluginQuery {
+    /// Name of the scoring plugin of the collection
+    #[validate(length(min = 1))]
+    pub name: String,
+    /// Fail, if the plugin of the collection has another version
+    pub version: Option<u64>,
+}
+
 #[derive(Debug, Clone)]
 pub enum QueryEnum {
     Nearest(NamedVectorStruct),
     RecommendBestScore(NamedRecoQuery),
+    Discover(NamedDiscoveryQuery),
+    Context(NamedContextQuery),
+    Text(TextQuery),
+    Sample(Sample),
 }
 
 impl QueryEnum {
-    pub fn get_vector_name(&self) -> &str {
+    /// Name of the vector to search with, `None` for queries scored without vectors
+    pub fn get_vector_name(&self) -> Option<&str> {
+        match self {
+            QueryEnum::Nearest(vector) => Some(vector.get_name()),
+            QueryEnum::RecommendBestScore(reco_query) => Some(reco_query.get_name()),
+            QueryEnum::Discover(discovery_query) => Some(discovery_query.get_name()),
+            QueryEnum::Context(context_query) => Some(context_query.get_name()),
+            QueryEnum::Text(_) | QueryEnum::Sample(_) => None,
+        }
+    }
+
+    /// Whether the score is a similarity of the vector distance, so it has to be postprocessed
+    /// and ordered according to the distance.
+    /// Other scores are always larger for better points.
+    pub fn is_distance_scored(&self) -> bool {
         match self {
-            QueryEnum::Nearest(vector) => vector.get_name(),
-            QueryEnum::RecommendBestScore(reco_query) => reco_query.get_name(),
+            QueryEnum::Nearest(_) | QueryEnum::RecommendBestScore(_) => true,
+            QueryEnum::Discover(_)
+            | QueryEnum::Context(_)
+            | QueryEnum::Text(_)
+            | QueryEnum::Sample(_) => false,
         }
     }
 }
@@ -316,8 +861,9 @@ pub struct CoreSearchRequestBatch {
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 pub struct SearchGroupsRequest {
-    /// Look for vectors closest to this
-    pub vector: NamedVectorStruct,
+    /// Look for vectors closest to this.
+    /// Might be the id of an existing point, then its stored vector is used.
+    pub vector: SearchVector,
 
     /// Look only for points which satisfies this conditions
     #[validate]
@@ -345,7 +891,7 @@ pub struct SearchGroupsRequest {
     pub group_request: BaseGroupRequest,
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct PointRequest {
     /// Look for points with ids
@@ -484,7 +1030,7 @@ pub struct RecommendRequest {
     pub lookup_from: Option<LookupLocation>,
 }
 
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct RecommendRequestBatch {
     #[validate]
@@ -558,7 +1104,7 @@ pub struct GroupsResult {
 /// Count Request
 /// Counts the number of points which satisfy the given filter.
 /// If filter is not provided, the count of all points in the collection will be returned.
-#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 #[serde(rename_all = "snake_case")]
 pub struct CountRequest {
     /// Look only for points which satisfies this conditions
@@ -574,6 +1120,13 @@ pub const fn default_exact_count() -> bool {
     true
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct CountRequestBatch {
+    #[validate]
+    pub counts: Vec<CountRequest>,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 pub struct CountResult {
@@ -615,14 +1168,17 @@ pub enum CollectionError {
     OutOfMemory { description: String, free: u64 },
     #[error("Timeout error: {description}")]
     Timeout { description: String },
+    #[error("Precondition failed: {description}")]
+    PreconditionFailed { description: String },
 }
 
 impl CollectionError {
-    pub fn timeout(timeout_sec: usize, operation: impl Into<String>) -> CollectionError {
+    pub fn timeout(timeout: Duration, operation: impl Into<String>) -> CollectionError {
         CollectionError::Timeout {
             description: format!(
-                "Operation '{}' timed out after {timeout_sec} seconds",
-                operation.into()
+                "Operation '{}' timed out after {} seconds",
+                operation.into(),
+                timeout.as_secs_f32(),
             ),
         }
     }
@@ -642,6 +1198,10 @@ impl CollectionError {
         CollectionError::BadRequest { description }
     }
 
+    pub fn precondition_failed(description: String) -> CollectionError {
+        CollectionError::PreconditionFailed { description }
+    }
+
     pub fn bad_shard_selection(description: String) -> CollectionError {
         CollectionError::BadShardSelection { description }
     }
@@ -677,6 +1237,7 @@ impl CollectionError {
             Self::BadShardSelection { .. } => false,
             Self::InconsistentShardFailure { .. } => false,
             Self::ForwardProxyError { .. } => false,
+            Self::PreconditionFailed { .. } => false,
         }
     }
 }
@@ -833,6 +1394,9 @@ impl From<tonic::Status> for CollectionError {
             tonic::Code::DeadlineExceeded => CollectionError::Timeout {
                 description: format!("Deadline Exceeded: {err}"),
             },
+            tonic::Code::Aborted => CollectionError::PreconditionFailed {
+                description: err.message().to_string(),
+            },
             other => CollectionError::ServiceError {
                 error: format!("Tonic status error: {other}"),
                 backtrace: Some(Backtrace::force_capture().to_string()),
@@ -901,10 +1465,14 @@ impl Record {
         }
     }
 
+    /// Get dense vector by name. Multi dense and sparse vectors are not returned.
     pub fn get_vector_by_name(&self, name: &str) -> Option<&VectorType> {
         match &self.vector {
             Some(VectorStruct::Single(vector)) => (name == DEFAULT_VECTOR_NAME).then_some(vector),
-            Some(VectorStruct::Multi(vectors)) => vectors.get(name),
+            Some(VectorStruct::Multi(vectors)) => match vectors.get(name)? {
+                Vector::Dense(vector) => Some(vector),
+                Vector::MultiDense(_) | Vector::Sparse(_) => None,
+            },
             None => None,
         }
     }
@@ -912,6 +1480,7 @@ impl Record {
 
 /// Params of single vector data storage
 #[derive(Debug, Hash, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq)]
+#[validate(schema(function = "validate_vector_params"))]
 #[serde(rename_all = "snake_case")]
 pub struct VectorParams {
     /// Size of a vectors used
@@ -935,6 +1504,62 @@ pub struct VectorParams {
     /// Default: false
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub on_disk: Option<bool>,
+    /// If set, each point holds a list of vectors of `size` dimensions, compared with the
+    /// given comparator. Multivectors are always kept in RAM and are not quantized, so they
+    /// can't be combined with `on_disk` and `quantization_config`. The collection-wide
+    /// quantization and `memmap_threshold` don't apply to them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multivector_config: Option<MultiVectorConfig>,
+    /// Data type used to store vector elements. `float16` vectors take half of the RAM of
+    /// `float32` ones, `uint8` vectors a quarter and are scored without conversion.
+    /// Both are always kept in RAM.
+    /// Default: float32
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datatype: Option<VectorStorageDatatype>,
+}
+
+/// Validate the combination of vector params.
+///
+/// Binary distances interpret each element as 8 bits, which only `uint8` elements have.
+/// Multivectors and half precision vectors are only kept in RAM, multivectors in full precision.
+/// Byte vectors are only kept in RAM and are already as compact as quantization would make them.
+pub fn validate_vector_params(params: &VectorParams) -> Result<(), ValidationError> {
+    if params.distance.is_binary() && params.datatype != Some(VectorStorageDatatype::Uint8) {
+        return Err(ValidationError::new(
+            "Hamming and Jaccard distances require uint8 datatype",
+        ));
+    }
+    if params.multivector_config.is_some() {
+        if params.on_disk == Some(true) {
+            return Err(ValidationError::new("Multivectors can't be stored on disk"));
+        }
+        if params.quantization_config.is_some() {
+            return Err(ValidationError::new("Multivectors can't be quantized"));
+        }
+        if params.datatype == Some(VectorStorageDatatype::Float16) {
+            return Err(ValidationError::new(
+                "Multivectors don't support float16 datatype",
+            ));
+        }
+    }
+    if params.datatype == Some(VectorStorageDatatype::Float16) && params.on_disk == Some(true) {
+        return Err(ValidationError::new(
+            "Vectors with float16 datatype can't be stored on disk",
+        ));
+    }
+    if params.datatype == Some(VectorStorageDatatype::Uint8) {
+        if params.on_disk == Some(true) {
+            return Err(ValidationError::new(
+                "Vectors with uint8 datatype can't be stored on disk",
+            ));
+        }
+        if params.quantization_config.is_some() {
+            return Err(ValidationError::new(
+                "Vectors with uint8 datatype can't be quantized",
+            ));
+        }
+    }
+    Ok(())
 }
 
 /// Validate the value is in `[1, 65536]` or `None`.
@@ -958,6 +1583,35 @@ impl Anonymize for VectorParams {
     }
 }
 
+/// Params of single sparse vector data storage
+#[derive(
+    Debug, Hash, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Default,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVectorParams {
+    /// Custom params for index. If none - values from collection configuration are used.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub index: Option<SparseIndexParams>,
+}
+
+/// Configuration for sparse inverted index
+#[derive(Debug, Hash, Deserialize, Serialize, JsonSchema, Validate, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseIndexParams {
+    /// We prefer a full scan search upto (excluding) this number of vectors.
+    ///
+    /// Note: this is number of vectors, not KiloBytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub full_scan_threshold: Option<usize>,
+}
+
+impl Anonymize for SparseVectorParams {
+    fn anonymize(&self) -> Self {
+        self.clone()
+    }
+}
+
 /// Vector params separator for single and multiple vector modes
 /// Single mode:
 ///
@@ -1290,18 +1944,26 @@ pub struct BaseGroupRequest {
     pub with_lookup: Option<WithLookupInterface>,
 }
 
-impl From<SearchRequestBatch> for CoreSearchRequestBatch {
-    fn from(batch: SearchRequestBatch) -> Self {
-        CoreSearchRequestBatch {
-            searches: batch.searches.into_iter().map_into().collect(),
-        }
+impl TryFrom<SearchRequestBatch> for CoreSearchRequestBatch {
+    type Error = CollectionError;
+
+    fn try_from(batch: SearchRequestBatch) -> Result<Self, Self::Error> {
+        Ok(CoreSearchRequestBatch {
+            searches: batch
+                .searches
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
     }
 }
 
-impl From<SearchRequest> for CoreSearchRequest {
-    fn from(request: SearchRequest) -> Self {
-        Self {
-            query: QueryEnum::Nearest(request.vector),
+impl TryFrom<SearchRequest> for CoreSearchRequest {
+    type Error = CollectionError;
+
+    fn try_from(request: SearchRequest) -> Result<Self, Self::Error> {
+        Ok(Self {
+            query: QueryEnum::Nearest(request.vector.into_vector()?),
             filter: request.filter,
             params: request.params,
             limit: request.limit,
@@ -1309,15 +1971,25 @@ impl From<SearchRequest> for CoreSearchRequest {
             with_payload: request.with_payload,
             with_vector: request.with_vector,
             score_threshold: request.score_threshold,
-        }
+        })
     }
 }
 
-impl From<QueryEnum> for QueryVector {
-    fn from(query: QueryEnum) -> Self {
+impl TryFrom<QueryEnum> for QueryVector {
+    type Error = CollectionError;
+
+    fn try_from(query: QueryEnum) -> Result<Self, Self::Error> {
         match query {
-            QueryEnum::Nearest(named) => QueryVector::Nearest(named.to_vector()),
-            QueryEnum::RecommendBestScore(named) => QueryVector::Recommend(named.query),
+            QueryEnum::Nearest(named) => Ok(named.to_vector().into()),
+            QueryEnum::RecommendBestScore(named) => Ok(QueryVector::Recommend(named.query)),
+            QueryEnum::Discover(named) => Ok(QueryVector::Discovery(named.query)),
+            QueryEnum::Context(named) => Ok(QueryVector::Context(named.query)),
+            QueryEnum::Text(_) => Err(CollectionError::service_error(
+                "Text query can't be searched by vector",
+            )),
+            QueryEnum::Sample(_) => Err(CollectionError::service_error(
+                "Sample query can't be searched by vector",
+            )),
         }
     }
 }
@@ -3,13 +3,14 @@ use std::sync::Arc;
 
 use schemars::JsonSchema;
 use segment::common::cpu::get_num_cpus;
-use segment::types::{HnswConfig, QuantizationConfig};
+use segment::types::{HnswConfig, PayloadKeyType, QuantizationConfig};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
 use crate::collection_manager::optimizers::config_mismatch_optimizer::ConfigMismatchOptimizer;
 use crate::collection_manager::optimizers::indexing_optimizer::IndexingOptimizer;
 use crate::collection_manager::optimizers::merge_optimizer::MergeOptimizer;
+use crate::collection_manager::optimizers::rebuild_index_optimizer::RebuildIndexOptimizer;
 use crate::collection_manager::optimizers::segment_optimizer::OptimizerThresholds;
 use crate::collection_manager::optimizers::vacuum_optimizer::VacuumOptimizer;
 use crate::config::CollectionParams;
@@ -73,6 +74,80 @@ pub struct OptimizersConfig {
     pub flush_interval_sec: u64,
     /// Maximum available threads for optimization workers
     pub max_optimization_threads: usize,
+    /// Payload key with the expiration time of points, as an RFC 3339 datetime.
+    /// Expired points are deleted every minute and replicated like regular updates.
+    /// A datetime index is created on the key, if missing. No expiration if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiration_key: Option<PayloadKeyType>,
+    /// Policy of merging small segments by size tiers.
+    /// If set, replaces merging of the smallest segments down to `default_segment_number`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub merge_policy: Option<TieredMergePolicy>,
+    /// Payload key, which groups the points in optimized segments, e.g. a tenant id.
+    /// Points with the same value are stored next to each other, which speeds up search filtered by this key.
+    /// If not set, points are grouped by the keyword index, marked as tenant, if there is one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub defragmentation_key: Option<PayloadKeyType>,
+}
+
+/// Segments are grouped into size tiers, each tier holds segments `merge_factor` times larger
+/// than the previous one. Segments of a tier are merged, once there are too many of them,
+/// so the merged segment moves up to the next tier.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Eq, Hash)]
+pub struct TieredMergePolicy {
+    /// Upper size bound of the smallest tier (in kilobytes).
+    /// All segments below this size are merged as if they were of this size.
+    ///
+    /// Note: 1Kb = 1 vector of size 256
+    #[validate(range(min = 1))]
+    pub floor_segment_size_kb: usize,
+    /// Number of segments in a tier, which triggers merging of the tier
+    #[validate(range(min = 3))]
+    pub max_segments_per_tier: usize,
+    /// Maximal number of segments merged at once, and size ratio of the adjacent tiers
+    #[validate(range(min = 3))]
+    pub merge_factor: usize,
+}
+
+impl TieredMergePolicy {
+    /// Tier of a segment of the given size (in kilobytes), starting from `0`
+    pub fn tier(&self, segment_size_kb: usize) -> usize {
+        let mut tier = 0;
+        let mut tier_size_kb = self.floor_segment_size_kb.max(1);
+        while segment_size_kb > tier_size_kb {
+            tier_size_kb = tier_size_kb.saturating_mul(self.merge_factor.max(2));
+            tier += 1;
+        }
+        tier
+    }
+}
+
+/// Optimizations, which can be requested to run right away
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OptimizeTarget {
+    /// Merge small segments
+    Merge,
+    /// Build vector indexes of the segments, which don't have them yet
+    Indexing,
+    /// Rebuild segments with deleted points
+    Vacuum,
+    /// Rebuild vector and payload indexes of all segments, only done if requested explicitly
+    RebuildIndex,
+}
+
+/// Indexes, which can be requested to be rebuilt
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum RebuildIndexTarget {
+    /// Rebuild all segments
+    #[default]
+    All,
+    /// Rebuild segments with vector indexes, e.g. HNSW
+    Vectors,
+    /// Rebuild segments with payload indexes
+    Payload,
 }
 
 impl OptimizersConfig {
@@ -87,6 +162,9 @@ impl OptimizersConfig {
             indexing_threshold: Some(100_000),
             flush_interval_sec: 60,
             max_optimization_threads: 0,
+            expiration_key: None,
+            merge_policy: None,
+            defragmentation_key: None,
         }
     }
 
@@ -125,16 +203,7 @@ pub fn clear_temp_segments(shard_path: &Path) {
     }
 }
 
-pub fn build_optimizers(
-    shard_path: &Path,
-    collection_params: &CollectionParams,
-    optimizers_config: &OptimizersConfig,
-    hnsw_config: &HnswConfig,
-    quantization_config: &Option<QuantizationConfig>,
-) -> Arc<Vec<Arc<Optimizer>>> {
-    let segments_path = shard_path.join(SEGMENTS_PATH);
-    let temp_segments_path = shard_path.join(TEMP_SEGMENTS_PATH);
-
+fn optimizer_thresholds(optimizers_config: &OptimizersConfig) -> OptimizerThresholds {
     let indexing_threshold = match optimizers_config.indexing_threshold {
         None => DEFAULT_INDEXING_THRESHOLD_KB, // default value
         Some(0) => usize::MAX,                 // disable vector index
@@ -146,15 +215,29 @@ pub fn build_optimizers(
         Some(custom) => custom,
     };
 
-    let threshold_config = OptimizerThresholds {
+    OptimizerThresholds {
         memmap_threshold,
         indexing_threshold,
         max_segment_size: optimizers_config.get_max_segment_size(),
-    };
+        defragmentation_key: optimizers_config.defragmentation_key.clone(),
+    }
+}
+
+pub fn build_optimizers(
+    shard_path: &Path,
+    collection_params: &CollectionParams,
+    optimizers_config: &OptimizersConfig,
+    hnsw_config: &HnswConfig,
+    quantization_config: &Option<QuantizationConfig>,
+) -> Arc<Vec<Arc<Optimizer>>> {
+    let segments_path = shard_path.join(SEGMENTS_PATH);
+    let temp_segments_path = shard_path.join(TEMP_SEGMENTS_PATH);
+    let threshold_config = optimizer_thresholds(optimizers_config);
 
     Arc::new(vec![
         Arc::new(MergeOptimizer::new(
             optimizers_config.get_number_segments(),
+            optimizers_config.merge_policy.clone(),
             threshold_config.clone(),
             segments_path.clone(),
             temp_segments_path.clone(),
@@ -190,3 +273,88 @@ pub fn build_optimizers(
         )),
     ])
 }
+
+/// Build optimizers, which optimize segments right away, instead of waiting for the thresholds
+/// of `optimizers_config` to be reached. Optimizers of all targets are built if `target` is `None`,
+/// except for [`OptimizeTarget::RebuildIndex`], see [`build_rebuild_index_optimizer`].
+pub fn build_forced_optimizers(
+    shard_path: &Path,
+    collection_params: &CollectionParams,
+    optimizers_config: &OptimizersConfig,
+    hnsw_config: &HnswConfig,
+    quantization_config: &Option<QuantizationConfig>,
+    target: Option<OptimizeTarget>,
+) -> Arc<Vec<Arc<Optimizer>>> {
+    let segments_path = shard_path.join(SEGMENTS_PATH);
+    let temp_segments_path = shard_path.join(TEMP_SEGMENTS_PATH);
+    let is_target = |optimize_target| target.map_or(true, |target| target == optimize_target);
+
+    let mut forced_config = optimizers_config.clone();
+    // Index segments of any size, unless indexing is disabled
+    if is_target(OptimizeTarget::Indexing) && optimizers_config.indexing_threshold != Some(0) {
+        forced_config.indexing_threshold = Some(1);
+    }
+    let threshold_config = optimizer_thresholds(&forced_config);
+
+    let mut optimizers: Vec<Arc<Optimizer>> = Vec::new();
+    if is_target(OptimizeTarget::Merge) {
+        // Merge as many segments, as fit into the max segment size
+        optimizers.push(Arc::new(MergeOptimizer::new(
+            1,
+            None,
+            threshold_config.clone(),
+            segments_path.clone(),
+            temp_segments_path.clone(),
+            collection_params.clone(),
+            hnsw_config.clone(),
+            quantization_config.clone(),
+        )));
+    }
+    if is_target(OptimizeTarget::Indexing) {
+        optimizers.push(Arc::new(IndexingOptimizer::new(
+            threshold_config.clone(),
+            segments_path.clone(),
+            temp_segments_path.clone(),
+            collection_params.clone(),
+            hnsw_config.clone(),
+            quantization_config.clone(),
+        )));
+    }
+    if is_target(OptimizeTarget::Vacuum) {
+        // Vacuum segments with any deleted points
+        optimizers.push(Arc::new(VacuumOptimizer::new(
+            0.0,
+            1,
+            threshold_config,
+            segments_path,
+            temp_segments_path,
+            collection_params.clone(),
+            hnsw_config.clone(),
+            quantization_config.clone(),
+        )));
+    }
+    Arc::new(optimizers)
+}
+
+/// Build an optimizer, which rebuilds indexes of the segments regardless of their state.
+/// Only segments with an index on `field_name` are rebuilt, if it is set.
+pub fn build_rebuild_index_optimizer(
+    shard_path: &Path,
+    collection_params: &CollectionParams,
+    optimizers_config: &OptimizersConfig,
+    hnsw_config: &HnswConfig,
+    quantization_config: &Option<QuantizationConfig>,
+    target: RebuildIndexTarget,
+    field_name: Option<PayloadKeyType>,
+) -> RebuildIndexOptimizer {
+    RebuildIndexOptimizer::new(
+        target,
+        field_name,
+        optimizer_thresholds(optimizers_config),
+        shard_path.join(SEGMENTS_PATH),
+        shard_path.join(TEMP_SEGMENTS_PATH),
+        collection_params.clone(),
+        hnsw_config.clone(),
+        quantization_config.clone(),
+    )
+}
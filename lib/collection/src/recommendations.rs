@@ -417,6 +417,7 @@ fn recommend_by_avg_vector<'a>(
         .into(),
         filter: Some(Filter {
             should: None,
+            min_should: None,
             must: filter.clone().map(|filter| vec![Condition::Filter(filter)]),
             must_not: Some(vec![Condition::HasId(HasIdCondition {
                 has_id: reference_vectors_ids.iter().cloned().collect(),
@@ -428,6 +429,7 @@ fn recommend_by_avg_vector<'a>(
         limit,
         score_threshold,
         offset,
+        text: None,
     }
 }
 
@@ -451,6 +453,7 @@ fn recommend_by_best_score<'a>(
         query,
         filter: Some(Filter {
             should: None,
+            min_should: None,
             must: request
                 .filter
                 .clone()
@@ -0,0 +1,367 @@
+use std::cmp::max;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use segment::common::rocksdb_wrapper::{open_db_with_existing_cf, DatabaseColumnWrapper};
+use segment::common::Flusher;
+use segment::types::PointIdType;
+
+use crate::operations::clock_ops::ClockTag;
+use crate::operations::types::{CollectionError, CollectionResult};
+
+const POINT_CLOCKS_DIR: &str = "point_clocks";
+const POINT_CLOCKS_CF: &str = "clock";
+const PAYLOAD_CLOCKS_CF: &str = "payload_clock";
+
+/// Clocks of the points, saved into a shard snapshot instead of the storage directory
+const POINT_CLOCKS_SNAPSHOT_FILE: &str = "point_clocks.cbor";
+const PAYLOAD_CLOCKS_SNAPSHOT_FILE: &str = "payload_clocks.cbor";
+
+/// Clocks of deleted points are checked for removal at most once per this interval
+const PURGE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Hybrid logical clock of a replica set.
+///
+/// Ticks grow with each operation and are never lower than the current time in milliseconds,
+/// so the clock doesn't have to be persisted to remain ahead of the ticks, issued before a restart.
+#[derive(Debug, Default)]
+pub struct LogicalClock {
+    last_tick: AtomicU64,
+}
+
+impl LogicalClock {
+    /// Tick for a new operation, higher than any tick issued or observed before
+    pub fn tick(&self) -> u64 {
+        let now = now_millis();
+        let next = |last_tick: u64| max(last_tick + 1, now);
+        let last_tick = self
+            .last_tick
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last_tick| {
+                Some(next(last_tick))
+            })
+            .expect("update function never fails");
+        next(last_tick)
+    }
+
+    /// Advance the clock to the tick of an operation, received from another replica
+    pub fn observe(&self, tick: u64) {
+        self.last_tick.fetch_max(tick, Ordering::SeqCst);
+    }
+}
+
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Clocks of the last operations, which replaced the points of a local shard as a whole,
+/// and of the last operations, which replaced their whole payload.
+///
+/// Clocks of deleted points are kept within the retention window, so a delayed update doesn't
+/// bring them back. Replicas, which lag behind for longer, are recovered by a shard transfer,
+/// which copies the clocks together with the points.
+pub struct PointClocks {
+    store: DatabaseColumnWrapper,
+    payload_store: DatabaseColumnWrapper,
+    tombstone_retention: Duration,
+    last_purge: AtomicU64,
+}
+
+impl PointClocks {
+    pub fn path(shard_path: &Path) -> PathBuf {
+        shard_path.join(POINT_CLOCKS_DIR)
+    }
+
+    pub fn open(shard_path: &Path, tombstone_retention: Duration) -> CollectionResult<Self> {
+        let (store, payload_store) = open_stores(shard_path)?;
+        Ok(Self {
+            store,
+            payload_store,
+            tombstone_retention,
+            last_purge: AtomicU64::new(0),
+        })
+    }
+
+    /// Clock of the last operation, which replaced or deleted the point
+    pub fn get(&self, point_id: PointIdType) -> CollectionResult<Option<ClockTag>> {
+        get_clock(&self.store, point_id)
+    }
+
+    /// Remember the clock of a point change, unless the point was changed with a higher clock
+    pub fn advance(&self, point_id: PointIdType, clock_tag: ClockTag) -> CollectionResult<()> {
+        advance_clock(&self.store, point_id, clock_tag)
+    }
+
+    /// Replace the clock of a point, e.g. with the clock of the point copied from another replica
+    pub fn set(&self, point_id: PointIdType, clock_tag: ClockTag) -> CollectionResult<()> {
+        self.store.put(encode(&point_id)?, encode(&clock_tag)?)?;
+        Ok(())
+    }
+
+    /// Clock of the last operation, which replaced the whole payload of the point
+    pub fn get_payload(&self, point_id: PointIdType) -> CollectionResult<Option<ClockTag>> {
+        get_clock(&self.payload_store, point_id)
+    }
+
+    /// Remember the clock of a payload replacement,
+    /// unless the payload was replaced with a higher clock
+    pub fn advance_payload(
+        &self,
+        point_id: PointIdType,
+        clock_tag: ClockTag,
+    ) -> CollectionResult<()> {
+        advance_clock(&self.payload_store, point_id, clock_tag)
+    }
+
+    /// Remove the clocks of deleted points, which are older than the retention window,
+    /// at most once per purge interval. `has_point` tells, whether the point still exists.
+    pub fn purge_tombstones(
+        &self,
+        has_point: impl Fn(PointIdType) -> bool,
+    ) -> CollectionResult<()> {
+        let now = now_millis();
+        let last_purge = self.last_purge.load(Ordering::Relaxed);
+        if now.saturating_sub(last_purge) >= PURGE_INTERVAL.as_millis() as u64
+            && self
+                .last_purge
+                .compare_exchange(last_purge, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            self.purge_tombstones_at(now, has_point)?;
+        }
+        Ok(())
+    }
+
+    fn purge_tombstones_at(
+        &self,
+        now: u64,
+        has_point: impl Fn(PointIdType) -> bool,
+    ) -> CollectionResult<()> {
+        let retention = u64::try_from(self.tombstone_retention.as_millis()).unwrap_or(u64::MAX);
+        for store in [&self.store, &self.payload_store] {
+            let mut expired = Vec::new();
+            for (key, value) in store.lock_db().iter()? {
+                let clock_tag: ClockTag = decode(&value)?;
+                if now.saturating_sub(clock_tag.clock_tick) > retention && !has_point(decode(&key)?)
+                {
+                    expired.push(key);
+                }
+            }
+            if !expired.is_empty() {
+                log::debug!("Removing {} clocks of deleted points", expired.len());
+            }
+            for key in expired {
+                store.remove(key)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn flusher(&self) -> Flusher {
+        let flush_points = self.store.flusher();
+        let flush_payloads = self.payload_store.flusher();
+        Box::new(move || {
+            flush_points()?;
+            flush_payloads()
+        })
+    }
+
+    /// Save all clocks into the shard snapshot
+    pub fn save_snapshot(&self, snapshot_shard_path: &Path) -> CollectionResult<()> {
+        save_clocks(
+            &self.store,
+            &snapshot_shard_path.join(POINT_CLOCKS_SNAPSHOT_FILE),
+        )?;
+        save_clocks(
+            &self.payload_store,
+            &snapshot_shard_path.join(PAYLOAD_CLOCKS_SNAPSHOT_FILE),
+        )
+    }
+
+    /// Move clocks from the snapshot files into the storage of the restored shard
+    pub fn restore_snapshot(snapshot_shard_path: &Path) -> CollectionResult<()> {
+        let snapshot_file = snapshot_shard_path.join(POINT_CLOCKS_SNAPSHOT_FILE);
+        if !snapshot_file.exists() {
+            return Ok(());
+        }
+        let (store, payload_store) = open_stores(snapshot_shard_path)?;
+        restore_clocks(&store, &snapshot_file)?;
+        // Snapshots of older versions have no payload clocks
+        let payload_snapshot_file = snapshot_shard_path.join(PAYLOAD_CLOCKS_SNAPSHOT_FILE);
+        if payload_snapshot_file.exists() {
+            restore_clocks(&payload_store, &payload_snapshot_file)?;
+        }
+        store.flusher()()?;
+        payload_store.flusher()()?;
+        Ok(())
+    }
+}
+
+fn open_stores(
+    shard_path: &Path,
+) -> CollectionResult<(DatabaseColumnWrapper, DatabaseColumnWrapper)> {
+    let db = open_db_with_existing_cf(&PointClocks::path(shard_path)).map_err(|err| {
+        CollectionError::service_error(format!("Can't open point clocks storage: {err}"))
+    })?;
+    let store = DatabaseColumnWrapper::new(db.clone(), POINT_CLOCKS_CF);
+    store.create_column_family_if_not_exists()?;
+    let payload_store = DatabaseColumnWrapper::new(db, PAYLOAD_CLOCKS_CF);
+    payload_store.create_column_family_if_not_exists()?;
+    Ok((store, payload_store))
+}
+
+fn get_clock(
+    store: &DatabaseColumnWrapper,
+    point_id: PointIdType,
+) -> CollectionResult<Option<ClockTag>> {
+    let key = encode(&point_id)?;
+    store.get_pinned(&key, |value| decode(value))?.transpose()
+}
+
+fn advance_clock(
+    store: &DatabaseColumnWrapper,
+    point_id: PointIdType,
+    clock_tag: ClockTag,
+) -> CollectionResult<()> {
+    if get_clock(store, point_id)?.map_or(false, |current| current >= clock_tag) {
+        return Ok(());
+    }
+    store.put(encode(&point_id)?, encode(&clock_tag)?)?;
+    Ok(())
+}
+
+fn save_clocks(store: &DatabaseColumnWrapper, path: &Path) -> CollectionResult<()> {
+    let mut clocks: Vec<(PointIdType, ClockTag)> = Vec::new();
+    for (key, value) in store.lock_db().iter()? {
+        clocks.push((decode(&key)?, decode(&value)?));
+    }
+    let file = File::create(path)?;
+    serde_cbor::to_writer(BufWriter::new(file), &clocks)
+        .map_err(|err| CollectionError::service_error(format!("Can't save point clocks: {err}")))
+}
+
+fn restore_clocks(store: &DatabaseColumnWrapper, path: &Path) -> CollectionResult<()> {
+    let clocks: Vec<(PointIdType, ClockTag)> =
+        serde_cbor::from_reader(BufReader::new(File::open(path)?)).map_err(|err| {
+            CollectionError::service_error(format!("Can't read point clocks: {err}"))
+        })?;
+    for (point_id, clock_tag) in clocks {
+        advance_clock(store, point_id, clock_tag)?;
+    }
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+fn encode<T: serde::Serialize>(value: &T) -> CollectionResult<Vec<u8>> {
+    serde_cbor::to_vec(value)
+        .map_err(|err| CollectionError::service_error(format!("Can't encode point clock: {err}")))
+}
+
+fn decode<T: serde::de::DeserializeOwned>(value: &[u8]) -> CollectionResult<T> {
+    serde_cbor::from_slice(value)
+        .map_err(|err| CollectionError::service_error(format!("Can't decode point clock: {err}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn test_logical_clock() {
+        let clock = LogicalClock::default();
+        let first = clock.tick();
+        assert!(first >= now_millis() - 1000);
+        assert!(clock.tick() > first);
+
+        clock.observe(first + 1_000_000);
+        assert_eq!(clock.tick(), first + 1_000_001);
+    }
+
+    #[test]
+    fn test_point_clocks() {
+        let dir = Builder::new().prefix("shard").tempdir().unwrap();
+        let point_clocks = PointClocks::open(dir.path(), Duration::from_secs(60)).unwrap();
+
+        let point_id: PointIdType = 1.into();
+        assert_eq!(point_clocks.get(point_id).unwrap(), None);
+
+        point_clocks
+            .advance(point_id, ClockTag::new(10, 2))
+            .unwrap();
+        // Lower clock doesn't override the higher one
+        point_clocks
+            .advance(point_id, ClockTag::new(10, 1))
+            .unwrap();
+        assert_eq!(
+            point_clocks.get(point_id).unwrap(),
+            Some(ClockTag::new(10, 2))
+        );
+
+        // Payload clocks are kept separately from the clocks of whole points
+        assert_eq!(point_clocks.get_payload(point_id).unwrap(), None);
+        point_clocks
+            .advance_payload(point_id, ClockTag::new(11, 1))
+            .unwrap();
+        assert_eq!(
+            point_clocks.get(point_id).unwrap(),
+            Some(ClockTag::new(10, 2))
+        );
+
+        let snapshot_dir = Builder::new().prefix("snapshot").tempdir().unwrap();
+        point_clocks.save_snapshot(snapshot_dir.path()).unwrap();
+        PointClocks::restore_snapshot(snapshot_dir.path()).unwrap();
+        let restored = PointClocks::open(snapshot_dir.path(), Duration::from_secs(60)).unwrap();
+        assert_eq!(restored.get(point_id).unwrap(), Some(ClockTag::new(10, 2)));
+        assert_eq!(
+            restored.get_payload(point_id).unwrap(),
+            Some(ClockTag::new(11, 1))
+        );
+    }
+
+    #[test]
+    fn test_purge_tombstones() {
+        let dir = Builder::new().prefix("shard").tempdir().unwrap();
+        let point_clocks = PointClocks::open(dir.path(), Duration::from_secs(60)).unwrap();
+
+        let now = now_millis();
+        let old_tick = now - 61_000;
+        let (existing, deleted, recently_deleted): (PointIdType, PointIdType, PointIdType) =
+            (1.into(), 2.into(), 3.into());
+        for point_id in [existing, deleted] {
+            point_clocks
+                .advance(point_id, ClockTag::new(old_tick, 1))
+                .unwrap();
+            point_clocks
+                .advance_payload(point_id, ClockTag::new(old_tick, 1))
+                .unwrap();
+        }
+        point_clocks
+            .advance(recently_deleted, ClockTag::new(now, 1))
+            .unwrap();
+
+        // Only old clocks of the points, which don't exist anymore, are removed
+        point_clocks
+            .purge_tombstones_at(now, |point_id| point_id == existing)
+            .unwrap();
+        assert!(point_clocks.get(existing).unwrap().is_some());
+        assert!(point_clocks.get_payload(existing).unwrap().is_some());
+        assert_eq!(point_clocks.get(deleted).unwrap(), None);
+        assert_eq!(point_clocks.get_payload(deleted).unwrap(), None);
+        assert!(point_clocks.get(recently_deleted).unwrap().is_some());
+
+        // Purge runs once per interval
+        point_clocks.purge_tombstones(|_| false).unwrap();
+        point_clocks
+            .advance(deleted, ClockTag::new(old_tick, 1))
+            .unwrap();
+        point_clocks.purge_tombstones(|_| false).unwrap();
+        assert!(point_clocks.get(deleted).unwrap().is_some());
+    }
+}
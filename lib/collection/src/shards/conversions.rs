@@ -1,34 +1,44 @@
 use api::grpc::conversions::payload_to_proto;
 use api::grpc::qdrant::points_selector::PointsSelectorOneOf;
+use api::grpc::qdrant::points_update_operation::{self, Operation};
 use api::grpc::qdrant::{
     ClearPayloadPoints, ClearPayloadPointsInternal, CreateFieldIndexCollection,
     CreateFieldIndexCollectionInternal, DeleteFieldIndexCollection,
     DeleteFieldIndexCollectionInternal, DeletePayloadPoints, DeletePayloadPointsInternal,
-    DeletePointVectors, DeletePoints, DeletePointsInternal, DeleteVectorsInternal, PointVectors,
-    PointsIdsList, PointsSelector, SetPayloadPoints, SetPayloadPointsInternal, SyncPoints,
-    SyncPointsInternal, UpdatePointVectors, UpdateVectorsInternal, UpsertPoints,
+    DeletePointVectors, DeletePoints, DeletePointsInternal, DeleteVectorsInternal, PointStruct,
+    PointVectors, PointsIdsList, PointsSelector, PointsUpdateOperation, SetPayloadPoints,
+    SetPayloadPointsInternal, SyncPoints, SyncPointsInternal, UpdateBatchInternal,
+    UpdateBatchPoints, UpdatePointVectors, UpdateVectorsInternal, UpsertPoints,
     UpsertPointsInternal, VectorsSelector,
 };
 use segment::types::{Filter, PayloadFieldSchema, PayloadSchemaParams, PointIdType, ScoredPoint};
 use tonic::Status;
 
-use crate::operations::conversions::write_ordering_to_proto;
-use crate::operations::payload_ops::{DeletePayload, SetPayload};
-use crate::operations::point_ops::{PointInsertOperations, PointSyncOperation, WriteOrdering};
-use crate::operations::types::CollectionResult;
-use crate::operations::vector_ops::UpdateVectors;
-use crate::operations::CreateIndex;
+use crate::operations::clock_ops::ClockTag;
+use crate::operations::conversions::{point_clock_tag_to_proto, write_ordering_to_proto};
+use crate::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
+use crate::operations::point_ops::{
+    PointInsertOperations, PointOperations, PointSyncOperation, UpsertCondition, WriteOrdering,
+};
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::vector_ops::{UpdateVectors, VectorOperations};
+use crate::operations::{CollectionUpdateOperations, CreateIndex};
 use crate::shards::shard::ShardId;
 
 pub fn internal_sync_points(
     shard_id: Option<ShardId>,
     collection_name: String,
     points_sync_operation: PointSyncOperation,
+    point_clock_tags: Vec<(PointIdType, ClockTag)>,
     wait: bool,
     ordering: Option<WriteOrdering>,
 ) -> CollectionResult<SyncPointsInternal> {
     Ok(SyncPointsInternal {
         shard_id,
+        point_clock_tags: point_clock_tags
+            .into_iter()
+            .map(point_clock_tag_to_proto)
+            .collect(),
         sync_points: Some(SyncPoints {
             collection_name,
             wait: Some(wait),
@@ -48,6 +58,7 @@ pub fn internal_upsert_points(
     shard_id: Option<ShardId>,
     collection_name: String,
     point_insert_operations: PointInsertOperations,
+    condition: Option<UpsertCondition>,
     wait: bool,
     ordering: Option<WriteOrdering>,
 ) -> CollectionResult<UpsertPointsInternal> {
@@ -64,6 +75,15 @@ pub fn internal_upsert_points(
                     .collect::<Result<Vec<_>, Status>>()?,
             },
             ordering: ordering.map(write_ordering_to_proto),
+            if_version: match condition {
+                Some(UpsertCondition::IfVersion(version)) => Some(version),
+                _ => None,
+            },
+            if_absent: match condition {
+                Some(UpsertCondition::IfAbsent) => Some(true),
+                _ => None,
+            },
+            idempotency_token: None,
         }),
     })
 }
@@ -86,6 +106,7 @@ pub fn internal_delete_points(
                 })),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token: None,
         }),
     }
 }
@@ -106,6 +127,7 @@ pub fn internal_delete_points_by_filter(
                 points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token: None,
         }),
     }
 }
@@ -131,6 +153,7 @@ pub fn internal_update_vectors(
                 })
                 .collect(),
             ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token: None,
         }),
     }
 }
@@ -157,6 +180,7 @@ pub fn internal_delete_vectors(
                 names: vector_names,
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token: None,
         }),
     }
 }
@@ -181,6 +205,7 @@ pub fn internal_delete_vectors_by_filter(
                 names: vector_names,
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token: None,
         }),
     }
 }
@@ -189,6 +214,7 @@ pub fn internal_set_payload(
     shard_id: Option<ShardId>,
     collection_name: String,
     set_payload: SetPayload,
+    deep_merge: bool,
     wait: bool,
     ordering: Option<WriteOrdering>,
 ) -> SetPayloadPointsInternal {
@@ -212,6 +238,8 @@ pub fn internal_set_payload(
             payload: payload_to_proto(set_payload.payload),
             points_selector,
             ordering: ordering.map(write_ordering_to_proto),
+            deep_merge: Some(deep_merge),
+            idempotency_token: None,
         }),
     }
 }
@@ -243,6 +271,7 @@ pub fn internal_delete_payload(
             keys: delete_payload.keys,
             points_selector,
             ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token: None,
         }),
     }
 }
@@ -265,6 +294,7 @@ pub fn internal_clear_payload(
                 })),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token: None,
         }),
     }
 }
@@ -285,10 +315,192 @@ pub fn internal_clear_payload_by_filter(
                 points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token: None,
         }),
     }
 }
 
+fn ids_selector(ids: Vec<PointIdType>) -> PointsSelector {
+    PointsSelector {
+        points_selector_one_of: Some(PointsSelectorOneOf::Points(PointsIdsList {
+            ids: ids.into_iter().map(|id| id.into()).collect(),
+        })),
+    }
+}
+
+fn filter_selector(filter: Filter) -> PointsSelector {
+    PointsSelector {
+        points_selector_one_of: Some(PointsSelectorOneOf::Filter(filter.into())),
+    }
+}
+
+/// Convert operations of a batch into the operations of the gRPC batch update
+fn update_operations_to_grpc(
+    operations: Vec<CollectionUpdateOperations>,
+    grpc_operations: &mut Vec<PointsUpdateOperation>,
+) -> CollectionResult<()> {
+    for operation in operations {
+        let operation = match operation {
+            CollectionUpdateOperations::PointOperation(point_operation) => match point_operation {
+                PointOperations::UpsertPoints(point_insert_operations) => {
+                    let points: Vec<PointStruct> = match point_insert_operations {
+                        PointInsertOperations::PointsBatch(batch) => batch.try_into()?,
+                        PointInsertOperations::PointsList(list) => list
+                            .into_iter()
+                            .map(|point| point.try_into())
+                            .collect::<Result<Vec<_>, Status>>()?,
+                    };
+                    Operation::Upsert(points_update_operation::PointStructList { points })
+                }
+                PointOperations::DeletePoints { ids } => Operation::Delete(ids_selector(ids)),
+                PointOperations::DeletePointsByFilter(filter) => {
+                    Operation::Delete(filter_selector(filter))
+                }
+                PointOperations::UpsertPointsConditional(_) => {
+                    return Err(CollectionError::bad_request(
+                        "Conditional upsert can't be a part of a batch".to_string(),
+                    ))
+                }
+                PointOperations::SyncPoints(_) => {
+                    return Err(CollectionError::bad_request(
+                        "Sync operation can't be a part of a batch".to_string(),
+                    ))
+                }
+            },
+            CollectionUpdateOperations::VectorOperation(vector_operation) => match vector_operation
+            {
+                VectorOperations::UpdateVectors(update_vectors) => {
+                    Operation::UpdateVectors(points_update_operation::UpdateVectors {
+                        points: update_vectors
+                            .points
+                            .into_iter()
+                            .map(|point| PointVectors {
+                                id: Some(point.id.into()),
+                                vectors: Some(point.vector.into()),
+                            })
+                            .collect(),
+                    })
+                }
+                VectorOperations::DeleteVectors(ids, vector_names) => {
+                    Operation::DeleteVectors(points_update_operation::DeleteVectors {
+                        points_selector: Some(ids_selector(ids.points)),
+                        vectors: Some(VectorsSelector {
+                            names: vector_names,
+                        }),
+                    })
+                }
+                VectorOperations::DeleteVectorsByFilter(filter, vector_names) => {
+                    Operation::DeleteVectors(points_update_operation::DeleteVectors {
+                        points_selector: Some(filter_selector(filter)),
+                        vectors: Some(VectorsSelector {
+                            names: vector_names,
+                        }),
+                    })
+                }
+            },
+            CollectionUpdateOperations::PayloadOperation(payload_operation) => {
+                match payload_operation {
+                    PayloadOps::SetPayload(set_payload) => {
+                        Operation::SetPayload(set_payload_to_grpc(set_payload, false))
+                    }
+                    PayloadOps::MergePayload(set_payload) => {
+                        Operation::SetPayload(set_payload_to_grpc(set_payload, true))
+                    }
+                    PayloadOps::OverwritePayload(set_payload) => {
+                        Operation::OverwritePayload(set_payload_to_grpc(set_payload, false))
+                    }
+                    PayloadOps::DeletePayload(delete_payload) => {
+                        Operation::DeletePayload(points_update_operation::DeletePayload {
+                            keys: delete_payload.keys,
+                            points_selector: delete_payload
+                                .points
+                                .map(ids_selector)
+                                .or_else(|| delete_payload.filter.map(filter_selector)),
+                        })
+                    }
+                    PayloadOps::ClearPayload { points } => {
+                        Operation::ClearPayload(ids_selector(points))
+                    }
+                    PayloadOps::ClearPayloadByFilter(filter) => {
+                        Operation::ClearPayload(filter_selector(filter))
+                    }
+                    PayloadOps::MutatePayload(mutate_payload) => {
+                        Operation::MutatePayload(points_update_operation::MutatePayload {
+                            mutations: mutate_payload
+                                .mutations
+                                .into_iter()
+                                .map(Into::into)
+                                .collect(),
+                            filter: Some(mutate_payload.filter.into()),
+                        })
+                    }
+                }
+            }
+            CollectionUpdateOperations::FieldIndexOperation(_) => {
+                return Err(CollectionError::bad_request(
+                    "Field index operation can't be a part of a batch".to_string(),
+                ))
+            }
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                update_operations_to_grpc(operations, grpc_operations)?;
+                continue;
+            }
+            CollectionUpdateOperations::ClockedOperation(_) => {
+                return Err(CollectionError::bad_request(
+                    "Clocked operation can't be a part of a batch".to_string(),
+                ))
+            }
+            CollectionUpdateOperations::IdempotentOperation(_) => {
+                return Err(CollectionError::bad_request(
+                    "Idempotent operation can't be a part of a batch".to_string(),
+                ))
+            }
+        };
+        grpc_operations.push(PointsUpdateOperation {
+            operation: Some(operation),
+        });
+    }
+    Ok(())
+}
+
+fn set_payload_to_grpc(
+    set_payload: SetPayload,
+    deep_merge: bool,
+) -> points_update_operation::SetPayload {
+    points_update_operation::SetPayload {
+        payload: payload_to_proto(set_payload.payload),
+        points_selector: set_payload
+            .points
+            .map(ids_selector)
+            .or_else(|| set_payload.filter.map(filter_selector)),
+        deep_merge: Some(deep_merge),
+    }
+}
+
+pub fn internal_update_batch(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    operations: Vec<CollectionUpdateOperations>,
+    clock_tag: Option<ClockTag>,
+    idempotency_token: Option<String>,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> CollectionResult<UpdateBatchInternal> {
+    let mut grpc_operations = Vec::with_capacity(operations.len());
+    update_operations_to_grpc(operations, &mut grpc_operations)?;
+    Ok(UpdateBatchInternal {
+        shard_id,
+        clock_tag: clock_tag.map(Into::into),
+        update_batch_points: Some(UpdateBatchPoints {
+            collection_name,
+            wait: Some(wait),
+            operations: grpc_operations,
+            ordering: ordering.map(write_ordering_to_proto),
+            idempotency_token,
+        }),
+    })
+}
+
 pub fn internal_create_index(
     shard_id: Option<ShardId>,
     collection_name: String,
@@ -319,14 +531,43 @@ pub fn internal_create_index(
                     segment::types::PayloadSchemaType::Bool => {
                         api::grpc::qdrant::FieldType::Bool as i32
                     }
+                    segment::types::PayloadSchemaType::Datetime => {
+                        api::grpc::qdrant::FieldType::Datetime as i32
+                    }
+                    segment::types::PayloadSchemaType::Uuid => {
+                        api::grpc::qdrant::FieldType::Uuid as i32
+                    }
+                    segment::types::PayloadSchemaType::Composite => {
+                        api::grpc::qdrant::FieldType::Composite as i32
+                    }
                 },
                 None,
             ),
             PayloadFieldSchema::FieldParams(field_params) => match field_params {
+                PayloadSchemaParams::Keyword(keyword_index_params) => (
+                    api::grpc::qdrant::FieldType::Keyword as i32,
+                    Some(keyword_index_params.into()),
+                ),
+                PayloadSchemaParams::Integer(integer_index_params) => (
+                    api::grpc::qdrant::FieldType::Integer as i32,
+                    Some(integer_index_params.into()),
+                ),
+                PayloadSchemaParams::Float(float_index_params) => (
+                    api::grpc::qdrant::FieldType::Float as i32,
+                    Some(float_index_params.into()),
+                ),
+                PayloadSchemaParams::Geo(geo_index_params) => (
+                    api::grpc::qdrant::FieldType::Geo as i32,
+                    Some(geo_index_params.into()),
+                ),
                 PayloadSchemaParams::Text(text_index_params) => (
                     api::grpc::qdrant::FieldType::Text as i32,
                     Some(text_index_params.into()),
                 ),
+                PayloadSchemaParams::Composite(composite_index_params) => (
+                    api::grpc::qdrant::FieldType::Composite as i32,
+                    Some(composite_index_params.into()),
+                ),
             },
         })
         .map(|(field_type, field_params)| (Some(field_type), field_params))
@@ -390,5 +631,8 @@ pub fn try_scored_point_from_grpc(
         score: point.score,
         payload,
         vector,
+        explanation: point
+            .explanation
+            .map(|explanation| Box::new(explanation.into())),
     })
 }
@@ -2,11 +2,13 @@ use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 
+use crate::collection_manager::holders::segment_holder::SegmentVersions;
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest,
     CountResult, PointRequest, Record, SearchRequestBatch, UpdateResult,
@@ -32,6 +34,7 @@ impl DummyShard {
         _temp_path: &Path,
         _target_path: &Path,
         _save_wal: bool,
+        _base_segments: Option<&SegmentVersions>,
     ) -> CollectionResult<()> {
         self.dummy()
     }
@@ -45,6 +48,9 @@ impl DummyShard {
             variant_name: Some("dummy shard".into()),
             segments: vec![],
             optimizations: Default::default(),
+            wal_size_bytes: None,
+            searches: Default::default(),
+            updates: Default::default(),
         }
     }
 
@@ -72,6 +78,7 @@ impl ShardOperation for DummyShard {
         _: &WithVector,
         _: Option<&Filter>,
         _: &Handle,
+        _: Option<&OrderBy>,
     ) -> CollectionResult<Vec<Record>> {
         self.dummy()
     }
@@ -2,14 +2,19 @@ use std::path::Path;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common::types::TelemetryDetail;
+use segment::data_types::order_by::OrderBy;
+use segment::index::field_index::stats::FieldIndexStats;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PayloadKeyTypeRef, PointIdType, ScoredPoint, WithPayload,
+    WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 use tokio::sync::Mutex;
 
 use super::update_tracker::UpdateTracker;
+use crate::collection_manager::holders::segment_holder::SegmentVersions;
+use crate::operations::clock_ops::{ClockTag, ClockedOperation};
 use crate::operations::point_ops::{PointOperations, PointStruct, PointSyncOperation};
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest,
@@ -83,6 +88,7 @@ impl ForwardProxyShard {
                 &true.into(),
                 None,
                 runtime_handle,
+                None,
             )
             .await?;
         let next_page_offset = if batch.len() < limit {
@@ -98,6 +104,11 @@ impl ForwardProxyShard {
 
         let points = points?;
 
+        // Transferred points keep their clocks, so later updates are resolved the same way
+        let point_clock_tags = self
+            .wrapped_shard
+            .point_clock_tags(points.iter().map(|point| point.id))?;
+
         // Use sync API to leverage potentially existing points
         let insert_points_operation = {
             CollectionUpdateOperations::PointOperation(PointOperations::SyncPoints(
@@ -108,6 +119,15 @@ impl ForwardProxyShard {
                 },
             ))
         };
+        let insert_points_operation = if point_clock_tags.is_empty() {
+            insert_points_operation
+        } else {
+            CollectionUpdateOperations::ClockedOperation(ClockedOperation {
+                clock_tag: ClockTag::default(),
+                point_clock_tags,
+                operation: Box::new(insert_points_operation),
+            })
+        };
 
         // We only need to wait for the last batch.
         let wait = next_page_offset.is_none();
@@ -128,9 +148,10 @@ impl ForwardProxyShard {
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base_segments: Option<&SegmentVersions>,
     ) -> CollectionResult<()> {
         self.wrapped_shard
-            .create_snapshot(temp_path, target_path, save_wal)
+            .create_snapshot(temp_path, target_path, save_wal, base_segments)
             .await
     }
 
@@ -138,8 +159,12 @@ impl ForwardProxyShard {
         self.wrapped_shard.on_optimizer_config_update().await
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
-        self.wrapped_shard.get_telemetry_data()
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> LocalShardTelemetry {
+        self.wrapped_shard.get_telemetry_data(detail)
+    }
+
+    pub fn get_field_index_stats(&self, field: PayloadKeyTypeRef) -> Option<FieldIndexStats> {
+        self.wrapped_shard.get_field_index_stats(field)
     }
 
     pub fn update_tracker(&self) -> &UpdateTracker {
@@ -176,6 +201,7 @@ impl ShardOperation for ForwardProxyShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
     ) -> CollectionResult<Vec<Record>> {
         let local_shard = &self.wrapped_shard;
         local_shard
@@ -186,6 +212,7 @@ impl ShardOperation for ForwardProxyShard {
                 with_vector,
                 filter,
                 search_runtime_handle,
+                order_by,
             )
             .await
     }
@@ -0,0 +1,198 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use segment::common::rocksdb_wrapper::{open_db_with_existing_cf, DatabaseColumnWrapper};
+use segment::common::Flusher;
+use segment::types::SeqNumberType;
+use serde::{Deserialize, Serialize};
+
+use crate::operations::types::{CollectionError, CollectionResult};
+use crate::shards::clocks::now_millis;
+
+const IDEMPOTENCY_TOKENS_DIR: &str = "idempotency_tokens";
+const IDEMPOTENCY_TOKENS_CF: &str = "token";
+
+/// Tokens, saved into a shard snapshot instead of the storage directory
+const IDEMPOTENCY_TOKENS_SNAPSHOT_FILE: &str = "idempotency_tokens.cbor";
+
+/// Expired tokens are removed at most once per this interval
+const PURGE_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq)]
+struct TokenRecord {
+    /// Number of the operation, applied with the token
+    op_num: SeqNumberType,
+    /// Time of the operation in milliseconds since the UNIX epoch
+    applied_at: u64,
+}
+
+/// Idempotency tokens of the operations, applied to a local shard within the retention window
+pub struct IdempotencyTokens {
+    store: DatabaseColumnWrapper,
+    retention: Duration,
+    last_purge: AtomicU64,
+}
+
+impl IdempotencyTokens {
+    pub fn path(shard_path: &Path) -> PathBuf {
+        shard_path.join(IDEMPOTENCY_TOKENS_DIR)
+    }
+
+    pub fn open(shard_path: &Path, retention: Duration) -> CollectionResult<Self> {
+        Ok(Self {
+            store: open_store(shard_path)?,
+            retention,
+            last_purge: AtomicU64::new(0),
+        })
+    }
+
+    /// Number of the operation, which was applied with the token within the retention window
+    pub fn get(&self, token: &str) -> CollectionResult<Option<SeqNumberType>> {
+        let record = self
+            .store
+            .get_pinned(token.as_bytes(), |value| decode(value))?
+            .transpose()?;
+        Ok(record
+            .filter(|record| !self.is_expired(record, now_millis()))
+            .map(|record| record.op_num))
+    }
+
+    /// Remember the token of an applied operation, also removes expired tokens from time to time
+    pub fn record(&self, token: &str, op_num: SeqNumberType) -> CollectionResult<()> {
+        let now = now_millis();
+        let record = TokenRecord {
+            op_num,
+            applied_at: now,
+        };
+        self.store.put(token.as_bytes(), encode(&record)?)?;
+
+        let last_purge = self.last_purge.load(Ordering::Relaxed);
+        if now.saturating_sub(last_purge) >= PURGE_INTERVAL.as_millis() as u64
+            && self
+                .last_purge
+                .compare_exchange(last_purge, now, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            self.purge_expired(now)?;
+        }
+        Ok(())
+    }
+
+    fn is_expired(&self, record: &TokenRecord, now: u64) -> bool {
+        now.saturating_sub(record.applied_at) > self.retention.as_millis() as u64
+    }
+
+    fn purge_expired(&self, now: u64) -> CollectionResult<()> {
+        let mut expired = Vec::new();
+        for (key, value) in self.store.lock_db().iter()? {
+            if self.is_expired(&decode(&value)?, now) {
+                expired.push(key);
+            }
+        }
+        if !expired.is_empty() {
+            log::debug!("Removing {} expired idempotency tokens", expired.len());
+        }
+        for key in expired {
+            self.store.remove(key)?;
+        }
+        Ok(())
+    }
+
+    pub fn flusher(&self) -> Flusher {
+        self.store.flusher()
+    }
+
+    /// Save all tokens into the shard snapshot
+    pub fn save_snapshot(&self, snapshot_shard_path: &Path) -> CollectionResult<()> {
+        let mut tokens: Vec<(String, TokenRecord)> = Vec::new();
+        for (key, value) in self.store.lock_db().iter()? {
+            tokens.push((String::from_utf8_lossy(&key).into_owned(), decode(&value)?));
+        }
+        let file = File::create(snapshot_shard_path.join(IDEMPOTENCY_TOKENS_SNAPSHOT_FILE))?;
+        serde_cbor::to_writer(BufWriter::new(file), &tokens).map_err(|err| {
+            CollectionError::service_error(format!("Can't save idempotency tokens: {err}"))
+        })
+    }
+
+    /// Move tokens from the snapshot file into the storage of the restored shard
+    pub fn restore_snapshot(snapshot_shard_path: &Path) -> CollectionResult<()> {
+        let snapshot_file = snapshot_shard_path.join(IDEMPOTENCY_TOKENS_SNAPSHOT_FILE);
+        if !snapshot_file.exists() {
+            return Ok(());
+        }
+        let tokens: Vec<(String, TokenRecord)> = serde_cbor::from_reader(BufReader::new(
+            File::open(&snapshot_file)?,
+        ))
+        .map_err(|err| {
+            CollectionError::service_error(format!("Can't read idempotency tokens: {err}"))
+        })?;
+        let store = open_store(snapshot_shard_path)?;
+        for (token, record) in tokens {
+            store.put(token.as_bytes(), encode(&record)?)?;
+        }
+        store.flusher()()?;
+        std::fs::remove_file(snapshot_file)?;
+        Ok(())
+    }
+}
+
+fn open_store(shard_path: &Path) -> CollectionResult<DatabaseColumnWrapper> {
+    let db = open_db_with_existing_cf(&IdempotencyTokens::path(shard_path)).map_err(|err| {
+        CollectionError::service_error(format!("Can't open idempotency tokens storage: {err}"))
+    })?;
+    let store = DatabaseColumnWrapper::new(db, IDEMPOTENCY_TOKENS_CF);
+    store.create_column_family_if_not_exists()?;
+    Ok(store)
+}
+
+fn encode(record: &TokenRecord) -> CollectionResult<Vec<u8>> {
+    serde_cbor::to_vec(record).map_err(|err| {
+        CollectionError::service_error(format!("Can't encode idempotency token: {err}"))
+    })
+}
+
+fn decode(value: &[u8]) -> CollectionResult<TokenRecord> {
+    serde_cbor::from_slice(value).map_err(|err| {
+        CollectionError::service_error(format!("Can't decode idempotency token: {err}"))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn test_idempotency_tokens() {
+        let dir = Builder::new().prefix("shard").tempdir().unwrap();
+        let tokens = IdempotencyTokens::open(dir.path(), Duration::from_secs(60)).unwrap();
+
+        assert_eq!(tokens.get("retry").unwrap(), None);
+        tokens.record("retry", 10).unwrap();
+        assert_eq!(tokens.get("retry").unwrap(), Some(10));
+
+        // Tokens are forgotten after the retention window
+        let record = TokenRecord {
+            op_num: 11,
+            applied_at: now_millis() - 61_000,
+        };
+        tokens
+            .store
+            .put("expired".as_bytes(), encode(&record).unwrap())
+            .unwrap();
+        assert_eq!(tokens.get("expired").unwrap(), None);
+        tokens.purge_expired(now_millis()).unwrap();
+        assert_eq!(tokens.store.lock_db().iter().unwrap().count(), 1);
+
+        let snapshot_dir = Builder::new().prefix("snapshot").tempdir().unwrap();
+        tokens.save_snapshot(snapshot_dir.path()).unwrap();
+        IdempotencyTokens::restore_snapshot(snapshot_dir.path()).unwrap();
+        let restored =
+            IdempotencyTokens::open(snapshot_dir.path(), Duration::from_secs(60)).unwrap();
+        assert_eq!(restored.get("retry").unwrap(), Some(10));
+    }
+}
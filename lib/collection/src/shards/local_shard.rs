@@ -1,23 +1,30 @@
 use std::collections::hash_map::Entry;
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::mem::size_of;
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::thread;
 
 use arc_swap::ArcSwap;
+use common::types::TelemetryDetail;
+use futures::future::{self, BoxFuture};
+use futures::FutureExt as _;
 use indicatif::{ProgressBar, ProgressStyle};
+use io::file_operations::{atomic_save_json, read_json};
 use itertools::Itertools;
 use parking_lot::{Mutex as ParkingMutex, RwLock};
+use segment::common::operation_time_statistics::OperationDurationsAggregator;
 use segment::data_types::vectors::VectorElementType;
 use segment::entry::entry_point::SegmentEntry as _;
+use segment::index::field_index::stats::FieldIndexStats;
 use segment::index::field_index::CardinalityEstimation;
 use segment::segment::Segment;
 use segment::segment_constructor::{build_segment, load_segment};
 use segment::types::{
-    CompressionRatio, Filter, PayloadIndexInfo, PayloadKeyType, PayloadStorageType, PointIdType,
-    QuantizationConfig, SegmentConfig, SegmentType,
+    CompressionRatio, Filter, PayloadIndexInfo, PayloadKeyType, PayloadKeyTypeRef,
+    PayloadStorageType, PointIdType, QuantizationConfig, SegmentConfig, SegmentType, SeqNumberType,
 };
 use segment::utils::mem::Mem;
 use tokio::fs::{copy, create_dir_all, remove_dir_all};
@@ -28,16 +35,27 @@ use wal::{Wal, WalOptions};
 
 use super::update_tracker::UpdateTracker;
 use crate::collection_manager::collection_updater::CollectionUpdater;
-use crate::collection_manager::holders::segment_holder::{LockedSegment, SegmentHolder};
+use crate::collection_manager::holders::segment_holder::{
+    LockedSegment, SegmentHolder, SegmentVersions,
+};
 use crate::collection_manager::optimizers::TrackerLog;
+use crate::collection_manager::segments_updater::check_upsert_condition;
 use crate::common::file_utils::move_dir;
 use crate::config::CollectionConfig;
+use crate::operations::clock_ops::ClockTag;
+use crate::operations::point_ops::UpsertCondition;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{
-    CollectionError, CollectionInfo, CollectionResult, CollectionStatus, OptimizersStatus,
+    CollectionError, CollectionInfo, CollectionResult, CollectionStatus, OptimizationsInfo,
+    OptimizersStatus, ShardSegmentInfo, ShardSegmentsInfo, ShardWalInfo,
 };
 use crate::operations::CollectionUpdateOperations;
-use crate::optimizers_builder::{build_optimizers, clear_temp_segments};
+use crate::optimizers_builder::{
+    build_forced_optimizers, build_optimizers, build_rebuild_index_optimizer, clear_temp_segments,
+    OptimizeTarget, RebuildIndexTarget,
+};
+use crate::shards::clocks::PointClocks;
+use crate::shards::idempotency_tokens::IdempotencyTokens;
 use crate::shards::shard::ShardId;
 use crate::shards::shard_config::{ShardConfig, SHARD_CONFIG_FILE};
 use crate::shards::telemetry::{LocalShardTelemetry, OptimizerTelemetry};
@@ -47,6 +65,18 @@ use crate::wal::SerdeWal;
 
 pub type LockedWal = Arc<ParkingMutex<SerdeWal<CollectionUpdateOperations>>>;
 
+/// Numbered operations, read from WAL, and the number of the next operation after them
+pub type WalOperations = (
+    Vec<(SeqNumberType, CollectionUpdateOperations)>,
+    SeqNumberType,
+);
+
+/// Versions of the segments in the shard snapshot, including ones not archived into it
+pub const SEGMENT_VERSIONS_FILE: &str = "segment_versions.json";
+
+/// Number of the next operation after the WAL in the shard snapshot
+pub const WAL_POSITION_FILE: &str = "wal_position.json";
+
 /// LocalShard
 ///
 /// LocalShard is an entity that can be moved between peers and contains some part of one collections data.
@@ -61,9 +91,13 @@ pub struct LocalShard {
     pub(super) update_sender: ArcSwap<Sender<UpdateSignal>>,
     pub(super) update_tracker: UpdateTracker,
     pub(super) path: PathBuf,
-    pub(super) optimizers: Arc<Vec<Arc<Optimizer>>>,
+    pub(super) optimizers: ArcSwap<Vec<Arc<Optimizer>>>,
     pub(super) optimizers_log: Arc<ParkingMutex<TrackerLog>>,
+    /// Background optimizations are suspended, until resumed or the shard is reloaded
+    optimizers_paused: Arc<AtomicBool>,
     update_runtime: Handle,
+    pub(super) telemetry_search_durations: Arc<ParkingMutex<OperationDurationsAggregator>>,
+    pub(super) telemetry_update_durations: Arc<ParkingMutex<OperationDurationsAggregator>>,
 }
 
 /// Shard holds information about segments and WAL.
@@ -118,6 +152,7 @@ impl LocalShard {
         let config = collection_config.read().await;
         let locked_wal = Arc::new(ParkingMutex::new(wal));
         let optimizers_log = Arc::new(ParkingMutex::new(Default::default()));
+        let optimizers_paused = Arc::new(AtomicBool::new(false));
 
         let mut update_handler = UpdateHandler::new(
             shared_storage_config.clone(),
@@ -128,11 +163,14 @@ impl LocalShard {
             locked_wal.clone(),
             config.optimizer_config.flush_interval_sec,
             config.optimizer_config.max_optimization_threads,
+            config.wal_config.wal_durability,
+            config.wal_config.fsync_interval(),
+            optimizers_paused.clone(),
         );
 
         let (update_sender, update_receiver) =
             mpsc::channel(shared_storage_config.update_queue_size);
-        update_handler.run_workers(update_receiver);
+        update_handler.run_workers(update_sender.clone(), update_receiver);
 
         let update_tracker = segment_holder.read().update_tracker();
 
@@ -148,8 +186,11 @@ impl LocalShard {
             update_tracker,
             path: shard_path.to_owned(),
             update_runtime,
-            optimizers,
+            optimizers: ArcSwap::new(optimizers),
             optimizers_log,
+            optimizers_paused,
+            telemetry_search_durations: OperationDurationsAggregator::new(),
+            telemetry_update_durations: OperationDurationsAggregator::new(),
         }
     }
 
@@ -176,7 +217,9 @@ impl LocalShard {
             wal_path.to_str().unwrap(),
             (&collection_config_read.wal_config).into(),
         )
-        .map_err(|e| CollectionError::service_error(format!("Wal error: {e}")))?;
+        .map_err(|e| CollectionError::service_error(format!("Wal error: {e}")))?
+        .with_compression(collection_config_read.wal_config.wal_compression)
+        .with_retention(collection_config_read.wal_config.retention());
 
         let segment_dirs = std::fs::read_dir(&segments_path).map_err(|err| {
             CollectionError::service_error(format!(
@@ -219,10 +262,13 @@ impl LocalShard {
                 ))
             })??;
 
-            let Some(segment) = segment else {
+            let Some(mut segment) = segment else {
                 continue;
             };
 
+            // Vectors might have been added or removed without the segment being updated
+            sync_segment_vectors(&mut segment, &collection_config_read)?;
+
             collection_config_read
                 .params
                 .vectors
@@ -231,6 +277,15 @@ impl LocalShard {
             segment_holder.add(segment);
         }
 
+        segment_holder.point_clocks = Some(Arc::new(PointClocks::open(
+            shard_path,
+            shared_storage_config.deleted_point_clocks_retention,
+        )?));
+        segment_holder.idempotency_tokens = Some(Arc::new(IdempotencyTokens::open(
+            shard_path,
+            shared_storage_config.idempotency_token_retention,
+        )?));
+
         let res = segment_holder.deduplicate_points()?;
         if res > 0 {
             log::debug!("Deduplicated {} points", res);
@@ -350,12 +405,14 @@ impl LocalShard {
         let mut build_handlers = vec![];
 
         let vector_params = config.params.into_base_vector_data()?;
+        let sparse_vector_params = config.params.into_sparse_vector_data()?;
         let segment_number = config.optimizer_config.get_number_segments();
 
         for _sid in 0..segment_number {
             let path_clone = segments_path.clone();
             let segment_config = SegmentConfig {
                 vector_data: vector_params.clone(),
+                sparse_vector_data: sparse_vector_params.clone(),
                 payload_storage_type: if config.params.on_disk_payload {
                     PayloadStorageType::OnDisk
                 } else {
@@ -388,8 +445,19 @@ impl LocalShard {
             segment_holder.add(segment);
         }
 
+        segment_holder.point_clocks = Some(Arc::new(PointClocks::open(
+            shard_path,
+            shared_storage_config.deleted_point_clocks_retention,
+        )?));
+        segment_holder.idempotency_tokens = Some(Arc::new(IdempotencyTokens::open(
+            shard_path,
+            shared_storage_config.idempotency_token_retention,
+        )?));
+
         let wal: SerdeWal<CollectionUpdateOperations> =
-            SerdeWal::new(wal_path.to_str().unwrap(), (&config.wal_config).into())?;
+            SerdeWal::new(wal_path.to_str().unwrap(), (&config.wal_config).into())?
+                .with_compression(config.wal_config.wal_compression)
+                .with_retention(config.wal_config.retention());
 
         let optimizers = build_optimizers(
             shard_path,
@@ -494,11 +562,27 @@ impl LocalShard {
         let (update_sender, update_receiver) =
             mpsc::channel(self.shared_storage_config.update_queue_size);
         // makes sure that the Stop signal is the last one in this channel
-        let old_sender = self.update_sender.swap(Arc::new(update_sender));
+        let old_sender = self.update_sender.swap(Arc::new(update_sender.clone()));
         old_sender.send(UpdateSignal::Stop).await?;
         update_handler.stop_flush_worker();
 
         update_handler.wait_workers_stops().await?;
+
+        // No updates or optimizations are running now, bring segments in line with added or
+        // removed vectors
+        for (_, segment) in self.segments.read().iter() {
+            match segment {
+                LockedSegment::Original(segment) => {
+                    sync_segment_vectors(&mut segment.write(), &config)?;
+                }
+                LockedSegment::Proxy(_) => {
+                    return Err(CollectionError::service_error(
+                        "Proxy segment found while optimizers are stopped",
+                    ));
+                }
+            }
+        }
+
         let new_optimizers = build_optimizers(
             &self.path,
             &config.params,
@@ -506,9 +590,10 @@ impl LocalShard {
             &config.hnsw_config,
             &config.quantization_config,
         );
+        self.optimizers.store(new_optimizers.clone());
         update_handler.optimizers = new_optimizers;
         update_handler.flush_interval_sec = config.optimizer_config.flush_interval_sec;
-        update_handler.run_workers(update_receiver);
+        update_handler.run_workers(update_sender, update_receiver);
         self.update_sender.load().send(UpdateSignal::Nop).await?;
 
         Ok(())
@@ -547,15 +632,51 @@ impl LocalShard {
                 std::fs::remove_file(&entry_path)?;
             }
         }
+
+        PointClocks::restore_snapshot(snapshot_path)?;
+        IdempotencyTokens::restore_snapshot(snapshot_path)?;
+
+        for file in [SEGMENT_VERSIONS_FILE, WAL_POSITION_FILE] {
+            let file_path = snapshot_path.join(file);
+            if file_path.exists() {
+                std::fs::remove_file(file_path)?;
+            }
+        }
         Ok(())
     }
 
+    /// Versions of the segments, saved by [`LocalShard::create_snapshot`] into the snapshot
+    pub fn load_snapshot_segment_versions(
+        snapshot_path: &Path,
+    ) -> CollectionResult<Option<SegmentVersions>> {
+        let segment_versions_path = snapshot_path.join(SEGMENT_VERSIONS_FILE);
+        if !segment_versions_path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(read_json(&segment_versions_path)?))
+    }
+
+    /// Number of the next operation after the WAL, saved by [`LocalShard::snapshot_wal`] into the snapshot.
+    /// The snapshot contains all operations before it.
+    pub fn load_snapshot_wal_position(
+        snapshot_path: &Path,
+    ) -> CollectionResult<Option<SeqNumberType>> {
+        let wal_position_path = snapshot_path.join(WAL_POSITION_FILE);
+        if !wal_position_path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(read_json(&wal_position_path)?))
+    }
+
     /// Create snapshot for local shard into `target_path`
+    ///
+    /// Segments, which didn't change since `base_segments` were taken, are not included.
     pub async fn create_snapshot(
         &self,
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base_segments: Option<&SegmentVersions>,
     ) -> CollectionResult<()> {
         let snapshot_shard_path = target_path;
 
@@ -578,12 +699,27 @@ impl LocalShard {
         }
 
         let temp_path = temp_path.to_owned();
+        let base_segments = base_segments.cloned();
 
         tokio::task::spawn_blocking(move || {
             let segments_read = segments.read();
 
             // Do not change segments while snapshotting
-            segments_read.snapshot_all_segments(&temp_path, &snapshot_segments_shard_path)?;
+            let segment_versions = segments_read.snapshot_all_segments(
+                &temp_path,
+                &snapshot_segments_shard_path,
+                base_segments.as_ref(),
+            )?;
+            atomic_save_json(
+                &snapshot_shard_path_owned.join(SEGMENT_VERSIONS_FILE),
+                &segment_versions,
+            )?;
+            if let Some(point_clocks) = &segments_read.point_clocks {
+                point_clocks.save_snapshot(&snapshot_shard_path_owned)?;
+            }
+            if let Some(idempotency_tokens) = &segments_read.idempotency_tokens {
+                idempotency_tokens.save_snapshot(&snapshot_shard_path_owned)?;
+            }
 
             if save_wal {
                 // snapshot all shard's WAL
@@ -635,6 +771,7 @@ impl LocalShard {
     /// snapshot WAL
     ///
     /// copies all WAL files into `snapshot_shard_path/wal`
+    /// and saves the number of the next operation after them
     pub fn snapshot_wal(wal: LockedWal, snapshot_shard_path: &Path) -> CollectionResult<()> {
         // lock wal during snapshot
         let mut wal_guard = wal.lock();
@@ -646,6 +783,8 @@ impl LocalShard {
                 "Error while copy WAL {snapshot_shard_path:?} {err}"
             ))
         })?;
+        let wal_position: SeqNumberType = wal_guard.first_index() + wal_guard.len();
+        atomic_save_json(&snapshot_shard_path.join(WAL_POSITION_FILE), &wal_position)?;
         Ok(())
     }
 
@@ -690,7 +829,30 @@ impl LocalShard {
         Ok(all_points)
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
+    /// Statistics of the payload index of `field` merged over all segments,
+    /// `None` if none of the segments has the index
+    pub fn get_field_index_stats(&self, field: PayloadKeyTypeRef) -> Option<FieldIndexStats> {
+        self.segments
+            .read()
+            .iter()
+            .filter_map(|(_id, segment)| segment.get().read().get_field_index_stats(field))
+            .reduce(|mut stats, segment_stats| {
+                stats.merge(segment_stats);
+                stats
+            })
+    }
+
+    /// Version of the data of the shard, it is changed by every applied update operation
+    pub fn data_version(&self) -> SeqNumberType {
+        self.segments
+            .read()
+            .iter()
+            .map(|(_id, segment)| segment.get().read().version())
+            .max()
+            .unwrap_or_default()
+    }
+
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> LocalShardTelemetry {
         let segments_read_guard = self.segments.read();
         let segments: Vec<_> = segments_read_guard
             .iter()
@@ -702,11 +864,13 @@ impl LocalShard {
             Some(error) => OptimizersStatus::Error(error.to_string()),
         };
         drop(segments_read_guard);
-        let optimizations = self
-            .optimizers
+        let optimizers = self.optimizers.load();
+        let optimizations = optimizers
             .iter()
             .map(|optimizer| optimizer.get_telemetry_data())
             .fold(Default::default(), |acc, x| acc + x);
+        let pending = UpdateHandler::pending_optimizations(&optimizers, self.segments.clone());
+        let wal_size_bytes = fs_extra::dir::get_size(self.wal.lock().path()).ok();
 
         LocalShardTelemetry {
             variant_name: None,
@@ -715,7 +879,17 @@ impl LocalShard {
                 status: optimizer_status,
                 optimizations,
                 log: self.optimizers_log.lock().to_telemetry(),
+                pending,
             },
+            wal_size_bytes,
+            searches: self
+                .telemetry_search_durations
+                .lock()
+                .get_statistics_with_detail(detail),
+            updates: self
+                .telemetry_update_durations
+                .lock()
+                .get_statistics_with_detail(detail),
         }
     }
 
@@ -795,6 +969,19 @@ impl LocalShard {
             None => OptimizersStatus::Ok,
             Some(error) => OptimizersStatus::Error(error.to_string()),
         };
+        drop(segments);
+
+        let pending =
+            UpdateHandler::pending_optimizations(&self.optimizers.load(), self.segments.clone());
+        let optimizations = {
+            let optimizers_log = self.optimizers_log.lock();
+            OptimizationsInfo {
+                paused: self.optimizers_paused(),
+                running: optimizers_log.running(),
+                pending,
+                last_error: optimizers_log.last_error(),
+            }
+        };
 
         CollectionInfo {
             status,
@@ -805,12 +992,288 @@ impl LocalShard {
             segments_count,
             config: collection_config,
             payload_schema: schema,
+            optimizations,
         }
     }
 
     pub fn update_tracker(&self) -> &UpdateTracker {
         &self.update_tracker
     }
+
+    /// Operations from WAL, starting from the `from` operation number, and the number of the next
+    /// operation after them.
+    ///
+    /// If `from` is not specified, reads from the earliest operation, which is still stored.
+    pub fn read_wal_operations(
+        &self,
+        from: Option<SeqNumberType>,
+        limit: usize,
+    ) -> CollectionResult<WalOperations> {
+        let wal = self.wal.lock();
+        let first_index = wal.first_stored_index();
+        let next_index = wal.first_index() + wal.len();
+        let from = from.unwrap_or(first_index);
+        if from < first_index {
+            return Err(CollectionError::bad_request(format!(
+                "Operations before {first_index} are no longer stored in WAL, requested {from}"
+            )));
+        }
+        if from > next_index {
+            return Err(CollectionError::bad_request(format!(
+                "Operation {from} is ahead of WAL, next operation is {next_index}"
+            )));
+        }
+
+        let operations: Vec<_> = wal.read(from).take(limit).collect();
+        let next = operations.last().map_or(from, |(op_num, _)| op_num + 1);
+        Ok((operations, next))
+    }
+
+    /// Number of the first operation, which is still stored in WAL, and of the next operation after WAL
+    pub fn stored_wal_range(&self) -> (SeqNumberType, SeqNumberType) {
+        let wal = self.wal.lock();
+        (wal.first_stored_index(), wal.first_index() + wal.len())
+    }
+
+    /// Number of the first operation in WAL, written after the `timestamp` (milliseconds since the epoch)
+    pub fn wal_index_after(&self, timestamp: i64) -> SeqNumberType {
+        self.wal.lock().first_index_after(timestamp)
+    }
+
+    pub fn wal_info(&self, shard_id: ShardId) -> CollectionResult<ShardWalInfo> {
+        let wal = self.wal.lock();
+        let first_stored_index = wal.first_stored_index();
+        let first_index = wal.first_index();
+        let disk_size_bytes = fs_extra::dir::get_size(wal.path()).map_err(|err| {
+            CollectionError::service_error(format!(
+                "Can't get size of WAL {}: {err}",
+                wal.path().display()
+            ))
+        })?;
+        Ok(ShardWalInfo {
+            shard_id,
+            first_stored_operation: first_stored_index,
+            first_replayed_operation: first_index,
+            next_operation: first_index + wal.len(),
+            retained_operations: first_index.saturating_sub(first_stored_index),
+            disk_size_bytes,
+        })
+    }
+
+    /// Details of the segments of the shard.
+    /// Segments under optimization are described by the segment, which is being optimized.
+    pub fn segments_info(&self, shard_id: ShardId) -> ShardSegmentsInfo {
+        let segments: Vec<_> = self
+            .segments
+            .read()
+            .iter()
+            .filter_map(|(segment_id, segment)| match segment {
+                LockedSegment::Original(segment) => Some((*segment_id, segment.clone(), false)),
+                LockedSegment::Proxy(proxy) => match &proxy.read().wrapped_segment {
+                    LockedSegment::Original(segment) => Some((*segment_id, segment.clone(), true)),
+                    LockedSegment::Proxy(_) => None,
+                },
+            })
+            .collect();
+
+        // Segment details are read from disk, so the segment holder is not locked meanwhile
+        let mut segments: Vec<_> = segments
+            .into_iter()
+            .map(|(id, segment, under_optimization)| {
+                let segment = segment.read();
+                let name = segment
+                    .current_path
+                    .file_name()
+                    .map(|name| name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                ShardSegmentInfo {
+                    id,
+                    name,
+                    under_optimization,
+                    details: segment.details(),
+                }
+            })
+            .collect();
+        segments.sort_by_key(|segment| segment.id);
+
+        ShardSegmentsInfo { shard_id, segments }
+    }
+
+    /// Remove applied operations, which are kept in WAL within the retention limits
+    pub fn truncate_retained_wal(&self) -> CollectionResult<()> {
+        self.wal.lock().truncate_retained()?;
+        Ok(())
+    }
+
+    /// Launch optimizations of the segments right away, instead of waiting for the optimizer
+    /// thresholds to be reached.
+    ///
+    /// Returned future resolves once the launched optimizations are finished.
+    pub async fn launch_forced_optimization(
+        &self,
+        target: Option<OptimizeTarget>,
+    ) -> BoxFuture<'static, CollectionResult<()>> {
+        if self.optimizers_paused() {
+            return future::ready(Err(optimizers_paused_error())).boxed();
+        }
+        if target == Some(OptimizeTarget::RebuildIndex) {
+            return self
+                .launch_index_rebuild(RebuildIndexTarget::All, None)
+                .await;
+        }
+        let mut finished = {
+            let config = self.collection_config.read().await;
+            let optimizers = build_forced_optimizers(
+                &self.path,
+                &config.params,
+                &config.optimizer_config,
+                &config.hnsw_config,
+                &config.quantization_config,
+                target,
+            );
+            let update_handler = self.update_handler.lock().await;
+            update_handler.launch_forced_optimization(optimizers).await
+        };
+        let segments = self.segments.clone();
+        async move {
+            while finished.recv().await.is_some() {}
+            let optimizer_error = segments.read().optimizer_errors.clone();
+            optimizer_error.map_or(Ok(()), Err)
+        }
+        .boxed()
+    }
+
+    /// Rebuild indexes of the segments right away, one segment after another.
+    ///
+    /// Segments to rebuild are selected once, so segments created by the rebuild are not rebuilt
+    /// again. Returned future resolves once all of them are rebuilt, or the optimizers are paused.
+    pub async fn launch_index_rebuild(
+        &self,
+        target: RebuildIndexTarget,
+        field_name: Option<PayloadKeyType>,
+    ) -> BoxFuture<'static, CollectionResult<()>> {
+        if self.optimizers_paused() {
+            return future::ready(Err(optimizers_paused_error())).boxed();
+        }
+        let optimizer = {
+            let config = self.collection_config.read().await;
+            build_rebuild_index_optimizer(
+                &self.path,
+                &config.params,
+                &config.optimizer_config,
+                &config.hnsw_config,
+                &config.quantization_config,
+                target,
+                field_name,
+            )
+        };
+        let segment_ids = optimizer.candidates(self.segments.clone(), &HashSet::new());
+        let segments = self.segments.clone();
+        let update_handler = self.update_handler.clone();
+        let optimizers_paused = self.optimizers_paused.clone();
+        async move {
+            for segment_id in segment_ids {
+                if optimizers_paused.load(Ordering::Relaxed) {
+                    return Err(optimizers_paused_error());
+                }
+                // Segment is skipped, if it was optimized in the meantime
+                let segment_optimizer: Arc<Optimizer> = Arc::new(optimizer.for_segment(segment_id));
+                let mut finished = update_handler
+                    .lock()
+                    .await
+                    .launch_forced_optimization(Arc::new(vec![segment_optimizer]))
+                    .await;
+                while finished.recv().await.is_some() {}
+                if let Some(optimizer_error) = segments.read().optimizer_errors.clone() {
+                    return Err(optimizer_error);
+                }
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+
+    pub fn optimizers_paused(&self) -> bool {
+        self.optimizers_paused.load(Ordering::Relaxed)
+    }
+
+    /// Suspend background optimizations of the shard. Running optimizations are cancelled,
+    /// their segments are left as they were before the optimization.
+    pub async fn pause_optimizers(&self) -> CollectionResult<()> {
+        self.optimizers_paused.store(true, Ordering::Relaxed);
+        self.update_handler.lock().await.stop_optimizations().await
+    }
+
+    /// Resume background optimizations, suspended by [`LocalShard::pause_optimizers`]
+    pub async fn resume_optimizers(&self) -> CollectionResult<()> {
+        self.optimizers_paused.store(false, Ordering::Relaxed);
+        // Check optimizer conditions right away, instead of waiting for the next update
+        self.update_sender.load().send(UpdateSignal::Nop).await?;
+        Ok(())
+    }
+
+    /// Clocks of the last changes of the points. Points, changed without a clock, are skipped.
+    pub fn point_clock_tags(
+        &self,
+        point_ids: impl IntoIterator<Item = PointIdType>,
+    ) -> CollectionResult<Vec<(PointIdType, ClockTag)>> {
+        let Some(point_clocks) = self.segments.read().point_clocks.clone() else {
+            return Ok(Vec::new());
+        };
+        let mut clock_tags = Vec::new();
+        for point_id in point_ids {
+            if let Some(clock_tag) = point_clocks.get(point_id)? {
+                clock_tags.push((point_id, clock_tag));
+            }
+        }
+        Ok(clock_tags)
+    }
+
+    /// Check the condition of a conditional upsert against the points, stored in this shard
+    pub fn check_upsert_condition(
+        &self,
+        point_ids: &[PointIdType],
+        condition: UpsertCondition,
+    ) -> CollectionResult<()> {
+        check_upsert_condition(&self.segments.read(), point_ids, condition)
+    }
+}
+
+fn optimizers_paused_error() -> CollectionError {
+    CollectionError::bad_request("Optimizers of the collection are paused".to_string())
+}
+
+/// Bring named vectors of the given segment in line with the collection config
+///
+/// Vectors can be added to or removed from an existing collection, segments built before that
+/// still have the old set of vectors. Data of removed vectors stays on disk until the segment is
+/// rebuilt by the config mismatch optimizer.
+fn sync_segment_vectors(segment: &mut Segment, config: &CollectionConfig) -> CollectionResult<()> {
+    let segment_config = segment.config().clone();
+    let dropped_vectors = segment_config
+        .vector_data
+        .keys()
+        .chain(segment_config.sparse_vector_data.keys())
+        .filter(|vector_name| {
+            let is_sparse = config
+                .params
+                .sparse_vectors
+                .as_ref()
+                .map_or(false, |sparse_vectors| {
+                    sparse_vectors.contains_key(*vector_name)
+                });
+            !is_sparse && config.params.vectors.get_params(vector_name).is_none()
+        });
+    for vector_name in dropped_vectors {
+        segment.drop_vector_data(vector_name)?;
+    }
+
+    for (vector_name, vector_config) in config.params.into_base_vector_data()? {
+        if !segment.config().vector_data.contains_key(&vector_name) {
+            segment.create_vector_data(&vector_name, &vector_config)?;
+        }
+    }
+    Ok(())
 }
 
 impl Drop for LocalShard {
@@ -1,16 +1,23 @@
+use std::collections::HashMap;
+use std::ops::Bound;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use futures::future::try_join_all;
 use itertools::Itertools;
+use segment::common::operation_time_statistics::ScopeDurationMeasurer;
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 use tokio::sync::oneshot;
+use tokio::time::Instant;
 
 use crate::collection_manager::segments_searcher::SegmentsSearcher;
 use crate::common::stopping_guard::StoppingGuard;
+use crate::config::CollectionParams;
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest,
     CountResult, PointRequest, Record, SearchRequestBatch, UpdateResult, UpdateStatus,
@@ -22,11 +29,11 @@ use crate::shards::shard_trait::ShardOperation;
 use crate::update_handler::{OperationData, UpdateSignal};
 
 impl LocalShard {
-    async fn do_search(
+    /// Collection params and indexing threshold to search with, checks the vector names of the request
+    async fn search_params(
         &self,
-        core_request: Arc<CoreSearchRequestBatch>,
-        search_runtime_handle: &Handle,
-    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        core_request: &CoreSearchRequestBatch,
+    ) -> CollectionResult<(CollectionParams, usize)> {
         let (collection_params, indexing_threshold_kb) = {
             let collection_config = self.collection_config.read().await;
             (
@@ -40,9 +47,25 @@ impl LocalShard {
 
         // check vector names existing
         for req in &core_request.searches {
-            collection_params.get_vector_params(req.query.get_vector_name())?;
+            if let Some(vector_name) = req.query.get_vector_name() {
+                collection_params.get_distance(vector_name)?;
+            }
         }
 
+        Ok((collection_params, indexing_threshold_kb))
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    async fn do_search(
+        &self,
+        core_request: Arc<CoreSearchRequestBatch>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_search_durations);
+        timer.set_success(false);
+
+        let (collection_params, indexing_threshold_kb) = self.search_params(&core_request).await?;
+
         let is_stopped = StoppingGuard::new();
 
         let search_request = SegmentsSearcher::search(
@@ -58,39 +81,241 @@ impl LocalShard {
             res = search_request => res,
             _ = tokio::time::sleep(timeout) => {
                 is_stopped.stop();
-                log::debug!("Search timeout reached: {} seconds", timeout.as_secs());
-                Err(CollectionError::timeout(timeout.as_secs() as usize, "Search"))
+                log::debug!("Search timeout reached: {timeout:?}");
+                Err(CollectionError::timeout(timeout, "Search"))
             }
         }?;
 
-        let top_results = res
+        let top_results = postprocess_search_results(&collection_params, &core_request, res);
+        timer.set_success(true);
+        Ok(top_results)
+    }
+
+    /// Search until the deadline, segments which are not searched in time are left out of the results.
+    ///
+    /// The searches are stopped by `is_stopped` or once the deadline is reached.
+    /// Returns the results along with the flag whether any segment is left out.
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "debug", skip_all))]
+    pub async fn search_until(
+        &self,
+        core_request: Arc<CoreSearchRequestBatch>,
+        search_runtime_handle: &Handle,
+        deadline: Instant,
+        is_stopped: Arc<AtomicBool>,
+    ) -> CollectionResult<(Vec<Vec<ScoredPoint>>, bool)> {
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_search_durations);
+        timer.set_success(false);
+
+        let (collection_params, indexing_threshold_kb) = self.search_params(&core_request).await?;
+
+        // Search timeout of the shard still applies, if it is reached before the deadline
+        let deadline = deadline.min(Instant::now() + self.shared_storage_config.search_timeout);
+        let (res, partial) = SegmentsSearcher::search_until(
+            self.segments.clone(),
+            core_request.clone(),
+            search_runtime_handle,
+            true,
+            is_stopped,
+            indexing_threshold_kb,
+            Some(deadline),
+        )
+        .await?;
+
+        let top_results = postprocess_search_results(&collection_params, &core_request, res);
+        timer.set_success(true);
+        Ok((top_results, partial))
+    }
+
+    /// Scroll in the order of values of the `order_by` key, instead of the order of IDs
+    async fn scroll_by_field(
+        &self,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        search_runtime_handle: &Handle,
+        order_by: &OrderBy,
+    ) -> CollectionResult<Vec<Record>> {
+        let segments = self.segments();
+        let read_handles: Vec<_> = {
+            let segments_guard = segments.read();
+            segments_guard
+                .iter()
+                .map(|(_, segment)| {
+                    let segment = segment.clone();
+                    let filter = filter.cloned();
+                    let order_by = order_by.clone();
+                    search_runtime_handle.spawn_blocking(move || {
+                        segment.get().read().read_ordered_filtered(
+                            Some(limit),
+                            filter.as_ref(),
+                            &order_by,
+                        )
+                    })
+                })
+                .collect()
+        };
+        let all_points = try_join_all(read_handles)
+            .await?
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let direction = order_by.direction();
+        let ordered_points = all_points
             .into_iter()
-            .zip(core_request.searches.iter())
-            .map(|(vector_res, req)| {
-                let vector_name = req.query.get_vector_name();
-                let distance = collection_params
-                    .get_vector_params(vector_name)
-                    .unwrap()
-                    .distance;
-                let processed_res = vector_res.into_iter().map(|mut scored_point| {
-                    scored_point.score = distance.postprocess_score(scored_point.score);
-                    scored_point
-                });
-
-                if let Some(threshold) = req.score_threshold {
-                    processed_res
-                        .take_while(|scored_point| {
-                            distance.check_threshold(scored_point.score, threshold)
-                        })
-                        .collect()
-                } else {
-                    processed_res.collect()
-                }
+            .flatten()
+            .sorted_by(|(value_a, id_a), (value_b, id_b)| {
+                direction.compare(*value_a, *value_b).then(id_a.cmp(id_b))
+            })
+            // Point might be found in several segments, while it is moved by the optimizer
+            .unique_by(|(_value, id)| *id)
+            .take(limit)
+            .collect_vec();
+
+        let point_ids = ordered_points.iter().map(|(_value, id)| *id).collect_vec();
+        let with_payload = WithPayload::from(with_payload_interface);
+        let mut records: HashMap<_, _> =
+            SegmentsSearcher::retrieve(segments, &point_ids, &with_payload, with_vector)?
+                .into_iter()
+                .map(|record| (record.id, record))
+                .collect();
+
+        let points = ordered_points
+            .into_iter()
+            .filter_map(|(value, id)| {
+                let mut record = records.remove(&id)?;
+                record.order_value = Some(value);
+                Some(record)
             })
             .collect();
-        Ok(top_results)
+        Ok(points)
     }
+
+    /// Read sorted IDs of the points, matching the filter, starting from the `offset` ID
+    async fn read_filtered_ids(
+        &self,
+        offset: Option<ExtendedPointId>,
+        limit: usize,
+        filter: Option<&Filter>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Vec<ExtendedPointId>> {
+        // ToDo: Make faster points selection with a set
+        let read_handles: Vec<_> = {
+            let segments_guard = self.segments.read();
+            segments_guard
+                .iter()
+                .map(|(_, segment)| {
+                    let segment = segment.clone();
+                    let filter = filter.cloned();
+                    search_runtime_handle.spawn_blocking(move || {
+                        segment
+                            .get()
+                            .read()
+                            .read_filtered(offset, Some(limit), filter.as_ref())
+                    })
+                })
+                .collect()
+        };
+        let all_points = try_join_all(read_handles).await?;
+
+        Ok(all_points
+            .into_iter()
+            .flatten()
+            .sorted()
+            .dedup()
+            .take(limit)
+            .collect_vec())
+    }
+
+    /// Sorted IDs of all points, matching the filter.
+    ///
+    /// Pinned by stable scrolls, so that all pages show the points, which existed at the first one.
+    pub async fn read_all_filtered_ids(
+        &self,
+        filter: Option<&Filter>,
+        search_runtime_handle: &Handle,
+    ) -> CollectionResult<Vec<ExtendedPointId>> {
+        self.read_filtered_ids(None, usize::MAX, filter, search_runtime_handle)
+            .await
+    }
+
+    /// Read the pinned points of a stable scroll in the order of IDs, starting from `from`.
+    ///
+    /// `pinned_ids` must be sorted. Deleted points are skipped, changed ones are read in their
+    /// latest state.
+    pub fn scroll_pinned(
+        &self,
+        pinned_ids: &[ExtendedPointId],
+        from: Bound<ExtendedPointId>,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+    ) -> CollectionResult<Vec<Record>> {
+        let start = match from {
+            Bound::Included(id) => pinned_ids.partition_point(|pinned_id| *pinned_id < id),
+            Bound::Excluded(id) => pinned_ids.partition_point(|pinned_id| *pinned_id <= id),
+            Bound::Unbounded => 0,
+        };
+
+        let with_payload = WithPayload::from(with_payload_interface);
+        let mut points = Vec::with_capacity(limit);
+        for batch in pinned_ids[start..].chunks(limit.max(1)) {
+            let mut batch_points =
+                SegmentsSearcher::retrieve(self.segments(), batch, &with_payload, with_vector)?;
+            batch_points.sort_by_key(|point| point.id);
+            points.extend(batch_points);
+            if points.len() >= limit {
+                break;
+            }
+        }
+        points.truncate(limit);
+        Ok(points)
+    }
+}
+
+/// Postprocess the scores of the search results and apply the score thresholds of the requests
+fn postprocess_search_results(
+    collection_params: &CollectionParams,
+    core_request: &CoreSearchRequestBatch,
+    res: Vec<Vec<ScoredPoint>>,
+) -> Vec<Vec<ScoredPoint>> {
+    res.into_iter()
+        .zip(core_request.searches.iter())
+        .map(|(vector_res, req)| {
+            let Some(vector_name) = req
+                .query
+                .get_vector_name()
+                .filter(|_| req.query.is_distance_scored())
+            else {
+                // Text relevance, discovery, context and sample scores need no postprocessing,
+                // larger is better
+                return match req.score_threshold {
+                    Some(threshold) => vector_res
+                        .into_iter()
+                        .take_while(|scored_point| scored_point.score >= threshold)
+                        .collect(),
+                    None => vector_res,
+                };
+            };
+            let distance = collection_params.get_distance(vector_name).unwrap();
+            let processed_res = vector_res.into_iter().map(|mut scored_point| {
+                scored_point.score = distance.postprocess_score(scored_point.score);
+                scored_point
+            });
+
+            if let Some(threshold) = req.score_threshold {
+                processed_res
+                    .take_while(|scored_point| {
+                        distance.check_threshold(scored_point.score, threshold)
+                    })
+                    .collect()
+            } else {
+                processed_res.collect()
+            }
+        })
+        .collect()
 }
+
 #[async_trait]
 impl ShardOperation for LocalShard {
     /// Imply interior mutability.
@@ -101,6 +326,9 @@ impl ShardOperation for LocalShard {
         operation: CollectionUpdateOperations,
         wait: bool,
     ) -> CollectionResult<UpdateResult> {
+        let mut timer = ScopeDurationMeasurer::new(&self.telemetry_update_durations);
+        timer.set_success(false);
+
         let (callback_sender, callback_receiver) = if wait {
             let (tx, rx) = oneshot::channel();
             (Some(tx), Some(rx))
@@ -108,11 +336,21 @@ impl ShardOperation for LocalShard {
             (None, None)
         };
 
+        let wal_durability = self
+            .collection_config
+            .read()
+            .await
+            .wal_config
+            .wal_durability;
+
         let operation_id = {
             let update_sender = self.update_sender.load();
             let channel_permit = update_sender.reserve().await?;
             let mut wal_lock = self.wal.lock();
             let operation_id = wal_lock.write(&operation)?;
+            if wal_durability.sync_after_write(wal_lock.segment_filled_since_flush()) {
+                wal_lock.flush()?;
+            }
             channel_permit.send(UpdateSignal::Operation(OperationData {
                 op_num: operation_id,
                 operation,
@@ -123,15 +361,21 @@ impl ShardOperation for LocalShard {
         };
 
         if let Some(receiver) = callback_receiver {
-            let _res = receiver.await??;
+            let affected_points = receiver.await??;
+            timer.set_success(true);
             Ok(UpdateResult {
                 operation_id,
                 status: UpdateStatus::Completed,
+                affected_points: Some(affected_points),
+                ids: None,
             })
         } else {
+            timer.set_success(true);
             Ok(UpdateResult {
                 operation_id,
                 status: UpdateStatus::Acknowledged,
+                affected_points: None,
+                ids: None,
             })
         }
     }
@@ -144,35 +388,26 @@ impl ShardOperation for LocalShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
     ) -> CollectionResult<Vec<Record>> {
-        // ToDo: Make faster points selection with a set
-        let segments = self.segments();
-        let read_handles: Vec<_> = {
-            let segments_guard = segments.read();
-            segments_guard
-                .iter()
-                .map(|(_, segment)| {
-                    let segment = segment.clone();
-                    let filter = filter.cloned();
-                    search_runtime_handle.spawn_blocking(move || {
-                        segment
-                            .get()
-                            .read()
-                            .read_filtered(offset, Some(limit), filter.as_ref())
-                    })
-                })
-                .collect()
-        };
-        let all_points = try_join_all(read_handles).await?;
+        if let Some(order_by) = order_by {
+            return self
+                .scroll_by_field(
+                    limit,
+                    with_payload_interface,
+                    with_vector,
+                    filter,
+                    search_runtime_handle,
+                    order_by,
+                )
+                .await;
+        }
 
-        let point_ids = all_points
-            .into_iter()
-            .flatten()
-            .sorted()
-            .dedup()
-            .take(limit)
-            .collect_vec();
+        let point_ids = self
+            .read_filtered_ids(offset, limit, filter, search_runtime_handle)
+            .await?;
 
+        let segments = self.segments();
         let with_payload = WithPayload::from(with_payload_interface);
         let mut points =
             SegmentsSearcher::retrieve(segments, &point_ids, &with_payload, with_vector)?;
@@ -193,11 +428,9 @@ impl ShardOperation for LocalShard {
         request: Arc<SearchRequestBatch>,
         search_runtime_handle: &Handle,
     ) -> CollectionResult<Vec<Vec<ScoredPoint>>> {
-        self.do_search(
-            Arc::new(request.as_ref().clone().into()),
-            search_runtime_handle,
-        )
-        .await
+        let request: CoreSearchRequestBatch = request.as_ref().clone().try_into()?;
+        self.do_search(Arc::new(request), search_runtime_handle)
+            .await
     }
 
     // ! COPY-PASTE: `core_search` is a copy-paste of `search` with different request type
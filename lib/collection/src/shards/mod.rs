@@ -1,8 +1,10 @@
 pub mod channel_service;
+pub mod clocks;
 pub mod collection_shard_distribution;
 mod conversions;
 pub mod dummy_shard;
 pub mod forward_proxy_shard;
+pub mod idempotency_tokens;
 pub mod local_shard;
 pub mod local_shard_operations;
 pub mod proxy_shard;
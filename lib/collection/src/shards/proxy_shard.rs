@@ -5,15 +5,19 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
+use common::types::TelemetryDetail;
+use segment::data_types::order_by::OrderBy;
+use segment::index::field_index::stats::FieldIndexStats;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PayloadKeyTypeRef, PointIdType, ScoredPoint, WithPayload,
+    WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
 use tokio::sync::{oneshot, RwLock};
 use tokio::time::timeout;
 
 use super::update_tracker::UpdateTracker;
+use crate::collection_manager::holders::segment_holder::SegmentVersions;
 use crate::operations::operation_effect::{
     EstimateOperationEffectArea, OperationEffectArea, PointsOperationEffect,
 };
@@ -36,7 +40,7 @@ type ChangedPointsSet = Arc<RwLock<HashSet<PointIdType>>>;
 /// It can be used to provide all read and write operations while the wrapped shard is being transferred to another node.
 /// It keeps track of changed points during the shard transfer to assure consistency.
 pub struct ProxyShard {
-    wrapped_shard: LocalShard,
+    pub(crate) wrapped_shard: LocalShard,
     changed_points: ChangedPointsSet,
     pub changed_alot: AtomicBool,
 }
@@ -68,9 +72,10 @@ impl ProxyShard {
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base_segments: Option<&SegmentVersions>,
     ) -> CollectionResult<()> {
         self.wrapped_shard
-            .create_snapshot(temp_path, target_path, save_wal)
+            .create_snapshot(temp_path, target_path, save_wal, base_segments)
             .await
     }
 
@@ -117,8 +122,12 @@ impl ProxyShard {
         Ok(())
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
-        self.wrapped_shard.get_telemetry_data()
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> LocalShardTelemetry {
+        self.wrapped_shard.get_telemetry_data(detail)
+    }
+
+    pub fn get_field_index_stats(&self, field: PayloadKeyTypeRef) -> Option<FieldIndexStats> {
+        self.wrapped_shard.get_field_index_stats(field)
     }
 
     pub fn update_tracker(&self) -> &UpdateTracker {
@@ -181,6 +190,7 @@ impl ShardOperation for ProxyShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
     ) -> CollectionResult<Vec<Record>> {
         let local_shard = &self.wrapped_shard;
         local_shard
@@ -191,6 +201,7 @@ impl ShardOperation for ProxyShard {
                 with_vector,
                 filter,
                 search_runtime_handle,
+                order_by,
             )
             .await
     }
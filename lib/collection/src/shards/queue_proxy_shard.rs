@@ -3,14 +3,19 @@ use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common::types::TelemetryDetail;
+use segment::data_types::order_by::OrderBy;
+use segment::index::field_index::stats::FieldIndexStats;
 use segment::types::{
-    ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
+    ExtendedPointId, Filter, PayloadKeyTypeRef, ScoredPoint, WithPayload, WithPayloadInterface,
+    WithVector,
 };
 use tokio::runtime::Handle;
 use tokio::sync::Mutex;
 
 use super::remote_shard::RemoteShard;
 use super::update_tracker::UpdateTracker;
+use crate::collection_manager::holders::segment_holder::SegmentVersions;
 use crate::operations::point_ops::WriteOrdering;
 use crate::operations::types::{
     CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest, CountResult,
@@ -69,9 +74,10 @@ impl QueueProxyShard {
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base_segments: Option<&SegmentVersions>,
     ) -> CollectionResult<()> {
         self.wrapped_shard
-            .create_snapshot(temp_path, target_path, save_wal)
+            .create_snapshot(temp_path, target_path, save_wal, base_segments)
             .await
     }
 
@@ -155,8 +161,12 @@ impl QueueProxyShard {
         self.wrapped_shard.on_optimizer_config_update().await
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
-        self.wrapped_shard.get_telemetry_data()
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> LocalShardTelemetry {
+        self.wrapped_shard.get_telemetry_data(detail)
+    }
+
+    pub fn get_field_index_stats(&self, field: PayloadKeyTypeRef) -> Option<FieldIndexStats> {
+        self.wrapped_shard.get_field_index_stats(field)
     }
 
     pub fn update_tracker(&self) -> &UpdateTracker {
@@ -188,6 +198,7 @@ impl ShardOperation for QueueProxyShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
     ) -> CollectionResult<Vec<Record>> {
         let local_shard = &self.wrapped_shard;
         local_shard
@@ -198,6 +209,7 @@ impl ShardOperation for QueueProxyShard {
                 with_vector,
                 filter,
                 search_runtime_handle,
+                order_by,
             )
             .await
     }
@@ -6,25 +6,34 @@ use api::grpc::qdrant::collections_internal_client::CollectionsInternalClient;
 use api::grpc::qdrant::points_internal_client::PointsInternalClient;
 use api::grpc::qdrant::{
     CollectionOperationResponse, CoreSearchBatchPointsInternal, CountPoints, CountPointsInternal,
-    GetCollectionInfoRequest, GetCollectionInfoRequestInternal, GetPoints, GetPointsInternal,
-    InitiateShardTransferRequest, ScrollPoints, ScrollPointsInternal, SearchBatchPointsInternal,
+    ExplainMode, GetCollectionInfoRequest, GetCollectionInfoRequestInternal, GetPoints,
+    GetPointsInternal, InitiateShardTransferRequest, ScrollPoints, ScrollPointsInternal,
+    SearchBatchPointsInternal,
 };
+use api::grpc::trace_context::TraceContextInterceptor;
 use async_trait::async_trait;
+use common::types::TelemetryDetail;
 use parking_lot::Mutex;
 use segment::common::operation_time_statistics::{
     OperationDurationsAggregator, ScopeDurationMeasurer,
 };
+use segment::data_types::order_by::OrderBy;
 use segment::types::{
     ExtendedPointId, Filter, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
 use tokio::runtime::Handle;
+use tonic::codegen::InterceptedService;
 use tonic::transport::{Channel, Uri};
 use tonic::Status;
 
 use super::conversions::{
     internal_delete_vectors, internal_delete_vectors_by_filter, internal_update_vectors,
 };
+use crate::common::hardware_usage::{is_hardware_usage_collected, record_hardware_usage};
+use crate::common::query_explain::{explain_mode, record_shard_explain};
+use crate::operations::clock_ops::ClockedOperation;
 use crate::operations::conversions::try_record_from_grpc;
+use crate::operations::idempotency_ops::IdempotentOperation;
 use crate::operations::payload_ops::PayloadOps;
 use crate::operations::point_ops::{PointOperations, WriteOrdering};
 use crate::operations::types::{
@@ -39,13 +48,16 @@ use crate::shards::conversions::{
     internal_clear_payload, internal_clear_payload_by_filter, internal_create_index,
     internal_delete_index, internal_delete_payload, internal_delete_points,
     internal_delete_points_by_filter, internal_set_payload, internal_sync_points,
-    internal_upsert_points, try_scored_point_from_grpc,
+    internal_update_batch, internal_upsert_points, try_scored_point_from_grpc,
 };
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_trait::ShardOperation;
 use crate::shards::telemetry::RemoteShardTelemetry;
 use crate::shards::CollectionId;
 
+/// Clients of the other peers, which propagate the trace context of the requests
+type TracedChannel = InterceptedService<Channel, TraceContextInterceptor>;
+
 /// RemoteShard
 ///
 /// Remote Shard is a representation of a shard that is located on a remote peer.
@@ -94,13 +106,14 @@ impl RemoteShard {
 
     async fn with_points_client<T, O: Future<Output = Result<T, Status>>>(
         &self,
-        f: impl Fn(PointsInternalClient<Channel>) -> O,
+        f: impl Fn(PointsInternalClient<TracedChannel>) -> O,
     ) -> CollectionResult<T> {
         let current_address = self.current_address()?;
         self.channel_service
             .channel_pool
             .with_channel(&current_address, |channel| {
-                let client = PointsInternalClient::new(channel);
+                let client =
+                    PointsInternalClient::with_interceptor(channel, TraceContextInterceptor);
                 let client = client.max_decoding_message_size(usize::MAX);
                 f(client)
             })
@@ -110,13 +123,14 @@ impl RemoteShard {
 
     async fn with_collections_client<T, O: Future<Output = Result<T, Status>>>(
         &self,
-        f: impl Fn(CollectionsInternalClient<Channel>) -> O,
+        f: impl Fn(CollectionsInternalClient<TracedChannel>) -> O,
     ) -> CollectionResult<T> {
         let current_address = self.current_address()?;
         self.channel_service
             .channel_pool
             .with_channel(&current_address, |channel| {
-                let client = CollectionsInternalClient::new(channel);
+                let client =
+                    CollectionsInternalClient::with_interceptor(channel, TraceContextInterceptor);
                 let client = client.max_decoding_message_size(usize::MAX);
                 f(client)
             })
@@ -124,12 +138,18 @@ impl RemoteShard {
             .map_err(|err| err.into())
     }
 
-    pub fn get_telemetry_data(&self) -> RemoteShardTelemetry {
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> RemoteShardTelemetry {
         RemoteShardTelemetry {
             shard_id: self.id,
             peer_id: Some(self.peer_id),
-            searches: self.telemetry_search_durations.lock().get_statistics(),
-            updates: self.telemetry_update_durations.lock().get_statistics(),
+            searches: self
+                .telemetry_search_durations
+                .lock()
+                .get_statistics_with_detail(detail),
+            updates: self
+                .telemetry_update_durations
+                .lock()
+                .get_statistics_with_detail(detail),
         }
     }
 
@@ -182,6 +202,22 @@ impl RemoteShard {
                         shard_id,
                         collection_name,
                         point_insert_operations,
+                        None,
+                        wait,
+                        ordering,
+                    )?;
+                    self.with_points_client(|mut client| async move {
+                        client.upsert(tonic::Request::new(request.clone())).await
+                    })
+                    .await?
+                    .into_inner()
+                }
+                PointOperations::UpsertPointsConditional(operation) => {
+                    let request = &internal_upsert_points(
+                        shard_id,
+                        collection_name,
+                        operation.points,
+                        Some(operation.condition),
                         wait,
                         ordering,
                     )?;
@@ -219,6 +255,7 @@ impl RemoteShard {
                         shard_id,
                         collection_name,
                         operation,
+                        Vec::new(),
                         wait,
                         ordering,
                     )?;
@@ -287,6 +324,24 @@ impl RemoteShard {
                         shard_id,
                         collection_name,
                         set_payload,
+                        false,
+                        wait,
+                        ordering,
+                    );
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .set_payload(tonic::Request::new(request.clone()))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
+                PayloadOps::MergePayload(set_payload) => {
+                    let request = &internal_set_payload(
+                        shard_id,
+                        collection_name,
+                        set_payload,
+                        true,
                         wait,
                         ordering,
                     );
@@ -341,11 +396,30 @@ impl RemoteShard {
                     .await?
                     .into_inner()
                 }
+                operation @ PayloadOps::MutatePayload(_) => {
+                    // Mutations are only sent within a batch update
+                    let request = &internal_update_batch(
+                        shard_id,
+                        collection_name,
+                        vec![CollectionUpdateOperations::PayloadOperation(operation)],
+                        None,
+                        wait,
+                        ordering,
+                    )?;
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .update_batch(tonic::Request::new(request.clone()))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
                 PayloadOps::OverwritePayload(set_payload) => {
                     let request = &internal_set_payload(
                         shard_id,
                         collection_name,
                         set_payload,
+                        false,
                         wait,
                         ordering,
                     );
@@ -393,6 +467,94 @@ impl RemoteShard {
                     .into_inner()
                 }
             },
+            CollectionUpdateOperations::BatchOperation(operations) => {
+                let request = &internal_update_batch(
+                    shard_id,
+                    collection_name,
+                    operations,
+                    None,
+                    None,
+                    wait,
+                    ordering,
+                )?;
+                self.with_points_client(|mut client| async move {
+                    client
+                        .update_batch(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
+            CollectionUpdateOperations::ClockedOperation(ClockedOperation {
+                clock_tag,
+                point_clock_tags,
+                operation,
+            }) => match *operation {
+                // Clocks of individual points are only sent with sync, e.g. by shard transfer
+                CollectionUpdateOperations::PointOperation(PointOperations::SyncPoints(
+                    operation,
+                )) => {
+                    let request = &internal_sync_points(
+                        shard_id,
+                        collection_name,
+                        operation,
+                        point_clock_tags,
+                        wait,
+                        ordering,
+                    )?;
+                    self.with_points_client(|mut client| async move {
+                        client.sync(tonic::Request::new(request.clone())).await
+                    })
+                    .await?
+                    .into_inner()
+                }
+                operation => {
+                    let request = &internal_update_batch(
+                        shard_id,
+                        collection_name,
+                        vec![operation],
+                        Some(clock_tag),
+                        None,
+                        wait,
+                        ordering,
+                    )?;
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .update_batch(tonic::Request::new(request.clone()))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
+            },
+            // Token is sent along with a batch, the receiving shard applies the batch under it
+            CollectionUpdateOperations::IdempotentOperation(IdempotentOperation {
+                idempotency_token,
+                operation,
+            }) => {
+                let (operation, clock_tag) = match *operation {
+                    CollectionUpdateOperations::ClockedOperation(clocked) => {
+                        (*clocked.operation, Some(clocked.clock_tag))
+                    }
+                    operation => (operation, None),
+                };
+                let request = &internal_update_batch(
+                    shard_id,
+                    collection_name,
+                    vec![operation],
+                    clock_tag,
+                    Some(idempotency_token),
+                    wait,
+                    ordering,
+                )?;
+                self.with_points_client(|mut client| async move {
+                    client
+                        .update_batch(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
         };
         match point_operation_response.result {
             None => Err(CollectionError::service_error(
@@ -429,6 +591,7 @@ impl ShardOperation for RemoteShard {
         with_vector: &WithVector,
         filter: Option<&Filter>,
         search_runtime_handle: &Handle,
+        order_by: Option<&OrderBy>,
     ) -> CollectionResult<Vec<Record>> {
         let scroll_points = ScrollPoints {
             collection_name: self.collection_id.clone(),
@@ -438,6 +601,10 @@ impl ShardOperation for RemoteShard {
             with_payload: Some(with_payload_interface.clone().into()),
             with_vectors: Some(with_vector.clone().into()),
             read_consistency: None,
+            order_by: order_by.map(|order_by| order_by.clone().into()),
+            stable: None,
+            cursor: None,
+            timeout: None,
         };
         let request = &ScrollPointsInternal {
             scroll_points: Some(scroll_points),
@@ -492,13 +659,15 @@ impl ShardOperation for RemoteShard {
         let search_points = batch_request
             .searches
             .iter()
-            .map(|s| CollectionSearchRequest((self.collection_id.clone(), s)).into())
-            .collect();
+            .map(|s| CollectionSearchRequest((self.collection_id.clone(), s)).try_into())
+            .collect::<CollectionResult<_>>()?;
 
         let request = &SearchBatchPointsInternal {
             collection_name: self.collection_id.clone(),
             search_points,
             shard_id: Some(self.id),
+            with_usage: Some(is_hardware_usage_collected()),
+            explain: explain_mode().map(|mode| ExplainMode::from(mode) as i32),
         };
         let search_batch_response = self
             .with_points_client(|mut client| async move {
@@ -509,6 +678,16 @@ impl ShardOperation for RemoteShard {
             .await?
             .into_inner();
 
+        if let Some(usage) = search_batch_response.usage {
+            record_hardware_usage(usage.into());
+        }
+        let explain = search_batch_response
+            .explain
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, Status>>()?;
+        record_shard_explain(explain);
+
         let result: Result<Vec<Vec<ScoredPoint>>, Status> = search_batch_response
             .result
             .into_iter()
@@ -553,6 +732,8 @@ impl ShardOperation for RemoteShard {
             collection_name: self.collection_id.clone(),
             search_points,
             shard_id: Some(self.id),
+            with_usage: Some(is_hardware_usage_collected()),
+            explain: explain_mode().map(|mode| ExplainMode::from(mode) as i32),
         };
         let search_batch_response = self
             .with_points_client(|mut client| async move {
@@ -563,6 +744,16 @@ impl ShardOperation for RemoteShard {
             .await?
             .into_inner();
 
+        if let Some(usage) = search_batch_response.usage {
+            record_hardware_usage(usage.into());
+        }
+        let explain = search_batch_response
+            .explain
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<_, Status>>()?;
+        record_shard_explain(explain);
+
         let result: Result<Vec<Vec<ScoredPoint>>, Status> = search_batch_response
             .result
             .into_iter()
@@ -592,6 +783,7 @@ impl ShardOperation for RemoteShard {
             collection_name: self.collection_id.clone(),
             filter: request.filter.clone().map(|f| f.into()),
             exact: Some(request.exact),
+            timeout: None,
         };
 
         let request = &CountPointsInternal {
@@ -626,6 +818,7 @@ impl ShardOperation for RemoteShard {
             with_payload: request.with_payload.clone().map(|wp| wp.into()),
             with_vectors: Some(with_vector.clone().into()),
             read_consistency: None,
+            timeout: None,
         };
         let request = &GetPointsInternal {
             get_points: Some(get_points),
@@ -1,40 +1,50 @@
 use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
-use std::ops::Deref as _;
+use std::ops::{Bound, Deref as _};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
 
+use common::types::TelemetryDetail;
 use futures::future::{self, BoxFuture};
 use futures::stream::FuturesUnordered;
 use futures::{FutureExt as _, StreamExt as _};
 use itertools::Itertools;
 use rand::seq::SliceRandom as _;
 use schemars::JsonSchema;
+use segment::data_types::order_by::OrderBy;
+use segment::index::field_index::stats::FieldIndexStats;
 use segment::types::{
-    ExtendedPointId, Filter, PointIdType, ScoredPoint, WithPayload, WithPayloadInterface,
-    WithVector,
+    ExtendedPointId, Filter, PayloadKeyType, PayloadKeyTypeRef, PointIdType, ScoredPoint,
+    SeqNumberType, WithPayload, WithPayloadInterface, WithVector,
 };
 use serde::{Deserialize, Serialize};
 use tokio::runtime::Handle;
 use tokio::sync::{Mutex, RwLock};
+use tokio::time::Instant;
 
-use super::local_shard::LocalShard;
+use super::local_shard::{LocalShard, WalOperations};
 use super::queue_proxy_shard::QueueProxyShard;
 use super::remote_shard::RemoteShard;
 use super::resolve::{Resolve, ResolveCondition};
 use super::{create_shard_dir, CollectionId};
+use crate::collection_manager::holders::segment_holder::SegmentVersions;
 use crate::config::CollectionConfig;
+use crate::operations::clock_ops::{ClockTag, ClockedOperation};
 use crate::operations::consistency_params::{ReadConsistency, ReadConsistencyType};
-use crate::operations::point_ops::WriteOrdering;
+use crate::operations::point_ops::{UpsertCondition, WriteOrdering};
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CoreSearchRequestBatch, CountRequest,
-    CountResult, PointRequest, Record, SearchRequestBatch, UpdateResult,
+    CountResult, PointRequest, Record, SearchRequestBatch, ShardSegmentsInfo, ShardWalInfo,
+    UpdateResult,
 };
 use crate::operations::CollectionUpdateOperations;
+use crate::optimizers_builder::{OptimizeTarget, RebuildIndexTarget};
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::channel_service::ChannelService;
+use crate::shards::clocks::LogicalClock;
 use crate::shards::dummy_shard::DummyShard;
 use crate::shards::forward_proxy_shard::ForwardProxyShard;
 use crate::shards::shard::Shard::{Dummy, ForwardProxy, Local, QueueProxy};
@@ -178,6 +188,8 @@ pub struct ShardReplicaSet {
     search_runtime: Handle,
     /// Lock to serialized write operations on the replicaset when a write ordering is used.
     write_ordering_lock: Mutex<()>,
+    /// Clock of the updates, used by replicas to resolve conflicting changes of the same points
+    clock: LogicalClock,
 }
 
 impl ShardReplicaSet {
@@ -195,6 +207,118 @@ impl ShardReplicaSet {
         self.local.read().await.is_some()
     }
 
+    /// Resolves on the next update of the local replica, never resolves if there is none
+    pub async fn watch_local_update(&self) -> BoxFuture<'static, ()> {
+        let update_watcher = self
+            .local
+            .read()
+            .await
+            .as_ref()
+            .map(Shard::watch_for_update);
+
+        async move {
+            match update_watcher {
+                Some(update_watcher) => update_watcher.await,
+                None => future::pending().await,
+            }
+        }
+        .boxed()
+    }
+
+    /// Operations from WAL of the local replica, see [`LocalShard::read_wal_operations`].
+    /// Returns `None`, if there is no local replica.
+    pub async fn read_local_wal_operations(
+        &self,
+        from: Option<SeqNumberType>,
+        limit: usize,
+    ) -> CollectionResult<Option<WalOperations>> {
+        let local = self.local.read().await;
+        let Some(local_shard) = local.as_ref().and_then(Shard::local_shard) else {
+            return Ok(None);
+        };
+        local_shard.read_wal_operations(from, limit).map(Some)
+    }
+
+    /// Operations, stored in WAL of the local replica, see [`LocalShard::stored_wal_range`].
+    /// Returns `None`, if there is no local replica.
+    pub async fn local_stored_wal_range(&self) -> Option<(SeqNumberType, SeqNumberType)> {
+        let local = self.local.read().await;
+        local
+            .as_ref()
+            .and_then(Shard::local_shard)
+            .map(LocalShard::stored_wal_range)
+    }
+
+    /// Usage of WAL of the local replica, `None` if there is none
+    pub async fn local_wal_info(&self) -> CollectionResult<Option<ShardWalInfo>> {
+        let local = self.local.read().await;
+        let Some(local_shard) = local.as_ref().and_then(Shard::local_shard) else {
+            return Ok(None);
+        };
+        local_shard.wal_info(self.shard_id).map(Some)
+    }
+
+    /// See [`LocalShard::segments_info`], returns `None` if there is no local replica.
+    pub async fn local_segments_info(&self) -> Option<ShardSegmentsInfo> {
+        let local = self.local.read().await;
+        let local_shard = local.as_ref().and_then(Shard::local_shard)?;
+        Some(local_shard.segments_info(self.shard_id))
+    }
+
+    /// Remove applied operations, kept in WAL of the local replica, see [`LocalShard::truncate_retained_wal`].
+    /// Returns usage of WAL after truncation, `None` if there is no local replica.
+    pub async fn truncate_local_retained_wal(&self) -> CollectionResult<Option<ShardWalInfo>> {
+        let local = self.local.read().await;
+        let Some(local_shard) = local.as_ref().and_then(Shard::local_shard) else {
+            return Ok(None);
+        };
+        local_shard.truncate_retained_wal()?;
+        local_shard.wal_info(self.shard_id).map(Some)
+    }
+
+    /// See [`LocalShard::launch_forced_optimization`], returns `None` if there is no local replica.
+    pub async fn launch_local_forced_optimization(
+        &self,
+        target: Option<OptimizeTarget>,
+    ) -> Option<BoxFuture<'static, CollectionResult<()>>> {
+        let local = self.local.read().await;
+        let local_shard = local.as_ref().and_then(Shard::local_shard)?;
+        Some(local_shard.launch_forced_optimization(target).await)
+    }
+
+    /// See [`LocalShard::launch_index_rebuild`], returns `None` if there is no local replica.
+    pub async fn launch_local_index_rebuild(
+        &self,
+        target: RebuildIndexTarget,
+        field_name: Option<PayloadKeyType>,
+    ) -> Option<BoxFuture<'static, CollectionResult<()>>> {
+        let local = self.local.read().await;
+        let local_shard = local.as_ref().and_then(Shard::local_shard)?;
+        Some(local_shard.launch_index_rebuild(target, field_name).await)
+    }
+
+    /// Pause or resume background optimizations of the local replica, if there is one
+    pub async fn set_local_optimizers_paused(&self, paused: bool) -> CollectionResult<()> {
+        let local = self.local.read().await;
+        let Some(local_shard) = local.as_ref().and_then(Shard::local_shard) else {
+            return Ok(());
+        };
+        if paused {
+            local_shard.pause_optimizers().await
+        } else {
+            local_shard.resume_optimizers().await
+        }
+    }
+
+    /// See [`LocalShard::wal_index_after`], returns `None`, if there is no local replica.
+    pub async fn local_wal_index_after(&self, timestamp: i64) -> Option<SeqNumberType> {
+        let local = self.local.read().await;
+        local
+            .as_ref()
+            .and_then(Shard::local_shard)
+            .map(|local_shard| local_shard.wal_index_after(timestamp))
+    }
+
     pub fn peers(&self) -> HashMap<PeerId, ReplicaState> {
         self.replica_state.read().peers()
     }
@@ -361,6 +485,7 @@ impl ShardReplicaSet {
             update_runtime,
             search_runtime,
             write_ordering_lock: Mutex::new(()),
+            clock: LogicalClock::default(),
         })
     }
 
@@ -581,6 +706,7 @@ impl ShardReplicaSet {
             update_runtime,
             search_runtime,
             write_ordering_lock: Mutex::new(()),
+            clock: LogicalClock::default(),
         };
 
         if local_load_failure && replica_set.active_remote_shards().await.is_empty() {
@@ -1047,11 +1173,11 @@ impl ShardReplicaSet {
         }
     }
 
-    pub(crate) async fn get_telemetry_data(&self) -> ReplicaSetTelemetry {
+    pub(crate) async fn get_telemetry_data(&self, detail: TelemetryDetail) -> ReplicaSetTelemetry {
         let local_shard = self.local.read().await;
         let local = local_shard
             .as_ref()
-            .map(|local_shard| local_shard.get_telemetry_data());
+            .map(|local_shard| local_shard.get_telemetry_data(detail));
         ReplicaSetTelemetry {
             id: self.shard_id,
             local,
@@ -1060,12 +1186,35 @@ impl ShardReplicaSet {
                 .read()
                 .await
                 .iter()
-                .map(|remote| remote.get_telemetry_data())
+                .map(|remote| remote.get_telemetry_data(detail))
                 .collect(),
             replicate_states: self.replica_state.read().peers(),
         }
     }
 
+    pub(crate) async fn get_field_index_stats(
+        &self,
+        field: PayloadKeyTypeRef,
+    ) -> Option<FieldIndexStats> {
+        self.local
+            .read()
+            .await
+            .as_ref()
+            .and_then(|local_shard| local_shard.get_field_index_stats(field))
+    }
+
+    /// Version of the data of the local replica, if it is active
+    pub(crate) async fn local_data_version(&self) -> Option<SeqNumberType> {
+        if self.peer_state(&self.this_peer_id()) != Some(ReplicaState::Active) {
+            return None;
+        }
+        self.local
+            .read()
+            .await
+            .as_ref()
+            .and_then(Shard::data_version)
+    }
+
     /// Returns if local shard was recovered from path
     pub async fn restore_local_replica_from(&self, replica_path: &Path) -> CollectionResult<bool> {
         if !LocalShard::check_data(replica_path) {
@@ -1188,12 +1337,13 @@ impl ShardReplicaSet {
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base_segments: Option<&SegmentVersions>,
     ) -> CollectionResult<()> {
         let local_read = self.local.read().await;
 
         if let Some(local) = &*local_read {
             local
-                .create_snapshot(temp_path, target_path, save_wal)
+                .create_snapshot(temp_path, target_path, save_wal, base_segments)
                 .await?
         }
 
@@ -1378,6 +1528,11 @@ impl ShardReplicaSet {
         operation: CollectionUpdateOperations,
         wait: bool,
     ) -> CollectionResult<Option<UpdateResult>> {
+        if let CollectionUpdateOperations::ClockedOperation(clocked) =
+            operation.without_idempotency_token()
+        {
+            self.clock.observe(clocked.clock_tag.clock_tick);
+        }
         if let Some(local_shard) = &*self.local.read().await {
             match self.peer_state(&self.this_peer_id()) {
                 Some(ReplicaState::Active | ReplicaState::Partial | ReplicaState::Initializing) => {
@@ -1592,6 +1747,28 @@ impl ShardReplicaSet {
                 .filter(|rs| self.peer_is_active_or_pending(&rs.peer_id))
                 .collect();
 
+            // Replicas may receive concurrent updates in a different order,
+            // the clock lets them agree on the last change of each point.
+            // Idempotency token stays outside, so a skipped retry doesn't advance point clocks
+            let (idempotency_token, operation) = operation.into_idempotency_token();
+            // Preconditions are checked once by the update leader, before the update is written
+            // anywhere. Replicas apply plain upserts, as their point versions are numbers
+            // of their own operations, and might differ from the versions of the leader.
+            let (upsert_conditions, operation) = operation.take_upsert_conditions();
+            if !upsert_conditions.is_empty() {
+                self.check_upsert_conditions(local.deref(), &upsert_conditions)?;
+            }
+            let operation = match operation {
+                CollectionUpdateOperations::FieldIndexOperation(_)
+                | CollectionUpdateOperations::ClockedOperation(_) => operation,
+                _ if active_remote_shards.is_empty() => operation,
+                _ => CollectionUpdateOperations::ClockedOperation(ClockedOperation::new(
+                    ClockTag::new(self.clock.tick(), this_peer_id),
+                    operation,
+                )),
+            }
+            .with_idempotency_token(idempotency_token);
+
             // local is defined AND the peer itself can receive updates
             let local_is_updatable =
                 local.is_some() && self.peer_is_active_or_pending(&this_peer_id);
@@ -1621,6 +1798,7 @@ impl ShardReplicaSet {
                             .get()
                             .update(operation, local_wait)
                             .await
+                            .map(|res| (this_peer_id, res))
                             .map_err(|err| {
                                 let peer_id = err.remote_peer_id().unwrap_or(this_peer_id);
 
@@ -1639,6 +1817,7 @@ impl ShardReplicaSet {
                     remote
                         .update(operation, wait)
                         .await
+                        .map(|res| (remote.peer_id, res))
                         .map_err(|err| (remote.peer_id, err))
                 };
 
@@ -1717,14 +1896,39 @@ impl ShardReplicaSet {
                 return Err(err);
             }
         }
-        // there are enough successes, return the first one
-        let res = successes
+        // there are enough successes, return the local one if any, or the first one.
+        // Versions of the points, returned to the client, are the versions of the local replica
+        let this_peer_id = self.this_peer_id();
+        let (_peer_id, res) = successes
             .into_iter()
-            .next()
+            .min_by_key(|(peer_id, _)| *peer_id != this_peer_id)
             .expect("successes is not empty");
         Ok(res)
     }
 
+    /// Check preconditions of conditional upserts against the local replica of the update leader
+    fn check_upsert_conditions(
+        &self,
+        local: &Option<Shard>,
+        conditions: &[(Vec<PointIdType>, UpsertCondition)],
+    ) -> CollectionResult<()> {
+        let this_peer_id = self.this_peer_id();
+        let local_shard = local
+            .as_ref()
+            .and_then(Shard::local_shard)
+            .filter(|_| self.peer_is_active(&this_peer_id));
+        let Some(local_shard) = local_shard else {
+            return Err(CollectionError::service_error(format!(
+                "Can't check the upsert condition, shard {} has no active replica on the update leader peer {}",
+                self.shard_id, this_peer_id
+            )));
+        };
+        for (point_ids, condition) in conditions {
+            local_shard.check_upsert_condition(point_ids, *condition)?;
+        }
+        Ok(())
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub async fn scroll_by(
         &self,
@@ -1735,16 +1939,19 @@ impl ShardReplicaSet {
         filter: Option<&Filter>,
         read_consistency: Option<ReadConsistency>,
         local_only: bool,
+        order_by: Option<&OrderBy>,
     ) -> CollectionResult<Vec<Record>> {
         let with_payload_interface = Arc::new(with_payload_interface.clone());
         let with_vector = Arc::new(with_vector.clone());
         let filter = filter.map(|filter| Arc::new(filter.clone()));
+        let order_by = order_by.map(|order_by| Arc::new(order_by.clone()));
 
         self.execute_and_resolve_read_operation(
             |shard| {
                 let with_payload_interface = with_payload_interface.clone();
                 let with_vector = with_vector.clone();
                 let filter = filter.clone();
+                let order_by = order_by.clone();
                 let search_runtime = self.search_runtime.clone();
 
                 async move {
@@ -1756,6 +1963,7 @@ impl ShardReplicaSet {
                             &with_vector,
                             filter.as_deref(),
                             &search_runtime,
+                            order_by.as_deref(),
                         )
                         .await
                 }
@@ -1767,6 +1975,50 @@ impl ShardReplicaSet {
         .await
     }
 
+    /// Sorted IDs of the points of the active local replica, which match the filter
+    pub async fn local_point_ids(
+        &self,
+        filter: Option<&Filter>,
+    ) -> CollectionResult<Vec<ExtendedPointId>> {
+        let local = self.local.read().await;
+        self.active_local_shard(&local)?
+            .read_all_filtered_ids(filter, &self.search_runtime)
+            .await
+    }
+
+    /// Read the pinned points of a stable scroll from the active local replica
+    pub async fn scroll_local_pinned(
+        &self,
+        pinned_ids: &[ExtendedPointId],
+        from: Bound<ExtendedPointId>,
+        limit: usize,
+        with_payload_interface: &WithPayloadInterface,
+        with_vector: &WithVector,
+    ) -> CollectionResult<Vec<Record>> {
+        let local = self.local.read().await;
+        self.active_local_shard(&local)?.scroll_pinned(
+            pinned_ids,
+            from,
+            limit,
+            with_payload_interface,
+            with_vector,
+        )
+    }
+
+    fn active_local_shard<'a>(&self, local: &'a Option<Shard>) -> CollectionResult<&'a LocalShard> {
+        local
+            .as_ref()
+            .and_then(Shard::local_shard)
+            .filter(|_| self.peer_is_active(&self.this_peer_id()))
+            .ok_or_else(|| {
+                CollectionError::bad_request(format!(
+                    "Stable scroll requires an active replica of shard {} on peer {}",
+                    self.shard_id,
+                    self.this_peer_id(),
+                ))
+            })
+    }
+
     pub async fn info(&self, local_only: bool) -> CollectionResult<CollectionInfo> {
         self.execute_read_operation(
             |shard| async move { shard.info().await }.boxed(),
@@ -1817,6 +2069,29 @@ impl ShardReplicaSet {
         .await
     }
 
+    /// Search in the active local replica until the deadline, see [`LocalShard::search_until`].
+    ///
+    /// Returns `None` if there is no active local replica to search in.
+    pub async fn search_local_until(
+        &self,
+        request: Arc<CoreSearchRequestBatch>,
+        deadline: Instant,
+        is_stopped: Arc<AtomicBool>,
+    ) -> CollectionResult<Option<(Vec<Vec<ScoredPoint>>, bool)>> {
+        let local = self.local.read().await;
+        let Some(local_shard) = local
+            .as_ref()
+            .and_then(Shard::local_shard)
+            .filter(|_| self.peer_is_active(&self.this_peer_id()))
+        else {
+            return Ok(None);
+        };
+        let result = local_shard
+            .search_until(request, &self.search_runtime, deadline, is_stopped)
+            .await?;
+        Ok(Some(result))
+    }
+
     pub async fn count_local(
         &self,
         request: Arc<CountRequest>,
@@ -1890,6 +2165,9 @@ mod tests {
         indexing_threshold: Some(50_000),
         flush_interval_sec: 30,
         max_optimization_threads: 2,
+        expiration_key: None,
+        merge_policy: None,
+        defragmentation_key: None,
     };
 
     pub fn dummy_on_replica_failure() -> ChangePeerState {
@@ -1903,6 +2181,7 @@ mod tests {
         let wal_config = WalConfig {
             wal_capacity_mb: 1,
             wal_segments_ahead: 0,
+            ..Default::default()
         };
 
         let collection_params = CollectionParams {
@@ -1912,7 +2191,10 @@ mod tests {
                 hnsw_config: None,
                 quantization_config: None,
                 on_disk: None,
+                multivector_config: None,
+                datatype: None,
             }),
+            sparse_vectors: None,
             shard_number: NonZeroU32::new(4).unwrap(),
             replication_factor: NonZeroU32::new(3).unwrap(),
             write_consistency_factor: NonZeroU32::new(2).unwrap(),
@@ -2,7 +2,12 @@ use core::marker::{Send, Sync};
 use std::future::{self, Future};
 use std::path::Path;
 
+use common::types::TelemetryDetail;
+use segment::index::field_index::stats::FieldIndexStats;
+use segment::types::{PayloadKeyTypeRef, SeqNumberType};
+
 use super::update_tracker::UpdateTracker;
+use crate::collection_manager::holders::segment_holder::SegmentVersions;
 use crate::operations::types::CollectionResult;
 use crate::shards::dummy_shard::DummyShard;
 use crate::shards::forward_proxy_shard::ForwardProxyShard;
@@ -50,48 +55,69 @@ impl Shard {
         }
     }
 
-    pub fn get_telemetry_data(&self) -> LocalShardTelemetry {
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> LocalShardTelemetry {
         let mut telemetry = match self {
-            Shard::Local(local_shard) => local_shard.get_telemetry_data(),
-            Shard::Proxy(proxy_shard) => proxy_shard.get_telemetry_data(),
-            Shard::ForwardProxy(proxy_shard) => proxy_shard.get_telemetry_data(),
-            Shard::QueueProxy(proxy_shard) => proxy_shard.get_telemetry_data(),
+            Shard::Local(local_shard) => local_shard.get_telemetry_data(detail),
+            Shard::Proxy(proxy_shard) => proxy_shard.get_telemetry_data(detail),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.get_telemetry_data(detail),
+            Shard::QueueProxy(proxy_shard) => proxy_shard.get_telemetry_data(detail),
             Shard::Dummy(dummy_shard) => dummy_shard.get_telemetry_data(),
         };
         telemetry.variant_name = Some(self.variant_name().to_string());
         telemetry
     }
 
+    pub fn get_field_index_stats(&self, field: PayloadKeyTypeRef) -> Option<FieldIndexStats> {
+        match self {
+            Shard::Local(local_shard) => local_shard.get_field_index_stats(field),
+            Shard::Proxy(proxy_shard) => proxy_shard.get_field_index_stats(field),
+            Shard::ForwardProxy(proxy_shard) => proxy_shard.get_field_index_stats(field),
+            Shard::QueueProxy(proxy_shard) => proxy_shard.get_field_index_stats(field),
+            Shard::Dummy(_) => None,
+        }
+    }
+
+    /// Version of the data of the shard, only known for a plain local shard
+    pub fn data_version(&self) -> Option<SeqNumberType> {
+        match self {
+            Shard::Local(local_shard) => Some(local_shard.data_version()),
+            Shard::Proxy(_) | Shard::ForwardProxy(_) | Shard::QueueProxy(_) | Shard::Dummy(_) => {
+                None
+            }
+        }
+    }
+
     pub async fn create_snapshot(
         &self,
         temp_path: &Path,
         target_path: &Path,
         save_wal: bool,
+        base_segments: Option<&SegmentVersions>,
     ) -> CollectionResult<()> {
         match self {
             Shard::Local(local_shard) => {
                 local_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base_segments)
                     .await
             }
             Shard::Proxy(proxy_shard) => {
                 proxy_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base_segments)
                     .await
             }
             Shard::ForwardProxy(proxy_shard) => {
                 proxy_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base_segments)
                     .await
             }
             Shard::QueueProxy(proxy_shard) => {
                 proxy_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base_segments)
                     .await
             }
             Shard::Dummy(dummy_shard) => {
                 dummy_shard
-                    .create_snapshot(temp_path, target_path, save_wal)
+                    .create_snapshot(temp_path, target_path, save_wal, base_segments)
                     .await
             }
         }
@@ -123,6 +149,17 @@ impl Shard {
         }
     }
 
+    /// Local shard, which stores the data on this peer
+    pub fn local_shard(&self) -> Option<&LocalShard> {
+        match self {
+            Self::Local(local_shard) => Some(local_shard),
+            Self::Proxy(proxy_shard) => Some(&proxy_shard.wrapped_shard),
+            Self::ForwardProxy(proxy_shard) => Some(&proxy_shard.wrapped_shard),
+            Self::QueueProxy(proxy_shard) => Some(&proxy_shard.wrapped_shard),
+            Self::Dummy(_) => None,
+        }
+    }
+
     fn update_tracker(&self) -> Option<&UpdateTracker> {
         let update_tracker = match self {
             Self::Local(local_shard) => local_shard.update_tracker(),
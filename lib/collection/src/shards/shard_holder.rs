@@ -7,14 +7,15 @@ use tokio::runtime::Handle;
 use tokio::sync::RwLock;
 
 use crate::common::file_utils::move_file;
-use crate::config::CollectionConfig;
+use crate::config::{CollectionConfig, CollectionParams};
 use crate::hash_ring::HashRing;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::snapshot_ops::{
-    get_snapshot_description, list_snapshots_in_directory, SnapshotDescription,
+    create_snapshot_checksum, get_snapshot_description, list_snapshots_in_directory,
+    save_snapshot_metadata, SnapshotDescription, SnapshotMetadata,
 };
 use crate::operations::types::{CollectionError, CollectionResult, ShardTransferInfo};
-use crate::operations::{OperationToShard, SplitByShard};
+use crate::operations::{CollectionUpdateOperations, OperationToShard, SplitByShard};
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::channel_service::ChannelService;
 use crate::shards::local_shard::LocalShard;
@@ -112,6 +113,41 @@ impl ShardHolder {
         shard_ops
     }
 
+    /// Split a batch of operations by shard, keeping the order of operations within each shard
+    pub fn split_batch_by_shard(
+        &self,
+        operations: Vec<CollectionUpdateOperations>,
+    ) -> Vec<(&ShardReplicaSet, CollectionUpdateOperations)> {
+        let mut batch_by_shard: Vec<(&ShardReplicaSet, Vec<CollectionUpdateOperations>)> =
+            Vec::new();
+        for operation in operations {
+            let shard_ops = match operation {
+                CollectionUpdateOperations::BatchOperation(operations) => {
+                    self.split_batch_by_shard(operations)
+                }
+                operation => self.split_by_shard(operation),
+            };
+            for (replica_set, operation) in shard_ops {
+                match batch_by_shard
+                    .iter_mut()
+                    .find(|(shard, _)| shard.shard_id == replica_set.shard_id)
+                {
+                    Some((_, batch)) => batch.push(operation),
+                    None => batch_by_shard.push((replica_set, vec![operation])),
+                }
+            }
+        }
+        batch_by_shard
+            .into_iter()
+            .map(|(replica_set, operations)| {
+                (
+                    replica_set,
+                    CollectionUpdateOperations::BatchOperation(operations),
+                )
+            })
+            .collect()
+    }
+
     pub fn register_start_shard_transfer(&self, transfer: ShardTransfer) -> CollectionResult<bool> {
         Ok(self
             .shard_transfers
@@ -395,9 +431,11 @@ impl ShardHolder {
         &self,
         snapshots_path: &Path,
         collection_name: &str,
+        collection_params: CollectionParams,
         shard_id: ShardId,
         temp_dir: &Path,
     ) -> CollectionResult<SnapshotDescription> {
+        let started = std::time::Instant::now();
         let shard = self
             .get_shard(&shard_id)
             .ok_or_else(|| shard_not_found_error(shard_id))?;
@@ -422,7 +460,12 @@ impl ShardHolder {
             .tempdir_in(temp_dir)?;
 
         shard
-            .create_snapshot(snapshot_temp_dir.path(), snapshot_target_dir.path(), false)
+            .create_snapshot(
+                snapshot_temp_dir.path(),
+                snapshot_target_dir.path(),
+                false,
+                None,
+            )
             .await?;
 
         if let Err(err) = snapshot_temp_dir.close() {
@@ -464,6 +507,12 @@ impl ShardHolder {
         }
 
         move_file(temp_file.path(), &snapshot_path).await?;
+        create_snapshot_checksum(&snapshot_path).await?;
+        save_snapshot_metadata(
+            &snapshot_path,
+            &SnapshotMetadata::new(started, Some(collection_params)),
+        )
+        .await?;
 
         get_snapshot_description(&snapshot_path).await
     }
@@ -6,7 +6,7 @@ use segment::common::operation_time_statistics::OperationDurationStatistics;
 use segment::telemetry::SegmentTelemetry;
 use serde::{Deserialize, Serialize};
 
-use crate::collection_manager::optimizers::TrackerTelemetry;
+use crate::collection_manager::optimizers::{PendingOptimization, TrackerTelemetry};
 use crate::operations::types::OptimizersStatus;
 use crate::shards::replica_set::ReplicaState;
 use crate::shards::shard::{PeerId, ShardId};
@@ -32,6 +32,15 @@ pub struct LocalShardTelemetry {
     pub variant_name: Option<String>,
     pub segments: Vec<SegmentTelemetry>,
     pub optimizations: OptimizerTelemetry,
+    /// Size of the write-ahead log on disk
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wal_size_bytes: Option<u64>,
+    /// Searches in this shard, both from this peer and from the other peers
+    #[serde(default)]
+    pub searches: OperationDurationStatistics,
+    /// Updates of this shard, the time includes waiting for the update, if requested
+    #[serde(default)]
+    pub updates: OperationDurationStatistics,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema, Default)]
@@ -39,6 +48,9 @@ pub struct OptimizerTelemetry {
     pub status: OptimizersStatus,
     pub optimizations: OperationDurationStatistics,
     pub log: Vec<TrackerTelemetry>,
+    /// Optimizations, which wait for a free optimization thread
+    #[serde(default)]
+    pub pending: Vec<PendingOptimization>,
 }
 
 impl Anonymize for OptimizerTelemetry {
@@ -47,6 +59,7 @@ impl Anonymize for OptimizerTelemetry {
             status: self.status.clone(),
             optimizations: self.optimizations.anonymize(),
             log: self.log.anonymize(),
+            pending: self.pending.anonymize(),
         }
     }
 }
@@ -57,6 +70,9 @@ impl Anonymize for LocalShardTelemetry {
             variant_name: self.variant_name.clone(),
             segments: self.segments.anonymize(),
             optimizations: self.optimizations.anonymize(),
+            wal_size_bytes: self.wal_size_bytes,
+            searches: self.searches.anonymize(),
+            updates: self.updates.anonymize(),
         }
     }
 }
@@ -67,12 +83,22 @@ impl Anonymize for TrackerTelemetry {
             name: self.name.clone(),
             segment_ids: self.segment_ids.anonymize(),
             status: self.status.clone(),
+            progress: self.progress.clone(),
             start_at: self.start_at.anonymize(),
             end_at: self.end_at.anonymize(),
         }
     }
 }
 
+impl Anonymize for PendingOptimization {
+    fn anonymize(&self) -> Self {
+        PendingOptimization {
+            name: self.name.clone(),
+            segment_ids: self.segment_ids.anonymize(),
+        }
+    }
+}
+
 impl Anonymize for RemoteShardTelemetry {
     fn anonymize(&self) -> Self {
         RemoteShardTelemetry {
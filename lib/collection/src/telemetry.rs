@@ -2,6 +2,7 @@ use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
 
+use crate::common::search_cache::SearchCacheTelemetry;
 use crate::config::CollectionConfig;
 use crate::operations::types::ShardTransferInfo;
 use crate::shards::telemetry::ReplicaSetTelemetry;
@@ -13,6 +14,8 @@ pub struct CollectionTelemetry {
     pub config: CollectionConfig,
     pub shards: Vec<ReplicaSetTelemetry>,
     pub transfers: Vec<ShardTransferInfo>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_cache: Option<SearchCacheTelemetry>,
 }
 
 impl CollectionTelemetry {
@@ -34,6 +37,7 @@ impl Anonymize for CollectionTelemetry {
             init_time_ms: self.init_time_ms,
             shards: self.shards.anonymize(),
             transfers: vec![],
+            search_cache: self.search_cache.clone(),
         }
     }
 }
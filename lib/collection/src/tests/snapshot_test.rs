@@ -23,6 +23,9 @@ pub const TEST_OPTIMIZERS_CONFIG: OptimizersConfig = OptimizersConfig {
     indexing_threshold: Some(50_000),
     flush_interval_sec: 30,
     max_optimization_threads: 2,
+    expiration_key: None,
+    merge_policy: None,
+    defragmentation_key: None,
 };
 
 pub fn dummy_on_replica_failure() -> ChangePeerState {
@@ -41,6 +44,7 @@ async fn _test_snapshot_collection(node_type: NodeType) {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -50,7 +54,10 @@ async fn _test_snapshot_collection(node_type: NodeType) {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            multivector_config: None,
+            datatype: None,
         }),
+        sparse_vectors: None,
         shard_number: NonZeroU32::new(4).unwrap(),
         replication_factor: NonZeroU32::new(3).unwrap(),
         write_consistency_factor: NonZeroU32::new(2).unwrap(),
@@ -103,7 +110,7 @@ async fn _test_snapshot_collection(node_type: NodeType) {
 
     let snapshots_temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
     let snapshot_description = collection
-        .create_snapshot(snapshots_temp_dir.path(), 0)
+        .create_snapshot(snapshots_temp_dir.path(), 0, None)
         .await
         .unwrap();
 
@@ -117,7 +124,7 @@ async fn _test_snapshot_collection(node_type: NodeType) {
     .is_err());
 
     if let Err(err) = Collection::restore_snapshot(
-        &snapshots_path.path().join(snapshot_description.name),
+        &snapshots_path.path().join(&snapshot_description.name),
         recover_dir.path(),
         0,
         true,
@@ -155,6 +162,71 @@ async fn _test_snapshot_collection(node_type: NodeType) {
         assert!(replica_ser_3.is_local().await);
         assert_eq!(replica_ser_3.peers().len(), 3); // 2 remotes + 1 local
     }
+
+    // Nothing changed, so segments of the incremental snapshot are restored from the base
+    let incremental_description = collection
+        .create_named_snapshot(
+            snapshots_temp_dir.path(),
+            0,
+            Some(&snapshot_description.name),
+            "incremental.snapshot".to_string(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(incremental_description.name, "incremental.snapshot");
+
+    let incremental_recover_dir = Builder::new()
+        .prefix("test_collection_inc_rec")
+        .tempdir()
+        .unwrap();
+    if let Err(err) = Collection::restore_snapshot(
+        &snapshots_path.path().join(&incremental_description.name),
+        incremental_recover_dir.path(),
+        0,
+        true,
+    ) {
+        panic!("Failed to restore incremental snapshot: {err}")
+    }
+    let restored_segments = std::fs::read_dir(incremental_recover_dir.path().join("0/segments"))
+        .unwrap()
+        .count();
+    assert_eq!(
+        restored_segments,
+        TEST_OPTIMIZERS_CONFIG.default_segment_number
+    );
+
+    // Uploaded or downloaded increment is not next to its base
+    let moved_dir = Builder::new().prefix("moved_snapshot").tempdir().unwrap();
+    let moved_path = moved_dir.path().join(&incremental_description.name);
+    std::fs::copy(
+        snapshots_path.path().join(&incremental_description.name),
+        &moved_path,
+    )
+    .unwrap();
+    assert_eq!(
+        Collection::snapshot_base(&moved_path).unwrap(),
+        Some(snapshot_description.name.clone())
+    );
+
+    let moved_recover_dir = Builder::new()
+        .prefix("test_collection_moved_rec")
+        .tempdir()
+        .unwrap();
+    assert!(Collection::restore_snapshot(&moved_path, moved_recover_dir.path(), 0, true).is_err());
+
+    let moved_recover_dir = Builder::new()
+        .prefix("test_collection_moved_rec")
+        .tempdir()
+        .unwrap();
+    if let Err(err) = Collection::restore_snapshot_with_bases(
+        &moved_path,
+        &[snapshots_path.path().to_path_buf()],
+        moved_recover_dir.path(),
+        0,
+        true,
+    ) {
+        panic!("Failed to restore incremental snapshot with bases: {err}")
+    }
 }
 
 #[tokio::test(flavor = "multi_thread")]
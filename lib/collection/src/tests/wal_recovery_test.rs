@@ -18,6 +18,7 @@ fn create_collection_config() -> CollectionConfig {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -27,6 +28,8 @@ fn create_collection_config() -> CollectionConfig {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            multivector_config: None,
+            datatype: None,
         }),
         ..CollectionParams::empty()
     };
@@ -1,5 +1,6 @@
 use std::cmp::min;
 use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use itertools::Itertools;
@@ -15,12 +16,15 @@ use tokio::time::error::Elapsed;
 use tokio::time::{timeout, Duration};
 
 use crate::collection_manager::collection_updater::CollectionUpdater;
-use crate::collection_manager::holders::segment_holder::LockedSegmentHolder;
+use crate::collection_manager::holders::segment_holder::{LockedSegment, LockedSegmentHolder};
 use crate::collection_manager::optimizers::segment_optimizer::SegmentOptimizer;
-use crate::collection_manager::optimizers::{Tracker, TrackerLog, TrackerStatus};
+use crate::collection_manager::optimizers::{
+    PendingOptimization, Tracker, TrackerLog, TrackerStatus,
+};
 use crate::common::stoppable_task::{
     panic_payload_into_string, spawn_stoppable, StoppableTaskHandle,
 };
+use crate::config::WalDurability;
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::types::{CollectionError, CollectionResult};
 use crate::operations::CollectionUpdateOperations;
@@ -89,6 +93,12 @@ pub struct UpdateHandler {
     flush_worker: Option<JoinHandle<()>>,
     /// Sender to stop flush worker
     flush_stop: Option<oneshot::Sender<()>>,
+    /// When the WAL is synced to disk, besides the periodic flush
+    pub wal_durability: WalDurability,
+    /// Interval of the WAL sync, if required by `wal_durability`
+    pub wal_fsync_interval: Option<Duration>,
+    /// Process that periodically syncs WAL, stops once the update channel is closed
+    wal_fsync_worker: Option<JoinHandle<()>>,
     runtime_handle: Handle,
     /// WAL, required for operations
     wal: LockedWal,
@@ -98,6 +108,8 @@ pub struct UpdateHandler {
     pub(super) max_ack_version: Arc<TokioMutex<Option<u64>>>,
     optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
     max_optimization_threads: usize,
+    /// If set, optimizer worker doesn't launch new optimizations
+    optimizers_paused: Arc<AtomicBool>,
 }
 
 impl UpdateHandler {
@@ -111,6 +123,9 @@ impl UpdateHandler {
         wal: LockedWal,
         flush_interval_sec: u64,
         max_optimization_threads: usize,
+        wal_durability: WalDurability,
+        wal_fsync_interval: Option<Duration>,
+        optimizers_paused: Arc<AtomicBool>,
     ) -> UpdateHandler {
         UpdateHandler {
             shared_storage_config,
@@ -121,16 +136,24 @@ impl UpdateHandler {
             optimizers_log,
             flush_worker: None,
             flush_stop: None,
+            wal_durability,
+            wal_fsync_interval,
+            wal_fsync_worker: None,
             runtime_handle,
             wal,
             max_ack_version: Default::default(),
             flush_interval_sec,
             optimization_handles: Arc::new(TokioMutex::new(vec![])),
             max_optimization_threads,
+            optimizers_paused,
         }
     }
 
-    pub fn run_workers(&mut self, update_receiver: Receiver<UpdateSignal>) {
+    pub fn run_workers(
+        &mut self,
+        update_sender: Sender<UpdateSignal>,
+        update_receiver: Receiver<UpdateSignal>,
+    ) {
         let (tx, rx) = mpsc::channel(self.shared_storage_config.update_queue_size);
         self.optimizer_worker = Some(self.runtime_handle.spawn(Self::optimization_worker_fn(
             self.optimizers.clone(),
@@ -141,12 +164,14 @@ impl UpdateHandler {
             self.optimization_handles.clone(),
             self.optimizers_log.clone(),
             self.max_optimization_threads,
+            self.optimizers_paused.clone(),
         )));
         self.update_worker = Some(self.runtime_handle.spawn(Self::update_worker_fn(
             update_receiver,
             tx,
             self.wal.clone(),
             self.segments.clone(),
+            self.wal_durability,
         )));
         let (flush_tx, flush_rx) = oneshot::channel();
         self.flush_worker = Some(self.runtime_handle.spawn(Self::flush_worker(
@@ -157,6 +182,14 @@ impl UpdateHandler {
             flush_rx,
         )));
         self.flush_stop = Some(flush_tx);
+        self.wal_fsync_worker = self.wal_fsync_interval.map(|fsync_interval| {
+            self.runtime_handle.spawn(Self::wal_fsync_worker(
+                self.segments.clone(),
+                self.wal.clone(),
+                fsync_interval,
+                update_sender,
+            ))
+        });
     }
 
     pub fn stop_flush_worker(&mut self) {
@@ -182,9 +215,19 @@ impl UpdateHandler {
         if let Some(handle) = maybe_handle {
             handle.await?;
         }
+        let maybe_handle = self.wal_fsync_worker.take();
+        if let Some(handle) = maybe_handle {
+            handle.await?;
+        }
+
+        self.stop_optimizations().await
+    }
 
+    /// Cancel all running optimizations and wait until they are stopped
+    pub async fn stop_optimizations(&self) -> CollectionResult<()> {
         let mut opt_handles_guard = self.optimization_handles.lock().await;
         let opt_handles = std::mem::take(&mut *opt_handles_guard);
+        drop(opt_handles_guard);
         let stopping_handles = opt_handles
             .into_iter()
             .filter_map(|h| h.stop())
@@ -256,7 +299,12 @@ impl UpdateHandler {
                             optimizers_log.lock().register(tracker);
 
                             // Optimize and handle result
-                            match optimizer.as_ref().optimize(segments.clone(), nsi, stopped) {
+                            match optimizer.as_ref().optimize_with_progress(
+                                segments.clone(),
+                                nsi,
+                                &tracker_handle,
+                                stopped,
+                            ) {
                                 // Perform some actions when optimization if finished
                                 Ok(result) => {
                                     tracker_handle.update(TrackerStatus::Done);
@@ -308,6 +356,37 @@ impl UpdateHandler {
         handles
     }
 
+    /// Optimizations, which are required by the optimizer conditions, but not launched yet,
+    /// e.g. because all optimization threads are busy
+    pub fn pending_optimizations(
+        optimizers: &[Arc<Optimizer>],
+        segments: LockedSegmentHolder,
+    ) -> Vec<PendingOptimization> {
+        // Segments under optimization are wrapped into proxies
+        let mut scheduled_segment_ids: HashSet<_> = segments
+            .read()
+            .iter()
+            .filter(|(_, segment)| matches!(segment, LockedSegment::Proxy(_)))
+            .map(|(segment_id, _)| *segment_id)
+            .collect();
+        let mut pending = Vec::new();
+        for optimizer in optimizers {
+            loop {
+                let segment_ids =
+                    optimizer.check_condition(segments.clone(), &scheduled_segment_ids);
+                if segment_ids.is_empty() {
+                    break;
+                }
+                scheduled_segment_ids.extend(&segment_ids);
+                pending.push(PendingOptimization {
+                    name: optimizer.name().to_string(),
+                    segment_ids,
+                });
+            }
+        }
+        pending
+    }
+
     pub(crate) async fn process_optimization(
         optimizers: Arc<Vec<Arc<Optimizer>>>,
         segments: LockedSegmentHolder,
@@ -331,6 +410,30 @@ impl UpdateHandler {
         handles.append(&mut new_handles);
     }
 
+    /// Launch the given optimizers once, besides the optimizations triggered by updates.
+    ///
+    /// Returns a receiver, which is closed once all launched optimizations are finished,
+    /// cancelled or failed.
+    pub async fn launch_forced_optimization(
+        &self,
+        optimizers: Arc<Vec<Arc<Optimizer>>>,
+    ) -> Receiver<()> {
+        // Each optimization holds a sender until it is over
+        let (finished_sender, finished_receiver) = mpsc::channel(1);
+        let mut new_handles = Self::launch_optimization(
+            optimizers,
+            self.optimizers_log.clone(),
+            self.segments.clone(),
+            move |_optimization_result| {
+                let _ = finished_sender.try_send(());
+            },
+        );
+        // Keep handles with the regular ones, so they are stopped with the update handler
+        let mut handles = self.optimization_handles.lock().await;
+        handles.append(&mut new_handles);
+        finished_receiver
+    }
+
     /// Cleanup finalized optimization task handles
     ///
     /// This finds and removes completed tasks from our list of optimization handles.
@@ -368,6 +471,7 @@ impl UpdateHandler {
         optimization_handles: Arc<TokioMutex<Vec<StoppableTaskHandle<bool>>>>,
         optimizers_log: Arc<Mutex<TrackerLog>>,
         max_handles: usize,
+        optimizers_paused: Arc<AtomicBool>,
     ) {
         loop {
             let receiver = timeout(OPTIMIZER_CLEANUP_INTERVAL, receiver.recv());
@@ -396,6 +500,11 @@ impl UpdateHandler {
                     {
                         continue;
                     }
+
+                    // Optimizations are launched again once resumed
+                    if optimizers_paused.load(Ordering::Relaxed) {
+                        continue;
+                    }
                     Self::process_optimization(
                         optimizers.clone(),
                         segments.clone(),
@@ -414,6 +523,7 @@ impl UpdateHandler {
         optimize_sender: Sender<OptimizerSignal>,
         wal: LockedWal,
         segments: LockedSegmentHolder,
+        wal_durability: WalDurability,
     ) {
         while let Some(signal) = receiver.recv().await {
             match signal {
@@ -423,7 +533,7 @@ impl UpdateHandler {
                     sender,
                     wait,
                 }) => {
-                    let flush_res = if wait {
+                    let flush_res = if wal_durability.sync_before_apply(wait) {
                         wal.lock().flush().map_err(|err| {
                             CollectionError::service_error(format!(
                                 "Can't flush WAL before operation {} - {}",
@@ -543,6 +653,34 @@ impl UpdateHandler {
         }
     }
 
+    /// Periodically syncs WAL to disk, independently of the periodic flush of segments
+    async fn wal_fsync_worker(
+        segments: LockedSegmentHolder,
+        wal: LockedWal,
+        fsync_interval: Duration,
+        update_sender: Sender<UpdateSignal>,
+    ) {
+        loop {
+            tokio::select! {
+                _ = tokio::time::sleep(fsync_interval) => {},
+                _ = update_sender.closed() => {
+                    debug!("Stopping WAL fsync worker.");
+                    return;
+                }
+            };
+
+            let wal_flash_job = wal.lock().flush_async();
+            if let Err(err) = wal_flash_job.join() {
+                error!("Failed to flush wal: {:?}", err);
+                segments
+                    .write()
+                    .report_optimizer_error(WalError::WriteWalError(format!(
+                        "WAL flush error: {err:?}"
+                    )));
+            }
+        }
+    }
+
     /// Returns confirmed version after flush of all segments
     ///
     /// # Errors
@@ -1,10 +1,13 @@
+use std::borrow::Cow;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::result;
 use std::thread::JoinHandle;
+use std::time::Duration;
 
 use io::file_operations::{atomic_save_json, read_json};
+use schemars::JsonSchema;
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
@@ -22,7 +25,7 @@ pub enum WalError {
     TruncateWalError(String),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(rename_all = "snake_case")]
 struct TestInternalStruct1 {
     data: usize,
@@ -45,6 +48,101 @@ enum TestRecord {
 
 type Result<T> = result::Result<T, WalError>;
 
+/// Compression of the records, written to the WAL
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum WalCompression {
+    /// Store records as is
+    #[default]
+    None,
+    /// Fast compression with a moderate ratio
+    Lz4,
+    /// Better compression ratio, at the cost of more CPU
+    Zstd,
+}
+
+// Compressed records start with a marker byte, which is reserved in CBOR.
+// So records, written before the compression was changed, are still readable.
+const LZ4_RECORD_MARKER: u8 = 0x1c;
+const ZSTD_RECORD_MARKER: u8 = 0x1d;
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+impl WalCompression {
+    fn compress(self, record: Vec<u8>) -> std::io::Result<Vec<u8>> {
+        let compressed = match self {
+            WalCompression::None => return Ok(record),
+            WalCompression::Lz4 => {
+                let mut compressed = vec![LZ4_RECORD_MARKER];
+                compressed.extend(lz4_flex::compress_prepend_size(&record));
+                compressed
+            }
+            WalCompression::Zstd => {
+                let mut compressed = vec![ZSTD_RECORD_MARKER];
+                compressed.extend(zstd::bulk::compress(&record, ZSTD_COMPRESSION_LEVEL)?);
+                compressed
+            }
+        };
+        // Small records may not benefit from compression
+        if compressed.len() < record.len() {
+            Ok(compressed)
+        } else {
+            Ok(record)
+        }
+    }
+
+    fn decompress(record: &[u8]) -> result::Result<Cow<[u8]>, String> {
+        match record.first() {
+            Some(&LZ4_RECORD_MARKER) => lz4_flex::decompress_size_prepended(&record[1..])
+                .map(Cow::Owned)
+                .map_err(|err| err.to_string()),
+            Some(&ZSTD_RECORD_MARKER) => zstd::decode_all(&record[1..])
+                .map(Cow::Owned)
+                .map_err(|err| err.to_string()),
+            _ => Ok(Cow::Borrowed(record)),
+        }
+    }
+}
+
+// Records are prefixed with the time they were written at (milliseconds since the epoch),
+// so operations can be looked up by time. The marker is reserved in CBOR as well.
+const TIMESTAMP_RECORD_MARKER: u8 = 0x1e;
+const TIMESTAMP_HEADER_LEN: usize = 1 + std::mem::size_of::<i64>();
+
+fn add_timestamp(record: Vec<u8>, timestamp: i64) -> Vec<u8> {
+    let mut timestamped = Vec::with_capacity(TIMESTAMP_HEADER_LEN + record.len());
+    timestamped.push(TIMESTAMP_RECORD_MARKER);
+    timestamped.extend(timestamp.to_le_bytes());
+    timestamped.extend(record);
+    timestamped
+}
+
+/// Split the record into its timestamp and the rest of it.
+/// Records, written before timestamps were introduced, have none.
+fn split_timestamp(record: &[u8]) -> (Option<i64>, &[u8]) {
+    if record.len() < TIMESTAMP_HEADER_LEN || record[0] != TIMESTAMP_RECORD_MARKER {
+        return (None, record);
+    }
+    let (header, record) = record.split_at(TIMESTAMP_HEADER_LEN);
+    let timestamp = i64::from_le_bytes(header[1..].try_into().unwrap());
+    (Some(timestamp), record)
+}
+
+/// Limits of the acknowledged records, which are kept stored on disk.
+///
+/// Records are kept while they are within all of the set limits, none are kept if no limit is set.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WalRetention {
+    pub records: Option<u64>,
+    pub duration: Option<Duration>,
+    pub size_bytes: Option<u64>,
+}
+
+impl WalRetention {
+    fn is_set(&self) -> bool {
+        self.records.is_some() || self.duration.is_some() || self.size_bytes.is_some()
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 struct WalState {
     pub ack_index: u64,
@@ -66,7 +164,11 @@ pub struct SerdeWal<R> {
     record: PhantomData<R>,
     wal: Wal,
     options: WalOptions,
+    compression: WalCompression,
+    retention: WalRetention,
     first_index: Option<u64>,
+    /// Size of the records, written since the last flush
+    unflushed_bytes: usize,
 }
 
 const FIRST_INDEX_FILE: &str = "first-index";
@@ -96,17 +198,42 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
             record: PhantomData,
             wal,
             options: wal_options,
+            compression: WalCompression::default(),
+            retention: WalRetention::default(),
             first_index,
+            unflushed_bytes: 0,
         })
     }
 
+    /// Compress records, written from now on.
+    /// Records are decompressed on read, regardless of this setting.
+    pub fn with_compression(mut self, compression: WalCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Keep acknowledged records stored on disk within the retention limits.
+    /// They are not read by [`SerdeWal::read_all`], but are still available with [`SerdeWal::read`].
+    pub fn with_retention(mut self, retention: WalRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
     /// Write a record to the WAL but does guarantee durability.
     pub fn write(&mut self, entity: &R) -> Result<u64> {
         // ToDo: Replace back to faster rmp, once this https://github.com/serde-rs/serde/issues/2055 solved
         let binary_entity = serde_cbor::to_vec(&entity).unwrap();
-        self.wal
+        let binary_entity = self
+            .compression
+            .compress(binary_entity)
+            .map_err(|err| WalError::WriteWalError(format!("{err:?}")))?;
+        let binary_entity = add_timestamp(binary_entity, chrono::Utc::now().timestamp_millis());
+        let index = self
+            .wal
             .append(&binary_entity)
-            .map_err(|err| WalError::WriteWalError(format!("{err:?}")))
+            .map_err(|err| WalError::WriteWalError(format!("{err:?}")))?;
+        self.unflushed_bytes += binary_entity.len();
+        Ok(index)
     }
 
     pub fn read_all(&'s self) -> impl Iterator<Item = (u64, R)> + 's {
@@ -149,6 +276,9 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
 
         (start_from..(first_index + len)).map(move |idx| {
             let record_bin = self.wal.entry(idx).expect("Can't read entry from WAL");
+            let (_timestamp, record_bin) = split_timestamp(&record_bin);
+            let record_bin = WalCompression::decompress(record_bin)
+                .expect("Can't decompress entry, probably corrupted WAL");
             let record: R = serde_cbor::from_slice(&record_bin)
                 .or_else(|_err| rmp_serde::from_slice(&record_bin))
                 .expect("Can't deserialize entry, probably corrupted WAL on version mismatch");
@@ -164,9 +294,9 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
     /// * `until_index` - the newest no longer required record sequence number
     ///
     pub fn ack(&mut self, until_index: u64) -> Result<()> {
-        // Truncate WAL
+        // Truncate WAL, keeping retained records
         self.wal
-            .prefix_truncate(until_index)
+            .prefix_truncate(self.retained_from(until_index))
             .map_err(|err| WalError::TruncateWalError(format!("{err:?}")))?;
 
         // Acknowledge index should not decrease
@@ -187,6 +317,54 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
         Ok(())
     }
 
+    /// First of the records before `until_index`, which are kept by the retention limits
+    fn retained_from(&self, until_index: u64) -> u64 {
+        if !self.retention.is_set() {
+            return until_index;
+        }
+        let WalRetention {
+            records,
+            duration,
+            size_bytes,
+        } = self.retention;
+
+        let mut retained_from = self.first_stored_index();
+        if let Some(records) = records {
+            retained_from = retained_from.max(until_index.saturating_sub(records));
+        }
+        if let Some(duration) = duration {
+            let cutoff = chrono::Utc::now().timestamp_millis() - duration.as_millis() as i64;
+            retained_from = retained_from.max(self.first_index_after(cutoff));
+        }
+        if let Some(size_bytes) = size_bytes {
+            let mut retained_size = 0;
+            let mut index = until_index.max(retained_from);
+            while index > retained_from {
+                let record_size = self
+                    .wal
+                    .entry(index - 1)
+                    .map_or(0, |entry| entry.len() as u64);
+                if retained_size + record_size > size_bytes {
+                    break;
+                }
+                retained_size += record_size;
+                index -= 1;
+            }
+            retained_from = index;
+        }
+        retained_from.min(until_index)
+    }
+
+    /// Remove acknowledged records, which are kept by the retention limits.
+    ///
+    /// WAL is truncated by segments, so records of the segment with the first
+    /// not acknowledged record remain stored.
+    pub fn truncate_retained(&mut self) -> Result<()> {
+        self.wal
+            .prefix_truncate(self.first_index())
+            .map_err(|err| WalError::TruncateWalError(format!("{err:?}")))
+    }
+
     fn flush_first_index(&self) -> Result<()> {
         let Some(first_index) = self.first_index else {
             return Ok(());
@@ -206,13 +384,22 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
     pub fn flush(&mut self) -> Result<()> {
         self.wal
             .flush_open_segment()
-            .map_err(|err| WalError::WriteWalError(format!("{err:?}")))
+            .map_err(|err| WalError::WriteWalError(format!("{err:?}")))?;
+        self.unflushed_bytes = 0;
+        Ok(())
     }
 
     pub fn flush_async(&mut self) -> JoinHandle<std::io::Result<()>> {
+        self.unflushed_bytes = 0;
         self.wal.flush_open_segment_async()
     }
 
+    /// Whether records of at least a whole segment were written since the last flush,
+    /// so the WAL has moved on to another segment
+    pub fn segment_filled_since_flush(&self) -> bool {
+        self.unflushed_bytes >= self.options.segment_capacity
+    }
+
     pub fn path(&self) -> &Path {
         self.wal.path()
     }
@@ -221,6 +408,31 @@ impl<'s, R: DeserializeOwned + Serialize + Debug> SerdeWal<R> {
         self.first_index.unwrap_or_else(|| self.wal.first_index())
     }
 
+    /// First record, which is still stored on disk.
+    /// Records before `first_index` are acknowledged, but can be read until their segment is removed.
+    pub fn first_stored_index(&self) -> u64 {
+        self.wal.first_index()
+    }
+
+    /// Index of the first stored record, written after the `timestamp` (milliseconds since the epoch).
+    /// Returns the index of the next record, if there is none.
+    ///
+    /// Records are expected to be written in the order of their timestamps,
+    /// records without a timestamp are considered written before any other.
+    pub fn first_index_after(&self, timestamp: i64) -> u64 {
+        let mut low = self.first_stored_index();
+        let mut high = self.first_index() + self.len();
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let record_bin = self.wal.entry(middle).expect("Can't read entry from WAL");
+            match split_timestamp(&record_bin) {
+                (Some(record_timestamp), _) if record_timestamp > timestamp => high = middle,
+                _ => low = middle + 1,
+            }
+        }
+        low
+    }
+
     pub fn last_index(&self) -> u64 {
         self.wal.last_index()
     }
@@ -293,4 +505,172 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_wal_compression() {
+        let record = serde_cbor::to_vec(&vec![TestInternalStruct1 { data: 42 }; 100]).unwrap();
+        for compression in [WalCompression::Lz4, WalCompression::Zstd] {
+            let compressed = compression.compress(record.clone()).unwrap();
+            assert!(compressed.len() < record.len());
+            let decompressed = WalCompression::decompress(&compressed).unwrap();
+            assert_eq!(decompressed.as_ref(), record.as_slice());
+        }
+
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let wal_options = || WalOptions {
+            segment_capacity: 1024 * 1024,
+            segment_queue_len: 0,
+        };
+
+        // Records, written with different compression, are readable together
+        for (data, compression) in [
+            (1, WalCompression::None),
+            (2, WalCompression::Lz4),
+            (3, WalCompression::Zstd),
+        ] {
+            let mut serde_wal: SerdeWal<TestRecord> =
+                SerdeWal::new(dir.path().to_str().unwrap(), wal_options())
+                    .unwrap()
+                    .with_compression(compression);
+            let record = TestRecord::Struct1(TestInternalStruct1 { data });
+            serde_wal.write(&record).expect("Can't write");
+            serde_wal.flush().unwrap();
+        }
+
+        let serde_wal: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options()).unwrap();
+        let records: Vec<_> = serde_wal
+            .read_all()
+            .map(|(_idx, record)| match record {
+                TestRecord::Struct1(x) => x.data,
+                TestRecord::Struct2(_) => panic!("Wrong structure"),
+            })
+            .collect();
+        assert_eq!(records, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_wal_retention_and_timestamps() {
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let wal_options = WalOptions {
+            segment_capacity: 1024 * 1024,
+            segment_queue_len: 0,
+        };
+        let mut serde_wal: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options)
+                .unwrap()
+                .with_retention(WalRetention {
+                    records: Some(10),
+                    ..Default::default()
+                });
+
+        let started = chrono::Utc::now().timestamp_millis();
+        let record = TestRecord::Struct1(TestInternalStruct1 { data: 1 });
+        serde_wal.write(&record).expect("Can't write");
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let middle = chrono::Utc::now().timestamp_millis();
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        serde_wal.write(&record).expect("Can't write");
+        serde_wal.write(&record).expect("Can't write");
+
+        assert_eq!(serde_wal.first_index_after(started - 1), 0);
+        assert_eq!(serde_wal.first_index_after(middle), 1);
+        assert_eq!(serde_wal.first_index_after(i64::MAX), 3);
+
+        // Acknowledged records are not read on recovery, but are still stored
+        serde_wal.ack(2).unwrap();
+        assert_eq!(serde_wal.first_index(), 2);
+        assert_eq!(serde_wal.first_stored_index(), 0);
+        assert_eq!(serde_wal.read_all().count(), 1);
+        assert_eq!(serde_wal.read(0).count(), 3);
+
+        // Records are kept while they are within all of the limits
+        let record_size = serde_wal.wal.entry(2).unwrap().len() as u64;
+        serde_wal.retention.size_bytes = Some(record_size * 2);
+        assert_eq!(serde_wal.retained_from(3), 1);
+        serde_wal.retention.duration = Some(Duration::from_secs(3600));
+        assert_eq!(serde_wal.retained_from(3), 1);
+        serde_wal.retention.duration = Some(Duration::ZERO);
+        assert_eq!(serde_wal.retained_from(3), 3);
+    }
+
+    #[test]
+    fn test_wal_segment_filled_since_flush() {
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let capacity = 4096;
+        let wal_options = WalOptions {
+            segment_capacity: capacity,
+            segment_queue_len: 0,
+        };
+        let mut serde_wal: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options).unwrap();
+
+        let record = TestRecord::Struct1(TestInternalStruct1 { data: 1 });
+        let mut written = 0;
+        while !serde_wal.segment_filled_since_flush() {
+            serde_wal.write(&record).expect("Can't write");
+            written += 1;
+        }
+        // Every record takes at least a byte, a whole segment takes more than one record
+        assert!(written > 1);
+        assert!(written <= capacity);
+
+        serde_wal.flush().unwrap();
+        assert!(!serde_wal.segment_filled_since_flush());
+        serde_wal.write(&record).expect("Can't write");
+        assert!(!serde_wal.segment_filled_since_flush());
+
+        // Records written before an asynchronous flush are synced by it
+        for _ in 0..written {
+            serde_wal.write(&record).expect("Can't write");
+        }
+        assert!(serde_wal.segment_filled_since_flush());
+        serde_wal.flush_async().join().unwrap().unwrap();
+        assert!(!serde_wal.segment_filled_since_flush());
+    }
+
+    #[test]
+    fn test_wal_truncate_retained() {
+        let dir = Builder::new().prefix("wal_test").tempdir().unwrap();
+        let wal_options = || WalOptions {
+            segment_capacity: 4096,
+            segment_queue_len: 0,
+        };
+        let mut serde_wal: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options())
+                .unwrap()
+                .with_retention(WalRetention {
+                    records: Some(1000),
+                    ..Default::default()
+                });
+
+        let record = TestRecord::Struct1(TestInternalStruct1 { data: 1 });
+        for _ in 0..500 {
+            serde_wal.write(&record).expect("Can't write");
+        }
+
+        // All acknowledged records are within the retention limit
+        serde_wal.ack(400).unwrap();
+        assert_eq!(serde_wal.first_index(), 400);
+        assert_eq!(serde_wal.first_stored_index(), 0);
+        assert_eq!(serde_wal.read(0).count(), 500);
+
+        // Segments before the one with the first not acknowledged record are removed
+        serde_wal.truncate_retained().unwrap();
+        let first_stored_index = serde_wal.first_stored_index();
+        assert!(first_stored_index > 0);
+        assert!(first_stored_index <= 400);
+        assert_eq!(serde_wal.read_all().count(), 100);
+        assert_eq!(
+            serde_wal.read(first_stored_index).count() as u64,
+            500 - first_stored_index
+        );
+
+        // Truncation is kept on reopen
+        drop(serde_wal);
+        let serde_wal: SerdeWal<TestRecord> =
+            SerdeWal::new(dir.path().to_str().unwrap(), wal_options()).unwrap();
+        assert_eq!(serde_wal.first_stored_index(), first_stored_index);
+        assert_eq!(serde_wal.read_all().count(), 100);
+    }
 }
@@ -1,3 +1,4 @@
+use collection::config::{WalConfig, WalDurability};
 use collection::operations::point_ops::{
     Batch, PointInsertOperations, PointOperations, WriteOrdering,
 };
@@ -8,7 +9,9 @@ use segment::types::{PayloadContainer, PayloadSelectorExclude, WithPayloadInterf
 use serde_json::Value;
 use tempfile::Builder;
 
-use crate::common::{load_local_collection, simple_collection_fixture, N_SHARDS};
+use crate::common::{
+    load_local_collection, simple_collection_fixture, wal_collection_fixture, N_SHARDS,
+};
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_reloading() {
@@ -52,6 +55,70 @@ async fn test_collection_reloading_with_shards(shard_number: u32) {
     assert_eq!(collection.info(None).await.unwrap().vectors_count, 2);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_reloading_with_wal_durability() {
+    for wal_durability in [
+        WalDurability::WaitedOperations,
+        WalDurability::EveryOperation,
+        WalDurability::Interval,
+        WalDurability::SegmentRotation,
+    ] {
+        test_collection_reloading_with_wal_durability_and_shards(wal_durability, 1).await;
+        test_collection_reloading_with_wal_durability_and_shards(wal_durability, N_SHARDS).await;
+    }
+}
+
+async fn test_collection_reloading_with_wal_durability_and_shards(
+    wal_durability: WalDurability,
+    shard_number: u32,
+) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let wal_config = WalConfig {
+        wal_capacity_mb: 1,
+        wal_segments_ahead: 0,
+        wal_durability,
+        wal_fsync_interval_ms: Some(10),
+        ..Default::default()
+    };
+
+    // Records of several WAL segments are written, the last operation waits for the result
+    let points_count = 2_000;
+    {
+        let collection =
+            wal_collection_fixture(collection_dir.path(), shard_number, wal_config).await;
+        for (chunk_index, ids) in (0..points_count).chunks(100).into_iter().enumerate() {
+            let ids = ids.collect_vec();
+            let vectors = ids
+                .iter()
+                .map(|id| vec![*id as f32, 1.0, 0.0, 1.0])
+                .collect_vec();
+            let insert_points = CollectionUpdateOperations::PointOperation(
+                PointOperations::UpsertPoints(PointInsertOperations::PointsBatch(Batch {
+                    ids: ids.into_iter().map(|x: u64| x.into()).collect_vec(),
+                    vectors: vectors.into(),
+                    payloads: None,
+                })),
+            );
+            let wait = chunk_index + 1 == points_count as usize / 100;
+            collection
+                .update_from_client(insert_points, wait, WriteOrdering::default())
+                .await
+                .unwrap();
+        }
+    }
+
+    let collection_path = collection_dir.path();
+    let collection = load_local_collection(
+        "test".to_string(),
+        collection_path,
+        &collection_path.join("snapshots"),
+    )
+    .await;
+    let info = collection.info(None).await.unwrap();
+    assert_eq!(info.config.wal_config.wal_durability, wal_durability);
+    assert_eq!(info.points_count, points_count as usize);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_payload_reloading() {
     test_collection_payload_reloading_with_shards(1).await;
@@ -90,6 +157,9 @@ async fn test_collection_payload_reloading_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: true.into(),
+                order_by: None,
+                stable: None,
+                cursor: None,
             },
             None,
             None,
@@ -161,6 +231,9 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Fields(vec![String::from("k2")])),
                 with_vector: true.into(),
+                order_by: None,
+                stable: None,
+                cursor: None,
             },
             None,
             None,
@@ -195,6 +268,9 @@ async fn test_collection_payload_custom_payload_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(PayloadSelectorExclude::new(vec!["k1".to_string()]).into()),
                 with_vector: false.into(),
+                order_by: None,
+                stable: None,
+                cursor: None,
             },
             None,
             None,
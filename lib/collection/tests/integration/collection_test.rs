@@ -1,18 +1,26 @@
 use std::collections::HashSet;
 use std::fs::File;
+use std::time::Duration;
 
+use collection::operations::config_diff::{CollectionParamsDiff, OptimizersConfigDiff};
 use collection::operations::payload_ops::{PayloadOps, SetPayload};
-use collection::operations::point_ops::{Batch, PointOperations, PointStruct, WriteOrdering};
+use collection::operations::point_ops::{
+    Batch, ConditionalInsertOperation, PointInsertOperations, PointOperations, PointStruct,
+    UpsertCondition, WriteOrdering,
+};
 use collection::operations::types::{
-    CountRequest, PointRequest, RecommendRequest, ScrollRequest, SearchRequest, UpdateStatus,
+    CollectionError, CountRequest, CountRequestBatch, PointRequest, RecommendRequest,
+    ScrollRequest, SearchRequest, SearchRequestBatch, UpdateStatus,
 };
-use collection::operations::CollectionUpdateOperations;
+use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use collection::recommendations::recommend_by;
 use collection::shards::replica_set::{ReplicaSetState, ReplicaState};
+use common::types::{DetailsLevel, TelemetryDetail};
 use itertools::Itertools;
 use segment::data_types::vectors::VectorStruct;
 use segment::types::{
-    Condition, FieldCondition, Filter, HasIdCondition, Payload, PointIdType, WithPayloadInterface,
+    Condition, FieldCondition, Filter, HasIdCondition, Payload, PayloadFieldSchema,
+    PayloadSchemaType, PointIdType, WithPayloadInterface,
 };
 use tempfile::Builder;
 
@@ -68,6 +76,7 @@ async fn test_collection_updater_with_shards(shard_number: u32) {
         limit: 3,
         offset: 0,
         score_threshold: None,
+        text: None,
     };
 
     let search_res = collection.search(search_request, None, None).await;
@@ -125,6 +134,7 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
         limit: 3,
         offset: 0,
         score_threshold: None,
+        text: None,
     };
 
     let search_res = collection.search(search_request, None, None).await;
@@ -147,6 +157,7 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
             key: "k".to_string(),
             r#match: Some(serde_json::from_str(r#"{ "value": "v2" }"#).unwrap()),
             range: None,
+            datetime_range: None,
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
@@ -157,9 +168,151 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
 
     let count_res = collection.count(count_request, None).await.unwrap();
     assert_eq!(count_res.count, 1);
+
+    let count_batch_request = CountRequestBatch {
+        counts: vec![
+            CountRequest {
+                filter: Some(Filter::new_must_not(Condition::Field(
+                    FieldCondition::new_match("k", "v2".to_string().into()),
+                ))),
+                exact: true,
+            },
+            CountRequest {
+                filter: None,
+                exact: true,
+            },
+        ],
+    };
+
+    let count_batch_res = collection
+        .count_batch(count_batch_request, None)
+        .await
+        .unwrap();
+    let counts: Vec<_> = count_batch_res.iter().map(|res| res.count).collect();
+    assert_eq!(counts, vec![1, 2]);
 }
 
 // FIXME: does not work
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_search_with_timeout() {
+    test_collection_search_with_timeout_with_shards(1).await;
+    test_collection_search_with_timeout_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_search_with_timeout_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..5).map(PointIdType::from).collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0, 0.0],
+                vec![1.0, 1.0, 1.0, 1.0],
+                vec![1.0, 1.0, 0.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+            ]
+            .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let request = SearchRequestBatch {
+        searches: vec![SearchRequest {
+            vector: vec![1.0, 1.0, 1.0, 1.0].into(),
+            with_payload: None,
+            with_vector: None,
+            filter: None,
+            params: None,
+            limit: 3,
+            offset: 0,
+            score_threshold: None,
+            text: None,
+        }],
+    };
+    let expected = collection
+        .search_batch(request.clone(), None, None)
+        .await
+        .unwrap();
+
+    // Local shards are searched segment by segment, the results are the same if all are searched in time
+    for allow_partial in [false, true] {
+        let res = collection
+            .search_batch_with_timeout(
+                request.clone(),
+                None,
+                None,
+                Duration::from_secs(10),
+                allow_partial,
+            )
+            .await
+            .unwrap();
+        assert!(!res.partial);
+        assert_eq!(res.results, expected);
+        assert_eq!(res.results[0][0].id, 2.into());
+    }
+}
+
+/// Segment searches, which run past the deadline, still hold the request when the partial results
+/// are merged
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_search_past_timeout() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+    let num_points = 10_000;
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..num_points).map(PointIdType::from).collect_vec(),
+            vectors: (0..num_points)
+                .map(|i| {
+                    let x = i as f32;
+                    vec![x.sin(), x.cos(), (x * 0.5).sin(), (x * 0.5).cos()]
+                })
+                .collect_vec()
+                .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let request = SearchRequestBatch {
+        searches: (0..100)
+            .map(|i| SearchRequest {
+                vector: vec![1.0, i as f32, 1.0, 1.0].into(),
+                with_payload: None,
+                with_vector: None,
+                filter: None,
+                params: None,
+                limit: 10,
+                offset: 0,
+                score_threshold: None,
+                text: None,
+            })
+            .collect(),
+    };
+
+    // Deadline is already reached, so the segments are searched past it
+    for _ in 0..10 {
+        let res = collection
+            .search_batch_with_timeout(request.clone(), None, None, Duration::ZERO, true)
+            .await
+            .unwrap();
+        assert_eq!(res.results.len(), request.searches.len());
+        assert!(res.results.iter().all(|points| points.len() <= 10));
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_loading() {
     test_collection_loading_with_shards(1).await;
@@ -388,6 +541,9 @@ async fn test_read_api_with_shards(shard_number: u32) {
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: false.into(),
+                order_by: None,
+                stable: None,
+                cursor: None,
             },
             None,
             None,
@@ -399,6 +555,103 @@ async fn test_read_api_with_shards(shard_number: u32) {
     assert_eq!(result.points.len(), 2);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_stable_scroll() {
+    test_stable_scroll_with_shards(1).await;
+    test_stable_scroll_with_shards(N_SHARDS).await;
+}
+
+async fn test_stable_scroll_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let upsert_points = |ids: Vec<u64>| {
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            Batch {
+                vectors: vec![vec![1.0, 0.0, 0.0, 0.0]; ids.len()].into(),
+                ids: ids.into_iter().map(|x| x.into()).collect_vec(),
+                payloads: None,
+            }
+            .into(),
+        ))
+    };
+
+    collection
+        .update_from_client(
+            upsert_points(vec![0, 2, 4, 6, 8, 10]),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+
+    let first_page = collection
+        .scroll_by(
+            ScrollRequest {
+                limit: Some(2),
+                stable: Some(true),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        first_page.points.iter().map(|point| point.id).collect_vec(),
+        [0.into(), 2.into()]
+    );
+    assert!(first_page.next_page_offset.is_none());
+    let cursor = first_page.next_page_cursor.unwrap();
+
+    // Points inserted or deleted after the first page are skipped, changed points are still read
+    collection
+        .update_from_client(
+            upsert_points(vec![1, 3, 5, 10]),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    let delete_point = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+        ids: vec![6.into()],
+    });
+    collection
+        .update_from_client(delete_point, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let read_page = |cursor: String| {
+        collection.scroll_by(
+            ScrollRequest {
+                limit: Some(2),
+                cursor: Some(cursor),
+                ..Default::default()
+            },
+            None,
+            None,
+        )
+    };
+
+    // Page is the same, if it is read again by the same cursor
+    let mut next_page_cursor = None;
+    for _ in 0..2 {
+        let page = read_page(cursor.clone()).await.unwrap();
+        assert_eq!(
+            page.points.iter().map(|point| point.id).collect_vec(),
+            [4.into(), 8.into()]
+        );
+        next_page_cursor = page.next_page_cursor;
+    }
+
+    let last_page = read_page(next_page_cursor.unwrap()).await.unwrap();
+    assert_eq!(
+        last_page.points.iter().map(|point| point.id).collect_vec(),
+        [10.into()]
+    );
+    assert!(last_page.next_page_cursor.is_none());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_delete_points_by_filter() {
     test_collection_delete_points_by_filter_with_shards(1).await;
@@ -444,6 +697,7 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
     let to_be_deleted: HashSet<PointIdType> = vec![0.into(), 3.into()].into_iter().collect();
     let delete_filter = segment::types::Filter {
         should: None,
+        min_should: None,
         must: Some(vec![Condition::HasId(HasIdCondition::from(to_be_deleted))]),
         must_not: None,
     };
@@ -471,6 +725,9 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
                 filter: None,
                 with_payload: Some(WithPayloadInterface::Bool(false)),
                 with_vector: false.into(),
+                order_by: None,
+                stable: None,
+                cursor: None,
             },
             None,
             None,
@@ -485,6 +742,136 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
     assert_eq!(result.points.get(2).unwrap().id, 4.into());
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_set_payload_by_filter() {
+    test_collection_set_payload_by_filter_with_shards(1).await;
+    test_collection_set_payload_by_filter_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_set_payload_by_filter_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let payloads = ["a", "a", "b", "a", "b"]
+        .into_iter()
+        .map(|label| Some(serde_json::from_value(serde_json::json!({ "label": label })).unwrap()))
+        .collect();
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0, 0.0],
+                vec![1.0, 1.0, 1.0, 1.0],
+                vec![1.0, 1.0, 0.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+            ]
+            .into(),
+            payloads: Some(payloads),
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let label_filter = |label: &str| {
+        Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "label",
+            label.to_string().into(),
+        )))
+    };
+    let count = |filter: Filter| {
+        let collection = &collection;
+        async move {
+            collection
+                .count(
+                    CountRequest {
+                        filter: Some(filter),
+                        exact: true,
+                    },
+                    None,
+                )
+                .await
+                .unwrap()
+                .count
+        }
+    };
+
+    // Set payload to the points of all shards, which match the filter
+    let set_payload =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+            payload: serde_json::from_str(r#"{"color":"red"}"#).unwrap(),
+            points: None,
+            filter: Some(label_filter("a")),
+        }));
+    let result = collection
+        .update_from_client(set_payload, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(result.status, UpdateStatus::Completed);
+    assert_eq!(result.affected_points, Some(3));
+
+    let red_filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "color",
+        "red".to_string().into(),
+    )));
+    assert_eq!(count(red_filter.clone()).await, 3);
+    // Set payload keeps the other keys
+    assert_eq!(count(label_filter("a")).await, 3);
+
+    // Overwrite replaces the whole payload of the matching points only
+    let overwrite_payload =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::OverwritePayload(SetPayload {
+            payload: serde_json::from_str(r#"{"label":"c"}"#).unwrap(),
+            points: None,
+            filter: Some(red_filter.clone()),
+        }));
+    let result = collection
+        .update_from_client(overwrite_payload, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(result.affected_points, Some(3));
+
+    assert_eq!(count(red_filter).await, 0);
+    assert_eq!(count(label_filter("c")).await, 3);
+    assert_eq!(count(label_filter("b")).await, 2);
+
+    // Nothing matches the filter
+    let set_payload =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+            payload: serde_json::from_str(r#"{"color":"blue"}"#).unwrap(),
+            points: None,
+            filter: Some(label_filter("a")),
+        }));
+    let result = collection
+        .update_from_client(set_payload, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(result.affected_points, Some(0));
+
+    // Not waiting for the update, so the number of the affected points is unknown
+    let set_payload =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(SetPayload {
+            payload: serde_json::from_str(r#"{"color":"green"}"#).unwrap(),
+            points: None,
+            filter: Some(label_filter("b")),
+        }));
+    let result = collection
+        .update_from_client(set_payload, false, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(result.status, UpdateStatus::Acknowledged);
+    assert_eq!(result.affected_points, None);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_collection_local_load_initializing_not_stuck() {
     let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
@@ -526,3 +913,355 @@ async fn test_collection_local_load_initializing_not_stuck() {
         }
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_cache() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), N_SHARDS).await;
+
+    collection
+        .update_params_from_diff(CollectionParamsDiff {
+            replication_factor: None,
+            write_consistency_factor: None,
+            read_fan_out_factor: None,
+            on_disk_payload: None,
+            search_cache_size: Some(10),
+            scoring_plugin: None,
+        })
+        .await
+        .unwrap();
+
+    let upsert = |id: u64, vector: Vec<f32>| {
+        CollectionUpdateOperations::PointOperation(
+            Batch {
+                ids: vec![id.into()],
+                vectors: vec![vector].into(),
+                payloads: None,
+            }
+            .into(),
+        )
+    };
+    collection
+        .update_from_client(
+            upsert(0, vec![1.0, 0.0, 0.0, 0.0]),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+
+    let search_request = SearchRequest {
+        vector: vec![1.0, 1.0, 1.0, 1.0].into(),
+        with_payload: None,
+        with_vector: None,
+        filter: None,
+        params: None,
+        limit: 3,
+        offset: 0,
+        score_threshold: None,
+        text: None,
+    };
+    let cache_telemetry = || async {
+        collection
+            .get_telemetry_data(TelemetryDetail::default())
+            .await
+            .search_cache
+            .unwrap()
+    };
+
+    let first = collection
+        .search(search_request.clone(), None, None)
+        .await
+        .unwrap();
+    let second = collection
+        .search(search_request.clone(), None, None)
+        .await
+        .unwrap();
+    assert_eq!(first, second);
+    let telemetry = cache_telemetry().await;
+    assert_eq!((telemetry.hits, telemetry.misses), (1, 1));
+
+    // Update changes the version of the data, so the cached result is not used anymore
+    collection
+        .update_from_client(
+            upsert(1, vec![1.0, 1.0, 1.0, 1.0]),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    let updated = collection.search(search_request, None, None).await.unwrap();
+    assert_eq!(updated.len(), 2);
+    assert_eq!(updated[0].id, 1.into());
+    let telemetry = cache_telemetry().await;
+    assert_eq!((telemetry.hits, telemetry.misses), (1, 2));
+
+    // Searches served from the cache don't reach the shards
+    let detail = TelemetryDetail::new(DetailsLevel::Level2, true);
+    let shards_telemetry = collection.get_telemetry_data(detail).await.shards;
+    let searches: usize = shards_telemetry
+        .iter()
+        .filter_map(|shard| shard.local.as_ref())
+        .map(|shard| shard.searches.count)
+        .sum();
+    assert_eq!(searches, 2 * N_SHARDS as usize);
+    let histogram = shards_telemetry[0]
+        .local
+        .as_ref()
+        .and_then(|shard| shard.updates.duration_histogram.as_ref());
+    assert!(histogram.is_some());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_pause_optimizers() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), N_SHARDS).await;
+
+    collection.set_optimizers_paused(true).await.unwrap();
+    let info = collection.info(None).await.unwrap();
+    assert!(info.optimizations.paused);
+
+    // Forced optimizations are rejected as well
+    for optimization in collection.launch_forced_optimization(None).await {
+        assert!(optimization.await.is_err());
+    }
+
+    collection.set_optimizers_paused(false).await.unwrap();
+    let info = collection.info(None).await.unwrap();
+    assert!(!info.optimizations.paused);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_conditional_upsert() {
+    test_collection_conditional_upsert_with_shards(1).await;
+    test_collection_conditional_upsert_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_conditional_upsert_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let upsert_if_absent = |ids: Vec<u64>| {
+        let points = ids
+            .into_iter()
+            .map(|id| PointStruct {
+                id: id.into(),
+                vector: vec![1.0, 0.0, 1.0, 1.0].into(),
+                payload: None,
+            })
+            .collect_vec();
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPointsConditional(
+            ConditionalInsertOperation {
+                points: PointInsertOperations::PointsList(points),
+                condition: UpsertCondition::IfAbsent,
+            },
+        ))
+    };
+    let count = || async {
+        collection
+            .count(
+                CountRequest {
+                    filter: None,
+                    exact: true,
+                },
+                None,
+            )
+            .await
+            .unwrap()
+            .count
+    };
+
+    let result = collection
+        .update_from_client(
+            upsert_if_absent((0..10).collect()),
+            true,
+            WriteOrdering::default(),
+        )
+        .await;
+
+    if shard_number == 1 {
+        assert_eq!(result.unwrap().status, UpdateStatus::Completed);
+        assert_eq!(count().await, 10);
+    } else {
+        // Points of several shards can't be updated conditionally, nothing is applied
+        assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+        assert_eq!(count().await, 0);
+
+        collection
+            .update_from_client(upsert_if_absent(vec![0]), true, WriteOrdering::default())
+            .await
+            .unwrap();
+        assert_eq!(count().await, 1);
+    }
+
+    // Point 0 is stored already
+    let result = collection
+        .update_from_client(upsert_if_absent(vec![0]), true, WriteOrdering::default())
+        .await;
+    assert!(matches!(
+        result,
+        Err(CollectionError::PreconditionFailed { .. })
+    ));
+
+    // Result of the condition can't be reported without waiting for the update
+    let result = collection
+        .update_from_client(upsert_if_absent(vec![100]), false, WriteOrdering::default())
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_expire_points() {
+    test_collection_expire_points_with_shards(1).await;
+    test_collection_expire_points_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_expire_points_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    // Even points are expired, odd ones expire in the future, the last one never expires
+    let payloads: Vec<Option<Payload>> = (0..10)
+        .map(|id| {
+            let expires_at = if id % 2 == 0 {
+                "2000-01-01T00:00:00Z"
+            } else {
+                "2100-01-01T00:00:00Z"
+            };
+            Some(serde_json::json!({ "expires_at": expires_at, "label": "a" }).into())
+        })
+        .chain([Some(serde_json::json!({ "label": "a" }).into())])
+        .collect();
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: (0..11).map(|x| x.into()).collect_vec(),
+            vectors: vec![vec![1.0, 0.0, 1.0, 1.0]; 11].into(),
+            payloads: Some(payloads),
+        }
+        .into(),
+    );
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let count = || async {
+        collection
+            .count(
+                CountRequest {
+                    filter: None,
+                    exact: true,
+                },
+                None,
+            )
+            .await
+            .unwrap()
+            .count
+    };
+    let set_expiration_key = |key: &str| {
+        serde_json::from_value::<OptimizersConfigDiff>(serde_json::json!({ "expiration_key": key }))
+            .unwrap()
+    };
+
+    // Nothing expires without the expiration key
+    assert_eq!(collection.expire_points().await.unwrap(), 0);
+    assert_eq!(count().await, 11);
+
+    collection
+        .update_optimizer_params_from_diff(set_expiration_key("expires_at"))
+        .await
+        .unwrap();
+
+    assert_eq!(collection.expire_points().await.unwrap(), 5);
+    assert_eq!(count().await, 6);
+
+    // Expired points are looked up in the datetime index, created on the first run
+    let info = collection.info(None).await.unwrap();
+    assert_eq!(
+        info.payload_schema["expires_at"].data_type,
+        PayloadSchemaType::Datetime
+    );
+
+    assert_eq!(collection.expire_points().await.unwrap(), 0);
+    assert_eq!(count().await, 6);
+
+    // Key with an index of another type is not scanned
+    let create_index = CollectionUpdateOperations::FieldIndexOperation(
+        FieldIndexOperations::CreateIndex(CreateIndex {
+            field_name: "label".to_string(),
+            field_schema: Some(PayloadFieldSchema::FieldType(PayloadSchemaType::Keyword)),
+        }),
+    );
+    collection
+        .update_from_client(create_index, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    collection
+        .update_optimizer_params_from_diff(set_expiration_key("label"))
+        .await
+        .unwrap();
+
+    assert_eq!(collection.expire_points().await.unwrap(), 0);
+    assert_eq!(count().await, 6);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_collection_wal_info() {
+    test_collection_wal_info_with_shards(1).await;
+    test_collection_wal_info_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_wal_info_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    for id in 0..10u64 {
+        let insert_point = CollectionUpdateOperations::PointOperation(
+            PointOperations::UpsertPoints(PointInsertOperations::PointsList(vec![PointStruct {
+                id: id.into(),
+                vector: vec![1.0, 0.0, 1.0, id as f32].into(),
+                payload: None,
+            }])),
+        );
+        collection
+            .update_from_client(insert_point, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+
+    let wal_info = collection.wal_info().await.unwrap();
+    assert_eq!(
+        wal_info.iter().map(|info| info.shard_id).collect_vec(),
+        (0..shard_number).collect_vec()
+    );
+    for info in &wal_info {
+        assert!(info.first_stored_operation <= info.first_replayed_operation);
+        assert!(info.first_replayed_operation <= info.next_operation);
+        assert_eq!(
+            info.retained_operations,
+            info.first_replayed_operation - info.first_stored_operation
+        );
+        assert!(info.disk_size_bytes > 0);
+    }
+    // Every point is upserted by a separate operation of its shard
+    let operations: u64 = wal_info.iter().map(|info| info.next_operation).sum();
+    assert_eq!(operations, 10);
+
+    // Nothing is retained without retention limits, so the truncation keeps operations to replay
+    let truncated = collection.truncate_retained_wal(0).await.unwrap();
+    assert_eq!(truncated.shard_id, 0);
+    assert_eq!(truncated.retained_operations, 0);
+    assert_eq!(truncated.next_operation, wal_info[0].next_operation);
+    assert_eq!(
+        truncated.first_replayed_operation,
+        wal_info[0].first_replayed_operation
+    );
+
+    assert!(matches!(
+        collection.truncate_retained_wal(shard_number).await,
+        Err(CollectionError::NotFound { .. })
+    ));
+}
@@ -29,6 +29,9 @@ pub const TEST_OPTIMIZERS_CONFIG: OptimizersConfig = OptimizersConfig {
     indexing_threshold: Some(50_000),
     flush_interval_sec: 30,
     max_optimization_threads: 2,
+    expiration_key: None,
+    merge_policy: None,
+    defragmentation_key: None,
 };
 
 #[cfg(test)]
@@ -37,8 +40,16 @@ pub async fn simple_collection_fixture(collection_path: &Path, shard_number: u32
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
+    wal_collection_fixture(collection_path, shard_number, wal_config).await
+}
 
+pub async fn wal_collection_fixture(
+    collection_path: &Path,
+    shard_number: u32,
+    wal_config: WalConfig,
+) -> Collection {
     let collection_params = CollectionParams {
         vectors: VectorParams {
             size: NonZeroU64::new(4).unwrap(),
@@ -46,8 +57,11 @@ pub async fn simple_collection_fixture(collection_path: &Path, shard_number: u32
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            multivector_config: None,
+            datatype: None,
         }
         .into(),
+        sparse_vectors: None,
         shard_number: NonZeroU32::new(shard_number).expect("Shard number can not be zero"),
         ..CollectionParams::empty()
     };
@@ -42,6 +42,7 @@ mod group_by {
             with_payload: None,
             with_vector: None,
             score_threshold: None,
+            text: None,
         });
 
         let request = GroupRequest::with_limit_from_request(source, "docId".to_string(), 3);
@@ -206,6 +207,7 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                text: None,
             }),
             "docId".to_string(),
             3,
@@ -241,6 +243,7 @@ mod group_by {
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: Some(WithVector::Bool(true)),
                 score_threshold: None,
+                text: None,
             }),
             "docId".to_string(),
             3,
@@ -287,6 +290,7 @@ mod group_by {
                 with_payload: Some(WithPayloadInterface::Bool(true)),
                 with_vector: Some(WithVector::Bool(true)),
                 score_threshold: None,
+                text: None,
             }),
             "other_stuff".to_string(),
             3,
@@ -331,6 +335,7 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                text: None,
             }),
             "docId".to_string(),
             0,
@@ -371,6 +376,7 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                text: None,
             }),
             "docId".to_string(),
             3,
@@ -411,6 +417,7 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                text: None,
             }),
             "docId".to_string(),
             3,
@@ -455,6 +462,7 @@ mod group_by {
                 with_payload: None,
                 with_vector: None,
                 score_threshold: None,
+                text: None,
             }),
             "docId".to_string(),
             400,
@@ -512,6 +520,7 @@ mod group_by_builder {
             with_payload: None,
             with_vector: None,
             score_threshold: None,
+            text: None,
         });
 
         let request = GroupRequest::with_limit_from_request(source_request, "docId".to_string(), 3);
@@ -2,13 +2,18 @@ use std::collections::BTreeMap;
 use std::num::{NonZeroU32, NonZeroU64};
 use std::path::Path;
 
-use collection::collection::Collection;
-use collection::config::{CollectionConfig, CollectionParams, WalConfig};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine as _;
+use collection::collection::{resolve_query_lookups, Collection};
+use collection::config::{CollectionConfig, CollectionParams, ScoringPluginConfig, WalConfig};
+use collection::operations::config_diff::{CollectionParamsDiff, Disabled, ScoringPluginDiff};
 use collection::operations::point_ops::{
     PointInsertOperations, PointOperations, PointStruct, WriteOrdering,
 };
 use collection::operations::types::{
-    CollectionError, PointRequest, RecommendRequest, SearchRequest, VectorParams, VectorsConfig,
+    CollectionError, Fusion, LookupLocation, PluginQuery, PointRequest, Prefetch, Query,
+    QueryRequest, RecommendExample, RecommendInput, RecommendRequest, SearchRequest, VectorParams,
+    VectorsConfig,
 };
 use collection::operations::CollectionUpdateOperations;
 use collection::recommendations::recommend_by;
@@ -16,11 +21,13 @@ use segment::data_types::named_vectors::NamedVectors;
 use segment::data_types::vectors::{NamedVector, VectorStruct};
 use segment::types::{Distance, WithPayloadInterface, WithVector};
 use tempfile::Builder;
+use tokio::sync::RwLock;
 
 use crate::common::{new_local_collection, N_SHARDS, TEST_OPTIMIZERS_CONFIG};
 
 const VEC_NAME1: &str = "vec1";
 const VEC_NAME2: &str = "vec2";
+const VEC_NAME3: &str = "vec3";
 
 #[tokio::test(flavor = "multi_thread")]
 async fn test_multi_vec() {
@@ -33,6 +40,7 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let vector_params1 = VectorParams {
@@ -41,6 +49,8 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         hnsw_config: None,
         quantization_config: None,
         on_disk: None,
+        multivector_config: None,
+        datatype: None,
     };
     let vector_params2 = VectorParams {
         size: NonZeroU64::new(4).unwrap(),
@@ -48,6 +58,8 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
         hnsw_config: None,
         quantization_config: None,
         on_disk: None,
+        multivector_config: None,
+        datatype: None,
     };
 
     let mut vectors_config = BTreeMap::new();
@@ -57,6 +69,7 @@ pub async fn multi_vec_collection_fixture(collection_path: &Path, shard_number:
 
     let collection_params = CollectionParams {
         vectors: VectorsConfig::Multi(vectors_config),
+        sparse_vectors: None,
         shard_number: NonZeroU32::new(shard_number).expect("Shard number can not be zero"),
         ..CollectionParams::empty()
     };
@@ -126,6 +139,7 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         with_vector: Some(true.into()),
         params: None,
         score_threshold: None,
+        text: None,
     };
 
     let result = collection
@@ -154,6 +168,7 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         with_vector: Some(true.into()),
         params: None,
         score_threshold: None,
+        text: None,
     };
 
     let result = collection.search(failed_search_request, None, None).await;
@@ -176,6 +191,7 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         with_vector: Some(true.into()),
         params: None,
         score_threshold: None,
+        text: None,
     };
 
     let result = collection
@@ -265,3 +281,652 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
         }
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_add_vector() {
+    test_add_vector_with_shards(1).await;
+    test_add_vector_with_shards(N_SHARDS).await;
+}
+
+async fn test_add_vector_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_add_vector_with_shards")
+        .tempdir()
+        .unwrap();
+
+    let collection = multi_vec_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let mut points = Vec::new();
+    for i in 0..100 {
+        let mut vectors = NamedVectors::default();
+        vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+        vectors.insert(VEC_NAME2.to_string(), vec![0.0, i as f32, 0.0, 0.0]);
+
+        points.push(PointStruct {
+            id: i.into(),
+            vector: vectors.into(),
+            payload: None,
+        });
+    }
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let new_vector_params = VectorParams {
+        size: NonZeroU64::new(2).unwrap(),
+        distance: Distance::Dot,
+        hnsw_config: None,
+        quantization_config: None,
+        on_disk: None,
+        multivector_config: None,
+        datatype: None,
+    };
+    let new_vectors =
+        VectorsConfig::Multi(BTreeMap::from([(VEC_NAME3.to_string(), new_vector_params)]));
+    collection.add_vectors(&new_vectors).await.unwrap();
+    collection.recreate_optimizers_blocking().await.unwrap();
+
+    // Vector names must be unique
+    let result = collection.add_vectors(&new_vectors).await;
+    assert!(
+        matches!(result, Err(CollectionError::BadInput { .. })),
+        "{result:?}"
+    );
+
+    let mut vectors = NamedVectors::default();
+    vectors.insert(VEC_NAME1.to_string(), vec![1.0, 0.0, 0.0, 0.0]);
+    vectors.insert(VEC_NAME3.to_string(), vec![1.0, 1.0]);
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(vec![PointStruct {
+            id: 1000.into(),
+            vector: vectors.into(),
+            payload: None,
+        }]),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Only the point with the new vector is found by it
+    let search_request = SearchRequest {
+        vector: NamedVector {
+            name: VEC_NAME3.to_string(),
+            vector: vec![1.0, 0.0],
+        }
+        .into(),
+        filter: None,
+        limit: 10,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        params: None,
+        score_threshold: None,
+        text: None,
+    };
+    let result = collection.search(search_request, None, None).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, 1000.into());
+
+    // Existing points are still searchable by the old vectors
+    let search_request = SearchRequest {
+        vector: NamedVector {
+            name: VEC_NAME1.to_string(),
+            vector: vec![1.0, 0.0, 0.0, 0.0],
+        }
+        .into(),
+        filter: None,
+        limit: 10,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        params: None,
+        score_threshold: None,
+        text: None,
+    };
+    let result = collection.search(search_request, None, None).await.unwrap();
+    assert_eq!(result.len(), 10);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_drop_vector() {
+    test_drop_vector_with_shards(1).await;
+    test_drop_vector_with_shards(N_SHARDS).await;
+}
+
+async fn test_drop_vector_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_drop_vector_with_shards")
+        .tempdir()
+        .unwrap();
+
+    let collection = multi_vec_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let mut points = Vec::new();
+    for i in 0..100 {
+        let mut vectors = NamedVectors::default();
+        vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+        vectors.insert(VEC_NAME2.to_string(), vec![0.0, i as f32, 0.0, 0.0]);
+
+        points.push(PointStruct {
+            id: i.into(),
+            vector: vectors.into(),
+            payload: None,
+        });
+    }
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    collection
+        .drop_vectors(&[VEC_NAME2.to_string()])
+        .await
+        .unwrap();
+    collection.recreate_optimizers_blocking().await.unwrap();
+
+    // Only existing vectors can be removed
+    let result = collection.drop_vectors(&[VEC_NAME2.to_string()]).await;
+    assert!(
+        matches!(result, Err(CollectionError::BadInput { .. })),
+        "{result:?}"
+    );
+
+    // Last vector of the collection can't be removed
+    let result = collection.drop_vectors(&[VEC_NAME1.to_string()]).await;
+    assert!(
+        matches!(result, Err(CollectionError::BadInput { .. })),
+        "{result:?}"
+    );
+
+    // Removed vector can't be searched anymore
+    let search_request = SearchRequest {
+        vector: NamedVector {
+            name: VEC_NAME2.to_string(),
+            vector: vec![0.0, 1.0, 0.0, 0.0],
+        }
+        .into(),
+        filter: None,
+        limit: 10,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        params: None,
+        score_threshold: None,
+        text: None,
+    };
+    assert!(collection.search(search_request, None, None).await.is_err());
+
+    // Points keep the other vectors
+    let retrieve = collection
+        .retrieve(
+            PointRequest {
+                ids: vec![6.into()],
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                with_vector: WithVector::Bool(true),
+            },
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(retrieve.len(), 1);
+    match retrieve[0].vector.as_ref().unwrap() {
+        VectorStruct::Single(_) => panic!("expected multi vector"),
+        VectorStruct::Multi(vectors) => {
+            assert!(vectors.contains_key(VEC_NAME1));
+            assert!(!vectors.contains_key(VEC_NAME2));
+        }
+    }
+
+    let search_request = SearchRequest {
+        vector: NamedVector {
+            name: VEC_NAME1.to_string(),
+            vector: vec![1.0, 0.0, 0.0, 0.0],
+        }
+        .into(),
+        filter: None,
+        limit: 10,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        params: None,
+        score_threshold: None,
+        text: None,
+    };
+    let result = collection.search(search_request, None, None).await.unwrap();
+    assert_eq!(result.len(), 10);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_multi_vec_rescore() {
+    test_multi_vec_rescore_with_shards(1).await;
+    test_multi_vec_rescore_with_shards(N_SHARDS).await;
+}
+
+async fn test_multi_vec_rescore_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_multi_vec_rescore")
+        .tempdir()
+        .unwrap();
+
+    let collection = multi_vec_collection_fixture(collection_dir.path(), shard_number).await;
+
+    // Second vector orders the points in the opposite direction
+    let mut points = Vec::new();
+    for i in 0..1000 {
+        let mut vectors = NamedVectors::default();
+        vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+        vectors.insert(
+            VEC_NAME2.to_string(),
+            vec![(1000 - i) as f32, 0.0, 0.0, 0.0],
+        );
+
+        points.push(PointStruct {
+            id: i.into(),
+            vector: vectors.into(),
+            payload: None,
+        });
+    }
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Candidates are selected by the first vector and rescored by the second one
+    let query_request = QueryRequest {
+        prefetch: vec![Prefetch {
+            prefetch: vec![],
+            query: Some(Query::Nearest(vec![1.0, 0.0, 0.0, 0.0].into())),
+            using: Some(VEC_NAME1.to_string()),
+            lookup_from: None,
+            filter: None,
+            params: None,
+            score_threshold: None,
+            limit: 20,
+        }],
+        query: Some(Query::Nearest(vec![1.0, 0.0, 0.0, 0.0].into())),
+        using: Some(VEC_NAME2.to_string()),
+        lookup_from: None,
+        filter: None,
+        params: None,
+        score_threshold: None,
+        limit: 3,
+        offset: 0,
+        diversity: None,
+        with_payload: None,
+        with_vector: None,
+        with_explanation: false,
+    };
+    let result = collection.query(query_request, None, None).await.unwrap();
+
+    let ids: Vec<_> = result.iter().map(|point| point.id).collect();
+    assert_eq!(ids, vec![980.into(), 981.into(), 982.into()]);
+    let scores: Vec<_> = result.iter().map(|point| point.score).collect();
+    assert_eq!(scores, vec![20.0, 19.0, 18.0]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_query_lookup_from() {
+    let collection_dir = Builder::new()
+        .prefix("test_query_lookup_from")
+        .tempdir()
+        .unwrap();
+    let lookup_dir = Builder::new()
+        .prefix("test_query_lookup_from_lookup")
+        .tempdir()
+        .unwrap();
+
+    let collection = multi_vec_collection_fixture(collection_dir.path(), 1).await;
+    let lookup_collection = multi_vec_collection_fixture(lookup_dir.path(), 1).await;
+
+    let upsert = |points: Vec<(u64, Vec<f32>, Vec<f32>)>| {
+        let points = points
+            .into_iter()
+            .map(|(id, vector1, vector2)| {
+                let mut vectors = NamedVectors::default();
+                vectors.insert(VEC_NAME1.to_string(), vector1);
+                vectors.insert(VEC_NAME2.to_string(), vector2);
+                PointStruct {
+                    id: id.into(),
+                    vector: vectors.into(),
+                    payload: None,
+                }
+            })
+            .collect();
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperations::PointsList(points),
+        ))
+    };
+
+    let basis = |i: usize| {
+        let mut vector = vec![0.0; 4];
+        vector[i] = 1.0;
+        vector
+    };
+    collection
+        .update_from_client(
+            upsert((0..4).map(|i| (i as u64, basis(i), basis(i))).collect()),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    // Example point exists only in the lookup collection
+    lookup_collection
+        .update_from_client(
+            upsert(vec![(100, basis(0), basis(2))]),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+
+    let request = QueryRequest {
+        prefetch: vec![],
+        query: Some(Query::Recommend(RecommendInput {
+            positive: vec![RecommendExample::PointId(100.into())],
+            negative: vec![],
+        })),
+        using: Some(VEC_NAME1.to_string()),
+        lookup_from: Some(LookupLocation {
+            collection: "lookup".to_string(),
+            vector: Some(VEC_NAME2.to_string()),
+        }),
+        filter: None,
+        params: None,
+        score_threshold: None,
+        limit: 1,
+        offset: 0,
+        diversity: None,
+        with_payload: None,
+        with_vector: None,
+        with_explanation: false,
+    };
+
+    let lookup_collection = RwLock::new(lookup_collection);
+    let lookup_collection = &lookup_collection;
+    let request = resolve_query_lookups(
+        request,
+        |name| {
+            assert_eq!(name, "lookup");
+            async move { Some(lookup_collection.read().await) }
+        },
+        None,
+    )
+    .await
+    .unwrap();
+
+    let result = collection.query(request, None, None).await.unwrap();
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, 2.into());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_query_explanation() {
+    let collection_dir = Builder::new()
+        .prefix("test_query_explanation")
+        .tempdir()
+        .unwrap();
+
+    let collection = multi_vec_collection_fixture(collection_dir.path(), 1).await;
+
+    let mut points = Vec::new();
+    for i in 0..10 {
+        let mut vectors = NamedVectors::default();
+        vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+        vectors.insert(VEC_NAME2.to_string(), vec![(10 - i) as f32, 0.0, 0.0, 0.0]);
+
+        points.push(PointStruct {
+            id: i.into(),
+            vector: vectors.into(),
+            payload: None,
+        });
+    }
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let prefetch = |using: &str, limit: usize| Prefetch {
+        prefetch: vec![],
+        query: Some(Query::Nearest(vec![1.0, 0.0, 0.0, 0.0].into())),
+        using: Some(using.to_string()),
+        lookup_from: None,
+        filter: None,
+        params: None,
+        score_threshold: None,
+        limit,
+    };
+    let query_request = QueryRequest {
+        prefetch: vec![prefetch(VEC_NAME1, 3), prefetch(VEC_NAME2, 10)],
+        query: Some(Query::Fusion(Fusion::Rrf)),
+        using: None,
+        lookup_from: None,
+        filter: None,
+        params: None,
+        score_threshold: None,
+        limit: 1,
+        offset: 0,
+        diversity: None,
+        with_payload: None,
+        with_vector: None,
+        with_explanation: true,
+    };
+    let result = collection.query(query_request, None, None).await.unwrap();
+
+    // Point 9 is the first by the first vector and the last by the second one
+    assert_eq!(result.len(), 1);
+    assert_eq!(result[0].id, 9.into());
+    let explanation = result[0].explanation.as_ref().unwrap();
+    let prefetches: Vec<_> = explanation
+        .prefetches
+        .iter()
+        .map(|prefetch| prefetch.as_ref().unwrap())
+        .collect();
+    assert_eq!(prefetches.len(), 2);
+    assert_eq!((prefetches[0].rank, prefetches[0].score), (0, 9.0));
+    assert_eq!((prefetches[1].rank, prefetches[1].score), (9, 1.0));
+
+    // Fused score is the sum of the contributions of the prefetches
+    let contributions: f32 = prefetches
+        .iter()
+        .map(|prefetch| prefetch.contribution.unwrap())
+        .sum();
+    assert!((contributions - result[0].score).abs() < 1e-6);
+
+    // Scores of the prefetches are explained by the similarity of the vectors
+    let similarity = prefetches[0].explanation.as_ref().unwrap().similarity;
+    assert_eq!(similarity, Some(9.0));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_query_scoring_plugin() {
+    let collection_dir = Builder::new()
+        .prefix("test_query_scoring_plugin")
+        .tempdir()
+        .unwrap();
+
+    let collection = multi_vec_collection_fixture(collection_dir.path(), 1).await;
+
+    let mut points = Vec::new();
+    for i in 0..10 {
+        let mut vectors = NamedVectors::default();
+        vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+        vectors.insert(VEC_NAME2.to_string(), vec![(10 - i) as f32, 0.0, 0.0, 0.0]);
+
+        let boost = if i == 2 { 10.0 } else { 0.0 };
+        points.push(PointStruct {
+            id: i.into(),
+            vector: vectors.into(),
+            payload: Some(serde_json::json!({ "boost": boost }).into()),
+        });
+    }
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // `score(score: f32, boost: f32) -> f32`, which returns `score + boost`
+    let module: Vec<u8> = [
+        0x00, 0x61, 0x73, 0x6d, 0x01, 0x00, 0x00, 0x00, // header
+        0x01, 0x07, 0x01, 0x60, 0x02, 0x7d, 0x7d, 0x01, 0x7d, // type (f32, f32) -> f32
+        0x03, 0x02, 0x01, 0x00, // function of the type
+        0x07, 0x09, 0x01, 0x05, b's', b'c', b'o', b'r', b'e', 0x00, 0x00, // export `score`
+        0x0a, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x92, 0x0b, // a + b
+    ]
+    .to_vec();
+    let plugin = ScoringPluginConfig {
+        name: "boost".to_string(),
+        version: 1,
+        module: BASE64.encode(module),
+        features: vec!["boost".to_string()],
+    };
+    let params_diff = |scoring_plugin| CollectionParamsDiff {
+        replication_factor: None,
+        write_consistency_factor: None,
+        read_fan_out_factor: None,
+        on_disk_payload: None,
+        search_cache_size: None,
+        scoring_plugin: Some(scoring_plugin),
+    };
+    collection
+        .update_params_from_diff(params_diff(ScoringPluginDiff::Plugin(plugin.clone())))
+        .await
+        .unwrap();
+
+    let query_request = |version| QueryRequest {
+        prefetch: vec![Prefetch {
+            prefetch: vec![],
+            query: Some(Query::Nearest(vec![1.0, 0.0, 0.0, 0.0].into())),
+            using: Some(VEC_NAME1.to_string()),
+            lookup_from: None,
+            filter: None,
+            params: None,
+            score_threshold: None,
+            limit: 10,
+        }],
+        query: Some(Query::Plugin(PluginQuery {
+            name: "boost".to_string(),
+            version,
+        })),
+        using: None,
+        lookup_from: None,
+        filter: None,
+        params: None,
+        score_threshold: None,
+        limit: 3,
+        offset: 0,
+        diversity: None,
+        with_payload: None,
+        with_vector: None,
+        with_explanation: false,
+    };
+    let result = collection
+        .query(query_request(Some(1)), None, None)
+        .await
+        .unwrap();
+
+    // Point 2 is boosted above the nearest points
+    let ids: Vec<_> = result.iter().map(|point| point.id).collect();
+    assert_eq!(ids, vec![2.into(), 9.into(), 8.into()]);
+    let scores: Vec<_> = result.iter().map(|point| point.score).collect();
+    assert_eq!(scores, vec![12.0, 9.0, 8.0]);
+
+    let result = collection.query(query_request(Some(2)), None, None).await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+
+    // Changed plugin must have a higher version
+    let same_version = ScoringPluginConfig {
+        features: vec![],
+        ..plugin
+    };
+    let result = collection
+        .update_params_from_diff(params_diff(ScoringPluginDiff::Plugin(same_version)))
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+
+    collection
+        .update_params_from_diff(params_diff(ScoringPluginDiff::Disabled(Disabled::Disabled)))
+        .await
+        .unwrap();
+    let result = collection.query(query_request(None), None, None).await;
+    assert!(matches!(result, Err(CollectionError::BadRequest { .. })));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_search_by_point_id() {
+    let collection_dir = Builder::new()
+        .prefix("test_search_by_point_id")
+        .tempdir()
+        .unwrap();
+
+    let collection = multi_vec_collection_fixture(collection_dir.path(), N_SHARDS).await;
+
+    // Second vector orders the points in the opposite direction
+    let mut points = Vec::new();
+    for i in 0..10 {
+        let mut vectors = NamedVectors::default();
+        vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+        vectors.insert(VEC_NAME2.to_string(), vec![(10 - i) as f32, 0.0, 0.0, 0.0]);
+
+        points.push(PointStruct {
+            id: i.into(),
+            vector: vectors.into(),
+            payload: None,
+        });
+    }
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let search_request = |id: u64| SearchRequest {
+        vector: PointVectorInput {
+            id: id.into(),
+            vector_name: Some(VEC_NAME2.to_string()),
+        }
+        .into(),
+        filter: None,
+        params: None,
+        limit: 2,
+        offset: 0,
+        with_payload: None,
+        with_vector: None,
+        score_threshold: None,
+        text: None,
+    };
+
+    // Second vector of point 5 is `[5.0, 0.0, 0.0, 0.0]`,
+    // so the points with the largest second vectors are the closest
+    let result = collection
+        .search(search_request(5), None, None)
+        .await
+        .unwrap();
+    let ids: Vec<_> = result.iter().map(|point| point.id).collect();
+    assert_eq!(ids, vec![0.into(), 1.into()]);
+    assert_eq!(result[0].score, 50.0);
+
+    let result = collection.search(search_request(100), None, None).await;
+    assert!(matches!(result, Err(CollectionError::PointNotFound { .. })));
+}
@@ -1,12 +1,16 @@
+use std::collections::BTreeMap;
 use std::num::NonZeroU64;
+use std::ops::Range;
 use std::sync::Arc;
 
 use collection::collection::Collection;
 use collection::config::{CollectionConfig, CollectionParams, WalConfig};
+use collection::operations::change_ops::ChangesOffset;
 use collection::operations::point_ops::{
     PointInsertOperations, PointOperations, PointStruct, WriteOrdering,
 };
 use collection::operations::shared_storage_config::SharedStorageConfig;
+use collection::operations::snapshot_ops::PointInTimeRecover;
 use collection::operations::types::{NodeType, SearchRequest, VectorParams, VectorsConfig};
 use collection::operations::CollectionUpdateOperations;
 use collection::shards::channel_service::ChannelService;
@@ -16,13 +20,15 @@ use segment::types::{Distance, WithPayloadInterface, WithVector};
 use tempfile::Builder;
 
 use crate::common::{
-    dummy_on_replica_failure, dummy_request_shard_transfer, TEST_OPTIMIZERS_CONFIG,
+    dummy_on_replica_failure, dummy_request_shard_transfer, simple_collection_fixture,
+    TEST_OPTIMIZERS_CONFIG,
 };
 
 async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
     let wal_config = WalConfig {
         wal_capacity_mb: 1,
         wal_segments_ahead: 0,
+        ..Default::default()
     };
 
     let collection_params = CollectionParams {
@@ -32,6 +38,8 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
             hnsw_config: None,
             quantization_config: None,
             on_disk: None,
+            multivector_config: None,
+            datatype: None,
         }),
         ..CollectionParams::empty()
     };
@@ -109,7 +117,7 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
     // Take a snapshot
     let snapshots_temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
     let snapshot_description = collection
-        .create_snapshot(snapshots_temp_dir.path(), 0)
+        .create_snapshot(snapshots_temp_dir.path(), 0, None)
         .await
         .unwrap();
 
@@ -147,6 +155,7 @@ async fn _test_snapshot_and_recover_collection(node_type: NodeType) {
         with_vector: Some(WithVector::Bool(true)),
         params: None,
         score_threshold: None,
+        text: None,
     };
 
     let reference_result = collection
@@ -177,3 +186,71 @@ async fn test_snapshot_and_recover_collection_normal() {
 async fn test_snapshot_and_recover_collection_listener() {
     _test_snapshot_and_recover_collection(NodeType::Listener).await;
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_point_in_time_operations() {
+    let collection_dir = Builder::new().prefix("test_collection").tempdir().unwrap();
+    let collection = simple_collection_fixture(collection_dir.path(), 1).await;
+
+    let upsert = |ids: Range<u64>| {
+        let points = ids
+            .map(|i| PointStruct {
+                id: i.into(),
+                vector: vec![i as f32, 0.0, 0.0, 0.0].into(),
+                payload: None,
+            })
+            .collect();
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+            PointInsertOperations::PointsList(points),
+        ))
+    };
+    collection
+        .update_from_client(upsert(0..10), true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let snapshots_temp_dir = Builder::new().prefix("temp_dir").tempdir().unwrap();
+    let snapshot = collection
+        .create_snapshot(snapshots_temp_dir.path(), 0, None)
+        .await
+        .unwrap();
+
+    collection
+        .update_from_client(upsert(10..20), true, WriteOrdering::default())
+        .await
+        .unwrap();
+    let delete = CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints {
+        ids: (0..20).map(Into::into).collect(),
+    });
+    collection
+        .update_from_client(delete, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let windows = collection.recovery_windows().await.unwrap();
+    assert_eq!(windows.len(), 1);
+    assert_eq!(windows[0].snapshot, snapshot.name);
+    let from = windows[0].from_offset.0[&0];
+    let to = windows[0].to_offset.0[&0];
+    assert_eq!(to - from, 2);
+
+    // Recover up to, but not including the delete
+    let request = PointInTimeRecover {
+        snapshot: snapshot.name,
+        until_offset: Some(ChangesOffset(BTreeMap::from([(0, to - 1)]))),
+        until_time: None,
+    };
+    let operations = collection.point_in_time_operations(&request).await.unwrap();
+    assert_eq!(operations[&0].len(), 1);
+    assert!(matches!(
+        operations[&0][0],
+        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(_))
+    ));
+
+    // Operations before the snapshot can't be excluded
+    let request = PointInTimeRecover {
+        until_offset: Some(ChangesOffset(BTreeMap::from([(0, from - 1)]))),
+        ..request
+    };
+    assert!(collection.point_in_time_operations(&request).await.is_err());
+}
@@ -26,3 +26,39 @@ impl PartialOrd for ScoredPointOffset {
         Some(self.cmp(other))
     }
 }
+
+/// How much of the telemetry to collect
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DetailsLevel {
+    /// Only the global counters
+    #[default]
+    Level0,
+    /// Aggregated telemetry of the collections
+    Level1,
+    /// Full telemetry of the collections, with their shards and segments
+    Level2,
+}
+
+impl From<usize> for DetailsLevel {
+    fn from(level: usize) -> Self {
+        match level {
+            0 => DetailsLevel::Level0,
+            1 => DetailsLevel::Level1,
+            _ => DetailsLevel::Level2,
+        }
+    }
+}
+
+/// Detail of the collected telemetry
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TelemetryDetail {
+    pub level: DetailsLevel,
+    /// Include the histograms of the operation durations
+    pub histograms: bool,
+}
+
+impl TelemetryDetail {
+    pub const fn new(level: DetailsLevel, histograms: bool) -> Self {
+        Self { level, histograms }
+    }
+}
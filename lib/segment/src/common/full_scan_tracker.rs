@@ -0,0 +1,42 @@
+//! Tells if a search fell back to a plain scan of the points, instead of using the vector index.
+//!
+//! Search in a segment runs on a single thread, so the flag is kept in a thread local
+//! and doesn't have to be passed through all the layers of the search.
+
+use std::cell::Cell;
+
+thread_local! {
+    static FULL_SCAN: Cell<bool> = Cell::new(false);
+}
+
+/// Mark the search, currently running on this thread, as a full scan
+pub fn mark_full_scan() {
+    FULL_SCAN.with(|full_scan| full_scan.set(true));
+}
+
+/// Run the search and tell if it, or any part of it, was a full scan
+pub fn track_full_scan<T>(search: impl FnOnce() -> T) -> (T, bool) {
+    let was_tracked = FULL_SCAN.with(|full_scan| full_scan.replace(false));
+    let result = search();
+    let full_scan = FULL_SCAN.with(|full_scan| full_scan.replace(was_tracked));
+    (result, full_scan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_full_scan() {
+        let ((), full_scan) = track_full_scan(|| {});
+        assert!(!full_scan);
+
+        let ((), full_scan) = track_full_scan(mark_full_scan);
+        assert!(full_scan);
+
+        // Nested tracking doesn't affect the outer one
+        let (inner, outer) = track_full_scan(|| track_full_scan(mark_full_scan).1);
+        assert!(inner);
+        assert!(!outer);
+    }
+}
@@ -0,0 +1,89 @@
+//! Measurement of the resources, used by a search: CPU time and bytes read from the disk storages.
+//!
+//! Same as the full scan flag, the counters are kept in thread locals, because search in
+//! a segment runs on a single thread. Storages only increment the counters of the current thread.
+
+use std::cell::Cell;
+use std::ops::AddAssign;
+
+use cpu_time::ThreadTime;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    static VECTOR_BYTES_READ: Cell<usize> = Cell::new(0);
+    static PAYLOAD_BYTES_READ: Cell<usize> = Cell::new(0);
+}
+
+/// Resources, used to process a request
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HardwareUsage {
+    /// CPU time, spent on the search in the segments, in microseconds
+    pub cpu_time_micros: u64,
+    /// Bytes of vectors, read from the on-disk vector storages
+    pub vector_bytes_read: u64,
+    /// Bytes of payloads, read from the on-disk payload storages
+    pub payload_bytes_read: u64,
+    /// Number of segments, the search was executed on
+    pub segments_searched: u64,
+}
+
+impl AddAssign for HardwareUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.cpu_time_micros += other.cpu_time_micros;
+        self.vector_bytes_read += other.vector_bytes_read;
+        self.payload_bytes_read += other.payload_bytes_read;
+        self.segments_searched += other.segments_searched;
+    }
+}
+
+/// Count the bytes of vectors, read from a disk storage on this thread
+pub fn add_vector_bytes_read(bytes: usize) {
+    VECTOR_BYTES_READ.with(|counter| counter.set(counter.get() + bytes));
+}
+
+/// Count the bytes of payloads, read from a disk storage on this thread
+pub fn add_payload_bytes_read(bytes: usize) {
+    PAYLOAD_BYTES_READ.with(|counter| counter.set(counter.get() + bytes));
+}
+
+/// Run the search and measure the resources it used on this thread
+pub fn measure_hardware_usage<T>(search: impl FnOnce() -> T) -> (T, HardwareUsage) {
+    let vector_bytes_before = VECTOR_BYTES_READ.with(Cell::get);
+    let payload_bytes_before = PAYLOAD_BYTES_READ.with(Cell::get);
+    let start = ThreadTime::now();
+
+    let result = search();
+
+    let usage = HardwareUsage {
+        cpu_time_micros: start.elapsed().as_micros() as u64,
+        vector_bytes_read: (VECTOR_BYTES_READ.with(Cell::get) - vector_bytes_before) as u64,
+        payload_bytes_read: (PAYLOAD_BYTES_READ.with(Cell::get) - payload_bytes_before) as u64,
+        segments_searched: 0,
+    };
+    (result, usage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_hardware_usage() {
+        let ((), usage) = measure_hardware_usage(|| {
+            add_vector_bytes_read(16);
+            add_payload_bytes_read(8);
+            add_vector_bytes_read(16);
+        });
+        assert_eq!(usage.vector_bytes_read, 32);
+        assert_eq!(usage.payload_bytes_read, 8);
+
+        // Nested measurements are included in the outer one
+        let (inner, outer) = measure_hardware_usage(|| {
+            add_payload_bytes_read(4);
+            measure_hardware_usage(|| add_payload_bytes_read(2)).1
+        });
+        assert_eq!(inner.payload_bytes_read, 2);
+        assert_eq!(outer.payload_bytes_read, 6);
+    }
+}
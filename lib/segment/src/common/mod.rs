@@ -2,21 +2,29 @@ pub mod anonymize;
 pub mod arc_atomic_ref_cell_iterator;
 pub mod cpu;
 pub mod error_logging;
+pub mod full_scan_tracker;
+pub mod hardware_counter;
 pub mod mmap_type;
 pub mod operation_error;
 pub mod operation_time_statistics;
 pub mod rocksdb_buffered_delete_wrapper;
 pub mod rocksdb_wrapper;
+pub mod search_explain;
 pub mod utils;
 pub mod vector_utils;
 pub mod version;
 
 use std::sync::atomic::AtomicBool;
 
+use sparse::common::sparse_vector::SparseVector;
+use validator::Validate;
+
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::data_types::named_vectors::NamedVectors;
 use crate::data_types::vectors::{QueryVector, VectorElementType};
-use crate::types::{SegmentConfig, VectorDataConfig};
+use crate::types::{
+    SegmentConfig, SparseVectorDataConfig, VectorDataConfig, VectorStorageDatatype,
+};
 
 pub type Flusher = Box<dyn FnOnce() -> OperationResult<()> + Send>;
 
@@ -24,6 +32,9 @@ pub type Flusher = Box<dyn FnOnce() -> OperationResult<()> + Send>;
 ///
 /// Returns an error if incompatible.
 pub fn check_vector_name(vector_name: &str, segment_config: &SegmentConfig) -> OperationResult<()> {
+    if segment_config.sparse_vector_data.contains_key(vector_name) {
+        return Ok(());
+    }
     get_vector_config_or_error(vector_name, segment_config)?;
     Ok(())
 }
@@ -36,6 +47,10 @@ pub fn check_vector(
     query_vector: &QueryVector,
     segment_config: &SegmentConfig,
 ) -> OperationResult<()> {
+    if let QueryVector::Sparse(vector) = query_vector {
+        get_sparse_vector_config_or_error(vector_name, segment_config)?;
+        return check_sparse_vector(vector);
+    }
     let vector_config = get_vector_config_or_error(vector_name, segment_config)?;
     _check_query_vector(query_vector, vector_config)
 }
@@ -49,6 +64,17 @@ fn _check_query_vector(
         QueryVector::Recommend(reco_query) => reco_query
             .iter_all()
             .try_for_each(|vector| check_vector_against_config(vector, vector_config))?,
+        QueryVector::Discovery(discovery_query) => discovery_query
+            .iter_all()
+            .try_for_each(|vector| check_vector_against_config(vector, vector_config))?,
+        QueryVector::Context(context_query) => context_query
+            .iter_all()
+            .try_for_each(|vector| check_vector_against_config(vector, vector_config))?,
+        QueryVector::Sparse(_) => {
+            return Err(OperationError::ValidationError {
+                description: "Sparse query can't be used with dense vectors".to_string(),
+            })
+        }
     }
 
     Ok(())
@@ -62,6 +88,11 @@ pub fn check_query_vectors(
     query_vectors: &[&QueryVector],
     segment_config: &SegmentConfig,
 ) -> OperationResult<()> {
+    if segment_config.sparse_vector_data.contains_key(vector_name) {
+        return query_vectors
+            .iter()
+            .try_for_each(|qv| check_vector(vector_name, qv, segment_config));
+    }
     let vector_config = get_vector_config_or_error(vector_name, segment_config)?;
     query_vectors
         .iter()
@@ -77,7 +108,30 @@ pub fn check_named_vectors(
     segment_config: &SegmentConfig,
 ) -> OperationResult<()> {
     for (vector_name, vector_data) in vectors.iter() {
-        check_vector(vector_name, &vector_data.to_vec().into(), segment_config)?;
+        let vector_config = get_vector_config_or_error(vector_name, segment_config)?;
+        check_vector_dim(vector_data, vector_config.size)?;
+        check_vector_datatype(vector_data, vector_config)?;
+    }
+    for (vector_name, multi_vector) in vectors.iter_multi() {
+        let vector_config = get_vector_config_or_error(vector_name, segment_config)?;
+        if !vector_config.is_multivector() {
+            return Err(OperationError::ValidationError {
+                description: format!("Vector {vector_name} is not configured as multivector"),
+            });
+        }
+        if multi_vector.is_empty() {
+            return Err(OperationError::ValidationError {
+                description: format!("Multivector {vector_name} must contain at least one vector"),
+            });
+        }
+        multi_vector.iter().try_for_each(|vector| {
+            check_vector_dim(vector, vector_config.size)?;
+            check_vector_datatype(vector, vector_config)
+        })?;
+    }
+    for (vector_name, vector_data) in vectors.iter_sparse() {
+        get_sparse_vector_config_or_error(vector_name, segment_config)?;
+        check_sparse_vector(vector_data)?;
     }
     Ok(())
 }
@@ -97,6 +151,28 @@ fn get_vector_config_or_error<'a>(
         })
 }
 
+/// Get the sparse vector config for the given name, or return a name error.
+fn get_sparse_vector_config_or_error<'a>(
+    vector_name: &str,
+    segment_config: &'a SegmentConfig,
+) -> OperationResult<&'a SparseVectorDataConfig> {
+    segment_config
+        .sparse_vector_data
+        .get(vector_name)
+        .ok_or_else(|| OperationError::VectorNameNotExists {
+            received_name: vector_name.into(),
+        })
+}
+
+/// Check that indices and values of the sparse vector are consistent.
+fn check_sparse_vector(vector: &SparseVector) -> OperationResult<()> {
+    vector
+        .validate()
+        .map_err(|err| OperationError::ValidationError {
+            description: format!("Invalid sparse vector: {err}"),
+        })
+}
+
 /// Check if the given vector data is compatible with the given configuration.
 ///
 /// Returns an error if incompatible.
@@ -104,8 +180,23 @@ fn check_vector_against_config(
     vector: &[VectorElementType],
     vector_config: &VectorDataConfig,
 ) -> OperationResult<()> {
-    // Check dimensionality
-    let dim = vector_config.size;
+    // Multivector queries are flattened, all of the vectors must have the configured size
+    if vector_config.is_multivector() {
+        let dim = vector_config.size;
+        if vector.is_empty() || dim == 0 || vector.len() % dim != 0 {
+            return Err(OperationError::WrongVector {
+                expected_dim: dim,
+                received_dim: vector.len(),
+            });
+        }
+        return check_vector_datatype(vector, vector_config);
+    }
+    check_vector_dim(vector, vector_config.size)?;
+    check_vector_datatype(vector, vector_config)
+}
+
+/// Check dimensionality of a single dense vector.
+fn check_vector_dim(vector: &[VectorElementType], dim: usize) -> OperationResult<()> {
     if vector.len() != dim {
         return Err(OperationError::WrongVector {
             expected_dim: dim,
@@ -115,6 +206,30 @@ fn check_vector_against_config(
     Ok(())
 }
 
+/// Check that all elements of the vector can be stored in the configured datatype without loss.
+///
+/// Byte vectors only accept integers in the `[0, 255]` range, anything else would be silently
+/// altered on conversion.
+fn check_vector_datatype(
+    vector: &[VectorElementType],
+    vector_config: &VectorDataConfig,
+) -> OperationResult<()> {
+    if vector_config.datatype != Some(VectorStorageDatatype::Uint8) {
+        return Ok(());
+    }
+    match vector
+        .iter()
+        .find(|value| value.fract() != 0.0 || !(0.0..=255.0).contains(*value))
+    {
+        Some(value) => Err(OperationError::ValidationError {
+            description: format!(
+                "Vector with uint8 datatype must only contain integers in range [0, 255], got {value}"
+            ),
+        }),
+        None => Ok(()),
+    }
+}
+
 pub fn check_stopped(is_stopped: &AtomicBool) -> OperationResult<()> {
     if is_stopped.load(std::sync::atomic::Ordering::Relaxed) {
         return Err(OperationError::Cancelled {
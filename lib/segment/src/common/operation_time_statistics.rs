@@ -2,6 +2,7 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use chrono::{DateTime, SubsecRound, Utc};
+use common::types::TelemetryDetail;
 use parking_lot::Mutex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,26 @@ use crate::common::anonymize::Anonymize;
 const AVG_DATASET_LEN: usize = 128;
 const SLIDING_WINDOW_LEN: usize = 8;
 
+/// Upper bounds of the duration histogram buckets, in microseconds
+const HISTOGRAM_BUCKET_BOUNDS_MICROS: [f32; 16] = [
+    100.0,
+    250.0,
+    500.0,
+    1_000.0,
+    2_500.0,
+    5_000.0,
+    10_000.0,
+    25_000.0,
+    50_000.0,
+    100_000.0,
+    250_000.0,
+    500_000.0,
+    1_000_000.0,
+    2_500_000.0,
+    5_000_000.0,
+    10_000_000.0,
+];
+
 #[derive(Serialize, Deserialize, Clone, Default, Debug, JsonSchema)]
 pub struct OperationDurationStatistics {
     pub count: usize,
@@ -34,6 +55,20 @@ pub struct OperationDurationStatistics {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub last_responded: Option<DateTime<Utc>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub duration_histogram: Option<DurationHistogram>,
+}
+
+/// Durations of all successful operations, in the form of a Prometheus histogram
+#[derive(Serialize, Deserialize, Clone, Default, Debug, JsonSchema)]
+pub struct DurationHistogram {
+    /// Upper bounds of the buckets in microseconds with the numbers of operations,
+    /// which took no longer than the bound
+    pub buckets: Vec<(f32, usize)>,
+    /// Total duration of the operations in microseconds
+    pub sum_micros: f64,
 }
 
 pub struct OperationDurationsAggregator {
@@ -45,6 +80,10 @@ pub struct OperationDurationsAggregator {
     min_value: Option<f32>,
     max_value: Option<f32>,
     last_response_date: Option<DateTime<Utc>>,
+    /// Non-cumulative numbers of operations in each histogram bucket,
+    /// the last one is for operations longer than all bounds
+    bucket_counts: [usize; HISTOGRAM_BUCKET_BOUNDS_MICROS.len() + 1],
+    sum_micros: f64,
 }
 
 pub struct ScopeDurationMeasurer {
@@ -58,8 +97,11 @@ impl Anonymize for OperationDurationStatistics {
         Self {
             count: self.count.anonymize(),
             fail_count: self.fail_count.anonymize(),
+            avg_duration_micros: self.avg_duration_micros,
+            min_duration_micros: self.min_duration_micros,
+            max_duration_micros: self.max_duration_micros,
             last_responded: self.last_responded.anonymize(),
-            ..*self
+            duration_histogram: self.duration_histogram.clone(),
         }
     }
 }
@@ -88,6 +130,27 @@ impl std::ops::Add for OperationDurationStatistics {
                 |a, b| a > b,
             ),
             last_responded: std::cmp::max(self.last_responded, other.last_responded),
+            duration_histogram: match (self.duration_histogram, other.duration_histogram) {
+                (Some(histogram), Some(other)) => Some(histogram + other),
+                (histogram, other) => histogram.or(other),
+            },
+        }
+    }
+}
+
+impl std::ops::Add for DurationHistogram {
+    type Output = Self;
+
+    /// Histograms are always built with the same bucket bounds
+    fn add(self, other: Self) -> Self {
+        Self {
+            buckets: self
+                .buckets
+                .into_iter()
+                .zip(other.buckets)
+                .map(|((bound, count), (_, other_count))| (bound, count + other_count))
+                .collect(),
+            sum_micros: self.sum_micros + other.sum_micros,
         }
     }
 }
@@ -181,6 +244,8 @@ impl OperationDurationsAggregator {
             min_value: None,
             max_value: None,
             last_response_date: Some(Utc::now().round_subsecs(2)),
+            bucket_counts: Default::default(),
+            sum_micros: 0.0,
         }))
     }
 
@@ -196,6 +261,10 @@ impl OperationDurationsAggregator {
                 None => duration,
             });
 
+            let bucket = HISTOGRAM_BUCKET_BOUNDS_MICROS.partition_point(|bound| *bound < duration);
+            self.bucket_counts[bucket] += 1;
+            self.sum_micros += duration as f64;
+
             self.ok_count += 1;
             self.timings[self.timing_index] = duration;
             self.timing_index += 1;
@@ -222,6 +291,38 @@ impl OperationDurationsAggregator {
             min_duration_micros: self.min_value,
             max_duration_micros: self.max_value,
             last_responded: self.last_response_date,
+            duration_histogram: None,
+        }
+    }
+
+    /// Statistics together with the histogram of the durations
+    pub fn get_statistics_with_histogram(&self) -> OperationDurationStatistics {
+        let buckets = HISTOGRAM_BUCKET_BOUNDS_MICROS
+            .iter()
+            .zip(&self.bucket_counts)
+            .scan(0, |cumulative_count, (bound, count)| {
+                *cumulative_count += count;
+                Some((*bound, *cumulative_count))
+            })
+            .collect();
+        OperationDurationStatistics {
+            duration_histogram: Some(DurationHistogram {
+                buckets,
+                sum_micros: self.sum_micros,
+            }),
+            ..self.get_statistics()
+        }
+    }
+
+    /// Statistics with the histogram of the durations, if it is requested
+    pub fn get_statistics_with_detail(
+        &self,
+        detail: TelemetryDetail,
+    ) -> OperationDurationStatistics {
+        if detail.histograms {
+            self.get_statistics_with_histogram()
+        } else {
+            self.get_statistics()
         }
     }
 
@@ -0,0 +1,278 @@
+//! Explanation of the searches in the vector indexes: the chosen strategy, the estimated and
+//! the actual cardinality of the filter, and the number of candidates at each stage.
+//!
+//! Same as the full scan flag, the explanation is kept in a thread local of the search,
+//! so the vector indexes don't need any extra parameters to report it.
+
+use std::cell::{Cell, RefCell};
+
+use common::types::ScoredPointOffset;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::index::field_index::{CardinalityEstimation, PrimaryCondition};
+use crate::types::PayloadKeyType;
+
+thread_local! {
+    static EXPLAIN: RefCell<Option<(ExplainMode, Vec<VectorSearchExplain>)>> = RefCell::new(None);
+    static CANDIDATES: Cell<SearchCandidates> = Cell::new(SearchCandidates::default());
+}
+
+/// How to explain a search
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExplainMode {
+    /// Only choose the strategies of the search, without executing it. No points are returned
+    Plan,
+    /// Execute the search and also report the actual cardinality and the number of candidates
+    Analyze,
+}
+
+/// Strategy of a search in the vector index of a segment
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStrategy {
+    /// Search in the HNSW graph, the filter is checked on the visited points
+    Hnsw,
+    /// Score all vectors of the segment
+    FullScan,
+    /// Select the points, matching the filter, and score each of them
+    FilteredScan,
+    /// Search in the inverted index of sparse vectors
+    SparseIndex,
+}
+
+/// Number of points, matching the filter, as estimated by the payload indexes
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+pub struct CardinalityEstimate {
+    pub min: usize,
+    pub exp: usize,
+    pub max: usize,
+}
+
+/// Number of points at the stages of an executed search, summed over its queries
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SearchCandidates {
+    /// Points, scored to find the nearest ones.
+    /// Not counted for the inverted index of sparse vectors
+    pub scored: usize,
+    /// Best of the points, scored with quantized vectors, and rescored with the original ones
+    pub rescored: usize,
+    /// Points, returned by the vector index
+    pub returned: usize,
+}
+
+/// Explanation of a search in the vector index of a segment
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct VectorSearchExplain {
+    pub strategy: SearchStrategy,
+    /// Number of query vectors, searched at once
+    pub queries: usize,
+    /// Vectors are scored with their quantized versions
+    pub quantized: bool,
+    /// Cardinality of the filter, estimated to choose the strategy
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_cardinality: Option<CardinalityEstimate>,
+    /// Number of points, matching the filter. Only counted in the analyze mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual_cardinality: Option<usize>,
+    /// Payload indexes, used to select the points matching the filter
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub payload_indexes: Vec<PayloadKeyType>,
+    /// Only counted in the analyze mode
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<SearchCandidates>,
+}
+
+/// Explanation of the searches in a segment
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct SegmentSearchExplain {
+    /// Id of the segment within the shard, assigned anew on restart
+    pub segment_id: usize,
+    /// Searches in the vector indexes of the segment
+    pub searches: Vec<VectorSearchExplain>,
+}
+
+/// Explanation of the searches in the segments of a shard
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct ShardSearchExplain {
+    pub shard_id: u32,
+    /// Peer, which searched the shard
+    pub peer_id: u64,
+    pub segments: Vec<SegmentSearchExplain>,
+}
+
+impl VectorSearchExplain {
+    pub fn new(
+        strategy: SearchStrategy,
+        queries: usize,
+        cardinality: Option<&CardinalityEstimation>,
+    ) -> Self {
+        let mut payload_indexes: Vec<PayloadKeyType> = cardinality
+            .into_iter()
+            .flat_map(|cardinality| &cardinality.primary_clauses)
+            .filter_map(|clause| match clause {
+                PrimaryCondition::Condition(condition) => Some(condition.key.clone()),
+                PrimaryCondition::IsEmpty(condition) => Some(condition.is_empty.key.clone()),
+                PrimaryCondition::IsNull(condition) => Some(condition.is_null.key.clone()),
+                PrimaryCondition::Composite(lookup) => Some(lookup.index.clone()),
+                PrimaryCondition::Ids(_) => None,
+            })
+            .collect();
+        payload_indexes.sort_unstable();
+        payload_indexes.dedup();
+
+        Self {
+            strategy,
+            queries,
+            quantized: false,
+            estimated_cardinality: cardinality.map(|cardinality| CardinalityEstimate {
+                min: cardinality.min,
+                exp: cardinality.exp,
+                max: cardinality.max,
+            }),
+            actual_cardinality: None,
+            payload_indexes,
+            candidates: None,
+        }
+    }
+
+    /// Add the actual cardinality of the filter.
+    /// Scans score each candidate point, so their scored points are known without counting.
+    pub fn analyze(mut self, actual_cardinality: Option<usize>, available_vectors: usize) -> Self {
+        let scored = match self.strategy {
+            SearchStrategy::FullScan => Some(available_vectors),
+            SearchStrategy::FilteredScan => actual_cardinality,
+            SearchStrategy::Hnsw | SearchStrategy::SparseIndex => None,
+        };
+        self.actual_cardinality = actual_cardinality;
+        self.candidates = scored.map(|scored| SearchCandidates {
+            scored: scored * self.queries,
+            ..Default::default()
+        });
+        self
+    }
+}
+
+/// Count the points, scored by a vector index on this thread
+pub fn count_scored_points(count: usize) {
+    CANDIDATES.with(|candidates| {
+        let mut current = candidates.get();
+        current.scored += count;
+        candidates.set(current);
+    });
+}
+
+/// Count the points, rescored with the original vectors on this thread
+pub fn count_rescored_points(count: usize) {
+    CANDIDATES.with(|candidates| {
+        let mut current = candidates.get();
+        current.rescored += count;
+        candidates.set(current);
+    });
+}
+
+/// Run the search and explain all searches in the vector indexes it makes on this thread
+pub fn explain_search<T>(
+    mode: ExplainMode,
+    search: impl FnOnce() -> T,
+) -> (T, Vec<VectorSearchExplain>) {
+    let outer = EXPLAIN.with(|explain| explain.replace(Some((mode, Vec::new()))));
+    let result = search();
+    let explained = EXPLAIN.with(|explain| explain.replace(outer));
+    let searches = explained.map(|(_, searches)| searches).unwrap_or_default();
+    (result, searches)
+}
+
+/// Run the search in a vector index, explaining it if requested.
+///
+/// The explanation is only built for explained searches.
+/// In the plan mode the search itself is skipped and returns no points.
+pub fn explain_vector_search(
+    explain: impl FnOnce(ExplainMode) -> VectorSearchExplain,
+    search: impl FnOnce() -> Vec<Vec<ScoredPointOffset>>,
+) -> Vec<Vec<ScoredPointOffset>> {
+    let Some(mode) = EXPLAIN.with(|explain| explain.borrow().as_ref().map(|(mode, _)| *mode))
+    else {
+        return search();
+    };
+
+    let mut explained = explain(mode);
+    let result = match mode {
+        ExplainMode::Plan => vec![Vec::new(); explained.queries],
+        ExplainMode::Analyze => {
+            let before = CANDIDATES.with(Cell::get);
+            let result = search();
+            let after = CANDIDATES.with(Cell::get);
+
+            let candidates = explained.candidates.get_or_insert_with(Default::default);
+            candidates.scored += after.scored - before.scored;
+            candidates.rescored += after.rescored - before.rescored;
+            candidates.returned = result.iter().map(Vec::len).sum();
+            result
+        }
+    };
+
+    EXPLAIN.with(|explain| {
+        if let Some((_, searches)) = explain.borrow_mut().as_mut() {
+            searches.push(explained);
+        }
+    });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search() -> Vec<Vec<ScoredPointOffset>> {
+        explain_vector_search(
+            |_| VectorSearchExplain::new(SearchStrategy::Hnsw, 1, None),
+            || {
+                count_scored_points(10);
+                count_scored_points(5);
+                count_rescored_points(2);
+                vec![vec![ScoredPointOffset { idx: 1, score: 1.0 }]]
+            },
+        )
+    }
+
+    #[test]
+    fn test_explain_search() {
+        // Searches are executed as usual, if not explained
+        assert_eq!(search()[0].len(), 1);
+
+        let (result, explained) = explain_search(ExplainMode::Analyze, search);
+        assert_eq!(result[0].len(), 1);
+        assert_eq!(explained.len(), 1);
+        assert_eq!(explained[0].strategy, SearchStrategy::Hnsw);
+        assert_eq!(
+            explained[0].candidates,
+            Some(SearchCandidates {
+                scored: 15,
+                rescored: 2,
+                returned: 1,
+            })
+        );
+
+        let (result, explained) = explain_search(ExplainMode::Plan, || (search(), search()));
+        assert_eq!(result, (vec![vec![]], vec![vec![]]));
+        assert_eq!(explained.len(), 2);
+        assert_eq!(explained[0].candidates, None);
+    }
+
+    #[test]
+    fn test_analyze_scans() {
+        let explain =
+            VectorSearchExplain::new(SearchStrategy::FilteredScan, 2, None).analyze(Some(30), 1000);
+        assert_eq!(explain.actual_cardinality, Some(30));
+        assert_eq!(explain.candidates.unwrap().scored, 60);
+
+        let explain =
+            VectorSearchExplain::new(SearchStrategy::FullScan, 1, None).analyze(None, 1000);
+        assert_eq!(explain.candidates.unwrap().scored, 1000);
+
+        let explain = VectorSearchExplain::new(SearchStrategy::Hnsw, 1, None).analyze(None, 1000);
+        assert_eq!(explain.candidates, None);
+    }
+}
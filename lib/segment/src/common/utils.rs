@@ -282,8 +282,14 @@ fn delete_array_path(
     MultiValue::default()
 }
 
+/// Path element, which matches any key of an object
+pub const WILDCARD_PATH: &str = "*";
+
 /// Remove value at a given JSON path from JSON map
 ///
+/// `*` element of the path matches all keys of an object, e.g. `metadata.tmp.*`
+/// removes every key of `metadata.tmp`, but keeps the object itself.
+///
 /// performance: the function could be improved by using the Entry API instead of BTreeMap.get_mut
 pub fn remove_value_from_json_map(
     path: &str,
@@ -291,6 +297,18 @@ pub fn remove_value_from_json_map(
 ) -> MultiValue<Value> {
     // check if leaf path element
     match path.split_once('.') {
+        Some((WILDCARD_PATH, rest_path)) => {
+            // dig into every nested object
+            let mut values = MultiValue::default();
+            if !rest_path.is_empty() {
+                for value in json_map.values_mut() {
+                    if let Value::Object(map) = value {
+                        values.extend(remove_value_from_json_map(rest_path, map));
+                    }
+                }
+            }
+            values
+        }
         Some((element, rest_path)) => {
             // check if targeting array
             match parse_array_path(element) {
@@ -311,6 +329,12 @@ pub fn remove_value_from_json_map(
                 }
             }
         }
+        None if path == WILDCARD_PATH => {
+            // remove all keys of the object
+            let mut values = MultiValue::default();
+            values.extend(std::mem::take(json_map).into_iter().map(|(_, value)| value));
+            values
+        }
         None => match parse_array_path(path) {
             Some((array_element_path, array_index)) => {
                 delete_array_path(array_element_path, array_index, None, json_map)
@@ -330,12 +354,13 @@ pub fn remove_value_from_json_map(
 /// assert!(!segment::common::utils::check_include_pattern("a.b.c", "a.b.d"));
 /// assert!(segment::common::utils::check_include_pattern("a.b.c", "a"));
 /// assert!(segment::common::utils::check_include_pattern("a", "a.d"));
+/// assert!(segment::common::utils::check_include_pattern("a.*.c", "a.b.c"));
 /// ```
 pub fn check_include_pattern(pattern: &str, path: &str) -> bool {
     pattern
         .split(['.', '['])
         .zip(path.split(['.', '[']))
-        .all(|(p, v)| p == v)
+        .all(|(p, v)| p == v || p == WILDCARD_PATH)
 }
 
 /// Check if a path should be excluded by a pattern
@@ -736,6 +761,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_remove_wildcard_value_from_json_map() {
+        let mut map = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "metadata": {
+                    "tmp": { "a": 1, "b": 2 },
+                    "keep": 3
+                },
+                "arr": [
+                    { "x": { "c": 4 } },
+                    { "y": { "c": 5, "d": 6 } }
+                ]
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            remove_value_from_json_map("metadata.tmp.*", &mut map).values(),
+            vec![Value::Number(1.into()), Value::Number(2.into())]
+        );
+        assert_eq!(
+            remove_value_from_json_map("arr[].*.c", &mut map).values(),
+            vec![Value::Number(4.into()), Value::Number(5.into())]
+        );
+
+        assert_eq!(
+            map,
+            serde_json::from_str::<serde_json::Map<String, Value>>(
+                r#"
+                {
+                    "metadata": {
+                        "tmp": {},
+                        "keep": 3
+                    },
+                    "arr": [
+                        { "x": {} },
+                        { "y": { "d": 6 } }
+                    ]
+                }
+                "#,
+            )
+            .unwrap()
+        );
+    }
+
     #[test]
     fn test_filter_json() {
         let map = serde_json::from_str::<serde_json::Map<String, Value>>(
@@ -52,6 +52,8 @@ impl From<SegmentConfigV5> for SegmentConfig {
                     storage_type: (old_data.on_disk == Some(true))
                         .then_some(VectorStorageType::Mmap)
                         .unwrap_or_else(|| old_segment.storage_type.into()),
+                    multivector_config: None,
+                    datatype: None,
                 };
 
                 (vector_name, new_data)
@@ -60,6 +62,7 @@ impl From<SegmentConfigV5> for SegmentConfig {
 
         SegmentConfig {
             vector_data,
+            sparse_vector_data: Default::default(),
             payload_storage_type: old_segment.payload_storage_type,
         }
     }
@@ -124,6 +127,8 @@ impl From<SegmentStateV5> for SegmentState {
         Self {
             version: old.version,
             config: old.config.into(),
+            created_at: None,
+            optimized_by: None,
         }
     }
 }
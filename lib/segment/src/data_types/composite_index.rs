@@ -0,0 +1,26 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::types::PayloadKeyType;
+
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CompositeIndexType {
+    #[default]
+    Composite,
+}
+
+#[derive(
+    Debug, Default, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Hash, Eq,
+)]
+#[serde(rename_all = "snake_case")]
+pub struct CompositeIndexParams {
+    // Required for OpenAPI pattern matching
+    pub r#type: CompositeIndexType,
+    /// Ordered list of payload fields, which values are indexed together as a tuple.
+    /// Filters with exact `match` conditions on all of these fields in `must` are resolved
+    /// with a single lookup in the index.
+    #[validate(length(min = 2))]
+    pub fields: Vec<PayloadKeyType>,
+}
@@ -0,0 +1,20 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FloatIndexType {
+    #[default]
+    Float,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct FloatIndexParams {
+    // Required for OpenAPI pattern matching
+    pub r#type: FloatIndexType,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// If true, store the index on disk. Default: false
+    pub on_disk: Option<bool>,
+}
@@ -0,0 +1,31 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Default, Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum KeywordIndexType {
+    #[default]
+    Keyword,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct KeywordIndexParams {
+    // Required for OpenAPI pattern matching
+    pub r#type: KeywordIndexType,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// If true, the field identifies a tenant and most of the queries filter by a single value of it.
+    /// Optimized segments keep points of the same tenant next to each other. Default: false
+    pub is_tenant: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// If true, store the index on disk. Default: false
+    pub on_disk: Option<bool>,
+}
+
+impl KeywordIndexParams {
+    pub fn is_tenant(&self) -> bool {
+        self.is_tenant.unwrap_or(false)
+    }
+}
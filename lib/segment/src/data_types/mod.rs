@@ -1,5 +1,11 @@
+pub mod composite_index;
+pub mod float_index;
+pub mod geo_index;
 pub mod groups;
+pub mod integer_index;
+pub mod keyword_index;
 pub mod named_vectors;
+pub mod order_by;
 pub mod text_index;
 pub mod tiny_map;
 pub mod vectors;
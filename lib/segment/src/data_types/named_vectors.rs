@@ -1,24 +1,41 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 
+use sparse::common::sparse_vector::SparseVector;
+
 use super::tiny_map;
-use super::vectors::{VectorElementType, DEFAULT_VECTOR_NAME};
+use super::vectors::{
+    flatten_multi_dense, Vector, VectorElementType, VectorRef, VectorType, DEFAULT_VECTOR_NAME,
+};
 use crate::types::Distance;
 
 type CowKey<'a> = Cow<'a, str>;
 type CowValue<'a> = Cow<'a, [VectorElementType]>;
+type CowMultiValue<'a> = Cow<'a, [VectorType]>;
+type CowSparseValue<'a> = Cow<'a, SparseVector>;
 type TinyMap<'a> = tiny_map::TinyMap<CowKey<'a>, CowValue<'a>>;
+type MultiTinyMap<'a> = tiny_map::TinyMap<CowKey<'a>, CowMultiValue<'a>>;
+type SparseTinyMap<'a> = tiny_map::TinyMap<CowKey<'a>, CowSparseValue<'a>>;
 
+/// Named vectors of a single point.
+///
+/// Dense, multi dense and sparse vectors are kept separately, a name can only refer to one of them.
 #[derive(Clone, Debug, Default, PartialEq)]
 pub struct NamedVectors<'a> {
     map: TinyMap<'a>,
+    multi_map: MultiTinyMap<'a>,
+    sparse_map: SparseTinyMap<'a>,
 }
 
 impl<'a> NamedVectors<'a> {
     pub fn from_ref(key: &'a str, value: &'a [VectorElementType]) -> Self {
         let mut map = TinyMap::new();
         map.insert(Cow::Borrowed(key), Cow::Borrowed(value));
-        Self { map }
+        Self {
+            map,
+            multi_map: MultiTinyMap::new(),
+            sparse_map: SparseTinyMap::new(),
+        }
     }
 
     pub fn from<const N: usize>(arr: [(String, Vec<VectorElementType>); N]) -> Self {
@@ -27,25 +44,37 @@ impl<'a> NamedVectors<'a> {
                 .into_iter()
                 .map(|(k, v)| (CowKey::from(k), CowValue::from(v)))
                 .collect(),
+            multi_map: MultiTinyMap::new(),
+            sparse_map: SparseTinyMap::new(),
         }
     }
 
-    pub fn from_map(map: HashMap<String, Vec<VectorElementType>>) -> Self {
-        Self {
-            map: map
-                .into_iter()
-                .map(|(k, v)| (CowKey::from(k), CowValue::from(v)))
-                .collect(),
+    pub fn from_map(map: HashMap<String, Vector>) -> Self {
+        let mut result = Self::default();
+        for (name, vector) in map {
+            result.insert_vector(name, vector);
         }
-    }
-
-    pub fn from_map_ref(map: &'a HashMap<String, Vec<VectorElementType>>) -> Self {
-        Self {
-            map: map
-                .iter()
-                .map(|(k, v)| (CowKey::from(k), CowValue::from(v)))
-                .collect(),
+        result
+    }
+
+    pub fn from_map_ref(map: &'a HashMap<String, Vector>) -> Self {
+        let mut result = Self::default();
+        for (name, vector) in map {
+            match vector {
+                Vector::Dense(vector) => result.insert_ref(name, vector),
+                Vector::MultiDense(vectors) => {
+                    result
+                        .multi_map
+                        .insert(CowKey::Borrowed(name), CowMultiValue::Borrowed(vectors));
+                }
+                Vector::Sparse(vector) => {
+                    result
+                        .sparse_map
+                        .insert(CowKey::Borrowed(name), CowSparseValue::Borrowed(vector));
+                }
+            }
         }
+        result
     }
 
     pub fn insert(&mut self, name: String, vector: Vec<VectorElementType>) {
@@ -53,6 +82,25 @@ impl<'a> NamedVectors<'a> {
             .insert(CowKey::Owned(name), CowValue::Owned(vector));
     }
 
+    pub fn insert_multi(&mut self, name: String, vectors: Vec<VectorType>) {
+        self.multi_map
+            .insert(CowKey::Owned(name), CowMultiValue::Owned(vectors));
+    }
+
+    pub fn insert_sparse(&mut self, name: String, vector: SparseVector) {
+        self.sparse_map
+            .insert(CowKey::Owned(name), CowSparseValue::Owned(vector));
+    }
+
+    /// Insert vector of any kind
+    pub fn insert_vector(&mut self, name: String, vector: Vector) {
+        match vector {
+            Vector::Dense(vector) => self.insert(name, vector),
+            Vector::MultiDense(vectors) => self.insert_multi(name, vectors),
+            Vector::Sparse(vector) => self.insert_sparse(name, vector),
+        }
+    }
+
     pub fn insert_ref(&mut self, name: &'a str, vector: &'a [VectorElementType]) {
         self.map
             .insert(CowKey::Borrowed(name), CowValue::Borrowed(vector));
@@ -60,18 +108,24 @@ impl<'a> NamedVectors<'a> {
 
     pub fn contains_key(&self, key: &str) -> bool {
         self.map.contains_key(key)
+            || self.multi_map.contains_key(key)
+            || self.sparse_map.contains_key(key)
     }
 
     pub fn len(&self) -> usize {
-        self.map.len()
+        self.map.len() + self.multi_map.len() + self.sparse_map.len()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.map.is_empty()
+        self.map.is_empty() && self.multi_map.is_empty() && self.sparse_map.is_empty()
     }
 
     pub fn keys(&self) -> impl Iterator<Item = &str> {
-        self.map.iter().map(|(k, _)| k.as_ref())
+        self.map
+            .iter()
+            .map(|(k, _)| k.as_ref())
+            .chain(self.multi_map.iter().map(|(k, _)| k.as_ref()))
+            .chain(self.sparse_map.iter().map(|(k, _)| k.as_ref()))
     }
 
     pub fn into_default_vector(mut self) -> Option<Vec<VectorElementType>> {
@@ -80,33 +134,109 @@ impl<'a> NamedVectors<'a> {
             .map(|src| std::mem::take(src).into_owned())
     }
 
-    pub fn into_owned_map(self) -> HashMap<String, Vec<VectorElementType>> {
+    pub fn into_owned_map(self) -> HashMap<String, Vector> {
         self.map
             .into_iter()
-            .map(|(k, v)| (k.into_owned(), v.into_owned()))
+            .map(|(k, v)| (k.into_owned(), Vector::Dense(v.into_owned())))
+            .chain(
+                self.multi_map
+                    .into_iter()
+                    .map(|(k, v)| (k.into_owned(), Vector::MultiDense(v.into_owned()))),
+            )
+            .chain(
+                self.sparse_map
+                    .into_iter()
+                    .map(|(k, v)| (k.into_owned(), Vector::Sparse(v.into_owned()))),
+            )
             .collect()
     }
 
+    /// Iterate over dense vectors only
     pub fn iter(&self) -> impl Iterator<Item = (&str, &[VectorElementType])> {
         self.map.iter().map(|(k, v)| (k.as_ref(), v.as_ref()))
     }
 
+    /// Iterate over multi dense vectors only
+    pub fn iter_multi(&self) -> impl Iterator<Item = (&str, &[VectorType])> {
+        self.multi_map.iter().map(|(k, v)| (k.as_ref(), v.as_ref()))
+    }
+
+    /// Iterate over sparse vectors only
+    pub fn iter_sparse(&self) -> impl Iterator<Item = (&str, &SparseVector)> {
+        self.sparse_map
+            .iter()
+            .map(|(k, v)| (k.as_ref(), v.as_ref()))
+    }
+
+    /// Get dense vector by name
     pub fn get(&self, key: &str) -> Option<&[VectorElementType]> {
         self.map.get(key).map(|v| v.as_ref())
     }
 
+    /// Get multi dense vector by name
+    pub fn get_multi(&self, key: &str) -> Option<&[VectorType]> {
+        self.multi_map.get(key).map(|v| v.as_ref())
+    }
+
+    /// Get sparse vector by name
+    pub fn get_sparse(&self, key: &str) -> Option<&SparseVector> {
+        self.sparse_map.get(key).map(|v| v.as_ref())
+    }
+
+    /// Get vector of any kind by name
+    pub fn get_any(&self, key: &str) -> Option<VectorRef> {
+        self.get(key)
+            .map(VectorRef::Dense)
+            .or_else(|| self.get_multi(key).map(VectorRef::MultiDense))
+            .or_else(|| self.get_sparse(key).map(VectorRef::Sparse))
+    }
+
+    /// Move multi dense vectors into the dense map, flattened as they are kept in vector storage
+    ///
+    /// Multi dense vectors must be preprocessed before, as distance is applied to each of them separately.
+    pub fn flatten_multi(mut self) -> Self {
+        for (name, vectors) in std::mem::take(&mut self.multi_map) {
+            self.map
+                .insert(name, CowValue::Owned(flatten_multi_dense(&vectors)));
+        }
+        self
+    }
+
+    /// Apply distance specific preprocessing to dense vectors
+    ///
+    /// Vectors for which `distance_map` returns `None` are kept as is.
     pub fn preprocess<F>(&mut self, distance_map: F)
     where
-        F: Fn(&str) -> Distance,
+        F: Fn(&str) -> Option<Distance>,
     {
         for (name, vector) in self.map.iter_mut() {
-            let distance = distance_map(name);
+            let Some(distance) = distance_map(name) else {
+                continue;
+            };
             let preprocessed_vector = distance.preprocess_vector(vector.to_vec());
             *vector = CowValue::Owned(preprocessed_vector);
         }
+        for (name, vectors) in self.multi_map.iter_mut() {
+            let Some(distance) = distance_map(name) else {
+                continue;
+            };
+            let preprocessed_vectors = vectors
+                .iter()
+                .map(|vector| distance.preprocess_vector(vector.clone()))
+                .collect();
+            *vectors = CowMultiValue::Owned(preprocessed_vectors);
+        }
+        // Sparse vectors are only scored with dot product, keep them sorted for that
+        for (_, vector) in self.sparse_map.iter_mut() {
+            if !vector.is_sorted() {
+                vector.to_mut().sort_by_indices();
+            }
+        }
     }
 }
 
+/// Iterates over dense vectors only, see [`NamedVectors::iter_multi`] and
+/// [`NamedVectors::iter_sparse`] for the other kinds
 impl<'a> IntoIterator for NamedVectors<'a> {
     type Item = (CowKey<'a>, CowValue<'a>);
 
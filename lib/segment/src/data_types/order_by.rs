@@ -0,0 +1,81 @@
+use std::cmp::Ordering;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+
+use crate::types::{DateTimePayloadType, FloatPayloadType, IntPayloadType, PayloadKeyType, Range};
+
+/// Value of the ordering key of a point.
+/// Datetime values are timestamps in microseconds, as they are stored in the payload index.
+pub type OrderValue = FloatPayloadType;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Direction {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl Direction {
+    /// Order of two values in this direction
+    pub fn compare(self, a: OrderValue, b: OrderValue) -> Ordering {
+        match self {
+            Direction::Asc => a.total_cmp(&b),
+            Direction::Desc => b.total_cmp(&a),
+        }
+    }
+}
+
+/// Value of the ordering key to start from
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
+#[serde(untagged)]
+pub enum StartFrom {
+    Integer(IntPayloadType),
+    Float(FloatPayloadType),
+    Datetime(DateTimePayloadType),
+}
+
+impl StartFrom {
+    fn order_value(&self) -> OrderValue {
+        match self {
+            StartFrom::Integer(integer) => *integer as OrderValue,
+            StartFrom::Float(float) => *float,
+            StartFrom::Datetime(datetime) => datetime.timestamp_micros() as OrderValue,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct OrderBy {
+    /// Payload key to order by, it must have an integer, float or datetime index
+    pub key: PayloadKeyType,
+    /// Direction of ordering: `asc` or `desc`. Default is ascending.
+    pub direction: Option<Direction>,
+    /// Value of the key to start from, including it.
+    /// Default is the smallest value for `asc` and the largest one for `desc`.
+    pub start_from: Option<StartFrom>,
+}
+
+impl OrderBy {
+    pub fn direction(&self) -> Direction {
+        self.direction.unwrap_or_default()
+    }
+
+    /// Range of values, which are read starting from `start_from` in the direction of ordering
+    pub fn value_range(&self) -> Range {
+        let start_from = self.start_from.as_ref().map(StartFrom::order_value);
+        match self.direction() {
+            Direction::Asc => Range {
+                gte: start_from,
+                ..Default::default()
+            },
+            Direction::Desc => Range {
+                lte: start_from,
+                ..Default::default()
+            },
+        }
+    }
+}
@@ -41,4 +41,56 @@ pub struct TextIndexParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     /// If true, lowercase all tokens. Default: true
     pub lowercase: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// If set, reduce tokens to their stem with the Snowball stemmer of this language.
+    /// Default: no stemming
+    pub stemmer: Option<Language>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// Tokens to ignore in documents and queries. Default: none
+    pub stopwords: Option<StopwordsSet>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// If true, keep the order of tokens in each document, so `phrase` conditions can be served
+    /// by the index. Default: false
+    pub phrase_matching: Option<bool>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    /// If true, store the index on disk. Default: false
+    pub on_disk: Option<bool>,
+}
+
+/// Language of indexed text, selects stemming rules and built-in stopword lists
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Language {
+    Arabic,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Greek,
+    Hungarian,
+    Italian,
+    Norwegian,
+    Portuguese,
+    Romanian,
+    Russian,
+    Spanish,
+    Swedish,
+    Turkish,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct StopwordsSet {
+    /// Use built-in stopword lists of these languages
+    #[serde(default)]
+    pub languages: Vec<Language>,
+    /// Additional words to ignore
+    #[serde(default)]
+    pub custom: Vec<String>,
 }
@@ -1,10 +1,14 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use sparse::common::sparse_vector::SparseVector;
 
 use super::named_vectors::NamedVectors;
 use crate::common::utils::transpose_map_into_named_vector;
+use crate::vector_storage::query::context_query::ContextQuery;
+use crate::vector_storage::query::discovery_query::DiscoveryQuery;
 use crate::vector_storage::query::reco_query::RecoQuery;
 
 /// Type of vector element.
@@ -23,12 +27,62 @@ pub fn only_default_vector(vec: &[VectorElementType]) -> NamedVectors {
     NamedVectors::from_ref(DEFAULT_VECTOR_NAME, vec)
 }
 
+/// Vector of any supported kind: dense, multiple dense or sparse
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
+#[serde(untagged, rename_all = "snake_case")]
+pub enum Vector {
+    Dense(VectorType),
+    MultiDense(Vec<VectorType>),
+    Sparse(SparseVector),
+}
+
+impl Vector {
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Vector::Dense(vector) => vector.is_empty(),
+            Vector::MultiDense(vectors) => vectors.is_empty(),
+            Vector::Sparse(vector) => vector.is_empty(),
+        }
+    }
+}
+
+/// Flatten multiple dense vectors into a single contiguous vector
+pub fn flatten_multi_dense(vectors: &[VectorType]) -> VectorType {
+    vectors.iter().flatten().copied().collect()
+}
+
+/// Split flattened multiple dense vectors of the given dimension
+pub fn unflatten_multi_dense(vector: &[VectorElementType], dim: usize) -> Vec<VectorType> {
+    if dim == 0 {
+        return Vec::new();
+    }
+    vector.chunks(dim).map(|chunk| chunk.to_vec()).collect()
+}
+
+impl From<VectorType> for Vector {
+    fn from(vector: VectorType) -> Self {
+        Vector::Dense(vector)
+    }
+}
+
+impl From<Vec<VectorType>> for Vector {
+    fn from(vectors: Vec<VectorType>) -> Self {
+        Vector::MultiDense(vectors)
+    }
+}
+
+impl From<SparseVector> for Vector {
+    fn from(vector: SparseVector) -> Self {
+        Vector::Sparse(vector)
+    }
+}
+
 /// Full vector data per point separator with single and multiple vector modes
 #[derive(Clone, Debug, PartialEq, Deserialize, Serialize, JsonSchema)]
 #[serde(untagged, rename_all = "snake_case")]
 pub enum VectorStruct {
     Single(VectorType),
-    Multi(HashMap<String, VectorType>),
+    Multi(HashMap<String, Vector>),
 }
 
 impl VectorStruct {
@@ -55,7 +109,7 @@ impl From<&[VectorElementType]> for VectorStruct {
 
 impl<'a> From<NamedVectors<'a>> for VectorStruct {
     fn from(v: NamedVectors) -> Self {
-        if v.len() == 1 && v.contains_key(DEFAULT_VECTOR_NAME) {
+        if v.len() == 1 && v.get(DEFAULT_VECTOR_NAME).is_some() {
             VectorStruct::Single(v.into_default_vector().unwrap())
         } else {
             VectorStruct::Multi(v.into_owned_map())
@@ -64,10 +118,14 @@ impl<'a> From<NamedVectors<'a>> for VectorStruct {
 }
 
 impl VectorStruct {
+    /// Get dense vector by name, sparse vectors are not returned
     pub fn get(&self, name: &str) -> Option<&VectorType> {
         match self {
             VectorStruct::Single(v) => (name == DEFAULT_VECTOR_NAME).then_some(v),
-            VectorStruct::Multi(v) => v.get(name),
+            VectorStruct::Multi(v) => match v.get(name) {
+                Some(Vector::Dense(v)) => Some(v),
+                Some(Vector::MultiDense(_) | Vector::Sparse(_)) | None => None,
+            },
         }
     }
 
@@ -89,6 +147,26 @@ pub struct NamedVector {
     pub vector: VectorType,
 }
 
+/// Multiple dense vectors data with name
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct NamedMultiDenseVector {
+    /// Name of vector data
+    pub name: String,
+    /// Vector data
+    pub vector: Vec<VectorType>,
+}
+
+/// Sparse vector data with name
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct NamedSparseVector {
+    /// Name of vector data
+    pub name: String,
+    /// Vector data
+    pub vector: SparseVector,
+}
+
 /// Vector data separator for named and unnamed modes
 /// Unnamed mode:
 ///
@@ -104,12 +182,32 @@ pub struct NamedVector {
 ///     "name": "image-embeddings"
 ///   }
 /// }
+///
+/// or multivector mode:
+///
+/// {
+///   "vector": {
+///     "vector": [[1.0, 2.0], [3.0, 4.0]],
+///     "name": "colbert-embeddings"
+///   }
+/// }
+///
+/// or sparse mode:
+///
+/// {
+///   "vector": {
+///     "vector": {"indices": [1, 42], "values": [0.5, 0.2]},
+///     "name": "text-sparse"
+///   }
+/// }
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
 pub enum NamedVectorStruct {
     Default(VectorType),
     Named(NamedVector),
+    MultiDense(NamedMultiDenseVector),
+    Sparse(NamedSparseVector),
 }
 
 impl From<VectorType> for NamedVectorStruct {
@@ -118,23 +216,24 @@ impl From<VectorType> for NamedVectorStruct {
     }
 }
 
-impl From<NamedVectorStruct> for NamedVector {
-    fn from(v: NamedVectorStruct) -> Self {
-        match v {
-            NamedVectorStruct::Default(v) => NamedVector {
-                name: DEFAULT_VECTOR_NAME.to_owned(),
-                vector: v,
-            },
-            NamedVectorStruct::Named(v) => v,
-        }
-    }
-}
-
 impl From<NamedVector> for NamedVectorStruct {
     fn from(v: NamedVector) -> Self {
         NamedVectorStruct::Named(v)
     }
 }
+
+impl From<NamedMultiDenseVector> for NamedVectorStruct {
+    fn from(v: NamedMultiDenseVector) -> Self {
+        NamedVectorStruct::MultiDense(v)
+    }
+}
+
+impl From<NamedSparseVector> for NamedVectorStruct {
+    fn from(v: NamedSparseVector) -> Self {
+        NamedVectorStruct::Sparse(v)
+    }
+}
+
 pub trait Named {
     fn get_name(&self) -> &str;
 }
@@ -144,21 +243,65 @@ impl Named for NamedVectorStruct {
         match self {
             NamedVectorStruct::Default(_) => DEFAULT_VECTOR_NAME,
             NamedVectorStruct::Named(v) => &v.name,
+            NamedVectorStruct::MultiDense(v) => &v.name,
+            NamedVectorStruct::Sparse(v) => &v.name,
         }
     }
 }
 
 impl NamedVectorStruct {
-    pub fn get_vector(&self) -> &VectorType {
+    pub fn new_from_vector(vector: Vector, name: Option<String>) -> Self {
+        match (vector, name) {
+            (Vector::Dense(vector), None) => NamedVectorStruct::Default(vector),
+            (Vector::Dense(vector), Some(name)) => {
+                NamedVectorStruct::Named(NamedVector { name, vector })
+            }
+            (Vector::MultiDense(vector), name) => {
+                NamedVectorStruct::MultiDense(NamedMultiDenseVector {
+                    name: name.unwrap_or_else(|| DEFAULT_VECTOR_NAME.to_owned()),
+                    vector,
+                })
+            }
+            (Vector::Sparse(vector), name) => NamedVectorStruct::Sparse(NamedSparseVector {
+                name: name.unwrap_or_else(|| DEFAULT_VECTOR_NAME.to_owned()),
+                vector,
+            }),
+        }
+    }
+
+    pub fn get_vector(&self) -> VectorRef {
         match self {
-            NamedVectorStruct::Default(v) => v,
-            NamedVectorStruct::Named(v) => &v.vector,
+            NamedVectorStruct::Default(v) => VectorRef::Dense(v),
+            NamedVectorStruct::Named(v) => VectorRef::Dense(&v.vector),
+            NamedVectorStruct::MultiDense(v) => VectorRef::MultiDense(&v.vector),
+            NamedVectorStruct::Sparse(v) => VectorRef::Sparse(&v.vector),
         }
     }
-    pub fn to_vector(self) -> VectorType {
+
+    pub fn to_vector(self) -> Vector {
         match self {
-            NamedVectorStruct::Default(v) => v,
-            NamedVectorStruct::Named(v) => v.vector,
+            NamedVectorStruct::Default(v) => Vector::Dense(v),
+            NamedVectorStruct::Named(v) => Vector::Dense(v.vector),
+            NamedVectorStruct::MultiDense(v) => Vector::MultiDense(v.vector),
+            NamedVectorStruct::Sparse(v) => Vector::Sparse(v.vector),
+        }
+    }
+}
+
+/// Borrowed vector of any supported kind
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VectorRef<'a> {
+    Dense(&'a [VectorElementType]),
+    MultiDense(&'a [VectorType]),
+    Sparse(&'a SparseVector),
+}
+
+impl<'a> VectorRef<'a> {
+    pub fn to_owned(self) -> Vector {
+        match self {
+            VectorRef::Dense(v) => Vector::Dense(v.to_vec()),
+            VectorRef::MultiDense(v) => Vector::MultiDense(v.to_vec()),
+            VectorRef::Sparse(v) => Vector::Sparse(v.clone()),
         }
     }
 }
@@ -216,22 +359,42 @@ impl BatchVectorStruct {
     }
 }
 
+/// Query scored by multiple vectors, with the name of the vector to score
 #[derive(Debug, Clone)]
-pub struct NamedRecoQuery {
-    pub query: RecoQuery<VectorType>,
+pub struct NamedQuery<T> {
+    pub query: T,
     pub using: Option<String>,
 }
 
-impl Named for NamedRecoQuery {
+impl<T> Named for NamedQuery<T> {
     fn get_name(&self) -> &str {
         self.using.as_deref().unwrap_or(DEFAULT_VECTOR_NAME)
     }
 }
 
+pub type NamedRecoQuery = NamedQuery<RecoQuery<VectorType>>;
+pub type NamedDiscoveryQuery = NamedQuery<DiscoveryQuery<VectorType>>;
+pub type NamedContextQuery = NamedQuery<ContextQuery<VectorType>>;
+
 #[derive(Debug, Clone)]
 pub enum QueryVector {
     Nearest(VectorType),
     Recommend(RecoQuery<VectorType>),
+    Discovery(DiscoveryQuery<VectorType>),
+    Context(ContextQuery<VectorType>),
+    /// Search by sparse vector, only applicable to sparse vector storages
+    Sparse(SparseVector),
+}
+
+impl From<Vector> for QueryVector {
+    fn from(vector: Vector) -> Self {
+        match vector {
+            Vector::Dense(vector) => Self::Nearest(vector),
+            // Multivectors are scored flattened, see `MultiMetricQueryScorer`
+            Vector::MultiDense(vectors) => Self::Nearest(flatten_multi_dense(&vectors)),
+            Vector::Sparse(vector) => Self::Sparse(vector),
+        }
+    }
 }
 
 impl From<VectorType> for QueryVector {
@@ -240,6 +403,12 @@ impl From<VectorType> for QueryVector {
     }
 }
 
+impl<'a> From<Cow<'a, [VectorElementType]>> for QueryVector {
+    fn from(vec: Cow<'a, [VectorElementType]>) -> Self {
+        Self::Nearest(vec.into_owned())
+    }
+}
+
 impl<'a> From<&'a [VectorElementType]> for QueryVector {
     fn from(vec: &'a [VectorElementType]) -> Self {
         Self::Nearest(vec.to_vec())
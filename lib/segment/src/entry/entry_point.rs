@@ -4,7 +4,9 @@ use std::sync::atomic::AtomicBool;
 
 use crate::common::operation_error::{OperationResult, SegmentFailedState};
 use crate::data_types::named_vectors::NamedVectors;
-use crate::data_types::vectors::{QueryVector, VectorElementType};
+use crate::data_types::order_by::{OrderBy, OrderValue};
+use crate::data_types::vectors::{QueryVector, Vector};
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::index::field_index::CardinalityEstimation;
 use crate::telemetry::SegmentTelemetry;
 use crate::types::{
@@ -50,6 +52,33 @@ pub trait SegmentEntry {
         is_stopped: &AtomicBool,
     ) -> OperationResult<Vec<Vec<ScoredPoint>>>;
 
+    /// Search for points most relevant to the text, scored by the full-text index of the field
+    #[allow(clippy::too_many_arguments)]
+    fn search_text(
+        &self,
+        key: PayloadKeyTypeRef,
+        text: &str,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPoint>>;
+
+    /// Uniform random sample of `top` points, which satisfy the filter.
+    ///
+    /// Scores are random as well: each matching point is assigned an independent random key,
+    /// and the sample is the points with the largest keys. So the best of the samples of
+    /// several segments is a uniform sample of all their points.
+    fn sample_points(
+        &self,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPoint>>;
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -104,11 +133,7 @@ pub trait SegmentEntry {
         point_id: PointIdType,
     ) -> OperationResult<bool>;
 
-    fn vector(
-        &self,
-        vector_name: &str,
-        point_id: PointIdType,
-    ) -> OperationResult<Option<Vec<VectorElementType>>>;
+    fn vector(&self, vector_name: &str, point_id: PointIdType) -> OperationResult<Option<Vector>>;
 
     fn all_vectors(&self, point_id: PointIdType) -> OperationResult<NamedVectors>;
 
@@ -125,6 +150,15 @@ pub trait SegmentEntry {
         filter: Option<&'a Filter>,
     ) -> Vec<PointIdType>;
 
+    /// Read points which satisfy filtering condition in the order of values of the `order_by` key,
+    /// starting with its `start_from` value including.
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>>;
+
     /// Read points in [from; to) range
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType>;
 
@@ -188,6 +222,9 @@ pub trait SegmentEntry {
     /// Get indexed fields
     fn get_indexed_fields(&self) -> HashMap<PayloadKeyType, PayloadFieldSchema>;
 
+    /// Get statistics of the index of a payload field, `None` if the field is not indexed
+    fn get_field_index_stats(&self, key: PayloadKeyTypeRef) -> Option<FieldIndexStats>;
+
     /// Checks if segment errored during last operations
     fn check_error(&self) -> Option<SegmentFailedState>;
 
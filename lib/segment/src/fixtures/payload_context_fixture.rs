@@ -199,6 +199,7 @@ pub fn create_plain_payload_index(path: &Path, num_points: usize, seed: u64) ->
     let condition_checker = Arc::new(SimpleConditionChecker::new(
         Arc::new(AtomicRefCell::new(payload_storage.into())),
         id_tracker.clone(),
+        Default::default(),
     ));
 
     PlainPayloadIndex::open(condition_checker, id_tracker, path).unwrap()
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use bitvec::prelude::BitSlice;
 use common::types::PointOffsetType;
 use rand::rngs::StdRng;
@@ -136,6 +138,31 @@ pub trait IdTracker {
                 }),
         )
     }
+
+    /// Iterator over distinct IDs which are not deleted, in uniformly random order
+    ///
+    /// Unlike [`IdTracker::sample_ids`], IDs are never repeated and the order is not seeded.
+    /// Only the shuffled positions are remembered, so taking a few IDs is cheap in any segment.
+    fn shuffled_ids(&self) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        let mut rng = rand::thread_rng();
+        let total = self.total_point_count();
+
+        // Lazy Fisher-Yates shuffle, values of the positions which were swapped so far
+        let mut swapped: HashMap<usize, usize> = HashMap::new();
+        Box::new(
+            (0..total)
+                .map(move |i| {
+                    let j = rng.gen_range(i..total);
+                    let current = swapped.remove(&i).unwrap_or(i);
+                    if j == i {
+                        return current as PointOffsetType;
+                    }
+                    let picked = swapped.insert(j, current).unwrap_or(j);
+                    picked as PointOffsetType
+                })
+                .filter(move |x| !self.is_deleted_point(*x)),
+        )
+    }
 }
 
 pub type IdTrackerSS = dyn IdTracker + Sync + Send;
@@ -471,4 +471,30 @@ mod tests {
 
         assert_eq!(sorted_from_tracker, values);
     }
+
+    #[test]
+    fn test_shuffled_ids() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        let mut id_tracker = SimpleIdTracker::open(db).unwrap();
+        for idx in 0..100u64 {
+            id_tracker
+                .set_link(idx.into(), idx as PointOffsetType)
+                .unwrap();
+        }
+        for idx in (0..100u64).step_by(3) {
+            id_tracker.drop(idx.into()).unwrap();
+        }
+
+        let shuffled = id_tracker.shuffled_ids().collect_vec();
+        assert_eq!(shuffled.len(), id_tracker.available_point_count());
+        assert!(shuffled
+            .iter()
+            .all(|&idx| !id_tracker.is_deleted_point(idx)));
+        assert_eq!(
+            shuffled.iter().copied().sorted().collect_vec(),
+            id_tracker.iter_ids().sorted().collect_vec()
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use common::types::PointOffsetType;
@@ -8,6 +9,7 @@ use self::memory::{BinaryItem, BinaryMemory};
 use super::{CardinalityEstimation, PayloadFieldIndex, PrimaryCondition, ValueIndexer};
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{FieldCondition, Match, MatchValue, PayloadKeyType, ValueVariants};
 
@@ -149,6 +151,10 @@ mod memory {
             self.indexed_count
         }
 
+        pub fn ram_usage_bytes(&self) -> usize {
+            (self.trues.len() + self.falses.len()) / u8::BITS as usize
+        }
+
         pub fn iter_has_true(&self) -> impl Iterator<Item = PointOffsetType> + '_ {
             self.trues.iter_ones().map(|v| v as PointOffsetType)
         }
@@ -191,6 +197,25 @@ impl BinaryIndex {
         }
     }
 
+    pub fn get_stats(&self) -> FieldIndexStats {
+        let postings = [
+            (true, self.memory.trues_count()),
+            (false, self.memory.falses_count()),
+        ]
+        .into_iter()
+        .filter(|(_value, points_count)| *points_count > 0)
+        .map(|(value, points_count)| (value.to_string(), points_count))
+        .collect::<HashMap<_, _>>();
+        FieldIndexStats {
+            points_count: self.memory.indexed_count(),
+            values_count: self.memory.trues_count() + self.memory.falses_count(),
+            postings: Some(postings),
+            histogram: None,
+            ram_usage_bytes: self.memory.ram_usage_bytes(),
+            disk_usage_bytes: 0,
+        }
+    }
+
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
         let binary_item = self.memory.get(point_id);
         binary_item.has_true() as usize + binary_item.has_false() as usize
@@ -0,0 +1,305 @@
+use std::sync::Arc;
+
+use common::types::PointOffsetType;
+use itertools::Itertools;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde_json::Value;
+use smol_str::SmolStr;
+
+use super::map_index::MapIndex;
+use super::stats::FieldIndexStats;
+use super::{
+    CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
+};
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::Flusher;
+use crate::data_types::composite_index::CompositeIndexParams;
+use crate::telemetry::PayloadIndexTelemetry;
+use crate::types::{
+    Condition, FieldCondition, Match, MatchValue, Payload, PayloadContainer, PayloadKeyType,
+    ValueVariants,
+};
+
+/// Lookup of a single tuple of values in a composite index
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompositeLookup {
+    /// Name of the composite index
+    pub index: PayloadKeyType,
+    /// Encoded tuple of the matched values
+    pub key: SmolStr,
+}
+
+/// Index over an ordered tuple of payload fields.
+///
+/// Each combination of the point's values of the indexed fields is stored as a single key,
+/// so a conjunction of exact matches on all of the fields is answered with one lookup.
+/// Points which miss a value for any of the fields are not indexed.
+pub struct CompositeIndex {
+    fields: Vec<PayloadKeyType>,
+    map_index: MapIndex<SmolStr>,
+}
+
+impl CompositeIndex {
+    pub fn new(
+        db: Arc<RwLock<DB>>,
+        field: &str,
+        params: &CompositeIndexParams,
+        is_appendable: bool,
+    ) -> Self {
+        Self {
+            fields: params.fields.clone(),
+            map_index: MapIndex::new(db, &Self::storage_name(field), is_appendable),
+        }
+    }
+
+    fn storage_name(field: &str) -> String {
+        format!("{field}_composite")
+    }
+
+    pub fn fields(&self) -> &[PayloadKeyType] {
+        &self.fields
+    }
+
+    pub fn recreate(&self) -> OperationResult<()> {
+        self.map_index.recreate()
+    }
+
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        self.map_index.get_telemetry_data()
+    }
+
+    pub fn get_stats(&self) -> FieldIndexStats {
+        self.map_index.get_stats()
+    }
+
+    pub fn values_count(&self, point_id: PointOffsetType) -> usize {
+        self.map_index.values_count(point_id)
+    }
+
+    pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
+        self.map_index.values_is_empty(point_id)
+    }
+
+    pub fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        self.map_index.remove_point(id)
+    }
+
+    /// Re-index the point with the values of all indexed fields of its payload
+    pub fn add_payload(&mut self, id: PointOffsetType, payload: &Payload) -> OperationResult<()> {
+        self.map_index.remove_point(id)?;
+
+        let keys = self
+            .fields
+            .iter()
+            .map(|field| {
+                payload
+                    .get_value(field)
+                    .values()
+                    .into_iter()
+                    .flat_map(|value| match value {
+                        Value::Array(values) => values.iter().collect(),
+                        _ => vec![value],
+                    })
+                    .filter_map(key_value)
+                    .map(|value| value.to_string())
+                    .unique()
+                    .collect_vec()
+            })
+            .multi_cartesian_product()
+            .map(|tuple| encode_key(&tuple))
+            .collect_vec();
+
+        if keys.is_empty() {
+            return Ok(());
+        }
+        self.map_index.add_many(id, keys)
+    }
+
+    /// Key of the exact matches on all indexed fields among `conditions`, if there are any.
+    ///
+    /// Also returns positions of the conditions, which are covered by the key.
+    pub fn condition_key(&self, conditions: &[Condition]) -> Option<(SmolStr, Vec<usize>)> {
+        let mut tuple = Vec::with_capacity(self.fields.len());
+        let mut positions = Vec::with_capacity(self.fields.len());
+        for field in &self.fields {
+            let (position, value) = conditions.iter().enumerate().find_map(
+                |(position, condition)| match condition {
+                    Condition::Field(FieldCondition {
+                        key,
+                        r#match: Some(Match::Value(MatchValue { value })),
+                        ..
+                    }) if key == field => Some((position, value)),
+                    _ => None,
+                },
+            )?;
+            let value = match value {
+                ValueVariants::Keyword(keyword) => Value::String(keyword.clone()),
+                ValueVariants::Integer(integer) => Value::from(*integer),
+                ValueVariants::Bool(flag) => Value::Bool(*flag),
+            };
+            tuple.push(value.to_string());
+            positions.push(position);
+        }
+        Some((encode_key(&tuple).into(), positions))
+    }
+
+    pub fn lookup(&self, key: &SmolStr) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        self.map_index.get_iterator(key)
+    }
+
+    pub fn lookup_cardinality(&self, lookup: CompositeLookup) -> CardinalityEstimation {
+        self.map_index
+            .match_cardinality(&lookup.key)
+            .with_primary_clause(PrimaryCondition::Composite(lookup))
+    }
+}
+
+/// Only values, which could be selected by an exact `match` condition, are indexed
+fn key_value(value: &Value) -> Option<&Value> {
+    match value {
+        Value::String(_) | Value::Bool(_) => Some(value),
+        Value::Number(number) if number.is_i64() => Some(value),
+        _ => None,
+    }
+}
+
+/// Tuple is encoded as a JSON array of its values
+fn encode_key(tuple: &[String]) -> String {
+    format!("[{}]", tuple.join(","))
+}
+
+impl PayloadFieldIndex for CompositeIndex {
+    fn count_indexed_points(&self) -> usize {
+        self.map_index.count_indexed_points()
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        self.map_index.load()
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.map_index.clear()
+    }
+
+    fn flusher(&self) -> Flusher {
+        PayloadFieldIndex::flusher(&self.map_index)
+    }
+
+    fn filter(
+        &self,
+        _condition: &FieldCondition,
+    ) -> OperationResult<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
+        Err(OperationError::service_error(
+            "composite index can't filter by a single field condition",
+        ))
+    }
+
+    fn estimate_cardinality(
+        &self,
+        _condition: &FieldCondition,
+    ) -> OperationResult<CardinalityEstimation> {
+        Err(OperationError::service_error(
+            "composite index can't estimate a single field condition",
+        ))
+    }
+
+    fn payload_blocks(
+        &self,
+        _threshold: usize,
+        _key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        // Blocks are built from the indexes of the individual fields
+        Box::new(std::iter::empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::{Builder, TempDir};
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+    use crate::data_types::composite_index::CompositeIndexType;
+
+    const INDEX_NAME: &str = "tenant_status";
+    const DB_NAME: &str = "test_db";
+
+    fn new_composite_index() -> (TempDir, CompositeIndex) {
+        let tmp_dir = Builder::new().prefix(DB_NAME).tempdir().unwrap();
+        let db = open_db_with_existing_cf(tmp_dir.path()).unwrap();
+        let params = CompositeIndexParams {
+            r#type: CompositeIndexType::Composite,
+            fields: vec!["tenant".to_string(), "status".to_string()],
+        };
+        let index = CompositeIndex::new(db, INDEX_NAME, &params, true);
+        index.recreate().unwrap();
+        (tmp_dir, index)
+    }
+
+    fn lookup(index: &CompositeIndex, conditions: &[Condition]) -> Vec<PointOffsetType> {
+        let (key, _) = index.condition_key(conditions).unwrap();
+        index.lookup(&key).sorted().collect()
+    }
+
+    #[test]
+    fn test_composite_lookup() {
+        let (_tmp_dir, mut index) = new_composite_index();
+
+        let payloads = [
+            json!({"tenant": "a", "status": "active"}),
+            json!({"tenant": "a", "status": "deleted"}),
+            json!({"tenant": "b", "status": "active"}),
+            json!({"tenant": ["a", "b"], "status": ["active", 1]}),
+            json!({"tenant": "a"}),
+            json!({"tenant": "a", "status": 1.5}),
+        ];
+        for (id, payload) in payloads.into_iter().enumerate() {
+            let payload: Payload = payload.into();
+            index.add_payload(id as PointOffsetType, &payload).unwrap();
+        }
+
+        // Points without a value for every field are not indexed
+        assert_eq!(index.count_indexed_points(), 4);
+
+        let tenant_a =
+            Condition::Field(FieldCondition::new_match("tenant", "a".to_string().into()));
+        let active = Condition::Field(FieldCondition::new_match(
+            "status",
+            "active".to_string().into(),
+        ));
+        let status_1 = Condition::Field(FieldCondition::new_match(
+            "status",
+            Match::Value(MatchValue {
+                value: ValueVariants::Integer(1),
+            }),
+        ));
+
+        // Order of conditions doesn't matter
+        assert_eq!(lookup(&index, &[tenant_a.clone(), active.clone()]), [0, 3]);
+        assert_eq!(lookup(&index, &[active.clone(), tenant_a.clone()]), [0, 3]);
+        assert_eq!(lookup(&index, &[status_1, tenant_a.clone()]), [3]);
+
+        let (key, positions) = index
+            .condition_key(&[active.clone(), tenant_a.clone()])
+            .unwrap();
+        assert_eq!(positions, [1, 0]);
+        let cardinality = index.lookup_cardinality(CompositeLookup {
+            index: INDEX_NAME.to_string(),
+            key,
+        });
+        assert_eq!(cardinality.exp, 2);
+
+        // All fields must be covered
+        assert!(index.condition_key(&[tenant_a.clone()]).is_none());
+
+        // Re-indexing the point replaces its previous tuples
+        let payload: Payload = json!({"tenant": "b", "status": "active"}).into();
+        index.add_payload(0, &payload).unwrap();
+        assert_eq!(lookup(&index, &[tenant_a.clone(), active.clone()]), [3]);
+
+        index.remove_point(3).unwrap();
+        assert!(lookup(&index, &[tenant_a, active]).is_empty());
+    }
+}
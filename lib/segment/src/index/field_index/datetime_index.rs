@@ -0,0 +1,255 @@
+use std::sync::Arc;
+
+use common::types::PointOffsetType;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde_json::Value;
+
+use super::numeric_index::NumericIndex;
+use super::{
+    CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
+};
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::Flusher;
+use crate::data_types::order_by::OrderValue;
+use crate::index::field_index::stats::FieldIndexStats;
+use crate::telemetry::PayloadIndexTelemetry;
+use crate::types::{
+    parse_datetime, DatetimeRange, FieldCondition, IntPayloadType, PayloadKeyType, Range,
+};
+
+/// Index of RFC 3339 datetime values.
+///
+/// Values are stored in a numeric index as timestamps in microseconds,
+/// `datetime_range` conditions are converted into ranges over these timestamps.
+pub struct DatetimeIndex {
+    numeric_index: NumericIndex<IntPayloadType>,
+}
+
+impl DatetimeIndex {
+    pub fn new(db: Arc<RwLock<DB>>, field: &str, is_appendable: bool) -> Self {
+        Self {
+            numeric_index: NumericIndex::new(db, field, is_appendable),
+        }
+    }
+
+    pub fn recreate(&self) -> OperationResult<()> {
+        self.numeric_index.recreate()
+    }
+
+    /// Timestamps of the point in microseconds
+    pub fn get_values(&self, idx: PointOffsetType) -> Option<&[IntPayloadType]> {
+        self.numeric_index.get_values(idx)
+    }
+
+    /// Timestamps in microseconds in the given range along with their points, ordered by timestamp
+    pub fn stream_range(
+        &self,
+        range: &Range,
+    ) -> Box<dyn DoubleEndedIterator<Item = (OrderValue, PointOffsetType)> + '_> {
+        self.numeric_index.stream_range(range)
+    }
+
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        self.numeric_index.get_telemetry_data()
+    }
+
+    pub fn get_stats(&self) -> FieldIndexStats {
+        self.numeric_index.get_stats()
+    }
+
+    pub fn values_count(&self, point_id: PointOffsetType) -> usize {
+        self.numeric_index.values_count(point_id)
+    }
+
+    pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
+        self.numeric_index.values_is_empty(point_id)
+    }
+}
+
+impl PayloadFieldIndex for DatetimeIndex {
+    fn count_indexed_points(&self) -> usize {
+        self.numeric_index.count_indexed_points()
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        self.numeric_index.load()
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.numeric_index.clear()
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.numeric_index.flusher()
+    }
+
+    fn filter(
+        &self,
+        condition: &FieldCondition,
+    ) -> OperationResult<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
+        let cond_range = condition.datetime_range.as_ref().ok_or_else(|| {
+            OperationError::service_error("failed to get condition datetime range")
+        })?;
+
+        Ok(self
+            .numeric_index
+            .range_filter(&cond_range.to_timestamp_range()))
+    }
+
+    fn estimate_cardinality(
+        &self,
+        condition: &FieldCondition,
+    ) -> OperationResult<CardinalityEstimation> {
+        condition
+            .datetime_range
+            .as_ref()
+            .map(|range| {
+                let mut cardinality = self
+                    .numeric_index
+                    .range_cardinality(&range.to_timestamp_range());
+                cardinality
+                    .primary_clauses
+                    .push(PrimaryCondition::Condition(condition.clone()));
+                cardinality
+            })
+            .ok_or_else(|| OperationError::service_error("failed to estimate cardinality"))
+    }
+
+    fn payload_blocks(
+        &self,
+        threshold: usize,
+        key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        let blocks = self
+            .numeric_index
+            .payload_blocks(threshold, key)
+            .map(|block| {
+                let range = block.condition.range.unwrap_or_default();
+                PayloadBlockCondition {
+                    condition: FieldCondition::new_datetime_range(
+                        block.condition.key,
+                        DatetimeRange::from_timestamp_range(&range),
+                    ),
+                    cardinality: block.cardinality,
+                }
+            });
+        Box::new(blocks)
+    }
+}
+
+impl ValueIndexer<IntPayloadType> for DatetimeIndex {
+    fn add_many(
+        &mut self,
+        id: PointOffsetType,
+        values: Vec<IntPayloadType>,
+    ) -> OperationResult<()> {
+        self.numeric_index.add_many(id, values)
+    }
+
+    fn get_value(&self, value: &Value) -> Option<IntPayloadType> {
+        if let Value::String(datetime) = value {
+            return parse_datetime(datetime).map(|datetime| datetime.timestamp_micros());
+        }
+        None
+    }
+
+    fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        self.numeric_index.remove_point(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use serde_json::json;
+    use tempfile::{Builder, TempDir};
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+    use crate::common::utils::MultiValue;
+
+    const FIELD_NAME: &str = "datetime_field";
+    const DB_NAME: &str = "test_db";
+
+    fn new_datetime_index() -> (TempDir, DatetimeIndex) {
+        let tmp_dir = Builder::new().prefix(DB_NAME).tempdir().unwrap();
+        let db = open_db_with_existing_cf(tmp_dir.path()).unwrap();
+        let index = DatetimeIndex::new(db, FIELD_NAME, true);
+        index.recreate().unwrap();
+        (tmp_dir, index)
+    }
+
+    fn datetime_range(gte: Option<&str>, lt: Option<&str>) -> FieldCondition {
+        FieldCondition::new_datetime_range(
+            FIELD_NAME,
+            DatetimeRange {
+                gte: gte.and_then(parse_datetime),
+                lt: lt.and_then(parse_datetime),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn test_datetime_range_filter() {
+        let (_tmp_dir, mut index) = new_datetime_index();
+
+        let payloads = [
+            json!("2023-01-01T00:00:00Z"),
+            json!("2023-06-15T12:30:00+02:00"),
+            json!(["2022-12-31T23:59:59Z", "2024-01-01T00:00:00Z"]),
+            json!("not a datetime"),
+            json!(1672531200),
+        ];
+        for (id, payload) in payloads.iter().enumerate() {
+            index
+                .add_point(id as PointOffsetType, &MultiValue::Single(Some(payload)))
+                .unwrap();
+        }
+
+        // Unparsable values are not indexed
+        assert_eq!(index.count_indexed_points(), 3);
+        assert!(index.values_is_empty(3));
+        assert!(index.values_is_empty(4));
+
+        let condition = datetime_range(Some("2023-01-01T00:00:00Z"), Some("2023-07-01T00:00:00Z"));
+        let points = index.filter(&condition).unwrap().sorted().collect_vec();
+        assert_eq!(points, vec![0, 1]);
+
+        // Bounds in a different timezone select the same instant
+        let condition = datetime_range(Some("2023-06-15T10:30:00Z"), None);
+        let points = index.filter(&condition).unwrap().sorted().collect_vec();
+        assert_eq!(points, vec![1, 2]);
+
+        let condition = datetime_range(None, Some("2023-01-01T00:00:00Z"));
+        let points = index.filter(&condition).unwrap().sorted().collect_vec();
+        assert_eq!(points, vec![2]);
+
+        let cardinality = index.estimate_cardinality(&condition).unwrap();
+        assert!(cardinality.min <= 1 && cardinality.max >= 1);
+    }
+
+    #[test]
+    fn test_datetime_payload_blocks() {
+        let (_tmp_dir, mut index) = new_datetime_index();
+
+        for id in 0..100 {
+            let payload = json!(format!("2023-01-01T00:00:{:02}Z", id % 60));
+            index
+                .add_point(id, &MultiValue::Single(Some(&payload)))
+                .unwrap();
+        }
+
+        let blocks = index
+            .payload_blocks(10, FIELD_NAME.to_string())
+            .collect_vec();
+        assert!(!blocks.is_empty());
+        for block in blocks {
+            assert!(block.condition.range.is_none());
+            assert!(block.condition.datetime_range.is_some());
+            // Every block must be served by the index itself
+            assert!(index.filter(&block.condition).is_ok());
+        }
+    }
+}
@@ -6,14 +6,20 @@ use super::map_index::MapIndex;
 use crate::common::operation_error::OperationResult;
 use crate::common::utils::MultiValue;
 use crate::common::Flusher;
+use crate::data_types::order_by::OrderValue;
 use crate::index::field_index::binary_index::BinaryIndex;
+use crate::index::field_index::composite_index::CompositeIndex;
+use crate::index::field_index::datetime_index::DatetimeIndex;
 use crate::index::field_index::full_text_index::text_index::FullTextIndex;
 use crate::index::field_index::geo_index::GeoMapIndex;
+use crate::index::field_index::null_index::NullIndex;
 use crate::index::field_index::numeric_index::NumericIndex;
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition};
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
-    FieldCondition, FloatPayloadType, IntPayloadType, Match, MatchText, PayloadKeyType,
+    FieldCondition, FloatPayloadType, IntPayloadType, Match, MatchPhrase, MatchText, Payload,
+    PayloadContainer, PayloadKeyType, PayloadKeyTypeRef, Range, UuidIntType,
 };
 
 pub trait PayloadFieldIndex {
@@ -124,6 +130,10 @@ pub enum FieldIndex {
     GeoIndex(GeoMapIndex),
     FullTextIndex(FullTextIndex),
     BinaryIndex(BinaryIndex),
+    DatetimeIndex(DatetimeIndex),
+    UuidMapIndex(MapIndex<UuidIntType>),
+    CompositeIndex(CompositeIndex),
+    NullIndex(NullIndex),
 }
 
 impl FieldIndex {
@@ -146,6 +156,10 @@ impl FieldIndex {
             FieldIndex::FloatIndex(_) => None,
             FieldIndex::GeoIndex(_) => None,
             FieldIndex::BinaryIndex(_) => None,
+            FieldIndex::DatetimeIndex(_) => None,
+            FieldIndex::UuidMapIndex(_) => None,
+            FieldIndex::CompositeIndex(_) => None,
+            FieldIndex::NullIndex(_) => None,
             FieldIndex::FullTextIndex(full_text_index) => match &condition.r#match {
                 Some(Match::Text(MatchText { text })) => {
                     let query = full_text_index.parse_query(text);
@@ -157,6 +171,16 @@ impl FieldIndex {
                     }
                     Some(false)
                 }
+                Some(Match::Phrase(MatchPhrase { phrase })) => {
+                    let query = full_text_index.parse_phrase(phrase)?;
+                    for value in full_text_index.get_values(payload_value) {
+                        let document = full_text_index.parse_document(&value);
+                        if query.check_match(&document) {
+                            return Some(true);
+                        }
+                    }
+                    Some(false)
+                }
                 _ => None,
             },
         }
@@ -170,6 +194,10 @@ impl FieldIndex {
             FieldIndex::FloatIndex(payload_field_index) => payload_field_index,
             FieldIndex::GeoIndex(payload_field_index) => payload_field_index,
             FieldIndex::BinaryIndex(payload_field_index) => payload_field_index,
+            FieldIndex::DatetimeIndex(payload_field_index) => payload_field_index,
+            FieldIndex::UuidMapIndex(payload_field_index) => payload_field_index,
+            FieldIndex::CompositeIndex(payload_field_index) => payload_field_index,
+            FieldIndex::NullIndex(payload_field_index) => payload_field_index,
             FieldIndex::FullTextIndex(payload_field_index) => payload_field_index,
         }
     }
@@ -183,6 +211,10 @@ impl FieldIndex {
             FieldIndex::FloatIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::GeoIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::BinaryIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::DatetimeIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::UuidMapIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::CompositeIndex(ref mut payload_field_index) => payload_field_index,
+            FieldIndex::NullIndex(ref mut payload_field_index) => payload_field_index,
             FieldIndex::FullTextIndex(ref mut payload_field_index) => payload_field_index,
         }
     }
@@ -195,6 +227,10 @@ impl FieldIndex {
             FieldIndex::FloatIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::GeoIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::BinaryIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::DatetimeIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::UuidMapIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::CompositeIndex(ref mut payload_field_index) => payload_field_index.load(),
+            FieldIndex::NullIndex(ref mut payload_field_index) => payload_field_index.load(),
             FieldIndex::FullTextIndex(ref mut payload_field_index) => payload_field_index.load(),
         }
     }
@@ -207,6 +243,10 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.clear(),
             FieldIndex::GeoIndex(index) => index.clear(),
             FieldIndex::BinaryIndex(index) => index.clear(),
+            FieldIndex::DatetimeIndex(index) => index.clear(),
+            FieldIndex::UuidMapIndex(index) => index.clear(),
+            FieldIndex::CompositeIndex(index) => index.clear(),
+            FieldIndex::NullIndex(index) => index.clear(),
             FieldIndex::FullTextIndex(index) => index.clear(),
         }
     }
@@ -219,6 +259,10 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.recreate(),
             FieldIndex::GeoIndex(index) => index.recreate(),
             FieldIndex::BinaryIndex(index) => index.recreate(),
+            FieldIndex::DatetimeIndex(index) => index.recreate(),
+            FieldIndex::UuidMapIndex(index) => index.recreate(),
+            FieldIndex::CompositeIndex(index) => index.recreate(),
+            FieldIndex::NullIndex(index) => index.recreate(),
             FieldIndex::FullTextIndex(index) => index.recreate(),
         }
     }
@@ -279,12 +323,38 @@ impl FieldIndex {
             FieldIndex::BinaryIndex(ref mut payload_field_index) => {
                 payload_field_index.add_point(id, payload)
             }
+            FieldIndex::DatetimeIndex(ref mut payload_field_index) => {
+                payload_field_index.add_point(id, payload)
+            }
+            FieldIndex::UuidMapIndex(ref mut payload_field_index) => {
+                payload_field_index.add_point(id, payload)
+            }
+            // Values of a single field are not enough, see `add_payload`
+            FieldIndex::CompositeIndex(_) => Ok(()),
+            FieldIndex::NullIndex(ref mut payload_field_index) => {
+                payload_field_index.add_point(id, payload)
+            }
             FieldIndex::FullTextIndex(ref mut payload_field_index) => {
                 payload_field_index.add_point(id, payload)
             }
         }
     }
 
+    /// Add point with the whole payload to index
+    ///
+    /// Composite indexes depend on the values of several fields, other indexes use `field` only.
+    pub fn add_payload(
+        &mut self,
+        id: PointOffsetType,
+        field: PayloadKeyTypeRef,
+        payload: &Payload,
+    ) -> OperationResult<()> {
+        match self {
+            FieldIndex::CompositeIndex(index) => index.add_payload(id, payload),
+            _ => self.add_point(id, &payload.get_value(field)),
+        }
+    }
+
     pub fn remove_point(&mut self, point_id: PointOffsetType) -> OperationResult<()> {
         match self {
             FieldIndex::IntIndex(index) => index.remove_point(point_id),
@@ -293,6 +363,10 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.remove_point(point_id),
             FieldIndex::GeoIndex(index) => index.remove_point(point_id),
             FieldIndex::BinaryIndex(index) => index.remove_point(point_id),
+            FieldIndex::DatetimeIndex(index) => index.remove_point(point_id),
+            FieldIndex::UuidMapIndex(index) => index.remove_point(point_id),
+            FieldIndex::CompositeIndex(index) => index.remove_point(point_id),
+            FieldIndex::NullIndex(index) => index.remove_point(point_id),
             FieldIndex::FullTextIndex(index) => index.remove_point(point_id),
         }
     }
@@ -305,10 +379,30 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.get_telemetry_data(),
             FieldIndex::GeoIndex(index) => index.get_telemetry_data(),
             FieldIndex::BinaryIndex(index) => index.get_telemetry_data(),
+            FieldIndex::DatetimeIndex(index) => index.get_telemetry_data(),
+            FieldIndex::UuidMapIndex(index) => index.get_telemetry_data(),
+            FieldIndex::CompositeIndex(index) => index.get_telemetry_data(),
+            FieldIndex::NullIndex(index) => index.get_telemetry_data(),
             FieldIndex::FullTextIndex(index) => index.get_telemetry_data(),
         }
     }
 
+    pub fn get_stats(&self) -> FieldIndexStats {
+        match self {
+            FieldIndex::IntIndex(index) => index.get_stats(),
+            FieldIndex::IntMapIndex(index) => index.get_stats(),
+            FieldIndex::KeywordIndex(index) => index.get_stats(),
+            FieldIndex::FloatIndex(index) => index.get_stats(),
+            FieldIndex::GeoIndex(index) => index.get_stats(),
+            FieldIndex::BinaryIndex(index) => index.get_stats(),
+            FieldIndex::DatetimeIndex(index) => index.get_stats(),
+            FieldIndex::UuidMapIndex(index) => index.get_stats(),
+            FieldIndex::CompositeIndex(index) => index.get_stats(),
+            FieldIndex::NullIndex(index) => index.get_stats(),
+            FieldIndex::FullTextIndex(index) => index.get_stats(),
+        }
+    }
+
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
         match self {
             FieldIndex::IntIndex(index) => index.values_count(point_id),
@@ -317,10 +411,35 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.values_count(point_id),
             FieldIndex::GeoIndex(index) => index.values_count(point_id),
             FieldIndex::BinaryIndex(index) => index.values_count(point_id),
+            FieldIndex::DatetimeIndex(index) => index.values_count(point_id),
+            FieldIndex::UuidMapIndex(index) => index.values_count(point_id),
+            FieldIndex::CompositeIndex(index) => index.values_count(point_id),
+            FieldIndex::NullIndex(index) => index.values_count(point_id),
             FieldIndex::FullTextIndex(index) => index.values_count(point_id),
         }
     }
 
+    /// Values of a numeric index in the given range along with their points, ordered by value.
+    /// `None` if the index can't be read in the order of values.
+    pub fn stream_range(
+        &self,
+        range: &Range,
+    ) -> Option<Box<dyn DoubleEndedIterator<Item = (OrderValue, PointOffsetType)> + '_>> {
+        match self {
+            FieldIndex::IntIndex(index) => Some(index.stream_range(range)),
+            FieldIndex::FloatIndex(index) => Some(index.stream_range(range)),
+            FieldIndex::DatetimeIndex(index) => Some(index.stream_range(range)),
+            FieldIndex::IntMapIndex(_)
+            | FieldIndex::KeywordIndex(_)
+            | FieldIndex::GeoIndex(_)
+            | FieldIndex::BinaryIndex(_)
+            | FieldIndex::UuidMapIndex(_)
+            | FieldIndex::CompositeIndex(_)
+            | FieldIndex::NullIndex(_)
+            | FieldIndex::FullTextIndex(_) => None,
+        }
+    }
+
     pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
         match self {
             FieldIndex::IntIndex(index) => index.values_is_empty(point_id),
@@ -329,6 +448,10 @@ impl FieldIndex {
             FieldIndex::FloatIndex(index) => index.values_is_empty(point_id),
             FieldIndex::GeoIndex(index) => index.values_is_empty(point_id),
             FieldIndex::BinaryIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::DatetimeIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::UuidMapIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::CompositeIndex(index) => index.values_is_empty(point_id),
+            FieldIndex::NullIndex(index) => index.values_is_empty(point_id),
             FieldIndex::FullTextIndex(index) => index.values_is_empty(point_id),
         }
     }
@@ -0,0 +1,122 @@
+use std::mem::size_of;
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+
+use memory::mmap_ops::{create_and_ensure_length, open_write_mmap};
+
+use crate::common::mmap_type::MmapSlice;
+use crate::common::operation_error::OperationResult;
+
+const FLAT_CONTAINER_FILE_EXTENSION: &str = "mmap";
+
+/// Flattened data of an immutable field index, such as its posting lists.
+///
+/// Data is kept either in RAM or in a memory mapped file, if the index is configured to be
+/// stored on disk. The file only mirrors the data of the index in RocksDB and is rewritten each
+/// time the index is loaded, so it never has to be consistent with the previous run.
+pub enum FlatContainer<T: Copy + 'static> {
+    Ram(Vec<T>),
+    Mmap(MmapSlice<T>),
+}
+
+impl<T: Copy + 'static> Default for FlatContainer<T> {
+    fn default() -> Self {
+        FlatContainer::Ram(Vec::new())
+    }
+}
+
+impl<T: Copy + 'static> FlatContainer<T> {
+    /// Move `data` into the file at `path`, or keep it in RAM if there is no path
+    pub fn new(data: Vec<T>, path: Option<&Path>) -> OperationResult<Self> {
+        let path = match path {
+            // Zero-length file can't be memory mapped
+            Some(path) if !data.is_empty() => path,
+            _ => return Ok(FlatContainer::Ram(data)),
+        };
+
+        create_and_ensure_length(path, data.len() * size_of::<T>())?;
+        let mmap = open_write_mmap(path)?;
+        let mut mmap_slice = unsafe { MmapSlice::try_from(mmap)? };
+        mmap_slice.copy_from_slice(&data);
+        Ok(FlatContainer::Mmap(mmap_slice))
+    }
+
+    pub fn is_on_disk(&self) -> bool {
+        matches!(self, FlatContainer::Mmap(_))
+    }
+}
+
+impl<T: Copy + 'static> Deref for FlatContainer<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            FlatContainer::Ram(data) => data,
+            FlatContainer::Mmap(data) => &data[..],
+        }
+    }
+}
+
+impl<T: Copy + 'static> DerefMut for FlatContainer<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self {
+            FlatContainer::Ram(data) => data,
+            FlatContainer::Mmap(data) => &mut data[..],
+        }
+    }
+}
+
+/// Path of the file for the flattened data of the index stored in column family `cf_name`.
+///
+/// Bytes of the name, which are not safe for file names, are percent-encoded, so different
+/// payload keys never share a file.
+pub fn flat_container_path(dir: &Path, cf_name: &str) -> PathBuf {
+    let mut file_name = String::with_capacity(cf_name.len());
+    for byte in cf_name.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'-' {
+            file_name.push(byte as char);
+        } else {
+            file_name.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    dir.join(format!("{file_name}.{FLAT_CONTAINER_FILE_EXTENSION}"))
+}
+
+/// Remove the file of the flattened data, if the index had one
+pub fn remove_flat_container(path: Option<&Path>) -> OperationResult<()> {
+    match path {
+        Some(path) if path.exists() => Ok(std::fs::remove_file(path)?),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::Builder;
+
+    use super::*;
+
+    #[test]
+    fn test_flat_container_on_disk() {
+        let dir = Builder::new().prefix("flat_container").tempdir().unwrap();
+        let path = flat_container_path(dir.path(), "a.b[]/c_map");
+        assert_eq!(path.file_name().unwrap(), "a%2Eb%5B%5D%2Fc_map.mmap");
+
+        let mut container = FlatContainer::new(vec![3u32, 1, 2], Some(&path)).unwrap();
+        assert!(container.is_on_disk());
+        container.swap(0, 2);
+        assert_eq!(&container[..], &[2, 1, 3]);
+        drop(container);
+
+        // Loading again overwrites the file
+        let container = FlatContainer::new(vec![5u32], Some(&path)).unwrap();
+        assert_eq!(&container[..], &[5]);
+        drop(container);
+
+        let container = FlatContainer::<u32>::new(vec![], Some(&path)).unwrap();
+        assert!(!container.is_on_disk());
+
+        remove_flat_container(Some(&path)).unwrap();
+        assert!(!path.exists());
+    }
+}
@@ -1,24 +1,70 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::Path;
 
-use common::types::PointOffsetType;
+use common::types::{PointOffsetType, ScoreType};
 use serde::{Deserialize, Serialize};
 
 use super::posting_list::PostingList;
 use super::postings_iterator::intersect_postings_iterator;
+use crate::common::operation_error::OperationResult;
+use crate::index::field_index::flat_container::FlatContainer;
 use crate::index::field_index::{CardinalityEstimation, PayloadBlockCondition, PrimaryCondition};
 use crate::types::{FieldCondition, Match, MatchText, PayloadKeyType};
 
 pub type TokenId = u32;
 
+/// Term frequency saturation parameter of BM25
+const BM25_K1: ScoreType = 1.2;
+/// Document length normalization parameter of BM25
+const BM25_B: ScoreType = 0.75;
+
 #[derive(Default, Serialize, Deserialize, Debug, Clone)]
 pub struct Document {
     tokens: Vec<TokenId>,
+    /// Number of occurrences of each token, aligned with `tokens`
+    frequencies: Vec<u32>,
+    /// Total number of tokens, including repeated ones
+    tokens_count: usize,
+    /// Tokens of each indexed value in their original order, kept only if phrase matching is enabled
+    sequences: Vec<Vec<TokenId>>,
 }
 
 impl Document {
-    pub fn new(mut tokens: Vec<TokenId>) -> Self {
-        tokens.sort_unstable();
-        Self { tokens }
+    pub fn new(tokens: Vec<TokenId>) -> Self {
+        Self::from_frequencies(tokens.into_iter().map(|token| (token, 1)).collect())
+    }
+
+    /// Build document from tokens with their number of occurrences, repeated tokens are merged
+    pub fn from_frequencies(mut token_frequencies: Vec<(TokenId, u32)>) -> Self {
+        token_frequencies.sort_unstable_by_key(|&(token, _)| token);
+        let mut tokens: Vec<TokenId> = Vec::with_capacity(token_frequencies.len());
+        let mut frequencies: Vec<u32> = Vec::with_capacity(token_frequencies.len());
+        for (token, frequency) in token_frequencies {
+            match frequencies.last_mut() {
+                Some(last_frequency) if tokens.last() == Some(&token) => {
+                    *last_frequency += frequency
+                }
+                _ => {
+                    tokens.push(token);
+                    frequencies.push(frequency);
+                }
+            }
+        }
+        let tokens_count = frequencies
+            .iter()
+            .map(|&frequency| frequency as usize)
+            .sum();
+        Self {
+            tokens,
+            frequencies,
+            tokens_count,
+            sequences: vec![],
+        }
+    }
+
+    pub fn with_sequences(self, sequences: Vec<Vec<TokenId>>) -> Self {
+        Self { sequences, ..self }
     }
 
     pub fn len(&self) -> usize {
@@ -36,11 +82,35 @@ impl Document {
     pub fn check(&self, token: TokenId) -> bool {
         self.tokens.binary_search(&token).is_ok()
     }
+
+    pub fn frequency(&self, token: TokenId) -> u32 {
+        self.tokens
+            .binary_search(&token)
+            .map_or(0, |position| self.frequencies[position])
+    }
+
+    pub fn tokens_count(&self) -> usize {
+        self.tokens_count
+    }
+
+    /// Check that any of the values contains the phrase as adjacent tokens in the same order
+    pub fn check_phrase(&self, phrase: &[TokenId]) -> bool {
+        if phrase.is_empty() {
+            return false;
+        }
+        self.sequences.iter().any(|sequence| {
+            sequence
+                .windows(phrase.len())
+                .any(|window| window == phrase)
+        })
+    }
 }
 
 #[derive(Debug)]
 pub struct ParsedQuery {
     pub tokens: Vec<Option<TokenId>>,
+    /// Tokens in the order they must appear in the document, if the query is a phrase
+    pub phrase: Option<Vec<TokenId>>,
 }
 
 impl ParsedQuery {
@@ -49,19 +119,126 @@ impl ParsedQuery {
             return false;
         }
         // Check that all tokens are in document
-        self.tokens
+        let has_all_tokens = self
+            .tokens
             .iter()
             // unwrap crash safety: all tokens exist in the vocabulary if it passes the above check
-            .all(|query_token| document.check(query_token.unwrap()))
+            .all(|query_token| document.check(query_token.unwrap()));
+        match &self.phrase {
+            Some(phrase) => has_all_tokens && document.check_phrase(phrase),
+            None => has_all_tokens,
+        }
+    }
+}
+
+/// Posting lists of the tokens, indexed by token id
+enum Postings {
+    Mutable(Vec<Option<PostingList>>),
+    /// Flattened posting lists, which documents can only be removed from
+    Immutable {
+        ranges: Vec<Range<u32>>,
+        container: FlatContainer<PointOffsetType>,
+    },
+}
+
+impl Default for Postings {
+    fn default() -> Self {
+        Postings::Mutable(Default::default())
+    }
+}
+
+impl Postings {
+    /// Flatten posting lists into the file at `path`
+    fn into_immutable(self, path: &Path) -> OperationResult<Self> {
+        let Postings::Mutable(postings) = self else {
+            return Ok(self);
+        };
+        let mut ranges = Vec::with_capacity(postings.len());
+        let mut container = Vec::new();
+        for posting in postings {
+            let start = container.len() as u32;
+            if let Some(posting) = posting {
+                container.extend_from_slice(posting.as_slice());
+            }
+            ranges.push(start..container.len() as u32);
+        }
+        Ok(Postings::Immutable {
+            ranges,
+            container: FlatContainer::new(container, Some(path))?,
+        })
+    }
+
+    fn is_on_disk(&self) -> bool {
+        match self {
+            Postings::Mutable(_) => false,
+            Postings::Immutable { container, .. } => container.is_on_disk(),
+        }
+    }
+
+    /// Sorted ids of the documents with the token
+    fn get(&self, token: TokenId) -> Option<&[PointOffsetType]> {
+        match self {
+            Postings::Mutable(postings) => postings
+                .get(token as usize)
+                .and_then(Option::as_ref)
+                .map(PostingList::as_slice),
+            Postings::Immutable { ranges, container } => ranges
+                .get(token as usize)
+                .map(|range| &container[range.start as usize..range.end as usize]),
+        }
+    }
+
+    fn insert(&mut self, token: TokenId, idx: PointOffsetType) {
+        let postings = match self {
+            Postings::Mutable(postings) => postings,
+            Postings::Immutable { .. } => {
+                debug_assert!(false, "Can't add documents to immutable postings");
+                return;
+            }
+        };
+        let token_idx_usize = token as usize;
+        if postings.len() <= token_idx_usize {
+            postings.resize(token_idx_usize + 1, Default::default());
+        }
+        let posting = postings
+            .get_mut(token_idx_usize)
+            .expect("posting must exist even if with None");
+        match posting {
+            None => *posting = Some(PostingList::new(idx)),
+            Some(vec) => vec.insert(idx),
+        }
+    }
+
+    fn remove(&mut self, token: TokenId, idx: PointOffsetType) {
+        match self {
+            Postings::Mutable(postings) => {
+                if let Some(Some(vec)) = postings.get_mut(token as usize) {
+                    vec.remove(idx);
+                }
+            }
+            Postings::Immutable { ranges, container } => {
+                let Some(range) = ranges.get_mut(token as usize) else {
+                    return;
+                };
+                // Shift the tail of the posting to keep it sorted
+                let posting = &mut container[range.start as usize..range.end as usize];
+                if let Ok(removal_idx) = posting.binary_search(&idx) {
+                    posting.copy_within(removal_idx + 1.., removal_idx);
+                    range.end -= 1;
+                }
+            }
+        }
     }
 }
 
 #[derive(Default)]
 pub struct InvertedIndex {
-    postings: Vec<Option<PostingList>>,
+    postings: Postings,
     pub vocab: HashMap<String, TokenId>,
     pub point_to_docs: Vec<Option<Document>>,
     pub points_count: usize,
+    /// Sum of lengths of all indexed documents, used to normalize BM25 scores
+    total_tokens_count: usize,
 }
 
 impl InvertedIndex {
@@ -69,49 +246,70 @@ impl InvertedIndex {
         Default::default()
     }
 
-    pub fn document_from_tokens(&mut self, tokens: &BTreeSet<String>) -> Document {
-        let mut document_tokens = vec![];
-        for token in tokens {
-            // check if in vocab
-            let vocab_idx = match self.vocab.get(token) {
-                Some(&idx) => idx,
-                None => {
-                    let next_token_id = self.vocab.len() as TokenId;
-                    self.vocab.insert(token.to_string(), next_token_id);
-                    next_token_id
-                }
-            };
-            document_tokens.push(vocab_idx);
+    fn register_token(&mut self, token: &str) -> TokenId {
+        // check if in vocab
+        match self.vocab.get(token) {
+            Some(&idx) => idx,
+            None => {
+                let next_token_id = self.vocab.len() as TokenId;
+                self.vocab.insert(token.to_string(), next_token_id);
+                next_token_id
+            }
         }
+    }
+
+    pub fn document_from_tokens(&mut self, tokens: &BTreeMap<String, u32>) -> Document {
+        let token_frequencies = tokens
+            .iter()
+            .map(|(token, &frequency)| (self.register_token(token), frequency))
+            .collect();
 
-        Document::new(document_tokens)
+        Document::from_frequencies(token_frequencies)
+    }
+
+    pub fn sequence_from_tokens(&mut self, tokens: &[String]) -> Vec<TokenId> {
+        tokens
+            .iter()
+            .map(|token| self.register_token(token))
+            .collect()
     }
 
     pub fn index_document(&mut self, idx: PointOffsetType, document: Document) {
         self.points_count += 1;
+        self.total_tokens_count += document.tokens_count();
         if self.point_to_docs.len() <= idx as usize {
             self.point_to_docs
                 .resize(idx as usize + 1, Default::default());
         }
 
         for token_idx in document.tokens() {
-            let token_idx_usize = *token_idx as usize;
-            if self.postings.len() <= token_idx_usize {
-                self.postings
-                    .resize(token_idx_usize + 1, Default::default());
-            }
-            let posting = self
-                .postings
-                .get_mut(token_idx_usize)
-                .expect("posting must exist even if with None");
-            match posting {
-                None => *posting = Some(PostingList::new(idx)),
-                Some(vec) => vec.insert(idx),
-            }
+            self.postings.insert(*token_idx, idx);
         }
         self.point_to_docs[idx as usize] = Some(document);
     }
 
+    pub fn is_immutable(&self) -> bool {
+        matches!(self.postings, Postings::Immutable { .. })
+    }
+
+    pub fn is_on_disk(&self) -> bool {
+        self.postings.is_on_disk()
+    }
+
+    /// Number of documents with each token of the vocabulary
+    pub fn token_postings(&self) -> impl Iterator<Item = (&str, usize)> + '_ {
+        self.vocab.iter().filter_map(|(token, &token_id)| {
+            let posting = self.postings.get(token_id)?;
+            Some((token.as_str(), posting.len()))
+        })
+    }
+
+    /// Flatten posting lists into the file at `path`, no documents can be added after that
+    pub fn flatten_postings(&mut self, path: &Path) -> OperationResult<()> {
+        self.postings = std::mem::take(&mut self.postings).into_immutable(path)?;
+        Ok(())
+    }
+
     pub fn remove_document(&mut self, idx: PointOffsetType) -> Option<Document> {
         if self.point_to_docs.len() <= idx as usize {
             return None; // Already removed or never actually existed
@@ -123,13 +321,10 @@ impl InvertedIndex {
         };
 
         self.points_count -= 1;
+        self.total_tokens_count -= removed_doc.tokens_count();
 
         for removed_token in removed_doc.tokens() {
-            // unwrap safety: posting list exists and contains the document id
-            let posting = self.postings.get_mut(*removed_token as usize).unwrap();
-            if let Some(vec) = posting {
-                vec.remove(idx);
-            }
+            self.postings.remove(*removed_token, idx);
         }
         Some(removed_doc)
     }
@@ -142,7 +337,7 @@ impl InvertedIndex {
                 None => None,
                 // if a ParsedQuery token was given an index, then it must exist in the vocabulary
                 // dictionary. Posting list entry can be None but it exists.
-                Some(idx) => self.postings.get(idx as usize).unwrap().as_ref(),
+                Some(idx) => self.postings.get(idx),
             })
             .collect();
         if postings_opt.is_none() {
@@ -154,7 +349,51 @@ impl InvertedIndex {
             // Empty request -> no matches
             return Box::new(vec![].into_iter());
         }
-        intersect_postings_iterator(postings)
+        let candidates = intersect_postings_iterator(postings);
+        match &query.phrase {
+            None => candidates,
+            // Points with all the tokens still need to have them adjacent and in order
+            Some(phrase) => {
+                let phrase = phrase.clone();
+                Box::new(candidates.filter(move |&idx| {
+                    self.point_to_docs
+                        .get(idx as usize)
+                        .and_then(Option::as_ref)
+                        .map_or(false, |doc| doc.check_phrase(&phrase))
+                }))
+            }
+        }
+    }
+
+    /// Score documents which contain any of the tokens with Okapi BM25.
+    ///
+    /// Statistics of the tokens are collected over this index only.
+    pub fn score_bm25(&self, tokens: &[TokenId]) -> HashMap<PointOffsetType, ScoreType> {
+        let mut scores = HashMap::new();
+        if self.points_count == 0 {
+            return scores;
+        }
+        let points_count = self.points_count as ScoreType;
+        let average_length = self.total_tokens_count as ScoreType / points_count;
+        for &token in tokens {
+            let Some(posting) = self.postings.get(token) else {
+                continue;
+            };
+            let document_frequency = posting.len() as ScoreType;
+            let idf =
+                (1.0 + (points_count - document_frequency + 0.5) / (document_frequency + 0.5)).ln();
+            for &idx in posting {
+                let Some(Some(document)) = self.point_to_docs.get(idx as usize) else {
+                    continue;
+                };
+                let frequency = document.frequency(token) as ScoreType;
+                let length_norm =
+                    1.0 - BM25_B + BM25_B * document.tokens_count() as ScoreType / average_length;
+                *scores.entry(idx).or_insert(0.0) +=
+                    idf * frequency * (BM25_K1 + 1.0) / (frequency + BM25_K1 * length_norm);
+            }
+        }
+        scores
     }
 
     pub fn estimate_cardinality(
@@ -167,8 +406,7 @@ impl InvertedIndex {
             .iter()
             .map(|&vocab_idx| match vocab_idx {
                 None => None,
-                // unwrap safety: same as in filter()
-                Some(idx) => self.postings.get(idx as usize).unwrap().as_ref(),
+                Some(idx) => self.postings.get(idx),
             })
             .collect();
         if postings_opt.is_none() {
@@ -193,7 +431,14 @@ impl InvertedIndex {
         // Smallest posting is the largest possible cardinality
         let smallest_posting = postings.iter().map(|posting| posting.len()).min().unwrap();
 
-        return if postings.len() == 1 {
+        // A phrase of a repeated single token is stricter than the token itself
+        let is_single_token = postings.len() == 1
+            && query
+                .phrase
+                .as_ref()
+                .map_or(true, |phrase| phrase.len() == 1);
+
+        return if is_single_token {
             CardinalityEstimation {
                 primary_clauses: vec![PrimaryCondition::Condition(condition.clone())],
                 min: smallest_posting,
@@ -225,18 +470,12 @@ impl InvertedIndex {
         Box::new(
             self.vocab
                 .iter()
-                .filter(|(_token, &posting_idx)| self.postings[posting_idx as usize].is_some())
-                .filter(move |(_token, &posting_idx)| {
-                    // unwrap crash safety: all tokens that passes the first filter should have postings
-                    self.postings[posting_idx as usize].as_ref().unwrap().len() >= threshold
-                })
-                .map(|(token, &posting_idx)| {
-                    (
-                        token,
-                        // same as the above case
-                        self.postings[posting_idx as usize].as_ref().unwrap(),
-                    )
+                .filter_map(|(token, &posting_idx)| {
+                    self.postings
+                        .get(posting_idx)
+                        .map(|posting| (token, posting))
                 })
+                .filter(move |(_token, posting)| posting.len() >= threshold)
                 .map(move |(token, posting)| PayloadBlockCondition {
                     condition: FieldCondition {
                         key: key.clone(),
@@ -244,6 +483,7 @@ impl InvertedIndex {
                             text: token.clone(),
                         })),
                         range: None,
+                        datetime_range: None,
                         geo_bounding_box: None,
                         geo_radius: None,
                         geo_polygon: None,
@@ -27,16 +27,9 @@ impl PostingList {
         }
     }
 
-    pub fn len(&self) -> usize {
-        self.list.len()
-    }
-
-    pub fn contains(&self, val: &PointOffsetType) -> bool {
-        self.list.binary_search(val).is_ok()
-    }
-
-    pub fn iter(&self) -> impl Iterator<Item = PointOffsetType> + '_ {
-        self.list.iter().copied()
+    /// Sorted ids of the documents
+    pub fn as_slice(&self) -> &[PointOffsetType] {
+        &self.list
     }
 }
 
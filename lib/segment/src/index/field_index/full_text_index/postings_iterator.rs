@@ -1,9 +1,8 @@
 use common::types::PointOffsetType;
 
-use super::posting_list::PostingList;
-
+/// Intersection of sorted posting lists
 pub fn intersect_postings_iterator<'a>(
-    mut postings: Vec<&'a PostingList>,
+    mut postings: Vec<&'a [PointOffsetType]>,
 ) -> Box<dyn Iterator<Item = PointOffsetType> + 'a> {
     let smallest_posting_idx = postings
         .iter()
@@ -13,9 +12,11 @@ pub fn intersect_postings_iterator<'a>(
         .unwrap();
     let smallest_posting = postings.remove(smallest_posting_idx);
 
-    let and_iter = smallest_posting
-        .iter()
-        .filter(move |doc_id| postings.iter().all(|posting| posting.contains(doc_id)));
+    let and_iter = smallest_posting.iter().copied().filter(move |doc_id| {
+        postings
+            .iter()
+            .all(|posting| posting.binary_search(doc_id).is_ok())
+    });
 
     Box::new(and_iter)
 }
@@ -23,6 +24,7 @@ pub fn intersect_postings_iterator<'a>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::index::field_index::full_text_index::posting_list::PostingList;
 
     #[test]
     fn test_postings_iterator() {
@@ -44,7 +46,7 @@ mod tests {
         p3.insert(6);
         p3.insert(7);
 
-        let postings = vec![&p1, &p2, &p3];
+        let postings = vec![p1.as_slice(), p2.as_slice(), p3.as_slice()];
         let merged = intersect_postings_iterator(postings);
 
         let res = merged.collect::<Vec<_>>();
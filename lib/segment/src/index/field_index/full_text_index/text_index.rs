@@ -1,7 +1,9 @@
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use common::types::PointOffsetType;
+use common::types::{PointOffsetType, ScoreType};
 use parking_lot::RwLock;
 use rocksdb::DB;
 use serde::{Deserialize, Serialize};
@@ -11,10 +13,12 @@ use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
 use crate::data_types::text_index::TextIndexParams;
+use crate::index::field_index::flat_container::{flat_container_path, remove_flat_container};
 use crate::index::field_index::full_text_index::inverted_index::{
-    Document, InvertedIndex, ParsedQuery,
+    Document, InvertedIndex, ParsedQuery, TokenId,
 };
 use crate::index::field_index::full_text_index::tokenizers::Tokenizer;
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::index::field_index::{
     CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, ValueIndexer,
 };
@@ -24,7 +28,11 @@ use crate::types::{FieldCondition, Match, PayloadKeyType};
 pub struct FullTextIndex {
     inverted_index: InvertedIndex,
     db_wrapper: DatabaseColumnWrapper,
-    config: TextIndexParams,
+    tokenizer: Tokenizer,
+    phrase_matching: bool,
+    on_disk: None,
+    /// File for the postings of a loaded index, which is not updated anymore
+    postings_path: Option<PathBuf>,
 }
 
 impl FullTextIndex {
@@ -36,12 +44,25 @@ impl FullTextIndex {
         bincode::deserialize(data).unwrap()
     }
 
-    fn serialize_document_tokens(&self, tokens: BTreeSet<String>) -> OperationResult<Vec<u8>> {
+    fn serialize_document_tokens(
+        &self,
+        tokens: BTreeMap<String, u32>,
+        sequences: Vec<Vec<String>>,
+    ) -> OperationResult<Vec<u8>> {
         #[derive(Serialize)]
         struct StoredDocument {
             tokens: BTreeSet<String>,
+            /// Aligned with `tokens`
+            frequencies: Vec<u32>,
+            #[serde(skip_serializing_if = "Vec::is_empty")]
+            sequences: Vec<Vec<String>>,
         }
-        let doc = StoredDocument { tokens };
+        let (tokens, frequencies) = tokens.into_iter().unzip();
+        let doc = StoredDocument {
+            tokens,
+            frequencies,
+            sequences,
+        };
         serde_cbor::to_vec(&doc).map_err(|e| {
             OperationError::service_error(format!("Failed to serialize document: {e}"))
         })
@@ -51,12 +72,28 @@ impl FullTextIndex {
         #[derive(Deserialize)]
         struct StoredDocument {
             tokens: BTreeSet<String>,
+            // Documents stored before BM25 scoring was introduced have no frequencies
+            #[serde(default)]
+            frequencies: Vec<u32>,
+            #[serde(default)]
+            sequences: Vec<Vec<String>>,
         }
         serde_cbor::from_slice::<StoredDocument>(data)
             .map_err(|e| {
                 OperationError::service_error(format!("Failed to deserialize document: {e}"))
             })
-            .map(|doc| index.document_from_tokens(&doc.tokens))
+            .map(|doc| {
+                let sequences = doc
+                    .sequences
+                    .iter()
+                    .map(|sequence| index.sequence_from_tokens(sequence))
+                    .collect();
+                let frequencies = doc.frequencies.into_iter().chain(std::iter::repeat(1));
+                let tokens = doc.tokens.into_iter().zip(frequencies).collect();
+                index
+                    .document_from_tokens(&tokens)
+                    .with_sequences(sequences)
+            })
     }
 
     fn storage_cf_name(field: &str) -> String {
@@ -69,7 +106,26 @@ impl FullTextIndex {
         FullTextIndex {
             inverted_index: InvertedIndex::new(),
             db_wrapper,
-            config,
+            tokenizer: Tokenizer::new(&config),
+            phrase_matching: config.phrase_matching.unwrap_or(false),
+            on_disk: None,
+            postings_path: None,
+        }
+    }
+
+    /// Token postings of a loaded non-appendable index are moved to a memory mapped file in `dir`
+    pub fn new_on_disk(
+        db: Arc<RwLock<DB>>,
+        config: TextIndexParams,
+        field: &str,
+        dir: &Path,
+        is_appendable: bool,
+    ) -> Self {
+        let postings_path =
+            (!is_appendable).then(|| flat_container_path(dir, &Self::storage_cf_name(field)));
+        FullTextIndex {
+            postings_path,
+            ..Self::new(db, config, field)
         }
     }
 
@@ -89,28 +145,98 @@ impl FullTextIndex {
         }
     }
 
+    pub fn get_stats(&self) -> FieldIndexStats {
+        let postings: HashMap<_, _> = self
+            .inverted_index
+            .token_postings()
+            .filter(|(_token, points_count)| *points_count > 0)
+            .map(|(token, points_count)| (token.to_owned(), points_count))
+            .collect();
+        let values_count = postings.values().sum();
+        let postings_bytes = values_count * size_of::<PointOffsetType>();
+        // Documents keep each of their tokens with its frequency
+        let documents_bytes = values_count * (size_of::<TokenId>() + size_of::<u32>());
+        let vocab_bytes: usize = postings
+            .keys()
+            .map(|token| token.len() + size_of::<String>() + size_of::<TokenId>())
+            .sum();
+        let is_on_disk = self.inverted_index.is_on_disk();
+        FieldIndexStats {
+            points_count: self.inverted_index.points_count,
+            values_count,
+            postings: Some(postings),
+            histogram: None,
+            ram_usage_bytes: documents_bytes
+                + vocab_bytes
+                + if is_on_disk { 0 } else { postings_bytes },
+            disk_usage_bytes: if is_on_disk { postings_bytes } else { 0 },
+        }
+    }
+
     pub fn recreate(&self) -> OperationResult<()> {
         self.db_wrapper.recreate_column_family()
     }
 
     pub fn parse_query(&self, text: &str) -> ParsedQuery {
         let mut tokens = HashSet::new();
-        Tokenizer::tokenize_query(text, &self.config, |token| {
+        self.tokenizer.tokenize_query(text, |token| {
             tokens.insert(self.inverted_index.vocab.get(token).copied());
         });
         ParsedQuery {
             tokens: tokens.into_iter().collect(),
+            phrase: None,
         }
     }
 
+    /// Parse phrase query, returns `None` if the index does not keep the order of tokens
+    pub fn parse_phrase(&self, phrase: &str) -> Option<ParsedQuery> {
+        if !self.phrase_matching {
+            return None;
+        }
+        let mut tokens = HashSet::new();
+        let mut phrase_tokens = vec![];
+        self.tokenizer.tokenize_query(phrase, |token| {
+            let token_id = self.inverted_index.vocab.get(token).copied();
+            tokens.insert(token_id);
+            phrase_tokens.extend(token_id);
+        });
+        Some(ParsedQuery {
+            tokens: tokens.into_iter().collect(),
+            phrase: Some(phrase_tokens),
+        })
+    }
+
     pub fn parse_document(&self, text: &str) -> Document {
         let mut document_tokens = vec![];
-        Tokenizer::tokenize_doc(text, &self.config, |token| {
+        self.tokenizer.tokenize_doc(text, |token| {
             if let Some(token_id) = self.inverted_index.vocab.get(token) {
                 document_tokens.push(*token_id);
             }
         });
-        Document::new(document_tokens)
+        let document = Document::new(document_tokens);
+        if !self.phrase_matching {
+            return document;
+        }
+        let mut sequence = vec![];
+        self.tokenizer.tokenize_query(text, |token| {
+            if let Some(token_id) = self.inverted_index.vocab.get(token) {
+                sequence.push(*token_id);
+            }
+        });
+        document.with_sequences(vec![sequence])
+    }
+
+    /// BM25 relevance of indexed points to the text.
+    /// Points without any of the query tokens are not included.
+    pub fn score_bm25(&self, text: &str) -> HashMap<PointOffsetType, ScoreType> {
+        let mut tokens = BTreeSet::new();
+        self.tokenizer.tokenize_query(text, |token| {
+            if let Some(&token_id) = self.inverted_index.vocab.get(token) {
+                tokens.insert(token_id);
+            }
+        });
+        self.inverted_index
+            .score_bm25(&tokens.into_iter().collect::<Vec<_>>())
     }
 
     #[cfg(test)]
@@ -135,19 +261,41 @@ impl ValueIndexer<String> for FullTextIndex {
             return Ok(());
         }
 
-        let mut tokens: BTreeSet<String> = BTreeSet::new();
+        if self.inverted_index.is_immutable() {
+            return Err(OperationError::service_error(
+                "Can't add values to immutable text index",
+            ));
+        }
+
+        let mut tokens: BTreeMap<String, u32> = BTreeMap::new();
+        let mut sequences: Vec<Vec<String>> = vec![];
 
         for value in values {
-            Tokenizer::tokenize_doc(&value, &self.config, |token| {
-                tokens.insert(token.to_owned());
+            self.tokenizer.tokenize_doc(&value, |token| {
+                *tokens.entry(token.to_owned()).or_insert(0) += 1;
             });
+            if self.phrase_matching {
+                // Sequences are tokenized the same way as phrase queries,
+                // so e.g. prefix tokenizer keeps a single token per word
+                let mut sequence = vec![];
+                self.tokenizer.tokenize_query(&value, |token| {
+                    tokens.entry(token.to_owned()).or_insert(0);
+                    sequence.push(token.to_owned());
+                });
+                sequences.push(sequence);
+            }
         }
 
         let document = self.inverted_index.document_from_tokens(&tokens);
-        self.inverted_index.index_document(idx, document);
+        let document_sequences = sequences
+            .iter()
+            .map(|sequence| self.inverted_index.sequence_from_tokens(sequence))
+            .collect();
+        self.inverted_index
+            .index_document(idx, document.with_sequences(document_sequences));
 
         let db_idx = Self::store_key(&idx);
-        let db_document = self.serialize_document_tokens(tokens)?;
+        let db_document = self.serialize_document_tokens(tokens, sequences)?;
 
         self.db_wrapper.put(db_idx, db_document)?;
 
@@ -190,10 +338,14 @@ impl PayloadFieldIndex for FullTextIndex {
             let document = Self::deserialize_document(&value, &mut self.inverted_index)?;
             self.inverted_index.index_document(idx, document);
         }
+        if let Some(path) = &self.postings_path {
+            self.inverted_index.flatten_postings(path)?;
+        }
         Ok(true)
     }
 
     fn clear(self) -> OperationResult<()> {
+        remove_flat_container(self.postings_path.as_deref())?;
         self.db_wrapper.remove_column_family()
     }
 
@@ -205,9 +357,17 @@ impl PayloadFieldIndex for FullTextIndex {
         &self,
         condition: &FieldCondition,
     ) -> OperationResult<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
-        if let Some(Match::Text(text_match)) = &condition.r#match {
-            let parsed_query = self.parse_query(&text_match.text);
-            return Ok(self.inverted_index.filter(&parsed_query));
+        match &condition.r#match {
+            Some(Match::Text(text_match)) => {
+                let parsed_query = self.parse_query(&text_match.text);
+                return Ok(self.inverted_index.filter(&parsed_query));
+            }
+            Some(Match::Phrase(phrase_match)) => {
+                if let Some(parsed_query) = self.parse_phrase(&phrase_match.phrase) {
+                    return Ok(self.inverted_index.filter(&parsed_query));
+                }
+            }
+            _ => {}
         }
         Err(OperationError::service_error("failed to filter"))
     }
@@ -216,8 +376,12 @@ impl PayloadFieldIndex for FullTextIndex {
         &self,
         condition: &FieldCondition,
     ) -> OperationResult<CardinalityEstimation> {
-        if let Some(Match::Text(text_match)) = &condition.r#match {
-            let parsed_query = self.parse_query(&text_match.text);
+        let parsed_query = match &condition.r#match {
+            Some(Match::Text(text_match)) => Some(self.parse_query(&text_match.text)),
+            Some(Match::Phrase(phrase_match)) => self.parse_phrase(&phrase_match.phrase),
+            _ => None,
+        };
+        if let Some(parsed_query) = parsed_query {
             return Ok(self
                 .inverted_index
                 .estimate_cardinality(&parsed_query, condition));
@@ -244,7 +408,7 @@ mod tests {
     use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
     use crate::common::utils::MultiValue;
     use crate::data_types::text_index::{TextIndexType, TokenizerType};
-    use crate::types::MatchText;
+    use crate::types::{MatchPhrase, MatchText};
 
     fn filter_request(text: &str) -> FieldCondition {
         FieldCondition {
@@ -253,6 +417,22 @@ mod tests {
                 text: text.to_owned(),
             })),
             range: None,
+            datetime_range: None,
+            geo_bounding_box: None,
+            geo_radius: None,
+            values_count: None,
+            geo_polygon: None,
+        }
+    }
+
+    fn phrase_request(phrase: &str) -> FieldCondition {
+        FieldCondition {
+            key: "text".to_owned(),
+            r#match: Some(Match::Phrase(MatchPhrase {
+                phrase: phrase.to_owned(),
+            })),
+            range: None,
+            datetime_range: None,
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
@@ -280,6 +460,10 @@ mod tests {
             min_token_len: None,
             max_token_len: None,
             lowercase: None,
+            stemmer: None,
+            stopwords: None,
+            phrase_matching: None,
+            on_disk: None,
         };
 
         {
@@ -350,4 +534,87 @@ mod tests {
             assert_eq!(search_res, vec![0, 1, 3, 4]);
         }
     }
+
+    #[test]
+    fn test_phrase_matching() {
+        let payloads: Vec<_> = vec![
+            serde_json::json!("Flights from New York to Berlin"),
+            serde_json::json!("York is a city in England, not new at all"),
+            serde_json::json!(["Visit New", "York cheesecake"]),
+            serde_json::json!("The new office in York"),
+        ];
+
+        let temp_dir = Builder::new().prefix("test_dir").tempdir().unwrap();
+        let config = TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Word,
+            min_token_len: None,
+            max_token_len: None,
+            lowercase: None,
+            stemmer: None,
+            stopwords: None,
+            phrase_matching: Some(true),
+            on_disk: None,
+        };
+
+        {
+            let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+            let mut index = FullTextIndex::new(db, config.clone(), "text");
+            index.recreate().unwrap();
+
+            for (idx, payload) in payloads.iter().enumerate() {
+                index
+                    .add_point(idx as PointOffsetType, &MultiValue::one(payload))
+                    .unwrap();
+            }
+
+            let search_res: Vec<_> = index.filter(&filter_request("new york")).unwrap().collect();
+            assert_eq!(search_res, vec![0, 1, 2, 3]);
+
+            // Values of an array are not adjacent to each other
+            let search_res: Vec<_> = index.filter(&phrase_request("New York")).unwrap().collect();
+            assert_eq!(search_res, vec![0]);
+
+            let search_res: Vec<_> = index.filter(&phrase_request("york new")).unwrap().collect();
+            assert!(search_res.is_empty());
+
+            let estimation = index
+                .estimate_cardinality(&phrase_request("new york"))
+                .unwrap();
+            assert_eq!(estimation.min, 0);
+            assert!(estimation.max >= 1);
+
+            index.flusher()().unwrap();
+        }
+
+        {
+            let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+            let mut index = FullTextIndex::new(db, config.clone(), "text");
+            assert!(index.load().unwrap());
+
+            let search_res: Vec<_> = index
+                .filter(&phrase_request("york cheesecake"))
+                .unwrap()
+                .collect();
+            assert_eq!(search_res, vec![2]);
+
+            let search_res: Vec<_> = index.filter(&phrase_request("new york")).unwrap().collect();
+            assert_eq!(search_res, vec![0]);
+        }
+
+        {
+            let db = open_db_with_existing_cf(&temp_dir.path().join("test_db")).unwrap();
+            let index = FullTextIndex::new(
+                db,
+                TextIndexParams {
+                    phrase_matching: None,
+                    on_disk: None,
+                    ..config
+                },
+                "text",
+            );
+            // Index without token order can't serve phrase conditions
+            assert!(index.filter(&phrase_request("new york")).is_err());
+        }
+    }
 }
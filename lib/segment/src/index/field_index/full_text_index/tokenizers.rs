@@ -1,6 +1,10 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
 use charabia::Tokenize;
+use rust_stemmers::{Algorithm, Stemmer};
 
-use crate::data_types::text_index::{TextIndexParams, TokenizerType};
+use crate::data_types::text_index::{Language, TextIndexParams, TokenizerType};
 
 struct WhiteSpaceTokenizer;
 
@@ -74,70 +78,152 @@ impl MultilingualTokenizer {
     }
 }
 
-pub struct Tokenizer;
+/// Splits text into tokens and normalizes them according to the text index params
+pub struct Tokenizer {
+    params: TextIndexParams,
+    stopwords: HashSet<String>,
+    stemmer: Option<Stemmer>,
+}
 
 impl Tokenizer {
+    pub fn new(params: &TextIndexParams) -> Self {
+        let lowercase = params.lowercase.unwrap_or(true);
+        let stopwords = params
+            .stopwords
+            .iter()
+            .flat_map(|stopwords| {
+                stopwords
+                    .languages
+                    .iter()
+                    .flat_map(|&language| stop_words::get(stop_words_language(language)))
+                    .chain(stopwords.custom.iter().cloned())
+            })
+            .map(|word| if lowercase { word.to_lowercase() } else { word })
+            .collect();
+        let stemmer = params
+            .stemmer
+            .map(|language| Stemmer::create(stemmer_algorithm(language)));
+        Self {
+            params: params.clone(),
+            stopwords,
+            stemmer,
+        }
+    }
+
     fn doc_token_filter<'a, C: FnMut(&str) + 'a>(
-        config: &'a TextIndexParams,
+        &'a self,
         mut callback: C,
     ) -> impl FnMut(&str) + 'a {
         move |token: &str| {
-            if config
+            if self
+                .params
                 .min_token_len
                 .map(|min_len| token.len() < min_len && token.chars().count() < min_len)
                 .unwrap_or(false)
             {
                 return;
             }
-            if config
+            if self
+                .params
                 .max_token_len
                 .map(|max_len| token.len() > max_len && token.chars().count() > max_len)
                 .unwrap_or(false)
             {
                 return;
             }
-            if config.lowercase.unwrap_or(true) {
-                callback(&token.to_lowercase());
+            let token = if self.params.lowercase.unwrap_or(true) {
+                Cow::Owned(token.to_lowercase())
             } else {
-                callback(token);
+                Cow::Borrowed(token)
+            };
+            if self.stopwords.contains(token.as_ref()) {
+                return;
+            }
+            match &self.stemmer {
+                Some(stemmer) => callback(&stemmer.stem(&token)),
+                None => callback(&token),
             }
         }
     }
 
-    pub fn tokenize_doc<C: FnMut(&str)>(text: &str, config: &TextIndexParams, mut callback: C) {
-        let token_filter = Self::doc_token_filter(config, &mut callback);
-        match config.tokenizer {
+    pub fn tokenize_doc<C: FnMut(&str)>(&self, text: &str, mut callback: C) {
+        let token_filter = self.doc_token_filter(&mut callback);
+        match self.params.tokenizer {
             TokenizerType::Whitespace => WhiteSpaceTokenizer::tokenize(text, token_filter),
             TokenizerType::Word => WordTokenizer::tokenize(text, token_filter),
             TokenizerType::Multilingual => MultilingualTokenizer::tokenize(text, token_filter),
             TokenizerType::Prefix => PrefixTokenizer::tokenize(
                 text,
-                config.min_token_len.unwrap_or(1),
-                config.max_token_len.unwrap_or(usize::MAX),
+                self.params.min_token_len.unwrap_or(1),
+                self.params.max_token_len.unwrap_or(usize::MAX),
                 token_filter,
             ),
         }
     }
 
-    pub fn tokenize_query<C: FnMut(&str)>(text: &str, config: &TextIndexParams, mut callback: C) {
-        let token_filter = Self::doc_token_filter(config, &mut callback);
-        match config.tokenizer {
+    pub fn tokenize_query<C: FnMut(&str)>(&self, text: &str, mut callback: C) {
+        let token_filter = self.doc_token_filter(&mut callback);
+        match self.params.tokenizer {
             TokenizerType::Whitespace => WhiteSpaceTokenizer::tokenize(text, token_filter),
             TokenizerType::Word => WordTokenizer::tokenize(text, token_filter),
             TokenizerType::Multilingual => MultilingualTokenizer::tokenize(text, token_filter),
             TokenizerType::Prefix => PrefixTokenizer::tokenize_query(
                 text,
-                config.max_token_len.unwrap_or(usize::MAX),
+                self.params.max_token_len.unwrap_or(usize::MAX),
                 token_filter,
             ),
         }
     }
 }
 
+fn stemmer_algorithm(language: Language) -> Algorithm {
+    match language {
+        Language::Arabic => Algorithm::Arabic,
+        Language::Danish => Algorithm::Danish,
+        Language::Dutch => Algorithm::Dutch,
+        Language::English => Algorithm::English,
+        Language::Finnish => Algorithm::Finnish,
+        Language::French => Algorithm::French,
+        Language::German => Algorithm::German,
+        Language::Greek => Algorithm::Greek,
+        Language::Hungarian => Algorithm::Hungarian,
+        Language::Italian => Algorithm::Italian,
+        Language::Norwegian => Algorithm::Norwegian,
+        Language::Portuguese => Algorithm::Portuguese,
+        Language::Romanian => Algorithm::Romanian,
+        Language::Russian => Algorithm::Russian,
+        Language::Spanish => Algorithm::Spanish,
+        Language::Swedish => Algorithm::Swedish,
+        Language::Turkish => Algorithm::Turkish,
+    }
+}
+
+fn stop_words_language(language: Language) -> stop_words::LANGUAGE {
+    match language {
+        Language::Arabic => stop_words::LANGUAGE::Arabic,
+        Language::Danish => stop_words::LANGUAGE::Danish,
+        Language::Dutch => stop_words::LANGUAGE::Dutch,
+        Language::English => stop_words::LANGUAGE::English,
+        Language::Finnish => stop_words::LANGUAGE::Finnish,
+        Language::French => stop_words::LANGUAGE::French,
+        Language::German => stop_words::LANGUAGE::German,
+        Language::Greek => stop_words::LANGUAGE::Greek,
+        Language::Hungarian => stop_words::LANGUAGE::Hungarian,
+        Language::Italian => stop_words::LANGUAGE::Italian,
+        Language::Norwegian => stop_words::LANGUAGE::Norwegian,
+        Language::Portuguese => stop_words::LANGUAGE::Portuguese,
+        Language::Romanian => stop_words::LANGUAGE::Romanian,
+        Language::Russian => stop_words::LANGUAGE::Russian,
+        Language::Spanish => stop_words::LANGUAGE::Spanish,
+        Language::Swedish => stop_words::LANGUAGE::Swedish,
+        Language::Turkish => stop_words::LANGUAGE::Turkish,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::data_types::text_index::TextIndexType;
+    use crate::data_types::text_index::{StopwordsSet, TextIndexType};
 
     #[test]
     fn test_whitespace_tokenizer() {
@@ -246,17 +332,18 @@ mod tests {
     fn test_tokenizer() {
         let text = "Hello, Мир!";
         let mut tokens = Vec::new();
-        Tokenizer::tokenize_doc(
-            text,
-            &TextIndexParams {
-                r#type: TextIndexType::Text,
-                tokenizer: TokenizerType::Prefix,
-                min_token_len: Some(1),
-                max_token_len: Some(4),
-                lowercase: Some(true),
-            },
-            |token| tokens.push(token.to_owned()),
-        );
+        Tokenizer::new(&TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Prefix,
+            min_token_len: Some(1),
+            max_token_len: Some(4),
+            lowercase: Some(true),
+            stemmer: None,
+            stopwords: None,
+            phrase_matching: None,
+            on_disk: None,
+        })
+        .tokenize_doc(text, |token| tokens.push(token.to_owned()));
         eprintln!("tokens = {tokens:#?}");
         assert_eq!(tokens.len(), 7);
         assert_eq!(tokens.get(0), Some(&"h".to_owned()));
@@ -267,4 +354,27 @@ mod tests {
         assert_eq!(tokens.get(5), Some(&"ми".to_owned()));
         assert_eq!(tokens.get(6), Some(&"мир".to_owned()));
     }
+
+    #[test]
+    fn test_tokenizer_stemmer_and_stopwords() {
+        let tokenizer = Tokenizer::new(&TextIndexParams {
+            r#type: TextIndexType::Text,
+            tokenizer: TokenizerType::Word,
+            min_token_len: None,
+            max_token_len: None,
+            lowercase: None,
+            stemmer: Some(Language::English),
+            stopwords: Some(StopwordsSet {
+                languages: vec![Language::English],
+                custom: vec!["Robots".to_owned()],
+            }),
+            phrase_matching: None,
+            on_disk: None,
+        });
+        let mut tokens = Vec::new();
+        tokenizer.tokenize_doc("The Robots were jumping in the gardens", |token| {
+            tokens.push(token.to_owned())
+        });
+        assert_eq!(tokens, vec!["jump", "garden"]);
+    }
 }
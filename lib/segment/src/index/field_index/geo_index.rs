@@ -1,5 +1,8 @@
 use std::cmp::{max, min};
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -12,11 +15,15 @@ use serde_json::Value;
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
+use crate::index::field_index::flat_container::{
+    flat_container_path, remove_flat_container, FlatContainer,
+};
 use crate::index::field_index::geo_hash::{
     circle_hashes, common_hash_prefix, encode_max_precision, geo_hash_to_box, polygon_hashes,
     polygon_hashes_estimation, rectangle_hashes, GeoHash,
 };
 use crate::index::field_index::stat_tools::estimate_multi_value_selection_cardinality;
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::index::field_index::{
     CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
 };
@@ -29,6 +36,134 @@ use crate::types::{
 // TODO discuss value, should it be dynamically computed?
 const GEO_QUERY_MAX_REGION: usize = 12;
 
+/// Points stored under each full precision geo-hash
+enum GeoPostings {
+    Mutable(BTreeMap<GeoHash, HashSet<PointOffsetType>>),
+    /// Flattened postings, which points can only be removed from
+    Immutable {
+        ranges: BTreeMap<GeoHash, Range<u32>>,
+        container: FlatContainer<PointOffsetType>,
+    },
+}
+
+impl Default for GeoPostings {
+    fn default() -> Self {
+        GeoPostings::Mutable(Default::default())
+    }
+}
+
+impl GeoPostings {
+    /// Flatten postings into the file at `path`
+    fn into_immutable(self, path: &Path) -> OperationResult<Self> {
+        let GeoPostings::Mutable(map) = self else {
+            return Ok(self);
+        };
+        let mut ranges = BTreeMap::new();
+        let mut container = Vec::new();
+        for (geo_hash, points) in map {
+            let start = container.len() as u32;
+            container.extend(points);
+            ranges.insert(geo_hash, start..container.len() as u32);
+        }
+        Ok(GeoPostings::Immutable {
+            ranges,
+            container: FlatContainer::new(container, Some(path))?,
+        })
+    }
+
+    fn is_immutable(&self) -> bool {
+        matches!(self, GeoPostings::Immutable { .. })
+    }
+
+    fn insert(&mut self, geo_hash: &GeoHash, idx: PointOffsetType) {
+        match self {
+            GeoPostings::Mutable(map) => {
+                map.entry(geo_hash.to_owned()).or_default().insert(idx);
+            }
+            GeoPostings::Immutable { .. } => {
+                debug_assert!(false, "Can't add points to immutable geo postings")
+            }
+        }
+    }
+
+    /// Returns false if there are no points stored under `geo_hash`
+    fn remove(&mut self, geo_hash: &GeoHash, idx: PointOffsetType) -> bool {
+        match self {
+            GeoPostings::Mutable(map) => {
+                let Some(hash_ids) = map.get_mut(geo_hash) else {
+                    return false;
+                };
+                hash_ids.remove(&idx);
+                if hash_ids.is_empty() {
+                    map.remove(geo_hash);
+                }
+            }
+            GeoPostings::Immutable { ranges, container } => {
+                let Some(range) = ranges.get_mut(geo_hash) else {
+                    return false;
+                };
+                // Move the removed point out of the range, same as in `ImmutableMapIndex`
+                let points = &mut container[range.start as usize..range.end as usize];
+                if let Some(pos) = points.iter().position(|&x| x == idx) {
+                    points.swap(pos, points.len() - 1);
+                    range.end -= 1;
+                }
+                if range.is_empty() {
+                    ranges.remove(geo_hash);
+                }
+            }
+        }
+        true
+    }
+
+    fn is_on_disk(&self) -> bool {
+        match self {
+            GeoPostings::Mutable(_) => false,
+            GeoPostings::Immutable { container, .. } => container.is_on_disk(),
+        }
+    }
+
+    /// Number of points stored under each geo-hash
+    fn sizes(&self) -> Box<dyn Iterator<Item = (&GeoHash, usize)> + '_> {
+        match self {
+            GeoPostings::Mutable(map) => Box::new(
+                map.iter()
+                    .map(|(geo_hash, points)| (geo_hash, points.len())),
+            ),
+            GeoPostings::Immutable { ranges, .. } => Box::new(
+                ranges
+                    .iter()
+                    .map(|(geo_hash, range)| (geo_hash, range.len())),
+            ),
+        }
+    }
+
+    /// Points of all stored geo-hashes, which start with `prefix`
+    fn sub_region_points(
+        &self,
+        prefix: &GeoHash,
+    ) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        let prefix_clone = prefix.clone();
+        match self {
+            GeoPostings::Mutable(map) => Box::new(
+                map.range(prefix.clone()..)
+                    .take_while(move |(p, _h)| p.starts_with(prefix_clone.as_str()))
+                    .flat_map(|(_geohash, points)| points.iter().copied()),
+            ),
+            GeoPostings::Immutable { ranges, container } => Box::new(
+                ranges
+                    .range(prefix.clone()..)
+                    .take_while(move |(p, _r)| p.starts_with(prefix_clone.as_str()))
+                    .flat_map(move |(_geohash, range)| {
+                        container[range.start as usize..range.end as usize]
+                            .iter()
+                            .copied()
+                    }),
+            ),
+        }
+    }
+}
+
 pub struct GeoMapIndex {
     /*
     {
@@ -50,12 +185,14 @@ pub struct GeoMapIndex {
         ...
     }
      */
-    points_map: BTreeMap<GeoHash, HashSet<PointOffsetType>>,
+    points_map: GeoPostings,
     point_to_values: Vec<Vec<GeoPoint>>,
     points_count: usize,
     values_count: usize,
     max_values_per_point: usize,
     db_wrapper: DatabaseColumnWrapper,
+    /// File to flatten `points_map` into on load, if the index is stored on disk
+    postings_path: Option<PathBuf>,
 }
 
 impl GeoMapIndex {
@@ -71,6 +208,18 @@ impl GeoMapIndex {
             values_count: 0,
             max_values_per_point: 1,
             db_wrapper,
+            postings_path: None,
+        }
+    }
+
+    /// Same as [`GeoMapIndex::new`], but in a non-appendable segment postings are kept in a memory
+    /// mapped file in `dir` instead of RAM
+    pub fn new_on_disk(db: Arc<RwLock<DB>>, field: &str, dir: &Path, is_appendable: bool) -> Self {
+        let postings_path =
+            (!is_appendable).then(|| flat_container_path(dir, &Self::storage_cf_name(field)));
+        GeoMapIndex {
+            postings_path,
+            ..Self::new(db, field)
         }
     }
 
@@ -192,10 +341,7 @@ impl GeoMapIndex {
                 .push(geo_hash.clone());
 
             self.point_to_values[idx as usize].push(geo_point);
-            self.points_map
-                .entry(geo_hash.clone())
-                .or_default()
-                .insert(idx);
+            self.points_map.insert(&geo_hash, idx);
 
             self.values_count += 1;
         }
@@ -207,6 +353,10 @@ impl GeoMapIndex {
                 self.increment_hash_value_counts(&geo_hash);
             }
         }
+
+        if let Some(postings_path) = &self.postings_path {
+            self.points_map = std::mem::take(&mut self.points_map).into_immutable(postings_path)?;
+        }
         Ok(true)
     }
 
@@ -327,6 +477,27 @@ impl GeoMapIndex {
         }
     }
 
+    pub fn get_stats(&self) -> FieldIndexStats {
+        let postings: HashMap<_, _> = self
+            .points_map
+            .sizes()
+            .map(|(geo_hash, points_count)| (geo_hash.to_string(), points_count))
+            .collect();
+        let postings_bytes = self.values_count * size_of::<PointOffsetType>();
+        let hashes_count = postings.len() + self.points_per_hash.len() + self.values_per_hash.len();
+        let ram_usage_bytes = self.values_count * size_of::<GeoPoint>()
+            + hashes_count * (size_of::<GeoHash>() + size_of::<usize>());
+        let is_on_disk = self.points_map.is_on_disk();
+        FieldIndexStats {
+            points_count: self.points_count,
+            values_count: self.values_count,
+            postings: Some(postings),
+            histogram: None,
+            ram_usage_bytes: ram_usage_bytes + if is_on_disk { 0 } else { postings_bytes },
+            disk_usage_bytes: if is_on_disk { postings_bytes } else { 0 },
+        }
+    }
+
     fn remove_point(&mut self, idx: PointOffsetType) -> OperationResult<()> {
         if self.point_to_values.len() <= idx as usize {
             return Ok(()); // Already removed or never actually existed
@@ -350,19 +521,11 @@ impl GeoMapIndex {
             let key = Self::encode_db_key(&removed_geo_hash, idx);
             self.db_wrapper.remove(key)?;
 
-            let is_last = if let Some(hash_ids) = self.points_map.get_mut(&removed_geo_hash) {
-                hash_ids.remove(&idx);
-                hash_ids.is_empty()
-            } else {
+            if !self.points_map.remove(&removed_geo_hash, idx) {
                 log::warn!(
                     "Geo index error: no points for hash {} was found",
                     removed_geo_hash
                 );
-                false
-            };
-
-            if is_last {
-                self.points_map.remove(&removed_geo_hash);
             }
 
             self.decrement_hash_value_counts(&removed_geo_hash);
@@ -381,6 +544,12 @@ impl GeoMapIndex {
             return Ok(());
         }
 
+        if self.points_map.is_immutable() {
+            return Err(OperationError::service_error(
+                "Can't add values to immutable geo index",
+            ));
+        }
+
         if self.point_to_values.len() <= idx as usize {
             // That's a smart reallocation
             self.point_to_values.resize(idx as usize + 1, vec![]);
@@ -403,10 +572,7 @@ impl GeoMapIndex {
         }
 
         for geo_hash in &geo_hashes {
-            self.points_map
-                .entry(geo_hash.to_owned())
-                .or_default()
-                .insert(idx);
+            self.points_map.insert(geo_hash, idx);
 
             self.increment_hash_value_counts(geo_hash);
         }
@@ -419,26 +585,11 @@ impl GeoMapIndex {
         Ok(())
     }
 
-    fn get_stored_sub_regions(
-        &self,
-        geo: &GeoHash,
-    ) -> Box<dyn Iterator<Item = (&GeoHash, &HashSet<PointOffsetType>)> + '_> {
-        let geo_clone = geo.clone();
-        Box::new(
-            self.points_map
-                .range(geo.clone()..)
-                .take_while(move |(p, _h)| p.starts_with(geo_clone.as_str())),
-        )
-    }
-
     fn get_iterator(&self, values: Vec<GeoHash>) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
         Box::new(
             values
                 .into_iter()
-                .flat_map(|top_geo_hash| {
-                    self.get_stored_sub_regions(&top_geo_hash)
-                        .flat_map(|(_geohash, points)| points.iter().copied())
-                })
+                .flat_map(|top_geo_hash| self.points_map.sub_region_points(&top_geo_hash))
                 .unique(),
         )
     }
@@ -519,6 +670,7 @@ impl PayloadFieldIndex for GeoMapIndex {
     }
 
     fn clear(self) -> OperationResult<()> {
+        remove_flat_container(self.postings_path.as_deref())?;
         self.db_wrapper.remove_column_family()
     }
 
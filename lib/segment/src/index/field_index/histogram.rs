@@ -6,6 +6,7 @@ use std::ops::Bound;
 use itertools::Itertools;
 use num_traits::{Num, Signed};
 
+use crate::index::field_index::stats::ValuesBucket;
 use crate::index::field_index::utils::check_boundaries;
 
 const MIN_BUCKET_SIZE: usize = 10;
@@ -135,6 +136,23 @@ impl<T: Numericable> Histogram<T> {
         Ok(())
     }
 
+    /// Values between each pair of neighbouring borders, the last border makes its own bucket
+    pub fn buckets(&self) -> Vec<ValuesBucket> {
+        let borders = self.borders.iter().collect_vec();
+        borders
+            .iter()
+            .enumerate()
+            .map(|(i, (border, counts))| ValuesBucket {
+                from: border.val.to_f64(),
+                to: borders
+                    .get(i + 1)
+                    .map_or(border.val, |(next, _)| next.val)
+                    .to_f64(),
+                count: counts.right + 1,
+            })
+            .collect()
+    }
+
     pub fn current_bucket_size(&self) -> usize {
         let bucket_size = (self.total_count as f64 * self.precision) as usize;
         bucket_size.clamp(MIN_BUCKET_SIZE, self.max_bucket_size)
@@ -1,12 +1,16 @@
+use std::path::Path;
 use std::sync::Arc;
 
 use parking_lot::RwLock;
 use rocksdb::DB;
 
 use super::binary_index::BinaryIndex;
+use super::composite_index::CompositeIndex;
+use super::datetime_index::DatetimeIndex;
 use crate::index::field_index::full_text_index::text_index::FullTextIndex;
 use crate::index::field_index::geo_index::GeoMapIndex;
 use crate::index::field_index::map_index::MapIndex;
+use crate::index::field_index::null_index::NullIndex;
 use crate::index::field_index::numeric_index::NumericIndex;
 use crate::index::field_index::FieldIndex;
 use crate::types::{
@@ -14,10 +18,31 @@ use crate::types::{
 };
 
 /// Selects index types based on field type
+///
+/// Every payload field also gets a [`NullIndex`] for `is_empty` and `is_null` conditions.
 pub fn index_selector(
     field: &str,
     payload_schema: &PayloadFieldSchema,
     db: Arc<RwLock<DB>>,
+    path: &Path,
+    is_appendable: bool,
+) -> Vec<FieldIndex> {
+    let mut indexes = typed_index_selector(field, payload_schema, db.clone(), path, is_appendable);
+    // Composite index is named after a tuple of fields, not a payload key
+    let is_payload_field = indexes
+        .iter()
+        .all(|index| !matches!(index, FieldIndex::CompositeIndex(_)));
+    if !indexes.is_empty() && is_payload_field {
+        indexes.push(FieldIndex::NullIndex(NullIndex::new(db, field)));
+    }
+    indexes
+}
+
+fn typed_index_selector(
+    field: &str,
+    payload_schema: &PayloadFieldSchema,
+    db: Arc<RwLock<DB>>,
+    path: &Path,
     is_appendable: bool,
 ) -> Vec<FieldIndex> {
     match payload_schema {
@@ -49,11 +74,77 @@ pub fn index_selector(
                 field,
             ))],
             PayloadSchemaType::Bool => vec![FieldIndex::BinaryIndex(BinaryIndex::new(db, field))],
+            PayloadSchemaType::Datetime => vec![FieldIndex::DatetimeIndex(DatetimeIndex::new(
+                db,
+                field,
+                is_appendable,
+            ))],
+            PayloadSchemaType::Uuid => vec![FieldIndex::UuidMapIndex(MapIndex::new(
+                db,
+                field,
+                is_appendable,
+            ))],
+            // Composite index can't be built without the list of fields
+            PayloadSchemaType::Composite => vec![],
         },
-        PayloadFieldSchema::FieldParams(payload_params) => match payload_params {
-            PayloadSchemaParams::Text(text_index_params) => vec![FieldIndex::FullTextIndex(
-                FullTextIndex::new(db, text_index_params.clone(), field),
-            )],
-        },
+        PayloadFieldSchema::FieldParams(payload_params) => {
+            let on_disk = payload_params.is_on_disk();
+            match payload_params {
+                // Tenant layout is applied to the whole segment by `SegmentBuilder`
+                PayloadSchemaParams::Keyword(_) => vec![FieldIndex::KeywordIndex(if on_disk {
+                    MapIndex::new_on_disk(db, field, path, is_appendable)
+                } else {
+                    MapIndex::new(db, field, is_appendable)
+                })],
+                PayloadSchemaParams::Integer(_) => {
+                    let (map_index, numeric_index) = if on_disk {
+                        (
+                            MapIndex::new_on_disk(db.clone(), field, path, is_appendable),
+                            NumericIndex::new_on_disk(db, field, path, is_appendable),
+                        )
+                    } else {
+                        (
+                            MapIndex::new(db.clone(), field, is_appendable),
+                            NumericIndex::new(db, field, is_appendable),
+                        )
+                    };
+                    vec![
+                        FieldIndex::IntMapIndex(map_index),
+                        FieldIndex::IntIndex(numeric_index),
+                    ]
+                }
+                PayloadSchemaParams::Float(_) => vec![FieldIndex::FloatIndex(if on_disk {
+                    NumericIndex::<FloatPayloadType>::new_on_disk(db, field, path, is_appendable)
+                } else {
+                    NumericIndex::<FloatPayloadType>::new(db, field, is_appendable)
+                })],
+                PayloadSchemaParams::Geo(_) => vec![FieldIndex::GeoIndex(if on_disk {
+                    GeoMapIndex::new_on_disk(db, field, path, is_appendable)
+                } else {
+                    GeoMapIndex::new(db, field)
+                })],
+                PayloadSchemaParams::Text(text_index_params) => {
+                    vec![FieldIndex::FullTextIndex(if on_disk {
+                        FullTextIndex::new_on_disk(
+                            db,
+                            text_index_params.clone(),
+                            field,
+                            path,
+                            is_appendable,
+                        )
+                    } else {
+                        FullTextIndex::new(db, text_index_params.clone(), field)
+                    })]
+                }
+                PayloadSchemaParams::Composite(composite_index_params) => {
+                    vec![FieldIndex::CompositeIndex(CompositeIndex::new(
+                        db,
+                        field,
+                        composite_index_params,
+                        is_appendable,
+                    ))]
+                }
+            }
+        }
     }
 }
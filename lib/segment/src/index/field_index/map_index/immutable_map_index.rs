@@ -3,6 +3,7 @@ use std::fmt::Display;
 use std::hash::Hash;
 use std::iter;
 use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -14,20 +15,23 @@ use super::mutable_map_index::MutableMapIndex;
 use super::MapIndex;
 use crate::common::operation_error::OperationResult;
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::index::field_index::flat_container::FlatContainer;
 
 pub struct ImmutableMapIndex<N: Hash + Eq + Clone + Display + FromStr> {
     value_to_points: HashMap<N, Range<u32>>,
-    value_to_points_container: Vec<PointOffsetType>,
+    value_to_points_container: FlatContainer<PointOffsetType>,
     point_to_values: Vec<Range<u32>>,
     point_to_values_container: Vec<N>,
     /// Amount of point which have at least one indexed payload value
     indexed_points: usize,
     values_count: usize,
     db_wrapper: DatabaseColumnWrapper,
+    /// File to keep `value_to_points_container` in, if the index is stored on disk
+    postings_path: Option<PathBuf>,
 }
 
 impl<N: Hash + Eq + Clone + Display + FromStr + Default> ImmutableMapIndex<N> {
-    pub fn new(db: Arc<RwLock<DB>>, field_name: &str) -> Self {
+    pub fn new(db: Arc<RwLock<DB>>, field_name: &str, postings_path: Option<PathBuf>) -> Self {
         let store_cf_name = MapIndex::<N>::storage_cf_name(field_name);
         let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
         Self {
@@ -38,9 +42,18 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> ImmutableMapIndex<N> {
             indexed_points: 0,
             values_count: 0,
             db_wrapper,
+            postings_path,
         }
     }
 
+    pub fn postings_path(&self) -> Option<&Path> {
+        self.postings_path.as_deref()
+    }
+
+    pub fn is_on_disk(&self) -> bool {
+        self.value_to_points_container.is_on_disk()
+    }
+
     /// Return mutable slice of a container which holds point_ids for given value.
     fn get_mut_point_ids_slice(&mut self, value: &N) -> Option<&mut [PointOffsetType]> {
         match self.value_to_points.get(value) {
@@ -168,18 +181,21 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> ImmutableMapIndex<N> {
         self.indexed_points = indexed_points;
         self.values_count = values_count;
         self.value_to_points.clear();
-        self.value_to_points_container.clear();
+        self.value_to_points_container = Default::default();
         self.point_to_values.clear();
         self.point_to_values_container.clear();
 
         // flatten values-to-points map
+        let mut value_to_points_container = Vec::with_capacity(values_count);
         for (value, points) in map {
             let points = points.into_iter().collect::<Vec<_>>();
-            let container_len = self.value_to_points_container.len() as u32;
+            let container_len = value_to_points_container.len() as u32;
             let range = container_len..container_len + points.len() as u32;
             self.value_to_points.insert(value, range.clone());
-            self.value_to_points_container.extend(points);
+            value_to_points_container.extend(points);
         }
+        self.value_to_points_container =
+            FlatContainer::new(value_to_points_container, self.postings_path.as_deref())?;
 
         // flatten points-to-values map
         for values in point_to_values {
@@ -1,8 +1,11 @@
 pub mod immutable_map_index;
 pub mod mutable_map_index;
 
+use std::collections::HashMap;
 use std::fmt::Display;
 use std::hash::Hash;
+use std::mem::size_of;
+use std::path::Path;
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -14,11 +17,14 @@ use parking_lot::RwLock;
 use rocksdb::DB;
 use serde_json::Value;
 use smol_str::SmolStr;
+use uuid::Uuid;
 
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
+use crate::index::field_index::flat_container::{flat_container_path, remove_flat_container};
 use crate::index::field_index::stat_tools::number_of_selected_points;
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::index::field_index::{
     CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
 };
@@ -26,7 +32,7 @@ use crate::index::query_estimator::combine_should_estimations;
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     AnyVariants, FieldCondition, IntPayloadType, Match, MatchAny, MatchExcept, MatchValue,
-    PayloadKeyType, ValueVariants,
+    PayloadKeyType, UuidIntType, ValueVariants,
 };
 use crate::vector_storage::div_ceil;
 
@@ -40,7 +46,23 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> MapIndex<N> {
         if is_appendable {
             MapIndex::Mutable(MutableMapIndex::new(db, field_name))
         } else {
-            MapIndex::Immutable(ImmutableMapIndex::new(db, field_name))
+            MapIndex::Immutable(ImmutableMapIndex::new(db, field_name, None))
+        }
+    }
+
+    /// Same as [`MapIndex::new`], but the immutable index keeps its postings in a memory mapped
+    /// file in `dir` instead of RAM
+    pub fn new_on_disk(
+        db: Arc<RwLock<DB>>,
+        field_name: &str,
+        dir: &Path,
+        is_appendable: bool,
+    ) -> Self {
+        if is_appendable {
+            MapIndex::Mutable(MutableMapIndex::new(db, field_name))
+        } else {
+            let postings_path = flat_container_path(dir, &Self::storage_cf_name(field_name));
+            MapIndex::Immutable(ImmutableMapIndex::new(db, field_name, Some(postings_path)))
         }
     }
 
@@ -98,7 +120,7 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> MapIndex<N> {
         }
     }
 
-    fn get_iterator<Q>(&self, value: &Q) -> Box<dyn Iterator<Item = PointOffsetType> + '_>
+    pub fn get_iterator<Q>(&self, value: &Q) -> Box<dyn Iterator<Item = PointOffsetType> + '_>
     where
         Q: ?Sized,
         N: std::borrow::Borrow<Q>,
@@ -125,11 +147,18 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> MapIndex<N> {
         self.get_db_wrapper().recreate_column_family()
     }
 
+    fn wipe(self) -> OperationResult<()> {
+        if let MapIndex::Immutable(index) = &self {
+            remove_flat_container(index.postings_path())?;
+        }
+        self.get_db_wrapper().recreate_column_family()
+    }
+
     fn flusher(&self) -> Flusher {
         self.get_db_wrapper().flusher()
     }
 
-    fn match_cardinality<Q>(&self, value: &Q) -> CardinalityEstimation
+    pub fn match_cardinality<Q>(&self, value: &Q) -> CardinalityEstimation
     where
         Q: ?Sized,
         N: std::borrow::Borrow<Q>,
@@ -149,6 +178,29 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> MapIndex<N> {
         }
     }
 
+    pub fn get_stats(&self) -> FieldIndexStats {
+        let postings: HashMap<_, _> = self
+            .get_values_iterator()
+            .filter_map(|value| {
+                let points_count = self.get_points_with_value_count(value)?;
+                (points_count > 0).then(|| (value.to_string(), points_count))
+            })
+            .collect();
+        let postings_bytes = self.get_values_count() * size_of::<PointOffsetType>();
+        // Values are stored for each point and once more as keys of the postings
+        let values_bytes =
+            (self.get_values_count() + self.get_unique_values_count()) * size_of::<N>();
+        let is_on_disk = matches!(self, MapIndex::Immutable(index) if index.is_on_disk());
+        FieldIndexStats {
+            points_count: self.get_indexed_points(),
+            values_count: self.get_values_count(),
+            postings: Some(postings),
+            histogram: None,
+            ram_usage_bytes: values_bytes + if is_on_disk { 0 } else { postings_bytes },
+            disk_usage_bytes: if is_on_disk { postings_bytes } else { 0 },
+        }
+    }
+
     pub fn encode_db_record(value: &N, idx: PointOffsetType) -> String {
         format!("{value}/{idx}")
     }
@@ -292,6 +344,28 @@ impl<N: Hash + Eq + Clone + Display + FromStr + Default> MapIndex<N> {
         }
     }
 
+    /// Estimates cardinality of a condition, which is satisfied by any value accepted by `predicate`
+    fn predicate_cardinality(&self, predicate: impl Fn(&N) -> bool) -> CardinalityEstimation {
+        let estimations = self
+            .get_values_iterator()
+            .filter(|value| predicate(value))
+            .map(|value| self.match_cardinality(value))
+            .collect_vec();
+        combine_should_estimations(&estimations, self.get_indexed_points())
+    }
+
+    fn predicate_iterator<'a>(
+        &'a self,
+        predicate: impl Fn(&N) -> bool + 'a,
+    ) -> Box<dyn Iterator<Item = PointOffsetType> + 'a> {
+        Box::new(
+            self.get_values_iterator()
+                .filter(move |value| predicate(value))
+                .flat_map(|value| self.get_iterator(value))
+                .unique(),
+        )
+    }
+
     fn except_iterator<'a, Q>(
         &'a self,
         excluded: &'a [Q],
@@ -318,7 +392,7 @@ impl PayloadFieldIndex for MapIndex<SmolStr> {
     }
 
     fn clear(self) -> OperationResult<()> {
-        self.get_db_wrapper().recreate_column_family()
+        self.wipe()
     }
 
     fn flusher(&self) -> Flusher {
@@ -344,6 +418,12 @@ impl PayloadFieldIndex for MapIndex<SmolStr> {
             Some(Match::Except(MatchExcept {
                 except: AnyVariants::Keywords(keywords),
             })) => Ok(self.except_iterator(keywords)),
+            Some(Match::Wildcard(wildcard)) => {
+                Ok(self.predicate_iterator(move |keyword| wildcard.is_match(keyword)))
+            }
+            Some(Match::Regex(regex)) => {
+                Ok(self.predicate_iterator(move |keyword| regex.is_match(keyword)))
+            }
             _ => Err(OperationError::service_error("failed to filter")),
         }
     }
@@ -384,6 +464,12 @@ impl PayloadFieldIndex for MapIndex<SmolStr> {
             Some(Match::Except(MatchExcept {
                 except: AnyVariants::Keywords(keywords),
             })) => Ok(self.except_cardinality::<str, &str>(keywords.iter().map(|k| k.as_str()))),
+            Some(Match::Wildcard(wildcard)) => Ok(self
+                .predicate_cardinality(|keyword| wildcard.is_match(keyword))
+                .with_primary_clause(PrimaryCondition::Condition(condition.clone()))),
+            Some(Match::Regex(regex)) => Ok(self
+                .predicate_cardinality(|keyword| regex.is_match(keyword))
+                .with_primary_clause(PrimaryCondition::Condition(condition.clone()))),
             _ => Err(OperationError::service_error(
                 "failed to estimate cardinality",
             )),
@@ -417,7 +503,7 @@ impl PayloadFieldIndex for MapIndex<IntPayloadType> {
     }
 
     fn clear(self) -> OperationResult<()> {
-        self.get_db_wrapper().recreate_column_family()
+        self.wipe()
     }
 
     fn flusher(&self) -> Flusher {
@@ -509,6 +595,133 @@ impl PayloadFieldIndex for MapIndex<IntPayloadType> {
     }
 }
 
+/// UUIDs are matched by value, so any textual form of the same UUID is accepted
+pub fn parse_uuid(keyword: &str) -> Option<UuidIntType> {
+    Uuid::parse_str(keyword).ok().map(|uuid| uuid.as_u128())
+}
+
+impl PayloadFieldIndex for MapIndex<UuidIntType> {
+    fn count_indexed_points(&self) -> usize {
+        self.get_indexed_points()
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        self.load_from_db()
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.wipe()
+    }
+
+    fn flusher(&self) -> Flusher {
+        MapIndex::flusher(self)
+    }
+
+    fn filter<'a>(
+        &'a self,
+        condition: &'a FieldCondition,
+    ) -> OperationResult<Box<dyn Iterator<Item = PointOffsetType> + 'a>> {
+        match &condition.r#match {
+            Some(Match::Value(MatchValue {
+                value: ValueVariants::Keyword(keyword),
+            })) => Ok(match parse_uuid(keyword) {
+                Some(uuid) => self.get_iterator(&uuid),
+                None => Box::new(std::iter::empty()),
+            }),
+            Some(Match::Any(MatchAny {
+                any: AnyVariants::Keywords(keywords),
+            })) => {
+                let uuids: Vec<_> = keywords.iter().filter_map(|k| parse_uuid(k)).collect();
+                Ok(Box::new(
+                    uuids
+                        .into_iter()
+                        .flat_map(|uuid| self.get_iterator(&uuid))
+                        .unique(),
+                ))
+            }
+            Some(Match::Except(MatchExcept {
+                except: AnyVariants::Keywords(keywords),
+            })) => {
+                let excluded: Vec<_> = keywords.iter().filter_map(|k| parse_uuid(k)).collect();
+                Ok(Box::new(
+                    self.get_values_iterator()
+                        .filter(move |uuid| !excluded.contains(uuid))
+                        .flat_map(|uuid| self.get_iterator(uuid))
+                        .unique(),
+                ))
+            }
+            _ => Err(OperationError::service_error("failed to filter")),
+        }
+    }
+
+    fn estimate_cardinality(
+        &self,
+        condition: &FieldCondition,
+    ) -> OperationResult<CardinalityEstimation> {
+        match &condition.r#match {
+            Some(Match::Value(MatchValue {
+                value: ValueVariants::Keyword(keyword),
+            })) => {
+                let mut estimation = match parse_uuid(keyword) {
+                    Some(uuid) => self.match_cardinality(&uuid),
+                    None => CardinalityEstimation::exact(0),
+                };
+                estimation
+                    .primary_clauses
+                    .push(PrimaryCondition::Condition(condition.clone()));
+                Ok(estimation)
+            }
+            Some(Match::Any(MatchAny {
+                any: AnyVariants::Keywords(keywords),
+            })) => {
+                let estimations = keywords
+                    .iter()
+                    .filter_map(|keyword| Some((keyword, parse_uuid(keyword)?)))
+                    .map(|(keyword, uuid)| {
+                        let uuid_condition = FieldCondition::new_match(
+                            condition.key.clone(),
+                            keyword.to_owned().into(),
+                        );
+                        self.match_cardinality(&uuid)
+                            .with_primary_clause(PrimaryCondition::Condition(uuid_condition))
+                    })
+                    .collect::<Vec<_>>();
+                Ok(combine_should_estimations(
+                    &estimations,
+                    self.get_indexed_points(),
+                ))
+            }
+            Some(Match::Except(MatchExcept {
+                except: AnyVariants::Keywords(keywords),
+            })) => Ok(self.except_cardinality::<UuidIntType, UuidIntType>(
+                keywords.iter().filter_map(|k| parse_uuid(k)),
+            )),
+            _ => Err(OperationError::service_error(
+                "failed to estimate cardinality",
+            )),
+        }
+    }
+
+    fn payload_blocks(
+        &self,
+        threshold: usize,
+        key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        Box::new(
+            self.get_values_iterator()
+                .map(|value| (value, self.get_points_with_value_count(value).unwrap_or(0)))
+                .filter(move |(_value, count)| *count >= threshold)
+                .map(move |(value, count)| PayloadBlockCondition {
+                    condition: FieldCondition::new_match(
+                        key.clone(),
+                        Uuid::from_u128(*value).to_string().into(),
+                    ),
+                    cardinality: count,
+                }),
+        )
+    }
+}
+
 impl ValueIndexer<String> for MapIndex<SmolStr> {
     fn add_many(&mut self, id: PointOffsetType, values: Vec<String>) -> OperationResult<()> {
         match self {
@@ -563,6 +776,31 @@ impl ValueIndexer<IntPayloadType> for MapIndex<IntPayloadType> {
     }
 }
 
+impl ValueIndexer<UuidIntType> for MapIndex<UuidIntType> {
+    fn add_many(&mut self, id: PointOffsetType, values: Vec<UuidIntType>) -> OperationResult<()> {
+        match self {
+            MapIndex::Mutable(index) => index.add_many_to_map(id, values),
+            MapIndex::Immutable(_) => Err(OperationError::service_error(
+                "Can't add values to immutable map index",
+            )),
+        }
+    }
+
+    fn get_value(&self, value: &Value) -> Option<UuidIntType> {
+        if let Value::String(keyword) = value {
+            return parse_uuid(keyword);
+        }
+        None
+    }
+
+    fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        match self {
+            MapIndex::Mutable(index) => index.remove_point(id),
+            MapIndex::Immutable(index) => index.remove_point(id),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -574,6 +812,7 @@ mod tests {
 
     use super::*;
     use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+    use crate::types::{MatchRegex, MatchWildcard};
 
     const FIELD_NAME: &str = "test";
 
@@ -660,4 +899,125 @@ mod tests {
         save_map_index(&data, temp_dir.path());
         load_map_index(&data, temp_dir.path());
     }
+
+    #[test]
+    fn test_on_disk_map_index() {
+        let data = vec![
+            vec![String::from("AABB"), String::from("UUFF")],
+            vec![String::from("IIBB")],
+            vec![String::from("AABB"), String::from("IIBB")],
+            vec![String::from("AABB")],
+        ];
+
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        save_map_index(&data, temp_dir.path());
+
+        let mut index = MapIndex::<SmolStr>::new_on_disk(
+            open_db_with_existing_cf(temp_dir.path()).unwrap(),
+            FIELD_NAME,
+            temp_dir.path(),
+            false,
+        );
+        index.load_from_db().unwrap();
+        let postings_path = flat_container_path(
+            temp_dir.path(),
+            &MapIndex::<SmolStr>::storage_cf_name(FIELD_NAME),
+        );
+        assert!(postings_path.exists());
+
+        let condition = FieldCondition::new_match(FIELD_NAME, "AABB".to_string().into());
+        let points: HashSet<_> = index.filter(&condition).unwrap().collect();
+        assert_eq!(points, HashSet::from([0, 2, 3]));
+
+        index.remove_point(2).unwrap();
+        let points: HashSet<_> = index.filter(&condition).unwrap().collect();
+        assert_eq!(points, HashSet::from([0, 3]));
+
+        index.clear().unwrap();
+        assert!(!postings_path.exists());
+    }
+
+    #[test]
+    fn test_pattern_map_index() {
+        let data = vec![
+            vec![String::from("SKU-100"), String::from("docs/a.pdf")],
+            vec![String::from("SKU-2")],
+            vec![String::from("ITEM-100")],
+            vec![String::from("docs/b.txt")],
+        ];
+
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        save_map_index(&data, temp_dir.path());
+
+        let mut index = MapIndex::<SmolStr>::new(
+            open_db_with_existing_cf(temp_dir.path()).unwrap(),
+            FIELD_NAME,
+            true,
+        );
+        index.load_from_db().unwrap();
+
+        let check = |r#match: Match, expected: &[PointOffsetType]| {
+            let condition = FieldCondition::new_match(FIELD_NAME, r#match);
+            let points: HashSet<_> = index.filter(&condition).unwrap().collect();
+            assert_eq!(points, expected.iter().copied().collect());
+
+            let estimation = index.estimate_cardinality(&condition).unwrap();
+            assert_eq!(estimation.primary_clauses.len(), 1);
+            assert!(estimation.min <= expected.len() && expected.len() <= estimation.max);
+        };
+
+        let wildcard = |pattern: &str| Match::Wildcard(MatchWildcard::new(pattern.into()).unwrap());
+        check(wildcard("SKU-*"), &[0, 1]);
+        check(wildcard("*.pdf"), &[0]);
+        check(wildcard("SKU-?"), &[1]);
+        check(wildcard("*-100"), &[0, 2]);
+        check(wildcard("SKU"), &[]);
+
+        let regex = |pattern: &str| Match::Regex(MatchRegex::new(pattern.into()).unwrap());
+        check(regex("[A-Z]+-[0-9]{3}"), &[0, 2]);
+        check(regex("docs/.*"), &[0, 3]);
+        // Regular expression should match the whole keyword
+        check(regex("SKU"), &[]);
+    }
+
+    #[test]
+    fn test_uuid_map_index() {
+        let uuids = [
+            "b6a1e07d-2ff4-4e0c-8b3e-0d0c3c7e8a61",
+            "9c3f47b2-1a8e-4d55-9f02-7e6b1c2d3e4f",
+            "00000000-0000-0000-0000-000000000000",
+        ];
+        let data: Vec<Vec<UuidIntType>> = vec![
+            vec![parse_uuid(uuids[0]).unwrap(), parse_uuid(uuids[1]).unwrap()],
+            vec![parse_uuid(uuids[1]).unwrap()],
+            vec![parse_uuid(uuids[2]).unwrap()],
+        ];
+
+        let temp_dir = Builder::new().prefix("store_dir").tempdir().unwrap();
+        save_map_index(&data, temp_dir.path());
+        load_map_index(&data, temp_dir.path());
+
+        let mut index = MapIndex::<UuidIntType>::new(
+            open_db_with_existing_cf(temp_dir.path()).unwrap(),
+            FIELD_NAME,
+            true,
+        );
+        index.load_from_db().unwrap();
+
+        // Uppercase and simple (no hyphens) forms address the same value
+        let condition =
+            FieldCondition::new_match(FIELD_NAME, uuids[1].to_uppercase().replace('-', "").into());
+        let points: HashSet<_> = index.filter(&condition).unwrap().collect();
+        assert_eq!(points, HashSet::from([0, 1]));
+
+        let condition = FieldCondition::new_match(FIELD_NAME, "not-a-uuid".to_string().into());
+        assert_eq!(index.filter(&condition).unwrap().count(), 0);
+
+        let blocks: Vec<_> = index.payload_blocks(2, FIELD_NAME.to_string()).collect();
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(
+            blocks[0].condition,
+            FieldCondition::new_match(FIELD_NAME, uuids[1].to_string().into())
+        );
+    }
 }
@@ -2,17 +2,23 @@ use std::collections::HashSet;
 
 use common::types::PointOffsetType;
 
+use crate::index::field_index::composite_index::CompositeLookup;
 use crate::types::{FieldCondition, IsEmptyCondition, IsNullCondition};
 
+pub mod composite_index;
+pub mod datetime_index;
 mod field_index_base;
+pub mod flat_container;
 pub mod full_text_index;
 pub mod geo_hash;
 pub mod geo_index;
 mod histogram;
 pub mod index_selector;
 pub mod map_index;
+pub mod null_index;
 pub mod numeric_index;
 mod stat_tools;
+pub mod stats;
 
 pub mod binary_index;
 #[cfg(test)]
@@ -28,6 +34,7 @@ pub enum PrimaryCondition {
     IsEmpty(IsEmptyCondition),
     IsNull(IsNullCondition),
     Ids(HashSet<PointOffsetType>),
+    Composite(CompositeLookup),
 }
 
 #[derive(Debug, Clone)]
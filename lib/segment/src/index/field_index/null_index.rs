@@ -0,0 +1,269 @@
+use std::sync::Arc;
+
+use bitvec::vec::BitVec;
+use common::types::PointOffsetType;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde_json::Value;
+
+use super::{CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex};
+use crate::common::operation_error::{OperationError, OperationResult};
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::utils::MultiValue;
+use crate::common::Flusher;
+use crate::index::field_index::stats::FieldIndexStats;
+use crate::telemetry::PayloadIndexTelemetry;
+use crate::types::{FieldCondition, PayloadKeyType};
+
+/// Index of points, which carry a non-empty value of the field, and of points with explicit `null`.
+///
+/// It is built along with the other indexes of a field, so `is_empty` and `is_null` conditions
+/// don't need to read payload of the points.
+/// Points, which don't have the field at all, are not stored.
+pub struct NullIndex {
+    has_values: BitVec,
+    is_null: BitVec,
+    has_values_count: usize,
+    is_null_count: usize,
+    db_wrapper: DatabaseColumnWrapper,
+}
+
+impl NullIndex {
+    const HAS_VALUES: u8 = 0b0000_0001;
+    const IS_NULL: u8 = 0b0000_0010;
+
+    pub fn new(db: Arc<RwLock<DB>>, field_name: &str) -> Self {
+        let store_cf_name = Self::storage_cf_name(field_name);
+        let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
+        Self {
+            has_values: BitVec::new(),
+            is_null: BitVec::new(),
+            has_values_count: 0,
+            is_null_count: 0,
+            db_wrapper,
+        }
+    }
+
+    fn storage_cf_name(field: &str) -> String {
+        format!("{field}_null")
+    }
+
+    pub fn recreate(&self) -> OperationResult<()> {
+        self.db_wrapper.recreate_column_family()
+    }
+
+    fn set(&mut self, id: PointOffsetType, has_values: bool, is_null: bool) {
+        let idx = id as usize;
+        if idx >= self.has_values.len() {
+            self.has_values.resize(idx + 1, false);
+            self.is_null.resize(idx + 1, false);
+        }
+
+        match (self.has_values.replace(idx, has_values), has_values) {
+            (false, true) => self.has_values_count += 1,
+            (true, false) => self.has_values_count -= 1,
+            _ => {}
+        }
+        match (self.is_null.replace(idx, is_null), is_null) {
+            (false, true) => self.is_null_count += 1,
+            (true, false) => self.is_null_count -= 1,
+            _ => {}
+        }
+    }
+
+    /// Add point with the value of the field, same as `ValueIndexer::add_point`
+    pub fn add_point(
+        &mut self,
+        id: PointOffsetType,
+        payload: &MultiValue<&Value>,
+    ) -> OperationResult<()> {
+        if let MultiValue::Single(None) = payload {
+            return Ok(());
+        }
+
+        let has_values = !payload.check_is_empty();
+        let is_null = payload.check_is_null();
+        if !has_values && !is_null {
+            return self.remove_point(id);
+        }
+
+        let mut flags = 0;
+        if has_values {
+            flags |= Self::HAS_VALUES;
+        }
+        if is_null {
+            flags |= Self::IS_NULL;
+        }
+        self.set(id, has_values, is_null);
+        self.db_wrapper.put(id.to_be_bytes(), [flags])?;
+        Ok(())
+    }
+
+    pub fn remove_point(&mut self, id: PointOffsetType) -> OperationResult<()> {
+        if (id as usize) < self.has_values.len() {
+            self.set(id, false, false);
+        }
+        self.db_wrapper.remove(id.to_be_bytes())?;
+        Ok(())
+    }
+
+    /// Number of points, which have at least one non-null value of the field
+    pub fn non_empty_count(&self) -> usize {
+        self.has_values_count
+    }
+
+    /// Number of points, which have `null` value of the field
+    pub fn null_count(&self) -> usize {
+        self.is_null_count
+    }
+
+    pub fn iter_null(&self) -> impl Iterator<Item = PointOffsetType> + '_ {
+        self.is_null.iter_ones().map(|idx| idx as PointOffsetType)
+    }
+
+    pub fn values_is_null(&self, point_id: PointOffsetType) -> bool {
+        self.is_null
+            .get(point_id as usize)
+            .map_or(false, |is_null| *is_null)
+    }
+
+    pub fn values_is_empty(&self, point_id: PointOffsetType) -> bool {
+        !self
+            .has_values
+            .get(point_id as usize)
+            .map_or(false, |has_values| *has_values)
+    }
+
+    pub fn values_count(&self, point_id: PointOffsetType) -> usize {
+        usize::from(!self.values_is_empty(point_id))
+    }
+
+    pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
+        PayloadIndexTelemetry {
+            field_name: None,
+            points_count: self.has_values_count,
+            points_values_count: self.has_values_count + self.is_null_count,
+            histogram_bucket_size: None,
+        }
+    }
+
+    /// Only memory is reported, the values are counted by the typed indexes of the field
+    pub fn get_stats(&self) -> FieldIndexStats {
+        FieldIndexStats {
+            ram_usage_bytes: (self.has_values.capacity() + self.is_null.capacity())
+                / u8::BITS as usize,
+            ..Default::default()
+        }
+    }
+}
+
+impl PayloadFieldIndex for NullIndex {
+    fn count_indexed_points(&self) -> usize {
+        self.has_values_count
+    }
+
+    fn load(&mut self) -> OperationResult<bool> {
+        if !self.db_wrapper.has_column_family()? {
+            return Ok(false);
+        }
+
+        let mut points = Vec::new();
+        for (key, value) in self.db_wrapper.lock_db().iter()? {
+            let idx = PointOffsetType::from_be_bytes(key.as_ref().try_into().unwrap());
+            debug_assert_eq!(value.len(), 1);
+            points.push((idx, value[0]));
+        }
+        for (idx, flags) in points {
+            self.set(
+                idx,
+                flags & Self::HAS_VALUES != 0,
+                flags & Self::IS_NULL != 0,
+            );
+        }
+        Ok(true)
+    }
+
+    fn clear(self) -> OperationResult<()> {
+        self.db_wrapper.remove_column_family()
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.db_wrapper.flusher()
+    }
+
+    fn filter<'a>(
+        &'a self,
+        _condition: &'a FieldCondition,
+    ) -> OperationResult<Box<dyn Iterator<Item = PointOffsetType> + 'a>> {
+        Err(OperationError::service_error("failed to filter"))
+    }
+
+    fn estimate_cardinality(
+        &self,
+        _condition: &FieldCondition,
+    ) -> OperationResult<CardinalityEstimation> {
+        Err(OperationError::service_error(
+            "failed to estimate cardinality",
+        ))
+    }
+
+    fn payload_blocks(
+        &self,
+        _threshold: usize,
+        _key: PayloadKeyType,
+    ) -> Box<dyn Iterator<Item = PayloadBlockCondition> + '_> {
+        Box::new(std::iter::empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use tempfile::Builder;
+
+    use super::*;
+    use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
+
+    const FIELD_NAME: &str = "test";
+
+    #[test]
+    fn test_null_index() {
+        let tmp_dir = Builder::new().prefix("test_db").tempdir().unwrap();
+        let db = open_db_with_existing_cf(tmp_dir.path()).unwrap();
+        let mut index = NullIndex::new(db.clone(), FIELD_NAME);
+        index.recreate().unwrap();
+
+        let values = [json!("a"), Value::Null, json!([]), json!([null, 1])];
+        for (id, value) in values.iter().enumerate() {
+            index
+                .add_point(id as PointOffsetType, &MultiValue::one(value))
+                .unwrap();
+        }
+        // Values of a nested array field, one of the objects has `null`
+        let nested = [json!(1), Value::Null];
+        index
+            .add_point(4, &MultiValue::Multiple(nested.iter().collect()))
+            .unwrap();
+        // Point 5 doesn't have the field
+        index.add_point(5, &MultiValue::Single(None)).unwrap();
+
+        let check = |index: &NullIndex| {
+            let empty: Vec<_> = (0..6).filter(|&id| index.values_is_empty(id)).collect();
+            assert_eq!(empty, [1, 2, 5]);
+            assert_eq!(index.iter_null().collect::<Vec<_>>(), [1, 4]);
+            assert_eq!(index.non_empty_count(), 3);
+            assert_eq!(index.null_count(), 2);
+        };
+        check(&index);
+
+        index.flusher()().unwrap();
+        let mut loaded = NullIndex::new(db, FIELD_NAME);
+        assert!(loaded.load().unwrap());
+        check(&loaded);
+
+        // Update to an empty array removes the point from the index
+        loaded.add_point(0, &MultiValue::one(&json!([]))).unwrap();
+        assert!(loaded.values_is_empty(0));
+        assert_eq!(loaded.non_empty_count(), 2);
+    }
+}
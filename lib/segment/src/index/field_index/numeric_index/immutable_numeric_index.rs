@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 use std::ops::Bound::{Excluded, Unbounded};
 use std::ops::{Bound, Range};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use common::types::PointOffsetType;
@@ -11,6 +12,7 @@ use super::mutable_numeric_index::MutableNumericIndex;
 use super::{Encodable, NumericIndex, HISTOGRAM_MAX_BUCKET_SIZE, HISTOGRAM_PRECISION};
 use crate::common::operation_error::OperationResult;
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::index::field_index::flat_container::FlatContainer;
 use crate::index::field_index::histogram::{Histogram, Numericable, Point};
 
 pub struct ImmutableNumericIndex<T: Encodable + Numericable> {
@@ -21,9 +23,11 @@ pub struct ImmutableNumericIndex<T: Encodable + Numericable> {
     pub(super) max_values_per_point: usize,
     point_to_values: Vec<Range<u32>>,
     point_to_values_container: Vec<T>,
+    /// File to keep the sorted keys in, if the index is stored on disk
+    keys_path: Option<PathBuf>,
 }
 
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, Copy, PartialEq, Debug)]
 pub(super) struct NumericIndexKey<T> {
     key: T,
     idx: PointOffsetType,
@@ -31,7 +35,7 @@ pub(super) struct NumericIndexKey<T> {
 }
 
 struct NumericKeySortedVec<T: Encodable + Numericable> {
-    data: Vec<NumericIndexKey<T>>,
+    data: FlatContainer<NumericIndexKey<T>>,
     deleted_count: usize,
 }
 
@@ -103,15 +107,28 @@ impl<T: PartialEq + PartialOrd + Encodable> Eq for NumericIndexKey<T> {}
 impl<T: Encodable + Numericable> NumericKeySortedVec<T> {
     fn from_btree_map(map: BTreeMap<Vec<u8>, u32>) -> Self {
         Self {
-            data: map
-                .keys()
-                .cloned()
-                .map(|b| NumericIndexKey::<T>::decode(&b))
-                .collect(),
+            data: FlatContainer::Ram(
+                map.keys()
+                    .cloned()
+                    .map(|b| NumericIndexKey::<T>::decode(&b))
+                    .collect(),
+            ),
             deleted_count: 0,
         }
     }
 
+    /// Move the keys into the file at `path`, if there is one
+    fn with_storage(self, path: Option<&Path>) -> OperationResult<Self> {
+        let data = match self.data {
+            FlatContainer::Ram(data) => FlatContainer::new(data, path)?,
+            data => data,
+        };
+        Ok(Self {
+            data,
+            deleted_count: self.deleted_count,
+        })
+    }
+
     fn len(&self) -> usize {
         self.data.len() - self.deleted_count
     }
@@ -194,7 +211,7 @@ impl<'a, T: Encodable + Numericable> DoubleEndedIterator for NumericKeySortedVec
 }
 
 impl<T: Encodable + Numericable> ImmutableNumericIndex<T> {
-    pub(super) fn new(db: Arc<RwLock<DB>>, field: &str) -> Self {
+    pub(super) fn new(db: Arc<RwLock<DB>>, field: &str, keys_path: Option<PathBuf>) -> Self {
         let store_cf_name = NumericIndex::<T>::storage_cf_name(field);
         let db_wrapper = DatabaseColumnWrapper::new(db, &store_cf_name);
         Self {
@@ -208,6 +225,7 @@ impl<T: Encodable + Numericable> ImmutableNumericIndex<T> {
             max_values_per_point: 1,
             point_to_values: Default::default(),
             point_to_values_container: Default::default(),
+            keys_path,
         }
     }
 
@@ -215,6 +233,14 @@ impl<T: Encodable + Numericable> ImmutableNumericIndex<T> {
         &self.db_wrapper
     }
 
+    pub(super) fn keys_path(&self) -> Option<&Path> {
+        self.keys_path.as_deref()
+    }
+
+    pub(super) fn is_on_disk(&self) -> bool {
+        self.map.data.is_on_disk()
+    }
+
     pub(super) fn get_values(&self, idx: PointOffsetType) -> Option<&[T]> {
         let range = self.point_to_values.get(idx as usize)?.clone();
         let range = range.start as usize..range.end as usize;
@@ -235,6 +261,16 @@ impl<T: Encodable + Numericable> ImmutableNumericIndex<T> {
             .map(|NumericIndexKey { idx, .. }| idx)
     }
 
+    pub(super) fn orderable_values_range(
+        &self,
+        start_bound: Bound<NumericIndexKey<T>>,
+        end_bound: Bound<NumericIndexKey<T>>,
+    ) -> impl DoubleEndedIterator<Item = (T, PointOffsetType)> + '_ {
+        self.map
+            .values_range(start_bound, end_bound)
+            .map(|NumericIndexKey { key, idx, .. }| (key, idx))
+    }
+
     pub(super) fn load(&mut self) -> OperationResult<bool> {
         let mut mutable = MutableNumericIndex::<T> {
             map: Default::default(),
@@ -254,7 +290,8 @@ impl<T: Encodable + Numericable> ImmutableNumericIndex<T> {
             ..
         } = mutable;
 
-        self.map = NumericKeySortedVec::from_btree_map(map);
+        self.map =
+            NumericKeySortedVec::from_btree_map(map).with_storage(self.keys_path.as_deref())?;
         self.histogram = histogram;
         self.points_count = points_count;
         self.max_values_per_point = max_values_per_point;
@@ -5,8 +5,10 @@ mod immutable_numeric_index;
 mod mutable_numeric_index;
 
 use std::cmp::{max, min};
+use std::mem::size_of;
 use std::ops::Bound;
 use std::ops::Bound::{Excluded, Included, Unbounded};
+use std::path::Path;
 use std::sync::Arc;
 
 use common::types::PointOffsetType;
@@ -20,8 +22,11 @@ use super::utils::check_boundaries;
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
 use crate::common::Flusher;
+use crate::data_types::order_by::OrderValue;
+use crate::index::field_index::flat_container::{flat_container_path, remove_flat_container};
 use crate::index::field_index::histogram::{Histogram, Numericable};
 use crate::index::field_index::stat_tools::estimate_multi_value_selection_cardinality;
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::index::field_index::{
     CardinalityEstimation, PayloadBlockCondition, PayloadFieldIndex, PrimaryCondition, ValueIndexer,
 };
@@ -35,7 +40,7 @@ use crate::types::{FieldCondition, FloatPayloadType, IntPayloadType, PayloadKeyT
 const HISTOGRAM_MAX_BUCKET_SIZE: usize = 10_000;
 const HISTOGRAM_PRECISION: f64 = 0.01;
 
-pub trait Encodable: Copy {
+pub trait Encodable: Copy + 'static {
     fn encode_key(&self, id: PointOffsetType) -> Vec<u8>;
 
     fn decode_key(key: &[u8]) -> (PointOffsetType, Self);
@@ -90,7 +95,18 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         if is_appendable {
             NumericIndex::Mutable(MutableNumericIndex::new(db, field))
         } else {
-            NumericIndex::Immutable(ImmutableNumericIndex::new(db, field))
+            NumericIndex::Immutable(ImmutableNumericIndex::new(db, field, None))
+        }
+    }
+
+    /// Same as [`NumericIndex::new`], but the immutable index keeps its sorted keys in a memory
+    /// mapped file in `dir` instead of RAM
+    pub fn new_on_disk(db: Arc<RwLock<DB>>, field: &str, dir: &Path, is_appendable: bool) -> Self {
+        if is_appendable {
+            NumericIndex::Mutable(MutableNumericIndex::new(db, field))
+        } else {
+            let keys_path = flat_container_path(dir, &Self::storage_cf_name(field));
+            NumericIndex::Immutable(ImmutableNumericIndex::new(db, field, Some(keys_path)))
         }
     }
 
@@ -130,6 +146,13 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         self.get_db_wrapper().recreate_column_family()
     }
 
+    fn wipe(self) -> OperationResult<()> {
+        if let NumericIndex::Immutable(index) = &self {
+            remove_flat_container(index.keys_path())?;
+        }
+        self.get_db_wrapper().recreate_column_family()
+    }
+
     pub fn load(&mut self) -> OperationResult<bool> {
         match self {
             NumericIndex::Mutable(index) => index.load(),
@@ -162,7 +185,7 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         }
     }
 
-    fn range_cardinality(&self, range: &Range) -> CardinalityEstimation {
+    pub fn range_cardinality(&self, range: &Range) -> CardinalityEstimation {
         let lbound = if let Some(lte) = range.lte {
             Included(T::from_f64(lte))
         } else if let Some(lt) = range.lt {
@@ -217,6 +240,87 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         }
     }
 
+    /// Bounds of the index keys with values in the given `range`, `None` if there are no such keys
+    fn key_bounds(range: &Range) -> Option<(Bound<NumericIndexKey<T>>, Bound<NumericIndexKey<T>>)> {
+        let start_bound = match range {
+            Range { gt: Some(gt), .. } => {
+                let v: T = T::from_f64(*gt);
+                Excluded(NumericIndexKey::new(v, PointOffsetType::MAX))
+            }
+            Range { gte: Some(gte), .. } => {
+                let v: T = T::from_f64(*gte);
+                Included(NumericIndexKey::new(v, PointOffsetType::MIN))
+            }
+            _ => Unbounded,
+        };
+
+        let end_bound = match range {
+            Range { lt: Some(lt), .. } => {
+                let v: T = T::from_f64(*lt);
+                Excluded(NumericIndexKey::new(v, PointOffsetType::MIN))
+            }
+            Range { lte: Some(lte), .. } => {
+                let v: T = T::from_f64(*lte);
+                Included(NumericIndexKey::new(v, PointOffsetType::MAX))
+            }
+            _ => Unbounded,
+        };
+
+        // map.range
+        // Panics if range start > end. Panics if range start == end and both bounds are Excluded.
+        check_boundaries(&start_bound, &end_bound).then_some((start_bound, end_bound))
+    }
+
+    fn encode_bound(bound: Bound<NumericIndexKey<T>>) -> Bound<Vec<u8>> {
+        match bound {
+            Included(k) => Included(k.encode()),
+            Excluded(k) => Excluded(k.encode()),
+            Unbounded => Unbounded,
+        }
+    }
+
+    /// Get iterator over points with at least one value in the given `range`
+    pub fn range_filter(&self, range: &Range) -> Box<dyn Iterator<Item = PointOffsetType> + '_> {
+        let Some((start_bound, end_bound)) = Self::key_bounds(range) else {
+            return Box::new(vec![].into_iter());
+        };
+
+        match self {
+            NumericIndex::Mutable(index) => Box::new(index.values_range(
+                Self::encode_bound(start_bound),
+                Self::encode_bound(end_bound),
+            )),
+            NumericIndex::Immutable(index) => Box::new(index.values_range(start_bound, end_bound)),
+        }
+    }
+
+    /// Values in the given `range` along with their points, in ascending order of values.
+    /// Points with multiple values in the range appear once per value.
+    pub fn stream_range(
+        &self,
+        range: &Range,
+    ) -> Box<dyn DoubleEndedIterator<Item = (OrderValue, PointOffsetType)> + '_> {
+        let Some((start_bound, end_bound)) = Self::key_bounds(range) else {
+            return Box::new(std::iter::empty());
+        };
+
+        match self {
+            NumericIndex::Mutable(index) => Box::new(
+                index
+                    .orderable_values_range(
+                        Self::encode_bound(start_bound),
+                        Self::encode_bound(end_bound),
+                    )
+                    .map(|(value, idx)| (value.to_f64(), idx)),
+            ),
+            NumericIndex::Immutable(index) => Box::new(
+                index
+                    .orderable_values_range(start_bound, end_bound)
+                    .map(|(value, idx)| (value.to_f64(), idx)),
+            ),
+        }
+    }
+
     pub fn get_telemetry_data(&self) -> PayloadIndexTelemetry {
         PayloadIndexTelemetry {
             field_name: None,
@@ -226,6 +330,21 @@ impl<T: Encodable + Numericable> NumericIndex<T> {
         }
     }
 
+    pub fn get_stats(&self) -> FieldIndexStats {
+        let values_count = self.get_values_count();
+        let keys_bytes = values_count * size_of::<NumericIndexKey<T>>();
+        let is_on_disk = matches!(self, NumericIndex::Immutable(index) if index.is_on_disk());
+        FieldIndexStats {
+            points_count: self.get_points_count(),
+            values_count,
+            postings: None,
+            histogram: Some(self.get_histogram().buckets()),
+            ram_usage_bytes: values_count * size_of::<T>()
+                + if is_on_disk { 0 } else { keys_bytes },
+            disk_usage_bytes: if is_on_disk { keys_bytes } else { 0 },
+        }
+    }
+
     pub fn values_count(&self, point_id: PointOffsetType) -> usize {
         self.get_values(point_id).map(|x| x.len()).unwrap_or(0)
     }
@@ -247,7 +366,7 @@ impl<T: Encodable + Numericable> PayloadFieldIndex for NumericIndex<T> {
     }
 
     fn clear(self) -> OperationResult<()> {
-        self.get_db_wrapper().recreate_column_family()
+        self.wipe()
     }
 
     fn flusher(&self) -> Flusher {
@@ -263,52 +382,7 @@ impl<T: Encodable + Numericable> PayloadFieldIndex for NumericIndex<T> {
             .as_ref()
             .ok_or_else(|| OperationError::service_error("failed to get condition range"))?;
 
-        let start_bound = match cond_range {
-            Range { gt: Some(gt), .. } => {
-                let v: T = T::from_f64(*gt);
-                Excluded(NumericIndexKey::new(v, PointOffsetType::MAX))
-            }
-            Range { gte: Some(gte), .. } => {
-                let v: T = T::from_f64(*gte);
-                Included(NumericIndexKey::new(v, PointOffsetType::MIN))
-            }
-            _ => Unbounded,
-        };
-
-        let end_bound = match cond_range {
-            Range { lt: Some(lt), .. } => {
-                let v: T = T::from_f64(*lt);
-                Excluded(NumericIndexKey::new(v, PointOffsetType::MIN))
-            }
-            Range { lte: Some(lte), .. } => {
-                let v: T = T::from_f64(*lte);
-                Included(NumericIndexKey::new(v, PointOffsetType::MAX))
-            }
-            _ => Unbounded,
-        };
-
-        // map.range
-        // Panics if range start > end. Panics if range start == end and both bounds are Excluded.
-        if !check_boundaries(&start_bound, &end_bound) {
-            return Ok(Box::new(vec![].into_iter()));
-        }
-
-        Ok(match self {
-            NumericIndex::Mutable(index) => {
-                let start_bound = match start_bound {
-                    Included(k) => Included(k.encode()),
-                    Excluded(k) => Excluded(k.encode()),
-                    Unbounded => Unbounded,
-                };
-                let end_bound = match end_bound {
-                    Included(k) => Included(k.encode()),
-                    Excluded(k) => Excluded(k.encode()),
-                    Unbounded => Unbounded,
-                };
-                Box::new(index.values_range(start_bound, end_bound))
-            }
-            NumericIndex::Immutable(index) => Box::new(index.values_range(start_bound, end_bound)),
-        })
+        Ok(self.range_filter(cond_range))
     }
 
     fn estimate_cardinality(
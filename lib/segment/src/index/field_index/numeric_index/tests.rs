@@ -133,6 +133,35 @@ fn test_cardinality_exp(#[case] immutable: bool) {
     );
 }
 
+#[rstest]
+#[case(true)]
+#[case(false)]
+fn test_stream_range(#[case] immutable: bool) {
+    let (_temp_dir, index) = random_index(1000, 2, immutable);
+
+    let range = Range {
+        gte: Some(10.0),
+        lte: Some(60.0),
+        ..Default::default()
+    };
+    let ascending = index.stream_range(&range).collect_vec();
+    assert!(ascending
+        .iter()
+        .tuple_windows()
+        .all(|((a, _), (b, _))| a <= b));
+    assert!(ascending
+        .iter()
+        .all(|&(value, _)| (10.0..=60.0).contains(&value)));
+
+    // Every point of the range filter appears in the stream
+    let points = index.range_filter(&range).sorted().collect_vec();
+    let streamed = ascending.iter().map(|&(_, idx)| idx).sorted().collect_vec();
+    assert_eq!(points, streamed);
+
+    let descending = index.stream_range(&range).rev().collect_vec();
+    assert_eq!(descending, ascending.into_iter().rev().collect_vec());
+}
+
 #[rstest]
 #[case(true)]
 #[case(false)]
@@ -364,6 +393,7 @@ fn test_cond<T: Encodable + Numericable + PartialOrd + Clone>(
         key: "".to_string(),
         r#match: None,
         range: Some(rng),
+        datetime_range: None,
         geo_bounding_box: None,
         geo_radius: None,
         values_count: None,
@@ -0,0 +1,269 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Iterations of the bisection, which finds percentiles of histogram values
+const PERCENTILE_SEARCH_STEPS: usize = 64;
+
+/// Values of a numeric index between two neighbouring histogram borders.
+///
+/// `count` includes the value at `from`, but not the one at `to`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValuesBucket {
+    pub from: f64,
+    pub to: f64,
+    pub count: usize,
+}
+
+impl ValuesBucket {
+    /// Number of values of the bucket, which are not greater than `value`,
+    /// assuming values are evenly spread over the bucket
+    fn count_up_to(&self, value: f64) -> f64 {
+        if value < self.from {
+            0.0
+        } else if value >= self.to {
+            self.count as f64
+        } else {
+            self.count as f64 * (value - self.from) / (self.to - self.from)
+        }
+    }
+}
+
+/// Statistics of a field index in a single segment.
+///
+/// Statistics of all segments and shards are merged before they are summarized.
+#[derive(Debug, Clone, Default)]
+pub struct FieldIndexStats {
+    /// Number of points with at least one indexed value
+    pub points_count: usize,
+    /// Total number of indexed values
+    pub values_count: usize,
+    /// Number of points per distinct value, if the index keeps a posting list for each value
+    pub postings: Option<HashMap<String, usize>>,
+    /// Histogram of the values, if the index is numeric
+    pub histogram: Option<Vec<ValuesBucket>>,
+    /// Estimated size of the in-memory structures of the index
+    pub ram_usage_bytes: usize,
+    /// Estimated size of the memory mapped files of the index
+    pub disk_usage_bytes: usize,
+}
+
+impl FieldIndexStats {
+    /// Merge statistics of the same index over other points, e.g. in another segment
+    pub fn merge(&mut self, other: FieldIndexStats) {
+        self.points_count += other.points_count;
+        self.values_count += other.values_count;
+        if let Some(other_postings) = other.postings {
+            let postings = self.postings.get_or_insert_with(Default::default);
+            for (value, points_count) in other_postings {
+                *postings.entry(value).or_insert(0) += points_count;
+            }
+        }
+        if let Some(other_histogram) = other.histogram {
+            self.histogram
+                .get_or_insert_with(Default::default)
+                .extend(other_histogram);
+        }
+        self.ram_usage_bytes += other.ram_usage_bytes;
+        self.disk_usage_bytes += other.disk_usage_bytes;
+    }
+
+    /// Combine statistics of another index over the same points,
+    /// e.g. of map and range indexes of an integer field
+    pub fn combine(&mut self, other: FieldIndexStats) {
+        self.points_count = self.points_count.max(other.points_count);
+        self.values_count = self.values_count.max(other.values_count);
+        if self.postings.is_none() {
+            self.postings = other.postings;
+        }
+        if self.histogram.is_none() {
+            self.histogram = other.histogram;
+        }
+        self.ram_usage_bytes += other.ram_usage_bytes;
+        self.disk_usage_bytes += other.disk_usage_bytes;
+    }
+
+    pub fn summarize(self) -> PayloadIndexStatistics {
+        let distinct_values = self.postings.as_ref().map(HashMap::len);
+        let postings = self.postings.and_then(|postings| {
+            let sizes = postings
+                .into_values()
+                .map(|size| size as f64)
+                .sorted_by(f64::total_cmp);
+            Distribution::from_sorted(&sizes.collect_vec())
+        });
+        let values = self
+            .histogram
+            .and_then(|buckets| Distribution::from_buckets(&buckets));
+        PayloadIndexStatistics {
+            points_count: self.points_count,
+            values_count: self.values_count,
+            distinct_values,
+            postings,
+            values,
+            ram_usage_bytes: self.ram_usage_bytes,
+            disk_usage_bytes: self.disk_usage_bytes,
+        }
+    }
+}
+
+/// Summary of a set of numbers
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct Distribution {
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+impl Distribution {
+    fn from_sorted(values: &[f64]) -> Option<Self> {
+        let (&min, &max) = (values.first()?, values.last()?);
+        let percentile = |q: f64| values[((values.len() - 1) as f64 * q).round() as usize];
+        Some(Distribution {
+            min,
+            max,
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+        })
+    }
+
+    /// Approximate distribution of the values, which are evenly spread over each bucket.
+    /// Buckets may overlap, if they come from histograms of different segments.
+    fn from_buckets(buckets: &[ValuesBucket]) -> Option<Self> {
+        let total = buckets.iter().map(|bucket| bucket.count).sum::<usize>() as f64;
+        if total == 0.0 {
+            return None;
+        }
+        let min = buckets
+            .iter()
+            .map(|bucket| bucket.from)
+            .fold(f64::MAX, f64::min);
+        let max = buckets
+            .iter()
+            .map(|bucket| bucket.to)
+            .fold(f64::MIN, f64::max);
+        let mean = buckets
+            .iter()
+            .map(|bucket| bucket.count as f64 * (bucket.from + bucket.to) / 2.0)
+            .sum::<f64>()
+            / total;
+
+        let percentile = |q: f64| {
+            let target = total * q;
+            let (mut low, mut high) = (min, max);
+            for _ in 0..PERCENTILE_SEARCH_STEPS {
+                let mid = low + (high - low) / 2.0;
+                let count: f64 = buckets.iter().map(|bucket| bucket.count_up_to(mid)).sum();
+                if count < target {
+                    low = mid;
+                } else {
+                    high = mid;
+                }
+            }
+            high
+        };
+
+        Some(Distribution {
+            min,
+            max,
+            mean,
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            p99: percentile(0.99),
+        })
+    }
+}
+
+/// Statistics of a payload index, collected over all local shards of a collection
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct PayloadIndexStatistics {
+    /// Number of points with at least one indexed value
+    pub points_count: usize,
+    /// Total number of indexed values
+    pub values_count: usize,
+    /// Number of distinct indexed values.
+    /// Tokens are counted for full-text index and geo-hashes of the points for geo index.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distinct_values: Option<usize>,
+    /// Distribution of the number of points per distinct value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postings: Option<Distribution>,
+    /// Approximate distribution of numeric values, timestamps in microseconds for datetime index
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub values: Option<Distribution>,
+    /// Estimated size of the in-memory structures of the index
+    pub ram_usage_bytes: usize,
+    /// Estimated size of the memory mapped files of the index
+    pub disk_usage_bytes: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_and_summarize() {
+        let mut stats = FieldIndexStats {
+            points_count: 3,
+            values_count: 4,
+            postings: Some(HashMap::from([("a".to_string(), 3), ("b".to_string(), 1)])),
+            histogram: None,
+            ram_usage_bytes: 100,
+            disk_usage_bytes: 0,
+        };
+        stats.merge(FieldIndexStats {
+            points_count: 2,
+            values_count: 2,
+            postings: Some(HashMap::from([("a".to_string(), 1), ("c".to_string(), 1)])),
+            histogram: None,
+            ram_usage_bytes: 0,
+            disk_usage_bytes: 50,
+        });
+
+        let summary = stats.summarize();
+        assert_eq!(summary.points_count, 5);
+        assert_eq!(summary.values_count, 6);
+        assert_eq!(summary.distinct_values, Some(3));
+        let postings = summary.postings.unwrap();
+        assert_eq!((postings.min, postings.max, postings.mean), (1.0, 4.0, 2.0));
+        assert_eq!(postings.p50, 1.0);
+        assert_eq!(
+            (summary.ram_usage_bytes, summary.disk_usage_bytes),
+            (100, 50)
+        );
+        assert!(summary.values.is_none());
+    }
+
+    #[test]
+    fn test_histogram_percentiles() {
+        // Two segments with 100 values evenly spread over [0, 100) each
+        let segment_histogram = (0..10)
+            .map(|i| ValuesBucket {
+                from: i as f64 * 10.0,
+                to: (i + 1) as f64 * 10.0,
+                count: 10,
+            })
+            .collect_vec();
+        let mut stats = FieldIndexStats::default();
+        for _ in 0..2 {
+            stats.merge(FieldIndexStats {
+                histogram: Some(segment_histogram.clone()),
+                ..Default::default()
+            });
+        }
+
+        let values = stats.summarize().values.unwrap();
+        assert_eq!((values.min, values.max, values.mean), (0.0, 100.0, 50.0));
+        assert!((values.p50 - 50.0).abs() < 1e-6);
+        assert!((values.p90 - 90.0).abs() < 1e-6);
+    }
+}
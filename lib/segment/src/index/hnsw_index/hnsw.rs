@@ -16,13 +16,18 @@ use rayon::prelude::*;
 use rayon::ThreadPool;
 
 use super::graph_links::{GraphLinks, GraphLinksMmap};
+use crate::common::full_scan_tracker::mark_full_scan;
 use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
 use crate::common::operation_time_statistics::{
     OperationDurationsAggregator, ScopeDurationMeasurer,
 };
+use crate::common::search_explain::{
+    count_rescored_points, explain_vector_search, ExplainMode, SearchStrategy, VectorSearchExplain,
+};
 use crate::common::BYTES_IN_KB;
 use crate::data_types::vectors::QueryVector;
 use crate::id_tracker::{IdTracker, IdTrackerSS};
+use crate::index::field_index::CardinalityEstimation;
 use crate::index::hnsw_index::build_condition_checker::BuildConditionChecker;
 use crate::index::hnsw_index::config::HnswGraphConfig;
 use crate::index::hnsw_index::graph_layers::GraphLayers;
@@ -38,7 +43,7 @@ use crate::telemetry::VectorIndexSearchesTelemetry;
 use crate::types::Condition::Field;
 use crate::types::{
     default_quantization_ignore_value, default_quantization_oversampling_value, FieldCondition,
-    Filter, HnswConfig, QuantizationSearchParams, SearchParams, VECTOR_ELEMENT_SIZE,
+    Filter, HnswConfig, SearchParams, VECTOR_ELEMENT_SIZE,
 };
 use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
 use crate::vector_storage::{
@@ -322,7 +327,9 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
             .and_then(|p| p.quantization)
             .map(|q| q.ignore)
             .unwrap_or(default_quantization_ignore_value());
-        quantized_storage.is_some() && !ignore_quantization
+        // Exact search is always done with full precision vectors
+        let exact = params.map(|p| p.exact).unwrap_or(false);
+        quantized_storage.is_some() && !ignore_quantization && !exact
     }
 
     fn construct_search_scorer<'a>(
@@ -401,6 +408,7 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
                 is_stopped,
             );
 
+            count_rescored_points(search_result.len());
             let mut ids_iterator = search_result.iter().map(|x| x.idx);
             let mut re_scored = raw_scorer.score_points_unfiltered(&mut ids_iterator);
 
@@ -413,6 +421,64 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         postprocess_result.truncate(top);
         postprocess_result
     }
+
+    /// Tell if the search should use the HNSW graph, or score the points directly.
+    /// Also returns the estimated cardinality of the filter, if it was needed for the choice.
+    fn choose_graph_search(
+        &self,
+        filter: Option<&Filter>,
+        exact: bool,
+    ) -> (bool, Option<CardinalityEstimation>) {
+        // if exact search is requested, we should not use HNSW index
+        if exact {
+            return (false, None);
+        }
+
+        let vector_storage = self.vector_storage.borrow();
+        let available_vector_count = vector_storage.available_vector_count();
+
+        let Some(query_filter) = filter else {
+            // Because an HNSW graph is built, we'd normally always assume to search the graph.
+            // But because a lot of points may be deleted in this graph, it may just be faster
+            // to do a plain search instead.
+            return (
+                available_vector_count >= self.config.full_scan_threshold,
+                None,
+            );
+        };
+
+        // depending on the amount of filtered-out points the optimal strategy could be
+        // - to retrieve possible points and score them after
+        // - to use HNSW index with filtering condition
+        let payload_index = self.payload_index.borrow();
+        let id_tracker = self.id_tracker.borrow();
+        let query_point_cardinality = payload_index.estimate_cardinality(query_filter);
+        let query_cardinality = adjust_to_available_vectors(
+            query_point_cardinality,
+            available_vector_count,
+            id_tracker.available_point_count(),
+        );
+
+        if query_cardinality.max < self.config.full_scan_threshold {
+            // if cardinality is small - use plain index
+            return (false, Some(query_cardinality));
+        }
+
+        if query_cardinality.min > self.config.full_scan_threshold {
+            // if cardinality is high enough - use HNSW index
+            return (true, Some(query_cardinality));
+        }
+
+        // Fast cardinality estimation is not enough, do sample estimation of cardinality
+        let filter_context = payload_index.filter_context(query_filter);
+        let graph_search = sample_check_cardinality(
+            id_tracker.sample_ids(Some(vector_storage.deleted_vector_bitslice())),
+            |idx| filter_context.check(idx),
+            self.config.full_scan_threshold,
+            available_vector_count, // Check cardinality among available vectors
+        );
+        (graph_search, Some(query_cardinality))
+    }
 }
 
 impl HNSWIndex<GraphLinksMmap> {
@@ -431,124 +497,77 @@ impl<TGraphLinks: GraphLinks> VectorIndex for HNSWIndex<TGraphLinks> {
         is_stopped: &AtomicBool,
     ) -> Vec<Vec<ScoredPointOffset>> {
         let exact = params.map(|params| params.exact).unwrap_or(false);
-        match filter {
-            None => {
-                let id_tracker = self.id_tracker.borrow();
-                let vector_storage = self.vector_storage.borrow();
-
-                // Determine whether to do a plain or graph search, and pick search timer aggregator
-                // Because an HNSW graph is built, we'd normally always assume to search the graph.
-                // But because a lot of points may be deleted in this graph, it may just be faster
-                // to do a plain search instead.
-                let plain_search = exact
-                    || vector_storage.available_vector_count() < self.config.full_scan_threshold;
-
-                // Do plain or graph search
-                if plain_search {
-                    let _timer = ScopeDurationMeasurer::new(if exact {
-                        &self.searches_telemetry.exact_unfiltered
-                    } else {
-                        &self.searches_telemetry.unfiltered_plain
-                    });
-                    vectors
-                        .iter()
-                        .map(|&vector| {
-                            new_stoppable_raw_scorer(
-                                vector.to_owned(),
-                                &vector_storage,
-                                id_tracker.deleted_point_bitslice(),
-                                is_stopped,
-                            )
-                            .peek_top_all(top)
-                        })
-                        .collect()
-                } else {
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.unfiltered_hnsw);
-                    self.search_vectors_with_graph(vectors, None, top, params, is_stopped)
-                }
-            }
-            Some(query_filter) => {
-                // depending on the amount of filtered-out points the optimal strategy could be
-                // - to retrieve possible points and score them after
-                // - to use HNSW index with filtering condition
-
-                // if exact search is requested, we should not use HNSW index
-                if exact {
-                    let exact_params = params.map(|params| {
-                        let mut params = *params;
-                        params.quantization = Some(QuantizationSearchParams {
-                            ignore: true,
-                            rescore: Some(false),
-                            oversampling: None,
-                        }); // disable quantization for exact search
-                        params
-                    });
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.exact_filtered);
-                    return self.search_vectors_plain(
-                        vectors,
-                        query_filter,
-                        top,
-                        exact_params.as_ref(),
-                        is_stopped,
-                    );
-                }
+        let (graph_search, cardinality) = self.choose_graph_search(filter, exact);
+        let strategy = match (graph_search, filter) {
+            (true, _) => SearchStrategy::Hnsw,
+            (false, Some(_)) => SearchStrategy::FilteredScan,
+            (false, None) => SearchStrategy::FullScan,
+        };
 
-                let payload_index = self.payload_index.borrow();
+        explain_vector_search(
+            |mode| {
                 let vector_storage = self.vector_storage.borrow();
-                let id_tracker = self.id_tracker.borrow();
-                let available_vector_count = vector_storage.available_vector_count();
-                let query_point_cardinality = payload_index.estimate_cardinality(query_filter);
-                let query_cardinality = adjust_to_available_vectors(
-                    query_point_cardinality,
-                    available_vector_count,
-                    id_tracker.available_point_count(),
-                );
-
-                if query_cardinality.max < self.config.full_scan_threshold {
-                    // if cardinality is small - use plain index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
-                    return self.search_vectors_plain(
-                        vectors,
-                        query_filter,
-                        top,
-                        params,
-                        is_stopped,
-                    );
+                let mut explain =
+                    VectorSearchExplain::new(strategy, vectors.len(), cardinality.as_ref());
+                // Unfiltered plain search always scores the original vectors
+                explain.quantized = strategy != SearchStrategy::FullScan
+                    && Self::is_quantized_search(vector_storage.quantized_storage(), params);
+                match mode {
+                    ExplainMode::Plan => explain,
+                    ExplainMode::Analyze => explain.analyze(
+                        filter.map(|filter| self.payload_index.borrow().query_points(filter).len()),
+                        vector_storage.available_vector_count(),
+                    ),
                 }
-
-                if query_cardinality.min > self.config.full_scan_threshold {
-                    // if cardinality is high enough - use HNSW index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
+            },
+            || {
+                if graph_search {
+                    let _timer = ScopeDurationMeasurer::new(if filter.is_some() {
+                        &self.searches_telemetry.large_cardinality
+                    } else {
+                        &self.searches_telemetry.unfiltered_hnsw
+                    });
                     return self
                         .search_vectors_with_graph(vectors, filter, top, params, is_stopped);
                 }
 
-                let filter_context = payload_index.filter_context(query_filter);
-
-                // Fast cardinality estimation is not enough, do sample estimation of cardinality
-                let id_tracker = self.id_tracker.borrow();
-                if sample_check_cardinality(
-                    id_tracker.sample_ids(Some(vector_storage.deleted_vector_bitslice())),
-                    |idx| filter_context.check(idx),
-                    self.config.full_scan_threshold,
-                    available_vector_count, // Check cardinality among available vectors
-                ) {
-                    // if cardinality is high enough - use HNSW index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.large_cardinality);
-                    self.search_vectors_with_graph(vectors, filter, top, params, is_stopped)
-                } else {
-                    // if cardinality is small - use plain index
-                    let _timer =
-                        ScopeDurationMeasurer::new(&self.searches_telemetry.small_cardinality);
-                    self.search_vectors_plain(vectors, query_filter, top, params, is_stopped)
+                // Exact search is a full scan by request, not a fallback
+                if !exact {
+                    mark_full_scan();
                 }
-            }
-        }
+                match filter {
+                    Some(query_filter) => {
+                        let _timer = ScopeDurationMeasurer::new(if exact {
+                            &self.searches_telemetry.exact_filtered
+                        } else {
+                            &self.searches_telemetry.small_cardinality
+                        });
+                        self.search_vectors_plain(vectors, query_filter, top, params, is_stopped)
+                    }
+                    None => {
+                        let _timer = ScopeDurationMeasurer::new(if exact {
+                            &self.searches_telemetry.exact_unfiltered
+                        } else {
+                            &self.searches_telemetry.unfiltered_plain
+                        });
+                        let id_tracker = self.id_tracker.borrow();
+                        let vector_storage = self.vector_storage.borrow();
+                        vectors
+                            .iter()
+                            .map(|&vector| {
+                                new_stoppable_raw_scorer(
+                                    vector.to_owned(),
+                                    &vector_storage,
+                                    id_tracker.deleted_point_bitslice(),
+                                    is_stopped,
+                                )
+                                .peek_top_all(top)
+                            })
+                            .collect()
+                    }
+                }
+            },
+        )
     }
 
     fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()> {
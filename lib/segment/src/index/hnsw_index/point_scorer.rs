@@ -1,5 +1,6 @@
 use common::types::{PointOffsetType, ScoreType, ScoredPointOffset};
 
+use crate::common::search_explain::count_scored_points;
 use crate::payload_storage::FilterContext;
 use crate::vector_storage::RawScorer;
 
@@ -59,6 +60,7 @@ impl<'a> FilteredScorer<'a> {
                 &point_ids[0..filtered_len]
             }
         };
+        count_scored_points(filtered_point_ids.len());
         if limit == 0 {
             self.points_buffer
                 .resize(filtered_point_ids.len(), ScoredPointOffset::default());
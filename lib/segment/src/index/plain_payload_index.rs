@@ -9,10 +9,14 @@ use common::types::{PointOffsetType, ScoredPointOffset};
 use parking_lot::Mutex;
 use schemars::_serde_json::Value;
 
+use crate::common::full_scan_tracker::mark_full_scan;
 use crate::common::operation_error::OperationResult;
 use crate::common::operation_time_statistics::{
     OperationDurationStatistics, OperationDurationsAggregator, ScopeDurationMeasurer,
 };
+use crate::common::search_explain::{
+    explain_vector_search, ExplainMode, SearchStrategy, VectorSearchExplain,
+};
 use crate::common::utils::JsonPathPayload;
 use crate::common::Flusher;
 use crate::data_types::vectors::QueryVector;
@@ -226,47 +230,71 @@ impl VectorIndex for PlainIndex {
         vectors: &[&QueryVector],
         filter: Option<&Filter>,
         top: usize,
-        _params: Option<&SearchParams>,
+        params: Option<&SearchParams>,
         is_stopped: &AtomicBool,
     ) -> Vec<Vec<ScoredPointOffset>> {
-        match filter {
-            Some(filter) => {
-                let _timer = ScopeDurationMeasurer::new(&self.filtered_searches_telemetry);
-                let id_tracker = self.id_tracker.borrow();
-                let payload_index = self.payload_index.borrow();
-                let vector_storage = self.vector_storage.borrow();
-                let filtered_ids_vec = payload_index.query_points(filter);
-                vectors
-                    .iter()
-                    .map(|&vector| {
-                        new_stoppable_raw_scorer(
-                            vector.to_owned(),
-                            &vector_storage,
-                            id_tracker.deleted_point_bitslice(),
-                            is_stopped,
-                        )
-                        .peek_top_iter(&mut filtered_ids_vec.iter().copied(), top)
-                    })
-                    .collect()
-            }
-            None => {
-                let _timer = ScopeDurationMeasurer::new(&self.unfiltered_searches_telemetry);
-                let vector_storage = self.vector_storage.borrow();
-                let id_tracker = self.id_tracker.borrow();
-                vectors
-                    .iter()
-                    .map(|&vector| {
-                        new_stoppable_raw_scorer(
-                            vector.to_owned(),
-                            &vector_storage,
-                            id_tracker.deleted_point_bitslice(),
-                            is_stopped,
-                        )
-                        .peek_top_all(top)
-                    })
-                    .collect()
-            }
-        }
+        let strategy = if filter.is_some() {
+            SearchStrategy::FilteredScan
+        } else {
+            SearchStrategy::FullScan
+        };
+        explain_vector_search(
+            |mode| {
+                let explain = VectorSearchExplain::new(strategy, vectors.len(), None);
+                match mode {
+                    ExplainMode::Plan => explain,
+                    ExplainMode::Analyze => explain.analyze(
+                        filter.map(|filter| self.payload_index.borrow().query_points(filter).len()),
+                        self.vector_storage.borrow().available_vector_count(),
+                    ),
+                }
+            },
+            || {
+                // Exact search is a full scan by request, not a fallback
+                if !params.map(|params| params.exact).unwrap_or(false) {
+                    mark_full_scan();
+                }
+                match filter {
+                    Some(filter) => {
+                        let _timer = ScopeDurationMeasurer::new(&self.filtered_searches_telemetry);
+                        let id_tracker = self.id_tracker.borrow();
+                        let payload_index = self.payload_index.borrow();
+                        let vector_storage = self.vector_storage.borrow();
+                        let filtered_ids_vec = payload_index.query_points(filter);
+                        vectors
+                            .iter()
+                            .map(|&vector| {
+                                new_stoppable_raw_scorer(
+                                    vector.to_owned(),
+                                    &vector_storage,
+                                    id_tracker.deleted_point_bitslice(),
+                                    is_stopped,
+                                )
+                                .peek_top_iter(&mut filtered_ids_vec.iter().copied(), top)
+                            })
+                            .collect()
+                    }
+                    None => {
+                        let _timer =
+                            ScopeDurationMeasurer::new(&self.unfiltered_searches_telemetry);
+                        let vector_storage = self.vector_storage.borrow();
+                        let id_tracker = self.id_tracker.borrow();
+                        vectors
+                            .iter()
+                            .map(|&vector| {
+                                new_stoppable_raw_scorer(
+                                    vector.to_owned(),
+                                    &vector_storage,
+                                    id_tracker.deleted_point_bitslice(),
+                                    is_stopped,
+                                )
+                                .peek_top_all(top)
+                            })
+                            .collect()
+                    }
+                }
+            },
+        )
     }
 
     fn build_index(&mut self, _stopped: &AtomicBool) -> OperationResult<()> {
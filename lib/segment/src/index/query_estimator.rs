@@ -8,7 +8,7 @@ use std::cmp::{max, min};
 use itertools::Itertools;
 
 use crate::index::field_index::{CardinalityEstimation, PrimaryCondition};
-use crate::types::{Condition, Filter};
+use crate::types::{Condition, Filter, MinShould};
 
 /// Re-estimate cardinality based on number of available vectors
 /// Assuming that deleted vectors are not correlated with the filter
@@ -125,6 +125,63 @@ pub fn combine_should_estimations(
     }
 }
 
+/// Estimate number of points, which match at least `min_count` of the conditions,
+/// assuming the conditions are independent
+pub fn combine_min_should_estimations(
+    estimations: &[CardinalityEstimation],
+    min_count: usize,
+    total: usize,
+) -> CardinalityEstimation {
+    if min_count == 0 {
+        return CardinalityEstimation::exact(total);
+    }
+    if min_count > estimations.len() || total == 0 {
+        return CardinalityEstimation::exact(0);
+    }
+
+    // Any matched point matches at least one of the conditions
+    let mut clauses: Vec<PrimaryCondition> = vec![];
+    for estimation in estimations {
+        if estimation.primary_clauses.is_empty() {
+            clauses = vec![];
+            break;
+        }
+        clauses.append(&mut estimation.primary_clauses.clone());
+    }
+
+    // Each matched point is counted at least `min_count` times in the sum of matches
+    let max_estimation = min(
+        estimations.iter().map(|x| x.max).sum::<usize>() / min_count,
+        total,
+    );
+    // Unmatched points are counted at most `min_count - 1` times, matched ones at most once per condition
+    let min_matches = estimations.iter().map(|x| x.min).sum::<usize>();
+    let min_estimation =
+        min_matches.saturating_sub((min_count - 1) * total) / (estimations.len() - min_count + 1);
+
+    // Probabilities of a point to match exactly `i` conditions, the last one is for `min_count` and more
+    let mut matches_prob = vec![0.0; min_count + 1];
+    matches_prob[0] = 1.0;
+    for estimation in estimations {
+        let prob = estimation.exp as f64 / total as f64;
+        matches_prob[min_count] += matches_prob[min_count - 1] * prob;
+        for i in (1..min_count).rev() {
+            matches_prob[i] = matches_prob[i] * (1.0 - prob) + matches_prob[i - 1] * prob;
+        }
+        matches_prob[0] *= 1.0 - prob;
+    }
+    let exp_estimation = ((matches_prob[min_count] * total as f64).round() as usize)
+        .max(min_estimation)
+        .min(max_estimation);
+
+    CardinalityEstimation {
+        primary_clauses: clauses,
+        min: min_estimation,
+        exp: exp_estimation,
+        max: max_estimation,
+    }
+}
+
 pub fn combine_must_estimations(
     estimations: &[CardinalityEstimation],
     total: usize,
@@ -196,6 +253,19 @@ where
             }
         }
     }
+    match &filter.min_should {
+        None => {}
+        Some(MinShould {
+            conditions,
+            min_count,
+        }) => {
+            if !conditions.is_empty() {
+                filter_estimations.push(estimate_min_should(
+                    estimator, conditions, *min_count, total,
+                ));
+            }
+        }
+    }
     match &filter.must_not {
         None => {}
         Some(conditions) => {
@@ -221,6 +291,20 @@ where
     combine_should_estimations(&should_estimations, total)
 }
 
+fn estimate_min_should<F>(
+    estimator: &F,
+    conditions: &[Condition],
+    min_count: usize,
+    total: usize,
+) -> CardinalityEstimation
+where
+    F: Fn(&Condition) -> CardinalityEstimation,
+{
+    let estimate = |x| estimate_condition(estimator, x, total);
+    let min_should_estimations = conditions.iter().map(estimate).collect_vec();
+    combine_min_should_estimations(&min_should_estimations, min_count, total)
+}
+
 fn estimate_must<F>(estimator: &F, conditions: &[Condition], total: usize) -> CardinalityEstimation
 where
     F: Fn(&Condition) -> CardinalityEstimation,
@@ -271,6 +355,7 @@ mod tests {
             key,
             r#match: None,
             range: None,
+            datetime_range: None,
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
@@ -327,6 +412,7 @@ mod tests {
                 exp: TOTAL / 2,
                 max: TOTAL,
             },
+            Condition::HasVector(_) => CardinalityEstimation::unknown(TOTAL),
         }
     }
 
@@ -342,6 +428,7 @@ mod tests {
     fn must_estimation_query_test() {
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![
                 test_condition("color".to_owned()),
                 test_condition("size".to_owned()),
@@ -368,6 +455,7 @@ mod tests {
                 test_condition("color".to_owned()),
                 test_condition("size".to_owned()),
             ]),
+            min_should: None,
             must: None,
             must_not: None,
         };
@@ -379,6 +467,39 @@ mod tests {
         assert!(estimation.min <= estimation.exp);
     }
 
+    #[test]
+    fn min_should_estimation_query_test() {
+        let conditions = vec![
+            test_condition("color".to_owned()),
+            test_condition("size".to_owned()),
+            test_condition("price".to_owned()),
+        ];
+        let query = Filter::new_min_should(MinShould {
+            conditions: conditions.clone(),
+            min_count: 2,
+        });
+
+        let estimation = estimate_filter(&test_estimator, &query, TOTAL);
+        assert_eq!(estimation.primary_clauses.len(), 3);
+        assert_eq!(estimation.min, 0);
+        assert_eq!(estimation.exp, 24);
+        assert_eq!(estimation.max, 210);
+
+        // Single required match is the same as `should`
+        let query = Filter::new_min_should(MinShould {
+            conditions: conditions.clone(),
+            min_count: 1,
+        });
+        let should_query = Filter {
+            should: Some(conditions),
+            ..Default::default()
+        };
+        assert_eq!(
+            estimate_filter(&test_estimator, &query, TOTAL).exp,
+            estimate_filter(&test_estimator, &should_query, TOTAL).exp,
+        );
+    }
+
     #[test]
     fn another_should_estimation_query_test() {
         let query = Filter {
@@ -387,6 +508,7 @@ mod tests {
                 test_condition("size".to_owned()),
                 test_condition("un-indexed".to_owned()),
             ]),
+            min_should: None,
             must: None,
             must_not: None,
         };
@@ -405,6 +527,7 @@ mod tests {
             should: Some(vec![
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![
                         test_condition("color".to_owned()),
                         test_condition("size".to_owned()),
@@ -413,6 +536,7 @@ mod tests {
                 }),
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![
                         test_condition("price".to_owned()),
                         test_condition("size".to_owned()),
@@ -420,6 +544,7 @@ mod tests {
                     must_not: None,
                 }),
             ]),
+            min_should: None,
             must: None,
             must_not: Some(vec![Condition::HasId(HasIdCondition {
                 has_id: HashSet::from_iter([1, 2, 3, 4, 5].into_iter().map(|x| x.into())),
@@ -437,6 +562,7 @@ mod tests {
     fn another_complex_estimation_query_test() {
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![
                 Condition::Filter(Filter {
                     must: None,
@@ -444,6 +570,7 @@ mod tests {
                         test_condition("color".to_owned()),
                         test_condition("size".to_owned()),
                     ]),
+                    min_should: None,
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
@@ -452,6 +579,7 @@ mod tests {
                         test_condition("price".to_owned()),
                         test_condition("size".to_owned()),
                     ]),
+                    min_should: None,
                     must_not: None,
                 }),
             ]),
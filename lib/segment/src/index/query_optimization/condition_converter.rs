@@ -1,10 +1,12 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use common::types::PointOffsetType;
 use serde_json::Value;
 
 use crate::common::utils::IndexesMap;
 use crate::id_tracker::IdTrackerSS;
+use crate::index::field_index::map_index::parse_uuid;
+use crate::index::field_index::null_index::NullIndex;
 use crate::index::field_index::FieldIndex;
 use crate::index::query_optimization::optimized_filter::ConditionCheckerFn;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
@@ -13,14 +15,16 @@ use crate::payload_storage::query_checker::{
     select_nested_indexes,
 };
 use crate::types::{
-    AnyVariants, Condition, FieldCondition, FloatPayloadType, GeoBoundingBox, GeoPolygon,
-    GeoRadius, Match, MatchAny, MatchExcept, MatchText, MatchValue, OwnedPayloadRef,
-    PayloadContainer, Range, ValueVariants,
+    AnyVariants, Condition, DatetimeRange, FieldCondition, FloatPayloadType, GeoBoundingBox,
+    GeoPolygon, GeoRadius, Match, MatchAny, MatchExcept, MatchPhrase, MatchText, MatchValue,
+    OwnedPayloadRef, PayloadContainer, PayloadKeyTypeRef, Range, ValueVariants,
 };
+use crate::vector_storage::VectorStorageRef;
 
 pub fn condition_converter<'a>(
     condition: &'a Condition,
     field_indexes: &'a IndexesMap,
+    vector_storages: &'a HashMap<String, VectorStorageRef>,
     payload_provider: PayloadProvider,
     id_tracker: &IdTrackerSS,
 ) -> ConditionCheckerFn<'a> {
@@ -39,9 +43,13 @@ pub fn condition_converter<'a>(
                     })
                 })
             }),
-        // We can use index for `is_empty` condition effectively only when it is not empty.
-        // If the index says it is "empty", we still need to check the payload.
         Condition::IsEmpty(is_empty) => {
+            if let Some(null_index) = get_null_index(field_indexes, &is_empty.is_empty.key) {
+                return Box::new(move |point_id| null_index.values_is_empty(point_id));
+            }
+
+            // We can use index for `is_empty` condition effectively only when it is not empty.
+            // If the index says it is "empty", we still need to check the payload.
             let first_field_index = field_indexes
                 .get(&is_empty.is_empty.key)
                 .and_then(|indexes| indexes.first());
@@ -58,11 +66,14 @@ pub fn condition_converter<'a>(
             }
         }
 
-        Condition::IsNull(is_null) => Box::new(move |point_id| {
-            payload_provider.with_payload(point_id, |payload| {
-                check_is_null_condition(is_null, &payload)
-            })
-        }),
+        Condition::IsNull(is_null) => match get_null_index(field_indexes, &is_null.is_null.key) {
+            Some(null_index) => Box::new(move |point_id| null_index.values_is_null(point_id)),
+            None => Box::new(move |point_id| {
+                payload_provider.with_payload(point_id, |payload| {
+                    check_is_null_condition(is_null, &payload)
+                })
+            }),
+        },
         // ToDo: It might be possible to make this condition faster by using `VisitedPool` instead of HashSet
         Condition::HasId(has_id) => {
             let segment_ids: HashSet<_> = has_id
@@ -72,6 +83,10 @@ pub fn condition_converter<'a>(
                 .collect();
             Box::new(move |point_id| segment_ids.contains(&point_id))
         }
+        Condition::HasVector(has_vector) => match vector_storages.get(&has_vector.has_vector) {
+            Some(vector_storage) => Box::new(move |point_id| vector_storage.has_vector(point_id)),
+            None => Box::new(|_| false),
+        },
         Condition::Nested(nested) => {
             // Select indexes for nested fields. Trim nested part from key, so
             // that nested condition can address fields without nested part.
@@ -107,6 +122,8 @@ pub fn condition_converter<'a>(
                                 // None because has_id in nested is not supported. So retrieving
                                 // IDs through the tracker would always return None.
                                 None,
+                                // Vectors belong to the whole point, not to the nested object
+                                None,
                                 &nested.nested.filter,
                                 point_id,
                                 &nested_indexes,
@@ -144,6 +161,14 @@ pub fn field_condition_index<'a>(
         return Some(checker);
     }
 
+    if let Some(checker) = field_condition
+        .datetime_range
+        .clone()
+        .and_then(|cond| get_datetime_range_checkers(index, cond))
+    {
+        return Some(checker);
+    }
+
     if let Some(checker) = field_condition
         .geo_radius
         .clone()
@@ -240,6 +265,26 @@ pub fn get_range_checkers(index: &FieldIndex, range: Range) -> Option<ConditionC
     }
 }
 
+pub fn get_datetime_range_checkers(
+    index: &FieldIndex,
+    datetime_range: DatetimeRange,
+) -> Option<ConditionCheckerFn> {
+    let range = datetime_range.to_timestamp_range();
+    match index {
+        FieldIndex::DatetimeIndex(datetime_index) => {
+            Some(Box::new(move |point_id: PointOffsetType| {
+                datetime_index.get_values(point_id).map_or(false, |values| {
+                    values
+                        .iter()
+                        .copied()
+                        .any(|i| range.check_range(i as FloatPayloadType))
+                })
+            }))
+        }
+        _ => None,
+    }
+}
+
 pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<ConditionCheckerFn> {
     match cond_match {
         Match::Value(MatchValue {
@@ -252,6 +297,16 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                         .map_or(false, |values| values.iter().any(|k| k == &keyword))
                 }))
             }
+            (ValueVariants::Keyword(keyword), FieldIndex::UuidMapIndex(index)) => {
+                let uuid = parse_uuid(&keyword);
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    uuid.map_or(false, |uuid| {
+                        index
+                            .get_values(point_id)
+                            .map_or(false, |values| values.contains(&uuid))
+                    })
+                }))
+            }
             (ValueVariants::Integer(value), FieldIndex::IntMapIndex(index)) => {
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
@@ -281,6 +336,17 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
             }
             _ => None,
         },
+        Match::Phrase(MatchPhrase { phrase }) => match index {
+            FieldIndex::FullTextIndex(full_text_index) => {
+                let parsed_query = full_text_index.parse_phrase(&phrase)?;
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    full_text_index
+                        .get_doc(point_id)
+                        .map_or(false, |doc| parsed_query.check_match(doc))
+                }))
+            }
+            _ => None,
+        },
         Match::Any(MatchAny { any }) => match (any, index) {
             (AnyVariants::Keywords(list), FieldIndex::KeywordIndex(index)) => {
                 Some(Box::new(move |point_id: PointOffsetType| {
@@ -291,6 +357,14 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                     })
                 }))
             }
+            (AnyVariants::Keywords(list), FieldIndex::UuidMapIndex(index)) => {
+                let uuids: Vec<_> = list.iter().filter_map(|k| parse_uuid(k)).collect();
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |values| values.iter().any(|u| uuids.contains(u)))
+                }))
+            }
             (AnyVariants::Integers(list), FieldIndex::IntMapIndex(index)) => {
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
@@ -310,6 +384,14 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                     })
                 }))
             }
+            (AnyVariants::Keywords(list), FieldIndex::UuidMapIndex(index)) => {
+                let uuids: Vec<_> = list.iter().filter_map(|k| parse_uuid(k)).collect();
+                Some(Box::new(move |point_id: PointOffsetType| {
+                    index
+                        .get_values(point_id)
+                        .map_or(false, |values| values.iter().any(|u| !uuids.contains(u)))
+                }))
+            }
             (AnyVariants::Integers(list), FieldIndex::IntMapIndex(index)) => {
                 Some(Box::new(move |point_id: PointOffsetType| {
                     index
@@ -317,14 +399,46 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
                         .map_or(false, |values| values.iter().any(|i| !list.contains(i)))
                 }))
             }
+            // Composite index doesn't store values of the fields separately
+            (_, FieldIndex::CompositeIndex(_)) => None,
+            (_, FieldIndex::NullIndex(_)) => None,
             (_, index) => Some(Box::new(|point_id: PointOffsetType| {
                 // If there is any other value of any other index, then it's a match
                 index.values_count(point_id) > 0
             })),
         },
+        Match::Wildcard(wildcard) => match index {
+            FieldIndex::KeywordIndex(index) => Some(Box::new(move |point_id: PointOffsetType| {
+                index
+                    .get_values(point_id)
+                    .map_or(false, |values| values.iter().any(|k| wildcard.is_match(k)))
+            })),
+            _ => None,
+        },
+        Match::Regex(regex) => match index {
+            FieldIndex::KeywordIndex(index) => Some(Box::new(move |point_id: PointOffsetType| {
+                index
+                    .get_values(point_id)
+                    .map_or(false, |values| values.iter().any(|k| regex.is_match(k)))
+            })),
+            _ => None,
+        },
     }
 }
 
+pub fn get_null_index<'a>(
+    field_indexes: &'a IndexesMap,
+    key: PayloadKeyTypeRef,
+) -> Option<&'a NullIndex> {
+    field_indexes
+        .get(key)?
+        .iter()
+        .find_map(|index| match index {
+            FieldIndex::NullIndex(null_index) => Some(null_index),
+            _ => None,
+        })
+}
+
 /// Get a checker that checks if the field is empty
 ///
 /// * `index` - index to check first
@@ -1,4 +1,5 @@
 use std::cmp::Reverse;
+use std::collections::HashMap;
 
 use itertools::Itertools;
 
@@ -6,12 +7,16 @@ use crate::common::utils::IndexesMap;
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::CardinalityEstimation;
 use crate::index::query_estimator::{
-    combine_must_estimations, combine_should_estimations, invert_estimation,
+    combine_min_should_estimations, combine_must_estimations, combine_should_estimations,
+    invert_estimation,
 };
 use crate::index::query_optimization::condition_converter::condition_converter;
-use crate::index::query_optimization::optimized_filter::{OptimizedCondition, OptimizedFilter};
+use crate::index::query_optimization::optimized_filter::{
+    OptimizedCondition, OptimizedFilter, OptimizedMinShould,
+};
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::types::{Condition, Filter};
+use crate::vector_storage::VectorStorageRef;
 
 /// Converts user-provided filtering condition into optimized representation
 ///
@@ -27,6 +32,7 @@ use crate::types::{Condition, Filter};
 ///
 /// * `filter` - original filter
 /// * `id_tracker` - used for converting collection-level ids into segment-level offsets of HasId condition
+/// * `vector_storages` - used for checking HasVector condition
 /// * `estimator` - function to estimate cardinality of individual conditions
 /// * `total` - total number of points in segment (used for cardinality estimation)
 ///
@@ -37,6 +43,7 @@ pub fn optimize_filter<'a, F>(
     filter: &'a Filter,
     id_tracker: &IdTrackerSS,
     field_indexes: &'a IndexesMap,
+    vector_storages: &'a HashMap<String, VectorStorageRef>,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
@@ -53,6 +60,7 @@ where
                     conditions,
                     id_tracker,
                     field_indexes,
+                    vector_storages,
                     payload_provider.clone(),
                     estimator,
                     total,
@@ -63,12 +71,34 @@ where
                 None
             }
         }),
+        min_should: filter.min_should.as_ref().and_then(|min_should| {
+            if !min_should.conditions.is_empty() {
+                let (optimized_conditions, estimation) = optimize_min_should(
+                    &min_should.conditions,
+                    min_should.min_count,
+                    id_tracker,
+                    field_indexes,
+                    vector_storages,
+                    payload_provider.clone(),
+                    estimator,
+                    total,
+                );
+                filter_estimations.push(estimation);
+                Some(OptimizedMinShould {
+                    conditions: optimized_conditions,
+                    min_count: min_should.min_count,
+                })
+            } else {
+                None
+            }
+        }),
         must: filter.must.as_ref().and_then(|conditions| {
             if !conditions.is_empty() {
                 let (optimized_conditions, estimation) = optimize_must(
                     conditions,
                     id_tracker,
                     field_indexes,
+                    vector_storages,
                     payload_provider.clone(),
                     estimator,
                     total,
@@ -85,6 +115,7 @@ where
                     conditions,
                     id_tracker,
                     field_indexes,
+                    vector_storages,
                     payload_provider.clone(),
                     estimator,
                     total,
@@ -107,6 +138,7 @@ fn convert_conditions<'a, F>(
     conditions: &'a [Condition],
     id_tracker: &IdTrackerSS,
     field_indexes: &'a IndexesMap,
+    vector_storages: &'a HashMap<String, VectorStorageRef>,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
@@ -122,6 +154,7 @@ where
                     filter,
                     id_tracker,
                     field_indexes,
+                    vector_storages,
                     payload_provider.clone(),
                     estimator,
                     total,
@@ -133,6 +166,7 @@ where
                 let condition_checker = condition_converter(
                     condition,
                     field_indexes,
+                    vector_storages,
                     payload_provider.clone(),
                     id_tracker,
                 );
@@ -146,6 +180,7 @@ fn optimize_should<'a, F>(
     conditions: &'a [Condition],
     id_tracker: &IdTrackerSS,
     field_indexes: &'a IndexesMap,
+    vector_storages: &'a HashMap<String, VectorStorageRef>,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
@@ -157,6 +192,7 @@ where
         conditions,
         id_tracker,
         field_indexes,
+        vector_storages,
         payload_provider,
         estimator,
         total,
@@ -168,10 +204,44 @@ where
     (conditions, combine_should_estimations(&estimations, total))
 }
 
+#[allow(clippy::too_many_arguments)]
+fn optimize_min_should<'a, F>(
+    conditions: &'a [Condition],
+    min_count: usize,
+    id_tracker: &IdTrackerSS,
+    field_indexes: &'a IndexesMap,
+    vector_storages: &'a HashMap<String, VectorStorageRef>,
+    payload_provider: PayloadProvider,
+    estimator: &F,
+    total: usize,
+) -> (Vec<OptimizedCondition<'a>>, CardinalityEstimation)
+where
+    F: Fn(&Condition) -> CardinalityEstimation,
+{
+    let mut converted = convert_conditions(
+        conditions,
+        id_tracker,
+        field_indexes,
+        vector_storages,
+        payload_provider,
+        estimator,
+        total,
+    );
+    // More probable conditions first, so enough of them are matched sooner
+    converted.sort_by_key(|(_, estimation)| Reverse(estimation.exp));
+    let (conditions, estimations): (Vec<_>, Vec<_>) = converted.into_iter().unzip();
+
+    (
+        conditions,
+        combine_min_should_estimations(&estimations, min_count, total),
+    )
+}
+
 fn optimize_must<'a, F>(
     conditions: &'a [Condition],
     id_tracker: &IdTrackerSS,
     field_indexes: &'a IndexesMap,
+    vector_storages: &'a HashMap<String, VectorStorageRef>,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
@@ -183,6 +253,7 @@ where
         conditions,
         id_tracker,
         field_indexes,
+        vector_storages,
         payload_provider,
         estimator,
         total,
@@ -198,6 +269,7 @@ fn optimize_must_not<'a, F>(
     conditions: &'a [Condition],
     id_tracker: &IdTrackerSS,
     field_indexes: &'a IndexesMap,
+    vector_storages: &'a HashMap<String, VectorStorageRef>,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
@@ -209,6 +281,7 @@ where
         conditions,
         id_tracker,
         field_indexes,
+        vector_storages,
         payload_provider,
         estimator,
         total,
@@ -0,0 +1 @@
+pub mod sparse_vector_index;
@@ -0,0 +1,272 @@
+use std::path::PathBuf;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use common::fixed_length_priority_queue::FixedLengthPriorityQueue;
+use common::types::{PointOffsetType, ScoredPointOffset};
+use parking_lot::Mutex;
+use sparse::common::sparse_vector::SparseVector;
+use sparse::index::inverted_index::inverted_index_ram::InvertedIndexRam;
+use sparse::index::inverted_index::InvertedIndex;
+use sparse::index::search_context::SearchContext;
+
+use crate::common::full_scan_tracker::mark_full_scan;
+use crate::common::operation_error::{check_process_stopped, OperationResult};
+use crate::common::operation_time_statistics::{
+    OperationDurationStatistics, OperationDurationsAggregator, ScopeDurationMeasurer,
+};
+use crate::common::search_explain::{
+    explain_vector_search, ExplainMode, SearchStrategy, VectorSearchExplain,
+};
+use crate::data_types::vectors::QueryVector;
+use crate::id_tracker::IdTrackerSS;
+use crate::index::struct_payload_index::StructPayloadIndex;
+use crate::index::{PayloadIndex, VectorIndex};
+use crate::telemetry::VectorIndexSearchesTelemetry;
+use crate::types::{Filter, SearchParams, SparseIndexConfig, DEFAULT_FULL_SCAN_THRESHOLD};
+use crate::vector_storage::simple_sparse_vector_storage::SimpleSparseVectorStorage;
+
+/// In-memory inverted index over sparse vectors.
+///
+/// Unlike dense vector indexes, it is kept up to date on every update,
+/// so appendable segments can use it right away.
+pub struct SparseVectorIndex {
+    config: SparseIndexConfig,
+    id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+    vector_storage: Arc<AtomicRefCell<SimpleSparseVectorStorage>>,
+    payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
+    inverted_index: InvertedIndex,
+    unfiltered_searches_telemetry: Arc<Mutex<OperationDurationsAggregator>>,
+    small_cardinality_searches_telemetry: Arc<Mutex<OperationDurationsAggregator>>,
+    large_cardinality_searches_telemetry: Arc<Mutex<OperationDurationsAggregator>>,
+}
+
+impl SparseVectorIndex {
+    /// Open index and fill it from the given storage
+    pub fn open(
+        config: SparseIndexConfig,
+        id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+        vector_storage: Arc<AtomicRefCell<SimpleSparseVectorStorage>>,
+        payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
+    ) -> OperationResult<Self> {
+        let mut index = SparseVectorIndex {
+            config,
+            id_tracker,
+            vector_storage,
+            payload_index,
+            inverted_index: InvertedIndex::Ram(InvertedIndexRam::default()),
+            unfiltered_searches_telemetry: OperationDurationsAggregator::new(),
+            small_cardinality_searches_telemetry: OperationDurationsAggregator::new(),
+            large_cardinality_searches_telemetry: OperationDurationsAggregator::new(),
+        };
+        index.build_index(&AtomicBool::new(false))?;
+        Ok(index)
+    }
+
+    pub fn vector_storage(&self) -> &Arc<AtomicRefCell<SimpleSparseVectorStorage>> {
+        &self.vector_storage
+    }
+
+    /// Store the vector and index it
+    pub fn upsert_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: &SparseVector,
+    ) -> OperationResult<()> {
+        let mut vector_storage = self.vector_storage.borrow_mut();
+        if !vector_storage.is_deleted_vector(key)
+            && (key as usize) < vector_storage.total_vector_count()
+        {
+            self.inverted_index
+                .remove(key, vector_storage.get_vector(key));
+        }
+        vector_storage.insert_vector(key, vector)?;
+        self.inverted_index
+            .upsert(key, vector_storage.get_vector(key));
+        Ok(())
+    }
+
+    /// Delete the vector and remove it from the index
+    pub fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
+        let mut vector_storage = self.vector_storage.borrow_mut();
+        if (key as usize) < vector_storage.total_vector_count()
+            && !vector_storage.is_deleted_vector(key)
+        {
+            self.inverted_index
+                .remove(key, vector_storage.get_vector(key));
+        }
+        vector_storage.delete_vector(key)
+    }
+
+    fn full_scan_threshold(&self) -> usize {
+        self.config
+            .full_scan_threshold
+            .unwrap_or(DEFAULT_FULL_SCAN_THRESHOLD)
+    }
+
+    /// Score the query against each of the given points directly
+    fn search_plain(
+        &self,
+        query: &SparseVector,
+        points: &mut dyn Iterator<Item = PointOffsetType>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> Vec<ScoredPointOffset> {
+        let vector_storage = self.vector_storage.borrow();
+        let id_tracker = self.id_tracker.borrow();
+        let mut query = query.clone();
+        query.sort_by_indices();
+
+        let mut result_queue = FixedLengthPriorityQueue::new(top);
+        for idx in points {
+            if check_process_stopped(is_stopped).is_err() {
+                break;
+            }
+            if id_tracker.is_deleted_point(idx) || vector_storage.is_deleted_vector(idx) {
+                continue;
+            }
+            if let Some(score) = vector_storage.get_vector(idx).score(&query) {
+                result_queue.push(ScoredPointOffset { score, idx });
+            }
+        }
+        result_queue.into_vec()
+    }
+
+    /// Search with the inverted index, unless `plain_search` is chosen for the filter
+    fn search_query(
+        &self,
+        query: &SparseVector,
+        filter: Option<&Filter>,
+        plain_search: bool,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> Vec<ScoredPointOffset> {
+        let vector_storage = self.vector_storage.borrow();
+        let id_tracker = self.id_tracker.borrow();
+        let is_available = |idx: PointOffsetType| {
+            !id_tracker.is_deleted_point(idx) && !vector_storage.is_deleted_vector(idx)
+        };
+
+        match filter {
+            None => {
+                let _timer = ScopeDurationMeasurer::new(&self.unfiltered_searches_telemetry);
+                SearchContext::new(query.clone(), top, &self.inverted_index).search(&is_available)
+            }
+            Some(filter) => {
+                let payload_index = self.payload_index.borrow();
+                if plain_search {
+                    let _timer =
+                        ScopeDurationMeasurer::new(&self.small_cardinality_searches_telemetry);
+                    mark_full_scan();
+                    let filtered_points = payload_index.query_points(filter);
+                    self.search_plain(query, &mut filtered_points.into_iter(), top, is_stopped)
+                } else {
+                    let _timer =
+                        ScopeDurationMeasurer::new(&self.large_cardinality_searches_telemetry);
+                    let filter_context = payload_index.filter_context(filter);
+                    SearchContext::new(query.clone(), top, &self.inverted_index)
+                        .search(&|idx| is_available(idx) && filter_context.check(idx))
+                }
+            }
+        }
+    }
+}
+
+impl VectorIndex for SparseVectorIndex {
+    fn search(
+        &self,
+        vectors: &[&QueryVector],
+        filter: Option<&Filter>,
+        top: usize,
+        _params: Option<&SearchParams>,
+        is_stopped: &AtomicBool,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        let cardinality =
+            filter.map(|filter| self.payload_index.borrow().estimate_cardinality(filter));
+        // Points, matching a filter of small cardinality, are scored directly
+        let plain_search = cardinality.as_ref().map_or(false, |cardinality| {
+            cardinality.max < self.full_scan_threshold()
+        });
+        let strategy = if plain_search {
+            SearchStrategy::FilteredScan
+        } else {
+            SearchStrategy::SparseIndex
+        };
+
+        explain_vector_search(
+            |mode| {
+                let explain =
+                    VectorSearchExplain::new(strategy, vectors.len(), cardinality.as_ref());
+                match mode {
+                    ExplainMode::Plan => explain,
+                    ExplainMode::Analyze => explain.analyze(
+                        filter.map(|filter| self.payload_index.borrow().query_points(filter).len()),
+                        self.vector_storage.borrow().available_vector_count(),
+                    ),
+                }
+            },
+            || {
+                vectors
+                    .iter()
+                    .map(|&vector| match vector {
+                        QueryVector::Sparse(query) => {
+                            self.search_query(query, filter, plain_search, top, is_stopped)
+                        }
+                        QueryVector::Nearest(_)
+                        | QueryVector::Recommend(_)
+                        | QueryVector::Discovery(_)
+                        | QueryVector::Context(_) => {
+                            debug_assert!(
+                                false,
+                                "Sparse index can only be queried with sparse vectors"
+                            );
+                            vec![]
+                        }
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    fn build_index(&mut self, stopped: &AtomicBool) -> OperationResult<()> {
+        let vector_storage = self.vector_storage.borrow();
+        let mut inverted_index = InvertedIndexRam::default();
+        for id in 0..vector_storage.total_vector_count() as PointOffsetType {
+            check_process_stopped(stopped)?;
+            if vector_storage.is_deleted_vector(id) {
+                continue;
+            }
+            inverted_index.upsert(id, vector_storage.get_vector(id));
+        }
+        self.inverted_index = InvertedIndex::Ram(inverted_index);
+        Ok(())
+    }
+
+    fn get_telemetry_data(&self) -> VectorIndexSearchesTelemetry {
+        VectorIndexSearchesTelemetry {
+            index_name: None,
+            unfiltered_plain: self.unfiltered_searches_telemetry.lock().get_statistics(),
+            filtered_plain: OperationDurationStatistics::default(),
+            unfiltered_hnsw: OperationDurationStatistics::default(),
+            filtered_small_cardinality: self
+                .small_cardinality_searches_telemetry
+                .lock()
+                .get_statistics(),
+            filtered_large_cardinality: self
+                .large_cardinality_searches_telemetry
+                .lock()
+                .get_statistics(),
+            filtered_exact: OperationDurationStatistics::default(),
+            unfiltered_exact: OperationDurationStatistics::default(),
+        }
+    }
+
+    fn files(&self) -> Vec<PathBuf> {
+        vec![]
+    }
+
+    fn indexed_vector_count(&self) -> usize {
+        self.vector_storage.borrow().available_vector_count()
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use common::types::PointOffsetType;
 
 use crate::common::utils::IndexesMap;
@@ -8,6 +10,7 @@ use crate::index::query_optimization::optimizer::optimize_filter;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::payload_storage::FilterContext;
 use crate::types::{Condition, Filter};
+use crate::vector_storage::VectorStorageRef;
 
 pub struct StructFilterContext<'a> {
     optimized_filter: OptimizedFilter<'a>,
@@ -19,6 +22,7 @@ impl<'a> StructFilterContext<'a> {
         id_tracker: &IdTrackerSS,
         payload_provider: PayloadProvider,
         field_indexes: &'a IndexesMap,
+        vector_storages: &'a HashMap<String, VectorStorageRef>,
         estimator: &F,
         total: usize,
     ) -> Self
@@ -29,6 +33,7 @@ impl<'a> StructFilterContext<'a> {
             filter,
             id_tracker,
             field_indexes,
+            vector_storages,
             payload_provider,
             estimator,
             total,
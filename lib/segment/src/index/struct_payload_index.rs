@@ -5,7 +5,7 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
-use common::types::PointOffsetType;
+use common::types::{PointOffsetType, ScoreType};
 use log::debug;
 use parking_lot::RwLock;
 use rocksdb::DB;
@@ -14,15 +14,19 @@ use schemars::_serde_json::Value;
 use crate::common::arc_atomic_ref_cell_iterator::ArcAtomicRefCellIterator;
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
-use crate::common::utils::{IndexesMap, JsonPathPayload, MultiValue};
+use crate::common::utils::{check_include_pattern, IndexesMap, JsonPathPayload, MultiValue};
 use crate::common::Flusher;
+use crate::data_types::order_by::{Direction, OrderBy, OrderValue};
 use crate::id_tracker::IdTrackerSS;
+use crate::index::field_index::composite_index::CompositeLookup;
 use crate::index::field_index::index_selector::index_selector;
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::index::field_index::{
     CardinalityEstimation, FieldIndex, PayloadBlockCondition, PrimaryCondition,
 };
 use crate::index::payload_config::PayloadConfig;
-use crate::index::query_estimator::estimate_filter;
+use crate::index::query_estimator::{combine_must_estimations, estimate_filter};
+use crate::index::query_optimization::condition_converter::get_null_index;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::index::struct_filter_context::StructFilterContext;
 use crate::index::visited_pool::VisitedPool;
@@ -35,6 +39,7 @@ use crate::types::{
     IsEmptyCondition, IsNullCondition, Payload, PayloadContainer, PayloadField, PayloadFieldSchema,
     PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType,
 };
+use crate::vector_storage::VectorStorageRef;
 
 pub const PAYLOAD_FIELD_INDEX_PATH: &str = "fields";
 
@@ -44,6 +49,8 @@ pub struct StructPayloadIndex {
     payload: Arc<AtomicRefCell<PayloadStorageEnum>>,
     /// Used for `has_id` condition and estimating cardinality
     id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+    /// Storages of named vectors, used for `has_vector` condition
+    vector_storages: HashMap<String, VectorStorageRef>,
     /// Indexes, associated with fields
     pub field_indexes: IndexesMap,
     config: PayloadConfig,
@@ -115,7 +122,13 @@ impl StructPayloadIndex {
         payload_schema: PayloadFieldSchema,
         is_appendable: bool,
     ) -> OperationResult<Vec<FieldIndex>> {
-        let mut indexes = index_selector(field, &payload_schema, self.db.clone(), is_appendable);
+        let mut indexes = index_selector(
+            field,
+            &payload_schema,
+            self.db.clone(),
+            &self.path,
+            is_appendable,
+        );
 
         let mut is_loaded = true;
         for ref mut index in indexes.iter_mut() {
@@ -153,6 +166,7 @@ impl StructPayloadIndex {
         let mut index = StructPayloadIndex {
             payload,
             id_tracker,
+            vector_storages: Default::default(),
             field_indexes: Default::default(),
             config,
             path: path.to_owned(),
@@ -170,21 +184,31 @@ impl StructPayloadIndex {
         Ok(index)
     }
 
+    /// Register storage of a named vector, so it can be checked by `has_vector` condition
+    pub fn add_vector_storage(&mut self, vector_name: &str, vector_storage: VectorStorageRef) {
+        self.vector_storages
+            .insert(vector_name.to_owned(), vector_storage);
+    }
+
+    pub fn remove_vector_storage(&mut self, vector_name: &str) {
+        self.vector_storages.remove(vector_name);
+    }
+
     pub fn build_field_indexes(
         &self,
         field: PayloadKeyTypeRef,
         payload_schema: PayloadFieldSchema,
     ) -> OperationResult<Vec<FieldIndex>> {
         let payload_storage = self.payload.borrow();
-        let mut field_indexes = index_selector(field, &payload_schema, self.db.clone(), true);
+        let mut field_indexes =
+            index_selector(field, &payload_schema, self.db.clone(), &self.path, true);
         for index in &field_indexes {
             index.recreate()?;
         }
 
         payload_storage.iter(|point_id, point_payload| {
-            let field_value = &point_payload.get_value(field);
             for field_index in field_indexes.iter_mut() {
-                field_index.add_point(point_id, field_value)?;
+                field_index.add_payload(point_id, field, point_payload)?;
             }
             Ok(true)
         })?;
@@ -217,6 +241,7 @@ impl StructPayloadIndex {
             id_tracker.deref(),
             payload_provider,
             &self.field_indexes,
+            &self.vector_storages,
             &estimator,
             self.available_point_count(),
         )
@@ -239,6 +264,21 @@ impl StructPayloadIndex {
                 let full_path = JsonPathPayload::extend_or_new(nested_path, &field.key);
                 let full_path = full_path.path;
 
+                // Values of nested objects are not distinguished by the null index
+                let null_index = nested_path
+                    .is_none()
+                    .then(|| get_null_index(&self.field_indexes, &full_path))
+                    .flatten();
+                if let Some(null_index) = null_index {
+                    let empty_points =
+                        available_points.saturating_sub(null_index.non_empty_count());
+                    return CardinalityEstimation::exact(empty_points).with_primary_clause(
+                        PrimaryCondition::IsEmpty(IsEmptyCondition {
+                            is_empty: PayloadField { key: full_path },
+                        }),
+                    );
+                }
+
                 let mut indexed_points = 0;
                 if let Some(field_indexes) = self.field_indexes.get(&full_path) {
                     for index in field_indexes {
@@ -268,6 +308,17 @@ impl StructPayloadIndex {
                 let full_path = JsonPathPayload::extend_or_new(nested_path, &field.key);
                 let full_path = full_path.path;
 
+                let null_index = nested_path
+                    .is_none()
+                    .then(|| get_null_index(&self.field_indexes, &full_path))
+                    .flatten();
+                if let Some(null_index) = null_index {
+                    return CardinalityEstimation::exact(null_index.null_count())
+                        .with_primary_clause(PrimaryCondition::IsNull(IsNullCondition {
+                            is_null: PayloadField { key: full_path },
+                        }));
+                }
+
                 let mut indexed_points = 0;
                 if let Some(field_indexes) = self.field_indexes.get(&full_path) {
                     for index in field_indexes {
@@ -307,12 +358,153 @@ impl StructPayloadIndex {
                     max: num_ids,
                 }
             }
+            Condition::HasVector(has_vector) => {
+                match self.vector_storages.get(&has_vector.has_vector) {
+                    Some(vector_storage) => {
+                        // Vectors of the deleted points may still be counted by the storage
+                        let vectors_count = vector_storage
+                            .available_vector_count()
+                            .min(self.available_point_count());
+                        CardinalityEstimation {
+                            primary_clauses: vec![],
+                            min: 0,
+                            exp: vectors_count,
+                            max: vectors_count,
+                        }
+                    }
+                    None => CardinalityEstimation::exact(0),
+                }
+            }
             Condition::Field(field_condition) => self
                 .estimate_field_condition(field_condition, nested_path)
                 .unwrap_or_else(|| CardinalityEstimation::unknown(self.available_point_count())),
         }
     }
 
+    /// Replaces exact matches in `must` of the filter with a lookup in a composite index.
+    ///
+    /// Returns estimation of the lookup and the rest of the filter, if some composite index
+    /// covers the conditions. The most selective lookup is chosen among several indexes.
+    fn composite_lookup(&self, filter: &Filter) -> Option<(CardinalityEstimation, Filter)> {
+        let conditions = filter.must.as_ref()?;
+        let (estimation, positions) = self
+            .field_indexes
+            .iter()
+            .flat_map(|(name, indexes)| indexes.iter().map(move |index| (name, index)))
+            .filter_map(|(name, index)| match index {
+                FieldIndex::CompositeIndex(composite_index) => {
+                    let (key, positions) = composite_index.condition_key(conditions)?;
+                    let lookup = CompositeLookup {
+                        index: name.clone(),
+                        key,
+                    };
+                    Some((composite_index.lookup_cardinality(lookup), positions))
+                }
+                _ => None,
+            })
+            .min_by_key(|(estimation, _)| estimation.exp)?;
+
+        let rest = conditions
+            .iter()
+            .enumerate()
+            .filter(|(position, _)| !positions.contains(position))
+            .map(|(_, condition)| condition.clone())
+            .collect();
+        let rest_filter = Filter {
+            must: Some(rest),
+            ..filter.clone()
+        };
+        Some((estimation, rest_filter))
+    }
+
+    fn query_composite(
+        &self,
+        lookup: &CompositeLookup,
+    ) -> Option<Box<dyn Iterator<Item = PointOffsetType> + '_>> {
+        self.field_indexes.get(&lookup.index).and_then(|indexes| {
+            indexes.iter().find_map(|index| match index {
+                FieldIndex::CompositeIndex(composite_index) => {
+                    Some(composite_index.lookup(&lookup.key))
+                }
+                _ => None,
+            })
+        })
+    }
+
+    /// Composite indexes depend on several fields, so they are updated from the stored payload
+    fn update_composite_indexes(&mut self, point_id: PointOffsetType) -> OperationResult<()> {
+        let has_composite_indexes = self
+            .field_indexes
+            .values()
+            .flatten()
+            .any(|index| matches!(index, FieldIndex::CompositeIndex(_)));
+        if !has_composite_indexes {
+            return Ok(());
+        }
+
+        let payload = self.payload.borrow().payload(point_id)?;
+        for index in self.field_indexes.values_mut().flatten() {
+            if let FieldIndex::CompositeIndex(composite_index) = index {
+                composite_index.add_payload(point_id, &payload)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// BM25 relevance of points to the text, requires a full-text index on the field
+    pub fn score_text(
+        &self,
+        field: PayloadKeyTypeRef,
+        text: &str,
+    ) -> OperationResult<HashMap<PointOffsetType, ScoreType>> {
+        self.field_indexes
+            .get(field)
+            .and_then(|indexes| {
+                indexes.iter().find_map(|index| match index {
+                    FieldIndex::FullTextIndex(full_text_index) => {
+                        Some(full_text_index.score_bm25(text))
+                    }
+                    _ => None,
+                })
+            })
+            .ok_or_else(|| OperationError::ValidationError {
+                description: format!("Text query requires a full-text index on field {field}"),
+            })
+    }
+
+    /// Points in the order of values of the `order_by` key, requires a numeric or datetime index.
+    /// Points with multiple values appear once per value.
+    pub fn iter_ordered<'a>(
+        &'a self,
+        order_by: &OrderBy,
+    ) -> OperationResult<Box<dyn Iterator<Item = (OrderValue, PointOffsetType)> + 'a>> {
+        let range = order_by.value_range();
+        let stream = self
+            .field_indexes
+            .get(&order_by.key)
+            .and_then(|indexes| indexes.iter().find_map(|index| index.stream_range(&range)))
+            .ok_or_else(|| OperationError::ValidationError {
+                description: format!(
+                    "Ordering by {} requires an integer, float or datetime index with range support",
+                    order_by.key,
+                ),
+            })?;
+        Ok(match order_by.direction() {
+            Direction::Asc => Box::new(stream),
+            Direction::Desc => Box::new(stream.rev()),
+        })
+    }
+
+    /// Statistics of all indexes of the field combined
+    pub fn get_field_index_stats(&self, key: PayloadKeyTypeRef) -> Option<FieldIndexStats> {
+        let indexes = self.field_indexes.get(key)?;
+        let mut stats = FieldIndexStats::default();
+        for index in indexes {
+            stats.combine(index.get_stats());
+        }
+        Some(stats)
+    }
+
     pub fn get_telemetry_data(&self) -> Vec<PayloadIndexTelemetry> {
         self.field_indexes
             .iter()
@@ -377,7 +569,13 @@ impl PayloadIndex for StructPayloadIndex {
     fn estimate_cardinality(&self, query: &Filter) -> CardinalityEstimation {
         let available_points = self.available_point_count();
         let estimator = |condition: &Condition| self.condition_cardinality(condition, None);
-        estimate_filter(&estimator, query, available_points)
+        match self.composite_lookup(query) {
+            Some((lookup_estimation, rest_filter)) => {
+                let rest_estimation = estimate_filter(&estimator, &rest_filter, available_points);
+                combine_must_estimations(&[lookup_estimation, rest_estimation], available_points)
+            }
+            None => estimate_filter(&estimator, query, available_points),
+        }
     }
 
     fn estimate_nested_cardinality(
@@ -429,8 +627,16 @@ impl PayloadIndex for StructPayloadIndex {
                             )
                         }
                         PrimaryCondition::Ids(ids) => Box::new(ids.iter().copied()),
+                        PrimaryCondition::Composite(lookup) => self
+                            .query_composite(lookup)
+                            .unwrap_or_else(|| points_iterator_ref.iter_ids()),
                         PrimaryCondition::IsEmpty(_) => points_iterator_ref.iter_ids(), /* there are no fast index for IsEmpty */
-                        PrimaryCondition::IsNull(_) => points_iterator_ref.iter_ids(),  /* no fast index for IsNull too */
+                        PrimaryCondition::IsNull(IsNullCondition { is_null: field }) => {
+                            match get_null_index(&self.field_indexes, &field.key) {
+                                Some(null_index) => Box::new(null_index.iter_null()),
+                                None => points_iterator_ref.iter_ids(),
+                            }
+                        }
                     }
                 })
                 .filter(|&id| !visited_list.check_and_update_visited(id))
@@ -483,7 +689,8 @@ impl PayloadIndex for StructPayloadIndex {
                 index.add_point(point_id, field_value)?;
             }
         }
-        self.payload.borrow_mut().assign(point_id, payload)
+        self.payload.borrow_mut().assign(point_id, payload)?;
+        self.update_composite_indexes(point_id)
     }
 
     fn payload(&self, point_id: PointOffsetType) -> OperationResult<Payload> {
@@ -495,12 +702,24 @@ impl PayloadIndex for StructPayloadIndex {
         point_id: PointOffsetType,
         key: PayloadKeyTypeRef,
     ) -> OperationResult<Vec<Value>> {
-        if let Some(indexes) = self.field_indexes.get_mut(key) {
-            for index in indexes {
+        let deleted = self.payload.borrow_mut().delete(point_id, key)?;
+        if deleted.is_empty() {
+            return Ok(deleted);
+        }
+        // Key may be a prefix or a wildcard path, so nested indexed fields are re-indexed too
+        let payload = self.payload.borrow().payload(point_id)?;
+        for (field, field_index) in &mut self.field_indexes {
+            if !check_include_pattern(key, field) {
+                continue;
+            }
+            let field_value = &payload.get_value(field);
+            for index in field_index {
                 index.remove_point(point_id)?;
+                index.add_point(point_id, field_value)?;
             }
         }
-        self.payload.borrow_mut().delete(point_id, key)
+        self.update_composite_indexes(point_id)?;
+        Ok(deleted)
     }
 
     fn drop(&mut self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
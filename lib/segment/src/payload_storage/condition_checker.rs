@@ -3,8 +3,9 @@
 use serde_json::Value;
 
 use crate::types::{
-    AnyVariants, FieldCondition, GeoBoundingBox, GeoPoint, GeoPolygon, GeoRadius, Match, MatchAny,
-    MatchExcept, MatchText, MatchValue, Range, ValueVariants, ValuesCount,
+    parse_datetime, AnyVariants, DatetimeRange, FieldCondition, GeoBoundingBox, GeoPoint,
+    GeoPolygon, GeoRadius, Match, MatchAny, MatchExcept, MatchPhrase, MatchText, MatchValue, Range,
+    ValueVariants, ValuesCount,
 };
 
 pub trait ValueChecker {
@@ -37,6 +38,11 @@ impl ValueChecker for FieldCondition {
                 .range
                 .as_ref()
                 .map_or(false, |condition| condition.check_match(payload));
+        res = res
+            || self
+                .datetime_range
+                .as_ref()
+                .map_or(false, |condition| condition.check_match(payload));
         res = res
             || self
                 .geo_radius
@@ -84,6 +90,10 @@ impl ValueChecker for Match {
                 Value::String(stored) => stored.contains(text),
                 _ => false,
             },
+            Match::Phrase(MatchPhrase { phrase }) => match payload {
+                Value::String(stored) => stored.contains(phrase),
+                _ => false,
+            },
             Match::Any(MatchAny { any }) => match (payload, any) {
                 (Value::String(stored), AnyVariants::Keywords(list)) => list.contains(stored),
                 (Value::Number(stored), AnyVariants::Integers(list)) => stored
@@ -105,6 +115,14 @@ impl ValueChecker for Match {
                 (Value::Number(_), _) => true,
                 (Value::String(_), _) => true,
             },
+            Match::Wildcard(wildcard) => match payload {
+                Value::String(stored) => wildcard.is_match(stored),
+                _ => false,
+            },
+            Match::Regex(regex) => match payload {
+                Value::String(stored) => regex.is_match(stored),
+                _ => false,
+            },
         }
     }
 }
@@ -121,6 +139,17 @@ impl ValueChecker for Range {
     }
 }
 
+impl ValueChecker for DatetimeRange {
+    fn check_match(&self, payload: &Value) -> bool {
+        match payload {
+            Value::String(datetime) => parse_datetime(datetime)
+                .map(|datetime| self.check_range(datetime))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
 impl ValueChecker for GeoBoundingBox {
     fn check_match(&self, payload: &Value) -> bool {
         match payload {
@@ -256,4 +285,26 @@ mod tests {
         };
         assert!(gte_two_countries_query.check(&countries));
     }
+
+    #[test]
+    fn test_datetime_range() {
+        let timestamps = json!(["2023-01-01T00:00:00Z", "2023-06-15T12:30:00+02:00"]);
+
+        let summer_query = DatetimeRange {
+            gte: parse_datetime("2023-06-15T10:30:00Z"),
+            lt: parse_datetime("2023-09-01T00:00:00Z"),
+            ..Default::default()
+        };
+        assert!(summer_query.check(&timestamps));
+
+        let before_query = DatetimeRange {
+            lt: parse_datetime("2023-01-01T00:00:00Z"),
+            ..Default::default()
+        };
+        assert!(!before_query.check(&timestamps));
+
+        // Only RFC 3339 strings are datetime values
+        assert!(!summer_query.check(&json!("2023-07-01")));
+        assert!(!summer_query.check(&json!(1688169600)));
+    }
 }
@@ -5,6 +5,7 @@ use parking_lot::RwLock;
 use rocksdb::DB;
 use serde_json::Value;
 
+use crate::common::hardware_counter::add_payload_bytes_read;
 use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::rocksdb_wrapper::{DatabaseColumnWrapper, DB_PAYLOAD_CF};
 use crate::common::Flusher;
@@ -42,7 +43,10 @@ impl OnDiskPayloadStorage {
     pub fn read_payload(&self, point_id: PointOffsetType) -> OperationResult<Option<Payload>> {
         let key = serde_cbor::to_vec(&point_id).unwrap();
         self.db_wrapper
-            .get_pinned(&key, |raw| serde_cbor::from_slice(raw))?
+            .get_pinned(&key, |raw| {
+                add_payload_bytes_read(raw.len());
+                serde_cbor::from_slice(raw)
+            })?
             .transpose()
             .map_err(OperationError::from)
     }
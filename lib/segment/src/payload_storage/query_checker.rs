@@ -13,9 +13,10 @@ use crate::payload_storage::condition_checker::ValueChecker;
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::payload_storage::ConditionChecker;
 use crate::types::{
-    Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, OwnedPayloadRef, Payload,
-    PayloadContainer, PayloadKeyType,
+    Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, MinShould,
+    OwnedPayloadRef, Payload, PayloadContainer, PayloadKeyType,
 };
+use crate::vector_storage::VectorStorageRef;
 
 fn check_condition<F>(checker: &F, condition: &Condition) -> bool
 where
@@ -32,6 +33,7 @@ where
     F: Fn(&Condition) -> bool,
 {
     check_should(checker, &filter.should)
+        && check_min_should(checker, &filter.min_should)
         && check_must(checker, &filter.must)
         && check_must_not(checker, &filter.must_not)
 }
@@ -47,6 +49,27 @@ where
     }
 }
 
+fn check_min_should<F>(checker: &F, min_should: &Option<MinShould>) -> bool
+where
+    F: Fn(&Condition) -> bool,
+{
+    let check = |x| check_condition(checker, x);
+    match min_should {
+        None => true,
+        Some(MinShould {
+            conditions,
+            min_count,
+        }) => {
+            conditions
+                .iter()
+                .filter(|x| check(x))
+                .take(*min_count)
+                .count()
+                == *min_count
+        }
+    }
+}
+
 fn check_must<F>(checker: &F, must: &Option<Vec<Condition>>) -> bool
 where
     F: Fn(&Condition) -> bool,
@@ -90,6 +113,7 @@ where
 pub fn check_payload<'a, R>(
     get_payload: Box<dyn Fn() -> OwnedPayloadRef<'a> + 'a>,
     id_tracker: Option<&IdTrackerSS>,
+    vector_storages: Option<&HashMap<String, VectorStorageRef>>,
     query: &Filter,
     point_id: PointOffsetType,
     field_indexes: &HashMap<PayloadKeyType, R>,
@@ -106,6 +130,9 @@ where
         Condition::HasId(has_id) => id_tracker
             .and_then(|id_tracker| id_tracker.external_id(point_id))
             .map_or(false, |id| has_id.has_id.contains(&id)),
+        Condition::HasVector(has_vector) => vector_storages
+            .and_then(|vector_storages| vector_storages.get(&has_vector.has_vector))
+            .map_or(false, |vector_storage| vector_storage.has_vector(point_id)),
         Condition::Nested(nested) => {
             let nested_path = nested.array_key();
             let nested_indexes = select_nested_indexes(&nested_path, field_indexes);
@@ -118,6 +145,7 @@ where
                     check_payload(
                         Box::new(|| OwnedPayloadRef::from(object)),
                         None,
+                        None,
                         &nested.nested.filter,
                         point_id,
                         &nested_indexes,
@@ -187,6 +215,7 @@ where
 pub struct SimpleConditionChecker {
     payload_storage: Arc<AtomicRefCell<PayloadStorageEnum>>,
     id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+    vector_storages: HashMap<String, VectorStorageRef>,
     empty_payload: Payload,
 }
 
@@ -194,10 +223,12 @@ impl SimpleConditionChecker {
     pub fn new(
         payload_storage: Arc<AtomicRefCell<PayloadStorageEnum>>,
         id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+        vector_storages: HashMap<String, VectorStorageRef>,
     ) -> Self {
         SimpleConditionChecker {
             payload_storage,
             id_tracker,
+            vector_storages,
             empty_payload: Default::default(),
         }
     }
@@ -246,6 +277,7 @@ impl ConditionChecker for SimpleConditionChecker {
                 payload_ref_cell.borrow().as_ref().cloned().unwrap()
             }),
             Some(id_tracker.deref()),
+            Some(&self.vector_storages),
             query,
             point_id,
             &IndexesMap::new(),
@@ -267,8 +299,10 @@ mod tests {
     use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
     use crate::payload_storage::PayloadStorage;
     use crate::types::{
-        FieldCondition, GeoBoundingBox, GeoPoint, PayloadField, Range, ValuesCount,
+        Distance, FieldCondition, GeoBoundingBox, GeoPoint, PayloadField, Range, ValuesCount,
     };
+    use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
+    use crate::vector_storage::VectorStorage;
 
     #[test]
     fn test_condition_checker() {
@@ -294,6 +328,8 @@ mod tests {
 
         let mut payload_storage: PayloadStorageEnum =
             SimplePayloadStorage::open(db.clone()).unwrap().into();
+        let vector_storage =
+            open_simple_vector_storage(db.clone(), DB_VECTOR_CF, 4, Distance::Dot).unwrap();
         let mut id_tracker = SimpleIdTracker::open(db).unwrap();
 
         id_tracker.set_link(0.into(), 0).unwrap();
@@ -302,9 +338,19 @@ mod tests {
         id_tracker.set_link(10.into(), 10).unwrap();
         payload_storage.assign_all(0, &payload).unwrap();
 
+        {
+            let mut vector_storage = vector_storage.borrow_mut();
+            vector_storage.insert_vector(0, &[1.0; 4]).unwrap();
+            vector_storage.insert_vector(1, &[1.0; 4]).unwrap();
+            vector_storage.delete_vector(1).unwrap();
+        }
+        let vector_storages =
+            HashMap::from([("image".to_string(), VectorStorageRef::Dense(vector_storage))]);
+
         let payload_checker = SimpleConditionChecker::new(
             Arc::new(AtomicRefCell::new(payload_storage)),
             Arc::new(AtomicRefCell::new(id_tracker)),
+            vector_storages,
         );
 
         let is_empty_condition = Filter::new_must(Condition::IsEmpty(IsEmptyCondition {
@@ -445,8 +491,21 @@ mod tests {
             },
         ));
 
+        let query = Filter::new_min_should(MinShould {
+            conditions: vec![match_red.clone(), match_blue.clone(), in_berlin.clone()],
+            min_count: 2,
+        });
+        assert!(payload_checker.check(0, &query));
+
+        let query = Filter::new_min_should(MinShould {
+            conditions: vec![match_red.clone(), match_blue.clone(), in_moscow.clone()],
+            min_count: 2,
+        });
+        assert!(!payload_checker.check(0, &query));
+
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![match_red.clone()]),
             must_not: None,
         };
@@ -454,6 +513,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![match_blue.clone()]),
             must_not: None,
         };
@@ -461,6 +521,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![match_blue.clone()]),
         };
@@ -468,6 +529,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![match_red.clone()]),
         };
@@ -475,6 +537,7 @@ mod tests {
 
         let query = Filter {
             should: Some(vec![match_red.clone(), match_blue.clone()]),
+            min_should: None,
             must: Some(vec![with_delivery.clone(), in_berlin.clone()]),
             must_not: None,
         };
@@ -482,6 +545,7 @@ mod tests {
 
         let query = Filter {
             should: Some(vec![match_red.clone(), match_blue.clone()]),
+            min_should: None,
             must: Some(vec![with_delivery, in_moscow.clone()]),
             must_not: None,
         };
@@ -491,15 +555,18 @@ mod tests {
             should: Some(vec![
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![match_red.clone(), in_moscow.clone()]),
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![match_blue.clone(), in_berlin.clone()]),
                     must_not: None,
                 }),
             ]),
+            min_should: None,
             must: None,
             must_not: None,
         };
@@ -509,15 +576,18 @@ mod tests {
             should: Some(vec![
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![match_blue, in_moscow]),
                     must_not: None,
                 }),
                 Condition::Filter(Filter {
                     should: None,
+                    min_should: None,
                     must: Some(vec![match_red, in_berlin]),
                     must_not: None,
                 }),
             ]),
+            min_should: None,
             must: None,
             must_not: None,
         };
@@ -525,6 +595,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![with_bad_rating]),
         };
@@ -534,6 +605,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![Condition::HasId(ids.into())]),
         };
@@ -543,6 +615,7 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![Condition::HasId(ids.into())]),
         };
@@ -552,9 +625,20 @@ mod tests {
 
         let query = Filter {
             should: None,
+            min_should: None,
             must: Some(vec![Condition::HasId(ids.into())]),
             must_not: None,
         };
         assert!(payload_checker.check(2, &query));
+
+        let has_vector = Filter::new_must(Condition::HasVector("image".to_string().into()));
+        assert!(payload_checker.check(0, &has_vector));
+        // Deleted vector
+        assert!(!payload_checker.check(1, &has_vector));
+        // Vector was never set
+        assert!(!payload_checker.check(10, &has_vector));
+
+        let has_vector = Filter::new_must(Condition::HasVector("text".to_string().into()));
+        assert!(!payload_checker.check(0, &has_vector));
     }
 }
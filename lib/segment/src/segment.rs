@@ -7,10 +7,13 @@ use std::sync::Arc;
 use std::thread::{self, JoinHandle};
 
 use atomic_refcell::AtomicRefCell;
-use common::types::{PointOffsetType, ScoredPointOffset};
+use chrono::{DateTime, Utc};
+use common::types::{PointOffsetType, ScoreType, ScoredPointOffset};
 use io::file_operations::{atomic_save_json, read_json};
 use memory::mmap_ops;
 use parking_lot::{Mutex, RwLock};
+use rand::seq::SliceRandom;
+use rand::Rng;
 use rocksdb::DB;
 use tar::Builder;
 use uuid::Uuid;
@@ -19,29 +22,42 @@ use crate::common::operation_error::OperationError::TypeInferenceError;
 use crate::common::operation_error::{
     get_service_error, OperationError, OperationResult, SegmentFailedState,
 };
+use crate::common::rocksdb_wrapper::{
+    create_db_cf_if_not_exists, DatabaseColumnWrapper, DB_VECTOR_CF,
+};
 use crate::common::version::{StorageVersion, VERSION_FILE};
 use crate::common::{
     check_named_vectors, check_query_vectors, check_stopped, check_vector, check_vector_name,
 };
 use crate::data_types::named_vectors::NamedVectors;
-use crate::data_types::vectors::{QueryVector, VectorElementType};
+use crate::data_types::order_by::{OrderBy, OrderValue};
+use crate::data_types::vectors::{unflatten_multi_dense, QueryVector, Vector};
 use crate::entry::entry_point::SegmentEntry;
 use crate::id_tracker::IdTrackerSS;
+use crate::index::field_index::stats::FieldIndexStats;
 use crate::index::field_index::CardinalityEstimation;
+use crate::index::sparse_index::sparse_vector_index::SparseVectorIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::{PayloadIndex, VectorIndex, VectorIndexEnum};
-use crate::spaces::tools::peek_top_smallest_iterable;
+use crate::segment_constructor::{
+    get_vector_index_path, get_vector_name_with_prefix, get_vector_storage_path, open_vector_data,
+};
+use crate::spaces::tools::{peek_top_largest_iterable, peek_top_smallest_iterable};
 use crate::telemetry::SegmentTelemetry;
 use crate::types::{
     Filter, Payload, PayloadFieldSchema, PayloadIndexInfo, PayloadKeyType, PayloadKeyTypeRef,
-    PayloadSchemaType, PointIdType, ScoredPoint, SearchParams, SegmentConfig, SegmentInfo,
-    SegmentState, SegmentType, SeqNumberType, VectorDataInfo, WithPayload, WithVector,
+    PayloadSchemaType, PointIdType, ScoredPoint, SearchParams, SegmentConfig, SegmentDetails,
+    SegmentInfo, SegmentState, SegmentType, SeqNumberType, VectorDataConfig, VectorDataInfo,
+    VectorFilesInfo, WithPayload, WithVector,
 };
 use crate::utils;
 use crate::utils::fs::find_symlink;
+use crate::vector_storage::simple_sparse_vector_storage::SimpleSparseVectorStorage;
 use crate::vector_storage::{VectorStorage, VectorStorageEnum};
 
 pub const SEGMENT_STATE_FILE: &str = "segment.json";
+/// Directory, which holds data of removed vectors until the segment is rebuilt by an optimizer
+pub const DROPPED_VECTOR_DATA_PATH: &str = "dropped_vectors";
 
 const SNAPSHOT_PATH: &str = "snapshot";
 
@@ -75,12 +91,17 @@ pub struct Segment {
     /// Component for mapping external ids to internal and also keeping track of point versions
     pub id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
     pub vector_data: HashMap<String, VectorData>,
+    pub sparse_vector_data: HashMap<String, SparseVectorData>,
     pub payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
     /// Shows if it is possible to insert more points into this segment
     pub appendable_flag: bool,
     /// Shows what kind of indexes and storages are used in this segment
     pub segment_type: SegmentType,
     pub segment_config: SegmentConfig,
+    /// Time the segment was created at, if known
+    pub created_at: Option<DateTime<Utc>>,
+    /// Name of the optimizer, which produced the segment
+    pub optimized_by: Option<String>,
     /// Last unhandled error
     /// If not None, all update operations will be aborted until original operation is performed properly
     pub error_status: Option<SegmentFailedState>,
@@ -93,6 +114,11 @@ pub struct VectorData {
     pub vector_storage: Arc<AtomicRefCell<VectorStorageEnum>>,
 }
 
+pub struct SparseVectorData {
+    pub vector_index: Arc<AtomicRefCell<SparseVectorIndex>>,
+    pub vector_storage: Arc<AtomicRefCell<SimpleSparseVectorStorage>>,
+}
+
 impl VectorData {
     pub fn prefault_mmap_pages(&self) -> impl Iterator<Item = mmap_ops::PrefaultMmapPages> {
         let index_task = match &*self.vector_index.borrow() {
@@ -130,6 +156,7 @@ impl Segment {
     ) -> OperationResult<()> {
         debug_assert!(self.is_appendable());
         check_named_vectors(&vectors, &self.segment_config)?;
+        let vectors = vectors.flatten_multi();
         for (vector_name, vector_data) in self.vector_data.iter_mut() {
             let vector = vectors.get(vector_name);
             match vector {
@@ -144,6 +171,15 @@ impl Segment {
                 }
             }
         }
+        for (vector_name, sparse_vector_data) in self.sparse_vector_data.iter_mut() {
+            let mut vector_index = sparse_vector_data.vector_index.borrow_mut();
+            match vectors.get_sparse(vector_name) {
+                Some(vector) => vector_index.upsert_vector(internal_id, vector)?,
+                None => {
+                    vector_index.delete_vector(internal_id)?;
+                }
+            }
+        }
         Ok(())
     }
 
@@ -167,6 +203,13 @@ impl Segment {
     ) -> OperationResult<()> {
         debug_assert!(self.is_appendable());
         check_named_vectors(&vectors, &self.segment_config)?;
+        let vectors = vectors.flatten_multi();
+        for (vector_name, new_vector) in vectors.iter_sparse() {
+            self.sparse_vector_data[vector_name]
+                .vector_index
+                .borrow_mut()
+                .upsert_vector(internal_id, new_vector)?;
+        }
         for (vector_name, new_vector) in vectors {
             let vector_data = &self.vector_data[vector_name.as_ref()];
             vector_data
@@ -189,6 +232,7 @@ impl Segment {
     ) -> OperationResult<PointOffsetType> {
         debug_assert!(self.is_appendable());
         check_named_vectors(&vectors, &self.segment_config)?;
+        let vectors = vectors.flatten_multi();
         let new_index = self.id_tracker.borrow().total_point_count() as PointOffsetType;
         for (vector_name, vector_data) in self.vector_data.iter_mut() {
             let vector_opt = vectors.get(vector_name);
@@ -204,6 +248,16 @@ impl Segment {
                 }
             }
         }
+        for (vector_name, sparse_vector_data) in self.sparse_vector_data.iter_mut() {
+            let mut vector_index = sparse_vector_data.vector_index.borrow_mut();
+            match vectors.get_sparse(vector_name) {
+                None => {
+                    vector_index.upsert_vector(new_index, &Default::default())?;
+                    vector_index.delete_vector(new_index)?;
+                }
+                Some(vec) => vector_index.upsert_vector(new_index, vec)?,
+            }
+        }
         self.id_tracker.borrow_mut().set_link(point_id, new_index)?;
         Ok(new_index)
     }
@@ -346,6 +400,8 @@ impl Segment {
         SegmentState {
             version: self.version,
             config: self.segment_config.clone(),
+            created_at: self.created_at,
+            optimized_by: self.optimized_by.clone(),
         }
     }
 
@@ -373,8 +429,11 @@ impl Segment {
         &self,
         vector_name: &str,
         point_offset: PointOffsetType,
-    ) -> OperationResult<Option<Vec<VectorElementType>>> {
+    ) -> OperationResult<Option<Vector>> {
         check_vector_name(vector_name, &self.segment_config)?;
+        if let Some(sparse_vector_data) = self.sparse_vector_data.get(vector_name) {
+            return self.sparse_vector_by_offset(sparse_vector_data, point_offset);
+        }
         let vector_data = &self.vector_data[vector_name];
         let is_vector_deleted = vector_data
             .vector_storage
@@ -397,13 +456,45 @@ impl Segment {
                     ),
                 })
             } else {
-                Ok(Some(vector_storage.get_vector(point_offset).to_vec()))
+                let vector = vector_storage.get_vector(point_offset);
+                if self.segment_config.vector_data[vector_name].is_multivector() {
+                    let dim = vector_storage.vector_dim();
+                    Ok(Some(Vector::MultiDense(unflatten_multi_dense(
+                        &vector, dim,
+                    ))))
+                } else {
+                    Ok(Some(vector.into_owned().into()))
+                }
             }
         } else {
             Ok(None)
         }
     }
 
+    fn sparse_vector_by_offset(
+        &self,
+        sparse_vector_data: &SparseVectorData,
+        point_offset: PointOffsetType,
+    ) -> OperationResult<Option<Vector>> {
+        let vector_storage = sparse_vector_data.vector_storage.borrow();
+        if vector_storage.is_deleted_vector(point_offset)
+            || self.id_tracker.borrow().is_deleted_point(point_offset)
+        {
+            return Ok(None);
+        }
+        if vector_storage.total_vector_count() <= point_offset as usize {
+            // Same as for dense vectors, expect WAL replay to restore consistency
+            return Err(OperationError::InconsistentStorage {
+                description: format!(
+                    "Sparse vector storage is inconsistent, total_vector_count: {}, point_offset: {}",
+                    vector_storage.total_vector_count(),
+                    point_offset
+                ),
+            });
+        }
+        Ok(Some(vector_storage.get_vector(point_offset).clone().into()))
+    }
+
     fn all_vectors_by_offset(
         &self,
         point_offset: PointOffsetType,
@@ -415,13 +506,22 @@ impl Segment {
                 .borrow()
                 .is_deleted_vector(point_offset);
             if !is_vector_deleted {
-                vectors.insert(
+                let vector_storage = vector_data.vector_storage.borrow();
+                let vector = vector_storage.get_vector(point_offset);
+                if self.segment_config.vector_data[vector_name].is_multivector() {
+                    let dim = vector_storage.vector_dim();
+                    vectors.insert_multi(vector_name.clone(), unflatten_multi_dense(&vector, dim));
+                } else {
+                    vectors.insert(vector_name.clone(), vector.into_owned());
+                }
+            }
+        }
+        for (vector_name, sparse_vector_data) in &self.sparse_vector_data {
+            let vector_storage = sparse_vector_data.vector_storage.borrow();
+            if !vector_storage.is_deleted_vector(point_offset) {
+                vectors.insert_sparse(
                     vector_name.clone(),
-                    vector_data
-                        .vector_storage
-                        .borrow()
-                        .get_vector(point_offset)
-                        .to_vec(),
+                    vector_storage.get_vector(point_offset).clone(),
                 );
             }
         }
@@ -438,6 +538,55 @@ impl Segment {
         Self::save_state(&self.get_state(), &self.current_path)
     }
 
+    /// Remember the optimizer, which produced this segment
+    pub fn set_optimized_by(&mut self, optimizer_name: &str) -> OperationResult<()> {
+        self.optimized_by = Some(optimizer_name.to_string());
+        self.save_current_state()
+    }
+
+    pub fn details(&self) -> SegmentDetails {
+        let files_size = |files: Vec<PathBuf>| -> u64 {
+            files
+                .iter()
+                .filter_map(|file| fs::metadata(file).ok())
+                .filter(|metadata| metadata.is_file())
+                .map(|metadata| metadata.len())
+                .sum()
+        };
+        let mut vector_files: HashMap<_, _> = self
+            .vector_data
+            .iter()
+            .map(|(vector_name, vector_data)| {
+                let vector_files_info = VectorFilesInfo {
+                    storage_bytes: files_size(vector_data.vector_storage.borrow().files()),
+                    index_bytes: files_size(vector_data.vector_index.borrow().files()),
+                };
+                (vector_name.clone(), vector_files_info)
+            })
+            .collect();
+        vector_files.extend(
+            self.sparse_vector_data
+                .iter()
+                .map(|(vector_name, vector_data)| {
+                    // Sparse vectors are stored in RocksDB
+                    let vector_files_info = VectorFilesInfo {
+                        storage_bytes: 0,
+                        index_bytes: files_size(vector_data.vector_index.borrow().files()),
+                    };
+                    (vector_name.clone(), vector_files_info)
+                }),
+        );
+
+        SegmentDetails {
+            info: self.info(),
+            config: self.segment_config.clone(),
+            created_at: self.created_at,
+            optimized_by: self.optimized_by.clone(),
+            vector_files,
+            disk_size_bytes: fs_extra::dir::get_size(&self.current_path).unwrap_or_default(),
+        }
+    }
+
     fn infer_from_payload_data(
         &self,
         key: PayloadKeyTypeRef,
@@ -582,7 +731,7 @@ impl Segment {
                             if let Some(vector) =
                                 self.vector_by_offset(vector_name, point_offset)?
                             {
-                                result.insert(vector_name.clone(), vector);
+                                result.insert_vector(vector_name.clone(), vector);
                             }
                         }
                         Some(result.into())
@@ -595,6 +744,7 @@ impl Segment {
                     score: scored_point_offset.score,
                     payload,
                     vector,
+                    explanation: None,
                 })
             })
             .collect()
@@ -673,6 +823,10 @@ impl Segment {
                     let mut vector_storage = vector_data.vector_storage.borrow_mut();
                     vector_storage.delete_vector(*internal_id)?;
                 }
+                for sparse_vector_data in self.sparse_vector_data.values() {
+                    let mut vector_index = sparse_vector_data.vector_index.borrow_mut();
+                    vector_index.delete_vector(*internal_id)?;
+                }
             }
 
             // We do not drop version here, because it is already not loaded into memory.
@@ -691,6 +845,12 @@ impl Segment {
 
     pub fn available_vector_count(&self, vector_name: &str) -> OperationResult<usize> {
         check_vector_name(vector_name, &self.segment_config)?;
+        if let Some(sparse_vector_data) = self.sparse_vector_data.get(vector_name) {
+            return Ok(sparse_vector_data
+                .vector_storage
+                .borrow()
+                .available_vector_count());
+        }
         Ok(self.vector_data[vector_name]
             .vector_storage
             .borrow()
@@ -701,6 +861,121 @@ impl Segment {
         self.id_tracker.borrow().total_point_count()
     }
 
+    /// Add a new named vector to this segment
+    ///
+    /// Points already stored in the segment don't have the new vector, it is marked as deleted
+    /// for each of them until it is set by an update.
+    pub fn create_vector_data(
+        &mut self,
+        vector_name: &str,
+        vector_config: &VectorDataConfig,
+    ) -> OperationResult<()> {
+        if self.segment_config.vector_data.contains_key(vector_name)
+            || self
+                .segment_config
+                .sparse_vector_data
+                .contains_key(vector_name)
+        {
+            return Err(OperationError::service_error(format!(
+                "Vector {vector_name} already exists in segment"
+            )));
+        }
+        if !vector_config.is_appendable() {
+            return Err(OperationError::service_error(format!(
+                "Vector {vector_name} can't be added to segment with non-appendable config"
+            )));
+        }
+
+        let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+        create_db_cf_if_not_exists(self.database.clone(), &db_column_name).map_err(|err| {
+            OperationError::service_error(format!("RocksDB create_cf error: {err}"))
+        })?;
+
+        let vector_data = open_vector_data(
+            self.database.clone(),
+            &self.current_path,
+            self.id_tracker.clone(),
+            self.payload_index.clone(),
+            vector_name,
+            vector_config,
+        )?;
+
+        {
+            let mut vector_storage = vector_data.vector_storage.borrow_mut();
+            let placeholder = vec![1.0; vector_storage.vector_dim()];
+            for point_offset in 0..self.total_point_count() as PointOffsetType {
+                vector_storage.insert_vector(point_offset, &placeholder)?;
+                vector_storage.delete_vector(point_offset)?;
+            }
+            // Segment version is not changed, so the regular flush would skip the new storage
+            vector_storage.flusher()()?;
+        }
+
+        self.vector_data.insert(vector_name.to_owned(), vector_data);
+        self.segment_config
+            .vector_data
+            .insert(vector_name.to_owned(), vector_config.clone());
+        self.save_current_state()
+    }
+
+    /// Remove a named vector from this segment
+    ///
+    /// Storage and index files are only moved into [`DROPPED_VECTOR_DATA_PATH`], so this is cheap
+    /// regardless of the amount of data. They are deleted together with the whole segment once an
+    /// optimizer rebuilds it, see [`Segment::has_dropped_vector_data`].
+    ///
+    /// The segment state is saved before any data is moved, so leftovers of an interrupted
+    /// removal are never loaded again.
+    pub fn drop_vector_data(&mut self, vector_name: &str) -> OperationResult<()> {
+        let removed_dense = self.vector_data.remove(vector_name).is_some();
+        let removed_sparse = self.sparse_vector_data.remove(vector_name).is_some();
+        if !removed_dense && !removed_sparse {
+            return Err(OperationError::VectorNameNotExists {
+                received_name: vector_name.to_owned(),
+            });
+        }
+        self.payload_index
+            .borrow_mut()
+            .remove_vector_storage(vector_name);
+        self.segment_config.vector_data.remove(vector_name);
+        self.segment_config.sparse_vector_data.remove(vector_name);
+        self.save_current_state()?;
+
+        let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+        DatabaseColumnWrapper::new(self.database.clone(), &db_column_name)
+            .remove_column_family()?;
+
+        // Same name might be added and removed again before the segment is rebuilt
+        let dropped_path = self
+            .current_path
+            .join(DROPPED_VECTOR_DATA_PATH)
+            .join(Uuid::new_v4().to_string());
+        for path in [
+            get_vector_storage_path(&self.current_path, vector_name),
+            get_vector_index_path(&self.current_path, vector_name),
+        ] {
+            if !path.exists() {
+                continue;
+            }
+            fs::create_dir_all(&dropped_path)?;
+            let file_name = path.file_name().expect("vector data path has a file name");
+            fs::rename(&path, dropped_path.join(file_name)).map_err(|err| {
+                OperationError::service_error(format!(
+                    "Can't move vector data {}: {err}",
+                    path.display(),
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Whether this segment still holds data of removed vectors on disk
+    ///
+    /// Such segments should be rebuilt to free the disk space.
+    pub fn has_dropped_vector_data(segment_path: &Path) -> bool {
+        segment_path.join(DROPPED_VECTOR_DATA_PATH).exists()
+    }
+
     pub fn prefault_mmap_pages(&self) {
         let tasks: Vec<_> = self
             .vector_data
@@ -715,6 +990,34 @@ impl Segment {
             ))
             .spawn(move || tasks.iter().for_each(mmap_ops::PrefaultMmapPages::exec));
     }
+
+    /// Search in the dense or sparse index of the given vector
+    fn search_index(
+        &self,
+        vector_name: &str,
+        query_vectors: &[&QueryVector],
+        filter: Option<&Filter>,
+        top: usize,
+        params: Option<&SearchParams>,
+        is_stopped: &AtomicBool,
+    ) -> Vec<Vec<ScoredPointOffset>> {
+        if let Some(sparse_vector_data) = self.sparse_vector_data.get(vector_name) {
+            return sparse_vector_data.vector_index.borrow().search(
+                query_vectors,
+                filter,
+                top,
+                params,
+                is_stopped,
+            );
+        }
+        self.vector_data[vector_name].vector_index.borrow().search(
+            query_vectors,
+            filter,
+            top,
+            params,
+            is_stopped,
+        )
+    }
 }
 
 /// This is a basic implementation of `SegmentEntry`,
@@ -743,12 +1046,8 @@ impl SegmentEntry for Segment {
         is_stopped: &AtomicBool,
     ) -> OperationResult<Vec<ScoredPoint>> {
         check_vector(vector_name, vector, &self.segment_config)?;
-        let vector_data = &self.vector_data[vector_name];
         let internal_result =
-            &vector_data
-                .vector_index
-                .borrow()
-                .search(&[vector], filter, top, params, is_stopped)[0];
+            &self.search_index(vector_name, &[vector], filter, top, params, is_stopped)[0];
 
         check_stopped(is_stopped)?;
         self.process_search_result(internal_result, with_payload, with_vector)
@@ -766,14 +1065,8 @@ impl SegmentEntry for Segment {
         is_stopped: &AtomicBool,
     ) -> OperationResult<Vec<Vec<ScoredPoint>>> {
         check_query_vectors(vector_name, query_vectors, &self.segment_config)?;
-        let vector_data = &self.vector_data[vector_name];
-        let internal_results = vector_data.vector_index.borrow().search(
-            query_vectors,
-            filter,
-            top,
-            params,
-            is_stopped,
-        );
+        let internal_results =
+            self.search_index(vector_name, query_vectors, filter, top, params, is_stopped);
 
         check_stopped(is_stopped)?;
 
@@ -787,6 +1080,97 @@ impl SegmentEntry for Segment {
         res
     }
 
+    fn search_text(
+        &self,
+        key: PayloadKeyTypeRef,
+        text: &str,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPoint>> {
+        let internal_result = {
+            let payload_index = self.payload_index.borrow();
+            let id_tracker = self.id_tracker.borrow();
+            let scores = payload_index.score_text(key, text)?;
+            check_stopped(is_stopped)?;
+
+            let filter_context = filter.map(|filter| payload_index.filter_context(filter));
+            let scored_points = scores
+                .into_iter()
+                .filter(|&(idx, _score)| !id_tracker.is_deleted_point(idx))
+                .filter(|&(idx, _score)| {
+                    filter_context
+                        .as_ref()
+                        .map_or(true, |filter_context| filter_context.check(idx))
+                })
+                .map(|(idx, score)| ScoredPointOffset { idx, score });
+            peek_top_largest_iterable(scored_points, top)
+        };
+
+        check_stopped(is_stopped)?;
+        self.process_search_result(&internal_result, with_payload, with_vector)
+    }
+
+    fn sample_points(
+        &self,
+        with_payload: &WithPayload,
+        with_vector: &WithVector,
+        filter: Option<&Filter>,
+        top: usize,
+        is_stopped: &AtomicBool,
+    ) -> OperationResult<Vec<ScoredPoint>> {
+        let internal_result = {
+            let payload_index = self.payload_index.borrow();
+            let id_tracker = self.id_tracker.borrow();
+            let available_points = id_tracker.available_point_count();
+
+            let (sampled, matching_points) = match filter {
+                None => (
+                    id_tracker.shuffled_ids().take(top).collect(),
+                    available_points,
+                ),
+                Some(filter) => {
+                    // Same heuristic as for filtered scroll: probe random points until the sample
+                    // is full, unless the filter is expected to reject too many of them
+                    let query_cardinality = payload_index.estimate_cardinality(filter);
+                    let check_probability =
+                        (query_cardinality.exp as f64 + 1.0) / (available_points as f64 + 1.0);
+                    let exp_stream_checks = top as f64 / check_probability;
+
+                    if exp_stream_checks > query_cardinality.max as f64 {
+                        let mut matching = payload_index.query_points(filter);
+                        matching.retain(|&idx| !id_tracker.is_deleted_point(idx));
+                        let (sampled, _) = matching.partial_shuffle(&mut rand::thread_rng(), top);
+                        (sampled.to_vec(), matching.len())
+                    } else {
+                        let filter_context = payload_index.filter_context(filter);
+                        let mut probed_points = 0;
+                        let sampled: Vec<_> = id_tracker
+                            .shuffled_ids()
+                            .inspect(|_| probed_points += 1)
+                            .filter(|&idx| filter_context.check(idx))
+                            .take(top)
+                            .collect();
+                        // Unless the sample is full, all points were probed
+                        let matching_points = if sampled.len() < top {
+                            sampled.len()
+                        } else {
+                            sampled.len() * available_points / probed_points
+                        };
+                        (sampled, matching_points)
+                    }
+                }
+            };
+            check_stopped(is_stopped)?;
+            sample_scores(sampled, matching_points)
+        };
+
+        check_stopped(is_stopped)?;
+        self.process_search_result(&internal_result, with_payload, with_vector)
+    }
+
     fn upsert_point(
         &mut self,
         op_num: SeqNumberType,
@@ -795,7 +1179,7 @@ impl SegmentEntry for Segment {
     ) -> OperationResult<bool> {
         debug_assert!(self.is_appendable());
         check_named_vectors(&vectors, &self.segment_config)?;
-        vectors.preprocess(|name| self.segment_config.vector_data[name].distance);
+        vectors.preprocess(|name| self.segment_config.vector_data[name].preprocessing_distance());
         let stored_internal_point = self.id_tracker.borrow().internal_id(point_id);
         self.handle_version_and_failure(op_num, stored_internal_point, |segment| {
             if let Some(existing_internal_id) = stored_internal_point {
@@ -848,7 +1232,7 @@ impl SegmentEntry for Segment {
         mut vectors: NamedVectors,
     ) -> OperationResult<bool> {
         check_named_vectors(&vectors, &self.segment_config)?;
-        vectors.preprocess(|name| self.segment_config.vector_data[name].distance);
+        vectors.preprocess(|name| self.segment_config.vector_data[name].preprocessing_distance());
         let internal_id = self.id_tracker.borrow().internal_id(point_id);
         match internal_id {
             None => Err(OperationError::PointIdError {
@@ -877,6 +1261,11 @@ impl SegmentEntry for Segment {
             }),
             Some(internal_id) => {
                 self.handle_version_and_failure(op_num, Some(internal_id), |segment| {
+                    if let Some(sparse_vector_data) = segment.sparse_vector_data.get(vector_name) {
+                        let mut vector_index = sparse_vector_data.vector_index.borrow_mut();
+                        let is_deleted = vector_index.delete_vector(internal_id)?;
+                        return Ok((is_deleted, Some(internal_id)));
+                    }
                     let vector_data = segment.vector_data.get(vector_name).ok_or(
                         OperationError::VectorNameNotExists {
                             received_name: vector_name.to_string(),
@@ -970,11 +1359,7 @@ impl SegmentEntry for Segment {
         })
     }
 
-    fn vector(
-        &self,
-        vector_name: &str,
-        point_id: PointIdType,
-    ) -> OperationResult<Option<Vec<VectorElementType>>> {
+    fn vector(&self, vector_name: &str, point_id: PointIdType) -> OperationResult<Option<Vector>> {
         check_vector_name(vector_name, &self.segment_config)?;
         let internal_id = self.lookup_internal_id(point_id)?;
         let vector_opt = self.vector_by_offset(vector_name, internal_id)?;
@@ -983,9 +1368,13 @@ impl SegmentEntry for Segment {
 
     fn all_vectors(&self, point_id: PointIdType) -> OperationResult<NamedVectors> {
         let mut result = NamedVectors::default();
-        for vector_name in self.vector_data.keys() {
+        for vector_name in self
+            .vector_data
+            .keys()
+            .chain(self.sparse_vector_data.keys())
+        {
             if let Some(vec) = self.vector(vector_name, point_id)? {
-                result.insert(vector_name.clone(), vec);
+                result.insert_vector(vector_name.clone(), vec);
             }
         }
         Ok(result)
@@ -1062,6 +1451,33 @@ impl SegmentEntry for Segment {
         }
     }
 
+    fn read_ordered_filtered<'a>(
+        &'a self,
+        limit: Option<usize>,
+        filter: Option<&'a Filter>,
+        order_by: &'a OrderBy,
+    ) -> OperationResult<Vec<(OrderValue, PointIdType)>> {
+        let payload_index = self.payload_index.borrow();
+        let id_tracker = self.id_tracker.borrow();
+
+        let filter_context = filter.map(|filter| payload_index.filter_context(filter));
+        let mut visited = HashSet::new();
+        let points = payload_index
+            .iter_ordered(order_by)?
+            .filter(|&(_value, idx)| !id_tracker.is_deleted_point(idx))
+            .filter(|&(_value, idx)| {
+                filter_context
+                    .as_ref()
+                    .map_or(true, |filter_context| filter_context.check(idx))
+            })
+            // Point is ordered by the first of its values in the direction of ordering
+            .filter(|&(_value, idx)| visited.insert(idx))
+            .filter_map(|(value, idx)| Some((value, id_tracker.external_id(idx)?)))
+            .take(limit.unwrap_or(usize::MAX))
+            .collect();
+        Ok(points)
+    }
+
     fn read_range(&self, from: Option<PointIdType>, to: Option<PointIdType>) -> Vec<PointIdType> {
         let id_tracker = self.id_tracker.borrow();
         let iterator = id_tracker.iter_from(from).map(|x| x.0);
@@ -1120,9 +1536,14 @@ impl SegmentEntry for Segment {
             .vector_data
             .values()
             .map(|data| data.vector_storage.borrow().available_vector_count())
-            .sum();
-
-        let vector_data_info = self
+            .sum::<usize>()
+            + self
+                .sparse_vector_data
+                .values()
+                .map(|data| data.vector_storage.borrow().available_vector_count())
+                .sum::<usize>();
+
+        let mut vector_data_info: HashMap<_, _> = self
             .vector_data
             .iter()
             .map(|(key, vector_data)| {
@@ -1138,6 +1559,18 @@ impl SegmentEntry for Segment {
             })
             .collect();
 
+        // Sparse vectors are indexed on insertion
+        vector_data_info.extend(self.sparse_vector_data.iter().map(|(key, vector_data)| {
+            let vector_storage = vector_data.vector_storage.borrow();
+            let num_vectors = vector_storage.available_vector_count();
+            let vector_data_info = VectorDataInfo {
+                num_vectors,
+                num_indexed_vectors: num_vectors,
+                num_deleted_vectors: vector_storage.deleted_vector_count(),
+            };
+            (key.to_string(), vector_data_info)
+        }));
+
         let num_indexed_vectors = if self.segment_type == SegmentType::Indexed {
             num_vectors
         } else {
@@ -1191,6 +1624,11 @@ impl SegmentEntry for Segment {
             .vector_data
             .values()
             .map(|v| v.vector_storage.borrow().flusher())
+            .chain(
+                self.sparse_vector_data
+                    .values()
+                    .map(|v| v.vector_storage.borrow().flusher()),
+            )
             .collect();
         let state = self.get_state();
         let current_path = self.current_path.clone();
@@ -1349,6 +1787,10 @@ impl SegmentEntry for Segment {
         self.payload_index.borrow().indexed_fields()
     }
 
+    fn get_field_index_stats(&self, key: PayloadKeyTypeRef) -> Option<FieldIndexStats> {
+        self.payload_index.borrow().get_field_index_stats(key)
+    }
+
     fn check_error(&self) -> Option<SegmentFailedState> {
         self.error_status.clone()
     }
@@ -1368,7 +1810,13 @@ impl SegmentEntry for Segment {
 
     fn vector_dim(&self, vector_name: &str) -> OperationResult<usize> {
         check_vector_name(vector_name, &self.segment_config)?;
-        let vector_data_config = &self.segment_config.vector_data[vector_name];
+        let vector_data_config = self
+            .segment_config
+            .vector_data
+            .get(vector_name)
+            .ok_or_else(|| OperationError::ValidationError {
+                description: format!("Sparse vector {vector_name} has no fixed dimension"),
+            })?;
         Ok(vector_data_config.size)
     }
 
@@ -1510,6 +1958,11 @@ impl SegmentEntry for Segment {
                 telemetry.index_name = Some(k.clone());
                 telemetry
             })
+            .chain(self.sparse_vector_data.iter().map(|(k, v)| {
+                let mut telemetry = v.vector_index.borrow().get_telemetry_data();
+                telemetry.index_name = Some(k.clone());
+                telemetry
+            }))
             .collect();
 
         SegmentTelemetry {
@@ -1521,6 +1974,29 @@ impl SegmentEntry for Segment {
     }
 }
 
+/// Random scores of the sampled points, which are the largest of `matching_points` independent
+/// random keys in descending order.
+///
+/// Keys are exponentially distributed, so the spacings between the smallest of them are
+/// independent: the `i`-th spacing is exponential with rate `matching_points - i`.
+/// Scores are negated keys, so they are larger for the sampled points, as usual.
+fn sample_scores(sampled: Vec<PointOffsetType>, matching_points: usize) -> Vec<ScoredPointOffset> {
+    let mut rng = rand::thread_rng();
+    let mut key = 0.0;
+    sampled
+        .into_iter()
+        .enumerate()
+        .map(|(i, idx)| {
+            let spacing = -(1.0 - rng.gen::<f64>()).ln();
+            key += spacing / (matching_points - i) as f64;
+            ScoredPointOffset {
+                idx,
+                score: -key as ScoreType,
+            }
+        })
+        .collect()
+}
+
 impl Drop for Segment {
     fn drop(&mut self) {
         let _lock = self.lock_flushing();
@@ -1535,7 +2011,10 @@ mod tests {
     use crate::common::operation_error::OperationError::PointIdError;
     use crate::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
     use crate::segment_constructor::{build_segment, load_segment};
-    use crate::types::{Distance, Indexes, SegmentConfig, VectorDataConfig, VectorStorageType};
+    use crate::types::{
+        Distance, Indexes, SegmentConfig, VectorDataConfig, VectorStorageDatatype,
+        VectorStorageType,
+    };
 
     // no longer valid since users are now allowed to store arbitrary json objects.
     // TODO(gvelo): add tests for invalid payload types on indexed fields.
@@ -1584,8 +2063,11 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    multivector_config: None,
+                    datatype: None,
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -1656,8 +2138,11 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    multivector_config: None,
+                    datatype: None,
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
 
@@ -1747,8 +2232,11 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    multivector_config: None,
+                    datatype: None,
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
 
@@ -1838,8 +2326,11 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    multivector_config: None,
+                    datatype: None,
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
 
@@ -1869,8 +2360,11 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    multivector_config: None,
+                    datatype: None,
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -1963,8 +2457,11 @@ mod tests {
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    multivector_config: None,
+                    datatype: None,
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -2015,6 +2512,8 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
                 (
@@ -2025,9 +2524,12 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
             ]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -2120,6 +2622,8 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
                 (
@@ -2130,9 +2634,12 @@ mod tests {
                         storage_type: VectorStorageType::Memory,
                         index: Indexes::Plain {},
                         quantization_config: None,
+                        multivector_config: None,
+                        datatype: None,
                     },
                 ),
             ]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         };
         let mut segment = build_segment(dir.path(), &config, true).unwrap();
@@ -2268,4 +2775,209 @@ mod tests {
                 .unwrap();
         }
     }
+
+    #[test]
+    fn test_uint8_vector_value_checks() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let config = SegmentConfig {
+            vector_data: HashMap::from([(
+                "a".into(),
+                VectorDataConfig {
+                    size: 3,
+                    distance: Distance::Dot,
+                    storage_type: VectorStorageType::Memory,
+                    index: Indexes::Plain {},
+                    quantization_config: None,
+                    multivector_config: None,
+                    datatype: Some(VectorStorageDatatype::Uint8),
+                },
+            )]),
+            sparse_vector_data: Default::default(),
+            payload_storage_type: Default::default(),
+        };
+        let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+        segment
+            .upsert_point(
+                1,
+                1.into(),
+                NamedVectors::from_ref("a", &[0.0, 17.0, 255.0]),
+            )
+            .unwrap();
+        assert_eq!(
+            segment.vector("a", 1.into()).unwrap(),
+            Some(vec![0.0, 17.0, 255.0].into()),
+        );
+
+        for wrong_vector in [[0.0, 1.5, 2.0], [0.0, 256.0, 2.0], [-1.0, 1.0, 2.0]] {
+            let vectors = NamedVectors::from_ref("a", &wrong_vector);
+            check_named_vectors(&vectors, &config).unwrap_err();
+            segment.upsert_point(2, 2.into(), vectors).unwrap_err();
+
+            let query_vector = wrong_vector.to_vec().into();
+            check_vector("a", &query_vector, &config).unwrap_err();
+        }
+        assert_eq!(segment.available_point_count(), 1);
+    }
+
+    #[test]
+    fn test_create_vector_data() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let vector_config = |size| VectorDataConfig {
+            size,
+            distance: Distance::Dot,
+            storage_type: VectorStorageType::Memory,
+            index: Indexes::Plain {},
+            quantization_config: None,
+            multivector_config: None,
+            datatype: None,
+        };
+        let config = SegmentConfig {
+            vector_data: HashMap::from([("a".into(), vector_config(2))]),
+            sparse_vector_data: Default::default(),
+            payload_storage_type: Default::default(),
+        };
+        let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+        for point_id in [1, 2] {
+            segment
+                .upsert_point(
+                    point_id,
+                    point_id.into(),
+                    NamedVectors::from_ref("a", &[0.1, 0.2]),
+                )
+                .unwrap();
+        }
+
+        segment.create_vector_data("b", &vector_config(3)).unwrap();
+        segment
+            .create_vector_data("b", &vector_config(3))
+            .unwrap_err();
+
+        // Existing points don't have the new vector
+        assert_eq!(segment.available_vector_count("b").unwrap(), 0);
+        assert_eq!(segment.vector("b", 1.into()).unwrap(), None);
+
+        SegmentEntry::update_vectors(
+            &mut segment,
+            3,
+            1.into(),
+            NamedVectors::from_ref("b", &[1.0, 0.0, 1.0]),
+        )
+        .unwrap();
+        segment
+            .upsert_point(4, 3.into(), NamedVectors::from_ref("b", &[0.0, 1.0, 0.0]))
+            .unwrap();
+        assert_eq!(segment.available_vector_count("b").unwrap(), 2);
+        assert_eq!(segment.vector("b", 2.into()).unwrap(), None);
+
+        let segment_path = segment.current_path.clone();
+        segment.flush(true).unwrap();
+        drop(segment);
+
+        // New vector is persisted in segment config and storage
+        let segment = load_segment(&segment_path).unwrap().unwrap();
+        assert_eq!(segment.config().vector_data["b"].size, 3);
+        assert_eq!(
+            segment.vector("b", 1.into()).unwrap(),
+            Some(vec![1.0, 0.0, 1.0].into()),
+        );
+        assert_eq!(segment.vector("b", 2.into()).unwrap(), None);
+        assert_eq!(segment.vector("a", 3.into()).unwrap(), None);
+    }
+
+    #[test]
+    fn test_drop_vector_data() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let vector_config = |storage_type| VectorDataConfig {
+            size: 2,
+            distance: Distance::Dot,
+            storage_type,
+            index: Indexes::Plain {},
+            quantization_config: None,
+            multivector_config: None,
+            datatype: None,
+        };
+        let config = SegmentConfig {
+            vector_data: HashMap::from([
+                ("a".into(), vector_config(VectorStorageType::Memory)),
+                ("b".into(), vector_config(VectorStorageType::ChunkedMmap)),
+            ]),
+            sparse_vector_data: Default::default(),
+            payload_storage_type: Default::default(),
+        };
+        let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+        let mut vectors = NamedVectors::from_ref("a", &[0.1, 0.2]);
+        vectors.insert("b".into(), vec![0.3, 0.4]);
+        segment.upsert_point(1, 1.into(), vectors).unwrap();
+
+        let segment_path = segment.current_path.clone();
+        let storage_path = get_vector_storage_path(&segment_path, "b");
+        assert!(storage_path.exists());
+
+        assert!(!Segment::has_dropped_vector_data(&segment_path));
+        segment.drop_vector_data("b").unwrap();
+        segment.drop_vector_data("b").unwrap_err();
+
+        // Data is moved away, to be deleted once the segment is rebuilt
+        assert!(!storage_path.exists());
+        assert!(!get_vector_index_path(&segment_path, "b").exists());
+        assert!(Segment::has_dropped_vector_data(&segment_path));
+        assert!(!segment.config().vector_data.contains_key("b"));
+        segment.vector("b", 1.into()).unwrap_err();
+
+        segment.flush(true).unwrap();
+        drop(segment);
+
+        // Removed vector stays removed after reload, other vectors are intact
+        let segment = load_segment(&segment_path).unwrap().unwrap();
+        assert_eq!(segment.config().vector_data.len(), 1);
+        assert_eq!(
+            segment.vector("a", 1.into()).unwrap(),
+            Some(vec![0.1, 0.2].into()),
+        );
+    }
+
+    #[test]
+    fn test_segment_details() {
+        let segment_base_dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let config = SegmentConfig {
+            vector_data: HashMap::from([(
+                DEFAULT_VECTOR_NAME.to_owned(),
+                VectorDataConfig {
+                    size: 2,
+                    distance: Distance::Dot,
+                    storage_type: VectorStorageType::ChunkedMmap,
+                    index: Indexes::Plain {},
+                    quantization_config: None,
+                    multivector_config: None,
+                    datatype: None,
+                },
+            )]),
+            sparse_vector_data: Default::default(),
+            payload_storage_type: Default::default(),
+        };
+
+        let mut segment = build_segment(segment_base_dir.path(), &config, true).unwrap();
+        segment
+            .upsert_point(0, 0.into(), only_default_vector(&[1.0, 1.0]))
+            .unwrap();
+        segment.set_optimized_by("merge").unwrap();
+        segment.flush(true).unwrap();
+
+        let details = segment.details();
+        assert!(details.created_at.is_some());
+        assert_eq!(details.optimized_by.as_deref(), Some("merge"));
+        assert_eq!(details.info.num_points, 1);
+        assert!(details.vector_files[DEFAULT_VECTOR_NAME].storage_bytes > 0);
+        assert!(details.disk_size_bytes > 0);
+
+        // Origin of the segment is persisted
+        let segment_path = segment.current_path.clone();
+        drop(segment);
+        let segment = load_segment(&segment_path).unwrap().unwrap();
+        assert_eq!(segment.details().created_at, details.created_at);
+        assert_eq!(segment.details().optimized_by.as_deref(), Some("merge"));
+    }
 }
@@ -3,6 +3,10 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
 
+use common::types::PointOffsetType;
+use itertools::Itertools;
+use serde_json::Value;
+
 use super::get_vector_storage_path;
 use crate::common::error_logging::LogError;
 use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
@@ -11,7 +15,7 @@ use crate::index::hnsw_index::max_rayon_threads;
 use crate::index::{PayloadIndex, VectorIndex};
 use crate::segment::Segment;
 use crate::segment_constructor::{build_segment, load_segment};
-use crate::types::{Indexes, PayloadFieldSchema, PayloadKeyType, SegmentConfig};
+use crate::types::{Indexes, PayloadContainer, PayloadFieldSchema, PayloadKeyType, SegmentConfig};
 use crate::vector_storage::VectorStorage;
 
 /// Structure for constructing segment out of several other segments
@@ -20,6 +24,8 @@ pub struct SegmentBuilder {
     pub destination_path: PathBuf,
     pub temp_path: PathBuf,
     pub indexed_fields: HashMap<PayloadKeyType, PayloadFieldSchema>,
+    /// Payload key to group the points by, tenant index is used if not set
+    pub defragmentation_key: Option<PayloadKeyType>,
 }
 
 impl SegmentBuilder {
@@ -38,6 +44,7 @@ impl SegmentBuilder {
             destination_path,
             temp_path,
             indexed_fields: Default::default(),
+            defragmentation_key: None,
         })
     }
 
@@ -53,6 +60,62 @@ impl SegmentBuilder {
     /// * `bool` - if `true` - data successfully added, if `false` - process was interrupted
     ///
     pub fn update_from(&mut self, other: &Segment, stopped: &AtomicBool) -> OperationResult<bool> {
+        self.update(&[other], stopped)
+    }
+
+    /// Update current segment builder with all (not deleted) vectors and payload from `segments`
+    ///
+    /// If there is a defragmentation key or a tenant index, points of all `segments` are grouped
+    /// by its value, so that the data of a single tenant occupies a contiguous range of offsets in
+    /// the new segment.
+    ///
+    /// # Result
+    ///
+    /// * `bool` - if `true` - data successfully added, if `false` - process was interrupted
+    ///
+    pub fn update(&mut self, segments: &[&Segment], stopped: &AtomicBool) -> OperationResult<bool> {
+        let grouping_key = self.defragmentation_key.clone().or_else(|| {
+            segments
+                .iter()
+                .find_map(|segment| Self::tenant_field(segment))
+        });
+
+        let Some(grouping_key) = grouping_key else {
+            for segment in segments {
+                let ids = segment.id_tracker.borrow().iter_ids().collect_vec();
+                self.copy_points(segment, &ids, stopped)?;
+            }
+            return Ok(true);
+        };
+
+        let mut keyed_ids = Vec::new();
+        for (segment_index, segment) in segments.iter().enumerate() {
+            check_process_stopped(stopped)?;
+            for (key, id) in Self::keyed_point_ids(segment, &grouping_key)? {
+                keyed_ids.push((key, segment_index, id));
+            }
+        }
+        // Ids within a group keep their original order
+        keyed_ids.sort_unstable();
+
+        // Copy consecutive points of the same segment at once
+        for (segment_index, ids) in &keyed_ids
+            .into_iter()
+            .group_by(|(_, segment_index, _)| *segment_index)
+        {
+            let ids = ids.map(|(_, _, id)| id).collect_vec();
+            self.copy_points(segments[segment_index], &ids, stopped)?;
+        }
+        Ok(true)
+    }
+
+    /// Copy vectors and payload of the `other_ids` points of `other` segment
+    fn copy_points(
+        &mut self,
+        other: &Segment,
+        other_ids: &[PointOffsetType],
+        stopped: &AtomicBool,
+    ) -> OperationResult<()> {
         let self_segment = match &mut self.segment {
             Some(segment) => segment,
             None => {
@@ -71,6 +134,13 @@ impl SegmentBuilder {
                 (vector_name.to_owned(), vector_data.vector_storage.borrow())
             })
             .collect();
+        let other_sparse_vector_storages: HashMap<_, _> = other
+            .sparse_vector_data
+            .iter()
+            .map(|(vector_name, vector_data)| {
+                (vector_name.to_owned(), vector_data.vector_storage.borrow())
+            })
+            .collect();
         let other_payload_index = other.payload_index.borrow();
 
         let mut id_tracker = self_segment.id_tracker.borrow_mut();
@@ -84,6 +154,16 @@ impl SegmentBuilder {
                 )
             })
             .collect();
+        let mut sparse_vector_storages: HashMap<_, _> = self_segment
+            .sparse_vector_data
+            .iter()
+            .map(|(vector_name, vector_data)| {
+                (
+                    vector_name.to_owned(),
+                    vector_data.vector_storage.borrow_mut(),
+                )
+            })
+            .collect();
         let mut payload_index = self_segment.payload_index.borrow_mut();
 
         if vector_storages.len() != other_vector_storages.len() {
@@ -92,6 +172,12 @@ impl SegmentBuilder {
             ));
         }
 
+        if sparse_vector_storages.len() != other_sparse_vector_storages.len() {
+            return Err(OperationError::service_error(
+                format!("Self and other segments have different sparse vector names count. Self count: {}, other count: {}", sparse_vector_storages.len(), other_sparse_vector_storages.len()),
+            ));
+        }
+
         let mut new_internal_range = None;
         for (vector_name, vector_storage) in &mut vector_storages {
             check_process_stopped(stopped)?;
@@ -102,7 +188,32 @@ impl SegmentBuilder {
             })?;
             let internal_range = vector_storage.update_from(
                 other_vector_storage,
-                &mut other_id_tracker.iter_ids(),
+                &mut other_ids.iter().copied(),
+                stopped,
+            )?;
+            match new_internal_range.clone() {
+                Some(new_internal_range) => {
+                    if new_internal_range != internal_range {
+                        return Err(OperationError::service_error(
+                            "Internal ids range mismatch between self segment vectors and other segment vectors",
+                        ));
+                    }
+                }
+                None => new_internal_range = Some(internal_range.clone()),
+            }
+        }
+
+        // Sparse index is rebuilt from storage on segment load, so only storages are merged here
+        for (vector_name, vector_storage) in &mut sparse_vector_storages {
+            check_process_stopped(stopped)?;
+            let other_vector_storage = other_sparse_vector_storages.get(vector_name).ok_or_else(|| {
+                OperationError::service_error(format!(
+                    "Cannot update from other segment because if missing sparse vector name {vector_name}"
+                ))
+            })?;
+            let internal_range = vector_storage.update_from(
+                other_vector_storage,
+                &mut other_ids.iter().copied(),
                 stopped,
             )?;
             match new_internal_range.clone() {
@@ -118,7 +229,7 @@ impl SegmentBuilder {
         }
 
         if let Some(new_internal_range) = new_internal_range {
-            let internal_id_iter = new_internal_range.zip(other_id_tracker.iter_ids());
+            let internal_id_iter = new_internal_range.zip(other_ids.iter().copied());
 
             for (new_internal_id, old_internal_id) in internal_id_iter {
                 check_process_stopped(stopped)?;
@@ -168,6 +279,9 @@ impl SegmentBuilder {
                         for vector_storage in vector_storages.values_mut() {
                             vector_storage.delete_vector(remove_id)?;
                         }
+                        for vector_storage in sparse_vector_storages.values_mut() {
+                            vector_storage.delete_vector(remove_id)?;
+                        }
                     }
                 }
             }
@@ -177,7 +291,43 @@ impl SegmentBuilder {
             self.indexed_fields.insert(field, payload_schema);
         }
 
-        Ok(true)
+        Ok(())
+    }
+
+    /// Field of the keyword index of `segment`, marked as tenant, if there is one
+    fn tenant_field(segment: &Segment) -> Option<PayloadKeyType> {
+        segment
+            .payload_index
+            .borrow()
+            .indexed_fields()
+            .into_iter()
+            .find_map(|(field, schema)| schema.is_tenant().then_some(field))
+    }
+
+    /// Internal ids of the points of `segment` with the first value of the `key` in their payload
+    fn keyed_point_ids(
+        segment: &Segment,
+        key: &PayloadKeyType,
+    ) -> OperationResult<Vec<(Option<String>, PointOffsetType)>> {
+        let payload_index = segment.payload_index.borrow();
+        let keyed_ids = segment
+            .id_tracker
+            .borrow()
+            .iter_ids()
+            .map(|id| {
+                let payload = payload_index.payload(id)?;
+                let value = payload
+                    .get_value(key)
+                    .values()
+                    .first()
+                    .map(|value| match value {
+                        Value::String(keyword) => keyword.clone(),
+                        value => value.to_string(),
+                    });
+                Ok((value, id))
+            })
+            .collect();
+        keyed_ids
     }
 
     pub fn build(mut self, stopped: &AtomicBool) -> Result<Segment, OperationError> {
@@ -5,8 +5,10 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
+use chrono::Utc;
 use log::info;
-use parking_lot::Mutex;
+use parking_lot::{Mutex, RwLock};
+use rocksdb::DB;
 use semver::Version;
 use serde::Deserialize;
 use uuid::Uuid;
@@ -16,23 +18,28 @@ use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
 use crate::common::version::StorageVersion;
 use crate::data_types::vectors::DEFAULT_VECTOR_NAME;
 use crate::id_tracker::simple_id_tracker::SimpleIdTracker;
-use crate::id_tracker::IdTracker;
+use crate::id_tracker::{IdTracker, IdTrackerSS};
 use crate::index::hnsw_index::graph_links::{GraphLinksMmap, GraphLinksRam};
 use crate::index::hnsw_index::hnsw::HNSWIndex;
 use crate::index::plain_payload_index::PlainIndex;
+use crate::index::sparse_index::sparse_vector_index::SparseVectorIndex;
 use crate::index::struct_payload_index::StructPayloadIndex;
 use crate::index::VectorIndexEnum;
 use crate::payload_storage::on_disk_payload_storage::OnDiskPayloadStorage;
 use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
-use crate::segment::{Segment, SegmentVersion, VectorData, SEGMENT_STATE_FILE};
+use crate::segment::{Segment, SegmentVersion, SparseVectorData, VectorData, SEGMENT_STATE_FILE};
 use crate::types::{
     Distance, Indexes, PayloadStorageType, SegmentConfig, SegmentState, SegmentType, SeqNumberType,
-    VectorStorageType,
+    VectorDataConfig, VectorStorageDatatype, VectorStorageType,
 };
 use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
 use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage;
+use crate::vector_storage::simple_byte_vector_storage::open_simple_byte_vector_storage;
+use crate::vector_storage::simple_half_vector_storage::open_simple_half_vector_storage;
+use crate::vector_storage::simple_multi_dense_vector_storage::open_simple_multi_dense_vector_storage;
+use crate::vector_storage::simple_sparse_vector_storage::open_simple_sparse_vector_storage;
 use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
-use crate::vector_storage::VectorStorage;
+use crate::vector_storage::{VectorStorage, VectorStorageRef};
 
 pub const PAYLOAD_INDEX_PATH: &str = "payload_index";
 pub const VECTOR_STORAGE_PATH: &str = "vector_storage";
@@ -42,7 +49,7 @@ fn sp<T>(t: T) -> Arc<AtomicRefCell<T>> {
     Arc::new(AtomicRefCell::new(t))
 }
 
-fn get_vector_name_with_prefix(prefix: &str, vector_name: &str) -> String {
+pub(crate) fn get_vector_name_with_prefix(prefix: &str, vector_name: &str) -> String {
     if !vector_name.is_empty() {
         format!("{prefix}-{vector_name}")
     } else {
@@ -61,14 +68,140 @@ pub fn get_vector_index_path(segment_path: &Path, vector_name: &str) -> PathBuf
     segment_path.join(get_vector_name_with_prefix(VECTOR_INDEX_PATH, vector_name))
 }
 
-fn create_segment(
-    version: Option<SeqNumberType>,
+/// Open storage and index of a single named vector of the segment
+pub(crate) fn open_vector_data(
+    database: Arc<RwLock<DB>>,
     segment_path: &Path,
-    config: &SegmentConfig,
-) -> OperationResult<Segment> {
+    id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+    payload_index: Arc<AtomicRefCell<StructPayloadIndex>>,
+    vector_name: &str,
+    vector_config: &VectorDataConfig,
+) -> OperationResult<VectorData> {
+    let vector_storage_path = get_vector_storage_path(segment_path, vector_name);
+    let vector_index_path = get_vector_index_path(segment_path, vector_name);
+
+    // Select suitable vector storage type based on configuration
+    let vector_storage = match (vector_config.storage_type, vector_config.multivector_config) {
+        // Multivectors are only kept in memory, regardless of the storage type
+        (_, Some(multivector_config)) => {
+            let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+            open_simple_multi_dense_vector_storage(
+                database.clone(),
+                &db_column_name,
+                vector_config.size,
+                vector_config.distance,
+                multivector_config,
+            )?
+        }
+        // Half precision vectors are only kept in memory, regardless of the storage type
+        (_, None) if vector_config.datatype == Some(VectorStorageDatatype::Float16) => {
+            let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+            open_simple_half_vector_storage(
+                database.clone(),
+                &db_column_name,
+                vector_config.size,
+                vector_config.distance,
+            )?
+        }
+        // Byte vectors are only kept in memory as well
+        (_, None) if vector_config.datatype == Some(VectorStorageDatatype::Uint8) => {
+            let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+            open_simple_byte_vector_storage(
+                database.clone(),
+                &db_column_name,
+                vector_config.size,
+                vector_config.distance,
+            )?
+        }
+        // In memory
+        (VectorStorageType::Memory, None) => {
+            let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+            open_simple_vector_storage(
+                database.clone(),
+                &db_column_name,
+                vector_config.size,
+                vector_config.distance,
+            )?
+        }
+        // Mmap on disk, not appendable
+        (VectorStorageType::Mmap, None) => open_memmap_vector_storage(
+            &vector_storage_path,
+            vector_config.size,
+            vector_config.distance,
+        )?,
+        // Chunked mmap on disk, appendable
+        (VectorStorageType::ChunkedMmap, None) => open_appendable_memmap_vector_storage(
+            &vector_storage_path,
+            vector_config.size,
+            vector_config.distance,
+        )?,
+    };
+
+    // Warn when number of points between ID tracker and storage differs
+    let point_count = id_tracker.borrow().total_point_count();
+    let vector_count = vector_storage.borrow().total_vector_count();
+    if vector_count != point_count {
+        log::debug!(
+            "Mismatch of point and vector counts ({point_count} != {vector_count}, storage: {})",
+            vector_storage_path.display(),
+        );
+    }
+
+    if vector_config.quantization_config.is_some() {
+        let quantized_data_path = vector_storage_path;
+        // Try to load quantization data from disk, if exists
+        // If not exists or it's a new segment, just ignore it
+        vector_storage
+            .borrow_mut()
+            .load_quantization(&quantized_data_path)?;
+    }
+
+    payload_index
+        .borrow_mut()
+        .add_vector_storage(vector_name, VectorStorageRef::Dense(vector_storage.clone()));
+
+    let vector_index: Arc<AtomicRefCell<VectorIndexEnum>> = match &vector_config.index {
+        Indexes::Plain {} => sp(VectorIndexEnum::Plain(PlainIndex::new(
+            id_tracker.clone(),
+            vector_storage.clone(),
+            payload_index.clone(),
+        ))),
+        Indexes::Hnsw(vector_hnsw_config) => sp(if vector_hnsw_config.on_disk == Some(true) {
+            VectorIndexEnum::HnswMmap(HNSWIndex::<GraphLinksMmap>::open(
+                &vector_index_path,
+                id_tracker.clone(),
+                vector_storage.clone(),
+                payload_index.clone(),
+                vector_hnsw_config.clone(),
+            )?)
+        } else {
+            VectorIndexEnum::HnswRam(HNSWIndex::<GraphLinksRam>::open(
+                &vector_index_path,
+                id_tracker.clone(),
+                vector_storage.clone(),
+                payload_index.clone(),
+                vector_hnsw_config.clone(),
+            )?)
+        }),
+    };
+
+    Ok(VectorData {
+        vector_storage,
+        vector_index,
+    })
+}
+
+fn create_segment(state: &SegmentState, segment_path: &Path) -> OperationResult<Segment> {
+    let SegmentState {
+        version,
+        config,
+        created_at,
+        optimized_by,
+    } = state;
     let vector_db_names: Vec<String> = config
         .vector_data
         .keys()
+        .chain(config.sparse_vector_data.keys())
         .map(|vector_name| get_vector_name_with_prefix(DB_VECTOR_CF, vector_name))
         .collect();
     let database = open_db(segment_path, &vector_db_names)
@@ -96,84 +229,38 @@ fn create_segment(
 
     let mut vector_data = HashMap::new();
     for (vector_name, vector_config) in &config.vector_data {
-        let vector_storage_path = get_vector_storage_path(segment_path, vector_name);
-        let vector_index_path = get_vector_index_path(segment_path, vector_name);
-
-        // Select suitable vector storage type based on configuration
-        let vector_storage = match vector_config.storage_type {
-            // In memory
-            VectorStorageType::Memory => {
-                let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
-                open_simple_vector_storage(
-                    database.clone(),
-                    &db_column_name,
-                    vector_config.size,
-                    vector_config.distance,
-                )?
-            }
-            // Mmap on disk, not appendable
-            VectorStorageType::Mmap => open_memmap_vector_storage(
-                &vector_storage_path,
-                vector_config.size,
-                vector_config.distance,
-            )?,
-            // Chunked mmap on disk, appendable
-            VectorStorageType::ChunkedMmap => open_appendable_memmap_vector_storage(
-                &vector_storage_path,
-                vector_config.size,
-                vector_config.distance,
-            )?,
-        };
-
-        // Warn when number of points between ID tracker and storage differs
-        let point_count = id_tracker.borrow().total_point_count();
-        let vector_count = vector_storage.borrow().total_vector_count();
-        if vector_count != point_count {
-            log::debug!(
-                "Mismatch of point and vector counts ({point_count} != {vector_count}, storage: {})",
-                vector_storage_path.display(),
-            );
-        }
+        let vector_data_item = open_vector_data(
+            database.clone(),
+            segment_path,
+            id_tracker.clone(),
+            payload_index.clone(),
+            vector_name,
+            vector_config,
+        )?;
+        vector_data.insert(vector_name.to_owned(), vector_data_item);
+    }
 
-        if config.quantization_config(vector_name).is_some() {
-            let quantized_data_path = vector_storage_path;
-            // Try to load quantization data from disk, if exists
-            // If not exists or it's a new segment, just ignore it
-            vector_storage
-                .borrow_mut()
-                .load_quantization(&quantized_data_path)?;
-        }
+    let mut sparse_vector_data = HashMap::new();
+    for (vector_name, sparse_vector_config) in &config.sparse_vector_data {
+        let db_column_name = get_vector_name_with_prefix(DB_VECTOR_CF, vector_name);
+        let vector_storage = open_simple_sparse_vector_storage(database.clone(), &db_column_name)?;
+        payload_index.borrow_mut().add_vector_storage(
+            vector_name,
+            VectorStorageRef::Sparse(vector_storage.clone()),
+        );
 
-        let vector_index: Arc<AtomicRefCell<VectorIndexEnum>> = match &vector_config.index {
-            Indexes::Plain {} => sp(VectorIndexEnum::Plain(PlainIndex::new(
-                id_tracker.clone(),
-                vector_storage.clone(),
-                payload_index.clone(),
-            ))),
-            Indexes::Hnsw(vector_hnsw_config) => sp(if vector_hnsw_config.on_disk == Some(true) {
-                VectorIndexEnum::HnswMmap(HNSWIndex::<GraphLinksMmap>::open(
-                    &vector_index_path,
-                    id_tracker.clone(),
-                    vector_storage.clone(),
-                    payload_index.clone(),
-                    vector_hnsw_config.clone(),
-                )?)
-            } else {
-                VectorIndexEnum::HnswRam(HNSWIndex::<GraphLinksRam>::open(
-                    &vector_index_path,
-                    id_tracker.clone(),
-                    vector_storage.clone(),
-                    payload_index.clone(),
-                    vector_hnsw_config.clone(),
-                )?)
-            }),
-        };
-
-        vector_data.insert(
+        let vector_index = sp(SparseVectorIndex::open(
+            sparse_vector_config.index,
+            id_tracker.clone(),
+            vector_storage.clone(),
+            payload_index.clone(),
+        )?);
+
+        sparse_vector_data.insert(
             vector_name.to_owned(),
-            VectorData {
-                vector_storage,
+            SparseVectorData {
                 vector_index,
+                vector_storage,
             },
         );
     }
@@ -185,15 +272,18 @@ fn create_segment(
     };
 
     Ok(Segment {
-        version,
-        persisted_version: Arc::new(Mutex::new(version)),
+        version: *version,
+        persisted_version: Arc::new(Mutex::new(*version)),
         current_path: segment_path.to_owned(),
         id_tracker,
         vector_data,
+        sparse_vector_data,
         segment_type,
         appendable_flag,
         payload_index,
         segment_config: config.clone(),
+        created_at: *created_at,
+        optimized_by: optimized_by.clone(),
         error_status: None,
         database,
         flush_thread: Mutex::new(None),
@@ -254,7 +344,7 @@ pub fn load_segment(path: &Path) -> OperationResult<Option<Segment>> {
 
     let segment_state = Segment::load_state(path)?;
 
-    let segment = create_segment(segment_state.version, path, &segment_state.config)?;
+    let segment = create_segment(&segment_state, path)?;
 
     Ok(Some(segment))
 }
@@ -276,7 +366,13 @@ pub fn build_segment(path: &Path, config: &SegmentConfig, ready: bool) -> Operat
 
     std::fs::create_dir_all(&segment_path)?;
 
-    let segment = create_segment(None, &segment_path, config)?;
+    let state = SegmentState {
+        version: None,
+        config: config.clone(),
+        created_at: Some(Utc::now()),
+        optimized_by: None,
+    };
+    let segment = create_segment(&state, &segment_path)?;
     segment.save_current_state()?;
 
     // Version is the last file to save, as it will be used to check if segment was built correctly.
@@ -346,6 +442,8 @@ fn load_segment_state_v3(segment_path: &Path) -> OperationResult<SegmentState> {
             SegmentState {
                 version: Some(state.version),
                 config: segment_config.into(),
+                created_at: None,
+                optimized_by: None,
             }
         })
         .map_err(|err| {
@@ -29,8 +29,11 @@ pub fn build_simple_segment(
                     storage_type: VectorStorageType::Memory,
                     index: Indexes::Plain {},
                     quantization_config: None,
+                    multivector_config: None,
+                    datatype: None,
                 },
             )]),
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         },
         true,
@@ -52,6 +55,8 @@ pub fn build_multivec_segment(
             storage_type: VectorStorageType::Memory,
             index: Indexes::Plain {},
             quantization_config: None,
+            multivector_config: None,
+            datatype: None,
         },
     );
     vectors_config.insert(
@@ -62,6 +67,8 @@ pub fn build_multivec_segment(
             storage_type: VectorStorageType::Memory,
             index: Indexes::Plain {},
             quantization_config: None,
+            multivector_config: None,
+            datatype: None,
         },
     );
 
@@ -69,6 +76,7 @@ pub fn build_multivec_segment(
         path,
         &SegmentConfig {
             vector_data: vectors_config,
+            sparse_vector_data: Default::default(),
             payload_storage_type: Default::default(),
         },
         true,
@@ -1,5 +1,8 @@
 pub mod metric;
 pub mod simple;
+pub mod simple_binary;
+pub mod simple_byte;
+pub mod simple_half;
 pub mod tools;
 
 #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -0,0 +1,175 @@
+use common::types::ScoreType;
+
+use super::metric::Metric;
+use super::simple_byte::ByteMetric;
+use crate::data_types::vectors::{VectorElementType, VectorType};
+use crate::types::Distance;
+use crate::vector_storage::simple_byte_vector_storage::to_byte_vector;
+
+/// Number of differing bits, each `u8` element holds 8 bits of a binary vector
+#[derive(Clone)]
+pub struct HammingMetric;
+
+/// Share of bits set in both vectors among bits set in any of them
+#[derive(Clone)]
+pub struct JaccardMetric;
+
+impl Metric for HammingMetric {
+    fn distance() -> Distance {
+        Distance::Hamming
+    }
+
+    fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        Self::similarity_bytes(&to_byte_vector(v1), &to_byte_vector(v2))
+    }
+
+    fn preprocess(vector: VectorType) -> VectorType {
+        vector
+    }
+
+    fn postprocess(score: ScoreType) -> ScoreType {
+        score.abs()
+    }
+}
+
+impl ByteMetric for HammingMetric {
+    fn similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+        -(hamming_distance_bytes(v1, v2) as ScoreType)
+    }
+}
+
+impl Metric for JaccardMetric {
+    fn distance() -> Distance {
+        Distance::Jaccard
+    }
+
+    fn similarity(v1: &[VectorElementType], v2: &[VectorElementType]) -> ScoreType {
+        Self::similarity_bytes(&to_byte_vector(v1), &to_byte_vector(v2))
+    }
+
+    fn preprocess(vector: VectorType) -> VectorType {
+        vector
+    }
+
+    fn postprocess(score: ScoreType) -> ScoreType {
+        score
+    }
+}
+
+impl ByteMetric for JaccardMetric {
+    fn similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+        let (intersection, union) = intersection_union_bytes(v1, v2);
+        if union == 0 {
+            // Both vectors have no bits set, so they are equal
+            return 1.0;
+        }
+        intersection as ScoreType / union as ScoreType
+    }
+}
+
+pub fn hamming_distance_bytes(v1: &[u8], v2: &[u8]) -> u32 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("popcnt") {
+            return unsafe { hamming_distance_bytes_popcnt(v1, v2) };
+        }
+    }
+
+    hamming_distance_bytes_impl(v1, v2)
+}
+
+/// Returns number of bits set in both vectors and number of bits set in any of them
+pub fn intersection_union_bytes(v1: &[u8], v2: &[u8]) -> (u32, u32) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if is_x86_feature_detected!("popcnt") {
+            return unsafe { intersection_union_bytes_popcnt(v1, v2) };
+        }
+    }
+
+    intersection_union_bytes_impl(v1, v2)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "popcnt")]
+unsafe fn hamming_distance_bytes_popcnt(v1: &[u8], v2: &[u8]) -> u32 {
+    hamming_distance_bytes_impl(v1, v2)
+}
+
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "popcnt")]
+unsafe fn intersection_union_bytes_popcnt(v1: &[u8], v2: &[u8]) -> (u32, u32) {
+    intersection_union_bytes_impl(v1, v2)
+}
+
+/// Bits are counted in 64-bit words, so that each word takes a single `popcnt` instruction
+#[inline(always)]
+fn hamming_distance_bytes_impl(v1: &[u8], v2: &[u8]) -> u32 {
+    let chunks1 = v1.chunks_exact(8);
+    let chunks2 = v2.chunks_exact(8);
+    let tail: u32 = chunks1
+        .remainder()
+        .iter()
+        .zip(chunks2.remainder())
+        .map(|(a, b)| (a ^ b).count_ones())
+        .sum();
+    let head: u32 = chunks1
+        .zip(chunks2)
+        .map(|(a, b)| (to_word(a) ^ to_word(b)).count_ones())
+        .sum();
+    head + tail
+}
+
+#[inline(always)]
+fn intersection_union_bytes_impl(v1: &[u8], v2: &[u8]) -> (u32, u32) {
+    let chunks1 = v1.chunks_exact(8);
+    let chunks2 = v2.chunks_exact(8);
+    let mut intersection = 0;
+    let mut union = 0;
+    for (a, b) in chunks1.remainder().iter().zip(chunks2.remainder()) {
+        intersection += (a & b).count_ones();
+        union += (a | b).count_ones();
+    }
+    for (a, b) in chunks1.zip(chunks2) {
+        let (a, b) = (to_word(a), to_word(b));
+        intersection += (a & b).count_ones();
+        union += (a | b).count_ones();
+    }
+    (intersection, union)
+}
+
+#[inline(always)]
+fn to_word(chunk: &[u8]) -> u64 {
+    u64::from_ne_bytes(chunk.try_into().expect("chunk has 8 bytes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_binary_similarities() {
+        // Longer than a word, so both the word and the tail paths are used
+        let v1 = [0b1111_0000, 0, 0, 0, 0, 0, 0, 0b1, 0b1010];
+        let v2 = [0b1100_0000, 0, 0, 0, 0, 0, 0, 0b1, 0b0110];
+
+        assert_eq!(hamming_distance_bytes(&v1, &v2), 4);
+        assert_eq!(hamming_distance_bytes_impl(&v1, &v2), 4);
+        assert_eq!(HammingMetric::similarity_bytes(&v1, &v2), -4.0);
+
+        assert_eq!(intersection_union_bytes(&v1, &v2), (4, 8));
+        assert_eq!(intersection_union_bytes_impl(&v1, &v2), (4, 8));
+        assert_eq!(JaccardMetric::similarity_bytes(&v1, &v2), 0.5);
+
+        let float1: Vec<f32> = v1.iter().map(|&x| x as f32).collect();
+        let float2: Vec<f32> = v2.iter().map(|&x| x as f32).collect();
+        assert_eq!(HammingMetric::similarity(&float1, &float2), -4.0);
+        assert_eq!(JaccardMetric::similarity(&float1, &float2), 0.5);
+    }
+
+    #[test]
+    fn test_jaccard_empty_vectors() {
+        assert_eq!(JaccardMetric::similarity_bytes(&[0, 0], &[0, 0]), 1.0);
+        assert_eq!(JaccardMetric::similarity_bytes(&[0, 0], &[0, 1]), 0.0);
+    }
+}
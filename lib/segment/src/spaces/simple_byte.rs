@@ -0,0 +1,102 @@
+use common::types::ScoreType;
+
+use super::metric::Metric;
+use super::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
+
+/// Defines how to compare vectors stored as bytes, without converting them to floats first
+pub trait ByteMetric: Metric {
+    /// Greater the value - closer the vectors
+    fn similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType;
+}
+
+impl ByteMetric for DotProductMetric {
+    fn similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+        dot_similarity_bytes(v1, v2) as ScoreType
+    }
+}
+
+impl ByteMetric for CosineMetric {
+    fn similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+        cosine_similarity_bytes(v1, v2)
+    }
+}
+
+impl ByteMetric for EuclidMetric {
+    fn similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+        euclid_similarity_bytes(v1, v2)
+    }
+}
+
+impl ByteMetric for ManhattanMetric {
+    fn similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+        manhattan_similarity_bytes(v1, v2)
+    }
+}
+
+/// Product of two byte vectors.
+/// Can't overflow: `255 * 255 * 65536` still fits into `u32`
+pub fn dot_similarity_bytes(v1: &[u8], v2: &[u8]) -> u32 {
+    v1.iter()
+        .zip(v2)
+        .map(|(a, b)| u32::from(*a) * u32::from(*b))
+        .sum()
+}
+
+pub fn euclid_similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let s: u32 = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| {
+            let diff = u32::from(a.abs_diff(*b));
+            diff * diff
+        })
+        .sum();
+    -(s as ScoreType)
+}
+
+pub fn manhattan_similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let s: u32 = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| u32::from(a.abs_diff(*b)))
+        .sum();
+    -(s as ScoreType)
+}
+
+/// Byte vectors can't be normalized on insertion, so the norms are computed on the fly
+pub fn cosine_similarity_bytes(v1: &[u8], v2: &[u8]) -> ScoreType {
+    let norm1 = dot_similarity_bytes(v1, v1) as ScoreType;
+    let norm2 = dot_similarity_bytes(v2, v2) as ScoreType;
+    if norm1 < ScoreType::EPSILON || norm2 < ScoreType::EPSILON {
+        return 0.0;
+    }
+    dot_similarity_bytes(v1, v2) as ScoreType / (norm1.sqrt() * norm2.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_similarities() {
+        let v1 = [1, 2, 3, 255];
+        let v2 = [4, 5, 6, 255];
+
+        assert_eq!(DotProductMetric::similarity_bytes(&v1, &v2), 65057.0);
+        assert_eq!(EuclidMetric::similarity_bytes(&v1, &v2), -27.0);
+        assert_eq!(ManhattanMetric::similarity_bytes(&v1, &v2), -9.0);
+
+        let float1: Vec<f32> = v1.iter().map(|&x| x as f32).collect();
+        let float2: Vec<f32> = v2.iter().map(|&x| x as f32).collect();
+        let expected = CosineMetric::similarity(
+            &CosineMetric::preprocess(float1),
+            &CosineMetric::preprocess(float2),
+        );
+        assert!((CosineMetric::similarity_bytes(&v1, &v2) - expected).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_cosine_zero_byte_vector() {
+        assert_eq!(CosineMetric::similarity_bytes(&[0, 0], &[1, 2]), 0.0);
+    }
+}
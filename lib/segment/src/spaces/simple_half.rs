@@ -0,0 +1,126 @@
+use common::types::ScoreType;
+use half::f16;
+use half::slice::HalfFloatSliceExt;
+
+use super::metric::Metric;
+use super::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
+use super::simple_binary::{HammingMetric, JaccardMetric};
+
+/// Defines how to compare vectors stored in half precision, without converting them to `f32`
+/// vectors first. Sums are accumulated in `f32` to avoid precision loss.
+pub trait HalfMetric: Metric {
+    /// Greater the value - closer the vectors
+    fn similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType;
+}
+
+impl HalfMetric for DotProductMetric {
+    fn similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+        dot_similarity_half(v1, v2)
+    }
+}
+
+/// Cosine vectors are normalized before conversion to half precision, same as for `f32` storage
+impl HalfMetric for CosineMetric {
+    fn similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+        dot_similarity_half(v1, v2)
+    }
+}
+
+impl HalfMetric for EuclidMetric {
+    fn similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+        euclid_similarity_half(v1, v2)
+    }
+}
+
+impl HalfMetric for ManhattanMetric {
+    fn similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+        manhattan_similarity_half(v1, v2)
+    }
+}
+
+/// Binary metrics operate on bytes, so half vectors are converted on the fly
+impl HalfMetric for HammingMetric {
+    fn similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+        Self::similarity(&v1.to_f32_vec(), &v2.to_f32_vec())
+    }
+}
+
+impl HalfMetric for JaccardMetric {
+    fn similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+        Self::similarity(&v1.to_f32_vec(), &v2.to_f32_vec())
+    }
+}
+
+pub fn dot_similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+    v1.iter()
+        .zip(v2)
+        .map(|(a, b)| a.to_f32() * b.to_f32())
+        .sum()
+}
+
+pub fn euclid_similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+    let s: ScoreType = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| (a.to_f32() - b.to_f32()).powi(2))
+        .sum();
+    -s
+}
+
+pub fn manhattan_similarity_half(v1: &[f16], v2: &[f16]) -> ScoreType {
+    let s: ScoreType = v1
+        .iter()
+        .zip(v2)
+        .map(|(a, b)| (a.to_f32() - b.to_f32()).abs())
+        .sum();
+    -s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_half(vector: &[f32]) -> Vec<f16> {
+        vector.iter().map(|&x| f16::from_f32(x)).collect()
+    }
+
+    #[test]
+    fn test_half_similarities_match_full_precision() {
+        let v1 = [1.0, -2.5, 3.25, 0.5];
+        let v2 = [4.0, 5.5, -6.0, 0.125];
+        let (h1, h2) = (to_half(&v1), to_half(&v2));
+
+        // All values are exactly representable in half precision
+        assert_eq!(
+            DotProductMetric::similarity_half(&h1, &h2),
+            DotProductMetric::similarity(&v1, &v2),
+        );
+        assert_eq!(
+            EuclidMetric::similarity_half(&h1, &h2),
+            EuclidMetric::similarity(&v1, &v2),
+        );
+        assert_eq!(
+            ManhattanMetric::similarity_half(&h1, &h2),
+            ManhattanMetric::similarity(&v1, &v2),
+        );
+
+        let n1 = CosineMetric::preprocess(v1.to_vec());
+        let n2 = CosineMetric::preprocess(v2.to_vec());
+        let expected = CosineMetric::similarity(&n1, &n2);
+        let score = CosineMetric::similarity_half(&to_half(&n1), &to_half(&n2));
+        assert!((score - expected).abs() < 1e-3, "{score} != {expected}");
+    }
+
+    #[test]
+    fn test_half_binary_similarities() {
+        let v1 = [255.0, 0.0, 15.0, 1.0];
+        let v2 = [255.0, 0.0, 7.0, 1.0];
+        let (h1, h2) = (to_half(&v1), to_half(&v2));
+
+        assert_eq!(HammingMetric::similarity_half(&h1, &h2), -1.0);
+        assert_eq!(
+            JaccardMetric::similarity_half(&h1, &h2),
+            JaccardMetric::similarity(&v1, &v2),
+        );
+    }
+}
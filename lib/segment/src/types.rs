@@ -7,11 +7,13 @@ use std::ops::Deref;
 use std::rc::Rc;
 use std::str::FromStr;
 
+use chrono::{DateTime, NaiveDateTime, Utc};
 use common::types::ScoreType;
 use geo::prelude::HaversineDistance;
 use geo::{Contains, Coord, LineString, Point, Polygon};
 use itertools::Itertools;
 use ordered_float::OrderedFloat;
+use regex::Regex;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
@@ -25,10 +27,16 @@ use crate::common::utils::{
     check_exclude_pattern, check_include_pattern, filter_json_values, get_value_from_json_map,
     MultiValue,
 };
+use crate::data_types::composite_index::CompositeIndexParams;
+use crate::data_types::float_index::FloatIndexParams;
+use crate::data_types::geo_index::GeoIndexParams;
+use crate::data_types::integer_index::IntegerIndexParams;
+use crate::data_types::keyword_index::KeywordIndexParams;
 use crate::data_types::text_index::TextIndexParams;
 use crate::data_types::vectors::{VectorElementType, VectorStruct, VectorType};
 use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
+use crate::spaces::simple_binary::{HammingMetric, JaccardMetric};
 
 pub type PayloadKeyType = String;
 pub type PayloadKeyTypeRef<'a> = &'a str;
@@ -39,6 +47,10 @@ pub type TagType = u64;
 pub type FloatPayloadType = f64;
 /// Type of integer point payload
 pub type IntPayloadType = i64;
+/// Type of datetime point payload, stored in payload as RFC 3339 string
+pub type DateTimePayloadType = DateTime<Utc>;
+/// Type of UUID point payload in the payload index, stored in payload as a string
+pub type UuidIntType = u128;
 
 pub const VECTOR_ELEMENT_SIZE: usize = size_of::<VectorElementType>();
 
@@ -122,6 +134,14 @@ pub enum Distance {
     Euclid,
     // <https://en.wikipedia.org/wiki/Dot_product>
     Dot,
+    // <https://en.wikipedia.org/wiki/Taxicab_geometry>
+    Manhattan,
+    // <https://en.wikipedia.org/wiki/Hamming_distance>
+    // Only for `uint8` vectors, each element holds 8 bits
+    Hamming,
+    // <https://en.wikipedia.org/wiki/Jaccard_index>
+    // Only for `uint8` vectors, each element holds 8 bits
+    Jaccard,
 }
 
 impl Distance {
@@ -130,6 +150,9 @@ impl Distance {
             Distance::Cosine => CosineMetric::preprocess(vector),
             Distance::Euclid => EuclidMetric::preprocess(vector),
             Distance::Dot => DotProductMetric::preprocess(vector),
+            Distance::Manhattan => ManhattanMetric::preprocess(vector),
+            Distance::Hamming => HammingMetric::preprocess(vector),
+            Distance::Jaccard => JaccardMetric::preprocess(vector),
         }
     }
 
@@ -138,13 +161,21 @@ impl Distance {
             Distance::Cosine => CosineMetric::postprocess(score),
             Distance::Euclid => EuclidMetric::postprocess(score),
             Distance::Dot => DotProductMetric::postprocess(score),
+            Distance::Manhattan => ManhattanMetric::postprocess(score),
+            Distance::Hamming => HammingMetric::postprocess(score),
+            Distance::Jaccard => JaccardMetric::postprocess(score),
         }
     }
 
+    /// Binary distances compare bits of `uint8` elements instead of element values
+    pub fn is_binary(&self) -> bool {
+        matches!(self, Distance::Hamming | Distance::Jaccard)
+    }
+
     pub fn distance_order(&self) -> Order {
         match self {
-            Distance::Cosine | Distance::Dot => Order::LargeBetter,
-            Distance::Euclid => Order::SmallBetter,
+            Distance::Cosine | Distance::Dot | Distance::Jaccard => Order::LargeBetter,
+            Distance::Euclid | Distance::Manhattan | Distance::Hamming => Order::SmallBetter,
         }
     }
 
@@ -164,6 +195,9 @@ impl Distance {
             Distance::Cosine => CosineMetric::similarity(v1, v2),
             Distance::Euclid => EuclidMetric::similarity(v1, v2),
             Distance::Dot => DotProductMetric::similarity(v1, v2),
+            Distance::Manhattan => ManhattanMetric::similarity(v1, v2),
+            Distance::Hamming => HammingMetric::similarity(v1, v2),
+            Distance::Jaccard => JaccardMetric::similarity(v1, v2),
         }
     }
 }
@@ -186,6 +220,9 @@ pub struct ScoredPoint {
     pub payload: Option<Payload>,
     /// Vector of the point
     pub vector: Option<VectorStruct>,
+    /// Breakdown of the score, only returned if requested
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<Box<ScoreExplanation>>,
 }
 
 impl Eq for ScoredPoint {}
@@ -208,6 +245,41 @@ impl PartialEq for ScoredPoint {
     }
 }
 
+/// Breakdown of the score of a point by the stages of a query
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct ScoreExplanation {
+    /// Score by the vector query, computed with the original vectors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub similarity: Option<ScoreType>,
+    /// Score by the vector query, computed with the quantized vectors only, before rescoring.
+    /// Present if the vectors are quantized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantized_similarity: Option<ScoreType>,
+    /// Scores of the point in the prefetches, which were fused or combined by a formula.
+    /// `null` for prefetches, which did not return the point.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub prefetches: Vec<Option<PrefetchScore>>,
+    /// Values of the top-level terms of the formula: operands of a sum or a product,
+    /// or the whole expression otherwise
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub formula_terms: Vec<f64>,
+}
+
+/// Score of a point in the results of a prefetch
+#[derive(Deserialize, Serialize, JsonSchema, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct PrefetchScore {
+    /// Position of the point in the results of the prefetch, starting from 0
+    pub rank: usize,
+    pub score: ScoreType,
+    /// Part of the fused score, which comes from this prefetch
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contribution: Option<ScoreType>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<Box<ScoreExplanation>>,
+}
+
 /// Type of segment
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -241,11 +313,36 @@ impl PayloadIndexInfo {
                 points: points_count,
             },
             PayloadFieldSchema::FieldParams(schema_params) => match schema_params {
+                PayloadSchemaParams::Keyword(_) => PayloadIndexInfo {
+                    data_type: PayloadSchemaType::Keyword,
+                    params: Some(schema_params),
+                    points: points_count,
+                },
+                PayloadSchemaParams::Integer(_) => PayloadIndexInfo {
+                    data_type: PayloadSchemaType::Integer,
+                    params: Some(schema_params),
+                    points: points_count,
+                },
+                PayloadSchemaParams::Float(_) => PayloadIndexInfo {
+                    data_type: PayloadSchemaType::Float,
+                    params: Some(schema_params),
+                    points: points_count,
+                },
+                PayloadSchemaParams::Geo(_) => PayloadIndexInfo {
+                    data_type: PayloadSchemaType::Geo,
+                    params: Some(schema_params),
+                    points: points_count,
+                },
                 PayloadSchemaParams::Text(_) => PayloadIndexInfo {
                     data_type: PayloadSchemaType::Text,
                     params: Some(schema_params),
                     points: points_count,
                 },
+                PayloadSchemaParams::Composite(_) => PayloadIndexInfo {
+                    data_type: PayloadSchemaType::Composite,
+                    params: Some(schema_params),
+                    points: points_count,
+                },
             },
         }
     }
@@ -275,6 +372,31 @@ pub struct SegmentInfo {
     pub vector_data: HashMap<String, VectorDataInfo>,
 }
 
+/// Disk usage of the storage and the index of a named vector
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub struct VectorFilesInfo {
+    /// Vectors, kept in the RocksDB of the segment, are not counted
+    pub storage_bytes: u64,
+    pub index_bytes: u64,
+}
+
+/// Detailed information about segment, for diagnostics
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(rename_all = "snake_case")]
+pub struct SegmentDetails {
+    pub info: SegmentInfo,
+    pub config: SegmentConfig,
+    /// Time the segment was created at, unknown for segments created by older versions
+    pub created_at: Option<DateTime<Utc>>,
+    /// Name of the optimizer, which produced the segment.
+    /// Not set for segments, created to accept new points.
+    pub optimized_by: Option<String>,
+    pub vector_files: HashMap<String, VectorFilesInfo>,
+    /// Size of the segment directory
+    pub disk_size_bytes: u64,
+}
+
 /// Additional parameters of the search
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, Copy, PartialEq, Default)]
 #[serde(rename_all = "snake_case")]
@@ -318,6 +440,8 @@ pub struct SearchParams {
     pub hnsw_ef: Option<usize>,
 
     /// Search without approximation. If set to true, search may run long but with exact results.
+    /// All points of all segments are scored with full precision vectors then,
+    /// quantization and `indexed_only` are ignored.
     #[serde(default)]
     pub exact: bool,
 
@@ -614,6 +738,8 @@ impl PayloadStorageType {
 #[serde(rename_all = "snake_case")]
 pub struct SegmentConfig {
     pub vector_data: HashMap<String, VectorDataConfig>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub sparse_vector_data: HashMap<String, SparseVectorDataConfig>,
     /// Defines payload storage type
     pub payload_storage_type: PayloadStorageType,
 }
@@ -678,6 +804,38 @@ impl VectorStorageType {
     }
 }
 
+/// Storage type of vector elements
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum VectorStorageDatatype {
+    /// Single-precision floating point
+    #[default]
+    Float32,
+    /// Half-precision floating point, halves RAM usage at the cost of precision.
+    /// Always kept in RAM, can't be used with `on_disk` or multivectors.
+    Float16,
+    /// Unsigned byte, for embeddings already quantized by the model. Values must be integers in
+    /// `0..=255`. Always kept in RAM and never quantized further.
+    Uint8,
+}
+
+/// Comparator used to score a list of vectors against another list of vectors
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum MultiVectorComparator {
+    /// Sum over query vectors of the best similarity to any of the stored vectors (late interaction)
+    #[default]
+    MaxSim,
+}
+
+/// Config for vectors holding multiple dense vectors per point
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub struct MultiVectorConfig {
+    /// How to compare multivectors
+    pub comparator: MultiVectorComparator,
+}
+
 /// Config of single vector data storage
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -692,9 +850,45 @@ pub struct VectorDataConfig {
     pub index: Indexes,
     /// Vector specific quantization config that overrides collection config
     pub quantization_config: Option<QuantizationConfig>,
+    /// If set, each point holds a list of vectors of `size` dimensions, compared with the given comparator
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub multivector_config: Option<MultiVectorConfig>,
+    /// Type of vector elements, `float32` if not specified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datatype: Option<VectorStorageDatatype>,
 }
 
 impl VectorDataConfig {
+    pub fn is_multivector(&self) -> bool {
+        self.multivector_config.is_some()
+    }
+
+    /// Whether vectors of this config can be stored on disk, otherwise they are always in RAM
+    pub fn supports_on_disk(&self) -> bool {
+        !self.is_multivector()
+            && !matches!(
+                self.datatype,
+                Some(VectorStorageDatatype::Float16 | VectorStorageDatatype::Uint8)
+            )
+    }
+
+    /// Whether vectors of this config can be quantized
+    pub fn supports_quantization(&self) -> bool {
+        !self.is_multivector() && self.datatype != Some(VectorStorageDatatype::Uint8)
+    }
+
+    /// Distance to preprocess inserted vectors with, if any
+    ///
+    /// Byte vectors are stored exactly as provided, normalization would destroy them.
+    pub fn preprocessing_distance(&self) -> Option<Distance> {
+        match self.datatype {
+            Some(VectorStorageDatatype::Uint8) => None,
+            Some(VectorStorageDatatype::Float32 | VectorStorageDatatype::Float16) | None => {
+                Some(self.distance)
+            }
+        }
+    }
+
     /// Whether this vector data can be appended to
     ///
     /// This requires an index and storage type that both support appending.
@@ -712,6 +906,24 @@ impl VectorDataConfig {
     }
 }
 
+/// Configuration for sparse inverted index
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseIndexConfig {
+    /// Prefer a full scan search upto (excluding) this number of vectors.
+    ///
+    /// Note: this is number of vectors, not KiloBytes.
+    pub full_scan_threshold: Option<usize>,
+}
+
+/// Config of single sparse vector data storage
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct SparseVectorDataConfig {
+    /// Sparse inverted index config
+    pub index: SparseIndexConfig,
+}
+
 /// Default value based on <https://github.com/google-research/google-research/blob/master/scann/docs/algorithms.md>
 pub const DEFAULT_FULL_SCAN_THRESHOLD: usize = 20_000;
 
@@ -721,6 +933,12 @@ pub const DEFAULT_FULL_SCAN_THRESHOLD: usize = 20_000;
 pub struct SegmentState {
     pub version: Option<SeqNumberType>,
     pub config: SegmentConfig,
+    /// Time the segment was created at, unknown for segments created by older versions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+    /// Name of the optimizer, which produced the segment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub optimized_by: Option<String>,
 }
 
 /// Geo point payload schema
@@ -772,6 +990,11 @@ impl GeoPoint {
         Self::validate(lon, lat)?;
         Ok(GeoPoint { lon, lat })
     }
+
+    /// Distance to the other point in meters
+    pub fn haversine_distance(&self, other: &GeoPoint) -> f64 {
+        Point::new(self.lon, self.lat).haversine_distance(&Point::new(other.lon, other.lat))
+    }
 }
 
 impl TryFrom<GeoPointShadow> for GeoPoint {
@@ -804,6 +1027,32 @@ impl Payload {
         }
     }
 
+    /// Merge values into nested objects recursively, as JSON merge patch (RFC 7396) does.
+    /// `null` values remove the keys.
+    pub fn merge_deep(&mut self, value: &Payload) {
+        fn merge_patch(target: &mut Map<String, Value>, patch: &Map<String, Value>) {
+            for (key, value) in patch {
+                match value {
+                    Value::Null => {
+                        target.remove(key);
+                    }
+                    Value::Object(patch) => match target.get_mut(key) {
+                        Some(Value::Object(target)) => merge_patch(target, patch),
+                        _ => {
+                            let mut object = Map::new();
+                            merge_patch(&mut object, patch);
+                            target.insert(key.to_owned(), Value::Object(object));
+                        }
+                    },
+                    _ => {
+                        target.insert(key.to_owned(), value.to_owned());
+                    }
+                }
+            }
+        }
+        merge_patch(&mut self.0, &value.0);
+    }
+
     pub fn remove(&mut self, path: &str) -> Vec<Value> {
         utils::remove_value_from_json_map(path, &mut self.0).values()
     }
@@ -971,6 +1220,9 @@ pub enum PayloadSchemaType {
     Geo,
     Text,
     Bool,
+    Datetime,
+    Uuid,
+    Composite,
 }
 
 /// Payload type with parameters
@@ -978,7 +1230,27 @@ pub enum PayloadSchemaType {
 #[serde(rename_all = "snake_case")]
 #[serde(untagged)]
 pub enum PayloadSchemaParams {
+    Keyword(KeywordIndexParams),
+    Integer(IntegerIndexParams),
+    Float(FloatIndexParams),
+    Geo(GeoIndexParams),
     Text(TextIndexParams),
+    Composite(CompositeIndexParams),
+}
+
+impl PayloadSchemaParams {
+    /// Whether the postings of the index are kept in mmap instead of RAM
+    pub fn is_on_disk(&self) -> bool {
+        let on_disk = match self {
+            PayloadSchemaParams::Keyword(params) => params.on_disk,
+            PayloadSchemaParams::Integer(params) => params.on_disk,
+            PayloadSchemaParams::Float(params) => params.on_disk,
+            PayloadSchemaParams::Geo(params) => params.on_disk,
+            PayloadSchemaParams::Text(params) => params.on_disk,
+            PayloadSchemaParams::Composite(_) => None,
+        };
+        on_disk.unwrap_or(false)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Hash, Eq)]
@@ -989,6 +1261,42 @@ pub enum PayloadFieldSchema {
     FieldParams(PayloadSchemaParams),
 }
 
+impl PayloadFieldSchema {
+    /// Whether the field is a keyword, which points are grouped by in optimized segments
+    pub fn is_tenant(&self) -> bool {
+        match self {
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(params)) => {
+                params.is_tenant()
+            }
+            _ => false,
+        }
+    }
+}
+
+impl Validate for PayloadFieldSchema {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            PayloadFieldSchema::FieldType(PayloadSchemaType::Composite) => {
+                let mut errors = ValidationErrors::new();
+                errors.add(
+                    "field_schema",
+                    ValidationError::new("composite index requires a list of fields"),
+                );
+                Err(errors)
+            }
+            PayloadFieldSchema::FieldType(_) => Ok(()),
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(_)) => Ok(()),
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Integer(_)) => Ok(()),
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Float(_)) => Ok(()),
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Geo(_)) => Ok(()),
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(_)) => Ok(()),
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Composite(params)) => {
+                params.validate()
+            }
+        }
+    }
+}
+
 impl From<PayloadSchemaType> for PayloadFieldSchema {
     fn from(payload_schema_type: PayloadSchemaType) -> Self {
         PayloadFieldSchema::FieldType(payload_schema_type)
@@ -1000,12 +1308,30 @@ impl TryFrom<PayloadIndexInfo> for PayloadFieldSchema {
 
     fn try_from(index_info: PayloadIndexInfo) -> Result<Self, Self::Error> {
         match (index_info.data_type, index_info.params) {
+            (PayloadSchemaType::Keyword, Some(PayloadSchemaParams::Keyword(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(params)),
+            ),
+            (PayloadSchemaType::Integer, Some(PayloadSchemaParams::Integer(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Integer(params)),
+            ),
+            (PayloadSchemaType::Float, Some(PayloadSchemaParams::Float(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Float(params)),
+            ),
+            (PayloadSchemaType::Geo, Some(PayloadSchemaParams::Geo(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Geo(params)),
+            ),
             (PayloadSchemaType::Text, Some(PayloadSchemaParams::Text(params))) => Ok(
                 PayloadFieldSchema::FieldParams(PayloadSchemaParams::Text(params)),
             ),
+            (PayloadSchemaType::Composite, Some(PayloadSchemaParams::Composite(params))) => Ok(
+                PayloadFieldSchema::FieldParams(PayloadSchemaParams::Composite(params)),
+            ),
             (data_type, Some(_)) => Err(format!(
                 "Payload field with type {data_type:?} has unexpected params"
             )),
+            (PayloadSchemaType::Composite, None) => {
+                Err("Composite payload index requires params".to_string())
+            }
             (data_type, None) => Ok(PayloadFieldSchema::FieldType(data_type)),
         }
     }
@@ -1092,6 +1418,108 @@ impl From<String> for MatchText {
     }
 }
 
+/// Full-text match of all tokens of the phrase, adjacent and in the same order.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct MatchPhrase {
+    pub phrase: String,
+}
+
+impl From<String> for MatchPhrase {
+    fn from(phrase: String) -> Self {
+        MatchPhrase { phrase }
+    }
+}
+
+/// Match of the whole keyword against a wildcard pattern, e.g. `SKU-*` or `*.pdf`.
+/// `*` stands for any sequence of characters and `?` for any single character.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(try_from = "MatchWildcardShadow")]
+#[serde(rename_all = "snake_case")]
+pub struct MatchWildcard {
+    pub wildcard: String,
+    #[serde(skip)]
+    compiled: Regex,
+}
+
+#[derive(Deserialize)]
+struct MatchWildcardShadow {
+    wildcard: String,
+}
+
+impl MatchWildcard {
+    pub fn new(wildcard: String) -> Result<Self, regex::Error> {
+        let pattern = wildcard
+            .split('*')
+            .map(|part| part.split('?').map(regex::escape).join("."))
+            .join(".*");
+        let compiled = Regex::new(&format!("^(?s:{pattern})$"))?;
+        Ok(MatchWildcard { wildcard, compiled })
+    }
+
+    pub fn is_match(&self, keyword: &str) -> bool {
+        self.compiled.is_match(keyword)
+    }
+}
+
+impl TryFrom<MatchWildcardShadow> for MatchWildcard {
+    type Error = regex::Error;
+
+    fn try_from(value: MatchWildcardShadow) -> Result<Self, Self::Error> {
+        MatchWildcard::new(value.wildcard)
+    }
+}
+
+impl PartialEq for MatchWildcard {
+    fn eq(&self, other: &Self) -> bool {
+        self.wildcard == other.wildcard
+    }
+}
+
+impl Eq for MatchWildcard {}
+
+/// Match of the whole keyword against a regular expression, e.g. `SKU-[0-9]+`
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+#[serde(try_from = "MatchRegexShadow")]
+#[serde(rename_all = "snake_case")]
+pub struct MatchRegex {
+    pub regex: String,
+    #[serde(skip)]
+    compiled: Regex,
+}
+
+#[derive(Deserialize)]
+struct MatchRegexShadow {
+    regex: String,
+}
+
+impl MatchRegex {
+    pub fn new(regex: String) -> Result<Self, regex::Error> {
+        let compiled = Regex::new(&format!("^(?:{regex})$"))?;
+        Ok(MatchRegex { regex, compiled })
+    }
+
+    pub fn is_match(&self, keyword: &str) -> bool {
+        self.compiled.is_match(keyword)
+    }
+}
+
+impl TryFrom<MatchRegexShadow> for MatchRegex {
+    type Error = regex::Error;
+
+    fn try_from(value: MatchRegexShadow) -> Result<Self, Self::Error> {
+        MatchRegex::new(value.regex)
+    }
+}
+
+impl PartialEq for MatchRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.regex == other.regex
+    }
+}
+
+impl Eq for MatchRegex {}
+
 /// Exact match on any of the given values
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -1113,8 +1541,11 @@ pub struct MatchExcept {
 pub enum MatchInterface {
     Value(MatchValue),
     Text(MatchText),
+    Phrase(MatchPhrase),
     Any(MatchAny),
     Except(MatchExcept),
+    Wildcard(MatchWildcard),
+    Regex(MatchRegex),
 }
 
 /// Match filter request
@@ -1124,8 +1555,11 @@ pub enum MatchInterface {
 pub enum Match {
     Value(MatchValue),
     Text(MatchText),
+    Phrase(MatchPhrase),
     Any(MatchAny),
     Except(MatchExcept),
+    Wildcard(MatchWildcard),
+    Regex(MatchRegex),
 }
 
 impl Match {
@@ -1158,10 +1592,15 @@ impl From<MatchInterface> for Match {
         match value {
             MatchInterface::Value(value) => Self::Value(MatchValue { value: value.value }),
             MatchInterface::Text(text) => Self::Text(MatchText { text: text.text }),
+            MatchInterface::Phrase(phrase) => Self::Phrase(MatchPhrase {
+                phrase: phrase.phrase,
+            }),
             MatchInterface::Any(any) => Self::Any(MatchAny { any: any.any }),
             MatchInterface::Except(except) => Self::Except(MatchExcept {
                 except: except.except,
             }),
+            MatchInterface::Wildcard(wildcard) => Self::Wildcard(wildcard),
+            MatchInterface::Regex(regex) => Self::Regex(regex),
         }
     }
 }
@@ -1253,6 +1692,61 @@ impl Range {
     }
 }
 
+/// Parse RFC 3339 string, e.g. `2023-10-15T10:22:19Z`, into a datetime payload value
+pub fn parse_datetime(value: &str) -> Option<DateTimePayloadType> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|datetime| datetime.with_timezone(&Utc))
+}
+
+/// Datetime range filter request, bounds are RFC 3339 strings
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Default, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct DatetimeRange {
+    /// point.key < range.lt
+    pub lt: Option<DateTimePayloadType>,
+    /// point.key > range.gt
+    pub gt: Option<DateTimePayloadType>,
+    /// point.key >= range.gte
+    pub gte: Option<DateTimePayloadType>,
+    /// point.key <= range.lte
+    pub lte: Option<DateTimePayloadType>,
+}
+
+impl DatetimeRange {
+    pub fn check_range(&self, datetime: DateTimePayloadType) -> bool {
+        self.lt.map_or(true, |x| datetime < x)
+            && self.gt.map_or(true, |x| datetime > x)
+            && self.lte.map_or(true, |x| datetime <= x)
+            && self.gte.map_or(true, |x| datetime >= x)
+    }
+
+    /// Same range over timestamps in microseconds, as they are stored in the payload index
+    pub fn to_timestamp_range(&self) -> Range {
+        let timestamp =
+            |datetime: DateTimePayloadType| datetime.timestamp_micros() as FloatPayloadType;
+        Range {
+            lt: self.lt.map(timestamp),
+            gt: self.gt.map(timestamp),
+            gte: self.gte.map(timestamp),
+            lte: self.lte.map(timestamp),
+        }
+    }
+
+    pub fn from_timestamp_range(range: &Range) -> Self {
+        let datetime = |timestamp: FloatPayloadType| {
+            NaiveDateTime::from_timestamp_micros(timestamp as i64)
+                .map(|naive| DateTime::from_naive_utc_and_offset(naive, Utc))
+        };
+        Self {
+            lt: range.lt.and_then(datetime),
+            gt: range.gt.and_then(datetime),
+            gte: range.gte.and_then(datetime),
+            lte: range.lte.and_then(datetime),
+        }
+    }
+}
+
 /// Values count filter request
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -1454,6 +1948,8 @@ pub struct FieldCondition {
     pub r#match: Option<Match>,
     /// Check if points value lies in a given range
     pub range: Option<Range>,
+    /// Check if points datetime value lies in a given range
+    pub datetime_range: Option<DatetimeRange>,
     /// Check if points geo location lies in a given area
     pub geo_bounding_box: Option<GeoBoundingBox>,
     /// Check if geo point is within a given radius
@@ -1470,6 +1966,7 @@ impl FieldCondition {
             key: key.into(),
             r#match: Some(r#match),
             range: None,
+            datetime_range: None,
             geo_bounding_box: None,
             geo_radius: None,
             geo_polygon: None,
@@ -1482,6 +1979,23 @@ impl FieldCondition {
             key: key.into(),
             r#match: None,
             range: Some(range),
+            datetime_range: None,
+            geo_bounding_box: None,
+            geo_radius: None,
+            geo_polygon: None,
+            values_count: None,
+        }
+    }
+
+    pub fn new_datetime_range(
+        key: impl Into<PayloadKeyType>,
+        datetime_range: DatetimeRange,
+    ) -> Self {
+        Self {
+            key: key.into(),
+            r#match: None,
+            range: None,
+            datetime_range: Some(datetime_range),
             geo_bounding_box: None,
             geo_radius: None,
             geo_polygon: None,
@@ -1497,6 +2011,7 @@ impl FieldCondition {
             key: key.into(),
             r#match: None,
             range: None,
+            datetime_range: None,
             geo_bounding_box: Some(geo_bounding_box),
             geo_radius: None,
             geo_polygon: None,
@@ -1509,6 +2024,7 @@ impl FieldCondition {
             key: key.into(),
             r#match: None,
             range: None,
+            datetime_range: None,
             geo_bounding_box: None,
             geo_radius: Some(geo_radius),
             geo_polygon: None,
@@ -1521,6 +2037,7 @@ impl FieldCondition {
             key: key.into(),
             r#match: None,
             range: None,
+            datetime_range: None,
             geo_bounding_box: None,
             geo_radius: None,
             geo_polygon: Some(geo_polygon),
@@ -1533,6 +2050,7 @@ impl FieldCondition {
             key: key.into(),
             r#match: None,
             range: None,
+            datetime_range: None,
             geo_bounding_box: None,
             geo_radius: None,
             geo_polygon: None,
@@ -1543,6 +2061,7 @@ impl FieldCondition {
     pub fn all_fields_none(&self) -> bool {
         self.r#match.is_none()
             && self.range.is_none()
+            && self.datetime_range.is_none()
             && self.geo_bounding_box.is_none()
             && self.geo_radius.is_none()
             && self.geo_polygon.is_none()
@@ -1607,6 +2126,18 @@ impl From<HashSet<PointIdType>> for HasIdCondition {
     }
 }
 
+/// Select points which have a value for a specified named vector
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct HasVectorCondition {
+    pub has_vector: String,
+}
+
+impl From<String> for HasVectorCondition {
+    fn from(vector: String) -> Self {
+        HasVectorCondition { has_vector: vector }
+    }
+}
+
 /// Select points with payload for a specified nested field
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Validate)]
 pub struct Nested {
@@ -1659,6 +2190,8 @@ pub enum Condition {
     IsNull(IsNullCondition),
     /// Check if points id is in a given set
     HasId(HasIdCondition),
+    /// Check if point has a value for a given named vector
+    HasVector(HasVectorCondition),
     /// Nested filters
     Nested(NestedCondition),
     /// Nested filter
@@ -1680,7 +2213,10 @@ impl Condition {
 impl Validate for Condition {
     fn validate(&self) -> Result<(), ValidationErrors> {
         match self {
-            Condition::HasId(_) | Condition::IsEmpty(_) | Condition::IsNull(_) => Ok(()),
+            Condition::HasId(_)
+            | Condition::HasVector(_)
+            | Condition::IsEmpty(_)
+            | Condition::IsNull(_) => Ok(()),
             Condition::Field(field_condition) => field_condition.validate(),
             Condition::Nested(nested_condition) => nested_condition.validate(),
             Condition::Filter(filter) => filter.validate(),
@@ -1873,6 +2409,15 @@ pub struct WithPayload {
     pub payload_selector: Option<PayloadSelector>,
 }
 
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "snake_case")]
+pub struct MinShould {
+    #[validate]
+    pub conditions: Vec<Condition>,
+    pub min_count: usize,
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone, PartialEq, Default)]
 #[serde(deny_unknown_fields)]
 #[serde(rename_all = "snake_case")]
@@ -1880,6 +2425,9 @@ pub struct Filter {
     /// At least one of those conditions should match
     #[validate]
     pub should: Option<Vec<Condition>>,
+    /// At least minimum amount of given conditions should match
+    #[validate]
+    pub min_should: Option<MinShould>,
     /// All conditions must match
     #[validate]
     pub must: Option<Vec<Condition>>,
@@ -1892,6 +2440,7 @@ impl Filter {
     pub fn new_should(condition: Condition) -> Self {
         Filter {
             should: Some(vec![condition]),
+            min_should: None,
             must: None,
             must_not: None,
         }
@@ -1900,6 +2449,7 @@ impl Filter {
     pub fn new_must(condition: Condition) -> Self {
         Filter {
             should: None,
+            min_should: None,
             must: Some(vec![condition]),
             must_not: None,
         }
@@ -1908,11 +2458,21 @@ impl Filter {
     pub fn new_must_not(condition: Condition) -> Self {
         Filter {
             should: None,
+            min_should: None,
             must: None,
             must_not: Some(vec![condition]),
         }
     }
 
+    pub fn new_min_should(min_should: MinShould) -> Self {
+        Filter {
+            should: None,
+            min_should: Some(min_should),
+            must: None,
+            must_not: None,
+        }
+    }
+
     pub fn merge(&self, other: &Filter) -> Filter {
         let merge_component = |this, other| -> Option<Vec<Condition>> {
             match (this, other) {
@@ -1925,9 +2485,23 @@ impl Filter {
                 }
             }
         };
+        let mut must = merge_component(self.must.clone(), other.must.clone());
+        let min_should = match (&self.min_should, &other.min_should) {
+            (Some(this), Some(other)) => {
+                // Only one `min_should` fits the filter, the other one becomes a nested filter
+                must.get_or_insert_with(Vec::new)
+                    .push(Condition::Filter(Filter {
+                        min_should: Some(other.clone()),
+                        ..Default::default()
+                    }));
+                Some(this.clone())
+            }
+            (this, other) => this.as_ref().or(other.as_ref()).cloned(),
+        };
         Filter {
             should: merge_component(self.should.clone(), other.should.clone()),
-            must: merge_component(self.must.clone(), other.must.clone()),
+            min_should,
+            must,
             must_not: merge_component(self.must_not.clone(), other.must_not.clone()),
         }
     }
@@ -2125,6 +2699,7 @@ mod tests {
             ))]),
             must_not: None,
             should: None,
+            min_should: None,
         };
         let json = serde_json::to_string_pretty(&filter).unwrap();
         eprintln!("{json}")
@@ -2188,6 +2763,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_match_pattern() {
+        let query = r#"
+        {
+            "key": "sku",
+            "match": { "wildcard": "SKU-*.?" }
+        }
+        "#;
+        let condition: FieldCondition = serde_json::from_str(query).unwrap();
+        let Some(Match::Wildcard(wildcard)) = condition.r#match else {
+            panic!("Match::Wildcard expected");
+        };
+        assert!(wildcard.is_match("SKU-10.a"));
+        assert!(!wildcard.is_match("SKU-10.ab"));
+        assert!(!wildcard.is_match("SKU-10-a"));
+
+        let query = r#"
+        {
+            "key": "sku",
+            "match": { "regex": "SKU-[0-9]+" }
+        }
+        "#;
+        let condition: FieldCondition = serde_json::from_str(query).unwrap();
+        let Some(Match::Regex(regex)) = condition.r#match else {
+            panic!("Match::Regex expected");
+        };
+        assert!(regex.is_match("SKU-10"));
+        assert!(!regex.is_match("OLD-SKU-10"));
+        assert_eq!(
+            serde_json::to_value(Match::Regex(regex)).unwrap(),
+            json!({ "regex": "SKU-[0-9]+" })
+        );
+
+        let query = r#"
+        {
+            "key": "sku",
+            "match": { "regex": "SKU-[0-9" }
+        }
+        "#;
+        let result: Result<FieldCondition, _> = serde_json::from_str(query);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_parse_match_any() {
         let query = r#"
@@ -2350,6 +2968,30 @@ mod tests {
         assert_eq!(c.is_null.key.as_str(), "Jason");
     }
 
+    #[test]
+    fn test_parse_has_vector_query() {
+        let query = r#"
+        {
+            "must_not": [
+                {
+                    "has_vector": "image"
+                }
+            ]
+        }
+        "#;
+
+        let filter: Filter = serde_json::from_str(query).unwrap();
+        let must_not = filter.must_not.unwrap();
+
+        assert_eq!(must_not.len(), 1);
+        let c = match must_not.get(0) {
+            Some(Condition::HasVector(c)) => c,
+            _ => panic!("Condition::HasVector expected"),
+        };
+
+        assert_eq!(c.has_vector, "image");
+    }
+
     #[test]
     fn test_parse_nested_filter_query() {
         let query = r#"
@@ -2756,6 +3398,25 @@ mod tests {
         eprintln!("field_type = {field_type:?}");
     }
 
+    #[test]
+    fn test_payload_merge_deep() {
+        let mut payload: Payload =
+            serde_json::from_str(r#"{ "metadata": { "a": 1, "b": { "c": 2, "d": 3 } }, "e": 4 }"#)
+                .unwrap();
+        let patch: Payload = serde_json::from_str(
+            r#"{ "metadata": { "b": { "c": 5, "d": null }, "f": { "g": null } }, "e": [6] }"#,
+        )
+        .unwrap();
+
+        payload.merge_deep(&patch);
+
+        let expected: Payload = serde_json::from_str(
+            r#"{ "metadata": { "a": 1, "b": { "c": 5 }, "f": {} }, "e": [6] }"#,
+        )
+        .unwrap();
+        assert_eq!(payload, expected);
+    }
+
     #[test]
     fn merge_filters() {
         let condition1 = Condition::Field(FieldCondition::new_match(
@@ -2782,6 +3443,20 @@ mod tests {
         assert!(merged.must.as_ref().unwrap().contains(&condition1));
         assert!(merged.must.as_ref().unwrap().contains(&condition2));
         assert!(merged.should.as_ref().unwrap().contains(&condition1));
+        assert!(merged.min_should.is_none());
+
+        let min_should = |condition: &Condition| MinShould {
+            conditions: vec![condition.clone(), condition.clone()],
+            min_count: 1,
+        };
+        let this = Filter::new_min_should(min_should(&condition1));
+        let other = Filter::new_min_should(min_should(&condition2));
+
+        let merged = this.merge(&other);
+
+        // Both clauses are kept, the second one as a nested filter
+        assert_eq!(merged.min_should, Some(min_should(&condition1)));
+        assert_eq!(merged.must, Some(vec![Condition::Filter(other)]));
     }
 
     #[test]
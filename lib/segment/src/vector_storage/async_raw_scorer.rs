@@ -4,11 +4,12 @@ use bitvec::prelude::BitSlice;
 use common::fixed_length_priority_queue::FixedLengthPriorityQueue;
 use common::types::{PointOffsetType, ScoreType, ScoredPointOffset};
 
-use super::query_scorer::reco_query_scorer::RecoQueryScorer;
+use super::query_scorer::custom_query_scorer::CustomQueryScorer;
 use crate::common::operation_error::OperationResult;
 use crate::data_types::vectors::QueryVector;
 use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
+use crate::spaces::simple_binary::{HammingMetric, JaccardMetric};
 use crate::types::Distance;
 use crate::vector_storage::memmap_vector_storage::MemmapVectorStorage;
 use crate::vector_storage::mmap_vectors::MmapVectors;
@@ -241,6 +242,9 @@ impl<'a> AsyncRawScorerBuilder<'a> {
             Distance::Cosine => self._build_with_metric::<CosineMetric>(),
             Distance::Euclid => self._build_with_metric::<EuclidMetric>(),
             Distance::Dot => self._build_with_metric::<DotProductMetric>(),
+            Distance::Manhattan => self._build_with_metric::<ManhattanMetric>(),
+            Distance::Hamming => self._build_with_metric::<HammingMetric>(),
+            Distance::Jaccard => self._build_with_metric::<JaccardMetric>(),
         }
     }
 
@@ -272,8 +276,8 @@ impl<'a> AsyncRawScorerBuilder<'a> {
                     is_stopped.unwrap_or(&DEFAULT_STOPPED),
                 ))
             }
-            QueryVector::Recommend(query) => {
-                let query_scorer = RecoQueryScorer::<TMetric, _>::new(query, storage);
+            QueryVector::Recommend(reco_query) => {
+                let query_scorer = CustomQueryScorer::<TMetric, _, _>::new(reco_query, storage);
                 Box::new(AsyncRawScorerImpl::new(
                     points_count,
                     query_scorer,
@@ -283,6 +287,32 @@ impl<'a> AsyncRawScorerBuilder<'a> {
                     is_stopped.unwrap_or(&DEFAULT_STOPPED),
                 ))
             }
+            QueryVector::Discovery(discovery_query) => {
+                let query_scorer =
+                    CustomQueryScorer::<TMetric, _, _>::new(discovery_query, storage);
+                Box::new(AsyncRawScorerImpl::new(
+                    points_count,
+                    query_scorer,
+                    storage.get_mmap_vectors(),
+                    point_deleted,
+                    vec_deleted,
+                    is_stopped.unwrap_or(&DEFAULT_STOPPED),
+                ))
+            }
+            QueryVector::Context(context_query) => {
+                let query_scorer = CustomQueryScorer::<TMetric, _, _>::new(context_query, storage);
+                Box::new(AsyncRawScorerImpl::new(
+                    points_count,
+                    query_scorer,
+                    storage.get_mmap_vectors(),
+                    point_deleted,
+                    vec_deleted,
+                    is_stopped.unwrap_or(&DEFAULT_STOPPED),
+                ))
+            }
+            QueryVector::Sparse(_) => {
+                unreachable!("sparse query is checked against segment config")
+            }
         }
     }
 }
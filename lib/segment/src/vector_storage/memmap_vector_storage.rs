@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::fs::{create_dir_all, File, OpenOptions};
 use std::io::{self, Write};
 use std::ops::Range;
@@ -100,8 +101,8 @@ impl VectorStorage for MemmapVectorStorage {
         self.mmap_store.as_ref().unwrap().num_vectors
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.mmap_store.as_ref().unwrap().get_vector(key)
+    fn get_vector(&self, key: PointOffsetType) -> Cow<[VectorElementType]> {
+        Cow::Borrowed(self.mmap_store.as_ref().unwrap().get_vector(key))
     }
 
     fn insert_vector(
@@ -134,7 +135,7 @@ impl VectorStorage for MemmapVectorStorage {
         for id in other_ids {
             check_process_stopped(stopped)?;
             let vector = other.get_vector(id);
-            let raw_bites = mmap_ops::transmute_to_u8_slice(vector);
+            let raw_bites = mmap_ops::transmute_to_u8_slice(vector.as_ref());
             vectors_file.write_all(raw_bites)?;
             end_index += 1;
 
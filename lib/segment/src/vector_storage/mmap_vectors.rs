@@ -13,6 +13,7 @@ use parking_lot::Mutex;
 
 use super::div_ceil;
 use crate::common::error_logging::LogError;
+use crate::common::hardware_counter::add_vector_bytes_read;
 use crate::common::mmap_type::MmapBitSlice;
 use crate::common::operation_error::OperationResult;
 use crate::common::Flusher;
@@ -177,6 +178,7 @@ impl MmapVectors {
     /// Returns reference to vector data by key
     pub fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
         let offset = self.data_offset(key).unwrap();
+        add_vector_bytes_read(self.raw_size());
         self.raw_vector_offset(offset)
     }
 
@@ -212,13 +214,17 @@ impl MmapVectors {
     fn process_points_uring(
         &self,
         points: impl Iterator<Item = PointOffsetType>,
-        callback: impl FnMut(usize, PointOffsetType, &[VectorElementType]),
+        mut callback: impl FnMut(usize, PointOffsetType, &[VectorElementType]),
     ) -> OperationResult<()> {
+        let raw_size = self.raw_size();
         self.uring_reader
             .lock()
             .as_mut()
             .expect("io_uring reader should be initialized")
-            .read_stream(points, callback)
+            .read_stream(points, |idx, point, vector| {
+                add_vector_bytes_read(raw_size);
+                callback(idx, point, vector)
+            })
     }
 
     #[cfg(not(target_os = "linux"))]
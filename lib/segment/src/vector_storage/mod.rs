@@ -9,6 +9,10 @@ pub mod memmap_vector_storage;
 mod mmap_vectors;
 pub mod quantized;
 pub mod raw_scorer;
+pub mod simple_byte_vector_storage;
+pub mod simple_half_vector_storage;
+pub mod simple_multi_dense_vector_storage;
+pub mod simple_sparse_vector_storage;
 pub mod simple_vector_storage;
 mod vector_storage_base;
 
@@ -1,5 +1,5 @@
+mod quantized_custom_query_scorer;
 mod quantized_mmap_storage;
 mod quantized_query_scorer;
-mod quantized_reco_query_scorer;
 mod quantized_scorer_builder;
 pub mod quantized_vectors;
@@ -1,27 +1,34 @@
+use std::marker::PhantomData;
+
 use common::types::{PointOffsetType, ScoreType};
 
 use crate::data_types::vectors::{VectorElementType, VectorType};
 use crate::types::Distance;
-use crate::vector_storage::query::reco_query::RecoQuery;
+use crate::vector_storage::query::{Query, TransformInto};
 use crate::vector_storage::query_scorer::QueryScorer;
 
-pub struct QuantizedRecoQueryScorer<'a, TEncodedQuery, TEncodedVectors>
+pub struct QuantizedCustomQueryScorer<'a, TEncodedQuery, TEncodedVectors, TQuery, TOriginalQuery>
 where
     TEncodedVectors: quantization::EncodedVectors<TEncodedQuery>,
+    TQuery: Query<TEncodedQuery>,
+    TOriginalQuery: Query<VectorType>,
 {
-    original_query: RecoQuery<VectorType>,
-    query: RecoQuery<TEncodedQuery>,
+    original_query: TOriginalQuery,
+    query: TQuery,
     quantized_storage: &'a TEncodedVectors,
     distance: Distance,
+    phantom: PhantomData<TEncodedQuery>,
 }
 
-impl<'a, TEncodedQuery, TEncodedVectors>
-    QuantizedRecoQueryScorer<'a, TEncodedQuery, TEncodedVectors>
+impl<'a, TEncodedQuery, TEncodedVectors, TQuery, TOriginalQuery>
+    QuantizedCustomQueryScorer<'a, TEncodedQuery, TEncodedVectors, TQuery, TOriginalQuery>
 where
     TEncodedVectors: quantization::EncodedVectors<TEncodedQuery>,
+    TQuery: Query<TEncodedQuery>,
+    TOriginalQuery: Query<VectorType> + TransformInto<TQuery, VectorType, TEncodedQuery> + Clone,
 {
     pub fn new(
-        raw_query: RecoQuery<VectorType>,
+        raw_query: impl TransformInto<TOriginalQuery>,
         quantized_storage: &'a TEncodedVectors,
         distance: Distance,
     ) -> Self {
@@ -35,14 +42,17 @@ where
             query,
             quantized_storage,
             distance,
+            phantom: PhantomData,
         }
     }
 }
 
-impl<TEncodedQuery, TEncodedVectors> QueryScorer
-    for QuantizedRecoQueryScorer<'_, TEncodedQuery, TEncodedVectors>
+impl<TEncodedQuery, TEncodedVectors, TQuery, TOriginalQuery> QueryScorer
+    for QuantizedCustomQueryScorer<'_, TEncodedQuery, TEncodedVectors, TQuery, TOriginalQuery>
 where
     TEncodedVectors: quantization::EncodedVectors<TEncodedQuery>,
+    TQuery: Query<TEncodedQuery>,
+    TOriginalQuery: Query<VectorType>,
 {
     fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
         self.query
@@ -59,6 +69,6 @@ where
     }
 
     fn score_internal(&self, _point_a: PointOffsetType, _point_b: PointOffsetType) -> ScoreType {
-        unimplemented!("Recommendation scorer compares against multiple vectors, not just one")
+        unimplemented!("Custom scorer compares against multiple vectors, not just one")
     }
 }
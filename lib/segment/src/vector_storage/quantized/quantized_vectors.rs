@@ -8,7 +8,7 @@ use quantization::{EncodedVectors, EncodedVectorsPQ, EncodedVectorsU8};
 use serde::{Deserialize, Serialize};
 
 use super::quantized_scorer_builder::QuantizedScorerBuilder;
-use crate::common::operation_error::OperationResult;
+use crate::common::operation_error::{OperationError, OperationResult};
 use crate::common::vector_utils::TrySetCapacityExact;
 use crate::data_types::vectors::{QueryVector, VectorElementType};
 use crate::types::{
@@ -110,7 +110,7 @@ impl QuantizedVectors {
         max_threads: usize,
         stopped: &AtomicBool,
     ) -> OperationResult<Self> {
-        let vector_parameters = Self::construct_vector_parameters(distance, dim, count);
+        let vector_parameters = Self::construct_vector_parameters(distance, dim, count)?;
 
         let quantized_storage = match quantization_config {
             QuantizationConfig::Scalar(ScalarQuantization {
@@ -367,17 +367,24 @@ impl QuantizedVectors {
         distance: Distance,
         dim: usize,
         count: usize,
-    ) -> quantization::VectorParameters {
-        quantization::VectorParameters {
+    ) -> OperationResult<quantization::VectorParameters> {
+        let distance_type = match distance {
+            Distance::Cosine => quantization::DistanceType::Dot,
+            Distance::Euclid => quantization::DistanceType::L2,
+            Distance::Dot => quantization::DistanceType::Dot,
+            Distance::Manhattan => quantization::DistanceType::L1,
+            Distance::Hamming | Distance::Jaccard => {
+                return Err(OperationError::service_error(format!(
+                    "Vectors with {distance:?} distance can't be quantized"
+                )));
+            }
+        };
+        Ok(quantization::VectorParameters {
             dim,
             count,
-            distance_type: match distance {
-                Distance::Cosine => quantization::DistanceType::Dot,
-                Distance::Euclid => quantization::DistanceType::L2,
-                Distance::Dot => quantization::DistanceType::Dot,
-            },
-            invert: distance == Distance::Euclid,
-        }
+            distance_type,
+            invert: distance == Distance::Euclid || distance == Distance::Manhattan,
+        })
     }
 
     fn get_bucket_size(compression: CompressionRatio) -> usize {
@@ -0,0 +1,132 @@
+use common::types::ScoreType;
+
+use super::{Query, TransformInto};
+use crate::data_types::vectors::{QueryVector, VectorType};
+
+/// Pair of examples, which splits the space into the positive and the negative zone
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextPair<T> {
+    pub positive: T,
+    pub negative: T,
+}
+
+impl<T> ContextPair<T> {
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        [&self.positive, &self.negative].into_iter()
+    }
+
+    pub fn transform<F, U>(self, mut f: F) -> ContextPair<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        ContextPair {
+            positive: f(self.positive),
+            negative: f(self.negative),
+        }
+    }
+
+    /// `1` if the vector is closer to the positive example, `-1` otherwise
+    pub fn rank_by(&self, similarity: impl Fn(&T) -> ScoreType) -> i32 {
+        if similarity(&self.positive) > similarity(&self.negative) {
+            1
+        } else {
+            -1
+        }
+    }
+
+    /// Smooth version of the rank: `0` in the positive zone, otherwise the negative difference
+    /// of similarities, so the search can approach the positive zone gradually.
+    pub fn loss_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType {
+        const MARGIN: ScoreType = ScoreType::EPSILON;
+
+        let difference = similarity(&self.positive) - similarity(&self.negative) - MARGIN;
+        difference.min(0.0)
+    }
+}
+
+/// Query for the points, which are in the positive zone of as many pairs as possible.
+///
+/// Score is the sum of the losses of all pairs, so it is `0` for points in the positive zones
+/// of all pairs, and negative otherwise.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContextQuery<T> {
+    pub pairs: Vec<ContextPair<T>>,
+}
+
+impl<T> ContextQuery<T> {
+    pub fn new(pairs: Vec<ContextPair<T>>) -> Self {
+        Self { pairs }
+    }
+
+    pub fn iter_all(&self) -> impl Iterator<Item = &T> {
+        self.pairs.iter().flat_map(ContextPair::iter)
+    }
+}
+
+impl<T, U> TransformInto<ContextQuery<U>, T, U> for ContextQuery<T> {
+    fn transform<F>(self, mut f: F) -> ContextQuery<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        ContextQuery::new(
+            self.pairs
+                .into_iter()
+                .map(|pair| pair.transform(&mut f))
+                .collect(),
+        )
+    }
+}
+
+impl<T> Query<T> for ContextQuery<T> {
+    fn score_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType {
+        self.pairs
+            .iter()
+            .map(|pair| pair.loss_by(&similarity))
+            .sum()
+    }
+}
+
+impl From<ContextQuery<VectorType>> for QueryVector {
+    fn from(query: ContextQuery<VectorType>) -> Self {
+        QueryVector::Context(query)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::types::ScoreType;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn dummy_similarity(x: &isize) -> ScoreType {
+        *x as ScoreType
+    }
+
+    #[rstest]
+    #[case::closer_to_positive(2, 1, 1)]
+    #[case::closer_to_negative(1, 2, -1)]
+    #[case::equal(1, 1, -1)]
+    fn rank_pair(#[case] positive: isize, #[case] negative: isize, #[case] expected: i32) {
+        let pair = ContextPair { positive, negative };
+        assert_eq!(pair.rank_by(dummy_similarity), expected);
+    }
+
+    #[rstest]
+    #[case::all_positive(vec![(3, 1), (5, 2)], 0.0)]
+    #[case::one_negative(vec![(3, 1), (1, 4)], -3.0)]
+    #[case::all_negative(vec![(1, 2), (1, 4)], -4.0)]
+    #[case::no_pairs(vec![], 0.0)]
+    fn score_context(#[case] pairs: Vec<(isize, isize)>, #[case] expected: ScoreType) {
+        let query = ContextQuery::new(
+            pairs
+                .into_iter()
+                .map(|(positive, negative)| ContextPair { positive, negative })
+                .collect(),
+        );
+
+        let score = query.score_by(dummy_similarity);
+
+        assert!((score - expected).abs() < 1e-5, "{score} != {expected}");
+    }
+}
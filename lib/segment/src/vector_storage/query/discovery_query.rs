@@ -0,0 +1,111 @@
+use common::types::ScoreType;
+
+use super::context_query::ContextPair;
+use super::{Query, TransformInto};
+use crate::data_types::vectors::{QueryVector, VectorType};
+
+/// Query for the points, which are in the positive zones of the context pairs
+/// and as close as possible to the target.
+///
+/// Score is the sum of the ranks of all pairs, plus the similarity to the target squashed
+/// into `(0, 1)`. So the context always dominates the target: a point in the positive zone
+/// of one more pair is better, regardless of its similarity to the target.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiscoveryQuery<T> {
+    pub target: T,
+    pub pairs: Vec<ContextPair<T>>,
+}
+
+impl<T> DiscoveryQuery<T> {
+    pub fn new(target: T, pairs: Vec<ContextPair<T>>) -> Self {
+        Self { target, pairs }
+    }
+
+    pub fn iter_all(&self) -> impl Iterator<Item = &T> {
+        std::iter::once(&self.target).chain(self.pairs.iter().flat_map(ContextPair::iter))
+    }
+
+    fn rank_by(&self, similarity: impl Fn(&T) -> ScoreType) -> i32 {
+        self.pairs
+            .iter()
+            .map(|pair| pair.rank_by(&similarity))
+            .sum()
+    }
+}
+
+impl<T, U> TransformInto<DiscoveryQuery<U>, T, U> for DiscoveryQuery<T> {
+    fn transform<F>(self, mut f: F) -> DiscoveryQuery<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        DiscoveryQuery::new(
+            f(self.target),
+            self.pairs
+                .into_iter()
+                .map(|pair| pair.transform(&mut f))
+                .collect(),
+        )
+    }
+}
+
+impl<T> Query<T> for DiscoveryQuery<T> {
+    fn score_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType {
+        let rank = self.rank_by(&similarity);
+        let target_similarity = scaled_fast_sigmoid(similarity(&self.target));
+
+        rank as ScoreType + target_similarity
+    }
+}
+
+/// Monotonic mapping of any score into `(0, 1)`, cheaper than the logistic function
+fn scaled_fast_sigmoid(x: ScoreType) -> ScoreType {
+    0.5 * (x / (1.0 + x.abs()) + 1.0)
+}
+
+impl From<DiscoveryQuery<VectorType>> for QueryVector {
+    fn from(query: DiscoveryQuery<VectorType>) -> Self {
+        QueryVector::Discovery(query)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use common::types::ScoreType;
+    use rstest::rstest;
+
+    use super::*;
+
+    fn dummy_similarity(x: &isize) -> ScoreType {
+        *x as ScoreType
+    }
+
+    #[rstest]
+    #[case::no_context(vec![], 0.0)]
+    #[case::positive_zone(vec![(2, 1), (3, 1)], 2.0)]
+    #[case::mixed_zones(vec![(2, 1), (1, 3)], 0.0)]
+    #[case::negative_zone(vec![(1, 2), (1, 3)], -2.0)]
+    fn score_discovery(#[case] pairs: Vec<(isize, isize)>, #[case] expected_rank: ScoreType) {
+        let pairs: Vec<_> = pairs
+            .into_iter()
+            .map(|(positive, negative)| ContextPair { positive, negative })
+            .collect();
+
+        let query = DiscoveryQuery::new(0, pairs.clone());
+        let score = query.score_by(dummy_similarity);
+        // Target similarity `0` is squashed into the middle of `(0, 1)`
+        assert!((score - (expected_rank + 0.5)).abs() < 1e-5, "{score}");
+
+        // Similarity to the target never outweighs a single rank
+        let closer = DiscoveryQuery::new(1000, pairs).score_by(dummy_similarity);
+        assert!(closer > score);
+        assert!(closer < expected_rank + 1.0);
+    }
+
+    #[test]
+    fn test_sigmoid_is_monotonic() {
+        let values = [-1000.0, -1.0, -0.5, 0.0, 0.5, 1.0, 1000.0];
+        let scaled: Vec<_> = values.iter().map(|&x| scaled_fast_sigmoid(x)).collect();
+        assert!(scaled.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(scaled.iter().all(|&x| 0.0 < x && x < 1.0));
+    }
+}
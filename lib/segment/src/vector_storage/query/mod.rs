@@ -1 +1,21 @@
+use common::types::ScoreType;
+
+use crate::data_types::vectors::VectorType;
+
+pub mod context_query;
+pub mod discovery_query;
 pub mod reco_query;
+
+/// Query, which is scored by multiple vectors, e.g. by examples of a recommendation
+pub trait Query<T> {
+    /// Compares all vectors of the query against a single vector via a similarity function,
+    /// then folds the similarities into a single score.
+    fn score_by(&self, similarity: impl Fn(&T) -> ScoreType) -> ScoreType;
+}
+
+/// Maps every vector of the query, e.g. to preprocess or to quantize it
+pub trait TransformInto<Output, T = VectorType, U = VectorType> {
+    fn transform<F>(self, f: F) -> Output
+    where
+        F: FnMut(T) -> U;
+}
@@ -0,0 +1,53 @@
+use std::marker::PhantomData;
+
+use common::types::{PointOffsetType, ScoreType};
+
+use crate::data_types::vectors::VectorElementType;
+use crate::spaces::simple_byte::ByteMetric;
+use crate::vector_storage::query::{Query, TransformInto};
+use crate::vector_storage::query_scorer::QueryScorer;
+use crate::vector_storage::simple_byte_vector_storage::{to_byte_vector, SimpleByteVectorStorage};
+
+pub struct ByteCustomQueryScorer<'a, TMetric: ByteMetric, TQuery: Query<Vec<u8>>> {
+    vector_storage: &'a SimpleByteVectorStorage,
+    query: TQuery,
+    metric: PhantomData<TMetric>,
+}
+
+impl<'a, TMetric: ByteMetric, TQuery: Query<Vec<u8>>> ByteCustomQueryScorer<'a, TMetric, TQuery> {
+    pub fn new(
+        query: impl TransformInto<TQuery, Vec<VectorElementType>, Vec<u8>>,
+        vector_storage: &'a SimpleByteVectorStorage,
+    ) -> Self {
+        let query = query.transform(|vector| to_byte_vector(&vector));
+
+        Self {
+            query,
+            vector_storage,
+            metric: PhantomData,
+        }
+    }
+
+    fn score_bytes(&self, against: &[u8]) -> ScoreType {
+        self.query
+            .score_by(|example| TMetric::similarity_bytes(example, against))
+    }
+}
+
+impl<'a, TMetric: ByteMetric, TQuery: Query<Vec<u8>>> QueryScorer
+    for ByteCustomQueryScorer<'a, TMetric, TQuery>
+{
+    #[inline]
+    fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
+        self.score_bytes(self.vector_storage.get_byte_vector(idx))
+    }
+
+    #[inline]
+    fn score(&self, against: &[VectorElementType]) -> ScoreType {
+        self.score_bytes(&to_byte_vector(against))
+    }
+
+    fn score_internal(&self, _point_a: PointOffsetType, _point_b: PointOffsetType) -> ScoreType {
+        unimplemented!("Custom scorer compares against multiple vectors, not just one")
+    }
+}
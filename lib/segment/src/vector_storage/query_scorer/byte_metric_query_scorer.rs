@@ -0,0 +1,42 @@
+use std::marker::PhantomData;
+
+use common::types::{PointOffsetType, ScoreType};
+
+use crate::data_types::vectors::{VectorElementType, VectorType};
+use crate::spaces::simple_byte::ByteMetric;
+use crate::vector_storage::query_scorer::QueryScorer;
+use crate::vector_storage::simple_byte_vector_storage::{to_byte_vector, SimpleByteVectorStorage};
+
+pub struct ByteMetricQueryScorer<'a, TMetric: ByteMetric> {
+    vector_storage: &'a SimpleByteVectorStorage,
+    query: Vec<u8>,
+    metric: PhantomData<TMetric>,
+}
+
+impl<'a, TMetric: ByteMetric> ByteMetricQueryScorer<'a, TMetric> {
+    pub fn new(query: VectorType, vector_storage: &'a SimpleByteVectorStorage) -> Self {
+        Self {
+            query: to_byte_vector(&query),
+            vector_storage,
+            metric: PhantomData,
+        }
+    }
+}
+
+impl<'a, TMetric: ByteMetric> QueryScorer for ByteMetricQueryScorer<'a, TMetric> {
+    #[inline]
+    fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
+        TMetric::similarity_bytes(&self.query, self.vector_storage.get_byte_vector(idx))
+    }
+
+    #[inline]
+    fn score(&self, v2: &[VectorElementType]) -> ScoreType {
+        TMetric::similarity_bytes(&self.query, &to_byte_vector(v2))
+    }
+
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        let v1 = self.vector_storage.get_byte_vector(point_a);
+        let v2 = self.vector_storage.get_byte_vector(point_b);
+        TMetric::similarity_bytes(v1, v2)
+    }
+}
@@ -4,20 +4,25 @@ use common::types::{PointOffsetType, ScoreType};
 
 use crate::data_types::vectors::{VectorElementType, VectorType};
 use crate::spaces::metric::Metric;
-use crate::vector_storage::query::reco_query::RecoQuery;
+use crate::vector_storage::query::{Query, TransformInto};
 use crate::vector_storage::query_scorer::QueryScorer;
 use crate::vector_storage::VectorStorage;
 
-pub struct RecoQueryScorer<'a, TMetric: Metric, TVectorStorage: VectorStorage> {
+pub struct CustomQueryScorer<
+    'a,
+    TMetric: Metric,
+    TVectorStorage: VectorStorage,
+    TQuery: Query<VectorType>,
+> {
     vector_storage: &'a TVectorStorage,
-    query: RecoQuery<VectorType>,
+    query: TQuery,
     metric: PhantomData<TMetric>,
 }
 
-impl<'a, TMetric: Metric, TVectorStorage: VectorStorage>
-    RecoQueryScorer<'a, TMetric, TVectorStorage>
+impl<'a, TMetric: Metric, TVectorStorage: VectorStorage, TQuery: Query<VectorType>>
+    CustomQueryScorer<'a, TMetric, TVectorStorage, TQuery>
 {
-    pub fn new(query: RecoQuery<VectorType>, vector_storage: &'a TVectorStorage) -> Self {
+    pub fn new(query: impl TransformInto<TQuery>, vector_storage: &'a TVectorStorage) -> Self {
         let query = query.transform(|vector| TMetric::preprocess(vector));
 
         Self {
@@ -28,13 +33,13 @@ impl<'a, TMetric: Metric, TVectorStorage: VectorStorage>
     }
 }
 
-impl<'a, TMetric: Metric, TVectorStorage: VectorStorage> QueryScorer
-    for RecoQueryScorer<'a, TMetric, TVectorStorage>
+impl<'a, TMetric: Metric, TVectorStorage: VectorStorage, TQuery: Query<VectorType>> QueryScorer
+    for CustomQueryScorer<'a, TMetric, TVectorStorage, TQuery>
 {
     #[inline]
     fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
         let stored = self.vector_storage.get_vector(idx);
-        self.score(stored)
+        self.score(&stored)
     }
 
     #[inline]
@@ -44,6 +49,6 @@ impl<'a, TMetric: Metric, TVectorStorage: VectorStorage> QueryScorer
     }
 
     fn score_internal(&self, _point_a: PointOffsetType, _point_b: PointOffsetType) -> ScoreType {
-        unimplemented!("Recommendation scorer compares against multiple vectors, not just one")
+        unimplemented!("Custom scorer compares against multiple vectors, not just one")
     }
 }
@@ -0,0 +1,54 @@
+use std::marker::PhantomData;
+
+use common::types::{PointOffsetType, ScoreType};
+use half::f16;
+
+use crate::data_types::vectors::VectorElementType;
+use crate::spaces::simple_half::HalfMetric;
+use crate::vector_storage::query::{Query, TransformInto};
+use crate::vector_storage::query_scorer::QueryScorer;
+use crate::vector_storage::simple_half_vector_storage::{to_half_vector, SimpleHalfVectorStorage};
+
+pub struct HalfCustomQueryScorer<'a, TMetric: HalfMetric, TQuery: Query<Vec<f16>>> {
+    vector_storage: &'a SimpleHalfVectorStorage,
+    query: TQuery,
+    metric: PhantomData<TMetric>,
+}
+
+impl<'a, TMetric: HalfMetric, TQuery: Query<Vec<f16>>> HalfCustomQueryScorer<'a, TMetric, TQuery> {
+    pub fn new(
+        query: impl TransformInto<TQuery, Vec<VectorElementType>, Vec<f16>>,
+        vector_storage: &'a SimpleHalfVectorStorage,
+    ) -> Self {
+        let query = query.transform(|vector| to_half_vector(&TMetric::preprocess(vector)));
+
+        Self {
+            query,
+            vector_storage,
+            metric: PhantomData,
+        }
+    }
+
+    fn score_half(&self, against: &[f16]) -> ScoreType {
+        self.query
+            .score_by(|example| TMetric::similarity_half(example, against))
+    }
+}
+
+impl<'a, TMetric: HalfMetric, TQuery: Query<Vec<f16>>> QueryScorer
+    for HalfCustomQueryScorer<'a, TMetric, TQuery>
+{
+    #[inline]
+    fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
+        self.score_half(self.vector_storage.get_half_vector(idx))
+    }
+
+    #[inline]
+    fn score(&self, against: &[VectorElementType]) -> ScoreType {
+        self.score_half(&to_half_vector(against))
+    }
+
+    fn score_internal(&self, _point_a: PointOffsetType, _point_b: PointOffsetType) -> ScoreType {
+        unimplemented!("Custom scorer compares against multiple vectors, not just one")
+    }
+}
@@ -0,0 +1,43 @@
+use std::marker::PhantomData;
+
+use common::types::{PointOffsetType, ScoreType};
+use half::f16;
+
+use crate::data_types::vectors::{VectorElementType, VectorType};
+use crate::spaces::simple_half::HalfMetric;
+use crate::vector_storage::query_scorer::QueryScorer;
+use crate::vector_storage::simple_half_vector_storage::{to_half_vector, SimpleHalfVectorStorage};
+
+pub struct HalfMetricQueryScorer<'a, TMetric: HalfMetric> {
+    vector_storage: &'a SimpleHalfVectorStorage,
+    query: Vec<f16>,
+    metric: PhantomData<TMetric>,
+}
+
+impl<'a, TMetric: HalfMetric> HalfMetricQueryScorer<'a, TMetric> {
+    pub fn new(query: VectorType, vector_storage: &'a SimpleHalfVectorStorage) -> Self {
+        Self {
+            query: to_half_vector(&TMetric::preprocess(query)),
+            vector_storage,
+            metric: PhantomData,
+        }
+    }
+}
+
+impl<'a, TMetric: HalfMetric> QueryScorer for HalfMetricQueryScorer<'a, TMetric> {
+    #[inline]
+    fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
+        TMetric::similarity_half(&self.query, self.vector_storage.get_half_vector(idx))
+    }
+
+    #[inline]
+    fn score(&self, v2: &[VectorElementType]) -> ScoreType {
+        TMetric::similarity_half(&self.query, &to_half_vector(v2))
+    }
+
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        let v1 = self.vector_storage.get_half_vector(point_a);
+        let v2 = self.vector_storage.get_half_vector(point_b);
+        TMetric::similarity_half(v1, v2)
+    }
+}
@@ -30,7 +30,7 @@ impl<'a, TMetric: Metric, TVectorStorage: VectorStorage> QueryScorer
 {
     #[inline]
     fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
-        TMetric::similarity(&self.query, self.vector_storage.get_vector(idx))
+        TMetric::similarity(&self.query, &self.vector_storage.get_vector(idx))
     }
 
     #[inline]
@@ -41,6 +41,6 @@ impl<'a, TMetric: Metric, TVectorStorage: VectorStorage> QueryScorer
     fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
         let v1 = self.vector_storage.get_vector(point_a);
         let v2 = self.vector_storage.get_vector(point_b);
-        TMetric::similarity(v1, v2)
+        TMetric::similarity(&v1, &v2)
     }
 }
@@ -2,8 +2,14 @@ use common::types::{PointOffsetType, ScoreType};
 
 use crate::data_types::vectors::VectorElementType;
 
+pub mod byte_custom_query_scorer;
+pub mod byte_metric_query_scorer;
+pub mod custom_query_scorer;
+pub mod half_custom_query_scorer;
+pub mod half_metric_query_scorer;
 pub mod metric_query_scorer;
-pub mod reco_query_scorer;
+pub mod multi_custom_query_scorer;
+pub mod multi_metric_query_scorer;
 
 pub trait QueryScorer {
     fn score_stored(&self, idx: PointOffsetType) -> ScoreType;
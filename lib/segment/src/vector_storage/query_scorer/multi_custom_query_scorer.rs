@@ -0,0 +1,57 @@
+use std::marker::PhantomData;
+
+use common::types::{PointOffsetType, ScoreType};
+
+use super::multi_metric_query_scorer::{preprocess_multi, score_max_sim};
+use crate::data_types::vectors::{VectorElementType, VectorType};
+use crate::spaces::metric::Metric;
+use crate::vector_storage::query::{Query, TransformInto};
+use crate::vector_storage::query_scorer::QueryScorer;
+use crate::vector_storage::VectorStorage;
+
+pub struct MultiCustomQueryScorer<
+    'a,
+    TMetric: Metric,
+    TVectorStorage: VectorStorage,
+    TQuery: Query<VectorType>,
+> {
+    vector_storage: &'a TVectorStorage,
+    query: TQuery,
+    metric: PhantomData<TMetric>,
+}
+
+impl<'a, TMetric: Metric, TVectorStorage: VectorStorage, TQuery: Query<VectorType>>
+    MultiCustomQueryScorer<'a, TMetric, TVectorStorage, TQuery>
+{
+    pub fn new(query: impl TransformInto<TQuery>, vector_storage: &'a TVectorStorage) -> Self {
+        let dim = vector_storage.vector_dim();
+        let query = query.transform(|vector| preprocess_multi::<TMetric>(vector, dim));
+
+        Self {
+            query,
+            vector_storage,
+            metric: PhantomData,
+        }
+    }
+}
+
+impl<'a, TMetric: Metric, TVectorStorage: VectorStorage, TQuery: Query<VectorType>> QueryScorer
+    for MultiCustomQueryScorer<'a, TMetric, TVectorStorage, TQuery>
+{
+    #[inline]
+    fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
+        let stored = self.vector_storage.get_vector(idx);
+        self.score(&stored)
+    }
+
+    #[inline]
+    fn score(&self, against: &[VectorElementType]) -> ScoreType {
+        let dim = self.vector_storage.vector_dim();
+        self.query
+            .score_by(|example| score_max_sim::<TMetric>(example, against, dim))
+    }
+
+    fn score_internal(&self, _point_a: PointOffsetType, _point_b: PointOffsetType) -> ScoreType {
+        unimplemented!("Custom scorer compares against multiple vectors, not just one")
+    }
+}
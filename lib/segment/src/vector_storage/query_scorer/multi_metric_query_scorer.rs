@@ -0,0 +1,80 @@
+use std::marker::PhantomData;
+
+use common::types::{PointOffsetType, ScoreType};
+
+use crate::data_types::vectors::{VectorElementType, VectorType};
+use crate::spaces::metric::Metric;
+use crate::vector_storage::query_scorer::QueryScorer;
+use crate::vector_storage::VectorStorage;
+
+pub struct MultiMetricQueryScorer<'a, TMetric: Metric, TVectorStorage: VectorStorage> {
+    vector_storage: &'a TVectorStorage,
+    query: Vec<VectorElementType>,
+    metric: PhantomData<TMetric>,
+}
+
+impl<'a, TMetric: Metric, TVectorStorage: VectorStorage>
+    MultiMetricQueryScorer<'a, TMetric, TVectorStorage>
+{
+    pub fn new(query: VectorType, vector_storage: &'a TVectorStorage) -> Self {
+        Self {
+            query: preprocess_multi::<TMetric>(query, vector_storage.vector_dim()),
+            vector_storage,
+            metric: PhantomData,
+        }
+    }
+}
+
+impl<'a, TMetric: Metric, TVectorStorage: VectorStorage> QueryScorer
+    for MultiMetricQueryScorer<'a, TMetric, TVectorStorage>
+{
+    #[inline]
+    fn score_stored(&self, idx: PointOffsetType) -> ScoreType {
+        self.score(&self.vector_storage.get_vector(idx))
+    }
+
+    #[inline]
+    fn score(&self, v2: &[VectorElementType]) -> ScoreType {
+        score_max_sim::<TMetric>(&self.query, v2, self.vector_storage.vector_dim())
+    }
+
+    fn score_internal(&self, point_a: PointOffsetType, point_b: PointOffsetType) -> ScoreType {
+        let v1 = self.vector_storage.get_vector(point_a);
+        let v2 = self.vector_storage.get_vector(point_b);
+        score_max_sim::<TMetric>(&v1, &v2, self.vector_storage.vector_dim())
+    }
+}
+
+/// Preprocess each of the flattened vectors separately
+pub fn preprocess_multi<TMetric: Metric>(multi_vector: VectorType, dim: usize) -> VectorType {
+    if dim == 0 {
+        return multi_vector;
+    }
+    multi_vector
+        .chunks(dim)
+        .flat_map(|vector| TMetric::preprocess(vector.to_vec()))
+        .collect()
+}
+
+/// Late interaction score of two flattened multivectors
+///
+/// For each vector of the query, take the best similarity to any of the stored vectors and sum them up.
+pub fn score_max_sim<TMetric: Metric>(
+    query: &[VectorElementType],
+    stored: &[VectorElementType],
+    dim: usize,
+) -> ScoreType {
+    if dim == 0 {
+        return 0.0;
+    }
+    query
+        .chunks_exact(dim)
+        .map(|query_vector| {
+            stored
+                .chunks_exact(dim)
+                .map(|stored_vector| TMetric::similarity(query_vector, stored_vector))
+                .fold(ScoreType::NEG_INFINITY, ScoreType::max)
+        })
+        .filter(|score| score.is_finite())
+        .sum()
+}
@@ -3,13 +3,25 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use bitvec::prelude::BitSlice;
 use common::types::{PointOffsetType, ScoreType, ScoredPointOffset};
 
-use super::query_scorer::reco_query_scorer::RecoQueryScorer;
+use super::query_scorer::byte_custom_query_scorer::ByteCustomQueryScorer;
+use super::query_scorer::byte_metric_query_scorer::ByteMetricQueryScorer;
+use super::query_scorer::custom_query_scorer::CustomQueryScorer;
+use super::query_scorer::half_custom_query_scorer::HalfCustomQueryScorer;
+use super::query_scorer::half_metric_query_scorer::HalfMetricQueryScorer;
+use super::query_scorer::multi_custom_query_scorer::MultiCustomQueryScorer;
+use super::query_scorer::multi_metric_query_scorer::MultiMetricQueryScorer;
+use super::simple_byte_vector_storage::SimpleByteVectorStorage;
+use super::simple_half_vector_storage::SimpleHalfVectorStorage;
+use super::simple_multi_dense_vector_storage::SimpleMultiDenseVectorStorage;
 use super::{VectorStorage, VectorStorageEnum};
 use crate::data_types::vectors::QueryVector;
 use crate::spaces::metric::Metric;
-use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
+use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric, ManhattanMetric};
+use crate::spaces::simple_binary::{HammingMetric, JaccardMetric};
+use crate::spaces::simple_byte::ByteMetric;
+use crate::spaces::simple_half::HalfMetric;
 use crate::spaces::tools::peek_top_largest_iterable;
-use crate::types::Distance;
+use crate::types::{Distance, MultiVectorComparator};
 use crate::vector_storage::query_scorer::metric_query_scorer::MetricQueryScorer;
 use crate::vector_storage::query_scorer::QueryScorer;
 
@@ -89,6 +101,14 @@ pub fn new_stoppable_raw_scorer<'a>(
     match vector_storage {
         VectorStorageEnum::Simple(vs) => raw_scorer_impl(query, vs, point_deleted, is_stopped),
 
+        VectorStorageEnum::SimpleHalf(vs) => {
+            raw_half_scorer_impl(query, vs, point_deleted, is_stopped)
+        }
+
+        VectorStorageEnum::SimpleByte(vs) => {
+            raw_byte_scorer_impl(query, vs, point_deleted, is_stopped)
+        }
+
         VectorStorageEnum::Memmap(vs) => {
             if vs.has_async_reader() {
                 #[cfg(target_os = "linux")]
@@ -111,6 +131,10 @@ pub fn new_stoppable_raw_scorer<'a>(
         VectorStorageEnum::AppendableMemmap(vs) => {
             raw_scorer_impl(query, vs.as_ref(), point_deleted, is_stopped)
         }
+
+        VectorStorageEnum::SimpleMultiDense(vs) => {
+            raw_multi_scorer_impl(query, vs, point_deleted, is_stopped)
+        }
     }
 }
 
@@ -149,6 +173,24 @@ pub fn raw_scorer_impl<'a, TVectorStorage: VectorStorage>(
             point_deleted,
             is_stopped,
         ),
+        Distance::Manhattan => new_scorer_with_metric::<ManhattanMetric, _>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Hamming => new_scorer_with_metric::<HammingMetric, _>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Jaccard => new_scorer_with_metric::<JaccardMetric, _>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
     }
 }
 
@@ -167,11 +209,278 @@ fn new_scorer_with_metric<'a, TMetric: Metric + 'a, TVectorStorage: VectorStorag
             is_stopped,
         ),
         QueryVector::Recommend(reco_query) => raw_scorer_from_query_scorer(
-            RecoQueryScorer::<TMetric, TVectorStorage>::new(reco_query, vector_storage),
+            CustomQueryScorer::<TMetric, TVectorStorage, _>::new(reco_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Discovery(discovery_query) => raw_scorer_from_query_scorer(
+            CustomQueryScorer::<TMetric, TVectorStorage, _>::new(discovery_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Context(context_query) => raw_scorer_from_query_scorer(
+            CustomQueryScorer::<TMetric, TVectorStorage, _>::new(context_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Sparse(_) => unreachable!("sparse query is checked against segment config"),
+    }
+}
+
+pub fn raw_byte_scorer_impl<'a>(
+    query: QueryVector,
+    vector_storage: &'a SimpleByteVectorStorage,
+    point_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+) -> Box<dyn RawScorer + 'a> {
+    match vector_storage.distance() {
+        Distance::Cosine => new_byte_scorer_with_metric::<CosineMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Euclid => new_byte_scorer_with_metric::<EuclidMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Dot => new_byte_scorer_with_metric::<DotProductMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Manhattan => new_byte_scorer_with_metric::<ManhattanMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Hamming => new_byte_scorer_with_metric::<HammingMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Jaccard => new_byte_scorer_with_metric::<JaccardMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+    }
+}
+
+fn new_byte_scorer_with_metric<'a, TMetric: ByteMetric + 'a>(
+    query: QueryVector,
+    vector_storage: &'a SimpleByteVectorStorage,
+    point_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+) -> Box<dyn RawScorer + 'a> {
+    let vec_deleted = vector_storage.deleted_vector_bitslice();
+    match query {
+        QueryVector::Nearest(vector) => raw_scorer_from_query_scorer(
+            ByteMetricQueryScorer::<TMetric>::new(vector, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Recommend(reco_query) => raw_scorer_from_query_scorer(
+            ByteCustomQueryScorer::<TMetric, _>::new(reco_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Discovery(discovery_query) => raw_scorer_from_query_scorer(
+            ByteCustomQueryScorer::<TMetric, _>::new(discovery_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Context(context_query) => raw_scorer_from_query_scorer(
+            ByteCustomQueryScorer::<TMetric, _>::new(context_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Sparse(_) => unreachable!("sparse query is checked against segment config"),
+    }
+}
+
+pub fn raw_half_scorer_impl<'a>(
+    query: QueryVector,
+    vector_storage: &'a SimpleHalfVectorStorage,
+    point_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+) -> Box<dyn RawScorer + 'a> {
+    match vector_storage.distance() {
+        Distance::Cosine => new_half_scorer_with_metric::<CosineMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Euclid => new_half_scorer_with_metric::<EuclidMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Dot => new_half_scorer_with_metric::<DotProductMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Manhattan => new_half_scorer_with_metric::<ManhattanMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Hamming => new_half_scorer_with_metric::<HammingMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+        Distance::Jaccard => new_half_scorer_with_metric::<JaccardMetric>(
+            query,
+            vector_storage,
+            point_deleted,
+            is_stopped,
+        ),
+    }
+}
+
+fn new_half_scorer_with_metric<'a, TMetric: HalfMetric + 'a>(
+    query: QueryVector,
+    vector_storage: &'a SimpleHalfVectorStorage,
+    point_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+) -> Box<dyn RawScorer + 'a> {
+    let vec_deleted = vector_storage.deleted_vector_bitslice();
+    match query {
+        QueryVector::Nearest(vector) => raw_scorer_from_query_scorer(
+            HalfMetricQueryScorer::<TMetric>::new(vector, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Recommend(reco_query) => raw_scorer_from_query_scorer(
+            HalfCustomQueryScorer::<TMetric, _>::new(reco_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Discovery(discovery_query) => raw_scorer_from_query_scorer(
+            HalfCustomQueryScorer::<TMetric, _>::new(discovery_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Context(context_query) => raw_scorer_from_query_scorer(
+            HalfCustomQueryScorer::<TMetric, _>::new(context_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Sparse(_) => unreachable!("sparse query is checked against segment config"),
+    }
+}
+
+pub fn raw_multi_scorer_impl<'a>(
+    query: QueryVector,
+    vector_storage: &'a SimpleMultiDenseVectorStorage,
+    point_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+) -> Box<dyn RawScorer + 'a> {
+    match vector_storage.multivector_config().comparator {
+        MultiVectorComparator::MaxSim => match vector_storage.distance() {
+            Distance::Cosine => new_multi_scorer_with_metric::<CosineMetric, _>(
+                query,
+                vector_storage,
+                point_deleted,
+                is_stopped,
+            ),
+            Distance::Euclid => new_multi_scorer_with_metric::<EuclidMetric, _>(
+                query,
+                vector_storage,
+                point_deleted,
+                is_stopped,
+            ),
+            Distance::Dot => new_multi_scorer_with_metric::<DotProductMetric, _>(
+                query,
+                vector_storage,
+                point_deleted,
+                is_stopped,
+            ),
+            Distance::Manhattan => new_multi_scorer_with_metric::<ManhattanMetric, _>(
+                query,
+                vector_storage,
+                point_deleted,
+                is_stopped,
+            ),
+            Distance::Hamming => new_multi_scorer_with_metric::<HammingMetric, _>(
+                query,
+                vector_storage,
+                point_deleted,
+                is_stopped,
+            ),
+            Distance::Jaccard => new_multi_scorer_with_metric::<JaccardMetric, _>(
+                query,
+                vector_storage,
+                point_deleted,
+                is_stopped,
+            ),
+        },
+    }
+}
+
+fn new_multi_scorer_with_metric<'a, TMetric: Metric + 'a, TVectorStorage: VectorStorage>(
+    query: QueryVector,
+    vector_storage: &'a TVectorStorage,
+    point_deleted: &'a BitSlice,
+    is_stopped: &'a AtomicBool,
+) -> Box<dyn RawScorer + 'a> {
+    let vec_deleted = vector_storage.deleted_vector_bitslice();
+    match query {
+        QueryVector::Nearest(vector) => raw_scorer_from_query_scorer(
+            MultiMetricQueryScorer::<TMetric, TVectorStorage>::new(vector, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Recommend(reco_query) => raw_scorer_from_query_scorer(
+            MultiCustomQueryScorer::<TMetric, TVectorStorage, _>::new(reco_query, vector_storage),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Discovery(discovery_query) => raw_scorer_from_query_scorer(
+            MultiCustomQueryScorer::<TMetric, TVectorStorage, _>::new(
+                discovery_query,
+                vector_storage,
+            ),
+            point_deleted,
+            vec_deleted,
+            is_stopped,
+        ),
+        QueryVector::Context(context_query) => raw_scorer_from_query_scorer(
+            MultiCustomQueryScorer::<TMetric, TVectorStorage, _>::new(
+                context_query,
+                vector_storage,
+            ),
             point_deleted,
             vec_deleted,
             is_stopped,
         ),
+        QueryVector::Sparse(_) => unreachable!("sparse query is checked against segment config"),
     }
 }
 
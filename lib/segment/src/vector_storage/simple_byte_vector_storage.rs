@@ -0,0 +1,254 @@
+use std::borrow::Cow;
+use std::mem::size_of;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use bitvec::prelude::{BitSlice, BitVec};
+use common::types::PointOffsetType;
+use log::debug;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+
+use super::chunked_vectors::ChunkedVectors;
+use super::simple_vector_storage::bitvec_set_deleted;
+use super::vector_storage_base::VectorStorage;
+use super::VectorStorageEnum;
+use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::Flusher;
+use crate::data_types::vectors::VectorElementType;
+use crate::types::{Distance, QuantizationConfig};
+use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
+
+/// In-memory storage of byte vectors with on-update persistence using `store`
+///
+/// Intended for embeddings which are already quantized by the model, each element is kept as a
+/// single byte. Vectors are converted from and to [`VectorElementType`] on access, scoring operates
+/// on bytes directly, see [`crate::spaces::simple_byte::ByteMetric`].
+pub struct SimpleByteVectorStorage {
+    dim: usize,
+    distance: Distance,
+    vectors: ChunkedVectors<u8>,
+    db_wrapper: DatabaseColumnWrapper,
+    update_buffer: StoredRecord,
+    /// BitVec for deleted flags. Grows dynamically upto last set flag.
+    deleted: BitVec,
+    /// Current number of deleted vectors.
+    deleted_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct StoredRecord {
+    pub deleted: bool,
+    pub vector: Vec<u8>,
+}
+
+pub fn open_simple_byte_vector_storage(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let mut vectors = ChunkedVectors::new(dim);
+    let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
+
+    let db_wrapper = DatabaseColumnWrapper::new(database, database_column_name);
+
+    for (key, value) in db_wrapper.lock_db().iter()? {
+        let point_id: PointOffsetType = bincode::deserialize(&key)
+            .map_err(|_| OperationError::service_error("cannot deserialize point id from db"))?;
+        let stored_record: StoredRecord = bincode::deserialize(&value)
+            .map_err(|_| OperationError::service_error("cannot deserialize record from db"))?;
+
+        // Propagate deleted flag
+        if stored_record.deleted {
+            bitvec_set_deleted(&mut deleted, point_id, true);
+            deleted_count += 1;
+        }
+        vectors.insert(point_id, &stored_record.vector)?;
+    }
+
+    debug!("Segment vectors: {}", vectors.len());
+    debug!(
+        "Estimated segment size {} MB",
+        vectors.len() * dim * size_of::<u8>() / 1024 / 1024
+    );
+
+    Ok(Arc::new(AtomicRefCell::new(VectorStorageEnum::SimpleByte(
+        SimpleByteVectorStorage {
+            dim,
+            distance,
+            vectors,
+            db_wrapper,
+            update_buffer: StoredRecord {
+                deleted: false,
+                vector: vec![0; dim],
+            },
+            deleted,
+            deleted_count,
+        },
+    ))))
+}
+
+impl SimpleByteVectorStorage {
+    /// Get stored vector by key without conversion
+    pub fn get_byte_vector(&self, key: PointOffsetType) -> &[u8] {
+        self.vectors.get(key)
+    }
+
+    /// Set deleted flag for given key. Returns previous deleted state.
+    #[inline]
+    fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> bool {
+        if key as usize >= self.vectors.len() {
+            return false;
+        }
+        let was_deleted = bitvec_set_deleted(&mut self.deleted, key, deleted);
+        if was_deleted != deleted {
+            if !was_deleted {
+                self.deleted_count += 1;
+            } else {
+                self.deleted_count -= 1;
+            }
+        }
+        was_deleted
+    }
+
+    fn update_stored(
+        &mut self,
+        key: PointOffsetType,
+        deleted: bool,
+        vector: Option<&[u8]>,
+    ) -> OperationResult<()> {
+        // Write vector state to buffer record
+        let record = &mut self.update_buffer;
+        record.deleted = deleted;
+        if let Some(vector) = vector {
+            record.vector.copy_from_slice(vector);
+        }
+
+        // Store updated record
+        self.db_wrapper.put(
+            bincode::serialize(&key).unwrap(),
+            bincode::serialize(&record).unwrap(),
+        )?;
+
+        Ok(())
+    }
+}
+
+impl VectorStorage for SimpleByteVectorStorage {
+    fn vector_dim(&self) -> usize {
+        self.dim
+    }
+
+    fn distance(&self) -> Distance {
+        self.distance
+    }
+
+    fn total_vector_count(&self) -> usize {
+        self.vectors.len()
+    }
+
+    fn get_vector(&self, key: PointOffsetType) -> Cow<[VectorElementType]> {
+        Cow::Owned(
+            self.vectors
+                .get(key)
+                .iter()
+                .map(|&byte| byte as VectorElementType)
+                .collect(),
+        )
+    }
+
+    fn insert_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: &[VectorElementType],
+    ) -> OperationResult<()> {
+        let vector = to_byte_vector(vector);
+        self.vectors.insert(key, &vector)?;
+        self.set_deleted(key, false);
+        self.update_stored(key, false, Some(&vector))?;
+        Ok(())
+    }
+
+    fn update_from(
+        &mut self,
+        other: &VectorStorageEnum,
+        other_ids: &mut dyn Iterator<Item = PointOffsetType>,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Range<PointOffsetType>> {
+        let start_index = self.vectors.len() as PointOffsetType;
+        for point_id in other_ids {
+            check_process_stopped(stopped)?;
+            // Do not perform preprocessing - vectors should be already processed
+            let other_vector = to_byte_vector(&other.get_vector(point_id));
+            let other_deleted = other.is_deleted_vector(point_id);
+            let new_id = self.vectors.push(&other_vector)?;
+            self.set_deleted(new_id, other_deleted);
+            self.update_stored(new_id, other_deleted, Some(&other_vector))?;
+        }
+        let end_index = self.vectors.len() as PointOffsetType;
+        Ok(start_index..end_index)
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.db_wrapper.flusher()
+    }
+
+    fn quantize(
+        &mut self,
+        _path: &Path,
+        _quantization_config: &QuantizationConfig,
+        _max_threads: usize,
+        _stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        // Byte vectors are already as compact as scalar quantization, rejected by config validation
+        Err(OperationError::service_error(
+            "Quantization of uint8 vectors is not supported",
+        ))
+    }
+
+    fn load_quantization(&mut self, _path: &Path) -> OperationResult<()> {
+        Ok(())
+    }
+
+    fn quantized_storage(&self) -> Option<&QuantizedVectors> {
+        None
+    }
+
+    fn files(&self) -> Vec<std::path::PathBuf> {
+        vec![]
+    }
+
+    fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
+        let is_deleted = !self.set_deleted(key, true);
+        if is_deleted {
+            self.update_stored(key, true, None)?;
+        }
+        Ok(is_deleted)
+    }
+
+    fn is_deleted_vector(&self, key: PointOffsetType) -> bool {
+        self.deleted.get(key as usize).map(|b| *b).unwrap_or(false)
+    }
+
+    fn deleted_vector_count(&self) -> usize {
+        self.deleted_count
+    }
+
+    fn deleted_vector_bitslice(&self) -> &BitSlice {
+        self.deleted.as_bitslice()
+    }
+}
+
+/// Convert vector to bytes
+///
+/// Elements must be integers in the `[0, 255]` range, which is checked against the segment config
+/// before vectors reach the storage.
+pub fn to_byte_vector(vector: &[VectorElementType]) -> Vec<u8> {
+    vector.iter().map(|&value| value as u8).collect()
+}
@@ -0,0 +1,236 @@
+use std::borrow::Cow;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use bitvec::prelude::{BitSlice, BitVec};
+use common::types::PointOffsetType;
+use log::debug;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+
+use super::simple_vector_storage::bitvec_set_deleted;
+use super::vector_storage_base::VectorStorage;
+use super::VectorStorageEnum;
+use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::Flusher;
+use crate::data_types::vectors::{VectorElementType, VectorType};
+use crate::types::{Distance, MultiVectorConfig, QuantizationConfig};
+use crate::vector_storage::quantized::quantized_vectors::QuantizedVectors;
+
+/// In-memory storage for multiple dense vectors per point with on-update persistence using `store`
+///
+/// Vectors of a single point are kept flattened into one contiguous slice,
+/// its length is always a multiple of the vector dimension.
+pub struct SimpleMultiDenseVectorStorage {
+    dim: usize,
+    distance: Distance,
+    multivector_config: MultiVectorConfig,
+    vectors: Vec<VectorType>,
+    db_wrapper: DatabaseColumnWrapper,
+    /// BitVec for deleted flags. Grows dynamically upto last set flag.
+    deleted: BitVec,
+    /// Current number of deleted vectors.
+    deleted_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct StoredRecord {
+    pub deleted: bool,
+    pub vector: VectorType,
+}
+
+pub fn open_simple_multi_dense_vector_storage(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+    dim: usize,
+    distance: Distance,
+    multivector_config: MultiVectorConfig,
+) -> OperationResult<Arc<AtomicRefCell<VectorStorageEnum>>> {
+    let mut vectors = Vec::new();
+    let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
+
+    let db_wrapper = DatabaseColumnWrapper::new(database, database_column_name);
+
+    for (key, value) in db_wrapper.lock_db().iter()? {
+        let point_id: PointOffsetType = bincode::deserialize(&key)
+            .map_err(|_| OperationError::service_error("cannot deserialize point id from db"))?;
+        let stored_record: StoredRecord = bincode::deserialize(&value)
+            .map_err(|_| OperationError::service_error("cannot deserialize record from db"))?;
+
+        // Propagate deleted flag
+        if stored_record.deleted {
+            bitvec_set_deleted(&mut deleted, point_id, true);
+            deleted_count += 1;
+        }
+        if point_id as usize >= vectors.len() {
+            vectors.resize(point_id as usize + 1, VectorType::new());
+        }
+        vectors[point_id as usize] = stored_record.vector;
+    }
+
+    debug!("Segment multivectors: {}", vectors.len());
+
+    Ok(Arc::new(AtomicRefCell::new(
+        VectorStorageEnum::SimpleMultiDense(SimpleMultiDenseVectorStorage {
+            dim,
+            distance,
+            multivector_config,
+            vectors,
+            db_wrapper,
+            deleted,
+            deleted_count,
+        }),
+    )))
+}
+
+impl SimpleMultiDenseVectorStorage {
+    pub fn multivector_config(&self) -> &MultiVectorConfig {
+        &self.multivector_config
+    }
+
+    /// Set deleted flag for given key. Returns previous deleted state.
+    #[inline]
+    fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> bool {
+        if key as usize >= self.vectors.len() {
+            return false;
+        }
+        let was_deleted = bitvec_set_deleted(&mut self.deleted, key, deleted);
+        if was_deleted != deleted {
+            if !was_deleted {
+                self.deleted_count += 1;
+            } else {
+                self.deleted_count -= 1;
+            }
+        }
+        was_deleted
+    }
+
+    fn update_stored(&mut self, key: PointOffsetType, deleted: bool) -> OperationResult<()> {
+        let record = StoredRecord {
+            deleted,
+            vector: self.vectors[key as usize].clone(),
+        };
+
+        // Store updated record
+        self.db_wrapper.put(
+            bincode::serialize(&key).unwrap(),
+            bincode::serialize(&record).unwrap(),
+        )?;
+
+        Ok(())
+    }
+
+    fn set_vector(&mut self, key: PointOffsetType, vector: &[VectorElementType]) {
+        debug_assert_eq!(vector.len() % self.dim, 0);
+        if key as usize >= self.vectors.len() {
+            self.vectors.resize(key as usize + 1, VectorType::new());
+        }
+        self.vectors[key as usize] = vector.to_vec();
+    }
+}
+
+impl VectorStorage for SimpleMultiDenseVectorStorage {
+    /// Dimension of each single vector of a multivector
+    fn vector_dim(&self) -> usize {
+        self.dim
+    }
+
+    fn distance(&self) -> Distance {
+        self.distance
+    }
+
+    fn total_vector_count(&self) -> usize {
+        self.vectors.len()
+    }
+
+    /// Get all vectors of the given point, flattened
+    fn get_vector(&self, key: PointOffsetType) -> Cow<[VectorElementType]> {
+        Cow::Borrowed(&self.vectors[key as usize])
+    }
+
+    fn insert_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: &[VectorElementType],
+    ) -> OperationResult<()> {
+        self.set_vector(key, vector);
+        self.set_deleted(key, false);
+        self.update_stored(key, false)?;
+        Ok(())
+    }
+
+    fn update_from(
+        &mut self,
+        other: &VectorStorageEnum,
+        other_ids: &mut dyn Iterator<Item = PointOffsetType>,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Range<PointOffsetType>> {
+        let start_index = self.vectors.len() as PointOffsetType;
+        for point_id in other_ids {
+            check_process_stopped(stopped)?;
+            // Do not perform preprocessing - vectors should be already processed
+            let other_vector = other.get_vector(point_id);
+            let other_deleted = other.is_deleted_vector(point_id);
+            let new_id = self.vectors.len() as PointOffsetType;
+            self.set_vector(new_id, &other_vector);
+            self.set_deleted(new_id, other_deleted);
+            self.update_stored(new_id, other_deleted)?;
+        }
+        let end_index = self.vectors.len() as PointOffsetType;
+        Ok(start_index..end_index)
+    }
+
+    fn flusher(&self) -> Flusher {
+        self.db_wrapper.flusher()
+    }
+
+    fn quantize(
+        &mut self,
+        _path: &Path,
+        _quantization_config: &QuantizationConfig,
+        _max_threads: usize,
+        _stopped: &AtomicBool,
+    ) -> OperationResult<()> {
+        // Multivectors are always scored in full, quantization configs are rejected earlier
+        Err(OperationError::service_error(
+            "Quantization of multivectors is not supported",
+        ))
+    }
+
+    fn load_quantization(&mut self, _path: &Path) -> OperationResult<()> {
+        Ok(())
+    }
+
+    fn quantized_storage(&self) -> Option<&QuantizedVectors> {
+        None
+    }
+
+    fn files(&self) -> Vec<std::path::PathBuf> {
+        vec![]
+    }
+
+    fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
+        let is_deleted = !self.set_deleted(key, true);
+        if is_deleted {
+            self.update_stored(key, true)?;
+        }
+        Ok(is_deleted)
+    }
+
+    fn is_deleted_vector(&self, key: PointOffsetType) -> bool {
+        self.deleted.get(key as usize).map(|b| *b).unwrap_or(false)
+    }
+
+    fn deleted_vector_count(&self) -> usize {
+        self.deleted_count
+    }
+
+    fn deleted_vector_bitslice(&self) -> &BitSlice {
+        self.deleted.as_bitslice()
+    }
+}
@@ -0,0 +1,193 @@
+use std::ops::Range;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use bitvec::prelude::{BitSlice, BitVec};
+use common::types::PointOffsetType;
+use log::debug;
+use parking_lot::RwLock;
+use rocksdb::DB;
+use serde::{Deserialize, Serialize};
+use sparse::common::sparse_vector::SparseVector;
+
+use super::simple_vector_storage::bitvec_set_deleted;
+use crate::common::operation_error::{check_process_stopped, OperationError, OperationResult};
+use crate::common::rocksdb_wrapper::DatabaseColumnWrapper;
+use crate::common::Flusher;
+
+/// In-memory sparse vector storage with on-update persistence using `store`
+pub struct SimpleSparseVectorStorage {
+    vectors: Vec<SparseVector>,
+    db_wrapper: DatabaseColumnWrapper,
+    /// BitVec for deleted flags. Grows dynamically upto last set flag.
+    deleted: BitVec,
+    /// Current number of deleted vectors.
+    deleted_count: usize,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+struct StoredRecord {
+    pub deleted: bool,
+    pub vector: SparseVector,
+}
+
+pub fn open_simple_sparse_vector_storage(
+    database: Arc<RwLock<DB>>,
+    database_column_name: &str,
+) -> OperationResult<Arc<AtomicRefCell<SimpleSparseVectorStorage>>> {
+    let mut vectors = Vec::new();
+    let (mut deleted, mut deleted_count) = (BitVec::new(), 0);
+
+    let db_wrapper = DatabaseColumnWrapper::new(database, database_column_name);
+
+    for (key, value) in db_wrapper.lock_db().iter()? {
+        let point_id: PointOffsetType = bincode::deserialize(&key)
+            .map_err(|_| OperationError::service_error("cannot deserialize point id from db"))?;
+        let stored_record: StoredRecord = bincode::deserialize(&value)
+            .map_err(|_| OperationError::service_error("cannot deserialize record from db"))?;
+
+        // Propagate deleted flag
+        if stored_record.deleted {
+            bitvec_set_deleted(&mut deleted, point_id, true);
+            deleted_count += 1;
+        }
+        if point_id as usize >= vectors.len() {
+            vectors.resize(point_id as usize + 1, SparseVector::default());
+        }
+        vectors[point_id as usize] = stored_record.vector;
+    }
+
+    debug!("Segment sparse vectors: {}", vectors.len());
+
+    Ok(Arc::new(AtomicRefCell::new(SimpleSparseVectorStorage {
+        vectors,
+        db_wrapper,
+        deleted,
+        deleted_count,
+    })))
+}
+
+impl SimpleSparseVectorStorage {
+    /// Set deleted flag for given key. Returns previous deleted state.
+    #[inline]
+    fn set_deleted(&mut self, key: PointOffsetType, deleted: bool) -> bool {
+        if key as usize >= self.vectors.len() {
+            return false;
+        }
+        let was_deleted = bitvec_set_deleted(&mut self.deleted, key, deleted);
+        if was_deleted != deleted {
+            if !was_deleted {
+                self.deleted_count += 1;
+            } else {
+                self.deleted_count -= 1;
+            }
+        }
+        was_deleted
+    }
+
+    fn update_stored(
+        &mut self,
+        key: PointOffsetType,
+        deleted: bool,
+        vector: &SparseVector,
+    ) -> OperationResult<()> {
+        let record = StoredRecord {
+            deleted,
+            vector: vector.clone(),
+        };
+        self.db_wrapper.put(
+            bincode::serialize(&key).unwrap(),
+            bincode::serialize(&record).unwrap(),
+        )?;
+        Ok(())
+    }
+
+    pub fn total_vector_count(&self) -> usize {
+        self.vectors.len()
+    }
+
+    /// Number of vectors, which are not marked as deleted
+    pub fn available_vector_count(&self) -> usize {
+        self.total_vector_count() - self.deleted_count
+    }
+
+    /// Get sparse vector by key. Vectors are stored sorted by indices.
+    pub fn get_vector(&self, key: PointOffsetType) -> &SparseVector {
+        &self.vectors[key as usize]
+    }
+
+    pub fn insert_vector(
+        &mut self,
+        key: PointOffsetType,
+        vector: &SparseVector,
+    ) -> OperationResult<()> {
+        let mut vector = vector.clone();
+        vector.sort_by_indices();
+        if key as usize >= self.vectors.len() {
+            self.vectors
+                .resize(key as usize + 1, SparseVector::default());
+        }
+        self.update_stored(key, false, &vector)?;
+        self.vectors[key as usize] = vector;
+        self.set_deleted(key, false);
+        Ok(())
+    }
+
+    /// Append vectors of `other` storage with given ids, returns range of new internal ids
+    pub fn update_from(
+        &mut self,
+        other: &SimpleSparseVectorStorage,
+        other_ids: &mut dyn Iterator<Item = PointOffsetType>,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Range<PointOffsetType>> {
+        let start_index = self.vectors.len() as PointOffsetType;
+        for point_id in other_ids {
+            check_process_stopped(stopped)?;
+            let other_vector = other.get_vector(point_id).clone();
+            let other_deleted = other.is_deleted_vector(point_id);
+            let new_id = self.vectors.len() as PointOffsetType;
+            self.update_stored(new_id, other_deleted, &other_vector)?;
+            self.vectors.push(other_vector);
+            self.set_deleted(new_id, other_deleted);
+        }
+        let end_index = self.vectors.len() as PointOffsetType;
+        Ok(start_index..end_index)
+    }
+
+    pub fn flusher(&self) -> Flusher {
+        self.db_wrapper.flusher()
+    }
+
+    /// Mark vector as deleted, returns true if it was not deleted before
+    pub fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
+        if key as usize >= self.vectors.len() {
+            return Ok(false);
+        }
+        let is_deleted = !self.set_deleted(key, true);
+        if is_deleted {
+            let record = StoredRecord {
+                deleted: true,
+                vector: SparseVector::default(),
+            };
+            self.db_wrapper.put(
+                bincode::serialize(&key).unwrap(),
+                bincode::serialize(&record).unwrap(),
+            )?;
+            self.vectors[key as usize] = SparseVector::default();
+        }
+        Ok(is_deleted)
+    }
+
+    pub fn is_deleted_vector(&self, key: PointOffsetType) -> bool {
+        self.deleted.get(key as usize).map(|b| *b).unwrap_or(false)
+    }
+
+    pub fn deleted_vector_count(&self) -> usize {
+        self.deleted_count
+    }
+
+    pub fn deleted_vector_bitslice(&self) -> &BitSlice {
+        self.deleted.as_bitslice()
+    }
+}
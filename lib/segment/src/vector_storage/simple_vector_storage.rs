@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::mem::size_of;
 use std::ops::Range;
 use std::path::Path;
@@ -144,8 +145,8 @@ impl VectorStorage for SimpleVectorStorage {
         self.vectors.len()
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
-        self.vectors.get(key)
+    fn get_vector(&self, key: PointOffsetType) -> Cow<[VectorElementType]> {
+        Cow::Borrowed(self.vectors.get(key))
     }
 
     fn insert_vector(
@@ -171,9 +172,9 @@ impl VectorStorage for SimpleVectorStorage {
             // Do not perform preprocessing - vectors should be already processed
             let other_vector = other.get_vector(point_id);
             let other_deleted = other.is_deleted_vector(point_id);
-            let new_id = self.vectors.push(other_vector)?;
+            let new_id = self.vectors.push(&other_vector)?;
             self.set_deleted(new_id, other_deleted);
-            self.update_stored(new_id, other_deleted, Some(other_vector))?;
+            self.update_stored(new_id, other_deleted, Some(&other_vector))?;
         }
         let end_index = self.vectors.len() as PointOffsetType;
         Ok(start_index..end_index)
@@ -251,7 +252,11 @@ impl VectorStorage for SimpleVectorStorage {
 ///
 /// Returns previous deleted state of the given point.
 #[inline]
-fn bitvec_set_deleted(bitvec: &mut BitVec, point_id: PointOffsetType, deleted: bool) -> bool {
+pub(super) fn bitvec_set_deleted(
+    bitvec: &mut BitVec,
+    point_id: PointOffsetType,
+    deleted: bool,
+) -> bool {
     // Set deleted flag if bitvec is large enough, no need to check bounds
     if (point_id as usize) < bitvec.len() {
         return unsafe { bitvec.replace_unchecked(point_id as usize, deleted) };
@@ -31,6 +31,11 @@ fn async_raw_scorer_dot() -> Result<()> {
     test_async_raw_scorer_defaults(Distance::Dot)
 }
 
+#[test]
+fn async_raw_scorer_manhattan() -> Result<()> {
+    test_async_raw_scorer_defaults(Distance::Manhattan)
+}
+
 fn test_async_raw_scorer_defaults(distance: Distance) -> Result<()> {
     test_async_raw_scorer(6942, 128, distance, 1024, 128, 256)
 }
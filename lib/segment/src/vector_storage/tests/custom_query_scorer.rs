@@ -22,6 +22,8 @@ use crate::types::{
 };
 #[cfg(target_os = "linux")]
 use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage_with_async_io;
+use crate::vector_storage::query::context_query::{ContextPair, ContextQuery};
+use crate::vector_storage::query::discovery_query::DiscoveryQuery;
 use crate::vector_storage::query::reco_query::RecoQuery;
 use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
 use crate::vector_storage::tests::utils::score;
@@ -42,23 +44,46 @@ type WithQuantization = (
     Box<dyn Iterator<Item = VectorElementType>>,
 );
 
-fn random_reco_query<R: Rng + ?Sized>(
+#[derive(Debug, Clone, Copy)]
+enum QueryVariant {
+    Recommend,
+    Discovery,
+    Context,
+}
+
+fn random_query<R: Rng + ?Sized>(
+    variant: QueryVariant,
     rnd: &mut R,
     dim: usize,
     sampler: &mut impl Iterator<Item = f32>,
 ) -> QueryVector {
-    let num_positives: usize = rnd.gen_range(0..MAX_EXAMPLES);
-    let num_negatives: usize = rnd.gen_range(0..MAX_EXAMPLES);
+    let mut random_vector = || sampler.take(dim).collect_vec();
+    let mut random_pairs = |rnd: &mut R| {
+        let num_pairs: usize = rnd.gen_range(0..MAX_EXAMPLES);
+        (0..num_pairs)
+            .map(|_| ContextPair {
+                positive: random_vector(),
+                negative: random_vector(),
+            })
+            .collect_vec()
+    };
 
-    let positives = (0..num_positives)
-        .map(|_| sampler.take(dim).collect())
-        .collect_vec();
+    match variant {
+        QueryVariant::Recommend => {
+            let num_positives: usize = rnd.gen_range(0..MAX_EXAMPLES);
+            let num_negatives: usize = rnd.gen_range(0..MAX_EXAMPLES);
 
-    let negatives = (0..num_negatives)
-        .map(|_| sampler.take(dim).collect())
-        .collect_vec();
+            let positives = (0..num_positives).map(|_| random_vector()).collect_vec();
+            let negatives = (0..num_negatives).map(|_| random_vector()).collect_vec();
 
-    RecoQuery::new(positives, negatives).into()
+            RecoQuery::new(positives, negatives).into()
+        }
+        QueryVariant::Discovery => {
+            let pairs = random_pairs(rnd);
+            DiscoveryQuery::new(random_vector(), pairs).into()
+        }
+        QueryVariant::Context => ContextQuery::new(random_pairs(rnd)).into(),
+    }
 }
 
 fn ram_storage(dir: &Path) -> AtomicRefCell<VectorStorageEnum> {
@@ -128,6 +153,7 @@ fn binary() -> Option<WithQuantization> {
 }
 
 fn scoring_equivalency(
+    query_variant: QueryVariant,
     other_storage: impl FnOnce(&std::path::Path) -> AtomicRefCell<VectorStorageEnum>,
     with_quantization: Option<WithQuantization>,
 ) -> Result<()> {
@@ -171,7 +197,7 @@ fn scoring_equivalency(
 
     let attempts = 50;
     for _i in 0..attempts {
-        let query = random_reco_query(&mut rng, DIMS, &mut sampler);
+        let query = random_query(query_variant, &mut rng, DIMS, &mut sampler);
 
         let raw_scorer = new_raw_scorer(
             query.clone(),
@@ -207,7 +233,7 @@ fn scoring_equivalency(
             );
         } else {
             // Quantization is used for the other storage, so score should be similar
-            // but not necessarily the exact same. Recommend query has a step function,
+            // but not necessarily the exact same. Custom queries have a step function,
             // so small differences in similarities can lead to very different scores
 
             let top = SAMPLE_SIZE / 10;
@@ -244,6 +270,13 @@ fn scoring_equivalency(
 
 #[rstest]
 fn compare_scoring_equivalency(
+    #[values(
+        QueryVariant::Recommend,
+        QueryVariant::Discovery,
+        QueryVariant::Context
+    )]
+    query_variant: QueryVariant,
+
     #[values(ram_storage)] other_storage: impl FnOnce(
         &std::path::Path,
     ) -> AtomicRefCell<VectorStorageEnum>,
@@ -252,15 +285,22 @@ fn compare_scoring_equivalency(
         WithQuantization,
     >,
 ) -> Result<()> {
-    scoring_equivalency(other_storage, quantization_config)
+    scoring_equivalency(query_variant, other_storage, quantization_config)
 }
 
 #[cfg(target_os = "linux")]
 #[rstest]
 fn async_compare_scoring_equivalency(
+    #[values(
+        QueryVariant::Recommend,
+        QueryVariant::Discovery,
+        QueryVariant::Context
+    )]
+    query_variant: QueryVariant,
+
     #[values(async_memmap_storage)] other_storage: impl FnOnce(
         &std::path::Path,
     ) -> AtomicRefCell<VectorStorageEnum>,
 ) -> Result<()> {
-    scoring_equivalency(other_storage, None)
+    scoring_equivalency(query_variant, other_storage, None)
 }
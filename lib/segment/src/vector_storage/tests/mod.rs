@@ -1,5 +1,5 @@
 #[cfg(target_os = "linux")]
 mod async_raw_scorer;
-mod reco_query_scorer;
+mod custom_query_scorer;
 mod test_appendable_vector_storage;
 mod utils;
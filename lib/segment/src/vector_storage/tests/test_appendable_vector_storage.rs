@@ -11,6 +11,8 @@ use crate::fixtures::payload_context_fixture::FixtureIdTracker;
 use crate::id_tracker::{IdTracker, IdTrackerSS};
 use crate::types::{Distance, PointIdType, QuantizationConfig, ScalarQuantizationConfig};
 use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
+use crate::vector_storage::simple_byte_vector_storage::open_simple_byte_vector_storage;
+use crate::vector_storage::simple_half_vector_storage::open_simple_half_vector_storage;
 use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
 use crate::vector_storage::{new_raw_scorer, VectorStorage, VectorStorageEnum};
 
@@ -410,6 +412,160 @@ fn test_score_quantized_points_simple_vector_storages() {
 
 // ----------------------------------------------
 
+#[test]
+fn test_delete_points_in_simple_half_vector_storages() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    {
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage = open_simple_half_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+        do_test_delete_points(storage.clone());
+        storage.borrow().flusher()().unwrap();
+    }
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let _storage = open_simple_half_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+}
+
+#[test]
+fn test_score_points_in_simple_half_vector_storages() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage = open_simple_half_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+        do_test_score_points(storage.clone());
+        storage.borrow().flusher()().unwrap();
+    }
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let _storage = open_simple_half_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+}
+
+#[test]
+fn test_score_points_in_simple_half_vector_storages_match_full_precision() {
+    let points = vec![
+        vec![1.0, 0.5, -1.0, 0.25],
+        vec![-0.5, 2.0, 0.0, 1.0],
+        vec![0.75, -0.25, 1.5, -1.0],
+    ];
+    let query: QueryVector = [0.5, 1.0, -0.5, 0.25].into();
+
+    for distance in [
+        Distance::Cosine,
+        Distance::Dot,
+        Distance::Euclid,
+        Distance::Manhattan,
+    ] {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF, "full"]).unwrap();
+        let half_storage =
+            open_simple_half_vector_storage(db.clone(), DB_VECTOR_CF, 4, distance).unwrap();
+        let full_storage = open_simple_vector_storage(db, "full", 4, distance).unwrap();
+
+        let mut half_storage = half_storage.borrow_mut();
+        let mut full_storage = full_storage.borrow_mut();
+        for (i, vec) in points.iter().enumerate() {
+            // Vectors are preprocessed by the segment before insertion
+            let vec = distance.preprocess_vector(vec.clone());
+            half_storage
+                .insert_vector(i as PointOffsetType, &vec)
+                .unwrap();
+            full_storage
+                .insert_vector(i as PointOffsetType, &vec)
+                .unwrap();
+        }
+
+        let id_tracker = FixtureIdTracker::new(points.len());
+        let half_scorer = new_raw_scorer(
+            query.clone(),
+            &half_storage,
+            id_tracker.deleted_point_bitslice(),
+        );
+        let full_scorer = new_raw_scorer(
+            query.clone(),
+            &full_storage,
+            id_tracker.deleted_point_bitslice(),
+        );
+        for idx in 0..points.len() as PointOffsetType {
+            let half_score = half_scorer.score_point(idx);
+            let full_score = full_scorer.score_point(idx);
+            assert!(
+                (half_score - full_score).abs() < 1e-2,
+                "{distance:?}: {half_score} != {full_score}"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_delete_points_in_simple_byte_vector_storages() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    {
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage = open_simple_byte_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+        do_test_delete_points(storage.clone());
+        storage.borrow().flusher()().unwrap();
+    }
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let _storage = open_simple_byte_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+}
+
+#[test]
+fn test_score_points_in_simple_byte_vector_storages() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    {
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage = open_simple_byte_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+        do_test_score_points(storage.clone());
+        storage.borrow().flusher()().unwrap();
+    }
+    let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+    let _storage = open_simple_byte_vector_storage(db, DB_VECTOR_CF, 4, Distance::Dot).unwrap();
+}
+
+#[test]
+fn test_score_points_in_binary_byte_vector_storages() {
+    let points = vec![
+        vec![255.0, 0.0, 15.0, 1.0],
+        vec![255.0, 0.0, 0.0, 0.0],
+        vec![0.0, 255.0, 240.0, 1.0],
+    ];
+    let query: QueryVector = [255.0, 0.0, 7.0, 1.0].into();
+
+    for (distance, expected_scores) in [
+        (Distance::Hamming, [-1.0, -4.0, -23.0]),
+        (Distance::Jaccard, [12.0 / 13.0, 8.0 / 12.0, 1.0 / 24.0]),
+    ] {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+        let storage = open_simple_byte_vector_storage(db, DB_VECTOR_CF, 4, distance).unwrap();
+        let mut borrowed_storage = storage.borrow_mut();
+        for (i, vec) in points.iter().enumerate() {
+            borrowed_storage
+                .insert_vector(i as PointOffsetType, vec)
+                .unwrap();
+        }
+
+        let id_tracker = FixtureIdTracker::new(points.len());
+        let raw_scorer = new_raw_scorer(
+            query.clone(),
+            &borrowed_storage,
+            id_tracker.deleted_point_bitslice(),
+        );
+        let mut scores = vec![ScoredPointOffset { idx: 0, score: 0. }; points.len()];
+        let count = raw_scorer.score_points(&[0, 1, 2], &mut scores);
+        assert_eq!(count, points.len());
+        for (scored, expected) in scores.iter().zip(expected_scores) {
+            assert!(
+                (scored.score - expected).abs() < 1e-6,
+                "{distance:?}: {scored:?}"
+            );
+        }
+
+        let closest = raw_scorer.peek_top_iter(&mut [0, 1, 2].iter().cloned(), 1);
+        assert_eq!(closest[0].idx, 0);
+    }
+}
+
 #[test]
 fn test_delete_points_in_appendable_memmap_vector_storages() {
     let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
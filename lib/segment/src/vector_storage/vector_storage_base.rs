@@ -1,12 +1,19 @@
+use std::borrow::Cow;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
+use atomic_refcell::AtomicRefCell;
 use bitvec::prelude::BitSlice;
 use common::types::PointOffsetType;
 
 use super::memmap_vector_storage::MemmapVectorStorage;
 use super::quantized::quantized_vectors::QuantizedVectors;
+use super::simple_byte_vector_storage::SimpleByteVectorStorage;
+use super::simple_half_vector_storage::SimpleHalfVectorStorage;
+use super::simple_multi_dense_vector_storage::SimpleMultiDenseVectorStorage;
+use super::simple_sparse_vector_storage::SimpleSparseVectorStorage;
 use super::simple_vector_storage::SimpleVectorStorage;
 use crate::common::operation_error::OperationResult;
 use crate::common::Flusher;
@@ -39,8 +46,8 @@ pub trait VectorStorage {
             .saturating_sub(self.deleted_vector_count())
     }
 
-    /// Number of all stored vectors including deleted
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType];
+    /// Get vector by key, converted to the vector element type if stored differently
+    fn get_vector(&self, key: PointOffsetType) -> Cow<[VectorElementType]>;
 
     fn insert_vector(
         &mut self,
@@ -106,40 +113,55 @@ pub trait VectorStorage {
 
 pub enum VectorStorageEnum {
     Simple(SimpleVectorStorage),
+    SimpleHalf(SimpleHalfVectorStorage),
+    SimpleByte(SimpleByteVectorStorage),
     Memmap(Box<MemmapVectorStorage>),
     AppendableMemmap(Box<AppendableMmapVectorStorage>),
+    SimpleMultiDense(SimpleMultiDenseVectorStorage),
 }
 
 impl VectorStorage for VectorStorageEnum {
     fn vector_dim(&self) -> usize {
         match self {
             VectorStorageEnum::Simple(v) => v.vector_dim(),
+            VectorStorageEnum::SimpleHalf(v) => v.vector_dim(),
+            VectorStorageEnum::SimpleByte(v) => v.vector_dim(),
             VectorStorageEnum::Memmap(v) => v.vector_dim(),
             VectorStorageEnum::AppendableMemmap(v) => v.vector_dim(),
+            VectorStorageEnum::SimpleMultiDense(v) => v.vector_dim(),
         }
     }
 
     fn distance(&self) -> Distance {
         match self {
             VectorStorageEnum::Simple(v) => v.distance(),
+            VectorStorageEnum::SimpleHalf(v) => v.distance(),
+            VectorStorageEnum::SimpleByte(v) => v.distance(),
             VectorStorageEnum::Memmap(v) => v.distance(),
             VectorStorageEnum::AppendableMemmap(v) => v.distance(),
+            VectorStorageEnum::SimpleMultiDense(v) => v.distance(),
         }
     }
 
     fn total_vector_count(&self) -> usize {
         match self {
             VectorStorageEnum::Simple(v) => v.total_vector_count(),
+            VectorStorageEnum::SimpleHalf(v) => v.total_vector_count(),
+            VectorStorageEnum::SimpleByte(v) => v.total_vector_count(),
             VectorStorageEnum::Memmap(v) => v.total_vector_count(),
             VectorStorageEnum::AppendableMemmap(v) => v.total_vector_count(),
+            VectorStorageEnum::SimpleMultiDense(v) => v.total_vector_count(),
         }
     }
 
-    fn get_vector(&self, key: PointOffsetType) -> &[VectorElementType] {
+    fn get_vector(&self, key: PointOffsetType) -> Cow<[VectorElementType]> {
         match self {
             VectorStorageEnum::Simple(v) => v.get_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.get_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.get_vector(key),
             VectorStorageEnum::Memmap(v) => v.get_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.get_vector(key),
+            VectorStorageEnum::SimpleMultiDense(v) => v.get_vector(key),
         }
     }
 
@@ -150,8 +172,11 @@ impl VectorStorage for VectorStorageEnum {
     ) -> OperationResult<()> {
         match self {
             VectorStorageEnum::Simple(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SimpleHalf(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SimpleByte(v) => v.insert_vector(key, vector),
             VectorStorageEnum::Memmap(v) => v.insert_vector(key, vector),
             VectorStorageEnum::AppendableMemmap(v) => v.insert_vector(key, vector),
+            VectorStorageEnum::SimpleMultiDense(v) => v.insert_vector(key, vector),
         }
     }
 
@@ -163,16 +188,22 @@ impl VectorStorage for VectorStorageEnum {
     ) -> OperationResult<Range<PointOffsetType>> {
         match self {
             VectorStorageEnum::Simple(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SimpleHalf(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SimpleByte(v) => v.update_from(other, other_ids, stopped),
             VectorStorageEnum::Memmap(v) => v.update_from(other, other_ids, stopped),
             VectorStorageEnum::AppendableMemmap(v) => v.update_from(other, other_ids, stopped),
+            VectorStorageEnum::SimpleMultiDense(v) => v.update_from(other, other_ids, stopped),
         }
     }
 
     fn flusher(&self) -> Flusher {
         match self {
             VectorStorageEnum::Simple(v) => v.flusher(),
+            VectorStorageEnum::SimpleHalf(v) => v.flusher(),
+            VectorStorageEnum::SimpleByte(v) => v.flusher(),
             VectorStorageEnum::Memmap(v) => v.flusher(),
             VectorStorageEnum::AppendableMemmap(v) => v.flusher(),
+            VectorStorageEnum::SimpleMultiDense(v) => v.flusher(),
         }
     }
 
@@ -187,68 +218,130 @@ impl VectorStorage for VectorStorageEnum {
             VectorStorageEnum::Simple(v) => {
                 v.quantize(data_path, quantization_config, max_threads, stopped)
             }
+            VectorStorageEnum::SimpleHalf(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
+            VectorStorageEnum::SimpleByte(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
             VectorStorageEnum::Memmap(v) => {
                 v.quantize(data_path, quantization_config, max_threads, stopped)
             }
             VectorStorageEnum::AppendableMemmap(v) => {
                 v.quantize(data_path, quantization_config, max_threads, stopped)
             }
+            VectorStorageEnum::SimpleMultiDense(v) => {
+                v.quantize(data_path, quantization_config, max_threads, stopped)
+            }
         }
     }
 
     fn load_quantization(&mut self, data_path: &Path) -> OperationResult<()> {
         match self {
             VectorStorageEnum::Simple(v) => v.load_quantization(data_path),
+            VectorStorageEnum::SimpleHalf(v) => v.load_quantization(data_path),
+            VectorStorageEnum::SimpleByte(v) => v.load_quantization(data_path),
             VectorStorageEnum::Memmap(v) => v.load_quantization(data_path),
             VectorStorageEnum::AppendableMemmap(v) => v.load_quantization(data_path),
+            VectorStorageEnum::SimpleMultiDense(v) => v.load_quantization(data_path),
         }
     }
 
     fn quantized_storage(&self) -> Option<&QuantizedVectors> {
         match self {
             VectorStorageEnum::Simple(v) => v.quantized_storage(),
+            VectorStorageEnum::SimpleHalf(v) => v.quantized_storage(),
+            VectorStorageEnum::SimpleByte(v) => v.quantized_storage(),
             VectorStorageEnum::Memmap(v) => v.quantized_storage(),
             VectorStorageEnum::AppendableMemmap(v) => v.quantized_storage(),
+            VectorStorageEnum::SimpleMultiDense(v) => v.quantized_storage(),
         }
     }
 
     fn files(&self) -> Vec<PathBuf> {
         match self {
             VectorStorageEnum::Simple(v) => v.files(),
+            VectorStorageEnum::SimpleHalf(v) => v.files(),
+            VectorStorageEnum::SimpleByte(v) => v.files(),
             VectorStorageEnum::Memmap(v) => v.files(),
             VectorStorageEnum::AppendableMemmap(v) => v.files(),
+            VectorStorageEnum::SimpleMultiDense(v) => v.files(),
         }
     }
 
     fn delete_vector(&mut self, key: PointOffsetType) -> OperationResult<bool> {
         match self {
             VectorStorageEnum::Simple(v) => v.delete_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.delete_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.delete_vector(key),
             VectorStorageEnum::Memmap(v) => v.delete_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.delete_vector(key),
+            VectorStorageEnum::SimpleMultiDense(v) => v.delete_vector(key),
         }
     }
 
     fn is_deleted_vector(&self, key: PointOffsetType) -> bool {
         match self {
             VectorStorageEnum::Simple(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SimpleHalf(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SimpleByte(v) => v.is_deleted_vector(key),
             VectorStorageEnum::Memmap(v) => v.is_deleted_vector(key),
             VectorStorageEnum::AppendableMemmap(v) => v.is_deleted_vector(key),
+            VectorStorageEnum::SimpleMultiDense(v) => v.is_deleted_vector(key),
         }
     }
 
     fn deleted_vector_count(&self) -> usize {
         match self {
             VectorStorageEnum::Simple(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SimpleHalf(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SimpleByte(v) => v.deleted_vector_count(),
             VectorStorageEnum::Memmap(v) => v.deleted_vector_count(),
             VectorStorageEnum::AppendableMemmap(v) => v.deleted_vector_count(),
+            VectorStorageEnum::SimpleMultiDense(v) => v.deleted_vector_count(),
         }
     }
 
     fn deleted_vector_bitslice(&self) -> &BitSlice {
         match self {
             VectorStorageEnum::Simple(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SimpleHalf(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SimpleByte(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::Memmap(v) => v.deleted_vector_bitslice(),
             VectorStorageEnum::AppendableMemmap(v) => v.deleted_vector_bitslice(),
+            VectorStorageEnum::SimpleMultiDense(v) => v.deleted_vector_bitslice(),
+        }
+    }
+}
+
+/// Shared reference to the storage of a named vector, dense or sparse
+#[derive(Clone)]
+pub enum VectorStorageRef {
+    Dense(Arc<AtomicRefCell<VectorStorageEnum>>),
+    Sparse(Arc<AtomicRefCell<SimpleSparseVectorStorage>>),
+}
+
+impl VectorStorageRef {
+    /// Whether the point has a value of this vector.
+    ///
+    /// Storage may be shorter than the point ids range, if the latest points didn't set the vector.
+    pub fn has_vector(&self, key: PointOffsetType) -> bool {
+        match self {
+            VectorStorageRef::Dense(storage) => {
+                let storage = storage.borrow();
+                (key as usize) < storage.total_vector_count() && !storage.is_deleted_vector(key)
+            }
+            VectorStorageRef::Sparse(storage) => {
+                let storage = storage.borrow();
+                (key as usize) < storage.total_vector_count() && !storage.is_deleted_vector(key)
+            }
+        }
+    }
+
+    pub fn available_vector_count(&self) -> usize {
+        match self {
+            VectorStorageRef::Dense(storage) => storage.borrow().available_vector_count(),
+            VectorStorageRef::Sparse(storage) => storage.borrow().available_vector_count(),
         }
     }
 }
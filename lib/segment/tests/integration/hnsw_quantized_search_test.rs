@@ -62,8 +62,11 @@ fn hnsw_quantized_search_test(
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                multivector_config: None,
+                datatype: None,
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
     };
 
@@ -148,6 +151,11 @@ fn hnsw_quantized_search_test(
     check_oversampling(&query_vectors, &hnsw_index, None, ef, top);
     check_oversampling(&query_vectors, &hnsw_index, Some(&filter), ef, top);
 
+    // check that exact search ignores quantization
+    // to check it, compare exact search result with plain search result
+    check_exact(&query_vectors, &segment, &hnsw_index, None, top);
+    check_exact(&query_vectors, &segment, &hnsw_index, Some(&filter), top);
+
     // check that rescoring is working
     // to check it, set all vectors to zero and expect zero scores
     let zero_vector = vec![0.0; dim];
@@ -200,6 +208,32 @@ fn check_matches(
     assert!(acc > 40.0);
 }
 
+fn check_exact(
+    query_vectors: &[QueryVector],
+    segment: &Segment,
+    hnsw_index: &HNSWIndex<GraphLinksRam>,
+    filter: Option<&Filter>,
+    top: usize,
+) {
+    for query in query_vectors {
+        let plain_result = segment.vector_data[DEFAULT_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[query], filter, top, None, &false.into());
+        let exact_result = hnsw_index.search(
+            &[query],
+            filter,
+            top,
+            Some(&SearchParams {
+                exact: true,
+                ..Default::default()
+            }),
+            &false.into(),
+        );
+        assert_eq!(exact_result, plain_result);
+    }
+}
+
 fn check_oversampling(
     query_vectors: &[QueryVector],
     hnsw_index: &HNSWIndex<GraphLinksRam>,
@@ -311,6 +345,20 @@ fn hnsw_quantized_search_euclid_test() {
     );
 }
 
+#[test]
+fn hnsw_quantized_search_manhattan_test() {
+    hnsw_quantized_search_test(
+        Distance::Manhattan,
+        5003,
+        ScalarQuantizationConfig {
+            r#type: Default::default(),
+            quantile: None,
+            always_ram: None,
+        }
+        .into(),
+    );
+}
+
 #[test]
 fn hnsw_product_quantization_cosine_test() {
     hnsw_quantized_search_test(
@@ -15,6 +15,8 @@ pub mod fixtures;
 #[cfg(test)]
 pub mod hnsw_quantized_search_test;
 #[cfg(test)]
+pub mod multivector_search_test;
+#[cfg(test)]
 pub mod nested_filtering_test;
 #[cfg(test)]
 pub mod payload_index_test;
@@ -25,4 +27,6 @@ pub mod segment_builder_test;
 #[cfg(test)]
 pub mod segment_tests;
 #[cfg(test)]
+pub mod sparse_vector_search_test;
+#[cfg(test)]
 pub mod utils;
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::vectors::{QueryVector, Vector, VectorType};
+use segment::entry::entry_point::SegmentEntry;
+use segment::index::hnsw_index::graph_links::GraphLinksRam;
+use segment::index::hnsw_index::hnsw::HNSWIndex;
+use segment::index::VectorIndex;
+use segment::segment_constructor::build_segment;
+use segment::types::{
+    Distance, ExtendedPointId, HnswConfig, Indexes, MultiVectorConfig, SearchParams, SegmentConfig,
+    VectorDataConfig, VectorStorageType,
+};
+use tempfile::Builder;
+
+const MULTI_VECTOR_NAME: &str = "multi";
+
+fn random_multi_vector<R: Rng + ?Sized>(rnd: &mut R, dim: usize, count: usize) -> Vec<VectorType> {
+    (0..count)
+        .map(|_| (0..dim).map(|_| rnd.gen_range(-1.0..1.0)).collect())
+        .collect()
+}
+
+fn max_sim(query: &[VectorType], stored: &[VectorType]) -> f32 {
+    query
+        .iter()
+        .map(|q| {
+            stored
+                .iter()
+                .map(|s| q.iter().zip(s).map(|(a, b)| a * b).sum::<f32>())
+                .fold(f32::NEG_INFINITY, f32::max)
+        })
+        .sum()
+}
+
+fn multivector_segment_config(dim: usize) -> SegmentConfig {
+    SegmentConfig {
+        vector_data: HashMap::from([(
+            MULTI_VECTOR_NAME.to_owned(),
+            VectorDataConfig {
+                size: dim,
+                distance: Distance::Dot,
+                storage_type: VectorStorageType::Memory,
+                index: Indexes::Plain {},
+                quantization_config: None,
+                multivector_config: Some(MultiVectorConfig::default()),
+                datatype: None,
+            },
+        )]),
+        sparse_vector_data: Default::default(),
+        payload_storage_type: Default::default(),
+    }
+}
+
+#[test]
+fn multivector_search_test() {
+    let stopped = AtomicBool::new(false);
+
+    let num_points: u64 = 500;
+    let dim = 8;
+    let top = 10;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let config = multivector_segment_config(dim);
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+    let mut vectors = HashMap::new();
+    for n in 0..num_points {
+        // Each point holds a different number of vectors
+        let count = rnd.gen_range(1..5);
+        let multi_vector = random_multi_vector(&mut rnd, dim, count);
+        let mut named_vectors = NamedVectors::default();
+        named_vectors.insert_multi(MULTI_VECTOR_NAME.to_owned(), multi_vector.clone());
+        segment.upsert_point(n, n.into(), named_vectors).unwrap();
+        vectors.insert(ExtendedPointId::from(n), multi_vector);
+    }
+
+    // Stored multivectors are returned as they were inserted
+    let stored = segment.vector(MULTI_VECTOR_NAME, 7.into()).unwrap();
+    assert_eq!(
+        stored,
+        Some(Vector::MultiDense(
+            vectors[&ExtendedPointId::from(7)].clone()
+        ))
+    );
+
+    // Vectors of a wrong dimension are rejected
+    let mut wrong_vectors = NamedVectors::default();
+    wrong_vectors.insert_multi(MULTI_VECTOR_NAME.to_owned(), vec![vec![1.0; dim + 1]]);
+    assert!(segment
+        .upsert_point(num_points, num_points.into(), wrong_vectors)
+        .is_err());
+
+    for _ in 0..10 {
+        let query = random_multi_vector(&mut rnd, dim, 3);
+
+        let result = segment
+            .search(
+                MULTI_VECTOR_NAME,
+                &QueryVector::from(Vector::MultiDense(query.clone())),
+                &false.into(),
+                &false.into(),
+                None,
+                top,
+                None,
+                &stopped,
+            )
+            .unwrap();
+
+        let expected = vectors
+            .iter()
+            .map(|(id, vector)| (*id, max_sim(&query, vector)))
+            .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+            .take(top)
+            .collect_vec();
+
+        assert_eq!(result.len(), expected.len());
+        for (scored_point, (_, expected_score)) in result.iter().zip(expected.iter()) {
+            assert!((scored_point.score - expected_score).abs() < 1e-4);
+        }
+    }
+}
+
+#[test]
+fn multivector_hnsw_search_test() {
+    let stopped = AtomicBool::new(false);
+
+    let num_points: u64 = 2_000;
+    let dim = 8;
+    let top = 10;
+    let attempts = 20;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let hnsw_dir = Builder::new().prefix("hnsw_dir").tempdir().unwrap();
+
+    let config = multivector_segment_config(dim);
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+    let mut vectors = HashMap::new();
+    for n in 0..num_points {
+        let count = rnd.gen_range(1..5);
+        let multi_vector = random_multi_vector(&mut rnd, dim, count);
+        let mut named_vectors = NamedVectors::default();
+        named_vectors.insert_multi(MULTI_VECTOR_NAME.to_owned(), multi_vector.clone());
+        segment.upsert_point(n, n.into(), named_vectors).unwrap();
+        vectors.insert(ExtendedPointId::from(n), multi_vector);
+    }
+
+    let hnsw_config = HnswConfig {
+        m: 16,
+        ef_construct: 100,
+        full_scan_threshold: 1, // KB
+        max_indexing_threads: 2,
+        on_disk: Some(false),
+        payload_m: None,
+    };
+
+    let vector_storage = &segment.vector_data[MULTI_VECTOR_NAME].vector_storage;
+    let mut hnsw_index = HNSWIndex::<GraphLinksRam>::open(
+        hnsw_dir.path(),
+        segment.id_tracker.clone(),
+        vector_storage.clone(),
+        segment.payload_index.clone(),
+        hnsw_config,
+    )
+    .unwrap();
+    hnsw_index.build_index(&stopped).unwrap();
+
+    let mut hits = 0;
+    for _ in 0..attempts {
+        let query = QueryVector::from(Vector::MultiDense(random_multi_vector(&mut rnd, dim, 3)));
+
+        let index_result = hnsw_index.search(
+            &[&query],
+            None,
+            top,
+            Some(&SearchParams {
+                hnsw_ef: Some(64),
+                ..Default::default()
+            }),
+            &stopped,
+        );
+        let plain_result = segment.vector_data[MULTI_VECTOR_NAME]
+            .vector_index
+            .borrow()
+            .search(&[&query], None, top, None, &stopped);
+
+        let index_ids = index_result[0].iter().map(|point| point.idx).collect_vec();
+        hits += plain_result[0]
+            .iter()
+            .filter(|point| index_ids.contains(&point.idx))
+            .count();
+    }
+
+    // Search was performed using the HNSW graph
+    assert_eq!(
+        hnsw_index.get_telemetry_data().unfiltered_hnsw.count,
+        attempts
+    );
+
+    let recall = hits as f32 / (attempts * top) as f32;
+    assert!(recall > 0.9, "recall: {recall}");
+}
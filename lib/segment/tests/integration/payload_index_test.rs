@@ -7,6 +7,7 @@ use common::types::PointOffsetType;
 use itertools::Itertools;
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
+use segment::data_types::composite_index::{CompositeIndexParams, CompositeIndexType};
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
 use segment::fixtures::payload_context_fixture::FixtureIdTracker;
@@ -27,7 +28,8 @@ use segment::types::PayloadSchemaType::{Integer, Keyword};
 use segment::types::{
     AnyVariants, Condition, Distance, FieldCondition, Filter, GeoBoundingBox, GeoLineString,
     GeoPoint, GeoPolygon, GeoRadius, Indexes, IsEmptyCondition, Match, Payload, PayloadField,
-    PayloadSchemaType, Range, SegmentConfig, VectorDataConfig, VectorStorageType, WithPayload,
+    PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType, Range, SegmentConfig,
+    VectorDataConfig, VectorStorageType, WithPayload,
 };
 use serde_json::json;
 use tempfile::Builder;
@@ -49,8 +51,11 @@ fn build_test_segments(path_struct: &Path, path_plain: &Path) -> (Segment, Segme
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                multivector_config: None,
+                datatype: None,
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
     };
 
@@ -153,8 +158,11 @@ fn build_test_segments_nested_payload(path_struct: &Path, path_plain: &Path) ->
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Plain {},
                 quantization_config: None,
+                multivector_config: None,
+                datatype: None,
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
     };
 
@@ -798,6 +806,97 @@ fn test_update_payload_index_type() {
     assert_eq!(field_index[1].count_indexed_points(), point_num);
 }
 
+#[test]
+fn test_composite_index() {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+    let mut payload_storage = InMemoryPayloadStorage::default();
+
+    let point_num = 100;
+    for idx in 0..point_num {
+        let payload: Payload = json!({
+            "tenant": format!("tenant{}", idx % 5),
+            "status": idx % 3,
+        })
+        .into();
+        payload_storage
+            .assign(idx as PointOffsetType, &payload)
+            .unwrap();
+    }
+
+    let wrapped_payload_storage = Arc::new(AtomicRefCell::new(payload_storage.into()));
+    let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(point_num)));
+
+    let mut index =
+        StructPayloadIndex::open(wrapped_payload_storage, id_tracker, dir.path(), true).unwrap();
+
+    let params = CompositeIndexParams {
+        r#type: CompositeIndexType::Composite,
+        fields: vec!["tenant".to_string(), "status".to_string()],
+    };
+    index
+        .set_indexed(
+            "tenant_status",
+            PayloadFieldSchema::FieldParams(PayloadSchemaParams::Composite(params)),
+        )
+        .unwrap();
+
+    let filter = Filter {
+        should: None,
+        min_should: None,
+        must: Some(vec![
+            Condition::Field(FieldCondition::new_match("status", 1.into())),
+            Condition::Field(FieldCondition::new_match(
+                "tenant",
+                "tenant2".to_string().into(),
+            )),
+        ]),
+        must_not: None,
+    };
+
+    let expected = (0..point_num as PointOffsetType)
+        .filter(|idx| idx % 5 == 2 && idx % 3 == 1)
+        .collect_vec();
+
+    let estimation = index.estimate_cardinality(&filter);
+    assert_eq!(estimation.exp, expected.len());
+    assert!(matches!(
+        estimation.primary_clauses.as_slice(),
+        [PrimaryCondition::Composite(_)]
+    ));
+    assert_eq!(
+        index
+            .query_points(&filter)
+            .into_iter()
+            .sorted()
+            .collect_vec(),
+        expected
+    );
+
+    // Updating one of the fields re-indexes the point with the stored value of the other
+    let moved_point = expected[0];
+    let payload: Payload = json!({ "status": 0 }).into();
+    index.assign(moved_point, &payload).unwrap();
+    assert_eq!(index.estimate_cardinality(&filter).exp, expected.len() - 1);
+    assert_eq!(
+        index
+            .query_points(&filter)
+            .into_iter()
+            .sorted()
+            .collect_vec(),
+        expected[1..]
+    );
+
+    // Conditions on a part of the fields are not served by the composite index
+    let partial_filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "status",
+        1.into(),
+    )));
+    assert!(index
+        .estimate_cardinality(&partial_filter)
+        .primary_clauses
+        .is_empty());
+}
+
 #[test]
 fn test_any_matcher_cardinality_estimation() {
     let dir1 = Builder::new().prefix("segment1_dir").tempdir().unwrap();
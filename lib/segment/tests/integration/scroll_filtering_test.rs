@@ -1,7 +1,14 @@
+use std::collections::HashSet;
+use std::sync::atomic::AtomicBool;
+
+use itertools::Itertools;
 use rand::prelude::StdRng;
 use rand::{Rng, SeedableRng};
-use segment::fixtures::payload_fixtures::random_filter;
+use segment::data_types::order_by::{Direction, OrderBy, StartFrom};
+use segment::entry::entry_point::SegmentEntry;
+use segment::fixtures::payload_fixtures::{random_filter, INT_KEY};
 use segment::fixtures::segment_fixtures::random_segment;
+use segment::types::PayloadSchemaType;
 use tempfile::Builder;
 
 const NUM_POINTS: usize = 2000;
@@ -29,3 +36,86 @@ fn test_filtering_context_consistency() {
         assert_eq!(read_by_index_res, read_by_stream_res, "filter: {filter:#?}");
     }
 }
+
+#[test]
+fn test_read_ordered_filtered() {
+    let seed = 42;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    let mut segment = random_segment(dir.path(), NUM_POINTS);
+    segment
+        .create_field_index(101, INT_KEY, Some(&PayloadSchemaType::Integer.into()))
+        .unwrap();
+
+    for direction in [Direction::Asc, Direction::Desc] {
+        for _ in 0..ATTEMPTS {
+            let filter = random_filter(&mut rng, 3);
+            let order_by = OrderBy {
+                key: INT_KEY.to_string(),
+                direction: Some(direction),
+                start_from: Some(StartFrom::Integer(rng.gen_range(-10..10))),
+            };
+
+            let points = segment
+                .read_ordered_filtered(Some(10), Some(&filter), &order_by)
+                .unwrap();
+
+            assert!(points
+                .iter()
+                .tuple_windows()
+                .all(|((a, _), (b, _))| direction.compare(*a, *b).is_le()));
+            assert!(points
+                .iter()
+                .all(|&(value, _)| order_by.value_range().check_range(value)));
+
+            // Points with multiple values are returned once
+            assert!(points.iter().map(|(_, id)| id).all_unique());
+
+            let filtered: HashSet<_> = segment
+                .read_filtered(None, None, Some(&filter))
+                .into_iter()
+                .collect();
+            assert!(points.iter().all(|(_, id)| filtered.contains(id)));
+        }
+    }
+}
+
+#[test]
+fn test_sample_points() {
+    let seed = 42;
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    let segment = random_segment(dir.path(), NUM_POINTS);
+    let is_stopped = AtomicBool::new(false);
+
+    let sample = segment
+        .sample_points(&false.into(), &false.into(), None, 10, &is_stopped)
+        .unwrap();
+    assert_eq!(sample.len(), 10);
+    assert!(sample.iter().map(|point| point.id).all_unique());
+
+    for _ in 0..ATTEMPTS {
+        let filter = random_filter(&mut rng, 3);
+
+        let sample = segment
+            .sample_points(&false.into(), &false.into(), Some(&filter), 10, &is_stopped)
+            .unwrap();
+
+        assert!(sample
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a.score >= b.score));
+        assert!(sample.iter().map(|point| point.id).all_unique());
+
+        let filtered: HashSet<_> = segment
+            .read_filtered(None, None, Some(&filter))
+            .into_iter()
+            .collect();
+        assert_eq!(sample.len(), filtered.len().min(10));
+        assert!(sample.iter().all(|point| filtered.contains(&point.id)));
+    }
+}
@@ -5,11 +5,17 @@ use std::time::{Duration, Instant};
 
 use itertools::Itertools;
 use segment::common::operation_error::OperationError;
+use segment::data_types::keyword_index::{KeywordIndexParams, KeywordIndexType};
 use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
 use segment::entry::entry_point::SegmentEntry;
+use segment::id_tracker::IdTracker;
 use segment::segment::Segment;
 use segment::segment_constructor::segment_builder::SegmentBuilder;
-use segment::types::{Indexes, SegmentConfig, VectorDataConfig, VectorStorageType};
+use segment::types::{
+    Indexes, Payload, PayloadFieldSchema, PayloadSchemaParams, SegmentConfig, VectorDataConfig,
+    VectorStorageType,
+};
+use serde_json::json;
 use tempfile::Builder;
 
 use crate::fixtures::segment::{build_segment_1, build_segment_2, empty_segment};
@@ -70,6 +76,118 @@ fn test_building_new_segment() {
     assert_eq!(merged_segment.point_version(3.into()), Some(100));
 }
 
+#[test]
+fn test_building_segment_grouped_by_tenant() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let stopped = AtomicBool::new(false);
+
+    let mut segment = empty_segment(dir.path());
+    let tenant_schema =
+        PayloadFieldSchema::FieldParams(PayloadSchemaParams::Keyword(KeywordIndexParams {
+            r#type: KeywordIndexType::Keyword,
+            is_tenant: Some(true),
+            on_disk: None,
+        }));
+    segment
+        .create_field_index(1, "tenant", Some(&tenant_schema))
+        .unwrap();
+
+    let points_count = 30;
+    for idx in 0..points_count {
+        segment
+            .upsert_point(2, idx.into(), only_default_vector(&[0., 0., 0., 0.]))
+            .unwrap();
+        let payload: Payload = json!({ "tenant": format!("tenant{}", idx % 3) }).into();
+        segment.set_payload(2, idx.into(), &payload).unwrap();
+    }
+
+    let mut builder =
+        SegmentBuilder::new(dir.path(), temp_dir.path(), &segment.segment_config).unwrap();
+    builder.update_from(&segment, &stopped).unwrap();
+    let merged_segment: Segment = builder.build(&stopped).unwrap();
+
+    assert_eq!(
+        merged_segment.available_point_count(),
+        points_count as usize
+    );
+    assert_eq!(
+        merged_segment.get_indexed_fields().get("tenant"),
+        Some(&tenant_schema)
+    );
+
+    let id_tracker = merged_segment.id_tracker.borrow();
+    let tenants = (0..points_count)
+        .map(|idx| {
+            let internal_id = id_tracker.internal_id(idx.into()).unwrap();
+            let payload = merged_segment.payload(idx.into()).unwrap();
+            (
+                internal_id,
+                payload.0["tenant"].as_str().unwrap().to_string(),
+            )
+        })
+        .sorted()
+        .map(|(_, tenant)| tenant)
+        .collect_vec();
+
+    // Each tenant occupies a single contiguous range of internal ids
+    assert_eq!(tenants.iter().dedup().count(), 3);
+}
+
+#[test]
+fn test_building_segment_with_defragmentation_key() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let temp_dir = Builder::new().prefix("segment_temp_dir").tempdir().unwrap();
+
+    let stopped = AtomicBool::new(false);
+
+    // Both segments hold points of all groups, no payload index is required
+    let points_per_segment = 20;
+    let segments = (0..2)
+        .map(|segment_idx| {
+            let mut segment = empty_segment(dir.path());
+            for idx in 0..points_per_segment {
+                let point_id = (segment_idx * points_per_segment + idx).into();
+                segment
+                    .upsert_point(2, point_id, only_default_vector(&[0., 0., 0., 0.]))
+                    .unwrap();
+                let payload: Payload = json!({ "group": idx % 4 }).into();
+                segment.set_payload(2, point_id, &payload).unwrap();
+            }
+            segment
+        })
+        .collect_vec();
+
+    let mut builder =
+        SegmentBuilder::new(dir.path(), temp_dir.path(), &segments[0].segment_config).unwrap();
+    builder.defragmentation_key = Some("group".to_string());
+    builder
+        .update(&segments.iter().collect_vec(), &stopped)
+        .unwrap();
+    let merged_segment: Segment = builder.build(&stopped).unwrap();
+
+    let points_count = 2 * points_per_segment;
+    assert_eq!(
+        merged_segment.available_point_count(),
+        points_count as usize
+    );
+
+    let id_tracker = merged_segment.id_tracker.borrow();
+    let groups = (0..points_count)
+        .map(|idx| {
+            let internal_id = id_tracker.internal_id(idx.into()).unwrap();
+            let payload = merged_segment.payload(idx.into()).unwrap();
+            (internal_id, payload.0["group"].as_u64().unwrap())
+        })
+        .sorted()
+        .map(|(_, group)| group)
+        .collect_vec();
+
+    // Points of a group from both segments occupy a single contiguous range of internal ids
+    assert_eq!(groups.iter().dedup().count(), 4);
+}
+
 fn estimate_build_time(segment: &Segment, stop_delay_millis: u64) -> (u64, bool) {
     let stopped = Arc::new(AtomicBool::new(false));
 
@@ -85,8 +203,11 @@ fn estimate_build_time(segment: &Segment, stop_delay_millis: u64) -> (u64, bool)
                 storage_type: VectorStorageType::Memory,
                 index: Indexes::Hnsw(Default::default()),
                 quantization_config: None,
+                multivector_config: None,
+                datatype: None,
             },
         )]),
+        sparse_vector_data: Default::default(),
         payload_storage_type: Default::default(),
     };
 
@@ -4,12 +4,16 @@ use std::iter::FromIterator;
 use itertools::Itertools;
 use segment::common::operation_error::OperationError;
 use segment::data_types::named_vectors::NamedVectors;
-use segment::data_types::vectors::{only_default_vector, VectorStruct, DEFAULT_VECTOR_NAME};
+use segment::data_types::vectors::{
+    only_default_vector, Vector, VectorStruct, DEFAULT_VECTOR_NAME,
+};
 use segment::entry::entry_point::SegmentEntry;
 use segment::fixtures::index_fixtures::random_vector;
 use segment::segment_constructor::load_segment;
 use segment::segment_constructor::simple_segment_constructor::build_simple_segment;
-use segment::types::{Condition, Distance, Filter, SearchParams, WithPayload};
+use segment::types::{
+    Condition, Distance, Filter, HasIdCondition, PayloadSchemaType, SearchParams, WithPayload,
+};
 use tempfile::Builder;
 
 use crate::fixtures::segment::{build_segment_1, build_segment_3};
@@ -44,6 +48,7 @@ fn test_point_exclusion() {
 
     let frt = Filter {
         should: None,
+        min_should: None,
         must: None,
         must_not: Some(vec![Condition::HasId(ids.into())]),
     };
@@ -103,6 +108,7 @@ fn test_named_vector_search() {
 
     let frt = Filter {
         should: None,
+        min_should: None,
         must: None,
         must_not: Some(vec![Condition::HasId(ids.into())]),
     };
@@ -286,9 +292,11 @@ fn test_update_named_vector() {
         Some(VectorStruct::Single(v)) => {
             assert!((sqrt_distance(v) - 1.).abs() < 1e-5);
         }
-        Some(VectorStruct::Multi(v)) => {
-            assert!((sqrt_distance(&v[DEFAULT_VECTOR_NAME]) - 1.).abs() < 1e-5);
-        }
+        Some(VectorStruct::Multi(v)) => match &v[DEFAULT_VECTOR_NAME] {
+            Vector::Dense(v) => assert!((sqrt_distance(v) - 1.).abs() < 1e-5),
+            Vector::MultiDense(_) => panic!("unexpected multivector"),
+            Vector::Sparse(_) => panic!("unexpected sparse vector"),
+        },
         _ => panic!("unexpected vector type"),
     }
 
@@ -320,12 +328,81 @@ fn test_update_named_vector() {
         Some(VectorStruct::Single(v)) => {
             assert!((sqrt_distance(v) - 1.).abs() < 1e-5);
         }
-        Some(VectorStruct::Multi(v)) => {
-            assert!((sqrt_distance(&v[DEFAULT_VECTOR_NAME]) - 1.).abs() < 1e-5);
-        }
+        Some(VectorStruct::Multi(v)) => match &v[DEFAULT_VECTOR_NAME] {
+            Vector::Dense(v) => assert!((sqrt_distance(v) - 1.).abs() < 1e-5),
+            Vector::MultiDense(_) => panic!("unexpected multivector"),
+            Vector::Sparse(_) => panic!("unexpected sparse vector"),
+        },
         _ => panic!("unexpected vector type"),
     }
 
     // check that nearests are the same
     assert_eq!(nearest_upsert.id, nearest_update.id);
 }
+
+#[test]
+fn test_search_text() {
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+    let mut segment = build_simple_segment(dir.path(), 4, Distance::Dot).unwrap();
+
+    let texts = [
+        "Rust vector database, written in Rust",
+        "A database for documents",
+        "Vector search engine",
+        "Cooking recipes",
+    ];
+    for (i, text) in texts.iter().enumerate() {
+        let i = i as u64;
+        segment
+            .upsert_point(i, i.into(), only_default_vector(&[1.0, 0.0, 0.0, 0.0]))
+            .unwrap();
+        segment
+            .set_full_payload(i, i.into(), &serde_json::json!({ "text": text }).into())
+            .unwrap();
+    }
+    segment
+        .create_field_index(4, "text", Some(&PayloadSchemaType::Text.into()))
+        .unwrap();
+
+    let search_text = |segment: &dyn SegmentEntry, filter: Option<&Filter>| {
+        segment
+            .search_text(
+                "text",
+                "rust database",
+                &WithPayload::default(),
+                &false.into(),
+                filter,
+                10,
+                &false.into(),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|scored_point| scored_point.id)
+            .collect_vec()
+    };
+
+    assert_eq!(search_text(&segment, None), vec![0.into(), 1.into()]);
+
+    let filter = Filter::new_must(Condition::HasId(HasIdCondition {
+        has_id: HashSet::from([1.into(), 2.into()]),
+    }));
+    assert_eq!(search_text(&segment, Some(&filter)), vec![1.into()]);
+
+    segment.delete_point(5, 0.into()).unwrap();
+    assert_eq!(search_text(&segment, None), vec![1.into()]);
+
+    // Text can only be scored with a full-text index
+    let result = segment.search_text(
+        "other",
+        "rust",
+        &WithPayload::default(),
+        &false.into(),
+        None,
+        10,
+        &false.into(),
+    );
+    assert!(matches!(
+        result,
+        Err(OperationError::ValidationError { .. })
+    ));
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::sync::atomic::AtomicBool;
+
+use itertools::Itertools;
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::{Rng, SeedableRng};
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::vectors::QueryVector;
+use segment::entry::entry_point::SegmentEntry;
+use segment::segment_constructor::build_segment;
+use segment::types::{ExtendedPointId, SegmentConfig, SparseVectorDataConfig};
+use sparse::common::sparse_vector::SparseVector;
+use tempfile::Builder;
+
+const SPARSE_VECTOR_NAME: &str = "sparse";
+
+fn random_sparse_vector<R: Rng + ?Sized>(rnd: &mut R, max_dim: usize, nnz: usize) -> SparseVector {
+    let indices = sample(rnd, max_dim, nnz)
+        .into_iter()
+        .map(|i| i as u32)
+        .sorted()
+        .collect_vec();
+    let weights = indices.iter().map(|_| rnd.gen_range(0.0..1.0)).collect();
+    SparseVector::new(indices, weights)
+}
+
+#[test]
+fn sparse_vector_search_test() {
+    let stopped = AtomicBool::new(false);
+
+    let num_vectors: u64 = 1_000;
+    let max_dim = 100;
+    let nnz = 10;
+    let top = 10;
+
+    let mut rnd = StdRng::seed_from_u64(42);
+
+    let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+    let config = SegmentConfig {
+        vector_data: Default::default(),
+        sparse_vector_data: HashMap::from([(
+            SPARSE_VECTOR_NAME.to_owned(),
+            SparseVectorDataConfig::default(),
+        )]),
+        payload_storage_type: Default::default(),
+    };
+
+    let mut segment = build_segment(dir.path(), &config, true).unwrap();
+
+    let mut vectors = HashMap::new();
+    for n in 0..num_vectors {
+        let vector = random_sparse_vector(&mut rnd, max_dim, nnz);
+        let mut named_vectors = NamedVectors::default();
+        named_vectors.insert_sparse(SPARSE_VECTOR_NAME.to_owned(), vector.clone());
+        segment.upsert_point(n, n.into(), named_vectors).unwrap();
+        vectors.insert(ExtendedPointId::from(n), vector);
+    }
+
+    // Remove some points to make sure deleted vectors are not returned
+    for n in (0..num_vectors).step_by(10) {
+        segment.delete_point(num_vectors + n, n.into()).unwrap();
+        vectors.remove(&ExtendedPointId::from(n));
+    }
+
+    for _ in 0..10 {
+        let query = random_sparse_vector(&mut rnd, max_dim, nnz);
+
+        let result = segment
+            .search(
+                SPARSE_VECTOR_NAME,
+                &QueryVector::Sparse(query.clone()),
+                &false.into(),
+                &false.into(),
+                None,
+                top,
+                None,
+                &stopped,
+            )
+            .unwrap();
+
+        let expected = vectors
+            .iter()
+            .filter_map(|(id, vector)| query.score(vector).map(|score| (*id, score)))
+            .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+            .take(top)
+            .collect_vec();
+
+        assert_eq!(result.len(), expected.len());
+        for (scored_point, (_, expected_score)) in result.iter().zip(expected.iter()) {
+            assert!((scored_point.score - expected_score).abs() < 1e-5);
+            assert!(vectors.contains_key(&scored_point.id));
+        }
+    }
+}
@@ -1,8 +1,18 @@
+use std::collections::HashSet;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError, ValidationErrors};
+
 use crate::common::types::{DimId, DimWeight};
 
-#[derive(Debug, PartialEq, Clone)]
+/// Sparse vector structure
+#[derive(Debug, PartialEq, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct SparseVector {
+    /// indices must be unique
     pub indices: Vec<DimId>,
+    /// values and indices must be the same length
+    #[serde(rename = "values")]
     pub weights: Vec<DimWeight>,
 }
 
@@ -10,4 +20,121 @@ impl SparseVector {
     pub fn new(indices: Vec<DimId>, weights: Vec<DimWeight>) -> SparseVector {
         SparseVector { indices, weights }
     }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Sort this vector by indices.
+    ///
+    /// Sorting is required for scoring and overlap checks.
+    pub fn sort_by_indices(&mut self) {
+        if self.indices.windows(2).all(|w| w[0] <= w[1]) {
+            return;
+        }
+        let mut pairs: Vec<_> = self
+            .indices
+            .iter()
+            .copied()
+            .zip(self.weights.iter().copied())
+            .collect();
+        pairs.sort_unstable_by_key(|(index, _)| *index);
+        (self.indices, self.weights) = pairs.into_iter().unzip();
+    }
+
+    /// Check if this vector is sorted by indices.
+    pub fn is_sorted(&self) -> bool {
+        self.indices.windows(2).all(|w| w[0] < w[1])
+    }
+
+    /// Score this vector against another vector using dot product.
+    /// Warning: Expects both vectors to be sorted by indices.
+    ///
+    /// Return None if the vectors do not overlap.
+    pub fn score(&self, other: &SparseVector) -> Option<f32> {
+        debug_assert!(self.is_sorted());
+        debug_assert!(other.is_sorted());
+        let mut score = 0.0;
+        // track whether there is any overlap
+        let mut overlap = false;
+        let mut i = 0;
+        let mut j = 0;
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    overlap = true;
+                    score += self.weights[i] * other.weights[j];
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        overlap.then_some(score)
+    }
+}
+
+impl Validate for SparseVector {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+
+        if self.indices.len() != self.weights.len() {
+            errors.add(
+                "values",
+                ValidationError::new("must be the same length as indices"),
+            );
+        }
+
+        let mut unique = HashSet::with_capacity(self.indices.len());
+        if !self.indices.iter().all(|index| unique.insert(*index)) {
+            errors.add("indices", ValidationError::new("must be unique"));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_score_aligned_same_size() {
+        let v1 = SparseVector::new(vec![1, 2, 3], vec![1.0, 2.0, 3.0]);
+        let v2 = SparseVector::new(vec![1, 2, 3], vec![1.0, 2.0, 3.0]);
+        assert_eq!(v1.score(&v2), Some(14.0));
+    }
+
+    #[test]
+    fn test_score_not_aligned() {
+        let v1 = SparseVector::new(vec![1, 2, 3], vec![1.0, 2.0, 3.0]);
+        let v2 = SparseVector::new(vec![3, 4, 5], vec![1.0, 2.0, 3.0]);
+        assert_eq!(v1.score(&v2), Some(3.0));
+
+        let v3 = SparseVector::new(vec![4, 5, 6], vec![1.0, 2.0, 3.0]);
+        assert_eq!(v1.score(&v3), None);
+    }
+
+    #[test]
+    fn test_sort_by_indices() {
+        let mut v = SparseVector::new(vec![3, 1, 2], vec![3.0, 1.0, 2.0]);
+        v.sort_by_indices();
+        assert_eq!(v, SparseVector::new(vec![1, 2, 3], vec![1.0, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert!(SparseVector::new(vec![1, 2], vec![1.0, 2.0])
+            .validate()
+            .is_ok());
+        assert!(SparseVector::new(vec![1, 2], vec![1.0]).validate().is_err());
+        assert!(SparseVector::new(vec![1, 1], vec![1.0, 2.0])
+            .validate()
+            .is_err());
+    }
 }
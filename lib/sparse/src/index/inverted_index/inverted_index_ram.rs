@@ -1,12 +1,13 @@
 use std::collections::HashMap;
 
+use common::types::PointOffsetType;
+
+use crate::common::sparse_vector::SparseVector;
 use crate::common::types::DimId;
 use crate::index::posting_list::PostingList;
 
 /// Inverted flatten index from dimension id to posting list
-
-/// Inverted flatten index from dimension id to posting list
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default)]
 pub struct InvertedIndexRam {
     pub postings: Vec<PostingList>,
 }
@@ -15,8 +16,29 @@ impl InvertedIndexRam {
     pub fn get(&self, id: &DimId) -> Option<&PostingList> {
         self.postings.get((*id) as usize)
     }
+
+    /// Index all dimensions of the given vector for the record
+    pub fn upsert(&mut self, id: PointOffsetType, vector: &SparseVector) {
+        for (dim_id, weight) in vector.indices.iter().zip(vector.weights.iter()) {
+            let dim_id = *dim_id as usize;
+            if dim_id >= self.postings.len() {
+                self.postings.resize(dim_id + 1, PostingList::default());
+            }
+            self.postings[dim_id].upsert(id, *weight);
+        }
+    }
+
+    /// Remove the record from posting lists of all dimensions of the given vector
+    pub fn remove(&mut self, id: PointOffsetType, vector: &SparseVector) {
+        for dim_id in &vector.indices {
+            if let Some(posting) = self.postings.get_mut(*dim_id as usize) {
+                posting.delete(id);
+            }
+        }
+    }
 }
 
+#[derive(Default)]
 pub struct InvertedIndexBuilder {
     postings: HashMap<DimId, PostingList>,
 }
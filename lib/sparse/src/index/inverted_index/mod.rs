@@ -1,3 +1,6 @@
+use common::types::PointOffsetType;
+
+use crate::common::sparse_vector::SparseVector;
 use crate::common::types::DimId;
 use crate::index::inverted_index::inverted_index_mmap::InvertedIndexMmap;
 use crate::index::inverted_index::inverted_index_ram::InvertedIndexRam;
@@ -20,4 +23,20 @@ impl InvertedIndex {
             InvertedIndex::Mmap(index) => index.get(id).map(PostingListIterator::new),
         }
     }
+
+    /// Update the postings of the record. Only in-memory index is mutable.
+    pub fn upsert(&mut self, id: PointOffsetType, vector: &SparseVector) {
+        match self {
+            InvertedIndex::Ram(index) => index.upsert(id, vector),
+            InvertedIndex::Mmap(_) => panic!("Mmap inverted index is read-only"),
+        }
+    }
+
+    /// Remove the record from postings. Only in-memory index is mutable.
+    pub fn remove(&mut self, id: PointOffsetType, vector: &SparseVector) {
+        match self {
+            InvertedIndex::Ram(index) => index.remove(id, vector),
+            InvertedIndex::Mmap(_) => panic!("Mmap inverted index is read-only"),
+        }
+    }
 }
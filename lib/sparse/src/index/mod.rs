@@ -1,5 +1,3 @@
-#![allow(dead_code)]
-
-mod inverted_index;
-mod posting_list;
-mod search_context;
+pub mod inverted_index;
+pub mod posting_list;
+pub mod search_context;
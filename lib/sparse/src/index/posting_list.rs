@@ -24,8 +24,80 @@ impl PostingList {
         }
         posting_list.build()
     }
+
+    /// Creates a posting list with a single element
+    pub fn new_one(record_id: PointOffsetType, weight: DimWeight) -> PostingList {
+        PostingList {
+            elements: vec![PostingElement {
+                record_id,
+                weight,
+                max_next_weight: f32::NEG_INFINITY,
+            }],
+        }
+    }
+
+    /// Inserts or updates the weight of the element with the given id,
+    /// keeping the list ordered by id and `max_next_weight` consistent.
+    pub fn upsert(&mut self, record_id: PointOffsetType, weight: DimWeight) {
+        let position = match self
+            .elements
+            .binary_search_by_key(&record_id, |e| e.record_id)
+        {
+            Ok(position) => {
+                self.elements[position].weight = weight;
+                position
+            }
+            Err(position) => {
+                let max_next_weight = self
+                    .elements
+                    .get(position)
+                    .map(|next| next.weight.max(next.max_next_weight))
+                    .unwrap_or(f32::NEG_INFINITY);
+                self.elements.insert(
+                    position,
+                    PostingElement {
+                        record_id,
+                        weight,
+                        max_next_weight,
+                    },
+                );
+                position
+            }
+        };
+        self.propagate_max_next_weight(position);
+    }
+
+    /// Removes the element with the given id, if present.
+    pub fn delete(&mut self, record_id: PointOffsetType) {
+        if let Ok(position) = self
+            .elements
+            .binary_search_by_key(&record_id, |e| e.record_id)
+        {
+            self.elements.remove(position);
+            if position > 0 {
+                let max_next_weight = self
+                    .elements
+                    .get(position)
+                    .map(|next| next.weight.max(next.max_next_weight))
+                    .unwrap_or(f32::NEG_INFINITY);
+                self.elements[position - 1].max_next_weight = max_next_weight;
+                self.propagate_max_next_weight(position - 1);
+            }
+        }
+    }
+
+    /// Recalculates `max_next_weight` of all elements before `position`
+    fn propagate_max_next_weight(&mut self, position: usize) {
+        let element = &self.elements[position];
+        let mut max_next_weight = element.weight.max(element.max_next_weight);
+        for element in self.elements[..position].iter_mut().rev() {
+            element.max_next_weight = max_next_weight;
+            max_next_weight = max_next_weight.max(element.weight);
+        }
+    }
 }
 
+#[derive(Default)]
 pub struct PostingBuilder {
     elements: Vec<PostingElement>,
 }
@@ -186,4 +258,38 @@ mod tests {
         assert!(iter.skip_to(21).is_none());
         assert!(iter.peek().is_none());
     }
+
+    #[test]
+    fn test_upsert_delete() {
+        let mut posting_list = PostingList::from(vec![(1, 1.0), (3, 5.0), (5, 2.0)]);
+
+        posting_list.upsert(4, 7.0);
+        assert_eq!(
+            posting_list
+                .elements
+                .iter()
+                .map(|e| (e.record_id, e.max_next_weight))
+                .collect::<Vec<_>>(),
+            vec![(1, 7.0), (3, 7.0), (4, 2.0), (5, f32::NEG_INFINITY)]
+        );
+
+        posting_list.upsert(1, 3.0);
+        assert_eq!(posting_list.elements[0].weight, 3.0);
+
+        posting_list.delete(4);
+        assert_eq!(
+            posting_list
+                .elements
+                .iter()
+                .map(|e| (e.record_id, e.max_next_weight))
+                .collect::<Vec<_>>(),
+            vec![(1, 5.0), (3, 2.0), (5, f32::NEG_INFINITY)]
+        );
+
+        posting_list.delete(5);
+        assert_eq!(
+            posting_list.elements.last().unwrap().max_next_weight,
+            f32::NEG_INFINITY
+        );
+    }
 }
@@ -1,5 +1,5 @@
 use common::fixed_length_priority_queue::FixedLengthPriorityQueue;
-use common::types::ScoredPointOffset;
+use common::types::{PointOffsetType, ScoredPointOffset};
 
 use crate::common::sparse_vector::SparseVector;
 use crate::index::inverted_index::InvertedIndex;
@@ -123,11 +123,19 @@ impl<'a> SearchContext<'a> {
         });
     }
 
-    pub fn search(&mut self) -> Vec<ScoredPointOffset> {
+    /// Search for the top results, keeping only records accepted by `filter_condition`
+    pub fn search<F: Fn(PointOffsetType) -> bool>(
+        &mut self,
+        filter_condition: &F,
+    ) -> Vec<ScoredPointOffset> {
         if self.postings_iterators.is_empty() {
             return Vec::new();
         }
         while let Some(candidate) = self.advance() {
+            // check filter condition
+            if !filter_condition(candidate.idx) {
+                continue;
+            }
             // push candidate to result queue
             self.result_queue.push(candidate);
 
@@ -191,6 +199,10 @@ mod tests {
     use crate::index::inverted_index::inverted_index_ram::InvertedIndexBuilder;
     use crate::index::posting_list::PostingList;
 
+    fn match_all(_p: PointOffsetType) -> bool {
+        true
+    }
+
     fn _advance_test(inverted_index: &InvertedIndex) {
         let mut search_context = SearchContext::new(
             SparseVector {
@@ -259,7 +271,7 @@ mod tests {
         );
 
         assert_eq!(
-            search_context.search(),
+            search_context.search(&match_all),
             vec![
                 ScoredPointOffset {
                     score: 90.0,
@@ -311,7 +323,7 @@ mod tests {
         );
 
         assert_eq!(
-            search_context.search(),
+            search_context.search(&match_all),
             vec![
                 ScoredPointOffset {
                     score: 90.0,
@@ -338,7 +350,7 @@ mod tests {
         );
 
         assert_eq!(
-            search_context.search(),
+            search_context.search(&match_all),
             vec![
                 ScoredPointOffset {
                     score: 90.0,
@@ -357,6 +369,43 @@ mod tests {
         );
     }
 
+    fn _search_with_filter_test(inverted_index: &InvertedIndex) {
+        let mut search_context = SearchContext::new(
+            SparseVector {
+                indices: vec![1, 2, 3],
+                weights: vec![1.0, 1.0, 1.0],
+            },
+            10,
+            inverted_index,
+        );
+
+        assert_eq!(
+            search_context.search(&|idx| idx != 2),
+            vec![
+                ScoredPointOffset {
+                    score: 90.0,
+                    idx: 3
+                },
+                ScoredPointOffset {
+                    score: 30.0,
+                    idx: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn search_with_filter_test() {
+        let inverted_index_ram = InvertedIndexBuilder::new()
+            .add(1, PostingList::from(vec![(1, 10.0), (2, 20.0), (3, 30.0)]))
+            .add(2, PostingList::from(vec![(1, 10.0), (2, 20.0), (3, 30.0)]))
+            .add(3, PostingList::from(vec![(1, 10.0), (2, 20.0), (3, 30.0)]))
+            .build();
+
+        let inverted_index = InvertedIndex::Ram(inverted_index_ram);
+        _search_with_filter_test(&inverted_index);
+    }
+
     #[test]
     fn search_with_hot_key_test() {
         let inverted_index_ram = InvertedIndexBuilder::new()
@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
+
 use collection::config::CollectionConfig;
 use collection::operations::config_diff::{
     CollectionParamsDiff, HnswConfigDiff, OptimizersConfigDiff, QuantizationConfigDiff,
     WalConfigDiff,
 };
-use collection::operations::types::{VectorsConfig, VectorsConfigDiff};
+use collection::operations::types::{SparseVectorParams, VectorsConfig, VectorsConfigDiff};
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::shards::transfer::shard_transfer::{ShardTransfer, ShardTransferKey};
@@ -143,6 +145,9 @@ pub struct CreateCollection {
     #[serde(default, alias = "quantization")]
     #[validate]
     pub quantization_config: Option<QuantizationConfig>,
+    /// Sparse vector data config.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_vectors: Option<BTreeMap<String, SparseVectorParams>>,
 }
 
 /// Operation for creating new collection and (optionally) specify index params
@@ -197,6 +202,16 @@ pub struct UpdateCollection {
     #[serde(default, alias = "quantization")]
     #[validate]
     pub quantization_config: Option<QuantizationConfigDiff>,
+    /// New named vectors to add to the collection. Existing points don't have them until they
+    /// are set by an update.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate]
+    pub add_vectors: Option<VectorsConfig>,
+    /// Names of the dense or sparse vectors to remove from the collection, together with
+    /// all their data. At least one vector must remain. Disk space is freed once optimizers
+    /// rebuild the affected segments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drop_vectors: Option<Vec<String>>,
 }
 
 /// Operation for updating parameters of the existing collection
@@ -218,6 +233,8 @@ impl UpdateCollectionOperation {
                 params: None,
                 optimizers_config: None,
                 quantization_config: None,
+                add_vectors: None,
+                drop_vectors: None,
             },
             shard_replica_changes: None,
         }
@@ -321,6 +338,7 @@ impl From<CollectionConfig> for CreateCollection {
             optimizers_config: Some(value.optimizer_config.into()),
             init_from: None,
             quantization_config: value.quantization_config,
+            sparse_vectors: value.params.sparse_vectors,
         }
     }
 }
@@ -36,6 +36,10 @@ pub struct Persistent {
     /// Operations to applied, consensus consider them committed, but this peer didn't apply them yet
     #[serde(default)]
     pub apply_progress_queue: EntryApplyProgressQueue,
+    /// Number of operations of the current unapplied batch entry, which are applied already.
+    /// Allows to resume the batch after a failure without applying its operations twice.
+    #[serde(default)]
+    pub applied_batch_operations: usize,
     /// Last known cluster topology
     #[serde(with = "serialize_peer_addresses")]
     pub peer_address_by_id: Arc<RwLock<PeerAddressById>>,
@@ -66,6 +70,7 @@ impl Persistent {
         self.state.hard_state.term = cmp::max(self.state.hard_state.term, meta.term);
         self.state.hard_state.commit = meta.index;
         self.apply_progress_queue.set_from_snapshot(meta.index);
+        self.applied_batch_operations = 0;
         self.latest_snapshot_meta = meta.into();
         self.save()
     }
@@ -117,6 +122,16 @@ impl Persistent {
 
     pub fn entry_applied(&mut self) -> Result<(), StorageError> {
         self.apply_progress_queue.applied();
+        self.applied_batch_operations = 0;
+        self.save()
+    }
+
+    pub fn applied_batch_operations(&self) -> usize {
+        self.applied_batch_operations
+    }
+
+    pub fn batch_operation_applied(&mut self) -> Result<(), StorageError> {
+        self.applied_batch_operations += 1;
         self.save()
     }
 
@@ -126,6 +141,7 @@ impl Persistent {
         last_index: EntryId,
     ) -> Result<(), StorageError> {
         self.apply_progress_queue = EntryApplyProgressQueue::new(first_index, last_index);
+        self.applied_batch_operations = 0;
         self.save()
     }
 
@@ -187,6 +203,7 @@ impl Persistent {
                 conf_state: ConfState::from((voters, vec![])),
             },
             apply_progress_queue: Default::default(),
+            applied_batch_operations: 0,
             peer_address_by_id: Default::default(),
             this_peer_id,
             path,
@@ -23,6 +23,7 @@ use tokio::time::error::Elapsed;
 use tonic::transport::Uri;
 
 use super::alias_mapping::AliasMapping;
+use super::collection_meta_ops::CollectionMetaOperations;
 use super::consensus_ops::{ConsensusOperations, SnapshotStatus};
 use super::errors::StorageError;
 use super::CollectionContainer;
@@ -431,12 +432,19 @@ impl<C: CollectionContainer> ConsensusManager<C> {
     ///
     pub fn apply_normal_entry(&self, entry: &RaftEntry) -> Result<bool, StorageError> {
         let operation: ConsensusOperations = entry.try_into()?;
+
+        if let ConsensusOperations::CollectionMetaBatch(operations) = operation {
+            return self.apply_collection_meta_batch(operations);
+        }
+
         let on_apply = self.on_consensus_op_apply.lock().remove(&operation);
         let result = match operation {
             ConsensusOperations::CollectionMeta(operation) => {
                 self.toc.perform_collection_meta_op(*operation)
             }
 
+            ConsensusOperations::CollectionMetaBatch(_) => unreachable!(),
+
             ConsensusOperations::AddPeer { .. } | ConsensusOperations::RemovePeer(_) => {
                 // RemovePeer or AddPeer should be converted into native ConfChangeV2 message before sending to the Raft.
                 // So we do not expect to receive these operations as a normal entry.
@@ -449,6 +457,15 @@ impl<C: CollectionContainer> ConsensusManager<C> {
                 Ok(false)
             }
 
+            ConsensusOperations::UpdatePeerAddress { peer_id, uri } => {
+                match Uri::try_from(uri.as_str()) {
+                    Ok(uri) => self.update_peer_address(peer_id, uri).map(|()| true),
+                    Err(err) => Err(StorageError::bad_request(format!(
+                        "Failed to parse peer uri {uri}: {err}"
+                    ))),
+                }
+            }
+
             ConsensusOperations::RequestSnapshot | ConsensusOperations::ReportSnapshot { .. } => {
                 unreachable!()
             }
@@ -462,6 +479,54 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         result
     }
 
+    /// Apply operations of a batched entry one by one.
+    ///
+    /// Listeners are subscribed to individual operations, so each of them is notified with its
+    /// own result. User errors are reported to the listener and do not prevent other operations
+    /// of the batch from being applied. Service errors stop consensus, same as for single entries.
+    ///
+    /// Progress within the batch is persisted after every operation, so if the batch is
+    /// interrupted, it is resumed from the first not applied operation. This gives every
+    /// operation of the batch the same guarantees as an operation of a single entry.
+    fn apply_collection_meta_batch(
+        &self,
+        operations: Vec<CollectionMetaOperations>,
+    ) -> Result<bool, StorageError> {
+        let applied_operations = self.persistent.read().applied_batch_operations();
+        if applied_operations > 0 {
+            log::debug!(
+                "Resuming batch entry, {applied_operations} of {} operations are applied already",
+                operations.len(),
+            );
+        }
+
+        for meta_operation in operations.into_iter().skip(applied_operations) {
+            let operation = ConsensusOperations::CollectionMeta(Box::new(meta_operation.clone()));
+            let on_apply = self.on_consensus_op_apply.lock().remove(&operation);
+
+            let result = self.toc.perform_collection_meta_op(meta_operation);
+
+            if let Some(on_apply) = on_apply {
+                if on_apply.send(result.clone()).is_err() {
+                    log::warn!("Failed to notify on consensus operation completion: channel receiver is dropped")
+                }
+            }
+
+            match result {
+                Ok(_) => {}
+                Err(err @ StorageError::ServiceError { .. }) => return Err(err),
+                Err(err) => {
+                    log::warn!(
+                        "Failed to apply batched collection meta operation with user error: {err}"
+                    );
+                }
+            }
+
+            self.persistent.write().batch_operation_applied()?;
+        }
+        Ok(true)
+    }
+
     // Outer `Result` is "fatal" error, inner `Result` is "transient"/"local" error.
     pub fn apply_snapshot(
         &self,
@@ -523,6 +588,19 @@ impl<C: CollectionContainer> ConsensusManager<C> {
         self.persistent.read().save()
     }
 
+    /// Replace the address of a known peer, keeping its id, shards and replica states intact.
+    pub fn update_peer_address(&self, peer_id: PeerId, uri: Uri) -> Result<(), StorageError> {
+        if !self.peer_address_by_id().contains_key(&peer_id) {
+            return Err(StorageError::NotFound {
+                description: format!("Peer {peer_id} is not known to the cluster"),
+            });
+        }
+        // Same as in `remove_peer`: `id_to_address` is shared with the channel pool,
+        // so the update is performed by the `toc`, which also drops stale connections.
+        self.toc.update_peer_address(peer_id, uri)?;
+        self.persistent.read().save()
+    }
+
     async fn await_receiver(
         mut receiver: Receiver<Result<bool, StorageError>>,
         wait_timeout: Duration,
@@ -850,12 +928,18 @@ mod tests {
     use raft::eraftpb::Entry;
     use raft::storage::{MemStorage, Storage};
     use tempfile::Builder;
+    use tonic::transport::Uri;
 
     use super::ConsensusManager;
+    use crate::content_manager::collection_meta_ops::{
+        CollectionMetaOperations, DeleteCollectionOperation,
+    };
     use crate::content_manager::consensus::consensus_wal::ConsensusOpWal;
     use crate::content_manager::consensus::entry_queue::EntryApplyProgressQueue;
     use crate::content_manager::consensus::operation_sender::OperationSender;
     use crate::content_manager::consensus::persistent::Persistent;
+    use crate::content_manager::consensus_ops::ConsensusOperations;
+    use crate::content_manager::errors::StorageError;
     use crate::content_manager::CollectionContainer;
 
     #[test]
@@ -978,11 +1062,166 @@ mod tests {
             Ok(())
         }
 
+        fn update_peer_address(
+            &self,
+            _peer_id: PeerId,
+            _uri: Uri,
+        ) -> Result<(), crate::content_manager::errors::StorageError> {
+            Ok(())
+        }
+
         fn sync_local_state(&self) -> Result<(), crate::content_manager::errors::StorageError> {
             Ok(())
         }
     }
 
+    /// Records applied collection meta operations, fails the given operation once
+    #[derive(Default)]
+    struct RecordingCollections {
+        applied: parking_lot::Mutex<Vec<CollectionMetaOperations>>,
+        failure: parking_lot::Mutex<Option<(CollectionMetaOperations, StorageError)>>,
+    }
+
+    impl RecordingCollections {
+        fn failing_once(operation: CollectionMetaOperations, error: StorageError) -> Self {
+            Self {
+                applied: Default::default(),
+                failure: parking_lot::Mutex::new(Some((operation, error))),
+            }
+        }
+
+        fn applied(&self) -> Vec<CollectionMetaOperations> {
+            self.applied.lock().clone()
+        }
+    }
+
+    impl CollectionContainer for RecordingCollections {
+        fn perform_collection_meta_op(
+            &self,
+            operation: CollectionMetaOperations,
+        ) -> Result<bool, StorageError> {
+            let mut failure = self.failure.lock();
+            if matches!(&*failure, Some((failing, _)) if *failing == operation) {
+                return Err(failure.take().unwrap().1);
+            }
+            self.applied.lock().push(operation);
+            Ok(true)
+        }
+
+        fn collections_snapshot(&self) -> super::CollectionsSnapshot {
+            super::CollectionsSnapshot::default()
+        }
+
+        fn apply_collections_snapshot(
+            &self,
+            _data: super::CollectionsSnapshot,
+        ) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        fn remove_peer(&self, _peer_id: PeerId) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        fn update_peer_address(&self, _peer_id: PeerId, _uri: Uri) -> Result<(), StorageError> {
+            Ok(())
+        }
+
+        fn sync_local_state(&self) -> Result<(), StorageError> {
+            Ok(())
+        }
+    }
+
+    fn delete_collection(name: &str) -> CollectionMetaOperations {
+        CollectionMetaOperations::DeleteCollection(DeleteCollectionOperation(name.to_string()))
+    }
+
+    fn batch_entry(operations: Vec<CollectionMetaOperations>) -> Entry {
+        let operation = ConsensusOperations::CollectionMetaBatch(operations);
+        Entry {
+            index: 1,
+            data: serde_cbor::to_vec(&operation).unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn recording_consensus(
+        collections: Arc<RecordingCollections>,
+        path: &std::path::Path,
+    ) -> ConsensusManager<RecordingCollections> {
+        let persistent = Persistent::load_or_init(path, true).unwrap();
+        let (sender, _) = mpsc::channel();
+        ConsensusManager::new(
+            persistent,
+            collections,
+            OperationSender::new(sender),
+            path.to_str().unwrap(),
+        )
+    }
+
+    #[test]
+    fn batch_is_resumed_after_service_error() {
+        let dir = Builder::new().prefix("raft_state_test").tempdir().unwrap();
+        let operations = vec![
+            delete_collection("a"),
+            delete_collection("b"),
+            delete_collection("c"),
+        ];
+        let entry = batch_entry(operations.clone());
+        let collections = Arc::new(RecordingCollections::failing_once(
+            delete_collection("b"),
+            StorageError::service_error("No space left on device"),
+        ));
+
+        let consensus_state = recording_consensus(collections.clone(), dir.path());
+        let result = consensus_state.apply_normal_entry(&entry);
+        assert!(matches!(result, Err(StorageError::ServiceError { .. })));
+        assert_eq!(collections.applied(), operations[..1]);
+        drop(consensus_state);
+
+        // After restart, the batch continues from the failed operation
+        let consensus_state = recording_consensus(collections.clone(), dir.path());
+        assert_eq!(
+            consensus_state.persistent.read().applied_batch_operations(),
+            1
+        );
+        assert!(consensus_state.apply_normal_entry(&entry).unwrap());
+        assert_eq!(collections.applied(), operations);
+
+        consensus_state.persistent.write().entry_applied().unwrap();
+        assert_eq!(
+            consensus_state.persistent.read().applied_batch_operations(),
+            0
+        );
+    }
+
+    #[test]
+    fn batch_continues_after_user_error() {
+        let dir = Builder::new().prefix("raft_state_test").tempdir().unwrap();
+        let entry = batch_entry(vec![
+            delete_collection("a"),
+            delete_collection("b"),
+            delete_collection("c"),
+        ]);
+        let collections = Arc::new(RecordingCollections::failing_once(
+            delete_collection("b"),
+            StorageError::NotFound {
+                description: "Collection b not found".to_string(),
+            },
+        ));
+
+        let consensus_state = recording_consensus(collections.clone(), dir.path());
+        assert!(consensus_state.apply_normal_entry(&entry).unwrap());
+        assert_eq!(
+            collections.applied(),
+            vec![delete_collection("a"), delete_collection("c")],
+        );
+        assert_eq!(
+            consensus_state.persistent.read().applied_batch_operations(),
+            3
+        );
+    }
+
     fn setup_storages(
         entries: Vec<Entry>,
         path: &std::path::Path,
@@ -16,6 +16,7 @@ pub fn error_to_status(error: StorageError) -> tonic::Status {
         StorageError::BadRequest { .. } => tonic::Code::InvalidArgument,
         StorageError::Locked { .. } => tonic::Code::FailedPrecondition,
         StorageError::Timeout { .. } => tonic::Code::DeadlineExceeded,
+        StorageError::PreconditionFailed { .. } => tonic::Code::Aborted,
     };
     tonic::Status::new(error_code, format!("{error}"))
 }
@@ -45,6 +46,7 @@ impl TryFrom<api::grpc::qdrant::CreateCollection> for CollectionMetaOperations {
                     .quantization_config
                     .map(TryInto::try_into)
                     .transpose()?,
+                sparse_vectors: value.sparse_vectors_config.map(Into::into),
             },
         )))
     }
@@ -69,6 +71,12 @@ impl TryFrom<api::grpc::qdrant::UpdateCollection> for CollectionMetaOperations {
                     .quantization_config
                     .map(TryInto::try_into)
                     .transpose()?,
+                add_vectors: value
+                    .add_vectors
+                    .and_then(|config| config.config)
+                    .map(TryInto::try_into)
+                    .transpose()?,
+                drop_vectors: (!value.drop_vectors.is_empty()).then_some(value.drop_vectors),
             },
         )))
     }
@@ -83,6 +83,9 @@ async fn replicate_shard_data(
             filter: None,
             with_payload: Some(WithPayloadInterface::Bool(true)),
             with_vector: WithVector::Bool(true),
+            order_by: None,
+            stable: None,
+            cursor: None,
         };
 
         let collections_read = collections.read().await;
@@ -24,6 +24,8 @@ pub enum StorageError {
     Locked { description: String },
     #[error("Timeout: {description}")]
     Timeout { description: String },
+    #[error("Precondition failed: {description}")]
+    PreconditionFailed { description: String },
 }
 
 impl StorageError {
@@ -91,6 +93,9 @@ impl StorageError {
             CollectionError::Timeout { .. } => StorageError::Timeout {
                 description: overriding_description,
             },
+            CollectionError::PreconditionFailed { .. } => StorageError::PreconditionFailed {
+                description: overriding_description,
+            },
         }
     }
 }
@@ -132,6 +137,9 @@ impl From<CollectionError> for StorageError {
             CollectionError::Timeout { .. } => StorageError::Timeout {
                 description: format!("{err}"),
             },
+            CollectionError::PreconditionFailed { description } => {
+                StorageError::PreconditionFailed { description }
+            }
         }
     }
 }
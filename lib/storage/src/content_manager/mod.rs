@@ -1,4 +1,5 @@
 use collection::shards::shard::PeerId;
+use tonic::transport::Uri;
 
 use self::collection_meta_ops::CollectionMetaOperations;
 use self::consensus_manager::CollectionsSnapshot;
@@ -13,6 +14,7 @@ pub mod conversions;
 mod data_transfer;
 pub mod errors;
 pub mod shard_distribution;
+pub mod slow_query_log;
 pub mod snapshots;
 pub mod toc;
 
@@ -34,11 +36,21 @@ pub mod consensus_ops {
     #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
     pub enum ConsensusOperations {
         CollectionMeta(Box<CollectionMetaOperations>),
+        /// Several collection meta operations, committed as a single consensus entry.
+        /// Each operation is applied and reported separately, so a failure of one of them
+        /// does not affect the others.
+        CollectionMetaBatch(Vec<CollectionMetaOperations>),
         AddPeer {
             peer_id: PeerId,
             uri: String,
         },
         RemovePeer(PeerId),
+        /// Change the advertised address of an already known peer,
+        /// e.g. after an IP change or DNS migration
+        UpdatePeerAddress {
+            peer_id: PeerId,
+            uri: String,
+        },
         RequestSnapshot,
         ReportSnapshot {
             peer_id: PeerId,
@@ -108,6 +120,8 @@ pub mod consensus_ops {
                     params: None,
                     hnsw_config: None,
                     quantization_config: None,
+                    add_vectors: None,
+                    drop_vectors: None,
                 },
             );
             operation
@@ -148,6 +162,10 @@ pub mod consensus_ops {
             )))
         }
 
+        pub fn update_peer_address(peer_id: PeerId, uri: String) -> Self {
+            Self::UpdatePeerAddress { peer_id, uri }
+        }
+
         pub fn request_snapshot() -> Self {
             Self::RequestSnapshot
         }
@@ -199,5 +217,7 @@ pub trait CollectionContainer {
 
     fn remove_peer(&self, peer_id: PeerId) -> Result<(), StorageError>;
 
+    fn update_peer_address(&self, peer_id: PeerId, uri: Uri) -> Result<(), StorageError>;
+
     fn sync_local_state(&self) -> Result<(), StorageError>;
 }
@@ -0,0 +1,257 @@
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::future::Future;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::time::Instant;
+
+use chrono::{DateTime, Utc};
+use collection::common::query_trace::{collect_shard_traces, ShardTrace};
+use parking_lot::Mutex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::content_manager::errors::StorageError;
+use crate::types::{SlowQueryLogConfig, SlowQueryOperation};
+
+/// Read request, which took longer than the threshold of its operation
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct SlowQuery {
+    pub time: DateTime<Utc>,
+    pub collection: String,
+    pub operation: SlowQueryOperation,
+    /// Duration of the whole request, in seconds
+    pub duration_secs: f64,
+    /// Request as it was received, including filter and search params
+    pub request: serde_json::Value,
+    /// Responses of the shards, requested by this peer
+    pub shards: Vec<ShardTrace>,
+    /// If a search in any local shard fell back to a full scan instead of using the index
+    pub full_scan: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Latest slow queries in memory, also written into a rotated file if configured
+pub struct SlowQueryLog {
+    config: SlowQueryLogConfig,
+    entries: Mutex<VecDeque<SlowQuery>>,
+    file: Option<Mutex<RotatingFile>>,
+}
+
+impl SlowQueryLog {
+    pub fn new(config: SlowQueryLogConfig) -> Self {
+        let file = config.path.as_ref().map(|path| {
+            Mutex::new(RotatingFile {
+                path: PathBuf::from(path),
+                file: None,
+                max_size: config.max_file_size_mb * 1024 * 1024,
+                max_files: config.max_files,
+            })
+        });
+        Self {
+            entries: Mutex::new(VecDeque::with_capacity(config.max_entries)),
+            file,
+            config,
+        }
+    }
+
+    /// Execute the request, logging it if it takes longer than the threshold of the operation
+    pub async fn observe<R, T, F>(
+        &self,
+        collection_name: &str,
+        operation: SlowQueryOperation,
+        request: R,
+        query: impl FnOnce(R) -> F,
+    ) -> Result<T, StorageError>
+    where
+        R: Clone + Serialize,
+        F: Future<Output = Result<T, StorageError>>,
+    {
+        // Request is consumed by the query, keep a copy in case it has to be logged
+        let logged_request = request.clone();
+        let start = Instant::now();
+        let (result, shards) = collect_shard_traces(query(request)).await;
+        let duration = start.elapsed();
+
+        if duration >= self.config.threshold(operation) {
+            let entry = SlowQuery {
+                time: Utc::now(),
+                collection: collection_name.to_string(),
+                operation,
+                duration_secs: duration.as_secs_f64(),
+                request: serde_json::to_value(logged_request).unwrap_or_default(),
+                full_scan: shards.iter().any(|shard| shard.full_scan),
+                shards,
+                error: result.as_ref().err().map(|err| err.to_string()),
+            };
+            self.add(entry);
+        }
+
+        result
+    }
+
+    fn add(&self, entry: SlowQuery) {
+        if let Some(file) = &self.file {
+            if let Err(err) = file.lock().write(&entry) {
+                log::warn!("Failed to write slow query log: {err}");
+            }
+        }
+
+        let mut entries = self.entries.lock();
+        if entries.len() >= self.config.max_entries {
+            entries.pop_front();
+        }
+        if self.config.max_entries > 0 {
+            entries.push_back(entry);
+        }
+    }
+
+    /// Latest slow queries, the most recent first
+    pub fn entries(&self) -> Vec<SlowQuery> {
+        self.entries.lock().iter().rev().cloned().collect()
+    }
+}
+
+/// File of JSON lines, which is moved to `<path>.1`, `<path>.2`, ... when it grows too large
+struct RotatingFile {
+    path: PathBuf,
+    file: Option<File>,
+    max_size: u64,
+    max_files: usize,
+}
+
+impl RotatingFile {
+    fn write(&mut self, entry: &SlowQuery) -> io::Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let size = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => 0,
+            Err(err) => return Err(err),
+        };
+        if size > 0 && size + line.len() as u64 > self.max_size {
+            self.rotate()?;
+        }
+
+        let file = match &mut self.file {
+            Some(file) => file,
+            file @ None => file.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            ),
+        };
+        file.write_all(&line)
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file = None;
+        if self.max_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+            if from.exists() {
+                fs::rename(from, self.rotated_path(index + 1))?;
+            }
+        }
+        fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    fn rotated_path(&self, index: usize) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(format!(".{index}"));
+        PathBuf::from(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::executor::block_on;
+    use tempfile::Builder;
+
+    use super::*;
+
+    fn config(path: Option<String>) -> SlowQueryLogConfig {
+        SlowQueryLogConfig {
+            threshold_ms: 1000,
+            operation_threshold_ms: HashMap::from([(SlowQueryOperation::Count, 0)]),
+            max_entries: 2,
+            path,
+            max_file_size_mb: 1,
+            max_files: 2,
+        }
+    }
+
+    #[test]
+    fn test_slow_query_log() {
+        let dir = Builder::new().prefix("slow_queries").tempdir().unwrap();
+        let path = dir.path().join("slow.log");
+        let log = SlowQueryLog::new(config(Some(path.to_string_lossy().into_owned())));
+
+        // Fast searches are not logged, all counts are
+        for operation in [SlowQueryOperation::Search, SlowQueryOperation::Count] {
+            let result = block_on(log.observe(
+                "test",
+                operation,
+                "request",
+                |request| async move { Ok::<_, StorageError>(request.len()) },
+            ));
+            assert_eq!(result.unwrap(), 7);
+        }
+
+        let entries = log.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].operation, SlowQueryOperation::Count);
+        assert_eq!(entries[0].request, serde_json::json!("request"));
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+
+        // Only the latest entries are kept in memory
+        for _ in 0..3 {
+            block_on(
+                log.observe("test", SlowQueryOperation::Count, (), |_| async {
+                    Err::<(), _>(StorageError::bad_input("invalid"))
+                }),
+            )
+            .unwrap_err();
+        }
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert!(entries[0].error.is_some());
+    }
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = Builder::new().prefix("slow_queries").tempdir().unwrap();
+        let path = dir.path().join("slow.log");
+        let mut file = RotatingFile {
+            path: path.clone(),
+            file: None,
+            max_size: 1,
+            max_files: 2,
+        };
+        let entry = SlowQuery {
+            time: Utc::now(),
+            collection: "test".to_string(),
+            operation: SlowQueryOperation::Search,
+            duration_secs: 1.0,
+            request: serde_json::Value::Null,
+            shards: vec![],
+            full_scan: false,
+            error: None,
+        };
+
+        for _ in 0..4 {
+            file.write(&entry).unwrap();
+        }
+        assert!(path.exists());
+        assert!(file.rotated_path(1).exists());
+        assert!(file.rotated_path(2).exists());
+        assert!(!file.rotated_path(3).exists());
+    }
+}
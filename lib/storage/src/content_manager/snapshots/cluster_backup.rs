@@ -0,0 +1,263 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use collection::config::CollectionConfig;
+use collection::shards::replica_set::ReplicaState;
+use collection::shards::shard::{PeerId, ShardId};
+use futures::future::try_join_all;
+use io::file_operations::{atomic_save_json, read_json};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::dispatcher::Dispatcher;
+use crate::{StorageError, TableOfContent};
+
+const CLUSTER_BACKUP_PREFIX: &str = "cluster-backup-";
+const CLUSTER_BACKUP_EXTENSION: &str = "json";
+/// Time for all peers to reach the consensus commit of the backup
+const CONSENSUS_SYNC_TIMEOUT: Duration = Duration::from_secs(60);
+const SHARD_SNAPSHOT_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+/// Snapshot of a shard, created by a cluster backup
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct ShardBackup {
+    /// Peer, which stores the shard snapshot
+    pub peer_id: PeerId,
+    /// Name of the shard snapshot on the peer
+    pub snapshot: String,
+    /// SHA256 checksum of the shard snapshot
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct CollectionBackup {
+    pub config: CollectionConfig,
+    /// Placement of the shard replicas at the time of the backup
+    pub replicas: BTreeMap<ShardId, HashMap<PeerId, ReplicaState>>,
+    /// Snapshot of each shard, taken from one of its active replicas
+    pub shards: BTreeMap<ShardId, ShardBackup>,
+}
+
+/// Manifest of a cluster backup, from which all collections of the cluster can be rebuilt.
+///
+/// Every peer has applied the same consensus commit, when the backup is taken,
+/// so collection configs and shard placement are consistent across the shard snapshots.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct ClusterBackup {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    /// Consensus commit, applied by all peers during the backup
+    pub commit: u64,
+    /// Consensus term of the commit
+    pub term: u64,
+    /// Internal gRPC URIs of the peers
+    pub peers: BTreeMap<PeerId, String>,
+    /// Alias name to collection name
+    pub aliases: BTreeMap<String, String>,
+    pub collections: BTreeMap<String, CollectionBackup>,
+}
+
+fn cluster_backup_path(toc: &TableOfContent, name: &str) -> Result<PathBuf, StorageError> {
+    let is_backup_name = name.starts_with(CLUSTER_BACKUP_PREFIX)
+        && Path::new(name).file_name() == Some(name.as_ref())
+        && Path::new(name).extension() == Some(CLUSTER_BACKUP_EXTENSION.as_ref());
+    let path = Path::new(toc.snapshots_path()).join(name);
+    if !is_backup_name || !path.exists() {
+        return Err(StorageError::NotFound {
+            description: format!("Cluster backup {name} not found"),
+        });
+    }
+    Ok(path)
+}
+
+pub async fn do_list_cluster_backups(toc: &TableOfContent) -> Result<Vec<String>, StorageError> {
+    let mut entries = tokio::fs::read_dir(toc.snapshots_path()).await?;
+    let mut backups = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if path.is_file()
+            && name.starts_with(CLUSTER_BACKUP_PREFIX)
+            && path.extension() == Some(CLUSTER_BACKUP_EXTENSION.as_ref())
+        {
+            backups.push(name.to_string());
+        }
+    }
+    backups.sort();
+    Ok(backups)
+}
+
+pub async fn do_get_cluster_backup(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<ClusterBackup, StorageError> {
+    let path = cluster_backup_path(toc, name)?;
+    Ok(read_json(&path)?)
+}
+
+pub async fn do_create_cluster_backup(
+    dispatcher: &Dispatcher,
+    wait: bool,
+) -> Result<Option<ClusterBackup>, StorageError> {
+    let dispatcher = dispatcher.clone();
+    let task = tokio::spawn(async move { _do_create_cluster_backup(&dispatcher).await });
+    if wait {
+        Ok(Some(task.await??))
+    } else {
+        Ok(None)
+    }
+}
+
+async fn _do_create_cluster_backup(dispatcher: &Dispatcher) -> Result<ClusterBackup, StorageError> {
+    let Some(consensus_state) = dispatcher.consensus_state() else {
+        return Err(StorageError::bad_request(
+            "Cluster backup requires distributed mode, use full snapshot instead",
+        ));
+    };
+    let toc = dispatcher.toc();
+
+    let hard_state = consensus_state.hard_state();
+    let (commit, term) = (hard_state.commit, hard_state.term);
+    toc.await_commit_on_all_peers(commit, term, CONSENSUS_SYNC_TIMEOUT)
+        .await?;
+
+    let created_at = Utc::now();
+    let name = format!(
+        "{CLUSTER_BACKUP_PREFIX}{}.{CLUSTER_BACKUP_EXTENSION}",
+        created_at.format("%Y-%m-%d-%H-%M-%S")
+    );
+    log::info!("Creating cluster backup {name} at consensus commit {commit}");
+
+    let mut collections = BTreeMap::new();
+    let mut aliases = BTreeMap::new();
+    let mut snapshot_requests = Vec::new();
+    for collection_name in toc.all_collections().await {
+        let state = toc.get_collection(&collection_name).await?.state().await;
+        for alias in toc.collection_aliases(&collection_name).await? {
+            aliases.insert(alias, collection_name.clone());
+        }
+
+        let replicas: BTreeMap<_, _> = state
+            .shards
+            .into_iter()
+            .map(|(shard_id, shard_info)| (shard_id, shard_info.replicas))
+            .collect();
+        for (shard_id, shard_replicas) in &replicas {
+            let peer_id =
+                select_backup_replica(toc.this_peer_id, shard_replicas).ok_or_else(|| {
+                    StorageError::service_error(format!(
+                        "Shard {shard_id} of collection {collection_name} has no active replica"
+                    ))
+                })?;
+            snapshot_requests.push((collection_name.clone(), *shard_id, peer_id));
+        }
+
+        collections.insert(
+            collection_name,
+            CollectionBackup {
+                config: state.config,
+                replicas,
+                shards: BTreeMap::new(),
+            },
+        );
+    }
+
+    let snapshots = try_join_all(snapshot_requests.into_iter().map(
+        |(collection_name, shard_id, peer_id)| async move {
+            let (snapshot, checksum) = if peer_id == toc.this_peer_id {
+                let snapshot = toc
+                    .create_shard_snapshot(&collection_name, shard_id)
+                    .await?;
+                (snapshot.name, snapshot.checksum)
+            } else {
+                let snapshot = toc
+                    .create_remote_shard_snapshot(
+                        peer_id,
+                        &collection_name,
+                        shard_id,
+                        SHARD_SNAPSHOT_TIMEOUT,
+                    )
+                    .await?;
+                (snapshot.name, snapshot.checksum)
+            };
+            let shard_backup = ShardBackup {
+                peer_id,
+                snapshot,
+                checksum,
+            };
+            Ok::<_, StorageError>((collection_name, shard_id, shard_backup))
+        },
+    ))
+    .await?;
+
+    // Collections or shard placement, changed during the backup, may not match the snapshots
+    let hard_state = consensus_state.hard_state();
+    if hard_state.commit != commit || hard_state.term != term {
+        return Err(StorageError::service_error(format!(
+            "Cluster state changed during backup {name} (consensus commit {commit} -> {}), shard snapshots are kept on the peers, but the backup is not saved",
+            hard_state.commit
+        )));
+    }
+
+    for (collection_name, shard_id, shard_backup) in snapshots {
+        if let Some(collection) = collections.get_mut(&collection_name) {
+            collection.shards.insert(shard_id, shard_backup);
+        }
+    }
+
+    let backup = ClusterBackup {
+        name,
+        created_at,
+        commit,
+        term,
+        peers: consensus_state
+            .peer_address_by_id()
+            .into_iter()
+            .map(|(peer_id, uri)| (peer_id, uri.to_string()))
+            .collect(),
+        aliases,
+        collections,
+    };
+    atomic_save_json(&Path::new(toc.snapshots_path()).join(&backup.name), &backup)?;
+    log::info!("Cluster backup {} completed", backup.name);
+
+    Ok(backup)
+}
+
+/// Active replica of the shard to snapshot, the local one is preferred
+fn select_backup_replica(
+    this_peer_id: PeerId,
+    replicas: &HashMap<PeerId, ReplicaState>,
+) -> Option<PeerId> {
+    let is_active = |peer_id: &PeerId| replicas.get(peer_id) == Some(&ReplicaState::Active);
+    if is_active(&this_peer_id) {
+        return Some(this_peer_id);
+    }
+    replicas.keys().copied().filter(is_active).min()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_select_backup_replica() {
+        let replicas = HashMap::from([
+            (1, ReplicaState::Dead),
+            (2, ReplicaState::Active),
+            (3, ReplicaState::Active),
+            (4, ReplicaState::Partial),
+        ]);
+        assert_eq!(select_backup_replica(3, &replicas), Some(3));
+        assert_eq!(select_backup_replica(1, &replicas), Some(2));
+        assert_eq!(select_backup_replica(4, &replicas), Some(2));
+
+        let replicas = HashMap::from([(1, ReplicaState::Dead)]);
+        assert_eq!(select_backup_replica(1, &replicas), None);
+    }
+}
@@ -1,12 +1,18 @@
+use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use futures::StreamExt;
 use reqwest;
+use reqwest::header::{ACCEPT_ENCODING, RANGE};
+use reqwest::StatusCode;
 use tokio::fs::File;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use url::Url;
 use uuid::Uuid;
 
+use crate::content_manager::snapshots::s3;
+use crate::types::S3Config;
 use crate::StorageError;
 
 fn random_name() -> String {
@@ -22,24 +28,75 @@ fn snapshot_name(url: &Url) -> String {
         .unwrap_or_else(random_name)
 }
 
+/// How many times an interrupted download is resumed
+const DOWNLOAD_RETRIES: usize = 10;
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+/// Download the file, resuming it with range requests if the connection breaks.
+///
+/// If the server doesn't support ranges, the download starts over.
 async fn download_file(url: &Url, path: &Path) -> Result<(), StorageError> {
+    let client = reqwest::Client::new();
     let mut file = File::create(path).await?;
+    let mut downloaded: u64 = 0;
+    let mut retries = 0;
 
-    let response = reqwest::get(url.clone()).await?;
+    loop {
+        let mut request = client.get(url.clone());
+        if downloaded > 0 {
+            // Range of a compressed response would not match the file offset
+            request = request
+                .header(RANGE, format!("bytes={downloaded}-"))
+                .header(ACCEPT_ENCODING, "identity");
+        }
 
-    if !response.status().is_success() {
-        return Err(StorageError::bad_input(format!(
-            "Failed to download snapshot from {}: status - {}",
-            url,
-            response.status()
-        )));
-    }
+        let interrupted = match request.send().await {
+            Ok(response) => {
+                if !response.status().is_success() {
+                    return Err(StorageError::bad_input(format!(
+                        "Failed to download snapshot from {}: status - {}",
+                        url,
+                        response.status()
+                    )));
+                }
 
-    let mut stream = response.bytes_stream();
+                if downloaded > 0 && response.status() != StatusCode::PARTIAL_CONTENT {
+                    log::warn!("Server of {url} doesn't support ranges, downloading from start");
+                    file.set_len(0).await?;
+                    file.seek(SeekFrom::Start(0)).await?;
+                    downloaded = 0;
+                }
 
-    while let Some(chunk_result) = stream.next().await {
-        let chunk = chunk_result?;
-        file.write_all(&chunk).await?;
+                let mut stream = response.bytes_stream();
+                let mut interrupted = None;
+                while let Some(chunk_result) = stream.next().await {
+                    match chunk_result {
+                        Ok(chunk) => {
+                            file.write_all(&chunk).await?;
+                            downloaded += chunk.len() as u64;
+                        }
+                        Err(err) => {
+                            interrupted = Some(err);
+                            break;
+                        }
+                    }
+                }
+                interrupted
+            }
+            Err(err) => Some(err),
+        };
+
+        match interrupted {
+            None => break,
+            Some(err) if retries < DOWNLOAD_RETRIES => {
+                retries += 1;
+                log::warn!(
+                    "Download of snapshot {url} interrupted after {downloaded} bytes, resuming ({retries}/{DOWNLOAD_RETRIES}): {err}"
+                );
+                tokio::time::sleep(DOWNLOAD_RETRY_DELAY).await;
+            }
+            Some(err) => return Err(err.into()),
+        }
     }
 
     file.flush().await?;
@@ -47,7 +104,14 @@ async fn download_file(url: &Url, path: &Path) -> Result<(), StorageError> {
     Ok(())
 }
 
-pub async fn download_snapshot(url: Url, snapshots_dir: &Path) -> Result<PathBuf, StorageError> {
+/// Download the snapshot into the directory, unless it is a local file.
+///
+/// `s3://<bucket>/<key>` URLs are read with the S3 config of the node, if any.
+pub async fn download_snapshot(
+    url: Url,
+    snapshots_dir: &Path,
+    s3_config: Option<&S3Config>,
+) -> Result<PathBuf, StorageError> {
     match url.scheme() {
         "file" => {
             let local_path = url.to_file_path().map_err(|_| {
@@ -68,6 +132,12 @@ pub async fn download_snapshot(url: Url, snapshots_dir: &Path) -> Result<PathBuf
             download_file(&url, &download_to).await?;
             Ok(download_to)
         }
+        "s3" => {
+            let download_to = snapshots_dir.join(snapshot_name(&url));
+
+            s3::download_snapshot(s3_config, &url, &download_to).await?;
+            Ok(download_to)
+        }
         _ => Err(StorageError::bad_request(format!(
             "URL {} with schema {} is not supported",
             url,
@@ -75,3 +145,123 @@ pub async fn download_snapshot(url: Url, snapshots_dir: &Path) -> Result<PathBuf
         ))),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parking_lot::Mutex;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// Serve `content` over HTTP, the first response is cut in the middle of the body.
+    ///
+    /// Returns the URL of the file and the offsets of the range requests, `None` for full ones.
+    async fn serve_interrupted(
+        content: Vec<u8>,
+        support_ranges: bool,
+    ) -> (Url, Arc<Mutex<Vec<Option<u64>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!(
+            "http://{}/test.snapshot",
+            listener.local_addr().unwrap()
+        ))
+        .unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let served_requests = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let (mut stream, _) = listener.accept().await.unwrap();
+
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let read = stream.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..read]);
+                }
+                let request = String::from_utf8(request).unwrap();
+                let range = request.lines().find_map(|line| {
+                    let (name, value) = line.split_once(':')?;
+                    name.eq_ignore_ascii_case("range").then(|| {
+                        let value = value.trim().strip_prefix("bytes=").unwrap();
+                        value.trim_end_matches('-').parse::<u64>().unwrap()
+                    })
+                });
+
+                let is_first = {
+                    let mut requests = served_requests.lock();
+                    requests.push(range);
+                    requests.len() == 1
+                };
+
+                let offset = range.filter(|_| support_ranges).unwrap_or(0) as usize;
+                let body = &content[offset..];
+                let head = if offset > 0 {
+                    format!(
+                        "HTTP/1.1 206 Partial Content\r\nContent-Length: {}\r\nContent-Range: bytes {}-{}/{}\r\nConnection: close\r\n\r\n",
+                        body.len(),
+                        offset,
+                        content.len() - 1,
+                        content.len(),
+                    )
+                } else {
+                    format!(
+                        "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                        body.len(),
+                    )
+                };
+                stream.write_all(head.as_bytes()).await.unwrap();
+
+                // Connection breaks before the whole body is sent
+                let sent = if is_first { body.len() / 3 } else { body.len() };
+                stream.write_all(&body[..sent]).await.unwrap();
+                stream.flush().await.unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn test_content() -> Vec<u8> {
+        (0..100_000).map(|i| (i % 251) as u8).collect()
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_with_range_request() {
+        let dir = tempfile::Builder::new()
+            .prefix("download")
+            .tempdir()
+            .unwrap();
+        let content = test_content();
+        let (url, requests) = serve_interrupted(content.clone(), true).await;
+
+        let path = dir.path().join("test.snapshot");
+        download_file(&url, &path).await.unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), content);
+
+        // Download continues from the bytes received before the connection broke
+        let requests = requests.lock().clone();
+        assert_eq!(requests, vec![None, Some(content.len() as u64 / 3)]);
+    }
+
+    #[tokio::test]
+    async fn test_download_starts_over_without_range_support() {
+        let dir = tempfile::Builder::new()
+            .prefix("download")
+            .tempdir()
+            .unwrap();
+        let content = test_content();
+        let (url, requests) = serve_interrupted(content.clone(), false).await;
+
+        let path = dir.path().join("test.snapshot");
+        download_file(&url, &path).await.unwrap();
+
+        // Full response to the range request replaces the partially downloaded file
+        assert_eq!(std::fs::read(&path).unwrap(), content);
+        assert_eq!(requests.lock().len(), 2);
+    }
+}
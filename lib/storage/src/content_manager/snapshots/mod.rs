@@ -1,11 +1,15 @@
+pub mod cluster_backup;
 pub mod download;
 pub mod recover;
+pub mod s3;
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 
 use collection::operations::snapshot_ops::{
-    get_snapshot_description, list_snapshots_in_directory, SnapshotDescription,
+    create_snapshot_checksum, get_checksum_path, get_metadata_path, get_snapshot_description,
+    list_snapshots_in_directory, remove_snapshot, save_snapshot_metadata, SnapshotDescription,
+    SnapshotMetadata,
 };
 use serde::{Deserialize, Serialize};
 use tar::Builder as TarBuilder;
@@ -60,7 +64,7 @@ async fn _do_delete_full_snapshot(
 ) -> Result<bool, StorageError> {
     let snapshot_dir = get_full_snapshot_path(dispatcher.toc(), snapshot_name).await?;
     log::info!("Deleting full storage snapshot {:?}", snapshot_dir);
-    tokio::fs::remove_file(snapshot_dir).await?;
+    remove_snapshot(&snapshot_dir).await?;
     Ok(true)
 }
 
@@ -93,7 +97,7 @@ async fn _do_delete_collection_snapshot(
     let collection = dispatcher.get_collection(collection_name).await?;
     let file_name = collection.get_snapshot_path(snapshot_name).await?;
     log::info!("Deleting collection snapshot {:?}", file_name);
-    tokio::fs::remove_file(file_name).await?;
+    remove_snapshot(&file_name).await?;
     Ok(true)
 }
 
@@ -120,6 +124,7 @@ pub async fn do_create_full_snapshot(
 async fn _do_create_full_snapshot(
     dispatcher: &Dispatcher,
 ) -> Result<SnapshotDescription, StorageError> {
+    let started = std::time::Instant::now();
     let dispatcher = dispatcher.clone();
 
     let snapshot_dir = Path::new(dispatcher.snapshots_path()).to_path_buf();
@@ -127,7 +132,7 @@ async fn _do_create_full_snapshot(
     let all_collections = dispatcher.all_collections().await;
     let mut created_snapshots: Vec<(&str, SnapshotDescription)> = vec![];
     for collection_name in &all_collections {
-        let snapshot_details = dispatcher.create_snapshot(collection_name).await?;
+        let snapshot_details = dispatcher.create_snapshot(collection_name, None).await?;
         created_snapshots.push((collection_name, snapshot_details));
     }
     let current_time = chrono::Utc::now().format("%Y-%m-%d-%H-%M-%S").to_string();
@@ -182,7 +187,9 @@ async fn _do_create_full_snapshot(
                 .join(collection_name)
                 .join(&snapshot_details.name);
             builder.append_path_with_name(&snapshot_path, &snapshot_details.name)?;
-            std::fs::remove_file(snapshot_path)?;
+            std::fs::remove_file(&snapshot_path)?;
+            std::fs::remove_file(get_checksum_path(&snapshot_path))?;
+            std::fs::remove_file(get_metadata_path(&snapshot_path))?;
         }
         builder.append_path_with_name(&config_path_clone, "config.json")?;
 
@@ -191,6 +198,56 @@ async fn _do_create_full_snapshot(
     });
     archiving.await??;
     tokio::fs::remove_file(&config_path).await?;
+    create_snapshot_checksum(&full_snapshot_path).await?;
+    save_snapshot_metadata(&full_snapshot_path, &SnapshotMetadata::new(started, None)).await?;
+
+    upload_snapshot_to_s3(dispatcher.toc(), None, &full_snapshot_path).await?;
 
     Ok(get_snapshot_description(&full_snapshot_path).await?)
 }
+
+/// Upload the created snapshot to S3, if the node is configured to.
+///
+/// Snapshots of collections are stored under `<prefix>/<collection name>/`, full snapshots under `<prefix>/`.
+pub async fn upload_snapshot_to_s3(
+    toc: &TableOfContent,
+    collection_name: Option<&str>,
+    snapshot_path: &Path,
+) -> Result<(), StorageError> {
+    let Some(config) = toc.snapshots_s3_config() else {
+        return Ok(());
+    };
+    let file_name = snapshot_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| {
+            StorageError::service_error(format!(
+                "Invalid snapshot path {}",
+                snapshot_path.display()
+            ))
+        })?;
+    let key = s3::snapshot_key(config, collection_name, file_name);
+
+    log::info!(
+        "Uploading snapshot {file_name} to S3 bucket {} as {key}",
+        config.bucket
+    );
+    s3::upload_snapshot(config, snapshot_path, &key).await
+}
+
+/// Delete the collection snapshot from S3, if the node is configured to store snapshots there
+pub async fn delete_snapshot_from_s3(
+    toc: &TableOfContent,
+    collection_name: &str,
+    snapshot_name: &str,
+) -> Result<(), StorageError> {
+    let Some(config) = toc.snapshots_s3_config() else {
+        return Ok(());
+    };
+    let key = s3::snapshot_key(config, Some(collection_name), snapshot_name);
+    log::info!(
+        "Deleting snapshot {snapshot_name} from S3 bucket {} at {key}",
+        config.bucket
+    );
+    s3::delete_snapshot(config, &key).await
+}
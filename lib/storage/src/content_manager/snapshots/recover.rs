@@ -1,16 +1,23 @@
-use collection::collection::Collection;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use collection::collection::{Collection, PointInTimeOperations};
 use collection::config::CollectionConfig;
-use collection::operations::snapshot_ops::{SnapshotPriority, SnapshotRecover};
+use collection::operations::snapshot_ops::{
+    verify_snapshot_checksum, PointInTimeRecover, SnapshotPriority, SnapshotRecover,
+};
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::shards::shard_config::ShardType;
 use collection::shards::shard_versioning::latest_shard_paths;
+use url::Url;
 
 use crate::content_manager::collection_meta_ops::{
     CollectionMetaOperations, CreateCollectionOperation,
 };
 use crate::content_manager::snapshots::download::download_snapshot;
 use crate::dispatcher::Dispatcher;
+use crate::types::S3Config;
 use crate::{StorageError, TableOfContent};
 
 pub async fn activate_shard(
@@ -53,10 +60,48 @@ pub async fn do_recover_from_snapshot(
 ) -> Result<bool, StorageError> {
     let dispatch = dispatcher.clone();
     let collection_name = collection_name.to_string();
-    let recovery =
-        tokio::spawn(
-            async move { _do_recover_from_snapshot(dispatch, &collection_name, source).await },
-        );
+    let recovery = tokio::spawn(async move {
+        _do_recover_from_snapshot(dispatch, &collection_name, source, Default::default()).await
+    });
+    if wait {
+        Ok(recovery.await??)
+    } else {
+        Ok(true)
+    }
+}
+
+/// Recover the collection from its snapshot on this peer, and replay the operations,
+/// which followed the snapshot up to the requested point in time, from the retained WAL.
+pub async fn do_recover_to_point_in_time(
+    dispatcher: &Dispatcher,
+    collection_name: &str,
+    request: PointInTimeRecover,
+    wait: bool,
+) -> Result<bool, StorageError> {
+    let dispatch = dispatcher.clone();
+    let collection_name = collection_name.to_string();
+    let recovery = tokio::spawn(async move {
+        let toc = dispatch.toc();
+        let (snapshot_path, operations) = {
+            let collection = toc.get_collection(&collection_name).await?;
+            let snapshot_path = collection.get_snapshot_path(&request.snapshot).await?;
+            // WAL must be read before the recovery replaces it
+            let operations = collection.point_in_time_operations(&request).await?;
+            (snapshot_path, operations)
+        };
+        let location = Url::from_file_path(&snapshot_path).map_err(|_| {
+            StorageError::service_error(format!(
+                "Invalid snapshot path {}",
+                snapshot_path.display()
+            ))
+        })?;
+        let source = SnapshotRecover {
+            location,
+            priority: Some(SnapshotPriority::Snapshot),
+            checksum: None,
+        };
+        _do_recover_from_snapshot(dispatch, &collection_name, source, operations).await
+    });
     if wait {
         Ok(recovery.await??)
     } else {
@@ -64,12 +109,68 @@ pub async fn do_recover_from_snapshot(
     }
 }
 
+/// Make the chain of base snapshots of an incremental snapshot available for its restore.
+///
+/// Each base is looked up in `base_dirs` first. If it is not there and the snapshot was
+/// downloaded, the base is downloaded from the same location into `download_dir`.
+async fn fetch_base_snapshots(
+    location: &Url,
+    snapshot_path: &Path,
+    base_dirs: &[PathBuf],
+    download_dir: &Path,
+    s3_config: Option<&S3Config>,
+) -> Result<(), StorageError> {
+    let mut visited = HashSet::new();
+    let mut current = snapshot_path.to_path_buf();
+    loop {
+        let path = current.clone();
+        let base = tokio::task::spawn_blocking(move || Collection::snapshot_base(&path)).await??;
+        let Some(base) = base else {
+            return Ok(());
+        };
+        if !visited.insert(base.clone()) {
+            return Err(StorageError::bad_input(format!(
+                "Base snapshots of {} form a cycle at {base}",
+                snapshot_path.display()
+            )));
+        }
+
+        let local_base = base_dirs
+            .iter()
+            .map(|dir| dir.join(&base))
+            .find(|path| path.is_file());
+        current = match local_base {
+            Some(base_path) => base_path,
+            None if location.scheme() != "file" => {
+                let base_location = location.join(&base).map_err(|err| {
+                    StorageError::bad_input(format!(
+                        "Invalid location of base snapshot {base}: {err}"
+                    ))
+                })?;
+                log::debug!("Downloading base snapshot from {base_location}");
+                download_snapshot(base_location, download_dir, s3_config).await?
+            }
+            None => {
+                return Err(StorageError::bad_input(format!(
+                    "Base snapshot {base} of {} is not found in {base_dirs:?}",
+                    snapshot_path.display()
+                )))
+            }
+        };
+    }
+}
+
 async fn _do_recover_from_snapshot(
     dispatcher: Dispatcher,
     collection_name: &str,
     source: SnapshotRecover,
+    mut replay_operations: PointInTimeOperations,
 ) -> Result<bool, StorageError> {
-    let SnapshotRecover { location, priority } = source;
+    let SnapshotRecover {
+        location,
+        priority,
+        checksum,
+    } = source;
     let toc = dispatcher.toc();
 
     let this_peer_id = toc.this_peer_id;
@@ -84,10 +185,40 @@ async fn _do_recover_from_snapshot(
         download_dir.path().display()
     );
 
-    let snapshot_path = download_snapshot(location, download_dir.path()).await?;
+    let snapshot_path = download_snapshot(
+        location.clone(),
+        download_dir.path(),
+        toc.snapshots_s3_config(),
+    )
+    .await?;
 
     log::debug!("Snapshot downloaded to {}", snapshot_path.display());
 
+    if let Some(checksum) = checksum {
+        log::debug!("Verifying checksum of snapshot {}", snapshot_path.display());
+        verify_snapshot_checksum(&snapshot_path, &checksum).await?;
+    }
+
+    // Bases of an incremental snapshot are placed next to it, downloaded with it,
+    // or are among the snapshots of the collection on this peer
+    let base_dirs: Vec<PathBuf> = snapshot_path
+        .parent()
+        .map(Path::to_path_buf)
+        .into_iter()
+        .chain([
+            download_dir.path().to_path_buf(),
+            toc.snapshots_path_for_collection(collection_name),
+        ])
+        .collect();
+    fetch_base_snapshots(
+        &location,
+        &snapshot_path,
+        &base_dirs,
+        download_dir.path(),
+        toc.snapshots_s3_config(),
+    )
+    .await?;
+
     let temp_storage_path = toc.optional_temp_or_storage_temp_path()?;
 
     let tmp_collection_dir = tempfile::Builder::new()
@@ -108,8 +239,9 @@ async fn _do_recover_from_snapshot(
     let tmp_collection_dir_clone = tmp_collection_dir.path().to_path_buf();
     let restoring = tokio::task::spawn_blocking(move || {
         // Unpack snapshot collection to the target folder
-        Collection::restore_snapshot(
+        Collection::restore_snapshot_with_bases(
             &snapshot_path,
+            &base_dirs,
             &tmp_collection_dir_clone,
             this_peer_id,
             is_distributed,
@@ -207,6 +339,17 @@ async fn _do_recover_from_snapshot(
                 continue;
             }
 
+            if let Some(operations) = replay_operations.remove(shard_id) {
+                log::debug!(
+                    "Replaying {} operations on shard {} after the snapshot",
+                    operations.len(),
+                    shard_id
+                );
+                collection
+                    .replay_local_operations(*shard_id, operations)
+                    .await?;
+            }
+
             // If this is the only replica, we can activate it
             // If not - de-sync is possible, so we need to run synchronization
             let other_active_replicas: Vec<_> = shard_info
@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use futures::StreamExt;
+use object_store::aws::{AmazonS3, AmazonS3Builder};
+use object_store::path::Path as ObjectPath;
+use object_store::ObjectStore;
+use tokio::fs::File;
+use tokio::io::AsyncWriteExt;
+use url::Url;
+
+use crate::types::S3Config;
+use crate::StorageError;
+
+fn s3_error(err: object_store::Error) -> StorageError {
+    StorageError::service_error(format!("S3 snapshot storage error: {err}"))
+}
+
+/// Client of the bucket, credentials and region not set in the config are read from
+/// the standard `AWS_*` environment variables
+fn object_store(config: Option<&S3Config>, bucket: &str) -> Result<AmazonS3, StorageError> {
+    let mut builder = AmazonS3Builder::from_env().with_bucket_name(bucket);
+    if let Some(config) = config {
+        if let Some(region) = &config.region {
+            builder = builder.with_region(region);
+        }
+        if let Some(endpoint_url) = &config.endpoint_url {
+            builder = builder
+                .with_endpoint(endpoint_url)
+                .with_allow_http(endpoint_url.starts_with("http://"));
+        }
+        if let Some(access_key) = &config.access_key {
+            builder = builder.with_access_key_id(access_key);
+        }
+        if let Some(secret_key) = &config.secret_key {
+            builder = builder.with_secret_access_key(secret_key);
+        }
+    }
+    builder.build().map_err(s3_error)
+}
+
+/// Key of the snapshot in the configured bucket
+pub fn snapshot_key(config: &S3Config, collection_name: Option<&str>, file_name: &str) -> String {
+    let prefix = config
+        .prefix
+        .as_deref()
+        .unwrap_or_default()
+        .trim_matches('/');
+    [prefix, collection_name.unwrap_or_default(), file_name]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Upload the snapshot file to the configured bucket, in parts
+pub async fn upload_snapshot(
+    config: &S3Config,
+    snapshot_path: &Path,
+    key: &str,
+) -> Result<(), StorageError> {
+    let store = object_store(Some(config), &config.bucket)?;
+    let location = ObjectPath::from(key);
+
+    let (multipart_id, mut writer) = store.put_multipart(&location).await.map_err(s3_error)?;
+    let mut file = File::open(snapshot_path).await?;
+    let uploaded = async {
+        tokio::io::copy(&mut file, &mut writer).await?;
+        writer.shutdown().await
+    }
+    .await;
+
+    if let Err(err) = uploaded {
+        // Don't leave the uploaded parts behind
+        if let Err(abort_err) = store.abort_multipart(&location, &multipart_id).await {
+            log::warn!("Failed to abort upload of snapshot {key}: {abort_err}");
+        }
+        return Err(StorageError::service_error(format!(
+            "Failed to upload snapshot {} to S3 bucket {} as {key}: {err}",
+            snapshot_path.display(),
+            config.bucket,
+        )));
+    }
+    Ok(())
+}
+
+/// Delete the snapshot from the configured bucket
+pub async fn delete_snapshot(config: &S3Config, key: &str) -> Result<(), StorageError> {
+    let store = object_store(Some(config), &config.bucket)?;
+    match store.delete(&ObjectPath::from(key)).await {
+        Ok(()) | Err(object_store::Error::NotFound { .. }) => Ok(()),
+        Err(err) => Err(s3_error(err)),
+    }
+}
+
+/// Download the snapshot from `s3://<bucket>/<key>` URL
+pub async fn download_snapshot(
+    config: Option<&S3Config>,
+    url: &Url,
+    path: &Path,
+) -> Result<(), StorageError> {
+    let bucket = url.host_str().ok_or_else(|| {
+        StorageError::bad_request(format!("Snapshot URL {url} doesn't specify a bucket"))
+    })?;
+    let key = url.path().trim_start_matches('/');
+    let store = object_store(config, bucket)?;
+
+    let mut stream = store
+        .get(&ObjectPath::from(key))
+        .await
+        .map_err(|err| match err {
+            object_store::Error::NotFound { .. } => {
+                StorageError::bad_input(format!("Snapshot {url} does not exist"))
+            }
+            err => s3_error(err),
+        })?
+        .into_stream();
+
+    let mut file = File::create(path).await?;
+    while let Some(chunk) = stream.next().await {
+        file.write_all(&chunk.map_err(s3_error)?).await?;
+    }
+    file.flush().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_key() {
+        let mut config = S3Config {
+            bucket: "backups".to_string(),
+            prefix: None,
+            region: None,
+            endpoint_url: None,
+            access_key: None,
+            secret_key: None,
+        };
+        assert_eq!(
+            snapshot_key(&config, Some("test"), "a.snapshot"),
+            "test/a.snapshot"
+        );
+
+        config.prefix = Some("/qdrant/node-1/".to_string());
+        assert_eq!(
+            snapshot_key(&config, Some("test"), "a.snapshot"),
+            "qdrant/node-1/test/a.snapshot"
+        );
+        assert_eq!(
+            snapshot_key(&config, None, "full.snapshot"),
+            "qdrant/node-1/full.snapshot"
+        );
+    }
+}
@@ -7,6 +7,7 @@ use collection::shards::collection_shard_distribution::CollectionShardDistributi
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::PeerId;
 use collection::shards::CollectionId;
+use tonic::transport::Uri;
 
 use super::TableOfContent;
 use crate::content_manager::collection_meta_ops::*;
@@ -66,6 +67,30 @@ impl CollectionContainer for TableOfContent {
         })
     }
 
+    fn update_peer_address(&self, peer_id: PeerId, uri: Uri) -> Result<(), StorageError> {
+        self.general_runtime.block_on(async {
+            let previous_uri = self
+                .channel_service
+                .id_to_address
+                .write()
+                .insert(peer_id, uri.clone());
+
+            match previous_uri {
+                Some(previous_uri) if previous_uri != uri => {
+                    log::info!("Peer {peer_id} address changed from {previous_uri} to {uri}");
+                    // Connections to the old address are useless now
+                    self.channel_service
+                        .channel_pool
+                        .drop_pool(&previous_uri)
+                        .await;
+                }
+                Some(_) => {}
+                None => log::warn!("Address of unknown peer {peer_id} set to {uri}"),
+            }
+            Ok(())
+        })
+    }
+
     fn sync_local_state(&self) -> Result<(), StorageError> {
         self.general_runtime.block_on(async {
             let collections = self.collections.read().await;
@@ -81,7 +81,19 @@ impl TableOfContent {
             params,
             optimizers_config,
             quantization_config,
+            add_vectors,
+            drop_vectors,
         } = operation.update_collection;
+        if let Some((new_vectors, vector_names)) = add_vectors.as_ref().zip(drop_vectors.as_ref()) {
+            if let Some((vector_name, _)) = new_vectors
+                .params_iter()
+                .find(|(vector_name, _)| vector_names.iter().any(|name| name == *vector_name))
+            {
+                return Err(StorageError::bad_input(format!(
+                    "Vector {vector_name} can't be added and removed in the same update"
+                )));
+            }
+        }
         let collection = self.get_collection(&operation.collection_name).await?;
         let mut recreate_optimizers = false;
 
@@ -107,6 +119,14 @@ impl TableOfContent {
                 .await?;
             recreate_optimizers = true;
         }
+        if let Some(vector_names) = drop_vectors {
+            collection.drop_vectors(&vector_names).await?;
+            recreate_optimizers = true;
+        }
+        if let Some(new_vectors) = add_vectors {
+            collection.add_vectors(&new_vectors).await?;
+            recreate_optimizers = true;
+        }
         if let Some(changes) = replica_changes {
             collection.handle_replica_changes(changes).await?;
         }
@@ -3,11 +3,12 @@ use std::num::NonZeroU32;
 use collection::collection::Collection;
 use collection::config::{self, CollectionConfig, CollectionParams};
 use collection::operations::config_diff::DiffConfig as _;
-use collection::operations::types::{CollectionResult, VectorsConfig};
+use collection::operations::types::{CollectionError, CollectionResult, VectorsConfig};
 use collection::shards::collection_shard_distribution::CollectionShardDistribution;
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::shards::CollectionId;
+use validator::Validate as _;
 
 use super::TableOfContent;
 use crate::content_manager::collection_meta_ops::*;
@@ -39,6 +40,7 @@ impl TableOfContent {
             write_consistency_factor,
             init_from,
             quantization_config,
+            sparse_vectors,
         } = operation;
 
         self.collections
@@ -58,6 +60,9 @@ impl TableOfContent {
             )));
         }
 
+        // Not all APIs validate the combination of the vector params
+        vectors.validate().map_err(CollectionError::from)?;
+
         if let Some(init_from) = &init_from {
             self.check_collections_compatibility(&vectors, &init_from.collection)
                 .await?;
@@ -79,8 +84,20 @@ impl TableOfContent {
         let write_consistency_factor = write_consistency_factor
             .unwrap_or_else(|| config::default_write_consistency_factor().get());
 
+        if let Some(sparse_vectors) = &sparse_vectors {
+            if let Some(name) = sparse_vectors
+                .keys()
+                .find(|name| vectors.get_params(name).is_some())
+            {
+                return Err(StorageError::bad_input(format!(
+                    "Can't create collection with sparse vector name {name}. Dense vector with the same name already exists",
+                )));
+            }
+        }
+
         let collection_params = CollectionParams {
             vectors,
+            sparse_vectors,
             shard_number: NonZeroU32::new(collection_shard_distribution.shard_count() as u32)
                 .ok_or(StorageError::BadInput {
                     description: "`shard_number` cannot be 0".to_string(),
@@ -97,6 +114,8 @@ impl TableOfContent {
                 },
             )?,
             read_fan_out_factor: None,
+            search_cache_size: None,
+            scoring_plugin: None,
         };
         let wal_config = match wal_config_diff {
             None => self.storage_config.wal.clone(),
@@ -25,6 +25,7 @@ use collection::shards::replica_set;
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::telemetry::CollectionTelemetry;
+use common::types::TelemetryDetail;
 use futures::future::try_join_all;
 use futures::Future;
 use segment::common::cpu::get_num_cpus;
@@ -39,6 +40,7 @@ use crate::content_manager::collections_ops::{Checker, Collections};
 use crate::content_manager::consensus::operation_sender::OperationSender;
 use crate::content_manager::errors::StorageError;
 use crate::content_manager::shard_distribution::ShardDistributionProposal;
+use crate::content_manager::slow_query_log::{SlowQuery, SlowQueryLog};
 use crate::types::{PeerAddressById, StorageConfig};
 use crate::ConsensusOperations;
 
@@ -71,6 +73,9 @@ pub struct TableOfContent {
     /// A lock to prevent concurrent collection creation.
     /// Effectively, this lock ensures that `create_collection` is called sequentially.
     collection_create_lock: Mutex<()>,
+    /// Read requests, slower than the configured thresholds.
+    /// If not defined - slow queries are not recorded.
+    slow_query_log: Option<SlowQueryLog>,
 }
 
 impl TableOfContent {
@@ -182,6 +187,7 @@ impl TableOfContent {
             lock_error_message: parking_lot::Mutex::new(None),
             update_rate_limiter: rate_limiter,
             collection_create_lock: Default::default(),
+            slow_query_log: storage_config.slow_query_log.clone().map(SlowQueryLog::new),
         }
     }
 
@@ -194,6 +200,14 @@ impl TableOfContent {
         &self.storage_config.storage_path
     }
 
+    /// Latest slow read requests, empty if the slow query log is disabled
+    pub fn slow_queries(&self) -> Vec<SlowQuery> {
+        self.slow_query_log
+            .as_ref()
+            .map(SlowQueryLog::entries)
+            .unwrap_or_default()
+    }
+
     /// List of all collections
     pub async fn all_collections(&self) -> Vec<String> {
         self.collections.read().await.keys().cloned().collect()
@@ -261,6 +275,11 @@ impl TableOfContent {
         Ok(resolved_name)
     }
 
+    /// Name of the collection, which the alias refers to, `None` if the name is not an alias
+    pub async fn resolve_alias(&self, alias: &str) -> Option<String> {
+        self.alias_persistence.read().await.get(alias)
+    }
+
     /// List of all aliases for a given collection
     pub async fn collection_aliases(
         &self,
@@ -379,12 +398,12 @@ impl TableOfContent {
         false
     }
 
-    pub async fn get_telemetry_data(&self) -> Vec<CollectionTelemetry> {
+    pub async fn get_telemetry_data(&self, detail: TelemetryDetail) -> Vec<CollectionTelemetry> {
         let mut result = Vec::new();
         let all_collections = self.all_collections().await;
         for collection_name in &all_collections {
             if let Ok(collection) = self.get_collection(collection_name).await {
-                result.push(collection.get_telemetry_data().await);
+                result.push(collection.get_telemetry_data(detail).await);
             }
         }
         result
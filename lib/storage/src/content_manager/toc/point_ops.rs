@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::time::Duration;
+
+use collection::collection::resolve_query_lookups;
 use collection::grouping::group_by::GroupRequest;
 use collection::grouping::GroupBy;
 use collection::operations::consistency_params::ReadConsistency;
@@ -6,12 +11,39 @@ use collection::operations::types::*;
 use collection::operations::CollectionUpdateOperations;
 use collection::recommendations;
 use collection::shards::shard::ShardId;
+use futures::future;
 use segment::types::ScoredPoint;
+use serde::Serialize;
 
 use super::TableOfContent;
 use crate::content_manager::errors::StorageError;
+use crate::types::SlowQueryOperation;
 
 impl TableOfContent {
+    /// Execute the read request, recording it in the slow query log, if it is enabled.
+    /// Only requests of the clients are recorded, requests of the other peers are part of those.
+    async fn slow_query_logged<R, T, F>(
+        &self,
+        collection_name: &str,
+        operation: SlowQueryOperation,
+        shard_selection: Option<ShardId>,
+        request: R,
+        query: impl FnOnce(R) -> F,
+    ) -> Result<T, StorageError>
+    where
+        R: Clone + Serialize,
+        F: Future<Output = Result<T, StorageError>>,
+    {
+        match (&self.slow_query_log, shard_selection) {
+            (Some(slow_query_log), None) => {
+                slow_query_log
+                    .observe(collection_name, operation, request, query)
+                    .await
+            }
+            _ => query(request).await,
+        }
+    }
+
     /// Recommend points using positive and negative example from the request
     ///
     /// # Arguments
@@ -22,6 +54,10 @@ impl TableOfContent {
     /// # Result
     ///
     /// Points with recommendation score
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn recommend(
         &self,
         collection_name: &str,
@@ -29,14 +65,23 @@ impl TableOfContent {
         read_consistency: Option<ReadConsistency>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
-        recommendations::recommend_by(
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Recommend,
+            None,
             request,
-            &collection,
-            |name| self.get_collection_opt(name),
-            read_consistency,
+            |request| async move {
+                recommendations::recommend_by(
+                    request,
+                    &collection,
+                    |name| self.get_collection_opt(name),
+                    read_consistency,
+                )
+                .await
+                .map_err(StorageError::from)
+            },
         )
         .await
-        .map_err(|err| err.into())
     }
 
     /// Recommend points in a batching fashion using positive and negative example from the request
@@ -49,6 +94,10 @@ impl TableOfContent {
     /// # Result
     ///
     /// Points with recommendation score
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn recommend_batch(
         &self,
         collection_name: &str,
@@ -56,14 +105,23 @@ impl TableOfContent {
         read_consistency: Option<ReadConsistency>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
-        recommendations::recommend_batch_by(
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Recommend,
+            None,
             request,
-            &collection,
-            |name| self.get_collection_opt(name),
-            read_consistency,
+            |request| async move {
+                recommendations::recommend_batch_by(
+                    request,
+                    &collection,
+                    |name| self.get_collection_opt(name),
+                    read_consistency,
+                )
+                .await
+                .map_err(StorageError::from)
+            },
         )
         .await
-        .map_err(|err| err.into())
     }
 
     /// Search for the closest points using vector similarity with given restrictions defined
@@ -77,6 +135,10 @@ impl TableOfContent {
     /// # Result
     ///
     /// Points with search score
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn search(
         &self,
         collection_name: &str,
@@ -85,10 +147,19 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<Vec<ScoredPoint>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
-        collection
-            .search(request, read_consistency, shard_selection)
-            .await
-            .map_err(|err| err.into())
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Search,
+            shard_selection,
+            request,
+            |request| async move {
+                collection
+                    .search(request, read_consistency, shard_selection)
+                    .await
+                    .map_err(StorageError::from)
+            },
+        )
+        .await
     }
 
     /// Search in a batching fashion for the closest points using vector similarity with given restrictions defined
@@ -102,6 +173,10 @@ impl TableOfContent {
     /// # Result
     ///
     /// Points with search score
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn search_batch(
         &self,
         collection_name: &str,
@@ -110,14 +185,155 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Search,
+            shard_selection,
+            request,
+            |request| async move {
+                collection
+                    .search_batch(request, read_consistency, shard_selection)
+                    .await
+                    .map_err(StorageError::from)
+            },
+        )
+        .await
+    }
+
+    /// Search in a batching fashion, with a timeout for the shards of the collection
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we search
+    /// * `request` - [`SearchRequestBatch`]
+    /// * `shard_selection` - which local shard to use
+    /// * `timeout` - how long to wait for the shards
+    /// * `allow_partial` - return results of the shards and segments, which were searched in time, instead of an error
+    ///
+    /// # Result
+    ///
+    /// Points with search score, flagged as partial if some shards or segments were not searched in time
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
+    pub async fn search_batch_with_timeout(
+        &self,
+        collection_name: &str,
+        request: SearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+        timeout: Duration,
+        allow_partial: bool,
+    ) -> Result<PartialSearchBatchResult, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Search,
+            shard_selection,
+            request,
+            |request| async move {
+                collection
+                    .search_batch_with_timeout(
+                        request,
+                        read_consistency,
+                        shard_selection,
+                        timeout,
+                        allow_partial,
+                    )
+                    .await
+                    .map_err(StorageError::from)
+            },
+        )
+        .await
+    }
+
+    /// Search in multiple collections, searches of the same collection are batched together
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - [`CollectionsSearchRequestBatch`]
+    ///
+    /// # Result
+    ///
+    /// Points with search score, in the order of the searches of the request
+    pub async fn search_batch_collections(
+        &self,
+        request: CollectionsSearchRequestBatch,
+        read_consistency: Option<ReadConsistency>,
+    ) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
+        let searches_count = request.searches.len();
+
+        // Positions of the searches in the request, and the searches, by collection
+        let mut batches: HashMap<String, (Vec<usize>, Vec<SearchRequest>)> = HashMap::new();
+        for (position, search) in request.searches.into_iter().enumerate() {
+            let (positions, searches) = batches.entry(search.collection_name).or_default();
+            positions.push(position);
+            searches.push(search.search);
+        }
+
+        let batch_results = future::try_join_all(batches.into_iter().map(
+            |(collection_name, (positions, searches))| async move {
+                let results = self
+                    .search_batch(
+                        &collection_name,
+                        SearchRequestBatch { searches },
+                        read_consistency,
+                        None,
+                    )
+                    .await?;
+                Ok::<_, StorageError>(positions.into_iter().zip(results))
+            },
+        ))
+        .await?;
+
+        let mut results = vec![Vec::new(); searches_count];
+        for (position, result) in batch_results.into_iter().flatten() {
+            results[position] = result;
+        }
+        Ok(results)
+    }
+
+    /// Query points with the prefetch stages and the final query defined in the request
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we query
+    /// * `request` - [`QueryRequest`]
+    /// * `shard_selection` - which local shard to use
+    /// # Result
+    ///
+    /// Points with the score of the final stage
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
+    pub async fn query(
+        &self,
+        collection_name: &str,
+        request: QueryRequest,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> Result<Vec<ScoredPoint>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        let request = resolve_query_lookups(
+            request,
+            |name| self.get_collection_opt(name),
+            read_consistency,
+        )
+        .await?;
         collection
-            .search_batch(request, read_consistency, shard_selection)
+            .query(request, read_consistency, shard_selection)
             .await
             .map_err(|err| err.into())
     }
 
     // ! COPY-PASTE: `core_search_batch` is a copy-paste of `search_batch` with different request type
     // ! please replicate any changes to both methods
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn core_search_batch(
         &self,
         collection_name: &str,
@@ -144,6 +360,10 @@ impl TableOfContent {
     ///
     /// Number of points in the collection.
     ///
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn count(
         &self,
         collection_name: &str,
@@ -151,10 +371,56 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<CountResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
-        collection
-            .count(request, shard_selection)
-            .await
-            .map_err(|err| err.into())
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Count,
+            shard_selection,
+            request,
+            |request| async move {
+                collection
+                    .count(request, shard_selection)
+                    .await
+                    .map_err(StorageError::from)
+            },
+        )
+        .await
+    }
+
+    /// Count points for each filter of the batch
+    ///
+    /// # Arguments
+    ///
+    /// * `collection_name` - in what collection do we count
+    /// * `request` - [`CountRequestBatch`]
+    /// * `shard_selection` - which local shard to use
+    ///
+    /// # Result
+    ///
+    /// Number of points for each request of the batch
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
+    pub async fn count_batch(
+        &self,
+        collection_name: &str,
+        request: CountRequestBatch,
+        shard_selection: Option<ShardId>,
+    ) -> Result<Vec<CountResult>, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Count,
+            shard_selection,
+            request,
+            |request| async move {
+                collection
+                    .count_batch(request, shard_selection)
+                    .await
+                    .map_err(StorageError::from)
+            },
+        )
+        .await
     }
 
     /// Return specific points by IDs
@@ -168,6 +434,10 @@ impl TableOfContent {
     /// # Result
     ///
     /// List of points with specified information included
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn retrieve(
         &self,
         collection_name: &str,
@@ -176,12 +446,25 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<Vec<Record>, StorageError> {
         let collection = self.get_collection(collection_name).await?;
-        collection
-            .retrieve(request, read_consistency, shard_selection)
-            .await
-            .map_err(|err| err.into())
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Retrieve,
+            shard_selection,
+            request,
+            |request| async move {
+                collection
+                    .retrieve(request, read_consistency, shard_selection)
+                    .await
+                    .map_err(StorageError::from)
+            },
+        )
+        .await
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn group(
         &self,
         collection_name: &str,
@@ -191,23 +474,32 @@ impl TableOfContent {
     ) -> Result<GroupsResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
 
-        let collection_by_name = |name| self.get_collection_opt(name);
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Group,
+            shard_selection,
+            request,
+            |request| async move {
+                let collection_by_name = |name| self.get_collection_opt(name);
 
-        let mut group_by = GroupBy::new(request, &collection, collection_by_name);
+                let mut group_by = GroupBy::new(request, &collection, collection_by_name);
 
-        if let Some(read_consistency) = read_consistency {
-            group_by = group_by.with_read_consistency(read_consistency);
-        }
+                if let Some(read_consistency) = read_consistency {
+                    group_by = group_by.with_read_consistency(read_consistency);
+                }
 
-        if let Some(shard_selection) = shard_selection {
-            group_by = group_by.with_shard_selection(shard_selection);
-        }
+                if let Some(shard_selection) = shard_selection {
+                    group_by = group_by.with_shard_selection(shard_selection);
+                }
 
-        group_by
-            .execute()
-            .await
-            .map(|groups| GroupsResult { groups })
-            .map_err(|err| err.into())
+                group_by
+                    .execute()
+                    .await
+                    .map(|groups| GroupsResult { groups })
+                    .map_err(StorageError::from)
+            },
+        )
+        .await
     }
 
     /// Paginate over all stored points with given filtering conditions
@@ -221,6 +513,10 @@ impl TableOfContent {
     /// # Result
     ///
     /// List of points with specified information included
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn scroll(
         &self,
         collection_name: &str,
@@ -229,12 +525,25 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
     ) -> Result<ScrollResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
-        collection
-            .scroll_by(request, read_consistency, shard_selection)
-            .await
-            .map_err(|err| err.into())
+        self.slow_query_logged(
+            collection_name,
+            SlowQueryOperation::Scroll,
+            shard_selection,
+            request,
+            |request| async move {
+                collection
+                    .scroll_by(request, read_consistency, shard_selection)
+                    .await
+                    .map_err(StorageError::from)
+            },
+        )
+        .await
     }
 
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", skip_all, fields(collection = collection_name))
+    )]
     pub async fn update(
         &self,
         collection_name: &str,
@@ -1,6 +1,10 @@
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+use api::grpc::qdrant::collections_internal_client::CollectionsInternalClient;
+use api::grpc::qdrant::CreateShardSnapshotRequestInternal;
 use collection::operations::snapshot_ops::SnapshotDescription;
+use collection::operations::types::CollectionError;
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::shards::transfer::shard_transfer::ShardTransfer;
@@ -9,12 +13,17 @@ use super::TableOfContent;
 use crate::content_manager::consensus::operation_sender::OperationSender;
 use crate::content_manager::consensus_ops::ConsensusOperations;
 use crate::content_manager::errors::StorageError;
+use crate::types::S3Config;
 
 impl TableOfContent {
     pub fn snapshots_path(&self) -> &str {
         &self.storage_config.snapshots_path
     }
 
+    pub fn snapshots_s3_config(&self) -> Option<&S3Config> {
+        self.storage_config.snapshots_s3.as_ref()
+    }
+
     pub fn collection_snapshots_path(snapshots_path: &Path, collection_name: &str) -> PathBuf {
         snapshots_path.join(collection_name)
     }
@@ -45,16 +54,78 @@ impl TableOfContent {
     pub async fn create_snapshot(
         &self,
         collection_name: &str,
+        base_snapshot: Option<&str>,
     ) -> Result<SnapshotDescription, StorageError> {
         let collection = self.get_collection(collection_name).await?;
         // We want to use temp dir inside the temp_path (storage if not specified), because it is possible, that
         // snapshot directory is mounted as network share and multiple writes to it could be slow
         let temp_dir = self.optional_temp_or_storage_temp_path()?;
         Ok(collection
-            .create_snapshot(&temp_dir, self.this_peer_id)
+            .create_snapshot(&temp_dir, self.this_peer_id, base_snapshot)
+            .await?)
+    }
+
+    /// Create snapshot of the shard, stored on this peer
+    pub async fn create_shard_snapshot(
+        &self,
+        collection_name: &str,
+        shard_id: ShardId,
+    ) -> Result<SnapshotDescription, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        Ok(collection
+            .create_shard_snapshot(shard_id, &self.optional_temp_or_snapshot_temp_path()?)
             .await?)
     }
 
+    /// Create snapshot of the shard, stored on the remote peer.
+    /// The snapshot stays on the remote peer.
+    pub async fn create_remote_shard_snapshot(
+        &self,
+        peer_id: PeerId,
+        collection_name: &str,
+        shard_id: ShardId,
+        timeout: Duration,
+    ) -> Result<api::grpc::qdrant::SnapshotDescription, StorageError> {
+        let address = self
+            .channel_service
+            .id_to_address
+            .read()
+            .get(&peer_id)
+            .ok_or_else(|| CollectionError::service_error("Address for peer ID is not found."))?
+            .clone();
+        let response = self
+            .channel_service
+            .channel_pool
+            .with_channel_timeout(
+                &address,
+                |channel| async move {
+                    let mut client = CollectionsInternalClient::new(channel);
+                    let request = CreateShardSnapshotRequestInternal {
+                        collection_name: collection_name.to_string(),
+                        shard_id,
+                    };
+                    client
+                        .create_shard_snapshot(tonic::Request::new(request))
+                        .await
+                },
+                Some(timeout),
+                // Retried request may leave a duplicate snapshot on the peer
+                0,
+            )
+            .await
+            .map_err(|err| {
+                StorageError::service_error(format!(
+                    "Failed to create snapshot of shard {shard_id} of collection {collection_name} on peer {peer_id}: {err}"
+                ))
+            })?
+            .into_inner();
+        response.snapshot_description.ok_or_else(|| {
+            StorageError::service_error(format!(
+                "Peer {peer_id} didn't return snapshot of shard {shard_id} of collection {collection_name}"
+            ))
+        })
+    }
+
     pub fn send_set_replica_state_proposal(
         &self,
         collection_name: String,
@@ -18,6 +18,14 @@ use validator::Validate;
 
 pub type PeerAddressById = HashMap<PeerId, Uri>;
 
+/// Change the address under which a peer is reachable by the rest of the cluster
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct UpdatePeerAddress {
+    /// New URI of the peer's internal (p2p) gRPC endpoint, e.g. `http://10.0.0.5:6335`
+    #[validate(length(min = 1))]
+    pub uri: String,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PerformanceConfig {
     pub max_search_threads: usize,
@@ -72,6 +80,100 @@ pub struct StorageConfig {
     pub recovery_mode: Option<String>,
     #[serde(default)]
     pub update_concurrency: Option<NonZeroUsize>,
+    /// If provided - created snapshots are also uploaded to this S3-compatible storage
+    #[serde(default)]
+    #[validate]
+    pub snapshots_s3: Option<S3Config>,
+    /// How long shards remember idempotency tokens of the applied update requests
+    #[serde(default)]
+    pub idempotency_token_retention_sec: Option<u64>,
+    /// How long shards remember the clocks of deleted points, so delayed updates don't
+    /// bring them back
+    #[serde(default)]
+    pub deleted_point_clocks_retention_sec: Option<u64>,
+    /// If provided - read requests, slower than the thresholds, are logged
+    #[serde(default)]
+    #[validate]
+    pub slow_query_log: Option<SlowQueryLogConfig>,
+}
+
+/// S3-compatible object storage, such as AWS S3, MinIO or GCS with HMAC keys.
+///
+/// Region and credentials, which are not set, are read from the `AWS_*` environment variables.
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct S3Config {
+    #[validate(length(min = 1))]
+    pub bucket: String,
+    /// Prefix of the snapshot keys in the bucket
+    #[serde(default)]
+    pub prefix: Option<String>,
+    #[serde(default)]
+    pub region: Option<String>,
+    /// URL of the storage, if it is not AWS S3
+    #[serde(default)]
+    pub endpoint_url: Option<String>,
+    #[serde(default)]
+    pub access_key: Option<String>,
+    #[serde(default)]
+    pub secret_key: Option<String>,
+}
+
+/// Read operations with separate thresholds in the slow query log
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum SlowQueryOperation {
+    Search,
+    Recommend,
+    Group,
+    Count,
+    Scroll,
+    Retrieve,
+}
+
+#[derive(Clone, Debug, Deserialize, Validate)]
+pub struct SlowQueryLogConfig {
+    /// Requests, which take longer than this, are logged
+    #[validate(range(min = 1))]
+    pub threshold_ms: u64,
+    /// Thresholds of specific operations, instead of `threshold_ms`
+    #[serde(default)]
+    pub operation_threshold_ms: HashMap<SlowQueryOperation, u64>,
+    /// Number of the latest slow queries, available through the API
+    #[serde(default = "default_slow_query_log_max_entries")]
+    pub max_entries: usize,
+    /// If provided - slow queries are also written into this file, one JSON per line
+    #[serde(default)]
+    pub path: Option<String>,
+    /// The file is rotated when it grows over this size
+    #[serde(default = "default_slow_query_log_max_file_size_mb")]
+    #[validate(range(min = 1))]
+    pub max_file_size_mb: u64,
+    /// Number of rotated files to keep
+    #[serde(default = "default_slow_query_log_max_files")]
+    pub max_files: usize,
+}
+
+impl SlowQueryLogConfig {
+    pub fn threshold(&self, operation: SlowQueryOperation) -> Duration {
+        let threshold_ms = self
+            .operation_threshold_ms
+            .get(&operation)
+            .copied()
+            .unwrap_or(self.threshold_ms);
+        Duration::from_millis(threshold_ms)
+    }
+}
+
+const fn default_slow_query_log_max_entries() -> usize {
+    100
+}
+
+const fn default_slow_query_log_max_file_size_mb() -> u64 {
+    100
+}
+
+const fn default_slow_query_log_max_files() -> usize {
+    5
 }
 
 impl StorageConfig {
@@ -86,6 +188,10 @@ impl StorageConfig {
                 .map(|x| Duration::from_secs(x as u64)),
             self.update_concurrency,
             is_distributed,
+            self.idempotency_token_retention_sec
+                .map(Duration::from_secs),
+            self.deleted_point_clocks_retention_sec
+                .map(Duration::from_secs),
         )
     }
 }
@@ -39,6 +39,9 @@ fn test_alias_operation() {
             indexing_threshold: Some(100),
             flush_interval_sec: 2,
             max_optimization_threads: 2,
+            expiration_key: None,
+            merge_policy: None,
+            defragmentation_key: None,
         },
         wal: Default::default(),
         performance: PerformanceConfig {
@@ -57,6 +60,10 @@ fn test_alias_operation() {
         async_scorer: false,
         update_concurrency: Some(NonZeroUsize::new(2).unwrap()),
         // update_concurrency: None,
+        snapshots_s3: None,
+        idempotency_token_retention_sec: None,
+        deleted_point_clocks_retention_sec: None,
+        slow_query_log: None,
     };
 
     let search_runtime = Runtime::new().unwrap();
@@ -92,6 +99,8 @@ fn test_alias_operation() {
                             hnsw_config: None,
                             quantization_config: None,
                             on_disk: None,
+                            multivector_config: None,
+                            datatype: None,
                         }
                         .into(),
                         hnsw_config: None,
@@ -103,6 +112,7 @@ fn test_alias_operation() {
                         write_consistency_factor: None,
                         init_from: None,
                         quantization_config: None,
+                        sparse_vectors: None,
                     },
                 )),
                 None,
@@ -69,3 +69,46 @@ def test_approx_count_search():
     assert response.ok
     assert response.json()['result']['count'] < 8
     assert response.json()['result']['count'] > 0
+
+
+def test_count_batch():
+    filters = [
+        {"must": [{"key": "city", "match": {"value": "London"}}]},
+        {"must": [{"key": "city", "match": {"value": "Berlin"}}]},
+        {"must": [{"key": "city", "match": {"value": "Tokyo"}}]},
+        None,
+    ]
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/count/batch',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "counts": [{"filter": filter, "exact": True} for filter in filters]
+        }
+    )
+    assert response.ok, response.text
+    counts = [result['count'] for result in response.json()['result']]
+    assert counts == [2, 3, 0, 8]
+
+    # Results are the same as of the separate requests, in the order of the requests
+    for filter, count in zip(filters, counts):
+        response = request_with_validation(
+            api='/collections/{collection_name}/points/count',
+            method="POST",
+            path_params={'collection_name': collection_name},
+            body={"filter": filter, "exact": True}
+        )
+        assert response.ok, response.text
+        assert response.json()['result']['count'] == count
+
+
+def test_count_batch_empty():
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/count/batch',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={"counts": []}
+    )
+    assert response.ok, response.text
+    assert response.json()['result'] == []
@@ -0,0 +1,151 @@
+import json
+
+import pytest
+import requests
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+from .helpers.settings import QDRANT_HOST
+
+collection_name = 'test_collection_export'
+import_collection_name = 'test_collection_import'
+
+
+@pytest.fixture(autouse=True)
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    drop_collection(collection_name=import_collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PUT",
+        path_params={'collection_name': import_collection_name},
+        body={
+            "vectors": {
+                "size": 4,
+                "distance": "Dot",
+                "on_disk": on_disk_vectors,
+            },
+        }
+    )
+    assert response.ok
+    yield
+    drop_collection(collection_name=collection_name)
+    drop_collection(collection_name=import_collection_name)
+
+
+def export_points(name, body=None):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/export',
+        method="POST",
+        path_params={'collection_name': name},
+        body=body or {},
+    )
+    assert response.ok
+    assert response.headers['content-type'].startswith('application/x-ndjson')
+    return [json.loads(line) for line in response.text.splitlines() if line]
+
+
+def import_points(name, body, content_type='application/x-ndjson'):
+    return requests.post(
+        f"{QDRANT_HOST}/collections/{name}/points/import",
+        data=body,
+        headers={'content-type': content_type},
+    )
+
+
+def assert_same_points(actual, expected):
+    assert [point['id'] for point in actual] == [point['id'] for point in expected]
+    for actual_point, expected_point in zip(actual, expected):
+        assert actual_point.get('payload') == expected_point.get('payload')
+        assert actual_point['vector'] == pytest.approx(expected_point['vector'])
+
+
+def test_import_export_round_trip():
+    exported = export_points(collection_name)
+    assert [point['id'] for point in exported] == [1, 2, 3, 4, 5, 6, 7, 8]
+
+    body = "\n".join(json.dumps(point) for point in exported) + "\n"
+    response = import_points(import_collection_name, body)
+    assert response.ok
+    result = response.json()['result']
+    assert result['points_imported'] == 8
+    assert not result['cancelled']
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/import_tasks/{task_id}',
+        method="GET",
+        path_params={'collection_name': import_collection_name, 'task_id': result['task_id']},
+    )
+    assert response.ok
+    task = response.json()['result']
+    assert task['status'] == 'completed'
+    assert task['points_read'] == 8
+    assert task['points_imported'] == 8
+
+    assert_same_points(export_points(import_collection_name), exported)
+
+
+def test_export_resume_with_offset():
+    exported = export_points(collection_name, {"offset": 5})
+    assert [point['id'] for point in exported] == [5, 6, 7, 8]
+
+    exported = export_points(collection_name, {
+        "filter": {"must": [{"key": "city", "match": {"value": "Berlin"}}]},
+    })
+    assert [point['id'] for point in exported] == [1, 2, 3]
+
+
+def test_import_invalid_line():
+    exported = export_points(collection_name)
+    body = json.dumps(exported[0]) + "\n\nnot a point\n" + json.dumps(exported[1]) + "\n"
+
+    response = import_points(import_collection_name, body)
+    assert response.status_code == 400
+    assert 'line 3' in response.json()['status']['error']
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/import_tasks',
+        method="GET",
+        path_params={'collection_name': import_collection_name},
+    )
+    assert response.ok
+    tasks = response.json()['result']
+    assert len(tasks) == 1
+    assert tasks[0]['status'] == 'failed'
+    assert 'line 3' in tasks[0]['error']
+
+
+def test_import_parquet_is_rejected():
+    response = import_points(import_collection_name, b'PAR1', 'application/vnd.apache.parquet')
+    assert response.status_code == 400
+    assert 'Parquet' in response.json()['status']['error']
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/import_tasks',
+        method="GET",
+        path_params={'collection_name': import_collection_name},
+    )
+    assert response.ok
+    assert response.json()['result'] == []
+
+
+def test_cancel_unknown_import_task():
+    response = request_with_validation(
+        api='/collections/{collection_name}/import_tasks/{task_id}/cancel',
+        method="POST",
+        path_params={
+            'collection_name': import_collection_name,
+            'task_id': '00000000-0000-0000-0000-000000000000',
+        },
+    )
+    assert response.status_code == 404
+
+
+def test_export_parquet_is_rejected():
+    response = requests.post(
+        f"{QDRANT_HOST}/collections/{collection_name}/points/export",
+        json={},
+        headers={'accept': 'application/vnd.apache.parquet'},
+    )
+    assert response.status_code == 400
+    assert 'Parquet' in response.json()['status']['error']
@@ -0,0 +1,84 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_search'
+collection_name2 = 'test_collection_search2'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    basic_collection_setup(collection_name=collection_name2, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+    drop_collection(collection_name=collection_name2)
+
+
+def search(collection, body):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection},
+        body=body,
+    )
+    assert response.ok, response.text
+    return response.json()['result']
+
+
+def test_search_batch_collections():
+    # Second collection differs from the first one, so the results tell the collections apart
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': collection_name2},
+        query_params={'wait': 'true'},
+        body={"points": [1, 2, 3]}
+    )
+    assert response.ok, response.text
+
+    searches = [
+        (collection_name, {"vector": [0.2, 0.1, 0.9, 0.7], "limit": 3}),
+        (collection_name2, {"vector": [0.2, 0.1, 0.9, 0.7], "limit": 3}),
+        (collection_name, {
+            "vector": [0.5, 0.5, 0.5, 0.5],
+            "limit": 2,
+            "filter": {"must": [{"key": "city", "match": {"value": "London"}}]},
+        }),
+        (collection_name2, {"vector": [0.9, 0.1, 0.1, 0.1], "limit": 5, "with_payload": True}),
+    ]
+
+    response = request_with_validation(
+        api='/collections/search/batch',
+        method="POST",
+        body={
+            "searches": [
+                {"collection_name": collection, **search_body}
+                for collection, search_body in searches
+            ]
+        }
+    )
+    assert response.ok, response.text
+    results = response.json()['result']
+
+    # Results are in the order of the searches, not grouped by collection
+    assert len(results) == len(searches)
+    for result, (collection, search_body) in zip(results, searches):
+        assert result == search(collection, search_body)
+
+    assert {point['id'] for point in results[1]}.isdisjoint({1, 2, 3})
+
+
+def test_search_batch_collections_missing_collection():
+    response = request_with_validation(
+        api='/collections/search/batch',
+        method="POST",
+        body={
+            "searches": [
+                {"collection_name": collection_name, "vector": [0.2, 0.1, 0.9, 0.7], "limit": 3},
+                {"collection_name": "missing_collection", "vector": [0.2, 0.1, 0.9, 0.7], "limit": 3},
+            ]
+        }
+    )
+    assert response.status_code == 404, response.text
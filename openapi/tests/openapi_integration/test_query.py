@@ -0,0 +1,247 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_query'
+lookup_collection_name = 'test_collection_query_lookup'
+
+
+@pytest.fixture(autouse=True, scope="module")
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+    drop_collection(collection_name=lookup_collection_name)
+
+
+def search(vector, limit, filter=None):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/search',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "vector": vector,
+            "limit": limit,
+            "filter": filter,
+        }
+    )
+    assert response.ok, response.text
+    return response.json()['result']
+
+
+def query(body):
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/query',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body=body,
+    )
+    assert response.ok, response.text
+    return response.json()['result']
+
+
+def test_query_nearest_matches_search():
+    vector = [0.2, 0.1, 0.9, 0.7]
+    expected = search(vector, limit=3)
+
+    result = query({
+        "query": {"nearest": vector},
+        "limit": 3,
+    })
+
+    assert [point['id'] for point in result] == [point['id'] for point in expected]
+    for point, expected_point in zip(result, expected):
+        assert point['score'] == pytest.approx(expected_point['score'], abs=1e-5)
+
+
+def test_query_rescores_prefetch():
+    london = {"must": [{"key": "city", "match": {"value": "London"}}]}
+    candidates = search([0.2, 0.1, 0.9, 0.7], limit=10, filter=london)
+    candidate_ids = {point['id'] for point in candidates}
+
+    result = query({
+        "prefetch": [
+            {
+                "query": {"nearest": [0.2, 0.1, 0.9, 0.7]},
+                "filter": london,
+                "limit": 10,
+            }
+        ],
+        "query": {"nearest": [0.9, 0.1, 0.1, 0.1]},
+        "limit": 10,
+    })
+
+    # Only the candidates of the prefetch are rescored
+    assert {point['id'] for point in result} == candidate_ids
+    scores = [point['score'] for point in result]
+    assert scores == sorted(scores, reverse=True)
+
+    expected = search([0.9, 0.1, 0.1, 0.1], limit=10, filter=london)
+    assert [point['id'] for point in result] == [point['id'] for point in expected]
+
+
+def test_query_nested_prefetches():
+    inner_ids = {point['id'] for point in search([0.2, 0.1, 0.9, 0.7], limit=5)}
+
+    result = query({
+        "prefetch": [
+            {
+                "prefetch": [
+                    {
+                        "query": {"nearest": [0.2, 0.1, 0.9, 0.7]},
+                        "limit": 5,
+                    }
+                ],
+                "query": {"nearest": [0.1, 0.9, 0.1, 0.1]},
+                "limit": 3,
+            }
+        ],
+        "query": {"nearest": [0.9, 0.1, 0.1, 0.1]},
+        "limit": 2,
+    })
+
+    # Each stage narrows down the candidates of the previous one
+    assert len(result) == 2
+    assert {point['id'] for point in result} <= inner_ids
+
+
+def test_query_without_query_returns_prefetch():
+    expected = search([0.2, 0.1, 0.9, 0.7], limit=3)
+
+    result = query({
+        "prefetch": [
+            {
+                "query": {"nearest": [0.2, 0.1, 0.9, 0.7]},
+                "limit": 3,
+            }
+        ],
+        "limit": 3,
+    })
+
+    assert [point['id'] for point in result] == [point['id'] for point in expected]
+
+
+def test_query_rrf_fusion():
+    first = search([0.2, 0.1, 0.9, 0.7], limit=4)
+    second = search([0.9, 0.1, 0.1, 0.1], limit=4)
+
+    expected_scores = {}
+    for ranking in (first, second):
+        for rank, point in enumerate(ranking):
+            expected_scores[point['id']] = expected_scores.get(point['id'], 0.0) + 1.0 / (60 + rank + 1)
+
+    result = query({
+        "prefetch": [
+            {"query": {"nearest": [0.2, 0.1, 0.9, 0.7]}, "limit": 4},
+            {"query": {"nearest": [0.9, 0.1, 0.1, 0.1]}, "limit": 4},
+        ],
+        "query": {"fusion": "rrf"},
+        "limit": 8,
+    })
+
+    assert {point['id'] for point in result} == set(expected_scores)
+    for point in result:
+        assert point['score'] == pytest.approx(expected_scores[point['id']], abs=1e-5)
+    scores = [point['score'] for point in result]
+    assert scores == sorted(scores, reverse=True)
+
+
+def test_query_relative_score_fusion():
+    first = search([0.2, 0.1, 0.9, 0.7], limit=4)
+    second = search([0.9, 0.1, 0.1, 0.1], limit=4)
+
+    result = query({
+        "prefetch": [
+            {"query": {"nearest": [0.2, 0.1, 0.9, 0.7]}, "limit": 4},
+            {"query": {"nearest": [0.9, 0.1, 0.1, 0.1]}, "limit": 4},
+        ],
+        "query": {"fusion": "relative_score"},
+        "limit": 8,
+        "with_explanation": True,
+    })
+
+    assert {point['id'] for point in result} == {point['id'] for point in first + second}
+    for point in result:
+        # Each ranking contributes a normalized score in [0, 1]
+        assert 0.0 <= point['score'] <= 2.0 + 1e-5
+        prefetches = point['explanation']['prefetches']
+        assert len(prefetches) == 2
+        contributions = [score['contribution'] for score in prefetches if score is not None]
+        assert sum(contributions) == pytest.approx(point['score'], abs=1e-5)
+
+    # The best points of each ranking get the full contribution of it
+    best = {point['id']: point for point in result}
+    assert best[first[0]['id']]['explanation']['prefetches'][0]['contribution'] == pytest.approx(1.0)
+    assert best[second[0]['id']]['explanation']['prefetches'][1]['contribution'] == pytest.approx(1.0)
+
+
+def test_query_lookup_from(on_disk_vectors):
+    drop_collection(collection_name=lookup_collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PUT",
+        path_params={'collection_name': lookup_collection_name},
+        body={
+            "vectors": {
+                "other": {
+                    "size": 4,
+                    "distance": "Dot",
+                    "on_disk": on_disk_vectors,
+                }
+            }
+        }
+    )
+    assert response.ok, response.text
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points',
+        method="PUT",
+        path_params={'collection_name': lookup_collection_name},
+        query_params={'wait': 'true'},
+        body={
+            "points": [
+                {"id": 1, "vector": {"other": [0.9, 0.1, 0.1, 0.1]}},
+                {"id": 2, "vector": {"other": [0.1, 0.1, 0.9, 0.1]}},
+            ]
+        }
+    )
+    assert response.ok, response.text
+
+    expected = search([0.9, 0.1, 0.1, 0.1], limit=3)
+
+    # Point 1 of the lookup collection is used as the example, not point 1 of the queried one
+    result = query({
+        "query": {"recommend": {"positive": [1]}},
+        "lookup_from": {"collection": lookup_collection_name, "vector": "other"},
+        "limit": 3,
+    })
+    assert [point['id'] for point in result] == [point['id'] for point in expected]
+
+    # Lookup location of a prefetch applies to its own examples only
+    result = query({
+        "prefetch": [
+            {
+                "query": {"recommend": {"positive": [2]}},
+                "lookup_from": {"collection": lookup_collection_name, "vector": "other"},
+                "limit": 3,
+            }
+        ],
+        "query": {"nearest": [0.9, 0.1, 0.1, 0.1]},
+        "limit": 3,
+    })
+    candidate_ids = {point['id'] for point in search([0.1, 0.1, 0.9, 0.1], limit=3)}
+    assert {point['id'] for point in result} == candidate_ids
+
+    # Missing point in the lookup collection
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/query',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        body={
+            "query": {"recommend": {"positive": [3]}},
+            "lookup_from": {"collection": lookup_collection_name, "vector": "other"},
+            "limit": 3,
+        },
+    )
+    assert response.status_code == 404, response.text
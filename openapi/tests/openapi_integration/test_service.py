@@ -47,3 +47,18 @@ def test_telemetry():
     last_queried = datetime.fromisoformat(last_queried)
     # Assert today
     assert last_queried.date() == datetime.now().date()
+
+
+def test_collection_optimizations():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+
+    # Small collection needs no optimizations
+    optimizations = response.json()['result']['optimizations']
+    assert optimizations['running'] == []
+    assert optimizations['pending'] == []
+    assert 'last_error' not in optimizations
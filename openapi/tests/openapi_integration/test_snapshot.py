@@ -261,3 +261,93 @@ def test_snapshot_invalid_file_uri():
     )
     assert response.status_code == 400
     assert response.json()["status"]["error"] == "Bad request: Snapshot file \"/whatever.snapshot\" does not exist"
+
+
+def assert_snapshot_metadata(snapshot, with_collection_params):
+    assert isinstance(snapshot['version'], str) and snapshot['version']
+    assert snapshot['creation_duration_sec'] >= 0
+    assert snapshot['compatible'] is True
+    if with_collection_params:
+        assert snapshot['collection_params']['vectors']['size'] == 4
+        assert snapshot['collection_params']['vectors']['distance'] == 'Dot'
+    else:
+        assert 'collection_params' not in snapshot
+
+
+def test_snapshot_metadata():
+    # collection snapshot
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok
+    created = response.json()['result']
+    assert_snapshot_metadata(created, with_collection_params=True)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    listed = response.json()['result'][0]
+    assert listed['name'] == created['name']
+    assert listed['version'] == created['version']
+    assert listed['creation_duration_sec'] == created['creation_duration_sec']
+    assert_snapshot_metadata(listed, with_collection_params=True)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/snapshots/{snapshot_name}',
+        method="DELETE",
+        path_params={'collection_name': collection_name,
+                     'snapshot_name': created['name']},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok
+
+    # shard snapshot
+    response = request_with_validation(
+        api='/collections/{collection_name}/shards/{shard_id}/snapshots',
+        method="POST",
+        path_params={'shard_id': 0, 'collection_name': collection_name},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok
+    shard_snapshot = response.json()['result']
+    assert_snapshot_metadata(shard_snapshot, with_collection_params=True)
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/shards/{shard_id}/snapshots/{snapshot_name}',
+        method="DELETE",
+        path_params={'shard_id': 0, 'collection_name': collection_name,
+                     'snapshot_name': shard_snapshot['name']},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok
+
+    # full snapshot has no collection params
+    response = request_with_validation(
+        api='/snapshots',
+        method="POST",
+        query_params={'wait': 'true'},
+    )
+    assert response.ok
+    full_snapshot = response.json()['result']
+    assert_snapshot_metadata(full_snapshot, with_collection_params=False)
+
+    response = request_with_validation(
+        api='/snapshots/{snapshot_name}',
+        method="DELETE",
+        path_params={'snapshot_name': full_snapshot['name']},
+        query_params={'wait': 'true'},
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/snapshots',
+        method="GET",
+    )
+    assert response.ok
+    assert len(response.json()['result']) == 0
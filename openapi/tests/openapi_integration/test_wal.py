@@ -0,0 +1,110 @@
+import pytest
+
+from .helpers.collection_setup import basic_collection_setup, drop_collection
+from .helpers.helpers import request_with_validation
+
+collection_name = 'test_collection_wal'
+
+
+@pytest.fixture(autouse=True)
+def setup(on_disk_vectors):
+    basic_collection_setup(collection_name=collection_name, on_disk_vectors=on_disk_vectors)
+    yield
+    drop_collection(collection_name=collection_name)
+
+
+def get_wal_info():
+    response = request_with_validation(
+        api='/collections/{collection_name}/wal',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    return response.json()['result']
+
+
+def test_wal_info():
+    wal_info = get_wal_info()
+    assert [shard['shard_id'] for shard in wal_info] == [0]
+
+    shard = wal_info[0]
+    assert shard['first_stored_operation'] <= shard['first_replayed_operation'] <= shard['next_operation']
+    assert shard['retained_operations'] == shard['first_replayed_operation'] - shard['first_stored_operation']
+    assert shard['disk_size_bytes'] > 0
+    # Collection setup upserts points with a single operation
+    assert shard['next_operation'] == 1
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/points/delete',
+        method="POST",
+        path_params={'collection_name': collection_name},
+        query_params={'wait': 'true'},
+        body={"points": [1, 2]},
+    )
+    assert response.ok
+    assert get_wal_info()[0]['next_operation'] == 2
+
+
+def test_truncate_retained_wal():
+    response = request_with_validation(
+        api='/collections/{collection_name}/shards/{shard_id}/wal/truncate',
+        method="POST",
+        path_params={'collection_name': collection_name, 'shard_id': 0},
+    )
+    assert response.ok
+    shard = response.json()['result']
+    assert shard['shard_id'] == 0
+    assert shard['retained_operations'] == 0
+    # Operations, which are not flushed yet, are kept
+    assert shard['next_operation'] == 1
+    assert shard['first_replayed_operation'] <= 1
+
+    response = request_with_validation(
+        api='/collections/{collection_name}/shards/{shard_id}/wal/truncate',
+        method="POST",
+        path_params={'collection_name': collection_name, 'shard_id': 10},
+    )
+    assert response.status_code == 404
+
+
+def test_wal_retention_config():
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': collection_name},
+    )
+    assert response.ok
+    wal_config = response.json()['result']['config']['wal_config']
+    # No applied operations are kept by default
+    assert 'wal_retention_ops' not in wal_config
+    assert 'wal_retention_sec' not in wal_config
+    assert 'wal_retention_mb' not in wal_config
+
+    retention_collection_name = 'test_collection_wal_retention'
+    drop_collection(collection_name=retention_collection_name)
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="PUT",
+        path_params={'collection_name': retention_collection_name},
+        body={
+            "vectors": {"size": 4, "distance": "Dot"},
+            "wal_config": {
+                "wal_retention_ops": 1000,
+                "wal_retention_sec": 3600,
+                "wal_retention_mb": 10,
+            },
+        }
+    )
+    assert response.ok
+
+    response = request_with_validation(
+        api='/collections/{collection_name}',
+        method="GET",
+        path_params={'collection_name': retention_collection_name},
+    )
+    assert response.ok
+    wal_config = response.json()['result']['config']['wal_config']
+    assert wal_config['wal_retention_ops'] == 1000
+    assert wal_config['wal_retention_sec'] == 3600
+    assert wal_config['wal_retention_mb'] == 10
+    drop_collection(collection_name=retention_collection_name)
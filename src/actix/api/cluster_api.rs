@@ -1,14 +1,16 @@
 use actix_web::rt::time::Instant;
-use actix_web::{delete, get, post, web, Responder};
-use actix_web_validator::Query;
+use actix_web::{delete, get, post, put, web, Responder};
+use actix_web_validator::{Json, Query};
 use serde::Deserialize;
 use storage::content_manager::consensus_ops::ConsensusOperations;
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
+use storage::types::UpdatePeerAddress;
 use validator::Validate;
 
 use crate::actix::helpers::process_response;
+use crate::common::cluster::do_update_peer_address;
 
 #[derive(Debug, Deserialize, Validate)]
 struct QueryParams {
@@ -68,9 +70,29 @@ async fn remove_peer(
     process_response(response, timing)
 }
 
+#[put("/cluster/peer/{peer_id}")]
+async fn update_peer_address(
+    dispatcher: web::Data<Dispatcher>,
+    peer_id: web::Path<u64>,
+    operation: Json<UpdatePeerAddress>,
+    Query(params): Query<QueryParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let UpdatePeerAddress { uri } = operation.into_inner();
+    let response = do_update_peer_address(
+        dispatcher.get_ref(),
+        peer_id.into_inner(),
+        uri,
+        params.timeout.map(std::time::Duration::from_secs),
+    )
+    .await;
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_cluster_api(cfg: &mut web::ServiceConfig) {
     cfg.service(cluster_status)
         .service(remove_peer)
+        .service(update_peer_address)
         .service(recover_current_peer);
 }
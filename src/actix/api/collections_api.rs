@@ -4,6 +4,8 @@ use actix_web::rt::time::Instant;
 use actix_web::{delete, get, patch, post, put, web, Responder};
 use actix_web_validator::{Json, Path, Query};
 use collection::operations::cluster_ops::ClusterOperations;
+use collection::optimizers_builder::{OptimizeTarget, RebuildIndexTarget};
+use collection::shards::shard::ShardId;
 use serde::Deserialize;
 use storage::content_manager::collection_meta_ops::{
     ChangeAliasesOperation, CollectionMetaOperations, CreateCollection, CreateCollectionOperation,
@@ -13,7 +15,7 @@ use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
 use validator::Validate;
 
-use super::CollectionPath;
+use super::{CollectionPath, FieldPath};
 use crate::actix::api::StrictCollectionPath;
 use crate::actix::helpers::process_response;
 use crate::common::collections::*;
@@ -139,6 +141,17 @@ async fn update_aliases(
     process_response(response, timing)
 }
 
+#[get("/collections/{name}/index/{field_name}/stats")]
+async fn get_payload_index_stats(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    field: Path<FieldPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_payload_index_stats(toc.get_ref(), &collection.name, &field.name).await;
+    process_response(response, timing)
+}
+
 #[get("/collections/{name}/cluster")]
 async fn get_cluster_info(
     toc: web::Data<TableOfContent>,
@@ -170,6 +183,120 @@ async fn update_collection_cluster(
     process_response(response, timing)
 }
 
+#[get("/collections/{name}/segments")]
+async fn get_segments_info(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_segments_info(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/wal")]
+async fn get_wal_info(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_wal_info(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/shards/{shard}/wal/truncate")]
+async fn truncate_wal(
+    toc: web::Data<TableOfContent>,
+    path: web::Path<(String, ShardId)>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let (collection_name, shard_id) = path.into_inner();
+    let response = do_truncate_retained_wal(toc.get_ref(), &collection_name, shard_id).await;
+    process_response(response, timing)
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct OptimizeParam {
+    target: Option<OptimizeTarget>,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+struct RebuildIndexParam {
+    #[serde(default)]
+    target: RebuildIndexTarget,
+    #[validate(length(min = 1))]
+    field_name: Option<String>,
+}
+
+#[derive(Deserialize, Validate)]
+struct OptimizeTaskPath {
+    #[validate(length(min = 1))]
+    task_id: String,
+}
+
+#[post("/collections/{name}/optimize")]
+async fn optimize_collection(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    params: Query<OptimizeParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_optimize_collection(toc.get_ref(), &collection.name, params.target).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/index/rebuild")]
+async fn rebuild_index(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    params: Query<RebuildIndexParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let RebuildIndexParam { target, field_name } = params.into_inner();
+    let response = do_rebuild_index(toc.get_ref(), &collection.name, target, field_name).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/optimizers/pause")]
+async fn pause_optimizers(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_set_optimizers_paused(toc.get_ref(), &collection.name, true).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/optimizers/resume")]
+async fn resume_optimizers(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_set_optimizers_paused(toc.get_ref(), &collection.name, false).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/optimize_tasks")]
+async fn get_optimize_tasks(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_optimize_tasks(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/optimize_tasks/{task_id}")]
+async fn get_optimize_task(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    task: Path<OptimizeTaskPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_optimize_task(toc.get_ref(), &collection.name, &task.task_id).await;
+    process_response(response, timing)
+}
+
 // Configure services
 pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
     cfg.service(get_collections)
@@ -180,8 +307,18 @@ pub fn config_collections_api(cfg: &mut web::ServiceConfig) {
         .service(get_aliases)
         .service(get_collection_aliases)
         .service(update_aliases)
+        .service(get_payload_index_stats)
         .service(get_cluster_info)
-        .service(update_collection_cluster);
+        .service(update_collection_cluster)
+        .service(get_segments_info)
+        .service(get_wal_info)
+        .service(truncate_wal)
+        .service(optimize_collection)
+        .service(rebuild_index)
+        .service(get_optimize_tasks)
+        .service(get_optimize_task)
+        .service(pause_optimizers)
+        .service(resume_optimizers);
 }
 
 #[cfg(test)]
@@ -1,23 +1,48 @@
 use actix_web::rt::time::Instant;
 use actix_web::{post, web, Responder};
-use actix_web_validator::{Json, Path};
-use collection::operations::types::CountRequest;
+use actix_web_validator::{Json, Path, Query};
+use collection::operations::types::{CountRequest, CountRequestBatch};
 use storage::content_manager::toc::TableOfContent;
 
+use super::read_params::TimeoutParams;
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
-use crate::common::points::do_count_points;
+use crate::common::points::{do_count_batch_points, do_count_points, with_read_timeout};
 
 #[post("/collections/{name}/points/count")]
 async fn count_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     request: Json<CountRequest>,
+    timeout_params: Query<TimeoutParams>,
 ) -> impl Responder {
     let timing = Instant::now();
 
-    let response =
-        do_count_points(toc.get_ref(), &collection.name, request.into_inner(), None).await;
+    let response = with_read_timeout(
+        timeout_params.timeout(),
+        "Count",
+        do_count_points(toc.get_ref(), &collection.name, request.into_inner(), None),
+    )
+    .await;
+
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/count/batch")]
+async fn count_batch_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<CountRequestBatch>,
+    timeout_params: Query<TimeoutParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response = with_read_timeout(
+        timeout_params.timeout(),
+        "Count",
+        do_count_batch_points(toc.get_ref(), &collection.name, request.into_inner(), None),
+    )
+    .await;
 
     process_response(response, timing)
 }
@@ -1,6 +1,7 @@
 pub mod cluster_api;
 pub mod collections_api;
 pub mod count_api;
+pub mod query_api;
 pub mod read_params;
 pub mod recommend_api;
 pub mod retrieve_api;
@@ -34,3 +35,10 @@ struct CollectionPath {
     #[validate(length(min = 1, max = 255))]
     name: String,
 }
+
+#[derive(Deserialize, Validate)]
+struct FieldPath {
+    #[serde(rename = "field_name")]
+    #[validate(length(min = 1))]
+    name: String,
+}
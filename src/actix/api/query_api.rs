@@ -0,0 +1,45 @@
+use actix_web::rt::time::Instant;
+use actix_web::{post, web, Responder};
+use actix_web_validator::{Json, Path, Query};
+use collection::operations::types::QueryRequest;
+use storage::content_manager::toc::TableOfContent;
+
+use super::read_params::ReadParams;
+use super::CollectionPath;
+use crate::actix::api_key::check_lookup_access;
+use crate::actix::helpers::process_response;
+use crate::common::auth::CollectionAccessCheck;
+use crate::common::points::do_query_points;
+
+#[post("/collections/{name}/points/query")]
+async fn query_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<QueryRequest>,
+    params: Query<ReadParams>,
+    access: Option<web::ReqData<CollectionAccessCheck>>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    if let Err(response) =
+        check_lookup_access(toc.get_ref(), access, &collection.name, &*request).await
+    {
+        return response;
+    }
+
+    let response = do_query_points(
+        toc.get_ref(),
+        &collection.name,
+        request.into_inner(),
+        params.consistency,
+        None,
+    )
+    .await;
+
+    process_response(response, timing)
+}
+
+// Configure services
+pub fn config_query_api(cfg: &mut web::ServiceConfig) {
+    cfg.service(query_points);
+}
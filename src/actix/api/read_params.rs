@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use collection::operations::consistency_params::ReadConsistency;
 use schemars::JsonSchema;
+use segment::common::search_explain::ExplainMode;
 use serde::Deserialize;
 use validator::Validate;
 
@@ -10,6 +13,63 @@ pub struct ReadParams {
     pub consistency: Option<ReadConsistency>,
 }
 
+/// Timeout of a read request
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, JsonSchema, Validate)]
+pub struct TimeoutParams {
+    /// Time to wait for the result, in seconds
+    #[validate(range(min = 1))]
+    pub timeout: Option<u64>,
+    /// Search only: return the results of the shards and segments, which were searched in time,
+    /// instead of a timeout error
+    #[serde(default)]
+    pub partial: bool,
+}
+
+impl TimeoutParams {
+    pub fn timeout(&self) -> Option<Duration> {
+        self.timeout.map(Duration::from_secs)
+    }
+}
+
+/// Measurement of the resources, used by a search request
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, JsonSchema, Validate)]
+pub struct UsageParams {
+    /// Return the resources used to process the request in the `usage` field of the response
+    #[serde(default)]
+    pub with_usage: bool,
+}
+
+/// Explanation of the strategies of a search request
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, JsonSchema, Validate)]
+pub struct ExplainParams {
+    /// Return the strategies of the searches in the segments in the `explain` field of the response.
+    /// With `plan` the search is not executed and no points are returned
+    pub explain: Option<ExplainMode>,
+}
+
+/// Format of the points in the response of a read request
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputFormat {
+    /// Usual JSON response
+    #[default]
+    Json,
+    /// Columns of the points in the Arrow IPC streaming format
+    Arrow,
+}
+
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Deserialize, JsonSchema, Validate)]
+pub struct OutputParams {
+    #[serde(default)]
+    pub format: OutputFormat,
+}
+
+impl OutputParams {
+    pub fn is_arrow(&self) -> bool {
+        self.format == OutputFormat::Arrow
+    }
+}
+
 fn deserialize_read_consistency<'de, D>(
     deserializer: D,
 ) -> Result<Option<ReadConsistency>, D::Error>
@@ -67,6 +127,48 @@ mod test {
         assert!(try_deserialize(&str("0")).is_err());
     }
 
+    #[test]
+    fn deserialize_timeout() {
+        let params: TimeoutParams = serde_urlencoded::from_str("timeout=5&partial=true").unwrap();
+        assert_eq!(params.timeout(), Some(Duration::from_secs(5)));
+        assert!(params.partial);
+
+        // Consistency and timeout are read from the same query string
+        let params: TimeoutParams = serde_urlencoded::from_str("consistency=all").unwrap();
+        assert_eq!(params, TimeoutParams::default());
+    }
+
+    #[test]
+    fn deserialize_output_format() {
+        let params: OutputParams = serde_urlencoded::from_str("format=arrow").unwrap();
+        assert!(params.is_arrow());
+
+        let params: OutputParams = serde_urlencoded::from_str("consistency=all").unwrap();
+        assert_eq!(params.format, OutputFormat::Json);
+
+        assert!(serde_urlencoded::from_str::<OutputParams>("format=csv").is_err());
+    }
+
+    #[test]
+    fn deserialize_with_usage() {
+        let params: UsageParams = serde_urlencoded::from_str("with_usage=true").unwrap();
+        assert!(params.with_usage);
+
+        let params: UsageParams = serde_urlencoded::from_str("consistency=all").unwrap();
+        assert!(!params.with_usage);
+    }
+
+    #[test]
+    fn deserialize_explain() {
+        let params: ExplainParams = serde_urlencoded::from_str("explain=analyze").unwrap();
+        assert_eq!(params.explain, Some(ExplainMode::Analyze));
+
+        let params: ExplainParams = serde_urlencoded::from_str("with_usage=true").unwrap();
+        assert_eq!(params.explain, None);
+
+        assert!(serde_urlencoded::from_str::<ExplainParams>("explain=full").is_err());
+    }
+
     fn test(value: &str, params: ReadParams) {
         test_str(&str(value), params);
     }
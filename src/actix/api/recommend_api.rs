@@ -9,9 +9,12 @@ use segment::types::ScoredPoint;
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 
-use super::read_params::ReadParams;
+use super::read_params::{ReadParams, TimeoutParams};
 use super::CollectionPath;
+use crate::actix::api_key::check_lookup_access;
 use crate::actix::helpers::process_response;
+use crate::common::auth::CollectionAccessCheck;
+use crate::common::points::with_read_timeout;
 
 async fn do_recommend_points(
     toc: &TableOfContent,
@@ -29,14 +32,26 @@ async fn recommend_points(
     collection: Path<CollectionPath>,
     request: Json<RecommendRequest>,
     params: Query<ReadParams>,
+    timeout_params: Query<TimeoutParams>,
+    access: Option<web::ReqData<CollectionAccessCheck>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
-    let response = do_recommend_points(
-        toc.get_ref(),
-        &collection.name,
-        request.into_inner(),
-        params.consistency,
+    if let Err(response) =
+        check_lookup_access(toc.get_ref(), access, &collection.name, &*request).await
+    {
+        return response;
+    }
+
+    let response = with_read_timeout(
+        timeout_params.timeout(),
+        "Recommend",
+        do_recommend_points(
+            toc.get_ref(),
+            &collection.name,
+            request.into_inner(),
+            params.consistency,
+        ),
     )
     .await;
 
@@ -59,14 +74,26 @@ async fn recommend_batch_points(
     collection: Path<CollectionPath>,
     request: Json<RecommendRequestBatch>,
     params: Query<ReadParams>,
+    timeout_params: Query<TimeoutParams>,
+    access: Option<web::ReqData<CollectionAccessCheck>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
-    let response = do_recommend_batch_points(
-        toc.get_ref(),
-        &collection.name,
-        request.into_inner(),
-        params.consistency,
+    if let Err(response) =
+        check_lookup_access(toc.get_ref(), access, &collection.name, &*request).await
+    {
+        return response;
+    }
+
+    let response = with_read_timeout(
+        timeout_params.timeout(),
+        "Recommend",
+        do_recommend_batch_points(
+            toc.get_ref(),
+            &collection.name,
+            request.into_inner(),
+            params.consistency,
+        ),
     )
     .await;
 
@@ -79,9 +106,16 @@ async fn recommend_point_groups(
     collection: Path<CollectionPath>,
     request: Json<RecommendGroupsRequest>,
     params: Query<ReadParams>,
+    access: Option<web::ReqData<CollectionAccessCheck>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
+    if let Err(response) =
+        check_lookup_access(toc.get_ref(), access, &collection.name, &*request).await
+    {
+        return response;
+    }
+
     let response = crate::common::points::do_recommend_point_groups(
         toc.get_ref(),
         &collection.name,
@@ -9,10 +9,11 @@ use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use validator::Validate;
 
-use super::read_params::ReadParams;
+use super::read_params::{OutputParams, ReadParams, TimeoutParams};
 use super::CollectionPath;
+use crate::actix::arrow_format::process_arrow_response;
 use crate::actix::helpers::process_response;
-use crate::common::points::do_get_points;
+use crate::common::points::{do_get_points, with_read_timeout};
 
 #[derive(Deserialize, Validate)]
 struct PointPath {
@@ -54,6 +55,7 @@ async fn get_point(
     collection: Path<CollectionPath>,
     point: Path<PointPath>,
     params: Query<ReadParams>,
+    timeout_params: Query<TimeoutParams>,
 ) -> impl Responder {
     let timing = Instant::now();
 
@@ -70,11 +72,15 @@ async fn get_point(
         }
     };
 
-    let response = do_get_point(
-        toc.get_ref(),
-        &collection.name,
-        point_id,
-        params.consistency,
+    let response = with_read_timeout(
+        timeout_params.timeout(),
+        "Retrieve",
+        do_get_point(
+            toc.get_ref(),
+            &collection.name,
+            point_id,
+            params.consistency,
+        ),
     )
     .await;
 
@@ -96,17 +102,26 @@ async fn get_points(
     collection: Path<CollectionPath>,
     request: Json<PointRequest>,
     params: Query<ReadParams>,
+    timeout_params: Query<TimeoutParams>,
+    output_params: Query<OutputParams>,
 ) -> impl Responder {
     let timing = Instant::now();
 
-    let response = do_get_points(
-        toc.get_ref(),
-        &collection.name,
-        request.into_inner(),
-        params.consistency,
-        None,
+    let response = with_read_timeout(
+        timeout_params.timeout(),
+        "Retrieve",
+        do_get_points(
+            toc.get_ref(),
+            &collection.name,
+            request.into_inner(),
+            params.consistency,
+            None,
+        ),
     )
     .await;
+    if output_params.is_arrow() {
+        return process_arrow_response(response, timing);
+    }
     process_response(response, timing)
 }
 
@@ -116,15 +131,24 @@ async fn scroll_points(
     collection: Path<CollectionPath>,
     request: Json<ScrollRequest>,
     params: Query<ReadParams>,
+    timeout_params: Query<TimeoutParams>,
+    output_params: Query<OutputParams>,
 ) -> impl Responder {
     let timing = Instant::now();
 
-    let response = scroll_get_points(
-        toc.get_ref(),
-        &collection.name,
-        request.into_inner(),
-        params.consistency,
+    let response = with_read_timeout(
+        timeout_params.timeout(),
+        "Scroll",
+        scroll_get_points(
+            toc.get_ref(),
+            &collection.name,
+            request.into_inner(),
+            params.consistency,
+        ),
     )
     .await;
+    if output_params.is_arrow() {
+        return process_arrow_response(response, timing);
+    }
     process_response(response, timing)
 }
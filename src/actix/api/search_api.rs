@@ -1,13 +1,23 @@
 use actix_web::rt::time::Instant;
 use actix_web::{post, web, Responder};
 use actix_web_validator::{Json, Path, Query};
-use collection::operations::types::{SearchGroupsRequest, SearchRequest, SearchRequestBatch};
+use collection::common::hardware_usage::collect_hardware_usage;
+use collection::common::query_explain::collect_search_explain;
+use collection::operations::types::{
+    CollectionsSearchRequestBatch, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
+};
 use storage::content_manager::toc::TableOfContent;
 
-use super::read_params::ReadParams;
+use super::read_params::{ExplainParams, OutputParams, ReadParams, TimeoutParams, UsageParams};
 use super::CollectionPath;
-use crate::actix::helpers::process_response;
-use crate::common::points::{do_search_batch_points, do_search_point_groups, do_search_points};
+use crate::actix::api_key::check_lookup_access;
+use crate::actix::arrow_format::process_arrow_response;
+use crate::actix::helpers::{process_partial_response, process_response};
+use crate::common::auth::CollectionAccessCheck;
+use crate::common::points::{
+    do_search_batch_collections, do_search_batch_points, do_search_batch_points_with_timeout,
+    do_search_point_groups, do_search_points, with_read_timeout,
+};
 
 #[post("/collections/{name}/points/search")]
 async fn search_points(
@@ -15,19 +25,58 @@ async fn search_points(
     collection: Path<CollectionPath>,
     request: Json<SearchRequest>,
     params: Query<ReadParams>,
+    timeout_params: Query<TimeoutParams>,
+    output_params: Query<OutputParams>,
+    usage_params: Query<UsageParams>,
+    explain_params: Query<ExplainParams>,
 ) -> impl Responder {
     let timing = Instant::now();
 
-    let response = do_search_points(
-        toc.get_ref(),
-        &collection.name,
-        request.into_inner(),
-        params.consistency,
-        None,
+    let ((response, usage), explain) = collect_search_explain(
+        explain_params.explain,
+        collect_hardware_usage(usage_params.with_usage, async {
+            if let Some(timeout) = timeout_params.timeout().filter(|_| timeout_params.partial) {
+                let request = SearchRequestBatch {
+                    searches: vec![request.into_inner()],
+                };
+                return do_search_batch_points_with_timeout(
+                    toc.get_ref(),
+                    &collection.name,
+                    request,
+                    params.consistency,
+                    None,
+                    timeout,
+                    true,
+                )
+                .await
+                .map(|res| {
+                    let points = res.results.into_iter().next().unwrap_or_default();
+                    (points, Some(res.partial))
+                });
+            }
+
+            with_read_timeout(
+                timeout_params.timeout(),
+                "Search",
+                do_search_points(
+                    toc.get_ref(),
+                    &collection.name,
+                    request.into_inner(),
+                    params.consistency,
+                    None,
+                ),
+            )
+            .await
+            .map(|points| (points, None))
+        }),
     )
     .await;
 
-    process_response(response, timing)
+    if output_params.is_arrow() {
+        let response = response.map(|(points, partial)| (points, partial, usage, explain));
+        return process_arrow_response(response, timing);
+    }
+    process_partial_response(response, timing, usage, explain)
 }
 
 #[post("/collections/{name}/points/search/batch")]
@@ -36,18 +85,60 @@ async fn batch_search_points(
     collection: Path<CollectionPath>,
     request: Json<SearchRequestBatch>,
     params: Query<ReadParams>,
+    timeout_params: Query<TimeoutParams>,
+    usage_params: Query<UsageParams>,
+    explain_params: Query<ExplainParams>,
 ) -> impl Responder {
     let timing = Instant::now();
 
-    let response = do_search_batch_points(
-        toc.get_ref(),
-        &collection.name,
-        request.into_inner(),
-        params.consistency,
-        None,
+    let ((response, usage), explain) = collect_search_explain(
+        explain_params.explain,
+        collect_hardware_usage(usage_params.with_usage, async {
+            if let Some(timeout) = timeout_params.timeout().filter(|_| timeout_params.partial) {
+                return do_search_batch_points_with_timeout(
+                    toc.get_ref(),
+                    &collection.name,
+                    request.into_inner(),
+                    params.consistency,
+                    None,
+                    timeout,
+                    true,
+                )
+                .await
+                .map(|res| (res.results, Some(res.partial)));
+            }
+
+            with_read_timeout(
+                timeout_params.timeout(),
+                "Search",
+                do_search_batch_points(
+                    toc.get_ref(),
+                    &collection.name,
+                    request.into_inner(),
+                    params.consistency,
+                    None,
+                ),
+            )
+            .await
+            .map(|points| (points, None))
+        }),
     )
     .await;
 
+    process_partial_response(response, timing, usage, explain)
+}
+
+#[post("/collections/search/batch")]
+async fn batch_search_collections(
+    toc: web::Data<TableOfContent>,
+    request: Json<CollectionsSearchRequestBatch>,
+    params: Query<ReadParams>,
+) -> impl Responder {
+    let timing = Instant::now();
+
+    let response =
+        do_search_batch_collections(toc.get_ref(), request.into_inner(), params.consistency).await;
+
     process_response(response, timing)
 }
 
@@ -57,9 +148,16 @@ async fn search_point_groups(
     collection: Path<CollectionPath>,
     request: Json<SearchGroupsRequest>,
     params: Query<ReadParams>,
+    access: Option<web::ReqData<CollectionAccessCheck>>,
 ) -> impl Responder {
     let timing = Instant::now();
 
+    if let Err(response) =
+        check_lookup_access(toc.get_ref(), access, &collection.name, &*request).await
+    {
+        return response;
+    }
+
     let response = do_search_point_groups(
         toc.get_ref(),
         &collection.name,
@@ -76,5 +174,6 @@ async fn search_point_groups(
 pub fn config_search_api(cfg: &mut web::ServiceConfig) {
     cfg.service(search_points)
         .service(batch_search_points)
+        .service(batch_search_collections)
         .service(search_point_groups);
 }
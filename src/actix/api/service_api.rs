@@ -3,6 +3,7 @@ use actix_web::rt::time::Instant;
 use actix_web::web::Query;
 use actix_web::{get, post, web, HttpResponse, Responder};
 use actix_web_validator::Json;
+use common::types::TelemetryDetail;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
@@ -19,6 +20,7 @@ use crate::common::telemetry::TelemetryCollector;
 pub struct TelemetryParam {
     pub anonymize: Option<bool>,
     pub details_level: Option<usize>,
+    pub histograms: Option<bool>,
 }
 
 #[get("/telemetry")]
@@ -28,9 +30,12 @@ async fn telemetry(
 ) -> impl Responder {
     let timing = Instant::now();
     let anonymize = params.anonymize.unwrap_or(false);
-    let details_level = params.details_level.unwrap_or(0);
+    let detail = TelemetryDetail::new(
+        params.details_level.unwrap_or(0).into(),
+        params.histograms.unwrap_or(false),
+    );
     let telemetry_collector = telemetry_collector.lock().await;
-    let telemetry_data = telemetry_collector.prepare_data(details_level).await;
+    let telemetry_data = telemetry_collector.prepare_data(detail).await;
     let telemetry_data = if anonymize {
         telemetry_data.anonymize()
     } else {
@@ -50,8 +55,8 @@ async fn metrics(
     params: Query<MetricsParam>,
 ) -> impl Responder {
     let anonymize = params.anonymize.unwrap_or(false);
-    let telemetry_collector = telemetry_collector.lock().await;
-    let telemetry_data = telemetry_collector.prepare_data(1).await;
+    let mut telemetry_collector = telemetry_collector.lock().await;
+    let telemetry_data = telemetry_collector.prepare_metrics_data().await;
     let telemetry_data = if anonymize {
         telemetry_data.anonymize()
     } else {
@@ -88,6 +93,13 @@ async fn get_locks(toc: web::Data<TableOfContent>) -> impl Responder {
     process_response(Ok(result), timing)
 }
 
+#[get("/slow_queries")]
+async fn get_slow_queries(toc: web::Data<TableOfContent>) -> impl Responder {
+    let timing = Instant::now();
+    let result = toc.get_ref().slow_queries();
+    process_response(Ok(result), timing)
+}
+
 #[get("/stacktrace")]
 async fn get_stacktrace() -> impl Responder {
     let timing = Instant::now();
@@ -123,6 +135,7 @@ pub fn config_service_api(cfg: &mut web::ServiceConfig) {
         .service(metrics)
         .service(put_locks)
         .service(get_locks)
+        .service(get_slow_queries)
         .service(get_stacktrace)
         .service(healthz)
         .service(livez)
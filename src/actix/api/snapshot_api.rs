@@ -9,8 +9,11 @@ use actix_web::{delete, get, post, put, web, Responder, Result};
 use actix_web_validator as valid;
 use collection::collection::Collection;
 use collection::common::file_utils::move_file;
+use collection::common::snapshot_retention::SnapshotRetention;
+use collection::common::snapshot_schedule::SnapshotSchedule;
 use collection::operations::snapshot_ops::{
-    ShardSnapshotLocation, ShardSnapshotRecover, SnapshotPriority, SnapshotRecover,
+    remove_snapshot, verify_snapshot_checksum, PointInTimeRecover, ShardSnapshotLocation,
+    ShardSnapshotRecover, SnapshotPriority, SnapshotRecover,
 };
 use collection::shards::replica_set::ReplicaState;
 use collection::shards::shard::ShardId;
@@ -18,7 +21,12 @@ use reqwest::Url;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use storage::content_manager::errors::StorageError;
-use storage::content_manager::snapshots::recover::{activate_shard, do_recover_from_snapshot};
+use storage::content_manager::snapshots::cluster_backup::{
+    do_create_cluster_backup, do_get_cluster_backup, do_list_cluster_backups,
+};
+use storage::content_manager::snapshots::recover::{
+    activate_shard, do_recover_from_snapshot, do_recover_to_point_in_time,
+};
 use storage::content_manager::snapshots::{
     self, do_create_full_snapshot, do_delete_collection_snapshot, do_delete_full_snapshot,
     do_list_full_snapshots, get_full_snapshot_path,
@@ -47,6 +55,8 @@ struct SnapshotPath {
 pub struct SnapshotUploadingParam {
     pub wait: Option<bool>,
     pub priority: Option<SnapshotPriority>,
+    /// SHA256 checksum of the uploaded snapshot, to verify it before recovery
+    pub checksum: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
@@ -54,11 +64,26 @@ pub struct SnapshottingParam {
     pub wait: Option<bool>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct CollectionSnapshottingParam {
+    pub wait: Option<bool>,
+    /// Create an incremental snapshot on top of this collection snapshot
+    pub base: Option<String>,
+}
+
 #[derive(MultipartForm)]
 pub struct SnapshottingForm {
     snapshot: TempFile,
 }
 
+#[derive(MultipartForm)]
+pub struct CollectionSnapshottingForm {
+    snapshot: TempFile,
+    /// Base snapshots of an incremental snapshot, uploaded with their original names
+    #[multipart(rename = "bases")]
+    bases: Vec<TempFile>,
+}
+
 // Actix specific code
 pub async fn do_get_full_snapshot(toc: &TableOfContent, snapshot_name: &str) -> Result<NamedFile> {
     let file_name = get_full_snapshot_path(toc, snapshot_name)
@@ -133,13 +158,19 @@ async fn list_snapshots(toc: web::Data<TableOfContent>, path: web::Path<String>)
 async fn create_snapshot(
     dispatcher: web::Data<Dispatcher>,
     path: web::Path<String>,
-    params: valid::Query<SnapshottingParam>,
+    params: valid::Query<CollectionSnapshottingParam>,
 ) -> impl Responder {
     let collection_name = path.into_inner();
     let wait = params.wait.unwrap_or(true);
 
     let timing = Instant::now();
-    let response = do_create_snapshot(dispatcher.get_ref(), &collection_name, wait).await;
+    let response = do_create_snapshot(
+        dispatcher.get_ref(),
+        &collection_name,
+        params.base.as_deref(),
+        wait,
+    )
+    .await;
     match response {
         Err(_) => process_response(response, timing),
         Ok(_) if wait => process_response(response, timing),
@@ -151,13 +182,30 @@ async fn create_snapshot(
 async fn upload_snapshot(
     dispatcher: web::Data<Dispatcher>,
     collection: valid::Path<CollectionPath>,
-    MultipartForm(form): MultipartForm<SnapshottingForm>,
+    MultipartForm(form): MultipartForm<CollectionSnapshottingForm>,
     params: valid::Query<SnapshotUploadingParam>,
 ) -> impl Responder {
     let timing = Instant::now();
-    let snapshot = form.snapshot;
+    let CollectionSnapshottingForm { snapshot, bases } = form;
     let wait = params.wait.unwrap_or(true);
 
+    // Bases are saved next to the snapshot, where its restore looks them up
+    for base in bases {
+        if base.file_name.is_none() {
+            return process_response::<()>(
+                Err(StorageError::bad_input(
+                    "Base snapshot must be uploaded with its file name",
+                )),
+                timing,
+            );
+        }
+        if let Err(err) =
+            do_save_uploaded_snapshot(dispatcher.get_ref(), &collection.name, base).await
+        {
+            return process_response::<()>(Err(err), timing);
+        }
+    }
+
     let snapshot_location =
         match do_save_uploaded_snapshot(dispatcher.get_ref(), &collection.name, snapshot).await {
             Ok(location) => location,
@@ -167,6 +215,7 @@ async fn upload_snapshot(
     let snapshot_recover = SnapshotRecover {
         location: snapshot_location,
         priority: params.priority,
+        checksum: params.checksum.clone(),
     };
 
     let response = do_recover_from_snapshot(
@@ -208,6 +257,40 @@ async fn recover_from_snapshot(
     }
 }
 
+#[put("/collections/{name}/snapshots/recover_point_in_time")]
+async fn recover_point_in_time(
+    dispatcher: web::Data<Dispatcher>,
+    collection: valid::Path<CollectionPath>,
+    request: valid::Json<PointInTimeRecover>,
+    params: valid::Query<SnapshottingParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let wait = params.wait.unwrap_or(true);
+
+    let response = do_recover_to_point_in_time(
+        dispatcher.get_ref(),
+        &collection.name,
+        request.into_inner(),
+        wait,
+    )
+    .await;
+    match response {
+        Err(_) => process_response(response, timing),
+        Ok(_) if wait => process_response(response, timing),
+        Ok(_) => accepted_response(timing),
+    }
+}
+
+#[get("/collections/{name}/recovery_windows")]
+async fn list_recovery_windows(
+    toc: web::Data<TableOfContent>,
+    collection: valid::Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_list_recovery_windows(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
 #[get("/collections/{name}/snapshots/{snapshot_name}")]
 async fn get_snapshot(
     toc: web::Data<TableOfContent>,
@@ -238,6 +321,38 @@ async fn create_full_snapshot(
     }
 }
 
+#[get("/cluster/backups")]
+async fn list_cluster_backups(toc: web::Data<TableOfContent>) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_list_cluster_backups(toc.get_ref()).await;
+    process_response(response, timing)
+}
+
+#[post("/cluster/backups")]
+async fn create_cluster_backup(
+    dispatcher: web::Data<Dispatcher>,
+    params: valid::Query<SnapshottingParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let wait = params.wait.unwrap_or(true);
+    let response = do_create_cluster_backup(dispatcher.get_ref(), wait).await;
+    match response {
+        Err(_) => process_response(response, timing),
+        Ok(_) if wait => process_response(response, timing),
+        Ok(_) => accepted_response(timing),
+    }
+}
+
+#[get("/cluster/backups/{backup_name}")]
+async fn get_cluster_backup(
+    toc: web::Data<TableOfContent>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_cluster_backup(toc.get_ref(), &path.into_inner()).await;
+    process_response(response, timing)
+}
+
 #[get("/snapshots/{snapshot_name}")]
 async fn get_full_snapshot(
     toc: web::Data<TableOfContent>,
@@ -264,6 +379,17 @@ async fn delete_full_snapshot(
     }
 }
 
+#[post("/collections/{name}/snapshots/{snapshot_name}/verify")]
+async fn verify_collection_snapshot(
+    toc: web::Data<TableOfContent>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    let (collection_name, snapshot_name) = path.into_inner();
+    let timing = Instant::now();
+    let response = do_verify_snapshot(toc.get_ref(), &collection_name, &snapshot_name).await;
+    process_response(response, timing)
+}
+
 #[delete("/collections/{name}/snapshots/{snapshot_name}")]
 async fn delete_collection_snapshot(
     dispatcher: web::Data<Dispatcher>,
@@ -283,6 +409,75 @@ async fn delete_collection_snapshot(
     }
 }
 
+#[get("/collections/{name}/snapshot_schedule")]
+async fn get_snapshot_schedule(
+    toc: web::Data<TableOfContent>,
+    collection: valid::Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_snapshot_schedule(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[put("/collections/{name}/snapshot_schedule")]
+async fn set_snapshot_schedule(
+    toc: web::Data<TableOfContent>,
+    collection: valid::Path<CollectionPath>,
+    schedule: valid::Json<SnapshotSchedule>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response =
+        do_set_snapshot_schedule(toc.get_ref(), &collection.name, Some(schedule.into_inner()))
+            .await;
+    process_response(response, timing)
+}
+
+#[delete("/collections/{name}/snapshot_schedule")]
+async fn delete_snapshot_schedule(
+    toc: web::Data<TableOfContent>,
+    collection: valid::Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_set_snapshot_schedule(toc.get_ref(), &collection.name, None).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/snapshot_retention")]
+async fn get_snapshot_retention(
+    toc: web::Data<TableOfContent>,
+    collection: valid::Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_snapshot_retention(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[put("/collections/{name}/snapshot_retention")]
+async fn set_snapshot_retention(
+    toc: web::Data<TableOfContent>,
+    collection: valid::Path<CollectionPath>,
+    retention: valid::Json<SnapshotRetention>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_set_snapshot_retention(
+        toc.get_ref(),
+        &collection.name,
+        Some(retention.into_inner()),
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[delete("/collections/{name}/snapshot_retention")]
+async fn delete_snapshot_retention(
+    toc: web::Data<TableOfContent>,
+    collection: valid::Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_set_snapshot_retention(toc.get_ref(), &collection.name, None).await;
+    process_response(response, timing)
+}
+
 #[get("/collections/{collection}/shards/{shard}/snapshots")]
 async fn list_shard_snapshots(
     toc: web::Data<TableOfContent>,
@@ -334,7 +529,7 @@ async fn recover_shard_snapshot(
 
         let snapshot_path = match request.location {
             ShardSnapshotLocation::Url(url) => {
-                if !matches!(url.scheme(), "http" | "https") {
+                if !matches!(url.scheme(), "http" | "https" | "s3") {
                     let description = format!(
                         "Invalid snapshot URL {url}: URLs with {} scheme are not supported",
                         url.scheme(),
@@ -342,7 +537,12 @@ async fn recover_shard_snapshot(
 
                     return Err(StorageError::bad_input(description).into());
                 }
-                snapshots::download::download_snapshot(url, download_dir.path()).await?
+                snapshots::download::download_snapshot(
+                    url,
+                    download_dir.path(),
+                    toc.snapshots_s3_config(),
+                )
+                .await?
             }
 
             ShardSnapshotLocation::Path(path) => {
@@ -352,6 +552,10 @@ async fn recover_shard_snapshot(
             }
         };
 
+        if let Some(checksum) = request.checksum {
+            verify_snapshot_checksum(&snapshot_path, &checksum).await?;
+        }
+
         recover_shard_snapshot_impl(
             &toc,
             &collection,
@@ -375,13 +579,21 @@ async fn upload_shard_snapshot(
     query: web::Query<SnapshotUploadingParam>,
     MultipartForm(form): MultipartForm<SnapshottingForm>,
 ) -> impl Responder {
-    let SnapshotUploadingParam { wait, priority } = query.into_inner();
+    let SnapshotUploadingParam {
+        wait,
+        priority,
+        checksum,
+    } = query.into_inner();
 
     let future = async move {
         let (collection, shard) = path.into_inner();
         let collection = toc.get_collection(&collection).await?;
         collection.assert_shard_exists(shard).await?;
 
+        if let Some(checksum) = checksum {
+            verify_snapshot_checksum(form.snapshot.file.path(), &checksum).await?;
+        }
+
         recover_shard_snapshot_impl(
             &toc,
             &collection,
@@ -421,7 +633,7 @@ async fn delete_shard_snapshot(
         let snapshot_path = collection.get_shard_snapshot_path(shard, &snapshot).await?;
 
         check_shard_snapshot_file_exists(&snapshot_path)?;
-        std::fs::remove_file(&snapshot_path)?;
+        remove_snapshot(&snapshot_path).await?;
 
         Ok(true)
     };
@@ -523,12 +735,24 @@ pub fn config_snapshots_api(cfg: &mut web::ServiceConfig) {
         .service(create_snapshot)
         .service(upload_snapshot)
         .service(recover_from_snapshot)
+        .service(recover_point_in_time)
+        .service(list_recovery_windows)
         .service(get_snapshot)
         .service(list_full_snapshots)
         .service(create_full_snapshot)
         .service(get_full_snapshot)
         .service(delete_full_snapshot)
+        .service(list_cluster_backups)
+        .service(create_cluster_backup)
+        .service(get_cluster_backup)
         .service(delete_collection_snapshot)
+        .service(verify_collection_snapshot)
+        .service(get_snapshot_schedule)
+        .service(set_snapshot_schedule)
+        .service(delete_snapshot_schedule)
+        .service(get_snapshot_retention)
+        .service(set_snapshot_retention)
+        .service(delete_snapshot_retention)
         .service(list_shard_snapshots)
         .service(create_shard_snapshot)
         .service(recover_shard_snapshot)
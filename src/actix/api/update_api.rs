@@ -1,51 +1,111 @@
+use std::mem;
+
+use actix_web::http::header;
 use actix_web::rt::time::Instant;
-use actix_web::{delete, post, put, web, Responder};
+use actix_web::web::Bytes;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Responder};
 use actix_web_validator::{Json, Path, Query};
-use collection::operations::payload_ops::{DeletePayload, SetPayload};
-use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
+use collection::operations::change_ops::ChangesRequest;
+use collection::operations::payload_ops::{DeletePayload, MutatePayload, SetPayload};
+use collection::operations::point_ops::{
+    FilterSelector, PointInsertOperations, PointStruct, PointsAutoId, PointsSelector,
+    UpsertCondition, WriteOrdering,
+};
+use collection::operations::types::UpdateResult;
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
+use futures::{stream, Stream, StreamExt as _};
 use schemars::JsonSchema;
+use segment::types::SeqNumberType;
 use serde::{Deserialize, Serialize};
+use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
 use validator::Validate;
 
-use super::CollectionPath;
-use crate::actix::helpers::process_response;
+use super::{CollectionPath, FieldPath};
+use crate::actix::helpers::{process_response, storage_into_actix_error};
 use crate::common::points::{
-    do_batch_update_points, do_clear_payload, do_create_index, do_delete_index, do_delete_payload,
-    do_delete_points, do_delete_vectors, do_overwrite_payload, do_set_payload, do_update_vectors,
-    do_upsert_points, CreateFieldIndex, UpdateOperations,
+    do_batch_update_points, do_cancel_delete_task, do_cancel_import_task, do_clear_payload,
+    do_create_index, do_delete_index, do_delete_payload, do_delete_points, do_delete_points_async,
+    do_delete_vectors, do_export_points, do_get_changes, do_get_delete_task, do_get_delete_tasks,
+    do_get_import_task, do_get_import_tasks, do_import_points, do_mutate_payload,
+    do_overwrite_payload, do_set_payload, do_update_vectors, do_upsert_points, CreateFieldIndex,
+    ExportRequest, UpdateOperations,
 };
 
-#[derive(Deserialize, Validate)]
-struct FieldPath {
-    #[serde(rename = "field_name")]
-    #[validate(length(min = 1))]
-    name: String,
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct UpdateParam {
+    pub wait: Option<bool>,
+    pub ordering: Option<WriteOrdering>,
+    /// Retries of the request with the same token are applied only once
+    pub idempotency_token: Option<String>,
 }
 
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
-pub struct UpdateParam {
+pub struct UpsertParam {
+    pub wait: Option<bool>,
+    pub ordering: Option<WriteOrdering>,
+    /// Upsert only if all points are stored with this version. Points must belong to the same shard, requires `wait`
+    pub if_version: Option<SeqNumberType>,
+    /// Upsert only if none of the points are stored. Points must belong to the same shard, requires `wait`
+    pub if_absent: Option<bool>,
+    /// Retries of the request with the same token are applied only once
+    pub idempotency_token: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct SetPayloadParam {
+    pub wait: Option<bool>,
+    pub ordering: Option<WriteOrdering>,
+    /// Merge values into nested objects recursively, as JSON merge patch
+    pub deep_merge: Option<bool>,
+    /// Retries of the request with the same token are applied only once
+    pub idempotency_token: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct FieldIndexParam {
     pub wait: Option<bool>,
     pub ordering: Option<WriteOrdering>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct DeleteAsyncParam {
+    pub ordering: Option<WriteOrdering>,
+}
+
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct ImportParam {
+    pub ordering: Option<WriteOrdering>,
+}
+
+#[derive(Deserialize, Validate)]
+struct TaskPath {
+    #[validate(length(min = 1))]
+    task_id: String,
+}
+
 #[put("/collections/{name}/points")]
 async fn upsert_points(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     operation: Json<PointInsertOperations>,
-    params: Query<UpdateParam>,
+    params: Query<UpsertParam>,
 ) -> impl Responder {
     let timing = Instant::now();
     let operation = operation.into_inner();
     let wait = params.wait.unwrap_or(false);
     let ordering = params.ordering.unwrap_or_default();
+    let condition = match UpsertCondition::from_params(params.if_version, params.if_absent) {
+        Ok(condition) => condition,
+        Err(err) => return process_response::<()>(Err(StorageError::from(err)), timing),
+    };
 
     let response = do_upsert_points(
         toc.get_ref(),
         &collection.name,
         operation,
+        condition,
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -54,6 +114,47 @@ async fn upsert_points(
     process_response(response, timing)
 }
 
+#[put("/collections/{name}/points/auto_id")]
+async fn upsert_points_auto_id(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<PointsAutoId>,
+    params: Query<UpsertParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let mut operation = operation.into_inner();
+    // Retries with a token of either the request or the body get the same IDs and apply once
+    operation.idempotency_token = params
+        .idempotency_token
+        .clone()
+        .or(operation.idempotency_token);
+    let idempotency_token = operation.idempotency_token.clone();
+    let (ids, points) = operation.into_points();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+    let condition = match UpsertCondition::from_params(params.if_version, params.if_absent) {
+        Ok(condition) => condition,
+        Err(err) => return process_response::<()>(Err(StorageError::from(err)), timing),
+    };
+
+    let response = do_upsert_points(
+        toc.get_ref(),
+        &collection.name,
+        PointInsertOperations::PointsList(points),
+        condition,
+        idempotency_token,
+        None,
+        wait,
+        ordering,
+    )
+    .await
+    .map(|result| UpdateResult {
+        ids: Some(ids),
+        ..result
+    });
+    process_response(response, timing)
+}
+
 #[post("/collections/{name}/points/delete")]
 async fn delete_points(
     toc: web::Data<TableOfContent>,
@@ -70,6 +171,7 @@ async fn delete_points(
         toc.get_ref(),
         &collection.name,
         operation,
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -78,6 +180,228 @@ async fn delete_points(
     process_response(response, timing)
 }
 
+#[post("/collections/{name}/points/delete/async")]
+async fn delete_points_async(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<FilterSelector>,
+    params: Query<DeleteAsyncParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response =
+        do_delete_points_async(toc.into_inner(), &collection.name, operation, ordering).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/delete_tasks")]
+async fn get_delete_tasks(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_delete_tasks(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/delete_tasks/{task_id}")]
+async fn get_delete_task(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    task: Path<TaskPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_delete_task(toc.get_ref(), &collection.name, &task.task_id).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/delete_tasks/{task_id}/cancel")]
+async fn cancel_delete_task(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    task: Path<TaskPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_cancel_delete_task(toc.get_ref(), &collection.name, &task.task_id).await;
+    process_response(response, timing)
+}
+
+fn parse_ndjson_point(
+    line: &[u8],
+    line_number: usize,
+) -> Result<Option<PointStruct>, StorageError> {
+    if line.iter().all(u8::is_ascii_whitespace) {
+        return Ok(None);
+    }
+    serde_json::from_slice(line).map(Some).map_err(|err| {
+        StorageError::bad_input(format!("Invalid point at line {line_number}: {err}"))
+    })
+}
+
+/// Parquet files are recognized, to reject them with a clear error instead of a parse error
+fn is_parquet_media_type(request: &HttpRequest, header_name: header::HeaderName) -> bool {
+    request
+        .headers()
+        .get(header_name)
+        .and_then(|value| value.to_str().ok())
+        .map_or(false, |value| {
+            matches!(
+                value.split(';').next().unwrap_or_default().trim(),
+                "application/vnd.apache.parquet" | "application/x-parquet"
+            )
+        })
+}
+
+/// Read points from a body of newline-delimited JSON, one point per line. Empty lines are skipped.
+fn read_ndjson_points(
+    payload: web::Payload,
+) -> impl Stream<Item = Result<PointStruct, StorageError>> {
+    stream::try_unfold(
+        (payload, Vec::new(), 0, false),
+        |(mut payload, mut buffer, mut line_number, mut eof)| async move {
+            loop {
+                let line = match buffer.iter().position(|byte| *byte == b'\n') {
+                    Some(end) => {
+                        let rest = buffer.split_off(end + 1);
+                        mem::replace(&mut buffer, rest)
+                    }
+                    None if eof && buffer.is_empty() => return Ok::<_, StorageError>(None),
+                    None if eof => mem::take(&mut buffer),
+                    None => {
+                        match payload.next().await {
+                            Some(chunk) => buffer.extend_from_slice(&chunk.map_err(|err| {
+                                StorageError::bad_input(format!(
+                                    "Failed to read request body: {err}"
+                                ))
+                            })?),
+                            None => eof = true,
+                        }
+                        continue;
+                    }
+                };
+                line_number += 1;
+                if let Some(point) = parse_ndjson_point(&line, line_number)? {
+                    return Ok(Some((point, (payload, buffer, line_number, eof))));
+                }
+            }
+        },
+    )
+}
+
+#[post("/collections/{name}/points/import")]
+async fn import_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: HttpRequest,
+    payload: web::Payload,
+    params: Query<ImportParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let ordering = params.ordering.unwrap_or_default();
+
+    if is_parquet_media_type(&request, header::CONTENT_TYPE) {
+        return process_response::<()>(
+            Err(StorageError::bad_input(
+                "Parquet import is not supported, points must be sent as newline-delimited JSON",
+            )),
+            timing,
+        );
+    }
+
+    let response = do_import_points(
+        toc.get_ref(),
+        &collection.name,
+        read_ndjson_points(payload),
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/import_tasks")]
+async fn get_import_tasks(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_import_tasks(toc.get_ref(), &collection.name).await;
+    process_response(response, timing)
+}
+
+#[get("/collections/{name}/import_tasks/{task_id}")]
+async fn get_import_task(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    task: Path<TaskPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_import_task(toc.get_ref(), &collection.name, &task.task_id).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/import_tasks/{task_id}/cancel")]
+async fn cancel_import_task(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    task: Path<TaskPath>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_cancel_import_task(toc.get_ref(), &collection.name, &task.task_id).await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/export")]
+async fn export_points(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    http_request: HttpRequest,
+    request: Json<ExportRequest>,
+) -> HttpResponse {
+    let timing = Instant::now();
+    if is_parquet_media_type(&http_request, header::ACCEPT) {
+        return process_response::<()>(
+            Err(StorageError::bad_input(
+                "Parquet export is not supported, points are exported as newline-delimited JSON",
+            )),
+            timing,
+        );
+    }
+    // Report a missing collection with a regular error, before the export starts streaming
+    if let Err(err) = toc.get_collection(&collection.name).await {
+        return process_response::<()>(Err(err), timing);
+    }
+
+    let pages = do_export_points(
+        toc.into_inner(),
+        collection.name.clone(),
+        request.into_inner(),
+    );
+    let body = pages.map(|page| {
+        let mut bytes = Vec::new();
+        for point in page.map_err(storage_into_actix_error)? {
+            serde_json::to_writer(&mut bytes, &point)?;
+            bytes.push(b'\n');
+        }
+        Ok::<_, actix_web::Error>(Bytes::from(bytes))
+    });
+    HttpResponse::Ok()
+        .content_type("application/x-ndjson")
+        .streaming(body)
+}
+
+#[post("/collections/{name}/points/changes")]
+async fn get_changes(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    request: Json<ChangesRequest>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let response = do_get_changes(toc.get_ref(), &collection.name, request.into_inner()).await;
+    process_response(response, timing)
+}
+
 #[put("/collections/{name}/points/vectors")]
 async fn update_vectors(
     toc: web::Data<TableOfContent>,
@@ -94,6 +418,7 @@ async fn update_vectors(
         toc.get_ref(),
         &collection.name,
         operation,
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -118,6 +443,7 @@ async fn delete_vectors(
         toc.get_ref(),
         &collection.name,
         operation,
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -131,7 +457,7 @@ async fn set_payload(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     operation: Json<SetPayload>,
-    params: Query<UpdateParam>,
+    params: Query<SetPayloadParam>,
 ) -> impl Responder {
     let timing = Instant::now();
     let operation = operation.into_inner();
@@ -142,6 +468,8 @@ async fn set_payload(
         toc.get_ref(),
         &collection.name,
         operation,
+        params.deep_merge.unwrap_or(false),
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -166,6 +494,7 @@ async fn overwrite_payload(
         toc.get_ref(),
         &collection.name,
         operation,
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -190,6 +519,7 @@ async fn delete_payload(
         toc.get_ref(),
         &collection.name,
         operation,
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -214,6 +544,32 @@ async fn clear_payload(
         toc.get_ref(),
         &collection.name,
         operation,
+        params.idempotency_token.clone(),
+        None,
+        wait,
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/payload/mutate")]
+async fn mutate_payload(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<MutatePayload>,
+    params: Query<UpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = do_mutate_payload(
+        toc.get_ref(),
+        &collection.name,
+        operation,
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -238,6 +594,7 @@ async fn update_batch(
         &toc,
         &collection.name,
         operations.operations,
+        params.idempotency_token.clone(),
         None,
         wait,
         ordering,
@@ -250,7 +607,7 @@ async fn create_field_index(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     operation: Json<CreateFieldIndex>,
-    params: Query<UpdateParam>,
+    params: Query<FieldIndexParam>,
 ) -> impl Responder {
     let timing = Instant::now();
     let operation = operation.into_inner();
@@ -274,7 +631,7 @@ async fn delete_field_index(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     field: Path<FieldPath>,
-    params: Query<UpdateParam>,
+    params: Query<FieldIndexParam>,
 ) -> impl Responder {
     let timing = Instant::now();
     let wait = params.wait.unwrap_or(false);
@@ -295,13 +652,25 @@ async fn delete_field_index(
 // Configure services
 pub fn config_update_api(cfg: &mut web::ServiceConfig) {
     cfg.service(upsert_points)
+        .service(upsert_points_auto_id)
+        .service(import_points)
+        .service(get_import_tasks)
+        .service(get_import_task)
+        .service(cancel_import_task)
+        .service(export_points)
+        .service(get_changes)
         .service(delete_points)
+        .service(delete_points_async)
+        .service(get_delete_tasks)
+        .service(get_delete_task)
+        .service(cancel_delete_task)
         .service(update_vectors)
         .service(delete_vectors)
         .service(set_payload)
         .service(overwrite_payload)
         .service(delete_payload)
         .service(clear_payload)
+        .service(mutate_payload)
         .service(create_field_index)
         .service(delete_field_index)
         .service(update_batch);
@@ -1,20 +1,26 @@
 use std::future::{ready, Ready};
+use std::rc::Rc;
 
 use actix_web::body::{BoxBody, EitherBody};
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::{Error, HttpResponse};
-use constant_time_eq::constant_time_eq;
+use actix_web::{web, Error, HttpMessage, HttpResponse};
 use futures_util::future::LocalBoxFuture;
+use storage::content_manager::toc::TableOfContent;
+
+use crate::common::auth::{
+    check_collections_access, rest_required_access, Access, Authenticator, CollectionAccessCheck,
+    LookupCollections, RequiredAccess,
+};
 
 pub struct ApiKey {
-    api_key: String,
+    authenticator: Option<Authenticator>,
     whitelist: Vec<WhitelistItem>,
 }
 
 impl ApiKey {
-    pub fn new(api_key: &str, whitelist: Vec<WhitelistItem>) -> Self {
+    pub fn new(authenticator: Option<Authenticator>, whitelist: Vec<WhitelistItem>) -> Self {
         Self {
-            api_key: api_key.to_string(),
+            authenticator,
             whitelist,
         }
     }
@@ -22,7 +28,8 @@ impl ApiKey {
 
 impl<S, B> Transform<S, ServiceRequest> for ApiKey
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>
+        + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -34,9 +41,9 @@ where
 
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(ApiKeyMiddleware {
-            api_key: self.api_key.clone(),
+            authenticator: self.authenticator.clone(),
             whitelist: self.whitelist.clone(),
-            service,
+            service: Rc::new(service),
         }))
     }
 }
@@ -76,10 +83,10 @@ impl PathMode {
 }
 
 pub struct ApiKeyMiddleware<S> {
-    api_key: String,
+    authenticator: Option<Authenticator>,
     /// List of items whitelisted from authentication.
     whitelist: Vec<WhitelistItem>,
-    service: S,
+    service: Rc<S>,
 }
 
 impl<S> ApiKeyMiddleware<S> {
@@ -90,7 +97,8 @@ impl<S> ApiKeyMiddleware<S> {
 
 impl<S, B> Service<ServiceRequest> for ApiKeyMiddleware<S>
 where
-    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>,
+    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>
+        + 'static,
     S::Future: 'static,
     B: 'static,
 {
@@ -103,22 +111,77 @@ where
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let path = req.path();
 
+        let Some(authenticator) = &self.authenticator else {
+            return Box::pin(self.service.call(req));
+        };
+
         if self.is_path_whitelisted(path) {
             return Box::pin(self.service.call(req));
         }
 
-        if let Some(key) = req.headers().get("api-key") {
-            if let Ok(key) = key.to_str() {
-                if constant_time_eq(self.api_key.as_bytes(), key.as_bytes()) {
-                    return Box::pin(self.service.call(req));
+        let header = |name| {
+            req.headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        let access = authenticator.authenticate(header("api-key"), header("authorization"));
+
+        let service = self.service.clone();
+        Box::pin(async move {
+            let result = match access {
+                Ok((access, client)) => {
+                    // Clients are rate limited by their credentials only after authentication
+                    req.extensions_mut().insert(client);
+                    check_request_access(&req, access).await
                 }
+                Err(error) => Err(error),
+            };
+
+            match result {
+                Ok(()) => service.call(req).await,
+                Err(error) => Ok(req
+                    .into_response(HttpResponse::Forbidden().body(error))
+                    .map_into_right_body()),
             }
-        }
-
-        Box::pin(async {
-            Ok(req
-                .into_response(HttpResponse::Forbidden().body("Invalid api-key"))
-                .map_into_right_body())
         })
     }
 }
+
+/// Check the access, which the request requires by its path.
+///
+/// If the access to collections is limited, it is kept in the request, so the handlers can
+/// check the access to the other collections, which the request body refers to.
+async fn check_request_access(req: &ServiceRequest, access: Access) -> Result<(), String> {
+    let (collection_name, level) = match rest_required_access(req.method().as_str(), req.path()) {
+        RequiredAccess::Collection(collection_name, level) if !access.allows_global(level) => {
+            (collection_name.to_string(), level)
+        }
+        required => return access.check(required),
+    };
+
+    let toc = req
+        .app_data::<web::Data<TableOfContent>>()
+        .ok_or_else(|| "Collections are not available".to_string())?;
+    let check = CollectionAccessCheck { access, level };
+    check_collections_access(toc, &check, &collection_name, &[]).await?;
+
+    req.extensions_mut().insert(check);
+    Ok(())
+}
+
+/// Check the access to the collections, which the request reads besides the one of its path.
+///
+/// Returns the response to reject the request with, if the access is not granted.
+pub async fn check_lookup_access(
+    toc: &TableOfContent,
+    check: Option<web::ReqData<CollectionAccessCheck>>,
+    collection_name: &str,
+    request: &impl LookupCollections,
+) -> Result<(), HttpResponse> {
+    let Some(check) = check else {
+        return Ok(());
+    };
+    check_collections_access(toc, &check, collection_name, &request.lookup_collections())
+        .await
+        .map_err(|error| HttpResponse::Forbidden().body(error))
+}
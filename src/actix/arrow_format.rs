@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use actix_web::rt::time::Instant;
+use actix_web::HttpResponse;
+use arrow::array::{
+    ArrayRef, Float32Array, Float32Builder, ListArray, ListBuilder, StringBuilder, StructArray,
+    UInt32Builder, UInt64Array,
+};
+use arrow::buffer::NullBuffer;
+use arrow::datatypes::{Field, Fields, Schema};
+use arrow::error::ArrowError;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use collection::operations::types::{Record, ScrollResult};
+use segment::common::hardware_counter::HardwareUsage;
+use segment::common::search_explain::ShardSearchExplain;
+use segment::data_types::vectors::{Vector, VectorStruct, DEFAULT_VECTOR_NAME};
+use segment::types::{Payload, PointIdType, ScoredPoint};
+use storage::content_manager::errors::StorageError;
+
+use crate::actix::helpers::process_response;
+
+/// Media type of the Arrow IPC streaming format
+pub const ARROW_STREAM_CONTENT_TYPE: &str = "application/vnd.apache.arrow.stream";
+
+/// Points in columnar form: one Arrow record batch, written in the IPC streaming format.
+///
+/// Columns:
+/// - `id` - point id, number or UUID, as a string
+/// - `version` and `score` - search results only
+/// - `payload` - payload as a JSON string, null if not requested
+/// - `vector` - default vector, `vector.<name>` - named vectors.
+///   Dense vectors are lists of floats, multi-vectors are lists of dense vectors,
+///   sparse vectors are structs of `indices` and `values` lists.
+///   Null if the point has no such vector or vectors were not requested.
+///
+/// Response fields besides the points, like the offset of the next scroll page,
+/// are stored as JSON strings in the schema metadata.
+pub fn points_to_arrow(
+    points: &[ArrowPoint],
+    metadata: HashMap<String, String>,
+) -> Result<Vec<u8>, ArrowError> {
+    let scored = points.iter().any(|point| point.score.is_some());
+
+    let mut columns: Vec<(String, ArrayRef)> = Vec::new();
+
+    let mut ids = StringBuilder::new();
+    for point in points {
+        ids.append_value(point.id.to_string());
+    }
+    columns.push(("id".to_string(), Arc::new(ids.finish())));
+
+    if scored {
+        let versions = UInt64Array::from_iter(points.iter().map(|point| point.version));
+        columns.push(("version".to_string(), Arc::new(versions)));
+        let scores = Float32Array::from_iter(points.iter().map(|point| point.score));
+        columns.push(("score".to_string(), Arc::new(scores)));
+    }
+
+    let mut payloads = StringBuilder::new();
+    for point in points {
+        match point.payload {
+            Some(payload) => payloads.append_value(
+                serde_json::to_string(payload)
+                    .map_err(|err| ArrowError::JsonError(err.to_string()))?,
+            ),
+            None => payloads.append_null(),
+        }
+    }
+    columns.push(("payload".to_string(), Arc::new(payloads.finish())));
+
+    // Vector names are sorted to keep the order of the columns stable between responses
+    let mut vector_kinds: BTreeMap<&str, &Vector> = BTreeMap::new();
+    for point in points {
+        for (name, vector) in point.vectors() {
+            vector_kinds.entry(name).or_insert(vector);
+        }
+    }
+    for (name, kind) in vector_kinds {
+        let vectors: Vec<Option<&Vector>> = points
+            .iter()
+            .map(|point| {
+                point
+                    .vectors()
+                    .find(|(vector_name, _)| *vector_name == name)
+                    .map(|(_, vector)| vector)
+            })
+            .collect();
+        let column_name = if name == DEFAULT_VECTOR_NAME {
+            "vector".to_string()
+        } else {
+            format!("vector.{name}")
+        };
+        columns.push((column_name, vectors_to_array(kind, &vectors)?));
+    }
+
+    let fields: Vec<Field> = columns
+        .iter()
+        .map(|(name, array)| Field::new(name, array.data_type().clone(), name != "id"))
+        .collect();
+    let schema = Arc::new(Schema::new(fields).with_metadata(metadata));
+    let batch = RecordBatch::try_new(
+        schema.clone(),
+        columns.into_iter().map(|(_, array)| array).collect(),
+    )?;
+
+    let mut buffer = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut buffer, &schema)?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    drop(writer);
+    Ok(buffer)
+}
+
+/// Build a column of vectors of the same kind as `kind`
+fn vectors_to_array(kind: &Vector, vectors: &[Option<&Vector>]) -> Result<ArrayRef, ArrowError> {
+    let array: ArrayRef = match kind {
+        Vector::Dense(_) => {
+            let mut builder = ListBuilder::new(Float32Builder::new());
+            for vector in vectors {
+                match vector {
+                    Some(Vector::Dense(vector)) => {
+                        builder.values().append_slice(vector);
+                        builder.append(true);
+                    }
+                    None => builder.append_null(),
+                    Some(_) => return Err(mixed_vector_kinds()),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        Vector::MultiDense(_) => {
+            let mut builder = ListBuilder::new(ListBuilder::new(Float32Builder::new()));
+            for vector in vectors {
+                match vector {
+                    Some(Vector::MultiDense(multi_vector)) => {
+                        for vector in multi_vector {
+                            builder.values().values().append_slice(vector);
+                            builder.values().append(true);
+                        }
+                        builder.append(true);
+                    }
+                    None => builder.append_null(),
+                    Some(_) => return Err(mixed_vector_kinds()),
+                }
+            }
+            Arc::new(builder.finish())
+        }
+        Vector::Sparse(_) => {
+            let mut indices = ListBuilder::new(UInt32Builder::new());
+            let mut values = ListBuilder::new(Float32Builder::new());
+            for vector in vectors {
+                match vector {
+                    Some(Vector::Sparse(vector)) => {
+                        indices.values().append_slice(&vector.indices);
+                        indices.append(true);
+                        values.values().append_slice(&vector.weights);
+                        values.append(true);
+                    }
+                    None => {
+                        indices.append_null();
+                        values.append_null();
+                    }
+                    Some(_) => return Err(mixed_vector_kinds()),
+                }
+            }
+            let indices: ListArray = indices.finish();
+            let values: ListArray = values.finish();
+            let fields = Fields::from(vec![
+                Field::new("indices", indices.data_type().clone(), true),
+                Field::new("values", values.data_type().clone(), true),
+            ]);
+            let nulls = NullBuffer::from(
+                vectors
+                    .iter()
+                    .map(|vector| vector.is_some())
+                    .collect::<Vec<_>>(),
+            );
+            Arc::new(StructArray::try_new(
+                fields,
+                vec![Arc::new(indices), Arc::new(values)],
+                Some(nulls),
+            )?)
+        }
+    };
+    Ok(array)
+}
+
+fn mixed_vector_kinds() -> ArrowError {
+    ArrowError::InvalidArgumentError(
+        "vectors with the same name must be of the same kind".to_string(),
+    )
+}
+
+/// Fields of a search result or a retrieved point, which are written into Arrow columns
+pub struct ArrowPoint<'a> {
+    id: PointIdType,
+    version: Option<u64>,
+    score: Option<f32>,
+    payload: Option<&'a Payload>,
+    vector: Option<&'a VectorStruct>,
+    single_vector: Option<Vector>,
+}
+
+impl<'a> ArrowPoint<'a> {
+    fn new(
+        id: PointIdType,
+        version: Option<u64>,
+        score: Option<f32>,
+        payload: Option<&'a Payload>,
+        vector: Option<&'a VectorStruct>,
+    ) -> Self {
+        // Single vector is stored as dense vector, so it can be iterated together with named ones
+        let single_vector = match vector {
+            Some(VectorStruct::Single(vector)) => Some(Vector::Dense(vector.clone())),
+            _ => None,
+        };
+        Self {
+            id,
+            version,
+            score,
+            payload,
+            vector,
+            single_vector,
+        }
+    }
+
+    fn vectors(&self) -> Box<dyn Iterator<Item = (&str, &Vector)> + '_> {
+        match (self.vector, &self.single_vector) {
+            (Some(VectorStruct::Multi(vectors)), _) => {
+                Box::new(vectors.iter().map(|(name, vector)| (name.as_str(), vector)))
+            }
+            (_, Some(vector)) => Box::new(std::iter::once((DEFAULT_VECTOR_NAME, vector))),
+            _ => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+impl<'a> From<&'a ScoredPoint> for ArrowPoint<'a> {
+    fn from(point: &'a ScoredPoint) -> Self {
+        Self::new(
+            point.id,
+            Some(point.version),
+            Some(point.score),
+            point.payload.as_ref(),
+            point.vector.as_ref(),
+        )
+    }
+}
+
+impl<'a> From<&'a Record> for ArrowPoint<'a> {
+    fn from(record: &'a Record) -> Self {
+        Self::new(
+            record.id,
+            None,
+            None,
+            record.payload.as_ref(),
+            record.vector.as_ref(),
+        )
+    }
+}
+
+/// Result of a read request, which can be returned in Arrow format
+pub trait ArrowPoints {
+    fn arrow_points(&self) -> Vec<ArrowPoint<'_>>;
+
+    fn arrow_metadata(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+}
+
+impl ArrowPoints for Vec<ScoredPoint> {
+    fn arrow_points(&self) -> Vec<ArrowPoint<'_>> {
+        self.iter().map(ArrowPoint::from).collect()
+    }
+}
+
+impl ArrowPoints for Vec<Record> {
+    fn arrow_points(&self) -> Vec<ArrowPoint<'_>> {
+        self.iter().map(ArrowPoint::from).collect()
+    }
+}
+
+impl ArrowPoints for ScrollResult {
+    fn arrow_points(&self) -> Vec<ArrowPoint<'_>> {
+        self.points.arrow_points()
+    }
+
+    fn arrow_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        if let Some(offset) = &self.next_page_offset {
+            metadata.insert("next_page_offset".to_string(), json_string(offset));
+        }
+        if let Some(cursor) = &self.next_page_cursor {
+            metadata.insert("next_page_cursor".to_string(), json_string(cursor));
+        }
+        metadata
+    }
+}
+
+/// Search result with the flag of the partial result, the resources used by the search
+/// and the explanation of its strategies
+impl ArrowPoints
+    for (
+        Vec<ScoredPoint>,
+        Option<bool>,
+        Option<HardwareUsage>,
+        Option<Vec<ShardSearchExplain>>,
+    )
+{
+    fn arrow_points(&self) -> Vec<ArrowPoint<'_>> {
+        self.0.arrow_points()
+    }
+
+    fn arrow_metadata(&self) -> HashMap<String, String> {
+        let mut metadata = HashMap::new();
+        if let Some(partial) = &self.1 {
+            metadata.insert("partial".to_string(), json_string(partial));
+        }
+        if let Some(usage) = &self.2 {
+            metadata.insert("usage".to_string(), json_string(usage));
+        }
+        if let Some(explain) = &self.3 {
+            metadata.insert("explain".to_string(), json_string(explain));
+        }
+        metadata
+    }
+}
+
+fn json_string(value: &impl serde::Serialize) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Response with points in Arrow IPC format, errors are returned as usual JSON responses
+pub fn process_arrow_response<T: ArrowPoints>(
+    response: Result<T, StorageError>,
+    timing: Instant,
+) -> HttpResponse {
+    let result = match response {
+        Ok(result) => result,
+        Err(err) => return process_response::<()>(Err(err), timing),
+    };
+    let mut metadata = result.arrow_metadata();
+    metadata.insert(
+        "time".to_string(),
+        json_string(&timing.elapsed().as_secs_f64()),
+    );
+    match points_to_arrow(&result.arrow_points(), metadata) {
+        Ok(body) => HttpResponse::Ok()
+            .content_type(ARROW_STREAM_CONTENT_TYPE)
+            .body(body),
+        Err(err) => process_response::<()>(
+            Err(StorageError::service_error(format!(
+                "Can't encode points in Arrow format: {err}"
+            ))),
+            timing,
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::Float32Type;
+    use arrow::ipc::reader::StreamReader;
+
+    use super::*;
+
+    #[test]
+    fn test_scroll_result_to_arrow() {
+        let records = vec![
+            Record {
+                id: 1.into(),
+                payload: Some(serde_json::json!({"city": "Berlin"}).into()),
+                vector: Some(VectorStruct::Multi(HashMap::from([(
+                    "image".to_string(),
+                    Vector::Dense(vec![1.0, 2.0]),
+                )]))),
+                order_value: None,
+            },
+            Record {
+                id: 2.into(),
+                payload: None,
+                vector: None,
+                order_value: None,
+            },
+        ];
+        let result = ScrollResult {
+            points: records,
+            next_page_offset: Some(3.into()),
+            next_page_cursor: None,
+        };
+
+        let body = points_to_arrow(&result.arrow_points(), result.arrow_metadata()).unwrap();
+        let mut reader = StreamReader::try_new(body.as_slice(), None).unwrap();
+        let schema = reader.schema();
+        assert_eq!(schema.metadata()["next_page_offset"], "3");
+        let batch = reader.next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 2);
+        assert!(batch.column_by_name("score").is_none());
+
+        let payloads = batch.column_by_name("payload").unwrap().as_string::<i32>();
+        assert_eq!(payloads.value(0), r#"{"city":"Berlin"}"#);
+        assert!(payloads.is_null(1));
+
+        let vectors = batch
+            .column_by_name("vector.image")
+            .unwrap()
+            .as_list::<i32>();
+        let vector = vectors.value(0);
+        assert_eq!(vector.as_primitive::<Float32Type>().values(), &[1.0, 2.0]);
+        assert!(vectors.is_null(1));
+    }
+}
@@ -0,0 +1,113 @@
+use std::path::Path;
+
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse};
+
+use crate::actix::api_key::WhitelistItem;
+use crate::actix::WEB_UI_PATH;
+
+/// Minimal dashboard, built into the binary.
+/// Served if there are no files of the full Web UI in the static content folder.
+const DASHBOARD_HTML: &str = include_str!("dashboard.html");
+
+async fn dashboard() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(DASHBOARD_HTML)
+}
+
+fn config_dashboard(cfg: &mut web::ServiceConfig) {
+    cfg.route(WEB_UI_PATH, web::get().to(dashboard))
+        .route(&format!("{WEB_UI_PATH}/"), web::get().to(dashboard));
+}
+
+/// Whether the full Web UI can be served from the static content folder
+pub fn has_web_ui_files(static_folder: &Path) -> bool {
+    static_folder.is_dir()
+}
+
+/// Serve the full Web UI from the static content folder if it is available,
+/// or the built-in dashboard otherwise
+pub fn config_web_ui(cfg: &mut web::ServiceConfig, static_folder: &str, web_ui_available: bool) {
+    if web_ui_available {
+        cfg.service(actix_files::Files::new(WEB_UI_PATH, static_folder).index_file("index.html"));
+    } else {
+        config_dashboard(cfg);
+    }
+}
+
+/// Paths of the Web UI, which are served without an API key.
+/// Only the Web UI itself, not the other paths which happen to start with the same prefix.
+pub fn web_ui_whitelist() -> [WhitelistItem; 2] {
+    [
+        WhitelistItem::exact(WEB_UI_PATH),
+        WhitelistItem::prefix(format!("{WEB_UI_PATH}/")),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use actix_web::{test, App};
+
+    use super::*;
+
+    #[actix_web::test]
+    async fn test_dashboard_without_static_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        let static_folder = dir.path().join("static");
+        assert!(!has_web_ui_files(&static_folder));
+
+        let static_folder = static_folder.to_str().unwrap().to_string();
+        let app = test::init_service(
+            App::new().configure(|cfg| config_web_ui(cfg, &static_folder, false)),
+        )
+        .await;
+
+        for path in [WEB_UI_PATH.to_string(), format!("{WEB_UI_PATH}/")] {
+            let req = test::TestRequest::get().uri(&path).to_request();
+            let resp = test::call_service(&app, req).await;
+            assert!(resp.status().is_success(), "{path}: {}", resp.status());
+            let body = test::read_body(resp).await;
+            assert_eq!(body, DASHBOARD_HTML.as_bytes());
+        }
+    }
+
+    #[actix_web::test]
+    async fn test_web_ui_from_static_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("index.html"), "full web ui").unwrap();
+        assert!(has_web_ui_files(dir.path()));
+
+        let static_folder = dir.path().to_str().unwrap().to_string();
+        let app = test::init_service(
+            App::new().configure(|cfg| config_web_ui(cfg, &static_folder, true)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("{WEB_UI_PATH}/"))
+            .to_request();
+        let body = test::call_and_read_body(&app, req).await;
+        assert_eq!(body, "full web ui".as_bytes());
+    }
+
+    #[test]
+    fn test_web_ui_whitelist() {
+        let whitelisted = |path: &str| web_ui_whitelist().iter().any(|item| item.matches(path));
+
+        assert!(whitelisted("/dashboard"));
+        assert!(whitelisted("/dashboard/"));
+        assert!(whitelisted("/dashboard/index.html"));
+        assert!(whitelisted("/dashboard/assets/index.js"));
+
+        // Other paths still require an API key
+        assert!(!whitelisted("/"));
+        assert!(!whitelisted("/dashboards"));
+        assert!(!whitelisted("/dashboard-api"));
+        assert!(!whitelisted("/collections"));
+        assert!(!whitelisted("/collections/dashboard"));
+        assert!(!whitelisted("/cluster"));
+    }
+}
@@ -6,6 +6,8 @@ use actix_web::{error, http, Error, HttpResponse};
 use api::grpc::models::{ApiResponse, ApiStatus};
 use collection::operations::types::CollectionError;
 use futures::Future;
+use segment::common::hardware_counter::HardwareUsage;
+use segment::common::search_explain::ShardSearchExplain;
 use serde::Serialize;
 use storage::content_manager::errors::StorageError;
 
@@ -22,6 +24,7 @@ pub fn storage_into_actix_error(err: StorageError) -> Error {
         StorageError::BadRequest { .. } => error::ErrorBadRequest(format!("{err}")),
         StorageError::Locked { .. } => error::ErrorForbidden(format!("{err}")),
         StorageError::Timeout { .. } => error::ErrorRequestTimeout(format!("{err}")),
+        StorageError::PreconditionFailed { .. } => error::ErrorConflict(format!("{err}")),
     }
 }
 
@@ -30,6 +33,9 @@ pub fn accepted_response(timing: Instant) -> HttpResponse {
         result: None,
         status: ApiStatus::Accepted,
         time: timing.elapsed().as_secs_f64(),
+        partial: None,
+        usage: None,
+        explain: None,
     })
 }
 
@@ -42,6 +48,9 @@ where
             result: Some(res),
             status: ApiStatus::Ok,
             time: timing.elapsed().as_secs_f64(),
+            partial: None,
+            usage: None,
+            explain: None,
         }),
         Err(err) => {
             let error_description = format!("{err}");
@@ -62,17 +71,45 @@ where
                 StorageError::BadRequest { .. } => HttpResponse::BadRequest(),
                 StorageError::Locked { .. } => HttpResponse::Forbidden(),
                 StorageError::Timeout { .. } => HttpResponse::RequestTimeout(),
+                StorageError::PreconditionFailed { .. } => HttpResponse::Conflict(),
             };
 
             resp.json(ApiResponse::<()> {
                 result: None,
                 status: ApiStatus::Error(error_description),
                 time: timing.elapsed().as_secs_f64(),
+                partial: None,
+                usage: None,
+                explain: None,
             })
         }
     }
 }
 
+/// Same as [`process_response`], with the flag of results, which are missing due to a timeout,
+/// the resources used by the request, if they were measured, and the explanation of its searches
+pub fn process_partial_response<D>(
+    response: Result<(D, Option<bool>), StorageError>,
+    timing: Instant,
+    usage: Option<HardwareUsage>,
+    explain: Option<Vec<ShardSearchExplain>>,
+) -> HttpResponse
+where
+    D: Serialize,
+{
+    match response {
+        Ok((res, partial)) => HttpResponse::Ok().json(ApiResponse {
+            result: Some(res),
+            status: ApiStatus::Ok,
+            time: timing.elapsed().as_secs_f64(),
+            partial,
+            usage,
+            explain,
+        }),
+        Err(err) => process_response::<()>(Err(err), timing),
+    }
+}
+
 pub async fn time<T, Fut>(future: Fut) -> impl actix_web::Responder
 where
     Fut: Future<Output = HttpResult<T>>,
@@ -120,6 +157,9 @@ where
                 result,
                 status,
                 time,
+                partial: None,
+                usage: None,
+                explain: None,
             };
 
             (status_code, response)
@@ -130,6 +170,9 @@ where
                 result: None,
                 status: ApiStatus::Error(error.to_string()),
                 time,
+                partial: None,
+                usage: None,
+                explain: None,
             };
 
             (error.status_code(), response)
@@ -182,6 +225,9 @@ impl From<StorageError> for HttpError {
             StorageError::Timeout { description } => {
                 (http::StatusCode::REQUEST_TIMEOUT, description)
             }
+            StorageError::PreconditionFailed { description } => {
+                (http::StatusCode::CONFLICT, description)
+            }
         };
 
         Self {
@@ -2,9 +2,12 @@
 pub mod actix_telemetry;
 pub mod api;
 mod api_key;
-mod certificate_helpers;
+mod arrow_format;
+mod dashboard;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod helpers;
+mod rate_limit;
+mod trace_context;
 
 use std::io;
 use std::path::Path;
@@ -21,7 +24,8 @@ use storage::dispatcher::Dispatcher;
 
 use crate::actix::api::cluster_api::config_cluster_api;
 use crate::actix::api::collections_api::config_collections_api;
-use crate::actix::api::count_api::count_points;
+use crate::actix::api::count_api::{count_batch_points, count_points};
+use crate::actix::api::query_api::config_query_api;
 use crate::actix::api::recommend_api::config_recommend_api;
 use crate::actix::api::retrieve_api::{get_point, get_points, scroll_points};
 use crate::actix::api::search_api::config_search_api;
@@ -29,6 +33,11 @@ use crate::actix::api::service_api::config_service_api;
 use crate::actix::api::snapshot_api::config_snapshots_api;
 use crate::actix::api::update_api::config_update_api;
 use crate::actix::api_key::{ApiKey, WhitelistItem};
+use crate::actix::dashboard::{config_web_ui, has_web_ui_files, web_ui_whitelist};
+use crate::actix::rate_limit::RateLimit;
+use crate::actix::trace_context::TraceContextTransform;
+use crate::common::auth::Authenticator;
+use crate::common::certificate_helpers;
 use crate::common::telemetry::TelemetryCollector;
 use crate::settings::{max_web_workers, Settings};
 
@@ -49,13 +58,15 @@ pub fn init(
     actix_web::rt::System::new().block_on(async {
         let toc_data = web::Data::from(dispatcher.toc().clone());
         let dispatcher_data = web::Data::from(dispatcher);
-        let actix_telemetry_collector = telemetry_collector
-            .lock()
-            .await
-            .actix_telemetry_collector
-            .clone();
+        let (actix_telemetry_collector, rate_limiter) = {
+            let telemetry_collector = telemetry_collector.lock().await;
+            (
+                telemetry_collector.actix_telemetry_collector.clone(),
+                telemetry_collector.rate_limiter.clone(),
+            )
+        };
         let telemetry_collector_data = web::Data::from(telemetry_collector);
-        let api_key = settings.service.api_key.clone();
+        let authenticator = Authenticator::new(&settings.service)?;
         let static_folder = settings
             .service
             .static_content_dir
@@ -64,23 +75,13 @@ pub fn init(
 
         let web_ui_enabled = settings.service.enable_static_content.unwrap_or(true);
         // validate that the static folder exists IF the web UI is enabled
-        let web_ui_available = if web_ui_enabled {
-            let static_folder = Path::new(&static_folder);
-            if !static_folder.exists() || !static_folder.is_dir() {
-                // enabled BUT folder does not exist
-                log::warn!(
-                    "Static content folder for Web UI '{}' does not exist",
-                    static_folder.display(),
-                );
-                false
-            } else {
-                // enabled AND folder exists
-                true
-            }
-        } else {
-            // not enabled
-            false
-        };
+        let web_ui_available = web_ui_enabled && has_web_ui_files(Path::new(&static_folder));
+        if web_ui_enabled && !web_ui_available {
+            // enabled BUT folder does not exist, fall back to the built-in dashboard
+            log::warn!(
+                "Static content folder for Web UI '{static_folder}' does not exist, serving built-in dashboard",
+            );
+        }
 
         let mut api_key_whitelist = vec![
             WhitelistItem::exact("/"),
@@ -88,8 +89,8 @@ pub fn init(
             WhitelistItem::prefix("/readyz"),
             WhitelistItem::prefix("/livez"),
         ];
-        if web_ui_available {
-            api_key_whitelist.push(WhitelistItem::prefix(WEB_UI_PATH));
+        if web_ui_enabled {
+            api_key_whitelist.extend(web_ui_whitelist());
         }
 
         let upload_dir = dispatcher_data.upload_dir().unwrap();
@@ -109,20 +110,26 @@ pub fn init(
 
             let mut app = App::new()
                 .wrap(Compress::default()) // Reads the `Accept-Encoding` header to negotiate which compression codec to use.
+                // rate_limit middleware, clients are known only after the api_key middleware
+                .wrap(RateLimit::new(
+                    rate_limiter.clone(),
+                    api_key_whitelist.clone(),
+                ))
                 // api_key middleware
                 // note: the last call to `wrap()` or `wrap_fn()` is executed first
                 .wrap(Condition::new(
-                    api_key.is_some(),
-                    ApiKey::new(
-                        &api_key.clone().unwrap_or_default(),
-                        api_key_whitelist.clone(),
-                    ),
+                    authenticator.is_some(),
+                    ApiKey::new(authenticator.clone(), api_key_whitelist.clone()),
                 ))
                 .wrap(Condition::new(settings.service.enable_cors, cors))
                 .wrap(Logger::default().exclude("/")) // Avoid logging healthcheck requests
                 .wrap(actix_telemetry::ActixTelemetryTransform::new(
                     actix_telemetry_collector.clone(),
                 ))
+                .wrap(Condition::new(
+                    settings.opentelemetry.is_some(),
+                    TraceContextTransform,
+                ))
                 .app_data(dispatcher_data.clone())
                 .app_data(toc_data.clone())
                 .app_data(telemetry_collector_data.clone())
@@ -139,15 +146,15 @@ pub fn init(
                 .configure(config_service_api)
                 .configure(config_search_api)
                 .configure(config_recommend_api)
+                .configure(config_query_api)
                 .service(get_point)
                 .service(get_points)
                 .service(scroll_points)
-                .service(count_points);
+                .service(count_points)
+                .service(count_batch_points);
 
-            if web_ui_available {
-                app = app.service(
-                    actix_files::Files::new(WEB_UI_PATH, &static_folder).index_file("index.html"),
-                )
+            if web_ui_enabled {
+                app = app.configure(|cfg| config_web_ui(cfg, &static_folder, web_ui_available))
             }
             app
         })
@@ -221,6 +228,9 @@ fn validation_error_handler(
         result: None,
         status: ApiStatus::Error(msg),
         time: 0.0,
+        partial: None,
+        usage: None,
+        explain: None,
     });
     error::InternalError::from_response(err, response).into()
 }
@@ -0,0 +1,99 @@
+use std::future::{ready, Ready};
+use std::sync::Arc;
+
+use actix_web::body::{BoxBody, EitherBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::RETRY_AFTER;
+use actix_web::{Error, HttpMessage, HttpResponse};
+use futures_util::future::LocalBoxFuture;
+
+use crate::actix::api_key::WhitelistItem;
+use crate::common::auth::AuthenticatedClient;
+use crate::common::rate_limiter::RateLimiter;
+
+pub struct RateLimit {
+    rate_limiter: Option<Arc<RateLimiter>>,
+    whitelist: Vec<WhitelistItem>,
+}
+
+impl RateLimit {
+    pub fn new(rate_limiter: Option<Arc<RateLimiter>>, whitelist: Vec<WhitelistItem>) -> Self {
+        Self {
+            rate_limiter,
+            whitelist,
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            rate_limiter: self.rate_limiter.clone(),
+            whitelist: self.whitelist.clone(),
+            service,
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    rate_limiter: Option<Arc<RateLimiter>>,
+    /// List of items, which are not limited, e.g. health checks
+    whitelist: Vec<WhitelistItem>,
+    service: S,
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<EitherBody<B, BoxBody>>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B, BoxBody>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return Box::pin(self.service.call(req));
+        };
+
+        if self.whitelist.iter().any(|item| item.matches(req.path())) {
+            return Box::pin(self.service.call(req));
+        }
+
+        let client_key = RateLimiter::client_key(
+            req.extensions().get::<AuthenticatedClient>(),
+            req.peer_addr().map(|addr| addr.ip().to_string()),
+        );
+
+        match rate_limiter.try_acquire(&client_key) {
+            Ok(permit) => {
+                let response = self.service.call(req);
+                Box::pin(async move {
+                    let response = response.await;
+                    drop(permit);
+                    response
+                })
+            }
+            Err(error) => Box::pin(async move {
+                let response = HttpResponse::TooManyRequests()
+                    .insert_header((RETRY_AFTER, error.retry_after_secs()))
+                    .body(error.to_string());
+                Ok(req.into_response(response).map_into_right_body())
+            }),
+        }
+    }
+}
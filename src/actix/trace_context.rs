@@ -0,0 +1,76 @@
+use std::future::{ready, Ready};
+
+use actix_web::dev::{Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+use tracing::Instrument as _;
+
+pub struct TraceContextService<S> {
+    service: S,
+}
+
+pub struct TraceContextTransform;
+
+/// Wraps every request into a span, which continues the trace of the client,
+/// if it is passed in the `traceparent` header
+impl<S, B> Service<ServiceRequest> for TraceContextService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error>,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    actix_web::dev::forward_ready!(service);
+
+    fn call(&self, request: ServiceRequest) -> Self::Future {
+        let match_pattern = request
+            .match_pattern()
+            .unwrap_or_else(|| "unknown".to_owned());
+        let span = tracing::debug_span!(
+            "http_request",
+            otel.name = %format!("{} {}", request.method(), match_pattern),
+            otel.kind = "server",
+            http.status_code = tracing::field::Empty,
+        );
+
+        #[cfg(feature = "opentelemetry")]
+        api::grpc::trace_context::set_parent_from_headers(
+            &span,
+            request
+                .headers()
+                .iter()
+                .filter_map(|(key, value)| Some((key.as_str(), value.to_str().ok()?))),
+        );
+
+        let future = span.in_scope(|| self.service.call(request));
+        Box::pin(
+            async move {
+                let response = future.await?;
+                tracing::Span::current()
+                    .record("http.status_code", response.response().status().as_u16());
+                Ok(response)
+            }
+            .instrument(span),
+        )
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for TraceContextTransform
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = TraceContextService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(TraceContextService { service }))
+    }
+}
@@ -0,0 +1,774 @@
+use std::collections::HashMap;
+use std::{fmt, io};
+
+use collection::lookup::types::WithLookupInterface;
+use collection::operations::types::{
+    LookupLocation, Prefetch, QueryRequest, RecommendGroupsRequest, RecommendRequest,
+    RecommendRequestBatch, SearchGroupsRequest,
+};
+use constant_time_eq::constant_time_eq;
+use serde::Deserialize;
+use storage::content_manager::toc::TableOfContent;
+
+use crate::common::jwt::JwtValidator;
+use crate::settings::ServiceConfig;
+
+/// REST endpoints of a collection, which only read points, relative to `/collections/{name}`
+const READ_ONLY_COLLECTION_ENDPOINTS: &[&str] = &[
+    "/points",
+    "/points/scroll",
+    "/points/count",
+    "/points/count/batch",
+    "/points/search",
+    "/points/search/batch",
+    "/points/search/groups",
+    "/points/recommend",
+    "/points/recommend/batch",
+    "/points/recommend/groups",
+    "/points/query",
+    "/points/export",
+    "/points/changes",
+];
+
+/// REST endpoints of a collection, which change its points, relative to `/collections/{name}`
+const WRITE_COLLECTION_ENDPOINT_PREFIXES: &[&str] = &["/points", "/delete_tasks"];
+
+/// Paths under `/collections/`, which don't refer to a single collection
+const NON_COLLECTION_PATHS: &[&str] = &["aliases", "search"];
+
+/// gRPC methods, which don't change any data
+const READ_ONLY_GRPC_METHODS: &[&str] = &[
+    "/qdrant.Qdrant/HealthCheck",
+    "/qdrant.Collections/Get",
+    "/qdrant.Collections/List",
+    "/qdrant.Collections/ListCollectionAliases",
+    "/qdrant.Collections/ListAliases",
+    "/qdrant.Collections/CollectionClusterInfo",
+    "/qdrant.Points/Get",
+    "/qdrant.Points/Search",
+    "/qdrant.Points/SearchBatch",
+    "/qdrant.Points/SearchGroups",
+    "/qdrant.Points/Scroll",
+    "/qdrant.Points/ScrollStream",
+    "/qdrant.Points/Recommend",
+    "/qdrant.Points/RecommendBatch",
+    "/qdrant.Points/RecommendGroups",
+    "/qdrant.Points/Count",
+    "/qdrant.Points/Query",
+    "/qdrant.Snapshots/List",
+    "/qdrant.Snapshots/ListFull",
+    "/grpc.health.v1.Health/Check",
+    "/grpc.health.v1.Health/Watch",
+];
+
+/// gRPC services, which methods mostly refer to a single collection
+const COLLECTION_GRPC_SERVICES: &[&str] = &[
+    "/qdrant.Points/",
+    "/qdrant.Collections/",
+    "/qdrant.Snapshots/",
+    "/grpc.health.v1.Health/",
+];
+
+/// gRPC methods of [`COLLECTION_GRPC_SERVICES`], which don't refer to a single collection
+const NON_COLLECTION_GRPC_METHODS: &[&str] = &[
+    "/qdrant.Collections/List",
+    "/qdrant.Collections/ListAliases",
+    "/qdrant.Collections/UpdateAliases",
+    "/qdrant.Snapshots/CreateFull",
+    "/qdrant.Snapshots/ListFull",
+    "/qdrant.Snapshots/DeleteFull",
+];
+
+/// gRPC methods of the points service, which manage the collection rather than change points
+const MANAGE_POINTS_GRPC_METHODS: &[&str] = &[
+    "/qdrant.Points/CreateFieldIndex",
+    "/qdrant.Points/DeleteFieldIndex",
+];
+
+/// gRPC reflection only describes the API, so it is allowed with the read-only key
+const GRPC_REFLECTION_PREFIX: &str = "/grpc.reflection.";
+
+/// Health check of the whole server is served without credentials, so load balancers can use it.
+/// Other services of the check are only known once its message is decoded, so the health service
+/// checks the access and rate limit of those itself.
+pub const GRPC_HEALTH_CHECK_METHOD: &str = "/grpc.health.v1.Health/Check";
+
+/// Level of access to a collection or to the whole service, higher levels include the lower ones
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+pub enum AccessLevel {
+    /// Read points and collection info, e.g. search, retrieve and scroll points
+    Read,
+    /// Change points, their vectors and payload
+    Write,
+    /// Create, change and delete collections, their indexes, snapshots and cluster setup
+    Manage,
+}
+
+impl fmt::Display for AccessLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AccessLevel::Read => write!(f, "read"),
+            AccessLevel::Write => write!(f, "write"),
+            AccessLevel::Manage => write!(f, "manage"),
+        }
+    }
+}
+
+/// Access to the collections, which match the name
+#[derive(Debug, Deserialize, Clone, PartialEq, Eq)]
+pub struct CollectionAccess {
+    /// Name of the collection, or a prefix of the names followed by `*`
+    pub collection: String,
+    pub access: AccessLevel,
+}
+
+impl CollectionAccess {
+    fn matches(&self, collection_name: &str) -> bool {
+        match self.collection.strip_suffix('*') {
+            Some(prefix) => collection_name.starts_with(prefix),
+            None => collection_name == self.collection,
+        }
+    }
+}
+
+/// Access, granted to a request by its api-key or token.
+///
+/// Tokens carry it in their claims, e.g.
+/// `{"exp": 1700000000, "access": "read", "collections": [{"collection": "team_a_*", "access": "write"}]}`
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, Eq)]
+pub struct Access {
+    /// Access to all collections and to the service itself
+    #[serde(default, rename = "access")]
+    pub global: Option<AccessLevel>,
+    /// Access to specific collections
+    #[serde(default)]
+    pub collections: Vec<CollectionAccess>,
+}
+
+/// Access, which a request requires
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequiredAccess<'a> {
+    /// Any valid credentials, e.g. to describe the API
+    Any,
+    /// Access to the whole service, e.g. to list all collections
+    Global(AccessLevel),
+    /// Access to a single collection
+    Collection(&'a str, AccessLevel),
+}
+
+impl Access {
+    pub fn full() -> Self {
+        Self {
+            global: Some(AccessLevel::Manage),
+            collections: Vec::new(),
+        }
+    }
+
+    pub fn read_only() -> Self {
+        Self {
+            global: Some(AccessLevel::Read),
+            collections: Vec::new(),
+        }
+    }
+
+    /// Whether all collections and the service itself may be accessed on this level
+    pub fn allows_global(&self, level: AccessLevel) -> bool {
+        self.global.map_or(false, |global| global >= level)
+    }
+
+    /// Whether at least some collections may be accessed on this level
+    pub fn allows_some_collection(&self, level: AccessLevel) -> bool {
+        self.allows_global(level)
+            || self
+                .collections
+                .iter()
+                .any(|collection| collection.access >= level)
+    }
+
+    pub fn check(&self, required: RequiredAccess) -> Result<(), String> {
+        match required {
+            RequiredAccess::Any => Ok(()),
+            RequiredAccess::Global(level) if self.allows_global(level) => Ok(()),
+            RequiredAccess::Global(level) => Err(format!("No {level} access to the service")),
+            RequiredAccess::Collection(collection_name, level) => {
+                let allowed = self.allows_global(level)
+                    || self.collections.iter().any(|collection| {
+                        collection.access >= level && collection.matches(collection_name)
+                    });
+                if allowed {
+                    Ok(())
+                } else {
+                    Err(format!("No {level} access to collection {collection_name}"))
+                }
+            }
+        }
+    }
+}
+
+impl Access {
+    /// Check the access to the collection, which a request is sent to, and to the collections,
+    /// which it only reads besides it, e.g. by `with_lookup` or `lookup_from`.
+    ///
+    /// Access is granted by the names of the collections, so aliases are checked by the
+    /// collections they refer to, which `resolve_alias` gives.
+    pub fn check_collections<'a>(
+        &self,
+        collection_name: &str,
+        level: AccessLevel,
+        lookup_collections: impl IntoIterator<Item = &'a str>,
+        resolve_alias: impl Fn(&str) -> Option<String>,
+    ) -> Result<(), String> {
+        let check = |name: &str, level| {
+            let resolved_name = resolve_alias(name);
+            let resolved_name = resolved_name.as_deref().unwrap_or(name);
+            self.check(RequiredAccess::Collection(resolved_name, level))
+                .map_err(|_| format!("No {level} access to collection {name}"))
+        };
+
+        check(collection_name, level)?;
+        lookup_collections
+            .into_iter()
+            .try_for_each(|name| check(name, AccessLevel::Read))
+    }
+}
+
+/// Access of a request, which refers to collections, but doesn't grant access to all of them.
+///
+/// Collections of a request are only known once its path and body are parsed,
+/// so the access to them is checked by the handlers, see [`check_collections_access`].
+#[derive(Clone)]
+pub struct CollectionAccessCheck {
+    pub access: Access,
+    pub level: AccessLevel,
+}
+
+/// Check the access to the collection of a request and to the collections it reads besides it,
+/// resolving their aliases.
+pub async fn check_collections_access(
+    toc: &TableOfContent,
+    check: &CollectionAccessCheck,
+    collection_name: &str,
+    lookup_collections: &[&str],
+) -> Result<(), String> {
+    let mut aliases = HashMap::new();
+    for &name in std::iter::once(&collection_name).chain(lookup_collections) {
+        if let Some(resolved_name) = toc.resolve_alias(name).await {
+            aliases.insert(name, resolved_name);
+        }
+    }
+
+    check.access.check_collections(
+        collection_name,
+        check.level,
+        lookup_collections.iter().copied(),
+        |name| aliases.get(name).cloned(),
+    )
+}
+
+/// Request, which reads other collections besides the one it is sent to
+pub trait LookupCollections {
+    /// Names of the other collections, which the request reads
+    fn lookup_collections(&self) -> Vec<&str>;
+}
+
+fn lookup_location_collection(lookup_from: &Option<LookupLocation>) -> Option<&str> {
+    lookup_from
+        .as_ref()
+        .map(|location| location.collection.as_str())
+}
+
+fn with_lookup_collection(with_lookup: &Option<WithLookupInterface>) -> Option<&str> {
+    with_lookup.as_ref().map(|with_lookup| match with_lookup {
+        WithLookupInterface::Collection(collection_name) => collection_name.as_str(),
+        WithLookupInterface::WithLookup(with_lookup) => with_lookup.collection_name.as_str(),
+    })
+}
+
+impl LookupCollections for SearchGroupsRequest {
+    fn lookup_collections(&self) -> Vec<&str> {
+        with_lookup_collection(&self.group_request.with_lookup)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl LookupCollections for RecommendRequest {
+    fn lookup_collections(&self) -> Vec<&str> {
+        lookup_location_collection(&self.lookup_from)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl LookupCollections for RecommendRequestBatch {
+    fn lookup_collections(&self) -> Vec<&str> {
+        self.searches
+            .iter()
+            .flat_map(|request| request.lookup_collections())
+            .collect()
+    }
+}
+
+impl LookupCollections for RecommendGroupsRequest {
+    fn lookup_collections(&self) -> Vec<&str> {
+        lookup_location_collection(&self.lookup_from)
+            .into_iter()
+            .chain(with_lookup_collection(&self.group_request.with_lookup))
+            .collect()
+    }
+}
+
+impl LookupCollections for Prefetch {
+    fn lookup_collections(&self) -> Vec<&str> {
+        lookup_location_collection(&self.lookup_from)
+            .into_iter()
+            .chain(
+                self.prefetch
+                    .iter()
+                    .flat_map(|prefetch| prefetch.lookup_collections()),
+            )
+            .collect()
+    }
+}
+
+impl LookupCollections for QueryRequest {
+    fn lookup_collections(&self) -> Vec<&str> {
+        lookup_location_collection(&self.lookup_from)
+            .into_iter()
+            .chain(
+                self.prefetch
+                    .iter()
+                    .flat_map(|prefetch| prefetch.lookup_collections()),
+            )
+            .collect()
+    }
+}
+
+/// Api-keys, which the requests are authenticated with.
+///
+/// Either key may be left unset. E.g. with only the read-only key set, no request may change data.
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    api_key: Option<String>,
+    read_only_api_key: Option<String>,
+}
+
+impl ApiKeys {
+    pub fn new(api_key: Option<String>, read_only_api_key: Option<String>) -> Self {
+        Self {
+            api_key,
+            read_only_api_key,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.api_key.is_none() && self.read_only_api_key.is_none()
+    }
+
+    /// Access, granted by the key, `None` if the key is invalid
+    pub fn access(&self, key: &str) -> Option<Access> {
+        let matches = |expected: &Option<String>| {
+            expected.as_ref().map_or(false, |expected| {
+                constant_time_eq(expected.as_bytes(), key.as_bytes())
+            })
+        };
+
+        if matches(&self.api_key) {
+            Some(Access::full())
+        } else if matches(&self.read_only_api_key) {
+            Some(Access::read_only())
+        } else {
+            None
+        }
+    }
+}
+
+/// Credential, which the client of the request was authenticated with
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthenticatedClient {
+    ApiKey(String),
+    Token(String),
+}
+
+/// Authentication of the requests by an api-key or a JWT token
+#[derive(Clone)]
+pub struct Authenticator {
+    api_keys: ApiKeys,
+    jwt: Option<JwtValidator>,
+}
+
+impl Authenticator {
+    /// Authenticator of the configured credentials, `None` if authentication is disabled
+    pub fn new(config: &ServiceConfig) -> io::Result<Option<Self>> {
+        let api_keys = ApiKeys::new(config.api_key.clone(), config.read_only_api_key.clone());
+        let jwt = config.jwt.as_ref().map(JwtValidator::new).transpose()?;
+        if api_keys.is_empty() && jwt.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self { api_keys, jwt }))
+    }
+
+    /// Access, granted by the `api-key` header or by the token of the `Authorization` header,
+    /// and the credential, which granted it
+    pub fn authenticate(
+        &self,
+        api_key: Option<&str>,
+        authorization: Option<&str>,
+    ) -> Result<(Access, AuthenticatedClient), String> {
+        if let Some(key) = api_key {
+            if let Some(access) = self.api_keys.access(key) {
+                return Ok((access, AuthenticatedClient::ApiKey(key.to_string())));
+            }
+        }
+
+        let Some(jwt) = &self.jwt else {
+            return Err("Invalid api-key".to_string());
+        };
+        match authorization.and_then(|header| header.strip_prefix("Bearer ")) {
+            Some(token) => {
+                let access = jwt.validate(token)?;
+                Ok((access, AuthenticatedClient::Token(token.to_string())))
+            }
+            None => Err("Invalid api-key or token".to_string()),
+        }
+    }
+}
+
+/// Whether the REST request only reads data, so it is allowed with the read-only key
+pub fn is_read_only_rest_request(method: &str, path: &str) -> bool {
+    match method {
+        "GET" | "HEAD" => true,
+        "POST" => {
+            if path == "/collections/search/batch" {
+                return true;
+            }
+            collection_endpoint(path).map_or(false, |(_, endpoint)| {
+                READ_ONLY_COLLECTION_ENDPOINTS.contains(&endpoint)
+            })
+        }
+        _ => false,
+    }
+}
+
+/// Collection name and the endpoint relative to it, if the path refers to a single collection
+fn collection_endpoint(path: &str) -> Option<(&str, &str)> {
+    let path = path.strip_prefix("/collections/")?;
+    let (collection_name, endpoint) = match path.find('/') {
+        Some(pos) => path.split_at(pos),
+        None => (path, ""),
+    };
+    if collection_name.is_empty() || NON_COLLECTION_PATHS.contains(&collection_name) {
+        return None;
+    }
+    Some((collection_name, endpoint))
+}
+
+/// Access, which the REST request requires
+pub fn rest_required_access<'a>(method: &str, path: &'a str) -> RequiredAccess<'a> {
+    let read_only = is_read_only_rest_request(method, path);
+    match collection_endpoint(path) {
+        Some((collection_name, _)) if read_only => {
+            RequiredAccess::Collection(collection_name, AccessLevel::Read)
+        }
+        Some((collection_name, endpoint))
+            if WRITE_COLLECTION_ENDPOINT_PREFIXES
+                .iter()
+                .any(|prefix| endpoint.starts_with(prefix)) =>
+        {
+            RequiredAccess::Collection(collection_name, AccessLevel::Write)
+        }
+        Some((collection_name, _)) => {
+            RequiredAccess::Collection(collection_name, AccessLevel::Manage)
+        }
+        None if read_only => RequiredAccess::Global(AccessLevel::Read),
+        None => RequiredAccess::Global(AccessLevel::Manage),
+    }
+}
+
+/// Whether the gRPC method only reads data, so it is allowed with the read-only key
+pub fn is_read_only_grpc_method(path: &str) -> bool {
+    READ_ONLY_GRPC_METHODS.contains(&path) || path.starts_with(GRPC_REFLECTION_PREFIX)
+}
+
+/// Access, which a gRPC method requires.
+///
+/// The collection of a request is only known once its message is decoded,
+/// so the access to it is checked by the service itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrpcRequiredAccess {
+    Any,
+    Global(AccessLevel),
+    Collection(AccessLevel),
+}
+
+pub fn grpc_required_access(path: &str) -> GrpcRequiredAccess {
+    if path.starts_with(GRPC_REFLECTION_PREFIX) || path == "/qdrant.Qdrant/HealthCheck" {
+        return GrpcRequiredAccess::Any;
+    }
+
+    let level = if is_read_only_grpc_method(path) {
+        AccessLevel::Read
+    } else if path.starts_with("/qdrant.Points/") && !MANAGE_POINTS_GRPC_METHODS.contains(&path) {
+        AccessLevel::Write
+    } else {
+        AccessLevel::Manage
+    };
+
+    let is_collection_method = COLLECTION_GRPC_SERVICES
+        .iter()
+        .any(|service| path.starts_with(service))
+        && !NON_COLLECTION_GRPC_METHODS.contains(&path);
+
+    if is_collection_method {
+        GrpcRequiredAccess::Collection(level)
+    } else {
+        GrpcRequiredAccess::Global(level)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_keys_access() {
+        assert!(ApiKeys::new(None, None).is_empty());
+
+        let keys = ApiKeys::new(Some("secret".to_string()), Some("public".to_string()));
+        assert_eq!(keys.access("secret"), Some(Access::full()));
+        assert_eq!(keys.access("public"), Some(Access::read_only()));
+        assert_eq!(keys.access("wrong"), None);
+        assert_eq!(keys.access(""), None);
+
+        let keys = ApiKeys::new(None, Some("public".to_string()));
+        assert_eq!(keys.access("public"), Some(Access::read_only()));
+        assert_eq!(keys.access(""), None);
+    }
+
+    #[test]
+    fn test_read_only_requests() {
+        assert!(is_read_only_rest_request("GET", "/collections/test"));
+        assert!(is_read_only_rest_request(
+            "POST",
+            "/collections/test/points/search"
+        ));
+        assert!(is_read_only_rest_request(
+            "POST",
+            "/collections/test/points"
+        ));
+        assert!(is_read_only_rest_request(
+            "POST",
+            "/collections/search/batch"
+        ));
+        assert!(!is_read_only_rest_request(
+            "POST",
+            "/collections/test/points/delete"
+        ));
+        assert!(!is_read_only_rest_request(
+            "PUT",
+            "/collections/test/points"
+        ));
+        assert!(!is_read_only_rest_request("DELETE", "/collections/test"));
+        assert!(!is_read_only_rest_request("POST", "/snapshots"));
+
+        assert!(is_read_only_grpc_method("/qdrant.Points/Search"));
+        assert!(is_read_only_grpc_method(
+            "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo"
+        ));
+        assert!(!is_read_only_grpc_method("/qdrant.Points/Upsert"));
+        assert!(!is_read_only_grpc_method("/qdrant.Collections/Delete"));
+    }
+
+    #[test]
+    fn test_required_access() {
+        use AccessLevel::*;
+
+        assert_eq!(
+            rest_required_access("POST", "/collections/test/points/search"),
+            RequiredAccess::Collection("test", Read),
+        );
+        assert_eq!(
+            rest_required_access("PUT", "/collections/test/points"),
+            RequiredAccess::Collection("test", Write),
+        );
+        assert_eq!(
+            rest_required_access("PUT", "/collections/test/index"),
+            RequiredAccess::Collection("test", Manage),
+        );
+        assert_eq!(
+            rest_required_access("DELETE", "/collections/test"),
+            RequiredAccess::Collection("test", Manage),
+        );
+        assert_eq!(
+            rest_required_access("GET", "/collections"),
+            RequiredAccess::Global(Read),
+        );
+        assert_eq!(
+            rest_required_access("POST", "/collections/aliases"),
+            RequiredAccess::Global(Manage),
+        );
+
+        assert_eq!(
+            grpc_required_access("/qdrant.Points/Upsert"),
+            GrpcRequiredAccess::Collection(Write),
+        );
+        assert_eq!(
+            grpc_required_access("/qdrant.Points/CreateFieldIndex"),
+            GrpcRequiredAccess::Collection(Manage),
+        );
+        assert_eq!(
+            grpc_required_access("/qdrant.Collections/List"),
+            GrpcRequiredAccess::Global(Read),
+        );
+        assert_eq!(
+            grpc_required_access("/qdrant.Snapshots/CreateFull"),
+            GrpcRequiredAccess::Global(Manage),
+        );
+        assert_eq!(
+            grpc_required_access("/qdrant.Cluster/UpdatePeerAddress"),
+            GrpcRequiredAccess::Global(Manage),
+        );
+        assert_eq!(
+            grpc_required_access("/grpc.health.v1.Health/Watch"),
+            GrpcRequiredAccess::Collection(Read),
+        );
+    }
+
+    #[test]
+    fn test_collection_access() {
+        let access: Access = serde_json::from_str(
+            r#"{"collections": [{"collection": "team_a_*", "access": "write"}, {"collection": "shared", "access": "read"}]}"#,
+        )
+        .unwrap();
+
+        let check = |collection_name, level| {
+            access
+                .check(RequiredAccess::Collection(collection_name, level))
+                .is_ok()
+        };
+        assert!(check("team_a_docs", AccessLevel::Write));
+        assert!(!check("team_a_docs", AccessLevel::Manage));
+        assert!(check("shared", AccessLevel::Read));
+        assert!(!check("shared", AccessLevel::Write));
+        assert!(!check("team_b_docs", AccessLevel::Read));
+        assert!(access
+            .check(RequiredAccess::Global(AccessLevel::Read))
+            .is_err());
+        assert!(access.check(RequiredAccess::Any).is_ok());
+        assert!(access.allows_some_collection(AccessLevel::Write));
+        assert!(!access.allows_some_collection(AccessLevel::Manage));
+
+        assert!(Access::read_only()
+            .check(RequiredAccess::Collection("any", AccessLevel::Read))
+            .is_ok());
+        assert!(Access::read_only()
+            .check(RequiredAccess::Collection("any", AccessLevel::Write))
+            .is_err());
+        assert!(Access::full()
+            .check(RequiredAccess::Global(AccessLevel::Manage))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_lookup_collections_access() {
+        let access: Access = serde_json::from_str(
+            r#"{"collections": [{"collection": "team_a_*", "access": "read"}]}"#,
+        )
+        .unwrap();
+        let check = |request: &dyn LookupCollections| {
+            access.check_collections(
+                "team_a_docs",
+                AccessLevel::Read,
+                request.lookup_collections(),
+                |_| None,
+            )
+        };
+
+        let request: SearchGroupsRequest = serde_json::from_value(serde_json::json!({
+            "vector": [0.1, 0.2],
+            "group_by": "document_id",
+            "group_size": 1,
+            "limit": 10,
+            "with_lookup": "team_b_docs",
+        }))
+        .unwrap();
+        assert_eq!(request.lookup_collections(), vec!["team_b_docs"]);
+        assert!(check(&request).is_err());
+
+        let request: RecommendGroupsRequest = serde_json::from_value(serde_json::json!({
+            "positive": [1],
+            "group_by": "document_id",
+            "group_size": 1,
+            "limit": 10,
+            "lookup_from": {"collection": "team_a_vectors"},
+            "with_lookup": {"collection": "team_b_docs"},
+        }))
+        .unwrap();
+        assert_eq!(
+            request.lookup_collections(),
+            vec!["team_a_vectors", "team_b_docs"],
+        );
+        assert!(check(&request).is_err());
+
+        let request: RecommendRequestBatch = serde_json::from_value(serde_json::json!({
+            "searches": [
+                {"positive": [1], "limit": 10},
+                {"positive": [1], "limit": 10, "lookup_from": {"collection": "team_b_docs"}},
+            ],
+        }))
+        .unwrap();
+        assert!(check(&request).is_err());
+
+        let request: QueryRequest = serde_json::from_value(serde_json::json!({
+            "prefetch": [{
+                "prefetch": [{
+                    "query": {"recommend": {"positive": [1]}},
+                    "lookup_from": {"collection": "team_b_docs"},
+                }],
+            }],
+        }))
+        .unwrap();
+        assert_eq!(request.lookup_collections(), vec!["team_b_docs"]);
+        assert!(check(&request).is_err());
+
+        let request: QueryRequest = serde_json::from_value(serde_json::json!({
+            "query": {"recommend": {"positive": [1]}},
+            "lookup_from": {"collection": "team_a_vectors"},
+        }))
+        .unwrap();
+        assert!(check(&request).is_ok());
+    }
+
+    #[test]
+    fn test_alias_access() {
+        let access: Access = serde_json::from_str(
+            r#"{"collections": [{"collection": "team_a_*", "access": "write"}]}"#,
+        )
+        .unwrap();
+        let aliases = HashMap::from([
+            ("team_a_secret".to_string(), "team_b_docs".to_string()),
+            ("latest".to_string(), "team_a_docs_v2".to_string()),
+        ]);
+        let check = |collection_name, lookup_collections: &[&str]| {
+            access.check_collections(
+                collection_name,
+                AccessLevel::Read,
+                lookup_collections.iter().copied(),
+                |name| aliases.get(name).cloned(),
+            )
+        };
+
+        // Alias matches the prefix, but refers to a collection of another team
+        assert_eq!(
+            check("team_a_secret", &[]),
+            Err("No read access to collection team_a_secret".to_string()),
+        );
+        assert!(check("team_a_docs", &["team_a_secret"]).is_err());
+
+        // Alias doesn't match the prefix, but refers to a collection of the team
+        assert!(check("latest", &[]).is_ok());
+        assert!(check("team_a_docs", &["latest"]).is_ok());
+        assert!(check("team_b_docs", &[]).is_err());
+    }
+}
@@ -132,15 +132,36 @@ fn load_certified_key(tls_config: &TlsConfig) -> Result<Arc<CertifiedKey>> {
 ///
 /// Uses TLS settings as configured in configuration by user.
 pub fn actix_tls_server_config(settings: &Settings) -> Result<ServerConfig> {
-    let config = ServerConfig::builder().with_safe_defaults();
     let tls_config = settings
         .tls
         .clone()
         .ok_or_else(Settings::tls_config_is_undefined_error)
         .map_err(Error::Io)?;
 
+    tls_server_config(tls_config, settings.service.verify_https_client_certificate)
+}
+
+/// Generate a gRPC server configuration with TLS, which negotiates HTTP/2
+///
+/// Internal gRPC always verifies client certificates, so peers authenticate each other.
+pub fn grpc_tls_server_config(
+    tls_config: TlsConfig,
+    verify_client_certificate: bool,
+) -> Result<ServerConfig> {
+    let mut config = tls_server_config(tls_config, verify_client_certificate)?;
+    config.alpn_protocols = vec![b"h2".to_vec()];
+    Ok(config)
+}
+
+/// Generate a server configuration with TLS, which reloads the certificate once its TTL expires
+fn tls_server_config(
+    tls_config: TlsConfig,
+    verify_client_certificate: bool,
+) -> Result<ServerConfig> {
+    let config = ServerConfig::builder().with_safe_defaults();
+
     // Verify client CA or not
-    let config = if settings.service.verify_https_client_certificate {
+    let config = if verify_client_certificate {
         let mut root_cert_store = RootCertStore::empty();
         let ca_certs: Vec<Vec<u8>> = with_buf_read(&tls_config.ca_cert, rustls_pemfile::certs)?;
         root_cert_store.add_parsable_certificates(&ca_certs[..]);
@@ -167,7 +188,7 @@ fn with_buf_read<T>(path: &str, f: impl FnOnce(&mut dyn BufRead) -> io::Result<T
     f(dyn_reader).map_err(|err| Error::ReadFile(err, path.into()))
 }
 
-/// Actix TLS errors.
+/// TLS errors.
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error("TLS file could not be opened: {1}")]
@@ -185,3 +206,76 @@ pub enum Error {
     #[error("TLS signing error")]
     Sign(#[source] rustls::sign::SignError),
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::thread;
+
+    use super::*;
+
+    const CERT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tls/cert");
+
+    /// TLS config of the copies of the test certificate, so the test can change the files
+    fn tls_config(dir: &Path) -> TlsConfig {
+        for file in ["cert.pem", "key.pem", "cacert.pem"] {
+            fs::copy(Path::new(CERT_DIR).join(file), dir.join(file)).unwrap();
+        }
+        let path = |file: &str| dir.join(file).to_str().unwrap().to_string();
+        TlsConfig {
+            cert: path("cert.pem"),
+            key: path("key.pem"),
+            ca_cert: path("cacert.pem"),
+            cert_ttl: None,
+        }
+    }
+
+    #[test]
+    fn test_certificate_reloaded_after_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let ttl = Duration::from_millis(200);
+        let resolver = RotatingCertificateResolver::new(tls_config(dir.path()), Some(ttl)).unwrap();
+
+        let key = resolver.get_key_or_refresh();
+        assert!(Arc::ptr_eq(&key, &resolver.get_key_or_refresh()));
+
+        thread::sleep(ttl * 2);
+        let reloaded = resolver.get_key_or_refresh();
+        assert!(!Arc::ptr_eq(&key, &reloaded));
+        assert_eq!(key.cert, reloaded.cert);
+        // TTL starts over from the reload
+        assert!(Arc::ptr_eq(&reloaded, &resolver.get_key_or_refresh()));
+    }
+
+    #[test]
+    fn test_certificate_not_reloaded_without_ttl() {
+        let dir = tempfile::tempdir().unwrap();
+        let resolver = RotatingCertificateResolver::new(tls_config(dir.path()), None).unwrap();
+
+        let key = resolver.get_key_or_refresh();
+        fs::remove_file(dir.path().join("cert.pem")).unwrap();
+        assert!(Arc::ptr_eq(&key, &resolver.get_key_or_refresh()));
+    }
+
+    #[test]
+    fn test_certificate_kept_if_reload_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        // Key expires right away, so it is reloaded on every handshake
+        let resolver =
+            RotatingCertificateResolver::new(tls_config(dir.path()), Some(Duration::ZERO)).unwrap();
+        let key = resolver.get_key_or_refresh();
+
+        fs::write(dir.path().join("key.pem"), "not a key").unwrap();
+        assert!(Arc::ptr_eq(&key, &resolver.get_key_or_refresh()));
+
+        fs::remove_file(dir.path().join("cert.pem")).unwrap();
+        assert!(Arc::ptr_eq(&key, &resolver.get_key_or_refresh()));
+
+        // Reloaded again once the files are fixed
+        tls_config(dir.path());
+        let reloaded = resolver.get_key_or_refresh();
+        assert!(!Arc::ptr_eq(&key, &reloaded));
+        assert_eq!(key.cert, reloaded.cert);
+    }
+}
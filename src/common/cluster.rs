@@ -0,0 +1,39 @@
+use std::time::Duration;
+
+use storage::content_manager::consensus_ops::ConsensusOperations;
+use storage::content_manager::errors::StorageError;
+use storage::dispatcher::Dispatcher;
+use tonic::transport::Uri;
+
+/// Change the address, by which the other peers reach the given peer, through consensus
+pub async fn do_update_peer_address(
+    dispatcher: &Dispatcher,
+    peer_id: u64,
+    uri: String,
+    wait_timeout: Option<Duration>,
+) -> Result<bool, StorageError> {
+    if let Err(err) = uri.parse::<Uri>() {
+        return Err(StorageError::BadRequest {
+            description: format!("Invalid peer uri {uri}: {err}"),
+        });
+    }
+
+    let Some(consensus_state) = dispatcher.consensus_state() else {
+        return Err(StorageError::BadRequest {
+            description: "Distributed mode disabled.".to_string(),
+        });
+    };
+
+    if !consensus_state.peer_address_by_id().contains_key(&peer_id) {
+        return Err(StorageError::NotFound {
+            description: format!("Peer {peer_id} is not known to the cluster"),
+        });
+    }
+
+    consensus_state
+        .propose_consensus_op_with_await(
+            ConsensusOperations::update_peer_address(peer_id, uri),
+            wait_timeout,
+        )
+        .await
+}
@@ -1,23 +1,37 @@
 use std::time::Duration;
 
 use api::grpc::models::{CollectionDescription, CollectionsResponse};
+use collection::common::optimize_tasks::{OptimizeTaskInfo, OptimizeTasks};
+use collection::common::snapshot_retention::SnapshotRetention;
+use collection::common::snapshot_schedule::{
+    SnapshotSchedule, SnapshotScheduleInfo, SnapshotTarget,
+};
 use collection::operations::cluster_ops::{
     AbortTransferOperation, ClusterOperations, DropReplicaOperation, MoveShardOperation,
     ReplicateShardOperation,
 };
-use collection::operations::snapshot_ops::SnapshotDescription;
+use collection::operations::snapshot_ops::{
+    verify_snapshot, RecoveryWindow, SnapshotDescription, SnapshotVerification,
+};
 use collection::operations::types::{
-    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
+    AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionResult,
+    CollectionsAliasesResponse, ShardSegmentsInfo, ShardWalInfo,
 };
+use collection::optimizers_builder::{OptimizeTarget, RebuildIndexTarget};
 use collection::shards::replica_set;
 use collection::shards::shard::ShardId;
 use collection::shards::transfer::shard_transfer::{ShardTransfer, ShardTransferKey};
+use futures::future::BoxFuture;
+use futures::stream::FuturesUnordered;
+use futures::StreamExt as _;
 use itertools::Itertools;
+use segment::index::field_index::stats::PayloadIndexStatistics;
 use storage::content_manager::collection_meta_ops::ShardTransferOperations::{Abort, Start};
 use storage::content_manager::collection_meta_ops::{
     CollectionMetaOperations, UpdateCollectionOperation,
 };
 use storage::content_manager::errors::StorageError;
+use storage::content_manager::snapshots::upload_snapshot_to_s3;
 use storage::content_manager::toc::TableOfContent;
 use storage::dispatcher::Dispatcher;
 
@@ -30,6 +44,15 @@ pub async fn do_get_collection(
     Ok(collection.info(shard_selection).await?)
 }
 
+pub async fn do_get_payload_index_stats(
+    toc: &TableOfContent,
+    name: &str,
+    field_name: &str,
+) -> Result<PayloadIndexStatistics, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.payload_index_stats(field_name).await?)
+}
+
 pub async fn do_list_collections(toc: &TableOfContent) -> CollectionsResponse {
     let collections = toc
         .all_collections()
@@ -73,14 +96,39 @@ pub async fn do_list_snapshots(
         .await?)
 }
 
+pub async fn do_list_recovery_windows(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<Vec<RecoveryWindow>, StorageError> {
+    Ok(toc
+        .get_collection(collection_name)
+        .await?
+        .recovery_windows()
+        .await?)
+}
+
 pub async fn do_create_snapshot(
     dispatcher: &Dispatcher,
     collection_name: &str,
+    base_snapshot: Option<&str>,
     wait: bool,
 ) -> Result<SnapshotDescription, StorageError> {
     let collection = collection_name.to_string();
+    let base_snapshot = base_snapshot.map(str::to_string);
     let dispatcher = dispatcher.clone();
-    let snapshot = tokio::spawn(async move { dispatcher.create_snapshot(&collection).await });
+    let snapshot = tokio::spawn(async move {
+        let snapshot = dispatcher
+            .create_snapshot(&collection, base_snapshot.as_deref())
+            .await?;
+        // Don't hold the collection during the upload
+        let (collection_name, snapshot_path) = {
+            let collection = dispatcher.get_collection(&collection).await?;
+            let snapshot_path = collection.get_snapshot_path(&snapshot.name).await?;
+            (collection.name(), snapshot_path)
+        };
+        upload_snapshot_to_s3(dispatcher.toc(), Some(&collection_name), &snapshot_path).await?;
+        Ok::<_, StorageError>(snapshot)
+    });
     if wait {
         Ok(snapshot.await??)
     } else {
@@ -88,10 +136,72 @@ pub async fn do_create_snapshot(
             name: "".to_string(),
             creation_time: None,
             size: 0,
+            checksum: None,
+            version: None,
+            creation_duration_sec: None,
+            collection_params: None,
+            compatible: None,
         })
     }
 }
 
+pub async fn do_verify_snapshot(
+    toc: &TableOfContent,
+    collection_name: &str,
+    snapshot_name: &str,
+) -> Result<SnapshotVerification, StorageError> {
+    // Don't hold the collection while reading the snapshot
+    let snapshot_path = toc
+        .get_collection(collection_name)
+        .await?
+        .get_snapshot_path(snapshot_name)
+        .await?;
+    Ok(verify_snapshot(&snapshot_path).await?)
+}
+
+pub async fn do_get_snapshot_schedule(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<Option<SnapshotScheduleInfo>, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.scheduled_snapshots().info())
+}
+
+pub async fn do_set_snapshot_schedule(
+    toc: &TableOfContent,
+    collection_name: &str,
+    schedule: Option<SnapshotSchedule>,
+) -> Result<bool, StorageError> {
+    if let Some(schedule) = &schedule {
+        if schedule.target == SnapshotTarget::S3 && toc.snapshots_s3_config().is_none() {
+            return Err(StorageError::bad_request(
+                "Snapshots can't be scheduled to S3, storage.snapshots_s3 is not configured",
+            ));
+        }
+    }
+    let collection = toc.get_collection(collection_name).await?;
+    collection.scheduled_snapshots().set(schedule)?;
+    Ok(true)
+}
+
+pub async fn do_get_snapshot_retention(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<Option<SnapshotRetention>, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.snapshot_retention().get())
+}
+
+pub async fn do_set_snapshot_retention(
+    toc: &TableOfContent,
+    collection_name: &str,
+    retention: Option<SnapshotRetention>,
+) -> Result<bool, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    collection.snapshot_retention().set(retention)?;
+    Ok(true)
+}
+
 pub async fn do_get_collection_cluster(
     toc: &TableOfContent,
     name: &str,
@@ -100,6 +210,124 @@ pub async fn do_get_collection_cluster(
     Ok(collection.cluster_info(toc.this_peer_id).await?)
 }
 
+/// Start optimizing the local shards of the collection right away, in the background
+pub async fn do_optimize_collection(
+    toc: &TableOfContent,
+    name: &str,
+    target: Option<OptimizeTarget>,
+) -> Result<OptimizeTaskInfo, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    let optimizations = collection.launch_forced_optimization(target).await;
+    Ok(spawn_optimize_task(
+        collection.optimize_tasks(),
+        name,
+        target,
+        optimizations,
+    ))
+}
+
+/// Start rebuilding indexes of the local shards of the collection, in the background.
+/// Only segments with an index on `field_name` are rebuilt, if it is given.
+pub async fn do_rebuild_index(
+    toc: &TableOfContent,
+    name: &str,
+    target: RebuildIndexTarget,
+    field_name: Option<String>,
+) -> Result<OptimizeTaskInfo, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    let rebuilds = collection.launch_index_rebuild(target, field_name).await?;
+    Ok(spawn_optimize_task(
+        collection.optimize_tasks(),
+        name,
+        Some(OptimizeTarget::RebuildIndex),
+        rebuilds,
+    ))
+}
+
+/// Track the launched optimizations of the local shards as a task, until all of them are over
+fn spawn_optimize_task(
+    optimize_tasks: &OptimizeTasks,
+    name: &str,
+    target: Option<OptimizeTarget>,
+    optimizations: Vec<BoxFuture<'static, CollectionResult<()>>>,
+) -> OptimizeTaskInfo {
+    let mut optimizations: FuturesUnordered<_> = optimizations.into_iter().collect();
+    let task = optimize_tasks.start(target, optimizations.len());
+    let info = task.info();
+
+    let collection_name = name.to_string();
+    tokio::spawn(async move {
+        let mut result = Ok(());
+        while let Some(shard_result) = optimizations.next().await {
+            match shard_result {
+                Ok(()) => task.shard_done(),
+                Err(err) => {
+                    log::error!("Failed to optimize collection {collection_name}: {err}");
+                    if result.is_ok() {
+                        result = Err(err);
+                    }
+                }
+            }
+        }
+        task.finish(result);
+    });
+
+    info
+}
+
+/// Pause or resume background optimizations of the local shards of the collection
+pub async fn do_set_optimizers_paused(
+    toc: &TableOfContent,
+    name: &str,
+    paused: bool,
+) -> Result<bool, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    collection.set_optimizers_paused(paused).await?;
+    Ok(true)
+}
+
+pub async fn do_get_optimize_tasks(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<Vec<OptimizeTaskInfo>, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.optimize_tasks().list())
+}
+
+pub async fn do_get_optimize_task(
+    toc: &TableOfContent,
+    name: &str,
+    task_id: &str,
+) -> Result<OptimizeTaskInfo, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.optimize_tasks().info(task_id)?)
+}
+
+pub async fn do_get_segments_info(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<Vec<ShardSegmentsInfo>, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.segments_info().await)
+}
+
+pub async fn do_get_wal_info(
+    toc: &TableOfContent,
+    name: &str,
+) -> Result<Vec<ShardWalInfo>, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.wal_info().await?)
+}
+
+pub async fn do_truncate_retained_wal(
+    toc: &TableOfContent,
+    name: &str,
+    shard_id: ShardId,
+) -> Result<ShardWalInfo, StorageError> {
+    let collection = toc.get_collection(name).await?;
+    Ok(collection.truncate_retained_wal(shard_id).await?)
+}
+
 pub async fn do_update_collection_cluster(
     toc: &TableOfContent,
     collection_name: String,
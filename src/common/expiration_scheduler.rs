@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use storage::dispatcher::Dispatcher;
+
+/// Interval at which expired points are deleted
+const EXPIRATION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Deletes points of collections with an expiration key, which expiration time has passed.
+///
+/// Each peer deletes expired points of the shards it leads, deletions are replicated
+/// to the other peers like regular updates.
+pub struct ExpirationScheduler {
+    dispatcher: Dispatcher,
+}
+
+impl ExpirationScheduler {
+    pub async fn run(dispatcher: Dispatcher) {
+        let scheduler = Self { dispatcher };
+        loop {
+            tokio::time::sleep(EXPIRATION_INTERVAL).await;
+            scheduler.expire_points().await;
+        }
+    }
+
+    async fn expire_points(&self) {
+        for collection_name in self.dispatcher.all_collections().await {
+            let Ok(collection) = self.dispatcher.get_collection(&collection_name).await else {
+                continue;
+            };
+            match collection.expire_points().await {
+                Ok(0) => {}
+                Ok(deleted) => {
+                    log::debug!("Deleted {deleted} expired points of collection {collection_name}")
+                }
+                Err(err) => {
+                    log::error!(
+                        "Failed to delete expired points of collection {collection_name}: {err}"
+                    )
+                }
+            }
+        }
+    }
+}
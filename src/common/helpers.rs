@@ -7,7 +7,7 @@ use segment::common::cpu::get_num_cpus;
 use serde::{Deserialize, Serialize};
 use tokio::runtime;
 use tokio::runtime::Runtime;
-use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+use tonic::transport::{Certificate, ClientTlsConfig, Identity};
 use validator::Validate;
 
 use crate::settings::{Settings, TlsConfig};
@@ -95,18 +95,6 @@ pub fn load_tls_client_config(settings: &Settings) -> io::Result<Option<ClientTl
     }
 }
 
-/// Load server TLS configuration for external gRPC
-pub fn load_tls_external_server_config(tls_config: &TlsConfig) -> io::Result<ServerTlsConfig> {
-    Ok(ServerTlsConfig::new().identity(load_identity(tls_config)?))
-}
-
-/// Load server TLS configuration for internal gRPC, check client certificate against CA
-pub fn load_tls_internal_server_config(tls_config: &TlsConfig) -> io::Result<ServerTlsConfig> {
-    Ok(ServerTlsConfig::new()
-        .identity(load_identity(tls_config)?)
-        .client_ca_root(load_ca_certificate(tls_config)?))
-}
-
 fn load_identity(tls_config: &TlsConfig) -> io::Result<Identity> {
     let cert = fs::read_to_string(&tls_config.cert)?;
     let key = fs::read_to_string(&tls_config.key)?;
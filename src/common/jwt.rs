@@ -0,0 +1,110 @@
+use std::{fs, io};
+
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+
+use crate::common::auth::Access;
+use crate::settings::JwtConfig;
+
+/// Validation of the JWT tokens, which grant [`Access`] by their claims
+#[derive(Clone)]
+pub struct JwtValidator {
+    key: DecodingKey,
+    validation: Validation,
+}
+
+impl JwtValidator {
+    pub fn new(config: &JwtConfig) -> io::Result<Self> {
+        let invalid_config = |message: String| io::Error::new(io::ErrorKind::InvalidInput, message);
+
+        let key = match config.algorithm {
+            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                let secret = config.secret.as_ref().ok_or_else(|| {
+                    invalid_config(format!(
+                        "JWT secret must be set for the {:?} algorithm",
+                        config.algorithm,
+                    ))
+                })?;
+                DecodingKey::from_secret(secret.as_bytes())
+            }
+            Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 => {
+                let public_key_path = config.public_key_path.as_ref().ok_or_else(|| {
+                    invalid_config(format!(
+                        "JWT public key must be set for the {:?} algorithm",
+                        config.algorithm,
+                    ))
+                })?;
+                DecodingKey::from_rsa_pem(&fs::read(public_key_path)?).map_err(|err| {
+                    invalid_config(format!("Invalid JWT public key {public_key_path}: {err}"))
+                })?
+            }
+            algorithm => {
+                return Err(invalid_config(format!(
+                    "Unsupported JWT algorithm {algorithm:?}"
+                )))
+            }
+        };
+
+        let mut validation = Validation::new(config.algorithm);
+        if let Some(issuer) = &config.issuer {
+            validation.set_issuer(&[issuer]);
+        }
+
+        Ok(Self { key, validation })
+    }
+
+    /// Access, granted by the claims of the token.
+    ///
+    /// Fails if the signature is invalid or the token is expired.
+    pub fn validate(&self, token: &str) -> Result<Access, String> {
+        decode::<Access>(token, &self.key, &self.validation)
+            .map(|token_data| token_data.claims)
+            .map_err(|err| format!("Invalid token: {err}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use serde_json::json;
+
+    use super::*;
+    use crate::common::auth::{AccessLevel, RequiredAccess};
+
+    #[test]
+    fn test_validate_token() {
+        let config = JwtConfig {
+            algorithm: Algorithm::HS256,
+            secret: Some("secret".to_string()),
+            public_key_path: None,
+            issuer: None,
+        };
+        let validator = JwtValidator::new(&config).unwrap();
+
+        let token = |exp: u64, secret: &str| {
+            let claims = json!({
+                "exp": exp,
+                "collections": [{"collection": "team_a_*", "access": "write"}],
+            });
+            let key = EncodingKey::from_secret(secret.as_bytes());
+            encode(&Header::new(Algorithm::HS256), &claims, &key).unwrap()
+        };
+
+        let access = validator.validate(&token(u64::MAX / 2, "secret")).unwrap();
+        assert!(access
+            .check(RequiredAccess::Collection(
+                "team_a_docs",
+                AccessLevel::Write
+            ))
+            .is_ok());
+        assert!(access
+            .check(RequiredAccess::Collection("team_b_docs", AccessLevel::Read))
+            .is_err());
+
+        // Expired
+        assert!(validator.validate(&token(1, "secret")).is_err());
+        // Signed by another key
+        assert!(validator
+            .validate(&token(u64::MAX / 2, "other secret"))
+            .is_err());
+    }
+}
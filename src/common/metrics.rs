@@ -1,5 +1,9 @@
-use prometheus::proto::{Counter, Gauge, LabelPair, Metric, MetricFamily, MetricType};
+use collection::collection_manager::optimizers::TrackerStatus;
+use prometheus::proto::{
+    Bucket, Counter, Gauge, Histogram, LabelPair, Metric, MetricFamily, MetricType,
+};
 use prometheus::TextEncoder;
+use segment::common::operation_time_statistics::OperationDurationStatistics;
 
 use crate::common::telemetry::TelemetryData;
 use crate::common::telemetry_ops::app_telemetry::{AppBuildTelemetry, AppFeaturesTelemetry};
@@ -8,33 +12,38 @@ use crate::common::telemetry_ops::collections_telemetry::{
     CollectionTelemetryEnum, CollectionsTelemetry,
 };
 use crate::common::telemetry_ops::requests_telemetry::{
-    GrpcTelemetry, RequestsTelemetry, WebApiTelemetry,
+    GrpcTelemetry, RateLimitTelemetry, RequestsTelemetry, WebApiTelemetry,
 };
 
 /// Whitelist for REST endpoints in metrics output.
 ///
-/// Contains selection of search, recommend and upsert endpoints.
+/// Contains selection of search, recommend, read and upsert endpoints.
 ///
 /// This array *must* be sorted.
 const REST_ENDPOINT_WHITELIST: &[&str] = &[
     "/collections/{name}/index",
     "/collections/{name}/points",
+    "/collections/{name}/points/count",
     "/collections/{name}/points/payload",
     "/collections/{name}/points/recommend",
     "/collections/{name}/points/recommend/batch",
+    "/collections/{name}/points/scroll",
     "/collections/{name}/points/search",
     "/collections/{name}/points/search/batch",
 ];
 
 /// Whitelist for GRPC endpoints in metrics output.
 ///
-/// Contains selection of search, recommend and upsert endpoints.
+/// Contains selection of search, recommend, read and upsert endpoints.
 ///
 /// This array *must* be sorted.
 const GRPC_ENDPOINT_WHITELIST: &[&str] = &[
+    "/qdrant.Points/Count",
+    "/qdrant.Points/Get",
     "/qdrant.Points/OverwritePayload",
     "/qdrant.Points/Recommend",
     "/qdrant.Points/RecommendBatch",
+    "/qdrant.Points/Scroll",
     "/qdrant.Points/Search",
     "/qdrant.Points/SearchBatch",
     "/qdrant.Points/SetPayload",
@@ -126,6 +135,172 @@ impl MetricsProvider for CollectionsTelemetry {
             MetricType::GAUGE,
             vec![gauge(vector_count as f64, &[])],
         ));
+
+        let (cache_hits, cache_misses) = self
+            .collections
+            .iter()
+            .flatten()
+            .filter_map(|p| match p {
+                CollectionTelemetryEnum::Aggregated(a) => a.search_cache.as_ref(),
+                CollectionTelemetryEnum::Full(c) => c.search_cache.as_ref(),
+            })
+            .fold((0, 0), |(hits, misses), cache| {
+                (hits + cache.hits, misses + cache.misses)
+            });
+        metrics.push(metric_family(
+            "collections_search_cache_hits_total",
+            "number of searches served from the search caches of collections",
+            MetricType::COUNTER,
+            vec![counter(cache_hits as f64, &[])],
+        ));
+        metrics.push(metric_family(
+            "collections_search_cache_misses_total",
+            "number of searches missed by the search caches of collections",
+            MetricType::COUNTER,
+            vec![counter(cache_misses as f64, &[])],
+        ));
+
+        self.add_collection_metrics(metrics);
+    }
+}
+
+impl CollectionsTelemetry {
+    /// Series per collection, only available in full collection telemetry.
+    /// Only local shards of the collections are counted.
+    fn add_collection_metrics(&self, metrics: &mut Vec<MetricFamily>) {
+        let (mut points, mut vectors, mut segments, mut wal_size) =
+            (vec![], vec![], vec![], vec![]);
+        let (mut optimizations_running, mut optimizations_pending) = (vec![], vec![]);
+        let (mut optimizations_total, mut optimizations_fail_total) = (vec![], vec![]);
+        let (mut search_durations, mut update_durations) = (vec![], vec![]);
+        for collection in self.collections.iter().flatten() {
+            let CollectionTelemetryEnum::Full(collection) = collection else {
+                continue;
+            };
+            let labels = [("collection", collection.id.as_str())];
+            let local_shards: Vec<_> = collection
+                .shards
+                .iter()
+                .filter_map(|shard| shard.local.as_ref())
+                .collect();
+
+            let shard_segments = || local_shards.iter().flat_map(|shard| &shard.segments);
+            points.push(gauge(
+                shard_segments().map(|s| s.info.num_points).sum::<usize>() as f64,
+                &labels,
+            ));
+            vectors.push(gauge(collection.count_vectors() as f64, &labels));
+            segments.push(gauge(shard_segments().count() as f64, &labels));
+            wal_size.push(gauge(
+                local_shards
+                    .iter()
+                    .filter_map(|shard| shard.wal_size_bytes)
+                    .sum::<u64>() as f64,
+                &labels,
+            ));
+
+            let optimizers = || local_shards.iter().map(|shard| &shard.optimizations);
+            optimizations_running.push(gauge(
+                optimizers()
+                    .flat_map(|optimizer| &optimizer.log)
+                    .filter(|tracker| tracker.status == TrackerStatus::Optimizing)
+                    .count() as f64,
+                &labels,
+            ));
+            optimizations_pending.push(gauge(
+                optimizers()
+                    .map(|optimizer| optimizer.pending.len())
+                    .sum::<usize>() as f64,
+                &labels,
+            ));
+            let optimizations = optimizers()
+                .fold(OperationDurationStatistics::default(), |acc, optimizer| {
+                    acc + optimizer.optimizations.clone()
+                });
+            optimizations_total.push(counter(optimizations.count as f64, &labels));
+            optimizations_fail_total.push(counter(optimizations.fail_count as f64, &labels));
+
+            for shard in &collection.shards {
+                let Some(local) = &shard.local else {
+                    continue;
+                };
+                let shard_id = shard.id.to_string();
+                let labels = [
+                    ("collection", collection.id.as_str()),
+                    ("shard", shard_id.as_str()),
+                ];
+                search_durations.extend(histogram(&local.searches, &labels));
+                update_durations.extend(histogram(&local.updates, &labels));
+            }
+        }
+
+        if points.is_empty() {
+            return;
+        }
+        metrics.push(metric_family(
+            "collection_points",
+            "number of points in the local shards of the collection",
+            MetricType::GAUGE,
+            points,
+        ));
+        metrics.push(metric_family(
+            "collection_vectors",
+            "number of vectors in the local shards of the collection",
+            MetricType::GAUGE,
+            vectors,
+        ));
+        metrics.push(metric_family(
+            "collection_segments",
+            "number of segments in the local shards of the collection",
+            MetricType::GAUGE,
+            segments,
+        ));
+        metrics.push(metric_family(
+            "collection_wal_size_bytes",
+            "size of the write-ahead logs of the local shards of the collection",
+            MetricType::GAUGE,
+            wal_size,
+        ));
+        metrics.push(metric_family(
+            "collection_optimizations_running",
+            "number of optimizations, currently running in the local shards of the collection",
+            MetricType::GAUGE,
+            optimizations_running,
+        ));
+        metrics.push(metric_family(
+            "collection_optimizations_pending",
+            "number of optimizations, waiting for a free optimization thread",
+            MetricType::GAUGE,
+            optimizations_pending,
+        ));
+        metrics.push(metric_family(
+            "collection_optimizations_total",
+            "total number of finished optimizations",
+            MetricType::COUNTER,
+            optimizations_total,
+        ));
+        metrics.push(metric_family(
+            "collection_optimizations_fail_total",
+            "total number of failed optimizations",
+            MetricType::COUNTER,
+            optimizations_fail_total,
+        ));
+        if !search_durations.is_empty() {
+            metrics.push(metric_family(
+                "collection_search_duration_seconds",
+                "duration histogram of the searches in the local shards of the collection",
+                MetricType::HISTOGRAM,
+                search_durations,
+            ));
+        }
+        if !update_durations.is_empty() {
+            metrics.push(metric_family(
+                "collection_update_duration_seconds",
+                "duration histogram of the updates in the local shards of the collection",
+                MetricType::HISTOGRAM,
+                update_durations,
+            ));
+        }
     }
 }
 
@@ -186,6 +361,33 @@ impl MetricsProvider for RequestsTelemetry {
     fn add_metrics(&self, metrics: &mut Vec<MetricFamily>) {
         self.rest.add_metrics(metrics);
         self.grpc.add_metrics(metrics);
+
+        if let Some(ref rate_limit) = self.rate_limit {
+            rate_limit.add_metrics(metrics);
+        }
+    }
+}
+
+impl MetricsProvider for RateLimitTelemetry {
+    fn add_metrics(&self, metrics: &mut Vec<MetricFamily>) {
+        metrics.push(metric_family(
+            "rate_limit_clients_total",
+            "total number of clients tracked by the rate limiter",
+            MetricType::GAUGE,
+            vec![gauge(self.clients as f64, &[])],
+        ));
+        metrics.push(metric_family(
+            "rate_limit_rejected_total",
+            "total number of requests rejected by the rate limiter",
+            MetricType::COUNTER,
+            vec![
+                counter(self.rejected_by_rate as f64, &[("reason", "rate")]),
+                counter(
+                    self.rejected_by_concurrency as f64,
+                    &[("reason", "concurrency")],
+                ),
+            ],
+        ));
     }
 }
 
@@ -193,6 +395,7 @@ impl MetricsProvider for WebApiTelemetry {
     fn add_metrics(&self, metrics: &mut Vec<MetricFamily>) {
         let (mut total, mut fail_total, mut avg_secs, mut min_secs, mut max_secs) =
             (vec![], vec![], vec![], vec![], vec![]);
+        let mut duration_secs = vec![];
         for (endpoint, responses) in &self.responses {
             let (method, endpoint) = endpoint.split_once(' ').unwrap();
 
@@ -223,6 +426,7 @@ impl MetricsProvider for WebApiTelemetry {
                         stats.max_duration_micros.unwrap_or(0.0) as f64 / 1_000_000.0,
                         &labels,
                     ));
+                    duration_secs.extend(histogram(stats, &labels));
                 }
             }
         }
@@ -267,6 +471,14 @@ impl MetricsProvider for WebApiTelemetry {
                 max_secs,
             ));
         }
+        if !duration_secs.is_empty() {
+            metrics.push(metric_family(
+                "rest_responses_duration_seconds",
+                "response duration histogram",
+                MetricType::HISTOGRAM,
+                duration_secs,
+            ));
+        }
     }
 }
 
@@ -274,6 +486,7 @@ impl MetricsProvider for GrpcTelemetry {
     fn add_metrics(&self, metrics: &mut Vec<MetricFamily>) {
         let (mut total, mut fail_total, mut avg_secs, mut min_secs, mut max_secs) =
             (vec![], vec![], vec![], vec![], vec![]);
+        let mut duration_secs = vec![];
         for (endpoint, stats) in &self.responses {
             // Endpoint must be whitelisted
             if GRPC_ENDPOINT_WHITELIST
@@ -298,6 +511,7 @@ impl MetricsProvider for GrpcTelemetry {
                 stats.max_duration_micros.unwrap_or(0.0) as f64 / 1_000_000.0,
                 &labels,
             ));
+            duration_secs.extend(histogram(stats, &labels));
         }
 
         if !total.is_empty() {
@@ -340,6 +554,14 @@ impl MetricsProvider for GrpcTelemetry {
                 max_secs,
             ));
         }
+        if !duration_secs.is_empty() {
+            metrics.push(metric_family(
+                "grpc_responses_duration_seconds",
+                "response duration histogram",
+                MetricType::HISTOGRAM,
+                duration_secs,
+            ));
+        }
     }
 }
 
@@ -374,6 +596,32 @@ fn gauge(value: f64, labels: &[(&str, &str)]) -> Metric {
     metric
 }
 
+/// Histogram of the durations in seconds, if the statistics have one
+fn histogram(stats: &OperationDurationStatistics, labels: &[(&str, &str)]) -> Option<Metric> {
+    let duration_histogram = stats.duration_histogram.as_ref()?;
+    let mut metric = Metric::default();
+    metric.set_label(labels.iter().map(|(n, v)| label_pair(n, v)).collect());
+    metric.set_histogram({
+        let mut histogram = Histogram::default();
+        histogram.set_sample_count(stats.count as u64);
+        histogram.set_sample_sum(duration_histogram.sum_micros / 1_000_000.0);
+        histogram.set_bucket(
+            duration_histogram
+                .buckets
+                .iter()
+                .map(|(upper_bound, cumulative_count)| {
+                    let mut bucket = Bucket::default();
+                    bucket.set_upper_bound(*upper_bound as f64 / 1_000_000.0);
+                    bucket.set_cumulative_count(*cumulative_count as u64);
+                    bucket
+                })
+                .collect(),
+        );
+        histogram
+    });
+    Some(metric)
+}
+
 fn label_pair(name: &str, value: &str) -> LabelPair {
     let mut label = LabelPair::default();
     label.set_name(name.into());
@@ -396,4 +644,45 @@ mod tests {
             "GRPC_ENDPOINT_WHITELIST must be sorted in code to allow binary search"
         );
     }
+
+    #[test]
+    fn test_response_duration_histogram() {
+        use std::collections::HashMap;
+        use std::time::Duration;
+
+        use segment::common::operation_time_statistics::OperationDurationsAggregator;
+
+        use super::{GrpcTelemetry, MetricsData, MetricsProvider};
+
+        let aggregator = OperationDurationsAggregator::new();
+        aggregator
+            .lock()
+            .add_operation_result(true, Duration::from_millis(3));
+        aggregator
+            .lock()
+            .add_operation_result(true, Duration::from_secs(20));
+        let telemetry = GrpcTelemetry {
+            responses: HashMap::from([(
+                "/qdrant.Points/Search".to_string(),
+                aggregator.lock().get_statistics_with_histogram(),
+            )]),
+        };
+
+        let mut metrics = vec![];
+        telemetry.add_metrics(&mut metrics);
+        let text = MetricsData { metrics }.format_metrics();
+
+        let bucket = |le: &str, count: usize| {
+            format!(
+                "grpc_responses_duration_seconds_bucket{{endpoint=\"/qdrant.Points/Search\",le=\"{le}\"}} {count}"
+            )
+        };
+        assert!(text.contains(&bucket("0.0025", 0)));
+        assert!(text.contains(&bucket("0.005", 1)));
+        assert!(text.contains(&bucket("10", 1)));
+        assert!(text.contains(&bucket("+Inf", 2)));
+        assert!(text.contains(
+            "grpc_responses_duration_seconds_count{endpoint=\"/qdrant.Points/Search\"} 2"
+        ));
+    }
 }
@@ -1,13 +1,24 @@
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod auth;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod certificate_helpers;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod cluster;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod collections;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod error_reporting;
+pub mod expiration_scheduler;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod helpers;
+pub mod jwt;
 pub mod metrics;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod points;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
+pub mod rate_limiter;
+pub mod snapshot_scheduler;
+#[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod stacktrace;
 #[allow(dead_code)] // May contain functions used in different binaries. Not actually dead
 pub mod telemetry;
@@ -1,27 +1,41 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use collection::common::delete_tasks::DeleteTaskInfo;
+use collection::common::import_tasks::ImportTaskInfo;
+use collection::operations::change_ops::{ChangesRequest, ChangesResult, DEFAULT_CHANGES_LIMIT};
 use collection::operations::consistency_params::ReadConsistency;
-use collection::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
+use collection::operations::payload_ops::{DeletePayload, MutatePayload, PayloadOps, SetPayload};
 use collection::operations::point_ops::{
-    PointInsertOperations, PointOperations, PointsSelector, WriteOrdering,
+    ConditionalInsertOperation, FilterSelector, PointInsertOperations, PointOperations,
+    PointStruct, PointsSelector, UpsertCondition, WriteOrdering,
 };
 use collection::operations::types::{
-    CoreSearchRequestBatch, CountRequest, CountResult, GroupsResult, PointRequest,
-    RecommendGroupsRequest, Record, ScrollRequest, ScrollResult, SearchGroupsRequest,
+    CollectionError, CollectionsSearchRequestBatch, CoreSearchRequestBatch, CountRequest,
+    CountRequestBatch, CountResult, GroupsResult, PartialSearchBatchResult, PointRequest,
+    QueryRequest, RecommendGroupsRequest, Record, ScrollRequest, ScrollResult, SearchGroupsRequest,
     SearchRequest, SearchRequestBatch, UpdateResult,
 };
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors, VectorOperations};
 use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use collection::shards::shard::ShardId;
+use futures::{future, stream, Stream, TryStreamExt as _};
 use schemars::JsonSchema;
-use segment::types::{PayloadFieldSchema, ScoredPoint};
+use segment::types::{
+    Filter, PayloadFieldSchema, PointIdType, ScoredPoint, WithPayloadInterface, WithVector,
+};
 use serde::{Deserialize, Serialize};
 use storage::content_manager::errors::StorageError;
 use storage::content_manager::toc::TableOfContent;
+use tokio::time::Instant;
 use validator::Validate;
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
 pub struct CreateFieldIndex {
     pub field_name: String,
     #[serde(alias = "field_type")]
+    #[validate]
     pub field_schema: Option<PayloadFieldSchema>,
 }
 
@@ -61,6 +75,12 @@ pub struct ClearPayloadOperation {
     clear_payload: PointsSelector,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct MutatePayloadOperation {
+    #[validate]
+    mutate_payload: MutatePayload,
+}
+
 #[derive(Deserialize, Serialize, JsonSchema, Validate)]
 pub struct UpdateVectorsOperation {
     #[validate]
@@ -83,6 +103,7 @@ pub enum UpdateOperation {
     OverwritePayload(OverwritePayloadOperation),
     DeletePayload(DeletePayloadOperation),
     ClearPayload(ClearPayloadOperation),
+    MutatePayload(MutatePayloadOperation),
     UpdateVectors(UpdateVectorsOperation),
     DeleteVectors(DeleteVectorsOperation),
 }
@@ -101,6 +122,7 @@ impl Validate for UpdateOperation {
             UpdateOperation::OverwritePayload(op) => op.validate(),
             UpdateOperation::DeletePayload(op) => op.validate(),
             UpdateOperation::ClearPayload(op) => op.validate(),
+            UpdateOperation::MutatePayload(op) => op.validate(),
             UpdateOperation::UpdateVectors(op) => op.validate(),
             UpdateOperation::DeleteVectors(op) => op.validate(),
         }
@@ -111,15 +133,23 @@ pub async fn do_upsert_points(
     toc: &TableOfContent,
     collection_name: &str,
     operation: PointInsertOperations,
+    condition: Option<UpsertCondition>,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let collection_operation =
-        CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(operation));
+    let point_operation = match condition {
+        None => PointOperations::UpsertPoints(operation),
+        Some(condition) => PointOperations::UpsertPointsConditional(ConditionalInsertOperation {
+            points: operation,
+            condition,
+        }),
+    };
+    let collection_operation = CollectionUpdateOperations::PointOperation(point_operation);
     toc.update(
         collection_name,
-        collection_operation,
+        collection_operation.with_idempotency_token(idempotency_token),
         shard_selection,
         wait,
         ordering,
@@ -131,22 +161,15 @@ pub async fn do_delete_points(
     toc: &TableOfContent,
     collection_name: &str,
     points: PointsSelector,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let point_operation = match points {
-        PointsSelector::PointIdsSelector(points) => {
-            PointOperations::DeletePoints { ids: points.points }
-        }
-        PointsSelector::FilterSelector(filter_selector) => {
-            PointOperations::DeletePointsByFilter(filter_selector.filter)
-        }
-    };
-    let collection_operation = CollectionUpdateOperations::PointOperation(point_operation);
+    let collection_operation = delete_points_operation(points);
     toc.update(
         collection_name,
-        collection_operation,
+        collection_operation.with_idempotency_token(idempotency_token),
         shard_selection,
         wait,
         ordering,
@@ -154,10 +177,286 @@ pub async fn do_delete_points(
     .await
 }
 
+/// Number of points upserted at once by an import
+const IMPORT_BATCH_SIZE: usize = 1_000;
+
+/// Number of import batches, which are upserted concurrently
+const IMPORT_CONCURRENCY: usize = 4;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ImportResult {
+    /// ID of the import task, which reported the progress of the import
+    pub task_id: String,
+    /// Number of imported points
+    pub points_imported: usize,
+    /// True, if the import was cancelled before the end of the stream
+    pub cancelled: bool,
+}
+
+/// Upsert a stream of points in batches.
+///
+/// Batches are routed to shards by the collection and upserted concurrently, the import stops
+/// at the first invalid point or failed batch. Batches upserted before that are kept.
+///
+/// The progress is tracked by an import task of the collection, which also allows to cancel
+/// the import. Cancelled imports stop after the batches in progress.
+pub async fn do_import_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    points: impl Stream<Item = Result<PointStruct, StorageError>>,
+    ordering: WriteOrdering,
+) -> Result<ImportResult, StorageError> {
+    // Fail early, if there is no collection to import into
+    let task = toc
+        .get_collection(collection_name)
+        .await?
+        .import_tasks()
+        .start();
+
+    let task_ref = &task;
+    let result = points
+        .inspect_ok(|_| task_ref.add_read(1))
+        .try_chunks(IMPORT_BATCH_SIZE)
+        .map_err(|err| err.1)
+        .try_take_while(|_| future::ready(Ok(!task_ref.is_cancelled())))
+        .map_ok(|batch| async move {
+            let count = batch.len();
+            do_upsert_points(
+                toc,
+                collection_name,
+                PointInsertOperations::PointsList(batch),
+                None,
+                None,
+                None,
+                true,
+                ordering,
+            )
+            .await?;
+            task_ref.add_imported(count);
+            Ok::<_, StorageError>(count)
+        })
+        .try_buffer_unordered(IMPORT_CONCURRENCY)
+        .try_fold(0, |total, count| future::ready(Ok(total + count)))
+        .await;
+
+    match result {
+        Ok(points_imported) => {
+            task.finish(Ok(()));
+            Ok(ImportResult {
+                task_id: task.id(),
+                points_imported,
+                cancelled: task.is_cancelled(),
+            })
+        }
+        Err(err) => {
+            task.finish(Err(match &err {
+                StorageError::BadInput { description } => {
+                    CollectionError::bad_input(description.clone())
+                }
+                err => CollectionError::service_error(err.to_string()),
+            }));
+            Err(err)
+        }
+    }
+}
+
+pub async fn do_get_import_tasks(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<Vec<ImportTaskInfo>, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.import_tasks().list())
+}
+
+pub async fn do_get_import_task(
+    toc: &TableOfContent,
+    collection_name: &str,
+    task_id: &str,
+) -> Result<ImportTaskInfo, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.import_tasks().info(task_id)?)
+}
+
+pub async fn do_cancel_import_task(
+    toc: &TableOfContent,
+    collection_name: &str,
+    task_id: &str,
+) -> Result<ImportTaskInfo, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.import_tasks().cancel(task_id)?)
+}
+
+/// Number of points read at once by an export
+const EXPORT_PAGE_SIZE: usize = 1_000;
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
+pub struct ExportRequest {
+    /// Export only points, which match this filter. If not provided - all points.
+    #[validate]
+    pub filter: Option<Filter>,
+    /// Start ID to export points from, allows to resume an interrupted export
+    pub offset: Option<PointIdType>,
+}
+
+/// Read points of the export page by page, so only one page is kept in memory.
+///
+/// Points are ordered by ID and include their vectors and payload.
+pub fn do_export_points(
+    toc: Arc<TableOfContent>,
+    collection_name: String,
+    request: ExportRequest,
+) -> impl Stream<Item = Result<Vec<Record>, StorageError>> {
+    let ExportRequest { filter, offset } = request;
+    // Offset of the next page to read, none once all pages are read
+    stream::try_unfold(Some(offset), move |offset| {
+        let toc = toc.clone();
+        let collection_name = collection_name.clone();
+        let filter = filter.clone();
+        async move {
+            let Some(offset) = offset else {
+                return Ok(None);
+            };
+            let page = toc
+                .scroll(
+                    &collection_name,
+                    ScrollRequest {
+                        offset,
+                        limit: Some(EXPORT_PAGE_SIZE),
+                        filter,
+                        with_payload: Some(WithPayloadInterface::Bool(true)),
+                        with_vector: WithVector::Bool(true),
+                        ..Default::default()
+                    },
+                    None,
+                    None,
+                )
+                .await?;
+            Ok(Some((page.points, page.next_page_offset.map(Some))))
+        }
+    })
+}
+
+/// Changes of the points after the offset, read from WAL of the shards on this peer.
+///
+/// If there are no changes yet, waits for them up to the timeout of the request.
+/// The collection is not locked while waiting.
+pub async fn do_get_changes(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: ChangesRequest,
+) -> Result<ChangesResult, StorageError> {
+    let ChangesRequest {
+        offset,
+        limit,
+        timeout,
+    } = request;
+    let offset = offset.unwrap_or_default();
+    let limit = limit.unwrap_or(DEFAULT_CHANGES_LIMIT);
+    let deadline = Instant::now() + Duration::from_secs(timeout.unwrap_or(0));
+
+    loop {
+        let update_watcher = {
+            let collection = toc.get_collection(collection_name).await?;
+            // Subscribe before reading, so an update right after the read is not missed
+            let update_watcher = collection.watch_local_updates().await;
+            let result = collection.changes(&offset, limit).await?;
+            if !result.changes.is_empty() || Instant::now() >= deadline {
+                return Ok(result);
+            }
+            update_watcher
+        };
+        // Read once more after the timeout, to return the latest offset
+        let _ = tokio::time::timeout_at(deadline, update_watcher).await;
+    }
+}
+
+/// Number of points deleted at once by a background deletion
+const DELETE_TASK_BATCH_SIZE: usize = 10_000;
+
+/// Start deleting points, which match the filter, in the background.
+///
+/// Points are deleted shard by shard in batches, so the task can be cancelled in between.
+pub async fn do_delete_points_async(
+    toc: Arc<TableOfContent>,
+    collection_name: &str,
+    selector: FilterSelector,
+    ordering: WriteOrdering,
+) -> Result<DeleteTaskInfo, StorageError> {
+    let (task, shard_ids) = {
+        let collection = toc.get_collection(collection_name).await?;
+        let shard_ids = collection.shard_ids().await;
+        (collection.delete_tasks().start(shard_ids.len()), shard_ids)
+    };
+    let info = task.info();
+
+    let collection_name = collection_name.to_string();
+    let filter = selector.filter;
+    tokio::spawn(async move {
+        let result: Result<(), StorageError> = async {
+            for shard_id in shard_ids {
+                loop {
+                    if task.is_cancelled() {
+                        return Ok(());
+                    }
+                    let collection = toc.get_collection(&collection_name).await?;
+                    let deleted = collection
+                        .delete_points_by_filter_batch(
+                            shard_id,
+                            &filter,
+                            DELETE_TASK_BATCH_SIZE,
+                            ordering,
+                        )
+                        .await?;
+                    if deleted == 0 {
+                        break;
+                    }
+                    task.add_deleted(deleted);
+                }
+                task.shard_done();
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(err) = &result {
+            log::error!("Failed to delete points of collection {collection_name}: {err}");
+        }
+        task.finish(result.map_err(|err| CollectionError::service_error(err.to_string())));
+    });
+
+    Ok(info)
+}
+
+pub async fn do_get_delete_tasks(
+    toc: &TableOfContent,
+    collection_name: &str,
+) -> Result<Vec<DeleteTaskInfo>, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.delete_tasks().list())
+}
+
+pub async fn do_get_delete_task(
+    toc: &TableOfContent,
+    collection_name: &str,
+    task_id: &str,
+) -> Result<DeleteTaskInfo, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.delete_tasks().info(task_id)?)
+}
+
+pub async fn do_cancel_delete_task(
+    toc: &TableOfContent,
+    collection_name: &str,
+    task_id: &str,
+) -> Result<DeleteTaskInfo, StorageError> {
+    let collection = toc.get_collection(collection_name).await?;
+    Ok(collection.delete_tasks().cancel(task_id)?)
+}
+
 pub async fn do_update_vectors(
     toc: &TableOfContent,
     collection_name: &str,
     operation: UpdateVectors,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
@@ -166,7 +465,7 @@ pub async fn do_update_vectors(
         CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(operation));
     toc.update(
         collection_name,
-        collection_operation,
+        collection_operation.with_idempotency_token(idempotency_token),
         shard_selection,
         wait,
         ordering,
@@ -178,61 +477,47 @@ pub async fn do_delete_vectors(
     toc: &TableOfContent,
     collection_name: &str,
     operation: DeleteVectors,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let vector_names: Vec<_> = operation.vector.into_iter().collect();
-
-    let mut result = None;
-
-    if let Some(filter) = operation.filter {
-        let vectors_operation =
-            VectorOperations::DeleteVectorsByFilter(filter, vector_names.clone());
-        let collection_operation = CollectionUpdateOperations::VectorOperation(vectors_operation);
-        result = Some(
-            toc.update(
-                collection_name,
-                collection_operation,
-                shard_selection,
-                wait,
-                ordering,
-            )
-            .await?,
-        );
+    let operations = delete_vectors_operations(operation);
+    if operations.is_empty() {
+        return Err(StorageError::bad_request("No filter or points provided"));
     }
 
-    if let Some(points) = operation.points {
-        let vectors_operation = VectorOperations::DeleteVectors(points.into(), vector_names);
-        let collection_operation = CollectionUpdateOperations::VectorOperation(vectors_operation);
-        result = Some(
-            toc.update(
-                collection_name,
-                collection_operation,
-                shard_selection,
-                wait,
-                ordering,
-            )
-            .await?,
-        );
-    }
-
-    result.ok_or_else(|| StorageError::bad_request("No filter or points provided"))
+    // Operations by filter and by IDs are applied as one update, under the same token
+    let collection_operation = CollectionUpdateOperations::BatchOperation(operations);
+    toc.update(
+        collection_name,
+        collection_operation.with_idempotency_token(idempotency_token),
+        shard_selection,
+        wait,
+        ordering,
+    )
+    .await
 }
 
 pub async fn do_set_payload(
     toc: &TableOfContent,
     collection_name: &str,
     operation: SetPayload,
+    deep_merge: bool,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let collection_operation =
-        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetPayload(operation));
+    let payload_operation = if deep_merge {
+        PayloadOps::MergePayload(operation)
+    } else {
+        PayloadOps::SetPayload(operation)
+    };
+    let collection_operation = CollectionUpdateOperations::PayloadOperation(payload_operation);
     toc.update(
         collection_name,
-        collection_operation,
+        collection_operation.with_idempotency_token(idempotency_token),
         shard_selection,
         wait,
         ordering,
@@ -244,6 +529,7 @@ pub async fn do_overwrite_payload(
     toc: &TableOfContent,
     collection_name: &str,
     operation: SetPayload,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
@@ -252,7 +538,7 @@ pub async fn do_overwrite_payload(
         CollectionUpdateOperations::PayloadOperation(PayloadOps::OverwritePayload(operation));
     toc.update(
         collection_name,
-        collection_operation,
+        collection_operation.with_idempotency_token(idempotency_token),
         shard_selection,
         wait,
         ordering,
@@ -264,6 +550,7 @@ pub async fn do_delete_payload(
     toc: &TableOfContent,
     collection_name: &str,
     operation: DeletePayload,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
@@ -272,7 +559,7 @@ pub async fn do_delete_payload(
         CollectionUpdateOperations::PayloadOperation(PayloadOps::DeletePayload(operation));
     toc.update(
         collection_name,
-        collection_operation,
+        collection_operation.with_idempotency_token(idempotency_token),
         shard_selection,
         wait,
         ordering,
@@ -284,23 +571,36 @@ pub async fn do_clear_payload(
     toc: &TableOfContent,
     collection_name: &str,
     points: PointsSelector,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
-    let points_operation = match points {
-        PointsSelector::PointIdsSelector(points) => PayloadOps::ClearPayload {
-            points: points.points,
-        },
-        PointsSelector::FilterSelector(filter_selector) => {
-            PayloadOps::ClearPayloadByFilter(filter_selector.filter)
-        }
-    };
+    let collection_operation = clear_payload_operation(points);
+    toc.update(
+        collection_name,
+        collection_operation.with_idempotency_token(idempotency_token),
+        shard_selection,
+        wait,
+        ordering,
+    )
+    .await
+}
 
-    let collection_operation = CollectionUpdateOperations::PayloadOperation(points_operation);
+pub async fn do_mutate_payload(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operation: MutatePayload,
+    idempotency_token: Option<String>,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::MutatePayload(operation));
     toc.update(
         collection_name,
-        collection_operation,
+        collection_operation.with_idempotency_token(idempotency_token),
         shard_selection,
         wait,
         ordering,
@@ -308,109 +608,151 @@ pub async fn do_clear_payload(
     .await
 }
 
+/// Apply operations in order, as a single update of each shard.
+///
+/// All operations share the result of the update.
 pub async fn do_batch_update_points(
     toc: &TableOfContent,
     collection_name: &str,
     operations: Vec<UpdateOperation>,
+    idempotency_token: Option<String>,
     shard_selection: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<Vec<UpdateResult>, StorageError> {
-    let mut results = Vec::with_capacity(operations.len());
+    let operations_count = operations.len();
+    if operations_count == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut collection_operations = Vec::with_capacity(operations_count);
     for operation in operations {
-        let result = match operation {
+        match operation {
             UpdateOperation::Upsert(operation) => {
-                do_upsert_points(
-                    toc,
-                    collection_name,
-                    operation.upsert,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                collection_operations.push(CollectionUpdateOperations::PointOperation(
+                    PointOperations::UpsertPoints(operation.upsert),
+                ))
             }
             UpdateOperation::Delete(operation) => {
-                do_delete_points(
-                    toc,
-                    collection_name,
-                    operation.delete,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                collection_operations.push(delete_points_operation(operation.delete))
             }
             UpdateOperation::SetPayload(operation) => {
-                do_set_payload(
-                    toc,
-                    collection_name,
-                    operation.set_payload,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                collection_operations.push(CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::SetPayload(operation.set_payload),
+                ))
             }
             UpdateOperation::OverwritePayload(operation) => {
-                do_overwrite_payload(
-                    toc,
-                    collection_name,
-                    operation.overwrite_payload,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                collection_operations.push(CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::OverwritePayload(operation.overwrite_payload),
+                ))
             }
             UpdateOperation::DeletePayload(operation) => {
-                do_delete_payload(
-                    toc,
-                    collection_name,
-                    operation.delete_payload,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                collection_operations.push(CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::DeletePayload(operation.delete_payload),
+                ))
             }
             UpdateOperation::ClearPayload(operation) => {
-                do_clear_payload(
-                    toc,
-                    collection_name,
-                    operation.clear_payload,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                collection_operations.push(clear_payload_operation(operation.clear_payload))
+            }
+            UpdateOperation::MutatePayload(operation) => {
+                collection_operations.push(CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::MutatePayload(operation.mutate_payload),
+                ))
             }
             UpdateOperation::UpdateVectors(operation) => {
-                do_update_vectors(
-                    toc,
-                    collection_name,
-                    operation.update_vectors,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                collection_operations.push(CollectionUpdateOperations::VectorOperation(
+                    VectorOperations::UpdateVectors(operation.update_vectors),
+                ))
             }
             UpdateOperation::DeleteVectors(operation) => {
-                do_delete_vectors(
-                    toc,
-                    collection_name,
-                    operation.delete_vectors,
-                    shard_selection,
-                    wait,
-                    ordering,
-                )
-                .await
+                let operations = delete_vectors_operations(operation.delete_vectors);
+                if operations.is_empty() {
+                    return Err(StorageError::bad_request("No filter or points provided"));
+                }
+                collection_operations.extend(operations)
             }
-        }?;
-        results.push(result);
+        }
     }
-    Ok(results)
+
+    let result = do_update_batch(
+        toc,
+        collection_name,
+        collection_operations,
+        idempotency_token,
+        shard_selection,
+        wait,
+        ordering,
+    )
+    .await?;
+    Ok(vec![result; operations_count])
+}
+
+pub async fn do_update_batch(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operations: Vec<CollectionUpdateOperations>,
+    idempotency_token: Option<String>,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation = CollectionUpdateOperations::BatchOperation(operations);
+    toc.update(
+        collection_name,
+        collection_operation.with_idempotency_token(idempotency_token),
+        shard_selection,
+        wait,
+        ordering,
+    )
+    .await
+}
+
+pub fn delete_points_operation(points: PointsSelector) -> CollectionUpdateOperations {
+    let point_operation = match points {
+        PointsSelector::PointIdsSelector(points) => {
+            PointOperations::DeletePoints { ids: points.points }
+        }
+        PointsSelector::FilterSelector(filter_selector) => {
+            PointOperations::DeletePointsByFilter(filter_selector.filter)
+        }
+    };
+    CollectionUpdateOperations::PointOperation(point_operation)
+}
+
+pub fn clear_payload_operation(points: PointsSelector) -> CollectionUpdateOperations {
+    let points_operation = match points {
+        PointsSelector::PointIdsSelector(points) => PayloadOps::ClearPayload {
+            points: points.points,
+        },
+        PointsSelector::FilterSelector(filter_selector) => {
+            PayloadOps::ClearPayloadByFilter(filter_selector.filter)
+        }
+    };
+    CollectionUpdateOperations::PayloadOperation(points_operation)
+}
+
+/// Operations deleting vectors of the points selected by filter and by IDs
+pub fn delete_vectors_operations(operation: DeleteVectors) -> Vec<CollectionUpdateOperations> {
+    let vector_names: Vec<_> = operation.vector.into_iter().collect();
+
+    let mut operations = Vec::new();
+
+    if let Some(filter) = operation.filter {
+        let vectors_operation =
+            VectorOperations::DeleteVectorsByFilter(filter, vector_names.clone());
+        operations.push(CollectionUpdateOperations::VectorOperation(
+            vectors_operation,
+        ));
+    }
+
+    if let Some(points) = operation.points {
+        let vectors_operation = VectorOperations::DeleteVectors(points.into(), vector_names);
+        operations.push(CollectionUpdateOperations::VectorOperation(
+            vectors_operation,
+        ));
+    }
+
+    operations
 }
 
 pub async fn do_create_index(
@@ -458,6 +800,23 @@ pub async fn do_delete_index(
     .await
 }
 
+/// Fail the read operation, if it doesn't complete within the timeout.
+///
+/// The operation is dropped once the timeout passes, along with its requests to the shards.
+/// This stops the searches in the local shards and cancels the requests to the remote ones.
+pub async fn with_read_timeout<T>(
+    timeout: Option<Duration>,
+    operation: &str,
+    future: impl Future<Output = Result<T, StorageError>>,
+) -> Result<T, StorageError> {
+    let Some(timeout) = timeout else {
+        return future.await;
+    };
+    tokio::time::timeout(timeout, future)
+        .await
+        .unwrap_or_else(|_elapsed| Err(CollectionError::timeout(timeout, operation).into()))
+}
+
 pub async fn do_search_points(
     toc: &TableOfContent,
     collection_name: &str,
@@ -480,6 +839,46 @@ pub async fn do_search_batch_points(
         .await
 }
 
+pub async fn do_search_batch_points_with_timeout(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: SearchRequestBatch,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+    timeout: Duration,
+    allow_partial: bool,
+) -> Result<PartialSearchBatchResult, StorageError> {
+    toc.search_batch_with_timeout(
+        collection_name,
+        request,
+        read_consistency,
+        shard_selection,
+        timeout,
+        allow_partial,
+    )
+    .await
+}
+
+pub async fn do_search_batch_collections(
+    toc: &TableOfContent,
+    request: CollectionsSearchRequestBatch,
+    read_consistency: Option<ReadConsistency>,
+) -> Result<Vec<Vec<ScoredPoint>>, StorageError> {
+    toc.search_batch_collections(request, read_consistency)
+        .await
+}
+
+pub async fn do_query_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: QueryRequest,
+    read_consistency: Option<ReadConsistency>,
+    shard_selection: Option<ShardId>,
+) -> Result<Vec<ScoredPoint>, StorageError> {
+    toc.query(collection_name, request, read_consistency, shard_selection)
+        .await
+}
+
 pub async fn do_core_search_batch_points(
     toc: &TableOfContent,
     collection_name: &str,
@@ -526,6 +925,16 @@ pub async fn do_count_points(
     toc.count(collection_name, request, shard_selection).await
 }
 
+pub async fn do_count_batch_points(
+    toc: &TableOfContent,
+    collection_name: &str,
+    request: CountRequestBatch,
+    shard_selection: Option<ShardId>,
+) -> Result<Vec<CountResult>, StorageError> {
+    toc.count_batch(collection_name, request, shard_selection)
+        .await
+}
+
 pub async fn do_get_points(
     toc: &TableOfContent,
     collection_name: &str,
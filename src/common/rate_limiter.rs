@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use parking_lot::Mutex;
+
+use crate::common::auth::AuthenticatedClient;
+use crate::common::telemetry_ops::requests_telemetry::RateLimitTelemetry;
+use crate::settings::RateLimitConfig;
+
+/// Idle clients are forgotten once there are more clients than this.
+/// If all of them are active, new clients share the limits of a single overflow client.
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+const OVERFLOW_CLIENT_KEY: &str = "overflow";
+
+/// Request rate and concurrency limits, enforced for each client separately.
+///
+/// The rate is limited by a token bucket, which holds up to `burst` requests
+/// and is refilled with `requests_per_second`.
+pub struct RateLimiter {
+    requests_per_second: Option<f64>,
+    burst: f64,
+    max_concurrent_requests: Option<usize>,
+    clients: Mutex<HashMap<String, ClientState>>,
+    rejected_by_rate: AtomicUsize,
+    rejected_by_concurrency: AtomicUsize,
+}
+
+struct ClientState {
+    tokens: f64,
+    last_refill: Instant,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl ClientState {
+    fn is_idle(&self, burst: f64) -> bool {
+        self.in_flight.load(Ordering::Relaxed) == 0 && self.tokens >= burst
+    }
+}
+
+/// Request is rejected, as the client exceeded one of its limits
+#[derive(Debug, Clone, PartialEq)]
+pub enum RateLimitError {
+    TooManyRequests {
+        requests_per_second: f64,
+        retry_after: Duration,
+    },
+    TooManyConcurrentRequests {
+        max_concurrent_requests: usize,
+    },
+}
+
+impl RateLimitError {
+    /// Whole seconds to wait before retrying, for the `Retry-After` header
+    pub fn retry_after_secs(&self) -> u64 {
+        match self {
+            RateLimitError::TooManyRequests { retry_after, .. } => {
+                retry_after.as_secs_f64().ceil() as u64
+            }
+            RateLimitError::TooManyConcurrentRequests { .. } => 1,
+        }
+    }
+}
+
+impl fmt::Display for RateLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RateLimitError::TooManyRequests {
+                requests_per_second,
+                retry_after,
+            } => write!(
+                f,
+                "Rate limit of {requests_per_second} requests per second exceeded, retry after {:.3}s",
+                retry_after.as_secs_f64(),
+            ),
+            RateLimitError::TooManyConcurrentRequests {
+                max_concurrent_requests,
+            } => write!(
+                f,
+                "Limit of {max_concurrent_requests} concurrent requests exceeded",
+            ),
+        }
+    }
+}
+
+/// Counts the request as in flight until it is dropped
+pub struct RequestPermit {
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl Drop for RequestPermit {
+    fn drop(&mut self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl RateLimiter {
+    /// Limiter of the configured limits, the rate must be a positive number of requests
+    pub fn new(config: &RateLimitConfig) -> Result<Self, String> {
+        if let Some(requests_per_second) = config.requests_per_second {
+            if !(requests_per_second.is_finite() && requests_per_second > 0.0) {
+                return Err(format!(
+                    "Rate limit must be a positive number of requests per second, got {requests_per_second}"
+                ));
+            }
+        }
+
+        let burst = config
+            .burst
+            .map(f64::from)
+            .or(config.requests_per_second)
+            .unwrap_or(0.0)
+            .max(1.0);
+        Ok(Self {
+            requests_per_second: config.requests_per_second,
+            burst,
+            max_concurrent_requests: config.max_concurrent_requests,
+            clients: Mutex::new(HashMap::new()),
+            rejected_by_rate: AtomicUsize::new(0),
+            rejected_by_concurrency: AtomicUsize::new(0),
+        })
+    }
+
+    /// Key of the client: the credential it was authenticated with, or its IP address.
+    /// Credentials are not authenticated without the api-key or JWT configured,
+    /// so they are not trusted then, and any client could make up new ones.
+    pub fn client_key(client: Option<&AuthenticatedClient>, ip: Option<String>) -> String {
+        match (client, ip) {
+            (Some(AuthenticatedClient::ApiKey(api_key)), _) => format!("api-key:{api_key}"),
+            (Some(AuthenticatedClient::Token(token)), _) => format!("token:{token}"),
+            (None, Some(ip)) => format!("ip:{ip}"),
+            (None, None) => "unknown".to_string(),
+        }
+    }
+
+    /// Admit a request of the client, if it is within the limits of the client
+    pub fn try_acquire(&self, client_key: &str) -> Result<RequestPermit, RateLimitError> {
+        self.try_acquire_at(client_key, Instant::now())
+    }
+
+    fn try_acquire_at(
+        &self,
+        client_key: &str,
+        now: Instant,
+    ) -> Result<RequestPermit, RateLimitError> {
+        let mut clients = self.clients.lock();
+        let mut client_key = client_key;
+        if clients.len() >= MAX_TRACKED_CLIENTS && !clients.contains_key(client_key) {
+            self.forget_idle_clients(&mut clients, now);
+            if clients.len() >= MAX_TRACKED_CLIENTS {
+                client_key = OVERFLOW_CLIENT_KEY;
+            }
+        }
+
+        let client = clients
+            .entry(client_key.to_string())
+            .or_insert_with(|| ClientState {
+                tokens: self.burst,
+                last_refill: now,
+                in_flight: Arc::new(AtomicUsize::new(0)),
+            });
+
+        if let Some(max_concurrent_requests) = self.max_concurrent_requests {
+            if client.in_flight.load(Ordering::Relaxed) >= max_concurrent_requests {
+                self.rejected_by_concurrency.fetch_add(1, Ordering::Relaxed);
+                return Err(RateLimitError::TooManyConcurrentRequests {
+                    max_concurrent_requests,
+                });
+            }
+        }
+
+        if let Some(requests_per_second) = self.requests_per_second {
+            self.refill(client, now);
+            if client.tokens < 1.0 {
+                self.rejected_by_rate.fetch_add(1, Ordering::Relaxed);
+                // Very low rates would overflow the duration
+                let retry_after =
+                    Duration::try_from_secs_f64((1.0 - client.tokens) / requests_per_second)
+                        .unwrap_or(Duration::MAX);
+                return Err(RateLimitError::TooManyRequests {
+                    requests_per_second,
+                    retry_after,
+                });
+            }
+            client.tokens -= 1.0;
+        }
+
+        client.in_flight.fetch_add(1, Ordering::Relaxed);
+        Ok(RequestPermit {
+            in_flight: client.in_flight.clone(),
+        })
+    }
+
+    fn refill(&self, client: &mut ClientState, now: Instant) {
+        let Some(requests_per_second) = self.requests_per_second else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(client.last_refill);
+        client.tokens =
+            (client.tokens + elapsed.as_secs_f64() * requests_per_second).min(self.burst);
+        client.last_refill = now;
+    }
+
+    fn forget_idle_clients(&self, clients: &mut HashMap<String, ClientState>, now: Instant) {
+        for client in clients.values_mut() {
+            self.refill(client, now);
+        }
+        clients.retain(|_, client| !client.is_idle(self.burst));
+    }
+
+    pub fn get_telemetry_data(&self) -> RateLimitTelemetry {
+        RateLimitTelemetry {
+            clients: self.clients.lock().len(),
+            rejected_by_rate: self.rejected_by_rate.load(Ordering::Relaxed),
+            rejected_by_concurrency: self.rejected_by_concurrency.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_limit() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            requests_per_second: Some(2.0),
+            burst: Some(2),
+            max_concurrent_requests: None,
+        })
+        .unwrap();
+        let start = Instant::now();
+
+        assert!(limiter.try_acquire_at("a", start).is_ok());
+        assert!(limiter.try_acquire_at("a", start).is_ok());
+        let err = limiter.try_acquire_at("a", start).err().unwrap();
+        assert!(matches!(err, RateLimitError::TooManyRequests { .. }));
+        assert_eq!(err.retry_after_secs(), 1);
+
+        // Other clients have their own limits
+        assert!(limiter.try_acquire_at("b", start).is_ok());
+
+        // Bucket is refilled over time
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.try_acquire_at("a", later).is_ok());
+        assert!(limiter.try_acquire_at("a", later).is_err());
+
+        let telemetry = limiter.get_telemetry_data();
+        assert_eq!(telemetry.clients, 2);
+        assert_eq!(telemetry.rejected_by_rate, 2);
+    }
+
+    #[test]
+    fn test_invalid_rate_limit() {
+        let config = |requests_per_second| RateLimitConfig {
+            requests_per_second: Some(requests_per_second),
+            burst: None,
+            max_concurrent_requests: None,
+        };
+        assert!(RateLimiter::new(&config(0.0)).is_err());
+        assert!(RateLimiter::new(&config(-1.0)).is_err());
+        assert!(RateLimiter::new(&config(f64::NAN)).is_err());
+        assert!(RateLimiter::new(&config(f64::INFINITY)).is_err());
+
+        // Very low rates don't overflow the time to retry
+        let limiter = RateLimiter::new(&config(f64::MIN_POSITIVE)).unwrap();
+        let start = Instant::now();
+        assert!(limiter.try_acquire_at("a", start).is_ok());
+        let err = limiter.try_acquire_at("a", start).err().unwrap();
+        assert!(matches!(
+            err,
+            RateLimitError::TooManyRequests { retry_after, .. } if retry_after == Duration::MAX
+        ));
+    }
+
+    #[test]
+    fn test_concurrency_limit() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            requests_per_second: None,
+            burst: None,
+            max_concurrent_requests: Some(1),
+        })
+        .unwrap();
+
+        let permit = limiter.try_acquire("a").unwrap();
+        assert_eq!(
+            limiter.try_acquire("a").err(),
+            Some(RateLimitError::TooManyConcurrentRequests {
+                max_concurrent_requests: 1
+            }),
+        );
+        drop(permit);
+        assert!(limiter.try_acquire("a").is_ok());
+        assert_eq!(limiter.get_telemetry_data().rejected_by_concurrency, 1);
+    }
+
+    #[test]
+    fn test_client_key() {
+        let ip = || Some("127.0.0.1".to_string());
+        let client = AuthenticatedClient::ApiKey("secret".to_string());
+        assert_eq!(
+            RateLimiter::client_key(Some(&client), ip()),
+            "api-key:secret"
+        );
+        // Unauthenticated clients are limited by their address
+        assert_eq!(RateLimiter::client_key(None, ip()), "ip:127.0.0.1");
+    }
+
+    #[test]
+    fn test_tracked_clients_limit() {
+        let limiter = RateLimiter::new(&RateLimitConfig {
+            requests_per_second: None,
+            burst: None,
+            max_concurrent_requests: Some(1),
+        })
+        .unwrap();
+
+        let permits: Vec<_> = (0..MAX_TRACKED_CLIENTS)
+            .map(|client| limiter.try_acquire(&client.to_string()).unwrap())
+            .collect();
+
+        // All tracked clients are active, new ones share the limits of the overflow client
+        let permit = limiter.try_acquire("new").unwrap();
+        assert!(limiter.try_acquire("other").is_err());
+        assert_eq!(
+            limiter.get_telemetry_data().clients,
+            MAX_TRACKED_CLIENTS + 1
+        );
+
+        // Idle clients are forgotten to make room for new ones
+        drop(permits);
+        drop(permit);
+        assert!(limiter.try_acquire("other").is_ok());
+        assert_eq!(limiter.get_telemetry_data().clients, 1);
+    }
+}
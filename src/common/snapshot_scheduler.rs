@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use collection::common::snapshot_schedule::{SnapshotSchedule, SnapshotTarget};
+use collection::operations::snapshot_ops::remove_snapshot;
+use collection::operations::types::CollectionStatus;
+use storage::content_manager::errors::StorageError;
+use storage::content_manager::snapshots::delete_snapshot_from_s3;
+use storage::dispatcher::Dispatcher;
+use tokio::task::JoinHandle;
+
+use crate::common::collections::do_create_snapshot;
+
+const SCHEDULER_INTERVAL: Duration = Duration::from_secs(10);
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+/// Scheduled snapshot is postponed while segments of the collection are being optimized,
+/// but not longer than this
+const OPTIMIZATION_WAIT_LIMIT: Duration = Duration::from_secs(30 * 60);
+
+/// Creates snapshots of collections by their snapshot schedules,
+/// and deletes snapshots beyond their retention policies.
+///
+/// Snapshots of different collections are created concurrently, of the same collection - one at a time.
+pub struct SnapshotScheduler {
+    dispatcher: Dispatcher,
+    running: HashMap<String, JoinHandle<()>>,
+    last_prune: Instant,
+}
+
+impl SnapshotScheduler {
+    pub async fn run(dispatcher: Dispatcher) {
+        let mut scheduler = Self {
+            dispatcher,
+            running: HashMap::new(),
+            last_prune: Instant::now(),
+        };
+        loop {
+            tokio::time::sleep(SCHEDULER_INTERVAL).await;
+            scheduler.start_due().await;
+            if scheduler.last_prune.elapsed() >= PRUNE_INTERVAL {
+                scheduler.prune().await;
+                scheduler.last_prune = Instant::now();
+            }
+        }
+    }
+
+    async fn prune(&self) {
+        for collection_name in self.dispatcher.all_collections().await {
+            // Pruned on the next run, after the scheduled snapshot is created
+            if self.running.contains_key(&collection_name) {
+                continue;
+            }
+            let Ok(collection) = self.dispatcher.get_collection(&collection_name).await else {
+                continue;
+            };
+            match collection.prune_snapshots().await {
+                Ok(deleted) => {
+                    for snapshot_name in deleted {
+                        log::info!(
+                            "Deleted snapshot {snapshot_name} of collection {collection_name} by retention policy"
+                        );
+                    }
+                }
+                Err(err) => {
+                    log::error!("Failed to prune snapshots of collection {collection_name}: {err}")
+                }
+            }
+        }
+    }
+
+    async fn start_due(&mut self) {
+        self.running.retain(|_, task| !task.is_finished());
+
+        let now = Utc::now();
+        for collection_name in self.dispatcher.all_collections().await {
+            if self.running.contains_key(&collection_name) {
+                continue;
+            }
+            let Ok(collection) = self.dispatcher.get_collection(&collection_name).await else {
+                continue;
+            };
+            let Some(schedule) = collection.scheduled_snapshots().due(now) else {
+                continue;
+            };
+            drop(collection);
+
+            let dispatcher = self.dispatcher.clone();
+            let name = collection_name.clone();
+            let task = tokio::spawn(async move {
+                if let Err(err) = create_scheduled_snapshot(&dispatcher, &name, schedule).await {
+                    log::error!("Failed to create scheduled snapshot of collection {name}: {err}");
+                }
+            });
+            self.running.insert(collection_name, task);
+        }
+    }
+}
+
+async fn wait_for_optimizations(
+    dispatcher: &Dispatcher,
+    collection_name: &str,
+) -> Result<(), StorageError> {
+    let started = Instant::now();
+    loop {
+        let status = {
+            let collection = dispatcher.get_collection(collection_name).await?;
+            collection.info(None).await?.status
+        };
+        if status != CollectionStatus::Yellow {
+            return Ok(());
+        }
+        if started.elapsed() >= OPTIMIZATION_WAIT_LIMIT {
+            log::warn!(
+                "Collection {collection_name} is still being optimized, creating scheduled snapshot anyway"
+            );
+            return Ok(());
+        }
+        tokio::time::sleep(SCHEDULER_INTERVAL).await;
+    }
+}
+
+async fn create_scheduled_snapshot(
+    dispatcher: &Dispatcher,
+    collection_name: &str,
+    schedule: SnapshotSchedule,
+) -> Result<(), StorageError> {
+    wait_for_optimizations(dispatcher, collection_name).await?;
+
+    let created_at = Utc::now();
+    log::info!("Creating scheduled snapshot of collection {collection_name}");
+    let snapshot = do_create_snapshot(dispatcher, collection_name, None, true).await?;
+
+    if schedule.target == SnapshotTarget::S3 {
+        // The snapshot is already uploaded by `do_create_snapshot`
+        let snapshot_path = dispatcher
+            .get_collection(collection_name)
+            .await?
+            .get_snapshot_path(&snapshot.name)
+            .await?;
+        remove_snapshot(&snapshot_path).await?;
+    }
+
+    let expired = dispatcher
+        .get_collection(collection_name)
+        .await?
+        .scheduled_snapshots()
+        .record(snapshot.name, created_at)?;
+
+    for snapshot_name in expired {
+        log::info!(
+            "Deleting expired scheduled snapshot {snapshot_name} of collection {collection_name}"
+        );
+        let snapshot_path = dispatcher
+            .get_collection(collection_name)
+            .await?
+            .get_snapshot_path(&snapshot_name)
+            .await;
+        // Not found locally, if stored in S3 only or deleted by the user
+        if let Ok(snapshot_path) = snapshot_path {
+            remove_snapshot(&snapshot_path).await?;
+        }
+        delete_snapshot_from_s3(dispatcher.toc(), collection_name, &snapshot_name).await?;
+    }
+
+    Ok(())
+}
@@ -1,5 +1,7 @@
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
+use common::types::{DetailsLevel, TelemetryDetail};
 use parking_lot::Mutex;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
@@ -7,6 +9,7 @@ use serde::{Deserialize, Serialize};
 use storage::dispatcher::Dispatcher;
 use uuid::Uuid;
 
+use crate::common::rate_limiter::RateLimiter;
 use crate::common::telemetry_ops::app_telemetry::{AppBuildTelemetry, AppBuildTelemetryCollector};
 use crate::common::telemetry_ops::cluster_telemetry::ClusterTelemetry;
 use crate::common::telemetry_ops::collections_telemetry::CollectionsTelemetry;
@@ -15,6 +18,12 @@ use crate::common::telemetry_ops::requests_telemetry::{
 };
 use crate::settings::Settings;
 
+/// Detail of the telemetry, which metrics are built from
+const METRICS_DETAIL: TelemetryDetail = TelemetryDetail::new(DetailsLevel::Level2, true);
+
+/// How long a telemetry snapshot is reused for metrics
+const METRICS_CACHE_TTL: Duration = Duration::from_secs(10);
+
 pub struct TelemetryCollector {
     process_id: Uuid,
     settings: Settings,
@@ -22,6 +31,8 @@ pub struct TelemetryCollector {
     pub app_telemetry_collector: AppBuildTelemetryCollector,
     pub actix_telemetry_collector: Arc<Mutex<ActixTelemetryCollector>>,
     pub tonic_telemetry_collector: Arc<Mutex<TonicTelemetryCollector>>,
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+    metrics_cache: Option<(Instant, TelemetryData)>,
 }
 
 // Whole telemetry data
@@ -55,10 +66,14 @@ impl TelemetryCollector {
         Uuid::new_v4()
     }
 
-    pub fn new(settings: Settings, dispatcher: Arc<Dispatcher>, id: Uuid) -> Self {
+    pub fn new(
+        settings: Settings,
+        dispatcher: Arc<Dispatcher>,
+        rate_limiter: Option<Arc<RateLimiter>>,
+        id: Uuid,
+    ) -> Self {
         Self {
             process_id: id,
-            settings,
             dispatcher,
             app_telemetry_collector: AppBuildTelemetryCollector::new(),
             actix_telemetry_collector: Arc::new(Mutex::new(ActixTelemetryCollector {
@@ -67,19 +82,43 @@ impl TelemetryCollector {
             tonic_telemetry_collector: Arc::new(Mutex::new(TonicTelemetryCollector {
                 workers: Vec::new(),
             })),
+            rate_limiter,
+            settings,
+            metrics_cache: None,
         }
     }
 
-    pub async fn prepare_data(&self, level: usize) -> TelemetryData {
+    pub async fn prepare_data(&self, detail: TelemetryDetail) -> TelemetryData {
         TelemetryData {
             id: self.process_id.to_string(),
-            collections: CollectionsTelemetry::collect(level, self.dispatcher.toc()).await,
-            app: AppBuildTelemetry::collect(level, &self.app_telemetry_collector, &self.settings),
-            cluster: ClusterTelemetry::collect(level, &self.dispatcher, &self.settings),
+            collections: CollectionsTelemetry::collect(detail, self.dispatcher.toc()).await,
+            app: AppBuildTelemetry::collect(
+                detail.level,
+                &self.app_telemetry_collector,
+                &self.settings,
+            ),
+            cluster: ClusterTelemetry::collect(detail.level, &self.dispatcher, &self.settings),
             requests: RequestsTelemetry::collect(
                 &self.actix_telemetry_collector.lock(),
                 &self.tonic_telemetry_collector.lock(),
+                self.rate_limiter.as_deref(),
+                detail,
             ),
         }
     }
+
+    /// Telemetry, which metrics are built from.
+    ///
+    /// Collecting full telemetry with histograms is expensive, so the snapshot is reused for
+    /// [`METRICS_CACHE_TTL`] by subsequent scrapes.
+    pub async fn prepare_metrics_data(&mut self) -> TelemetryData {
+        if let Some((collected_at, telemetry_data)) = &self.metrics_cache {
+            if collected_at.elapsed() < METRICS_CACHE_TTL {
+                return telemetry_data.clone();
+            }
+        }
+        let telemetry_data = self.prepare_data(METRICS_DETAIL).await;
+        self.metrics_cache = Some((Instant::now(), telemetry_data.clone()));
+        telemetry_data
+    }
 }
@@ -1,6 +1,7 @@
 use std::path::Path;
 
 use chrono::{DateTime, SubsecRound, Utc};
+use common::types::DetailsLevel;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
@@ -53,14 +54,14 @@ pub struct AppBuildTelemetry {
 
 impl AppBuildTelemetry {
     pub fn collect(
-        level: usize,
+        level: DetailsLevel,
         collector: &AppBuildTelemetryCollector,
         settings: &Settings,
     ) -> Self {
         AppBuildTelemetry {
             name: env!("CARGO_PKG_NAME").to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
-            features: if level > 0 {
+            features: if level >= DetailsLevel::Level1 {
                 Some(AppFeaturesTelemetry {
                     debug: cfg!(debug_assertions),
                     web_feature: cfg!(feature = "web"),
@@ -70,7 +71,7 @@ impl AppBuildTelemetry {
             } else {
                 None
             },
-            system: if level > 0 {
+            system: if level >= DetailsLevel::Level1 {
                 Some(get_system_data())
             } else {
                 None
@@ -1,4 +1,5 @@
 use collection::shards::shard::PeerId;
+use common::types::DetailsLevel;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
 use serde::{Deserialize, Serialize};
@@ -64,8 +65,12 @@ pub struct ClusterTelemetry {
 }
 
 impl ClusterTelemetry {
-    pub fn collect(level: usize, dispatcher: &Dispatcher, settings: &Settings) -> ClusterTelemetry {
-        let status = if level > 0 {
+    pub fn collect(
+        level: DetailsLevel,
+        dispatcher: &Dispatcher,
+        settings: &Settings,
+    ) -> ClusterTelemetry {
+        let status = if level >= DetailsLevel::Level1 {
             match dispatcher.cluster_status() {
                 ClusterStatus::Disabled => None,
                 ClusterStatus::Enabled(cluster_info) => Some(ClusterStatusTelemetry {
@@ -83,7 +88,7 @@ impl ClusterTelemetry {
             None
         };
 
-        let config = if level > 1 {
+        let config = if level >= DetailsLevel::Level2 {
             Some(ClusterConfigTelemetry::from(settings))
         } else {
             None
@@ -1,8 +1,11 @@
+use collection::common::search_cache::SearchCacheTelemetry;
 use collection::config::CollectionParams;
 use collection::operations::types::OptimizersStatus;
 use collection::telemetry::CollectionTelemetry;
+use common::types::{DetailsLevel, TelemetryDetail};
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
+use segment::common::operation_time_statistics::OperationDurationStatistics;
 use serde::{Deserialize, Serialize};
 use storage::content_manager::toc::TableOfContent;
 
@@ -11,6 +14,14 @@ pub struct CollectionsAggregatedTelemetry {
     pub vectors: usize,
     pub optimizers_status: OptimizersStatus,
     pub params: CollectionParams,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search_cache: Option<SearchCacheTelemetry>,
+    /// Durations of the searches, summed over the local shards
+    #[serde(default)]
+    pub searches: OperationDurationStatistics,
+    /// Durations of the updates, summed over the local shards
+    #[serde(default)]
+    pub updates: OperationDurationStatistics,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
@@ -36,24 +47,44 @@ impl From<CollectionTelemetry> for CollectionsAggregatedTelemetry {
             .max()
             .unwrap_or(OptimizersStatus::Ok);
 
+        let (searches, updates) = telemetry
+            .shards
+            .iter()
+            .filter_map(|shard| shard.local.as_ref())
+            .fold(
+                (
+                    OperationDurationStatistics::default(),
+                    OperationDurationStatistics::default(),
+                ),
+                |(searches, updates), local| {
+                    (
+                        searches + local.searches.clone(),
+                        updates + local.updates.clone(),
+                    )
+                },
+            );
+
         CollectionsAggregatedTelemetry {
             vectors: telemetry.count_vectors(),
             optimizers_status,
             params: telemetry.config.params,
+            search_cache: telemetry.search_cache,
+            searches,
+            updates,
         }
     }
 }
 
 impl CollectionsTelemetry {
-    pub async fn collect(level: usize, toc: &TableOfContent) -> Self {
+    pub async fn collect(detail: TelemetryDetail, toc: &TableOfContent) -> Self {
         let number_of_collections = toc.all_collections().await.len();
-        let collections = if level > 0 {
+        let collections = if detail.level >= DetailsLevel::Level1 {
             let telemetry_data = toc
-                .get_telemetry_data()
+                .get_telemetry_data(detail)
                 .await
                 .into_iter()
                 .map(|telemetry| {
-                    if level > 1 {
+                    if detail.level >= DetailsLevel::Level2 {
                         CollectionTelemetryEnum::Full(telemetry)
                     } else {
                         CollectionTelemetryEnum::Aggregated(telemetry.into())
@@ -101,6 +132,9 @@ impl Anonymize for CollectionsAggregatedTelemetry {
             optimizers_status: self.optimizers_status.clone(),
             vectors: self.vectors.anonymize(),
             params: self.params.anonymize(),
+            search_cache: self.search_cache.clone(),
+            searches: self.searches.anonymize(),
+            updates: self.updates.anonymize(),
         }
     }
 }
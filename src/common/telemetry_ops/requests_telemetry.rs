@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use common::types::TelemetryDetail;
 use parking_lot::Mutex;
 use schemars::JsonSchema;
 use segment::common::anonymize::Anonymize;
@@ -9,6 +10,8 @@ use segment::common::operation_time_statistics::{
 };
 use serde::{Deserialize, Serialize};
 
+use crate::common::rate_limiter::RateLimiter;
+
 pub type HttpStatusCode = u16;
 
 #[derive(Serialize, Deserialize, Clone, Default, Debug, JsonSchema)]
@@ -46,10 +49,10 @@ impl ActixTelemetryCollector {
         worker
     }
 
-    pub fn get_telemetry_data(&self) -> WebApiTelemetry {
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> WebApiTelemetry {
         let mut result = WebApiTelemetry::default();
         for web_data in &self.workers {
-            let lock = web_data.lock().get_telemetry_data();
+            let lock = web_data.lock().get_telemetry_data(detail);
             result.merge(&lock);
         }
         result
@@ -64,10 +67,10 @@ impl TonicTelemetryCollector {
         worker
     }
 
-    pub fn get_telemetry_data(&self) -> GrpcTelemetry {
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> GrpcTelemetry {
         let mut result = GrpcTelemetry::default();
         for grpc_data in &self.workers {
-            let lock = grpc_data.lock().get_telemetry_data();
+            let lock = grpc_data.lock().get_telemetry_data(detail);
             result.merge(&lock);
         }
         result
@@ -84,10 +87,13 @@ impl TonicWorkerTelemetryCollector {
         ScopeDurationMeasurer::new_with_instant(aggregator, instant);
     }
 
-    pub fn get_telemetry_data(&self) -> GrpcTelemetry {
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> GrpcTelemetry {
         let mut responses = HashMap::new();
         for (method, aggregator) in self.methods.iter() {
-            responses.insert(method.clone(), aggregator.lock().get_statistics());
+            responses.insert(
+                method.clone(),
+                aggregator.lock().get_statistics_with_detail(detail),
+            );
         }
         GrpcTelemetry { responses }
     }
@@ -109,12 +115,15 @@ impl ActixWorkerTelemetryCollector {
         ScopeDurationMeasurer::new_with_instant(aggregator, instant);
     }
 
-    pub fn get_telemetry_data(&self) -> WebApiTelemetry {
+    pub fn get_telemetry_data(&self, detail: TelemetryDetail) -> WebApiTelemetry {
         let mut responses = HashMap::new();
         for (method, status_codes) in &self.methods {
             let mut status_codes_map = HashMap::new();
             for (status_code, aggregator) in status_codes {
-                status_codes_map.insert(*status_code, aggregator.lock().get_statistics());
+                status_codes_map.insert(
+                    *status_code,
+                    aggregator.lock().get_statistics_with_detail(detail),
+                );
             }
             responses.insert(method.clone(), status_codes_map);
         }
@@ -143,20 +152,39 @@ impl WebApiTelemetry {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Default, Debug, JsonSchema)]
+pub struct RateLimitTelemetry {
+    /// Clients, currently tracked by the rate limiter
+    pub clients: usize,
+    /// Requests, rejected as their client exceeded its request rate
+    pub rejected_by_rate: usize,
+    /// Requests, rejected as their client had too many requests in flight
+    pub rejected_by_concurrency: usize,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, JsonSchema)]
 pub struct RequestsTelemetry {
     pub rest: WebApiTelemetry,
     pub grpc: GrpcTelemetry,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitTelemetry>,
 }
 
 impl RequestsTelemetry {
     pub fn collect(
         actix_collector: &ActixTelemetryCollector,
         tonic_collector: &TonicTelemetryCollector,
+        rate_limiter: Option<&RateLimiter>,
+        detail: TelemetryDetail,
     ) -> Self {
-        let rest = actix_collector.get_telemetry_data();
-        let grpc = tonic_collector.get_telemetry_data();
-        Self { rest, grpc }
+        let rest = actix_collector.get_telemetry_data(detail);
+        let grpc = tonic_collector.get_telemetry_data(detail);
+        let rate_limit = rate_limiter.map(RateLimiter::get_telemetry_data);
+        Self {
+            rest,
+            grpc,
+            rate_limit,
+        }
     }
 }
 
@@ -164,7 +192,11 @@ impl Anonymize for RequestsTelemetry {
     fn anonymize(&self) -> Self {
         let rest = self.rest.anonymize();
         let grpc = self.grpc.anonymize();
-        Self { rest, grpc }
+        Self {
+            rest,
+            grpc,
+            rate_limit: self.rate_limit.clone(),
+        }
     }
 }
 
@@ -1,14 +1,17 @@
 use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
-use std::sync::{mpsc, Arc};
+use std::sync::{mpsc, Arc, RwLock};
 use std::thread::JoinHandle;
 use std::time::{Duration, Instant};
 use std::{fmt, thread};
 
 use anyhow::{anyhow, Context};
 use api::grpc::dynamic_channel_pool::make_grpc_channel;
+use api::grpc::qdrant::qdrant_internal_client::QdrantInternalClient;
 use api::grpc::qdrant::raft_client::RaftClient;
-use api::grpc::qdrant::{AllPeers, PeerId as GrpcPeerId, RaftMessage as GrpcRaftMessage};
+use api::grpc::qdrant::{
+    AllPeers, ConsensusFeaturesRequest, PeerId as GrpcPeerId, RaftMessage as GrpcRaftMessage,
+};
 use api::grpc::transport_channel_pool::TransportChannelPool;
 use collection::shards::channel_service::ChannelService;
 use collection::shards::shard::PeerId;
@@ -25,8 +28,8 @@ use tokio::sync::watch;
 use tokio::time::sleep;
 use tonic::transport::{ClientTlsConfig, Uri};
 
-use crate::common::helpers;
 use crate::common::telemetry_ops::requests_telemetry::TonicTelemetryCollector;
+use crate::common::{certificate_helpers, helpers};
 use crate::settings::{ConsensusConfig, Settings};
 use crate::tonic::init_internal;
 
@@ -35,6 +38,9 @@ type Node = RawNode<ConsensusStateRef>;
 const RECOVERY_RETRY_TIMEOUT: Duration = Duration::from_secs(1);
 const RECOVERY_MAX_RETRY_COUNT: usize = 3;
 
+/// How often peers are asked, if they can apply batches of collection meta operations
+const META_BATCH_PEERS_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
 pub enum Message {
     FromClient(ConsensusOperations),
     FromPeer(Box<RaftMessage>),
@@ -53,6 +59,8 @@ pub struct Consensus {
     /// ToDo: Make if many
     config: ConsensusConfig,
     broker: RaftMessageBroker,
+    /// Peers, which reported that they can apply batches of collection meta operations
+    meta_batch_peers: Arc<RwLock<HashSet<PeerId>>>,
 }
 
 impl Consensus {
@@ -122,7 +130,10 @@ impl Consensus {
                 .clone()
                 .ok_or_else(Settings::tls_config_is_undefined_error)?;
 
-            Some(helpers::load_tls_internal_server_config(&tls_config)?)
+            // Peers verify certificates of each other against the CA, so the traffic is mutual TLS
+            Some(certificate_helpers::grpc_tls_server_config(
+                tls_config, true,
+            )?)
         } else {
             None
         };
@@ -221,6 +232,16 @@ impl Consensus {
         // They might have not been applied due to unplanned Qdrant shutdown
         let _stop_consensus = state_ref.apply_entries(&mut node)?;
 
+        let meta_batch_peers = Arc::new(RwLock::new(HashSet::new()));
+        if config.max_meta_op_batch_size > 1 {
+            runtime.spawn(Self::check_meta_batch_peers(
+                state_ref.clone(),
+                channel_service.channel_pool.clone(),
+                Duration::from_millis(config.message_timeout_ticks * config.tick_period_ms),
+                meta_batch_peers.clone(),
+            ));
+        }
+
         let broker = RaftMessageBroker::new(
             runtime.clone(),
             bootstrap_peer,
@@ -236,6 +257,7 @@ impl Consensus {
             runtime,
             config,
             broker,
+            meta_batch_peers,
         };
 
         Ok((consensus, sender))
@@ -485,66 +507,16 @@ impl Consensus {
         });
         match received {
             Ok(Some(Message::FromPeer(message))) => {
-                if message.get_msg_type() == MessageType::MsgHeartbeat
-                    || message.get_msg_type() == MessageType::MsgHeartbeatResponse
-                {
-                    // Do not log heartbeat messages
-                } else {
-                    log::trace!(
-                        "Received a message from peer with progress: {:?}. Message: {:?}",
-                        self.node.raft.prs().get(message.from),
-                        message
-                    );
-                }
-                if let Err(error) = self.node.step(*message) {
-                    log::warn!("Failed to step message: {:?}", error);
-                }
+                self.step_peer_message(message);
                 Ok(true)
             }
             Ok(Some(Message::FromClient(operation))) => {
-                let result = match operation {
-                    ConsensusOperations::RemovePeer(peer_id) => {
-                        let mut change = ConfChangeV2::default();
-                        change.set_changes(vec![raft_proto::new_conf_change_single(
-                            peer_id,
-                            ConfChangeType::RemoveNode,
-                        )]);
-                        log::debug!("Proposing network configuration change: {:?}", change);
-                        self.node.propose_conf_change(vec![], change)
-                    }
-                    ConsensusOperations::AddPeer { peer_id, uri } => {
-                        let mut change = ConfChangeV2::default();
-                        change.set_changes(vec![raft_proto::new_conf_change_single(
-                            peer_id,
-                            ConfChangeType::AddLearnerNode,
-                        )]);
-                        log::debug!("Proposing network configuration change: {:?}", change);
-                        self.node.propose_conf_change(uri.into_bytes(), change)
-                    }
-                    ConsensusOperations::RequestSnapshot => self.node.request_snapshot(),
-                    ConsensusOperations::ReportSnapshot { peer_id, status } => {
-                        self.node.report_snapshot(peer_id, status.into());
-                        Ok(())
-                    }
-                    _ => {
-                        let message = match serde_cbor::to_vec(&operation) {
-                            Ok(message) => message,
-                            Err(err) => {
-                                log::error!("Failed to serialize operation: {}", err);
-                                return Ok(true);
-                            }
-                        };
-                        log::trace!("Proposing entry from client with length: {}", message.len());
-                        self.node.propose(vec![], message)
-                    }
-                };
-
-                match result {
-                    Ok(_) => {}
-                    Err(consensus_err) => {
-                        // Do not stop consensus if client proposal failed.
-                        log::error!("Failed to propose entry: {:?}", consensus_err);
-                    }
+                let (operation, deferred) = self.collect_meta_batch(operation);
+                self.propose_operation(operation);
+                match deferred {
+                    Some(Message::FromClient(operation)) => self.propose_operation(operation),
+                    Some(Message::FromPeer(message)) => self.step_peer_message(message),
+                    None => {}
                 }
                 Ok(true)
             }
@@ -556,6 +528,175 @@ impl Consensus {
         }
     }
 
+    fn step_peer_message(&mut self, message: Box<RaftMessage>) {
+        if message.get_msg_type() == MessageType::MsgHeartbeat
+            || message.get_msg_type() == MessageType::MsgHeartbeatResponse
+        {
+            // Do not log heartbeat messages
+        } else {
+            log::trace!(
+                "Received a message from peer with progress: {:?}. Message: {:?}",
+                self.node.raft.prs().get(message.from),
+                message
+            );
+        }
+        if let Err(error) = self.node.step(*message) {
+            log::warn!("Failed to step message: {:?}", error);
+        }
+    }
+
+    /// Periodically ask all known peers, if they can apply batches of collection meta operations.
+    ///
+    /// Peers of older versions don't know the `GetConsensusFeatures` call, so they are never
+    /// added to the set, as well as peers, which are not reachable at the moment.
+    async fn check_meta_batch_peers(
+        state_ref: ConsensusStateRef,
+        channel_pool: Arc<TransportChannelPool>,
+        timeout: Duration,
+        meta_batch_peers: Arc<RwLock<HashSet<PeerId>>>,
+    ) {
+        loop {
+            let mut supported = HashSet::new();
+            for (peer_id, uri) in state_ref.peer_address_by_id() {
+                if peer_id == state_ref.this_peer_id() {
+                    supported.insert(peer_id);
+                    continue;
+                }
+                let features = channel_pool
+                    .with_channel_timeout(
+                        &uri,
+                        |channel| async {
+                            let mut client = QdrantInternalClient::new(channel);
+                            let mut request = tonic::Request::new(ConsensusFeaturesRequest {});
+                            request.set_timeout(timeout);
+                            client.get_consensus_features(request).await
+                        },
+                        Some(timeout),
+                        0,
+                    )
+                    .await;
+                match features {
+                    Ok(features) if features.into_inner().collection_meta_batch => {
+                        supported.insert(peer_id);
+                    }
+                    Ok(_) => {}
+                    Err(err) => {
+                        log::trace!("Failed to get consensus features of peer {peer_id}: {err}")
+                    }
+                }
+            }
+            *meta_batch_peers.write().unwrap() = supported;
+            sleep(META_BATCH_PEERS_CHECK_INTERVAL).await;
+        }
+    }
+
+    /// Batches are only proposed, if every peer of the cluster can apply them.
+    /// Otherwise peers of older versions would fail to apply the entry and stop consensus.
+    fn can_propose_meta_batch(&self) -> bool {
+        let this_peer_id = self.node.store().this_peer_id();
+        let meta_batch_peers = self.meta_batch_peers.read().unwrap();
+        self.node
+            .store()
+            .peer_address_by_id()
+            .keys()
+            .all(|peer_id| *peer_id == this_peer_id || meta_batch_peers.contains(peer_id))
+    }
+
+    /// Merge collection meta operations, which are already waiting in the queue, into a single
+    /// consensus entry. This reduces the number of Raft log entries (and round-trips) when many
+    /// collections are modified concurrently.
+    ///
+    /// All meta operations still go through the single consensus log of the cluster, batching
+    /// only merges them into fewer entries.
+    ///
+    /// Returns the operation to propose and a message which was received while collecting the
+    /// batch, but could not be merged into it.
+    fn collect_meta_batch(
+        &mut self,
+        operation: ConsensusOperations,
+    ) -> (ConsensusOperations, Option<Message>) {
+        let max_batch_size = self.config.max_meta_op_batch_size;
+        let batching = max_batch_size > 1 && self.can_propose_meta_batch();
+
+        let first = match operation {
+            ConsensusOperations::CollectionMeta(operation) if batching => *operation,
+            operation => return (operation, None),
+        };
+
+        let mut batch = vec![first];
+        let mut deferred = None;
+        while batch.len() < max_batch_size {
+            match self.receiver.try_recv() {
+                Ok(Message::FromClient(ConsensusOperations::CollectionMeta(operation))) => {
+                    batch.push(*operation)
+                }
+                Ok(message) => {
+                    deferred = Some(message);
+                    break;
+                }
+                Err(_) => break,
+            }
+        }
+
+        let operation = if batch.len() == 1 {
+            ConsensusOperations::CollectionMeta(Box::new(batch.remove(0)))
+        } else {
+            log::trace!(
+                "Proposing batch of {} collection meta operations",
+                batch.len()
+            );
+            ConsensusOperations::CollectionMetaBatch(batch)
+        };
+        (operation, deferred)
+    }
+
+    fn propose_operation(&mut self, operation: ConsensusOperations) {
+        let result = match operation {
+            ConsensusOperations::RemovePeer(peer_id) => {
+                let mut change = ConfChangeV2::default();
+                change.set_changes(vec![raft_proto::new_conf_change_single(
+                    peer_id,
+                    ConfChangeType::RemoveNode,
+                )]);
+                log::debug!("Proposing network configuration change: {:?}", change);
+                self.node.propose_conf_change(vec![], change)
+            }
+            ConsensusOperations::AddPeer { peer_id, uri } => {
+                let mut change = ConfChangeV2::default();
+                change.set_changes(vec![raft_proto::new_conf_change_single(
+                    peer_id,
+                    ConfChangeType::AddLearnerNode,
+                )]);
+                log::debug!("Proposing network configuration change: {:?}", change);
+                self.node.propose_conf_change(uri.into_bytes(), change)
+            }
+            ConsensusOperations::RequestSnapshot => self.node.request_snapshot(),
+            ConsensusOperations::ReportSnapshot { peer_id, status } => {
+                self.node.report_snapshot(peer_id, status.into());
+                Ok(())
+            }
+            _ => {
+                let message = match serde_cbor::to_vec(&operation) {
+                    Ok(message) => message,
+                    Err(err) => {
+                        log::error!("Failed to serialize operation: {}", err);
+                        return;
+                    }
+                };
+                log::trace!("Proposing entry from client with length: {}", message.len());
+                self.node.propose(vec![], message)
+            }
+        };
+
+        match result {
+            Ok(_) => {}
+            Err(consensus_err) => {
+                // Do not stop consensus if client proposal failed.
+                log::error!("Failed to propose entry: {:?}", consensus_err);
+            }
+        }
+    }
+
     /// Returns `true` if learner promotion was proposed, `false` otherwise.
     /// Learner node does not vote on elections, cause it might not have a big picture yet.
     /// So consensus should guarantee that learners are promoted one-by-one.
@@ -1222,6 +1363,8 @@ mod tests {
                                 hnsw_config: None,
                                 quantization_config: None,
                                 on_disk: None,
+                                multivector_config: None,
+                                datatype: None,
                             }
                             .into(),
                             hnsw_config: None,
@@ -1233,6 +1376,7 @@ mod tests {
                             write_consistency_factor: None,
                             init_from: None,
                             quantization_config: None,
+                            sparse_vectors: None,
                         },
                     )),
                     None,
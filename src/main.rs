@@ -33,10 +33,13 @@ use storage::dispatcher::Dispatcher;
 #[cfg(not(target_env = "msvc"))]
 use tikv_jemallocator::Jemalloc;
 
+use crate::common::expiration_scheduler::ExpirationScheduler;
 use crate::common::helpers::{
     create_general_purpose_runtime, create_search_runtime, create_update_runtime,
     load_tls_client_config,
 };
+use crate::common::rate_limiter::RateLimiter;
+use crate::common::snapshot_scheduler::SnapshotScheduler;
 use crate::common::telemetry::TelemetryCollector;
 use crate::common::telemetry_reporting::TelemetryReporter;
 use crate::greeting::welcome;
@@ -130,7 +133,7 @@ fn main() -> anyhow::Result<()> {
 
     let reporting_id = TelemetryCollector::generate_id();
 
-    tracing::setup(&settings.log_level)?;
+    let _tracing_guard = tracing::setup(&settings.log_level, settings.opentelemetry.as_ref())?;
 
     setup_panic_hook(reporting_enabled, reporting_id.to_string());
 
@@ -149,6 +152,16 @@ fn main() -> anyhow::Result<()> {
     // Validate as soon as possible, but we must initialize logging first
     settings.validate_and_warn();
 
+    // Invalid limits would fail the requests, so they are rejected right away
+    let rate_limiter = settings
+        .service
+        .rate_limit
+        .as_ref()
+        .map(RateLimiter::new)
+        .transpose()
+        .map_err(anyhow::Error::msg)?
+        .map(Arc::new);
+
     // Saved state of the consensus.
     let persistent_consensus_state =
         Persistent::load_or_init(&settings.storage.storage_path, args.bootstrap.is_none())?;
@@ -271,8 +284,12 @@ fn main() -> anyhow::Result<()> {
         let dispatcher_arc = Arc::new(dispatcher);
 
         // Monitoring and telemetry.
-        let telemetry_collector =
-            TelemetryCollector::new(settings.clone(), dispatcher_arc.clone(), reporting_id);
+        let telemetry_collector = TelemetryCollector::new(
+            settings.clone(),
+            dispatcher_arc.clone(),
+            rate_limiter.clone(),
+            reporting_id,
+        );
         let tonic_telemetry_collector = telemetry_collector.tonic_telemetry_collector.clone();
 
         // `raft` crate uses `slog` crate so it is needed to use `slog_stdlog::StdLog` to forward
@@ -337,8 +354,12 @@ fn main() -> anyhow::Result<()> {
         let dispatcher_arc = Arc::new(dispatcher);
 
         // Monitoring and telemetry.
-        let telemetry_collector =
-            TelemetryCollector::new(settings.clone(), dispatcher_arc.clone(), reporting_id);
+        let telemetry_collector = TelemetryCollector::new(
+            settings.clone(),
+            dispatcher_arc.clone(),
+            rate_limiter.clone(),
+            reporting_id,
+        );
         (telemetry_collector, dispatcher_arc)
     };
 
@@ -359,6 +380,9 @@ fn main() -> anyhow::Result<()> {
         log::info!("Telemetry reporting disabled");
     }
 
+    runtime_handle.spawn(SnapshotScheduler::run(dispatcher_arc.as_ref().clone()));
+    runtime_handle.spawn(ExpirationScheduler::run(dispatcher_arc.as_ref().clone()));
+
     // Helper to better log start errors
     let log_err_if_any = |server_name, result| match result {
         Err(err) => {
@@ -402,6 +426,7 @@ fn main() -> anyhow::Result<()> {
                     tonic::init(
                         dispatcher_arc,
                         tonic_telemetry_collector,
+                        rate_limiter,
                         settings,
                         grpc_port,
                         runtime_handle,
@@ -1,29 +1,45 @@
 use api::grpc::models::CollectionsResponse;
+use collection::common::delete_tasks::DeleteTaskInfo;
+use collection::common::import_tasks::ImportTaskInfo;
+use collection::common::optimize_tasks::OptimizeTaskInfo;
+use collection::common::snapshot_retention::SnapshotRetention;
+use collection::common::snapshot_schedule::{SnapshotSchedule, SnapshotScheduleInfo};
+use collection::operations::change_ops::{ChangesRequest, ChangesResult};
 use collection::operations::cluster_ops::ClusterOperations;
 use collection::operations::consistency_params::ReadConsistency;
 use collection::operations::payload_ops::{DeletePayload, SetPayload};
-use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
+use collection::operations::point_ops::{
+    PointInsertOperations, PointsAutoId, PointsSelector, WriteOrdering,
+};
 use collection::operations::snapshot_ops::{
-    ShardSnapshotRecover, SnapshotDescription, SnapshotRecover,
+    PointInTimeRecover, RecoveryWindow, ShardSnapshotRecover, SnapshotDescription, SnapshotRecover,
+    SnapshotVerification,
 };
 use collection::operations::types::{
     AliasDescription, CollectionClusterInfo, CollectionInfo, CollectionsAliasesResponse,
-    CountRequest, CountResult, GroupsResult, PointGroup, PointRequest, RecommendGroupsRequest,
-    RecommendRequest, RecommendRequestBatch, Record, ScrollRequest, ScrollResult,
-    SearchGroupsRequest, SearchRequest, SearchRequestBatch, UpdateResult,
+    CollectionsSearchRequestBatch, CountRequest, CountRequestBatch, CountResult, GroupsResult,
+    PointGroup, PointRequest, QueryRequest, RecommendGroupsRequest, RecommendRequest,
+    RecommendRequestBatch, Record, ScrollRequest, ScrollResult, SearchGroupsRequest, SearchRequest,
+    SearchRequestBatch, ShardSegmentsInfo, ShardWalInfo, UpdateResult,
 };
 use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
+use collection::optimizers_builder::RebuildIndexTarget;
 use schemars::gen::SchemaSettings;
 use schemars::JsonSchema;
+use segment::common::hardware_counter::HardwareUsage;
+use segment::common::search_explain::ShardSearchExplain;
+use segment::index::field_index::stats::PayloadIndexStatistics;
 use segment::types::ScoredPoint;
 use serde::{Deserialize, Serialize};
 use storage::content_manager::collection_meta_ops::{
     ChangeAliasesOperation, CreateCollection, UpdateCollection,
 };
-use storage::types::ClusterStatus;
+use storage::content_manager::slow_query_log::SlowQuery;
+use storage::content_manager::snapshots::cluster_backup::ClusterBackup;
+use storage::types::{ClusterStatus, UpdatePeerAddress};
 
 use crate::common::helpers::LocksOption;
-use crate::common::points::{CreateFieldIndex, UpdateOperations};
+use crate::common::points::{CreateFieldIndex, ExportRequest, ImportResult, UpdateOperations};
 use crate::common::telemetry::TelemetryData;
 
 mod actix;
@@ -75,6 +91,32 @@ struct AllDefinitions {
     b7: GroupsResult,
     b8: UpdateOperations,
     b9: ShardSnapshotRecover,
+    c1: UpdatePeerAddress,
+    c2: PayloadIndexStatistics,
+    c3: QueryRequest,
+    c4: CollectionsSearchRequestBatch,
+    c5: CountRequestBatch,
+    c6: PointsAutoId,
+    c7: DeleteTaskInfo,
+    c8: ImportResult,
+    c9: ExportRequest,
+    c10: SnapshotSchedule,
+    c11: SnapshotScheduleInfo,
+    c12: SnapshotVerification,
+    c13: SnapshotRetention,
+    c14: ClusterBackup,
+    c15: ChangesRequest,
+    c16: ChangesResult,
+    c17: PointInTimeRecover,
+    c18: RecoveryWindow,
+    c19: ShardWalInfo,
+    c20: OptimizeTaskInfo,
+    c21: RebuildIndexTarget,
+    c22: ShardSegmentsInfo,
+    c23: SlowQuery,
+    c24: HardwareUsage,
+    c25: ShardSearchExplain,
+    c26: ImportTaskInfo,
 }
 
 fn save_schema<T: JsonSchema>() {
@@ -27,6 +27,16 @@ pub struct ServiceConfig {
     #[serde(default)]
     pub verify_https_client_certificate: bool,
     pub api_key: Option<String>,
+    /// Api-key, which only allows to read data, e.g. to search, retrieve and scroll points
+    #[serde(default)]
+    pub read_only_api_key: Option<String>,
+    /// Validation of the JWT tokens, which grant access to specific collections
+    #[serde(default)]
+    pub jwt: Option<JwtConfig>,
+    /// Limits of the requests of each client, not limited if not set
+    #[serde(default)]
+    #[validate]
+    pub rate_limit: Option<RateLimitConfig>,
     /// Directory where static files are served from.
     /// For example, the Web-UI should be placed here.
     #[serde(default)]
@@ -34,10 +44,47 @@ pub struct ServiceConfig {
 
     /// If serving of the static content is enabled.
     /// This includes the Web-UI. True by default.
+    /// If the static content directory doesn't exist, a built-in dashboard is served instead.
     #[serde(default)]
     pub enable_static_content: Option<bool>,
 }
 
+/// Limits of the requests of a single client.
+///
+/// Clients are told apart by the api-key or token, they are authenticated with,
+/// or by their IP address if authentication is disabled or the path doesn't require it.
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct RateLimitConfig {
+    /// Sustained number of requests per second, not limited if not set
+    #[serde(default)]
+    #[validate(range(min = 0.001))]
+    pub requests_per_second: Option<f64>,
+    /// Number of requests, which may be made at once above the sustained rate.
+    /// Same as `requests_per_second` if not set.
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub burst: Option<u32>,
+    /// Number of requests, processed at the same time, not limited if not set
+    #[serde(default)]
+    #[validate(range(min = 1))]
+    pub max_concurrent_requests: Option<usize>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct JwtConfig {
+    /// Algorithm of the token signatures: HS256, HS384, HS512, RS256, RS384 or RS512
+    pub algorithm: jsonwebtoken::Algorithm,
+    /// Secret of the HMAC algorithms
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Path to the PEM file with the public key of the RSA algorithms
+    #[serde(default)]
+    pub public_key_path: Option<String>,
+    /// Expected `iss` claim of the tokens, not checked if not set
+    #[serde(default)]
+    pub issuer: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Clone, Default, Validate)]
 pub struct ClusterConfig {
     pub enabled: bool, // disabled by default
@@ -89,6 +136,12 @@ pub struct ConsensusConfig {
     #[validate(range(min = 1))]
     #[serde(default = "default_message_timeout_tics")]
     pub message_timeout_ticks: u64,
+    /// Maximal number of queued collection meta operations, which can be committed as a single
+    /// consensus entry. `1` disables batching, which is the default.
+    /// Enable batching only once all peers of the cluster run a version, which supports it.
+    #[validate(range(min = 1))]
+    #[serde(default = "default_max_meta_op_batch_size")]
+    pub max_meta_op_batch_size: usize,
 }
 
 impl Default for ConsensusConfig {
@@ -98,10 +151,24 @@ impl Default for ConsensusConfig {
             tick_period_ms: default_tick_period_ms(),
             bootstrap_timeout_sec: default_bootstrap_timeout_sec(),
             message_timeout_ticks: default_message_timeout_tics(),
+            max_meta_op_batch_size: default_max_meta_op_batch_size(),
         }
     }
 }
 
+#[derive(Debug, Deserialize, Clone, Validate)]
+pub struct OpenTelemetryConfig {
+    /// gRPC endpoint of the OTLP collector, e.g. `http://localhost:4317`
+    #[validate(length(min = 1))]
+    pub otlp_endpoint: String,
+    #[serde(default = "default_opentelemetry_service_name")]
+    pub service_name: String,
+    /// Fraction of the traces to export, traces started on other peers follow their decision
+    #[serde(default = "default_opentelemetry_sampling_ratio")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub sampling_ratio: f64,
+}
+
 #[derive(Debug, Deserialize, Clone, Validate)]
 pub struct TlsConfig {
     pub cert: String,
@@ -127,6 +194,10 @@ pub struct Settings {
     pub telemetry_disabled: bool,
     #[validate]
     pub tls: Option<TlsConfig>,
+    /// If provided - spans of the requests are exported to this OpenTelemetry collector
+    #[serde(default)]
+    #[validate]
+    pub opentelemetry: Option<OpenTelemetryConfig>,
     /// A list of messages for errors that happened during loading the configuration. We collect
     /// them and store them here while loading because then our logger is not configured yet.
     /// We therefore need to log these messages later, after the logger is ready.
@@ -186,6 +257,14 @@ fn default_log_level() -> String {
     "INFO".to_string()
 }
 
+fn default_opentelemetry_service_name() -> String {
+    "qdrant".to_string()
+}
+
+const fn default_opentelemetry_sampling_ratio() -> f64 {
+    1.0
+}
+
 const fn default_timeout_ms() -> u64 {
     DEFAULT_GRPC_TIMEOUT.as_millis() as u64
 }
@@ -215,6 +294,10 @@ const fn default_message_timeout_tics() -> u64 {
     10
 }
 
+const fn default_max_meta_op_batch_size() -> usize {
+    1
+}
+
 const fn default_tls_cert_ttl() -> Option<u64> {
     // Default one hour
     Some(3600)
@@ -302,14 +385,18 @@ mod tests {
     /// Ensure we can successfully deserialize into [`Settings`] with just the default configuration.
     #[test]
     fn test_default_config() {
-        Config::builder()
+        let settings = Config::builder()
             .add_source(File::from_str(DEFAULT_CONFIG, FileFormat::Yaml))
             .build()
             .expect("failed to build default config")
             .try_deserialize::<Settings>()
-            .expect("failed to deserialize default config")
+            .expect("failed to deserialize default config");
+        settings
             .validate()
             .expect("failed to validate default config");
+
+        // Peers of older versions can't apply batches of meta operations
+        assert_eq!(settings.cluster.consensus.max_meta_op_batch_size, 1);
     }
 
     #[sealed_test(files = ["config/config.yaml", "config/development.yaml"])]
@@ -0,0 +1,49 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use api::grpc::qdrant::cluster_server::Cluster;
+use api::grpc::qdrant::{ClusterOperationResponse, UpdatePeerAddressRequest};
+use storage::content_manager::conversions::error_to_status;
+use storage::dispatcher::Dispatcher;
+use tonic::{async_trait, Request, Response, Status};
+
+use super::validate;
+use crate::common::cluster::do_update_peer_address;
+
+pub struct ClusterService {
+    dispatcher: Arc<Dispatcher>,
+}
+
+impl ClusterService {
+    pub fn new(dispatcher: Arc<Dispatcher>) -> Self {
+        Self { dispatcher }
+    }
+}
+
+#[async_trait]
+impl Cluster for ClusterService {
+    async fn update_peer_address(
+        &self,
+        request: Request<UpdatePeerAddressRequest>,
+    ) -> Result<Response<ClusterOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        let UpdatePeerAddressRequest {
+            peer_id,
+            uri,
+            timeout,
+        } = request.into_inner();
+        let timing = Instant::now();
+        let result = do_update_peer_address(
+            &self.dispatcher,
+            peer_id,
+            uri,
+            timeout.map(Duration::from_secs),
+        )
+        .await
+        .map_err(error_to_status)?;
+        Ok(Response::new(ClusterOperationResponse {
+            result,
+            time: timing.elapsed().as_secs_f64(),
+        }))
+    }
+}
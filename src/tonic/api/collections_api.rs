@@ -13,7 +13,7 @@ use storage::content_manager::conversions::error_to_status;
 use storage::dispatcher::Dispatcher;
 use tonic::{Request, Response, Status};
 
-use super::validate;
+use super::{check_collection_access, validate};
 use crate::common::collections::*;
 use crate::tonic::api::collections_common::get;
 
@@ -105,6 +105,7 @@ impl Collections for CollectionsService {
         request: Request<GetCollectionInfoRequest>,
     ) -> Result<Response<GetCollectionInfoResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         get(self.dispatcher.as_ref(), request.into_inner(), None).await
     }
 
@@ -125,6 +126,7 @@ impl Collections for CollectionsService {
         request: Request<CreateCollection>,
     ) -> Result<Response<CollectionOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         self.perform_operation(request).await
     }
 
@@ -133,6 +135,7 @@ impl Collections for CollectionsService {
         request: Request<UpdateCollection>,
     ) -> Result<Response<CollectionOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         self.perform_operation(request).await
     }
 
@@ -141,6 +144,7 @@ impl Collections for CollectionsService {
         request: Request<DeleteCollection>,
     ) -> Result<Response<CollectionOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         self.perform_operation(request).await
     }
 
@@ -157,6 +161,7 @@ impl Collections for CollectionsService {
         request: Request<ListCollectionAliasesRequest>,
     ) -> Result<Response<ListAliasesResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         self.list_collection_aliases(request).await
     }
 
@@ -173,6 +178,7 @@ impl Collections for CollectionsService {
         request: Request<CollectionClusterInfoRequest>,
     ) -> Result<Response<CollectionClusterInfoResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         let response = do_get_collection_cluster(
             self.dispatcher.toc(),
             request.into_inner().collection_name.as_str(),
@@ -189,6 +195,7 @@ impl Collections for CollectionsService {
         request: Request<UpdateCollectionClusterSetupRequest>,
     ) -> Result<Response<UpdateCollectionClusterSetupResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         let UpdateCollectionClusterSetupRequest {
             collection_name,
             operation,
@@ -3,8 +3,8 @@ use std::time::Instant;
 
 use api::grpc::qdrant::collections_internal_server::CollectionsInternal;
 use api::grpc::qdrant::{
-    CollectionOperationResponse, GetCollectionInfoRequestInternal, GetCollectionInfoResponse,
-    InitiateShardTransferRequest,
+    CollectionOperationResponse, CreateShardSnapshotRequestInternal, CreateSnapshotResponse,
+    GetCollectionInfoRequestInternal, GetCollectionInfoResponse, InitiateShardTransferRequest,
 };
 use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::toc::TableOfContent;
@@ -68,4 +68,27 @@ impl CollectionsInternal for CollectionsInternalService {
         };
         Ok(Response::new(response))
     }
+
+    async fn create_shard_snapshot(
+        &self,
+        request: Request<CreateShardSnapshotRequestInternal>,
+    ) -> Result<Response<CreateSnapshotResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let timing = Instant::now();
+        let CreateShardSnapshotRequestInternal {
+            collection_name,
+            shard_id,
+        } = request.into_inner();
+
+        let snapshot = self
+            .toc
+            .create_shard_snapshot(&collection_name, shard_id)
+            .await
+            .map_err(error_to_status)?;
+
+        Ok(Response::new(CreateSnapshotResponse {
+            snapshot_description: Some(snapshot.into()),
+            time: timing.elapsed().as_secs_f64(),
+        }))
+    }
 }
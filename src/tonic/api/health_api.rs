@@ -0,0 +1,367 @@
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use api::grpc::grpc_health_v1::health_check_response::ServingStatus;
+use api::grpc::grpc_health_v1::health_server::Health;
+use api::grpc::grpc_health_v1::{HealthCheckRequest, HealthCheckResponse};
+use collection::collection_state::ShardInfo;
+use collection::shards::replica_set::ReplicaState;
+use futures::stream::{self, BoxStream, Stream};
+use futures::StreamExt;
+use storage::content_manager::toc::TableOfContent;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tonic::{async_trait, Request, Response, Status};
+
+use crate::common::auth::{AuthenticatedClient, CollectionAccessCheck};
+use crate::common::rate_limiter::RateLimiter;
+
+/// Prefix of the service names, which refer to a collection, e.g. `collections/my_collection`
+const COLLECTION_SERVICE_PREFIX: &str = "collections/";
+
+/// Public gRPC services, which are always served
+const SERVICE_NAMES: &[&str] = &[
+    "qdrant.Qdrant",
+    "qdrant.Collections",
+    "qdrant.Points",
+    "qdrant.Snapshots",
+    "qdrant.Cluster",
+    "grpc.health.v1.Health",
+];
+
+/// How often the serving status is checked for the `Watch` calls
+const WATCH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Max number of open `Watch` streams, each of them checks the status every [`WATCH_INTERVAL`]
+const MAX_WATCH_STREAMS: usize = 128;
+
+/// Health check service that follows gRPC health check protocol as described in #2614
+///
+/// Besides the whole server (empty service name) and the public gRPC services, the status of a
+/// single collection can be checked with the `collections/{collection_name}` service name.
+/// A collection is served once each of its shards has an active replica.
+///
+/// Checks of the whole server are served to anyone, the others require read access
+/// to the collection, or to any collection for the public services, and are rate limited.
+pub struct HealthService {
+    toc: Arc<TableOfContent>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    watch_streams: Arc<Semaphore>,
+}
+
+impl HealthService {
+    pub fn new(toc: Arc<TableOfContent>, rate_limiter: Option<Arc<RateLimiter>>) -> Self {
+        Self {
+            toc,
+            rate_limiter,
+            watch_streams: Arc::new(Semaphore::new(MAX_WATCH_STREAMS)),
+        }
+    }
+
+    /// Check the access to the service of the request, set by the api-key middleware
+    async fn check_access(&self, request: &Request<HealthCheckRequest>) -> Result<(), Status> {
+        let Some(check) = request.extensions().get::<CollectionAccessCheck>() else {
+            return Ok(());
+        };
+        let service = &request.get_ref().service;
+        let alias_target = match service.strip_prefix(COLLECTION_SERVICE_PREFIX) {
+            Some(collection_name) => self.toc.resolve_alias(collection_name).await,
+            None => None,
+        };
+        check_service_access(check, service, |_| alias_target.clone())
+            .map_err(Status::permission_denied)
+    }
+
+    /// Admit a check of the client, if it is within the rate limits of the client
+    fn check_rate_limit(&self, request: &Request<HealthCheckRequest>) -> Result<(), Status> {
+        let Some(rate_limiter) = &self.rate_limiter else {
+            return Ok(());
+        };
+        let client_key = RateLimiter::client_key(
+            request.extensions().get::<AuthenticatedClient>(),
+            request.remote_addr().map(|addr| addr.ip().to_string()),
+        );
+        // Checks are done right away, so the permit is not held
+        rate_limiter
+            .try_acquire(&client_key)
+            .map(drop)
+            .map_err(|err| Status::resource_exhausted(err.to_string()))
+    }
+}
+
+/// Check the access to the service of a health check, `resolve_alias` gives the collection,
+/// which an alias refers to
+fn check_service_access(
+    check: &CollectionAccessCheck,
+    service: &str,
+    resolve_alias: impl Fn(&str) -> Option<String>,
+) -> Result<(), String> {
+    if service.is_empty() {
+        return Ok(());
+    }
+    match service.strip_prefix(COLLECTION_SERVICE_PREFIX) {
+        Some(collection_name) => {
+            check
+                .access
+                .check_collections(collection_name, check.level, [], resolve_alias)
+        }
+        None if check.access.allows_some_collection(check.level) => Ok(()),
+        None => Err(format!("No {} access to any collection", check.level)),
+    }
+}
+
+/// Reserve one of the open `Watch` streams, released once the permit is dropped
+fn acquire_watch_stream(watch_streams: &Arc<Semaphore>) -> Result<OwnedSemaphorePermit, Status> {
+    watch_streams.clone().try_acquire_owned().map_err(|_| {
+        Status::resource_exhausted(format!(
+            "Too many health watch streams, max {MAX_WATCH_STREAMS}"
+        ))
+    })
+}
+
+/// Serving status of the service, `None` if there is no such service
+async fn serving_status(toc: &TableOfContent, service: &str) -> Option<ServingStatus> {
+    if service.is_empty() || SERVICE_NAMES.contains(&service) {
+        return Some(ServingStatus::Serving);
+    }
+
+    let collection_name = service.strip_prefix(COLLECTION_SERVICE_PREFIX)?;
+    let collection = toc.get_collection(collection_name).await.ok()?;
+    let state = collection.state().await;
+    Some(collection_serving_status(state.shards.values()))
+}
+
+/// Collection is served once each of its shards has an active replica
+fn collection_serving_status<'a>(shards: impl IntoIterator<Item = &'a ShardInfo>) -> ServingStatus {
+    let all_shards_active = shards.into_iter().all(|shard_info| {
+        shard_info
+            .replicas
+            .values()
+            .any(|replica_state| *replica_state == ReplicaState::Active)
+    });
+
+    if all_shards_active {
+        ServingStatus::Serving
+    } else {
+        ServingStatus::NotServing
+    }
+}
+
+/// Stream of the serving status, checked every `interval`.
+/// Current status is sent right away, then only its changes are sent.
+fn watch_serving_status<F, Fut>(
+    read_status: F,
+    interval: Duration,
+) -> impl Stream<Item = Result<HealthCheckResponse, Status>>
+where
+    F: Fn() -> Fut + Clone,
+    Fut: Future<Output = ServingStatus>,
+{
+    stream::unfold(None, move |last_status: Option<ServingStatus>| {
+        let read_status = read_status.clone();
+        async move {
+            loop {
+                if last_status.is_some() {
+                    tokio::time::sleep(interval).await;
+                }
+                let status = read_status().await;
+                if last_status != Some(status) {
+                    let response = HealthCheckResponse {
+                        status: status as i32,
+                    };
+                    return Some((Ok(response), Some(status)));
+                }
+            }
+        }
+    })
+}
+
+#[async_trait]
+impl Health for HealthService {
+    async fn check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        if !request.get_ref().service.is_empty() {
+            self.check_access(&request).await?;
+            self.check_rate_limit(&request)?;
+        }
+
+        let service = request.into_inner().service;
+        let status = serving_status(&self.toc, &service)
+            .await
+            .ok_or_else(|| Status::not_found(format!("Unknown service {service}")))?;
+
+        Ok(Response::new(HealthCheckResponse {
+            status: status as i32,
+        }))
+    }
+
+    type WatchStream = BoxStream<'static, Result<HealthCheckResponse, Status>>;
+
+    async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        self.check_access(&request).await?;
+        let permit = acquire_watch_stream(&self.watch_streams)?;
+
+        let service = request.into_inner().service;
+        let toc = self.toc.clone();
+
+        // Unknown services are reported as such, as they may appear later, e.g. a new collection
+        let stream = watch_serving_status(
+            move || {
+                let toc = toc.clone();
+                let service = service.clone();
+                async move {
+                    serving_status(&toc, &service)
+                        .await
+                        .unwrap_or(ServingStatus::ServiceUnknown)
+                }
+            },
+            WATCH_INTERVAL,
+        );
+
+        // Stream is counted as open until the client drops it
+        let stream = stream.map(move |response| {
+            let _permit = &permit;
+            response
+        });
+
+        Ok(Response::new(stream.boxed()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, VecDeque};
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::common::auth::{Access, AccessLevel, CollectionAccess};
+
+    fn shard(replicas: &[ReplicaState]) -> ShardInfo {
+        ShardInfo {
+            replicas: replicas
+                .iter()
+                .enumerate()
+                .map(|(peer_id, state)| (peer_id as u64, *state))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    #[test]
+    fn test_collection_serving_status() {
+        use ReplicaState::*;
+
+        let serving = |shards: &[ShardInfo]| collection_serving_status(shards);
+
+        assert_eq!(serving(&[]), ServingStatus::Serving);
+        assert_eq!(
+            serving(&[shard(&[Active]), shard(&[Dead, Active])]),
+            ServingStatus::Serving
+        );
+        // Each shard needs an active replica
+        assert_eq!(
+            serving(&[shard(&[Active]), shard(&[Dead, Partial])]),
+            ServingStatus::NotServing
+        );
+        assert_eq!(
+            serving(&[shard(&[Initializing]), shard(&[Active])]),
+            ServingStatus::NotServing
+        );
+        assert_eq!(serving(&[shard(&[Listener])]), ServingStatus::NotServing);
+        assert_eq!(serving(&[shard(&[])]), ServingStatus::NotServing);
+    }
+
+    #[test]
+    fn test_check_service_access() {
+        let check = |access: Access, service: &str| {
+            let check = CollectionAccessCheck {
+                access,
+                level: AccessLevel::Read,
+            };
+            let resolve_alias = |name: &str| (name == "alias").then(|| "shared".to_string());
+            check_service_access(&check, service, resolve_alias)
+        };
+        let shared = Access {
+            global: None,
+            collections: vec![CollectionAccess {
+                collection: "shared".to_string(),
+                access: AccessLevel::Read,
+            }],
+        };
+
+        // Whole server is checked without credentials
+        assert!(check(Access::default(), "").is_ok());
+        assert!(check(Access::default(), "qdrant.Points").is_err());
+        assert!(check(Access::default(), "collections/shared").is_err());
+
+        assert!(check(shared.clone(), "qdrant.Points").is_ok());
+        assert!(check(shared.clone(), "collections/shared").is_ok());
+        assert!(check(shared.clone(), "collections/alias").is_ok());
+        assert!(check(shared, "collections/other").is_err());
+        assert!(check(Access::read_only(), "collections/other").is_ok());
+    }
+
+    #[test]
+    fn test_watch_streams_limit() {
+        let watch_streams = Arc::new(Semaphore::new(1));
+
+        let permit = acquire_watch_stream(&watch_streams).unwrap();
+        let error = acquire_watch_stream(&watch_streams).unwrap_err();
+        assert_eq!(error.code(), tonic::Code::ResourceExhausted);
+
+        drop(permit);
+        assert!(acquire_watch_stream(&watch_streams).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_watch_serving_status_transitions() {
+        use ServingStatus::*;
+
+        // Statuses of the consecutive checks, the last one is kept afterwards
+        let checks = Mutex::new(VecDeque::from([
+            Serving,
+            Serving,
+            NotServing,
+            NotServing,
+            NotServing,
+            Serving,
+            ServiceUnknown,
+        ]));
+        let checks = &checks;
+        let read_status = move || async move {
+            let mut checks = checks.lock().unwrap();
+            if checks.len() > 1 {
+                checks.pop_front().unwrap()
+            } else {
+                checks[0]
+            }
+        };
+
+        // Only the changes of the status are sent
+        let statuses: Vec<_> = watch_serving_status(read_status, Duration::from_millis(1))
+            .take(4)
+            .map(|response| response.unwrap().status)
+            .collect()
+            .await;
+        assert_eq!(
+            statuses,
+            [
+                Serving as i32,
+                NotServing as i32,
+                Serving as i32,
+                ServiceUnknown as i32
+            ]
+        );
+
+        // No more changes, so the stream waits for the next one
+        let mut stream = Box::pin(watch_serving_status(read_status, Duration::from_millis(1)));
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(first.status, ServiceUnknown as i32);
+        let next = tokio::time::timeout(Duration::from_millis(50), stream.next()).await;
+        assert!(next.is_err());
+    }
+}
@@ -1,16 +1,31 @@
+pub mod cluster_api;
 pub mod collections_api;
 mod collections_common;
 pub mod collections_internal_api;
+pub mod health_api;
 pub mod points_api;
 mod points_common;
 pub mod points_internal_api;
 pub mod raft_api;
 pub mod snapshots_api;
 
+use api::grpc::qdrant::{
+    ClearPayloadPoints, CollectionClusterInfoRequest, CountPoints, CreateCollection,
+    CreateFieldIndexCollection, CreateSnapshotRequest, DeleteCollection,
+    DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints,
+    DeleteSnapshotRequest, GetCollectionInfoRequest, GetPoints, ListCollectionAliasesRequest,
+    ListSnapshotsRequest, LookupLocation, PrefetchQuery, QueryPoints, RecommendBatchPoints,
+    RecommendPointGroups, RecommendPoints, ScrollPoints, SearchBatchPoints, SearchPointGroups,
+    SearchPoints, SetPayloadPoints, UpdateBatchPoints, UpdateCollection,
+    UpdateCollectionClusterSetupRequest, UpdatePointVectors, UpsertPoints, WithLookup,
+};
 use collection::operations::validation;
-use tonic::Status;
+use storage::content_manager::toc::TableOfContent;
+use tonic::{Request, Status};
 use validator::Validate;
 
+use crate::common::auth::{check_collections_access, CollectionAccessCheck};
+
 /// Validate the given request and fail on error.
 ///
 /// Returns validation error on failure.
@@ -20,6 +35,180 @@ fn validate(request: &impl Validate) -> Result<(), Status> {
     })
 }
 
+/// Request, which refers to a single collection
+trait CollectionRequest {
+    fn collection_name(&self) -> &str;
+
+    /// Names of the other collections, which the request reads, e.g. by `with_lookup`
+    fn lookup_collections(&self) -> Vec<&str> {
+        Vec::new()
+    }
+}
+
+macro_rules! impl_collection_request {
+    ($($request:ty),* $(,)?) => {
+        $(
+            impl CollectionRequest for $request {
+                fn collection_name(&self) -> &str {
+                    &self.collection_name
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_lookup_collection_request {
+    ($($request:ty),* $(,)?) => {
+        $(
+            impl CollectionRequest for $request {
+                fn collection_name(&self) -> &str {
+                    &self.collection_name
+                }
+
+                fn lookup_collections(&self) -> Vec<&str> {
+                    LookupCollections::lookup_collections(self)
+                }
+            }
+        )*
+    };
+}
+
+/// Collections, which a message reads besides the collection of the request
+trait LookupCollections {
+    fn lookup_collections(&self) -> Vec<&str>;
+}
+
+fn lookup_location_collection(lookup_from: &Option<LookupLocation>) -> Option<&str> {
+    lookup_from
+        .as_ref()
+        .map(|location| location.collection_name.as_str())
+}
+
+fn with_lookup_collection(with_lookup: &Option<WithLookup>) -> Option<&str> {
+    with_lookup
+        .as_ref()
+        .map(|with_lookup| with_lookup.collection.as_str())
+}
+
+impl LookupCollections for SearchPointGroups {
+    fn lookup_collections(&self) -> Vec<&str> {
+        with_lookup_collection(&self.with_lookup)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl LookupCollections for RecommendPoints {
+    fn lookup_collections(&self) -> Vec<&str> {
+        lookup_location_collection(&self.lookup_from)
+            .into_iter()
+            .collect()
+    }
+}
+
+impl LookupCollections for RecommendBatchPoints {
+    fn lookup_collections(&self) -> Vec<&str> {
+        self.recommend_points
+            .iter()
+            .flat_map(LookupCollections::lookup_collections)
+            .collect()
+    }
+}
+
+impl LookupCollections for RecommendPointGroups {
+    fn lookup_collections(&self) -> Vec<&str> {
+        lookup_location_collection(&self.lookup_from)
+            .into_iter()
+            .chain(with_lookup_collection(&self.with_lookup))
+            .collect()
+    }
+}
+
+impl LookupCollections for PrefetchQuery {
+    fn lookup_collections(&self) -> Vec<&str> {
+        lookup_location_collection(&self.lookup_from)
+            .into_iter()
+            .chain(
+                self.prefetch
+                    .iter()
+                    .flat_map(LookupCollections::lookup_collections),
+            )
+            .collect()
+    }
+}
+
+impl LookupCollections for QueryPoints {
+    fn lookup_collections(&self) -> Vec<&str> {
+        lookup_location_collection(&self.lookup_from)
+            .into_iter()
+            .chain(
+                self.prefetch
+                    .iter()
+                    .flat_map(LookupCollections::lookup_collections),
+            )
+            .collect()
+    }
+}
+
+impl_lookup_collection_request!(
+    SearchPointGroups,
+    RecommendPoints,
+    RecommendBatchPoints,
+    RecommendPointGroups,
+    QueryPoints,
+);
+
+impl_collection_request!(
+    UpsertPoints,
+    DeletePoints,
+    GetPoints,
+    UpdatePointVectors,
+    DeletePointVectors,
+    SetPayloadPoints,
+    DeletePayloadPoints,
+    ClearPayloadPoints,
+    UpdateBatchPoints,
+    CreateFieldIndexCollection,
+    DeleteFieldIndexCollection,
+    SearchPoints,
+    SearchBatchPoints,
+    ScrollPoints,
+    CountPoints,
+    GetCollectionInfoRequest,
+    CreateCollection,
+    UpdateCollection,
+    DeleteCollection,
+    ListCollectionAliasesRequest,
+    CollectionClusterInfoRequest,
+    UpdateCollectionClusterSetupRequest,
+    CreateSnapshotRequest,
+    ListSnapshotsRequest,
+    DeleteSnapshotRequest,
+);
+
+/// Check the access to the collection of the given request,
+/// and to the other collections it reads, e.g. by `with_lookup` or `lookup_from`.
+///
+/// Only checked if the credentials of the request don't grant access to all collections,
+/// otherwise it is checked by the auth middleware already.
+async fn check_collection_access<T: CollectionRequest>(
+    request: &Request<T>,
+    toc: &TableOfContent,
+) -> Result<(), Status> {
+    let Some(check) = request.extensions().get::<CollectionAccessCheck>() else {
+        return Ok(());
+    };
+    let request = request.get_ref();
+    check_collections_access(
+        toc,
+        check,
+        request.collection_name(),
+        &request.lookup_collections(),
+    )
+    .await
+    .map_err(Status::permission_denied)
+}
+
 /// Validate the given request. Returns validation error on failure.
 fn validate_and_log(request: &impl Validate) {
     if let Err(ref err) = request.validate() {
@@ -61,4 +250,63 @@ mod tests {
             "Validation error in body: [things[0].idx: value 0 invalid, must be 1.0 or larger]"
         )
     }
+
+    #[test]
+    fn test_lookup_collections() {
+        let lookup_from = |collection_name: &str| {
+            Some(LookupLocation {
+                collection_name: collection_name.to_string(),
+                ..Default::default()
+            })
+        };
+
+        let request = QueryPoints {
+            collection_name: "team_a_docs".to_string(),
+            lookup_from: lookup_from("team_a_vectors"),
+            prefetch: vec![PrefetchQuery {
+                prefetch: vec![PrefetchQuery {
+                    lookup_from: lookup_from("team_b_docs"),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        assert_eq!(
+            CollectionRequest::lookup_collections(&request),
+            vec!["team_a_vectors", "team_b_docs"],
+        );
+
+        let request = RecommendPointGroups {
+            collection_name: "team_a_docs".to_string(),
+            with_lookup: Some(WithLookup {
+                collection: "team_b_docs".to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        assert_eq!(
+            CollectionRequest::lookup_collections(&request),
+            vec!["team_b_docs"],
+        );
+
+        let request = RecommendBatchPoints {
+            collection_name: "team_a_docs".to_string(),
+            recommend_points: vec![
+                RecommendPoints::default(),
+                RecommendPoints {
+                    lookup_from: lookup_from("team_b_docs"),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert_eq!(
+            CollectionRequest::lookup_collections(&request),
+            vec!["team_b_docs"],
+        );
+
+        let request = SearchPoints::default();
+        assert!(request.lookup_collections().is_empty());
+    }
 }
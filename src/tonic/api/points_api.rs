@@ -1,26 +1,28 @@
 use std::sync::Arc;
+use std::time::Duration;
 
 use api::grpc::qdrant::points_server::Points;
 use api::grpc::qdrant::{
     ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
     DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints, GetPoints,
-    GetResponse, PointsOperationResponse, RecommendBatchPoints, RecommendBatchResponse,
-    RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
-    ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse,
-    SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, UpdateBatchPoints,
-    UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
+    GetResponse, PointsOperationResponse, QueryPoints, QueryResponse, RecommendBatchPoints,
+    RecommendBatchResponse, RecommendGroupsResponse, RecommendPointGroups, RecommendPoints,
+    RecommendResponse, ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse,
+    SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints,
+    UpdateBatchPoints, UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
 };
+use futures::stream::BoxStream;
 use storage::content_manager::toc::TableOfContent;
 use tonic::{Request, Response, Status};
 
 use super::points_common::{
     delete_vectors, recommend_groups, search_groups, update_batch, update_vectors,
 };
-use super::validate;
+use super::{check_collection_access, validate};
 use crate::tonic::api::points_common::{
     clear_payload, count, create_field_index, delete, delete_field_index, delete_payload, get,
-    overwrite_payload, recommend, recommend_batch, scroll, search, search_batch, set_payload,
-    upsert,
+    overwrite_payload, query, recommend, recommend_batch, scroll, scroll_stream, search,
+    search_batch, set_payload, upsert,
 };
 
 pub struct PointsService {
@@ -40,6 +42,7 @@ impl Points for PointsService {
         request: Request<UpsertPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         upsert(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -48,11 +51,13 @@ impl Points for PointsService {
         request: Request<DeletePoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         delete(self.toc.as_ref(), request.into_inner(), None).await
     }
 
     async fn get(&self, request: Request<GetPoints>) -> Result<Response<GetResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         get(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -61,6 +66,7 @@ impl Points for PointsService {
         request: Request<UpdatePointVectors>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         update_vectors(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -69,6 +75,7 @@ impl Points for PointsService {
         request: Request<DeletePointVectors>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         delete_vectors(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -77,6 +84,7 @@ impl Points for PointsService {
         request: Request<SetPayloadPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         set_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -85,6 +93,7 @@ impl Points for PointsService {
         request: Request<SetPayloadPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         overwrite_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -93,6 +102,7 @@ impl Points for PointsService {
         request: Request<DeletePayloadPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         delete_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -101,6 +111,7 @@ impl Points for PointsService {
         request: Request<ClearPayloadPoints>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         clear_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -109,6 +120,7 @@ impl Points for PointsService {
         request: Request<UpdateBatchPoints>,
     ) -> Result<Response<UpdateBatchResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         update_batch(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -117,6 +129,7 @@ impl Points for PointsService {
         request: Request<CreateFieldIndexCollection>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         create_field_index(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -125,6 +138,7 @@ impl Points for PointsService {
         request: Request<DeleteFieldIndexCollection>,
     ) -> Result<Response<PointsOperationResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         delete_field_index(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -133,6 +147,7 @@ impl Points for PointsService {
         request: Request<SearchPoints>,
     ) -> Result<Response<SearchResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         search(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -141,10 +156,14 @@ impl Points for PointsService {
         request: Request<SearchBatchPoints>,
     ) -> Result<Response<SearchBatchResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         let SearchBatchPoints {
             collection_name,
             search_points,
             read_consistency,
+            timeout,
+            with_usage,
+            explain,
         } = request.into_inner();
         search_batch(
             self.toc.as_ref(),
@@ -152,6 +171,9 @@ impl Points for PointsService {
             search_points,
             read_consistency,
             None,
+            timeout.map(Duration::from_secs),
+            with_usage.unwrap_or_default(),
+            explain,
         )
         .await
     }
@@ -161,6 +183,7 @@ impl Points for PointsService {
         request: Request<SearchPointGroups>,
     ) -> Result<Response<SearchGroupsResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         search_groups(self.toc.as_ref(), request.into_inner(), None).await
     }
 
@@ -169,14 +192,31 @@ impl Points for PointsService {
         request: Request<ScrollPoints>,
     ) -> Result<Response<ScrollResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         scroll(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    type ScrollStreamStream = BoxStream<'static, Result<ScrollResponse, Status>>;
+
+    async fn scroll_stream(
+        &self,
+        request: Request<ScrollPoints>,
+    ) -> Result<Response<Self::ScrollStreamStream>, Status> {
+        validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
+        Ok(Response::new(scroll_stream(
+            self.toc.clone(),
+            request.into_inner(),
+            None,
+        )))
+    }
+
     async fn recommend(
         &self,
         request: Request<RecommendPoints>,
     ) -> Result<Response<RecommendResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         recommend(self.toc.as_ref(), request.into_inner()).await
     }
 
@@ -185,16 +225,19 @@ impl Points for PointsService {
         request: Request<RecommendBatchPoints>,
     ) -> Result<Response<RecommendBatchResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         let RecommendBatchPoints {
             collection_name,
             recommend_points,
             read_consistency,
+            timeout,
         } = request.into_inner();
         recommend_batch(
             self.toc.as_ref(),
             collection_name,
             recommend_points,
             read_consistency,
+            timeout.map(Duration::from_secs),
         )
         .await
     }
@@ -204,6 +247,7 @@ impl Points for PointsService {
         request: Request<RecommendPointGroups>,
     ) -> Result<Response<RecommendGroupsResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         recommend_groups(self.toc.as_ref(), request.into_inner()).await
     }
 
@@ -212,6 +256,16 @@ impl Points for PointsService {
         request: Request<CountPoints>,
     ) -> Result<Response<CountResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
         count(self.toc.as_ref(), request.into_inner(), None).await
     }
+
+    async fn query(
+        &self,
+        request: Request<QueryPoints>,
+    ) -> Result<Response<QueryResponse>, Status> {
+        validate(request.get_ref())?;
+        check_collection_access(&request, &self.toc).await?;
+        query(self.toc.as_ref(), request.into_inner()).await
+    }
 }
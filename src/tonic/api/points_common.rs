@@ -1,43 +1,56 @@
-use std::time::Instant;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use api::grpc::conversions::proto_to_payloads;
+use api::grpc::conversions::{explain_mode_from_i32, proto_to_payloads};
 use api::grpc::qdrant::payload_index_params::IndexParams;
 use api::grpc::qdrant::{
     points_update_operation, BatchResult, ClearPayloadPoints, CoreSearchPoints, CountPoints,
     CountResponse, CreateFieldIndexCollection, DeleteFieldIndexCollection, DeletePayloadPoints,
     DeletePointVectors, DeletePoints, FieldType, GetPoints, GetResponse, PayloadIndexParams,
-    PointsOperationResponse, PointsSelector, ReadConsistency as ReadConsistencyGrpc,
-    RecommendBatchResponse, RecommendGroupsResponse, RecommendPointGroups, RecommendPoints,
-    RecommendResponse, ScrollPoints, ScrollResponse, SearchBatchResponse, SearchGroupsResponse,
-    SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, SyncPoints,
-    UpdateBatchPoints, UpdateBatchResponse, UpdatePointVectors, UpsertPoints,
+    PointClockTag, PointsOperationResponse, PointsSelector, QueryPoints, QueryResponse,
+    ReadConsistency as ReadConsistencyGrpc, RecommendBatchResponse, RecommendGroupsResponse,
+    RecommendPointGroups, RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse,
+    SearchBatchResponse, SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse,
+    SetPayloadPoints, SyncPoints, UpdateBatchPoints, UpdateBatchResponse, UpdatePointVectors,
+    UpsertPoints,
 };
+use collection::common::hardware_usage::collect_hardware_usage;
+use collection::common::query_explain::collect_search_explain;
+use collection::operations::clock_ops::{ClockTag, ClockedOperation};
 use collection::operations::consistency_params::ReadConsistency;
-use collection::operations::conversions::write_ordering_from_proto;
-use collection::operations::payload_ops::DeletePayload;
+use collection::operations::conversions::{point_clock_tag_from_proto, write_ordering_from_proto};
+use collection::operations::payload_ops::{DeletePayload, MutatePayload, PayloadOps};
 use collection::operations::point_ops::{
-    self, PointInsertOperations, PointOperations, PointSyncOperation,
+    self, PointInsertOperations, PointOperations, PointSyncOperation, UpsertCondition,
 };
 use collection::operations::types::{
-    default_exact_count, CoreSearchRequestBatch, PointRequest, RecommendExample,
-    RecommendRequestBatch, ScrollRequest, SearchRequest, SearchRequestBatch,
+    default_exact_count, CoreSearchRequestBatch, PointRequest, QueryRequest, RecommendExample,
+    RecommendRequestBatch, ScrollRequest, SearchRequest, SearchRequestBatch, UpdateResult,
+};
+use collection::operations::vector_ops::{
+    DeleteVectors, PointVectors, UpdateVectors, VectorOperations,
 };
-use collection::operations::vector_ops::{DeleteVectors, PointVectors, UpdateVectors};
 use collection::operations::CollectionUpdateOperations;
 use collection::shards::shard::ShardId;
-use segment::data_types::vectors::NamedVector;
+use futures::stream::{self, BoxStream, Stream};
+use futures::StreamExt as _;
+use segment::data_types::vectors::NamedVectorStruct;
 use segment::types::{
     ExtendedPointId, Filter, PayloadFieldSchema, PayloadSchemaParams, PayloadSchemaType,
 };
+use sparse::common::sparse_vector::SparseVector;
 use storage::content_manager::conversions::error_to_status;
 use storage::content_manager::toc::TableOfContent;
 use tonic::{Response, Status};
 
+use super::validate;
 use crate::common::points::{
-    do_clear_payload, do_core_search_batch_points, do_count_points, do_create_index,
-    do_delete_index, do_delete_payload, do_delete_points, do_delete_vectors, do_get_points,
-    do_overwrite_payload, do_scroll_points, do_search_batch_points, do_search_points,
-    do_set_payload, do_update_vectors, do_upsert_points, CreateFieldIndex,
+    clear_payload_operation, delete_points_operation, delete_vectors_operations, do_clear_payload,
+    do_core_search_batch_points, do_count_points, do_create_index, do_delete_index,
+    do_delete_payload, do_delete_points, do_delete_vectors, do_get_points, do_overwrite_payload,
+    do_query_points, do_scroll_points, do_search_batch_points, do_search_points, do_set_payload,
+    do_update_batch, do_update_vectors, do_upsert_points, with_read_timeout, CreateFieldIndex,
 };
 
 fn extract_points_selector(
@@ -65,6 +78,25 @@ pub fn points_operation_response(
     }
 }
 
+/// Build list of operation points
+fn point_vectors_from_proto(
+    points: Vec<api::grpc::qdrant::PointVectors>,
+) -> Result<Vec<PointVectors>, Status> {
+    let mut op_points = Vec::with_capacity(points.len());
+    for point in points {
+        let id = match point.id {
+            Some(id) => id.try_into()?,
+            None => return Err(Status::invalid_argument("id is expected")),
+        };
+        let vector = match point.vectors {
+            Some(vectors) => vectors.try_into()?,
+            None => return Err(Status::invalid_argument("vectors is expected")),
+        };
+        op_points.push(PointVectors { id, vector });
+    }
+    Ok(op_points)
+}
+
 pub async fn upsert(
     toc: &TableOfContent,
     upsert_points: UpsertPoints,
@@ -73,19 +105,38 @@ pub async fn upsert(
     let UpsertPoints {
         collection_name,
         wait,
-        points,
+        mut points,
         ordering,
+        if_version,
+        if_absent,
+        idempotency_token,
     } = upsert_points;
+
+    // Assign IDs to the points without them
+    let missing_ids = points.iter().filter(|point| point.id.is_none()).count();
+    let ids = point_ops::generate_point_ids(missing_ids, idempotency_token.as_deref());
+    for (point, id) in points
+        .iter_mut()
+        .filter(|point| point.id.is_none())
+        .zip(&ids)
+    {
+        point.id = Some((*id).into());
+    }
+
     let points = points
         .into_iter()
         .map(|point| point.try_into())
         .collect::<Result<_, _>>()?;
     let operation = PointInsertOperations::PointsList(points);
+    let condition = UpsertCondition::from_params(if_version, if_absent)
+        .map_err(|err| Status::invalid_argument(err.to_string()))?;
     let timing = Instant::now();
     let result = do_upsert_points(
         toc,
         &collection_name,
         operation,
+        condition,
+        idempotency_token,
         shard_selection,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
@@ -93,6 +144,10 @@ pub async fn upsert(
     .await
     .map_err(error_to_status)?;
 
+    let result = UpdateResult {
+        ids: (!ids.is_empty()).then_some(ids),
+        ..result
+    };
     let response = points_operation_response(timing, result);
     Ok(Response::new(response))
 }
@@ -100,6 +155,7 @@ pub async fn upsert(
 pub async fn sync(
     toc: &TableOfContent,
     sync_points: SyncPoints,
+    point_clock_tags: Vec<PointClockTag>,
     shard_selection: Option<ShardId>,
 ) -> Result<Response<PointsOperationResponse>, Status> {
     let SyncPoints {
@@ -123,8 +179,16 @@ pub async fn sync(
         from_id: from_id.map(|x| x.try_into()).transpose()?,
         to_id: to_id.map(|x| x.try_into()).transpose()?,
     };
-    let collection_operation =
+    let mut collection_operation =
         CollectionUpdateOperations::PointOperation(PointOperations::SyncPoints(operation));
+    if !point_clock_tags.is_empty() {
+        let mut clocked = ClockedOperation::new(ClockTag::default(), collection_operation);
+        clocked.point_clock_tags = point_clock_tags
+            .into_iter()
+            .map(point_clock_tag_from_proto)
+            .collect::<Result<_, _>>()?;
+        collection_operation = CollectionUpdateOperations::ClockedOperation(clocked);
+    }
     let result = toc
         .update(
             &collection_name,
@@ -150,6 +214,7 @@ pub async fn delete(
         wait,
         points,
         ordering,
+        idempotency_token,
     } = delete_points;
 
     let points_selector = match points {
@@ -162,6 +227,7 @@ pub async fn delete(
         toc,
         &collection_name,
         points_selector,
+        idempotency_token,
         shard_selection,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
@@ -183,29 +249,19 @@ pub async fn update_vectors(
         wait,
         points,
         ordering,
+        idempotency_token,
     } = update_point_vectors;
 
-    // Build list of operation points
-    let mut op_points = Vec::with_capacity(points.len());
-    for point in points {
-        let id = match point.id {
-            Some(id) => id.try_into()?,
-            None => return Err(Status::invalid_argument("id is expected")),
-        };
-        let vector = match point.vectors {
-            Some(vectors) => vectors.try_into()?,
-            None => return Err(Status::invalid_argument("vectors is expected")),
-        };
-        op_points.push(PointVectors { id, vector });
-    }
-
-    let operation = UpdateVectors { points: op_points };
+    let operation = UpdateVectors {
+        points: point_vectors_from_proto(points)?,
+    };
 
     let timing = Instant::now();
     let result = do_update_vectors(
         toc,
         &collection_name,
         operation,
+        idempotency_token,
         shard_selection,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
@@ -228,6 +284,7 @@ pub async fn delete_vectors(
         points_selector,
         vectors,
         ordering,
+        idempotency_token,
     } = delete_point_vectors;
 
     let (points, filter) = extract_points_selector(points_selector)?;
@@ -247,6 +304,7 @@ pub async fn delete_vectors(
         toc,
         &collection_name,
         operation,
+        idempotency_token,
         shard_selection,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
@@ -269,6 +327,8 @@ pub async fn set_payload(
         payload,
         points_selector,
         ordering,
+        deep_merge,
+        idempotency_token,
     } = set_payload_points;
 
     let (points, filter) = extract_points_selector(points_selector)?;
@@ -283,6 +343,8 @@ pub async fn set_payload(
         toc,
         &collection_name,
         operation,
+        deep_merge.unwrap_or(false),
+        idempotency_token,
         shard_selection,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
@@ -305,6 +367,8 @@ pub async fn overwrite_payload(
         payload,
         points_selector,
         ordering,
+        deep_merge: _,
+        idempotency_token,
     } = set_payload_points;
 
     let (points, filter) = extract_points_selector(points_selector)?;
@@ -319,6 +383,7 @@ pub async fn overwrite_payload(
         toc,
         &collection_name,
         operation,
+        idempotency_token,
         shard_selection,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
@@ -341,6 +406,7 @@ pub async fn delete_payload(
         keys,
         points_selector,
         ordering,
+        idempotency_token,
     } = delete_payload_points;
 
     let (points, filter) = extract_points_selector(points_selector)?;
@@ -355,6 +421,7 @@ pub async fn delete_payload(
         toc,
         &collection_name,
         operation,
+        idempotency_token,
         shard_selection,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
@@ -376,6 +443,7 @@ pub async fn clear_payload(
         wait,
         points,
         ordering,
+        idempotency_token,
     } = clear_payload_points;
 
     let points_selector = match points {
@@ -388,6 +456,7 @@ pub async fn clear_payload(
         toc,
         &collection_name,
         points_selector,
+        idempotency_token,
         shard_selection,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
@@ -404,85 +473,109 @@ pub async fn update_batch(
     update_batch_points: UpdateBatchPoints,
     shard_selection: Option<ShardId>,
 ) -> Result<Response<UpdateBatchResponse>, Status> {
+    let operations_count = update_batch_points.operations.len();
+
+    let timing = Instant::now();
+    let result = apply_update_batch(toc, update_batch_points, shard_selection, None).await?;
+
+    // Operations are applied as a single update, so all of them share its result
+    let result: api::grpc::qdrant::UpdateResult = result.into();
+    Ok(Response::new(UpdateBatchResponse {
+        result: vec![result; operations_count],
+        time: timing.elapsed().as_secs_f64(),
+    }))
+}
+
+pub async fn update_batch_internal(
+    toc: &TableOfContent,
+    update_batch_points: UpdateBatchPoints,
+    clock_tag: Option<api::grpc::qdrant::ClockTag>,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let timing = Instant::now();
+    let result = apply_update_batch(
+        toc,
+        update_batch_points,
+        shard_selection,
+        clock_tag.map(ClockTag::from),
+    )
+    .await?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
+async fn apply_update_batch(
+    toc: &TableOfContent,
+    update_batch_points: UpdateBatchPoints,
+    shard_selection: Option<ShardId>,
+    clock_tag: Option<ClockTag>,
+) -> Result<UpdateResult, Status> {
     let UpdateBatchPoints {
         collection_name,
         wait,
         operations,
         ordering,
+        idempotency_token,
     } = update_batch_points;
 
-    let timing = Instant::now();
-    let mut results = Vec::with_capacity(operations.len());
+    let mut collection_operations = Vec::with_capacity(operations.len());
     for op in operations {
         let operation = op
             .operation
             .ok_or(Status::invalid_argument("Operation is missing"))?;
-        let collection_name = collection_name.clone();
-        let ordering = ordering.clone();
-        let result = match operation {
+        match operation {
             points_update_operation::Operation::Upsert(points) => {
-                upsert(
-                    toc,
-                    UpsertPoints {
-                        collection_name,
-                        points: points.points,
-                        wait,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
+                let points = points
+                    .points
+                    .into_iter()
+                    .map(|point| point.try_into())
+                    .collect::<Result<_, _>>()?;
+                collection_operations.push(CollectionUpdateOperations::PointOperation(
+                    PointOperations::UpsertPoints(PointInsertOperations::PointsList(points)),
+                ));
             }
             points_update_operation::Operation::Delete(points) => {
-                delete(
-                    toc,
-                    DeletePoints {
-                        collection_name,
-                        wait,
-                        points: Some(points),
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
+                collection_operations.push(delete_points_operation(points.try_into()?));
             }
             points_update_operation::Operation::SetPayload(
                 points_update_operation::SetPayload {
                     payload,
                     points_selector,
+                    deep_merge,
                 },
             ) => {
-                set_payload(
-                    toc,
-                    SetPayloadPoints {
-                        collection_name,
-                        wait,
-                        payload,
-                        points_selector,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
+                let (points, filter) = extract_points_selector(points_selector)?;
+                let operation = collection::operations::payload_ops::SetPayload {
+                    payload: proto_to_payloads(payload)?,
+                    points,
+                    filter,
+                };
+                let payload_operation = if deep_merge.unwrap_or(false) {
+                    PayloadOps::MergePayload(operation)
+                } else {
+                    PayloadOps::SetPayload(operation)
+                };
+                collection_operations.push(CollectionUpdateOperations::PayloadOperation(
+                    payload_operation,
+                ));
             }
             points_update_operation::Operation::OverwritePayload(
                 points_update_operation::SetPayload {
                     payload,
                     points_selector,
+                    deep_merge: _,
                 },
             ) => {
-                overwrite_payload(
-                    toc,
-                    SetPayloadPoints {
-                        collection_name,
-                        wait,
-                        payload,
-                        points_selector,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
+                let (points, filter) = extract_points_selector(points_selector)?;
+                let operation = collection::operations::payload_ops::SetPayload {
+                    payload: proto_to_payloads(payload)?,
+                    points,
+                    filter,
+                };
+                collection_operations.push(CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::OverwritePayload(operation),
+                ));
             }
             points_update_operation::Operation::DeletePayload(
                 points_update_operation::DeletePayload {
@@ -490,46 +583,28 @@ pub async fn update_batch(
                     points_selector,
                 },
             ) => {
-                delete_payload(
-                    toc,
-                    DeletePayloadPoints {
-                        collection_name,
-                        wait,
-                        keys,
-                        points_selector,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
+                let (points, filter) = extract_points_selector(points_selector)?;
+                let operation = DeletePayload {
+                    keys,
+                    points,
+                    filter,
+                };
+                collection_operations.push(CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::DeletePayload(operation),
+                ));
             }
             points_update_operation::Operation::ClearPayload(points) => {
-                clear_payload(
-                    toc,
-                    ClearPayloadPoints {
-                        collection_name,
-                        wait,
-                        points: Some(points),
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
+                collection_operations.push(clear_payload_operation(points.try_into()?));
             }
             points_update_operation::Operation::UpdateVectors(
                 points_update_operation::UpdateVectors { points },
             ) => {
-                update_vectors(
-                    toc,
-                    UpdatePointVectors {
-                        collection_name,
-                        wait,
-                        points,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
+                let operation = UpdateVectors {
+                    points: point_vectors_from_proto(points)?,
+                };
+                collection_operations.push(CollectionUpdateOperations::VectorOperation(
+                    VectorOperations::UpdateVectors(operation),
+                ));
             }
             points_update_operation::Operation::DeleteVectors(
                 points_update_operation::DeleteVectors {
@@ -537,29 +612,70 @@ pub async fn update_batch(
                     vectors,
                 },
             ) => {
-                delete_vectors(
-                    toc,
-                    DeletePointVectors {
-                        collection_name,
-                        wait,
-                        points_selector,
-                        vectors,
-                        ordering,
-                    },
-                    shard_selection,
-                )
-                .await
+                let (points, filter) = extract_points_selector(points_selector)?;
+                let vector_names = match vectors {
+                    Some(vectors) => vectors.names,
+                    None => return Err(Status::invalid_argument("vectors is expected")),
+                };
+                let operation = DeleteVectors {
+                    points,
+                    filter,
+                    vector: vector_names.into_iter().collect(),
+                };
+                collection_operations.extend(delete_vectors_operations(operation));
+            }
+            points_update_operation::Operation::MutatePayload(
+                points_update_operation::MutatePayload { mutations, filter },
+            ) => {
+                let filter =
+                    filter.ok_or_else(|| Status::invalid_argument("filter is expected"))?;
+                let operation = MutatePayload {
+                    mutations: mutations
+                        .into_iter()
+                        .map(TryInto::try_into)
+                        .collect::<Result<_, _>>()?,
+                    filter: filter.try_into()?,
+                };
+                collection_operations.push(CollectionUpdateOperations::PayloadOperation(
+                    PayloadOps::MutatePayload(operation),
+                ));
             }
-        }?;
-        results.push(result);
+        }
     }
-    Ok(Response::new(UpdateBatchResponse {
-        result: results
-            .into_iter()
-            .map(|response| response.into_inner().result.unwrap())
-            .collect(),
-        time: timing.elapsed().as_secs_f64(),
-    }))
+
+    let wait = wait.unwrap_or(false);
+    let ordering = write_ordering_from_proto(ordering)?;
+    match clock_tag {
+        // Batch from another replica keeps the clock, assigned by the replica set
+        Some(clock_tag) => {
+            let operation = ClockedOperation::new(
+                clock_tag,
+                CollectionUpdateOperations::BatchOperation(collection_operations),
+            );
+            toc.update(
+                &collection_name,
+                CollectionUpdateOperations::ClockedOperation(operation)
+                    .with_idempotency_token(idempotency_token),
+                shard_selection,
+                wait,
+                ordering,
+            )
+            .await
+        }
+        None => {
+            do_update_batch(
+                toc,
+                &collection_name,
+                collection_operations,
+                idempotency_token,
+                shard_selection,
+                wait,
+                ordering,
+            )
+            .await
+        }
+    }
+    .map_err(error_to_status)
 }
 
 pub async fn create_field_index(
@@ -596,6 +712,81 @@ pub async fn create_field_index(
                 ))
             }
         },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::CompositeIndexParams(composite_index_params)),
+            }),
+        ) => match v {
+            FieldType::Composite => Some(PayloadFieldSchema::FieldParams(
+                PayloadSchemaParams::Composite(composite_index_params.try_into()?),
+            )),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::KeywordIndexParams(keyword_index_params)),
+            }),
+        ) => match v {
+            FieldType::Keyword => Some(PayloadFieldSchema::FieldParams(
+                PayloadSchemaParams::Keyword(keyword_index_params.into()),
+            )),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::IntegerIndexParams(integer_index_params)),
+            }),
+        ) => match v {
+            FieldType::Integer => Some(PayloadFieldSchema::FieldParams(
+                PayloadSchemaParams::Integer(integer_index_params.into()),
+            )),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::FloatIndexParams(float_index_params)),
+            }),
+        ) => match v {
+            FieldType::Float => Some(PayloadFieldSchema::FieldParams(PayloadSchemaParams::Float(
+                float_index_params.into(),
+            ))),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
+        (
+            Some(v),
+            Some(PayloadIndexParams {
+                index_params: Some(IndexParams::GeoIndexParams(geo_index_params)),
+            }),
+        ) => match v {
+            FieldType::Geo => Some(PayloadFieldSchema::FieldParams(PayloadSchemaParams::Geo(
+                geo_index_params.into(),
+            ))),
+            _ => {
+                return Err(Status::invalid_argument(
+                    "field_type and field_index_params do not match",
+                ))
+            }
+        },
         (Some(v), None | Some(PayloadIndexParams { index_params: None })) => match v {
             FieldType::Keyword => Some(PayloadSchemaType::Keyword.into()),
             FieldType::Integer => Some(PayloadSchemaType::Integer.into()),
@@ -603,6 +794,13 @@ pub async fn create_field_index(
             FieldType::Geo => Some(PayloadSchemaType::Geo.into()),
             FieldType::Text => Some(PayloadSchemaType::Text.into()),
             FieldType::Bool => Some(PayloadSchemaType::Bool.into()),
+            FieldType::Datetime => Some(PayloadSchemaType::Datetime.into()),
+            FieldType::Uuid => Some(PayloadSchemaType::Uuid.into()),
+            FieldType::Composite => {
+                return Err(Status::invalid_argument(
+                    "composite index requires field_index_params",
+                ))
+            }
         },
         (None, Some(_)) => return Err(Status::invalid_argument("field type is missing")),
         (None, None) => None,
@@ -674,13 +872,20 @@ pub async fn search(
         vector_name,
         with_vectors,
         read_consistency,
+        sparse_indices,
+        text,
+        timeout,
+        with_usage,
+        explain,
     } = search_points;
 
+    let vector = match sparse_indices {
+        Some(indices) => SparseVector::new(indices.data, vector).into(),
+        None => vector.into(),
+    };
+
     let search_request = SearchRequest {
-        vector: match vector_name {
-            None => vector.into(),
-            Some(name) => NamedVector { name, vector }.into(),
-        },
+        vector: NamedVectorStruct::new_from_vector(vector, vector_name).into(),
         filter: filter.map(|f| f.try_into()).transpose()?,
         params: params.map(|p| p.into()),
         limit: limit as usize,
@@ -692,20 +897,32 @@ pub async fn search(
                 .unwrap_or_default(),
         ),
         score_threshold,
+        text: text.map(|text| text.into()),
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+    let explain = explain.map(explain_mode_from_i32).transpose()?;
 
     let timing = Instant::now();
-    let scored_points = do_search_points(
-        toc,
-        &collection_name,
-        search_request,
-        read_consistency,
-        shard_selection,
+    let ((scored_points, usage), explain) = collect_search_explain(
+        explain,
+        collect_hardware_usage(
+            with_usage.unwrap_or_default(),
+            with_read_timeout(
+                timeout.map(Duration::from_secs),
+                "Search",
+                do_search_points(
+                    toc,
+                    &collection_name,
+                    search_request,
+                    read_consistency,
+                    shard_selection,
+                ),
+            ),
+        ),
     )
-    .await
-    .map_err(error_to_status)?;
+    .await;
+    let scored_points = scored_points.map_err(error_to_status)?;
 
     let response = SearchResponse {
         result: scored_points
@@ -713,6 +930,12 @@ pub async fn search(
             .map(|point| point.into())
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        usage: usage.map(|usage| usage.into()),
+        explain: explain
+            .unwrap_or_default()
+            .into_iter()
+            .map(|explain| explain.into())
+            .collect(),
     };
 
     Ok(Response::new(response))
@@ -726,6 +949,9 @@ pub async fn search_batch(
     search_points: Vec<SearchPoints>,
     read_consistency: Option<ReadConsistencyGrpc>,
     shard_selection: Option<ShardId>,
+    timeout: Option<Duration>,
+    with_usage: bool,
+    explain: Option<i32>,
 ) -> Result<Response<SearchBatchResponse>, Status> {
     let searches: Result<Vec<_>, Status> =
         search_points.into_iter().map(TryInto::try_into).collect();
@@ -735,17 +961,28 @@ pub async fn search_batch(
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+    let explain = explain.map(explain_mode_from_i32).transpose()?;
 
     let timing = Instant::now();
-    let scored_points = do_search_batch_points(
-        toc,
-        &collection_name,
-        search_requests,
-        read_consistency,
-        shard_selection,
+    let ((scored_points, usage), explain) = collect_search_explain(
+        explain,
+        collect_hardware_usage(
+            with_usage,
+            with_read_timeout(
+                timeout,
+                "Search",
+                do_search_batch_points(
+                    toc,
+                    &collection_name,
+                    search_requests,
+                    read_consistency,
+                    shard_selection,
+                ),
+            ),
+        ),
     )
-    .await
-    .map_err(error_to_status)?;
+    .await;
+    let scored_points = scored_points.map_err(error_to_status)?;
 
     let response = SearchBatchResponse {
         result: scored_points
@@ -755,6 +992,12 @@ pub async fn search_batch(
             })
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        usage: usage.map(|usage| usage.into()),
+        explain: explain
+            .unwrap_or_default()
+            .into_iter()
+            .map(|explain| explain.into())
+            .collect(),
     };
 
     Ok(Response::new(response))
@@ -768,6 +1011,9 @@ pub async fn core_search_batch(
     search_points: Vec<CoreSearchPoints>,
     read_consistency: Option<ReadConsistencyGrpc>,
     shard_selection: Option<ShardId>,
+    timeout: Option<Duration>,
+    with_usage: bool,
+    explain: Option<i32>,
 ) -> Result<Response<SearchBatchResponse>, Status> {
     let searches: Result<Vec<_>, Status> =
         search_points.into_iter().map(TryInto::try_into).collect();
@@ -777,17 +1023,28 @@ pub async fn core_search_batch(
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+    let explain = explain.map(explain_mode_from_i32).transpose()?;
 
     let timing = Instant::now();
-    let scored_points = do_core_search_batch_points(
-        toc,
-        &collection_name,
-        search_requests,
-        read_consistency,
-        shard_selection,
+    let ((scored_points, usage), explain) = collect_search_explain(
+        explain,
+        collect_hardware_usage(
+            with_usage,
+            with_read_timeout(
+                timeout,
+                "Search",
+                do_core_search_batch_points(
+                    toc,
+                    &collection_name,
+                    search_requests,
+                    read_consistency,
+                    shard_selection,
+                ),
+            ),
+        ),
     )
-    .await
-    .map_err(error_to_status)?;
+    .await;
+    let scored_points = scored_points.map_err(error_to_status)?;
 
     let response = SearchBatchResponse {
         result: scored_points
@@ -797,6 +1054,12 @@ pub async fn core_search_batch(
             })
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        usage: usage.map(|usage| usage.into()),
+        explain: explain
+            .unwrap_or_default()
+            .into_iter()
+            .map(|explain| explain.into())
+            .collect(),
     };
 
     Ok(Response::new(response))
@@ -857,6 +1120,7 @@ pub async fn recommend(
         with_vectors,
         lookup_from,
         read_consistency,
+        timeout,
     } = recommend_points;
 
     let positive_ids = positive
@@ -901,10 +1165,13 @@ pub async fn recommend(
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
     let timing = Instant::now();
-    let recommended_points = toc
-        .recommend(&collection_name, request, read_consistency)
-        .await
-        .map_err(error_to_status)?;
+    let recommended_points = with_read_timeout(
+        timeout.map(Duration::from_secs),
+        "Recommend",
+        toc.recommend(&collection_name, request, read_consistency),
+    )
+    .await
+    .map_err(error_to_status)?;
 
     let response = RecommendResponse {
         result: recommended_points
@@ -917,11 +1184,45 @@ pub async fn recommend(
     Ok(Response::new(response))
 }
 
+pub async fn query(
+    toc: &TableOfContent,
+    query_points: QueryPoints,
+) -> Result<Response<QueryResponse>, Status> {
+    let collection_name = query_points.collection_name.clone();
+    let read_consistency = query_points.read_consistency.clone();
+    let timeout = query_points.timeout;
+    let request = QueryRequest::try_from(query_points)?;
+    // Checks the parts of the query, which are not validated by the gRPC messages
+    validate(&request)?;
+
+    let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
+
+    let timing = Instant::now();
+    let scored_points = with_read_timeout(
+        timeout.map(Duration::from_secs),
+        "Query",
+        do_query_points(toc, &collection_name, request, read_consistency, None),
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = QueryResponse {
+        result: scored_points
+            .into_iter()
+            .map(|point| point.into())
+            .collect(),
+        time: timing.elapsed().as_secs_f64(),
+    };
+
+    Ok(Response::new(response))
+}
+
 pub async fn recommend_batch(
     toc: &TableOfContent,
     collection_name: String,
     recommend_points: Vec<RecommendPoints>,
     read_consistency: Option<ReadConsistencyGrpc>,
+    timeout: Option<Duration>,
 ) -> Result<Response<RecommendBatchResponse>, Status> {
     let searches: Result<Vec<_>, Status> = recommend_points
         .into_iter()
@@ -934,10 +1235,13 @@ pub async fn recommend_batch(
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
     let timing = Instant::now();
-    let scored_points = toc
-        .recommend_batch(&collection_name, recommend_batch, read_consistency)
-        .await
-        .map_err(error_to_status)?;
+    let scored_points = with_read_timeout(
+        timeout,
+        "Recommend",
+        toc.recommend_batch(&collection_name, recommend_batch, read_consistency),
+    )
+    .await
+    .map_err(error_to_status)?;
 
     let response = RecommendBatchResponse {
         result: scored_points
@@ -996,6 +1300,10 @@ pub async fn scroll(
         with_payload,
         with_vectors,
         read_consistency,
+        order_by,
+        stable,
+        cursor,
+        timeout,
     } = scroll_points;
 
     let scroll_request = ScrollRequest {
@@ -1006,17 +1314,24 @@ pub async fn scroll(
         with_vector: with_vectors
             .map(|selector| selector.into())
             .unwrap_or_default(),
+        order_by: order_by.map(|order_by| order_by.try_into()).transpose()?,
+        stable,
+        cursor,
     };
 
     let read_consistency = ReadConsistency::try_from_optional(read_consistency)?;
 
     let timing = Instant::now();
-    let scrolled_points = do_scroll_points(
-        toc,
-        &collection_name,
-        scroll_request,
-        read_consistency,
-        shard_selection,
+    let scrolled_points = with_read_timeout(
+        timeout.map(Duration::from_secs),
+        "Scroll",
+        do_scroll_points(
+            toc,
+            &collection_name,
+            scroll_request,
+            read_consistency,
+            shard_selection,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -1029,11 +1344,63 @@ pub async fn scroll(
             .map(|point| point.into())
             .collect(),
         time: timing.elapsed().as_secs_f64(),
+        next_page_cursor: scrolled_points.next_page_cursor,
     };
 
     Ok(Response::new(response))
 }
 
+/// Scroll all points, which match the filter, in chunks of `limit` points.
+///
+/// The next chunk is only read once the previous one is taken by the client,
+/// so at most one chunk is kept in memory.
+pub fn scroll_stream(
+    toc: Arc<TableOfContent>,
+    scroll_points: ScrollPoints,
+    shard_selection: Option<ShardId>,
+) -> BoxStream<'static, Result<ScrollResponse, Status>> {
+    scroll_pages(scroll_points, move |scroll_points| {
+        let toc = toc.clone();
+        async move {
+            scroll(&toc, scroll_points, shard_selection)
+                .await
+                .map(Response::into_inner)
+        }
+    })
+    .boxed()
+}
+
+/// Stream of the pages, read one by one, each from the offset or cursor of the previous page
+fn scroll_pages<F, Fut>(
+    scroll_points: ScrollPoints,
+    read_page: F,
+) -> impl Stream<Item = Result<ScrollResponse, Status>>
+where
+    F: Fn(ScrollPoints) -> Fut,
+    Fut: Future<Output = Result<ScrollResponse, Status>>,
+{
+    stream::unfold(Some(scroll_points), move |scroll_points| {
+        let page =
+            scroll_points.map(|scroll_points| (read_page(scroll_points.clone()), scroll_points));
+        async move {
+            let (page, scroll_points) = page?;
+            let response = match page.await {
+                Ok(response) => response,
+                // Stream ends with the first error
+                Err(status) => return Some((Err(status), None)),
+            };
+            let has_more =
+                response.next_page_offset.is_some() || response.next_page_cursor.is_some();
+            let next_scroll_points = has_more.then(|| ScrollPoints {
+                offset: response.next_page_offset.clone(),
+                cursor: response.next_page_cursor.clone(),
+                ..scroll_points
+            });
+            Some((Ok(response), next_scroll_points))
+        }
+    })
+}
+
 pub async fn count(
     toc: &TableOfContent,
     count_points: CountPoints,
@@ -1043,6 +1410,7 @@ pub async fn count(
         collection_name,
         filter,
         exact,
+        timeout,
     } = count_points;
 
     let count_request = collection::operations::types::CountRequest {
@@ -1051,9 +1419,13 @@ pub async fn count(
     };
 
     let timing = Instant::now();
-    let count_result = do_count_points(toc, &collection_name, count_request, shard_selection)
-        .await
-        .map_err(error_to_status)?;
+    let count_result = with_read_timeout(
+        timeout.map(Duration::from_secs),
+        "Count",
+        do_count_points(toc, &collection_name, count_request, shard_selection),
+    )
+    .await
+    .map_err(error_to_status)?;
 
     let response = CountResponse {
         result: Some(count_result.into()),
@@ -1074,6 +1446,7 @@ pub async fn get(
         with_payload,
         with_vectors,
         read_consistency,
+        timeout,
     } = get_points;
 
     let point_request = PointRequest {
@@ -1091,12 +1464,16 @@ pub async fn get(
 
     let timing = Instant::now();
 
-    let records = do_get_points(
-        toc,
-        &collection_name,
-        point_request,
-        read_consistency,
-        shard_selection,
+    let records = with_read_timeout(
+        timeout.map(Duration::from_secs),
+        "Retrieve",
+        do_get_points(
+            toc,
+            &collection_name,
+            point_request,
+            read_consistency,
+            shard_selection,
+        ),
     )
     .await
     .map_err(error_to_status)?;
@@ -1108,3 +1485,145 @@ pub async fn get(
 
     Ok(Response::new(response))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    use api::grpc::qdrant::{PointId, RetrievedPoint};
+    use segment::types::PointIdType;
+
+    use super::*;
+
+    const POINTS: u64 = 5;
+
+    fn point_id(id: u64) -> PointId {
+        PointId::from(PointIdType::from(id))
+    }
+
+    /// Page of the points `0..POINTS` from the offset of the request
+    fn read_page(scroll_points: ScrollPoints) -> Result<ScrollResponse, Status> {
+        let offset = match scroll_points.offset {
+            Some(offset) => (0..POINTS)
+                .find(|id| point_id(*id) == offset)
+                .ok_or_else(|| Status::invalid_argument("unknown offset"))?,
+            None => 0,
+        };
+        let end = POINTS.min(offset + u64::from(scroll_points.limit.unwrap()));
+        Ok(ScrollResponse {
+            next_page_offset: (end < POINTS).then(|| point_id(end)),
+            result: (offset..end)
+                .map(|id| RetrievedPoint {
+                    id: Some(point_id(id)),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    fn ids(response: &ScrollResponse) -> Vec<PointId> {
+        response
+            .result
+            .iter()
+            .map(|point| point.id.clone().unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_scroll_stream_chunks() {
+        let pages_read = AtomicUsize::new(0);
+        let scroll_points = ScrollPoints {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let mut stream = Box::pin(scroll_pages(scroll_points, |scroll_points| {
+            pages_read.fetch_add(1, Ordering::Relaxed);
+            async move { read_page(scroll_points) }
+        }));
+
+        // Pages are read only once the previous one is taken
+        assert_eq!(pages_read.load(Ordering::Relaxed), 0);
+        let first = stream.next().await.unwrap().unwrap();
+        assert_eq!(ids(&first), [point_id(0), point_id(1)]);
+        assert_eq!(pages_read.load(Ordering::Relaxed), 1);
+
+        let rest: Vec<_> = stream.map(Result::unwrap).collect().await;
+        assert_eq!(
+            rest.iter().map(ids).collect::<Vec<_>>(),
+            [vec![point_id(2), point_id(3)], vec![point_id(4)]]
+        );
+        // Stream ends after the last page, without reading past it
+        assert_eq!(pages_read.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_scroll_stream_exact_pages() {
+        let scroll_points = ScrollPoints {
+            limit: Some(POINTS as u32),
+            ..Default::default()
+        };
+        let pages: Vec<_> = scroll_pages(scroll_points, |scroll_points| async move {
+            read_page(scroll_points)
+        })
+        .collect()
+        .await;
+        assert_eq!(pages.len(), 1);
+        assert_eq!(ids(pages[0].as_ref().unwrap()).len(), POINTS as usize);
+    }
+
+    #[tokio::test]
+    async fn test_scroll_stream_ends_on_error() {
+        let scroll_points = ScrollPoints {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let pages: Vec<_> = scroll_pages(scroll_points, |scroll_points| async move {
+            match scroll_points.offset {
+                None => read_page(scroll_points),
+                Some(_) => Err(Status::unavailable("shard is not available")),
+            }
+        })
+        .collect()
+        .await;
+
+        assert_eq!(pages.len(), 2);
+        assert!(pages[0].is_ok());
+        assert_eq!(
+            pages[1].as_ref().unwrap_err().code(),
+            tonic::Code::Unavailable
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scroll_stream_follows_cursor() {
+        let scroll_points = ScrollPoints {
+            limit: Some(2),
+            ..Default::default()
+        };
+        let requests = Mutex::new(Vec::new());
+        let pages: Vec<_> = scroll_pages(scroll_points, |scroll_points| {
+            requests.lock().unwrap().push(scroll_points.cursor.clone());
+            async move {
+                let next_page_cursor = match scroll_points.cursor.as_deref() {
+                    None => Some("1".to_string()),
+                    Some("1") => Some("2".to_string()),
+                    _ => None,
+                };
+                Ok(ScrollResponse {
+                    next_page_cursor,
+                    ..Default::default()
+                })
+            }
+        })
+        .collect()
+        .await;
+
+        assert_eq!(pages.len(), 3);
+        assert_eq!(
+            *requests.lock().unwrap(),
+            [None, Some("1".to_string()), Some("2".to_string())]
+        );
+    }
+}
@@ -8,7 +8,7 @@ use api::grpc::qdrant::{
     GetResponse, PointsOperationResponse, RecommendPointsInternal, RecommendResponse,
     ScrollPointsInternal, ScrollResponse, SearchBatchPointsInternal, SearchBatchResponse,
     SearchPointsInternal, SearchResponse, SetPayloadPointsInternal, SyncPointsInternal,
-    UpdateVectorsInternal, UpsertPointsInternal,
+    UpdateBatchInternal, UpdateVectorsInternal, UpsertPointsInternal,
 };
 use storage::content_manager::toc::TableOfContent;
 use tonic::{Request, Response, Status};
@@ -18,7 +18,7 @@ use super::validate_and_log;
 use crate::tonic::api::points_common::{
     clear_payload, count, create_field_index, delete, delete_field_index, delete_payload,
     delete_vectors, get, overwrite_payload, recommend, scroll, search, search_batch, set_payload,
-    sync, update_vectors, upsert,
+    sync, update_batch_internal, update_vectors, upsert,
 };
 
 /// This API is intended for P2P communication within a distributed deployment.
@@ -144,6 +144,23 @@ impl PointsInternal for PointsInternalService {
         clear_payload(self.toc.as_ref(), clear_payload_points, shard_id).await
     }
 
+    async fn update_batch(
+        &self,
+        request: Request<UpdateBatchInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let UpdateBatchInternal {
+            update_batch_points,
+            shard_id,
+            clock_tag,
+        } = request.into_inner();
+
+        let update_batch_points = update_batch_points
+            .ok_or_else(|| Status::invalid_argument("UpdateBatchPoints is missing"))?;
+
+        update_batch_internal(self.toc.as_ref(), update_batch_points, clock_tag, shard_id).await
+    }
+
     async fn create_field_index(
         &self,
         request: Request<CreateFieldIndexCollectionInternal>,
@@ -205,6 +222,8 @@ impl PointsInternal for PointsInternalService {
             collection_name,
             search_points,
             shard_id,
+            with_usage,
+            explain,
         } = request.into_inner();
 
         // Individual `read_consistency` values are ignored by `search_batch`...
@@ -219,6 +238,9 @@ impl PointsInternal for PointsInternalService {
             search_points,
             None, // *Has* to be `None`!
             shard_id,
+            None,
+            with_usage.unwrap_or_default(),
+            explain,
         )
         .await
     }
@@ -234,6 +256,8 @@ impl PointsInternal for PointsInternalService {
             collection_name,
             search_points,
             shard_id,
+            with_usage,
+            explain,
         } = request.into_inner();
 
         // Individual `read_consistency` values are ignored by `core_search_batch`...
@@ -248,6 +272,9 @@ impl PointsInternal for PointsInternalService {
             search_points,
             None, // *Has* to be `None`!
             shard_id,
+            None,
+            with_usage.unwrap_or_default(),
+            explain,
         )
         .await
     }
@@ -330,10 +357,11 @@ impl PointsInternal for PointsInternalService {
         let SyncPointsInternal {
             sync_points,
             shard_id,
+            point_clock_tags,
         } = request.into_inner();
         let sync_points =
             sync_points.ok_or_else(|| Status::invalid_argument("SyncPoints is missing"))?;
-        sync(self.toc.as_ref(), sync_points, shard_id).await
+        sync(self.toc.as_ref(), sync_points, point_clock_tags, shard_id).await
     }
 
     async fn overwrite_payload(
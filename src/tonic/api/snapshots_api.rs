@@ -15,7 +15,7 @@ use storage::content_manager::snapshots::{
 use storage::dispatcher::Dispatcher;
 use tonic::{async_trait, Request, Response, Status};
 
-use super::validate;
+use super::{check_collection_access, validate};
 use crate::common::collections::{do_create_snapshot, do_list_snapshots};
 
 pub struct SnapshotsService {
@@ -35,10 +35,14 @@ impl Snapshots for SnapshotsService {
         request: Request<CreateSnapshotRequest>,
     ) -> Result<Response<CreateSnapshotResponse>, Status> {
         validate(request.get_ref())?;
-        let collection_name = request.into_inner().collection_name;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
+        let CreateSnapshotRequest {
+            collection_name,
+            base,
+        } = request.into_inner();
         let timing = Instant::now();
         let dispatcher = self.dispatcher.clone();
-        let response = do_create_snapshot(&dispatcher, &collection_name, true)
+        let response = do_create_snapshot(&dispatcher, &collection_name, base.as_deref(), true)
             .await
             .map_err(error_to_status)?;
         Ok(Response::new(CreateSnapshotResponse {
@@ -52,6 +56,7 @@ impl Snapshots for SnapshotsService {
         request: Request<ListSnapshotsRequest>,
     ) -> Result<Response<ListSnapshotsResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         let collection_name = request.into_inner().collection_name;
 
         let timing = Instant::now();
@@ -69,6 +74,7 @@ impl Snapshots for SnapshotsService {
         request: Request<DeleteSnapshotRequest>,
     ) -> Result<Response<DeleteSnapshotResponse>, Status> {
         validate(request.get_ref())?;
+        check_collection_access(&request, self.dispatcher.toc()).await?;
         let DeleteSnapshotRequest {
             collection_name,
             snapshot_name,
@@ -1,6 +1,5 @@
 use std::task::{Context, Poll};
 
-use constant_time_eq::constant_time_eq;
 use futures_util::future::BoxFuture;
 use reqwest::header::HeaderValue;
 use reqwest::StatusCode;
@@ -9,15 +8,20 @@ use tonic::Code;
 use tower::Service;
 use tower_layer::Layer;
 
+use crate::common::auth::{
+    grpc_required_access, AccessLevel, Authenticator, CollectionAccessCheck, GrpcRequiredAccess,
+    RequiredAccess, GRPC_HEALTH_CHECK_METHOD,
+};
+
 #[derive(Clone)]
 pub struct ApiKeyMiddleware<T> {
     service: T,
-    api_key: String,
+    authenticator: Authenticator,
 }
 
 #[derive(Clone)]
 pub struct ApiKeyMiddlewareLayer {
-    api_key: String,
+    authenticator: Authenticator,
 }
 
 impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for ApiKeyMiddleware<S>
@@ -38,38 +42,81 @@ where
 
     fn call(
         &mut self,
-        request: tonic::codegen::http::Request<tonic::transport::Body>,
+        mut request: tonic::codegen::http::Request<tonic::transport::Body>,
     ) -> Self::Future {
-        if let Some(key) = request.headers().get("api-key") {
-            if let Ok(key) = key.to_str() {
-                if constant_time_eq(self.api_key.as_bytes(), key.as_bytes()) {
-                    let future = self.service.call(request);
+        let header = |name| {
+            request
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        let access = self
+            .authenticator
+            .authenticate(header("api-key"), header("authorization"));
 
-                    return Box::pin(async move {
-                        let response = future.await?;
-                        Ok(response)
-                    });
-                }
+        // Health checks pass without credentials, the health service checks the access to all
+        // services but the whole server. Without credentials no collection is accessible.
+        if request.uri().path() == GRPC_HEALTH_CHECK_METHOD {
+            let (access, client) = access
+                .map(|(access, client)| (access, Some(client)))
+                .unwrap_or_default();
+            if let Some(client) = client {
+                request.extensions_mut().insert(client);
             }
+            request.extensions_mut().insert(CollectionAccessCheck {
+                access,
+                level: AccessLevel::Read,
+            });
+            return Box::pin(self.service.call(request));
         }
 
+        let result = access.and_then(|(access, client)| {
+            // Clients are rate limited by their credentials only after authentication
+            request.extensions_mut().insert(client);
+            match grpc_required_access(request.uri().path()) {
+                GrpcRequiredAccess::Any => Ok(None),
+                GrpcRequiredAccess::Global(level) => {
+                    access.check(RequiredAccess::Global(level)).map(|()| None)
+                }
+                GrpcRequiredAccess::Collection(level) if access.allows_global(level) => Ok(None),
+                GrpcRequiredAccess::Collection(level) if access.allows_some_collection(level) => {
+                    Ok(Some(CollectionAccessCheck { access, level }))
+                }
+                GrpcRequiredAccess::Collection(level) => {
+                    Err(format!("No {level} access to any collection"))
+                }
+            }
+        });
+
+        let error = match result {
+            Ok(collection_access_check) => {
+                if let Some(collection_access_check) = collection_access_check {
+                    request.extensions_mut().insert(collection_access_check);
+                }
+                return Box::pin(self.service.call(request));
+            }
+            Err(error) => error,
+        };
+
         let mut response = Self::Response::new(BoxBody::default());
         *response.status_mut() = StatusCode::FORBIDDEN;
         response.headers_mut().append(
             "grpc-status",
             HeaderValue::from(Code::PermissionDenied as i32),
         );
-        response
-            .headers_mut()
-            .append("grpc-message", HeaderValue::from_static("Invalid api-key"));
+        response.headers_mut().append(
+            "grpc-message",
+            HeaderValue::from_str(&error)
+                .unwrap_or_else(|_| HeaderValue::from_static("Permission denied")),
+        );
 
         Box::pin(async move { Ok(response) })
     }
 }
 
 impl ApiKeyMiddlewareLayer {
-    pub fn new(api_key: String) -> Self {
-        Self { api_key }
+    pub fn new(authenticator: Authenticator) -> Self {
+        Self { authenticator }
     }
 }
 
@@ -79,7 +126,7 @@ impl<S> Layer<S> for ApiKeyMiddlewareLayer {
     fn layer(&self, service: S) -> Self::Service {
         ApiKeyMiddleware {
             service,
-            api_key: self.api_key.clone(),
+            authenticator: self.authenticator.clone(),
         }
     }
 }
@@ -1,20 +1,19 @@
 mod api;
 mod api_key;
 mod logging;
+mod rate_limit;
+mod tls;
 mod tonic_telemetry;
+mod trace_context;
 
 use std::io;
 use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
 use std::time::Duration;
 
-use ::api::grpc::grpc_health_v1::health_check_response::ServingStatus;
-use ::api::grpc::grpc_health_v1::health_server::{Health, HealthServer};
-use ::api::grpc::grpc_health_v1::{
-    HealthCheckRequest as ProtocolHealthCheckRequest,
-    HealthCheckResponse as ProtocolHealthCheckResponse,
-};
+use ::api::grpc::grpc_health_v1::health_server::HealthServer;
 use ::api::grpc::models::VersionInfo;
+use ::api::grpc::qdrant::cluster_server::ClusterServer;
 use ::api::grpc::qdrant::collections_internal_server::CollectionsInternalServer;
 use ::api::grpc::qdrant::collections_server::CollectionsServer;
 use ::api::grpc::qdrant::points_internal_server::PointsInternalServer;
@@ -23,8 +22,8 @@ use ::api::grpc::qdrant::qdrant_internal_server::{QdrantInternal, QdrantInternal
 use ::api::grpc::qdrant::qdrant_server::{Qdrant, QdrantServer};
 use ::api::grpc::qdrant::snapshots_server::SnapshotsServer;
 use ::api::grpc::qdrant::{
-    HealthCheckReply, HealthCheckRequest, HttpPortRequest, HttpPortResponse,
-    WaitOnConsensusCommitRequest, WaitOnConsensusCommitResponse,
+    ConsensusFeaturesRequest, ConsensusFeaturesResponse, HealthCheckReply, HealthCheckRequest,
+    HttpPortRequest, HttpPortResponse, WaitOnConsensusCommitRequest, WaitOnConsensusCommitResponse,
 };
 use ::api::grpc::QDRANT_DESCRIPTOR_SET;
 use storage::content_manager::consensus_manager::ConsensusStateRef;
@@ -33,14 +32,18 @@ use storage::dispatcher::Dispatcher;
 use tokio::runtime::Handle;
 use tokio::signal;
 use tonic::codec::CompressionEncoding;
-use tonic::transport::{Server, ServerTlsConfig};
+use tonic::transport::Server;
 use tonic::{Request, Response, Status};
 
-use crate::common::helpers;
+use crate::common::auth::Authenticator;
+use crate::common::rate_limiter::RateLimiter;
 use crate::common::telemetry_ops::requests_telemetry::TonicTelemetryCollector;
+use crate::common::{certificate_helpers, helpers};
 use crate::settings::Settings;
+use crate::tonic::api::cluster_api::ClusterService;
 use crate::tonic::api::collections_api::CollectionsService;
 use crate::tonic::api::collections_internal_api::CollectionsInternalService;
+use crate::tonic::api::health_api::HealthService;
 use crate::tonic::api::points_api::PointsService;
 use crate::tonic::api::points_internal_api::PointsInternalService;
 use crate::tonic::api::snapshots_api::SnapshotsService;
@@ -58,24 +61,6 @@ impl Qdrant for QdrantService {
     }
 }
 
-// Additional health check service that follows gRPC health check protocol as described in #2614
-#[derive(Default)]
-pub struct HealthService {}
-
-#[tonic::async_trait]
-impl Health for HealthService {
-    async fn check(
-        &self,
-        _request: Request<ProtocolHealthCheckRequest>,
-    ) -> Result<Response<ProtocolHealthCheckResponse>, Status> {
-        let response = ProtocolHealthCheckResponse {
-            status: ServingStatus::Serving as i32,
-        };
-
-        Ok(Response::new(response))
-    }
-}
-
 pub struct QdrantInternalService {
     /// HTTP port accessible from inside the cluster
     settings: Settings,
@@ -118,6 +103,15 @@ impl QdrantInternal for QdrantInternalService {
             .await;
         Ok(Response::new(WaitOnConsensusCommitResponse { ok }))
     }
+
+    async fn get_consensus_features(
+        &self,
+        _request: Request<ConsensusFeaturesRequest>,
+    ) -> Result<Response<ConsensusFeaturesResponse>, Status> {
+        Ok(Response::new(ConsensusFeaturesResponse {
+            collection_meta_batch: true,
+        }))
+    }
 }
 
 #[cfg(not(unix))]
@@ -140,6 +134,7 @@ async fn wait_stop_signal(for_what: &str) {
 pub fn init(
     dispatcher: Arc<Dispatcher>,
     telemetry_collector: Arc<parking_lot::Mutex<TonicTelemetryCollector>>,
+    rate_limiter: Option<Arc<RateLimiter>>,
     settings: Settings,
     grpc_port: u16,
     runtime: Handle,
@@ -149,10 +144,11 @@ pub fn init(
             SocketAddr::from((settings.service.host.parse::<IpAddr>().unwrap(), grpc_port));
 
         let qdrant_service = QdrantService::default();
-        let health_service = HealthService::default();
+        let health_service = HealthService::new(dispatcher.toc().clone(), rate_limiter.clone());
         let collections_service = CollectionsService::new(dispatcher.clone());
         let points_service = PointsService::new(dispatcher.toc().clone());
         let snapshot_service = SnapshotsService::new(dispatcher.clone());
+        let cluster_service = ClusterService::new(dispatcher.clone());
 
         // Only advertise the public services. By default, all services in QDRANT_DESCRIPTOR_SET
         // will be advertised, so explicitly list the services to be included.
@@ -161,6 +157,7 @@ pub fn init(
             .with_service_name("qdrant.Collections")
             .with_service_name("qdrant.Points")
             .with_service_name("qdrant.Snapshots")
+            .with_service_name("qdrant.Cluster")
             .with_service_name("qdrant.Qdrant")
             .with_service_name("grpc.health.v1.Health")
             .build()
@@ -168,35 +165,39 @@ pub fn init(
 
         log::info!("Qdrant gRPC listening on {}", grpc_port);
 
-        let mut server = Server::builder();
+        let trace_context_enabled = settings.opentelemetry.is_some();
 
-        if settings.service.enable_tls {
-            log::info!("TLS enabled for gRPC API (TTL not supported)");
+        let tls_server_config = if settings.service.enable_tls {
+            log::info!("TLS enabled for gRPC API");
 
-            let tls_server_config = helpers::load_tls_external_server_config(settings.tls()?)?;
-
-            server = server
-                .tls_config(tls_server_config)
-                .map_err(helpers::tonic_error_to_io_error)?;
+            let tls_server_config = certificate_helpers::grpc_tls_server_config(
+                settings.tls()?.clone(),
+                settings.service.verify_https_client_certificate,
+            )
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+            Some(tls_server_config)
         } else {
             log::info!("TLS disabled for gRPC API");
-        }
+            None
+        };
 
         // The stack of middleware that our service will be wrapped in
         let middleware_layer = tower::ServiceBuilder::new()
+            .option_layer(
+                trace_context_enabled.then(trace_context::TraceContextMiddlewareLayer::new),
+            )
             .layer(logging::LoggingMiddlewareLayer::new())
             .layer(tonic_telemetry::TonicTelemetryLayer::new(
                 telemetry_collector,
             ))
             .option_layer(
-                settings
-                    .service
-                    .api_key
-                    .map(api_key::ApiKeyMiddlewareLayer::new),
+                Authenticator::new(&settings.service)?.map(api_key::ApiKeyMiddlewareLayer::new),
             )
+            // Clients are known only after the api-key layer
+            .option_layer(rate_limiter.map(rate_limit::RateLimitMiddlewareLayer::new))
             .into_inner();
 
-        server
+        let router = Server::builder()
             .layer(middleware_layer)
             .add_service(reflection_service)
             .add_service(
@@ -224,16 +225,29 @@ pub fn init(
                     .max_decoding_message_size(usize::MAX),
             )
             .add_service(
-                HealthServer::new(health_service)
+                ClusterServer::new(cluster_service)
                     .send_compressed(CompressionEncoding::Gzip)
                     .accept_compressed(CompressionEncoding::Gzip)
                     .max_decoding_message_size(usize::MAX),
             )
-            .serve_with_shutdown(socket, async {
-                wait_stop_signal("gRPC service").await;
-            })
-            .await
-            .map_err(helpers::tonic_error_to_io_error)
+            .add_service(
+                HealthServer::new(health_service)
+                    .send_compressed(CompressionEncoding::Gzip)
+                    .accept_compressed(CompressionEncoding::Gzip)
+                    .max_decoding_message_size(usize::MAX),
+            );
+
+        let shutdown_signal = wait_stop_signal("gRPC service");
+        let result = match tls_server_config {
+            Some(tls_server_config) => {
+                let incoming = tls::tls_incoming(socket, tls_server_config).await?;
+                router
+                    .serve_with_incoming_shutdown(incoming, shutdown_signal)
+                    .await
+            }
+            None => router.serve_with_shutdown(socket, shutdown_signal).await,
+        };
+        result.map_err(helpers::tonic_error_to_io_error)
     })?;
 
     Ok(())
@@ -247,7 +261,7 @@ pub fn init_internal(
     settings: Settings,
     host: String,
     internal_grpc_port: u16,
-    tls_config: Option<ServerTlsConfig>,
+    tls_config: Option<rustls::ServerConfig>,
     to_consensus: tokio::sync::mpsc::Sender<crate::consensus::Message>,
     runtime: Handle,
 ) -> std::io::Result<()> {
@@ -255,82 +269,89 @@ pub fn init_internal(
 
     use crate::tonic::api::raft_api::RaftService;
 
-    runtime
-        .block_on(async {
-            let socket = SocketAddr::from((host.parse::<IpAddr>().unwrap(), internal_grpc_port));
-
-            let qdrant_service = QdrantService::default();
-            let qdrant_internal_service =
-                QdrantInternalService::new(settings, consensus_state.clone());
-            let collections_internal_service = CollectionsInternalService::new(toc.clone());
-            let points_internal_service = PointsInternalService::new(toc.clone());
-            let raft_service = RaftService::new(to_consensus, consensus_state);
-
-            log::debug!("Qdrant internal gRPC listening on {}", internal_grpc_port);
-
-            let mut server = Server::builder()
-                // Internally use a high limit for pending accept streams.
-                // We can have a huge number of reset/dropped HTTP2 streams in our internal
-                // communication when there are a lot of clients dropping connections. This
-                // internally causes an GOAWAY/ENHANCE_YOUR_CALM error breaking cluster consensus.
-                // We prefer to keep more pending reset streams even though this may be expensive,
-                // versus an internal error that is very hard to handle.
-                // More info: <https://github.com/qdrant/qdrant/issues/1907>
-                .http2_max_pending_accept_reset_streams(Some(1024));
-
-            if let Some(config) = tls_config {
-                log::info!("TLS enabled for internal gRPC API (TTL not supported)");
-
-                server = server.tls_config(config)?;
-            } else {
-                log::info!("TLS disabled for internal gRPC API");
-            };
-
-            // The stack of middleware that our service will be wrapped in
-            let middleware_layer = tower::ServiceBuilder::new()
-                .layer(logging::LoggingMiddlewareLayer::new())
-                .layer(tonic_telemetry::TonicTelemetryLayer::new(
-                    telemetry_collector,
-                ))
-                .into_inner();
-
-            server
-                .layer(middleware_layer)
-                .add_service(
-                    QdrantServer::new(qdrant_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    QdrantInternalServer::new(qdrant_internal_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    CollectionsInternalServer::new(collections_internal_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    PointsInternalServer::new(points_internal_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .add_service(
-                    RaftServer::new(raft_service)
-                        .send_compressed(CompressionEncoding::Gzip)
-                        .accept_compressed(CompressionEncoding::Gzip)
-                        .max_decoding_message_size(usize::MAX),
-                )
-                .serve_with_shutdown(socket, async {
-                    wait_stop_signal("internal gRPC").await;
-                })
-                .await
-        })
-        .unwrap();
+    runtime.block_on(async {
+        let socket = SocketAddr::from((host.parse::<IpAddr>().unwrap(), internal_grpc_port));
+
+        let qdrant_service = QdrantService::default();
+        let trace_context_enabled = settings.opentelemetry.is_some();
+        let qdrant_internal_service = QdrantInternalService::new(settings, consensus_state.clone());
+        let collections_internal_service = CollectionsInternalService::new(toc.clone());
+        let points_internal_service = PointsInternalService::new(toc.clone());
+        let raft_service = RaftService::new(to_consensus, consensus_state);
+
+        log::debug!("Qdrant internal gRPC listening on {}", internal_grpc_port);
+
+        let server = Server::builder()
+            // Internally use a high limit for pending accept streams.
+            // We can have a huge number of reset/dropped HTTP2 streams in our internal
+            // communication when there are a lot of clients dropping connections. This
+            // internally causes an GOAWAY/ENHANCE_YOUR_CALM error breaking cluster consensus.
+            // We prefer to keep more pending reset streams even though this may be expensive,
+            // versus an internal error that is very hard to handle.
+            // More info: <https://github.com/qdrant/qdrant/issues/1907>
+            .http2_max_pending_accept_reset_streams(Some(1024));
+
+        if tls_config.is_some() {
+            log::info!("TLS enabled for internal gRPC API");
+        } else {
+            log::info!("TLS disabled for internal gRPC API");
+        };
+
+        // The stack of middleware that our service will be wrapped in
+        let middleware_layer = tower::ServiceBuilder::new()
+            .option_layer(
+                trace_context_enabled.then(trace_context::TraceContextMiddlewareLayer::new),
+            )
+            .layer(logging::LoggingMiddlewareLayer::new())
+            .layer(tonic_telemetry::TonicTelemetryLayer::new(
+                telemetry_collector,
+            ))
+            .into_inner();
+
+        let router = server
+            .layer(middleware_layer)
+            .add_service(
+                QdrantServer::new(qdrant_service)
+                    .send_compressed(CompressionEncoding::Gzip)
+                    .accept_compressed(CompressionEncoding::Gzip)
+                    .max_decoding_message_size(usize::MAX),
+            )
+            .add_service(
+                QdrantInternalServer::new(qdrant_internal_service)
+                    .send_compressed(CompressionEncoding::Gzip)
+                    .accept_compressed(CompressionEncoding::Gzip)
+                    .max_decoding_message_size(usize::MAX),
+            )
+            .add_service(
+                CollectionsInternalServer::new(collections_internal_service)
+                    .send_compressed(CompressionEncoding::Gzip)
+                    .accept_compressed(CompressionEncoding::Gzip)
+                    .max_decoding_message_size(usize::MAX),
+            )
+            .add_service(
+                PointsInternalServer::new(points_internal_service)
+                    .send_compressed(CompressionEncoding::Gzip)
+                    .accept_compressed(CompressionEncoding::Gzip)
+                    .max_decoding_message_size(usize::MAX),
+            )
+            .add_service(
+                RaftServer::new(raft_service)
+                    .send_compressed(CompressionEncoding::Gzip)
+                    .accept_compressed(CompressionEncoding::Gzip)
+                    .max_decoding_message_size(usize::MAX),
+            );
+
+        let shutdown_signal = wait_stop_signal("internal gRPC");
+        let result = match tls_config {
+            Some(tls_config) => {
+                let incoming = tls::tls_incoming(socket, tls_config).await?;
+                router
+                    .serve_with_incoming_shutdown(incoming, shutdown_signal)
+                    .await
+            }
+            None => router.serve_with_shutdown(socket, shutdown_signal).await,
+        };
+        result.map_err(helpers::tonic_error_to_io_error)
+    })?;
     Ok(())
 }
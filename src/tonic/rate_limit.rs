@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use reqwest::header::{HeaderValue, RETRY_AFTER};
+use reqwest::StatusCode;
+use tonic::body::BoxBody;
+use tonic::transport::server::{TcpConnectInfo, TlsConnectInfo};
+use tonic::Code;
+use tower::Service;
+use tower_layer::Layer;
+
+use crate::common::auth::{AuthenticatedClient, GRPC_HEALTH_CHECK_METHOD};
+use crate::common::rate_limiter::RateLimiter;
+
+#[derive(Clone)]
+pub struct RateLimitMiddleware<T> {
+    service: T,
+    rate_limiter: Arc<RateLimiter>,
+}
+
+#[derive(Clone)]
+pub struct RateLimitMiddlewareLayer {
+    rate_limiter: Arc<RateLimiter>,
+}
+
+/// IP address of the client, for both plain and TLS connections
+fn client_ip<B>(request: &tonic::codegen::http::Request<B>) -> Option<String> {
+    let extensions = request.extensions();
+    let tcp_connect_info = extensions.get::<TcpConnectInfo>().or_else(|| {
+        extensions
+            .get::<TlsConnectInfo<TcpConnectInfo>>()
+            .map(TlsConnectInfo::get_ref)
+    })?;
+    tcp_connect_info
+        .remote_addr()
+        .map(|addr| addr.ip().to_string())
+}
+
+impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for RateLimitMiddleware<S>
+where
+    S: Service<
+        tonic::codegen::http::Request<tonic::transport::Body>,
+        Response = tonic::codegen::http::Response<tonic::body::BoxBody>,
+    >,
+    S::Future: Send + 'static,
+{
+    type Response = tonic::codegen::http::Response<tonic::body::BoxBody>;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(
+        &mut self,
+        request: tonic::codegen::http::Request<tonic::transport::Body>,
+    ) -> Self::Future {
+        // Health checks of the whole server are not limited, so load balancers can always use
+        // them, the health service limits the checks of other services
+        if request.uri().path() == GRPC_HEALTH_CHECK_METHOD {
+            return Box::pin(self.service.call(request));
+        }
+
+        let client_key = RateLimiter::client_key(
+            request.extensions().get::<AuthenticatedClient>(),
+            client_ip(&request),
+        );
+
+        let error = match self.rate_limiter.try_acquire(&client_key) {
+            Ok(permit) => {
+                let response = self.service.call(request);
+                return Box::pin(async move {
+                    let response = response.await;
+                    drop(permit);
+                    response
+                });
+            }
+            Err(error) => error,
+        };
+
+        let mut response = Self::Response::new(BoxBody::default());
+        *response.status_mut() = StatusCode::TOO_MANY_REQUESTS;
+        response.headers_mut().append(
+            "grpc-status",
+            HeaderValue::from(Code::ResourceExhausted as i32),
+        );
+        response.headers_mut().append(
+            "grpc-message",
+            HeaderValue::from_str(&error.to_string())
+                .unwrap_or_else(|_| HeaderValue::from_static("Too many requests")),
+        );
+        response
+            .headers_mut()
+            .append(RETRY_AFTER, HeaderValue::from(error.retry_after_secs()));
+
+        Box::pin(async move { Ok(response) })
+    }
+}
+
+impl RateLimitMiddlewareLayer {
+    pub fn new(rate_limiter: Arc<RateLimiter>) -> Self {
+        Self { rate_limiter }
+    }
+}
+
+impl<S> Layer<S> for RateLimitMiddlewareLayer {
+    type Service = RateLimitMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RateLimitMiddleware {
+            service,
+            rate_limiter: self.rate_limiter.clone(),
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::stream::{self, Stream};
+use rustls::ServerConfig;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+
+/// Connections, accepted at most this many ahead of the server taking them
+const PENDING_CONNECTIONS: usize = 128;
+
+/// Delay after a failed accept, e.g. if the process is out of file descriptors
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_millis(100);
+
+/// TLS connections, accepted on the address.
+///
+/// Unlike the TLS of the tonic server, it resolves the certificate for each handshake,
+/// so the certificate is reloaded from disk without a restart once its TTL expires.
+/// Handshakes run concurrently, so a slow client doesn't hold up the others.
+pub async fn tls_incoming(
+    addr: SocketAddr,
+    config: ServerConfig,
+) -> io::Result<impl Stream<Item = io::Result<TlsStream<TcpStream>>>> {
+    let listener = TcpListener::bind(addr).await?;
+    let acceptor = TlsAcceptor::from(Arc::new(config));
+    let (sender, receiver) = mpsc::channel(PENDING_CONNECTIONS);
+
+    tokio::spawn(async move {
+        loop {
+            let (tcp_stream, peer_addr) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(err) => {
+                    log::warn!("Failed to accept TCP connection: {err}");
+                    tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                    continue;
+                }
+            };
+            if let Err(err) = tcp_stream.set_nodelay(true) {
+                log::debug!("Failed to set TCP_NODELAY for {peer_addr}: {err}");
+            }
+
+            let acceptor = acceptor.clone();
+            let sender = sender.clone();
+            tokio::spawn(async move {
+                match acceptor.accept(tcp_stream).await {
+                    Ok(tls_stream) => {
+                        // Server is stopped if the receiver is dropped
+                        let _ = sender.send(Ok(tls_stream)).await;
+                    }
+                    Err(err) => log::debug!("TLS handshake with {peer_addr} failed: {err}"),
+                }
+            });
+
+            if sender.is_closed() {
+                break;
+            }
+        }
+    });
+
+    Ok(stream::unfold(receiver, |mut receiver| async move {
+        receiver
+            .recv()
+            .await
+            .map(|connection| (connection, receiver))
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::BufReader;
+
+    use futures::StreamExt as _;
+    use rustls::{Certificate, ClientConfig, PrivateKey, RootCertStore, ServerName};
+    use tokio::io::{AsyncReadExt as _, AsyncWriteExt as _};
+    use tokio_rustls::{client, TlsConnector};
+
+    use super::*;
+    use crate::common::certificate_helpers::grpc_tls_server_config;
+    use crate::settings::TlsConfig;
+
+    const CERT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/tls/cert");
+
+    /// Name of the test certificate
+    const SERVER_NAME: &str = "node1.qdrant";
+
+    fn cert_path(file: &str) -> String {
+        format!("{CERT_DIR}/{file}")
+    }
+
+    fn tls_config() -> TlsConfig {
+        TlsConfig {
+            cert: cert_path("cert.pem"),
+            key: cert_path("key.pem"),
+            ca_cert: cert_path("cacert.pem"),
+            cert_ttl: None,
+        }
+    }
+
+    fn read_certs(file: &str) -> Vec<Certificate> {
+        let mut reader = BufReader::new(File::open(cert_path(file)).unwrap());
+        rustls_pemfile::certs(&mut reader)
+            .unwrap()
+            .into_iter()
+            .map(Certificate)
+            .collect()
+    }
+
+    /// Client, which trusts the test CA and optionally authenticates by the test certificate
+    fn client_config(client_certificate: bool) -> ClientConfig {
+        let mut root_cert_store = RootCertStore::empty();
+        for cert in read_certs("cacert.pem") {
+            root_cert_store.add(&cert).unwrap();
+        }
+        let config = ClientConfig::builder()
+            .with_safe_defaults()
+            .with_root_certificates(root_cert_store);
+        let mut config = if client_certificate {
+            let mut reader = BufReader::new(File::open(cert_path("key.pem")).unwrap());
+            let key = rustls_pemfile::pkcs8_private_keys(&mut reader)
+                .unwrap()
+                .remove(0);
+            config
+                .with_client_auth_cert(read_certs("cert.pem"), PrivateKey(key))
+                .unwrap()
+        } else {
+            config.with_no_client_auth()
+        };
+        config.alpn_protocols = vec![b"h2".to_vec()];
+        config
+    }
+
+    fn free_addr() -> SocketAddr {
+        std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    async fn connect(
+        addr: SocketAddr,
+        config: ClientConfig,
+    ) -> io::Result<client::TlsStream<TcpStream>> {
+        let tcp_stream = TcpStream::connect(addr).await?;
+        let server_name = ServerName::try_from(SERVER_NAME).unwrap();
+        TlsConnector::from(Arc::new(config))
+            .connect(server_name, tcp_stream)
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_tls_handshake() {
+        let addr = free_addr();
+        let server_config = grpc_tls_server_config(tls_config(), false).unwrap();
+        let mut incoming = Box::pin(tls_incoming(addr, server_config).await.unwrap());
+
+        let mut client_stream = connect(addr, client_config(false)).await.unwrap();
+        assert_eq!(client_stream.get_ref().1.alpn_protocol(), Some(&b"h2"[..]));
+        client_stream.write_all(b"ping").await.unwrap();
+
+        let mut server_stream = incoming.next().await.unwrap().unwrap();
+        assert_eq!(server_stream.get_ref().1.alpn_protocol(), Some(&b"h2"[..]));
+        let mut buf = [0; 4];
+        server_stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+
+    /// Internal gRPC of the peers always verifies the client certificates
+    #[tokio::test]
+    async fn test_p2p_mutual_tls_handshake() {
+        let addr = free_addr();
+        let server_config = grpc_tls_server_config(tls_config(), true).unwrap();
+        let mut incoming = Box::pin(tls_incoming(addr, server_config).await.unwrap());
+
+        // Client without a certificate is rejected, with TLS 1.3 only once it reads the alert
+        let rejected = match connect(addr, client_config(false)).await {
+            Ok(mut client_stream) => {
+                let mut buf = [0; 1];
+                let read =
+                    tokio::time::timeout(Duration::from_secs(5), client_stream.read(&mut buf))
+                        .await;
+                matches!(read, Ok(Err(_) | Ok(0)))
+            }
+            Err(_) => true,
+        };
+        assert!(rejected);
+
+        let mut client_stream = connect(addr, client_config(true)).await.unwrap();
+        client_stream.write_all(b"ping").await.unwrap();
+
+        // Rejected connection is not passed to the server
+        let mut server_stream = incoming.next().await.unwrap().unwrap();
+        assert_eq!(
+            server_stream.get_ref().1.peer_certificates(),
+            Some(&read_certs("cert.pem")[..])
+        );
+        let mut buf = [0; 4];
+        server_stream.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"ping");
+    }
+}
@@ -0,0 +1,158 @@
+use std::task::{Context, Poll};
+
+use futures_util::future::BoxFuture;
+use tonic::body::BoxBody;
+use tonic::codegen::http::Response;
+use tower::Service;
+use tower_layer::Layer;
+use tracing::Instrument as _;
+
+#[derive(Clone)]
+pub struct TraceContextMiddleware<T> {
+    inner: T,
+}
+
+/// Wraps every request into a span, which continues the trace of the client or the other peer,
+/// if it is passed in the request metadata
+#[derive(Clone)]
+pub struct TraceContextMiddlewareLayer;
+
+impl TraceContextMiddlewareLayer {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl<S> Service<tonic::codegen::http::Request<tonic::transport::Body>> for TraceContextMiddleware<S>
+where
+    S: Service<tonic::codegen::http::Request<tonic::transport::Body>, Response = Response<BoxBody>>
+        + Clone,
+    S::Future: Send + 'static,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<S::Response, S::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(
+        &mut self,
+        request: tonic::codegen::http::Request<tonic::transport::Body>,
+    ) -> Self::Future {
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let span = tracing::debug_span!(
+            "grpc_request",
+            otel.name = request.uri().path(),
+            otel.kind = "server",
+        );
+
+        #[cfg(feature = "opentelemetry")]
+        api::grpc::trace_context::set_parent_from_headers(
+            &span,
+            request
+                .headers()
+                .iter()
+                .filter_map(|(key, value)| Some((key.as_str(), value.to_str().ok()?))),
+        );
+
+        let future = span.in_scope(|| inner.call(request));
+        Box::pin(future.instrument(span))
+    }
+}
+
+impl<S> Layer<S> for TraceContextMiddlewareLayer {
+    type Service = TraceContextMiddleware<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        TraceContextMiddleware { inner: service }
+    }
+}
+
+#[cfg(all(test, feature = "opentelemetry"))]
+mod tests {
+    use api::grpc::trace_context::{set_parent_from_headers, TraceContextInterceptor};
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use opentelemetry::sdk::trace as sdktrace;
+    use opentelemetry::trace::{TraceContextExt as _, TracerProvider as _};
+    use tonic::service::Interceptor as _;
+    use tracing_opentelemetry::OpenTelemetrySpanExt as _;
+    use tracing_subscriber::layer::SubscriberExt as _;
+
+    fn headers(request: &tonic::Request<()>) -> Vec<(String, String)> {
+        request
+            .metadata()
+            .clone()
+            .into_headers()
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_str().unwrap().to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_trace_context_propagation() {
+        opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+        let provider = sdktrace::TracerProvider::builder().build();
+        let tracer = provider.tracer("test");
+        let subscriber =
+            tracing_subscriber::registry().with(tracing_opentelemetry::layer().with_tracer(tracer));
+
+        tracing::subscriber::with_default(subscriber, || {
+            // Requests outside of a span have no trace context to propagate
+            let request = TraceContextInterceptor
+                .call(tonic::Request::new(()))
+                .unwrap();
+            assert!(headers(&request).is_empty());
+
+            let client_span = tracing::info_span!("client");
+            let request = client_span
+                .in_scope(|| TraceContextInterceptor.call(tonic::Request::new(())))
+                .unwrap();
+            let client_context = client_span.context().span().span_context().clone();
+            assert!(client_context.is_valid());
+
+            let headers = headers(&request);
+            assert!(headers.iter().any(|(key, _)| key == "traceparent"));
+
+            // Span of the receiving peer continues the trace of the client
+            let server_span = tracing::info_span!("server");
+            set_parent_from_headers(
+                &server_span,
+                headers
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str())),
+            );
+            let server_context = server_span.context().span().span_context().clone();
+            assert_eq!(server_context.trace_id(), client_context.trace_id());
+            assert_ne!(server_context.span_id(), client_context.span_id());
+
+            // Header names are case insensitive
+            let upper_case_span = tracing::info_span!("server");
+            let upper_case_headers: Vec<_> = headers
+                .iter()
+                .map(|(key, value)| (key.to_ascii_uppercase(), value.clone()))
+                .collect();
+            set_parent_from_headers(
+                &upper_case_span,
+                upper_case_headers
+                    .iter()
+                    .map(|(key, value)| (key.as_str(), value.as_str())),
+            );
+            assert_eq!(
+                upper_case_span.context().span().span_context().trace_id(),
+                client_context.trace_id()
+            );
+
+            // Span without the trace context in the headers starts a new trace
+            let new_trace_span = tracing::info_span!("server");
+            set_parent_from_headers(&new_trace_span, []);
+            assert_ne!(
+                new_trace_span.context().span().span_context().trace_id(),
+                client_context.trace_id()
+            );
+        });
+    }
+}
@@ -4,6 +4,8 @@ use std::str::FromStr as _;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{filter, fmt};
 
+use crate::settings::OpenTelemetryConfig;
+
 const DEFAULT_LOG_LEVEL: log::LevelFilter = log::LevelFilter::Info;
 
 const DEFAULT_FILTERS: &[(&str, log::LevelFilter)] = &[
@@ -15,7 +17,26 @@ const DEFAULT_FILTERS: &[(&str, log::LevelFilter)] = &[
     ("raft", log::LevelFilter::Warn),
 ];
 
-pub fn setup(user_filters: &str) -> anyhow::Result<()> {
+/// Keeps the exporter of the spans running, the remaining spans are exported when it is dropped
+#[derive(Default)]
+pub struct TracingGuard {
+    #[cfg(feature = "opentelemetry")]
+    exporter_runtime: Option<tokio::runtime::Runtime>,
+}
+
+#[cfg(feature = "opentelemetry")]
+impl Drop for TracingGuard {
+    fn drop(&mut self) {
+        if self.exporter_runtime.is_some() {
+            opentelemetry::global::shutdown_tracer_provider();
+        }
+    }
+}
+
+pub fn setup(
+    user_filters: &str,
+    opentelemetry_config: Option<&OpenTelemetryConfig>,
+) -> anyhow::Result<TracingGuard> {
     tracing_log::LogTracer::init()?;
 
     let mut filters = DEFAULT_LOG_LEVEL.to_string();
@@ -66,7 +87,85 @@ pub fn setup(user_filters: &str) -> anyhow::Result<()> {
         tracing_subscriber::filter::filter_fn(|metadata| metadata.is_span()),
     ));
 
+    // Use `opentelemetry` feature to export spans to an OpenTelemetry collector
+    #[cfg(feature = "opentelemetry")]
+    let (reg, guard) = match opentelemetry_config {
+        Some(config) => {
+            let (layer, exporter_runtime) = opentelemetry_layer(config)?;
+            let guard = TracingGuard {
+                exporter_runtime: Some(exporter_runtime),
+            };
+            (reg.with(Some(layer)), guard)
+        }
+        None => (reg.with(None), TracingGuard::default()),
+    };
+
     tracing::subscriber::set_global_default(reg)?;
 
-    Ok(())
+    #[cfg(not(feature = "opentelemetry"))]
+    let guard = {
+        if opentelemetry_config.is_some() {
+            log::warn!(
+                "OpenTelemetry is configured, but Qdrant is built without `opentelemetry` feature, \
+                 spans are not exported"
+            );
+        }
+        TracingGuard::default()
+    };
+
+    Ok(guard)
+}
+
+#[cfg(feature = "opentelemetry")]
+fn opentelemetry_layer<S>(
+    config: &OpenTelemetryConfig,
+) -> anyhow::Result<(impl tracing_subscriber::Layer<S>, tokio::runtime::Runtime)>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+{
+    use opentelemetry::sdk::propagation::TraceContextPropagator;
+    use opentelemetry::sdk::trace::{self as sdktrace, Sampler};
+    use opentelemetry::sdk::Resource;
+    use opentelemetry::KeyValue;
+    use opentelemetry_otlp::WithExportConfig as _;
+
+    // Tracing is set up before the runtimes of the service, so the exporter gets its own
+    let exporter_runtime = tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(1)
+        .thread_name("otlp-exporter")
+        .enable_all()
+        .build()?;
+
+    let tracer = {
+        let _runtime_guard = exporter_runtime.enter();
+
+        let exporter = opentelemetry_otlp::new_exporter()
+            .tonic()
+            .with_endpoint(&config.otlp_endpoint);
+
+        // Sampling decision of the other peers and clients is respected
+        let sampler =
+            Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(config.sampling_ratio)));
+        let resource = Resource::new([KeyValue::new("service.name", config.service_name.clone())]);
+
+        opentelemetry_otlp::new_pipeline()
+            .tracing()
+            .with_exporter(exporter)
+            .with_trace_config(
+                sdktrace::config()
+                    .with_sampler(sampler)
+                    .with_resource(resource),
+            )
+            .install_batch(opentelemetry::runtime::Tokio)?
+    };
+
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    let layer = tracing_opentelemetry::layer()
+        .with_tracer(tracer)
+        .with_filter(tracing_subscriber::filter::filter_fn(|metadata| {
+            metadata.is_span()
+        }));
+
+    Ok((layer, exporter_runtime))
 }
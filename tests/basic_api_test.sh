@@ -104,6 +104,39 @@ curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/search"
         "top": 3
     }' | jq
 
+# search points with a timeout
+curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/search?timeout=10" \
+  -H 'Content-Type: application/json' \
+  --fail -s \
+  --data-raw '{
+        "vector": [0.2,0.1,0.9,0.7],
+        "top": 3
+    }' | jq
+
+# search points with the used resources
+curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/search?with_usage=true" \
+  -H 'Content-Type: application/json' \
+  --fail -s \
+  --data-raw '{
+        "vector": [0.2,0.1,0.9,0.7],
+        "top": 3
+    }' | jq -e '.usage.segments_searched > 0'
+
+# plan the search without executing it
+curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/search?explain=plan" \
+  -H 'Content-Type: application/json' \
+  --fail -s \
+  --data-raw '{
+        "vector": [0.2,0.1,0.9,0.7],
+        "top": 3
+    }' | jq -e '(.result | length) == 0 and (.explain[0].segments | length) > 0'
+
+# scroll points with a timeout
+curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/scroll?timeout=10" \
+  -H 'Content-Type: application/json' \
+  --fail -s \
+  --data-raw '{"limit": 3}' | jq
+
 # search points batch
 curl -L -X POST "http://$QDRANT_HOST/collections/test_collection/points/search/batch" \
   -H 'Content-Type: application/json' \
@@ -26,6 +26,11 @@ $docker_grpcurl -d '{
 
 $docker_grpcurl -d '{}' $QDRANT_HOST qdrant.Collections/List
 
+# Health of the collection, described through the reflection service without the proto files
+docker run --rm --network=host fullstorydev/grpcurl -plaintext -d '{
+   "service": "collections/test_collection"
+}' $QDRANT_HOST grpc.health.v1.Health/Check
+
 $docker_grpcurl -d '{
   "collection_name": "test_collection",
   "wait": true,
@@ -94,6 +99,11 @@ $docker_grpcurl -d '{
   }
 }' $QDRANT_HOST qdrant.Points/Scroll
 
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "limit": 2
+}' $QDRANT_HOST qdrant.Points/ScrollStream
+
 $docker_grpcurl -d '{
   "collection_name": "test_collection",
   "with_vectors": {"enable": true},
@@ -106,6 +116,32 @@ $docker_grpcurl -d '{
   "negative": [{ "num": 2 }]
 }' $QDRANT_HOST qdrant.Points/Recommend
 
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "prefetch": [
+    { "query": { "nearest": { "data": [0.2,0.1,0.9,0.7] } }, "limit": 4 },
+    { "query": { "recommend": { "positive": [{ "id": { "num": 1 } }] } }, "limit": 4 }
+  ],
+  "query": { "fusion": "RRF" },
+  "limit": 3,
+  "with_explanation": true
+}' $QDRANT_HOST qdrant.Points/Query
+
+$docker_grpcurl -d '{
+  "collection_name": "test_collection",
+  "prefetch": [
+    { "query": { "nearest": { "data": [0.2,0.1,0.9,0.7] } }, "limit": 4 }
+  ],
+  "query": {
+    "formula": {
+      "expression": {
+        "sum": { "sum": [{ "variable": "$score" }, { "constant": 1.0 }] }
+      }
+    }
+  },
+  "limit": 3
+}' $QDRANT_HOST qdrant.Points/Query
+
 # create alias
 $docker_grpcurl -d '{
   "actions": [
@@ -172,6 +208,7 @@ $docker_grpcurl $QDRANT_HOST describe
 $docker_grpcurl $QDRANT_HOST describe qdrant.Collections
 $docker_grpcurl $QDRANT_HOST describe qdrant.Points
 $docker_grpcurl $QDRANT_HOST describe qdrant.Snapshots
+$docker_grpcurl $QDRANT_HOST describe qdrant.Cluster
 $docker_grpcurl $QDRANT_HOST describe qdrant.Qdrant
 $docker_grpcurl $QDRANT_HOST describe grpc.health.v1.Health
 
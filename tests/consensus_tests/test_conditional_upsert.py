@@ -0,0 +1,64 @@
+import pathlib
+
+from .fixtures import create_collection
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 3
+N_SHARDS = 1
+N_REPLICA = 3
+
+COLLECTION_NAME = "test_collection"
+
+
+def conditional_upsert(peer_url, condition, wait="true"):
+    return requests.put(
+        f"{peer_url}/collections/{COLLECTION_NAME}/points?wait={wait}&{condition}", json={
+            "points": [
+                {
+                    "id": 1,
+                    "vector": [0.05, 0.61, 0.76, 0.74],
+                    "payload": {"city": "Berlin"}
+                }
+            ]
+        })
+
+
+# Conditions are checked once by the update leader, so failed checks don't deactivate replicas
+def test_conditional_upsert(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, _peer_dirs, _bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    create_collection(peer_api_uris[0], shard_number=N_SHARDS, replication_factor=N_REPLICA)
+    wait_collection_exists_and_active_on_all_peers(
+        collection_name=COLLECTION_NAME,
+        peer_api_uris=peer_api_uris
+    )
+
+    # Result of the condition can't be reported without waiting for the update
+    r = conditional_upsert(peer_api_uris[0], "if_absent=true", wait="false")
+    assert r.status_code == 400
+
+    r = conditional_upsert(peer_api_uris[0], "if_absent=true")
+    assert_http_ok(r)
+    version = r.json()["result"]["operation_id"]
+
+    # Point exists on the leader, no matter which peer receives the request
+    for peer_api_uri in peer_api_uris:
+        r = conditional_upsert(peer_api_uri, "if_absent=true")
+        assert r.status_code == 409, r.text
+
+    r = conditional_upsert(peer_api_uris[-1], f"if_version={version}")
+    assert_http_ok(r)
+
+    r = conditional_upsert(peer_api_uris[1], f"if_version={version}")
+    assert r.status_code == 409, r.text
+
+    for peer_api_uri in peer_api_uris:
+        assert check_all_replicas_active(peer_api_uri, COLLECTION_NAME)
+        r = requests.post(
+            f"{peer_api_uri}/collections/{COLLECTION_NAME}/points/count", json={"exact": True}
+        )
+        assert_http_ok(r)
+        assert r.json()["result"]["count"] == 1
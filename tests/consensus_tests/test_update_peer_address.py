@@ -0,0 +1,68 @@
+import pathlib
+
+from .utils import *
+from .assertions import assert_http_ok
+
+N_PEERS = 3
+
+
+def peer_uris(peer_api_uri: str) -> dict:
+    peers = get_cluster_info(peer_api_uri)["peers"]
+    return {peer_id: peer["uri"].rstrip("/") for peer_id, peer in peers.items()}
+
+
+def check_peer_uri(peer_api_uri: str, peer_id: int, expected_uri: str) -> bool:
+    return peer_uris(peer_api_uri).get(str(peer_id)) == expected_uri
+
+
+def test_update_peer_address(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    last_peer_id = get_cluster_info(peer_api_uris[-1])["peer_id"]
+    old_uri = peer_uris(peer_api_uris[0])[str(last_peer_id)]
+    # Same peer, reachable by another host name
+    new_uri = old_uri.replace("127.0.0.1", "localhost")
+
+    r = requests.put(f"{peer_api_uris[0]}/cluster/peer/{last_peer_id}?timeout=10", json={"uri": new_uri})
+    assert_http_ok(r)
+    assert r.json()["result"] is True
+
+    # The new address is known to all peers
+    for peer_api_uri in peer_api_uris:
+        wait_for(check_peer_uri, peer_api_uri, last_peer_id, new_uri)
+
+    # The cluster keeps working with the updated address
+    r = requests.put(
+        f"{peer_api_uris[0]}/collections/test_collection", json={
+            "vectors": {"size": 4, "distance": "Dot"},
+            "shard_number": N_PEERS,
+            "replication_factor": 1,
+        })
+    assert_http_ok(r)
+    wait_collection_exists_and_active_on_all_peers(collection_name="test_collection", peer_api_uris=peer_api_uris)
+
+
+def test_update_peer_address_rejected(tmp_path: pathlib.Path):
+    assert_project_root()
+
+    peer_api_uris, peer_dirs, bootstrap_uri = start_cluster(tmp_path, N_PEERS)
+
+    last_peer_id = get_cluster_info(peer_api_uris[-1])["peer_id"]
+    old_uris = peer_uris(peer_api_uris[0])
+
+    # Unknown peer
+    unknown_peer_id = max(int(peer_id) for peer_id in old_uris) + 1
+    r = requests.put(f"{peer_api_uris[0]}/cluster/peer/{unknown_peer_id}", json={"uri": "http://127.0.0.1:6335"})
+    assert r.status_code == 404, r.text
+
+    # Invalid address
+    r = requests.put(f"{peer_api_uris[0]}/cluster/peer/{last_peer_id}", json={"uri": "not a uri"})
+    assert r.status_code == 400, r.text
+
+    # Empty address
+    r = requests.put(f"{peer_api_uris[0]}/cluster/peer/{last_peer_id}", json={"uri": ""})
+    assert r.status_code == 422, r.text
+
+    assert peer_uris(peer_api_uris[0]) == old_uris
@@ -103,6 +103,8 @@ declare TESTS=(
 	recover-local-invalid-collection
 	recover-local-invalid-shard
 	recover-local-invalid-snapshot
+	recover-local-checksum
+	recover-local-checksum-mismatch
 
 	recover-remote
 	${CLUSTER:+recover-remote-priority-snapshot}
@@ -111,6 +113,8 @@ declare TESTS=(
 	recover-remote-invalid-collection
 	recover-remote-invalid-shard
 	recover-remote-invalid-snapshot
+	recover-remote-checksum
+	recover-remote-checksum-mismatch
 
 	upload
 	${CLUSTER:+upload-priority-snapshot}
@@ -118,6 +122,8 @@ declare TESTS=(
 	${CLUSTER:+upload-concurrent-priority-snapshot}
 	upload-invalid-collection
 	upload-invalid-shard
+	upload-checksum
+	upload-checksum-mismatch
 
 	download
 	download-invalid-collection
@@ -189,6 +195,17 @@ function do-recover {
 		--data-raw "$(json --arg location "$LOCATION" --arg priority "$PRIORITY")"
 }
 
+function do-recover-checksum {
+	declare STATUS="$1"
+	declare LOCATION="$2"
+	declare CHECKSUM="$3"
+
+	curl-status "$STATUS" \
+		-X PUT "$(url - -)"/recover \
+		-H 'Content-Type: application/json' \
+		--data-raw "$(json --arg location "$LOCATION" --arg checksum "$CHECKSUM")"
+}
+
 
 function recover-local {
 	fixture-with-snapshot
@@ -239,6 +256,22 @@ function recover-local-invalid-snapshot {
 		--data-raw '{ "location": "invalid.snapshot" }'
 }
 
+function recover-local-checksum {
+	fixture-with-downloaded-snapshot
+	fixture-with-empty-collection
+
+	do-recover-checksum 200 "$SNAPSHOT" "$(checksum "$DOWNLOADED_SNAPSHOT")"
+	check-recovered - "$SNAPSHOT_POINTS"
+}
+
+function recover-local-checksum-mismatch {
+	fixture-with-snapshot
+	fixture-with-empty-collection
+
+	do-recover-checksum 400 "$SNAPSHOT" "$(invalid-checksum)"
+	[[ "$(points-count)" == 0 ]]
+}
+
 
 function recover-remote {
 	fixture-with-remote-snapshot
@@ -289,6 +322,25 @@ function recover-remote-invalid-snapshot {
 		--data-raw '{ "location": "http://localhost:8080/invalid.snapshot" }'
 }
 
+function recover-remote-checksum {
+	fixture-with-remote-snapshot
+	fixture-with-empty-collection
+
+	do-recover-checksum 200 \
+		"$FILESERVER_URL/$DOWNLOADED_SNAPSHOT" \
+		"$(checksum "$DOWNLOADED_SNAPSHOT")"
+
+	check-recovered - "$DOWNLOADED_SNAPSHOT_POINTS"
+}
+
+function recover-remote-checksum-mismatch {
+	fixture-with-remote-snapshot
+	fixture-with-empty-collection
+
+	do-recover-checksum 400 "$FILESERVER_URL/$DOWNLOADED_SNAPSHOT" "$(invalid-checksum)"
+	[[ "$(points-count)" == 0 ]]
+}
+
 
 function do-upload {
 	declare PRIORITY="${1:-replica}"
@@ -329,6 +381,28 @@ function upload-invalid-shard {
 	curl-status 404 -X POST "$(url - 99)"/upload -F snapshot=invalid-snapshot-data
 }
 
+function upload-checksum {
+	fixture-with-downloaded-snapshot
+	fixture-with-empty-collection
+
+	curl-ok \
+		-X POST "$(url - -)/upload?checksum=$(checksum "$DOWNLOADED_SNAPSHOT")" \
+		-F snapshot=@"$DOWNLOADED_SNAPSHOT"
+
+	check-recovered - "$DOWNLOADED_SNAPSHOT_POINTS"
+}
+
+function upload-checksum-mismatch {
+	fixture-with-downloaded-snapshot
+	fixture-with-empty-collection
+
+	curl-status 400 \
+		-X POST "$(url - -)/upload?checksum=$(invalid-checksum)" \
+		-F snapshot=@"$DOWNLOADED_SNAPSHOT"
+
+	[[ "$(points-count)" == 0 ]]
+}
+
 
 function download {
 	fixture-with-collection
@@ -588,6 +662,14 @@ function points-count {
 	curl-ok "$(url)" | jq .result.points_count
 }
 
+function checksum {
+	sha256sum "$1" | cut -d ' ' -f 1
+}
+
+function invalid-checksum {
+	printf '0%.0s' {1..64}
+}
+
 function concurrent {
 	declare PARALLEL ; PARALLEL="$(or-default "$1" 2)"
 	declare CMD=( "${@:2}" )
@@ -100,6 +100,41 @@ curl -X GET "http://${QDRANT_HOST}/collections/test_collection_recovered_1" --fa
 
 curl -X GET "http://${QDRANT_HOST}/collections/test_collection_recovered_2" --fail | jq
 
+# Same for an incremental snapshot on top of the snapshot
+
+curl -L -X PUT "http://$QDRANT_HOST/collections/test_collection/points?wait=true" \
+  -H 'Content-Type: application/json' \
+  --fail -s \
+  --data-raw '{"points": [{"id": 3, "vector": [0.36, 0.55, 0.47, 0.94], "payload": {"city": "Moscow"}}]}' | jq
+
+declare INCREMENTAL_SNAPSHOT_NAME=$(curl -X POST "http://${QDRANT_HOST}/collections/test_collection/snapshots?base=${SNAPSHOT_NAME}" --fail -H 'Content-Type: application/json' --data-raw '{}' | jq -r '.result.name')
+
+declare INCREMENTAL_SNAPSHOT_URL="http://${QDRANT_HOST}/collections/test_collection/snapshots/${INCREMENTAL_SNAPSHOT_NAME}"
+
+curl -X GET ${INCREMENTAL_SNAPSHOT_URL} -H 'Content-Type: application/json' --fail -s -o test_collection_incremental.snapshot
+
+# Base snapshot is downloaded from the same location
+
+curl -X PUT "http://${QDRANT_HOST}/collections/test_collection_recovered_3/snapshots/recover" \
+     -H 'Content-Type: application/json' \
+     --fail -s -d "{\"location\": \"${INCREMENTAL_SNAPSHOT_URL}\"}" | jq
+
+# Base snapshot is uploaded with the increment, under its original name
+
+curl -X POST "http://${QDRANT_HOST}/collections/test_collection_recovered_4/snapshots/upload" \
+     -H 'Content-Type:multipart/form-data' \
+     --fail -s \
+     -F 'snapshot=@test_collection_incremental.snapshot' \
+     -F "bases=@test_collection.snapshot;filename=${SNAPSHOT_NAME}" | jq
+
+for collection in test_collection_recovered_3 test_collection_recovered_4; do
+  POINTS_COUNT=$(curl -X GET "http://${QDRANT_HOST}/collections/${collection}" --fail -s | jq '.result.points_count')
+  [[ "$POINTS_COUNT" == "3" ]] || {
+    echo "${collection} has ${POINTS_COUNT} points instead of 3"
+    exit 1
+  }
+done
+
 # Same for the shard snapshot
 
 SHARD_SNAPSHOT_NAME=$(curl -X POST "http://${QDRANT_HOST}/collections/test_collection/shards/0/snapshots" --fail -H 'Content-Type: application/json' --data-raw '{}' | tee log.json | jq -r '.result.name')